@@ -68,6 +68,36 @@ fn entry(b: &mut Criterion) {
             value
         });
     });
+
+    let (mut vm, io) = make_dispatch_vm().unwrap();
+
+    b.bench_function("brainfuck_dispatch_hello_world", |b| {
+        // Every operation is a separate type, so each instance call site
+        // dispatches on a number of different receivers.
+        let program = "++++++++[>++++[>++>+++>+++>+<<<<-]>+>+>->>+[<]<-]>>.>---.+++++++..+++.>>.<-.<.+++.------.--------.>>+.>++.";
+        let entry = Hash::type_hash(["main"]);
+
+        b.iter(|| {
+            let value = vm.call(entry, (program,)).expect("failed call");
+            let out = io.drain_utf8();
+            assert_eq!(out.as_deref(), Ok("Hello World!\n"));
+            value
+        });
+    });
+
+    let (mut vm, io) = make_dispatch_vm().unwrap();
+
+    b.bench_function("brainfuck_dispatch_loopity", |b| {
+        let program = ">+[>++>+++[-<]>>]+";
+        let entry = Hash::type_hash(["main"]);
+
+        b.iter(|| {
+            let value = vm.call(entry, (program,)).expect("failed call");
+            let out = io.drain_utf8();
+            assert_eq!(out.as_deref(), Ok(""));
+            value
+        });
+    });
 }
 
 fn make_vm() -> Result<(Vm, CaptureIo)> {
@@ -178,3 +208,114 @@ fn make_vm() -> Result<(Vm, CaptureIo)> {
         }
     })
 }
+
+fn make_dispatch_vm() -> Result<(Vm, CaptureIo)> {
+    Ok(rune_vm_capture! {
+        struct Tape {
+            pos,
+            tape,
+        }
+
+        impl Tape {
+            fn new() {
+                Tape { pos: 0, tape: [0] }
+            }
+
+            fn get(self) {
+                self.tape[self.pos]
+            }
+
+            fn inc(self, x) {
+                self.tape[self.pos] = (self.tape[self.pos] + x) % 256;
+
+                if self.tape[self.pos] < 0 {
+                    self.tape[self.pos] = self.tape[self.pos] + 256;
+                }
+            }
+
+            fn mov(self, x) {
+                self.pos += x;
+
+                while self.pos >= self.tape.len() {
+                    self.tape.push(0);
+                }
+            }
+
+            fn set(self, v) {
+                self.tape[self.pos] = v;
+            }
+        }
+
+        struct Inc { v }
+        struct Move { v }
+        struct Loop { ops }
+        struct Print;
+        struct Input;
+
+        impl Inc {
+            fn exec(self, tape) {
+                tape.inc(self.v);
+            }
+        }
+
+        impl Move {
+            fn exec(self, tape) {
+                tape.mov(self.v);
+            }
+        }
+
+        impl Loop {
+            fn exec(self, tape) {
+                while tape.get() != 0 {
+                    run(self.ops, tape);
+                }
+            }
+        }
+
+        impl Print {
+            fn exec(self, tape) {
+                let c = char::from_i64(tape.get()).expect("A valid char");
+                print!("{}", c);
+            }
+        }
+
+        impl Input {
+            fn exec(self, tape) {
+                tape.set(0);
+            }
+        }
+
+        fn run(ops, tape) {
+            for op in ops {
+                op.exec(tape);
+            }
+        }
+
+        fn parse(it) {
+            let buf = Vec::new();
+
+            while let Some(c) = it.next() {
+                let op = match c {
+                    '+' => Inc { v: 1 },
+                    '-' => Inc { v: -1 },
+                    '>' => Move { v: 1 },
+                    '<' => Move { v: -1 },
+                    '.' => Print,
+                    '[' => Loop { ops: parse(it) },
+                    ',' => Input,
+                    ']' => break,
+                    _ => continue,
+                };
+
+                buf.push(op);
+            }
+
+            buf
+        }
+
+        pub fn main(s) {
+            let tape = Tape::new();
+            run(parse(s.chars()), tape);
+        }
+    })
+}
@@ -0,0 +1,83 @@
+//! Benchmark of startup time, which includes compiling a script and running it
+//! once, with and without constant folding.
+
+use std::sync::Arc;
+
+use criterion::Criterion;
+use rune::{Context, Diagnostics, Options, Vm};
+
+criterion::criterion_group!(benches, startup_folded, startup_unfolded);
+
+const SOURCE: &str = r#"
+const TARGET = 1000 * 2 + 20;
+
+fn part1(v, target) {
+    let a = 0;
+    let b = v.len() - 1;
+
+    while a != b {
+        match v[a] + v[b] {
+            n if n < target => a += 1,
+            n if n > target => b -= 1,
+            _ => return Some((a, b)),
+        }
+    }
+
+    None
+}
+
+pub fn main() {
+    let v = [];
+
+    for n in 0..(1 << 8) - 1 {
+        v.push(n * (2 * 4 + 1) % (60 * 60 - 1));
+    }
+
+    v.sort();
+    let mask = (1 << 4) - 1 | 1 << 12;
+    let label = `part1 ${1 + 1}: ${TARGET}`;
+    (label, part1(v, TARGET), mask & !0 == mask, 24 * 60 * 60 * 1000)
+}
+"#;
+
+fn startup(context: &Context, constant_folding: bool) -> Vm {
+    let mut options = Options::default();
+    options.constant_folding(constant_folding);
+
+    let mut sources = crate::sources(SOURCE);
+    let mut diagnostics = Diagnostics::new();
+
+    let unit = rune::prepare(&mut sources)
+        .with_context(context)
+        .with_diagnostics(&mut diagnostics)
+        .with_options(&options)
+        .build()
+        .expect("Program to compile successfully");
+
+    let runtime = Arc::new(context.runtime().expect("Failed to build runtime"));
+    Vm::new(runtime, Arc::new(unit))
+}
+
+fn startup_folded(b: &mut Criterion) {
+    let context = Context::with_default_modules().expect("Failed to build context");
+    let entry = rune::Hash::type_hash(["main"]);
+
+    b.bench_function("startup_folded", |b| {
+        b.iter(|| {
+            let mut vm = startup(&context, true);
+            vm.call(entry, ()).expect("failed call")
+        });
+    });
+}
+
+fn startup_unfolded(b: &mut Criterion) {
+    let context = Context::with_default_modules().expect("Failed to build context");
+    let entry = rune::Hash::type_hash(["main"]);
+
+    b.bench_function("startup_unfolded", |b| {
+        b.iter(|| {
+            let mut vm = startup(&context, false);
+            vm.call(entry, ()).expect("failed call")
+        });
+    });
+}
@@ -1,6 +1,6 @@
 use criterion::Criterion;
 
-criterion::criterion_group!(benches, fib_15, fib_20);
+criterion::criterion_group!(benches, fib_15, fib_20, fib_instance_15);
 
 fn fib_15(b: &mut Criterion) {
     let mut vm = rune_vm! {
@@ -45,3 +45,29 @@ fn fib_20(b: &mut Criterion) {
         b.iter(|| vm.call(entry, (20,)).expect("failed call"));
     });
 }
+
+fn fib_instance_15(b: &mut Criterion) {
+    let mut vm = rune_vm! {
+        struct Fib;
+
+        impl Fib {
+            fn fib(self, n) {
+                if n <= 1 {
+                    n
+                } else {
+                    self.fib(n - 2) + self.fib(n - 1)
+                }
+            }
+        }
+
+        pub fn main(v) {
+            Fib.fib(v)
+        }
+    };
+
+    let entry = rune::Hash::type_hash(["main"]);
+
+    b.bench_function("fib_instance_15", |b| {
+        b.iter(|| vm.call(entry, (15,)).expect("failed call"));
+    });
+}
@@ -0,0 +1,37 @@
+//! Compare repeated calls through a typed function handle against calls
+//! through `Vm::call`.
+
+use criterion::Criterion;
+
+criterion::criterion_group!(benches, vm_call, typed_function_call);
+
+fn vm_call(b: &mut Criterion) {
+    let mut vm = rune_vm! {
+        pub fn add(a, b) {
+            a + b
+        }
+    };
+
+    b.bench_function("vm_call", |b| {
+        b.iter(|| {
+            let value = vm.call(["add"], (1i64, 2i64)).expect("failed call");
+            rune::from_value::<i64>(value).expect("failed conversion")
+        });
+    });
+}
+
+fn typed_function_call(b: &mut Criterion) {
+    let mut vm = rune_vm! {
+        pub fn add(a, b) {
+            a + b
+        }
+    };
+
+    let add = vm
+        .typed_function::<(i64, i64), i64>(["add"])
+        .expect("failed to look up function");
+
+    b.bench_function("typed_function_call", |b| {
+        b.iter(|| add.call(&mut vm, (1, 2)).expect("failed call"));
+    });
+}
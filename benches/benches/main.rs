@@ -51,8 +51,10 @@ mod benchmarks {
     pub mod aoc_2020_1a;
     pub mod aoc_2020_1b;
     pub mod brainfuck;
+    pub mod constant_folding;
     pub mod external_functions;
     pub mod fib;
+    pub mod typed_function;
 }
 
 criterion::criterion_main! {
@@ -61,6 +63,8 @@ criterion::criterion_main! {
     benchmarks::aoc_2020_11a::benches,
     benchmarks::aoc_2020_19b::benches,
     benchmarks::brainfuck::benches,
+    benchmarks::constant_folding::benches,
     benchmarks::fib::benches,
     benchmarks::external_functions::benches,
+    benchmarks::typed_function::benches,
 }
@@ -0,0 +1,119 @@
+//! Measures the number of allocations needed to construct objects.
+//!
+//! Object literals use keys which are interned in the unit, so constructing
+//! them should only allocate for the object itself and not for its keys. As a
+//! baseline, the same objects are also constructed by inserting their keys
+//! dynamically, which allocates an owned string for every key.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use criterion::Criterion;
+use rune::{Context, Diagnostics, Hash, Source, Sources, Vm};
+
+/// An allocator which counts the number of allocations performed.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const SOURCE: &str = r#"
+pub fn main(n) {
+    let out = [];
+
+    for i in 0..n {
+        out.push(#{ first: i, second: i + 1, third: i + 2, fourth: i + 3 });
+    }
+
+    out
+}
+
+pub fn owned(n) {
+    let out = [];
+
+    for i in 0..n {
+        let object = #{};
+        object.insert("first", i);
+        object.insert("second", i + 1);
+        object.insert("third", i + 2);
+        object.insert("fourth", i + 3);
+        out.push(object);
+    }
+
+    out
+}
+"#;
+
+fn vm() -> Vm {
+    let context = Context::with_default_modules().expect("Failed to build context");
+
+    let mut sources = Sources::new();
+    sources
+        .insert(Source::new("main", SOURCE).expect("Failed to construct source"))
+        .expect("Failed to insert source");
+
+    let mut diagnostics = Diagnostics::new();
+
+    let unit = rune::prepare(&mut sources)
+        .with_context(&context)
+        .with_diagnostics(&mut diagnostics)
+        .build()
+        .expect("Program to compile successfully");
+
+    let context = Arc::new(context.runtime().expect("Failed to build runtime"));
+    Vm::new(context, Arc::new(unit))
+}
+
+/// Count the number of allocations performed per object when calling the
+/// given function.
+fn allocations(vm: &mut Vm, entry: Hash, count: usize) -> f64 {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let output = vm.call(entry, (count,)).expect("failed call");
+    let after = ALLOCATIONS.load(Ordering::Relaxed);
+    drop(output);
+    (after - before) as f64 / count as f64
+}
+
+fn object_keys(b: &mut Criterion) {
+    const COUNT: usize = 1000;
+
+    let mut vm = vm();
+    let main = Hash::type_hash(["main"]);
+    let owned = Hash::type_hash(["owned"]);
+
+    println!(
+        "object_keys: {} allocations per object (owned keys: {})",
+        allocations(&mut vm, main, COUNT),
+        allocations(&mut vm, owned, COUNT),
+    );
+
+    b.bench_function("object_keys", |b| {
+        b.iter(|| vm.call(main, (COUNT,)).expect("failed call"));
+    });
+
+    b.bench_function("object_keys_owned", |b| {
+        b.iter(|| vm.call(owned, (COUNT,)).expect("failed call"));
+    });
+}
+
+criterion::criterion_group!(benches, object_keys);
+criterion::criterion_main!(benches);
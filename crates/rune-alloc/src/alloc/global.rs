@@ -33,7 +33,10 @@ impl Global {
     /// Acquire the specified memory.
     pub(crate) fn take(&self, layout: Layout) -> Result<(), AllocError> {
         if !crate::limit::take(layout.size()) {
-            return Err(AllocError { layout });
+            return Err(AllocError {
+                layout,
+                limited: true,
+            });
         }
 
         Ok(())
@@ -54,7 +57,10 @@ impl Global {
                 };
 
                 let Some(ptr) = NonNull::new(raw_ptr) else {
-                    return Err(AllocError { layout });
+                    return Err(AllocError {
+                        layout,
+                        limited: false,
+                    });
                 };
 
                 Ok(NonNull::slice_from_raw_parts(ptr, size))
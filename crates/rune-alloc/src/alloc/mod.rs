@@ -16,6 +16,25 @@ use crate::error::{CustomError, Error};
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct AllocError {
     pub(crate) layout: Layout,
+    /// If the allocation was rejected by the memory limit.
+    pub(crate) limited: bool,
+}
+
+impl AllocError {
+    /// The size in bytes of the allocation which failed.
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.layout.size()
+    }
+
+    /// Test if the allocation was rejected because it would have exceeded
+    /// the memory limit set up through [`limit::with`].
+    ///
+    /// [`limit::with`]: crate::limit::with
+    #[inline]
+    pub fn is_limited(&self) -> bool {
+        self.limited
+    }
 }
 
 impl fmt::Display for AllocError {
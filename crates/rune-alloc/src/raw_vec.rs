@@ -375,7 +375,10 @@ impl<T, A: Allocator> RawVec<T, A> {
                 let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
                 self.alloc
                     .shrink(ptr, layout, new_layout)
-                    .map_err(|_| AllocError { layout: new_layout })?
+                    .map_err(|error| AllocError {
+                        layout: new_layout,
+                        ..error
+                    })?
             };
             self.set_ptr_and_cap(ptr, cap);
         }
@@ -412,7 +415,13 @@ where
         alloc.allocate(new_layout)
     };
 
-    memory.map_err(|_| AllocError { layout: new_layout }.into())
+    memory.map_err(|error| {
+        AllocError {
+            layout: new_layout,
+            ..error
+        }
+        .into()
+    })
 }
 
 #[cfg(not(rune_nightly))]
@@ -3,8 +3,10 @@ use core::marker::PhantomData;
 
 use serde::de::{self, Error as _};
 use serde::ser::{self, SerializeSeq};
+use serde::Deserialize;
 
 use crate::alloc::alloc::Allocator;
+use crate::alloc::Box;
 use crate::item::{Component, Item, ItemBuf};
 
 impl ser::Serialize for Item {
@@ -12,7 +14,7 @@ impl ser::Serialize for Item {
     where
         S: serde::Serializer,
     {
-        let mut seq = serializer.serialize_seq(None)?;
+        let mut seq = serializer.serialize_seq(Some(self.iter().count()))?;
 
         for item in self.iter() {
             seq.serialize_element(&item)?;
@@ -39,7 +41,7 @@ where
     where
         D: serde::Deserializer<'de>,
     {
-        deserializer.deserialize_bytes(BytesVisitor(PhantomData))
+        deserializer.deserialize_seq(BytesVisitor(PhantomData))
     }
 }
 
@@ -52,7 +54,7 @@ where
     type Value = ItemBuf<A>;
 
     fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "item buffer deserialization to be implemented")
+        write!(f, "a sequence of item components")
     }
 
     fn visit_seq<S>(self, mut seq: S) -> Result<Self::Value, S::Error>
@@ -61,10 +63,26 @@ where
     {
         let mut buf = ItemBuf::new_in(A::default());
 
-        while let Some(c) = seq.next_element::<Component>()? {
+        while let Some(c) = seq.next_element::<OwnedComponentRef>()? {
+            let c = match c {
+                OwnedComponentRef::Crate(s) => Component::Crate(s),
+                OwnedComponentRef::Str(s) => Component::Str(s),
+                OwnedComponentRef::Id(n) => Component::Id(n),
+            };
+
             buf.push(c).map_err(S::Error::custom)?;
         }
 
         Ok(buf)
     }
 }
+
+/// An owned component, deserialized from how a
+/// [`ComponentRef`][crate::item::ComponentRef] is serialized.
+#[derive(Deserialize)]
+#[serde(rename = "ComponentRef")]
+enum OwnedComponentRef {
+    Crate(Box<str>),
+    Str(Box<str>),
+    Id(usize),
+}
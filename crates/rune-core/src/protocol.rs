@@ -152,6 +152,26 @@ define! {
         /// Allows an indexing set operation to work.
     };
 
+    /// The function to access a tuple index which doesn't have a dedicated
+    /// [`Protocol::GET`] function.
+    ///
+    /// Signature: `fn(Value, usize) -> Value`.
+    pub const TUPLE_INDEX_GET: Protocol = Protocol {
+        hash: 0x6bc2dc56ecb16e26u64,
+        repr: "let $out = $value.index",
+        /// Allows tuple index get operations on any index to work.
+    };
+
+    /// The function to set a tuple index which doesn't have a dedicated
+    /// [`Protocol::SET`] function.
+    ///
+    /// Signature: `fn(Value, usize, Value)`.
+    pub const TUPLE_INDEX_SET: Protocol = Protocol {
+        hash: 0x34f87959ab9e7f9fu64,
+        repr: "$value.index = $input",
+        /// Allows tuple index set operations on any index to work.
+    };
+
     /// Check two types for partial equality.
     pub const PARTIAL_EQ: Protocol = Protocol {
         method: "eq",
@@ -369,6 +389,20 @@ define! {
         /// Allows the `>>=` operator to apply to values of this type, where the current type is the left-hand side.
     };
 
+    /// The function to implement for the negation operation.
+    pub const NEG: Protocol = Protocol {
+        hash: 0x7e8c240543c12cc7u64,
+        repr: "let $out = -$value",
+        /// Allows the unary `-` operator to apply to values of this type.
+    };
+
+    /// The function to implement for the logical or bitwise not operation.
+    pub const NOT: Protocol = Protocol {
+        hash: 0xf0a9c84eb081805cu64,
+        repr: "let $out = !$value",
+        /// Allows the unary `!` operator to apply to values of this type.
+    };
+
     /// Protocol function used by template strings.
     pub const DISPLAY_FMT: Protocol = Protocol {
         hash: 0x811b62957ea9d9f9u64,
@@ -76,7 +76,7 @@ fn main() -> Result<()> {
 
     let options = Options::from_default_env()?;
 
-    let runtime = tokio::runtime::Builder::new_current_thread()
+    let runtime = tokio::runtime::Builder::new_multi_thread()
         .enable_all()
         .build()?;
 
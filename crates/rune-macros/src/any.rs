@@ -1,8 +1,9 @@
 use std::collections::BTreeMap;
 
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 use quote::{quote, quote_spanned, ToTokens};
 use rune_core::hash::Hash;
+use rune_core::protocol::Protocol;
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::Token;
@@ -214,6 +215,14 @@ pub(crate) fn expand_install_with(
             expand_struct_install_with(cx, installers, ident, st, tokens, attr)?;
         }
         syn::Data::Enum(en) => {
+            if let Some(span) = attr.index {
+                cx.error(syn::Error::new(
+                    span,
+                    "#[rune(index)] is only supported on tuple structs",
+                ));
+                return Err(());
+            }
+
             expand_enum_install_with(cx, installers, ident, en, tokens, attr, &input.generics)?;
         }
         syn::Data::Union(..) => {
@@ -225,6 +234,20 @@ pub(crate) fn expand_install_with(
         }
     }
 
+    expand_ops(cx, installers, tokens, attr)?;
+
+    if let Some(span) = attr.immutable {
+        installers.push(quote_spanned! { span =>
+            module.type_meta::<Self>()?.immutable()?;
+        });
+    }
+
+    if let Some(span) = attr.serialize {
+        installers.push(quote_spanned! { span =>
+            module.type_meta::<Self>()?.serializable()?;
+        });
+    }
+
     if let Some(install_with) = &attr.install_with {
         installers.push(quote_spanned! { input.span() =>
             #install_with(module)?;
@@ -242,8 +265,11 @@ fn expand_struct_install_with(
     tokens: &Tokens,
     attr: &TypeAttr,
 ) -> Result<(), ()> {
+    let mut copy = Vec::new();
+
     for (n, field) in st.fields.iter().enumerate() {
         let attrs = cx.field_attrs(&field.attrs);
+        copy.push(attrs.copy);
         let name;
         let index;
 
@@ -326,6 +352,10 @@ fn expand_struct_install_with(
             installers.push(quote! {
                 module.type_meta::<Self>()?.make_unnamed_struct(#len)?.static_docs(&#docs)?;
             });
+
+            if attr.index.is_some() {
+                expand_tuple_index(installers, fields, &copy, tokens);
+            }
         }
         syn::Fields::Unit => {
             installers.push(quote! {
@@ -334,9 +364,291 @@ fn expand_struct_install_with(
         }
     }
 
+    if let (Some(span), syn::Fields::Named(..) | syn::Fields::Unit) = (attr.index, &st.fields) {
+        cx.error(syn::Error::new(
+            span,
+            "#[rune(index)] is only supported on tuple structs",
+        ));
+        return Err(());
+    }
+
     Ok(())
 }
 
+/// The kind of an operator supported by `#[rune(ops(..))]`.
+#[derive(Clone, Copy)]
+enum OpKind {
+    /// A binary operator implemented through a trait in `core::ops`.
+    Binary(&'static str, &'static str),
+    /// An assign operator implemented through a trait in `core::ops`.
+    Assign(&'static str, &'static str),
+    /// A unary operator implemented through a trait in `core::ops`.
+    Unary(&'static str, &'static str),
+    PartialEq,
+    Eq,
+    PartialCmp,
+    Cmp,
+}
+
+/// Operators supported by `#[rune(ops(..))]`.
+const OPS: &[(&str, &Protocol, OpKind)] = &[
+    ("add", &Protocol::ADD, OpKind::Binary("Add", "add")),
+    ("sub", &Protocol::SUB, OpKind::Binary("Sub", "sub")),
+    ("mul", &Protocol::MUL, OpKind::Binary("Mul", "mul")),
+    ("div", &Protocol::DIV, OpKind::Binary("Div", "div")),
+    ("rem", &Protocol::REM, OpKind::Binary("Rem", "rem")),
+    (
+        "bit_and",
+        &Protocol::BIT_AND,
+        OpKind::Binary("BitAnd", "bitand"),
+    ),
+    (
+        "bit_or",
+        &Protocol::BIT_OR,
+        OpKind::Binary("BitOr", "bitor"),
+    ),
+    (
+        "bit_xor",
+        &Protocol::BIT_XOR,
+        OpKind::Binary("BitXor", "bitxor"),
+    ),
+    ("shl", &Protocol::SHL, OpKind::Binary("Shl", "shl")),
+    ("shr", &Protocol::SHR, OpKind::Binary("Shr", "shr")),
+    (
+        "add_assign",
+        &Protocol::ADD_ASSIGN,
+        OpKind::Assign("AddAssign", "add_assign"),
+    ),
+    (
+        "sub_assign",
+        &Protocol::SUB_ASSIGN,
+        OpKind::Assign("SubAssign", "sub_assign"),
+    ),
+    (
+        "mul_assign",
+        &Protocol::MUL_ASSIGN,
+        OpKind::Assign("MulAssign", "mul_assign"),
+    ),
+    (
+        "div_assign",
+        &Protocol::DIV_ASSIGN,
+        OpKind::Assign("DivAssign", "div_assign"),
+    ),
+    (
+        "rem_assign",
+        &Protocol::REM_ASSIGN,
+        OpKind::Assign("RemAssign", "rem_assign"),
+    ),
+    (
+        "bit_and_assign",
+        &Protocol::BIT_AND_ASSIGN,
+        OpKind::Assign("BitAndAssign", "bitand_assign"),
+    ),
+    (
+        "bit_or_assign",
+        &Protocol::BIT_OR_ASSIGN,
+        OpKind::Assign("BitOrAssign", "bitor_assign"),
+    ),
+    (
+        "bit_xor_assign",
+        &Protocol::BIT_XOR_ASSIGN,
+        OpKind::Assign("BitXorAssign", "bitxor_assign"),
+    ),
+    (
+        "shl_assign",
+        &Protocol::SHL_ASSIGN,
+        OpKind::Assign("ShlAssign", "shl_assign"),
+    ),
+    (
+        "shr_assign",
+        &Protocol::SHR_ASSIGN,
+        OpKind::Assign("ShrAssign", "shr_assign"),
+    ),
+    ("neg", &Protocol::NEG, OpKind::Unary("Neg", "neg")),
+    ("not", &Protocol::NOT, OpKind::Unary("Not", "not")),
+    ("partial_eq", &Protocol::PARTIAL_EQ, OpKind::PartialEq),
+    ("eq", &Protocol::EQ, OpKind::Eq),
+    ("partial_cmp", &Protocol::PARTIAL_CMP, OpKind::PartialCmp),
+    ("cmp", &Protocol::CMP, OpKind::Cmp),
+];
+
+/// Generate protocol implementations for `#[rune(ops(..))]` which delegate to
+/// the corresponding trait implementations of the type.
+fn expand_ops(
+    cx: &Context,
+    installers: &mut Vec<TokenStream>,
+    tokens: &Tokens,
+    attr: &TypeAttr,
+) -> Result<(), ()> {
+    let mut ok = true;
+
+    for op in &attr.ops {
+        let name = op.name.to_string();
+
+        let Some(&(_, protocol, kind)) = OPS.iter().find(|(n, ..)| *n == name) else {
+            let supported = OPS.iter().map(|(n, ..)| *n).collect::<Vec<_>>().join(", ");
+
+            cx.error(syn::Error::new_spanned(
+                &op.name,
+                format!("Unsupported operator `{name}`, expected one of: {supported}"),
+            ));
+
+            ok = false;
+            continue;
+        };
+
+        if let (Some(rhs), OpKind::Unary(..) | OpKind::Eq | OpKind::Cmp) = (&op.rhs, kind) {
+            cx.error(syn::Error::new_spanned(
+                rhs,
+                format!("Operator `{name}` does not support a right-hand side type"),
+            ));
+
+            ok = false;
+            continue;
+        }
+
+        let span = op.name.span();
+        let protocol = tokens.protocol(protocol);
+
+        let (rhs_arg, rhs_ty, rhs_value, rhs_ref) = match &op.rhs {
+            Some(ty) => (quote!(rhs: #ty), quote!(#ty), quote!(rhs), quote!(&rhs)),
+            None => (
+                quote!(rhs: &Self),
+                quote!(Self),
+                quote!(::core::clone::Clone::clone(rhs)),
+                quote!(rhs),
+            ),
+        };
+
+        let function = match kind {
+            OpKind::Binary(tr, method) => {
+                let tr = syn::Ident::new(tr, span);
+                let method = syn::Ident::new(method, span);
+
+                quote_spanned! { span =>
+                    |this: &Self, #rhs_arg| <Self as ::core::ops::#tr<#rhs_ty>>::#method(::core::clone::Clone::clone(this), #rhs_value)
+                }
+            }
+            OpKind::Assign(tr, method) => {
+                let tr = syn::Ident::new(tr, span);
+                let method = syn::Ident::new(method, span);
+
+                quote_spanned! { span =>
+                    |this: &mut Self, #rhs_arg| <Self as ::core::ops::#tr<#rhs_ty>>::#method(this, #rhs_value)
+                }
+            }
+            OpKind::Unary(tr, method) => {
+                let tr = syn::Ident::new(tr, span);
+                let method = syn::Ident::new(method, span);
+
+                quote_spanned! { span =>
+                    |this: &Self| <Self as ::core::ops::#tr>::#method(::core::clone::Clone::clone(this))
+                }
+            }
+            OpKind::PartialEq => quote_spanned! { span =>
+                |this: &Self, #rhs_arg| <Self as ::core::cmp::PartialEq<#rhs_ty>>::eq(this, #rhs_ref)
+            },
+            OpKind::Eq => quote_spanned! { span =>
+                |this: &Self, rhs: &Self| {
+                    fn assert_eq<T: ?Sized + ::core::cmp::Eq>() {}
+                    assert_eq::<Self>();
+                    <Self as ::core::cmp::PartialEq>::eq(this, rhs)
+                }
+            },
+            OpKind::PartialCmp => quote_spanned! { span =>
+                |this: &Self, #rhs_arg| <Self as ::core::cmp::PartialOrd<#rhs_ty>>::partial_cmp(this, #rhs_ref)
+            },
+            OpKind::Cmp => quote_spanned! { span =>
+                |this: &Self, rhs: &Self| <Self as ::core::cmp::Ord>::cmp(this, rhs)
+            },
+        };
+
+        installers.push(quote_spanned! { span =>
+            module.associated_function(&#protocol, #function)?;
+        });
+    }
+
+    if ok {
+        Ok(())
+    } else {
+        Err(())
+    }
+}
+
+/// Generate `INDEX_GET` and `INDEX_SET` implementations which dispatch on the
+/// index of a field in a tuple struct.
+///
+/// The same implementations are registered for `TUPLE_INDEX_GET` and
+/// `TUPLE_INDEX_SET`, which opts the type into tuple index operations like
+/// `v.2` raising a missing index error instead of being unsupported.
+fn expand_tuple_index(
+    installers: &mut Vec<TokenStream>,
+    fields: &syn::FieldsUnnamed,
+    copy: &[bool],
+    tokens: &Tokens,
+) {
+    let Tokens {
+        from_value,
+        protocol,
+        runtime_error,
+        to_value,
+        try_clone,
+        type_name,
+        value,
+        vm_result,
+        vm_try,
+        ..
+    } = tokens;
+
+    let mut get = Vec::new();
+    let mut set = Vec::new();
+
+    for ((n, field), copy) in fields.unnamed.iter().enumerate().zip(copy) {
+        let index = syn::LitInt::new(&n.to_string(), field.span());
+
+        let access = if *copy {
+            quote!(this.#index)
+        } else {
+            quote!(#vm_try!(#try_clone::try_clone(&this.#index)))
+        };
+
+        get.push(quote!(#n => #vm_result::Ok(#vm_try!(#to_value::to_value(#access)))));
+        set.push(quote!(#n => this.#index = #vm_try!(#from_value::from_value(value))));
+    }
+
+    for name in ["INDEX_GET", "TUPLE_INDEX_GET"] {
+        let name = syn::Ident::new(name, Span::call_site());
+
+        installers.push(quote! {
+            module.associated_function(&#protocol::#name, |this: &Self, index: usize| {
+                match index {
+                    #(#get,)*
+                    _ => #vm_result::err(
+                        #runtime_error::__rune_macros__missing_tuple_index(#type_name::<Self>(), index)
+                    ),
+                }
+            })?;
+        });
+    }
+
+    for name in ["INDEX_SET", "TUPLE_INDEX_SET"] {
+        let name = syn::Ident::new(name, Span::call_site());
+
+        installers.push(quote! {
+            module.associated_function(&#protocol::#name, |this: &mut Self, index: usize, value: #value| {
+                match index {
+                    #(#set,)*
+                    _ => return #vm_result::err(
+                        #runtime_error::__rune_macros__missing_tuple_index(#type_name::<Self>(), index)
+                    ),
+                }
+
+                #vm_result::Ok(())
+            })?;
+        });
+    }
+}
+
 fn expand_enum_install_with(
     cx: &Context,
     installers: &mut Vec<TokenStream>,
@@ -350,6 +662,8 @@ fn expand_enum_install_with(
         protocol,
         runtime_error,
         to_value,
+        from_value,
+        value,
         vm_result,
         vm_try,
         any_t,
@@ -368,6 +682,12 @@ fn expand_enum_install_with(
     let mut field_fns = BTreeMap::<String, Vec<TokenStream>>::new();
     let mut index_fns = BTreeMap::<usize, Vec<TokenStream>>::new();
 
+    // Protocol::SET implementations per assignable field. Since the same field
+    // might have different types in different variants the value is converted
+    // in each match arm.
+    let mut set_field_fns = BTreeMap::<String, Vec<TokenStream>>::new();
+    let mut set_index_fns = BTreeMap::<usize, Vec<TokenStream>>::new();
+
     for (variant_index, variant) in en.variants.iter().enumerate() {
         let span = variant.fields.span();
 
@@ -400,12 +720,13 @@ fn expand_enum_install_with(
                         return Err(());
                     };
 
+                    let f_name = f_ident.to_string();
+
                     if attrs.field {
-                        let f_name = f_ident.to_string();
                         let name = syn::LitStr::new(&f_name, f.span());
                         field_names.push(name);
 
-                        let fields = field_fns.entry(f_name).or_default();
+                        let fields = field_fns.entry(f_name.clone()).or_default();
 
                         let value = if attrs.copy {
                             quote!(#vm_result::Ok(#vm_try!(#to_value::to_value(*#f_ident))))
@@ -415,13 +736,39 @@ fn expand_enum_install_with(
 
                         fields.push(quote!(#ident::#variant_ident { #f_ident, .. } => #value));
                     }
+
+                    if attrs.set {
+                        let fields = set_field_fns.entry(f_name).or_default();
+
+                        fields.push(quote! {
+                            #ident::#variant_ident { #f_ident: field, .. } => {
+                                *field = #vm_try!(#from_value::from_value(value));
+                            }
+                        });
+                    }
                 }
 
+                if variant_attr.constructor.is_some() && field_names.len() != fields.named.len() {
+                    cx.error(syn::Error::new_spanned(fields, "#[rune(constructor)] can only be used if all fields are marked with #[rune(get)"));
+                }
+
+                let constructor = variant_attr.constructor.is_some().then(|| {
+                    let args = fields.named.iter().flat_map(|f| {
+                        let ident = f.ident.as_ref()?;
+                        let ty = &f.ty;
+                        Some(quote!(#ident: #ty))
+                    });
+
+                    let field_names = fields.named.iter().flat_map(|f| f.ident.as_ref());
+
+                    quote!(|#(#args),*| #ident #type_generics :: #variant_ident { #(#field_names),* })
+                });
+
                 variant_metas.push(quote! {
                     enum_.variant_mut(#variant_index)?.make_named(&[#(#field_names),*])?.static_docs(&#variant_docs)?
                 });
 
-                variants.push((None, variant_attr));
+                variants.push((constructor, variant_attr));
             }
             syn::Fields::Unnamed(fields) => {
                 let mut fields_len = 0usize;
@@ -443,6 +790,17 @@ fn expand_enum_install_with(
 
                         fields.push(quote!(#ident::#variant_ident { #n: value, .. } => #value));
                     }
+
+                    if attrs.set {
+                        let fields = set_index_fns.entry(n).or_default();
+                        let n = syn::LitInt::new(&n.to_string(), span);
+
+                        fields.push(quote! {
+                            #ident::#variant_ident { #n: field, .. } => {
+                                *field = #vm_try!(#from_value::from_value(value));
+                            }
+                        });
+                    }
                 }
 
                 variant_metas.push(quote! {
@@ -518,6 +876,40 @@ fn expand_enum_install_with(
         });
     }
 
+    for (field, matches) in set_field_fns {
+        installers.push(quote! {
+            module.field_function(&#protocol::SET, #field, |this: &mut Self, value: #value| {
+                match this {
+                    #(#matches,)*
+                    _ => return #vm_result::err(
+                        #runtime_error::__rune_macros__unsupported_object_field_set(
+                            <Self as #any_t>::ANY_TYPE_INFO
+                        )
+                    ),
+                }
+
+                #vm_result::Ok(())
+            })?;
+        });
+    }
+
+    for (index, matches) in set_index_fns {
+        installers.push(quote! {
+            module.index_function(&#protocol::SET, #index, |this: &mut Self, value: #value| {
+                match this {
+                    #(#matches,)*
+                    _ => return #vm_result::err(
+                        #runtime_error::__rune_macros__unsupported_tuple_index_set(
+                            <Self as #any_t>::ANY_TYPE_INFO
+                        )
+                    ),
+                }
+
+                #vm_result::Ok(())
+            })?;
+        });
+    }
+
     let mut docs = syn::ExprArray {
         attrs: Vec::new(),
         bracket_token: syn::token::Bracket::default(),
@@ -43,6 +43,7 @@ impl Derive {
             to_const_value_t,
             type_hash_t,
             from_value,
+            try_clone,
             value,
             ..
         } = &tokens;
@@ -85,7 +86,7 @@ impl Derive {
                         });
 
                         from_const_fields.push(quote! {
-                            <#ty as #from_const_value_t>::from_const_value(#var)?
+                            <#ty as #from_const_value_t>::from_const_value(#try_clone::try_clone(#var)?)?
                         });
 
                         from_value_fields.push(quote! {
@@ -39,6 +39,12 @@ pub(crate) struct FieldAttrs {
     pub(crate) copy: bool,
     /// Whether this field should be known at compile time or not.
     pub(crate) field: bool,
+    /// Whether this field can be assigned to through `#[rune(set)]`.
+    pub(crate) set: bool,
+    /// `#[rune(rename = "..")]` to use a different name for the field.
+    pub(crate) rename: Option<syn::LitStr>,
+    /// Parsed documentation.
+    pub(crate) docs: Vec<syn::Expr>,
 }
 
 impl FieldAttrs {
@@ -46,6 +52,17 @@ impl FieldAttrs {
     pub(crate) fn skip(&self) -> bool {
         self.skip.is_some() || self.id.is_some()
     }
+
+    /// Documentation to attach to a generated field function, if the field is
+    /// documented.
+    pub(crate) fn field_docs(&self) -> Option<TokenStream> {
+        if self.docs.is_empty() {
+            return None;
+        }
+
+        let docs = &self.docs;
+        Some(quote!(.docs([#(#docs),*])?))
+    }
 }
 
 /// Parsed #[const_value(..)] field attributes.
@@ -87,12 +104,31 @@ pub(crate) struct TypeAttr {
     pub(crate) item: Option<syn::Path>,
     /// `#[rune(constructor)]`.
     pub(crate) constructor: Option<Span>,
+    /// `#[rune(index)]` to generate `INDEX_GET` and `INDEX_SET` for tuple
+    /// structs.
+    pub(crate) index: Option<Span>,
+    /// `#[rune(immutable)]` to give the type value semantics in scripts.
+    pub(crate) immutable: Option<Span>,
+    /// `#[rune(serialize)]` to support serializing values of the type.
+    pub(crate) serialize: Option<Span>,
+    /// `#[rune(ops(..))]` to generate protocols which delegate to the
+    /// operator traits implemented for the type.
+    pub(crate) ops: Vec<TypeOp>,
     /// Parsed documentation.
     pub(crate) docs: Vec<syn::Expr>,
     /// Method to use to convert from value.
     pub(crate) impl_params: Option<syn::punctuated::Punctuated<syn::TypeParam, Token![,]>>,
 }
 
+/// An operator in `#[rune(ops(..))]`.
+pub(crate) struct TypeOp {
+    /// The name of the operator, like `add`.
+    pub(crate) name: syn::Ident,
+    /// The type of the right-hand side as specified with `add = T`, if it
+    /// differs from the type being derived.
+    pub(crate) rhs: Option<syn::Type>,
+}
+
 /// Parsed #[const_value(..)] field attributes.
 #[derive(Default)]
 #[must_use = "Attributes must be used or explicitly ignored"]
@@ -107,6 +143,8 @@ pub(crate) struct ConstValueTypeAttr {
 pub(crate) struct VariantAttrs {
     /// `#[rune(constructor)]`.
     pub(crate) constructor: Option<Span>,
+    /// `#[rune(rename = "..")]` to use a different name for the variant.
+    pub(crate) rename: Option<syn::LitStr>,
     /// Discovered documentation.
     pub(crate) docs: Vec<syn::Expr>,
 }
@@ -354,6 +392,14 @@ impl Context {
         let mut attr = FieldAttrs::default();
 
         for a in input {
+            if a.path().is_ident("doc") {
+                if let syn::Meta::NameValue(meta) = &a.meta {
+                    attr.docs.push(meta.value.clone());
+                }
+
+                continue;
+            }
+
             if !a.path().is_ident(RUNE) {
                 continue;
             }
@@ -423,6 +469,12 @@ impl Context {
                     return Ok(());
                 }
 
+                if meta.path.is_ident("rename") {
+                    meta.input.parse::<Token![=]>()?;
+                    attr.rename = Some(meta.input.parse::<syn::LitStr>()?);
+                    return Ok(());
+                }
+
                 if meta.path.is_ident("parse_with") {
                     if let Some(old) = &attr.parse_with {
                         let mut error = syn::Error::new_spanned(
@@ -466,9 +518,10 @@ impl Context {
                                     };
 
                                     let protocol = g.tokens.protocol(&Protocol::GET);
+                                    let docs = g.attrs.field_docs();
 
                                     quote_spanned! { g.field.span() =>
-                                        module.field_function(&#protocol, #field_name, |s: &Self| #vm_result::Ok(#access))?;
+                                        module.field_function(&#protocol, #field_name, |s: &Self| #vm_result::Ok(#access))?#docs;
                                     }
                                 }
                                 GenerateTarget::Numbered { field_index } => {
@@ -479,9 +532,10 @@ impl Context {
                                     };
 
                                     let protocol = g.tokens.protocol(&Protocol::GET);
+                                    let docs = g.attrs.field_docs();
 
                                     quote_spanned! { g.field.span() =>
-                                        module.index_function(&#protocol, #field_index, |s: &Self| #vm_result::Ok(#access))?;
+                                        module.index_function(&#protocol, #field_index, |s: &Self| #vm_result::Ok(#access))?#docs;
                                     }
                                 }
                             }
@@ -492,6 +546,7 @@ impl Context {
                 }
 
                 if meta.path.is_ident("set") {
+                    attr.set = true;
                     attr.protocols.push(FieldProtocol {
                         custom: self.parse_field_custom(meta.input)?,
                         generate: |g| {
@@ -659,6 +714,61 @@ impl Context {
                     return Ok(());
                 }
 
+                if meta.path.is_ident("index") {
+                    attr.index = Some(meta.path.span());
+                    return Ok(());
+                }
+
+                if meta.path.is_ident("immutable") {
+                    attr.immutable = Some(meta.path.span());
+                    return Ok(());
+                }
+
+                if meta.path.is_ident("serialize") {
+                    attr.serialize = Some(meta.path.span());
+                    return Ok(());
+                }
+
+                if meta.path.is_ident("ops") {
+                    meta.parse_nested_meta(|meta| {
+                        let Some(name) = meta.path.get_ident() else {
+                            return Err(syn::Error::new_spanned(
+                                &meta.path,
+                                "Expected the name of an operator, like `add`",
+                            ));
+                        };
+
+                        if let Some(existing) = attr.ops.iter().find(|op| op.name == *name) {
+                            let mut error = syn::Error::new_spanned(
+                                name,
+                                format!("Operator `{name}` can only be specified once"),
+                            );
+
+                            error.combine(syn::Error::new_spanned(
+                                &existing.name,
+                                "previously specified here",
+                            ));
+
+                            return Err(error);
+                        }
+
+                        let rhs = if meta.input.parse::<Option<Token![=]>>()?.is_some() {
+                            Some(meta.input.parse()?)
+                        } else {
+                            None
+                        };
+
+                        attr.ops.push(TypeOp {
+                            name: name.clone(),
+                            rhs,
+                        });
+
+                        Ok(())
+                    })?;
+
+                    return Ok(());
+                }
+
                 if meta.path.is_ident("impl_params") {
                     meta.input.parse::<Token![=]>()?;
                     let content;
@@ -709,6 +819,9 @@ impl Context {
                     }
 
                     attr.constructor = Some(meta.path.span());
+                } else if meta.path.is_ident("rename") {
+                    meta.input.parse::<Token![=]>()?;
+                    attr.rename = Some(meta.input.parse::<syn::LitStr>()?);
                 } else {
                     return Err(syn::Error::new_spanned(&meta.path, "Unsupported attribute"));
                 }
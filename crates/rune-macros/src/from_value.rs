@@ -74,9 +74,12 @@ impl Expander<'_> {
             }
         };
 
+        let generics = self.generics(input);
+        let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
         Ok(quote! {
             #[automatically_derived]
-            impl #from_value for #ident {
+            impl #impl_generics #from_value for #ident #type_generics #where_clause {
                 fn from_value(value: #value) -> #result<Self, #runtime_error> {
                     match #value::as_type_value(&value)? {
                         #expanded
@@ -112,7 +115,12 @@ impl Expander<'_> {
 
         for variant in &en.variants {
             let ident = &variant.ident;
-            let lit_str = syn::LitStr::new(&ident.to_string(), variant.span());
+            let attr = self.cx.variant_attr(&variant.attrs);
+
+            let lit_str = match attr.rename {
+                Some(rename) => rename,
+                None => syn::LitStr::new(&ident.to_string(), variant.span()),
+            };
 
             match &variant.fields {
                 syn::Fields::Unit => {
@@ -173,9 +181,12 @@ impl Expander<'_> {
             }
         };
 
+        let generics = self.generics(input);
+        let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
         Ok(quote! {
             #[automatically_derived]
-            impl #from_value for #ident {
+            impl #impl_generics #from_value for #ident #type_generics #where_clause {
                 fn from_value(value: #value) -> #result<Self, #runtime_error> {
                     match #value::as_type_value(&value)? {
                         #variant,
@@ -188,6 +199,28 @@ impl Expander<'_> {
         })
     }
 
+    /// Get the generics of the type being derived, where every type parameter
+    /// is required to implement `FromValue`.
+    fn generics(&self, input: &syn::DeriveInput) -> syn::Generics {
+        let from_value = &self.tokens.from_value;
+        let mut generics = input.generics.clone();
+
+        let params = generics
+            .type_params()
+            .map(|param| param.ident.clone())
+            .collect::<Vec<_>>();
+
+        let where_clause = generics.make_where_clause();
+
+        for ident in params {
+            where_clause
+                .predicates
+                .push(syn::parse_quote!(#ident: #from_value));
+        }
+
+        generics
+    }
+
     /// Get a field identifier.
     fn field_ident<'a>(&self, field: &'a syn::Field) -> Result<&'a syn::Ident, ()> {
         match &field.ident {
@@ -240,9 +273,12 @@ impl Expander<'_> {
 
         for field in &named.named {
             let ident = self.field_ident(field)?;
-            let _ = self.cx.field_attrs(&field.attrs);
+            let attrs = self.cx.field_attrs(&field.attrs);
 
-            let name = &syn::LitStr::new(&ident.to_string(), ident.span());
+            let name = &match attrs.rename {
+                Some(rename) => rename,
+                None => syn::LitStr::new(&ident.to_string(), ident.span()),
+            };
 
             let Tokens {
                 from_value,
@@ -28,6 +28,8 @@ pub(crate) struct FunctionAttrs {
     vm_result: bool,
     /// The function is deprecated.
     deprecated: Option<syn::LitStr>,
+    /// The function can be called in constant contexts.
+    is_const: bool,
 }
 
 impl FunctionAttrs {
@@ -36,6 +38,16 @@ impl FunctionAttrs {
         let mut out = Self::default();
 
         while !input.is_empty() {
+            if input.parse::<Option<Token![const]>>()?.is_some() {
+                out.is_const = true;
+
+                if input.parse::<Option<Token![,]>>()?.is_none() {
+                    break;
+                }
+
+                continue;
+            }
+
             let ident = input.parse::<syn::Ident>()?;
 
             if ident == "instance" {
@@ -128,6 +140,7 @@ pub(crate) struct Function {
     docs: syn::ExprArray,
     arguments: syn::ExprArray,
     takes_self: bool,
+    takes_vm_data: bool,
 }
 
 impl Function {
@@ -162,10 +175,27 @@ impl Function {
         };
 
         let mut takes_self = false;
+        let mut takes_vm_data = false;
 
         for arg in &sig.inputs {
             let argument_name = match arg {
-                syn::FnArg::Typed(ty) => argument_ident(&ty.pat),
+                syn::FnArg::Typed(ty) => {
+                    if let Some(by_ref) = vm_data_kind(&ty.ty) {
+                        if by_ref && sig.asyncness.is_some() {
+                            return Err(syn::Error::new_spanned(
+                                &ty.ty,
+                                "Async functions cannot take `&VmData`, use `VmData` instead",
+                            ));
+                        }
+
+                        // Host data is provided by the virtual machine and is
+                        // not an argument visible to Rune.
+                        takes_vm_data = true;
+                        continue;
+                    }
+
+                    argument_ident(&ty.pat)
+                }
                 syn::FnArg::Receiver(..) => {
                     takes_self = true;
                     syn::LitStr::new("self", arg.span())
@@ -188,6 +218,7 @@ impl Function {
             docs,
             arguments,
             takes_self,
+            takes_vm_data,
         })
     }
 
@@ -338,6 +369,7 @@ impl Function {
         }
 
         let generics = sig.generics.clone();
+        let inputs = sig.inputs.clone();
         stream.extend(sig.into_token_stream());
 
         if attrs.vm_result {
@@ -366,9 +398,17 @@ impl Function {
             None => quote!(None),
         };
 
+        let is_const = attrs.is_const;
+
         let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
         let type_generics = type_generics.as_turbofish();
 
+        let real_fn_path = if self.takes_vm_data {
+            vm_data_closure(&inputs, quote!(#real_fn_path #type_generics))
+        } else {
+            quote!(#real_fn_path #type_generics)
+        };
+
         stream.extend(quote! {
             /// Get function metadata.
             #[automatically_derived]
@@ -378,10 +418,11 @@ impl Function {
             #where_clause
             {
                 Ok(rune::__private::FunctionMetaData {
-                    kind: rune::__private::FunctionMetaKind::#meta_kind(#name, #real_fn_path #type_generics)?#build_with,
+                    kind: rune::__private::FunctionMetaKind::#meta_kind(#name, #real_fn_path)?#build_with,
                     statics: rune::__private::FunctionMetaStatics {
                         name: #name_string,
                         deprecated: #deprecated,
+                        is_const: #is_const,
                         docs: &#docs[..],
                         arguments: &#arguments[..],
                     },
@@ -393,6 +434,57 @@ impl Function {
     }
 }
 
+/// Test if the given type is `VmData` or `&VmData`, returning `true` if it is
+/// taken by reference.
+fn vm_data_kind(ty: &syn::Type) -> Option<bool> {
+    fn is_vm_data(ty: &syn::Type) -> bool {
+        let syn::Type::Path(ty) = ty else {
+            return false;
+        };
+
+        ty.qself.is_none()
+            && ty.path.segments.last().is_some_and(|segment| {
+                segment.ident == "VmData" && matches!(segment.arguments, syn::PathArguments::None)
+            })
+    }
+
+    match ty {
+        syn::Type::Reference(ty) if ty.mutability.is_none() => is_vm_data(&ty.elem).then_some(true),
+        ty => is_vm_data(ty).then_some(false),
+    }
+}
+
+/// Construct a closure which forwards all arguments to the given function,
+/// providing host data for arguments which take it.
+fn vm_data_closure(inputs: &Punctuated<syn::FnArg, Token![,]>, path: TokenStream) -> TokenStream {
+    let mut params = Vec::new();
+    let mut args = Vec::new();
+
+    for (index, arg) in inputs.iter().enumerate() {
+        let ident = syn::Ident::new(&format!("__rune_arg{index}"), arg.span());
+
+        let ty = match arg {
+            syn::FnArg::Receiver(receiver) => &receiver.ty,
+            syn::FnArg::Typed(pat) => match vm_data_kind(&pat.ty) {
+                Some(true) => {
+                    args.push(quote!(&rune::runtime::VmData::current()));
+                    continue;
+                }
+                Some(false) => {
+                    args.push(quote!(rune::runtime::VmData::current()));
+                    continue;
+                }
+                None => &pat.ty,
+            },
+        };
+
+        params.push(quote!(#ident: #ty));
+        args.push(quote!(#ident));
+    }
+
+    quote!(|#(#params),*| #path(#(#args),*))
+}
+
 /// The identifier of an argument.
 fn argument_ident(pat: &syn::Pat) -> syn::LitStr {
     match pat {
@@ -1,6 +1,7 @@
-use crate::context::{Context, Tokens};
+use crate::context::{Context, Tokens, TypeAttr};
 use proc_macro2::TokenStream;
 use quote::quote;
+use syn::punctuated::Punctuated;
 
 struct Expander<'cx> {
     cx: &'cx Context,
@@ -26,9 +27,12 @@ impl Expander<'_> {
             ..
         } = &self.tokens;
 
+        let generics = self.generics(input);
+        let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
         Ok(quote! {
             #[automatically_derived]
-            impl #to_value for #ident {
+            impl #impl_generics #to_value for #ident #type_generics #where_clause {
                 fn to_value(self) -> #result<#value, #runtime_error> {
                     #inner
                 }
@@ -36,6 +40,163 @@ impl Expander<'_> {
         })
     }
 
+    /// Expand on an enum.
+    fn expand_enum(
+        &mut self,
+        input: &syn::DeriveInput,
+        attr: &TypeAttr,
+        en: &syn::DataEnum,
+    ) -> Result<TokenStream, ()> {
+        let ident = &input.ident;
+
+        let Tokens {
+            value,
+            to_value,
+            result,
+            runtime_error,
+            vec,
+            item,
+            ..
+        } = &self.tokens;
+
+        let name = match &attr.name {
+            Some(name) => name,
+            None => ident,
+        };
+
+        let mut enum_item = match &attr.item {
+            Some(item) => item.clone(),
+            None => syn::Path {
+                leading_colon: None,
+                segments: Punctuated::default(),
+            },
+        };
+
+        enum_item
+            .segments
+            .push(syn::PathSegment::from(name.clone()));
+
+        let type_item = crate::hash::Arguments::new(enum_item).build_type_item(self.cx)?;
+
+        let enum_item = quote! {
+            {
+                const ITEM: &'static #item = unsafe { #item::from_bytes(&#type_item) };
+                ITEM
+            }
+        };
+
+        let mut variants = Vec::new();
+
+        for variant in &en.variants {
+            let variant_ident = &variant.ident;
+            let variant_attr = self.cx.variant_attr(&variant.attrs);
+
+            let name = match variant_attr.rename {
+                Some(rename) => rename,
+                None => syn::LitStr::new(&variant_ident.to_string(), variant_ident.span()),
+            };
+
+            match &variant.fields {
+                syn::Fields::Unit => {
+                    variants.push(quote! {
+                        Self::#variant_ident => {
+                            #result::Ok(#value::__rune_macros__empty_variant(#enum_item, #name)?)
+                        }
+                    });
+                }
+                syn::Fields::Unnamed(unnamed) => {
+                    let mut bindings = Vec::new();
+                    let mut to_values = Vec::new();
+
+                    for (index, f) in unnamed.unnamed.iter().enumerate() {
+                        _ = self.cx.field_attrs(&f.attrs);
+                        let binding = quote::format_ident!("f{}", index);
+                        to_values.push(
+                            quote!(#vec::try_push(&mut values, #to_value::to_value(#binding)?)?),
+                        );
+                        bindings.push(binding);
+                    }
+
+                    let cap = unnamed.unnamed.len();
+
+                    variants.push(quote! {
+                        Self::#variant_ident(#(#bindings),*) => {
+                            let mut values = #vec::try_with_capacity(#cap)?;
+                            #(#to_values;)*
+                            #result::Ok(#value::__rune_macros__tuple_variant(#enum_item, #name, values)?)
+                        }
+                    });
+                }
+                syn::Fields::Named(named) => {
+                    let mut bindings = Vec::new();
+                    let mut names = Vec::new();
+                    let mut to_values = Vec::new();
+
+                    for f in &named.named {
+                        let f_ident = self.cx.field_ident(f)?;
+                        let attrs = self.cx.field_attrs(&f.attrs);
+
+                        names.push(match attrs.rename {
+                            Some(rename) => rename,
+                            None => syn::LitStr::new(&f_ident.to_string(), f_ident.span()),
+                        });
+
+                        to_values.push(
+                            quote!(#vec::try_push(&mut values, #to_value::to_value(#f_ident)?)?),
+                        );
+                        bindings.push(f_ident);
+                    }
+
+                    let cap = named.named.len();
+
+                    variants.push(quote! {
+                        Self::#variant_ident { #(#bindings),* } => {
+                            let mut values = #vec::try_with_capacity(#cap)?;
+                            #(#to_values;)*
+                            #result::Ok(#value::__rune_macros__struct_variant(#enum_item, #name, &[#(#names),*], values)?)
+                        }
+                    });
+                }
+            }
+        }
+
+        let generics = self.generics(input);
+        let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+        Ok(quote! {
+            #[automatically_derived]
+            impl #impl_generics #to_value for #ident #type_generics #where_clause {
+                fn to_value(self) -> #result<#value, #runtime_error> {
+                    match self {
+                        #(#variants,)*
+                    }
+                }
+            }
+        })
+    }
+
+    /// Get the generics of the type being derived, where every type parameter
+    /// is required to implement `ToValue`.
+    fn generics(&self, input: &syn::DeriveInput) -> syn::Generics {
+        let to_value = &self.tokens.to_value;
+        let mut generics = input.generics.clone();
+
+        let params = generics
+            .type_params()
+            .map(|param| param.ident.clone())
+            .collect::<Vec<_>>();
+
+        let where_clause = generics.make_where_clause();
+
+        for ident in params {
+            where_clause
+                .predicates
+                .push(syn::parse_quote!(#ident: #to_value));
+        }
+
+        generics
+    }
+
     /// Expand field decoding.
     fn expand_fields(&mut self, fields: &syn::Fields) -> Result<TokenStream, ()> {
         match fields {
@@ -97,9 +258,12 @@ impl Expander<'_> {
 
         for f in &named.named {
             let ident = self.cx.field_ident(f)?;
-            _ = self.cx.field_attrs(&f.attrs);
+            let attrs = self.cx.field_attrs(&f.attrs);
 
-            let name = syn::LitStr::new(&ident.to_string(), ident.span());
+            let name = match attrs.rename {
+                Some(rename) => rename,
+                None => syn::LitStr::new(&ident.to_string(), ident.span()),
+            };
 
             to_values.push(quote! {
                 object.insert(<#string as #try_from<_>>::try_from(#name)?, #to_value::to_value(self.#ident)?)?
@@ -127,10 +291,9 @@ pub(super) fn expand(cx: &Context, input: &syn::DeriveInput) -> Result<TokenStre
             }
         }
         syn::Data::Enum(en) => {
-            expander.cx.error(syn::Error::new_spanned(
-                en.enum_token,
-                "not supported on enums",
-            ));
+            if let Ok(expanded) = expander.expand_enum(input, &attr, en) {
+                return Ok(expanded);
+            }
         }
         syn::Data::Union(un) => {
             expander.cx.error(syn::Error::new_spanned(
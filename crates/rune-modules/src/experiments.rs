@@ -0,0 +1,110 @@
+//! `std::experiments` module for the [Rune Language].
+//!
+//! [Rune Language]: https://rune-rs.github.io
+//!
+//! ## Usage
+//!
+//! Add the following to your `Cargo.toml`:
+//!
+//! ```toml
+//! rune-modules = { version = "0.14.0", features = ["experiments"] }
+//! ```
+//!
+//! Install it into your context:
+//!
+//! ```rust
+//! let mut context = rune::Context::with_default_modules()?;
+//! context.install(rune_modules::experiments::module(true)?)?;
+//! # Ok::<_, rune::support::Error>(())
+//! ```
+//!
+//! Use it in Rune:
+//!
+//! ```rust,ignore
+//! #[std::experiments::to_object]
+//! struct Point { x, y }
+//!
+//! fn main() {
+//!     let object = Point { x: 1, y: 2 }.to_object();
+//!     dbg!(object);
+//! }
+//! ```
+
+use rune::ast::{self, OptionSpanned};
+use rune::compile;
+use rune::macros::{quote, MacroContext, ToTokens, TokenStream};
+use rune::parse::Parser;
+use rune::{ContextError, Module};
+
+/// Experimental macros which are not yet part of the language.
+///
+/// # Examples
+///
+/// ```rune
+/// #[std::experiments::to_object]
+/// struct Point { x, y }
+///
+/// let object = Point { x: 1, y: 2 }.to_object();
+/// assert_eq!(object, #{ x: 1, y: 2 });
+/// ```
+#[rune::module(::std::experiments)]
+pub fn module(_stdio: bool) -> Result<Module, ContextError> {
+    let mut module = Module::from_meta(self::module_meta)?;
+    module.macro_meta(to_object)?;
+    Ok(module)
+}
+
+/// Derive a `to_object` method for a struct, which converts an instance of it
+/// into an object with the same fields.
+///
+/// # Examples
+///
+/// ```rune
+/// #[std::experiments::to_object]
+/// struct Point { x, y }
+///
+/// let object = Point { x: 1, y: 2 }.to_object();
+/// assert_eq!(object.x, 1);
+/// assert_eq!(object.y, 2);
+/// assert_eq!(object.len(), 2);
+/// ```
+#[rune::attribute_macro]
+fn to_object(
+    cx: &mut MacroContext<'_, '_, '_>,
+    input: &TokenStream,
+    item: &TokenStream,
+) -> compile::Result<TokenStream> {
+    if let Some(span) = input.option_span() {
+        return Err(compile::Error::msg(span, "to_object takes no arguments"));
+    }
+
+    let mut p = Parser::from_token_stream(item, cx.macro_span());
+    let item = p.parse_all::<ast::ItemStruct>()?;
+
+    let ast::Fields::Named(fields) = &item.body else {
+        return Err(compile::Error::msg(
+            &item,
+            "to_object is only supported on structs with named fields",
+        ));
+    };
+
+    let mut entries = TokenStream::new();
+
+    for (field, _) in fields {
+        let name = &field.name;
+        quote!(#name: self. #name,).to_tokens(cx, &mut entries)?;
+    }
+
+    let name = item.ident;
+
+    Ok(quote! {
+        #item
+
+        impl #name {
+            fn to_object(self) {
+                #{ #entries }
+            }
+        }
+    }
+    .into_token_stream(cx)?)
+}
@@ -50,10 +50,12 @@
 
 use core::cmp::Ordering;
 use core::hash::Hash;
+use core::pin::Pin;
+use core::task::{ready, Context, Poll};
 
 use rune::alloc::fmt::TryWrite;
 use rune::alloc::prelude::*;
-use rune::runtime::{Bytes, Formatter, Hasher, Ref, VmResult};
+use rune::runtime::{Bytes, Formatter, Hasher, Ref, Stream, VmError, VmResult};
 use rune::{docstring, item, Any, ContextError, Module, ToConstValue, Value};
 
 /// A simple HTTP module for Rune.
@@ -83,6 +85,7 @@ pub fn module(_stdio: bool) -> Result<Module, ContextError> {
     module.function_meta(Response::text__meta)?;
     module.function_meta(Response::json__meta)?;
     module.function_meta(Response::bytes__meta)?;
+    module.function_meta(Response::bytes_stream__meta)?;
     module.function_meta(Response::status__meta)?;
     module.function_meta(Response::version__meta)?;
     module.function_meta(Response::content_length__meta)?;
@@ -1454,6 +1457,33 @@ impl Error {
     }
 }
 
+/// Adapts the chunks of a response body into values which can be produced by a
+/// [`Stream`].
+struct Chunks<S> {
+    inner: Pin<Box<S>>,
+}
+
+impl<S, B> futures_core::Stream for Chunks<S>
+where
+    S: futures_core::Stream<Item = reqwest::Result<B>>,
+    B: AsRef<[u8]>,
+{
+    type Item = Result<Result<Bytes, Error>, VmError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let chunk = match ready!(self.get_mut().inner.as_mut().poll_next(cx)) {
+            Some(Ok(chunk)) => chunk,
+            Some(Err(error)) => return Poll::Ready(Some(Ok(Err(Error::from(error))))),
+            None => return Poll::Ready(None),
+        };
+
+        Poll::Ready(Some(match Bytes::try_from(chunk.as_ref()) {
+            Ok(bytes) => Ok(Ok(bytes)),
+            Err(error) => Err(VmError::from(error)),
+        }))
+    }
+}
+
 /// An asynchronous Client to make Requests with.
 #[derive(Debug, Any)]
 #[rune(item = ::http)]
@@ -1529,6 +1559,29 @@ impl Response {
         Ok(Bytes::from_vec(bytes))
     }
 
+    /// Get the response body as a stream of chunks of bytes.
+    ///
+    /// Chunks are only downloaded as they are read from the stream, which
+    /// makes this suitable for large responses.
+    ///
+    /// ```rune,no_run
+    /// let response = http::get("http://example.com").await?;
+    /// let stream = response.bytes_stream();
+    ///
+    /// while let Some(chunk) = stream.next().await {
+    ///     let chunk = chunk?;
+    ///     dbg!(chunk.len());
+    /// }
+    /// ```
+    #[rune::function(keep)]
+    fn bytes_stream(self) -> VmResult<Stream> {
+        let chunks = Chunks {
+            inner: rune::vm_try!(Box::try_pin(self.response.bytes_stream())),
+        };
+
+        VmResult::Ok(rune::vm_try!(Stream::from_native(chunks)))
+    }
+
     /// Get the status code of the response.
     #[rune::function(keep, instance)]
     fn status(&self) -> StatusCode {
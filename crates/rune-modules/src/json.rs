@@ -18,6 +18,18 @@
 //! # Ok::<_, rune::support::Error>(())
 //! ```
 //!
+//! Deserialization of untrusted input can be bounded by installing the module
+//! with [`module_with_limits`]:
+//!
+//! ```rust
+//! use rune_modules::limits::Limits;
+//!
+//! let mut context = rune::Context::with_default_modules()?;
+//! let limits = Limits::new().with_input(64 * 1024).with_memory(1024 * 1024);
+//! context.install(rune_modules::json::module_with_limits(true, limits)?)?;
+//! # Ok::<_, rune::support::Error>(())
+//! ```
+//!
 //! Use it in Rune:
 //!
 //! ```rust,ignore
@@ -32,7 +44,9 @@
 use rune::alloc::fmt::TryWrite;
 use rune::alloc::{String, Vec};
 use rune::runtime::{Bytes, Formatter, Value, VmResult};
-use rune::{vm_write, Any, ContextError, Module};
+use rune::{docstring, vm_write, Any, ContextError, Module};
+
+use crate::limits::Limits;
 
 #[rune::module(::json)]
 /// Module for processing JSON.
@@ -44,13 +58,58 @@ use rune::{vm_write, Any, ContextError, Module};
 /// let object = json::from_string(json::to_string(object)?)?;
 /// assert_eq!(object, #{"number": 42, "string": "Hello World"});
 /// ```
-pub fn module(_stdio: bool) -> Result<Module, ContextError> {
+pub fn module(stdio: bool) -> Result<Module, ContextError> {
+    module_with_limits(stdio, Limits::new())
+}
+
+/// Construct the `json` module, where deserialization abides by the given
+/// [`Limits`].
+///
+/// # Examples
+///
+/// ```
+/// use rune_modules::limits::Limits;
+///
+/// let limits = Limits::new().with_input(1024);
+/// let module = rune_modules::json::module_with_limits(true, limits)?;
+/// # Ok::<_, rune::support::Error>(())
+/// ```
+pub fn module_with_limits(_stdio: bool, limits: Limits) -> Result<Module, ContextError> {
     let mut module = Module::from_meta(self::module_meta)?;
     module.ty::<Error>()?;
     module.function_meta(Error::display)?;
     module.function_meta(Error::debug)?;
-    module.function_meta(from_bytes)?;
-    module.function_meta(from_string)?;
+
+    module
+        .function("from_bytes", move |bytes: &[u8]| from_bytes(&limits, bytes))
+        .build()?
+        .docs(docstring! {
+            /// Convert JSON bytes into a rune value.
+            ///
+            /// # Examples
+            ///
+            /// ```rune
+            /// let object = json::from_bytes(b"{\"number\": 42, \"string\": \"Hello World\"}")?;
+            /// assert_eq!(object, #{"number": 42, "string": "Hello World"});
+            /// ```
+        })?;
+
+    module
+        .function("from_string", move |string: &str| {
+            from_string(&limits, string)
+        })
+        .build()?
+        .docs(docstring! {
+            /// Convert a JSON string into a rune value.
+            ///
+            /// # Examples
+            ///
+            /// ```rune
+            /// let object = json::from_string("{\"number\": 42, \"string\": \"Hello World\"}")?;
+            /// assert_eq!(object, #{"number": 42, "string": "Hello World"});
+            /// ```
+        })?;
+
     module.function_meta(to_string)?;
     module.function_meta(to_bytes)?;
     Ok(module)
@@ -81,30 +140,20 @@ impl From<serde_json::Error> for Error {
     }
 }
 
-/// Convert JSON bytes into a rune value.
-///
-/// # Examples
-///
-/// ```rune
-/// let object = json::from_bytes(b"{\"number\": 42, \"string\": \"Hello World\"}")?;
-/// assert_eq!(object, #{"number": 42, "string": "Hello World"});
-/// ```
-#[rune::function]
-fn from_bytes(bytes: &[u8]) -> Result<Value, Error> {
-    Ok(serde_json::from_slice(bytes)?)
+fn from_bytes(limits: &Limits, bytes: &[u8]) -> Result<Value, Error> {
+    limits.check_input::<serde_json::Error>(bytes.len())?;
+    let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+    let value = limits.deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(value)
 }
 
-/// Convert a JSON string into a rune value.
-///
-/// # Examples
-///
-/// ```rune
-/// let object = json::from_string("{\"number\": 42, \"string\": \"Hello World\"}")?;
-/// assert_eq!(object, #{"number": 42, "string": "Hello World"});
-/// ```
-#[rune::function]
-fn from_string(string: &str) -> Result<Value, Error> {
-    Ok(serde_json::from_str(string)?)
+fn from_string(limits: &Limits, string: &str) -> Result<Value, Error> {
+    limits.check_input::<serde_json::Error>(string.len())?;
+    let mut deserializer = serde_json::Deserializer::from_str(string);
+    let value = limits.deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(value)
 }
 
 /// Convert any value to a json string.
@@ -136,3 +185,92 @@ fn to_bytes(value: Value) -> Result<Bytes, Error> {
         Vec::try_from(serde_json::to_vec(&value)?).vm?,
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rune::alloc::limit;
+    use rune::runtime::Value;
+    use rune::{Context, Vm};
+
+    use super::{from_string, Limits};
+
+    fn error(limits: &Limits, input: &str) -> String {
+        match from_string(limits, input) {
+            Ok(..) => panic!("expected error"),
+            Err(error) => error.error.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_input_limit() {
+        let limits = Limits::new().with_input(16);
+
+        assert!(from_string(&limits, "[1, 2, 3]").is_ok());
+        let input = format!("[{}]", "1, ".repeat(1000));
+        assert_eq!(
+            error(&limits, &input),
+            "input exceeds configured limit of 16 bytes"
+        );
+    }
+
+    #[test]
+    fn test_memory_limit() {
+        let limits = Limits::new().with_memory(1024);
+
+        assert!(from_string(&limits, r#"{"a": [1, 2, 3]}"#).is_ok());
+
+        let input = format!("[{}]", vec!["\"hello world\""; 1000].join(", "));
+        assert!(error(&limits, &input)
+            .starts_with("value exceeds configured memory limit of 1024 bytes"));
+
+        // The memory limit in effect for the caller also applies.
+        let result = limit::with(1024, || from_string(&Limits::new(), &input)).call();
+        assert!(result.is_err());
+        assert_eq!(limit::get(), usize::MAX);
+    }
+
+    #[test]
+    fn test_depth_limit() {
+        let limits = Limits::new().with_depth(8);
+
+        assert!(from_string(&limits, "[[[[1]]]]").is_ok());
+
+        let input = format!("{}{}", "[".repeat(16), "]".repeat(16));
+        assert!(error(&limits, &input).starts_with("value exceeds configured depth limit of 8"));
+
+        // Without a configured depth limit, deeply nested input is still
+        // rejected gracefully by the parser.
+        let input = format!("{}{}", "[".repeat(100_000), "]".repeat(100_000));
+        assert!(from_string(&Limits::new(), &input).is_err());
+    }
+
+    #[test]
+    fn test_script_error() -> rune::support::Result<()> {
+        let mut context = Context::with_default_modules()?;
+        context.install(super::module_with_limits(
+            true,
+            Limits::new().with_input(8),
+        )?)?;
+        let runtime = Arc::new(context.runtime()?);
+
+        let mut sources = rune::sources! {
+            entry => {
+                pub fn main() {
+                    match json::from_string("[1, 2, 3, 4, 5]") {
+                        Ok(..) => String::from("ok"),
+                        Err(error) => format!("{error}"),
+                    }
+                }
+            }
+        };
+
+        let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+        let mut vm = Vm::new(runtime, Arc::new(unit));
+        let output: Value = vm.call(["main"], ())?;
+        let output: String = rune::from_value(output)?;
+        assert_eq!(output, "input exceeds configured limit of 8 bytes");
+        Ok(())
+    }
+}
@@ -27,6 +27,7 @@
 //! See each module for documentation:
 //! * [base64]
 //! * [core]
+//! * [experiments]
 //! * [fmt]
 //! * [fs]
 //! * [http]
@@ -36,6 +37,8 @@
 //! * [process]
 //! * [rand]
 //! * [signal]
+//! * [sync]
+//! * [task]
 //! * [test]
 //! * [time]
 //! * [toml]
@@ -45,6 +48,7 @@
 //! ## Features
 //!
 //! * `core` for the [core module][toml]
+//! * `experiments` for the [experiments module][experiments]
 //! * `fmt` for the [fmt module][fmt]
 //! * `fs` for the [fs module][fs]
 //! * `full` includes all modules.
@@ -55,11 +59,14 @@
 //! * `process` for the [process module][process]
 //! * `rand` for the [rand module][rand]
 //! * `signal` for the [signal module][signal]
+//! * `sync` for the [sync module][sync]
+//! * `task` for the [task module][task]
 //! * `test` for the [test module][test]
 //! * `time` for the [time module][time]
 //! * `toml` for the [toml module][toml]
 //!
 //! [core]: https://docs.rs/rune-modules/0/rune_modules/core/
+//! [experiments]: https://docs.rs/rune-modules/0/rune_modules/experiments/
 //! [fmt]: https://docs.rs/rune-modules/0/rune_modules/fmt/
 //! [fs]: https://docs.rs/rune-modules/0/rune_modules/fs/
 //! [http]: https://docs.rs/rune-modules/0/rune_modules/http/
@@ -69,6 +76,8 @@
 //! [process]: https://docs.rs/rune-modules/0/rune_modules/process/
 //! [rand]: https://docs.rs/rune-modules/0/rune_modules/rand/
 //! [signal]: https://docs.rs/rune-modules/0/rune_modules/signal/
+//! [sync]: https://docs.rs/rune-modules/0/rune_modules/sync/
+//! [task]: https://docs.rs/rune-modules/0/rune_modules/task/
 //! [test]: https://docs.rs/rune-modules/0/rune_modules/test/
 //! [time]: https://docs.rs/rune-modules/0/rune_modules/time/
 //! [toml]: https://docs.rs/rune-modules/0/rune_modules/toml/
@@ -102,12 +111,36 @@ macro_rules! entry {
         pub fn default_context() -> Result<rune::Context, rune::ContextError> {
             with_config(true)
         }
+
+        /// Register all enabled modules as known to the given context without
+        /// installing them.
+        ///
+        /// This doesn't make any of the modules available, but if a script
+        /// tries to use a function from one of them the diagnostics will note
+        /// which module it is provided by. See
+        /// [`Context::known_module`](rune::Context::known_module).
+        pub fn register_known(context: &mut rune::Context) -> Result<(), rune::ContextError> {
+            $(
+                #[allow(deprecated)]
+                #[cfg(feature = $name)]
+                {
+                    context.known_module(self::$ident::module(true)?)?;
+                    $(context.known_module(self::$ident::$module::module(true)?)?;)*
+                }
+            )*
+
+            let _ = context;
+            Ok(())
+        }
     }
 }
 
 #[cfg(feature = "base64")]
 pub mod base64;
 
+#[cfg(feature = "experiments")]
+pub mod experiments;
+
 #[cfg(feature = "fs")]
 pub mod fs;
 
@@ -117,6 +150,9 @@ pub mod http;
 #[cfg(feature = "json")]
 pub mod json;
 
+#[cfg(any(feature = "json", feature = "toml"))]
+pub mod limits;
+
 #[cfg(feature = "process")]
 pub mod process;
 
@@ -126,6 +162,12 @@ pub mod rand;
 #[cfg(feature = "signal")]
 pub mod signal;
 
+#[cfg(feature = "sync")]
+pub mod sync;
+
+#[cfg(feature = "task")]
+pub mod task;
+
 #[cfg(feature = "test")]
 pub mod test;
 
@@ -137,12 +179,15 @@ pub mod toml;
 
 entry! {
     {base64, "base64"},
+    {experiments, "experiments"},
     {fs, "fs"},
     {http, "http"},
     {json, "json"},
     {process, "process"},
     {rand, "rand"},
     {signal, "signal"},
+    {sync, "sync"},
+    {task, "task"},
     {test, "test"},
     {time, "time"},
     {toml, "toml", ser, de},
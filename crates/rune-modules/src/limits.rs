@@ -0,0 +1,334 @@
+//! Limits applied when deserializing untrusted input.
+//!
+//! These are used by the `json` and `toml` modules through
+//! `json::module_with_limits` and `toml::module_with_limits`.
+
+use core::cell::Cell;
+use core::fmt;
+
+use rune::alloc::limit;
+use rune::alloc::prelude::*;
+use rune::alloc::{self, String};
+use rune::runtime::{Bytes, Object, Value, Vec};
+use serde::de::{self, DeserializeSeed, Error as _};
+
+/// Limits applied when deserializing values.
+///
+/// By default no limits are in effect.
+///
+/// # Examples
+///
+/// ```
+/// use rune_modules::limits::Limits;
+///
+/// let limits = Limits::new()
+///     .with_input(1024 * 1024)
+///     .with_memory(4 * 1024 * 1024)
+///     .with_depth(32);
+/// ```
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct Limits {
+    input: Option<usize>,
+    memory: Option<usize>,
+    depth: Option<usize>,
+}
+
+impl Limits {
+    /// Construct a new set of limits where nothing is limited.
+    pub const fn new() -> Self {
+        Self {
+            input: None,
+            memory: None,
+            depth: None,
+        }
+    }
+
+    /// Limit the size of the input in bytes.
+    ///
+    /// This is checked up front, before any deserialization takes place.
+    pub const fn with_input(mut self, input: usize) -> Self {
+        self.input = Some(input);
+        self
+    }
+
+    /// Limit the number of bytes which may be allocated for the produced
+    /// value.
+    ///
+    /// This is charged against [`rune::alloc::limit`] as values are being
+    /// constructed, so any limit in effect for the caller also applies.
+    pub const fn with_memory(mut self, memory: usize) -> Self {
+        self.memory = Some(memory);
+        self
+    }
+
+    /// Limit how deeply nested the produced value may be.
+    pub const fn with_depth(mut self, depth: usize) -> Self {
+        self.depth = Some(depth);
+        self
+    }
+
+    /// Check that the input abides by the configured input limit.
+    pub(crate) fn check_input<E>(&self, len: usize) -> Result<(), E>
+    where
+        E: de::Error,
+    {
+        match self.input {
+            Some(input) if len > input => Err(E::custom(format_args!(
+                "input exceeds configured limit of {input} bytes"
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Deserialize a value while abiding by the current limits.
+    pub(crate) fn deserialize<'de, D>(&self, deserializer: D) -> Result<Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let state = State {
+            limits: self,
+            start: limit::get(),
+            depth: Cell::new(0),
+        };
+
+        Bounded { state: &state }.deserialize(deserializer)
+    }
+}
+
+impl Default for Limits {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct State<'a> {
+    limits: &'a Limits,
+    /// The remaining memory when deserialization started.
+    start: usize,
+    depth: Cell<usize>,
+}
+
+impl State<'_> {
+    /// Check that the memory allocated so far is within the limit.
+    fn charge<E>(&self) -> Result<(), E>
+    where
+        E: de::Error,
+    {
+        let Some(memory) = self.limits.memory else {
+            return Ok(());
+        };
+
+        if self.start.saturating_sub(limit::get()) > memory {
+            return Err(E::custom(format_args!(
+                "value exceeds configured memory limit of {memory} bytes"
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn enter<E>(&self) -> Result<(), E>
+    where
+        E: de::Error,
+    {
+        let depth = self.depth.get() + 1;
+
+        if let Some(limit) = self.limits.depth {
+            if depth > limit {
+                return Err(E::custom(format_args!(
+                    "value exceeds configured depth limit of {limit}"
+                )));
+            }
+        }
+
+        self.depth.set(depth);
+        Ok(())
+    }
+
+    fn leave(&self) {
+        self.depth.set(self.depth.get().saturating_sub(1));
+    }
+
+    fn value<E>(&self, value: Result<Value, alloc::Error>) -> Result<Value, E>
+    where
+        E: de::Error,
+    {
+        let value = value.map_err(E::custom)?;
+        self.charge()?;
+        Ok(value)
+    }
+}
+
+/// A deserialization seed which tracks memory and depth.
+#[derive(Clone, Copy)]
+struct Bounded<'a> {
+    state: &'a State<'a>,
+}
+
+impl<'de> DeserializeSeed<'de> for Bounded<'_> {
+    type Value = Value;
+
+    #[inline]
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> de::Visitor<'de> for Bounded<'_> {
+    type Value = Value;
+
+    #[inline]
+    fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("any valid value")
+    }
+
+    #[inline]
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let v = v.try_to_owned().map_err(E::custom)?;
+        self.state.value(Value::try_from(v))
+    }
+
+    #[inline]
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let v = alloc::Vec::try_from(v).map_err(E::custom)?;
+        self.state.value(Value::try_from(Bytes::from_vec(v)))
+    }
+
+    #[inline]
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Value::from(v))
+    }
+
+    #[inline]
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Value::from(v as i64))
+    }
+
+    #[inline]
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Value::from(v))
+    }
+
+    #[inline]
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Value::from(v))
+    }
+
+    #[inline]
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let some = self.deserialize(deserializer)?;
+        self.state.value(Value::try_from(Some(some)))
+    }
+
+    #[inline]
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.state.value(Value::try_from(None))
+    }
+
+    #[inline]
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Value::from(()))
+    }
+
+    fn visit_seq<V>(self, mut visitor: V) -> Result<Self::Value, V::Error>
+    where
+        V: de::SeqAccess<'de>,
+    {
+        self.state.enter()?;
+
+        // The size hint is not trusted, since it might be derived from the
+        // input.
+        let mut vec = alloc::Vec::new();
+
+        while let Some(elem) = visitor.next_element_seed(self)? {
+            vec.try_push(elem).map_err(V::Error::custom)?;
+            self.state.charge()?;
+        }
+
+        self.state.leave();
+        self.state.value(Value::try_from(Vec::from(vec)))
+    }
+
+    fn visit_map<V>(self, mut visitor: V) -> Result<Self::Value, V::Error>
+    where
+        V: de::MapAccess<'de>,
+    {
+        self.state.enter()?;
+
+        let mut object = Object::new();
+
+        while let Some(key) = visitor.next_key_seed(Key)? {
+            self.state.charge()?;
+            let value = visitor.next_value_seed(self)?;
+            object.insert(key, value).map_err(V::Error::custom)?;
+            self.state.charge()?;
+        }
+
+        self.state.leave();
+        self.state.value(Value::try_from(object))
+    }
+}
+
+/// A seed for deserializing object keys.
+struct Key;
+
+impl<'de> DeserializeSeed<'de> for Key {
+    type Value = String;
+
+    #[inline]
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl de::Visitor<'_> for Key {
+    type Value = String;
+
+    #[inline]
+    fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("a string key")
+    }
+
+    #[inline]
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        v.try_to_owned().map_err(E::custom)
+    }
+}
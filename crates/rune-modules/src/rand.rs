@@ -30,9 +30,27 @@
 //!     println(`Random int between -100 and 100: {rand_int_range}`);
 //! }
 //! ```
+//!
+//! Seeded generators produce reproducible results, which can be used together
+//! with the sampling functions:
+//!
+//! ```rust,ignore
+//! fn main() {
+//!     let rng = rand::WyRand::new_seed(42);
+//!     let picked = rand::sample([1, 2, 3, 4, 5], 2, rng)?;
+//!     let choice = rand::weighted_choice(["a", "b"], [1, 3], rng)?;
+//!     let bytes = rng.fill_bytes(16);
+//!     Ok(())
+//! }
+//! ```
+
+use core::fmt;
 
 use nanorand::Rng;
-use rune::{Any, ContextError, Module};
+use rune::alloc::fmt::TryWrite;
+use rune::alloc::Vec;
+use rune::runtime::{self, Bytes, Formatter, RuntimeError, TypeHash, Value, VmResult};
+use rune::{vm_write, Any, ContextError, Module};
 
 /// Construct the `rand` module.
 pub fn module(_stdio: bool) -> Result<Module, ContextError> {
@@ -58,11 +76,260 @@ pub fn module(_stdio: bool) -> Result<Module, ContextError> {
     module.associated_function("int", Pcg64::int)?;
     module.associated_function("int_range", Pcg64::int_range)?;
 
+    module.associated_function("fill_bytes", WyRand::fill_bytes)?;
+    module.associated_function("fill_bytes", Pcg64::fill_bytes)?;
+
     module.function("int", int).build()?;
     module.function("int_range", int_range).build()?;
+
+    module.ty::<Error>()?;
+    module.function_meta(Error::display_fmt)?;
+    module.function_meta(Error::debug_fmt)?;
+    module.function_meta(sample__meta)?;
+    module.function_meta(weighted_choice__meta)?;
     Ok(module)
 }
 
+/// Error raised by the sampling functions in the `rand` module.
+#[derive(Debug, Any)]
+#[rune(item = ::rand)]
+struct Error {
+    kind: ErrorKind,
+}
+
+#[derive(Debug)]
+enum ErrorKind {
+    SampleTooLarge { amount: usize, len: usize },
+    WeightsMismatch { items: usize, weights: usize },
+    InvalidWeight { index: usize },
+    NonPositiveWeights,
+}
+
+impl Error {
+    #[rune::function(protocol = DISPLAY_FMT)]
+    fn display_fmt(&self, f: &mut Formatter) -> VmResult<()> {
+        vm_write!(f, "{}", self)
+    }
+
+    #[rune::function(protocol = DEBUG_FMT)]
+    fn debug_fmt(&self, f: &mut Formatter) -> VmResult<()> {
+        vm_write!(f, "{:?}", self.kind)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ErrorKind::SampleTooLarge { amount, len } => {
+                write!(
+                    f,
+                    "Cannot sample {amount} elements from a collection of length {len}"
+                )
+            }
+            ErrorKind::WeightsMismatch { items, weights } => {
+                write!(f, "Got {weights} weights for {items} items")
+            }
+            ErrorKind::InvalidWeight { index } => {
+                write!(
+                    f,
+                    "Weight at index {index} must be a finite non-negative number"
+                )
+            }
+            ErrorKind::NonPositiveWeights => write!(f, "Sum of weights must be positive"),
+        }
+    }
+}
+
+impl From<ErrorKind> for Error {
+    #[inline]
+    fn from(kind: ErrorKind) -> Self {
+        Self { kind }
+    }
+}
+
+/// A random number generator which can be used by the sampling functions.
+trait Generator {
+    /// Generate a number in the range `0..upper`.
+    fn below(&mut self, upper: u64) -> u64;
+
+    /// Generate a float in the range `0.0..=1.0`.
+    fn float(&mut self) -> f64;
+
+    /// Fill the given buffer with random bytes.
+    fn fill(&mut self, bytes: &mut [u8]);
+}
+
+macro_rules! generator {
+    ($ty:ty) => {
+        impl Generator for $ty {
+            #[inline]
+            fn below(&mut self, upper: u64) -> u64 {
+                self.inner.generate_range(0..upper)
+            }
+
+            #[inline]
+            fn float(&mut self) -> f64 {
+                self.inner.generate::<f64>()
+            }
+
+            #[inline]
+            fn fill(&mut self, bytes: &mut [u8]) {
+                self.inner.fill_bytes(bytes);
+            }
+        }
+
+        impl $ty {
+            /// Generate a collection of `n` random bytes.
+            fn fill_bytes(&mut self, n: usize) -> VmResult<Bytes> {
+                fill_bytes(self, n)
+            }
+        }
+    };
+}
+
+generator!(WyRand);
+generator!(Pcg64);
+
+/// Access the generator stored in `rng`, which must be one of the random number
+/// generators provided by this module.
+fn with_generator<O>(
+    rng: &Value,
+    f: impl FnOnce(&mut dyn Generator) -> O,
+) -> Result<O, RuntimeError> {
+    match rng.type_hash() {
+        WyRand::HASH => Ok(f(&mut *rng.borrow_mut::<WyRand>()?)),
+        Pcg64::HASH => Ok(f(&mut *rng.borrow_mut::<Pcg64>()?)),
+        _ => Err(RuntimeError::expected::<WyRand>(rng.type_info())),
+    }
+}
+
+fn fill_bytes(rng: &mut dyn Generator, n: usize) -> VmResult<Bytes> {
+    let mut bytes = rune::vm_try!(Vec::try_with_capacity(n));
+    rune::vm_try!(bytes.try_resize(n, 0));
+    rng.fill(&mut bytes);
+    VmResult::Ok(Bytes::from_vec(bytes))
+}
+
+/// Pick `amount` distinct elements from `values` without replacement using the
+/// random number generator `rng`.
+///
+/// The order of the returned elements is random. Using a seeded generator
+/// produces the same sample for the same input.
+///
+/// # Errors
+///
+/// Errors if `amount` is larger than the number of values.
+///
+/// # Examples
+///
+/// ```rune
+/// let rng = rand::WyRand::new_seed(42);
+/// let picked = rand::sample([1, 2, 3, 4, 5], 3, rng)?;
+/// assert_eq!(picked.len(), 3);
+/// ```
+#[rune::function(keep, vm_result)]
+fn sample(values: &[Value], amount: usize, rng: Value) -> Result<runtime::Vec, Error> {
+    if amount > values.len() {
+        return Err(Error::from(ErrorKind::SampleTooLarge {
+            amount,
+            len: values.len(),
+        }));
+    }
+
+    // Partial Fisher-Yates shuffle over indexes, which only needs to perform
+    // `amount` swaps.
+    let mut indexes = Vec::try_with_capacity(values.len()).vm?;
+
+    for index in 0..values.len() {
+        indexes.try_push(index).vm?;
+    }
+
+    with_generator(&rng, |rng| {
+        for n in 0..amount {
+            let remaining = (values.len() - n) as u64;
+            let swap = n + rng.below(remaining) as usize;
+            indexes.swap(n, swap);
+        }
+    })
+    .vm?;
+
+    let mut output = runtime::Vec::with_capacity(amount).vm?;
+
+    for &index in &indexes[..amount] {
+        output.push(values[index].clone()).vm?;
+    }
+
+    Ok(output)
+}
+
+/// Pick one of `items` at random, where each item is picked with a probability
+/// proportional to the corresponding entry in `weights`.
+///
+/// Weights may be integers or floats.
+///
+/// # Errors
+///
+/// Errors if the number of weights doesn't match the number of items, if any
+/// weight is negative or not finite, or if the weights don't sum up to a
+/// positive number.
+///
+/// # Examples
+///
+/// ```rune
+/// let rng = rand::WyRand::new_seed(42);
+/// let item = rand::weighted_choice(["a", "b", "c"], [0, 1, 0], rng)?;
+/// assert_eq!(item, "b");
+///
+/// assert!(rand::weighted_choice(["a", "b"], [1], rng).is_err());
+/// assert!(rand::weighted_choice(["a", "b"], [0, 0], rng).is_err());
+/// ```
+#[rune::function(keep, vm_result)]
+fn weighted_choice(items: &[Value], weights: &[Value], rng: Value) -> Result<Value, Error> {
+    if items.len() != weights.len() {
+        return Err(Error::from(ErrorKind::WeightsMismatch {
+            items: items.len(),
+            weights: weights.len(),
+        }));
+    }
+
+    let mut cumulative = Vec::try_with_capacity(weights.len()).vm?;
+    let mut total = 0.0f64;
+
+    for (index, weight) in weights.iter().enumerate() {
+        let weight = match weight.as_float() {
+            Ok(weight) => weight,
+            Err(..) => match weight.as_integer::<i64>() {
+                Ok(weight) => weight as f64,
+                Err(..) => return Err(Error::from(ErrorKind::InvalidWeight { index })),
+            },
+        };
+
+        if !weight.is_finite() || weight < 0.0 {
+            return Err(Error::from(ErrorKind::InvalidWeight { index }));
+        }
+
+        total += weight;
+        cumulative.try_push(total).vm?;
+    }
+
+    if total <= 0.0 || !total.is_finite() {
+        return Err(Error::from(ErrorKind::NonPositiveWeights));
+    }
+
+    let target = with_generator(&rng, |rng| rng.float()).vm? * total;
+
+    // The generated float is inclusive of `1.0`, so the target might land
+    // exactly on the total in which case we pick the last item with a
+    // non-zero weight.
+    let index = cumulative
+        .iter()
+        .position(|&c| target < c)
+        .or_else(|| cumulative.iter().rposition(|&c| c > 0.0))
+        .unwrap_or_default();
+
+    Ok(items[index].clone())
+}
+
 #[derive(Any)]
 #[rune(item = ::rand)]
 struct WyRand {
@@ -137,7 +404,154 @@ fn int_range(lower: i64, upper: i64) -> rune::support::Result<i64> {
 
 #[cfg(test)]
 mod tests {
-    use super::{int, int_range};
+    use rune::runtime::{Value, VmResult};
+
+    use super::{int, int_range, ErrorKind, Pcg64, WyRand};
+
+    fn wyrand(seed: i64) -> Value {
+        rune::to_value(WyRand::new_seed(seed)).unwrap()
+    }
+
+    fn values(values: &[i64]) -> Vec<Value> {
+        values.iter().map(|v| rune::to_value(*v).unwrap()).collect()
+    }
+
+    fn integers(values: &[Value]) -> Vec<i64> {
+        values.iter().map(|v| v.as_integer().unwrap()).collect()
+    }
+
+    fn sample(values: &[Value], amount: usize, rng: Value) -> Result<Vec<i64>, super::Error> {
+        match super::sample(values, amount, rng) {
+            VmResult::Ok(result) => result.map(|v| integers(&v)),
+            VmResult::Err(error) => panic!("{error}"),
+        }
+    }
+
+    fn weighted_choice(
+        items: &[Value],
+        weights: &[Value],
+        rng: Value,
+    ) -> Result<i64, super::Error> {
+        match super::weighted_choice(items, weights, rng) {
+            VmResult::Ok(result) => result.map(|v| v.as_integer().unwrap()),
+            VmResult::Err(error) => panic!("{error}"),
+        }
+    }
+
+    #[test]
+    fn test_sample_reproducible() {
+        let items = values(&(0..100).collect::<Vec<_>>());
+
+        let a = sample(&items, 10, wyrand(42)).unwrap();
+        let b = sample(&items, 10, wyrand(42)).unwrap();
+        assert_eq!(a, b);
+
+        let c = sample(&items, 10, wyrand(43)).unwrap();
+        assert_ne!(a, c);
+
+        let mut distinct = a.clone();
+        distinct.sort();
+        distinct.dedup();
+        assert_eq!(distinct.len(), 10);
+
+        let pcg = rune::to_value(Pcg64::new_seed(42)).unwrap();
+        assert_eq!(sample(&items, 100, pcg).unwrap().len(), 100);
+        assert!(sample(&items, 0, wyrand(1)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_sample_shares_generator_state() {
+        let items = values(&(0..100).collect::<Vec<_>>());
+        let rng = wyrand(7);
+
+        let a = sample(&items, 10, rng.clone()).unwrap();
+        let b = sample(&items, 10, rng).unwrap();
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_sample_too_large() {
+        let items = values(&[1, 2, 3]);
+        let error = sample(&items, 4, wyrand(1)).unwrap_err();
+
+        assert!(matches!(
+            error.kind,
+            ErrorKind::SampleTooLarge { amount: 4, len: 3 }
+        ));
+    }
+
+    #[test]
+    fn test_weighted_choice_distribution() {
+        let items = values(&[0, 1, 2]);
+        let weights = values(&[1, 0, 3]);
+        let rng = wyrand(1234);
+
+        let mut counts = [0usize; 3];
+
+        for _ in 0..10000 {
+            let index = weighted_choice(&items, &weights, rng.clone()).unwrap();
+            counts[index as usize] += 1;
+        }
+
+        assert_eq!(counts[1], 0);
+        // Expected ratio is 1:3, so allow for some variance.
+        assert!((2000..3000).contains(&counts[0]), "{counts:?}");
+        assert!((7000..8000).contains(&counts[2]), "{counts:?}");
+    }
+
+    #[test]
+    fn test_weighted_choice_reproducible() {
+        let items = values(&(0..10).collect::<Vec<_>>());
+        let weights = values(&[1; 10]);
+
+        let sequence = |rng: Value| {
+            (0..20)
+                .map(|_| weighted_choice(&items, &weights, rng.clone()).unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(sequence(wyrand(9)), sequence(wyrand(9)));
+        assert_ne!(sequence(wyrand(9)), sequence(wyrand(10)));
+    }
+
+    #[test]
+    fn test_weighted_choice_errors() {
+        let items = values(&[1, 2]);
+
+        let error = weighted_choice(&items, &values(&[1]), wyrand(1)).unwrap_err();
+        assert!(matches!(
+            error.kind,
+            ErrorKind::WeightsMismatch {
+                items: 2,
+                weights: 1
+            }
+        ));
+
+        let error = weighted_choice(&items, &values(&[0, 0]), wyrand(1)).unwrap_err();
+        assert!(matches!(error.kind, ErrorKind::NonPositiveWeights));
+
+        let error = weighted_choice(&items, &values(&[1, -1]), wyrand(1)).unwrap_err();
+        assert!(matches!(error.kind, ErrorKind::InvalidWeight { index: 1 }));
+    }
+
+    #[test]
+    fn test_fill_bytes() {
+        let a = WyRand::new_seed(3).fill_bytes(32);
+        let b = WyRand::new_seed(3).fill_bytes(32);
+
+        let (VmResult::Ok(a), VmResult::Ok(b)) = (a, b) else {
+            panic!("failed to fill bytes");
+        };
+
+        assert_eq!(a.len(), 32);
+        assert_eq!(a, b);
+
+        let VmResult::Ok(c) = WyRand::new_seed(4).fill_bytes(32) else {
+            panic!("failed to fill bytes");
+        };
+
+        assert_ne!(a, c);
+    }
 
     #[test]
     fn test_range_is_exclusive() {
@@ -0,0 +1,598 @@
+//! The native `sync` module for the [Rune Language].
+//!
+//! [Rune Language]: https://rune-rs.github.io
+//!
+//! ## Usage
+//!
+//! Add the following to your `Cargo.toml`:
+//!
+//! ```toml
+//! rune-modules = { version = "0.14.0", features = ["sync"] }
+//! ```
+//!
+//! Install it into your context:
+//!
+//! ```rust
+//! let mut context = rune::Context::with_default_modules()?;
+//! context.install(rune_modules::sync::module(true)?)?;
+//! # Ok::<_, rune::support::Error>(())
+//! ```
+//!
+//! Use it in Rune:
+//!
+//! ```rust,ignore
+//! async fn producer(tx) {
+//!     for n in 0..10 {
+//!         tx.send(n).await?;
+//!     }
+//!
+//!     Ok(())
+//! }
+//!
+//! async fn consumer(rx) {
+//!     while let Some(n) = rx.recv().await {
+//!         println!("Received: {n}");
+//!     }
+//! }
+//! ```
+
+use std::sync::Arc;
+
+use rune::alloc::fmt::TryWrite;
+use rune::runtime::{ConstValue, Formatter, Mut, Ref, Value, VmResult};
+use rune::{item, vm_panic, vm_try, vm_write, Any, ContextError, Module};
+use tokio::sync::{mpsc, OwnedMutexGuard};
+
+/// Synchronization primitives for communicating between script executions.
+///
+/// Everything in this module can be shared with script executions running on
+/// other threads, such as through a [`SyncFunction`]. Values which are sent
+/// over a channel or stored in a mutex are therefore converted into constant
+/// values, and values which can't be represented as constants, like functions
+/// or external types, produce an error.
+///
+/// # Tokio
+///
+/// This function is implemented using [Tokio], but since it only uses
+/// synchronization primitives it doesn't require the Tokio runtime to be in
+/// scope.
+///
+/// [`SyncFunction`]: rune::runtime::SyncFunction
+/// [Tokio]: https://tokio.rs
+#[rune::module(::sync)]
+pub fn module(_stdio: bool) -> Result<Module, ContextError> {
+    let mut module = Module::from_meta(self::module_meta)?;
+
+    module.function_meta(channel__meta)?;
+
+    module.ty::<Sender>()?;
+    module.function_meta(Sender::send__meta)?;
+    module.function_meta(Sender::try_send__meta)?;
+    module.function_meta(Sender::is_closed__meta)?;
+    module.function_meta(Sender::clone__meta)?;
+    module.implement_trait::<Sender>(item!(::std::clone::Clone))?;
+
+    module.ty::<Receiver>()?;
+    module.function_meta(Receiver::recv__meta)?;
+    module.function_meta(Receiver::try_recv__meta)?;
+
+    module.ty::<Mutex>()?;
+    module.function_meta(Mutex::new__meta)?;
+    module.function_meta(Mutex::lock__meta)?;
+    module.function_meta(Mutex::try_lock__meta)?;
+    module.function_meta(Mutex::clone__meta)?;
+    module.implement_trait::<Mutex>(item!(::std::clone::Clone))?;
+
+    module.ty::<MutexGuard>()?;
+    module.function_meta(MutexGuard::get__meta)?;
+    module.function_meta(MutexGuard::set__meta)?;
+
+    module.ty::<Error>()?;
+    module.function_meta(Error::display_fmt__meta)?;
+    module.function_meta(Error::debug_fmt__meta)?;
+    Ok(module)
+}
+
+/// Creates a bounded channel for communicating between asynchronous tasks with
+/// backpressure.
+///
+/// The channel will buffer up to the provided number of messages. Once the
+/// buffer is full, attempts to send new messages will wait until a message is
+/// received from the channel. The provided buffer capacity must be at least 1.
+///
+/// # Panics
+///
+/// Panics if the buffer capacity is 0.
+///
+/// # Examples
+///
+/// ```rune
+/// let (tx, rx) = sync::channel(10);
+///
+/// tx.send(1).await?;
+/// tx.send(2).await?;
+/// drop(tx);
+///
+/// assert_eq!(rx.recv().await, Some(1));
+/// assert_eq!(rx.recv().await, Some(2));
+/// assert_eq!(rx.recv().await, None);
+/// ```
+#[rune::function(keep)]
+pub fn channel(capacity: usize) -> VmResult<(Sender, Receiver)> {
+    if capacity == 0 {
+        vm_panic!("channel capacity must be greater than zero");
+    }
+
+    let (tx, rx) = mpsc::channel(capacity);
+    VmResult::Ok((Sender { inner: tx }, Receiver { inner: rx }))
+}
+
+/// Sends values to the associated [`Receiver`].
+///
+/// Instances are created by the [`channel`] function.
+#[derive(Debug, Clone, Any)]
+#[rune(item = ::sync)]
+pub struct Sender {
+    inner: mpsc::Sender<ConstValue>,
+}
+
+impl Sender {
+    /// Sends a value, waiting until there is capacity.
+    ///
+    /// An error is returned if the receiving half of the channel has been
+    /// closed, and the value can't be sent.
+    ///
+    /// # Cancel safety
+    ///
+    /// If `send` is used as the event in a `select` expression and some other
+    /// branch completes first, then it is guaranteed that the message was not
+    /// sent.
+    ///
+    /// # Examples
+    ///
+    /// ```rune
+    /// let (tx, rx) = sync::channel(1);
+    ///
+    /// tx.send(#{ "name": "rune" }).await?;
+    /// let value = rx.recv().await.expect("value was sent");
+    /// assert_eq!(value.name, "rune");
+    /// ```
+    ///
+    /// Values which can't be shared across threads can't be sent:
+    ///
+    /// ```rune,should_panic
+    /// let (tx, rx) = sync::channel(1);
+    /// tx.send(|| 42).await?;
+    /// ```
+    #[rune::function(keep, instance, path = Self::send)]
+    async fn send(this: Ref<Self>, value: Value) -> VmResult<Result<(), Error>> {
+        let value = vm_try!(ConstValue::from_value(&value));
+
+        VmResult::Ok(match this.inner.send(value).await {
+            Ok(()) => Ok(()),
+            Err(..) => Err(Error::new(ErrorKind::Closed)),
+        })
+    }
+
+    /// Attempts to immediately send a value.
+    ///
+    /// An error is returned if the channel is full or if the receiving half of
+    /// the channel has been closed.
+    ///
+    /// # Examples
+    ///
+    /// ```rune
+    /// let (tx, rx) = sync::channel(1);
+    ///
+    /// tx.try_send(1)?;
+    /// assert!(tx.try_send(2).is_err());
+    /// ```
+    #[rune::function(keep, instance)]
+    fn try_send(&self, value: Value) -> VmResult<Result<(), Error>> {
+        let value = vm_try!(ConstValue::from_value(&value));
+
+        VmResult::Ok(match self.inner.try_send(value) {
+            Ok(()) => Ok(()),
+            Err(mpsc::error::TrySendError::Full(..)) => Err(Error::new(ErrorKind::Full)),
+            Err(mpsc::error::TrySendError::Closed(..)) => Err(Error::new(ErrorKind::Closed)),
+        })
+    }
+
+    /// Checks if the receiving half of the channel has been closed.
+    ///
+    /// # Examples
+    ///
+    /// ```rune
+    /// let (tx, rx) = sync::channel(1);
+    /// assert!(!tx.is_closed());
+    /// drop(rx);
+    /// assert!(tx.is_closed());
+    /// ```
+    #[rune::function(keep, instance)]
+    fn is_closed(&self) -> bool {
+        self.inner.is_closed()
+    }
+
+    /// Clone the sender, producing another handle which can send values to the
+    /// same channel.
+    ///
+    /// The channel is closed once every sender has been dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rune
+    /// let (tx, rx) = sync::channel(2);
+    /// let tx2 = tx.clone();
+    ///
+    /// tx.send(1).await?;
+    /// tx2.send(2).await?;
+    /// drop(tx);
+    /// drop(tx2);
+    ///
+    /// assert_eq!(rx.recv().await, Some(1));
+    /// assert_eq!(rx.recv().await, Some(2));
+    /// assert_eq!(rx.recv().await, None);
+    /// ```
+    #[rune::function(keep, instance, protocol = CLONE)]
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+/// Receives values from the associated [`Sender`].
+///
+/// Instances are created by the [`channel`] function.
+#[derive(Debug, Any)]
+#[rune(item = ::sync)]
+pub struct Receiver {
+    inner: mpsc::Receiver<ConstValue>,
+}
+
+impl Receiver {
+    /// Receives the next value for this receiver.
+    ///
+    /// This method returns `None` if the channel has been closed and there are
+    /// no remaining messages in the channel's buffer.
+    ///
+    /// # Cancel safety
+    ///
+    /// This method is cancel safe. If `recv` is used as the event in a `select`
+    /// expression and some other branch completes first, it is guaranteed that
+    /// no messages were received on this channel.
+    ///
+    /// # Examples
+    ///
+    /// ```rune
+    /// let (tx, rx) = sync::channel(1);
+    ///
+    /// tx.send("hello").await?;
+    /// drop(tx);
+    ///
+    /// assert_eq!(rx.recv().await, Some("hello"));
+    /// assert_eq!(rx.recv().await, None);
+    /// ```
+    #[rune::function(keep, instance, path = Self::recv)]
+    async fn recv(mut this: Mut<Self>) -> VmResult<Option<Value>> {
+        let Some(value) = this.inner.recv().await else {
+            return VmResult::Ok(None);
+        };
+
+        VmResult::Ok(Some(vm_try!(value.to_value())))
+    }
+
+    /// Tries to receive the next value for this receiver without waiting.
+    ///
+    /// An error is returned if the channel is currently empty or if it has
+    /// been closed.
+    ///
+    /// # Examples
+    ///
+    /// ```rune
+    /// let (tx, rx) = sync::channel(1);
+    /// assert!(rx.try_recv().is_err());
+    ///
+    /// tx.try_send(1)?;
+    /// assert_eq!(rx.try_recv()?, 1);
+    /// ```
+    #[rune::function(keep, instance)]
+    fn try_recv(&mut self) -> VmResult<Result<Value, Error>> {
+        let value = match self.inner.try_recv() {
+            Ok(value) => value,
+            Err(mpsc::error::TryRecvError::Empty) => {
+                return VmResult::Ok(Err(Error::new(ErrorKind::Empty)));
+            }
+            Err(mpsc::error::TryRecvError::Disconnected) => {
+                return VmResult::Ok(Err(Error::new(ErrorKind::Closed)));
+            }
+        };
+
+        VmResult::Ok(Ok(vm_try!(value.to_value())))
+    }
+}
+
+/// An asynchronous mutual exclusion primitive useful for protecting shared
+/// data.
+///
+/// Cloning a mutex produces another handle to the same protected value.
+#[derive(Debug, Clone, Any)]
+#[rune(item = ::sync)]
+pub struct Mutex {
+    inner: Arc<tokio::sync::Mutex<ConstValue>>,
+}
+
+impl Mutex {
+    /// Creates a new lock in an unlocked state ready for use.
+    ///
+    /// # Examples
+    ///
+    /// ```rune
+    /// use sync::Mutex;
+    ///
+    /// let lock = Mutex::new(5);
+    /// ```
+    #[rune::function(keep, path = Self::new)]
+    pub fn new(value: Value) -> VmResult<Self> {
+        let value = vm_try!(ConstValue::from_value(&value));
+
+        VmResult::Ok(Self {
+            inner: Arc::new(tokio::sync::Mutex::new(value)),
+        })
+    }
+
+    /// Locks this mutex, causing the current task to yield until the lock has
+    /// been acquired. When the lock has been acquired, a guard is returned.
+    ///
+    /// The lock is released once the guard is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rune
+    /// use sync::Mutex;
+    ///
+    /// let mutex = Mutex::new(1);
+    ///
+    /// let guard = mutex.lock().await;
+    /// guard.set(guard.get() + 1);
+    /// drop(guard);
+    ///
+    /// assert_eq!(mutex.lock().await.get(), 2);
+    /// ```
+    #[rune::function(keep, instance, path = Self::lock)]
+    async fn lock(this: Ref<Self>) -> MutexGuard {
+        MutexGuard {
+            inner: this.inner.clone().lock_owned().await,
+        }
+    }
+
+    /// Attempts to acquire the lock without waiting, returning `None` if it's
+    /// currently held.
+    ///
+    /// # Examples
+    ///
+    /// ```rune
+    /// use sync::Mutex;
+    ///
+    /// let mutex = Mutex::new(1);
+    ///
+    /// let guard = mutex.try_lock().expect("mutex is unlocked");
+    /// assert!(mutex.try_lock().is_none());
+    /// drop(guard);
+    /// assert!(mutex.try_lock().is_some());
+    /// ```
+    #[rune::function(keep, instance)]
+    fn try_lock(&self) -> Option<MutexGuard> {
+        let inner = self.inner.clone().try_lock_owned().ok()?;
+        Some(MutexGuard { inner })
+    }
+
+    /// Clone the mutex, producing another handle to the same protected value.
+    ///
+    /// # Examples
+    ///
+    /// ```rune
+    /// use sync::Mutex;
+    ///
+    /// let a = Mutex::new(1);
+    /// let b = a.clone();
+    ///
+    /// a.lock().await.set(2);
+    /// assert_eq!(b.lock().await.get(), 2);
+    /// ```
+    #[rune::function(keep, instance, protocol = CLONE)]
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+/// A handle to a held [`Mutex`].
+///
+/// The lock is released when the guard is dropped.
+#[derive(Debug, Any)]
+#[rune(item = ::sync)]
+pub struct MutexGuard {
+    inner: OwnedMutexGuard<ConstValue>,
+}
+
+impl MutexGuard {
+    /// Get a copy of the value protected by the mutex.
+    ///
+    /// # Examples
+    ///
+    /// ```rune
+    /// use sync::Mutex;
+    ///
+    /// let mutex = Mutex::new([1, 2]);
+    /// assert_eq!(mutex.lock().await.get(), [1, 2]);
+    /// ```
+    #[rune::function(keep, instance)]
+    fn get(&self) -> VmResult<Value> {
+        VmResult::Ok(vm_try!(self.inner.to_value()))
+    }
+
+    /// Replace the value protected by the mutex.
+    ///
+    /// # Examples
+    ///
+    /// ```rune
+    /// use sync::Mutex;
+    ///
+    /// let mutex = Mutex::new(1);
+    /// mutex.lock().await.set(2);
+    /// assert_eq!(mutex.lock().await.get(), 2);
+    /// ```
+    #[rune::function(keep, instance)]
+    fn set(&mut self, value: Value) -> VmResult<()> {
+        *self.inner = vm_try!(ConstValue::from_value(&value));
+        VmResult::Ok(())
+    }
+}
+
+/// An error returned by channels in the `sync` module.
+#[derive(Debug, Any)]
+#[rune(item = ::sync)]
+pub struct Error {
+    kind: ErrorKind,
+}
+
+#[derive(Debug)]
+enum ErrorKind {
+    Closed,
+    Full,
+    Empty,
+}
+
+impl Error {
+    fn new(kind: ErrorKind) -> Self {
+        Self { kind }
+    }
+
+    /// Write a display representation the error.
+    #[rune::function(keep, instance, protocol = DISPLAY_FMT)]
+    fn display_fmt(&self, f: &mut Formatter) -> VmResult<()> {
+        match self.kind {
+            ErrorKind::Closed => vm_write!(f, "channel closed"),
+            ErrorKind::Full => vm_write!(f, "channel full"),
+            ErrorKind::Empty => vm_write!(f, "channel empty"),
+        }
+    }
+
+    /// Write a debug representation the error.
+    #[rune::function(keep, instance, protocol = DEBUG_FMT)]
+    fn debug_fmt(&self, f: &mut Formatter) -> VmResult<()> {
+        vm_write!(f, "{:?}", self.kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::thread;
+
+    use rune::runtime::SyncFunction;
+    use rune::{Context, Vm};
+
+    use super::{channel, Mutex};
+
+    fn functions() -> rune::support::Result<(SyncFunction, SyncFunction, SyncFunction)> {
+        let mut context = Context::with_default_modules()?;
+        context.install(super::module(true)?)?;
+
+        let mut sources = rune::sources! {
+            entry => {
+                async fn producer(tx, counter) {
+                    for n in 0..10 {
+                        tx.send(#{ n }).await?;
+
+                        let guard = counter.lock().await;
+                        guard.set(guard.get() + 1);
+                        drop(guard);
+                    }
+
+                    Ok(())
+                }
+
+                async fn consumer(rx, counter) {
+                    let values = [];
+
+                    while let Some(value) = rx.recv().await {
+                        values.push(value.n);
+
+                        let guard = counter.lock().await;
+                        guard.set(guard.get() + 1);
+                        drop(guard);
+                    }
+
+                    values
+                }
+
+                fn send_closure(tx) {
+                    tx.try_send(|| 42)
+                }
+
+                pub fn main() {
+                    (producer, consumer, send_closure)
+                }
+            }
+        };
+
+        let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+        let mut vm = Vm::new(Arc::new(context.runtime()?), Arc::new(unit));
+        let functions = vm.call(["main"], ())?;
+        Ok(rune::from_value(functions)?)
+    }
+
+    #[test]
+    fn test_producer_consumer() -> rune::support::Result<()> {
+        let (producer, consumer, _) = functions()?;
+
+        let counter = Mutex::new(rune::to_value(0i64)?).into_result()?;
+        let (tx, rx) = channel(2).into_result()?;
+
+        let values = thread::scope(|s| {
+            let produced = s.spawn(|| {
+                producer
+                    .call_blocking::<rune::runtime::Value>((tx, counter.clone()))
+                    .into_result()
+                    .map(drop)
+            });
+
+            let consumed = s.spawn(|| {
+                consumer
+                    .call_blocking::<Vec<i64>>((rx, counter.clone()))
+                    .into_result()
+            });
+
+            produced.join().expect("producer panicked")?;
+            consumed.join().expect("consumer panicked")
+        })?;
+
+        assert_eq!(values, (0..10).collect::<Vec<i64>>());
+
+        let count = counter.inner.try_lock()?.as_integer::<i64>()?;
+        assert_eq!(count, 20);
+        Ok(())
+    }
+
+    #[test]
+    fn test_send_non_const() -> rune::support::Result<()> {
+        let (_, _, send_closure) = functions()?;
+        let (tx, mut rx) = channel(1).into_result()?;
+
+        let error = send_closure
+            .call::<rune::runtime::Value>((tx,))
+            .into_result()
+            .unwrap_err()
+            .to_string();
+
+        assert!(
+            error.contains("can't be converted to a constant"),
+            "{error}"
+        );
+        assert!(rx.inner.try_recv().is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,323 @@
+//! The native `task` module for the [Rune Language].
+//!
+//! [Rune Language]: https://rune-rs.github.io
+//!
+//! ## Usage
+//!
+//! Add the following to your `Cargo.toml`:
+//!
+//! ```toml
+//! rune-modules = { version = "0.14.0", features = ["task"] }
+//! ```
+//!
+//! Install it into your context:
+//!
+//! ```rust
+//! let mut context = rune::Context::with_default_modules()?;
+//! context.install(rune_modules::task::module(true)?)?;
+//! # Ok::<_, rune::support::Error>(())
+//! ```
+//!
+//! Use it in Rune:
+//!
+//! ```rust,ignore
+//! async fn main() {
+//!     let a = task::spawn(async || http::get("https://example.com").await);
+//!     let b = task::spawn(async || http::get("https://example.org").await);
+//!
+//!     let a = a.join().await?;
+//!     let b = b.join().await?;
+//! }
+//! ```
+
+use core::fmt;
+
+use rune::alloc::fmt::TryWrite;
+use rune::runtime::{budget, ConstValue, Formatter, Function, Mut, Value, VmError, VmResult};
+use rune::{vm_panic, vm_try, vm_write, Any, ContextError, Module};
+use tokio::task;
+
+/// Running tasks concurrently.
+///
+/// # Tokio
+///
+/// This function is implemented using [Tokio], and requires the Tokio runtime
+/// to be in scope.
+///
+/// [Tokio]: https://tokio.rs
+#[rune::module(::task)]
+pub fn module(_stdio: bool) -> Result<Module, ContextError> {
+    let mut module = Module::from_meta(self::module_meta)?;
+
+    module.function_meta(spawn)?;
+
+    module.ty::<JoinHandle>()?;
+    module.function_meta(JoinHandle::join__meta)?;
+    module.function_meta(JoinHandle::abort__meta)?;
+    module.function_meta(JoinHandle::is_finished__meta)?;
+
+    module.ty::<Error>()?;
+    module.function_meta(Error::display_fmt__meta)?;
+    module.function_meta(Error::debug_fmt__meta)?;
+    Ok(module)
+}
+
+/// Spawns a new task, returning a [`JoinHandle`] for it.
+///
+/// The function is called without arguments on the Tokio runtime in a virtual
+/// machine of its own, which shares the unit and context of the caller. The
+/// task might run on another thread, so the function and any values it
+/// captures must be possible to represent as constants. For the same reason,
+/// an existing future can't be spawned. Instead it should be created by the
+/// spawned function.
+///
+/// If the caller is running under an instruction budget, half of the
+/// remaining budget is handed to the spawned task.
+///
+/// The task starts running immediately, even if the returned handle is never
+/// joined. Dropping the handle detaches the task.
+///
+/// # Examples
+///
+/// ```rune,no_run
+/// let n = 20;
+///
+/// let handle = task::spawn(async || n * 2);
+/// assert_eq!(handle.join().await?, 40);
+/// ```
+#[rune::function]
+fn spawn(function: Function) -> VmResult<JoinHandle> {
+    let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+        vm_panic!("spawning a task requires a Tokio runtime");
+    };
+
+    let function = vm_try!(function.into_sync());
+
+    let task = async move { function.async_send_call::<ConstValue>(()).await };
+
+    let inner = match budget::split() {
+        Some(budget) => runtime.spawn(budget::with(budget, task)),
+        None => runtime.spawn(task),
+    };
+
+    VmResult::Ok(JoinHandle { inner: Some(inner) })
+}
+
+/// An owned permission to join on a task.
+///
+/// This is returned by [`spawn`].
+#[derive(Debug, Any)]
+#[rune(item = ::task)]
+pub struct JoinHandle {
+    inner: Option<task::JoinHandle<VmResult<ConstValue>>>,
+}
+
+impl JoinHandle {
+    /// Waits for the task to complete, returning its output.
+    ///
+    /// An error is returned if the task raised an error or panicked, was
+    /// aborted, or if the task has already been joined.
+    ///
+    /// # Examples
+    ///
+    /// ```rune,no_run
+    /// let handle = task::spawn(|| panic!("boom"));
+    ///
+    /// let error = handle.join().await.expect_err("task should fail");
+    /// assert!(`${error}`.contains("boom"));
+    /// ```
+    #[rune::function(keep, instance, path = Self::join)]
+    async fn join(mut this: Mut<Self>) -> VmResult<Result<Value, Error>> {
+        let Some(inner) = &mut this.inner else {
+            return VmResult::Ok(Err(Error::new(ErrorKind::Joined)));
+        };
+
+        let result = inner.await;
+        this.inner = None;
+
+        let kind = match result {
+            Ok(VmResult::Ok(value)) => return VmResult::Ok(Ok(vm_try!(value.to_value()))),
+            Ok(VmResult::Err(error)) => ErrorKind::Vm(error),
+            Err(error) => ErrorKind::Join(error),
+        };
+
+        VmResult::Ok(Err(Error::new(kind)))
+    }
+
+    /// Aborts the task.
+    ///
+    /// Joining an aborted task which hasn't yet completed produces an error.
+    ///
+    /// # Examples
+    ///
+    /// ```rune,no_run
+    /// let handle = task::spawn(async || loop {
+    ///     time::sleep(time::Duration::from_secs(1)).await;
+    /// });
+    ///
+    /// handle.abort();
+    /// assert!(handle.join().await.is_err());
+    /// ```
+    #[rune::function(keep, instance)]
+    fn abort(&self) {
+        if let Some(inner) = &self.inner {
+            inner.abort();
+        }
+    }
+
+    /// Checks if the task has finished.
+    ///
+    /// # Examples
+    ///
+    /// ```rune,no_run
+    /// let handle = task::spawn(|| 42);
+    /// handle.join().await?;
+    /// assert!(handle.is_finished());
+    /// ```
+    #[rune::function(keep, instance)]
+    fn is_finished(&self) -> bool {
+        match &self.inner {
+            Some(inner) => inner.is_finished(),
+            None => true,
+        }
+    }
+}
+
+/// An error returned when joining a task fails.
+#[derive(Debug, Any)]
+#[rune(item = ::task)]
+pub struct Error {
+    kind: ErrorKind,
+}
+
+#[derive(Debug)]
+enum ErrorKind {
+    Vm(VmError),
+    Join(task::JoinError),
+    Joined,
+}
+
+impl Error {
+    fn new(kind: ErrorKind) -> Self {
+        Self { kind }
+    }
+
+    /// Write a display representation the error.
+    #[rune::function(keep, instance, protocol = DISPLAY_FMT)]
+    fn display_fmt(&self, f: &mut Formatter) -> VmResult<()> {
+        vm_write!(f, "{self}")
+    }
+
+    /// Write a debug representation the error.
+    #[rune::function(keep, instance, protocol = DEBUG_FMT)]
+    fn debug_fmt(&self, f: &mut Formatter) -> VmResult<()> {
+        vm_write!(f, "{:?}", self.kind)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ErrorKind::Vm(error) => fmt::Display::fmt(error, f),
+            ErrorKind::Join(error) => fmt::Display::fmt(error, f),
+            ErrorKind::Joined => write!(f, "task has already been joined"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    use rune::runtime::budget;
+    use rune::{Context, Module, Vm};
+
+    /// Sleep for the given number of milliseconds.
+    #[rune::function]
+    async fn sleep(ms: u64) {
+        tokio::time::sleep(Duration::from_millis(ms)).await;
+    }
+
+    fn vm() -> rune::support::Result<Vm> {
+        let mut time = Module::with_crate("time")?;
+        time.function_meta(sleep)?;
+
+        let mut context = Context::with_default_modules()?;
+        context.install(super::module(true)?)?;
+        context.install(time)?;
+
+        let mut sources = rune::sources! {
+            entry => {
+                async fn delay(ms) {
+                    time::sleep(ms).await;
+                    ms
+                }
+
+                pub async fn concurrent(ms) {
+                    let a = task::spawn(async || delay(ms).await);
+                    let b = task::spawn(async || delay(ms).await);
+                    (a.join().await?, b.join().await?)
+                }
+
+                pub async fn panics() {
+                    let handle = task::spawn(|| panic!("boom"));
+
+                    match handle.join().await {
+                        Ok(..) => None,
+                        Err(error) => Some(format!("{}", error)),
+                    }
+                }
+
+                pub async fn runaway() {
+                    let handle = task::spawn(|| {
+                        loop {}
+                    });
+
+                    handle.join().await.is_err()
+                }
+            }
+        };
+
+        let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+        Ok(Vm::new(Arc::new(context.runtime()?), Arc::new(unit)))
+    }
+
+    #[tokio::test]
+    async fn test_spawn_concurrently() -> rune::support::Result<()> {
+        let mut vm = vm()?;
+        let start = Instant::now();
+
+        let output = vm.async_call(["concurrent"], (300u64,)).await?;
+        let elapsed = start.elapsed();
+
+        let (a, b) = rune::from_value::<(u64, u64)>(output)?;
+        assert_eq!((a, b), (300, 300));
+
+        assert!(elapsed >= Duration::from_millis(300));
+        assert!(
+            elapsed < Duration::from_millis(550),
+            "tasks did not run concurrently, took {elapsed:?}"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_spawn_panic() -> rune::support::Result<()> {
+        let mut vm = vm()?;
+
+        let output = vm.async_call(["panics"], ()).await?;
+        let error = rune::from_value::<Option<String>>(output)?.expect("task should fail");
+        assert!(error.contains("boom"), "{error}");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_spawn_inherits_budget() -> rune::support::Result<()> {
+        let mut vm = vm()?;
+
+        let output = budget::with(10_000, vm.async_call(["runaway"], ())).await?;
+        assert!(rune::from_value::<bool>(output)?);
+        Ok(())
+    }
+}
@@ -18,6 +18,18 @@
 //! # Ok::<_, rune::support::Error>(())
 //! ```
 //!
+//! Deserialization of untrusted input can be bounded by installing the module
+//! with [`module_with_limits`]:
+//!
+//! ```rust
+//! use rune_modules::limits::Limits;
+//!
+//! let mut context = rune::Context::with_default_modules()?;
+//! let limits = Limits::new().with_input(64 * 1024).with_memory(1024 * 1024);
+//! context.install(rune_modules::toml::module_with_limits(true, limits)?)?;
+//! # Ok::<_, rune::support::Error>(())
+//! ```
+//!
 //! Use it in Rune:
 //!
 //! ```rust,ignore
@@ -30,14 +42,47 @@
 //! ```
 
 use rune::alloc::String;
-use rune::runtime::{Bytes, Value};
-use rune::{ContextError, Module};
+use rune::runtime::{Bytes, Value, VmResult};
+use rune::{docstring, vm_try, ContextError, Module};
+
+use crate::limits::Limits;
 
 /// Construct the `toml` module.
-pub fn module(_stdio: bool) -> Result<Module, ContextError> {
+pub fn module(stdio: bool) -> Result<Module, ContextError> {
+    module_with_limits(stdio, Limits::new())
+}
+
+/// Construct the `toml` module, where deserialization abides by the given
+/// [`Limits`].
+///
+/// # Examples
+///
+/// ```
+/// use rune_modules::limits::Limits;
+///
+/// let limits = Limits::new().with_input(1024);
+/// let module = rune_modules::toml::module_with_limits(true, limits)?;
+/// # Ok::<_, rune::support::Error>(())
+/// ```
+pub fn module_with_limits(_stdio: bool, limits: Limits) -> Result<Module, ContextError> {
     let mut module = Module::with_crate("toml")?;
-    module.function_meta(from_bytes)?;
-    module.function_meta(from_string)?;
+
+    module
+        .function("from_bytes", move |bytes: &[u8]| from_bytes(&limits, bytes))
+        .build()?
+        .docs(docstring! {
+            /// Convert bytes of TOML into a rune value.
+        })?;
+
+    module
+        .function("from_string", move |string: &str| {
+            from_string(&limits, string)
+        })
+        .build()?
+        .docs(docstring! {
+            /// Convert a string of TOML into a rune value.
+        })?;
+
     module.function_meta(to_string)?;
     module.function_meta(to_bytes)?;
     Ok(module)
@@ -123,24 +168,29 @@ pub mod ser {
     }
 }
 
-/// Convert bytes of TOML into a rune value.
-#[rune::function(vm_result)]
-fn from_bytes(bytes: &[u8]) -> Result<Value, Value> {
+fn from_bytes(limits: &Limits, bytes: &[u8]) -> VmResult<Result<Value, Value>> {
+    if let Err(error) = limits.check_input::<toml::de::Error>(bytes.len()) {
+        return VmResult::Ok(Err(vm_try!(rune::to_value(de::Error::from(error)))));
+    }
+
     let bytes = match std::str::from_utf8(bytes) {
         Ok(bytes) => bytes,
-        Err(error) => return Err(rune::to_value(error).vm?),
+        Err(error) => return VmResult::Ok(Err(vm_try!(rune::to_value(error)))),
     };
 
-    match toml::from_str(bytes).map_err(de::Error::from) {
-        Ok(value) => Ok(value),
-        Err(error) => Err(rune::to_value(error).vm?),
+    match deserialize(limits, bytes) {
+        Ok(value) => VmResult::Ok(Ok(value)),
+        Err(error) => VmResult::Ok(Err(vm_try!(rune::to_value(error)))),
     }
 }
 
-/// Convert a string of TOML into a rune value.
-#[rune::function]
-fn from_string(string: &str) -> Result<Value, de::Error> {
-    Ok(toml::from_str(string)?)
+fn from_string(limits: &Limits, string: &str) -> Result<Value, de::Error> {
+    limits.check_input::<toml::de::Error>(string.len())?;
+    deserialize(limits, string)
+}
+
+fn deserialize(limits: &Limits, string: &str) -> Result<Value, de::Error> {
+    Ok(limits.deserialize(toml::Deserializer::new(string))?)
 }
 
 /// Convert any value to a toml string.
@@ -155,3 +205,49 @@ fn to_bytes(value: Value) -> Result<Bytes, ser::Error> {
     let string = String::try_from(toml::to_string(&value)?).vm?;
     Ok(Bytes::from_vec(string.into_bytes()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{from_string, Limits};
+
+    fn error(limits: &Limits, input: &str) -> String {
+        match from_string(limits, input) {
+            Ok(..) => panic!("expected error"),
+            Err(error) => error.error.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_input_limit() {
+        let limits = Limits::new().with_input(16);
+
+        assert!(from_string(&limits, "a = 42").is_ok());
+        let input = format!("a = [{}]", "1, ".repeat(1000));
+        assert!(error(&limits, &input).contains("input exceeds configured limit of 16 bytes"));
+    }
+
+    #[test]
+    fn test_memory_limit() {
+        let limits = Limits::new().with_memory(1024);
+
+        assert!(from_string(&limits, "a = [1, 2, 3]").is_ok());
+        let input = format!("a = [{}]", vec!["\"hello world\""; 1000].join(", "));
+        assert!(
+            error(&limits, &input).contains("value exceeds configured memory limit of 1024 bytes")
+        );
+    }
+
+    #[test]
+    fn test_depth_limit() {
+        let limits = Limits::new().with_depth(8);
+
+        assert!(from_string(&limits, "a = [[[1]]]").is_ok());
+        let input = format!("a = {}{}", "[".repeat(16), "]".repeat(16));
+        assert!(error(&limits, &input).contains("value exceeds configured depth limit of 8"));
+
+        // Without a configured depth limit, deeply nested input is still
+        // rejected gracefully by the parser.
+        let input = format!("a = {}{}", "[".repeat(100_000), "]".repeat(100_000));
+        assert!(from_string(&Limits::new(), &input).is_err());
+    }
+}
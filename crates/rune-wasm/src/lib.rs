@@ -33,16 +33,22 @@ use std::sync::Arc;
 
 use anyhow::{Context as _, Result};
 use gloo_utils::format::JsValueSerdeExt;
+use rune::alloc::limit;
 use rune::ast::Spanned;
 use rune::compile::LinkerError;
 use rune::diagnostics::{Diagnostic, FatalDiagnosticKind};
+use rune::doc::Document;
 use rune::modules::capture_io::CaptureIo;
-use rune::runtime::{budget, VmResult};
-use rune::{Context, ContextError, Options};
+use rune::runtime::VmResult;
+use rune::{Context, ContextError, Diagnostics, Options, Source, Sources};
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+use self::sliced::{Cancel, Outcome};
 
 mod http;
+mod sliced;
 mod time;
 
 #[derive(Default, Serialize)]
@@ -62,9 +68,12 @@ impl From<(usize, usize)> for WasmPosition {
 
 #[derive(Deserialize)]
 struct Config {
-    /// Budget.
+    /// Instruction budget.
     #[serde(default)]
     budget: Option<usize>,
+    /// Allocation budget in bytes.
+    #[serde(default)]
+    memory: Option<usize>,
     /// Compiler options.
     #[serde(default)]
     options: Vec<String>,
@@ -102,6 +111,19 @@ pub struct WasmCompileResult {
     instructions: Option<String>,
 }
 
+#[derive(Serialize)]
+pub struct WasmFormatResult {
+    error: Option<String>,
+    diagnostics: Vec<WasmDiagnostic>,
+    output: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct WasmDocsResult {
+    error: Option<String>,
+    document: Option<Document>,
+}
+
 impl WasmCompileResult {
     /// Construct output from compile result.
     fn output(
@@ -159,18 +181,27 @@ fn setup_context(io: &CaptureIo) -> Result<Context, ContextError> {
     Ok(context)
 }
 
+/// Yield to the event loop of the browser, so that it can process other
+/// events.
+async fn yield_now() {
+    _ = JsFuture::from(time::js_sleep(0)).await;
+}
+
 async fn inner_compile(
     input: String,
     config: JsValue,
     io: &CaptureIo,
+    cancel: &Cancel,
+    on_slice: impl FnMut(),
 ) -> Result<WasmCompileResult> {
     let instructions = None;
 
     let config: Config = JsValueSerdeExt::into_serde(&config)?;
     let budget = config.budget.unwrap_or(1_000_000);
+    let memory = config.memory.unwrap_or(usize::MAX);
 
-    let source = rune::Source::new("entry", input)?;
-    let mut sources = rune::Sources::new();
+    let source = Source::new("entry", input)?;
+    let mut sources = Sources::new();
     sources.insert(source)?;
 
     let context = setup_context(io)?;
@@ -181,8 +212,7 @@ async fn inner_compile(
         options.parse_option(option)?;
     }
 
-    let mut d = rune::Diagnostics::new();
-    let mut diagnostics = Vec::new();
+    let mut d = Diagnostics::new();
 
     let result = rune::prepare(&mut sources)
         .with_context(&context)
@@ -190,71 +220,7 @@ async fn inner_compile(
         .with_options(&options)
         .build();
 
-    for diagnostic in d.diagnostics() {
-        match diagnostic {
-            Diagnostic::Fatal(error) => {
-                if let Some(source) = sources.get(error.source_id()) {
-                    match error.kind() {
-                        FatalDiagnosticKind::CompileError(error) => {
-                            let span = error.span();
-
-                            let start = WasmPosition::from(
-                                source.pos_to_utf8_linecol(span.start.into_usize()),
-                            );
-                            let end = WasmPosition::from(
-                                source.pos_to_utf8_linecol(span.end.into_usize()),
-                            );
-
-                            diagnostics.push(WasmDiagnostic {
-                                kind: WasmDiagnosticKind::Error,
-                                start,
-                                end,
-                                message: error.to_string(),
-                            });
-                        }
-                        FatalDiagnosticKind::LinkError(error) => match error {
-                            LinkerError::MissingFunction { hash, spans } => {
-                                for (span, _) in spans {
-                                    let start = WasmPosition::from(
-                                        source.pos_to_utf8_linecol(span.start.into_usize()),
-                                    );
-                                    let end = WasmPosition::from(
-                                        source.pos_to_utf8_linecol(span.end.into_usize()),
-                                    );
-
-                                    diagnostics.push(WasmDiagnostic {
-                                        kind: WasmDiagnosticKind::Error,
-                                        start,
-                                        end,
-                                        message: format!("missing function (hash: {})", hash),
-                                    });
-                                }
-                            }
-                            _ => {}
-                        },
-                        _ => {}
-                    }
-                }
-            }
-            Diagnostic::Warning(warning) => {
-                let span = warning.span();
-
-                if let Some(source) = sources.get(warning.source_id()) {
-                    let start =
-                        WasmPosition::from(source.pos_to_utf8_linecol(span.start.into_usize()));
-                    let end = WasmPosition::from(source.pos_to_utf8_linecol(span.end.into_usize()));
-
-                    diagnostics.push(WasmDiagnostic {
-                        kind: WasmDiagnosticKind::Warning,
-                        start,
-                        end,
-                        message: warning.to_string(),
-                    });
-                }
-            }
-            _ => {}
-        }
-    }
+    let mut diagnostics = collect_diagnostics(&sources, &d);
 
     let mut writer = rune::termcolor::Buffer::no_color();
 
@@ -304,11 +270,43 @@ async fn inner_compile(
         }
     };
 
-    let future = budget::with(budget, execution.async_complete());
+    let future = limit::with(
+        memory,
+        sliced::run(
+            &mut execution,
+            budget,
+            sliced::SLICE,
+            cancel,
+            on_slice,
+            yield_now,
+        ),
+    );
 
     let output = match future.await {
-        VmResult::Ok(output) => output,
+        VmResult::Ok(Outcome::Complete(output)) => output,
+        VmResult::Ok(Outcome::Exhausted) => {
+            return Ok(WasmCompileResult::from_error(
+                io,
+                format_args!("Execution exceeded the instruction budget of {budget}"),
+                diagnostics_output(writer),
+                diagnostics,
+                instructions,
+            ));
+        }
+        VmResult::Ok(Outcome::Cancelled) => {
+            return Ok(WasmCompileResult::from_error(
+                io,
+                "Execution was cancelled",
+                diagnostics_output(writer),
+                diagnostics,
+                instructions,
+            ));
+        }
         VmResult::Err(error) => {
+            error
+                .emit(&mut writer, &sources)
+                .context("Emitting to buffer should never fail")?;
+
             let vm = execution.vm();
 
             let (unit, ip) = match error.first_location() {
@@ -338,10 +336,6 @@ async fn inner_compile(
                 }
             }
 
-            error
-                .emit(&mut writer, &sources)
-                .context("Emitting to buffer should never fail")?;
-
             return Ok(WasmCompileResult::from_error(
                 io,
                 error,
@@ -363,6 +357,79 @@ async fn inner_compile(
     ))
 }
 
+/// Convert diagnostics into their wasm representation.
+fn collect_diagnostics(sources: &Sources, d: &Diagnostics) -> Vec<WasmDiagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for diagnostic in d.diagnostics() {
+        match diagnostic {
+            Diagnostic::Fatal(error) => {
+                if let Some(source) = sources.get(error.source_id()) {
+                    match error.kind() {
+                        FatalDiagnosticKind::CompileError(error) => {
+                            let span = error.span();
+
+                            let start = WasmPosition::from(
+                                source.pos_to_utf8_linecol(span.start.into_usize()),
+                            );
+                            let end = WasmPosition::from(
+                                source.pos_to_utf8_linecol(span.end.into_usize()),
+                            );
+
+                            diagnostics.push(WasmDiagnostic {
+                                kind: WasmDiagnosticKind::Error,
+                                start,
+                                end,
+                                message: error.to_string(),
+                            });
+                        }
+                        FatalDiagnosticKind::LinkError(error) => match error {
+                            LinkerError::MissingFunction { hash, spans, .. } => {
+                                for (span, _) in spans {
+                                    let start = WasmPosition::from(
+                                        source.pos_to_utf8_linecol(span.start.into_usize()),
+                                    );
+                                    let end = WasmPosition::from(
+                                        source.pos_to_utf8_linecol(span.end.into_usize()),
+                                    );
+
+                                    diagnostics.push(WasmDiagnostic {
+                                        kind: WasmDiagnosticKind::Error,
+                                        start,
+                                        end,
+                                        message: format!("missing function (hash: {})", hash),
+                                    });
+                                }
+                            }
+                            _ => {}
+                        },
+                        _ => {}
+                    }
+                }
+            }
+            Diagnostic::Warning(warning) => {
+                let span = warning.span();
+
+                if let Some(source) = sources.get(warning.source_id()) {
+                    let start =
+                        WasmPosition::from(source.pos_to_utf8_linecol(span.start.into_usize()));
+                    let end = WasmPosition::from(source.pos_to_utf8_linecol(span.end.into_usize()));
+
+                    diagnostics.push(WasmDiagnostic {
+                        kind: WasmDiagnosticKind::Warning,
+                        start,
+                        end,
+                        message: warning.to_string(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    diagnostics
+}
+
 fn diagnostics_output(writer: rune::termcolor::Buffer) -> Option<String> {
     let mut string = String::from_utf8(writer.into_inner()).ok()?;
     let new_len = string.trim_end().len();
@@ -374,10 +441,146 @@ fn diagnostics_output(writer: rune::termcolor::Buffer) -> Option<String> {
 pub async fn compile(input: String, config: JsValue) -> JsValue {
     let io = CaptureIo::new();
 
-    let result = match inner_compile(input, config, &io).await {
+    let result = match inner_compile(input, config, &io, &Cancel::default(), || {}).await {
         Ok(result) => result,
         Err(error) => WasmCompileResult::from_error(&io, error, None, Vec::new(), None),
     };
 
     <JsValue as JsValueSerdeExt>::from_serde(&result).unwrap()
 }
+
+/// A compilation started with [`compile_streaming`].
+#[wasm_bindgen]
+pub struct WasmExecution {
+    cancel: Cancel,
+    result: js_sys::Promise,
+}
+
+#[wasm_bindgen]
+impl WasmExecution {
+    /// Cancel the execution.
+    ///
+    /// The result resolves with an error once the slice currently executing
+    /// completes.
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+    }
+
+    /// A promise which resolves to the result of the compilation.
+    #[wasm_bindgen(getter)]
+    pub fn result(&self) -> js_sys::Promise {
+        self.result.clone()
+    }
+}
+
+/// Compile and run the given input like [`compile`], but call `on_output`
+/// with any captured output as execution progresses.
+///
+/// The returned handle can be used to cancel the execution.
+#[wasm_bindgen]
+pub fn compile_streaming(
+    input: String,
+    config: JsValue,
+    on_output: js_sys::Function,
+) -> WasmExecution {
+    let cancel = Cancel::default();
+
+    let result = wasm_bindgen_futures::future_to_promise({
+        let cancel = cancel.clone();
+
+        async move {
+            let io = CaptureIo::new();
+
+            let on_slice = || {
+                if io.is_empty() {
+                    return;
+                }
+
+                if let Ok(output) = io.drain_utf8() {
+                    _ = on_output.call1(&JsValue::NULL, &JsValue::from_str(&output));
+                }
+            };
+
+            let result = match inner_compile(input, config, &io, &cancel, on_slice).await {
+                Ok(result) => result,
+                Err(error) => WasmCompileResult::from_error(&io, error, None, Vec::new(), None),
+            };
+
+            Ok(<JsValue as JsValueSerdeExt>::from_serde(&result).unwrap_or(JsValue::NULL))
+        }
+    });
+
+    WasmExecution { cancel, result }
+}
+
+fn inner_format(input: String) -> Result<WasmFormatResult> {
+    let mut sources = Sources::new();
+    sources.insert(Source::new("entry", input)?)?;
+
+    let mut d = Diagnostics::new();
+
+    let result = rune::fmt::prepare(&sources)
+        .with_diagnostics(&mut d)
+        .format();
+
+    let diagnostics = collect_diagnostics(&sources, &d);
+
+    let output = match result {
+        Ok(files) => files
+            .into_iter()
+            .next()
+            .map(|(_, output)| output.into_std()),
+        Err(error) => {
+            return Ok(WasmFormatResult {
+                error: Some(error.to_string()),
+                diagnostics,
+                output: None,
+            });
+        }
+    };
+
+    Ok(WasmFormatResult {
+        error: None,
+        diagnostics,
+        output,
+    })
+}
+
+/// Format the given source, returning either the formatted source or
+/// diagnostics explaining why it couldn't be formatted.
+#[wasm_bindgen]
+pub fn format(input: String) -> JsValue {
+    let result = match inner_format(input) {
+        Ok(result) => result,
+        Err(error) => WasmFormatResult {
+            error: Some(error.to_string()),
+            diagnostics: Vec::new(),
+            output: None,
+        },
+    };
+
+    <JsValue as JsValueSerdeExt>::from_serde(&result).unwrap_or(JsValue::NULL)
+}
+
+fn inner_context_docs() -> Result<Document> {
+    let context = setup_context(&CaptureIo::new())?;
+    Document::from_context(&context)
+}
+
+/// Documentation for all modules and functions installed in the playground
+/// context, used by the editor for completions and hovers.
+#[wasm_bindgen]
+pub fn context_docs() -> JsValue {
+    let result = match inner_context_docs() {
+        Ok(document) => WasmDocsResult {
+            error: None,
+            document: Some(document),
+        },
+        Err(error) => WasmDocsResult {
+            error: Some(error.to_string()),
+            document: None,
+        },
+    };
+
+    <JsValue as JsValueSerdeExt>::from_serde(&result).unwrap_or(JsValue::NULL)
+}
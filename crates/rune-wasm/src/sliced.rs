@@ -0,0 +1,89 @@
+//! Execution of scripts in slices, so that the host gets a chance to run in
+//! between them.
+
+#[cfg(test)]
+mod tests;
+
+use std::cell::Cell;
+use std::future::Future;
+use std::pin::pin;
+use std::rc::Rc;
+
+use rune::runtime::{budget, Value, Vm, VmExecution, VmResult};
+
+/// The number of instructions executed in each slice.
+pub(crate) const SLICE: usize = 10_000;
+
+/// A handle used to cancel a sliced execution.
+///
+/// Cancellation takes effect once the slice currently executing completes.
+#[derive(Clone, Default)]
+pub(crate) struct Cancel(Rc<Cell<bool>>);
+
+impl Cancel {
+    /// Cancel the execution.
+    pub(crate) fn cancel(&self) {
+        self.0.set(true);
+    }
+
+    /// Test if the execution has been cancelled.
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.0.get()
+    }
+}
+
+/// The outcome of a sliced execution.
+pub(crate) enum Outcome {
+    /// Execution completed with the given value.
+    Complete(Value),
+    /// The instruction budget was exhausted.
+    Exhausted,
+    /// Execution was cancelled.
+    Cancelled,
+}
+
+/// Run the given execution in slices of at most `slice` instructions, using
+/// at most `budget` instructions in total.
+///
+/// The `on_slice` callback is called after each slice, and `yield_now` is
+/// awaited in between slices.
+pub(crate) async fn run<T, Y, F>(
+    execution: &mut VmExecution<T>,
+    mut budget: usize,
+    slice: usize,
+    cancel: &Cancel,
+    mut on_slice: impl FnMut(),
+    mut yield_now: Y,
+) -> VmResult<Outcome>
+where
+    T: AsRef<Vm> + AsMut<Vm>,
+    Y: FnMut() -> F,
+    F: Future<Output = ()>,
+{
+    loop {
+        if cancel.is_cancelled() {
+            return VmResult::Ok(Outcome::Cancelled);
+        }
+
+        if budget == 0 {
+            return VmResult::Ok(Outcome::Exhausted);
+        }
+
+        let mut future = pin!(budget::with(
+            budget.min(slice),
+            execution.async_complete_limited()
+        ));
+
+        let result = future.as_mut().await;
+        budget -= future.consumed();
+        on_slice();
+
+        match result {
+            VmResult::Ok(Some(value)) => return VmResult::Ok(Outcome::Complete(value)),
+            VmResult::Ok(None) => {}
+            VmResult::Err(error) => return VmResult::Err(error),
+        }
+
+        yield_now().await;
+    }
+}
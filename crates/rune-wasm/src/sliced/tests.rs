@@ -0,0 +1,118 @@
+use std::cell::Cell;
+use std::sync::Arc;
+
+use futures_executor::block_on;
+use rune::{Context, Vm};
+
+use super::{run, Cancel, Outcome};
+
+fn vm(source: &str) -> Vm {
+    let mut sources = rune::Sources::new();
+    sources
+        .insert(rune::Source::memory(source).unwrap())
+        .unwrap();
+
+    let context = Context::with_default_modules().unwrap();
+    let unit = rune::prepare(&mut sources)
+        .with_context(&context)
+        .build()
+        .unwrap();
+
+    Vm::new(Arc::new(context.runtime().unwrap()), Arc::new(unit))
+}
+
+const LOOP: &str = r#"
+pub fn main() {
+    let n = 0;
+
+    for i in 0..1000 {
+        n += i;
+    }
+
+    n
+}
+"#;
+
+#[test]
+fn complete_in_slices() {
+    let mut vm = vm(LOOP);
+    let mut execution = vm.execute(["main"], ()).unwrap();
+
+    let slices = Cell::new(0);
+    let yields = Cell::new(0);
+
+    let outcome = block_on(run(
+        &mut execution,
+        usize::MAX,
+        100,
+        &Cancel::default(),
+        || slices.set(slices.get() + 1),
+        || {
+            yields.set(yields.get() + 1);
+            async {}
+        },
+    ))
+    .into_result()
+    .unwrap();
+
+    let Outcome::Complete(value) = outcome else {
+        panic!("expected execution to complete");
+    };
+
+    assert_eq!(rune::from_value::<i64>(value).unwrap(), 499500);
+    assert!(slices.get() > 1);
+    // The host is yielded to between slices, but not after the last one.
+    assert_eq!(yields.get(), slices.get() - 1);
+}
+
+#[test]
+fn budget_exhausted() {
+    let mut vm = vm(LOOP);
+    let mut execution = vm.execute(["main"], ()).unwrap();
+
+    let slices = Cell::new(0);
+
+    let outcome = block_on(run(
+        &mut execution,
+        250,
+        100,
+        &Cancel::default(),
+        || slices.set(slices.get() + 1),
+        || async {},
+    ))
+    .into_result()
+    .unwrap();
+
+    assert!(matches!(outcome, Outcome::Exhausted));
+    // Two full slices and one with the remaining 50 instructions.
+    assert_eq!(slices.get(), 3);
+}
+
+#[test]
+fn cancelled() {
+    let mut vm = vm(LOOP);
+    let mut execution = vm.execute(["main"], ()).unwrap();
+
+    let cancel = Cancel::default();
+    let slices = Cell::new(0);
+
+    let outcome = block_on(run(
+        &mut execution,
+        usize::MAX,
+        100,
+        &cancel,
+        || {
+            slices.set(slices.get() + 1);
+
+            if slices.get() == 2 {
+                cancel.cancel();
+            }
+        },
+        || async {},
+    ))
+    .into_result()
+    .unwrap();
+
+    assert!(matches!(outcome, Outcome::Cancelled));
+    assert_eq!(slices.get(), 2);
+}
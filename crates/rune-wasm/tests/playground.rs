@@ -0,0 +1,51 @@
+#![cfg(target_arch = "wasm32")]
+
+use gloo_utils::format::JsValueSerdeExt;
+use serde_json::Value;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+fn format(input: &str) -> Value {
+    rune_wasm::format(input.to_owned()).into_serde().unwrap()
+}
+
+#[wasm_bindgen_test]
+fn format_round_trip() {
+    let result = format("pub fn main(){let a=1;a}");
+    assert!(result["error"].is_null(), "{result}");
+
+    let output = result["output"].as_str().unwrap();
+    assert_ne!(output, "pub fn main(){let a=1;a}");
+
+    // Formatting already formatted source doesn't change it.
+    let result = format(output);
+    assert_eq!(result["output"].as_str(), Some(output));
+}
+
+#[wasm_bindgen_test]
+fn format_invalid() {
+    let result = format("pub fn main( {");
+    assert!(result["output"].is_null(), "{result}");
+    assert!(result["error"].is_string(), "{result}");
+
+    let diagnostics = result["diagnostics"].as_array().unwrap();
+    assert!(!diagnostics.is_empty());
+    assert_eq!(diagnostics[0]["kind"], "error");
+}
+
+#[wasm_bindgen_test]
+fn context_docs() {
+    let result: Value = rune_wasm::context_docs().into_serde().unwrap();
+    assert!(result["error"].is_null(), "{result}");
+
+    let modules = result["document"]["modules"].as_array().unwrap();
+
+    let json = modules
+        .iter()
+        .find(|module| module["path"] == "::json")
+        .expect("json module");
+
+    let items = json["items"].as_array().unwrap();
+    assert!(items
+        .iter()
+        .any(|item| item["path"] == "::json::from_string"));
+}
@@ -97,7 +97,7 @@ impl<'a> AutoCompleteCtx<'a> {
                     }
                     self.fixed.try_insert(item.try_clone()?, meta)?;
                 }
-                Kind::Unsupported | Kind::Trait => {}
+                Kind::Unsupported | Kind::Trait | Kind::Alias(..) => {}
             }
         }
 
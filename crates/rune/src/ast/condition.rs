@@ -5,12 +5,54 @@ use crate::ast::prelude::*;
 fn ast_parse() {
     rt::<ast::Condition>("true");
     rt::<ast::Condition>("let [a, ..] = v");
+    rt::<ast::Condition>("a && b");
+
+    let c = rt::<ast::Condition>("let Some(a) = v && a > 0 && let Ok(b) = f(a)");
+
+    let ast::Condition::Expr(ast::Expr::Binary(expr)) = c else {
+        panic!("expected binary condition");
+    };
+
+    assert!(matches!(expr.op, ast::BinOp::And(..)));
+    assert!(matches!(*expr.rhs, ast::Expr::Let(..)));
+
+    // Operators other than `&&` are part of the scrutinee.
+    let c = rt::<ast::Condition>("let x = a || b");
+
+    let ast::Condition::ExprLet(expr) = c else {
+        panic!("expected let condition");
+    };
+
+    assert!(matches!(&*expr.expr, ast::Expr::Binary(b) if matches!(b.op, ast::BinOp::Or(..))));
+
+    let c = rt::<ast::Condition>("let x = 0..10");
+
+    let ast::Condition::ExprLet(expr) = c else {
+        panic!("expected let condition");
+    };
+
+    assert!(matches!(*expr.expr, ast::Expr::Range(..)));
+
+    let c = rt::<ast::Condition>("let x = a || b && x");
+
+    let ast::Condition::Expr(ast::Expr::Binary(expr)) = c else {
+        panic!("expected binary condition");
+    };
+
+    assert!(matches!(expr.op, ast::BinOp::And(..)));
+
+    let ast::Expr::Let(lhs) = &*expr.lhs else {
+        panic!("expected let expression");
+    };
+
+    assert!(matches!(&*lhs.expr, ast::Expr::Binary(b) if matches!(b.op, ast::BinOp::Or(..))));
 }
 
 /// The condition in an if statement.
 ///
 /// * `true`.
 /// * `let Some(<pat>) = <expr>`.
+/// * `let Some(<pat>) = <expr> && <expr>`, which is parsed as an expression.
 #[derive(Debug, TryClone, PartialEq, Eq, ToTokens, Spanned)]
 #[non_exhaustive]
 pub enum Condition {
@@ -23,7 +65,18 @@ pub enum Condition {
 impl Parse for Condition {
     fn parse(p: &mut Parser<'_>) -> Result<Self> {
         Ok(match p.nth(0)? {
-            K![let] => Self::ExprLet(ast::ExprLet::parse_without_eager_brace(p)?),
+            K![let] => {
+                let expr = ast::ExprLet::parse_without_eager_brace(p)?;
+
+                if !p.peek::<T![&&]>()? {
+                    return Ok(Self::ExprLet(expr));
+                }
+
+                Self::Expr(ast::Expr::parse_binary_without_eager_brace(
+                    p,
+                    ast::Expr::Let(expr),
+                )?)
+            }
             _ => Self::Expr(ast::Expr::parse_without_eager_brace(p)?),
         })
     }
@@ -328,6 +328,21 @@ impl Expr {
         Self::parse_with(p, NOT_EAGER_BRACE, EAGER_BINARY, CALLABLE)
     }
 
+    /// Parse the expression being matched against in a `let` expression.
+    ///
+    /// This stops at the lazy `&&` operator, so that `let` expressions can be
+    /// chained like in `if let Some(x) = a && x > 0`.
+    pub(crate) fn parse_let_scrutinee(p: &mut Parser<'_>) -> Result<Self> {
+        expr_with(p, NOT_EAGER_BRACE, EAGER_BINARY, CALLABLE, LET_CHAIN)
+    }
+
+    /// Continue parsing a binary expression without eager brace, where `lhs`
+    /// has already been parsed.
+    pub(crate) fn parse_binary_without_eager_brace(p: &mut Parser<'_>, lhs: Self) -> Result<Self> {
+        let lookahead = ast::BinOp::from_peeker(p.peeker());
+        binary(p, lhs, lookahead, 0, NOT_EAGER_BRACE, NOT_LET_CHAIN)
+    }
+
     /// Helper to perform a parse with the given meta.
     pub(crate) fn parse_with_meta(
         p: &mut Parser<'_>,
@@ -336,7 +351,7 @@ impl Expr {
     ) -> Result<Self> {
         let lhs = primary(p, attributes, EAGER_BRACE, callable)?;
         let lookahead = ast::BinOp::from_peeker(p.peeker());
-        binary(p, lhs, lookahead, 0, EAGER_BRACE)
+        binary(p, lhs, lookahead, 0, EAGER_BRACE, NOT_LET_CHAIN)
     }
 
     /// ull, configurable parsing of an expression.F
@@ -346,22 +361,7 @@ impl Expr {
         eager_binary: EagerBinary,
         callable: Callable,
     ) -> Result<Self> {
-        let mut attributes = p.parse()?;
-
-        let expr = primary(p, &mut attributes, eager_brace, callable)?;
-
-        let expr = if *eager_binary {
-            let lookeahead = ast::BinOp::from_peeker(p.peeker());
-            binary(p, expr, lookeahead, 0, eager_brace)?
-        } else {
-            expr
-        };
-
-        if let Some(span) = attributes.option_span() {
-            return Err(compile::Error::unsupported(span, "attributes"));
-        }
-
-        Ok(expr)
+        expr_with(p, eager_brace, eager_binary, callable, NOT_LET_CHAIN)
     }
 
     /// Parse expressions that start with an identifier.
@@ -441,6 +441,50 @@ impl Peek for Expr {
     }
 }
 
+/// Indicates if a binary expression is part of a let chain, in which case it
+/// ends at the first `&&` operator.
+#[derive(Debug, Clone, Copy)]
+struct LetChain(bool);
+
+/// Indicates that a binary expression is the scrutinee of a `let` expression.
+const LET_CHAIN: LetChain = LetChain(true);
+
+/// Indicates that a binary expression is not part of a let chain.
+const NOT_LET_CHAIN: LetChain = LetChain(false);
+
+impl LetChain {
+    /// Test if the given operator ends the binary expression.
+    fn ends_at(self, op: &ast::BinOp) -> bool {
+        self.0 && matches!(op, ast::BinOp::And(..))
+    }
+}
+
+/// Parse an expression, which might be the scrutinee of a `let` expression.
+fn expr_with(
+    p: &mut Parser<'_>,
+    eager_brace: EagerBrace,
+    eager_binary: EagerBinary,
+    callable: Callable,
+    let_chain: LetChain,
+) -> Result<Expr> {
+    let mut attributes = p.parse()?;
+
+    let expr = primary(p, &mut attributes, eager_brace, callable)?;
+
+    let expr = if *eager_binary {
+        let lookeahead = ast::BinOp::from_peeker(p.peeker());
+        binary(p, expr, lookeahead, 0, eager_brace, let_chain)?
+    } else {
+        expr
+    };
+
+    if let Some(span) = attributes.option_span() {
+        return Err(compile::Error::unsupported(span, "attributes"));
+    }
+
+    Ok(expr)
+}
+
 /// Primary parse entry point.
 fn primary(
     p: &mut Parser<'_>,
@@ -477,11 +521,25 @@ fn base(
     let expr = match p.nth(0)? {
         K![..] => {
             let limits = ast::ExprRangeLimits::HalfOpen(p.parse()?);
-            range(p, take(attributes), None, limits, eager_brace)?
+            range(
+                p,
+                take(attributes),
+                None,
+                limits,
+                eager_brace,
+                NOT_LET_CHAIN,
+            )?
         }
         K![..=] => {
             let limits = ast::ExprRangeLimits::Closed(p.parse()?);
-            range(p, take(attributes), None, limits, eager_brace)?
+            range(
+                p,
+                take(attributes),
+                None,
+                limits,
+                eager_brace,
+                NOT_LET_CHAIN,
+            )?
         }
         K![#] => {
             let ident = ast::ObjectIdent::Anonymous(p.parse()?);
@@ -653,11 +711,12 @@ fn binary(
     mut lookahead: Option<ast::BinOp>,
     min_precedence: usize,
     eager_brace: EagerBrace,
+    let_chain: LetChain,
 ) -> Result<Expr> {
     while let Some(op) = lookahead {
         let precedence = op.precedence();
 
-        if precedence < min_precedence {
+        if precedence < min_precedence || let_chain.ends_at(&op) {
             break;
         }
 
@@ -671,6 +730,7 @@ fn binary(
                     Some(Box::try_new(lhs)?),
                     ast::ExprRangeLimits::HalfOpen(token),
                     eager_brace,
+                    let_chain,
                 )?;
                 lookahead = ast::BinOp::from_peeker(p.peeker());
                 continue;
@@ -682,6 +742,7 @@ fn binary(
                     Some(Box::try_new(lhs)?),
                     ast::ExprRangeLimits::Closed(token),
                     eager_brace,
+                    let_chain,
                 )?;
                 lookahead = ast::BinOp::from_peeker(p.peeker());
                 continue;
@@ -693,10 +754,14 @@ fn binary(
         lookahead = ast::BinOp::from_peeker(p.peeker());
 
         while let Some(next) = lookahead {
+            if let_chain.ends_at(&next) {
+                break;
+            }
+
             match (precedence, next.precedence()) {
                 (lh, rh) if lh < rh => {
                     // Higher precedence elements require us to recurse.
-                    rhs = binary(p, rhs, Some(next), lh + 1, eager_brace)?;
+                    rhs = binary(p, rhs, Some(next), lh + 1, eager_brace, let_chain)?;
                     lookahead = ast::BinOp::from_peeker(p.peeker());
                     continue;
                 }
@@ -732,13 +797,15 @@ fn range(
     from: Option<Box<Expr>>,
     limits: ast::ExprRangeLimits,
     eager_brace: EagerBrace,
+    let_chain: LetChain,
 ) -> Result<Expr> {
     let to = if Expr::peek_with_brace(p.peeker(), eager_brace) {
-        Some(Box::try_new(Expr::parse_with(
+        Some(Box::try_new(expr_with(
             p,
             eager_brace,
             EAGER_BINARY,
             CALLABLE,
+            let_chain,
         )?)?)
     } else {
         None
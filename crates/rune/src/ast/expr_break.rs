@@ -6,6 +6,8 @@ fn ast_parse() {
     rt::<ast::ExprBreak>("break");
     rt::<ast::ExprBreak>("break 42");
     rt::<ast::ExprBreak>("#[attr] break 42");
+    rt::<ast::ExprBreak>("break 'outer");
+    rt::<ast::ExprBreak>("break 'outer x * y");
 }
 
 /// A break expression.
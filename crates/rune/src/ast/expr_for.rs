@@ -7,6 +7,7 @@ fn ast_parse() {
     rt::<ast::ExprFor>("for (a, _) in x {}");
     rt::<ast::ExprFor>("'label: for i in x {}");
     rt::<ast::ExprFor>("#[attr] 'label: for i in x {}");
+    rt::<ast::ExprFor>("'outer: for x in xs { for y in ys { continue 'outer; } }");
 }
 
 /// A `for` loop over an iterator.
@@ -5,6 +5,7 @@ use crate::ast::prelude::*;
 fn ast_parse() {
     rt::<ast::ExprLet>("let x = 1");
     rt::<ast::ExprLet>("#[attr] let a = f()");
+    rt::<ast::ExprLet>("let a = 1 + 2 * f() == b");
 }
 
 /// A let expression.
@@ -41,7 +42,7 @@ impl ExprLet {
             mut_token: parser.parse()?,
             pat: parser.parse()?,
             eq: parser.parse()?,
-            expr: Box::try_new(ast::Expr::parse_without_eager_brace(parser)?)?,
+            expr: Box::try_new(ast::Expr::parse_let_scrutinee(parser)?)?,
         })
     }
 
@@ -53,7 +54,7 @@ impl ExprLet {
             mut_token: parser.parse()?,
             pat: parser.parse()?,
             eq: parser.parse()?,
-            expr: Box::try_new(ast::Expr::parse_without_eager_brace(parser)?)?,
+            expr: Box::try_new(ast::Expr::parse_let_scrutinee(parser)?)?,
         })
     }
 }
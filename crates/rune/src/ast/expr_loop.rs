@@ -7,6 +7,7 @@ fn ast_parse() {
     rt::<ast::ExprLoop>("loop { 1; }");
     rt::<ast::ExprLoop>("'label: loop {1;}");
     rt::<ast::ExprLoop>("#[attr] 'label: loop {x();}");
+    rt::<ast::ExprLoop>("'outer: loop { for x in xs { break 'outer x; } }");
 }
 
 /// A `loop` expression.
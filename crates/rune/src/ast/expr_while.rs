@@ -6,6 +6,7 @@ fn ast_parse() {
     rt::<ast::ExprWhile>("while x {}");
     rt::<ast::ExprWhile>("'label: while x {}");
     rt::<ast::ExprWhile>("#[attr] 'label: while x {}");
+    rt::<ast::ExprWhile>("'outer: while x { loop { break 'outer; } }");
 }
 
 /// A `while` loop.
@@ -25,6 +25,8 @@ pub enum Item {
     Const(ast::ItemConst),
     /// A macro call expanding into an item.
     MacroCall(ast::MacroCall),
+    /// A declarative macro definition.
+    Macro(ast::ItemMacro),
 }
 
 impl Item {
@@ -39,6 +41,7 @@ impl Item {
             Self::Mod(item) => &item.attributes,
             Self::Const(item) => &item.attributes,
             Self::MacroCall(item) => &item.attributes,
+            Self::Macro(item) => &item.attributes,
         }
     }
     /// Get the item's attributes mutably
@@ -52,6 +55,7 @@ impl Item {
             Self::Mod(item) => &mut item.attributes,
             Self::Const(item) => &mut item.attributes,
             Self::MacroCall(item) => &mut item.attributes,
+            Self::Macro(item) => &mut item.attributes,
         }
     }
 
@@ -76,6 +80,7 @@ impl Item {
             K![fn] => true,
             K![mod] => true,
             K![const] => true,
+            K![macro] => true,
             _ => false,
         }
     }
@@ -129,6 +134,11 @@ impl Item {
                     take(&mut attributes),
                     take(&mut visibility),
                 )?),
+                K![macro] => Self::Macro(ast::ItemMacro::parse_with_meta(
+                    p,
+                    take(&mut attributes),
+                    take(&mut visibility),
+                )?),
                 K![ident] => {
                     if let Some(const_token) = const_token.take() {
                         Self::Const(ast::ItemConst::parse_with_meta(
@@ -144,7 +154,7 @@ impl Item {
                 _ => {
                     return Err(compile::Error::expected(
                         p.tok_at(0)?,
-                        "`fn`, `mod`, `struct`, `enum`, `use`, or macro call",
+                        "`fn`, `mod`, `struct`, `enum`, `use`, `macro`, or macro call",
                     ))
                 }
             };
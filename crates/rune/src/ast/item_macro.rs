@@ -0,0 +1,131 @@
+use crate::ast::prelude::*;
+
+#[test]
+#[cfg(not(miri))]
+fn ast_parse() {
+    rt::<ast::ItemMacro>("macro foo() {}");
+    rt::<ast::ItemMacro>("macro swap($a:expr, $b:expr) { let tmp = $a; $a = $b; $b = tmp; }");
+    rt::<ast::ItemMacro>("pub macro sum($($e:expr),*) { 0 $(+ $e)* }");
+    rt::<ast::ItemMacro>("macro nested(($a:tt) [$b:tt]) { { ($a, $b) } }");
+}
+
+/// A declarative macro definition.
+///
+/// * `macro <name>(<pattern>) { <body> }`.
+#[derive(Debug, TryClone, PartialEq, Eq, ToTokens, Spanned)]
+#[non_exhaustive]
+pub struct ItemMacro {
+    /// The attributes of the macro definition.
+    #[rune(iter)]
+    pub attributes: Vec<ast::Attribute>,
+    /// The visibility of the macro.
+    #[rune(option)]
+    pub visibility: ast::Visibility,
+    /// The `macro` keyword.
+    pub macro_token: T![macro],
+    /// The name of the macro.
+    pub name: ast::Ident,
+    /// The opening parenthesis of the pattern.
+    pub open: T!['('],
+    /// The pattern the input of the macro is matched against.
+    #[rune(iter)]
+    pub pattern: TokenStream,
+    /// The closing parenthesis of the pattern.
+    pub close: T![')'],
+    /// The opening brace of the body.
+    pub body_open: T!['{'],
+    /// The body the macro expands into.
+    #[rune(iter)]
+    pub body: TokenStream,
+    /// The closing brace of the body.
+    pub body_close: T!['}'],
+    /// Opaque identifier for the macro.
+    #[rune(skip)]
+    pub(crate) id: ItemId,
+}
+
+impl ItemMacro {
+    /// Get the descriptive span of this item, e.g. `macro swap` instead of
+    /// the span for the whole definition.
+    pub(crate) fn descriptive_span(&self) -> Span {
+        self.macro_token.span().join(self.name.span())
+    }
+
+    /// Parse a macro definition attaching the given meta.
+    pub(crate) fn parse_with_meta(
+        p: &mut Parser<'_>,
+        attributes: Vec<ast::Attribute>,
+        visibility: ast::Visibility,
+    ) -> Result<Self> {
+        let macro_token = p.parse()?;
+        let name = p.parse()?;
+
+        let open: T!['('] = p.parse()?;
+        let pattern = delimited(p, open.span, ast::Delimiter::Parenthesis)?;
+        let close = p.parse()?;
+
+        let body_open: T!['{'] = p.parse()?;
+        let body = delimited(p, body_open.span, ast::Delimiter::Brace)?;
+        let body_close = p.parse()?;
+
+        Ok(Self {
+            attributes,
+            visibility,
+            macro_token,
+            name,
+            open,
+            pattern,
+            close,
+            body_open,
+            body,
+            body_close,
+            id: Default::default(),
+        })
+    }
+}
+
+impl Parse for ItemMacro {
+    fn parse(p: &mut Parser<'_>) -> Result<Self> {
+        let attributes = p.parse()?;
+        let visibility = p.parse()?;
+        Self::parse_with_meta(p, attributes, visibility)
+    }
+}
+
+/// Collect a balanced token stream up until, but not including, the close
+/// delimiter matching `delim`.
+fn delimited(p: &mut Parser<'_>, open: Span, delim: ast::Delimiter) -> Result<TokenStream> {
+    let mut level = 0usize;
+    let mut stream = Vec::new();
+
+    loop {
+        match p.nth(0)? {
+            ast::Kind::Open(..) => level += 1,
+            ast::Kind::Close(actual) => {
+                if level == 0 {
+                    if actual != delim {
+                        return Err(compile::Error::new(
+                            open,
+                            ErrorKind::ExpectedMacroCloseDelimiter {
+                                actual: ast::Kind::Close(actual),
+                                expected: ast::Kind::Close(delim),
+                            },
+                        ));
+                    }
+
+                    break;
+                }
+
+                level -= 1;
+            }
+            ast::Kind::Eof => {
+                return Err(compile::Error::new(open, ErrorKind::UnexpectedEof));
+            }
+            _ => (),
+        }
+
+        stream.try_push(p.next()?)?;
+    }
+
+    Ok(TokenStream::from(stream))
+}
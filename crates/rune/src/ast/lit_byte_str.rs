@@ -6,11 +6,15 @@ use crate::ast::prelude::*;
 fn ast_parse() {
     rt::<ast::LitByteStr>("b\"hello world\"");
     rt::<ast::LitByteStr>("b\"hello\\nworld\"");
+    rt::<ast::LitByteStr>("br\"hello\\nworld\"");
+    rt::<ast::LitByteStr>("br#\"hello \"world\"\"#");
 }
 
-/// A string literal.
+/// A byte string literal.
 ///
-/// * `"Hello World"`.
+/// * `b"Hello World"`.
+/// * `br"Hello\nWorld"`, a raw byte string where escapes are not processed.
+/// * `br#"Hello "World""#`, a raw byte string delimited by any number of `#`.
 #[derive(Debug, TryClone, Clone, Copy, PartialEq, Eq, Spanned)]
 #[try_clone(copy)]
 #[non_exhaustive]
@@ -110,7 +114,7 @@ impl<'a> Resolve<'a> for LitByteStr {
             }
         };
 
-        let span = span.trim_start(2u32).trim_end(1u32);
+        let span = text.content_span(span, 1);
         let string = cx
             .sources
             .source(text.source_id, span)
@@ -10,6 +10,13 @@ fn ast_parse() {
     rt::<ast::LitNumber>("42.42");
     rt::<ast::LitNumber>("0.42");
     rt::<ast::LitNumber>("0.42e10");
+    rt::<ast::LitNumber>("1_000_000");
+    rt::<ast::LitNumber>("0b1010_1010");
+    rt::<ast::LitNumber>("0x_ff_ff");
+    rt::<ast::LitNumber>("0o7_7");
+    rt::<ast::LitNumber>("1_000.000_1");
+    rt::<ast::LitNumber>("1e1_0");
+    rt::<ast::LitNumber>("1_000_u32");
 }
 
 /// A number literal.
@@ -112,14 +119,19 @@ impl<'a> Resolve<'a> for LitNumber {
             }
         };
 
+        // NB: Underscores are permitted anywhere in the number to break up
+        // large numbers, so we strip them before parsing.
+        let mut digits = alloc::String::try_with_capacity(string.len())?;
+
+        for c in string.chars().filter(|&c| c != '_') {
+            digits.try_push(c)?;
+        }
+
         if matches!(
             (suffix, text.is_fractional),
             (Some(ast::NumberSuffix::Float(..)), _) | (None, true)
         ) {
-            let number: f64 = string
-                .trim_matches(|c: char| c == '_')
-                .parse()
-                .map_err(err_span(span))?;
+            let number: f64 = digits.parse().map_err(err_span(span))?;
 
             return Ok(ast::Number {
                 value: ast::NumberValue::Float(number),
@@ -134,7 +146,7 @@ impl<'a> Resolve<'a> for LitNumber {
             ast::NumberBase::Decimal => 10,
         };
 
-        let number = num::BigInt::from_str_radix(string, radix).map_err(err_span(span))?;
+        let number = num::BigInt::from_str_radix(&digits, radix).map_err(err_span(span))?;
 
         Ok(ast::Number {
             value: ast::NumberValue::Integer(number),
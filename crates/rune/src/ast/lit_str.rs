@@ -6,12 +6,17 @@ use crate::ast::prelude::*;
 fn ast_parse() {
     rt::<ast::LitStr>("\"hello world\"");
     rt::<ast::LitStr>("\"hello\\nworld\"");
+    rt::<ast::LitStr>("r\"hello\\nworld\"");
+    rt::<ast::LitStr>("r#\"hello \"world\"\"#");
+    rt::<ast::LitStr>("r##\"a \"# b\"##");
 }
 
 /// A string literal.
 ///
 /// * `"Hello World"`.
 /// * `"Hello\nWorld"`.
+/// * `r"Hello\nWorld"`, a raw string where escapes are not processed.
+/// * `r#"Hello "World""#`, a raw string delimited by any number of `#`.
 #[derive(Debug, TryClone, Clone, Copy, PartialEq, Eq, Spanned)]
 #[try_clone(copy)]
 #[non_exhaustive]
@@ -62,11 +67,7 @@ impl LitStr {
             }
         };
 
-        let span = if text.wrapped {
-            span.narrow(1u32)
-        } else {
-            span
-        };
+        let span = text.content_span(span, 0);
 
         let string = cx
             .sources
@@ -143,6 +143,7 @@ mod item_const;
 mod item_enum;
 mod item_fn;
 mod item_impl;
+mod item_macro;
 mod item_mod;
 mod item_struct;
 mod item_use;
@@ -212,6 +213,7 @@ pub use self::item_const::ItemConst;
 pub use self::item_enum::{ItemEnum, ItemVariant};
 pub use self::item_fn::ItemFn;
 pub use self::item_impl::ItemImpl;
+pub use self::item_macro::ItemMacro;
 pub use self::item_mod::{ItemInlineBody, ItemMod, ItemModBody};
 pub use self::item_struct::{Field, ItemStruct};
 pub use self::item_use::{ItemUse, ItemUsePath, ItemUseSegment};
@@ -15,6 +15,31 @@ fn ast_parse() {
     rt::<ast::Pat>("var");
     rt::<ast::Pat>("_");
     rt::<ast::Pat>("Foo(n)");
+    rt::<ast::Pat>("[first, ..]");
+    rt::<ast::Pat>("[first, ..rest]");
+    rt::<ast::Pat>("[first, ..rest, last]");
+    rt::<ast::Pat>("(.., last)");
+    rt::<ast::Pat>("#{a, ..rest}");
+
+    let pat = rt::<ast::Pat>("[first, ..rest]");
+
+    let ast::Pat::Vec(vec) = pat else {
+        panic!("expected vec pattern");
+    };
+
+    assert!(matches!(
+        vec.items.as_slice(),
+        [
+            (ast::Pat::Path(..), _),
+            (
+                ast::Pat::Rest(ast::PatRest {
+                    binding: Some(..),
+                    ..
+                }),
+                _
+            )
+        ]
+    ));
 }
 
 /// A pattern match.
@@ -92,6 +117,7 @@ impl Parse for Pat {
                 return Ok(Self::Rest(PatRest {
                     attributes,
                     dot_dot: p.parse()?,
+                    binding: p.parse()?,
                 }))
             }
             K!['('] => {
@@ -200,6 +226,9 @@ pub struct PatRest {
     pub attributes: Vec<ast::Attribute>,
     /// The rest token `..`.
     pub dot_dot: T![..],
+    /// The name the remaining items are bound to, as in `..rest`.
+    #[rune(iter)]
+    pub binding: Option<ast::Ident>,
 }
 
 /// An array pattern.
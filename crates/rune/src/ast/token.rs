@@ -51,6 +51,16 @@ impl Token {
                 }
             },
             Kind::ByteStr(s) => match s {
+                StrSource::Text(text) if text.raw.is_some() => {
+                    let s = cx
+                        .idx
+                        .q
+                        .sources
+                        .source(text.source_id, self.span)
+                        .ok_or(fmt::Error)?;
+
+                    write!(f, "{}", s)?;
+                }
                 StrSource::Text(text) => {
                     let span = if text.wrapped {
                         self.span.narrow(1u32)
@@ -73,6 +83,16 @@ impl Token {
                 }
             },
             Kind::Str(s) => match s {
+                StrSource::Text(text) if text.raw.is_some() => {
+                    let s = cx
+                        .idx
+                        .q
+                        .sources
+                        .source(text.source_id, self.span)
+                        .ok_or(fmt::Error)?;
+
+                    write!(f, "{}", s)?;
+                }
                 StrSource::Text(text) => {
                     let span = if text.wrapped {
                         self.span.narrow(1u32)
@@ -474,6 +494,25 @@ pub struct StrText {
     pub escaped: bool,
     /// Indicated if the buffer is wrapped or not.
     pub wrapped: bool,
+    /// The number of `#` delimiting the string if it's a raw string, like
+    /// `r#"hello"#`.
+    pub raw: Option<u32>,
+}
+
+impl StrText {
+    /// Get the span of the content of a string literal with the given span,
+    /// where `prefix` is the length of any prefix such as the `b` in a byte
+    /// string.
+    pub(crate) fn content_span(&self, span: Span, prefix: u32) -> Span {
+        if !self.wrapped {
+            return span;
+        }
+
+        match self.raw {
+            Some(hashes) => span.trim_start(prefix + hashes + 2).trim_end(hashes + 1),
+            None => span.trim_start(prefix + 1).trim_end(1u32),
+        }
+    }
 }
 
 /// The source of a number.
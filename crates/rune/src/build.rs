@@ -1,7 +1,9 @@
 use core::fmt;
 use core::marker::PhantomData;
 use core::mem::take;
+use core::sync::atomic::{AtomicBool, Ordering};
 
+use crate::alloc::prelude::*;
 use crate::alloc::{self, Vec};
 use crate::ast::{Span, Spanned};
 #[cfg(feature = "std")]
@@ -9,9 +11,10 @@ use crate::compile::FileSourceLoader as DefaultSourceLoader;
 #[cfg(not(feature = "std"))]
 use crate::compile::NoopSourceLoader as DefaultSourceLoader;
 use crate::compile::{
-    self, CompileVisitor, Located, MetaError, Options, ParseOptionError, Pool, SourceLoader,
+    self, BuildCache, CompileVisitor, DeclarationKind, Located, MetaError, Options,
+    ParseOptionError, Pool, SourceLoader,
 };
-use crate::runtime::unit::{DefaultStorage, UnitEncoder};
+use crate::runtime::unit::{DefaultStorage, UnitEncoder, UnitStorage};
 use crate::runtime::Unit;
 use crate::{Context, Diagnostics, Item, SourceId, Sources};
 
@@ -40,12 +43,27 @@ impl From<alloc::Error> for BuildError {
     }
 }
 
+impl BuildError {
+    fn cancelled() -> Self {
+        Self {
+            kind: BuildErrorKind::Cancelled,
+        }
+    }
+
+    /// Test if the build was aborted through the flag passed to
+    /// [`Build::with_cancellation`].
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self.kind, BuildErrorKind::Cancelled)
+    }
+}
+
 #[derive(Default, Debug)]
 enum BuildErrorKind {
     #[default]
     Default,
     ParseOptionError(ParseOptionError),
     Alloc(alloc::Error),
+    Cancelled,
 }
 
 impl fmt::Display for BuildError {
@@ -57,6 +75,7 @@ impl fmt::Display for BuildError {
             ),
             BuildErrorKind::ParseOptionError(error) => error.fmt(f),
             BuildErrorKind::Alloc(error) => error.fmt(f),
+            BuildErrorKind::Cancelled => write!(f, "Build was cancelled"),
         }
     }
 }
@@ -140,6 +159,8 @@ where
         options: None,
         visitors: Vec::new(),
         source_loader: None,
+        cache: None,
+        cancel: None,
         _unit_storage: PhantomData,
     }
 }
@@ -156,6 +177,8 @@ pub struct Build<'a, S> {
     options: Option<&'a Options>,
     visitors: Vec<&'a mut dyn compile::CompileVisitor>,
     source_loader: Option<&'a mut dyn SourceLoader>,
+    cache: Option<&'a mut BuildCache<S>>,
+    cancel: Option<&'a AtomicBool>,
     _unit_storage: PhantomData<S>,
 }
 
@@ -198,6 +221,35 @@ impl compile::CompileVisitor for CompileVisitorGroup<'_> {
         Ok(())
     }
 
+    fn visit_local(
+        &mut self,
+        source_id: SourceId,
+        name: &str,
+        span: &dyn Spanned,
+        scope: &dyn Spanned,
+        parameter: bool,
+    ) -> Result<(), MetaError> {
+        for v in self.visitors.iter_mut() {
+            v.visit_local(source_id, name, span, scope, parameter)?;
+        }
+
+        Ok(())
+    }
+
+    fn visit_field_use(
+        &mut self,
+        source_id: SourceId,
+        container: Option<crate::Hash>,
+        name: &str,
+        span: &dyn Spanned,
+    ) -> Result<(), MetaError> {
+        for v in self.visitors.iter_mut() {
+            v.visit_field_use(source_id, container, name, span)?;
+        }
+
+        Ok(())
+    }
+
     fn visit_mod(&mut self, location: &dyn Located) -> Result<(), MetaError> {
         for v in self.visitors.iter_mut() {
             v.visit_mod(location)?;
@@ -206,6 +258,20 @@ impl compile::CompileVisitor for CompileVisitorGroup<'_> {
         Ok(())
     }
 
+    fn visit_declaration(
+        &mut self,
+        location: &dyn Located,
+        name: Span,
+        item: &Item,
+        kind: DeclarationKind,
+    ) -> Result<(), MetaError> {
+        for v in self.visitors.iter_mut() {
+            v.visit_declaration(location, name, item, kind)?;
+        }
+
+        Ok(())
+    }
+
     fn visit_doc_comment(
         &mut self,
         location: &dyn Located,
@@ -220,6 +286,20 @@ impl compile::CompileVisitor for CompileVisitorGroup<'_> {
         Ok(())
     }
 
+    fn visit_field(
+        &mut self,
+        location: &dyn Located,
+        item: &Item,
+        hash: crate::Hash,
+        field: &str,
+    ) -> Result<(), MetaError> {
+        for v in self.visitors.iter_mut() {
+            v.visit_field(location, item, hash, field)?;
+        }
+
+        Ok(())
+    }
+
     fn visit_field_doc_comment(
         &mut self,
         location: &dyn Located,
@@ -286,6 +366,28 @@ impl<'a, S> Build<'a, S> {
         self
     }
 
+    /// Modify the current [Build] to use the given [BuildCache].
+    ///
+    /// The cache is held by the caller and should be reused across builds of
+    /// the same project, so that work is only redone for sources which have
+    /// changed since the last build.
+    #[inline]
+    pub fn with_cache(mut self, cache: &'a mut BuildCache<S>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Modify the current [Build] to be aborted once the given flag is set.
+    ///
+    /// The flag is checked between the phases of compilation and in between
+    /// compiling each item, after which the build fails with a [BuildError]
+    /// for which [BuildError::is_cancelled] returns `true`.
+    #[inline]
+    pub fn with_cancellation(mut self, cancel: &'a AtomicBool) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
     /// Build a [`Unit`] with the current configuration.
     ///
     /// See [`rune::prepare`] for more.
@@ -293,7 +395,7 @@ impl<'a, S> Build<'a, S> {
     /// [`rune::prepare`]: prepare
     pub fn build(mut self) -> Result<Unit<S>, BuildError>
     where
-        S: Default + UnitEncoder,
+        S: Default + TryClone + UnitEncoder + UnitStorage,
     {
         let default_context;
 
@@ -333,6 +435,10 @@ impl<'a, S> Build<'a, S> {
             }
         };
 
+        diagnostics.deny_from(options)?;
+
+        let has_visitors = !self.visitors.is_empty();
+
         let mut default_visitors;
         let visitors = match self.visitors.is_empty() {
             true => {
@@ -359,9 +465,38 @@ impl<'a, S> Build<'a, S> {
             }
         };
 
+        let mut cache = self.cache.take();
+
+        if let Some(cache) = &mut cache {
+            cache.begin(options, context)?;
+
+            // Visitors observe the build as it happens, so they would miss out
+            // on a cached unit.
+            if !has_visitors {
+                if let Some(unit) = cache.unit(self.sources)? {
+                    return Ok(unit);
+                }
+            }
+        }
+
+        let reported = diagnostics.diagnostics().len();
+        let source_count = self.sources.source_ids().count();
+
+        let cancel = self.cancel;
+        let is_cancelled = || cancel.is_some_and(|cancel| cancel.load(Ordering::Acquire));
+
         let mut pool = Pool::new()?;
         let mut unit_storage = S::default();
 
+        let (files, items) = match cache.as_deref_mut() {
+            // Reusing compiled items would hide them from visitors.
+            Some(cache) => (
+                Some(&mut cache.files),
+                (!has_visitors).then_some(&mut cache.items),
+            ),
+            None => (None, None),
+        };
+
         compile::compile(
             &mut unit,
             &prelude,
@@ -372,9 +507,20 @@ impl<'a, S> Build<'a, S> {
             diagnostics,
             source_loader,
             options,
+            files,
+            items,
+            &is_cancelled,
             &mut unit_storage,
         )?;
 
+        if is_cancelled() {
+            return Err(BuildError::cancelled());
+        }
+
+        if let Some(cache) = &mut cache {
+            cache.finish();
+        }
+
         if diagnostics.has_error() {
             return Err(BuildError::default());
         }
@@ -387,12 +533,48 @@ impl<'a, S> Build<'a, S> {
             return Err(BuildError::default());
         }
 
-        match unit.build(Span::empty(), unit_storage) {
-            Ok(unit) => Ok(unit),
+        if is_cancelled() {
+            return Err(BuildError::cancelled());
+        }
+
+        if options.dce {
+            let retain = options.retain.as_deref();
+
+            unit_storage =
+                match unit.eliminate_dead_code(Span::empty(), unit_storage, retain, diagnostics) {
+                    Ok(unit_storage) => unit_storage,
+                    Err(error) => {
+                        diagnostics.error(SourceId::empty(), error)?;
+                        return Err(BuildError::default());
+                    }
+                };
+        }
+
+        #[cfg(feature = "debug-access")]
+        unit.insert_debug_sources(self.sources)?;
+
+        let unit = match unit.build(Span::empty(), unit_storage) {
+            Ok(unit) => unit,
             Err(error) => {
                 diagnostics.error(SourceId::empty(), error)?;
-                Err(BuildError::default())
+                return Err(BuildError::default());
+            }
+        };
+
+        if let Some(cache) = cache {
+            // Reusing the unit wouldn't reproduce any diagnostics, nor notice
+            // changes to sources loaded through the source loader.
+            let cacheable = diagnostics.has_warnings_enabled()
+                && diagnostics.diagnostics().len() == reported
+                && self.sources.source_ids().count() == source_count;
+
+            if cacheable {
+                cache.insert_unit(self.sources, &unit)?;
+            } else {
+                cache.remove_unit();
             }
         }
+
+        Ok(unit)
     }
 }
@@ -85,6 +85,8 @@ where
         let item = naming.item(&e)?;
 
         let mut visitor = crate::doc::Visitor::new(&item)?;
+        // Private items can still be completed from within the script.
+        visitor.document_private = true;
         let mut sources = Sources::new();
 
         let source = match Source::from_path(e.path()) {
@@ -1,16 +1,26 @@
+#[cfg(test)]
+mod tests;
+
+use std::collections::BTreeMap;
 use std::fmt;
+use std::fs;
 use std::hint;
-use std::io::Write;
+use std::io::{self, Write};
 use std::path::PathBuf;
+use std::string::String;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
 
 use crate::alloc::Vec;
 use crate::cli::{AssetKind, CommandBase, Config, ExitCode, Io, SharedFlags};
 use crate::modules::capture_io::CaptureIo;
 use crate::modules::test::Bencher;
-use crate::runtime::{Function, Unit, Value};
+use crate::runtime::{budget, Function, Unit, Value};
 use crate::support::Result;
+use crate::termcolor::StandardStream;
 use crate::{Context, Hash, ItemBuf, Sources, Vm};
 
 use super::{Color, Stream};
@@ -19,23 +29,60 @@ mod cli {
     use std::path::PathBuf;
     use std::vec::Vec;
 
-    use clap::Parser;
+    use clap::{Parser, ValueEnum};
 
     #[derive(Parser, Debug)]
     #[command(rename_all = "kebab-case")]
     pub(crate) struct Flags {
-        /// Rounds of warmup to perform
+        /// Number of warmup iterations to perform before collecting samples.
+        #[arg(long, default_value = "10")]
+        pub(super) warmup: usize,
+        /// Number of samples to collect for each benchmark.
+        #[arg(long, default_value = "100")]
+        pub(super) samples: usize,
+        /// The format to report benchmark results in.
+        #[arg(long, default_value = "human")]
+        pub(super) format: Format,
+        /// Write machine-readable results to the given path instead of stdout.
+        #[arg(long)]
+        pub(super) output: Option<PathBuf>,
+        /// A file containing results from a previous run in the `json` format
+        /// to compare against. If any benchmark regressed by more than
+        /// `--threshold` the command fails.
+        #[arg(long)]
+        pub(super) baseline: Option<PathBuf>,
+        /// The percentage by which the median of a benchmark may increase
+        /// relative to the baseline before it's considered a regression.
         #[arg(long, default_value = "5.0")]
-        pub(super) warmup: f32,
-        /// Iterations to run of the benchmark
-        #[arg(long, default_value = "10.0")]
-        pub(super) iter: f32,
+        pub(super) threshold: f64,
         /// Explicit paths to benchmark.
         pub(super) bench_path: Vec<PathBuf>,
     }
+
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+    pub(crate) enum Format {
+        #[default]
+        /// Only report results in human-readable form.
+        Human,
+        /// Additionally report results as JSON.
+        Json,
+    }
 }
 
-pub(super) use cli::Flags;
+pub(super) use cli::{Flags, Format};
+
+/// The minimum amount of time each sample should take. Fast benchmarks run
+/// multiple iterations per sample so that the overhead of measuring doesn't
+/// dominate the result.
+const SAMPLE_TARGET: Duration = Duration::from_millis(1);
+
+impl Flags {
+    /// Test if results are written as JSON to stdout, in which case no
+    /// human-readable output may be written to it.
+    fn is_json_stdout(&self) -> bool {
+        self.format == Format::Json && self.output.is_none()
+    }
+}
 
 impl CommandBase for Flags {
     #[inline]
@@ -59,6 +106,116 @@ impl CommandBase for Flags {
     }
 }
 
+/// Statistics collected for a single benchmark, in nanoseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(super) struct Stats {
+    pub(super) samples: usize,
+    pub(super) mean: f64,
+    pub(super) median: f64,
+    pub(super) stddev: f64,
+    pub(super) min: f64,
+    pub(super) max: f64,
+}
+
+impl Stats {
+    /// Calculate statistics from the given samples, or `None` if there are no
+    /// samples.
+    pub(super) fn from_samples(samples: &mut [f64]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        samples.sort_unstable_by(f64::total_cmp);
+
+        let len = samples.len();
+        let mean = samples.iter().sum::<f64>() / len as f64;
+
+        let median = if len % 2 == 0 {
+            (samples[len / 2 - 1] + samples[len / 2]) / 2.0
+        } else {
+            samples[len / 2]
+        };
+
+        let variance = samples.iter().map(|n| (n - mean).powi(2)).sum::<f64>() / len as f64;
+
+        Some(Self {
+            samples: len,
+            mean,
+            median,
+            stddev: variance.sqrt(),
+            min: samples[0],
+            max: samples[len - 1],
+        })
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "mean={:.2}, median={:.2}, stddev={:.2}, min={:.2}, max={:.2}, samples={}",
+            Time(self.mean),
+            Time(self.median),
+            Time(self.stddev),
+            Time(self.min),
+            Time(self.max),
+            self.samples
+        )
+    }
+}
+
+/// A machine-readable record of benchmark results, keyed by the item path of
+/// each benchmark.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(super) struct Record {
+    pub(super) benches: BTreeMap<String, Stats>,
+}
+
+/// A benchmark which regressed relative to a baseline.
+#[derive(Debug, PartialEq)]
+pub(super) struct Regression<'a> {
+    pub(super) name: &'a str,
+    pub(super) before: f64,
+    pub(super) after: f64,
+    /// The change in percent.
+    pub(super) change: f64,
+}
+
+/// Compare the current record against a baseline, returning every benchmark
+/// whose median increased by more than `threshold` percent.
+///
+/// Benchmarks which are missing from either record are ignored.
+pub(super) fn compare<'a>(
+    baseline: &Record,
+    current: &'a Record,
+    threshold: f64,
+) -> std::vec::Vec<Regression<'a>> {
+    let mut regressions = std::vec::Vec::new();
+
+    for (name, after) in &current.benches {
+        let Some(before) = baseline.benches.get(name) else {
+            continue;
+        };
+
+        if before.median <= 0.0 {
+            continue;
+        }
+
+        let change = (after.median - before.median) / before.median * 100.0;
+
+        if change > threshold {
+            regressions.push(Regression {
+                name,
+                before: before.median,
+                after: after.median,
+                change,
+            });
+        }
+    }
+
+    regressions
+}
+
 /// Run benchmarks.
 pub(super) async fn run(
     io: &mut Io<'_>,
@@ -68,6 +225,7 @@ pub(super) async fn run(
     unit: Arc<Unit>,
     sources: &Sources,
     fns: &[(Hash, ItemBuf)],
+    record: &mut Record,
 ) -> Result<ExitCode> {
     let runtime = Arc::new(context.runtime()?);
     let mut vm = Vm::new(runtime, unit);
@@ -76,9 +234,11 @@ pub(super) async fn run(
         return Ok(ExitCode::Success);
     }
 
-    io.section("Benching", Stream::Stdout, Color::Highlight)?
-        .append(format_args!(" Found {} benches", fns.len()))?
-        .close()?;
+    if !args.is_json_stdout() {
+        io.section("Benching", Stream::Stdout, Color::Highlight)?
+            .append(format_args!(" Found {} benches", fns.len()))?
+            .close()?;
+    }
 
     let mut any_error = false;
 
@@ -86,15 +246,16 @@ pub(super) async fn run(
         let mut bencher = Bencher::default();
 
         if let Err(error) = vm.call(*hash, (&mut bencher,)) {
-            writeln!(io.stdout, "{}: Error in benchmark", item)?;
-            error.emit(io.stdout, sources)?;
+            let out = errors(io, args);
+            writeln!(out, "{}: Error in benchmark", item)?;
+            error.emit(&mut *out, sources)?;
             any_error = true;
 
             if let Some(capture_io) = capture_io {
                 if !capture_io.is_empty() {
-                    writeln!(io.stdout, "-- output --")?;
-                    capture_io.drain_into(&mut *io.stdout)?;
-                    writeln!(io.stdout, "-- end output --")?;
+                    writeln!(out, "-- output --")?;
+                    capture_io.drain_into(&mut *out)?;
+                    writeln!(out, "-- end output --")?;
                 }
             }
 
@@ -115,18 +276,29 @@ pub(super) async fn run(
                 &item
             };
 
-            if let Err(e) = bench_fn(io, item, args, f) {
-                writeln!(io.stdout, "{}: Error in bench iteration: {}", item, e)?;
+            // Budgeting is disabled while benchmarking, since it would
+            // otherwise skew the results.
+            let result = budget::with(usize::MAX, || bench_fn(io, item, args, f)).call();
 
-                if let Some(capture_io) = capture_io {
-                    if !capture_io.is_empty() {
-                        writeln!(io.stdout, "-- output --")?;
-                        capture_io.drain_into(&mut *io.stdout)?;
-                        writeln!(io.stdout, "-- end output --")?;
-                    }
+            match result {
+                Ok(Some(stats)) => {
+                    record.benches.insert(std::format!("{item}"), stats);
                 }
+                Ok(None) => {}
+                Err(e) => {
+                    let out = errors(io, args);
+                    writeln!(out, "{}: Error in bench iteration: {}", item, e)?;
+
+                    if let Some(capture_io) = capture_io {
+                        if !capture_io.is_empty() {
+                            writeln!(out, "-- output --")?;
+                            capture_io.drain_into(&mut *out)?;
+                            writeln!(out, "-- end output --")?;
+                        }
+                    }
 
-                any_error = true;
+                    any_error = true;
+                }
             }
         }
     }
@@ -138,6 +310,78 @@ pub(super) async fn run(
     }
 }
 
+/// The stream errors are written to, which is stderr if stdout is reserved for
+/// machine-readable results.
+fn errors<'a>(io: &'a mut Io<'_>, args: &Flags) -> &'a mut StandardStream {
+    if args.is_json_stdout() {
+        io.stderr
+    } else {
+        io.stdout
+    }
+}
+
+/// Write the collected record in the requested format and compare it against
+/// the baseline, if one is specified.
+pub(super) fn finish(io: &mut Io<'_>, args: &Flags, record: &Record) -> Result<ExitCode> {
+    if args.format == Format::Json {
+        match &args.output {
+            Some(path) => {
+                let mut out = io::BufWriter::new(fs::File::create(path)?);
+                serde_json::to_writer_pretty(&mut out, record)?;
+                writeln!(out)?;
+                out.flush()?;
+            }
+            None => {
+                serde_json::to_writer_pretty(&mut *io.stdout, record)?;
+                writeln!(io.stdout)?;
+            }
+        }
+    }
+
+    let Some(path) = &args.baseline else {
+        return Ok(ExitCode::Success);
+    };
+
+    let baseline = fs::read(path).with_context(|| std::format!("{}", path.display()))?;
+    let baseline: Record =
+        serde_json::from_slice(&baseline).with_context(|| std::format!("{}", path.display()))?;
+
+    let regressions = compare(&baseline, record, args.threshold);
+
+    // Regressions are reported on stderr if stdout is reserved for results.
+    let stream = if args.is_json_stdout() {
+        Stream::Stderr
+    } else {
+        Stream::Stdout
+    };
+
+    if regressions.is_empty() {
+        io.section("Compared", stream, Color::Passed)?
+            .append(format_args!(
+                " {} benches against {}, no regressions",
+                record.benches.len(),
+                path.display()
+            ))?
+            .close()?;
+
+        return Ok(ExitCode::Success);
+    }
+
+    for r in &regressions {
+        io.section("Regressed", stream, Color::Error)?
+            .append(format_args!(
+                " {}: median {:.2} -> {:.2} (+{:.2}%)",
+                r.name,
+                Time(r.before),
+                Time(r.after),
+                r.change
+            ))?
+            .close()?;
+    }
+
+    Ok(ExitCode::Failure)
+}
+
 struct DisplayHash<A, B>(A, B);
 
 impl<A, B> fmt::Display for DisplayHash<A, B>
@@ -151,118 +395,127 @@ where
     }
 }
 
-fn bench_fn(io: &mut Io<'_>, item: &dyn fmt::Display, args: &Flags, f: &Function) -> Result<()> {
-    let mut section = io.section("Warming up", Stream::Stdout, Color::Progress)?;
-    section.append(format_args!(" {item} for {:.2}s:", args.warmup))?;
-    section.flush()?;
+fn bench_fn(
+    io: &mut Io<'_>,
+    item: &dyn fmt::Display,
+    args: &Flags,
+    f: &Function,
+) -> Result<Option<Stats>> {
+    let quiet = args.is_json_stdout();
+
+    let mut section = None;
+
+    if !quiet {
+        let s = section.insert(io.section("Warming up", Stream::Stdout, Color::Progress)?);
+        s.append(format_args!(" {item} for {} iterations:", args.warmup))?;
+        s.flush()?;
+    }
 
     let start = Instant::now();
-    let mut warmup = 0;
 
-    let elapsed = loop {
+    for _ in 0..args.warmup {
         let value = f.call::<Value>(()).into_result()?;
         drop(hint::black_box(value));
-        warmup += 1;
+    }
 
-        let elapsed = start.elapsed().as_secs_f32();
+    let warmup = start.elapsed();
 
-        if elapsed >= args.warmup {
-            break elapsed;
-        }
-    };
+    if let Some(section) = &mut section {
+        section
+            .append(format_args!(" {:.2}s", warmup.as_secs_f32()))?
+            .close()?;
+    }
 
-    section
-        .append(format_args!(" {warmup} iters in {elapsed:.2}s"))?
-        .close()?;
+    let iterations = iterations(warmup, args.warmup);
 
-    let iterations = (((args.iter * warmup as f32) / args.warmup).round() as usize).max(1);
-    let step = (iterations / 10).max(1);
-    let mut collected = Vec::try_with_capacity(iterations)?;
+    let step = (args.samples / 10).max(1);
+    let mut collected = Vec::try_with_capacity(args.samples)?;
 
-    let mut section = io.section("Running", Stream::Stdout, Color::Progress)?;
-    section.append(format_args!(
-        " {item} {} iterations for {:.2}s: ",
-        iterations, args.iter
-    ))?;
+    let mut section = None;
 
-    let mut added = 0;
+    if !quiet {
+        let s = section.insert(io.section("Running", Stream::Stdout, Color::Progress)?);
+        s.append(format_args!(" {item} {} samples", args.samples))?;
 
-    for n in 0..=iterations {
-        if n % step == 0 {
-            section.append(".")?;
-            section.flush()?;
-            added += 1;
+        if iterations > 1 {
+            s.append(format_args!(" of {iterations} iterations"))?;
         }
 
-        let start = Instant::now();
-        let value = f.call::<Value>(()).into_result()?;
-        let duration = Instant::now().duration_since(start);
-        collected.try_push(duration.as_nanos() as i128)?;
-        drop(hint::black_box(value));
+        s.append(": ")?;
     }
 
-    for _ in added..10 {
-        section.append(".")?;
-        section.flush()?;
-    }
+    let mut added = 0;
 
-    section.close()?;
+    for n in 0..args.samples {
+        if let Some(section) = &mut section {
+            if n % step == 0 {
+                section.append(".")?;
+                section.flush()?;
+                added += 1;
+            }
+        }
 
-    collected.sort_unstable();
+        let start = Instant::now();
 
-    let len = collected.len() as f64;
-    let average = collected.iter().copied().sum::<i128>() as f64 / len;
+        for _ in 0..iterations {
+            let value = f.call::<Value>(()).into_result()?;
+            drop(hint::black_box(value));
+        }
 
-    let variance = collected
-        .iter()
-        .copied()
-        .map(|n| (n as f64 - average).powf(2.0))
-        .sum::<f64>()
-        / len;
+        let duration = Instant::now().duration_since(start);
+        collected.try_push(duration.as_nanos() as f64 / iterations as f64)?;
+    }
 
-    let stddev = variance.sqrt();
+    if let Some(section) = &mut section {
+        for _ in added..10 {
+            section.append(".")?;
+            section.flush()?;
+        }
 
-    let format = Format {
-        average: average as u128,
-        stddev: stddev as u128,
-        iterations,
+        section.close()?;
+    }
+
+    let Some(stats) = Stats::from_samples(&mut collected) else {
+        return Ok(None);
     };
 
-    let mut section = io.section("Result", Stream::Stdout, Color::Highlight)?;
-    section.append(format_args!(" {item}: {format}"))?.close()?;
-    Ok(())
-}
+    if !quiet {
+        let mut section = io.section("Result", Stream::Stdout, Color::Highlight)?;
+        section.append(format_args!(" {item}: {stats}"))?.close()?;
+    }
 
-struct Format {
-    average: u128,
-    stddev: u128,
-    iterations: usize,
+    Ok(Some(stats))
 }
 
-impl fmt::Display for Format {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "mean={:.2}, stddev={:.2}, iterations={}",
-            Time(self.average),
-            Time(self.stddev),
-            self.iterations
-        )
+/// Calculate the number of iterations to run per sample, based on the time it
+/// took to run the given number of warmup iterations.
+pub(super) fn iterations(warmup: Duration, count: usize) -> u32 {
+    if count == 0 {
+        return 1;
+    }
+
+    let per_iteration = warmup.as_nanos() / count as u128;
+
+    if per_iteration == 0 {
+        return u32::try_from(SAMPLE_TARGET.as_nanos()).unwrap_or(u32::MAX);
     }
+
+    let iterations = SAMPLE_TARGET.as_nanos().div_ceil(per_iteration);
+    u32::try_from(iterations).unwrap_or(u32::MAX).max(1)
 }
 
-struct Time(u128);
+struct Time(f64);
 
 impl fmt::Display for Time {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if self.0 >= 1_000_000_000 {
-            write!(f, "{:.3}s", self.0 as f64 / 1_000_000_000.0)
-        } else if self.0 >= 1_000_000 {
-            write!(f, "{:.3}ms", self.0 as f64 / 1_000_000.0)
-        } else if self.0 >= 1_000 {
-            write!(f, "{:.3}µs", self.0 as f64 / 1_000.0)
+        if self.0 >= 1_000_000_000.0 {
+            write!(f, "{:.3}s", self.0 / 1_000_000_000.0)
+        } else if self.0 >= 1_000_000.0 {
+            write!(f, "{:.3}ms", self.0 / 1_000_000.0)
+        } else if self.0 >= 1_000.0 {
+            write!(f, "{:.3}µs", self.0 / 1_000.0)
         } else {
-            write!(f, "{}ns", self.0)
+            write!(f, "{:.0}ns", self.0)
         }
     }
 }
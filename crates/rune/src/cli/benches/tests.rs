@@ -0,0 +1,113 @@
+use std::string::String;
+use std::time::Duration;
+
+use super::{compare, iterations, Record, Regression, Stats};
+
+fn stats(median: f64) -> Stats {
+    Stats {
+        samples: 1,
+        mean: median,
+        median,
+        stddev: 0.0,
+        min: median,
+        max: median,
+    }
+}
+
+fn record(benches: &[(&str, f64)]) -> Record {
+    let mut record = Record::default();
+
+    for &(name, median) in benches {
+        record.benches.insert(String::from(name), stats(median));
+    }
+
+    record
+}
+
+#[test]
+fn stats_empty() {
+    assert_eq!(Stats::from_samples(&mut []), None);
+}
+
+#[test]
+fn stats_odd() {
+    let mut samples = [5.0, 1.0, 3.0, 2.0, 4.0];
+    let stats = Stats::from_samples(&mut samples).unwrap();
+
+    assert_eq!(stats.samples, 5);
+    assert_eq!(stats.mean, 3.0);
+    assert_eq!(stats.median, 3.0);
+    assert_eq!(stats.min, 1.0);
+    assert_eq!(stats.max, 5.0);
+    assert_eq!(stats.stddev, 2.0f64.sqrt());
+}
+
+#[test]
+fn stats_even() {
+    let mut samples = [8.0, 2.0, 4.0, 6.0];
+    let stats = Stats::from_samples(&mut samples).unwrap();
+
+    assert_eq!(stats.samples, 4);
+    assert_eq!(stats.mean, 5.0);
+    assert_eq!(stats.median, 5.0);
+    assert_eq!(stats.min, 2.0);
+    assert_eq!(stats.max, 8.0);
+    assert_eq!(stats.stddev, 5.0f64.sqrt());
+}
+
+#[test]
+fn stats_constant() {
+    let mut samples = [7.0; 10];
+    let stats = Stats::from_samples(&mut samples).unwrap();
+
+    assert_eq!(stats.mean, 7.0);
+    assert_eq!(stats.median, 7.0);
+    assert_eq!(stats.stddev, 0.0);
+}
+
+#[test]
+fn baseline_regressions() {
+    let baseline = record(&[("a", 100.0), ("b", 100.0), ("c", 100.0), ("d", 100.0)]);
+    let current = record(&[("a", 104.0), ("b", 110.0), ("c", 50.0), ("e", 1000.0)]);
+
+    let regressions = compare(&baseline, &current, 5.0);
+
+    assert_eq!(
+        regressions,
+        [Regression {
+            name: "b",
+            before: 100.0,
+            after: 110.0,
+            change: 10.0,
+        }]
+    );
+}
+
+#[test]
+fn baseline_threshold() {
+    let baseline = record(&[("a", 100.0)]);
+    let current = record(&[("a", 104.0)]);
+
+    assert!(compare(&baseline, &current, 5.0).is_empty());
+    assert_eq!(compare(&baseline, &current, 3.0).len(), 1);
+}
+
+#[test]
+fn record_roundtrip() {
+    let record = record(&[("foo::bar", 42.0), ("foo::baz#1", 1.5)]);
+    let json = serde_json::to_string(&record).unwrap();
+    let decoded: Record = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded.benches, record.benches);
+}
+
+#[test]
+fn iterations_per_sample() {
+    // Without warmup there's nothing to base the number of iterations on.
+    assert_eq!(iterations(Duration::ZERO, 0), 1);
+    // Slow benchmarks run once per sample.
+    assert_eq!(iterations(Duration::from_millis(50), 10), 1);
+    // 10µs per iteration requires 100 iterations to reach 1ms.
+    assert_eq!(iterations(Duration::from_micros(100), 10), 100);
+    // 3µs per iteration rounds up.
+    assert_eq!(iterations(Duration::from_micros(30), 10), 334);
+}
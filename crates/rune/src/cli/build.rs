@@ -0,0 +1,115 @@
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::vec::Vec;
+
+use anyhow::{bail, Context, Result};
+
+use crate::cli::{
+    loader, visitor, AssetKind, CommandBase, Config, Entry, EntryPoint, ExitCode, Io, SharedFlags,
+};
+use crate::Options;
+
+mod cli {
+    use std::path::PathBuf;
+    use std::vec::Vec;
+
+    use clap::Parser;
+
+    #[derive(Parser, Debug)]
+    #[command(rename_all = "kebab-case")]
+    pub(crate) struct Flags {
+        /// The file to write the compiled unit to.
+        ///
+        /// Defaults to the path of the script with the `rnc` extension. Can
+        /// only be used when building a single script.
+        #[arg(short, long)]
+        pub(super) output: Option<PathBuf>,
+        /// Explicit paths to build.
+        pub(super) build_path: Vec<PathBuf>,
+    }
+}
+
+pub(super) use cli::Flags;
+
+impl CommandBase for Flags {
+    #[inline]
+    fn is_workspace(&self, kind: AssetKind) -> bool {
+        matches!(kind, AssetKind::Bin)
+    }
+
+    #[inline]
+    fn describe(&self) -> &str {
+        "Building"
+    }
+
+    #[inline]
+    fn paths(&self) -> &[PathBuf] {
+        &self.build_path
+    }
+}
+
+/// Compile the given scripts and write their units to disk, so that they can
+/// be executed later with `rune run`.
+pub(super) fn run<'p, I>(
+    io: &mut Io<'_>,
+    entry: &mut Entry<'_>,
+    c: &Config,
+    flags: &Flags,
+    shared: &SharedFlags,
+    options: &Options,
+    entries: I,
+) -> Result<ExitCode>
+where
+    I: IntoIterator<Item = EntryPoint<'p>>,
+{
+    let entries = entries.into_iter().collect::<Vec<_>>();
+
+    if flags.output.is_some() && entries.len() > 1 {
+        bail!("The `--output` option can only be used when building a single script");
+    }
+
+    let context = shared.context(entry, c, None)?;
+
+    for e in entries {
+        let mut options = options.clone();
+        options.bytecode(false);
+
+        if e.is_argument() {
+            options.function_body = true;
+        }
+
+        let path = e.path();
+
+        let output = match &flags.output {
+            Some(output) => output.clone(),
+            None => path.with_extension("rnc"),
+        };
+
+        let load = loader::load(
+            io,
+            &context,
+            shared,
+            &options,
+            path,
+            e.package(),
+            visitor::Attribute::None,
+        )?;
+
+        let f = fs::File::create(&output)
+            .with_context(|| format!("creating file: {}", output.display()))?;
+
+        let mut writer = BufWriter::new(f);
+        load.unit.serialize_to(&mut writer)?;
+        writer.flush()?;
+
+        writeln!(
+            io.stdout,
+            "Built: {} -> {}",
+            path.display(),
+            output.display()
+        )?;
+    }
+
+    Ok(ExitCode::Success)
+}
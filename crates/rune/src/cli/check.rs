@@ -1,11 +1,15 @@
 use std::io::Write;
-use std::path::Path;
 use std::path::PathBuf;
+use std::string::String;
 
 use anyhow::{Context, Result};
 
-use crate::cli::{visitor, AssetKind, CommandBase, Config, Entry, ExitCode, Io, SharedFlags};
+use crate::cli::{
+    visitor, AssetKind, CommandBase, Config, Entry, EntryPoint, ExitCode, Io, MessageFormat,
+    SharedFlags,
+};
 use crate::compile::FileSourceLoader;
+use crate::diagnostics::WarningDiagnosticKind;
 use crate::{Diagnostics, Options, Source, Sources};
 
 mod cli {
@@ -20,6 +24,10 @@ mod cli {
         /// Exit with a non-zero exit-code even for warnings
         #[arg(long)]
         pub(super) warnings_are_errors: bool,
+        /// Watch the sources for changes and re-run the command when they
+        /// change.
+        #[arg(long)]
+        pub(super) watch: bool,
         /// Explicit paths to check.
         pub(super) check_path: Vec<PathBuf>,
     }
@@ -27,6 +35,20 @@ mod cli {
 
 pub(super) use cli::Flags;
 
+/// Help text listing the warnings which can be denied with `-O deny=<name>`.
+pub(super) fn after_help() -> String {
+    let mut help = String::from("Warnings which can be denied with `-O deny=<name>`:\n");
+
+    for name in WarningDiagnosticKind::NAMES {
+        help.push_str("  ");
+        help.push_str(name);
+        help.push('\n');
+    }
+
+    help.push_str("\nAll warnings can be denied with `-O warnings-as-errors`.");
+    help
+}
+
 impl CommandBase for Flags {
     #[inline]
     fn is_debug(&self) -> bool {
@@ -47,6 +69,11 @@ impl CommandBase for Flags {
     fn paths(&self) -> &[PathBuf] {
         &self.check_path
     }
+
+    #[inline]
+    fn is_watch(&self) -> bool {
+        self.watch
+    }
 }
 
 pub(super) fn run(
@@ -56,9 +83,13 @@ pub(super) fn run(
     flags: &Flags,
     shared: &SharedFlags,
     options: &Options,
-    path: &Path,
+    e: &EntryPoint<'_>,
 ) -> Result<ExitCode> {
-    writeln!(io.stdout, "Checking: {}", path.display())?;
+    let path = e.path();
+
+    if c.message_format == MessageFormat::Human {
+        writeln!(io.stdout, "Checking: {}", path.display())?;
+    }
 
     let context = shared.context(entry, c, None)?;
 
@@ -67,6 +98,10 @@ pub(super) fn run(
 
     let mut sources = Sources::new();
 
+    if let Some(package) = e.package() {
+        package.prepare_dependencies(&mut sources)?;
+    }
+
     sources.insert(source)?;
 
     let mut diagnostics = if shared.warnings || flags.warnings_are_errors {
@@ -86,7 +121,8 @@ pub(super) fn run(
         .with_source_loader(&mut source_loader)
         .build();
 
-    diagnostics.emit(&mut io.stdout.lock(), &sources)?;
+    c.message_format
+        .emit_diagnostics(io.stdout, &diagnostics, &sources)?;
 
     if diagnostics.has_error() || flags.warnings_are_errors && diagnostics.has_warning() {
         Ok(ExitCode::Failure)
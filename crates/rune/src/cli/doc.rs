@@ -1,7 +1,8 @@
-use std::io::Write;
+use std::fs;
+use std::io::{self, Write};
 use std::path::PathBuf;
 
-use crate::doc::Artifacts;
+use crate::doc::{Artifacts, Externs, PackageMeta};
 
 use anyhow::{Context, Result};
 
@@ -13,9 +14,10 @@ use crate::{Diagnostics, Options, Source, Sources};
 
 mod cli {
     use std::path::PathBuf;
+    use std::string::String;
     use std::vec::Vec;
 
-    use clap::Parser;
+    use clap::{Parser, ValueEnum};
 
     #[derive(Parser, Debug)]
     #[command(rename_all = "kebab-case")]
@@ -29,12 +31,36 @@ mod cli {
         /// Open the generated documentation in a browser.
         #[arg(long)]
         pub(super) open: bool,
+        /// The format to generate documentation in.
+        #[arg(long, default_value = "html")]
+        pub(super) output_format: OutputFormat,
+        /// Link to documentation for the given crate published elsewhere
+        /// instead of documenting it, in the form `name=url`.
+        #[arg(long = "extern", value_name = "NAME=URL")]
+        pub(super) externs: Vec<String>,
+        /// Add a "Run" link to examples which opens them in the playground at
+        /// the given URL.
+        #[arg(long, value_name = "URL")]
+        pub(super) playground_url: Option<String>,
+        /// Include items which are not publicly visible in the generated
+        /// documentation.
+        #[arg(long)]
+        pub(super) document_private_items: bool,
         /// Explicit paths to format.
         pub(super) doc_path: Vec<PathBuf>,
     }
+
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+    pub(crate) enum OutputFormat {
+        /// Generate a static HTML site.
+        #[default]
+        Html,
+        /// Generate a single JSON file describing all documented items.
+        Json,
+    }
 }
 
-pub(super) use cli::Flags;
+pub(super) use cli::{Flags, OutputFormat};
 
 impl CommandBase for Flags {
     #[inline]
@@ -85,6 +111,12 @@ where
         },
     };
 
+    let mut externs = Externs::new();
+
+    for value in &flags.externs {
+        externs.parse(value)?;
+    }
+
     writeln!(io.stdout, "Building documentation: {}", root.display())?;
 
     let context = shared.context(entry, c, None)?;
@@ -103,6 +135,16 @@ where
         let item = naming.item(&e)?;
 
         let mut visitor = crate::doc::Visitor::new(&item)?;
+        visitor.document_private = flags.document_private_items;
+
+        if let Some(package) = e.package() {
+            visitor.package = Some(PackageMeta {
+                version: Some(package.version.try_to_string()?),
+                description: package.description.try_clone()?,
+                authors: package.authors.try_clone()?,
+            });
+        }
+
         let mut sources = Sources::new();
 
         let source = match Source::from_path(e.path()) {
@@ -110,6 +152,10 @@ where
             Err(error) => return Err(error).context(e.path().display().try_to_string()?),
         };
 
+        if let Some(package) = e.package() {
+            package.prepare_dependencies(&mut sources)?;
+        }
+
         sources.insert(source)?;
 
         let mut diagnostics = if shared.warnings || flags.warnings_are_errors {
@@ -137,9 +183,31 @@ where
         visitors.try_push(visitor)?;
     }
 
+    if flags.output_format == OutputFormat::Json {
+        let document = crate::doc::json::build("root", Some(&context), &visitors, &externs)?;
+
+        fs::create_dir_all(&root).context(root.display().try_to_string()?)?;
+        let path = root.join("doc.json");
+
+        let mut out = io::BufWriter::new(fs::File::create(&path)?);
+        serde_json::to_writer_pretty(&mut out, &document)?;
+        writeln!(out)?;
+        out.flush()?;
+
+        writeln!(io.stdout, "Wrote: {}", path.display())?;
+        return Ok(ExitCode::Success);
+    }
+
     let mut artifacts = Artifacts::new();
 
-    crate::doc::build("root", &mut artifacts, Some(&context), &visitors)?;
+    crate::doc::build(
+        "root",
+        &mut artifacts,
+        Some(&context),
+        &visitors,
+        &externs,
+        flags.playground_url.as_deref(),
+    )?;
 
     for asset in artifacts.assets() {
         asset.build(&root)?;
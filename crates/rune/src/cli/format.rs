@@ -1,5 +1,8 @@
+#[cfg(test)]
+mod tests;
+
 use std::fmt;
-use std::io::Write;
+use std::io::{Read, Write};
 use std::path::PathBuf;
 
 use similar::{ChangeTag, TextDiff};
@@ -27,6 +30,12 @@ mod cli {
         /// returns a non-successful exitcode.
         #[arg(long)]
         pub(super) check: bool,
+        /// Read source from stdin and write the formatted output to stdout.
+        ///
+        /// In combination with `--check`, a diff is written instead if the
+        /// source needs to be changed.
+        #[arg(long, conflicts_with = "fmt_path")]
+        pub(super) stdin: bool,
         /// Explicit paths to format.
         pub(super) fmt_path: Vec<PathBuf>,
     }
@@ -45,6 +54,11 @@ impl CommandBase for Flags {
         "Formatting"
     }
 
+    #[inline]
+    fn is_stdin(&self) -> bool {
+        self.stdin
+    }
+
     /// Extra paths to run.
     #[inline]
     fn paths(&self) -> &[PathBuf] {
@@ -64,6 +78,11 @@ pub(super) fn run<'m, I>(
 where
     I: IntoIterator<Item = EntryPoint<'m>>,
 {
+    if flags.stdin {
+        let stdin = std::io::stdin();
+        return format_stdin(&mut stdin.lock(), io.stdout, io.stderr, flags, options);
+    }
+
     let col = Colors::new();
 
     let mut changed = 0u32;
@@ -167,7 +186,7 @@ where
                 write!(io.stdout, "++ ")?;
                 io.stdout.reset()?;
                 writeln!(io.stdout, "{}", source.name())?;
-                diff(io, source.as_str(), &formatted, &col)?;
+                diff(io.stdout, source.as_str(), &formatted, &col)?;
             }
 
             if !flags.check {
@@ -223,12 +242,76 @@ where
     Ok(ExitCode::Success)
 }
 
-fn diff(io: &mut Io, source: &str, val: &str, col: &Colors) -> Result<(), anyhow::Error> {
+/// Format a single source read from `input`, writing the formatted output to
+/// `out`.
+///
+/// If the source fails to parse, diagnostics are written to `err` and nothing
+/// is written to `out`.
+fn format_stdin<I, O, E>(
+    input: &mut I,
+    out: &mut O,
+    err: &mut E,
+    flags: &Flags,
+    options: &Options,
+) -> Result<ExitCode>
+where
+    I: ?Sized + Read,
+    O: WriteColor,
+    E: WriteColor,
+{
+    let col = Colors::new();
+
+    let mut input_string = std::string::String::new();
+    input.read_to_string(&mut input_string)?;
+
+    let mut sources = Sources::new();
+    sources.insert(Source::new("<stdin>", &input_string)?)?;
+
+    let mut diagnostics = Diagnostics::new();
+
+    let result = crate::fmt::prepare(&sources)
+        .with_options(options)
+        .with_diagnostics(&mut diagnostics)
+        .format();
+
+    if !diagnostics.is_empty() {
+        diagnostics.emit(err, &sources)?;
+    }
+
+    let Ok(formatted) = result else {
+        return Ok(ExitCode::Failure);
+    };
+
+    for (id, formatted) in formatted {
+        let Some(source) = sources.get(id) else {
+            continue;
+        };
+
+        if flags.check {
+            if source.as_str() != formatted {
+                diff(out, source.as_str(), &formatted, &col)?;
+                return Ok(ExitCode::Failure);
+            }
+
+            continue;
+        }
+
+        out.write_all(formatted.as_bytes())?;
+    }
+
+    out.flush()?;
+    Ok(ExitCode::Success)
+}
+
+fn diff<O>(o: &mut O, source: &str, val: &str, col: &Colors) -> Result<(), anyhow::Error>
+where
+    O: ?Sized + WriteColor,
+{
     let diff = TextDiff::from_lines(source, val);
 
     for (idx, group) in diff.grouped_ops(3).iter().enumerate() {
         if idx > 0 {
-            println!("{:-^1$}", "-", 80);
+            writeln!(o, "{:-^1$}", "-", 80)?;
         }
 
         for op in group {
@@ -239,19 +322,19 @@ fn diff(io: &mut Io, source: &str, val: &str, col: &Colors) -> Result<(), anyhow
                     ChangeTag::Equal => (" ", &col.dim),
                 };
 
-                io.stdout.set_color(color)?;
+                o.set_color(color)?;
 
-                write!(io.stdout, "{}", Line(change.old_index()))?;
-                write!(io.stdout, "{sign}")?;
+                write!(o, "{}", Line(change.old_index()))?;
+                write!(o, "{sign}")?;
 
                 for (_, value) in change.iter_strings_lossy() {
-                    write!(io.stdout, "{value}")?;
+                    write!(o, "{value}")?;
                 }
 
-                io.stdout.reset()?;
+                o.reset()?;
 
                 if change.missing_newline() {
-                    writeln!(io.stdout)?;
+                    writeln!(o)?;
                 }
             }
         }
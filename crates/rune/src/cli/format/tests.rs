@@ -0,0 +1,81 @@
+use std::string::String;
+use std::vec::Vec;
+
+use clap::Parser;
+
+use crate::termcolor::NoColor;
+use crate::Options;
+
+use super::{format_stdin, ExitCode, Flags};
+
+/// Source with a mix of `\n` and `\r\n` line endings which also needs
+/// formatting.
+const MIXED_LINE_ENDINGS: &str =
+    "pub fn main() {\r\n    // a comment\r\n  let a = 1;\n\r\n    let b = 2;\r\n\n\n\n a + b\n}\r\n";
+
+/// The expected output of formatting [`MIXED_LINE_ENDINGS`].
+const MIXED_LINE_ENDINGS_FORMATTED: &str =
+    "pub fn main() {\n    // a comment\n    let a = 1;\n\n    let b = 2;\n\n    a + b\n}\n";
+
+/// Source which fails to parse.
+const BROKEN: &str = "pub fn main() {\r\n    let a = ;\n";
+
+struct Output {
+    code: ExitCode,
+    out: String,
+    err: String,
+}
+
+fn fmt(args: &[&str], input: &str) -> Output {
+    let flags = Flags::try_parse_from(["fmt"].iter().chain(args)).unwrap();
+    let options = Options::from_default_env().unwrap();
+
+    let mut out = NoColor::new(Vec::new());
+    let mut err = NoColor::new(Vec::new());
+
+    let code = format_stdin(&mut input.as_bytes(), &mut out, &mut err, &flags, &options).unwrap();
+
+    Output {
+        code,
+        out: String::from_utf8(out.into_inner()).unwrap(),
+        err: String::from_utf8(err.into_inner()).unwrap(),
+    }
+}
+
+#[test]
+fn stdin_mixed_line_endings() {
+    let output = fmt(&["--stdin"], MIXED_LINE_ENDINGS);
+    assert!(matches!(output.code, ExitCode::Success));
+    assert_eq!(output.out, MIXED_LINE_ENDINGS_FORMATTED);
+    assert!(!output.out.contains('\r'));
+    assert!(output.err.is_empty());
+}
+
+#[test]
+fn stdin_idempotent() {
+    let first = fmt(&["--stdin"], MIXED_LINE_ENDINGS);
+    let second = fmt(&["--stdin"], &first.out);
+    assert!(matches!(second.code, ExitCode::Success));
+    assert_eq!(first.out, second.out);
+}
+
+#[test]
+fn stdin_check() {
+    let output = fmt(&["--stdin", "--check"], MIXED_LINE_ENDINGS);
+    assert!(matches!(output.code, ExitCode::Failure));
+    assert!(output.out.contains("+    let a = 1;"), "{}", output.out);
+
+    let output = fmt(&["--stdin", "--check"], MIXED_LINE_ENDINGS_FORMATTED);
+    assert!(matches!(output.code, ExitCode::Success));
+    assert!(output.out.is_empty());
+}
+
+#[test]
+fn stdin_parse_error() {
+    for args in [&["--stdin"][..], &["--stdin", "--check"][..]] {
+        let output = fmt(args, BROKEN);
+        assert!(matches!(output.code, ExitCode::Failure));
+        assert!(output.out.is_empty());
+        assert!(output.err.contains("<stdin>"), "{}", output.err);
+    }
+}
@@ -0,0 +1,194 @@
+#[cfg(test)]
+mod tests;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use anyhow::{bail, Context as _, Result};
+
+use crate::cli::{ExitCode, Io};
+use crate::workspace::MANIFEST_FILE;
+
+use super::{Color, Stream};
+
+mod cli {
+    use std::path::PathBuf;
+
+    use clap::Parser;
+
+    #[derive(Parser, Debug)]
+    #[command(rename_all = "kebab-case")]
+    pub(crate) struct Flags {
+        /// Also create a library module at `bin/lib/mod.rn` which is used by
+        /// the entrypoint.
+        #[arg(long, conflicts_with = "workspace")]
+        pub(super) lib: bool,
+        /// Also create a test target in `tests/`.
+        #[arg(long, conflicts_with = "workspace")]
+        pub(super) test: bool,
+        /// Also create a benchmark target in `benches/`.
+        #[arg(long, conflicts_with = "workspace")]
+        pub(super) bench: bool,
+        /// Create a virtual workspace manifest listing its `members` instead of
+        /// a package.
+        #[arg(long)]
+        pub(super) workspace: bool,
+        /// Overwrite files which already exist.
+        #[arg(long)]
+        pub(super) force: bool,
+        /// The directory to create the project in, which is created if it
+        /// doesn't exist.
+        ///
+        /// Defaults to the current directory.
+        pub(super) path: Option<PathBuf>,
+    }
+}
+
+pub(super) use cli::Flags;
+
+const MAIN: &str = r#"pub fn main() {
+    println!("Hello, world!");
+}
+"#;
+
+const MAIN_LIB: &str = r#"mod lib;
+
+pub fn main() {
+    println!("{}", lib::greet("world"));
+}
+"#;
+
+const LIB: &str = r#"/// Construct a greeting for `name`.
+pub fn greet(name) {
+    `Hello, ${name}!`
+}
+"#;
+
+const TEST: &str = r#"#[test]
+fn smoke() {
+    assert_eq!(1 + 1, 2);
+}
+"#;
+
+const BENCH: &str = r#"#[bench]
+fn greeting(b) {
+    b.iter(|| `Hello, ${"world"}!`);
+}
+"#;
+
+const WORKSPACE: &str = r#"[workspace]
+members = []
+"#;
+
+pub(super) fn run(io: &mut Io<'_>, flags: &Flags) -> Result<ExitCode> {
+    let root = match &flags.path {
+        Some(path) => path.clone(),
+        None => PathBuf::from("."),
+    };
+
+    for path in scaffold(&root, flags)? {
+        io.section("Created", Stream::Stdout, Color::Passed)?
+            .append(format_args!(" {}", path.display()))?
+            .close()?;
+    }
+
+    Ok(ExitCode::Success)
+}
+
+/// Write a new project to `root`, returning the paths of the created files.
+///
+/// No files are written if any of them already exist, unless `--force` is
+/// specified.
+pub(super) fn scaffold(root: &Path, flags: &Flags) -> Result<Vec<PathBuf>> {
+    let files = if flags.workspace {
+        vec![(root.join(MANIFEST_FILE), String::from(WORKSPACE))]
+    } else {
+        let name = package_name(root)?;
+        let mut files = Vec::new();
+
+        files.push((root.join(MANIFEST_FILE), manifest(&name)));
+
+        if flags.lib {
+            files.push((root.join("bin").join("main.rn"), String::from(MAIN_LIB)));
+            files.push((
+                root.join("bin").join("lib").join("mod.rn"),
+                String::from(LIB),
+            ));
+        } else {
+            files.push((root.join("bin").join("main.rn"), String::from(MAIN)));
+        }
+
+        if flags.test {
+            files.push((root.join("tests").join("smoke.rn"), String::from(TEST)));
+        }
+
+        if flags.bench {
+            files.push((
+                root.join("benches").join("greeting.rn"),
+                String::from(BENCH),
+            ));
+        }
+
+        files
+    };
+
+    if !flags.force {
+        for (path, _) in &files {
+            if path.exists() {
+                bail!(
+                    "Refusing to overwrite existing file `{}` (use `--force` to overwrite)",
+                    path.display()
+                );
+            }
+        }
+    }
+
+    let mut created = Vec::with_capacity(files.len());
+
+    for (path, contents) in files {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| parent.display().to_string())?;
+        }
+
+        fs::write(&path, contents).with_context(|| path.display().to_string())?;
+        created.push(path);
+    }
+
+    Ok(created)
+}
+
+/// Derive the name of a package from the directory it's created in.
+fn package_name(root: &Path) -> Result<String> {
+    let canonical;
+
+    // Paths like `.` have no name of their own, so we need to resolve them.
+    let name = match root.file_name() {
+        Some(name) => name,
+        None => {
+            canonical = root
+                .canonicalize()
+                .with_context(|| root.display().to_string())?;
+
+            canonical.file_name().unwrap_or_default()
+        }
+    };
+
+    let Some(name) = name.to_str().filter(|name| !name.is_empty()) else {
+        bail!("Could not derive a package name from `{}`", root.display());
+    };
+
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+    {
+        bail!("Package name `{name}` may only contain alphanumeric characters, `-` or `_`");
+    }
+
+    Ok(String::from(name))
+}
+
+fn manifest(name: &str) -> String {
+    std::format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\n")
+}
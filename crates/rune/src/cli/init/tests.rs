@@ -0,0 +1,201 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::string::{String, ToString};
+use std::sync::Arc;
+use std::vec::Vec;
+
+use crate::compile::FileSourceLoader;
+use crate::modules::capture_io::{self, CaptureIo};
+use crate::workspace::{self, FoundKind, Manifest, MANIFEST_FILE};
+use crate::{Context, Diagnostics, Source, Sources, Vm};
+
+use super::{scaffold, Flags};
+
+/// A temporary directory which is removed when dropped.
+struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(std::format!("rune-init-{}-{name}", process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        Self { path }
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+fn flags() -> Flags {
+    Flags {
+        lib: false,
+        test: false,
+        bench: false,
+        workspace: false,
+        force: false,
+        path: None,
+    }
+}
+
+fn load_manifest(root: &Path) -> Manifest {
+    let mut sources = Sources::new();
+    sources
+        .insert(Source::from_path(root.join(MANIFEST_FILE)).unwrap())
+        .unwrap();
+
+    let mut diagnostics = workspace::Diagnostics::new();
+
+    let manifest = workspace::prepare(&mut sources)
+        .with_diagnostics(&mut diagnostics)
+        .build()
+        .unwrap();
+
+    assert!(!diagnostics.has_errors());
+    assert!(!diagnostics.has_warnings());
+    manifest
+}
+
+/// Build the given file in the same way as `rune check`.
+fn build(context: &Context, path: &Path) -> Vm {
+    let mut sources = Sources::new();
+    sources.insert(Source::from_path(path).unwrap()).unwrap();
+
+    let mut diagnostics = Diagnostics::new();
+    let mut source_loader = FileSourceLoader::new();
+
+    let unit = crate::prepare(&mut sources)
+        .with_context(context)
+        .with_diagnostics(&mut diagnostics)
+        .with_source_loader(&mut source_loader)
+        .build();
+
+    assert!(
+        diagnostics.is_empty(),
+        "{} has diagnostics: {:?}",
+        path.display(),
+        diagnostics.diagnostics()
+    );
+
+    let runtime = Arc::new(context.runtime().unwrap());
+    Vm::new(runtime, Arc::new(unit.unwrap()))
+}
+
+#[test]
+fn package() {
+    let dir = TempDir::new("package");
+    let root = dir.path.join("hello-rune");
+
+    let mut flags = flags();
+    flags.lib = true;
+    flags.test = true;
+    flags.bench = true;
+
+    let created = scaffold(&root, &flags).unwrap();
+
+    assert_eq!(
+        created,
+        [
+            root.join("Rune.toml"),
+            root.join("bin/main.rn"),
+            root.join("bin/lib/mod.rn"),
+            root.join("tests/smoke.rn"),
+            root.join("benches/greeting.rn"),
+        ]
+    );
+
+    let manifest = load_manifest(&root);
+    let [package] = manifest.packages() else {
+        panic!("expected a single package: {:?}", manifest.packages());
+    };
+
+    assert_eq!(package.name, "hello-rune");
+    assert_eq!(package.version.to_string(), "0.1.0");
+
+    let targets = manifest
+        .targets(package)
+        .unwrap()
+        .into_iter()
+        .map(|t| (t.kind, String::from(t.name.as_str())))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        targets,
+        [
+            (FoundKind::Binary, String::from("main")),
+            (FoundKind::Test, String::from("smoke")),
+            (FoundKind::Bench, String::from("greeting")),
+        ]
+    );
+
+    let capture = CaptureIo::new();
+    let mut context = Context::with_config(false).unwrap();
+    context
+        .install(capture_io::module(&capture).unwrap())
+        .unwrap();
+
+    let mut vm = build(&context, &root.join("bin/main.rn"));
+    vm.call(["main"], ()).unwrap();
+    assert_eq!(capture.drain_utf8().unwrap(), "Hello, world!\n");
+
+    let mut vm = build(&context, &root.join("tests/smoke.rn"));
+    vm.call(["smoke"], ()).unwrap();
+
+    build(&context, &root.join("benches/greeting.rn"));
+}
+
+#[test]
+fn refuse_overwrite() {
+    let dir = TempDir::new("overwrite");
+    let root = dir.path.join("project");
+
+    fs::create_dir_all(root.join("bin")).unwrap();
+    fs::write(root.join("bin/main.rn"), "existing").unwrap();
+
+    let error = scaffold(&root, &flags()).unwrap_err();
+    assert!(error.to_string().contains("--force"), "{error}");
+
+    // Nothing is written if any file would be overwritten.
+    assert!(!root.join(MANIFEST_FILE).exists());
+    assert_eq!(
+        fs::read_to_string(root.join("bin/main.rn")).unwrap(),
+        "existing"
+    );
+
+    let mut flags = flags();
+    flags.force = true;
+    scaffold(&root, &flags).unwrap();
+
+    assert_ne!(
+        fs::read_to_string(root.join("bin/main.rn")).unwrap(),
+        "existing"
+    );
+    assert_eq!(load_manifest(&root).packages()[0].name, "project");
+}
+
+#[test]
+fn workspace() {
+    let dir = TempDir::new("workspace");
+
+    let mut flags = flags();
+    flags.workspace = true;
+
+    let created = scaffold(&dir.path, &flags).unwrap();
+    assert_eq!(created, [dir.path.join(MANIFEST_FILE)]);
+
+    let manifest = load_manifest(&dir.path);
+    assert!(manifest.packages().is_empty());
+}
+
+#[test]
+fn invalid_name() {
+    let dir = TempDir::new("invalid");
+    let error = scaffold(&dir.path.join("not valid"), &flags()).unwrap_err();
+    assert!(error.to_string().contains("not valid"), "{error}");
+    assert!(!dir.path.join("not valid").exists());
+}
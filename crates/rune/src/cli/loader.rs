@@ -9,6 +9,7 @@ use anyhow::{anyhow, Context as _, Result};
 use crate::alloc::{Vec, VecDeque};
 use crate::cli::{visitor, Io, SharedFlags};
 use crate::compile::FileSourceLoader;
+use crate::workspace::Package;
 use crate::{Context, Diagnostics, Hash, ItemBuf, Options, Source, Sources, Unit};
 
 pub(super) struct Load {
@@ -24,23 +25,38 @@ pub(super) fn load(
     shared: &SharedFlags,
     options: &Options,
     path: &Path,
+    package: Option<&Package>,
     attribute: visitor::Attribute,
 ) -> Result<Load> {
+    // A unit which has been compiled ahead of time with `rune build`.
+    if path.extension() == Some(OsStr::new("rnc")) {
+        let unit = load_unit(context, path)?;
+
+        return Ok(Load {
+            unit: Arc::new(unit),
+            sources: Sources::new(),
+            functions: Vec::new(),
+        });
+    }
+
     let bytecode_path = path.with_extension("rnc");
 
     let source =
         Source::from_path(path).with_context(|| anyhow!("cannot read file: {}", path.display()))?;
 
     let mut sources = Sources::new();
+
+    if let Some(package) = package {
+        package.prepare_dependencies(&mut sources)?;
+    }
+
     sources.insert(source)?;
 
     let use_cache = options.bytecode && should_cache_be_used(path, &bytecode_path)?;
 
     // TODO: how do we deal with tests discovery for bytecode loading
     let maybe_unit = if use_cache {
-        let f = fs::File::open(&bytecode_path)?;
-
-        match bincode::deserialize_from::<_, Unit>(f) {
+        match load_unit(context, &bytecode_path) {
             Ok(unit) => {
                 tracing::trace!("Using cache: {}", bytecode_path.display());
                 Some(Arc::new(unit))
@@ -80,13 +96,15 @@ pub(super) fn load(
                 .with_source_loader(&mut source_loader)
                 .build();
 
-            diagnostics.emit(io.stdout, &sources)?;
+            shared
+                .message_format
+                .emit_diagnostics(io.stdout, &diagnostics, &sources)?;
             let unit = result?;
 
             if options.bytecode {
                 tracing::trace!("serializing cache: {}", bytecode_path.display());
                 let f = fs::File::create(&bytecode_path)?;
-                bincode::serialize_into(f, &unit)?;
+                unit.serialize_to(io::BufWriter::new(f))?;
             }
 
             (Arc::new(unit), functions.into_functions())
@@ -100,6 +118,18 @@ pub(super) fn load(
     })
 }
 
+/// Load a unit which was serialized with [`Unit::serialize_to`].
+fn load_unit(context: &Context, path: &Path) -> Result<Unit> {
+    let f =
+        fs::File::open(path).with_context(|| anyhow!("cannot open file: {}", path.display()))?;
+    let runtime = context.runtime()?;
+
+    let unit = Unit::deserialize_from(io::BufReader::new(f), &runtime)
+        .with_context(|| anyhow!("cannot load unit: {}", path.display()))?;
+
+    Ok(unit)
+}
+
 /// Test if path `a` is newer than path `b`.
 fn should_cache_be_used(source: &Path, cached: &Path) -> io::Result<bool> {
     let source = fs::metadata(source)?;
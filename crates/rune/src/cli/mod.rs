@@ -8,20 +8,25 @@
 
 mod ace;
 mod benches;
+mod build;
 mod check;
 mod doc;
 mod format;
+mod init;
 mod languageserver;
 mod loader;
 mod naming;
 mod out;
+mod repl;
 mod run;
 mod tests;
 mod visitor;
+mod watch;
 
 use std::fmt;
 use std::io::{self, IsTerminal, Write};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use rust_alloc::string::String;
 use rust_alloc::vec::Vec;
@@ -29,7 +34,7 @@ use rust_alloc::vec::Vec;
 use crate as rune;
 use crate::alloc;
 use crate::alloc::prelude::*;
-use crate::workspace::{self, WorkspaceFilter};
+use crate::workspace::{self, FoundKind, FoundPackage, WorkspaceFilter};
 
 use anyhow::{bail, Context as _, Error, Result};
 use clap::{Parser, Subcommand, ValueEnum};
@@ -259,6 +264,14 @@ impl EntryPoint<'_> {
             EntryPoint::Package(..) => false,
         }
     }
+
+    /// The package the entrypoint belongs to, if any.
+    pub(crate) fn package(&self) -> Option<&workspace::Package> {
+        match self {
+            EntryPoint::Path(..) => None,
+            EntryPoint::Package(p) => Some(p.package),
+        }
+    }
 }
 
 impl fmt::Display for EntryPoint<'_> {
@@ -310,6 +323,10 @@ where
             options.bytecode(false);
         }
 
+        if self.shared.warnings {
+            options.closure_captures(true);
+        }
+
         for option in &self.shared.compiler_option {
             options.parse_option(option)?;
         }
@@ -401,6 +418,19 @@ trait CommandBase {
         "Running"
     }
 
+    /// Test if the command reads its input from stdin, in which case no
+    /// entrypoints are collected.
+    #[inline]
+    fn is_stdin(&self) -> bool {
+        false
+    }
+
+    /// Test if the command should be re-run when its sources change.
+    #[inline]
+    fn is_watch(&self) -> bool {
+        false
+    }
+
     /// Propagate related flags from command and config.
     #[inline]
     fn propagate(&mut self, _: &mut Config, _: &mut SharedFlags) {}
@@ -415,7 +445,10 @@ trait CommandBase {
 #[derive(Subcommand, Debug)]
 enum Command {
     /// Run checks but do not execute
+    #[command(after_help = check::after_help())]
     Check(CommandShared<check::Flags>),
+    /// Compile the designated script into a unit which can be run later
+    Build(CommandShared<build::Flags>),
     /// Build documentation.
     Doc(CommandShared<doc::Flags>),
     /// Build ace autocompletion.
@@ -428,6 +461,10 @@ enum Command {
     Run(CommandShared<run::Flags>),
     /// Format the provided file
     Fmt(CommandShared<format::Flags>),
+    /// Start an interactive read-eval-print loop
+    Repl(CommandShared<repl::Flags>),
+    /// Create a new project or workspace
+    Init(init::Flags),
     /// Run a language server.
     LanguageServer(SharedFlags),
     /// Helper command to generate type hashes.
@@ -435,14 +472,17 @@ enum Command {
 }
 
 impl Command {
-    const ALL: [&'static str; 9] = [
+    const ALL: [&'static str; 12] = [
         "check",
+        "build",
         "doc",
         "ace",
         "test",
         "bench",
         "run",
         "fmt",
+        "repl",
+        "init",
         "languageserver",
         "hash",
     ];
@@ -450,12 +490,15 @@ impl Command {
     fn as_command_base_mut(&mut self) -> Option<(&mut SharedFlags, &mut dyn CommandBase)> {
         let (shared, command): (_, &mut dyn CommandBase) = match self {
             Command::Check(shared) => (&mut shared.shared, &mut shared.command),
+            Command::Build(shared) => (&mut shared.shared, &mut shared.command),
             Command::Doc(shared) => (&mut shared.shared, &mut shared.command),
             Command::Ace(shared) => (&mut shared.shared, &mut shared.command),
             Command::Test(shared) => (&mut shared.shared, &mut shared.command),
             Command::Bench(shared) => (&mut shared.shared, &mut shared.command),
             Command::Run(shared) => (&mut shared.shared, &mut shared.command),
             Command::Fmt(shared) => (&mut shared.shared, &mut shared.command),
+            Command::Repl(shared) => (&mut shared.shared, &mut shared.command),
+            Command::Init(..) => return None,
             Command::LanguageServer(..) => return None,
             Command::Hash(..) => return None,
         };
@@ -466,12 +509,15 @@ impl Command {
     fn as_command_shared_ref(&self) -> Option<CommandSharedRef<'_>> {
         let (shared, command): (_, &dyn CommandBase) = match self {
             Command::Check(shared) => (&shared.shared, &shared.command),
+            Command::Build(shared) => (&shared.shared, &shared.command),
             Command::Doc(shared) => (&shared.shared, &shared.command),
             Command::Ace(shared) => (&shared.shared, &shared.command),
             Command::Test(shared) => (&shared.shared, &shared.command),
             Command::Bench(shared) => (&shared.shared, &shared.command),
             Command::Run(shared) => (&shared.shared, &shared.command),
             Command::Fmt(shared) => (&shared.shared, &shared.command),
+            Command::Repl(shared) => (&shared.shared, &shared.command),
+            Command::Init(..) => return None,
             Command::LanguageServer(..) => return None,
             Command::Hash(..) => return None,
         };
@@ -499,6 +545,12 @@ struct Config {
     all_targets: bool,
     /// Manifest root directory.
     manifest_root: Option<PathBuf>,
+    /// Formatting options from the `[fmt]` section of the manifest.
+    fmt_options: alloc::Vec<alloc::String>,
+    /// The format to use for emitted diagnostics.
+    message_format: MessageFormat,
+    /// Signal used to interrupt running scripts in watch mode.
+    watch: Option<Arc<watch::Signal>>,
 }
 
 #[derive(Default)]
@@ -526,35 +578,36 @@ impl Inputs {
             }
         }
 
-        if let Some(filter) = cmd.find_bins(c.all_targets) {
-            c.filtered |= !matches!(filter, WorkspaceFilter::All);
+        let filters = [
+            (FoundKind::Binary, cmd.find_bins(c.all_targets)),
+            (FoundKind::Test, cmd.find_tests(c.all_targets)),
+            (FoundKind::Example, cmd.find_examples(c.all_targets)),
+            (FoundKind::Bench, cmd.find_benches(c.all_targets)),
+        ];
 
-            for p in self.manifest.find_bins(filter)? {
-                build_paths.try_push(BuildPath::Package(p))?;
-            }
+        if filters.iter().all(|(_, filter)| filter.is_none()) {
+            return Ok(build_paths);
         }
 
-        if let Some(filter) = cmd.find_tests(c.all_targets) {
-            c.filtered |= !matches!(filter, WorkspaceFilter::All);
+        let mut targets = alloc::Vec::new();
 
-            for p in self.manifest.find_tests(filter)? {
-                build_paths.try_push(BuildPath::Package(p))?;
+        for package in self.manifest.packages() {
+            for found in self.manifest.targets(package)? {
+                targets.try_push(FoundPackage { found, package })?;
             }
         }
 
-        if let Some(filter) = cmd.find_examples(c.all_targets) {
-            c.filtered |= !matches!(filter, WorkspaceFilter::All);
-
-            for p in self.manifest.find_examples(filter)? {
-                build_paths.try_push(BuildPath::Package(p))?;
-            }
-        }
+        for (kind, filter) in filters {
+            let Some(filter) = filter else {
+                continue;
+            };
 
-        if let Some(filter) = cmd.find_benches(c.all_targets) {
             c.filtered |= !matches!(filter, WorkspaceFilter::All);
 
-            for p in self.manifest.find_benches(filter)? {
-                build_paths.try_push(BuildPath::Package(p))?;
+            for p in &targets {
+                if p.found.kind == kind && filter.matches(&p.found.name) {
+                    build_paths.try_push(BuildPath::Package(p.try_clone()?))?;
+                }
             }
         }
 
@@ -602,6 +655,47 @@ enum ColorArgument {
     Never,
 }
 
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum MessageFormat {
+    #[default]
+    /// Emit human-readable diagnostics.
+    Human,
+    /// Emit diagnostics as JSON, one object per line.
+    Json,
+}
+
+impl MessageFormat {
+    /// Emit build diagnostics in this format.
+    fn emit_diagnostics(
+        self,
+        out: &mut StandardStream,
+        diagnostics: &crate::Diagnostics,
+        sources: &crate::Sources,
+    ) -> Result<()> {
+        match self {
+            MessageFormat::Human => diagnostics.emit(out, sources)?,
+            MessageFormat::Json => diagnostics.emit_json(out, sources)?,
+        }
+
+        Ok(())
+    }
+
+    /// Emit a virtual machine error in this format.
+    fn emit_vm_error(
+        self,
+        out: &mut StandardStream,
+        error: &crate::runtime::VmError,
+        sources: &crate::Sources,
+    ) -> Result<()> {
+        match self {
+            MessageFormat::Human => error.emit(out, sources)?,
+            MessageFormat::Json => error.emit_json(out, sources)?,
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Parser, Debug)]
 #[command(name = "rune", about = None)]
 struct Args {
@@ -633,6 +727,10 @@ struct SharedFlags {
     #[arg(long)]
     verbose: bool,
 
+    /// The format to use for diagnostics emitted by `check`, `test` and `run`.
+    #[arg(long, default_value = "human")]
+    message_format: MessageFormat,
+
     /// Collect sources to operate over from the workspace.
     ///
     /// This is what happens by default, but is disabled in case any `<paths>`
@@ -753,6 +851,7 @@ fn populate_config(
     cmd: CommandSharedRef<'_>,
 ) -> Result<()> {
     c.all_targets = cmd.shared.all_targets;
+    c.message_format = cmd.shared.message_format;
 
     inputs
         .found_paths
@@ -800,9 +899,54 @@ fn populate_config(
 
     diagnostics.emit(io.stdout, &sources)?;
     inputs.manifest = result?;
+    c.fmt_options = inputs.manifest.fmt.try_clone()?;
     Ok(())
 }
 
+/// Collect the entrypoints to build from the loaded inputs.
+fn collect_entries<'m>(
+    io: &mut Io<'_>,
+    c: &mut Config,
+    inputs: &'m Inputs,
+    cmd: CommandSharedRef<'_>,
+) -> Result<alloc::Vec<EntryPoint<'m>>> {
+    let mut entries = alloc::Vec::new();
+
+    let build_paths = inputs.build_paths(cmd, c)?;
+
+    let what = cmd.command.describe();
+    let verbose = c.verbose;
+    let recursive = cmd.shared.recursive;
+
+    for build_path in build_paths {
+        match build_path {
+            BuildPath::Path(path, explicit) => {
+                for path in loader::recurse_paths(recursive, path.try_to_owned()?) {
+                    entries.try_push(EntryPoint::Path(path?, explicit))?;
+                }
+            }
+            BuildPath::Package(p) => {
+                if verbose {
+                    let mut section = io.section(what, Stream::Stderr, Color::Highlight)?;
+
+                    section.append(format_args!(
+                        " {} `{}` (from {})",
+                        p.found.kind,
+                        p.found.path.display(),
+                        p.package.name
+                    ))?;
+
+                    section.close()?;
+                }
+
+                entries.try_push(EntryPoint::Package(p))?;
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
 async fn main_with_out(io: &mut Io<'_>, entry: &mut Entry<'_>, mut args: Args) -> Result<ExitCode> {
     let mut c = Config::default();
     let mut inputs = Inputs::default();
@@ -811,7 +955,7 @@ async fn main_with_out(io: &mut Io<'_>, entry: &mut Entry<'_>, mut args: Args) -
         base.propagate(&mut c, shared);
     }
 
-    let Some(cmd) = &args.cmd else {
+    let Some(command) = &args.cmd else {
         let commands: alloc::String = Command::ALL.into_iter().try_join(", ")?;
         writeln!(io.stdout, "Expected a subcommand: {commands}")?;
         return Ok(ExitCode::Failure);
@@ -819,7 +963,7 @@ async fn main_with_out(io: &mut Io<'_>, entry: &mut Entry<'_>, mut args: Args) -
 
     let mut entries = alloc::Vec::new();
 
-    if let Some(cmd) = cmd.as_command_shared_ref() {
+    if let Some(cmd) = command.as_command_shared_ref() {
         if cmd.shared.list_options {
             writeln!(
                 io.stdout,
@@ -857,42 +1001,20 @@ async fn main_with_out(io: &mut Io<'_>, entry: &mut Entry<'_>, mut args: Args) -
             return Ok(ExitCode::Success);
         }
 
-        populate_config(io, &mut c, &mut inputs, cmd)?;
-
-        let build_paths = inputs.build_paths(cmd, &mut c)?;
-
-        let what = cmd.command.describe();
-        let verbose = c.verbose;
-        let recursive = cmd.shared.recursive;
-
-        for build_path in build_paths {
-            match build_path {
-                BuildPath::Path(path, explicit) => {
-                    for path in loader::recurse_paths(recursive, path.try_to_owned()?) {
-                        entries.try_push(EntryPoint::Path(path?, explicit))?;
-                    }
-                }
-                BuildPath::Package(p) => {
-                    if verbose {
-                        let mut section = io.section(what, Stream::Stderr, Color::Highlight)?;
-
-                        section.append(format_args!(
-                            " {} `{}` (from {})",
-                            p.found.kind,
-                            p.found.path.display(),
-                            p.package.name
-                        ))?;
-
-                        section.close()?;
-                    }
+        // Commands which read from stdin don't operate over any paths.
+        if !cmd.command.is_stdin() {
+            populate_config(io, &mut c, &mut inputs, cmd)?;
+        }
 
-                    entries.try_push(EntryPoint::Package(p))?;
-                }
-            }
+        if cmd.command.is_watch() {
+            c.watch = Some(Arc::new(watch::Signal::default()));
+            return watch::run(io, &mut c, command, entry, inputs).await;
         }
+
+        entries = collect_entries(io, &mut c, &inputs, cmd)?;
     }
 
-    match run_path(io, &c, cmd, entry, entries).await? {
+    match run_path(io, &c, command, entry, entries).await? {
         ExitCode::Success => (),
         other => {
             return Ok(other);
@@ -924,12 +1046,16 @@ where
                     options.function_body = true;
                 }
 
-                match check::run(io, entry, c, &f.command, &f.shared, &options, e.path())? {
+                match check::run(io, entry, c, &f.command, &f.shared, &options, &e)? {
                     ExitCode::Success => (),
                     other => return Ok(other),
                 }
             }
         }
+        Command::Build(f) => {
+            let options = f.options()?;
+            return build::run(io, entry, c, &f.command, &f.shared, &options, entries);
+        }
         Command::Doc(f) => {
             let options = f.options()?;
             return doc::run(io, entry, c, &f.command, &f.shared, &options, entries);
@@ -939,9 +1065,25 @@ where
             return ace::run(io, entry, c, &f.command, &f.shared, &options, entries);
         }
         Command::Fmt(f) => {
-            let options = f.options()?;
+            let mut options = f.options()?;
+
+            // Options from the manifest are overridden by explicitly provided
+            // compiler options.
+            for option in &c.fmt_options {
+                options.parse_option(option)?;
+            }
+
+            for option in &f.shared.compiler_option {
+                options.parse_option(option)?;
+            }
+
             return format::run(io, entry, c, entries, &f.command, &f.shared, &options);
         }
+        Command::Repl(f) => {
+            let options = f.options()?;
+            let context = f.shared.context(entry, c, None)?;
+            return repl::run(io, &context, &f.command, &f.shared, &options).await;
+        }
         Command::Test(f) => {
             let options = f.options()?;
 
@@ -952,6 +1094,7 @@ where
         }
         Command::Bench(f) => {
             let options = f.options()?;
+            let mut record = benches::Record::default();
 
             for e in entries {
                 let mut options = options.clone();
@@ -969,6 +1112,7 @@ where
                     &f.shared,
                     &options,
                     e.path(),
+                    e.package(),
                     visitor::Attribute::Bench,
                 )?;
 
@@ -980,6 +1124,7 @@ where
                     load.unit,
                     &load.sources,
                     &load.functions,
+                    &mut record,
                 )
                 .await?
                 {
@@ -987,6 +1132,11 @@ where
                     other => return Ok(other),
                 }
             }
+
+            match benches::finish(io, &f.command, &record)? {
+                ExitCode::Success => (),
+                other => return Ok(other),
+            }
         }
         Command::Run(f) => {
             let options = f.options()?;
@@ -1005,6 +1155,7 @@ where
                     &f.shared,
                     &options,
                     e.path(),
+                    e.package(),
                     visitor::Attribute::None,
                 )?;
 
@@ -1021,6 +1172,9 @@ where
                 }
             }
         }
+        Command::Init(flags) => {
+            return init::run(io, flags);
+        }
         Command::LanguageServer(shared) => {
             let context = shared.context(entry, c, None)?;
             languageserver::run(context).await?;
@@ -0,0 +1,697 @@
+#[cfg(test)]
+mod tests;
+
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{BufRead, IsTerminal, StdinLock, Write};
+use std::path::{Path, PathBuf};
+use std::string::{String, ToString};
+use std::sync::Arc;
+use std::vec::Vec;
+
+use anyhow::{Context as _, Result};
+use rustyline::error::ReadlineError;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::{ValidationContext, ValidationResult, Validator};
+use rustyline::Editor;
+
+use crate::alloc;
+use crate::ast::{self, Spanned};
+use crate::cli::{AssetKind, CommandBase, ExitCode, Io, SharedFlags};
+use crate::runtime::{Formatter, Future, Inline, Object, Repr, RuntimeContext, Unit, VmError};
+use crate::termcolor::WriteColor;
+use crate::{Context, Diagnostics, Options, Source, SourceId, Sources, Value, Vm};
+
+mod cli {
+    use std::path::PathBuf;
+
+    use clap::Parser;
+
+    #[derive(Parser, Debug)]
+    #[command(rename_all = "kebab-case")]
+    pub(crate) struct Flags {
+        /// Path to the file used to store input history.
+        ///
+        /// Defaults to `.rune_history` in the home directory.
+        #[arg(long)]
+        pub(super) history: Option<PathBuf>,
+        /// Do not load or store input history.
+        #[arg(long, conflicts_with = "history")]
+        pub(super) no_history: bool,
+    }
+}
+
+pub(super) use cli::Flags;
+
+impl CommandBase for Flags {
+    #[inline]
+    fn is_workspace(&self, _: AssetKind) -> bool {
+        false
+    }
+
+    #[inline]
+    fn describe(&self) -> &str {
+        "Evaluating"
+    }
+
+    #[inline]
+    fn is_stdin(&self) -> bool {
+        true
+    }
+}
+
+/// The name of the history file stored in the home directory.
+const HISTORY_FILE: &str = ".rune_history";
+
+/// The maximum number of history entries to keep.
+const HISTORY_LIMIT: usize = 1000;
+
+const HELP: &str = "\
+Enter expressions, statements or items to evaluate them.
+
+Bindings introduced with `let` and items such as functions persist across
+inputs. Input continues on the next line until all braces are balanced, and
+previous inputs can be recalled with the up and down arrow keys.
+
+Commands:
+  :help          Show this help.
+  :type <expr>   Evaluate an expression and print its type.
+  :load <path>   Evaluate the contents of a file.
+  :history       Print input history.
+  :quit          Exit the repl (or press Ctrl-D).";
+
+pub(super) async fn run(
+    io: &mut Io<'_>,
+    context: &Context,
+    flags: &Flags,
+    shared: &SharedFlags,
+    options: &Options,
+) -> Result<ExitCode> {
+    let mut history = History::new(history_path(flags));
+    history.load()?;
+
+    let mut reader = Reader::new()?;
+
+    for entry in &history.entries {
+        reader.add_history(entry)?;
+    }
+
+    let mut repl = Repl::new(context, options, shared.warnings)?;
+
+    writeln!(
+        io.stdout,
+        "Type `:help` for help, `:quit` or Ctrl-D to exit."
+    )?;
+
+    while let Some(input) = reader.read(io.stdout)? {
+        let input = input.trim();
+
+        if input.is_empty() {
+            continue;
+        }
+
+        history.push(input);
+        reader.add_history(input)?;
+
+        let (command, rest) = match input.strip_prefix(':') {
+            Some(command) => match command.split_once(char::is_whitespace) {
+                Some((command, rest)) => (Some(command), rest.trim()),
+                None => (Some(command), ""),
+            },
+            None => (None, input),
+        };
+
+        match command {
+            None => {
+                if let Some(value) = repl.eval(io.stdout, rest, true).await? {
+                    if !matches!(value.as_ref(), Repr::Inline(Inline::Unit)) {
+                        writeln!(io.stdout, "{}", repl.debug(&value)?)?;
+                    }
+                }
+            }
+            Some("help" | "h") => {
+                writeln!(io.stdout, "{HELP}")?;
+            }
+            Some("type" | "t") => {
+                if let Some(value) = repl.eval(io.stdout, rest, false).await? {
+                    writeln!(io.stdout, "{}", value.type_info())?;
+                }
+            }
+            Some("load" | "l") => {
+                let source = match fs::read_to_string(rest) {
+                    Ok(source) => source,
+                    Err(error) => {
+                        writeln!(io.stdout, "Failed to read `{rest}`: {error}")?;
+                        continue;
+                    }
+                };
+
+                if let Some(value) = repl.eval(io.stdout, &source, true).await? {
+                    if !matches!(value.as_ref(), Repr::Inline(Inline::Unit)) {
+                        writeln!(io.stdout, "{}", repl.debug(&value)?)?;
+                    }
+                }
+            }
+            Some("history") => {
+                for (n, entry) in history.entries.iter().enumerate() {
+                    writeln!(io.stdout, "{n:>4}  {entry}")?;
+                }
+            }
+            Some("quit" | "q" | "exit") => {
+                break;
+            }
+            Some(command) => {
+                writeln!(
+                    io.stdout,
+                    "Unknown command `:{command}`, see `:help` for available commands"
+                )?;
+            }
+        }
+    }
+
+    history.save()?;
+    Ok(ExitCode::Success)
+}
+
+/// Reads inputs either through a line editor if stdin is a terminal, or
+/// directly from stdin otherwise.
+enum Reader {
+    Editor(Editor<InputHelper, DefaultHistory>),
+    Stdin(StdinLock<'static>),
+}
+
+impl Reader {
+    fn new() -> Result<Self> {
+        let stdin = std::io::stdin();
+
+        if !stdin.is_terminal() {
+            return Ok(Self::Stdin(stdin.lock()));
+        }
+
+        let config = rustyline::Config::builder()
+            .auto_add_history(false)
+            .max_history_size(HISTORY_LIMIT)?
+            .build();
+
+        let mut editor = Editor::with_config(config)?;
+        editor.set_helper(Some(InputHelper));
+        Ok(Self::Editor(editor))
+    }
+
+    /// Read a single input.
+    ///
+    /// Returns `None` once the input is closed.
+    fn read<O>(&mut self, out: &mut O) -> Result<Option<String>>
+    where
+        O: ?Sized + Write,
+    {
+        let editor = match self {
+            Self::Editor(editor) => editor,
+            Self::Stdin(stdin) => return read_input(stdin, out),
+        };
+
+        loop {
+            match editor.readline(">> ") {
+                Ok(input) => return Ok(Some(input)),
+                // Ctrl-C discards the current input.
+                Err(ReadlineError::Interrupted) => continue,
+                Err(ReadlineError::Eof) => return Ok(None),
+                Err(error) => return Err(error.into()),
+            }
+        }
+    }
+
+    /// Add an entry which can be recalled with the arrow keys.
+    fn add_history(&mut self, entry: &str) -> Result<()> {
+        if let Self::Editor(editor) = self {
+            editor.add_history_entry(entry)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Line editor helper which continues input on the next line until all
+/// delimiters are balanced.
+struct InputHelper;
+
+impl rustyline::Helper for InputHelper {}
+
+impl rustyline::completion::Completer for InputHelper {
+    type Candidate = String;
+}
+
+impl rustyline::hint::Hinter for InputHelper {
+    type Hint = String;
+}
+
+impl rustyline::highlight::Highlighter for InputHelper {}
+
+impl Validator for InputHelper {
+    fn validate(&self, ctx: &mut ValidationContext<'_>) -> rustyline::Result<ValidationResult> {
+        let input = ctx.input();
+
+        if input.trim_start().starts_with(':') || is_balanced(input) {
+            Ok(ValidationResult::Valid(None))
+        } else {
+            Ok(ValidationResult::Incomplete)
+        }
+    }
+}
+
+/// Read a single input, continuing on the next line until all delimiters are
+/// balanced.
+///
+/// Returns `None` once the input is closed.
+fn read_input<I, O>(input: &mut I, out: &mut O) -> Result<Option<String>>
+where
+    I: ?Sized + BufRead,
+    O: ?Sized + Write,
+{
+    let mut buf = String::new();
+
+    loop {
+        write!(out, "{}", if buf.is_empty() { ">> " } else { ".. " })?;
+        out.flush()?;
+
+        if input.read_line(&mut buf)? == 0 {
+            if buf.is_empty() {
+                writeln!(out)?;
+                return Ok(None);
+            }
+
+            return Ok(Some(buf));
+        }
+
+        if buf.trim_start().starts_with(':') || is_balanced(&buf) {
+            return Ok(Some(buf));
+        }
+    }
+}
+
+/// Test if all delimiters in the given input are balanced, ignoring any which
+/// are inside of string or character literals and comments.
+pub(super) fn is_balanced(input: &str) -> bool {
+    let mut depth = 0isize;
+    let mut it = input.chars().peekable();
+
+    while let Some(c) = it.next() {
+        match c {
+            '{' | '(' | '[' => depth += 1,
+            '}' | ')' | ']' => depth -= 1,
+            '"' => {
+                while let Some(c) = it.next() {
+                    match c {
+                        '\\' => {
+                            it.next();
+                        }
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '\'' => {
+                // Character literals, but not labels such as `'outer`.
+                let mut lookahead = it.clone();
+
+                let is_char = match lookahead.next() {
+                    Some('\\') => true,
+                    Some(_) => lookahead.next() == Some('\''),
+                    None => false,
+                };
+
+                if is_char {
+                    while let Some(c) = it.next() {
+                        match c {
+                            '\\' => {
+                                it.next();
+                            }
+                            '\'' => break,
+                            _ => {}
+                        }
+                    }
+                }
+            }
+            '/' if it.peek() == Some(&'/') => {
+                for c in it.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if it.peek() == Some(&'*') => {
+                it.next();
+                let mut last = '\0';
+
+                for c in it.by_ref() {
+                    if last == '*' && c == '/' {
+                        break;
+                    }
+
+                    last = c;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    depth <= 0
+}
+
+/// An input split into its constituent parts.
+#[derive(Default)]
+pub(super) struct Split {
+    /// Items declared in the input, which persist across inputs.
+    pub(super) items: String,
+    /// Statements making up the body of the evaluated function.
+    pub(super) body: String,
+    /// The trailing expression producing the value of the input, if any.
+    pub(super) value: Option<String>,
+    /// Names of the top-level bindings declared in the input.
+    pub(super) names: Vec<String>,
+}
+
+impl Split {
+    /// Split the given input into items, statements and a trailing value.
+    ///
+    /// If the input can't be parsed it is used as the body as-is, so that the
+    /// compiler can report any errors.
+    pub(super) fn new(input: &str) -> Self {
+        let wrapped = format!("{{{input}\n}}");
+
+        let Ok(block) = crate::parse::parse_all::<ast::Block>(&wrapped, SourceId::EMPTY, false)
+        else {
+            return Self {
+                body: input.to_string(),
+                ..Self::default()
+            };
+        };
+
+        let mut split = Self::default();
+        let mut it = block.statements.iter().peekable();
+
+        while let Some(stmt) = it.next() {
+            let Some(text) = wrapped.get(stmt.span().range()) else {
+                continue;
+            };
+
+            match stmt {
+                ast::Stmt::Item(..) => {
+                    split.items.push_str(text);
+                    split.items.push('\n');
+                }
+                ast::Stmt::Expr(..) if it.peek().is_none() => {
+                    split.value = Some(text.to_string());
+                }
+                ast::Stmt::Local(local) => {
+                    collect_names(&wrapped, &local.pat, &mut split.names);
+                    split.body.push_str(text);
+                    split.body.push('\n');
+                }
+                _ => {
+                    split.body.push_str(text);
+                    split.body.push('\n');
+                }
+            }
+        }
+
+        split
+    }
+}
+
+/// Collect the names bound by a pattern.
+fn collect_names(source: &str, pat: &ast::Pat, names: &mut Vec<String>) {
+    match pat {
+        ast::Pat::Path(pat) => {
+            let Some(ident) = pat.path.try_as_ident() else {
+                return;
+            };
+
+            if let Some(name) = source.get(ident.span().range()) {
+                names.push(name.to_string());
+            }
+        }
+        ast::Pat::Vec(pat) => {
+            for (pat, _) in pat.items.iter() {
+                collect_names(source, pat, names);
+            }
+        }
+        ast::Pat::Tuple(pat) => {
+            for (pat, _) in pat.items.iter() {
+                collect_names(source, pat, names);
+            }
+        }
+        ast::Pat::Object(pat) => {
+            for (pat, _) in pat.items.iter() {
+                collect_names(source, pat, names);
+            }
+        }
+        ast::Pat::Binding(pat) => {
+            collect_names(source, &pat.pat, names);
+        }
+        _ => {}
+    }
+}
+
+/// The state of an interactive session.
+///
+/// Every input is compiled as the body of a function which receives an object
+/// holding all bindings from previous inputs, and stores any new bindings
+/// back into it.
+pub(super) struct Repl<'a> {
+    context: &'a Context,
+    options: &'a Options,
+    warnings: bool,
+    vm: Vm,
+    /// Items declared by previous inputs.
+    items: String,
+    /// Names of bindings declared by previous inputs.
+    bindings: BTreeSet<String>,
+    /// The object holding the values of all bindings.
+    env: Value,
+}
+
+impl<'a> Repl<'a> {
+    pub(super) fn new(context: &'a Context, options: &'a Options, warnings: bool) -> Result<Self> {
+        let runtime: Arc<RuntimeContext> = Arc::new(context.runtime()?);
+        let vm = Vm::new(runtime, Arc::new(Unit::default()));
+
+        Ok(Self {
+            context,
+            options,
+            warnings,
+            vm,
+            items: String::new(),
+            bindings: BTreeSet::new(),
+            env: crate::to_value(Object::new())?,
+        })
+    }
+
+    /// Generate the source for the given input.
+    pub(super) fn source(&self, split: &Split, persist: bool) -> Result<String> {
+        let mut o = String::new();
+
+        o.push_str(&self.items);
+        o.push_str(&split.items);
+        writeln!(o, "pub async fn main(__env) {{")?;
+
+        for name in &self.bindings {
+            writeln!(o, "let {name} = __env[{name:?}];")?;
+        }
+
+        o.push_str(&split.body);
+        writeln!(
+            o,
+            "let __value = {};",
+            split.value.as_deref().unwrap_or("()")
+        )?;
+
+        if persist {
+            for name in self.bindings.iter().chain(&split.names) {
+                writeln!(o, "__env[{name:?}] = {name};")?;
+            }
+        }
+
+        writeln!(o, "__value")?;
+        writeln!(o, "}}")?;
+        Ok(o)
+    }
+
+    /// Evaluate the given input.
+    ///
+    /// Diagnostics and errors are written to `out`, in which case `None` is
+    /// returned. If `persist` is set, any items and bindings in the input are
+    /// retained for subsequent inputs.
+    pub(super) async fn eval<O>(
+        &mut self,
+        out: &mut O,
+        input: &str,
+        persist: bool,
+    ) -> Result<Option<Value>>
+    where
+        O: WriteColor,
+    {
+        let split = Split::new(input);
+        let source = self.source(&split, persist)?;
+
+        let mut sources = Sources::new();
+        sources.insert(Source::new("<repl>", &source)?)?;
+
+        let mut diagnostics = if self.warnings {
+            Diagnostics::new()
+        } else {
+            Diagnostics::without_warnings()
+        };
+
+        let result = crate::prepare(&mut sources)
+            .with_context(self.context)
+            .with_diagnostics(&mut diagnostics)
+            .with_options(self.options)
+            .build();
+
+        diagnostics.emit(out, &sources)?;
+
+        let Ok(unit) = result else {
+            return Ok(None);
+        };
+
+        *self.vm.unit_mut() = Arc::new(unit);
+
+        let result: Result<Value, VmError> = async {
+            let value = self.vm.call(["main"], (self.env.clone(),))?;
+            let future = crate::from_value::<Future>(value)?;
+            future.await.into_result()
+        }
+        .await;
+
+        let value = match result {
+            Ok(value) => value,
+            Err(error) => {
+                error.emit(out, &sources)?;
+                return Ok(None);
+            }
+        };
+
+        if persist {
+            self.items.push_str(&split.items);
+            self.bindings.extend(split.names);
+        }
+
+        Ok(Some(value))
+    }
+
+    /// Debug format a value using the `DEBUG_FMT` protocol.
+    pub(super) fn debug(&self, value: &Value) -> Result<alloc::String> {
+        let mut s = alloc::String::new();
+
+        self.vm
+            .with(|| Formatter::format_with(&mut s, |f| value.debug_fmt(f)))
+            .into_result()?;
+
+        Ok(s)
+    }
+}
+
+/// Input history which is persisted across sessions.
+struct History {
+    path: Option<PathBuf>,
+    entries: Vec<String>,
+}
+
+impl History {
+    fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            path,
+            entries: Vec::new(),
+        }
+    }
+
+    fn load(&mut self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(error) => {
+                return Err(error).with_context(|| format!("reading {}", path.display()));
+            }
+        };
+
+        for line in contents.lines() {
+            self.push(&unescape(line));
+        }
+
+        Ok(())
+    }
+
+    fn push(&mut self, entry: &str) {
+        if self.entries.last().map(String::as_str) == Some(entry) {
+            return;
+        }
+
+        self.entries.push(entry.to_string());
+
+        if self.entries.len() > HISTORY_LIMIT {
+            self.entries.remove(0);
+        }
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let mut contents = String::new();
+
+        for entry in &self.entries {
+            contents.push_str(&escape(entry));
+            contents.push('\n');
+        }
+
+        fs::write(path, contents).with_context(|| format!("writing {}", path.display()))?;
+        Ok(())
+    }
+}
+
+/// Resolve the path used to store history.
+fn history_path(flags: &Flags) -> Option<PathBuf> {
+    if flags.no_history {
+        return None;
+    }
+
+    if let Some(path) = &flags.history {
+        return Some(path.clone());
+    }
+
+    let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?;
+    Some(Path::new(&home).join(HISTORY_FILE))
+}
+
+/// Escape a multi-line history entry so that it's stored on a single line.
+fn escape(entry: &str) -> String {
+    entry.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+/// Unescape a history entry stored with [`escape`].
+fn unescape(line: &str) -> String {
+    let mut out = String::new();
+    let mut it = line.chars();
+
+    while let Some(c) = it.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+
+        match it.next() {
+            Some('n') => out.push('\n'),
+            Some(c) => out.push(c),
+            None => out.push('\\'),
+        }
+    }
+
+    out
+}
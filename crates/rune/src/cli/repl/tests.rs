@@ -0,0 +1,109 @@
+use std::string::String;
+use std::vec::Vec;
+
+use crate::termcolor::NoColor;
+use crate::{Context, Options};
+
+use super::{is_balanced, Repl, Split};
+
+#[test]
+fn balanced_input() {
+    assert!(is_balanced("1 + 2"));
+    assert!(is_balanced("fn foo() { 1 }"));
+    assert!(!is_balanced("fn foo() {"));
+    assert!(!is_balanced("let a = [1, 2,"));
+    assert!(is_balanced("let a = \"{\";"));
+    assert!(is_balanced("let a = '{';"));
+    assert!(is_balanced("let a = '\\'';"));
+    assert!(!is_balanced("'outer: loop {"));
+    assert!(is_balanced("let a = 1; // {"));
+    assert!(is_balanced("let a = /* { */ 1;"));
+}
+
+#[test]
+fn split_input() {
+    let split = Split::new("fn foo() { 1 } let a = 1; let (b, [c, _]) = (2, [3, 4]); a + b");
+    assert_eq!(split.items, "fn foo() { 1 }\n");
+    assert_eq!(split.body, "let a = 1;\nlet (b, [c, _]) = (2, [3, 4]);\n");
+    assert_eq!(split.value.as_deref(), Some("a + b"));
+    assert_eq!(split.names, ["a", "b", "c"]);
+
+    let split = Split::new("let a = 1;");
+    assert!(split.value.is_none());
+    assert_eq!(split.names, ["a"]);
+
+    // Unparseable input is left to the compiler.
+    let split = Split::new("let = ;");
+    assert_eq!(split.body, "let = ;");
+    assert!(split.names.is_empty());
+}
+
+struct Session<'a> {
+    repl: Repl<'a>,
+    out: NoColor<Vec<u8>>,
+}
+
+impl Session<'_> {
+    /// Evaluate input, returning the debug representation of its value.
+    fn eval(&mut self, input: &str) -> Option<String> {
+        let value =
+            futures_executor::block_on(self.repl.eval(&mut self.out, input, true)).unwrap()?;
+        Some(self.repl.debug(&value).unwrap().as_str().into())
+    }
+
+    /// Take output written by the session.
+    fn take(&mut self) -> String {
+        String::from_utf8(std::mem::take(self.out.get_mut())).unwrap()
+    }
+}
+
+#[test]
+fn bindings_and_items_persist() {
+    let context = Context::with_default_modules().unwrap();
+    let options = Options::from_default_env().unwrap();
+
+    let mut session = Session {
+        repl: Repl::new(&context, &options, false).unwrap(),
+        out: NoColor::new(Vec::new()),
+    };
+
+    assert_eq!(session.eval("let a = 1;").as_deref(), Some("()"));
+    assert_eq!(
+        session.eval("fn double(n) { n * 2 }").as_deref(),
+        Some("()")
+    );
+    assert_eq!(
+        session.eval("let b = double(a) + 1;").as_deref(),
+        Some("()")
+    );
+    assert_eq!(session.eval("a + b").as_deref(), Some("4"));
+    assert_eq!(session.eval("a = 10;").as_deref(), Some("()"));
+    assert_eq!(session.eval("[a, b]").as_deref(), Some("[10, 3]"));
+    assert!(session.take().is_empty());
+}
+
+#[test]
+fn diagnostics_are_reported_inline() {
+    let context = Context::with_default_modules().unwrap();
+    let options = Options::from_default_env().unwrap();
+
+    let mut session = Session {
+        repl: Repl::new(&context, &options, false).unwrap(),
+        out: NoColor::new(Vec::new()),
+    };
+
+    assert_eq!(session.eval("let a = 1;").as_deref(), Some("()"));
+
+    assert!(session.eval("a + missing").is_none());
+    assert!(session.take().contains("missing"));
+
+    // A failed input does not introduce bindings.
+    assert!(session.eval("let c = 1; c + missing").is_none());
+    assert!(session.eval("c").is_none());
+    session.take();
+
+    assert!(session.eval("a + \"x\"").is_none());
+    assert!(!session.take().is_empty());
+
+    assert_eq!(session.eval("a").as_deref(), Some("1"));
+}
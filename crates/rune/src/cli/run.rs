@@ -1,23 +1,33 @@
+#[cfg(test)]
+mod tests;
+
 use std::io::Write;
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, PoisonError};
 use std::time::Instant;
+use std::vec::Vec;
 
 use anyhow::{anyhow, Result};
 
-use crate::cli::{AssetKind, CommandBase, Config, ExitCode, Io, SharedFlags};
-use crate::runtime::{UnitStorage, VmError, VmExecution, VmResult};
+use crate::cli::{AssetKind, CommandBase, Config, ExitCode, Io, MessageFormat, SharedFlags};
+use crate::runtime::{Inst, Stack, Tracer, UnitStorage, VmError, VmExecution, VmResult};
 use crate::{Context, Hash, Sources, Unit, Value, Vm};
 
 mod cli {
     use std::path::PathBuf;
     use std::vec::Vec;
 
-    use clap::Parser;
+    use clap::{Parser, ValueEnum};
 
     #[derive(Parser, Debug)]
     #[command(rename_all = "kebab-case")]
     pub(crate) struct Flags {
+        /// How much of the backtrace to show when the script errors.
+        #[arg(long, default_value = "short")]
+        pub(super) backtrace: Backtrace,
+        /// Dump the table mapping instruction pointers to source spans.
+        #[arg(long)]
+        pub(super) dump_source_map: bool,
         /// Provide detailed tracing for each instruction executed.
         #[arg(short, long)]
         pub(super) trace: bool,
@@ -67,12 +77,41 @@ mod cli {
         /// implies `--trace`.
         #[arg(long)]
         pub(super) trace_limit: Option<usize>,
+        /// Watch the sources for changes and re-run the command when they
+        /// change.
+        #[arg(long)]
+        pub(super) watch: bool,
         /// Explicit paths to run.
         pub(super) run_path: Vec<PathBuf>,
     }
+
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+    pub(crate) enum Backtrace {
+        /// Show every frame.
+        Full,
+        /// Show the most recent frames.
+        #[default]
+        Short,
+        /// Only show where the error was raised.
+        Off,
+    }
 }
 
-pub(super) use cli::Flags;
+pub(super) use cli::{Backtrace, Flags};
+
+/// The number of frames shown with `--backtrace=short`.
+const SHORT_BACKTRACE: usize = 5;
+
+impl Backtrace {
+    /// The maximum number of frames to show.
+    fn limit(self) -> usize {
+        match self {
+            Backtrace::Full => usize::MAX,
+            Backtrace::Short => SHORT_BACKTRACE,
+            Backtrace::Off => 0,
+        }
+    }
+}
 
 impl CommandBase for Flags {
     #[inline]
@@ -117,6 +156,51 @@ impl CommandBase for Flags {
     fn paths(&self) -> &[PathBuf] {
         &self.run_path
     }
+
+    #[inline]
+    fn is_watch(&self) -> bool {
+        self.watch
+    }
+}
+
+/// Write the instruction pointer to source span table of `unit`.
+fn dump_source_map<O>(o: &mut O, unit: &Unit, sources: &Sources) -> Result<()>
+where
+    O: ?Sized + Write,
+{
+    let Some(debug_info) = unit.debug_info() else {
+        writeln!(o, "no debug info")?;
+        return Ok(());
+    };
+
+    let mut instructions = debug_info.instructions.iter().collect::<Vec<_>>();
+    instructions.sort_by_key(|&(ip, _)| *ip);
+
+    for (ip, inst) in instructions {
+        let range = inst.span.range();
+
+        if let Some(source) = sources.get(inst.source_id) {
+            let (line, col) = source.pos_to_utf8_linecol(range.start);
+
+            writeln!(
+                o,
+                "{ip:04} = {}:{}:{} ({}..{})",
+                source.name(),
+                line + 1,
+                col + 1,
+                range.start,
+                range.end
+            )?;
+        } else {
+            writeln!(
+                o,
+                "{ip:04} = {} ({}..{})",
+                inst.source_id, range.start, range.end
+            )?;
+        }
+    }
+
+    Ok(())
 }
 
 enum TraceError {
@@ -239,17 +323,34 @@ pub(super) async fn run(
         }
     }
 
+    if args.dump_source_map {
+        writeln!(io.stdout, "# source map")?;
+        dump_source_map(io.stdout, &unit, sources)?;
+    }
+
     let runtime = Arc::new(context.runtime()?);
 
     let last = Instant::now();
 
+    let sink = TraceSink::new(args.dump_stack);
+
     let mut vm = Vm::new(runtime, unit);
+
+    if args.trace {
+        vm = vm.with_tracer(sink.clone());
+    }
+
+    if let Some(signal) = &c.watch {
+        signal.install(&mut vm);
+    }
+
     let mut execution: VmExecution<_> = vm.execute(entry, ())?;
 
     let result = if args.trace {
         match do_trace(
             io,
             &mut execution,
+            &sink,
             sources,
             args.dump_stack,
             args.without_source,
@@ -278,6 +379,11 @@ pub(super) async fn run(
 
             None
         }
+        // The script was interrupted because its sources changed, so the
+        // command is about to be re-run.
+        VmResult::Err(error) if error.is_interrupted() && c.watch.is_some() => {
+            return Ok(ExitCode::Success);
+        }
         VmResult::Err(error) => {
             if c.verbose || args.time || args.dump_return {
                 let duration = Instant::now().saturating_duration_since(last);
@@ -292,7 +398,13 @@ pub(super) async fn run(
     };
 
     let exit = if let Some(error) = errored {
-        error.emit(io.stdout, sources)?;
+        match c.message_format {
+            MessageFormat::Human => {
+                error.emit_backtrace(io.stdout, sources, context, args.backtrace.limit())?
+            }
+            format => format.emit_vm_error(io.stdout, &error, sources)?,
+        }
+
         ExitCode::VmError
     } else {
         ExitCode::Success
@@ -352,10 +464,64 @@ pub(super) async fn run(
     Ok(exit)
 }
 
+/// An instruction recorded by [`TraceSink`].
+struct Traced {
+    ip: usize,
+    inst: Inst,
+    top: usize,
+    stack: Vec<std::string::String>,
+}
+
+/// A tracer which buffers executed instructions so that they can be printed
+/// by [`do_trace`].
+#[derive(Clone)]
+struct TraceSink {
+    dump_stack: bool,
+    traced: Arc<Mutex<Vec<Traced>>>,
+}
+
+impl TraceSink {
+    fn new(dump_stack: bool) -> Self {
+        Self {
+            dump_stack,
+            traced: Arc::default(),
+        }
+    }
+
+    fn drain(&self) -> Vec<Traced> {
+        let mut traced = self.traced.lock().unwrap_or_else(PoisonError::into_inner);
+        std::mem::take(&mut *traced)
+    }
+}
+
+impl Tracer for TraceSink {
+    fn trace(&self, ip: usize, inst: &Inst, stack: &Stack) {
+        let values = if self.dump_stack {
+            let values = stack.get(stack.top()..).unwrap_or_default();
+            values.iter().map(|value| format!("{value:?}")).collect()
+        } else {
+            Vec::new()
+        };
+
+        let traced = Traced {
+            ip,
+            inst: *inst,
+            top: stack.top(),
+            stack: values,
+        };
+
+        self.traced
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(traced);
+    }
+}
+
 /// Perform a detailed trace of the program.
 async fn do_trace<T>(
     io: &Io<'_>,
     execution: &mut VmExecution<T>,
+    sink: &TraceSink,
     sources: &Sources,
     dump_stack: bool,
     without_source: bool,
@@ -364,103 +530,68 @@ async fn do_trace<T>(
 where
     T: AsRef<Vm> + AsMut<Vm>,
 {
-    let mut current_frame_len = execution.vm().call_frames().len();
-    let mut result = VmResult::Ok(None);
+    let mut current_top = execution.vm().stack().top();
 
     while limit > 0 {
+        let result = execution.async_step().await;
+
         let vm = execution.vm();
-        let ip = vm.ip();
         let mut o = io.stdout.lock();
 
-        if let Some((hash, signature)) = vm.unit().debug_info().and_then(|d| d.function_at(ip)) {
-            writeln!(o, "fn {} ({}):", signature, hash)?;
-        }
+        for traced in sink.drain() {
+            let ip = traced.ip;
 
-        let debug = vm.unit().debug_info().and_then(|d| d.instruction_at(ip));
+            if let Some((hash, signature)) = vm.unit().debug_info().and_then(|d| d.function_at(ip))
+            {
+                writeln!(o, "fn {} ({}):", signature, hash)?;
+            }
 
-        for label in debug.map(|d| d.labels.as_slice()).unwrap_or_default() {
-            writeln!(o, "{}:", label)?;
-        }
+            let debug = vm.unit().debug_info().and_then(|d| d.instruction_at(ip));
 
-        if dump_stack {
-            let frames = vm.call_frames();
-            let stack = vm.stack();
+            for label in debug.map(|d| d.labels.as_slice()).unwrap_or_default() {
+                writeln!(o, "{}:", label)?;
+            }
 
-            if current_frame_len != frames.len() {
-                let op = if current_frame_len < frames.len() {
+            if dump_stack && current_top != traced.top {
+                let op = if current_top < traced.top {
                     "push"
                 } else {
                     "pop"
                 };
-                write!(o, "  {op} frame {} (+{})", frames.len(), stack.top())?;
 
-                if let Some(frame) = frames.last() {
-                    writeln!(o, " {frame:?}")?;
-                } else {
-                    writeln!(o, " *root*")?;
-                }
-
-                current_frame_len = frames.len();
+                writeln!(o, "  {op} frame (+{})", traced.top)?;
+                current_top = traced.top;
             }
-        }
 
-        if let Some((inst, _)) = vm.unit().instruction_at(ip).map_err(VmError::from)? {
-            write!(o, "  {:04} = {}", ip, inst)?;
-        } else {
-            write!(o, "  {:04} = *out of bounds*", ip)?;
-        }
+            write!(o, "  {:04} = {}", ip, traced.inst)?;
 
-        if let Some(comment) = debug.and_then(|d| d.comment.as_ref()) {
-            write!(o, " // {}", comment)?;
-        }
+            if let Some(comment) = debug.and_then(|d| d.comment.as_ref()) {
+                write!(o, " // {}", comment)?;
+            }
 
-        writeln!(o)?;
+            writeln!(o)?;
 
-        if !without_source {
-            let debug_info = debug.and_then(|d| sources.get(d.source_id).map(|s| (s, d.span)));
+            if !without_source {
+                let debug_info = debug.and_then(|d| sources.get(d.source_id).map(|s| (s, d.span)));
 
-            if let Some(line) = debug_info.and_then(|(s, span)| s.source_line(span)) {
-                write!(o, "  ")?;
-                line.write(&mut o)?;
-                writeln!(o)?;
+                if let Some(line) = debug_info.and_then(|(s, span)| s.source_line(span)) {
+                    write!(o, "  ")?;
+                    line.write(&mut o)?;
+                    writeln!(o)?;
+                }
             }
-        }
-
-        if dump_stack {
-            let stack = vm.stack();
-            let values = stack.get(stack.top()..).expect("bad stack slice");
-
-            if !values.is_empty() {
-                vm.with(|| {
-                    for (n, value) in values.iter().enumerate() {
-                        writeln!(o, "    {}+{n} = {value:?}", stack.top())?;
-                    }
 
-                    Ok::<_, TraceError>(())
-                })?;
+            for (n, value) in traced.stack.iter().enumerate() {
+                writeln!(o, "    {}+{n} = {value}", traced.top)?;
             }
         }
 
         match result {
-            VmResult::Ok(result) => {
-                if let Some(result) = result {
-                    return Ok(result);
-                }
-            }
-            VmResult::Err(error) => {
-                return Err(TraceError::VmError(error));
-            }
+            VmResult::Ok(Some(result)) => return Ok(result),
+            VmResult::Ok(None) => {}
+            VmResult::Err(error) => return Err(TraceError::VmError(error)),
         }
 
-        result = execution.async_step().await;
-
-        result = match result {
-            VmResult::Err(error) => {
-                return Err(TraceError::VmError(error));
-            }
-            result => result,
-        };
-
         limit = limit.wrapping_sub(1);
     }
 
@@ -0,0 +1,217 @@
+use std::string::String;
+use std::sync::Arc;
+use std::vec::Vec;
+
+use crate as rune;
+use crate::runtime::{VmError, VmResult};
+use crate::termcolor::NoColor;
+use crate::{Any, Context, Diagnostics, Module, Source, Sources, Vm};
+
+use super::{dump_source_map, Backtrace};
+
+fn get() -> VmResult<()> {
+    VmResult::panic("connection refused")
+}
+
+#[derive(Any)]
+#[rune(item = ::http)]
+struct Client;
+
+fn send(_: &Client) -> VmResult<()> {
+    VmResult::panic("connection reset")
+}
+
+fn context() -> Context {
+    let mut http = Module::with_crate("http").unwrap();
+    http.function("get", get).build().unwrap();
+    http.ty::<Client>().unwrap();
+    http.function("new", || Client)
+        .build_associated::<Client>()
+        .unwrap();
+    http.function("send", send)
+        .build_associated::<Client>()
+        .unwrap();
+
+    let mut context = Context::with_default_modules().unwrap();
+    context.install(http).unwrap();
+    context
+}
+
+fn error(context: &Context, source: &str) -> (Sources, VmError) {
+    let mut sources = Sources::new();
+    sources
+        .insert(Source::new("main.rn", source).unwrap())
+        .unwrap();
+
+    let mut diagnostics = Diagnostics::new();
+
+    let unit = crate::prepare(&mut sources)
+        .with_context(context)
+        .with_diagnostics(&mut diagnostics)
+        .build()
+        .unwrap();
+
+    let runtime = Arc::new(context.runtime().unwrap());
+    let mut vm = Vm::new(runtime, Arc::new(unit));
+    let error = vm.call(["main"], ()).unwrap_err();
+    (sources, error)
+}
+
+fn emit(context: &Context, source: &str, backtrace: Backtrace) -> String {
+    let (sources, error) = error(context, source);
+    let mut out = NoColor::new(Vec::new());
+
+    error
+        .emit_backtrace(&mut out, &sources, context, backtrace.limit())
+        .unwrap();
+
+    String::from_utf8(out.into_inner()).unwrap()
+}
+
+const THREE_DEEP: &str = r#"
+fn third() {
+    panic!("oh no");
+}
+
+fn second() {
+    third();
+}
+
+pub fn main() {
+    second();
+}
+"#;
+
+#[test]
+fn backtrace_full() {
+    let context = context();
+    let out = emit(&context, THREE_DEEP, Backtrace::Full);
+
+    let third = out.find("#0 `third`").expect(&out);
+    let second = out.find("#1 `second`").expect(&out);
+    let main = out.find("#2 `main`").expect(&out);
+
+    assert!(third < second && second < main, "{out}");
+    assert!(!out.contains("#3"), "{out}");
+    assert!(!out.contains("omitted"), "{out}");
+}
+
+#[test]
+fn backtrace_short() {
+    let context = context();
+
+    let out = emit(
+        &context,
+        r#"
+        fn depth(n) {
+            if n == 0 {
+                panic!("bottom");
+            }
+
+            depth(n - 1);
+        }
+
+        pub fn main() {
+            depth(6);
+        }
+        "#,
+        Backtrace::Short,
+    );
+
+    assert!(out.contains("#4 `depth`"), "{out}");
+    assert!(!out.contains("#5"), "{out}");
+    assert!(out.contains("3 more frames omitted"), "{out}");
+}
+
+#[test]
+fn backtrace_off() {
+    let context = context();
+    let out = emit(&context, THREE_DEEP, Backtrace::Off);
+
+    assert!(out.contains("oh no"), "{out}");
+    assert!(!out.contains("#0"), "{out}");
+}
+
+#[test]
+fn backtrace_native() {
+    let context = context();
+
+    let out = emit(
+        &context,
+        r#"
+        fn fetch() {
+            http::get();
+        }
+
+        pub fn main() {
+            fetch();
+        }
+        "#,
+        Backtrace::Full,
+    );
+
+    let native = out.find("#0 [native] ::http::get").expect(&out);
+    let fetch = out.find("#1 `fetch`").expect(&out);
+    let main = out.find("#2 `main`").expect(&out);
+
+    assert!(native < fetch && fetch < main, "{out}");
+}
+
+#[test]
+fn backtrace_native_instance_function() {
+    let context = context();
+
+    let out = emit(
+        &context,
+        r#"
+        pub fn main() {
+            let client = http::Client::new();
+            client.send();
+        }
+        "#,
+        Backtrace::Full,
+    );
+
+    let native = out
+        .find("#0 [native] instance function `send`")
+        .expect(&out);
+    let main = out.find("#1 `main`").expect(&out);
+
+    assert!(native < main, "{out}");
+}
+
+#[test]
+fn similar_instance_function() {
+    let context = context();
+
+    let out = emit(
+        &context,
+        r#"
+        pub fn main() {
+            let client = http::Client::new();
+            client.sedn();
+        }
+        "#,
+        Backtrace::Off,
+    );
+
+    assert!(
+        out.contains("help: an instance function with a similar name exists: `send`"),
+        "{out}"
+    );
+}
+
+#[test]
+fn source_map() {
+    let context = context();
+    let (sources, error) = error(&context, THREE_DEEP);
+
+    let location = error.first_location().unwrap();
+
+    let mut out = Vec::new();
+    dump_source_map(&mut out, &location.unit, &sources).unwrap();
+    let out = String::from_utf8(out).unwrap();
+
+    // The panic is raised on the third line of the source.
+    assert!(out.lines().any(|line| line.contains("main.rn:3:")), "{out}");
+}
@@ -1,31 +1,42 @@
+#[cfg(test)]
+mod tests;
+
+mod junit;
+
 use std::fmt;
-use std::io::Write;
+use std::fs;
+use std::io::{self, Write};
 use std::mem::take;
 use std::slice;
+use std::string::String as StdString;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
+use codespan_reporting::diagnostic as d;
+use codespan_reporting::term;
 
 use crate::alloc::fmt::TryWrite;
 use crate::alloc::prelude::*;
 use crate::cli::naming::Naming;
 use crate::cli::visitor;
 use crate::cli::{
-    AssetKind, Color, CommandBase, Config, Entry, EntryPoint, ExitCode, Io, Options, SharedFlags,
-    Stream,
+    AssetKind, Color, CommandBase, Config, Entry, EntryPoint, ExitCode, Io, MessageFormat, Options,
+    SharedFlags, Stream,
 };
-use crate::compile::FileSourceLoader;
-use crate::doc::{TestKind, TestParams};
+use crate::compile::{FileSourceLoader, Location};
+use crate::doc::{Externs, Test, TestKind, TestParams};
 use crate::modules::capture_io::CaptureIo;
 use crate::runtime::{Repr, Value, Vm, VmError, VmResult};
-use crate::{Diagnostics, Hash, Item, ItemBuf, Source, Sources, TypeHash, Unit};
+use crate::termcolor::{NoColor, WriteColor};
+use crate::{BuildError, Diagnostics, Hash, Item, ItemBuf, Source, Sources, TypeHash, Unit};
 
 mod cli {
+    use std::path::PathBuf;
     use std::string::String;
     use std::vec::Vec;
 
-    use clap::Parser;
+    use clap::{Parser, ValueEnum};
 
     #[derive(Parser, Debug, Clone)]
     #[command(rename_all = "kebab-case")]
@@ -43,12 +54,44 @@ mod cli {
         /// tests found in runtime contexts will be run.
         #[arg(long)]
         pub skip_lib_tests: bool,
+        /// Only run examples in documentation comments, skipping `#[test]`
+        /// functions.
+        #[arg(long)]
+        pub doc: bool,
+        /// Watch the sources for changes and re-run the command when they
+        /// change.
+        #[arg(long)]
+        pub watch: bool,
+        /// Only run tests whose name exactly matches one of the filters,
+        /// rather than containing it.
+        #[arg(long)]
+        pub exact: bool,
+        /// Print output of tests as they run, rather than only for tests
+        /// which fail.
+        #[arg(long)]
+        pub nocapture: bool,
+        /// The format to report test results in.
+        #[arg(long, default_value = "human")]
+        pub format: Format,
+        /// Write test results to the given path instead of stdout. This only
+        /// applies to machine-readable formats such as `junit`.
+        #[arg(long)]
+        pub output: Option<PathBuf>,
         /// Filter tests by name.
         pub filters: Vec<String>,
     }
+
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+    pub enum Format {
+        #[default]
+        /// Only report results in human-readable form.
+        Human,
+        /// Additionally report results as JUnit XML.
+        Junit,
+    }
 }
 
-pub(super) use cli::Flags;
+pub(super) use cli::{Flags, Format};
 
 impl CommandBase for Flags {
     #[inline]
@@ -70,6 +113,19 @@ impl CommandBase for Flags {
     fn propagate(&mut self, c: &mut Config, _: &mut SharedFlags) {
         c.test = true;
     }
+
+    #[inline]
+    fn is_watch(&self) -> bool {
+        self.watch
+    }
+}
+
+impl Flags {
+    /// Test if a JUnit report is written to stdout, in which case no other
+    /// output may be written to it.
+    fn is_junit_stdout(&self) -> bool {
+        self.format == Format::Junit && self.output.is_none()
+    }
 }
 
 enum BatchKind {
@@ -110,13 +166,14 @@ where
     let start = Instant::now();
 
     let mut executed = 0usize;
+    let mut passed = 0usize;
     let mut skipped = 0usize;
     let mut build_errors = 0usize;
     let mut skipped_entries = 0usize;
     let mut collected = Vec::new();
 
     let capture = crate::modules::capture_io::CaptureIo::new();
-    let context = shared.context(entry, c, Some(&capture))?;
+    let context = shared.context(entry, c, (!flags.nocapture).then_some(&capture))?;
 
     let mut batches = Vec::new();
     let mut naming = Naming::default();
@@ -130,12 +187,7 @@ where
         name.clear();
 
         write!(name, "{item}")?;
-
-        if !flags.filters.iter().any(|f| name.contains(f.as_str())) {
-            return Ok(true);
-        }
-
-        Ok(false)
+        Ok(is_filtered(&flags.filters, flags.exact, &name))
     };
 
     for e in entries {
@@ -158,6 +210,10 @@ where
             Err(error) => return Err(error).context(e.path().display().try_to_string()?),
         };
 
+        if let Some(package) = e.package() {
+            package.prepare_dependencies(&mut sources)?;
+        }
+
         sources.insert(source)?;
 
         let mut diagnostics = if shared.warnings || flags.warnings_are_errors {
@@ -167,6 +223,8 @@ where
         };
 
         let mut doc_visitor = crate::doc::Visitor::new(&item)?;
+        // Doc tests are run for every item, regardless of visibility.
+        doc_visitor.document_private = true;
         let mut functions = visitor::FunctionVisitor::new(visitor::Attribute::Test);
         let mut source_loader = FileSourceLoader::new();
 
@@ -181,11 +239,14 @@ where
 
         if diagnostics.has_error() || flags.warnings_are_errors && diagnostics.has_warning() {
             build_errors = build_errors.wrapping_add(1);
-            collected.try_push((diagnostics, sources))?;
+            collected.try_push((diagnostics, sources, None))?;
             continue;
         }
 
-        diagnostics.emit(&mut io.stdout.lock(), &sources)?;
+        if !flags.is_junit_stdout() {
+            c.message_format
+                .emit_diagnostics(io.stdout, &diagnostics, &sources)?;
+        }
 
         let unit = Arc::new(unit?);
         let sources = Arc::new(sources);
@@ -206,19 +267,29 @@ where
             ))?;
         }
 
-        batches.try_push(Batch {
-            kind: BatchKind::LibTests,
-            entry: Some(e.try_clone()?),
-            cases,
-        })?;
+        if !flags.doc {
+            batches.try_push(Batch {
+                kind: BatchKind::LibTests,
+                entry: Some(e.try_clone()?),
+                cases,
+            })?;
+        }
 
         let mut artifacts = crate::doc::Artifacts::without_assets();
-        crate::doc::build("root", &mut artifacts, None, slice::from_ref(&doc_visitor))?;
+        crate::doc::build(
+            "root",
+            &mut artifacts,
+            None,
+            slice::from_ref(&doc_visitor),
+            &Externs::new(),
+            None,
+        )?;
 
         if !c.filtered {
             let cases = populate_doc_tests(
                 io,
                 artifacts,
+                Some(&sources),
                 shared,
                 flags,
                 &options,
@@ -238,12 +309,20 @@ where
     }
 
     let mut artifacts = crate::doc::Artifacts::without_assets();
-    crate::doc::build("root", &mut artifacts, Some(&context), &[])?;
+    crate::doc::build(
+        "root",
+        &mut artifacts,
+        Some(&context),
+        &[],
+        &Externs::new(),
+        None,
+    )?;
 
     if !c.filtered {
         let cases = populate_doc_tests(
             io,
             artifacts,
+            None,
             shared,
             flags,
             options,
@@ -263,6 +342,11 @@ where
 
     let runtime = Arc::new(context.runtime()?);
     let mut failed = Vec::new();
+    let mut reports = Vec::new();
+
+    // Human-readable sections would be interleaved with machine-readable
+    // output, so they are only emitted when that is what's asked for.
+    let human = c.message_format == MessageFormat::Human && !flags.is_junit_stdout();
 
     for batch in batches {
         if batch.cases.is_empty() {
@@ -276,7 +360,7 @@ where
 
         let mut section = None;
 
-        if shared.verbose {
+        if shared.verbose && human {
             if all_ignored {
                 section = Some(("Ignoring", Color::Ignore));
             } else {
@@ -299,8 +383,19 @@ where
         }
 
         for mut case in batch.cases {
+            // Sources changed in watch mode, so the tests are about to be
+            // re-run.
+            if c.watch.as_ref().is_some_and(|signal| signal.is_raised()) {
+                return Ok(ExitCode::Success);
+            }
+
             if case.filtered {
                 skipped = skipped.wrapping_add(1);
+
+                if flags.format == Format::Junit {
+                    reports.try_push(case.report()?)?;
+                }
+
                 continue;
             }
 
@@ -309,20 +404,35 @@ where
             }
 
             let mut vm = Vm::new(runtime.clone(), case.unit.clone());
+
+            if let Some(signal) = &c.watch {
+                signal.install(&mut vm);
+            }
+
             case.execute(&mut vm, &capture).await?;
             executed = executed.wrapping_add(1);
 
+            if flags.format == Format::Junit {
+                reports.try_push(case.report()?)?;
+            }
+
             if case.outcome.is_ok() {
+                passed = passed.wrapping_add(1);
+
+                if !human {
+                    continue;
+                }
+
                 if flags.quiet {
                     write!(io.stdout, ".")?;
                 } else {
-                    case.emit(io)?;
+                    case.emit(io, c.message_format)?;
                 }
 
                 continue;
             }
 
-            if flags.quiet {
+            if flags.quiet && human {
                 write!(io.stdout, "f")?;
             }
 
@@ -334,22 +444,59 @@ where
         }
     }
 
-    if flags.quiet {
+    if flags.quiet && human {
         writeln!(io.stdout)?;
     }
 
     let failures = failed.len();
 
-    for (diagnostics, sources) in collected {
-        diagnostics.emit(&mut io.stdout.lock(), &sources)?;
-    }
+    // Build errors and failures are included in the report instead.
+    if !flags.is_junit_stdout() {
+        for (diagnostics, sources, origin) in &collected {
+            c.message_format
+                .emit_diagnostics(io.stdout, diagnostics, sources)?;
+
+            if let Some(origin) = origin {
+                if human {
+                    origin.emit(io.stdout)?;
+                }
+            }
+        }
 
-    for case in failed {
-        case.emit(io)?;
+        for case in failed {
+            case.emit(io, c.message_format)?;
+        }
     }
 
     let elapsed = start.elapsed();
 
+    if flags.format == Format::Junit {
+        for (diagnostics, sources, _) in &collected {
+            reports.try_push(build_error_report(diagnostics, sources)?)?;
+        }
+
+        match &flags.output {
+            Some(path) => {
+                let mut out = io::BufWriter::new(fs::File::create(path)?);
+                junit::write(&mut out, "rune", &reports, elapsed)?;
+                out.flush()?;
+            }
+            None => {
+                junit::write(io.stdout, "rune", &reports, elapsed)?;
+            }
+        }
+    }
+
+    let code = if build_errors == 0 && failures == 0 {
+        ExitCode::Success
+    } else {
+        ExitCode::Failure
+    };
+
+    if !human {
+        return Ok(code);
+    }
+
     let mut section = io.section("Executed", Stream::Stdout, Color::Highlight)?;
 
     section.append(format_args!(" {executed} tests"))?;
@@ -379,6 +526,7 @@ where
             Ok::<_, anyhow::Error>(())
         };
 
+        emit(Color::Passed, passed, "passed", "passed")?;
         emit(Color::Error, failures, "failure", "failures")?;
         emit(Color::Error, build_errors, "build error", "build errors")?;
         emit(Color::Ignore, skipped, "filtered", "filtered")?;
@@ -391,24 +539,118 @@ where
     }
 
     writeln!(io.stdout, " in {:.3} seconds", elapsed.as_secs_f64())?;
+    Ok(code)
+}
 
-    if build_errors == 0 && failures == 0 {
-        Ok(ExitCode::Success)
-    } else {
-        Ok(ExitCode::Failure)
+/// Test if a test with the given name is filtered out by the given filters.
+///
+/// A test is kept if it contains any of the filters, or if `exact` is set, if
+/// it is equal to any of them.
+fn is_filtered(filters: &[std::string::String], exact: bool, name: &str) -> bool {
+    if filters.is_empty() {
+        return false;
+    }
+
+    !filters.iter().any(|f| {
+        if exact {
+            name == f.as_str()
+        } else {
+            name.contains(f.as_str())
+        }
+    })
+}
+
+/// Construct a report for an entry or doc test which failed to build.
+fn build_error_report(diagnostics: &Diagnostics, sources: &Sources) -> Result<junit::Report> {
+    let name = match sources.iter().next() {
+        Some(source) => StdString::from(source.name()),
+        None => StdString::from("<unknown>"),
+    };
+
+    let mut details = NoColor::new(std::vec::Vec::new());
+    diagnostics.emit(&mut details, sources)?;
+
+    Ok(junit::Report {
+        name,
+        duration: Duration::ZERO,
+        status: junit::Status::Failed {
+            message: StdString::from("failed to build"),
+            details: StdString::from_utf8_lossy(&details.into_inner()).into_owned(),
+        },
+        output: StdString::new(),
+    })
+}
+
+/// A doc comment which a doc test was extracted from.
+struct Origin {
+    sources: Arc<Sources>,
+    location: Location,
+}
+
+impl Origin {
+    fn new(sources: Option<&Arc<Sources>>, test: &Test) -> Option<Self> {
+        Some(Self {
+            sources: sources?.clone(),
+            location: test.location?,
+        })
+    }
+
+    /// Emit a note pointing to the doc comment.
+    fn emit(&self, out: &mut dyn WriteColor) -> Result<()> {
+        let diagnostic = d::Diagnostic::note()
+            .with_message("in this doc test")
+            .with_labels(std::vec![d::Label::primary(
+                self.location.source_id,
+                self.location.span.range(),
+            )]);
+
+        term::emit(out, &term::Config::default(), &*self.sources, &diagnostic)?;
+        Ok(())
     }
 }
 
+/// Build the unit for a single doc test.
+fn build_doc_test(
+    context: &crate::Context,
+    options: &Options,
+    test: &Test,
+    diagnostics: &mut Diagnostics,
+) -> Result<(Sources, Result<Unit, BuildError>)> {
+    let mut sources = Sources::new();
+
+    let source = Source::new(test.item.try_to_string()?, &test.content)?;
+    sources.insert(source)?;
+
+    let mut source_loader = FileSourceLoader::new();
+
+    let mut options = options.clone();
+    options.function_body = true;
+
+    let unit = crate::prepare(&mut sources)
+        .with_context(context)
+        .with_diagnostics(diagnostics)
+        .with_options(&options)
+        .with_source_loader(&mut source_loader)
+        .build();
+
+    Ok((sources, unit))
+}
+
+/// Build doc tests from the given artifacts.
+///
+/// If the artifacts were built from an entry, `entry` are the sources of that
+/// entry, which are used to point out the doc comment of failing tests.
 fn populate_doc_tests(
     io: &mut Io,
     artifacts: crate::doc::Artifacts,
+    entry: Option<&Arc<Sources>>,
     shared: &SharedFlags,
     flags: &Flags,
     options: &Options,
     context: &crate::Context,
     build_errors: &mut usize,
     skipped_entries: &mut usize,
-    collected: &mut Vec<(Diagnostics, Sources)>,
+    collected: &mut Vec<(Diagnostics, Sources, Option<Origin>)>,
     filter: &mut dyn FnMut(&Item) -> Result<bool>,
 ) -> Result<Vec<TestCase>> {
     let mut cases = Vec::new();
@@ -425,42 +667,31 @@ fn populate_doc_tests(
             continue;
         }
 
-        let mut sources = Sources::new();
-
-        let source = Source::new(test.item.try_to_string()?, &test.content)?;
-        sources.insert(source)?;
-
         let mut diagnostics = if shared.warnings || flags.warnings_are_errors {
             Diagnostics::new()
         } else {
             Diagnostics::without_warnings()
         };
 
-        let mut source_loader = FileSourceLoader::new();
-
-        let mut options = options.clone();
-        options.function_body = true;
-
-        let unit = crate::prepare(&mut sources)
-            .with_context(context)
-            .with_diagnostics(&mut diagnostics)
-            .with_options(&options)
-            .with_source_loader(&mut source_loader)
-            .build();
+        let (sources, unit) = build_doc_test(context, options, test, &mut diagnostics)?;
 
         if diagnostics.has_error() || flags.warnings_are_errors && diagnostics.has_warning() {
             *build_errors = build_errors.wrapping_add(1);
-            collected.try_push((diagnostics, sources))?;
+            collected.try_push((diagnostics, sources, Origin::new(entry, test)))?;
             continue;
         }
 
-        diagnostics.emit(&mut io.stdout.lock(), &sources)?;
+        if !flags.is_junit_stdout() {
+            shared
+                .message_format
+                .emit_diagnostics(io.stdout, &diagnostics, &sources)?;
+        }
 
         if !test.params.no_run {
             let unit = Arc::new(unit?);
             let sources = Arc::new(sources);
 
-            cases.try_push(TestCase::new(
+            let mut case = TestCase::new(
                 Hash::EMPTY,
                 test.item.try_clone()?,
                 test.kind,
@@ -468,7 +699,10 @@ fn populate_doc_tests(
                 sources.clone(),
                 test.params,
                 is_filtered,
-            ))?;
+            );
+
+            case.origin = Origin::new(entry, test);
+            cases.try_push(case)?;
         }
     }
 
@@ -500,6 +734,9 @@ struct TestCase {
     outcome: Outcome,
     output: Vec<u8>,
     filtered: bool,
+    duration: Duration,
+    /// The doc comment the test was extracted from, if it's a doc test.
+    origin: Option<Origin>,
 }
 
 impl TestCase {
@@ -522,15 +759,21 @@ impl TestCase {
             outcome: Outcome::Ok,
             output: Vec::new(),
             filtered,
+            duration: Duration::ZERO,
+            origin: None,
         }
     }
 
     async fn execute(&mut self, vm: &mut Vm, capture_io: &CaptureIo) -> Result<()> {
+        let start = Instant::now();
+
         let result = match vm.execute(self.hash, ()) {
             Ok(mut execution) => execution.async_complete().await,
             Err(err) => VmResult::Err(err),
         };
 
+        self.duration = start.elapsed();
+
         capture_io.drain_into(&mut self.output)?;
 
         self.outcome = match result {
@@ -570,7 +813,113 @@ impl TestCase {
         Ok(())
     }
 
-    fn emit(self, io: &mut Io<'_>) -> Result<()> {
+    /// The name of the test case.
+    fn name(&self) -> StdString {
+        match self.kind {
+            TestKind::Free => std::format!("{}", self.item),
+            TestKind::Protocol(protocol) => std::format!("{} {}", self.item, protocol.name),
+        }
+    }
+
+    /// Construct a machine-readable report for the test case.
+    fn report(&self) -> Result<junit::Report> {
+        let name = self.name();
+
+        let status = if self.filtered {
+            junit::Status::Skipped
+        } else {
+            let (message, details) = match &self.outcome {
+                Outcome::Ok => {
+                    return Ok(junit::Report {
+                        name,
+                        duration: self.duration,
+                        status: junit::Status::Passed,
+                        output: StdString::from_utf8_lossy(&self.output).into_owned(),
+                    });
+                }
+                Outcome::Panic(error) => {
+                    let mut details = NoColor::new(std::vec::Vec::new());
+                    error.emit(&mut details, &self.sources)?;
+                    let details = StdString::from_utf8_lossy(&details.into_inner()).into_owned();
+                    (std::format!("{error}"), details)
+                }
+                Outcome::ExpectedPanic => (
+                    StdString::from(
+                        "expected panic because of `should_panic`, but ran without issue",
+                    ),
+                    StdString::new(),
+                ),
+                Outcome::Err(error) => (std::format!("err: {error:?}"), StdString::new()),
+                Outcome::None => (StdString::from("returned none"), StdString::new()),
+            };
+
+            junit::Status::Failed { message, details }
+        };
+
+        Ok(junit::Report {
+            name,
+            duration: self.duration,
+            status,
+            output: StdString::from_utf8_lossy(&self.output).into_owned(),
+        })
+    }
+
+    /// Emit a failing outcome as JSON diagnostics, one object per line.
+    ///
+    /// The outcome is reported as an error pointing to the doc comment the test
+    /// was extracted from, if there is one. If the test panicked, it's followed
+    /// by the diagnostics of the error.
+    fn emit_json<O>(&self, out: &mut O) -> Result<()>
+    where
+        O: ?Sized + io::Write,
+    {
+        let message = match &self.outcome {
+            Outcome::Ok => return Ok(()),
+            Outcome::Panic(..) => StdString::from("errored"),
+            Outcome::ExpectedPanic => {
+                StdString::from("expected panic because of `should_panic`, but ran without issue")
+            }
+            Outcome::Err(error) => std::format!("err: {error:?}"),
+            Outcome::None => StdString::from("returned none"),
+        };
+
+        let mut diagnostic =
+            d::Diagnostic::error().with_message(std::format!("test {}: {message}", self.name()));
+
+        if !self.output.is_empty() {
+            diagnostic = diagnostic.with_notes(std::vec![std::format!(
+                "output:\n{}",
+                StdString::from_utf8_lossy(&self.output)
+            )]);
+        }
+
+        let sources = match &self.origin {
+            Some(origin) => {
+                diagnostic = diagnostic.with_labels(std::vec![d::Label::primary(
+                    origin.location.source_id,
+                    origin.location.span.range(),
+                )
+                .with_message("in this doc test")]);
+
+                &origin.sources
+            }
+            None => &self.sources,
+        };
+
+        crate::diagnostics::write_json(out, sources, &diagnostic)?;
+
+        if let Outcome::Panic(error) = &self.outcome {
+            error.emit_json(out, &self.sources)?;
+        }
+
+        Ok(())
+    }
+
+    fn emit(self, io: &mut Io<'_>, message_format: MessageFormat) -> Result<()> {
+        if message_format != MessageFormat::Human {
+            return self.emit_json(io.stdout);
+        }
+
         let mut section = io.section("Test", Stream::Stdout, Color::Highlight)?;
 
         match self.kind {
@@ -608,7 +957,13 @@ impl TestCase {
         section.close()?;
 
         if let Some(error) = emitted {
-            error.emit(io.stdout, &self.sources)?;
+            message_format.emit_vm_error(io.stdout, error, &self.sources)?;
+        }
+
+        if !self.outcome.is_ok() {
+            if let Some(origin) = &self.origin {
+                origin.emit(io.stdout)?;
+            }
         }
 
         if !self.outcome.is_ok() && !self.output.is_empty() {
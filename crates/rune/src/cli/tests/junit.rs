@@ -0,0 +1,158 @@
+//! Output of test results in the JUnit XML format.
+
+use std::fmt;
+use std::io::{self, Write};
+use std::string::String;
+use std::time::Duration;
+
+/// The result of a single test.
+pub(super) struct Report {
+    /// The full name of the test.
+    pub(super) name: String,
+    /// How long the test took to run.
+    pub(super) duration: Duration,
+    /// The status of the test.
+    pub(super) status: Status,
+    /// Output captured while running the test.
+    pub(super) output: String,
+}
+
+/// The status of a single test.
+pub(super) enum Status {
+    /// The test passed.
+    Passed,
+    /// The test was not run.
+    Skipped,
+    /// The test failed.
+    Failed {
+        /// A short description of the failure.
+        message: String,
+        /// Details such as the rendered diagnostic.
+        details: String,
+    },
+}
+
+/// Write the given reports as a JUnit test suite.
+pub(super) fn write<O>(
+    out: &mut O,
+    name: &str,
+    reports: &[Report],
+    time: Duration,
+) -> io::Result<()>
+where
+    O: ?Sized + Write,
+{
+    let tests = reports.len();
+
+    let failures = reports
+        .iter()
+        .filter(|r| matches!(r.status, Status::Failed { .. }))
+        .count();
+
+    let skipped = reports
+        .iter()
+        .filter(|r| matches!(r.status, Status::Skipped))
+        .count();
+
+    let time = time.as_secs_f64();
+
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        out,
+        r#"<testsuites tests="{tests}" failures="{failures}" skipped="{skipped}" time="{time:.3}">"#
+    )?;
+    writeln!(
+        out,
+        r#"  <testsuite name="{}" tests="{tests}" failures="{failures}" skipped="{skipped}" time="{time:.3}">"#,
+        Escape(name)
+    )?;
+
+    for report in reports {
+        let classname = match report.name.rsplit_once("::") {
+            Some((classname, _)) => classname,
+            None => report.name.as_str(),
+        };
+
+        write!(
+            out,
+            r#"    <testcase name="{}" classname="{}" time="{:.3}""#,
+            Escape(&report.name),
+            Escape(classname),
+            report.duration.as_secs_f64()
+        )?;
+
+        if matches!(report.status, Status::Passed) && report.output.is_empty() {
+            writeln!(out, "/>")?;
+            continue;
+        }
+
+        writeln!(out, ">")?;
+
+        match &report.status {
+            Status::Passed => {}
+            Status::Skipped => {
+                writeln!(out, "      <skipped/>")?;
+            }
+            Status::Failed { message, details } => {
+                writeln!(
+                    out,
+                    r#"      <failure message="{}">{}</failure>"#,
+                    Escape(message),
+                    Escape(details)
+                )?;
+            }
+        }
+
+        if !report.output.is_empty() {
+            writeln!(
+                out,
+                "      <system-out>{}</system-out>",
+                Escape(&report.output)
+            )?;
+        }
+
+        writeln!(out, "    </testcase>")?;
+    }
+
+    writeln!(out, "  </testsuite>")?;
+    writeln!(out, "</testsuites>")?;
+    Ok(())
+}
+
+/// Escape a string for use in XML text or attributes.
+struct Escape<'a>(&'a str);
+
+impl fmt::Display for Escape<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut it = self.0.chars();
+
+        while let Some(c) = it.next() {
+            match c {
+                // Escape sequences used for coloring, such as `\x1b[31m`, are
+                // removed in their entirety.
+                '\x1b' => {
+                    if it.clone().next() == Some('[') {
+                        it.next();
+
+                        for c in it.by_ref() {
+                            if matches!(c, '\x40'..='\x7e') {
+                                break;
+                            }
+                        }
+                    }
+                }
+                '&' => f.write_str("&amp;")?,
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                '"' => f.write_str("&quot;")?,
+                '\'' => f.write_str("&apos;")?,
+                // Control characters other than whitespace are not permitted
+                // in XML.
+                c if c.is_control() && !matches!(c, '\n' | '\r' | '\t') => {}
+                c => write!(f, "{c}")?,
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,289 @@
+use std::slice;
+use std::string::String;
+use std::sync::Arc;
+use std::time::Duration;
+use std::vec::Vec;
+
+use crate::alloc::prelude::*;
+use crate::doc::{Artifacts, Externs, Test, TestKind, TestParams, Visitor};
+use crate::modules::capture_io::CaptureIo;
+use crate::termcolor::NoColor;
+use crate::{Context, Diagnostics, Hash, ItemBuf, Options, Source, Sources, Vm};
+
+use super::junit::{self, Report, Status};
+use super::{build_doc_test, is_filtered, Origin, Outcome, TestCase};
+
+fn filters(filters: &[&str]) -> Vec<String> {
+    filters.iter().map(|f| String::from(*f)).collect()
+}
+
+#[test]
+fn filter_substring() {
+    assert!(!is_filtered(&[], false, "foo::bar"));
+    assert!(!is_filtered(&filters(&["bar"]), false, "foo::bar"));
+    assert!(!is_filtered(&filters(&["baz", "foo"]), false, "foo::bar"));
+    assert!(is_filtered(&filters(&["baz"]), false, "foo::bar"));
+}
+
+#[test]
+fn filter_exact() {
+    assert!(!is_filtered(&[], true, "foo::bar"));
+    assert!(!is_filtered(&filters(&["foo::bar"]), true, "foo::bar"));
+    assert!(is_filtered(&filters(&["bar"]), true, "foo::bar"));
+    assert!(is_filtered(&filters(&["foo::bar"]), true, "foo::bar::baz"));
+}
+
+#[test]
+fn junit_output() {
+    let reports = [
+        Report {
+            name: String::from("foo::passing"),
+            duration: Duration::from_millis(12),
+            status: Status::Passed,
+            output: String::new(),
+        },
+        Report {
+            name: String::from("foo::failing"),
+            duration: Duration::from_millis(1500),
+            status: Status::Failed {
+                message: String::from("expected <1> & \"2\""),
+                details: String::from("error: \x1b[31mpanicked\x1b[0m"),
+            },
+            output: String::from("hello\n"),
+        },
+    ];
+
+    let mut out = Vec::new();
+    junit::write(&mut out, "rune", &reports, Duration::from_secs(2)).unwrap();
+    let out = String::from_utf8(out).unwrap();
+
+    let expected = r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites tests="2" failures="1" skipped="0" time="2.000">
+  <testsuite name="rune" tests="2" failures="1" skipped="0" time="2.000">
+    <testcase name="foo::passing" classname="foo" time="0.012"/>
+    <testcase name="foo::failing" classname="foo" time="1.500">
+      <failure message="expected &lt;1&gt; &amp; &quot;2&quot;">error: panicked</failure>
+      <system-out>hello
+</system-out>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#;
+
+    assert_eq!(out, expected);
+}
+
+const DOC_TESTS: &str = r#"
+/// Adds one.
+///
+/// ```rune
+/// assert_eq!(1 + 1, 2);
+/// ```
+pub fn passing() {}
+/// ```rune
+/// # let value = 1;
+/// assert_eq!(value, 2);
+/// ```
+pub fn failing() {}
+/// ```rune
+/// let = 1;
+/// ```
+pub fn compile_error() {}
+"#;
+
+#[test]
+fn doc_tests() {
+    let context = Context::with_default_modules().unwrap();
+    let runtime = Arc::new(context.runtime().unwrap());
+
+    let mut sources = Sources::new();
+    sources
+        .insert(Source::new("main.rn", DOC_TESTS).unwrap())
+        .unwrap();
+
+    let mut visitor = Visitor::new(&ItemBuf::with_crate("main").unwrap()).unwrap();
+    let mut diagnostics = Diagnostics::new();
+
+    crate::prepare(&mut sources)
+        .with_context(&context)
+        .with_diagnostics(&mut diagnostics)
+        .with_visitor(&mut visitor)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let sources = Arc::new(sources);
+
+    let mut artifacts = Artifacts::without_assets();
+    crate::doc::build(
+        "root",
+        &mut artifacts,
+        None,
+        slice::from_ref(&visitor),
+        &Externs::new(),
+        None,
+    )
+    .unwrap();
+
+    let tests = artifacts.tests().collect::<Vec<_>>();
+    assert_eq!(tests.len(), 3);
+
+    let find = |name: &str| {
+        tests
+            .iter()
+            .copied()
+            .find(|test| test.item.last().and_then(|c| c.as_str()) == Some(name))
+            .unwrap()
+    };
+
+    // Each test points to the doc comment it was extracted from.
+    let line = |test: &Test| {
+        let location = test.location.unwrap();
+        let source = sources.get(location.source_id).unwrap();
+        source.pos_to_utf8_linecol(location.span.range().start).0
+    };
+
+    let passing = find("passing");
+    let failing = find("failing");
+    let compile_error = find("compile_error");
+
+    assert_eq!(line(passing), 1);
+    assert_eq!(line(failing), 7);
+    assert_eq!(line(compile_error), 12);
+
+    // Hidden lines are compiled even though they're not rendered.
+    assert!(failing.content.contains("let value = 1;"));
+
+    let run = |test: &Test| {
+        let mut diagnostics = Diagnostics::new();
+        let (test_sources, unit) =
+            build_doc_test(&context, &Options::default(), test, &mut diagnostics).unwrap();
+
+        let mut case = TestCase::new(
+            Hash::EMPTY,
+            test.item.try_clone().unwrap(),
+            test.kind,
+            Arc::new(unit.unwrap()),
+            Arc::new(test_sources),
+            test.params,
+            false,
+        );
+
+        case.origin = Origin::new(Some(&sources), test);
+
+        let capture = CaptureIo::new();
+        let mut vm = Vm::new(runtime.clone(), case.unit.clone());
+        futures_executor::block_on(case.execute(&mut vm, &capture)).unwrap();
+        case
+    };
+
+    assert!(run(passing).outcome.is_ok());
+
+    let failed = run(failing);
+    assert!(matches!(failed.outcome, Outcome::Panic(..)));
+
+    // Failures point out the original doc comment.
+    let mut out = NoColor::new(Vec::new());
+    failed.origin.as_ref().unwrap().emit(&mut out).unwrap();
+    let out = String::from_utf8(out.into_inner()).unwrap();
+    assert!(out.contains("main.rn:8:1"), "{out}");
+
+    let mut diagnostics = Diagnostics::new();
+    let (_, unit) = build_doc_test(
+        &context,
+        &Options::default(),
+        compile_error,
+        &mut diagnostics,
+    )
+    .unwrap();
+    assert!(unit.is_err());
+    assert!(diagnostics.has_error());
+}
+
+#[test]
+fn json_outcomes() {
+    let capture = CaptureIo::new();
+
+    let mut context = Context::with_config(false).unwrap();
+    context
+        .install(crate::modules::capture_io::module(&capture).unwrap())
+        .unwrap();
+
+    let runtime = Arc::new(context.runtime().unwrap());
+
+    let mut sources = crate::sources! {
+        entry => {
+            pub fn panics() { panic!("boom") }
+            pub fn err() { Err(1) }
+            pub fn none() { None }
+            pub fn no_panic() { println!("hello") }
+            pub fn ok() { Ok(1) }
+        }
+    };
+
+    let unit = crate::prepare(&mut sources)
+        .with_context(&context)
+        .build()
+        .unwrap();
+
+    let unit = Arc::new(unit);
+    let sources = Arc::new(sources);
+
+    let run = |name: &str, should_panic: bool| {
+        let params = TestParams {
+            should_panic,
+            ..TestParams::default()
+        };
+
+        let mut case = TestCase::new(
+            Hash::type_hash([name]),
+            ItemBuf::with_item([name]).unwrap(),
+            TestKind::Free,
+            unit.clone(),
+            sources.clone(),
+            params,
+            false,
+        );
+
+        let mut vm = Vm::new(runtime.clone(), unit.clone());
+        futures_executor::block_on(case.execute(&mut vm, &capture)).unwrap();
+
+        let mut out = Vec::new();
+        case.emit_json(&mut out).unwrap();
+
+        String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>()
+    };
+
+    let events = run("panics", false);
+    assert!(events.len() > 1, "{events:?}");
+    assert_eq!(events[0]["severity"], "error");
+    assert_eq!(events[0]["message"], "test panics: errored");
+    assert!(events[1]["message"].as_str().unwrap().contains("boom"));
+
+    let events = run("err", false);
+    assert_eq!(events.len(), 1, "{events:?}");
+    assert_eq!(events[0]["severity"], "error");
+    assert_eq!(events[0]["message"], "test err: err: 1");
+
+    let events = run("none", false);
+    assert_eq!(events.len(), 1, "{events:?}");
+    assert_eq!(events[0]["severity"], "error");
+    assert_eq!(events[0]["message"], "test none: returned none");
+
+    let events = run("no_panic", true);
+    assert_eq!(events.len(), 1, "{events:?}");
+    assert_eq!(events[0]["severity"], "error");
+    assert_eq!(
+        events[0]["message"],
+        "test no_panic: expected panic because of `should_panic`, but ran without issue"
+    );
+    assert_eq!(events[0]["notes"], serde_json::json!(["output:\nhello\n"]));
+
+    // Passing tests don't emit anything.
+    assert!(run("ok", false).is_empty());
+    assert!(run("panics", true).is_empty());
+}
@@ -0,0 +1,401 @@
+//! Support for re-running commands when watched sources change.
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::BTreeSet;
+use std::ffi::OsStr;
+use std::io::{IsTerminal, Write};
+use std::ops::ControlFlow;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use notify::Watcher;
+use tokio::sync::mpsc;
+
+use crate::alloc::Vec;
+use crate::cli::{Color, Command, Config, Entry, EntryPoint, ExitCode, Inputs, Io, Stream};
+use crate::workspace::{Manifest, MANIFEST_FILE};
+use crate::Vm;
+
+/// How long to wait for changes to settle before re-running the command.
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// How many instructions to execute between each check for whether a running
+/// script should be interrupted.
+const INTERRUPT_EVERY: usize = 1024;
+
+/// Signal raised when a running command should be aborted.
+#[derive(Default)]
+pub(super) struct Signal {
+    changed: AtomicBool,
+    exit: AtomicBool,
+}
+
+impl Signal {
+    /// Test if the signal has been raised.
+    pub(super) fn is_raised(&self) -> bool {
+        self.changed.load(Ordering::Acquire) || self.exit.load(Ordering::Acquire)
+    }
+
+    /// Install an interrupt hook into the given virtual machine which
+    /// interrupts it once the signal has been raised.
+    pub(super) fn install(self: &Arc<Self>, vm: &mut Vm) {
+        let signal = self.clone();
+
+        vm.set_interrupt_hook(INTERRUPT_EVERY, move |_| {
+            if signal.is_raised() {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+    }
+
+    fn is_exit(&self) -> bool {
+        self.exit.load(Ordering::Acquire)
+    }
+
+    fn reset(&self) {
+        self.changed.store(false, Ordering::Release);
+    }
+}
+
+/// A message received by the watch loop.
+enum Message {
+    /// An event from the file system watcher.
+    Event(notify::Result<notify::Event>),
+    /// Ctrl-C was pressed.
+    Exit,
+}
+
+/// Collects changed paths and determines when they have settled for long
+/// enough to warrant re-running the command.
+pub(super) struct Debouncer {
+    delay: Duration,
+    deadline: Option<Instant>,
+    paths: BTreeSet<PathBuf>,
+}
+
+impl Debouncer {
+    /// Construct a new debouncer which waits for `delay` after the last
+    /// received change.
+    pub(super) fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            deadline: None,
+            paths: BTreeSet::new(),
+        }
+    }
+
+    /// Record that the given path changed at `now`, pushing back the deadline.
+    pub(super) fn push(&mut self, now: Instant, path: PathBuf) {
+        self.paths.insert(path);
+        self.deadline = Some(now + self.delay);
+    }
+
+    /// The deadline at which changes have settled, if any changes are pending.
+    pub(super) fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Take the collection of changed paths if they have settled at `now`.
+    pub(super) fn poll(&mut self, now: Instant) -> Option<BTreeSet<PathBuf>> {
+        let deadline = self.deadline?;
+
+        if now < deadline {
+            return None;
+        }
+
+        self.deadline = None;
+        Some(std::mem::take(&mut self.paths))
+    }
+}
+
+/// Test if the given event is relevant to re-running the command.
+pub(super) fn is_relevant(event: &notify::Event) -> bool {
+    if matches!(
+        event.kind,
+        notify::EventKind::Access(..) | notify::EventKind::Other
+    ) {
+        return false;
+    }
+
+    event
+        .paths
+        .iter()
+        .any(|path| path.extension() == Some(OsStr::new("rn")) || is_manifest(path))
+}
+
+/// Test if the given path refers to a workspace manifest.
+pub(super) fn is_manifest(path: &Path) -> bool {
+    path.file_name() == Some(OsStr::new(MANIFEST_FILE))
+}
+
+/// Collect the paths to watch for the given entrypoints.
+///
+/// The directory of each plain entrypoint is watched so that any modules it
+/// loads are included, and for workspaces the root of the workspace and of
+/// every package in the manifest is watched. Paths nested inside of another
+/// watched path are omitted.
+pub(super) fn collect_paths(
+    root: Option<&Path>,
+    manifest: &Manifest,
+    entries: &[EntryPoint<'_>],
+) -> BTreeSet<PathBuf> {
+    let mut candidates = BTreeSet::new();
+
+    if let Some(root) = root {
+        candidates.insert(normalize(root));
+    }
+
+    for e in entries {
+        if let EntryPoint::Path(path, _) = e {
+            candidates.insert(normalize(path.parent().unwrap_or(path)));
+        }
+    }
+
+    for package in manifest.packages() {
+        if let Some(root) = &package.root {
+            candidates.insert(normalize(root));
+        }
+    }
+
+    let mut paths = BTreeSet::<PathBuf>::new();
+
+    // Since the set is ordered, any parent sorts before its children.
+    for path in candidates {
+        if !paths.iter().any(|p| contains(p, &path)) {
+            paths.insert(path);
+        }
+    }
+
+    paths
+        .into_iter()
+        .map(|p| {
+            if p.as_os_str().is_empty() {
+                PathBuf::from(".")
+            } else {
+                p
+            }
+        })
+        .collect()
+}
+
+/// Normalize a path by removing `.` components, so that `./a` and `a` are
+/// treated the same. The current directory is represented by an empty path.
+fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .collect()
+}
+
+/// Test if the normalized path `parent` contains `path`.
+fn contains(parent: &Path, path: &Path) -> bool {
+    if parent.as_os_str().is_empty() {
+        return path.is_relative();
+    }
+
+    path.starts_with(parent)
+}
+
+/// Run the given command, and re-run it every time a watched path changes.
+///
+/// If the workspace manifest changes it is reloaded before the command is
+/// re-run, so that added or removed packages and targets are picked up.
+pub(super) async fn run(
+    io: &mut Io<'_>,
+    c: &mut Config,
+    cmd: &Command,
+    entry: &mut Entry<'_>,
+    mut inputs: Inputs,
+) -> Result<ExitCode> {
+    let Some(signal) = c.watch.clone() else {
+        return Err(anyhow!("Watch mode not configured"));
+    };
+
+    let Some(shared) = cmd.as_command_shared_ref() else {
+        return Err(anyhow!("Command does not support watch mode"));
+    };
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+
+    let mut watcher = {
+        let signal = signal.clone();
+        let tx = tx.clone();
+
+        notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            let relevant = match &event {
+                Ok(event) => is_relevant(event),
+                Err(..) => true,
+            };
+
+            if relevant {
+                signal.changed.store(true, Ordering::Release);
+                _ = tx.send(Message::Event(event));
+            }
+        })?
+    };
+
+    spawn_ctrl_c(signal.clone(), tx)?;
+
+    let clear = std::io::stdout().is_terminal();
+    let mut debouncer = Debouncer::new(DEBOUNCE);
+    let mut watched = BTreeSet::new();
+    let mut changed = BTreeSet::<PathBuf>::new();
+
+    loop {
+        if clear {
+            write!(io.stdout, "\x1b[2J\x1b[H")?;
+        }
+
+        let mut section = io.section("Recompiling", Stream::Stderr, Color::Highlight)?;
+
+        match changed.len() {
+            0 => {}
+            1 => {
+                if let Some(path) = changed.first() {
+                    section.append(format_args!(" ({} changed)", path.display()))?;
+                }
+            }
+            n => {
+                section.append(format_args!(" ({n} files changed)"))?;
+            }
+        }
+
+        section.close()?;
+
+        signal.reset();
+
+        if changed.iter().any(|path| is_manifest(path)) {
+            let mut reloaded = Inputs::default();
+
+            // If the manifest can't be loaded, the errors are reported and the
+            // previously loaded inputs are used until it is fixed.
+            match super::populate_config(io, c, &mut reloaded, shared) {
+                Ok(()) => {
+                    inputs = reloaded;
+                }
+                Err(error) => {
+                    report(io, &error)?;
+                }
+            }
+        }
+
+        let entries = match super::collect_entries(io, c, &inputs, shared) {
+            Ok(entries) => entries,
+            Err(error) => {
+                report(io, &error)?;
+                Vec::new()
+            }
+        };
+
+        let paths = collect_paths(c.manifest_root.as_deref(), &inputs.manifest, &entries);
+
+        for path in watched.difference(&paths) {
+            watcher.unwatch(path)?;
+        }
+
+        for path in paths.difference(&watched) {
+            watcher.watch(path, notify::RecursiveMode::Recursive)?;
+        }
+
+        watched = paths;
+
+        let task = super::run_path(io, c, cmd, entry, entries);
+
+        // Dropping the running command aborts any execution which is waiting
+        // on something, while executions which are busy are interrupted
+        // through the signal.
+        let result = tokio::select! {
+            result = task => Some(result),
+            message = rx.recv() => {
+                if !handle(&mut debouncer, message)? {
+                    return Ok(ExitCode::Success);
+                }
+
+                None
+            }
+        };
+
+        if let Some(Err(error)) = result {
+            report(io, &error)?;
+        }
+
+        if signal.is_exit() {
+            return Ok(ExitCode::Success);
+        }
+
+        let mut section = io.section("Watching", Stream::Stderr, Color::Progress)?;
+        section.append(" for changes, press Ctrl-C to exit")?;
+        section.close()?;
+
+        changed = loop {
+            if let Some(paths) = debouncer.poll(Instant::now()) {
+                break paths;
+            }
+
+            let message = match debouncer.deadline() {
+                Some(deadline) => {
+                    tokio::select! {
+                        message = rx.recv() => message,
+                        _ = tokio::time::sleep_until(tokio::time::Instant::from_std(deadline)) => continue,
+                    }
+                }
+                None => rx.recv().await,
+            };
+
+            if !handle(&mut debouncer, message)? {
+                return Ok(ExitCode::Success);
+            }
+        };
+    }
+}
+
+/// Report an error without exiting the watch loop.
+fn report(io: &mut Io<'_>, error: &anyhow::Error) -> Result<()> {
+    let o = io.with_color(Stream::Stdout, Color::Error)?;
+    super::format_errors(o, error)?;
+    o.close()?;
+    Ok(())
+}
+
+/// Handle a single message, returning `false` if the watch loop should exit.
+fn handle(debouncer: &mut Debouncer, message: Option<Message>) -> Result<bool> {
+    match message {
+        Some(Message::Event(event)) => {
+            let now = Instant::now();
+
+            for path in event?.paths {
+                debouncer.push(now, path);
+            }
+
+            Ok(true)
+        }
+        Some(Message::Exit) | None => Ok(false),
+    }
+}
+
+/// Spawn a thread which waits for Ctrl-C.
+///
+/// This is done on a separate thread since a busy script would otherwise
+/// prevent the signal from being observed.
+fn spawn_ctrl_c(signal: Arc<Signal>, tx: mpsc::UnboundedSender<Message>) -> Result<()> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+
+    std::thread::spawn(move || {
+        runtime.block_on(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                signal.exit.store(true, Ordering::Release);
+                _ = tx.send(Message::Exit);
+            }
+        });
+    });
+
+    Ok(())
+}
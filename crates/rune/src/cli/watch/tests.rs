@@ -0,0 +1,172 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use notify::event::{AccessKind, CreateKind, ModifyKind};
+use notify::{Event, EventKind};
+
+use crate::alloc::prelude::*;
+use crate::alloc::{self, String, Vec};
+use crate::cli::EntryPoint;
+use crate::workspace::{Manifest, Package};
+
+use super::{collect_paths, is_manifest, is_relevant, Debouncer};
+
+fn package(name: &str, root: &str) -> alloc::Result<Package> {
+    Ok(Package {
+        name: String::try_from(name)?,
+        version: semver::Version::new(0, 1, 0),
+        authors: Vec::new(),
+        description: None,
+        license: None,
+        root: Some(PathBuf::from(root)),
+        auto_bins: true,
+        auto_tests: true,
+        auto_examples: true,
+        auto_benches: true,
+        dependencies: Vec::new(),
+        libraries: Vec::new(),
+    })
+}
+
+fn entry(path: &str) -> EntryPoint<'static> {
+    EntryPoint::Path(PathBuf::from(path), false)
+}
+
+#[test]
+fn debounce_waits_for_quiet_period() {
+    let delay = Duration::from_millis(100);
+    let start = Instant::now();
+
+    let mut debouncer = Debouncer::new(delay);
+    assert!(debouncer.deadline().is_none());
+    assert!(debouncer.poll(start).is_none());
+
+    debouncer.push(start, PathBuf::from("a.rn"));
+    assert_eq!(debouncer.deadline(), Some(start + delay));
+    assert!(debouncer.poll(start + delay / 2).is_none());
+
+    // A second change pushes back the deadline.
+    debouncer.push(start + delay / 2, PathBuf::from("b.rn"));
+    debouncer.push(start + delay / 2, PathBuf::from("a.rn"));
+    assert!(debouncer.poll(start + delay).is_none());
+
+    let paths = debouncer.poll(start + delay / 2 + delay).unwrap();
+    let paths = paths
+        .iter()
+        .map(PathBuf::as_path)
+        .collect::<std::vec::Vec<_>>();
+    assert_eq!(paths, [Path::new("a.rn"), Path::new("b.rn")]);
+
+    assert!(debouncer.deadline().is_none());
+    assert!(debouncer.poll(start + delay * 10).is_none());
+}
+
+#[test]
+fn relevant_events() {
+    let modify = Event::new(EventKind::Modify(ModifyKind::Any)).add_path(PathBuf::from("main.rn"));
+    assert!(is_relevant(&modify));
+
+    let create = Event::new(EventKind::Create(CreateKind::File))
+        .add_path(PathBuf::from("target/out.txt"))
+        .add_path(PathBuf::from("src/lib.rn"));
+    assert!(is_relevant(&create));
+
+    let other = Event::new(EventKind::Modify(ModifyKind::Any)).add_path(PathBuf::from("notes.md"));
+    assert!(!is_relevant(&other));
+
+    let access = Event::new(EventKind::Access(AccessKind::Any)).add_path(PathBuf::from("main.rn"));
+    assert!(!is_relevant(&access));
+
+    let manifest = Event::new(EventKind::Modify(ModifyKind::Any))
+        .add_path(PathBuf::from("crates/a/Rune.toml"));
+    assert!(is_relevant(&manifest));
+    assert!(is_manifest(Path::new("crates/a/Rune.toml")));
+    assert!(!is_manifest(Path::new("crates/a/Cargo.toml")));
+}
+
+#[test]
+fn collect_entry_paths() {
+    let manifest = Manifest::default();
+
+    let entries = [
+        entry("main.rn"),
+        entry("scripts/a.rn"),
+        entry("scripts/b.rn"),
+        entry("scripts/nested/c.rn"),
+        entry("other/d.rn"),
+    ];
+
+    let paths = collect_paths(None, &manifest, &entries);
+    let paths = paths
+        .iter()
+        .map(PathBuf::as_path)
+        .collect::<std::vec::Vec<_>>();
+    assert_eq!(paths, [Path::new(".")]);
+
+    let entries = [
+        entry("scripts/a.rn"),
+        entry("scripts/nested/c.rn"),
+        entry("other/d.rn"),
+    ];
+
+    let paths = collect_paths(None, &manifest, &entries);
+    let paths = paths
+        .iter()
+        .map(PathBuf::as_path)
+        .collect::<std::vec::Vec<_>>();
+    assert_eq!(paths, [Path::new("other"), Path::new("scripts")]);
+}
+
+#[test]
+fn collect_workspace_paths() -> alloc::Result<()> {
+    let mut manifest = Manifest::default();
+    manifest.packages.try_push(package("a", "crates/a")?)?;
+    manifest.packages.try_push(package("b", "crates/b")?)?;
+    manifest
+        .packages
+        .try_push(package("c", "crates/a/nested")?)?;
+
+    let entries = [entry("crates/a/src/main.rn"), entry("tools/x.rn")];
+
+    let paths = collect_paths(None, &manifest, &entries);
+    let paths = paths
+        .iter()
+        .map(PathBuf::as_path)
+        .collect::<std::vec::Vec<_>>();
+
+    assert_eq!(
+        paths,
+        [
+            Path::new("crates/a"),
+            Path::new("crates/b"),
+            Path::new("tools")
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn collect_workspace_root() -> alloc::Result<()> {
+    let mut manifest = Manifest::default();
+    manifest.packages.try_push(package("a", "crates/a")?)?;
+    manifest.packages.try_push(package("b", "crates/b")?)?;
+
+    // The root of the workspace contains the manifest, which must be watched
+    // even if it isn't the root of any package.
+    let paths = collect_paths(Some(Path::new(".")), &manifest, &[]);
+    let paths = paths
+        .iter()
+        .map(PathBuf::as_path)
+        .collect::<std::vec::Vec<_>>();
+    assert_eq!(paths, [Path::new(".")]);
+
+    let paths = collect_paths(Some(Path::new("crates")), &manifest, &[]);
+    let paths = paths
+        .iter()
+        .map(PathBuf::as_path)
+        .collect::<std::vec::Vec<_>>();
+    assert_eq!(paths, [Path::new("crates")]);
+
+    Ok(())
+}
@@ -8,6 +8,7 @@ use crate::alloc::prelude::*;
 use crate::alloc::{hash_map, HashMap};
 use crate::ast::{Span, Spanned};
 use crate::compile::{self, Location};
+use crate::hir;
 use crate::runtime::{Inst, InstAddress, Label, Output};
 use crate::{Hash, SourceId};
 
@@ -34,6 +35,19 @@ pub(crate) enum AssemblyInst {
     },
 }
 
+/// A variable which is in scope for a range of instructions.
+#[derive(Debug, TryClone)]
+pub(crate) struct AssemblyVariable {
+    /// The variable.
+    pub(crate) name: hir::Variable,
+    /// The address of the variable.
+    pub(crate) addr: InstAddress,
+    /// The index of the first instruction in which the variable is in scope.
+    pub(crate) start: usize,
+    /// The index of the instruction at which the variable goes out of scope.
+    pub(crate) end: usize,
+}
+
 /// Helper structure to build instructions and maintain certain invariants.
 #[derive(Debug, TryClone, Default)]
 pub(crate) struct Assembly {
@@ -49,6 +63,8 @@ pub(crate) struct Assembly {
     pub(crate) label_count: usize,
     /// The collection of functions required by this assembly.
     pub(crate) required_functions: HashMap<Hash, Vec<(Span, SourceId)>>,
+    /// Variables and the range of instructions in which they are in scope.
+    pub(crate) variables: Vec<AssemblyVariable>,
 }
 
 impl Assembly {
@@ -61,6 +77,7 @@ impl Assembly {
             comments: Default::default(),
             label_count,
             required_functions: Default::default(),
+            variables: Default::default(),
         }
     }
 
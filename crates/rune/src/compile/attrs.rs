@@ -6,7 +6,9 @@ use crate::alloc::{Vec, VecDeque};
 use crate::ast;
 use crate::ast::{LitStr, Spanned};
 use crate::compile::{self, ErrorKind};
+use crate::diagnostics::LintLevel;
 use crate::parse::{self, Parse, Resolve, ResolveContext};
+use crate::runtime;
 
 /// Helper for parsing internal attributes.
 pub(crate) struct Parser {
@@ -216,6 +218,60 @@ impl Attribute for Bench {
     const PATH: &'static str = "bench";
 }
 
+/// The `#[protocol(..)]` attribute, which registers an instance function as
+/// the implementation of a protocol for the type of its impl block.
+#[derive(Parse)]
+pub(crate) struct Protocol {
+    /// The name of the protocol.
+    pub(crate) name: ast::Parenthesized<ast::Ident, T![,]>,
+}
+
+impl Protocol {
+    /// Resolve the protocol this attribute refers to.
+    pub(crate) fn resolve(
+        &self,
+        cx: ResolveContext<'_>,
+        span: &dyn Spanned,
+    ) -> compile::Result<&'static runtime::Protocol> {
+        let mut it = self.name.iter();
+
+        let (Some((ident, _)), None) = (it.next(), it.next()) else {
+            return Err(compile::Error::msg(
+                span,
+                "Expected exactly one protocol name, like `#[protocol(display_fmt)]`",
+            ));
+        };
+
+        let protocol = match ident.resolve(cx)? {
+            "display_fmt" => &runtime::Protocol::DISPLAY_FMT,
+            "debug_fmt" => &runtime::Protocol::DEBUG_FMT,
+            "partial_eq" => &runtime::Protocol::PARTIAL_EQ,
+            "eq" => &runtime::Protocol::EQ,
+            "partial_cmp" => &runtime::Protocol::PARTIAL_CMP,
+            "cmp" => &runtime::Protocol::CMP,
+            "hash" => &runtime::Protocol::HASH,
+            "into_iter" => &runtime::Protocol::INTO_ITER,
+            "index_get" => &runtime::Protocol::INDEX_GET,
+            "index_set" => &runtime::Protocol::INDEX_SET,
+            name => {
+                return Err(compile::Error::new(
+                    ident,
+                    ErrorKind::UnsupportedProtocol {
+                        name: name.try_into()?,
+                    },
+                ));
+            }
+        };
+
+        Ok(protocol)
+    }
+}
+
+impl Attribute for Protocol {
+    /// Must match the specified name.
+    const PATH: &'static str = "protocol";
+}
+
 #[derive(Parse)]
 pub(crate) struct Doc {
     /// The `=` token.
@@ -229,3 +285,53 @@ impl Attribute for Doc {
     /// Must match the specified name.
     const PATH: &'static str = "doc";
 }
+
+/// An attribute which sets the level of a collection of warnings, like
+/// `#[allow(unused_variable)]`.
+pub(crate) trait Lint: Attribute + Parse {
+    /// The level which the warnings are set to.
+    const LEVEL: LintLevel;
+
+    /// The names of the warnings.
+    fn lints(&self) -> &ast::Parenthesized<ast::Ident, T![,]>;
+}
+
+macro_rules! lint {
+    ($(#[doc = $doc:literal])* $name:ident, $path:literal, $level:ident) => {
+        $(#[doc = $doc])*
+        #[derive(Parse)]
+        pub(crate) struct $name {
+            /// The names of the warnings.
+            pub(crate) lints: ast::Parenthesized<ast::Ident, T![,]>,
+        }
+
+        impl Attribute for $name {
+            /// Must match the specified name.
+            const PATH: &'static str = $path;
+        }
+
+        impl Lint for $name {
+            const LEVEL: LintLevel = LintLevel::$level;
+
+            #[inline]
+            fn lints(&self) -> &ast::Parenthesized<ast::Ident, T![,]> {
+                &self.lints
+            }
+        }
+    };
+}
+
+lint!(
+    /// The `#[allow(..)]` attribute.
+    Allow, "allow", Allow
+);
+
+lint!(
+    /// The `#[warn(..)]` attribute.
+    Warn, "warn", Warn
+);
+
+lint!(
+    /// The `#[deny(..)]` attribute.
+    Deny, "deny", Deny
+);
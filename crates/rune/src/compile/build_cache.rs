@@ -0,0 +1,526 @@
+use crate::alloc::prelude::*;
+use crate::alloc::{self, HashMap, Vec};
+use crate::ast::{self, Span, Spanned};
+use crate::compile::{Context, Options, Recording};
+use crate::query::Effect;
+use crate::runtime::unit::DefaultStorage;
+use crate::runtime::Unit;
+use crate::{Hash, ItemBuf, Source, SourceId, Sources};
+
+/// A cache which can be reused across builds to avoid redoing work for
+/// sources which haven't changed.
+///
+/// If none of the sources have changed since the last build, the unit it
+/// produced is reused as-is, skipping indexing, lowering and assembly
+/// entirely. Otherwise the parsed syntax tree of every unchanged source is
+/// reused, and so are the compiled functions of every item declared in an
+/// unchanged source, skipping lowering and assembly for them.
+///
+/// Parsed sources are cached by their [`SourceId`] and [content hash], since
+/// the syntax tree refers back to the source it was parsed from.
+///
+/// Compiled items are cached by the key of the source they're declared in and
+/// by the *interface* of all sources, which is their content excluding the
+/// bodies of functions. So editing the body of a function only causes the
+/// items in its own source to be compiled again, while any other edit causes
+/// every item to be compiled again. Indexing is still performed for all
+/// sources, since the declarations in every source are needed to resolve
+/// names.
+///
+/// A unit is only cached if its build didn't produce any diagnostics, didn't
+/// load any additional sources through the source loader, and is only reused
+/// if no [`CompileVisitor`] is used. The cache is invalidated entirely if the
+/// [`Options`] or the [`Context`] used for the build changes.
+///
+/// See [`Build::with_cache`].
+///
+/// [content hash]: crate::Source::content_hash
+/// [`Build::with_cache`]: crate::Build::with_cache
+/// [`CompileVisitor`]: crate::compile::CompileVisitor
+///
+/// # Examples
+///
+/// ```
+/// use rune::compile::BuildCache;
+/// use rune::{Source, Sources};
+///
+/// let mut cache = BuildCache::new();
+///
+/// for _ in 0..2 {
+///     let mut sources = Sources::new();
+///     sources.insert(Source::new("main", "pub fn main() { 42 }")?)?;
+///
+///     let _ = rune::prepare(&mut sources).with_cache(&mut cache).build()?;
+/// }
+///
+/// assert_eq!(cache.misses(), 1);
+/// assert_eq!(cache.hits(), 1);
+/// assert_eq!(cache.reused(), 1);
+/// assert_eq!(cache.item_misses(), 1);
+/// # Ok::<_, rune::support::Error>(())
+/// ```
+pub struct BuildCache<S = DefaultStorage> {
+    /// The fingerprint of the options and context the cache was populated
+    /// with.
+    fingerprint: Option<Hash>,
+    /// Cached files by source id.
+    pub(crate) files: FileCache,
+    /// Cached items by item hash.
+    pub(crate) items: ItemCache,
+    /// The unit produced by the last build.
+    unit: Option<CachedUnit<S>>,
+    /// The number of times the unit of a previous build was reused.
+    reused: usize,
+}
+
+impl<S> Default for BuildCache<S> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            fingerprint: None,
+            files: FileCache::default(),
+            items: ItemCache::default(),
+            unit: None,
+            reused: 0,
+        }
+    }
+}
+
+/// Parsed files from previous builds.
+#[derive(Default)]
+pub(crate) struct FileCache {
+    files: HashMap<SourceId, CachedFile>,
+    /// The number of times a cached entry was used.
+    hits: usize,
+    /// The number of times a source had to be processed from scratch.
+    misses: usize,
+}
+
+struct CachedFile {
+    /// The content hash of the source the file was parsed from.
+    hash: Hash,
+    /// The hash of the source excluding the bodies of functions.
+    interface: Hash,
+    /// The parsed file.
+    file: ast::File,
+    /// If the entry was used during the current build.
+    used: bool,
+}
+
+/// Compiled items from previous builds.
+#[derive(Default)]
+pub(crate) struct ItemCache {
+    items: HashMap<Hash, CachedItem>,
+    /// The key of every source in the current build, and the interface of all
+    /// of them. This is only set if items can be reused by the current build.
+    current: Option<(Vec<Hash>, Hash)>,
+    /// The number of times a compiled item was reused.
+    hits: usize,
+    /// The number of times an item had to be compiled.
+    misses: usize,
+}
+
+/// An item compiled by a previous build.
+pub(crate) struct CachedItem {
+    /// The key of the source the item is declared in.
+    source: Hash,
+    /// The interface of all sources when the item was compiled.
+    interface: Hash,
+    /// Whether the item was used.
+    is_used: bool,
+    /// Effects on the query engine while compiling the item.
+    pub(crate) effects: Vec<Effect<ItemBuf>>,
+    /// Everything added to the unit while compiling the item.
+    pub(crate) recording: Recording,
+    /// If the entry was used during the current build.
+    used: bool,
+}
+
+struct CachedUnit<S> {
+    /// The key of every source the unit was built from, indexed by source id.
+    sources: Vec<Hash>,
+    /// The unit which was built.
+    unit: Unit<S>,
+}
+
+impl<S> BuildCache<S> {
+    /// Construct a new empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of times a cached entry was reused.
+    pub fn hits(&self) -> usize {
+        self.files.hits
+    }
+
+    /// The number of times a source had to be processed since it wasn't
+    /// cached or had changed.
+    pub fn misses(&self) -> usize {
+        self.files.misses
+    }
+
+    /// The number of builds which reused the unit of a previous build since
+    /// none of their sources had changed.
+    pub fn reused(&self) -> usize {
+        self.reused
+    }
+
+    /// The number of times the compiled functions of an item were reused,
+    /// skipping lowering and assembly for it.
+    pub fn item_hits(&self) -> usize {
+        self.items.hits
+    }
+
+    /// The number of times an item had to be compiled since it wasn't cached,
+    /// or since its source or the interface of any source had changed.
+    pub fn item_misses(&self) -> usize {
+        self.items.misses
+    }
+
+    /// Test if the given source is currently cached with the given id, in
+    /// which case it will be reused by the next build.
+    pub fn contains(&self, id: SourceId, source: &Source) -> bool {
+        self.files.contains(id, source)
+    }
+
+    /// Clear the cache.
+    pub fn clear(&mut self) {
+        self.fingerprint = None;
+        self.files.files.clear();
+        self.items.items.clear();
+        self.unit = None;
+    }
+
+    /// Prepare the cache for a build with the given options and context,
+    /// invalidating it if they've changed since the last build.
+    pub(crate) fn begin(&mut self, options: &Options, context: &Context) -> alloc::Result<()> {
+        let fingerprint = Hash::new(
+            options.fingerprint()?.into_inner() ^ context.fingerprint().into_inner().rotate_left(1),
+        );
+
+        if self.fingerprint != Some(fingerprint) {
+            self.clear();
+            self.fingerprint = Some(fingerprint);
+        }
+
+        for file in self.files.files.values_mut() {
+            file.used = false;
+        }
+
+        for item in self.items.items.values_mut() {
+            item.used = false;
+        }
+
+        self.items.current = None;
+        Ok(())
+    }
+
+    /// Evict entries which weren't used by the last build, since their
+    /// sources have been removed.
+    pub(crate) fn finish(&mut self) {
+        self.files.files.retain(|_, file| file.used);
+        self.items.items.retain(|_, item| item.used);
+        self.items.current = None;
+    }
+
+    /// Get a copy of the cached unit if none of the given sources have
+    /// changed since it was built.
+    pub(crate) fn unit(&mut self, sources: &Sources) -> alloc::Result<Option<Unit<S>>>
+    where
+        S: TryClone,
+    {
+        let Some(cached) = &self.unit else {
+            return Ok(None);
+        };
+
+        let keys = keys(sources)?;
+
+        if cached.sources != keys {
+            return Ok(None);
+        }
+
+        let unit = cached.unit.try_clone()?;
+
+        self.reused += 1;
+        self.files.hits += keys.len();
+
+        for file in self.files.files.values_mut() {
+            file.used = true;
+        }
+
+        for item in self.items.items.values_mut() {
+            item.used = true;
+        }
+
+        Ok(Some(unit))
+    }
+
+    /// Store the unit built from the given sources.
+    pub(crate) fn insert_unit(&mut self, sources: &Sources, unit: &Unit<S>) -> alloc::Result<()>
+    where
+        S: TryClone,
+    {
+        self.unit = Some(CachedUnit {
+            sources: keys(sources)?,
+            unit: unit.try_clone()?,
+        });
+
+        Ok(())
+    }
+
+    /// Forget the cached unit, since the last build couldn't be cached.
+    pub(crate) fn remove_unit(&mut self) {
+        self.unit = None;
+    }
+}
+
+impl FileCache {
+    /// Test if the given source is cached with the given id.
+    pub(crate) fn contains(&self, id: SourceId, source: &Source) -> bool {
+        self.files
+            .get(&id)
+            .is_some_and(|f| f.hash == source.content_hash())
+    }
+
+    /// Get a copy of the cached file for the given source if it's unchanged.
+    pub(crate) fn get(
+        &mut self,
+        id: SourceId,
+        source: &Source,
+    ) -> alloc::Result<Option<ast::File>> {
+        if let Some(cached) = self.files.get_mut(&id) {
+            if cached.hash == source.content_hash() {
+                cached.used = true;
+                self.hits += 1;
+                return Ok(Some(cached.file.try_clone()?));
+            }
+        }
+
+        self.misses += 1;
+        Ok(None)
+    }
+
+    /// Store the parsed file for the given source.
+    pub(crate) fn insert(
+        &mut self,
+        id: SourceId,
+        source: &Source,
+        file: &ast::File,
+    ) -> alloc::Result<()> {
+        let cached = CachedFile {
+            hash: source.content_hash(),
+            interface: interface(source, file)?,
+            file: file.try_clone()?,
+            used: true,
+        };
+
+        self.files.try_insert(id, cached)?;
+        Ok(())
+    }
+
+    /// Get the interface of the given source, if it's cached.
+    fn interface(&self, id: SourceId, source: &Source) -> Option<Hash> {
+        let cached = self.files.get(&id)?;
+
+        if cached.hash != source.content_hash() {
+            return None;
+        }
+
+        Some(cached.interface)
+    }
+}
+
+impl ItemCache {
+    /// Prepare to reuse items in a build of the given sources, which have all
+    /// been parsed into the given file cache.
+    ///
+    /// Items are not reused if any source is missing from the file cache.
+    pub(crate) fn prepare(&mut self, sources: &Sources, files: &FileCache) -> alloc::Result<()> {
+        self.current = None;
+
+        let mut interface = 0u64;
+
+        for id in sources.source_ids() {
+            let Some(source) = sources.get(id) else {
+                continue;
+            };
+
+            let Some(file) = files.interface(id, source) else {
+                return Ok(());
+            };
+
+            let key = key(sources, id, source, file);
+            interface = interface.rotate_left(5) ^ key.into_inner();
+        }
+
+        self.current = Some((keys(sources)?, Hash::new(interface)));
+        Ok(())
+    }
+
+    /// Test if items can be reused and recorded by the current build.
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.current.is_some()
+    }
+
+    /// Stop reusing and recording items for the rest of the current build.
+    pub(crate) fn disable(&mut self) {
+        self.current = None;
+    }
+
+    /// Get the cached item with the given hash, if it was compiled from the
+    /// same source with the same interface and usage as in the current build.
+    pub(crate) fn get(
+        &mut self,
+        hash: Hash,
+        source_id: SourceId,
+        is_used: bool,
+    ) -> Option<&CachedItem> {
+        let (keys, interface) = self.current.as_ref()?;
+        let source = keys.get(source_id.into_index()).copied();
+
+        match self.items.get_mut(&hash) {
+            Some(cached)
+                if Some(cached.source) == source
+                    && cached.interface == *interface
+                    && cached.is_used == is_used =>
+            {
+                cached.used = true;
+                self.hits += 1;
+                Some(cached)
+            }
+            _ => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Store an item compiled by the current build.
+    pub(crate) fn insert(
+        &mut self,
+        hash: Hash,
+        source_id: SourceId,
+        is_used: bool,
+        effects: Vec<Effect<ItemBuf>>,
+        recording: Recording,
+    ) -> alloc::Result<()> {
+        let Some((keys, interface)) = &self.current else {
+            return Ok(());
+        };
+
+        let Some(&source) = keys.get(source_id.into_index()) else {
+            return Ok(());
+        };
+
+        let cached = CachedItem {
+            source,
+            interface: *interface,
+            is_used,
+            effects,
+            recording,
+            used: true,
+        };
+
+        self.items.try_insert(hash, cached)?;
+        Ok(())
+    }
+}
+
+/// Compute the interface of a source, which is a hash of its content excluding
+/// the bodies of functions.
+///
+/// The body of a function is only excluded if it can't affect how other items
+/// are compiled, so the bodies of constant functions and functions which
+/// declare items or call macros are included.
+fn interface(source: &Source, file: &ast::File) -> alloc::Result<Hash> {
+    let mut bodies = Vec::new();
+    function_bodies(&file.items, &mut bodies)?;
+    bodies.sort_by_key(|span| span.start);
+
+    let text = source.as_str();
+    let mut hash = 0u64;
+    let mut at = 0;
+
+    for span in bodies {
+        let start = span.start.into_usize();
+
+        if let Some(segment) = text.get(at..start) {
+            hash = hash.rotate_left(5) ^ Hash::static_bytes(segment.as_bytes()).into_inner();
+        }
+
+        at = span.end.into_usize();
+    }
+
+    if let Some(segment) = text.get(at..) {
+        hash = hash.rotate_left(5) ^ Hash::static_bytes(segment.as_bytes()).into_inner();
+    }
+
+    Ok(Hash::new(hash))
+}
+
+fn function_bodies(items: &[(ast::Item, Option<T![;]>)], out: &mut Vec<Span>) -> alloc::Result<()> {
+    for (item, _) in items {
+        match item {
+            ast::Item::Fn(f) => function_body(f, out)?,
+            ast::Item::Impl(item) => {
+                for f in &item.functions {
+                    function_body(f, out)?;
+                }
+            }
+            ast::Item::Mod(item) => {
+                if let ast::ItemModBody::InlineBody(body) = &item.body {
+                    function_bodies(&body.file.items, out)?;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn function_body(f: &ast::ItemFn, out: &mut Vec<Span>) -> alloc::Result<()> {
+    if f.const_token.is_some() {
+        return Ok(());
+    }
+
+    let affects_others = f.body.statements.iter().any(|stmt| match stmt {
+        ast::Stmt::Item(..) => true,
+        ast::Stmt::Expr(ast::Expr::MacroCall(..)) => true,
+        ast::Stmt::Semi(semi) => matches!(semi.expr, ast::Expr::MacroCall(..)),
+        _ => false,
+    });
+
+    if affects_others {
+        return Ok(());
+    }
+
+    out.try_push(f.body.span())
+}
+
+/// Compute a key for every source, which changes if the name, crate or
+/// content of a source changes.
+fn keys(sources: &Sources) -> alloc::Result<Vec<Hash>> {
+    let mut keys = Vec::new();
+
+    for id in sources.source_ids() {
+        let Some(source) = sources.get(id) else {
+            continue;
+        };
+
+        keys.try_push(key(sources, id, source, source.content_hash()))?;
+    }
+
+    Ok(keys)
+}
+
+/// Compute the key of a single source with the given hash of its content.
+fn key(sources: &Sources, id: SourceId, source: &Source, content: Hash) -> Hash {
+    let name = Hash::static_bytes(source.name().as_bytes());
+    let krate = Hash::static_bytes(sources.crate_name(id).unwrap_or_default().as_bytes());
+
+    Hash::new(
+        content.into_inner() ^ name.into_inner().rotate_left(1) ^ krate.into_inner().rotate_left(2),
+    )
+}
@@ -3,14 +3,14 @@ use crate::alloc::prelude::*;
 use crate::ast::{Span, Spanned};
 use crate::compile::v1;
 use crate::compile::{
-    self, Assembly, CompileVisitor, Context, ErrorKind, Location, Options, Pool, Prelude,
-    SourceLoader, UnitBuilder,
+    self, Assembly, CompileVisitor, Context, ErrorKind, FileCache, ItemCache, ItemMeta, Location,
+    Options, Pool, Prelude, SourceLoader, UnitBuilder,
 };
 use crate::hir;
-use crate::indexing::FunctionAst;
+use crate::indexing::{Function, FunctionAst};
 use crate::macros::Storage;
 use crate::parse::Resolve;
-use crate::query::{Build, BuildEntry, Query, SecondaryBuild, Used};
+use crate::query::{Build, BuildEntry, Effect, Query, SecondaryBuild, Used};
 use crate::runtime::unit::UnitEncoder;
 use crate::shared::{Consts, Gen};
 use crate::worker::{LoadFileKind, Task, Worker};
@@ -27,6 +27,9 @@ pub(crate) fn compile(
     diagnostics: &mut Diagnostics,
     source_loader: &mut dyn SourceLoader,
     options: &Options,
+    files: Option<&mut FileCache>,
+    mut items: Option<&mut ItemCache>,
+    is_cancelled: &dyn Fn() -> bool,
     unit_storage: &mut dyn UnitEncoder,
 ) -> alloc::Result<()> {
     // Shared id generator.
@@ -35,6 +38,7 @@ pub(crate) fn compile(
     let mut consts = Consts::default();
     let mut storage = Storage::default();
     let mut inner = Default::default();
+    let source_count = sources.source_ids().count();
 
     let q = Query::new(
         unit,
@@ -54,7 +58,7 @@ pub(crate) fn compile(
     );
 
     // The worker queue.
-    let mut worker = Worker::new(q);
+    let mut worker = Worker::new(q, files);
 
     // Queue up the initial sources to be loaded.
     for source_id in worker.q.sources.source_ids() {
@@ -83,18 +87,41 @@ pub(crate) fn compile(
 
     worker.index()?;
 
-    if worker.q.diagnostics.has_error() {
+    if worker.q.diagnostics.has_error() || is_cancelled() {
         return Ok(());
     }
 
+    // Compiled items can only be reused if every source has been parsed through
+    // the cache, since that's where their interfaces are computed. Reusing an
+    // item also wouldn't reproduce any diagnostics, nor notice changes to
+    // sources loaded through the source loader.
+    if let Some(items) = items.as_deref_mut() {
+        match worker.cache.as_deref() {
+            Some(files)
+                if worker.q.diagnostics.has_warnings_enabled()
+                    && worker.q.sources.source_ids().count() == source_count =>
+            {
+                items.prepare(worker.q.sources, files)?;
+            }
+            _ => {
+                items.disable();
+            }
+        }
+    }
+
     loop {
         while let Some(entry) = worker.q.next_build_entry() {
+            if is_cancelled() {
+                return Ok(());
+            }
+
             tracing::trace!(item = ?worker.q.pool.item(entry.item_meta.item), "next build entry");
             let source_id = entry.item_meta.location.source_id;
 
             let task = CompileBuildEntry {
                 options,
                 q: worker.q.borrow(),
+                items: items.as_deref_mut(),
             };
 
             if let Err(error) = task.compile(entry, unit_storage) {
@@ -119,6 +146,7 @@ pub(crate) fn compile(
 struct CompileBuildEntry<'a, 'arena> {
     options: &'a Options,
     q: Query<'a, 'arena>,
+    items: Option<&'a mut ItemCache>,
 }
 
 impl<'arena> CompileBuildEntry<'_, 'arena> {
@@ -139,6 +167,7 @@ impl<'arena> CompileBuildEntry<'_, 'arena> {
             options: self.options,
             select_branches: Vec::new(),
             drop: Vec::new(),
+            try_kind: None,
         })
     }
 
@@ -148,8 +177,6 @@ impl<'arena> CompileBuildEntry<'_, 'arena> {
         entry: BuildEntry,
         unit_storage: &mut dyn UnitEncoder,
     ) -> compile::Result<()> {
-        use self::v1::assemble;
-
         let BuildEntry { item_meta, build } = entry;
 
         let location = item_meta.location;
@@ -173,184 +200,69 @@ impl<'arena> CompileBuildEntry<'_, 'arena> {
                         item_meta.location.span,
                         ErrorKind::MissingItem {
                             item: self.q.pool.item(item_meta.item).try_to_owned()?,
+                            did_you_mean: None,
                         },
                     ));
                 }
             }
             Build::Function(f) => {
-                let mut asm = self.q.unit.new_assembly(location);
+                let hash = self.q.pool.item_type_hash(item_meta.item);
+                let is_used = self.q.is_used(&item_meta);
 
-                tracing::trace!("function: {}", self.q.pool.item(item_meta.item));
+                if let Some(items) = &mut self.items {
+                    if let Some(cached) = items.get(hash, location.source_id, is_used) {
+                        tracing::trace!("cached: {}", self.q.pool.item(item_meta.item));
 
-                // For instance functions, we are required to know the type hash
-                // of the type it is associated with to perform the proper
-                // naming of the function.
-                let type_hash = if let Some(item) = f.impl_item.filter(|_| f.is_instance) {
-                    Some(self.q.pool.item_type_hash(item))
-                } else {
-                    None
-                };
-
-                let debug_args = format_ast_args(self.q.sources, location, false, &f.args)?;
-                let span: &dyn Spanned = &f.ast;
-
-                let arena = hir::Arena::new();
-                let mut secondary_builds = Vec::new();
-
-                let mut cx = hir::Ctxt::with_query(
-                    &arena,
-                    self.q.borrow(),
-                    item_meta.location.source_id,
-                    &mut secondary_builds,
-                )?;
-
-                let hir = match &f.ast {
-                    FunctionAst::Bare(node) => {
-                        #[cfg(feature = "std")]
-                        if cx.q.options.print_tree {
-                            node.print_with_sources(
-                                format_args!("Bare function {}", cx.q.pool.item(item_meta.item)),
-                                cx.q.sources,
-                            )?;
+                        for effect in &cached.effects {
+                            self.q.replay(&location, effect)?;
                         }
 
-                        node.parse(|p| hir::lowering2::bare(&mut cx, p))?
+                        self.q
+                            .unit
+                            .replay(&cached.recording, self.q.gen, unit_storage)?;
+                        return Ok(());
                     }
-                    FunctionAst::Node(node, _) => {
-                        #[cfg(feature = "std")]
-                        if cx.q.options.print_tree {
-                            node.print_with_sources(
-                                format_args!("Node function {}", cx.q.pool.item(item_meta.item)),
-                                cx.q.sources,
-                            )?;
-                        }
+                }
 
-                        node.parse(|p| hir::lowering2::item_fn(&mut cx, p, f.impl_item.is_some()))?
-                    }
-                    FunctionAst::Item(ast, _) => hir::lowering::item_fn(&mut cx, ast)?,
-                    FunctionAst::Empty(ast, span) => hir::lowering::empty_fn(&mut cx, ast, &span)?,
-                };
+                let record = self.items.as_ref().is_some_and(|items| items.is_enabled());
+                let reported = self.q.diagnostics.diagnostics().len();
 
-                let count = hir.args.len();
+                if record {
+                    self.q.begin_recording();
+                }
 
-                let mut scopes = self::v1::Scopes::new(location.source_id)?;
-                let mut c = self.compiler1(location, span, &mut asm, &mut scopes)?;
-                assemble::fn_from_item_fn(&mut c, &hir, f.is_instance)?;
-                let size = c.scopes.size();
+                let result = self.function(item_meta, f, unit_storage);
 
-                if !self.q.is_used(&item_meta) {
-                    self.q
-                        .diagnostics
-                        .not_used(location.source_id, span, None)?;
+                let recorded = if record {
+                    self.q.finish_recording()
                 } else {
-                    let instance = match (type_hash, &f.ast) {
-                        (Some(type_hash), FunctionAst::Item(_, name)) => {
-                            let name = name.resolve(resolve_context!(self.q))?;
-                            Some((type_hash, name))
-                        }
-                        (Some(type_hash), FunctionAst::Node(_, Some(name))) => {
-                            let name = name.resolve(resolve_context!(self.q))?;
-                            Some((type_hash, name))
-                        }
-                        _ => None,
-                    };
-
-                    let item = self.q.pool.item(item_meta.item);
-
-                    self.q.unit.new_function(
-                        location,
-                        item,
-                        instance,
-                        count,
-                        None,
-                        asm,
-                        f.call,
-                        debug_args,
-                        unit_storage,
-                        size,
-                    )?;
-                }
+                    None
+                };
 
-                for build in secondary_builds {
-                    let item_meta = build.item_meta;
-
-                    let mut asm = self.q.unit.new_assembly(item_meta.location);
-
-                    match build.build {
-                        SecondaryBuild::Closure(c) => {
-                            tracing::trace!("closure: {}", self.q.pool.item(item_meta.item));
-
-                            let debug_args =
-                                format_hir_args(self.q.sources, location, true, c.hir.args.iter())?;
-
-                            let mut scopes = self::v1::Scopes::new(location.source_id)?;
-                            let mut cx = self.compiler1(location, c.hir, &mut asm, &mut scopes)?;
-                            assemble::expr_closure_secondary(&mut cx, c.hir)?;
-                            let size = cx.scopes.size();
-
-                            if !self.q.is_used(&item_meta) {
-                                self.q.diagnostics.not_used(
-                                    location.source_id,
-                                    &location.span,
-                                    None,
-                                )?;
-                            } else {
-                                let captures =
-                                    (!c.hir.captures.is_empty()).then_some(c.hir.captures.len());
-
-                                let args = c
-                                    .hir
-                                    .args
-                                    .len()
-                                    .saturating_add(usize::from(captures.is_some()));
-
-                                self.q.unit.new_function(
-                                    location,
-                                    self.q.pool.item(item_meta.item),
-                                    None,
-                                    args,
-                                    captures,
-                                    asm,
-                                    c.call,
-                                    debug_args,
-                                    unit_storage,
-                                    size,
-                                )?;
-                            }
-                        }
-                        SecondaryBuild::AsyncBlock(b) => {
-                            tracing::trace!("async block: {}", self.q.pool.item(item_meta.item));
-
-                            let mut scopes = self::v1::Scopes::new(location.source_id)?;
-                            let mut cx = self.compiler1(location, b.hir, &mut asm, &mut scopes)?;
-                            assemble::async_block_secondary(&mut cx, b.hir)?;
-                            let size = cx.scopes.size();
-
-                            if !self.q.is_used(&item_meta) {
-                                self.q.diagnostics.not_used(
-                                    location.source_id,
-                                    &location.span,
-                                    None,
-                                )?;
-                            } else {
-                                let args = b.hir.captures.len();
-
-                                self.q.unit.new_function(
-                                    location,
-                                    self.q.pool.item(item_meta.item),
-                                    None,
-                                    args,
-                                    None,
-                                    asm,
-                                    b.call,
-                                    Default::default(),
-                                    unit_storage,
-                                    size,
-                                )?;
-                            }
+                // Items which produced diagnostics can't be reused, since
+                // reusing them wouldn't reproduce the diagnostics.
+                if let (Ok(()), Some((effects, recording)), Some(items)) =
+                    (&result, recorded, &mut self.items)
+                {
+                    if self.q.diagnostics.diagnostics().len() == reported {
+                        let mut cached = Vec::try_with_capacity(effects.len())?;
+
+                        for effect in effects {
+                            cached.try_push(match effect {
+                                Effect::Query(item, used) => {
+                                    Effect::Query(self.q.pool.item(item).try_to_owned()?, used)
+                                }
+                                Effect::Used(item) => {
+                                    Effect::Used(self.q.pool.item(item).try_to_owned()?)
+                                }
+                            })?;
                         }
+
+                        items.insert(hash, location.source_id, is_used, cached, recording)?;
                     }
                 }
+
+                result?;
             }
             Build::Unused => {
                 tracing::trace!("unused: {}", self.q.pool.item(item_meta.item));
@@ -399,6 +311,7 @@ impl<'arena> CompileBuildEntry<'_, 'arena> {
                         location,
                         ErrorKind::MissingItem {
                             item: self.q.pool.item(item).try_to_owned()?,
+                            did_you_mean: None,
                         },
                     ));
                 }
@@ -420,6 +333,7 @@ impl<'arena> CompileBuildEntry<'_, 'arena> {
                         location.span,
                         ErrorKind::MissingItem {
                             item: self.q.pool.item(item_meta.item).try_to_owned()?,
+                            did_you_mean: None,
                         },
                     ));
                 };
@@ -434,6 +348,200 @@ impl<'arena> CompileBuildEntry<'_, 'arena> {
 
         Ok(())
     }
+
+    /// Compile a function and everything it declares.
+    fn function(
+        &mut self,
+        item_meta: ItemMeta,
+        f: Function,
+        unit_storage: &mut dyn UnitEncoder,
+    ) -> compile::Result<()> {
+        use self::v1::assemble;
+
+        let location = item_meta.location;
+        let mut asm = self.q.unit.new_assembly(location);
+
+        tracing::trace!("function: {}", self.q.pool.item(item_meta.item));
+
+        // For instance functions, we are required to know the type hash
+        // of the type it is associated with to perform the proper
+        // naming of the function.
+        let type_hash = if let Some(item) = f.impl_item.filter(|_| f.is_instance) {
+            Some(self.q.pool.item_type_hash(item))
+        } else {
+            None
+        };
+
+        let debug_args = format_ast_args(self.q.sources, location, false, &f.args)?;
+        let span: &dyn Spanned = &f.ast;
+
+        let arena = hir::Arena::new();
+        let mut secondary_builds = Vec::new();
+
+        let mut cx = hir::Ctxt::with_query(
+            &arena,
+            self.q.borrow(),
+            item_meta.location.source_id,
+            &mut secondary_builds,
+        )?;
+
+        cx.self_type = type_hash;
+
+        let hir = match &f.ast {
+            FunctionAst::Bare(node) => {
+                #[cfg(feature = "std")]
+                if cx.q.options.print_tree {
+                    node.print_with_sources(
+                        format_args!("Bare function {}", cx.q.pool.item(item_meta.item)),
+                        cx.q.sources,
+                    )?;
+                }
+
+                node.parse(|p| hir::lowering2::bare(&mut cx, p))?
+            }
+            FunctionAst::Node(node, _) => {
+                #[cfg(feature = "std")]
+                if cx.q.options.print_tree {
+                    node.print_with_sources(
+                        format_args!("Node function {}", cx.q.pool.item(item_meta.item)),
+                        cx.q.sources,
+                    )?;
+                }
+
+                node.parse(|p| hir::lowering2::item_fn(&mut cx, p, f.impl_item.is_some()))?
+            }
+            FunctionAst::Item(ast, _) => hir::lowering::item_fn(&mut cx, ast)?,
+            FunctionAst::Empty(ast, span) => hir::lowering::empty_fn(&mut cx, ast, &span)?,
+        };
+
+        cx.export_variable_names()?;
+        cx.report_unused_variables()?;
+        cx.visit_locals(span)?;
+
+        let count = hir.args.len();
+
+        let mut scopes = self::v1::Scopes::new(location.source_id)?;
+        let mut c = self.compiler1(location, span, &mut asm, &mut scopes)?;
+        assemble::fn_from_item_fn(&mut c, &hir, f.is_instance)?;
+        let size = c.scopes.size();
+
+        if !self.q.is_used(&item_meta) {
+            self.q
+                .diagnostics
+                .not_used(location.source_id, span, None)?;
+        } else {
+            let instance = match (type_hash, &f.ast) {
+                (Some(type_hash), FunctionAst::Item(_, name)) => {
+                    let name = name.resolve(resolve_context!(self.q))?;
+                    Some((type_hash, name))
+                }
+                (Some(type_hash), FunctionAst::Node(_, Some(name))) => {
+                    let name = name.resolve(resolve_context!(self.q))?;
+                    Some((type_hash, name))
+                }
+                _ => None,
+            };
+
+            let item = self.q.pool.item(item_meta.item);
+
+            self.q.unit.new_function(
+                location,
+                item,
+                instance,
+                f.protocol.map(|protocol| protocol.hash),
+                count,
+                None,
+                asm,
+                f.call,
+                debug_args,
+                unit_storage,
+                size,
+                item_meta.visibility.is_public(),
+            )?;
+        }
+
+        for build in secondary_builds {
+            let item_meta = build.item_meta;
+
+            let mut asm = self.q.unit.new_assembly(item_meta.location);
+
+            match build.build {
+                SecondaryBuild::Closure(c) => {
+                    tracing::trace!("closure: {}", self.q.pool.item(item_meta.item));
+
+                    let debug_args =
+                        format_hir_args(self.q.sources, location, true, c.hir.args.iter())?;
+
+                    let mut scopes = self::v1::Scopes::new(location.source_id)?;
+                    let mut cx = self.compiler1(location, c.hir, &mut asm, &mut scopes)?;
+                    assemble::expr_closure_secondary(&mut cx, c.hir)?;
+                    let size = cx.scopes.size();
+
+                    if !self.q.is_used(&item_meta) {
+                        self.q
+                            .diagnostics
+                            .not_used(location.source_id, &location.span, None)?;
+                    } else {
+                        let captures = (!c.hir.captures.is_empty()).then_some(c.hir.captures.len());
+
+                        let args = c
+                            .hir
+                            .args
+                            .len()
+                            .saturating_add(usize::from(captures.is_some()));
+
+                        self.q.unit.new_function(
+                            location,
+                            self.q.pool.item(item_meta.item),
+                            None,
+                            None,
+                            args,
+                            captures,
+                            asm,
+                            c.call,
+                            debug_args,
+                            unit_storage,
+                            size,
+                            false,
+                        )?;
+                    }
+                }
+                SecondaryBuild::AsyncBlock(b) => {
+                    tracing::trace!("async block: {}", self.q.pool.item(item_meta.item));
+
+                    let mut scopes = self::v1::Scopes::new(location.source_id)?;
+                    let mut cx = self.compiler1(location, b.hir, &mut asm, &mut scopes)?;
+                    assemble::async_block_secondary(&mut cx, b.hir)?;
+                    let size = cx.scopes.size();
+
+                    if !self.q.is_used(&item_meta) {
+                        self.q
+                            .diagnostics
+                            .not_used(location.source_id, &location.span, None)?;
+                    } else {
+                        let args = b.hir.captures.len();
+
+                        self.q.unit.new_function(
+                            location,
+                            self.q.pool.item(item_meta.item),
+                            None,
+                            None,
+                            args,
+                            None,
+                            asm,
+                            b.call,
+                            Default::default(),
+                            unit_storage,
+                            size,
+                            false,
+                        )?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
 fn format_hir_args<'hir, I>(
@@ -1,7 +1,26 @@
-use crate::ast::Spanned;
+use crate::ast::{Span, Spanned};
 use crate::compile::{Located, MetaError, MetaRef};
 use crate::{Hash, Item, SourceId};
 
+/// The kind of an item being declared, see
+/// [CompileVisitor::visit_declaration].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DeclarationKind {
+    /// A module, like `mod foo { .. }` or `mod foo;`.
+    Module,
+    /// A struct.
+    Struct,
+    /// An enum.
+    Enum,
+    /// A variant of an enum.
+    Variant,
+    /// A function, including functions in `impl` blocks.
+    Function,
+    /// A constant.
+    Const,
+}
+
 /// A visitor that will be called for every language item compiled.
 pub trait CompileVisitor {
     /// Called when a meta item is registered.
@@ -24,11 +43,60 @@ pub trait CompileVisitor {
         Ok(())
     }
 
+    /// Visit a local variable definition, which can be referenced by code
+    /// inside of the `scope` span.
+    ///
+    /// `parameter` indicates that the variable is an argument of a function
+    /// or a closure.
+    fn visit_local(
+        &mut self,
+        _source_id: SourceId,
+        _name: &str,
+        _span: &dyn Spanned,
+        _scope: &dyn Spanned,
+        _parameter: bool,
+    ) -> Result<(), MetaError> {
+        Ok(())
+    }
+
+    /// Visit a use of a field by name, like `x` in `point.x` or in
+    /// `Point { x: 1 }`.
+    ///
+    /// The `container` is the type hash of the struct or variant the field
+    /// belongs to if it is known, which is the case for struct literals,
+    /// struct patterns, and accesses through `self` or through variables
+    /// bound to a struct literal.
+    fn visit_field_use(
+        &mut self,
+        _source_id: SourceId,
+        _container: Option<Hash>,
+        _name: &str,
+        _span: &dyn Spanned,
+    ) -> Result<(), MetaError> {
+        Ok(())
+    }
+
     /// Visit something that is a module.
     fn visit_mod(&mut self, _location: &dyn Located) -> Result<(), MetaError> {
         Ok(())
     }
 
+    /// Visit the declaration of an item while it is being indexed, where
+    /// `location` covers the whole declaration and `name` is the span of the
+    /// name of the item.
+    ///
+    /// This is called before the item is compiled, so it is also called for
+    /// items which fail to compile.
+    fn visit_declaration(
+        &mut self,
+        _location: &dyn Located,
+        _name: Span,
+        _item: &Item,
+        _kind: DeclarationKind,
+    ) -> Result<(), MetaError> {
+        Ok(())
+    }
+
     /// Visit anterior `///`-style comments, and interior `//!`-style doc
     /// comments for an item.
     ///
@@ -47,6 +115,19 @@ pub trait CompileVisitor {
         Ok(())
     }
 
+    /// Visit the declaration of a named field contained in a struct / enum
+    /// variant struct, where `location` is the location of the name of the
+    /// field.
+    fn visit_field(
+        &mut self,
+        _location: &dyn Located,
+        _item: &Item,
+        _hash: Hash,
+        _field: &str,
+    ) -> Result<(), MetaError> {
+        Ok(())
+    }
+
     /// Visit anterior `///`-style comments, and interior `//!`-style doc
     /// comments for a field contained in a struct / enum variant struct.
     ///
@@ -20,8 +20,9 @@ use crate::module::{
     TypeSpecification,
 };
 use crate::runtime::{
-    AnyTypeInfo, ConstConstruct, ConstContext, ConstValue, FunctionHandler, InstAddress, Memory,
-    Output, Protocol, Rtti, RttiKind, RuntimeContext, TypeCheck, TypeInfo, VmResult,
+    AnyTypeInfo, CloneHandler, ConstConstruct, ConstContext, ConstValue, FunctionHandler,
+    InstAddress, Memory, Output, Protocol, Rtti, RttiKind, RuntimeContext, SerializeHandler,
+    TypeCheck, TypeInfo, VmResult,
 };
 use crate::{Hash, Item, ItemBuf};
 
@@ -42,6 +43,8 @@ pub(crate) type AttributeMacroHandler = dyn Fn(&mut MacroContext, &TokenStream,
 pub struct TraitContext<'a> {
     /// The context the trait function are being installed into.
     cx: &'a mut Context,
+    /// The module which is installing the trait implementation.
+    module: &'a Item,
     /// The item being installed.
     item: &'a Item,
     /// The hash of the item being installed.
@@ -184,6 +187,8 @@ impl TraitContext<'_> {
         let function = ModuleFunction {
             handler: handler.clone(),
             trait_hash: Some(self.trait_hash),
+            is_const: false,
+            is_diverging: false,
             doc: DocFunction {
                 #[cfg(feature = "doc")]
                 is_async: false,
@@ -207,13 +212,13 @@ impl TraitContext<'_> {
             kind: ModuleAssociatedKind::Function(function),
         };
 
-        self.cx.install_associated(&assoc)?;
+        self.cx.install_associated(self.module, &assoc)?;
         Ok(())
     }
 }
 
 /// Context metadata.
-#[derive(Debug)]
+#[derive(Debug, TryClone)]
 #[non_exhaustive]
 pub(crate) struct ContextMeta {
     /// Type hash for the given meta item.
@@ -251,6 +256,27 @@ pub(crate) struct ContextType {
     type_info: TypeInfo,
     /// Type parameters.
     type_parameters: Hash,
+    /// Alternate item for a concrete instantiation of a generic type.
+    alias: Option<ItemBuf>,
+}
+
+/// The item and originating module of something registered in the context.
+#[derive(Debug, TryClone)]
+struct Provenance {
+    /// The item which was registered.
+    item: ItemBuf,
+    /// The item of the module which registered it.
+    module: ItemBuf,
+}
+
+/// A function which is provided by a module that is known, but which has not
+/// been installed in the context.
+#[derive(Debug, TryClone)]
+pub(crate) struct KnownFunction {
+    /// The item of the function.
+    pub(crate) item: ItemBuf,
+    /// The item of the module which provides the function.
+    pub(crate) module: ItemBuf,
 }
 
 impl fmt::Display for ContextType {
@@ -271,10 +297,11 @@ impl fmt::Display for ContextType {
 /// * Native functions.
 /// * Native instance functions.
 /// * And native type definitions.
-#[derive(Default)]
+#[derive(Default, TryClone)]
 pub struct Context {
-    /// Unique modules installed in the context.
-    unique: HashSet<&'static str>,
+    /// Unique modules installed in the context, and the item of the module
+    /// they were installed for.
+    unique: HashMap<&'static str, ItemBuf>,
     /// Whether or not to include the prelude when constructing a new unit.
     has_default_modules: bool,
     /// Registered metadata, in the order that it was registered.
@@ -283,10 +310,19 @@ pub struct Context {
     hash_to_meta: hash::Map<Vec<usize>>,
     /// Store item to hash mapping.
     item_to_hash: HashMap<ItemBuf, BTreeSet<Hash>>,
+    /// Metadata for names which refer to a concrete instantiation of a generic
+    /// type or one of its associated functions. These are not part of the
+    /// metadata registered for the hash, which is installed once under its
+    /// generic item.
+    aliases: HashMap<ItemBuf, ContextMeta>,
     /// Registered native function handlers.
     functions: hash::Map<Arc<FunctionHandler>>,
     /// Registered deprecation mesages for native functions.
     deprecations: hash::Map<String>,
+    /// Native functions which can be called in constant contexts.
+    const_fns: hash::Map<Arc<FunctionHandler>>,
+    /// Native functions which never return normally.
+    diverging: HashSet<Hash>,
     /// Information on associated types.
     #[cfg(feature = "doc")]
     associated: hash::Map<Vec<Hash>>,
@@ -309,6 +345,19 @@ pub struct Context {
     constants: hash::Map<ConstValue>,
     /// Constant constructor.
     construct: hash::Map<Arc<dyn ConstConstruct>>,
+    /// Clone handlers for types which have been marked as immutable.
+    immutable: hash::Map<Arc<CloneHandler>>,
+    /// Serialization handlers for types which have been marked as
+    /// serializable.
+    serialize: hash::Map<Arc<SerializeHandler>>,
+    /// Functions provided by modules which are known but not installed.
+    known: hash::Map<KnownFunction>,
+    /// The container of associated items, used to remove them together with
+    /// their container.
+    containers: hash::Map<Hash>,
+    /// Where registered functions, types and traits originate from, used to
+    /// report conflicts.
+    provenance: hash::Map<Provenance>,
 }
 
 impl Context {
@@ -341,6 +390,7 @@ impl Context {
         this.install(crate::modules::core::module()?)?;
         this.install(crate::modules::cmp::module()?)?;
         this.install(crate::modules::any::module()?)?;
+        this.install(crate::modules::budget::module()?)?;
         this.install(crate::modules::clone::module()?)?;
         this.install(crate::modules::num::module()?)?;
         this.install(crate::modules::hash::module()?)?;
@@ -407,6 +457,8 @@ impl Context {
             self.functions.try_clone()?,
             self.constants.try_clone()?,
             self.construct.try_clone()?,
+            self.immutable.try_clone()?,
+            self.serialize.try_clone()?,
         ))
     }
 
@@ -424,9 +476,11 @@ impl Context {
         tracing::trace!("installing");
 
         if let Some(id) = module.unique {
-            if !self.unique.try_insert(id)? {
+            if self.unique.contains_key(id) {
                 return Ok(());
             }
+
+            self.unique.try_insert(id, module.item.try_clone()?)?;
         }
 
         if let Some(ComponentRef::Crate(name)) = module.item.first() {
@@ -438,27 +492,27 @@ impl Context {
 
         tracing::trace!(types = module.types.len(), "types");
         for ty in &module.types {
-            self.install_type(ty)?;
+            self.install_type(&module.item, ty)?;
         }
 
         tracing::trace!(traits = module.traits.len(), "traits");
         for t in &module.traits {
-            self.install_trait(t)?;
+            self.install_trait(&module.item, t)?;
         }
 
         tracing::trace!(items = module.items.len(), "items");
         for item in &module.items {
-            self.install_item(item)?;
+            self.install_item(&module.item, item)?;
         }
 
         tracing::trace!(associated = module.associated.len(), "associated");
         for assoc in &module.associated {
-            self.install_associated(assoc)?;
+            self.install_associated(&module.item, assoc)?;
         }
 
         tracing::trace!(trait_impls = module.trait_impls.len(), "trait impls");
         for t in &module.trait_impls {
-            self.install_trait_impl(t)?;
+            self.install_trait_impl(&module.item, t)?;
         }
 
         tracing::trace!(reexports = module.reexports.len(), "reexports");
@@ -474,6 +528,281 @@ impl Context {
         Ok(())
     }
 
+    /// Replace an installed module with the given one.
+    ///
+    /// This removes everything installed under the item of the module as with
+    /// [`Context::remove_module`] before installing the new module. The
+    /// operation is atomic, so if installing the new module fails the context
+    /// is left unmodified.
+    ///
+    /// ```
+    /// use rune::{Context, Module};
+    ///
+    /// let mut m = Module::with_crate_item("std", ["io"])?;
+    /// m.function("println", |_: &str| {}).build()?;
+    ///
+    /// let mut context = Context::with_default_modules()?;
+    /// context.replace(m)?;
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    pub fn replace<M>(&mut self, module: M) -> Result<(), ContextError>
+    where
+        M: AsRef<Module>,
+    {
+        let module = module.as_ref();
+
+        let mut context = self.try_clone()?;
+
+        if context
+            .find_existing_module(Hash::type_hash(&module.item))
+            .is_some()
+        {
+            context.remove_module(&module.item)?;
+        }
+
+        context.install(module)?;
+        *self = context;
+        Ok(())
+    }
+
+    /// Remove an installed module.
+    ///
+    /// This removes all functions, types, constants and macros installed under
+    /// the given item, including functions associated with removed types.
+    ///
+    /// Units which have already been compiled against the context will fail to
+    /// call removed functions with a missing function error once they are run
+    /// with a [`RuntimeContext`] constructed after the removal.
+    ///
+    /// ```
+    /// use rune::{Context, Diagnostics, Source, Sources};
+    /// use rune::item;
+    ///
+    /// let mut context = Context::with_default_modules()?;
+    /// context.remove_module(item!(::std::io))?;
+    ///
+    /// let mut sources = Sources::new();
+    /// sources.insert(Source::memory("pub fn main() { println(\"Hello\") }")?)?;
+    ///
+    /// let mut diagnostics = Diagnostics::new();
+    ///
+    /// let result = rune::prepare(&mut sources)
+    ///     .with_context(&context)
+    ///     .with_diagnostics(&mut diagnostics)
+    ///     .build();
+    ///
+    /// assert!(result.is_err());
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    pub fn remove_module(&mut self, item: &Item) -> Result<(), ContextError> {
+        if self.find_existing_module(Hash::type_hash(item)).is_none() {
+            return Err(ContextError::MissingModule {
+                item: item.try_to_owned()?,
+            });
+        }
+
+        let mut removed = HashSet::new();
+
+        for meta in &self.meta {
+            if let Some(meta_item) = &meta.item {
+                if meta_item.starts_with(item) {
+                    removed.try_insert(meta.hash)?;
+                }
+            }
+        }
+
+        for (hash, container) in &self.containers {
+            if removed.contains(container) {
+                removed.try_insert(*hash)?;
+            }
+        }
+
+        for hash in &removed {
+            self.functions.remove(hash);
+            self.deprecations.remove(hash);
+            self.const_fns.remove(hash);
+            self.diverging.remove(hash);
+            self.constants.remove(hash);
+            self.constants
+                .remove(&Hash::associated_function(*hash, &Protocol::INTO_TYPE_NAME));
+            self.construct.remove(hash);
+            self.immutable.remove(hash);
+            self.serialize.remove(hash);
+            self.types.remove(hash);
+            self.macros.remove(hash);
+            self.attribute_macros.remove(hash);
+            self.traits.remove(hash);
+            self.containers.remove(hash);
+            self.provenance.remove(hash);
+        }
+
+        #[cfg(feature = "doc")]
+        {
+            self.associated.retain(|hash, _| !removed.contains(hash));
+
+            for hashes in self.associated.values_mut() {
+                hashes.retain(|hash| !removed.contains(hash));
+            }
+
+            self.implemented_traits
+                .retain(|hash, _| !removed.contains(hash));
+
+            for hashes in self.implemented_traits.values_mut() {
+                hashes.retain(|hash| !removed.contains(hash));
+            }
+        }
+
+        self.unique.retain(|_, unique| !unique.starts_with(item));
+        self.known
+            .retain(|_, known| !known.module.starts_with(item));
+
+        let meta = core::mem::take(&mut self.meta);
+        self.hash_to_meta.clear();
+        self.item_to_hash.clear();
+        self.names = Names::default();
+        self.crates.clear();
+
+        for meta in meta {
+            if removed.contains(&meta.hash) {
+                continue;
+            }
+
+            if let Some(item) = &meta.item {
+                if let Some(ComponentRef::Crate(name)) = item.first() {
+                    if !self.crates.contains(name) {
+                        self.crates.try_insert(name.try_into()?)?;
+                    }
+                }
+
+                self.names.insert(item)?;
+
+                self.item_to_hash
+                    .entry(item.try_clone()?)
+                    .or_try_default()?
+                    .try_insert(meta.hash)?;
+            }
+
+            self.hash_to_meta
+                .entry(meta.hash)
+                .or_try_default()?
+                .try_push(self.meta.len())?;
+
+            self.meta.try_push(meta)?;
+        }
+
+        self.aliases
+            .retain(|alias, meta| !alias.starts_with(item) && !removed.contains(&meta.hash));
+
+        for alias in self.aliases.keys() {
+            self.names.insert(alias)?;
+        }
+
+        Ok(())
+    }
+
+    /// Register a module which is known, but which is not installed in this
+    /// context.
+    ///
+    /// Nothing in the module is made available to scripts, but if a script
+    /// tries to use a function it provides the resulting diagnostics will
+    /// note which module has to be installed for it to be available.
+    ///
+    /// ```
+    /// use rune::{Context, Diagnostics, Module, Source, Sources};
+    ///
+    /// let mut m = Module::with_crate("http")?;
+    /// m.function("get", |url: &str| url.len() as i64).build()?;
+    ///
+    /// let mut context = Context::with_default_modules()?;
+    /// context.known_module(&m)?;
+    ///
+    /// let mut sources = Sources::new();
+    /// sources.insert(Source::memory("pub fn main() { http::get(\"https://example.com\") }")?)?;
+    ///
+    /// let mut diagnostics = Diagnostics::new();
+    ///
+    /// let result = rune::prepare(&mut sources)
+    ///     .with_context(&context)
+    ///     .with_diagnostics(&mut diagnostics)
+    ///     .build();
+    ///
+    /// assert!(result.is_err());
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    pub fn known_module<M>(&mut self, module: M) -> Result<(), ContextError>
+    where
+        M: AsRef<Module>,
+    {
+        let module = module.as_ref();
+
+        for m in &module.items {
+            if let rune::module::ModuleItemKind::Function(..) = &m.kind {
+                self.insert_known(m.hash, &m.item, &module.item)?;
+            }
+        }
+
+        for assoc in &module.associated {
+            let (meta::AssociatedKind::Instance(name), ModuleAssociatedKind::Function(..)) =
+                (&assoc.name.kind, &assoc.kind)
+            else {
+                continue;
+            };
+
+            let Some(ty) = module.types.iter().find(|ty| ty.hash == assoc.container) else {
+                continue;
+            };
+
+            let item = ty.item.extended(name.as_ref())?;
+            self.insert_known(Hash::type_hash(&item), &item, &module.item)?;
+        }
+
+        Ok(())
+    }
+
+    /// Insert a known function, unless a function with the same hash is
+    /// already installed.
+    fn insert_known(&mut self, hash: Hash, item: &Item, module: &Item) -> alloc::Result<()> {
+        if self.functions.contains_key(&hash) {
+            return Ok(());
+        }
+
+        self.known.try_insert(
+            hash,
+            KnownFunction {
+                item: item.try_to_owned()?,
+                module: module.try_to_owned()?,
+            },
+        )?;
+
+        Ok(())
+    }
+
+    /// Look up a function provided by a known module which is not installed
+    /// by its hash.
+    pub(crate) fn lookup_known(&self, hash: Hash) -> Option<&KnownFunction> {
+        if self.functions.contains_key(&hash) {
+            return None;
+        }
+
+        self.known.get(&hash)
+    }
+
+    /// Look up a function provided by a known module which is not installed
+    /// by the item it was referenced through.
+    ///
+    /// Since the module isn't installed, its crate won't have been resolved
+    /// when compiling the item, so we treat the first component as a crate.
+    pub(crate) fn lookup_known_item(&self, item: &Item) -> alloc::Result<Option<&KnownFunction>> {
+        let mut it = item.iter();
+
+        let Some(ComponentRef::Str(first)) = it.next() else {
+            return Ok(None);
+        };
+
+        let item = ItemBuf::with_crate_item(first, it)?;
+        Ok(self.lookup_known(Hash::type_hash(&item)))
+    }
+
     /// Iterate over all available functions in the [Context].
     #[cfg(any(feature = "cli", feature = "languageserver"))]
     pub(crate) fn iter_functions(&self) -> impl Iterator<Item = (&ContextMeta, &meta::Signature)> {
@@ -483,6 +812,12 @@ impl Context {
         })
     }
 
+    /// Iterate over all available meta in the [Context].
+    #[cfg(any(feature = "languageserver", feature = "emit"))]
+    pub(crate) fn iter_meta(&self) -> impl Iterator<Item = &ContextMeta> {
+        self.meta.iter()
+    }
+
     /// Iterate over all available types in the [Context].
     #[cfg(feature = "cli")]
     pub(crate) fn iter_types(&self) -> impl Iterator<Item = (Hash, &Item)> {
@@ -516,20 +851,26 @@ impl Context {
         &self,
         item: &Item,
     ) -> Option<impl Iterator<Item = &ContextMeta> + Clone> {
-        let hashes = self.item_to_hash.get(item)?;
+        let alias = self.aliases.get(item);
+        let hashes = self.item_to_hash.get(item);
+
+        if alias.is_none() && hashes.is_none() {
+            return None;
+        }
 
-        Some(hashes.iter().flat_map(|hash| {
+        let metas = hashes.into_iter().flatten().flat_map(|hash| {
             let indexes = self
                 .hash_to_meta
                 .get(hash)
                 .map(Vec::as_slice)
                 .unwrap_or_default();
             indexes.iter().map(|&i| &self.meta[i])
-        }))
+        });
+
+        Some(alias.into_iter().chain(metas))
     }
 
     /// Lookup meta by its hash.
-    #[cfg(any(feature = "cli", feature = "languageserver", feature = "emit"))]
     pub(crate) fn lookup_meta_by_hash(
         &self,
         hash: Hash,
@@ -548,6 +889,18 @@ impl Context {
         self.deprecations.get(&hash).map(|s| s.as_str())
     }
 
+    /// Lookup the handler of a native function which can be called in
+    /// constant contexts.
+    pub(crate) fn lookup_const_fn(&self, hash: Hash) -> Option<&Arc<FunctionHandler>> {
+        self.const_fns.get(&hash)
+    }
+
+    /// Test if the native function with the given hash never returns
+    /// normally.
+    pub(crate) fn is_diverging(&self, hash: Hash) -> bool {
+        self.diverging.contains(&hash)
+    }
+
     /// Check if unit contains the given name by prefix.
     pub(crate) fn contains_prefix(&self, item: &Item) -> alloc::Result<bool> {
         self.names.contains_prefix(item)
@@ -559,7 +912,7 @@ impl Context {
     }
 
     /// Get all associated types for the given hash.
-    #[cfg(all(feature = "doc", feature = "cli"))]
+    #[cfg(feature = "doc")]
     pub(crate) fn associated(&self, hash: Hash) -> impl Iterator<Item = Hash> + '_ {
         self.associated
             .get(&hash)
@@ -597,7 +950,7 @@ impl Context {
     }
 
     /// Iterate over available crates.
-    #[cfg(feature = "cli")]
+    #[cfg(feature = "doc")]
     pub(crate) fn iter_crates(&self) -> impl Iterator<Item = &str> {
         self.crates.iter().map(|s| s.as_ref())
     }
@@ -615,6 +968,22 @@ impl Context {
         self.has_default_modules
     }
 
+    /// Compute a fingerprint of the items installed in the context.
+    ///
+    /// Installing a module which adds or changes items changes the
+    /// fingerprint, which is used to invalidate a [`BuildCache`].
+    ///
+    /// [`BuildCache`]: crate::compile::BuildCache
+    pub(crate) fn fingerprint(&self) -> Hash {
+        let mut hash = Hash::new(u64::from(self.has_default_modules));
+
+        for meta in &self.meta {
+            hash = Hash::new(hash.into_inner().rotate_left(5) ^ meta.hash.into_inner());
+        }
+
+        hash
+    }
+
     /// Try to find an existing module.
     fn find_existing_module(&self, hash: Hash) -> Option<usize> {
         let indexes = self.hash_to_meta.get(&hash)?;
@@ -666,6 +1035,20 @@ impl Context {
         Ok(())
     }
 
+    /// Install metadata under a name which refers to a concrete instantiation
+    /// of a generic type, or one of its associated functions.
+    ///
+    /// Only the name is registered, the metadata of the hash itself is
+    /// installed separately under its generic item.
+    fn install_alias(&mut self, meta: ContextMeta) -> Result<(), ContextError> {
+        if let Some(item) = &meta.item {
+            self.names.insert(item)?;
+            self.aliases.try_insert(item.try_clone()?, meta)?;
+        }
+
+        Ok(())
+    }
+
     /// Install a module, ensuring that its meta is defined.
     fn install_module(&mut self, m: &Module) -> Result<(), ContextError> {
         self.names.insert(&m.item)?;
@@ -708,14 +1091,26 @@ impl Context {
     }
 
     /// Install a single type.
-    fn install_type(&mut self, ty: &ModuleType) -> Result<(), ContextError> {
-        self.install_type_info(ContextType {
-            item: ty.item.try_to_owned()?,
-            hash: ty.hash,
-            type_check: None,
-            type_info: ty.type_info.try_clone()?,
-            type_parameters: ty.type_parameters,
-        })?;
+    fn install_type(&mut self, module: &Item, ty: &ModuleType) -> Result<(), ContextError> {
+        self.install_type_info(
+            module,
+            ContextType {
+                item: ty.item.try_to_owned()?,
+                hash: ty.hash,
+                type_check: None,
+                type_info: ty.type_info.try_clone()?,
+                type_parameters: ty.type_parameters,
+                alias: ty.alias.try_clone()?,
+            },
+        )?;
+
+        if let Some(handler) = &ty.immutable {
+            self.immutable.try_insert(ty.hash, handler.clone())?;
+        }
+
+        if let Some(handler) = &ty.serialize {
+            self.serialize.try_insert(ty.hash, handler.clone())?;
+        }
 
         let parameters = Hash::EMPTY.with_type_parameters(ty.type_parameters);
 
@@ -733,7 +1128,16 @@ impl Context {
                                 return_type: meta::DocType::new(ty.hash),
                             };
 
-                            self.insert_native_fn(&ty.type_info, ty.hash, c, None)?;
+                            self.insert_native_fn(
+                                &ty.type_info,
+                                module,
+                                &ty.item,
+                                ty.hash,
+                                c,
+                                None,
+                                false,
+                                false,
+                            )?;
                             Some(signature)
                         }
                         None => None,
@@ -776,19 +1180,23 @@ impl Context {
                         let item = ty.item.extended(variant.name)?;
                         let hash = Hash::type_hash(&item);
 
-                        self.install_type_info(ContextType {
-                            item: item.try_clone()?,
-                            hash,
-                            type_check: None,
-                            type_info: TypeInfo::rtti(Arc::new(Rtti {
-                                kind,
-                                hash: ty.hash,
-                                variant_hash: hash,
+                        self.install_type_info(
+                            module,
+                            ContextType {
                                 item: item.try_clone()?,
-                                fields: fields.to_fields()?,
-                            })),
-                            type_parameters: Hash::EMPTY,
-                        })?;
+                                hash,
+                                type_check: None,
+                                type_info: TypeInfo::rtti(Arc::new(Rtti {
+                                    kind,
+                                    hash: ty.hash,
+                                    variant_hash: hash,
+                                    item: item.try_clone()?,
+                                    fields: fields.to_fields()?,
+                                })),
+                                type_parameters: Hash::EMPTY,
+                                alias: None,
+                            },
+                        )?;
 
                         let constructor = if let Some(c) = &variant.constructor {
                             let signature = meta::Signature {
@@ -800,7 +1208,16 @@ impl Context {
                                 return_type: meta::DocType::new(ty.hash),
                             };
 
-                            self.insert_native_fn(&item, hash, c, variant.deprecated.as_deref())?;
+                            self.insert_native_fn(
+                                &item,
+                                module,
+                                &item,
+                                hash,
+                                c,
+                                variant.deprecated.as_deref(),
+                                false,
+                                false,
+                            )?;
                             Some(signature)
                         } else {
                             None
@@ -847,6 +1264,29 @@ impl Context {
             meta::Kind::Type { parameters }
         };
 
+        if let Some(alias) = &ty.alias {
+            // An alias names a concrete instantiation, so it takes no further
+            // generic parameters.
+            let mut kind = kind.try_clone()?;
+
+            if let meta::Kind::Struct { parameters, .. }
+            | meta::Kind::Enum { parameters }
+            | meta::Kind::Type { parameters } = &mut kind
+            {
+                *parameters = Hash::EMPTY;
+            }
+
+            self.install_alias(ContextMeta {
+                hash: ty.hash,
+                item: Some(alias.try_clone()?),
+                kind,
+                #[cfg(feature = "doc")]
+                deprecated: ty.common.deprecated.try_clone()?,
+                #[cfg(feature = "doc")]
+                docs: ty.common.docs.try_clone()?,
+            })?;
+        }
+
         self.install_meta(ContextMeta {
             hash: ty.hash,
             item: Some(ty.item.try_to_owned()?),
@@ -860,14 +1300,21 @@ impl Context {
         Ok(())
     }
 
-    fn install_trait(&mut self, t: &ModuleTrait) -> Result<(), ContextError> {
+    fn install_trait(&mut self, module: &Item, t: &ModuleTrait) -> Result<(), ContextError> {
         if self.traits.try_insert(t.hash, t.handler.clone())?.is_some() {
+            let (existing, existing_module) = self.provenance(t.hash, &t.item, module)?;
+
             return Err(ContextError::ConflictingTrait {
                 item: t.item.try_clone()?,
                 hash: t.hash,
+                module: module.try_to_owned()?,
+                existing,
+                existing_module,
             });
         }
 
+        self.insert_provenance(module, &t.item, t.hash)?;
+
         self.install_meta(ContextMeta {
             hash: t.hash,
             item: Some(t.item.try_clone()?),
@@ -908,6 +1355,8 @@ impl Context {
                 None
             };
 
+            self.containers.try_insert(hash, t.hash)?;
+
             self.install_meta(ContextMeta {
                 hash,
                 item: item.map(|(_, item)| item),
@@ -922,7 +1371,11 @@ impl Context {
         Ok(())
     }
 
-    fn install_trait_impl(&mut self, i: &ModuleTraitImpl) -> Result<(), ContextError> {
+    fn install_trait_impl(
+        &mut self,
+        module: &Item,
+        i: &ModuleTraitImpl,
+    ) -> Result<(), ContextError> {
         if !self.types.contains_key(&i.hash) {
             return Err(ContextError::MissingType {
                 item: i.item.try_to_owned()?,
@@ -942,6 +1395,7 @@ impl Context {
         if let Some(handler) = handler {
             handler(&mut TraitContext {
                 cx: self,
+                module,
                 item: &i.item,
                 hash: i.hash,
                 type_info: &i.type_info,
@@ -960,6 +1414,27 @@ impl Context {
     }
 
     fn install_reexport(&mut self, r: &ModuleReexport) -> Result<(), ContextError> {
+        if let Some(meta) = self.lookup_meta_by_hash(r.hash).next() {
+            let meta::Kind::Alias(alias) = &meta.kind else {
+                return Err(ContextError::ConflictingReexport {
+                    item: r.item.try_clone()?,
+                    hash: r.hash,
+                    to: r.to.try_clone()?,
+                });
+            };
+
+            if alias.to != r.to {
+                return Err(ContextError::ConflictingReexportTarget {
+                    item: r.item.try_clone()?,
+                    to: r.to.try_clone()?,
+                    existing: alias.to.try_clone()?,
+                });
+            }
+
+            // The same re-export has already been installed.
+            return Ok(());
+        }
+
         self.install_meta(ContextMeta {
             hash: r.hash,
             item: Some(r.item.try_clone()?),
@@ -994,7 +1469,7 @@ impl Context {
         Ok(())
     }
 
-    fn install_type_info(&mut self, ty: ContextType) -> Result<(), ContextError> {
+    fn install_type_info(&mut self, module: &Item, ty: ContextType) -> Result<(), ContextError> {
         let item_hash = Hash::type_hash(&ty.item).with_type_parameters(ty.type_parameters);
 
         if ty.hash != item_hash {
@@ -1011,19 +1486,26 @@ impl Context {
             ConstValue::from(ty.item.try_to_string()?),
         )?;
 
-        if let Some(old) = self.types.try_insert(ty.hash, ty)? {
+        if self.types.contains_key(&ty.hash) {
+            let (existing, existing_module) = self.provenance(ty.hash, &ty.item, module)?;
+
             return Err(ContextError::ConflictingType {
-                item: old.item,
-                type_info: old.type_info,
-                hash: old.hash,
+                item: ty.item,
+                type_info: ty.type_info,
+                hash: ty.hash,
+                module: module.try_to_owned()?,
+                existing,
+                existing_module,
             });
         }
 
+        self.insert_provenance(module, &ty.item, ty.hash)?;
+        self.types.try_insert(ty.hash, ty)?;
         Ok(())
     }
 
     /// Install a function and check for duplicates.
-    fn install_item(&mut self, m: &ModuleItem) -> Result<(), ContextError> {
+    fn install_item(&mut self, module: &Item, m: &ModuleItem) -> Result<(), ContextError> {
         self.names.insert(&m.item)?;
 
         let kind = match &m.kind {
@@ -1039,7 +1521,16 @@ impl Context {
 
                 let signature = meta::Signature::from_context(&f.doc, &m.common)?;
 
-                self.insert_native_fn(&m.item, m.hash, &f.handler, m.common.deprecated.as_deref())?;
+                self.insert_native_fn(
+                    &m.item,
+                    module,
+                    &m.item,
+                    m.hash,
+                    &f.handler,
+                    m.common.deprecated.as_deref(),
+                    f.is_const,
+                    f.is_diverging,
+                )?;
 
                 meta::Kind::Function {
                     associated: None,
@@ -1078,7 +1569,11 @@ impl Context {
         Ok(())
     }
 
-    fn install_associated(&mut self, assoc: &ModuleAssociated) -> Result<(), ContextError> {
+    fn install_associated(
+        &mut self,
+        module: &Item,
+        assoc: &ModuleAssociated,
+    ) -> Result<(), ContextError> {
         let Some(info) = self.types.get(&assoc.container).try_cloned()? else {
             return Err(ContextError::MissingContainer {
                 container: assoc.container_type_info.try_clone()?,
@@ -1109,6 +1604,12 @@ impl Context {
             None
         };
 
+        self.containers.try_insert(hash, assoc.container)?;
+
+        if let Some((hash, ..)) = &item {
+            self.containers.try_insert(*hash, assoc.container)?;
+        }
+
         let kind = match &assoc.kind {
             ModuleAssociatedKind::Constant(value) => {
                 if let Some((hash, ..)) = item {
@@ -1129,17 +1630,27 @@ impl Context {
 
                     self.insert_native_fn(
                         &assoc.container_type_info,
+                        module,
+                        item,
                         *hash,
                         &f.handler,
                         assoc.common.deprecated.as_deref(),
+                        f.is_const,
+                        f.is_diverging,
                     )?;
                 }
 
+                let associated = info.item.extended(assoc.name.kind.try_to_string()?)?;
+
                 self.insert_native_fn(
                     &assoc.container_type_info,
+                    module,
+                    &associated,
                     hash,
                     &f.handler,
                     assoc.common.deprecated.as_deref(),
+                    f.is_const,
+                    f.is_diverging,
                 )?;
 
                 meta::Kind::Function {
@@ -1159,6 +1670,25 @@ impl Context {
             }
         };
 
+        if let (Some(alias), meta::AssociatedKind::Instance(name)) = (&info.alias, &assoc.name.kind)
+        {
+            let mut kind = kind.try_clone()?;
+
+            if let meta::Kind::Function { parameters, .. } = &mut kind {
+                *parameters = Hash::EMPTY.with_function_parameters(assoc.name.function_parameters);
+            }
+
+            self.install_alias(ContextMeta {
+                hash,
+                item: Some(alias.extended(name.as_ref())?),
+                kind,
+                #[cfg(feature = "doc")]
+                deprecated: assoc.common.deprecated.try_clone()?,
+                #[cfg(feature = "doc")]
+                docs: assoc.common.docs.try_clone()?,
+            })?;
+        }
+
         self.install_meta(ContextMeta {
             hash,
             item: item.map(|(_, item)| item),
@@ -1169,36 +1699,102 @@ impl Context {
             docs: assoc.common.docs.try_clone()?,
         })?;
 
+        // Constants don't carry their container in their meta, so associate
+        // them explicitly to have them show up in the documentation of the
+        // type.
+        #[cfg(feature = "doc")]
+        if let ModuleAssociatedKind::Constant(..) = &assoc.kind {
+            let associated = self.associated.entry(assoc.container).or_try_default()?;
+            associated.try_push(hash)?;
+        }
+
         Ok(())
     }
 
     fn insert_native_fn(
         &mut self,
         display: &dyn fmt::Display,
+        module: &Item,
+        item: &Item,
         hash: Hash,
         handler: &Arc<FunctionHandler>,
         deprecation: Option<&str>,
+        is_const: bool,
+        is_diverging: bool,
     ) -> Result<(), ContextError> {
         if self.functions.contains_key(&hash) {
+            let (existing, existing_module) = self.provenance(hash, item, module)?;
+
             return Err(ContextError::ConflictingFunction {
                 part: display.try_to_string()?.try_into()?,
+                item: item.try_to_owned()?,
                 hash,
+                module: module.try_to_owned()?,
+                existing,
+                existing_module,
             });
         }
 
+        self.insert_provenance(module, item, hash)?;
         self.functions.try_insert(hash, handler.clone())?;
 
         if let Some(msg) = deprecation {
             self.deprecations.try_insert(hash, msg.try_to_owned()?)?;
         }
 
+        if is_const {
+            self.const_fns.try_insert(hash, handler.clone())?;
+        }
+
+        if is_diverging {
+            self.diverging.try_insert(hash)?;
+        }
+
+        Ok(())
+    }
+
+    /// Record the provenance of something registered in the context.
+    fn insert_provenance(
+        &mut self,
+        module: &Item,
+        item: &Item,
+        hash: Hash,
+    ) -> Result<(), ContextError> {
+        self.provenance.try_insert(
+            hash,
+            Provenance {
+                item: item.try_to_owned()?,
+                module: module.try_to_owned()?,
+            },
+        )?;
+
         Ok(())
     }
 
+    /// Get the item and module of an existing registration, falling back to
+    /// the given ones if it is not known.
+    fn provenance(
+        &self,
+        hash: Hash,
+        item: &Item,
+        module: &Item,
+    ) -> alloc::Result<(ItemBuf, ItemBuf)> {
+        let Some(p) = self.provenance.get(&hash) else {
+            return Ok((item.try_to_owned()?, module.try_to_owned()?));
+        };
+
+        Ok((p.item.try_clone()?, p.module.try_clone()?))
+    }
+
     /// Get a constant value.
     pub(crate) fn get_const_value(&self, hash: Hash) -> Option<&ConstValue> {
         self.constants.get(&hash)
     }
+
+    /// Test if any types in the context have been marked as immutable.
+    pub(crate) fn has_immutable(&self) -> bool {
+        !self.immutable.is_empty()
+    }
 }
 
 impl fmt::Debug for Context {
@@ -29,7 +29,11 @@ pub enum ContextError {
     },
     ConflictingFunction {
         part: Box<str>,
+        item: ItemBuf,
         hash: Hash,
+        module: ItemBuf,
+        existing: ItemBuf,
+        existing_module: ItemBuf,
     },
     ConflictingFunctionName {
         item: ItemBuf,
@@ -69,15 +73,26 @@ pub enum ContextError {
         item: ItemBuf,
         type_info: TypeInfo,
         hash: Hash,
+        module: ItemBuf,
+        existing: ItemBuf,
+        existing_module: ItemBuf,
     },
     ConflictingReexport {
         item: ItemBuf,
         hash: Hash,
         to: ItemBuf,
     },
+    ConflictingReexportTarget {
+        item: ItemBuf,
+        to: ItemBuf,
+        existing: ItemBuf,
+    },
     ConflictingTrait {
         item: ItemBuf,
         hash: Hash,
+        module: ItemBuf,
+        existing: ItemBuf,
+        existing_module: ItemBuf,
     },
     ConflictingTraitImpl {
         trait_item: ItemBuf,
@@ -140,6 +155,9 @@ pub enum ContextError {
     MissingContainer {
         container: TypeInfo,
     },
+    MissingModule {
+        item: ItemBuf,
+    },
     MissingVariant {
         index: usize,
         type_info: TypeInfo,
@@ -200,10 +218,17 @@ impl fmt::Display for ContextError {
             ContextError::InternalAlreadyPresent { name } => {
                 write!(f, "Type for name `{name}` is already present")?;
             }
-            ContextError::ConflictingFunction { part, hash } => {
+            ContextError::ConflictingFunction {
+                part,
+                item,
+                hash,
+                module,
+                existing,
+                existing_module,
+            } => {
                 write!(
                     f,
-                    "Function with hash `{hash}` part of `{part}` already exists"
+                    "Function `{item}` with hash `{hash}` part of `{part}` in module `{module}` conflicts with existing function `{existing}` in module `{existing_module}`"
                 )?;
             }
             ContextError::ConflictingFunctionName { item, hash } => {
@@ -248,10 +273,13 @@ impl fmt::Display for ContextError {
                 item,
                 type_info,
                 hash,
+                module,
+                existing,
+                existing_module,
             } => {
                 write!(
                     f,
-                    "Type `{item}` already exists `{type_info}` with hash `{hash}`"
+                    "Type `{item}` (`{type_info}`) with hash `{hash}` in module `{module}` conflicts with existing type `{existing}` in module `{existing_module}`"
                 )?;
             }
             ContextError::ConflictingReexport { item, hash, to } => {
@@ -260,10 +288,22 @@ impl fmt::Display for ContextError {
                     "Reexport at `{item}` with hash `{hash}` to `{to}` already exists"
                 )?;
             }
-            ContextError::ConflictingTrait { item, hash } => {
+            ContextError::ConflictingReexportTarget { item, to, existing } => {
+                write!(
+                    f,
+                    "Reexport at `{item}` to `{to}` conflicts with existing reexport to `{existing}`"
+                )?;
+            }
+            ContextError::ConflictingTrait {
+                item,
+                hash,
+                module,
+                existing,
+                existing_module,
+            } => {
                 write!(
                     f,
-                    "Trait `{item}` with hash `{hash}` conflicts with other item in module"
+                    "Trait `{item}` with hash `{hash}` in module `{module}` conflicts with existing item `{existing}` in module `{existing_module}`"
                 )?;
             }
             ContextError::ConflictingTraitImpl {
@@ -358,6 +398,9 @@ impl fmt::Display for ContextError {
             ContextError::MissingContainer { container } => {
                 write!(f, "Container `{container}` is not registered")?;
             }
+            ContextError::MissingModule { item } => {
+                write!(f, "Module `{item}` is not installed")?;
+            }
             ContextError::MissingVariant { index, type_info } => {
                 write!(f, "Missing variant {index} for `{type_info}`")?;
             }
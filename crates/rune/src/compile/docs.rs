@@ -32,7 +32,7 @@ impl Docs {
     }
 
     /// Get lines of documentation.
-    #[cfg(all(feature = "doc", any(feature = "languageserver", feature = "cli")))]
+    #[cfg(feature = "doc")]
     pub(crate) fn lines(&self) -> &[String] {
         &self.docs
     }
@@ -13,7 +13,7 @@ use crate::ast;
 use crate::ast::unescape;
 use crate::ast::{Span, Spanned};
 use crate::compile::ir;
-use crate::compile::{HasSpan, Location, MetaInfo, Visibility};
+use crate::compile::{HasSpan, Location, MetaInfo, SimilarItem, Visibility};
 use crate::hash::TooManyParameters;
 use crate::indexing::items::{GuardMismatch, MissingLastId};
 use crate::macros::{SyntheticId, SyntheticKind};
@@ -66,6 +66,18 @@ impl Error {
         &self.kind
     }
 
+    /// The stable name of the error, like `missing-item`.
+    #[cfg(feature = "languageserver")]
+    pub(crate) fn name(&self) -> &'static str {
+        self.kind.name()
+    }
+
+    /// Get a machine-applicable suggestion for how to fix this error, if one
+    /// is available.
+    pub fn suggestion(&self) -> Option<Suggestion> {
+        self.kind.suggestion()
+    }
+
     /// Convert into the kind of the error.
     #[cfg(test)]
     pub(crate) fn into_kind(self) -> ErrorKind {
@@ -73,6 +85,24 @@ impl Error {
     }
 }
 
+/// A machine-applicable suggestion for how to fix a compile error.
+///
+/// Applying a suggestion means replacing the text covered by [`span`] with
+/// [`replacement`].
+///
+/// [`span`]: Suggestion::span
+/// [`replacement`]: Suggestion::replacement
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Suggestion {
+    /// The span of source to replace. This is empty for insertions.
+    pub span: Span,
+    /// The text to replace the span with.
+    pub replacement: &'static str,
+    /// A short description of what the suggestion does.
+    pub message: &'static str,
+}
+
 impl Spanned for Error {
     #[inline]
     fn span(&self) -> Span {
@@ -294,6 +324,7 @@ pub(crate) enum ErrorKind {
     },
     MissingItem {
         item: ItemBuf,
+        did_you_mean: Option<SimilarItem>,
     },
     MissingItemHash {
         hash: Hash,
@@ -302,6 +333,10 @@ pub(crate) enum ErrorKind {
         item: ItemBuf,
         parameters: [Option<Hash>; 2],
     },
+    MissingKnownItem {
+        item: ItemBuf,
+        module: ItemBuf,
+    },
     UnsupportedGlobal,
     UnsupportedModuleSource,
     #[cfg(feature = "std")]
@@ -329,6 +364,7 @@ pub(crate) enum ErrorKind {
     LitObjectNotField {
         field: Box<str>,
         item: ItemBuf,
+        did_you_mean: Option<Box<str>>,
     },
     UnsupportedAssignExpr,
     UnsupportedBinaryExpr,
@@ -350,7 +386,10 @@ pub(crate) enum ErrorKind {
         number: ast::Number,
     },
     BreakUnsupported,
-    BreakUnsupportedValue,
+    BreakUnsupportedValue {
+        #[cfg_attr(not(feature = "emit"), allow(unused))]
+        loop_span: Span,
+    },
     ContinueUnsupported,
     ContinueUnsupportedBlock,
     SelectMultipleDefaults,
@@ -413,6 +452,8 @@ pub(crate) enum ErrorKind {
     },
     MissingLabel {
         label: Box<str>,
+        #[cfg_attr(not(feature = "emit"), allow(unused))]
+        label_span: Span,
     },
     ExpectedLeadingPathSegment,
     UnsupportedVisibility,
@@ -428,6 +469,9 @@ pub(crate) enum ErrorKind {
         moved_at: Span,
     },
     UnsupportedGenerics,
+    UnsupportedProtocol {
+        name: Box<str>,
+    },
     NestedTest {
         #[cfg(feature = "emit")]
         nested_span: Span,
@@ -447,6 +491,10 @@ pub(crate) enum ErrorKind {
         #[cfg(feature = "emit")]
         fields: Box<[Box<str>]>,
     },
+    MatchMissingVariants {
+        item: ItemBuf,
+        variants: Box<[Box<str>]>,
+    },
     MissingLabelLocation {
         name: &'static str,
         index: usize,
@@ -455,9 +503,16 @@ pub(crate) enum ErrorKind {
         depth: usize,
         max: usize,
     },
-    YieldInConst,
-    AwaitInConst,
-    AwaitOutsideAsync,
+    YieldInConst {
+        function: Span,
+    },
+    AwaitInConst {
+        function: Span,
+    },
+    AwaitOutsideAsync {
+        function: Span,
+        closure: bool,
+    },
     ExpectedEof {
         actual: ast::Kind,
     },
@@ -619,6 +674,180 @@ impl ErrorKind {
             error: anyhow::Error::msg(message),
         }
     }
+
+    /// The stable name of the error kind, like `missing-item`.
+    #[cfg(feature = "languageserver")]
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            ErrorKind::Custom { .. } => "custom",
+            ErrorKind::AllocError { .. } => "alloc-error",
+            ErrorKind::IrError(..) => "ir-error",
+            ErrorKind::MetaError(..) => "meta-error",
+            ErrorKind::AccessError(..) => "access-error",
+            ErrorKind::VmError(..) => "vm-error",
+            ErrorKind::EncodeError(..) => "encode-error",
+            ErrorKind::MissingLastId(..) => "missing-last-id",
+            ErrorKind::GuardMismatch(..) => "guard-mismatch",
+            ErrorKind::MissingScope(..) => "missing-scope",
+            ErrorKind::PopError(..) => "pop-error",
+            ErrorKind::UnescapeError(..) => "unescape-error",
+            ErrorKind::Syntree(..) => "syntree",
+            ErrorKind::TooManyParameters(..) => "too-many-parameters",
+            ErrorKind::FormatError => "format-error",
+            #[cfg(feature = "std")]
+            ErrorKind::SourceError { .. } => "source-error",
+            ErrorKind::Expected { .. } => "expected",
+            ErrorKind::Unsupported { .. } => "unsupported",
+            #[cfg(feature = "std")]
+            ErrorKind::ModNotFound { .. } => "mod-not-found",
+            ErrorKind::ModAlreadyLoaded { .. } => "mod-already-loaded",
+            ErrorKind::MissingMacro { .. } => "missing-macro",
+            ErrorKind::MissingSelf => "missing-self",
+            ErrorKind::MissingLocal { .. } => "missing-local",
+            ErrorKind::MissingItem { .. } => "missing-item",
+            ErrorKind::MissingItemHash { .. } => "missing-item-hash",
+            ErrorKind::MissingItemParameters { .. } => "missing-item-parameters",
+            ErrorKind::MissingKnownItem { .. } => "missing-known-item",
+            ErrorKind::UnsupportedGlobal => "unsupported-global",
+            ErrorKind::UnsupportedModuleSource => "unsupported-module-source",
+            #[cfg(feature = "std")]
+            ErrorKind::UnsupportedModuleRoot { .. } => "unsupported-module-root",
+            #[cfg(feature = "std")]
+            ErrorKind::UnsupportedModuleItem { .. } => "unsupported-module-item",
+            ErrorKind::UnsupportedSelf => "unsupported-self",
+            ErrorKind::UnsupportedUnaryOp { .. } => "unsupported-unary-op",
+            ErrorKind::UnsupportedBinaryOp { .. } => "unsupported-binary-op",
+            ErrorKind::UnsupportedLitObject { .. } => "unsupported-lit-object",
+            ErrorKind::LitObjectMissingField { .. } => "lit-object-missing-field",
+            ErrorKind::LitObjectNotField { .. } => "lit-object-not-field",
+            ErrorKind::UnsupportedAssignExpr => "unsupported-assign-expr",
+            ErrorKind::UnsupportedBinaryExpr => "unsupported-binary-expr",
+            ErrorKind::UnsupportedRef => "unsupported-ref",
+            ErrorKind::BadArgumentCount { .. } => "bad-argument-count",
+            ErrorKind::UnsupportedPatternExpr => "unsupported-pattern-expr",
+            ErrorKind::UnsupportedBinding => "unsupported-binding",
+            ErrorKind::DuplicateObjectKey { .. } => "duplicate-object-key",
+            ErrorKind::InstanceFunctionOutsideImpl => "instance-function-outside-impl",
+            ErrorKind::UnsupportedTupleIndex { .. } => "unsupported-tuple-index",
+            ErrorKind::BreakUnsupported => "break-unsupported",
+            ErrorKind::BreakUnsupportedValue { .. } => "break-unsupported-value",
+            ErrorKind::ContinueUnsupported => "continue-unsupported",
+            ErrorKind::ContinueUnsupportedBlock => "continue-unsupported-block",
+            ErrorKind::SelectMultipleDefaults => "select-multiple-defaults",
+            ErrorKind::ExpectedBlockSemiColon { .. } => "expected-block-semi-colon",
+            ErrorKind::FnConstAsyncConflict => "fn-const-async-conflict",
+            ErrorKind::BlockConstAsyncConflict => "block-const-async-conflict",
+            ErrorKind::ClosureKind => "closure-kind",
+            ErrorKind::UnsupportedSelfType => "unsupported-self-type",
+            ErrorKind::UnsupportedSuper => "unsupported-super",
+            ErrorKind::UnsupportedSuperInSelfType => "unsupported-super-in-self-type",
+            ErrorKind::UnsupportedAfterGeneric => "unsupported-after-generic",
+            ErrorKind::IllegalUseSegment => "illegal-use-segment",
+            ErrorKind::UseAliasNotSupported => "use-alias-not-supported",
+            ErrorKind::FunctionConflict { .. } => "function-conflict",
+            ErrorKind::FunctionReExportConflict { .. } => "function-re-export-conflict",
+            ErrorKind::ConstantConflict { .. } => "constant-conflict",
+            ErrorKind::StaticStringMissing { .. } => "static-string-missing",
+            ErrorKind::StaticBytesMissing { .. } => "static-bytes-missing",
+            ErrorKind::StaticStringHashConflict { .. } => "static-string-hash-conflict",
+            ErrorKind::StaticBytesHashConflict { .. } => "static-bytes-hash-conflict",
+            ErrorKind::StaticObjectKeysMissing { .. } => "static-object-keys-missing",
+            ErrorKind::StaticObjectKeysHashConflict { .. } => "static-object-keys-hash-conflict",
+            ErrorKind::ConflictingLabels { .. } => "conflicting-labels",
+            ErrorKind::DuplicateSelectDefault { .. } => "duplicate-select-default",
+            ErrorKind::MissingLabel { .. } => "missing-label",
+            ErrorKind::ExpectedLeadingPathSegment => "expected-leading-path-segment",
+            ErrorKind::UnsupportedVisibility => "unsupported-visibility",
+            ErrorKind::ExpectedMeta { .. } => "expected-meta",
+            ErrorKind::NoSuchBuiltInMacro { .. } => "no-such-built-in-macro",
+            ErrorKind::VariableMoved { .. } => "variable-moved",
+            ErrorKind::UnsupportedGenerics => "unsupported-generics",
+            ErrorKind::UnsupportedProtocol { .. } => "unsupported-protocol",
+            ErrorKind::NestedTest { .. } => "nested-test",
+            ErrorKind::NestedBench { .. } => "nested-bench",
+            ErrorKind::MissingFunctionHash { .. } => "missing-function-hash",
+            ErrorKind::FunctionConflictHash { .. } => "function-conflict-hash",
+            ErrorKind::PatternMissingFields { .. } => "pattern-missing-fields",
+            ErrorKind::MatchMissingVariants { .. } => "match-missing-variants",
+            ErrorKind::MissingLabelLocation { .. } => "missing-label-location",
+            ErrorKind::MaxMacroRecursion { .. } => "max-macro-recursion",
+            ErrorKind::YieldInConst { .. } => "yield-in-const",
+            ErrorKind::AwaitInConst { .. } => "await-in-const",
+            ErrorKind::AwaitOutsideAsync { .. } => "await-outside-async",
+            ErrorKind::ExpectedEof { .. } => "expected-eof",
+            ErrorKind::UnexpectedEof => "unexpected-eof",
+            ErrorKind::BadLexerMode { .. } => "bad-lexer-mode",
+            ErrorKind::ExpectedEscape => "expected-escape",
+            ErrorKind::UnterminatedStrLit => "unterminated-str-lit",
+            ErrorKind::UnterminatedByteStrLit => "unterminated-byte-str-lit",
+            ErrorKind::UnterminatedCharLit => "unterminated-char-lit",
+            ErrorKind::UnterminatedByteLit => "unterminated-byte-lit",
+            ErrorKind::ExpectedCharClose => "expected-char-close",
+            ErrorKind::ExpectedCharOrLabel => "expected-char-or-label",
+            ErrorKind::ExpectedByteClose => "expected-byte-close",
+            ErrorKind::UnexpectedChar { .. } => "unexpected-char",
+            ErrorKind::PrecedenceGroupRequired => "precedence-group-required",
+            ErrorKind::BadSignedOutOfBounds { .. } => "bad-signed-out-of-bounds",
+            ErrorKind::BadUnsignedOutOfBounds { .. } => "bad-unsigned-out-of-bounds",
+            ErrorKind::BadFieldAccess => "bad-field-access",
+            ErrorKind::ExpectedMacroCloseDelimiter { .. } => "expected-macro-close-delimiter",
+            ErrorKind::MultipleMatchingAttributes { .. } => "multiple-matching-attributes",
+            ErrorKind::MissingSourceId { .. } => "missing-source-id",
+            ErrorKind::ExpectedMultilineCommentTerm => "expected-multiline-comment-term",
+            ErrorKind::BadSlice => "bad-slice",
+            ErrorKind::BadSyntheticId { .. } => "bad-synthetic-id",
+            ErrorKind::BadCharLiteral => "bad-char-literal",
+            ErrorKind::BadByteLiteral => "bad-byte-literal",
+            ErrorKind::BadNumberLiteral => "bad-number-literal",
+            ErrorKind::AmbiguousItem { .. } => "ambiguous-item",
+            ErrorKind::AmbiguousContextItem { .. } => "ambiguous-context-item",
+            ErrorKind::NotVisible { .. } => "not-visible",
+            ErrorKind::NotVisibleMod { .. } => "not-visible-mod",
+            ErrorKind::MissingMod { .. } => "missing-mod",
+            ErrorKind::ImportCycle { .. } => "import-cycle",
+            ErrorKind::ImportRecursionLimit { .. } => "import-recursion-limit",
+            ErrorKind::LastUseComponent => "last-use-component",
+            ErrorKind::RttiConflict { .. } => "rtti-conflict",
+            ErrorKind::TypeRttiConflict { .. } => "type-rtti-conflict",
+            ErrorKind::ArenaWriteSliceOutOfBounds { .. } => "arena-write-slice-out-of-bounds",
+            ErrorKind::ArenaAllocError { .. } => "arena-alloc-error",
+            ErrorKind::UnsupportedPatternRest => "unsupported-pattern-rest",
+            ErrorKind::UnsupportedMut => "unsupported-mut",
+            ErrorKind::UnsupportedSuffix => "unsupported-suffix",
+            ErrorKind::ClosureInConst => "closure-in-const",
+            ErrorKind::AsyncBlockInConst => "async-block-in-const",
+            #[cfg(feature = "fmt")]
+            ErrorKind::BadSpan { .. } => "bad-span",
+            ErrorKind::UnexpectedEndOfSyntax { .. } => "unexpected-end-of-syntax",
+            ErrorKind::UnexpectedEndOfSyntaxWith { .. } => "unexpected-end-of-syntax-with",
+            ErrorKind::ExpectedSyntaxEnd { .. } => "expected-syntax-end",
+            #[cfg(feature = "fmt")]
+            ErrorKind::BadIndent { .. } => "bad-indent",
+            ErrorKind::ExpectedSyntax { .. } => "expected-syntax",
+            ErrorKind::ExpectedSyntaxIn { .. } => "expected-syntax-in",
+            ErrorKind::ExpectedOne { .. } => "expected-one",
+            ErrorKind::ExpectedAtMostOne { .. } => "expected-at-most-one",
+            ErrorKind::ExpectedAtLeastOne { .. } => "expected-at-least-one",
+            #[cfg(feature = "fmt")]
+            ErrorKind::UnsupportedDelimiter { .. } => "unsupported-delimiter",
+        }
+    }
+
+    /// Get a machine-applicable suggestion for the error kind.
+    pub(crate) fn suggestion(&self) -> Option<Suggestion> {
+        match self {
+            ErrorKind::AwaitOutsideAsync { function, closure } => Some(Suggestion {
+                span: function.head(),
+                replacement: "async ",
+                message: if *closure {
+                    "Make the closure async"
+                } else {
+                    "Make the function async"
+                },
+            }),
+            _ => None,
+        }
+    }
 }
 
 impl core::error::Error for ErrorKind {
@@ -722,7 +951,7 @@ impl fmt::Display for ErrorKind {
             ErrorKind::MissingLocal { name } => {
                 write!(f, "No local variable `{name}`")?;
             }
-            ErrorKind::MissingItem { item } => {
+            ErrorKind::MissingItem { item, .. } => {
                 write!(f, "Missing item {item}")?;
             }
             ErrorKind::MissingItemHash { hash } => {
@@ -734,6 +963,9 @@ impl fmt::Display for ErrorKind {
             ErrorKind::MissingItemParameters { item, parameters } => {
                 write!(f, "Missing item {}", ParameterizedItem(item, parameters))?;
             }
+            ErrorKind::MissingKnownItem { item, .. } => {
+                write!(f, "Missing item {item}")?;
+            }
             ErrorKind::UnsupportedGlobal => {
                 write!(f, "Unsupported crate prefix `::`")?;
             }
@@ -770,7 +1002,7 @@ impl fmt::Display for ErrorKind {
             ErrorKind::LitObjectMissingField { field, item } => {
                 write!(f, "Missing field `{field}` in declaration of `{item}`")?;
             }
-            ErrorKind::LitObjectNotField { field, item } => {
+            ErrorKind::LitObjectNotField { field, item, .. } => {
                 write!(f, "Field `{field}` is not a field in `{item}`")?;
             }
             ErrorKind::UnsupportedAssignExpr => {
@@ -803,7 +1035,7 @@ impl fmt::Display for ErrorKind {
             ErrorKind::BreakUnsupported => {
                 write!(f, "Break outside of loop")?;
             }
-            ErrorKind::BreakUnsupportedValue => {
+            ErrorKind::BreakUnsupportedValue { .. } => {
                 write!(
                     f,
                     "Can only break with a value inside `loop` or breakable block"
@@ -931,7 +1163,7 @@ impl fmt::Display for ErrorKind {
             ErrorKind::DuplicateSelectDefault { .. } => {
                 write!(f, "Multiple default select branches")?;
             }
-            ErrorKind::MissingLabel { label } => {
+            ErrorKind::MissingLabel { label, .. } => {
                 write!(f, "Missing label '{label}")?;
             }
             ErrorKind::ExpectedLeadingPathSegment => {
@@ -952,6 +1184,9 @@ impl fmt::Display for ErrorKind {
             ErrorKind::UnsupportedGenerics => {
                 write!(f, "Unsupported generic argument")?;
             }
+            ErrorKind::UnsupportedProtocol { name } => {
+                write!(f, "Unsupported protocol `{name}`")?;
+            }
             ErrorKind::NestedTest { .. } => {
                 write!(f, "Attribute `#[test]` is not supported on nested items")?;
             }
@@ -967,6 +1202,28 @@ impl fmt::Display for ErrorKind {
             ErrorKind::PatternMissingFields { item, .. } => {
                 write!(f, "Non-exhaustive pattern for `{item}`")?;
             }
+            ErrorKind::MatchMissingVariants { item, variants } => {
+                write!(f, "Non-exhaustive match over `{item}`, missing ")?;
+                write!(
+                    f,
+                    "{}",
+                    if variants.len() == 1 {
+                        "variant"
+                    } else {
+                        "variants"
+                    }
+                )?;
+
+                let mut it = variants.iter();
+
+                if let Some(variant) = it.next() {
+                    write!(f, " `{variant}`")?;
+                }
+
+                for variant in it {
+                    write!(f, ", `{variant}`")?;
+                }
+            }
             ErrorKind::MissingLabelLocation { name, index } => {
                 write!(
                     f,
@@ -979,13 +1236,13 @@ impl fmt::Display for ErrorKind {
                     "Reached macro recursion limit at {depth}, limit is {max}",
                 )?;
             }
-            ErrorKind::YieldInConst => {
+            ErrorKind::YieldInConst { .. } => {
                 write!(f, "Expression `yield` inside of constant function")?;
             }
-            ErrorKind::AwaitInConst => {
+            ErrorKind::AwaitInConst { .. } => {
                 write!(f, "Expression `.await` inside of constant context")?;
             }
-            ErrorKind::AwaitOutsideAsync => {
+            ErrorKind::AwaitOutsideAsync { .. } => {
                 write!(f, "Expression `.await` outside of async function or block")?;
             }
             ErrorKind::ExpectedEof { actual } => {
@@ -1399,6 +1656,8 @@ pub(crate) enum IrErrorKind {
         actual: usize,
         expected: usize,
     },
+    /// Calling a function which can't be called in constant contexts.
+    NotConstFn,
 }
 
 impl core::error::Error for IrErrorKind {}
@@ -1436,6 +1695,12 @@ impl fmt::Display for IrErrorKind {
                     "Argument count mismatch, got {actual} but expected {expected}",
                 )?;
             }
+            IrErrorKind::NotConstFn => {
+                write!(
+                    f,
+                    "Only functions marked as `const` can be called in constant contexts"
+                )?;
+            }
         }
 
         Ok(())
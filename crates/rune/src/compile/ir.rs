@@ -21,6 +21,7 @@ use crate::indexing::index;
 use crate::macros::MacroContext;
 use crate::query::Used;
 use crate::runtime::{Inline, Value};
+use crate::Hash;
 
 pub(crate) use self::compiler::Ctxt;
 pub(crate) use self::eval::{eval_ir, EvalOutcome};
@@ -464,7 +465,18 @@ pub(crate) struct IrCall {
     /// Arguments to the call.
     pub(crate) args: Vec<Ir>,
     /// The target of the call.
-    pub(crate) id: ItemId,
+    pub(crate) target: IrCallTarget,
+}
+
+/// The target of a call expression.
+#[derive(Debug, TryClone, Clone, Copy)]
+#[try_clone(copy)]
+pub(crate) enum IrCallTarget {
+    /// A `const fn` declared in a script.
+    ConstFn(ItemId),
+    /// A native function which has been marked as callable in constant
+    /// contexts.
+    Native(Hash),
 }
 
 /// Vector expression.
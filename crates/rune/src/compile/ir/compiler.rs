@@ -4,10 +4,10 @@ use crate::alloc::prelude::*;
 use crate::alloc::{try_format, Box, Vec};
 use crate::ast::{self, Span, Spanned};
 use crate::compile::ir;
-use crate::compile::{self, ErrorKind, WithSpan};
+use crate::compile::{self, ErrorKind, IrErrorKind, WithSpan};
 use crate::hir;
 use crate::query::Query;
-use crate::runtime::{Bytes, Value};
+use crate::runtime::{Bytes, ConstValue, ConstValueKind, Value};
 use crate::SourceId;
 
 use tracing::instrument_ast;
@@ -37,7 +37,14 @@ pub(crate) fn expr(hir: &hir::Expr<'_>, c: &mut Ctxt<'_, '_>) -> compile::Result
         hir::ExprKind::Loop(hir) => ir::Ir::new(span, expr_loop(span, c, hir)?),
         hir::ExprKind::Lit(hir) => lit(c, span, hir)?,
         hir::ExprKind::Block(hir) => ir::Ir::new(span, block(hir, c)?),
-        hir::ExprKind::FieldAccess(..) => ir::Ir::new(span, ir_target(hir)?),
+        hir::ExprKind::FieldAccess(..) => {
+            if let Some(value) = const_field_access(hir, c)? {
+                let value = value.to_value_with(c.q.context).with_span(span)?;
+                return Ok(ir::Ir::new(span, value));
+            }
+
+            ir::Ir::new(span, ir_target(hir)?)
+        }
         hir::ExprKind::Break(hir) => ir::Ir::new(span, ir::IrBreak::compile_ast(span, c, hir)?),
         hir::ExprKind::Template(template) => {
             let ir_template = builtin_template(template, c)?;
@@ -66,6 +73,43 @@ pub(crate) fn expr(hir: &hir::Expr<'_>, c: &mut Ctxt<'_, '_>) -> compile::Result
     })
 }
 
+/// Resolve a field access into a constant, like `Thing::PAIR.0`, at compile
+/// time.
+fn const_field_access(
+    expr: &hir::Expr<'_>,
+    c: &mut Ctxt<'_, '_>,
+) -> compile::Result<Option<ConstValue>> {
+    let value = match expr.kind {
+        hir::ExprKind::Const(hash) => {
+            let Some(value) = c.q.get_const_value(hash) else {
+                return Ok(None);
+            };
+
+            value.try_clone()?
+        }
+        hir::ExprKind::FieldAccess(expr_field_access) => {
+            let Some(value) = const_field_access(&expr_field_access.expr, c)? else {
+                return Ok(None);
+            };
+
+            let field = match (value.as_kind(), expr_field_access.expr_field) {
+                (ConstValueKind::Tuple(values), hir::ExprField::Index(index)) => values.get(index),
+                (ConstValueKind::Object(object), hir::ExprField::Ident(name)) => object.get(name),
+                _ => return Ok(None),
+            };
+
+            let Some(field) = field else {
+                return Err(compile::Error::new(expr, ErrorKind::BadFieldAccess));
+            };
+
+            field.try_clone()?
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(Some(value))
+}
+
 /// Resolve an ir target from an expression.
 fn ir_target(expr: &hir::Expr<'_>) -> compile::Result<ir::IrTarget> {
     match expr.kind {
@@ -132,14 +176,24 @@ fn expr_call(
         args.try_push(expr(e, c)?)?;
     }
 
-    if let hir::Call::ConstFn { id, .. } = hir.call {
-        return Ok(ir::IrCall { span, id, args });
-    }
+    let target = match hir.call {
+        hir::Call::ConstFn { id, .. } => ir::IrCallTarget::ConstFn(id),
+        hir::Call::Meta { hash } => {
+            if c.q.context.lookup_const_fn(hash).is_none() {
+                return Err(compile::Error::new(span, IrErrorKind::NotConstFn));
+            }
 
-    Err(compile::Error::msg(
-        span,
-        "Call not supported in constant contexts",
-    ))
+            ir::IrCallTarget::Native(hash)
+        }
+        _ => {
+            return Err(compile::Error::msg(
+                span,
+                "Call not supported in constant contexts",
+            ));
+        }
+    };
+
+    Ok(ir::IrCall { span, args, target })
 }
 
 #[instrument_ast]
@@ -391,6 +445,10 @@ fn condition(hir: &hir::Condition<'_>, c: &mut Ctxt<'_, '_>) -> compile::Result<
                 ir,
             }))
         }
+        hir::Condition::Chain(span, ..) => Err(compile::Error::msg(
+            span,
+            "Chained conditions are not supported in constant contexts",
+        )),
     }
 }
 
@@ -204,7 +204,10 @@ fn eval_ir_call(
         args.try_push(eval_ir(arg, interp, used)?)?;
     }
 
-    Ok(interp.call_const_fn(ir, ir.id, args, used)?)
+    match ir.target {
+        ir::IrCallTarget::ConstFn(id) => Ok(interp.call_const_fn(ir, id, args, used)?),
+        ir::IrCallTarget::Native(hash) => Ok(interp.call_native_const_fn(ir, hash, args)?),
+    }
 }
 
 fn eval_ir_condition(
@@ -7,8 +7,8 @@ use crate::compile::meta;
 use crate::compile::{self, IrErrorKind, ItemId, ModId, WithSpan};
 use crate::hir;
 use crate::query::{Query, Used};
-use crate::runtime::{self, ConstValue, Object, OwnedTuple, Repr, Value};
-use crate::TypeHash;
+use crate::runtime::{self, ConstValue, InstAddress, Object, OwnedTuple, Repr, Stack, Value};
+use crate::{Hash, TypeHash};
 
 /// The interpreter that executed [Ir][crate::ir::Ir].
 pub struct Interpreter<'a, 'arena> {
@@ -176,6 +176,54 @@ impl Interpreter<'_, '_> {
         self.scopes.pop(guard).with_span(span)?;
         Ok(value)
     }
+
+    /// Call a native function which has been marked as callable in constant
+    /// contexts.
+    ///
+    /// Arguments and the return value are passed through [`ConstValue`], which
+    /// ensures that nothing which can't be represented as a constant leaks in
+    /// or out of the call.
+    pub(crate) fn call_native_const_fn<S>(
+        &mut self,
+        spanned: S,
+        hash: Hash,
+        args: Vec<Value>,
+    ) -> compile::Result<Value>
+    where
+        S: Copy + Spanned,
+    {
+        let span = Spanned::span(&spanned);
+        self.budget.take(span)?;
+
+        let Some(handler) = self.q.context.lookup_const_fn(hash) else {
+            return Err(compile::Error::new(span, IrErrorKind::NotConstFn));
+        };
+
+        let count = args.len();
+        let size = count.max(1);
+        let mut stack = Stack::with_capacity(size)?;
+
+        for value in args {
+            let value = ConstValue::from_value_ref(&value).with_span(span)?;
+            stack
+                .push(value.to_value_with(self.q.context).with_span(span)?)
+                .with_span(span)?;
+        }
+
+        stack.resize(size)?;
+
+        handler(
+            &mut stack,
+            InstAddress::ZERO,
+            count,
+            InstAddress::ZERO.output(),
+        )
+        .into_result()
+        .with_span(span)?;
+
+        let value = ConstValue::from_value_ref(stack.at(InstAddress::ZERO)).with_span(span)?;
+        Ok(value.to_value_with(self.q.context).with_span(span)?)
+    }
 }
 
 impl ir::Scopes {
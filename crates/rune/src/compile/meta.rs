@@ -33,6 +33,9 @@ pub struct MetaRef<'a> {
     pub kind: &'a Kind,
     /// The source of the meta.
     pub source: Option<&'a SourceMeta>,
+    /// If the item is publicly visible, which requires both the item and all
+    /// of the modules containing it to be declared `pub`.
+    pub public: bool,
 }
 
 /// Information on a compile sourc.
@@ -107,6 +110,7 @@ impl Meta {
             item: pool.item(self.item_meta.item),
             kind: &self.kind,
             source: self.source.as_ref(),
+            public: self.item_meta.is_public(pool),
         }
     }
 
@@ -4,18 +4,18 @@
 //! uses this compiler. In here you'll just find compiler-specific types.
 
 mod assembly;
-pub(crate) use self::assembly::{Assembly, AssemblyInst};
+pub(crate) use self::assembly::{Assembly, AssemblyInst, AssemblyVariable};
 
 pub(crate) mod attrs;
 
 pub(crate) mod error;
-pub use self::error::{Error, ImportStep, MetaError};
+pub use self::error::{Error, ImportStep, MetaError, Suggestion};
 pub(crate) use self::error::{ErrorKind, IrErrorKind};
 
 mod compile_visitor;
-pub use self::compile_visitor::CompileVisitor;
 #[cfg(feature = "std")]
 pub(crate) use self::compile_visitor::NoopCompileVisitor;
+pub use self::compile_visitor::{CompileVisitor, DeclarationKind};
 
 pub(crate) mod context;
 pub use self::context::Context;
@@ -32,6 +32,9 @@ pub(crate) use self::docs::Docs;
 mod prelude;
 pub(crate) use self::prelude::Prelude;
 
+mod similar;
+pub(crate) use self::similar::{similar, SimilarItem};
+
 pub(crate) mod ir;
 
 mod source_loader;
@@ -41,13 +44,13 @@ pub use self::source_loader::{NoopSourceLoader, SourceLoader};
 
 mod unit_builder;
 pub use self::unit_builder::LinkerError;
-pub(crate) use self::unit_builder::UnitBuilder;
+pub(crate) use self::unit_builder::{Recording, UnitBuilder};
 
 pub(crate) mod v1;
 
 mod options;
 #[cfg(any(feature = "fmt", feature = "languageserver"))]
-pub(crate) use self::options::FmtOptions;
+pub(crate) use self::options::{FmtOptions, NewlineStyle, TrailingComma};
 pub use self::options::{Options, ParseOptionError};
 
 mod location;
@@ -77,5 +80,9 @@ pub(crate) use self::with_span::{HasSpan, WithSpan};
 mod compile;
 pub(crate) use self::compile::compile;
 
+mod build_cache;
+pub use self::build_cache::BuildCache;
+pub(crate) use self::build_cache::{FileCache, ItemCache};
+
 /// Helper alias for compile results.
 pub type Result<T, E = Error> = core::result::Result<T, E>;
@@ -1,6 +1,12 @@
 use core::fmt;
 
 use ::rust_alloc::boxed::Box;
+use ::rust_alloc::vec::Vec;
+
+use crate::alloc;
+use crate::alloc::prelude::*;
+use crate::diagnostics::WarningDiagnosticKind;
+use crate::hash::{Hash, ToTypeHash};
 
 /// Error raised when trying to parse an invalid option.
 #[derive(Debug, Clone)]
@@ -23,6 +29,28 @@ impl fmt::Display for ParseOptionError {
 
 impl core::error::Error for ParseOptionError {}
 
+/// How the formatter treats trailing commas in lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TrailingComma {
+    /// Always write a trailing comma, even for lists on a single line.
+    Always,
+    /// Never write a trailing comma.
+    Never,
+    /// Only write a trailing comma for lists which are laid out vertically.
+    Vertical,
+}
+
+/// The newline style used by the formatter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum NewlineStyle {
+    /// Use `\n` line endings.
+    Unix,
+    /// Use `\r\n` line endings.
+    Windows,
+    /// Use the line endings of the first line in the formatted source.
+    Auto,
+}
+
 /// Options specific to formatting.
 #[derive(Debug, Clone)]
 pub(crate) struct FmtOptions {
@@ -30,6 +58,16 @@ pub(crate) struct FmtOptions {
     pub(crate) error_recovery: bool,
     /// Force newline at end of document.
     pub(crate) force_newline: bool,
+    /// The number of spaces used for each level of indentation.
+    pub(crate) indent_width: usize,
+    /// Indent using tabs instead of spaces.
+    pub(crate) hard_tabs: bool,
+    /// The width after which lists and chains are expanded.
+    pub(crate) max_width: usize,
+    /// How trailing commas are written.
+    pub(crate) trailing_comma: TrailingComma,
+    /// The style of newlines being written.
+    pub(crate) newline_style: NewlineStyle,
 }
 
 impl FmtOptions {
@@ -37,6 +75,11 @@ impl FmtOptions {
     pub(crate) const DEFAULT: Self = Self {
         error_recovery: false,
         force_newline: true,
+        indent_width: 4,
+        hard_tabs: false,
+        max_width: 80,
+        trailing_comma: TrailingComma::Vertical,
+        newline_style: NewlineStyle::Unix,
     };
 
     /// Parse an option with the extra diagnostics metadata.
@@ -58,6 +101,55 @@ impl FmtOptions {
             "force-newline" => {
                 self.force_newline = tail.map_or(true, |s| s == "true");
             }
+            "indent-width" => {
+                let Some(Ok(number)) = tail.map(str::parse) else {
+                    return Err(ParseOptionError {
+                        env,
+                        option: option.into(),
+                    });
+                };
+
+                self.indent_width = number;
+            }
+            "hard-tabs" => {
+                self.hard_tabs = tail.map_or(true, |s| s == "true");
+            }
+            "max-width" => {
+                let Some(Ok(number)) = tail.map(str::parse) else {
+                    return Err(ParseOptionError {
+                        env,
+                        option: option.into(),
+                    });
+                };
+
+                self.max_width = number;
+            }
+            "trailing-comma" => {
+                self.trailing_comma = match tail {
+                    Some("always") => TrailingComma::Always,
+                    Some("never") => TrailingComma::Never,
+                    Some("vertical") => TrailingComma::Vertical,
+                    _ => {
+                        return Err(ParseOptionError {
+                            env,
+                            option: option.into(),
+                        });
+                    }
+                };
+            }
+            "newline-style" => {
+                self.newline_style = match tail {
+                    Some("unix") => NewlineStyle::Unix,
+                    Some("windows") => NewlineStyle::Windows,
+                    Some("auto") => NewlineStyle::Auto,
+                    _ => {
+                        return Err(ParseOptionError {
+                            env,
+                            option: option.into(),
+                        });
+                    }
+                };
+            }
             _ => {
                 return Err(ParseOptionError {
                     env,
@@ -107,6 +199,24 @@ pub struct Options {
     pub(crate) macros: bool,
     /// Support bytecode caching.
     pub(crate) bytecode: bool,
+    /// Emit warnings when deprecated functions are used.
+    pub(crate) deprecations: bool,
+    /// Fold expressions which only operate on literals during compilation.
+    pub(crate) constant_folding: bool,
+    /// Emit warnings for variables which are never read.
+    pub(crate) unused_variables: bool,
+    /// Emit warnings listing the variables captured by closures and async
+    /// blocks.
+    pub(crate) closure_captures: bool,
+    /// Treat all warnings as errors.
+    pub(crate) warnings_as_errors: bool,
+    /// Names of warnings which are treated as errors.
+    pub(crate) deny: Vec<&'static str>,
+    /// Remove functions which can't be reached from any entry point.
+    pub(crate) dce: bool,
+    /// Entry points to retain when performing dead code elimination, instead
+    /// of all public functions.
+    pub(crate) retain: Option<Vec<Hash>>,
     /// Build sources as function bodies.
     ///
     /// The function to run will be named 0, which can be constructed with
@@ -122,6 +232,9 @@ pub struct Options {
     pub(crate) v2: bool,
     /// Maximum macro depth.
     pub(crate) max_macro_depth: usize,
+    /// Parse sources in parallel.
+    #[cfg_attr(not(feature = "parallel"), allow(dead_code))]
+    pub(crate) parallel: bool,
     /// Rune format options.
     pub(crate) fmt: FmtOptions,
 }
@@ -134,12 +247,21 @@ impl Options {
         debug_info: true,
         macros: true,
         bytecode: false,
+        deprecations: true,
+        constant_folding: true,
+        unused_variables: true,
+        closure_captures: false,
+        warnings_as_errors: false,
+        deny: Vec::new(),
+        dce: false,
+        retain: None,
         function_body: false,
         test_std: false,
         lowering: 0,
         print_tree: false,
         v2: false,
         max_macro_depth: 64,
+        parallel: false,
         fmt: FmtOptions::DEFAULT,
     };
 
@@ -162,6 +284,79 @@ impl Options {
         Ok(options)
     }
 
+    /// Compute a fingerprint of the options, which changes if any option
+    /// changes.
+    ///
+    /// This is used to invalidate a [`BuildCache`].
+    ///
+    /// [`BuildCache`]: crate::compile::BuildCache
+    pub(crate) fn fingerprint(&self) -> alloc::Result<Hash> {
+        // NB: Destructured so that adding an option requires deciding whether
+        // it affects the output of a build.
+        let Self {
+            link_checks,
+            memoize_instance_fn,
+            debug_info,
+            macros,
+            bytecode: _,
+            deprecations,
+            constant_folding,
+            unused_variables,
+            closure_captures,
+            warnings_as_errors,
+            ref deny,
+            dce,
+            ref retain,
+            function_body,
+            test_std,
+            lowering,
+            print_tree,
+            v2,
+            max_macro_depth,
+            parallel: _,
+            fmt: _,
+        } = *self;
+
+        let mut bytes = alloc::Vec::new();
+
+        for flag in [
+            link_checks,
+            memoize_instance_fn,
+            debug_info,
+            macros,
+            deprecations,
+            constant_folding,
+            unused_variables,
+            closure_captures,
+            warnings_as_errors,
+            dce,
+            function_body,
+            test_std,
+            print_tree,
+            v2,
+        ] {
+            bytes.try_push(u8::from(flag))?;
+        }
+
+        bytes.try_push(lowering)?;
+        bytes.try_extend_from_slice(&(max_macro_depth as u64).to_le_bytes())?;
+
+        for name in deny {
+            bytes.try_extend_from_slice(name.as_bytes())?;
+            bytes.try_push(0)?;
+        }
+
+        if let Some(retain) = retain {
+            bytes.try_push(1)?;
+
+            for hash in retain {
+                bytes.try_extend_from_slice(&hash.into_inner().to_le_bytes())?;
+            }
+        }
+
+        Ok(Hash::static_bytes(&bytes))
+    }
+
     /// Get a list and documentation for all available compiler options.
     pub fn available() -> &'static [OptionMeta] {
         static BOOL: &str = "true, false";
@@ -211,8 +406,83 @@ impl Options {
                 key: "bytecode",
                 unstable: true,
                 doc: &docstring! {
-                    /// Make use of bytecode, which might make
-                    /// compilation units smaller.
+                    /// Cache compiled units next to scripts with the
+                    /// `rnc` extension, and reuse them as long as the
+                    /// script has not been modified.
+                },
+                default: "false",
+                options: BOOL,
+            },
+            OptionMeta {
+                key: "deprecations",
+                unstable: false,
+                doc: &docstring! {
+                    /// Emit warnings when deprecated functions are
+                    /// used.
+                },
+                default: "true",
+                options: BOOL,
+            },
+            OptionMeta {
+                key: "constant-folding",
+                unstable: false,
+                doc: &docstring! {
+                    /// Fold expressions which only operate on
+                    /// literals, like `2 * 60 * 60`, into a single
+                    /// value during compilation.
+                },
+                default: "true",
+                options: BOOL,
+            },
+            OptionMeta {
+                key: "unused-variables",
+                unstable: false,
+                doc: &docstring! {
+                    /// Emit warnings for variables and arguments which
+                    /// are never read. Variables prefixed with an
+                    /// underscore, like `_unused`, are exempt.
+                },
+                default: "true",
+                options: BOOL,
+            },
+            OptionMeta {
+                key: "closure-captures",
+                unstable: false,
+                doc: &docstring! {
+                    /// Emit warnings listing the variables captured by
+                    /// each closure and async block. This is enabled by
+                    /// `--warnings` in the CLI.
+                },
+                default: "false",
+                options: BOOL,
+            },
+            OptionMeta {
+                key: "warnings-as-errors",
+                unstable: false,
+                doc: &docstring! {
+                    /// Treat all warnings as errors, causing the build
+                    /// to fail if any are emitted.
+                },
+                default: "false",
+                options: BOOL,
+            },
+            OptionMeta {
+                key: "deny",
+                unstable: false,
+                doc: &docstring! {
+                    /// Treat the named warning as an error, causing the
+                    /// build to fail if it is emitted. Can be specified
+                    /// multiple times to deny several warnings.
+                },
+                default: "",
+                options: WarningDiagnosticKind::NAMES_LIST,
+            },
+            OptionMeta {
+                key: "dce",
+                unstable: true,
+                doc: &docstring! {
+                    /// Remove functions which can't be reached from
+                    /// any public function from the compiled unit.
                 },
                 default: "false",
                 options: BOOL,
@@ -282,6 +552,20 @@ impl Options {
                 default: "64",
                 options: "<number>",
             },
+            OptionMeta {
+                key: "parallel",
+                unstable: true,
+                doc: &docstring! {
+                    /// Parse sources on multiple threads.
+                    ///
+                    /// Only parsing is performed in parallel. Indexing,
+                    /// lowering and assembly are still performed on the
+                    /// calling thread. Only has an effect when the `parallel`
+                    /// feature is enabled.
+                },
+                default: "false",
+                options: BOOL,
+            },
             OptionMeta {
                 key: "fmt.error-recovery",
                 unstable: true,
@@ -303,6 +587,61 @@ impl Options {
                 default: "true",
                 options: BOOL,
             },
+            OptionMeta {
+                key: "fmt.indent-width",
+                unstable: true,
+                doc: &docstring! {
+                    /// The number of spaces used for each level of
+                    /// indentation.
+                },
+                default: "4",
+                options: "<number>",
+            },
+            OptionMeta {
+                key: "fmt.hard-tabs",
+                unstable: true,
+                doc: &docstring! {
+                    /// Indent using tabs instead of spaces.
+                },
+                default: "false",
+                options: BOOL,
+            },
+            OptionMeta {
+                key: "fmt.max-width",
+                unstable: true,
+                doc: &docstring! {
+                    /// The width of an expression after which it is laid
+                    /// out over multiple lines.
+                    ///
+                    /// Whitespace is not counted.
+                },
+                default: "80",
+                options: "<number>",
+            },
+            OptionMeta {
+                key: "fmt.trailing-comma",
+                unstable: true,
+                doc: &docstring! {
+                    /// How trailing commas are written in lists.
+                    ///
+                    /// With `vertical`, only lists which are laid out over
+                    /// multiple lines get a trailing comma.
+                },
+                default: "vertical",
+                options: "always, never, vertical",
+            },
+            OptionMeta {
+                key: "fmt.newline-style",
+                unstable: true,
+                doc: &docstring! {
+                    /// The line endings to use.
+                    ///
+                    /// With `auto`, the line ending of the first line in the
+                    /// source is used.
+                },
+                default: "unix",
+                options: "unix, windows, auto",
+            },
         ];
 
         VALUES
@@ -348,6 +687,27 @@ impl Options {
                 "bytecode" => {
                     self.bytecode = tail.map_or(true, |s| s == "true");
                 }
+                "deprecations" => {
+                    self.deprecations = tail.map_or(true, |s| s == "true");
+                }
+                "constant-folding" => {
+                    self.constant_folding = tail.map_or(true, |s| s == "true");
+                }
+                "unused-variables" => {
+                    self.unused_variables = tail.map_or(true, |s| s == "true");
+                }
+                "closure-captures" => {
+                    self.closure_captures = tail.map_or(true, |s| s == "true");
+                }
+                "warnings-as-errors" => {
+                    self.warnings_as_errors = tail.map_or(true, |s| s == "true");
+                }
+                "deny" => {
+                    self.deny_with(tail.unwrap_or_default(), env)?;
+                }
+                "dce" => {
+                    self.dce = tail.map_or(true, |s| s == "true");
+                }
                 "function-body" => {
                     self.function_body = tail.map_or(true, |s| s == "true");
                 }
@@ -382,6 +742,9 @@ impl Options {
 
                     self.max_macro_depth = number;
                 }
+                "parallel" => {
+                    self.parallel = tail.map_or(true, |s| s == "true");
+                }
                 other => {
                     let Some((head, tail)) = other.split_once('.') else {
                         return Err(ParseOptionError {
@@ -438,6 +801,162 @@ impl Options {
         self.bytecode = enabled;
     }
 
+    /// Set if warnings should be emitted when deprecated functions are used.
+    /// Defaults to `true`.
+    pub fn deprecations(&mut self, enabled: bool) {
+        self.deprecations = enabled;
+    }
+
+    /// Set if expressions which only operate on literals should be folded
+    /// into a single value during compilation. Defaults to `true`.
+    ///
+    /// Folding an expression which would overflow at runtime, like
+    /// `i64::MAX + 1`, results in a compile error.
+    pub fn constant_folding(&mut self, enabled: bool) {
+        self.constant_folding = enabled;
+    }
+
+    /// Set if warnings should be emitted for variables and arguments which are
+    /// never read. Defaults to `true`.
+    ///
+    /// Like in Rust, variables whose names are prefixed with an underscore,
+    /// like `_unused`, never cause a warning.
+    pub fn unused_variables(&mut self, enabled: bool) {
+        self.unused_variables = enabled;
+    }
+
+    /// Set if warnings should be emitted listing the variables captured by
+    /// each closure and async block, and where they are declared. Defaults to
+    /// `false`.
+    pub fn closure_captures(&mut self, enabled: bool) {
+        self.closure_captures = enabled;
+    }
+
+    /// Set if all warnings should be treated as errors, causing the build to
+    /// fail if any are emitted. Defaults to `false`.
+    ///
+    /// Use [`Options::deny`] to only treat some warnings as errors.
+    pub fn warnings_as_errors(&mut self, enabled: bool) {
+        self.warnings_as_errors = enabled;
+    }
+
+    /// Treat the warning with the given name as an error, causing the build to
+    /// fail if it is emitted.
+    ///
+    /// The name is the one returned by [`WarningDiagnostic::name`], like
+    /// `unused-variable`. Errors if there is no warning with the given name.
+    ///
+    /// [`WarningDiagnostic::name`]: crate::diagnostics::WarningDiagnostic::name
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::{Diagnostics, Options};
+    ///
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         pub fn main() {
+    ///             let unused = 42;
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let mut options = Options::default();
+    /// options.deny("unused-variable")?;
+    /// assert!(options.deny("no-such-warning").is_err());
+    ///
+    /// let mut diagnostics = Diagnostics::new();
+    ///
+    /// let result = rune::prepare(&mut sources)
+    ///     .with_options(&options)
+    ///     .with_diagnostics(&mut diagnostics)
+    ///     .build();
+    ///
+    /// assert!(result.is_err());
+    /// assert!(diagnostics.has_error());
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    pub fn deny(&mut self, name: &str) -> Result<(), ParseOptionError> {
+        self.deny_with(name, None)
+    }
+
+    fn deny_with(&mut self, name: &str, env: Option<&'static str>) -> Result<(), ParseOptionError> {
+        let Some(&name) = WarningDiagnosticKind::NAMES.iter().find(|n| **n == name) else {
+            return Err(ParseOptionError {
+                env,
+                option: ::rust_alloc::format!("deny={name}").into(),
+            });
+        };
+
+        if !self.deny.contains(&name) {
+            self.deny.push(name);
+        }
+
+        Ok(())
+    }
+
+    /// Set if functions which can't be reached from any entry point should be
+    /// removed from the compiled unit. Defaults to `false`.
+    ///
+    /// By default every public function is considered an entry point, this
+    /// can be changed with [`Options::retain`]. Functions which are only ever
+    /// referenced by removed functions are removed as well, and a warning is
+    /// emitted for each removed function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use rune::{Context, Options, Vm};
+    ///
+    /// let context = Context::with_default_modules()?;
+    /// let runtime = Arc::new(context.runtime()?);
+    ///
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         fn helper() { 1 }
+    ///         fn unused() { 42 }
+    ///         pub fn main() { helper() }
+    ///     }
+    /// };
+    ///
+    /// let mut options = Options::default();
+    /// options.dce(true);
+    ///
+    /// let unit = rune::prepare(&mut sources)
+    ///     .with_context(&context)
+    ///     .with_options(&options)
+    ///     .build()?;
+    ///
+    /// let vm = Vm::new(runtime, Arc::new(unit));
+    ///
+    /// assert!(vm.lookup_function(["main"]).is_ok());
+    /// assert!(vm.lookup_function(["helper"]).is_ok());
+    /// assert!(vm.lookup_function(["unused"]).is_err());
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    pub fn dce(&mut self, enabled: bool) {
+        self.dce = enabled;
+    }
+
+    /// Set the entry points to retain when performing dead code elimination
+    /// with [`Options::dce`], instead of every public function.
+    ///
+    /// Functions which are referenced by name, like a function used as a
+    /// value, are always retained if the function referencing them is.
+    pub fn retain<I>(&mut self, entry_points: I)
+    where
+        I: IntoIterator<Item: ToTypeHash>,
+    {
+        self.retain = Some(
+            entry_points
+                .into_iter()
+                .map(|item| item.to_type_hash())
+                .collect(),
+        );
+    }
+
     /// Memoize the instance function in a loop. Defaults to `false`.
     pub fn memoize_instance_fn(&mut self, enabled: bool) {
         self.memoize_instance_fn = enabled;
@@ -28,6 +28,7 @@ impl Prelude {
         this.add_prelude("dbg", ["io", "dbg"])?;
         this.add_prelude("drop", ["mem", "drop"])?;
         this.add_prelude("clone", ["clone", "clone"])?;
+        this.add_prelude("compile_error", ["compile_error"])?;
         this.add_prelude("Err", ["result", "Result", "Err"])?;
         this.add_prelude("file", ["macros", "builtin", "file"])?;
         this.add_prelude("format", ["fmt", "format"])?;
@@ -57,6 +58,11 @@ impl Prelude {
         Some(self.prelude.get(name)?)
     }
 
+    /// Iterate over the local names defined in the prelude.
+    pub(crate) fn names(&self) -> impl Iterator<Item = &str> {
+        self.prelude.keys().map(|name| name.as_ref())
+    }
+
     /// Return the local name of an item
     #[allow(dead_code)]
     pub(crate) fn get_local<'a>(&'a self, item: &ItemBuf) -> Option<&'a str> {
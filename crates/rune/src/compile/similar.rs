@@ -0,0 +1,93 @@
+//! Finding similarly named candidates for names which couldn't be resolved.
+
+use core::fmt;
+
+use crate::alloc::prelude::*;
+use crate::alloc::{self, Vec};
+use crate::ItemBuf;
+
+/// An item with a name similar to one which couldn't be resolved.
+#[derive(Debug)]
+pub(crate) struct SimilarItem {
+    /// The similarly named item.
+    pub(crate) item: ItemBuf,
+    /// Whether the similarly named item is a function.
+    pub(crate) is_function: bool,
+}
+
+impl fmt::Display for SimilarItem {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.item.fmt(f)
+    }
+}
+
+/// Find the candidate most similar to `name`, if any is close enough to be a
+/// likely typo.
+///
+/// A candidate is considered close enough if its edit distance to `name` is at
+/// most a third of the length of `name`, but always allowing for at least one
+/// edit. Ties are broken by picking the candidate which sorts first.
+pub(crate) fn similar<'a, I>(name: &str, candidates: I) -> alloc::Result<Option<&'a str>>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let max = (name.chars().count() / 3).max(1);
+    let mut best = None::<(usize, &'a str)>;
+
+    for candidate in candidates {
+        if candidate == name {
+            continue;
+        }
+
+        let d = distance(name, candidate)?;
+
+        if d > max || matches!(best, Some((best, b)) if (best, b) <= (d, candidate)) {
+            continue;
+        }
+
+        best = Some((d, candidate));
+    }
+
+    Ok(best.map(|(_, candidate)| candidate))
+}
+
+/// Calculate the Levenshtein distance between two strings, where transposing
+/// two adjacent characters counts as a single edit.
+fn distance(a: &str, b: &str) -> alloc::Result<usize> {
+    let a = a.chars().try_collect::<Vec<_>>()?;
+    let b = b.chars().try_collect::<Vec<_>>()?;
+
+    let width = b.len() + 1;
+    let mut d = Vec::new();
+
+    for i in 0..=a.len() {
+        for j in 0..=b.len() {
+            d.try_push(if i == 0 {
+                j
+            } else if j == 0 {
+                i
+            } else {
+                0
+            })?;
+        }
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+
+            let mut value = (d[(i - 1) * width + j] + 1)
+                .min(d[i * width + j - 1] + 1)
+                .min(d[(i - 1) * width + j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                value = value.min(d[(i - 2) * width + j - 2] + 1);
+            }
+
+            d[i * width + j] = value;
+        }
+    }
+
+    Ok(d[a.len() * width + b.len()])
+}
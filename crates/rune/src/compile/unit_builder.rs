@@ -3,6 +3,11 @@
 //! A unit consists of a sequence of instructions, and lookaside tables for
 //! metadata like function locations.
 
+mod cache;
+mod dce;
+
+pub(crate) use self::cache::Recording;
+
 use core::fmt;
 
 use ::rust_alloc::sync::Arc;
@@ -14,14 +19,19 @@ use crate::ast::{Span, Spanned};
 use crate::compile::meta;
 use crate::compile::{self, Assembly, AssemblyInst, ErrorKind, Location, Pool, WithSpan};
 use crate::hash;
+use crate::hir;
 use crate::query::QueryInner;
-use crate::runtime::debug::{DebugArgs, DebugSignature};
+#[cfg(feature = "debug-access")]
+use crate::runtime::debug::DebugSource;
+use crate::runtime::debug::{DebugArgs, DebugSignature, DebugVariable};
 use crate::runtime::unit::UnitEncoder;
 use crate::runtime::{
-    Call, ConstValue, DebugInfo, DebugInst, Inst, InstAddress, Label, Protocol, Rtti, RttiKind,
-    StaticString, Unit, UnitFn,
+    Call, ConstValue, DebugInfo, DebugInst, Inst, InstAddress, Label, ObjectKey, ObjectKeys,
+    Protocol, Rtti, RttiKind, StaticString, Unit, UnitFn,
 };
-use crate::{Context, Diagnostics, Hash, Item, SourceId};
+#[cfg(feature = "debug-access")]
+use crate::Sources;
+use crate::{Context, Diagnostics, Hash, Item, ItemBuf, SourceId};
 
 /// Errors that can be raised when linking units.
 #[derive(Debug)]
@@ -31,12 +41,21 @@ pub enum LinkerError {
     MissingFunction {
         hash: Hash,
         spans: Vec<(Span, SourceId)>,
+        /// The item of the missing function and the module which provides it,
+        /// if it's provided by a module which is known but not installed.
+        known: Option<(ItemBuf, ItemBuf)>,
     },
 }
 
 impl fmt::Display for LinkerError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
+            LinkerError::MissingFunction {
+                known: Some((item, _)),
+                ..
+            } => {
+                write!(f, "Missing function `{item}`")
+            }
             LinkerError::MissingFunction { hash, .. } => {
                 write!(f, "Missing function with hash {hash}")
             }
@@ -55,6 +74,9 @@ pub(crate) struct UnitBuilder {
     functions: hash::Map<UnitFn>,
     /// Function by address.
     functions_rev: HashMap<usize, Hash>,
+    /// Information about function bodies by address, used when eliminating
+    /// dead code.
+    bodies: HashMap<usize, FunctionBody>,
     /// A static string.
     static_strings: Vec<Arc<StaticString>>,
     /// Reverse lookup for static strings.
@@ -69,9 +91,14 @@ pub(crate) struct UnitBuilder {
     /// to send the collection of keys to the virtual machine.
     ///
     /// All keys are sorted with the default string sort.
-    static_object_keys: Vec<Box<[String]>>,
+    static_object_keys: Vec<Box<[ObjectKey]>>,
     /// Used to detect duplicates in the collection of static object keys.
     static_object_keys_rev: HashMap<Hash, usize>,
+    /// Interned object keys, which are shared between all collections of
+    /// static object keys in the unit.
+    object_keys: ObjectKeys,
+    /// The number of call sites which use an inline cache.
+    call_sites: usize,
     /// A static string.
     drop_sets: Vec<Arc<[InstAddress]>>,
     /// Reverse lookup for drop sets.
@@ -88,6 +115,11 @@ pub(crate) struct UnitBuilder {
     constants: hash::Map<ConstValue>,
     /// Hash to identifiers.
     hash_to_ident: HashMap<Hash, Box<str>>,
+    /// Names of variables.
+    variable_names: HashMap<hir::Variable, Box<str>>,
+    /// Everything added to the unit while compiling the current item, if it's
+    /// being recorded.
+    recording: Option<Recording>,
 }
 
 impl UnitBuilder {
@@ -101,11 +133,48 @@ impl UnitBuilder {
 
     /// Insert an identifier for debug purposes.
     pub(crate) fn insert_debug_ident(&mut self, ident: &str) -> alloc::Result<()> {
+        self.record_debug_ident(ident)?;
+
         self.hash_to_ident
             .try_insert(Hash::ident(ident), ident.try_into()?)?;
         Ok(())
     }
 
+    /// Insert the name of a variable for debug purposes.
+    pub(crate) fn insert_variable_name(
+        &mut self,
+        id: hir::Variable,
+        name: hir::Name<'_>,
+    ) -> alloc::Result<()> {
+        let name = match name {
+            hir::Name::SelfValue => "self",
+            hir::Name::Str(name) => name,
+        };
+
+        self.variable_names.try_insert(id, name.try_into()?)?;
+        Ok(())
+    }
+
+    /// Insert the names and line starts of sources for debug purposes, so that
+    /// locations can be reported without access to the sources.
+    #[cfg(feature = "debug-access")]
+    pub(crate) fn insert_debug_sources(&mut self, sources: &Sources) -> alloc::Result<()> {
+        for id in sources.source_ids() {
+            let Some(source) = sources.get(id) else {
+                continue;
+            };
+
+            let source = DebugSource::new(
+                source.name().try_into()?,
+                Vec::try_from(source.line_starts())?,
+            );
+
+            self.debug_mut()?.sources.try_insert(id, source)?;
+        }
+
+        Ok(())
+    }
+
     /// Convert into a runtime unit, shedding our build metadata in the process.
     ///
     /// Returns `None` if the builder is still in use.
@@ -166,6 +235,7 @@ impl UnitBuilder {
             self.rtti,
             self.debug,
             self.constants,
+            self.call_sites,
         ))
     }
 
@@ -293,13 +363,22 @@ impl UnitBuilder {
                 )
             })?;
 
-            if *existing != current {
+            if !existing
+                .iter()
+                .map(ObjectKey::as_str)
+                .eq(current.iter().map(String::as_str))
+            {
+                let existing = existing
+                    .iter()
+                    .map(|key| key.as_str().try_to_owned())
+                    .try_collect::<alloc::Result<Box<_>>>()??;
+
                 return Err(compile::Error::new(
                     span,
                     ErrorKind::StaticObjectKeysHashConflict {
                         hash,
                         current,
-                        existing: existing.try_clone()?,
+                        existing,
                     },
                 ));
             }
@@ -307,12 +386,24 @@ impl UnitBuilder {
             return Ok(existing_slot);
         }
 
+        let current = current
+            .iter()
+            .map(|key| self.object_keys.intern(key))
+            .try_collect::<alloc::Result<Box<_>>>()??;
+
         let new_slot = self.static_object_keys.len();
         self.static_object_keys.try_push(current)?;
         self.static_object_keys_rev.try_insert(hash, new_slot)?;
         Ok(new_slot)
     }
 
+    /// Allocate a new inline cache slot for a call site.
+    pub(crate) fn new_call_site(&mut self) -> usize {
+        let slot = self.call_sites;
+        self.call_sites += 1;
+        slot
+    }
+
     /// Declare a new struct.
     pub(crate) fn insert_meta(
         &mut self,
@@ -685,6 +776,7 @@ impl UnitBuilder {
         location: Location,
         item: &Item,
         instance: Option<(Hash, &str)>,
+        protocol: Option<Hash>,
         args: usize,
         captures: Option<usize>,
         assembly: Assembly,
@@ -692,9 +784,24 @@ impl UnitBuilder {
         debug_args: Box<[Box<str>]>,
         unit_storage: &mut dyn UnitEncoder,
         size: usize,
+        is_public: bool,
     ) -> compile::Result<()> {
         tracing::trace!("instance fn: {}", item);
 
+        self.record_function(
+            location,
+            item,
+            instance,
+            protocol,
+            args,
+            captures,
+            &assembly,
+            call,
+            &debug_args,
+            size,
+            is_public,
+        )?;
+
         let offset = unit_storage.offset();
 
         let info = UnitFn::Offset {
@@ -725,6 +832,28 @@ impl UnitBuilder {
             self.debug_mut()?
                 .functions
                 .try_insert(instance_fn, signature.try_clone()?)?;
+
+            if let Some(protocol) = protocol {
+                let protocol_fn = Hash::associated_function(type_hash, protocol);
+
+                if self
+                    .functions
+                    .try_insert(protocol_fn, info)
+                    .with_span(location.span)?
+                    .is_some()
+                {
+                    return Err(compile::Error::new(
+                        location.span,
+                        ErrorKind::FunctionConflict {
+                            existing: signature,
+                        },
+                    ));
+                }
+
+                self.debug_mut()?
+                    .functions
+                    .try_insert(protocol_fn, signature.try_clone()?)?;
+            }
         }
 
         let hash = Hash::type_hash(item);
@@ -752,6 +881,14 @@ impl UnitBuilder {
 
         self.debug_mut()?.functions.try_insert(hash, signature)?;
         self.functions_rev.try_insert(offset, hash)?;
+        self.bodies.try_insert(
+            offset,
+            FunctionBody {
+                location,
+                is_public,
+                is_instance: instance.is_some(),
+            },
+        )?;
         self.add_assembly(location, assembly, unit_storage, size)?;
         Ok(())
     }
@@ -767,11 +904,17 @@ impl UnitBuilder {
     ) -> alloc::Result<()> {
         for (hash, spans) in &self.required_functions {
             if self.functions.get(hash).is_none() && context.lookup_function(*hash).is_none() {
+                let known = match context.lookup_known(*hash) {
+                    Some(known) => Some((known.item.try_clone()?, known.module.try_clone()?)),
+                    None => None,
+                };
+
                 diagnostics.error(
                     SourceId::empty(),
                     LinkerError::MissingFunction {
                         hash: *hash,
                         spans: spans.try_clone()?,
+                        known,
                     },
                 )?;
             }
@@ -816,10 +959,13 @@ impl UnitBuilder {
             }
         }
 
+        let mut offsets = Vec::try_with_capacity(assembly.instructions.len() + 1)?;
+
         for (pos, (inst, span)) in assembly.instructions.into_iter().enumerate() {
             let mut comment = String::new();
 
             let at = storage.offset();
+            offsets.try_push(at)?;
 
             let mut labels = Vec::new();
 
@@ -924,10 +1070,50 @@ impl UnitBuilder {
             )?;
         }
 
+        offsets.try_push(storage.offset())?;
+
+        let mut variables = assembly.variables;
+        variables.sort_by_key(|var| var.start);
+
+        for var in variables {
+            let Some(name) = self.variable_names.get(&var.name) else {
+                continue;
+            };
+
+            let (Some(&start), Some(&end)) = (offsets.get(var.start), offsets.get(var.end)) else {
+                continue;
+            };
+
+            if start == end {
+                continue;
+            }
+
+            let variable = DebugVariable {
+                name: name.try_clone()?,
+                addr: var.addr.offset(),
+                start,
+                end,
+            };
+
+            self.debug_mut()?.variables.try_push(variable)?;
+        }
+
         Ok(())
     }
 }
 
+/// Information about a function body.
+#[derive(Debug, Clone, Copy)]
+struct FunctionBody {
+    /// Where the function is declared.
+    location: Location,
+    /// Whether the function is public.
+    is_public: bool,
+    /// Whether the function is an instance function, which can be called
+    /// dynamically by name.
+    is_instance: bool,
+}
+
 /// A set of addresses that should be dropped.
 pub(crate) struct DropSet<'a> {
     builder: &'a mut UnitBuilder,
@@ -0,0 +1,440 @@
+//! Recording of the functions added to a unit while compiling an item, so that
+//! they can be added to the unit of a later build without compiling the item
+//! again.
+
+use crate::alloc::prelude::*;
+use crate::alloc::{self, Box, HashMap, String, Vec};
+use crate::ast::Span;
+use crate::compile::{self, Assembly, AssemblyInst, AssemblyVariable, Location};
+use crate::hir;
+use crate::runtime::unit::UnitEncoder;
+use crate::runtime::{Call, Inst, InstAddress, Label, Output};
+use crate::shared::Gen;
+use crate::{Hash, Item, ItemBuf};
+
+use super::UnitBuilder;
+
+/// Everything added to a unit while compiling a single item.
+#[derive(Debug, Default)]
+pub(crate) struct Recording {
+    /// Functions added to the unit.
+    functions: Vec<CachedFunction>,
+    /// Identifiers inserted for debug purposes.
+    idents: Vec<Box<str>>,
+}
+
+/// A function which was added to a unit.
+#[derive(Debug)]
+struct CachedFunction {
+    location: Location,
+    item: ItemBuf,
+    instance: Option<(Hash, Box<str>)>,
+    protocol: Option<Hash>,
+    args: usize,
+    captures: Option<usize>,
+    call: Call,
+    debug_args: Box<[Box<str>]>,
+    size: usize,
+    is_public: bool,
+    assembly: CachedAssembly,
+}
+
+/// An assembly which doesn't refer to any state in the unit it was added to.
+///
+/// Labels are stored as indexes into `labels`, and slots of static data are
+/// stored as indexes into `statics`.
+#[derive(Debug)]
+struct CachedAssembly {
+    /// The name of every label and the instruction it's placed at.
+    labels: Vec<(&'static str, Option<usize>)>,
+    instructions: Vec<(CachedInst, Span)>,
+    comments: Vec<(usize, String)>,
+    /// The name, address and scope of variables.
+    variables: Vec<(Box<str>, InstAddress, usize, usize)>,
+    statics: Vec<Static>,
+}
+
+#[derive(Debug)]
+enum CachedInst {
+    Jump {
+        label: usize,
+    },
+    JumpIf {
+        addr: InstAddress,
+        label: usize,
+    },
+    JumpIfNot {
+        addr: InstAddress,
+        label: usize,
+    },
+    IterNext {
+        addr: InstAddress,
+        label: usize,
+        out: Output,
+    },
+    Raw {
+        raw: Inst,
+    },
+}
+
+/// Static data referenced by an instruction.
+#[derive(Debug)]
+enum Static {
+    String(Box<str>),
+    Bytes(Vec<u8>),
+    ObjectKeys(Box<[String]>),
+    DropSet(Vec<InstAddress>),
+    CallSite,
+}
+
+impl UnitBuilder {
+    /// Start recording everything which is added to the unit.
+    pub(crate) fn begin_recording(&mut self) {
+        self.recording = Some(Recording::default());
+    }
+
+    /// Stop recording, returning everything which was added to the unit since
+    /// recording started.
+    pub(crate) fn finish_recording(&mut self) -> Option<Recording> {
+        self.recording.take()
+    }
+
+    /// Record an identifier inserted for debug purposes.
+    pub(super) fn record_debug_ident(&mut self, ident: &str) -> alloc::Result<()> {
+        if let Some(recording) = &mut self.recording {
+            recording.idents.try_push(ident.try_into()?)?;
+        }
+
+        Ok(())
+    }
+
+    /// Record a function which is about to be added to the unit.
+    pub(super) fn record_function(
+        &mut self,
+        location: Location,
+        item: &Item,
+        instance: Option<(Hash, &str)>,
+        protocol: Option<Hash>,
+        args: usize,
+        captures: Option<usize>,
+        assembly: &Assembly,
+        call: Call,
+        debug_args: &[Box<str>],
+        size: usize,
+        is_public: bool,
+    ) -> compile::Result<()> {
+        if self.recording.is_none() {
+            return Ok(());
+        }
+
+        let function = CachedFunction {
+            location,
+            item: item.try_to_owned()?,
+            instance: match instance {
+                Some((hash, name)) => Some((hash, name.try_into()?)),
+                None => None,
+            },
+            protocol,
+            args,
+            captures,
+            call,
+            debug_args: Vec::try_from(debug_args)?.try_into_boxed_slice()?,
+            size,
+            is_public,
+            assembly: self.cache_assembly(assembly)?,
+        };
+
+        if let Some(recording) = &mut self.recording {
+            recording.functions.try_push(function)?;
+        }
+
+        Ok(())
+    }
+
+    /// Add everything in a recording from a previous build to this unit.
+    pub(crate) fn replay(
+        &mut self,
+        recording: &Recording,
+        gen: &Gen,
+        unit_storage: &mut dyn UnitEncoder,
+    ) -> compile::Result<()> {
+        for ident in &recording.idents {
+            self.insert_debug_ident(ident)?;
+        }
+
+        for f in &recording.functions {
+            let assembly = self.restore_assembly(f.location, &f.assembly, gen)?;
+
+            self.new_function(
+                f.location,
+                &f.item,
+                f.instance.as_ref().map(|(hash, name)| (*hash, &name[..])),
+                f.protocol,
+                f.args,
+                f.captures,
+                assembly,
+                f.call,
+                f.debug_args.try_clone()?,
+                unit_storage,
+                f.size,
+                f.is_public,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Convert an assembly into one which doesn't refer to the state of this
+    /// unit.
+    fn cache_assembly(&self, assembly: &Assembly) -> compile::Result<CachedAssembly> {
+        let mut positions = assembly.labels.iter().try_collect::<Vec<_>>()?;
+        positions.sort_by_key(|&(&pos, _)| pos);
+
+        let mut labels = Vec::new();
+        let mut indexes = HashMap::new();
+
+        for (&pos, (_, placed)) in positions {
+            for label in placed {
+                indexes.try_insert(label.index, labels.len())?;
+                labels.try_push((label.name, Some(pos)))?;
+            }
+        }
+
+        let mut label = |label: &Label| -> alloc::Result<usize> {
+            if let Some(&index) = indexes.get(&label.index) {
+                return Ok(index);
+            }
+
+            let index = labels.len();
+            indexes.try_insert(label.index, index)?;
+            labels.try_push((label.name, None))?;
+            Ok(index)
+        };
+
+        let mut instructions = Vec::try_with_capacity(assembly.instructions.len())?;
+        let mut statics = Vec::new();
+
+        for (inst, span) in &assembly.instructions {
+            let inst = match inst {
+                AssemblyInst::Jump { label: l } => CachedInst::Jump { label: label(l)? },
+                AssemblyInst::JumpIf { addr, label: l } => CachedInst::JumpIf {
+                    addr: *addr,
+                    label: label(l)?,
+                },
+                AssemblyInst::JumpIfNot { addr, label: l } => CachedInst::JumpIfNot {
+                    addr: *addr,
+                    label: label(l)?,
+                },
+                AssemblyInst::IterNext {
+                    addr,
+                    label: l,
+                    out,
+                } => CachedInst::IterNext {
+                    addr: *addr,
+                    label: label(l)?,
+                    out: *out,
+                },
+                AssemblyInst::Raw { raw } => {
+                    let mut raw = *raw;
+
+                    if let Some((kind, slot)) = static_slot_mut(&mut raw) {
+                        let value = self.cache_static(kind, *slot, span)?;
+                        *slot = statics.len();
+                        statics.try_push(value)?;
+                    }
+
+                    CachedInst::Raw { raw }
+                }
+            };
+
+            instructions.try_push((inst, *span))?;
+        }
+
+        let mut comments = Vec::new();
+
+        for (&pos, comment) in &assembly.comments {
+            comments.try_push((pos, comment.try_clone()?))?;
+        }
+
+        let mut variables = Vec::new();
+
+        for var in &assembly.variables {
+            let Some(name) = self.variable_names.get(&var.name) else {
+                continue;
+            };
+
+            variables.try_push((name.try_clone()?, var.addr, var.start, var.end))?;
+        }
+
+        Ok(CachedAssembly {
+            labels,
+            instructions,
+            comments,
+            variables,
+            statics,
+        })
+    }
+
+    /// Get the static data referenced by the given slot.
+    fn cache_static(&self, kind: StaticKind, slot: usize, span: &Span) -> compile::Result<Static> {
+        let missing = || compile::Error::msg(span, "missing static data referenced by instruction");
+
+        let value = match kind {
+            StaticKind::String => {
+                let string = self.static_strings.get(slot).ok_or_else(missing)?;
+                Static::String(Box::try_from(string.as_str())?)
+            }
+            StaticKind::Bytes => {
+                let bytes = self.static_bytes.get(slot).ok_or_else(missing)?;
+                Static::Bytes(bytes.try_clone()?)
+            }
+            StaticKind::ObjectKeys => {
+                let keys = self.static_object_keys.get(slot).ok_or_else(missing)?;
+
+                let keys = keys
+                    .iter()
+                    .map(|key| key.as_str().try_to_owned())
+                    .try_collect::<alloc::Result<Box<[String]>>>()??;
+
+                Static::ObjectKeys(keys)
+            }
+            StaticKind::DropSet => {
+                let set = self.drop_sets.get(slot).ok_or_else(missing)?;
+                Static::DropSet(Vec::try_from(&set[..])?)
+            }
+            StaticKind::CallSite => Static::CallSite,
+        };
+
+        Ok(value)
+    }
+
+    /// Restore a cached assembly, allocating static data and labels in this
+    /// unit.
+    fn restore_assembly(
+        &mut self,
+        location: Location,
+        cached: &CachedAssembly,
+        gen: &Gen,
+    ) -> compile::Result<Assembly> {
+        let mut assembly = self.new_assembly(location);
+
+        let labels = cached
+            .labels
+            .iter()
+            .map(|&(name, _)| assembly.new_label(name))
+            .try_collect::<Vec<_>>()?;
+
+        let mut placed = cached
+            .labels
+            .iter()
+            .zip(&labels)
+            .filter_map(|(&(_, pos), label)| Some((pos?, label)))
+            .try_collect::<Vec<_>>()?;
+
+        placed.sort_by_key(|&(pos, _)| pos);
+        let mut placed = placed.into_iter().peekable();
+
+        for (pos, (inst, span)) in cached.instructions.iter().enumerate() {
+            while let Some((_, label)) = placed.next_if(|&(at, _)| at == pos) {
+                assembly.label(label)?;
+            }
+
+            match inst {
+                CachedInst::Jump { label } => {
+                    assembly.jump(&labels[*label], span)?;
+                }
+                CachedInst::JumpIf { addr, label } => {
+                    assembly.jump_if(*addr, &labels[*label], span)?;
+                }
+                CachedInst::JumpIfNot { addr, label } => {
+                    assembly.jump_if_not(*addr, &labels[*label], span)?;
+                }
+                CachedInst::IterNext { addr, label, out } => {
+                    assembly.iter_next(*addr, &labels[*label], span, *out)?;
+                }
+                CachedInst::Raw { raw } => {
+                    let mut raw = *raw;
+
+                    if let Some((_, slot)) = static_slot_mut(&mut raw) {
+                        *slot = self.restore_static(&cached.statics[*slot], span)?;
+                    }
+
+                    assembly.push(raw, span)?;
+                }
+            }
+        }
+
+        for (_, label) in placed {
+            assembly.label(label)?;
+        }
+
+        for (pos, comment) in &cached.comments {
+            assembly.comments.try_insert(*pos, comment.try_clone()?)?;
+        }
+
+        for (name, addr, start, end) in &cached.variables {
+            let id = hir::Variable(gen.next());
+            self.insert_variable_name(id, hir::Name::Str(&name[..]))?;
+
+            assembly.variables.try_push(AssemblyVariable {
+                name: id,
+                addr: *addr,
+                start: *start,
+                end: *end,
+            })?;
+        }
+
+        Ok(assembly)
+    }
+
+    /// Allocate the given static data in this unit, returning its slot.
+    fn restore_static(&mut self, value: &Static, span: &Span) -> compile::Result<usize> {
+        let slot = match value {
+            Static::String(string) => self.new_static_string(span, string)?,
+            Static::Bytes(bytes) => self.new_static_bytes(span, bytes)?,
+            Static::ObjectKeys(keys) => self.new_static_object_keys(span, keys.try_clone()?)?,
+            Static::DropSet(addresses) => {
+                let mut set = self.drop_set();
+
+                for &addr in addresses {
+                    set.push(addr)?;
+                }
+
+                let Some(set) = set.finish()? else {
+                    return Err(compile::Error::msg(span, "empty drop set"));
+                };
+
+                set
+            }
+            Static::CallSite => self.new_call_site(),
+        };
+
+        Ok(slot)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum StaticKind {
+    String,
+    Bytes,
+    ObjectKeys,
+    DropSet,
+    CallSite,
+}
+
+/// Get the slot of static data referenced by an instruction, if any.
+fn static_slot_mut(inst: &mut Inst) -> Option<(StaticKind, &mut usize)> {
+    if super::dce::string_slot(inst).is_some() {
+        return super::dce::string_slot_mut(inst).map(|slot| (StaticKind::String, slot));
+    }
+
+    match inst {
+        Inst::Bytes { slot, .. } | Inst::EqBytes { slot, .. } => Some((StaticKind::Bytes, slot)),
+        Inst::Object { slot, .. }
+        | Inst::ObjectRest { slot, .. }
+        | Inst::MatchObject { slot, .. } => Some((StaticKind::ObjectKeys, slot)),
+        Inst::Drop { set } => Some((StaticKind::DropSet, set)),
+        Inst::CallAssociated { cache, .. } => Some((StaticKind::CallSite, cache)),
+        _ => None,
+    }
+}
@@ -0,0 +1,397 @@
+//! Dead code elimination, which removes function bodies that can't be reached
+//! from any entry point of the unit.
+
+use core::mem::take;
+
+use crate::alloc::prelude::*;
+use crate::alloc::{HashMap, HashSet, Vec};
+use crate::ast::Span;
+use crate::compile::{self, WithSpan};
+use crate::runtime::unit::{BadInstruction, UnitEncoder, UnitStorage};
+use crate::runtime::{Inst, InstTarget, Protocol, UnitFn};
+use crate::{Diagnostics, Hash};
+
+use super::UnitBuilder;
+
+impl UnitBuilder {
+    /// Remove every function body which can't be reached from an entry point,
+    /// returning storage which only contains the reachable bodies.
+    ///
+    /// Entry points are the functions identified by `retain` if specified,
+    /// otherwise every public function. Instance functions and re-exported
+    /// functions are always retained since they can be reached dynamically.
+    pub(crate) fn eliminate_dead_code<S>(
+        &mut self,
+        span: Span,
+        storage: S,
+        retain: Option<&[Hash]>,
+        diagnostics: &mut Diagnostics,
+    ) -> compile::Result<S>
+    where
+        S: UnitStorage + UnitEncoder,
+    {
+        let mut starts = self.functions_rev.keys().copied().try_collect::<Vec<_>>()?;
+        starts.sort();
+
+        let end = storage.end();
+
+        let mut queue = Vec::new();
+
+        for (&start, body) in &self.bodies {
+            let is_root = match retain {
+                Some(..) => body.is_instance,
+                None => body.is_public || body.is_instance,
+            };
+
+            if is_root {
+                queue.try_push(start)?;
+            }
+        }
+
+        for hash in retain
+            .into_iter()
+            .flatten()
+            .chain(self.reexports.values())
+            .chain([&Hash::EMPTY])
+        {
+            if let Some(offset) = self.resolve_offset(*hash) {
+                queue.try_push(offset)?;
+            }
+        }
+
+        let mut reachable = HashSet::new();
+        let mut strings = HashSet::new();
+
+        while let Some(start) = queue.pop() {
+            if !reachable.try_insert(start)? {
+                continue;
+            }
+
+            for (_, inst) in body(span, &storage, &starts, end, start)? {
+                if let Some(slot) = string_slot(&inst) {
+                    strings.try_insert(slot)?;
+                }
+
+                let offset = match inst {
+                    Inst::Call { hash, .. } | Inst::LoadFn { hash, .. } => {
+                        self.resolve_offset(hash)
+                    }
+                    Inst::Closure { hash, .. } => self.resolve_offset(hash),
+                    Inst::CallOffset { offset, .. } => Some(offset),
+                    _ => None,
+                };
+
+                if let Some(offset) = offset {
+                    queue.try_push(offset)?;
+                }
+            }
+        }
+
+        // Static strings are renumbered in the order they were originally
+        // allocated.
+        let mut slots = HashMap::new();
+        let mut static_strings = Vec::new();
+        self.static_string_rev.clear();
+
+        for (slot, string) in take(&mut self.static_strings).into_iter().enumerate() {
+            if !strings.contains(&slot) {
+                continue;
+            }
+
+            let new = static_strings.len();
+            slots.try_insert(slot, new)?;
+            self.static_string_rev.try_insert(string.hash(), new)?;
+            static_strings.try_push(string)?;
+        }
+
+        self.static_strings = static_strings;
+
+        let mut out = S::default();
+        // Old instruction pointer to new instruction pointer.
+        let mut ips = HashMap::new();
+        // The end of each retained function body.
+        let mut ends = HashMap::new();
+        let mut removed = Vec::new();
+
+        for (index, &start) in starts.iter().enumerate() {
+            let body_end = starts.get(index + 1).copied().unwrap_or(end);
+
+            if !reachable.contains(&start) {
+                removed.try_push(start)?;
+                continue;
+            }
+
+            let insts = body(span, &storage, &starts, end, start)?;
+
+            // The position of an old jump target within the body, or the end
+            // of the body.
+            let position = |jump: usize| -> compile::Result<usize> {
+                let target = storage
+                    .translate(jump)
+                    .map_err(|e| compile::Error::msg(span, e))?;
+
+                if target == body_end {
+                    return Ok(insts.len());
+                }
+
+                insts
+                    .binary_search_by_key(&target, |&(ip, _)| ip)
+                    .map_err(|_| compile::Error::msg(span, "Jump outside of function body"))
+            };
+
+            let mut labels = HashMap::new();
+
+            for (_, inst) in &insts {
+                if let Some(jump) = jump(inst) {
+                    let pos = position(jump)?;
+
+                    if !labels.contains_key(&pos) {
+                        let label = labels.len();
+                        labels.try_insert(pos, label)?;
+                    }
+                }
+            }
+
+            let base = out.extend_offsets(labels.len())?;
+
+            for (pos, &(ip, mut inst)) in insts.iter().enumerate() {
+                if let Some(&label) = labels.get(&pos) {
+                    out.mark_offset(base + label);
+                }
+
+                ips.try_insert(ip, out.offset())?;
+
+                if let Some(jump) = jump_mut(&mut inst) {
+                    let pos = position(*jump)?;
+                    let label = labels.get(&pos).copied().unwrap_or_default();
+                    *jump = out.label_jump(base, pos, label);
+                }
+
+                if let Some(slot) = string_slot_mut(&mut inst) {
+                    *slot = slots.get(slot).copied().unwrap_or_default();
+                }
+
+                // Calls by offset are only assembled for functions whose
+                // bodies precede the call site, or for recursive calls. Their
+                // targets are reachable and bodies keep their relative order,
+                // so the target has always been written already.
+                if let Inst::CallOffset { offset, .. } = &mut inst {
+                    let Some(&new) = ips.get(offset) else {
+                        return Err(compile::Error::msg(
+                            span,
+                            "Call to function which has not been written",
+                        ));
+                    };
+
+                    *offset = new;
+                }
+
+                out.encode(inst).with_span(span)?;
+            }
+
+            if let Some(&label) = labels.get(&insts.len()) {
+                out.mark_offset(base + label);
+            }
+
+            ends.try_insert(body_end, out.offset())?;
+        }
+
+        let mut removed_hashes = HashSet::new();
+        // Closures share the location of the function they are declared in,
+        // so only warn once for each location.
+        let mut warned = Vec::new();
+
+        for start in &removed {
+            let Some(hash) = self.functions_rev.get(start) else {
+                continue;
+            };
+
+            let Some(body) = self.bodies.get(start) else {
+                continue;
+            };
+
+            let item = self
+                .debug
+                .as_ref()
+                .and_then(|debug| debug.functions.get(hash));
+
+            let location = (body.location.source_id, body.location.span);
+
+            if warned.contains(&location) {
+                continue;
+            }
+
+            warned.try_push(location)?;
+
+            if let Some(signature) = item {
+                diagnostics.removed_function(
+                    body.location.source_id,
+                    &body.location.span,
+                    signature.path.try_clone()?,
+                )?;
+            }
+        }
+
+        for (hash, f) in self.functions.iter_mut() {
+            let UnitFn::Offset { offset, .. } = f else {
+                continue;
+            };
+
+            match ips.get(offset) {
+                Some(new) => {
+                    *offset = *new;
+                }
+                None => {
+                    removed_hashes.try_insert(*hash)?;
+                }
+            }
+        }
+
+        self.functions
+            .retain(|hash, _| !removed_hashes.contains(hash));
+        self.reexports
+            .retain(|_, target| !removed_hashes.contains(target));
+
+        for hash in &removed_hashes {
+            self.constants
+                .remove(&Hash::associated_function(*hash, &Protocol::INTO_TYPE_NAME));
+        }
+
+        let mut functions_rev = HashMap::new();
+        let mut bodies = HashMap::new();
+
+        for (start, hash) in take(&mut self.functions_rev) {
+            let Some(&new) = ips.get(&start) else {
+                continue;
+            };
+
+            functions_rev.try_insert(new, hash)?;
+
+            if let Some(body) = self.bodies.remove(&start) {
+                bodies.try_insert(new, body)?;
+            }
+        }
+
+        self.functions_rev = functions_rev;
+        self.bodies = bodies;
+
+        if let Some(debug) = &mut self.debug {
+            let mut instructions = HashMap::new();
+
+            for (ip, inst) in take(&mut debug.instructions) {
+                if let Some(&new) = ips.get(&ip) {
+                    instructions.try_insert(new, inst)?;
+                }
+            }
+
+            debug.instructions = instructions;
+            debug
+                .functions
+                .retain(|hash, _| !removed_hashes.contains(hash));
+
+            debug.variables.retain_mut(|var| {
+                let (Some(&start), Some(&end)) = (
+                    ips.get(&var.start),
+                    ips.get(&var.end).or_else(|| ends.get(&var.end)),
+                ) else {
+                    return false;
+                };
+
+                var.start = start;
+                var.end = end;
+                true
+            });
+        }
+
+        Ok(out)
+    }
+
+    /// Resolve the offset of the function body with the given hash.
+    fn resolve_offset(&self, hash: Hash) -> Option<usize> {
+        let hash = self.reexports.get(&hash).copied().unwrap_or(hash);
+
+        match self.functions.get(&hash)? {
+            UnitFn::Offset { offset, .. } => Some(*offset),
+            _ => None,
+        }
+    }
+}
+
+/// Decode the instructions of the function body at `start`, which extends
+/// until the start of the next function body.
+fn body<S>(
+    span: Span,
+    storage: &S,
+    starts: &[usize],
+    end: usize,
+    start: usize,
+) -> compile::Result<Vec<(usize, Inst)>>
+where
+    S: UnitStorage,
+{
+    let body_end = match starts.binary_search(&start) {
+        Ok(index) => starts.get(index + 1).copied().unwrap_or(end),
+        Err(..) => return Err(compile::Error::msg(span, "Missing function body")),
+    };
+
+    let mut insts = Vec::new();
+    let mut ip = start;
+
+    while ip < body_end {
+        let Some((inst, len)) = storage.get(ip).map_err(|e| compile::Error::msg(span, e))? else {
+            break;
+        };
+
+        if len == 0 {
+            return Err(compile::Error::msg(span, BadInstruction { ip }));
+        }
+
+        insts.try_push((ip, inst))?;
+        ip += len;
+    }
+
+    Ok(insts)
+}
+
+/// Get the jump of an instruction, if it has one.
+fn jump(inst: &Inst) -> Option<usize> {
+    match *inst {
+        Inst::Jump { jump }
+        | Inst::JumpIf { jump, .. }
+        | Inst::JumpIfNot { jump, .. }
+        | Inst::IterNext { jump, .. } => Some(jump),
+        _ => None,
+    }
+}
+
+fn jump_mut(inst: &mut Inst) -> Option<&mut usize> {
+    match inst {
+        Inst::Jump { jump }
+        | Inst::JumpIf { jump, .. }
+        | Inst::JumpIfNot { jump, .. }
+        | Inst::IterNext { jump, .. } => Some(jump),
+        _ => None,
+    }
+}
+
+/// Get the static string slot referenced by an instruction, if any.
+pub(super) fn string_slot(inst: &Inst) -> Option<usize> {
+    let mut inst = *inst;
+    string_slot_mut(&mut inst).copied()
+}
+
+pub(super) fn string_slot_mut(inst: &mut Inst) -> Option<&mut usize> {
+    match inst {
+        Inst::String { slot, .. }
+        | Inst::EqString { slot, .. }
+        | Inst::ObjectIndexSet { slot, .. }
+        | Inst::ObjectIndexGetAt { slot, .. } => Some(slot),
+        Inst::AssignArithmetic { target, .. }
+        | Inst::AssignBitwise { target, .. }
+        | Inst::AssignShift { target, .. } => match target {
+            InstTarget::Field(_, slot) => Some(slot),
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -14,12 +14,14 @@ use crate::query::{ConstFn, Query, Used};
 use crate::runtime::{
     ConstValue, ConstValueKind, Inline, Inst, InstAddress, InstArithmeticOp, InstBitwiseOp, InstOp,
     InstRange, InstShiftOp, InstTarget, InstValue, InstVariant, Label, Output, PanicReason,
-    Protocol, TypeCheck,
+    Protocol, TryKind, TypeCheck,
 };
 use crate::shared::FixedVec;
 use crate::{Hash, SourceId};
 
-use super::{Address, Any, Break, Breaks, Linear, Needs, ScopeHandle, Scopes};
+use super::{
+    exhaustive, fold, try_kind, Address, Any, Break, Breaks, Linear, Needs, ScopeHandle, Scopes,
+};
 
 macro_rules! converge {
     ($expr:expr $(, $method:ident($($diverge:expr),* $(,)?))?) => {
@@ -68,6 +70,9 @@ pub(crate) struct Ctxt<'a, 'hir, 'arena> {
     pub(crate) select_branches: Vec<(Label, &'hir hir::ExprSelectBranch<'hir>)>,
     /// Values to drop.
     pub(crate) drop: Vec<InstAddress>,
+    /// The kind of value returned by the function being assembled, if it
+    /// could be inferred.
+    pub(crate) try_kind: Option<TryKind>,
 }
 
 impl<'hir> Ctxt<'_, 'hir, '_> {
@@ -217,6 +222,7 @@ pub(crate) fn fn_from_item_fn<'hir>(
     instance_fn: bool,
 ) -> compile::Result<()> {
     let mut first = true;
+    cx.try_kind = try_kind::block(&hir.body);
 
     let mut arguments = cx.scopes.linear(hir, hir.args.len())?;
 
@@ -227,7 +233,7 @@ pub(crate) fn fn_from_item_fn<'hir>(
                     return Err(compile::Error::new(span, ErrorKind::UnsupportedSelf));
                 }
 
-                cx.scopes.define(span, *name, needs)?;
+                cx.scopes.define(span, *name, needs, cx.asm)?;
             }
             hir::FnArg::Pat(pat) => {
                 let asm = pattern_panic(cx, pat, move |cx, false_label| {
@@ -235,6 +241,7 @@ pub(crate) fn fn_from_item_fn<'hir>(
                 })?;
 
                 asm.ignore();
+                clone_immutable(cx, pat, pat.names)?;
             }
         }
 
@@ -242,7 +249,8 @@ pub(crate) fn fn_from_item_fn<'hir>(
     }
 
     if hir.body.value.is_some() {
-        return_(cx, hir, &hir.body, block_without_scope)?.ignore();
+        let shared = hir.body.value.is_some_and(may_share);
+        return_(cx, hir, &hir.body, shared, block_without_scope)?.ignore();
     } else {
         let mut needs = Any::ignore(&hir.body);
 
@@ -252,7 +260,7 @@ pub(crate) fn fn_from_item_fn<'hir>(
     }
 
     arguments.free()?;
-    cx.scopes.pop_last(hir)?;
+    cx.scopes.pop_last(hir, cx.asm)?;
     Ok(())
 }
 
@@ -263,15 +271,17 @@ pub(crate) fn async_block_secondary<'hir>(
     hir: &'hir hir::AsyncBlock<'hir>,
 ) -> compile::Result<()> {
     let linear = cx.scopes.linear(&hir.block, hir.captures.len())?;
+    cx.try_kind = try_kind::block(hir.block);
 
     for (name, needs) in hir.captures.iter().copied().zip(&linear) {
-        cx.scopes.define(&hir.block, name, needs)?;
+        cx.scopes.define(&hir.block, name, needs, cx.asm)?;
     }
 
-    return_(cx, &hir.block, hir.block, block_without_scope)?.ignore();
+    let shared = hir.block.value.is_some_and(may_share);
+    return_(cx, &hir.block, hir.block, shared, block_without_scope)?.ignore();
 
     linear.free()?;
-    cx.scopes.pop_last(&hir.block)?;
+    cx.scopes.pop_last(&hir.block, cx.asm)?;
     Ok(())
 }
 
@@ -283,6 +293,7 @@ pub(crate) fn expr_closure_secondary<'hir>(
 ) -> compile::Result<()> {
     let mut arguments = cx.scopes.linear(hir, hir.args.len())?;
     let environment = cx.scopes.linear(hir, hir.captures.len())?;
+    cx.try_kind = try_kind::expr(hir.body);
 
     if !hir.captures.is_empty() {
         cx.asm.push(
@@ -295,7 +306,7 @@ pub(crate) fn expr_closure_secondary<'hir>(
         )?;
 
         for (capture, needs) in hir.captures.iter().copied().zip(&environment) {
-            cx.scopes.define(hir, capture, needs)?;
+            cx.scopes.define(hir, capture, needs, cx.asm)?;
         }
     }
 
@@ -310,18 +321,91 @@ pub(crate) fn expr_closure_secondary<'hir>(
                 })?;
 
                 asm.ignore();
+                clone_immutable(cx, pat, pat.names)?;
             }
         }
     }
 
-    return_(cx, hir, hir.body, expr)?.ignore();
+    return_(cx, hir, hir.body, may_share(hir.body), expr)?.ignore();
 
     environment.free()?;
     arguments.free()?;
-    cx.scopes.pop_last(hir)?;
+    cx.scopes.pop_last(hir, cx.asm)?;
+    Ok(())
+}
+
+/// Clone the values of the given variables if they are of a type which has been
+/// marked as immutable, so that they don't share state with any other binding.
+fn clone_immutable(
+    cx: &mut Ctxt<'_, '_, '_>,
+    span: &dyn Spanned,
+    names: &[hir::Variable],
+) -> compile::Result<()> {
+    if !cx.q.context.has_immutable() {
+        return Ok(());
+    }
+
+    for &name in names {
+        let var = cx.scopes.get(&mut cx.q, span, name)?;
+        cx.asm.push(Inst::CloneImmutable { addr: var.addr }, span)?;
+    }
+
     Ok(())
 }
 
+/// Clone the values in the given slots which might be shared if they are of a
+/// type which has been marked as immutable.
+///
+/// Each slot must have been allocated for the expression it's paired with, and
+/// not alias the address of a variable.
+fn clone_immutable_slots<'hir>(
+    cx: &mut Ctxt<'_, 'hir, '_>,
+    span: &dyn Spanned,
+    slots: impl IntoIterator<Item = (&'hir hir::Expr<'hir>, InstAddress)>,
+) -> compile::Result<()> {
+    if !cx.q.context.has_immutable() {
+        return Ok(());
+    }
+
+    for (hir, addr) in slots {
+        if may_share(hir) {
+            cx.asm.push(Inst::CloneImmutable { addr }, span)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Test if the value produced by an expression might be shared with something
+/// else, as opposed to being freshly constructed by it.
+fn may_share(hir: &hir::Expr<'_>) -> bool {
+    match hir.kind {
+        hir::ExprKind::Group(hir) => may_share(hir),
+        hir::ExprKind::Block(block) => block.value.is_some_and(may_share),
+        kind => !matches!(
+            kind,
+            hir::ExprKind::Lit(..)
+                | hir::ExprKind::Object(..)
+                | hir::ExprKind::Tuple(..)
+                | hir::ExprKind::Vec(..)
+                | hir::ExprKind::Range(..)
+                | hir::ExprKind::Template(..)
+                | hir::ExprKind::Format(..)
+                | hir::ExprKind::Const(..)
+                | hir::ExprKind::Type(..)
+                | hir::ExprKind::Fn(..)
+                | hir::ExprKind::CallClosure(..)
+                | hir::ExprKind::AsyncBlock(..)
+                | hir::ExprKind::Let(..)
+                | hir::ExprKind::Assign(..)
+                | hir::ExprKind::For(..)
+                | hir::ExprKind::Break(..)
+                | hir::ExprKind::Continue(..)
+                | hir::ExprKind::Return(..)
+        ),
+    }
+}
+
 #[instrument_ast(span = pat)]
 fn fn_arg_pat<'a, 'hir>(
     cx: &mut Ctxt<'a, 'hir, '_>,
@@ -352,21 +436,26 @@ fn fn_arg_pat<'a, 'hir>(
 }
 
 /// Assemble a return statement from the given Assemble.
+///
+/// If the returned value might be `shared` it is cloned if it's of a type
+/// which has been marked as immutable.
 fn return_<'a, 'hir, T>(
     cx: &mut Ctxt<'a, 'hir, '_>,
     span: &'hir dyn Spanned,
     hir: T,
+    shared: bool,
     asm: impl FnOnce(&mut Ctxt<'a, 'hir, '_>, T, &mut dyn Needs<'a, 'hir>) -> compile::Result<Asm<'hir>>,
 ) -> compile::Result<Asm<'hir>> {
     let mut needs = cx.scopes.defer(span).with_name("return value");
     converge!(asm(cx, hir, &mut needs)?, free(needs));
 
-    cx.asm.push(
-        Inst::Return {
-            addr: needs.addr()?.addr(),
-        },
-        span,
-    )?;
+    let addr = needs.addr()?.addr();
+
+    if shared && cx.q.context.has_immutable() {
+        cx.asm.push(Inst::CloneImmutable { addr }, span)?;
+    }
+
+    cx.asm.push(Inst::Return { addr }, span)?;
 
     needs.free()?;
     Ok(Asm::new(span, ()))
@@ -451,7 +540,7 @@ fn pat_binding_with<'a, 'hir>(
     }
 
     for (name, needs) in names.iter().copied().zip(linear.iter()) {
-        cx.scopes.define(needs.span(), name, needs)?;
+        cx.scopes.define(needs.span(), name, needs, cx.asm)?;
     }
 
     Ok(asm)
@@ -488,7 +577,7 @@ fn pat_binding_with_single<'a, 'hir>(
         ));
     };
 
-    cx.scopes.define(needs.span(), name, addr)?;
+    cx.scopes.define(needs.span(), name, addr, cx.asm)?;
     Ok(asm)
 }
 
@@ -639,7 +728,7 @@ fn pat_lit_inst(
 #[instrument_ast(span = hir)]
 fn condition<'a, 'hir>(
     cx: &mut Ctxt<'a, 'hir, '_>,
-    hir: &hir::Condition<'hir>,
+    hir: &'hir hir::Condition<'hir>,
     then_label: &Label,
     false_label: &Label,
     linear: &mut [Address<'a, 'hir>],
@@ -655,7 +744,7 @@ fn condition<'a, 'hir>(
                 Asm::new(hir, (scope, Pattern::Irrefutable))
             } else {
                 addr.free()?;
-                cx.scopes.pop(hir, scope)?;
+                cx.scopes.pop(hir, scope, cx.asm)?;
                 Asm::diverge(hir)
             };
 
@@ -684,10 +773,63 @@ fn condition<'a, 'hir>(
                 cx.asm.jump(then_label, span)?;
                 Ok(Asm::new(span, (scope, pat)))
             } else {
-                cx.scopes.pop(span, scope)?;
+                cx.scopes.pop(span, scope, cx.asm)?;
                 Ok(Asm::diverge(span))
             }
         }
+        hir::Condition::Chain(_, conditions) => {
+            let span = hir;
+
+            let scope = cx.scopes.child(span)?;
+            let mut linear = linear;
+
+            for c in conditions {
+                let converges = match *c {
+                    hir::Condition::ExprLet(hir) => {
+                        let (head, tail) = linear.split_at_mut(hir.pat.names.len());
+                        linear = tail;
+
+                        let mut load =
+                            |cx: &mut Ctxt<'a, 'hir, '_>, needs: &mut dyn Needs<'a, 'hir>| {
+                                expr(cx, &hir.expr, needs)
+                            };
+
+                        pat_binding_with(
+                            cx,
+                            &hir.pat,
+                            &hir.pat.pat,
+                            hir.pat.names,
+                            false_label,
+                            &mut load,
+                            head,
+                        )?
+                        .converging()
+                    }
+                    hir::Condition::Expr(hir) => {
+                        let mut addr = cx.scopes.alloc(hir)?.with_name("expression condition");
+                        let converges = expr(cx, hir, &mut addr)?.converging();
+
+                        if converges {
+                            cx.asm.jump_if_not(addr.addr(), false_label, hir)?;
+                        }
+
+                        addr.free()?;
+                        converges
+                    }
+                    hir::Condition::Chain(..) => {
+                        return Err(compile::Error::msg(c, "Unsupported nested condition chain"));
+                    }
+                };
+
+                if !converges {
+                    cx.scopes.pop(span, scope, cx.asm)?;
+                    return Ok(Asm::diverge(span));
+                }
+            }
+
+            cx.asm.jump(then_label, span)?;
+            Ok(Asm::new(span, (scope, Pattern::Refutable)))
+        }
     }
 }
 
@@ -732,16 +874,37 @@ fn pat_sequence<'a, 'hir>(
         cx.asm.push(inst, span)?;
         cx.asm.jump_if_not(cond.addr(), false_label, span)?;
 
+        let type_check = match hir.kind {
+            hir::PatSequenceKind::Anonymous { type_check, .. } => Some(type_check),
+            _ => None,
+        };
+
+        // Items following a rest pattern are indexed from the back.
+        let rest = match (hir.rest, type_check) {
+            (Some(rest), Some(type_check)) => Some((rest, type_check)),
+            (Some(..), None) => {
+                return Err(compile::Error::new(span, ErrorKind::UnsupportedPatternRest));
+            }
+            (None, _) => None,
+        };
+
         for (index, p) in hir.items.iter().enumerate() {
             let mut load = |cx: &mut Ctxt<'a, 'hir, '_>, needs: &mut dyn Needs<'a, 'hir>| {
-                cx.asm.push(
-                    Inst::TupleIndexGetAt {
+                let inst = match rest {
+                    Some((rest, type_check)) if index >= rest.index => Inst::SequenceIndexGetBack {
+                        type_check,
+                        addr: addr.addr(),
+                        index: hir.items.len() - index - 1,
+                        out: needs.alloc_output()?,
+                    },
+                    _ => Inst::TupleIndexGetAt {
                         addr: addr.addr(),
                         index,
                         out: needs.alloc_output()?,
                     },
-                    p,
-                )?;
+                };
+
+                cx.asm.push(inst, p)?;
                 Ok(Asm::new(p, ()))
             };
 
@@ -750,6 +913,33 @@ fn pat_sequence<'a, 'hir>(
                 free(cond, addr)
             );
         }
+
+        if let Some((
+            hir::PatRest {
+                index,
+                binding: Some(name),
+            },
+            type_check,
+        )) = rest
+        {
+            let Some(binding) = bindings.remove(&name) else {
+                return Err(compile::Error::msg(
+                    span,
+                    format!("No binding for {name:?}"),
+                ));
+            };
+
+            cx.asm.push(
+                Inst::SequenceRest {
+                    type_check,
+                    addr: addr.addr(),
+                    front: index,
+                    back: hir.items.len() - index,
+                    out: binding.output()?,
+                },
+                span,
+            )?;
+        }
     }
 
     cond.free()?;
@@ -769,6 +959,7 @@ fn pat_sequence_kind_to_inst(kind: hir::PatSequenceKind, addr: InstAddress, out:
             variant_hash,
             enum_hash,
             index,
+            ..
         } => Inst::MatchVariant {
             variant_hash,
             enum_hash,
@@ -830,6 +1021,7 @@ fn pat_object<'a, 'hir>(
             variant_hash,
             enum_hash,
             index,
+            ..
         } => Inst::MatchVariant {
             variant_hash,
             enum_hash,
@@ -898,6 +1090,28 @@ fn pat_object<'a, 'hir>(
         }
     }
 
+    if let Some(name) = hir.rest {
+        let Some(binding) = bindings.remove(&name) else {
+            return Err(compile::Error::msg(
+                span,
+                format!("No binding for {name:?}"),
+            ));
+        };
+
+        let slot =
+            cx.q.unit
+                .new_static_object_keys_iter(span, hir.bindings.iter().map(|b| b.key()))?;
+
+        cx.asm.push(
+            Inst::ObjectRest {
+                addr: addr.addr(),
+                slot,
+                out: binding.output()?,
+            },
+            span,
+        )?;
+    }
+
     needs.free()?;
     Ok(Asm::new(span, Pattern::Refutable))
 }
@@ -914,6 +1128,7 @@ fn block<'a, 'hir>(
 
         cx.breaks.push(Break {
             label: Some(label),
+            span: hir.span(),
             continue_label: None,
             break_label: break_label.try_clone()?,
             output: Some(needs.alloc_output()?),
@@ -927,7 +1142,7 @@ fn block<'a, 'hir>(
 
     let scope = cx.scopes.child(hir)?;
     let asm = block_without_scope(cx, hir, needs)?;
-    cx.scopes.pop(hir, scope)?;
+    cx.scopes.pop(hir, scope, cx.asm)?;
 
     cx.drop_dangling(hir)?;
 
@@ -953,7 +1168,8 @@ fn block_without_scope<'a, 'hir>(
         let mut needs = Any::ignore(hir).with_name("statement ignore");
 
         if let Some(cause) = diverge {
-            cx.q.diagnostics.unreachable(cx.source_id, stmt, cause)?;
+            cx.q.diagnostics
+                .unreachable_code(cx.source_id, stmt, cause)?;
             continue;
         }
 
@@ -969,7 +1185,7 @@ fn block_without_scope<'a, 'hir>(
 
     if let Some(cause) = diverge {
         if let Some(e) = hir.value {
-            cx.q.diagnostics.unreachable(cx.source_id, e, cause)?;
+            cx.q.diagnostics.unreachable_code(cx.source_id, e, cause)?;
         }
     } else if let Some(e) = hir.value {
         if expr(cx, e, needs)?.diverging() {
@@ -1001,7 +1217,7 @@ fn builtin_format<'a, 'hir>(
     use crate::runtime::format;
 
     let fill = format.spec.fill.unwrap_or(' ');
-    let align = format.spec.align.unwrap_or_default();
+    let align = format.spec.align;
     let flags = format.spec.flags.unwrap_or_default();
     let width = format.spec.width;
     let precision = format.spec.precision;
@@ -1034,6 +1250,21 @@ fn builtin_template<'a, 'hir>(
 ) -> compile::Result<Asm<'hir>> {
     let span = hir;
 
+    if cx.options.constant_folding {
+        if let Some(value) = fold::builtin_template(cx, hir)? {
+            let literal =
+                |hir: &hir::Expr<'_>| matches!(hir.kind, hir::ExprKind::Lit(hir::Lit::Str(..)));
+
+            if hir.from_literal && hir.exprs.iter().all(literal) {
+                cx.q.diagnostics
+                    .template_without_expansions(cx.source_id, span, cx.context())?;
+            }
+
+            const_(cx, &value, span, needs)?;
+            return Ok(Asm::new(span, ()));
+        }
+    }
+
     let mut size_hint = 0;
     let mut expansions = 0;
 
@@ -1345,6 +1576,11 @@ fn expr_assign<'a, 'hir>(
             let mut needs = Address::assigned(var.span, cx.scopes, var.addr);
             converge!(expr(cx, &hir.rhs, &mut needs)?, free(needs));
             needs.free()?;
+
+            if may_share(&hir.rhs) && cx.q.context.has_immutable() {
+                cx.asm.push(Inst::CloneImmutable { addr: var.addr }, span)?;
+            }
+
             true
         }
         // <expr>.<field> = <value>
@@ -1476,6 +1712,13 @@ fn expr_binary<'a, 'hir>(
         return compile_assign_binop(cx, &hir.lhs, &hir.rhs, &hir.op, span, needs);
     }
 
+    if cx.options.constant_folding {
+        if let Some(value) = fold::expr_binary(cx, hir, span)? {
+            const_(cx, &value, span, needs)?;
+            return Ok(Asm::new(span, ()));
+        }
+    }
+
     if hir.op.is_conditional() {
         return compile_conditional_binop(cx, &hir.lhs, &hir.rhs, &hir.op, span, needs);
     }
@@ -1801,10 +2044,15 @@ fn expr_async_block<'a, 'hir>(
 
         if hir.do_move {
             let var = cx.scopes.take(&mut cx.q, span, capture)?;
-            var.move_(cx.asm, span, Some(&"capture"), out)?;
+            var.clone_(cx.asm, span, Some(&"capture"), out)?;
         } else {
             let var = cx.scopes.get(&mut cx.q, span, capture)?;
             var.copy(cx.asm, span, Some(&"capture"), out)?;
+
+            if cx.q.context.has_immutable() {
+                let addr = needs.addr();
+                cx.asm.push(Inst::CloneImmutable { addr }, span)?;
+            }
         }
     }
 
@@ -1852,10 +2100,10 @@ fn expr_break<'hir>(
     hir: &hir::ExprBreak<'hir>,
     span: &'hir dyn Spanned,
 ) -> compile::Result<Asm<'hir>> {
-    let (break_label, output) = match hir.label {
+    let (break_label, output, loop_span) = match hir.label {
         Some(label) => {
             let l = cx.breaks.walk_until_label(span, label, &mut cx.drop)?;
-            (l.break_label.try_clone()?, l.output)
+            (l.break_label.try_clone()?, l.output, l.span)
         }
         None => {
             let Some(l) = cx.breaks.last() else {
@@ -1864,13 +2112,16 @@ fn expr_break<'hir>(
 
             cx.drop.clear();
             cx.drop.try_extend(l.drop).with_span(span)?;
-            (l.break_label.try_clone()?, l.output)
+            (l.break_label.try_clone()?, l.output, l.span)
         }
     };
 
     if let Some(hir) = hir.expr {
         let Some(output) = output else {
-            return Err(compile::Error::new(span, ErrorKind::BreakUnsupportedValue));
+            return Err(compile::Error::new(
+                span,
+                ErrorKind::BreakUnsupportedValue { loop_span },
+            ));
         };
 
         let mut needs = match output.as_addr() {
@@ -1933,6 +2184,7 @@ fn expr_call<'a, 'hir>(
             cx.asm.push(
                 Inst::CallAssociated {
                     hash,
+                    cache: cx.q.unit.new_call_site(),
                     addr: linear.addr(),
                     args: args + 1,
                     out: needs.alloc_output()?,
@@ -1956,6 +2208,10 @@ fn expr_call<'a, 'hir>(
             )?;
 
             linear.free_non_dangling()?;
+
+            if cx.q.context.is_diverging(hash) {
+                return Ok(Asm::diverge(span));
+            }
         }
         hir::Call::Expr { expr: e } => {
             let mut function = cx.scopes.defer(span);
@@ -2104,10 +2360,15 @@ fn expr_call_closure<'a, 'hir>(
 
         if hir.do_move {
             let var = cx.scopes.take(&mut cx.q, span, capture)?;
-            var.move_(cx.asm, span, Some(&"capture"), out)?;
+            var.clone_(cx.asm, span, Some(&"capture"), out)?;
         } else {
             let var = cx.scopes.get(&mut cx.q, span, capture)?;
             var.copy(cx.asm, span, Some(&"capture"), out)?;
+
+            if cx.q.context.has_immutable() {
+                let addr = needs.addr();
+                cx.asm.push(Inst::CloneImmutable { addr }, span)?;
+            }
         }
     }
 
@@ -2151,7 +2412,7 @@ fn expr_continue<'a, 'hir>(
     };
 
     cx.asm.jump(label, span)?;
-    Ok(Asm::new(span, ()))
+    Ok(Asm::diverge(span))
 }
 
 /// Assemble an expr field access, like `<value>.<field>`.
@@ -2234,7 +2495,7 @@ fn expr_for<'a, 'hir>(
     if !expr(cx, &hir.iter, &mut iter)?.converging() {
         iter.free()?;
         cx.q.diagnostics
-            .unreachable(cx.source_id, &hir.body, &hir.iter)?;
+            .unreachable_code(cx.source_id, &hir.body, &hir.iter)?;
         return Ok(Asm::diverge(span));
     }
 
@@ -2261,6 +2522,7 @@ fn expr_for<'a, 'hir>(
         Inst::CallAssociated {
             addr: into_iter.addr(),
             hash: Protocol::INTO_ITER.hash,
+            cache: cx.q.unit.new_call_site(),
             args: 1,
             out: into_iter.output(),
         },
@@ -2291,6 +2553,7 @@ fn expr_for<'a, 'hir>(
 
     cx.breaks.push(Break {
         label: hir.label,
+        span: span.span(),
         continue_label: Some(continue_label.try_clone()?),
         break_label: break_label.try_clone()?,
         output: None,
@@ -2323,6 +2586,7 @@ fn expr_for<'a, 'hir>(
             Inst::CallAssociated {
                 addr: into_iter_copy.addr(),
                 hash: Protocol::NEXT.hash,
+                cache: cx.q.unit.new_call_site(),
                 args: 1,
                 out: binding.output(),
             },
@@ -2361,7 +2625,7 @@ fn expr_for<'a, 'hir>(
 
     let asm = block(cx, &hir.body, &mut Any::ignore(span))?;
     bindings.free()?;
-    cx.scopes.pop(span, inner_loop_scope)?;
+    cx.scopes.pop(span, inner_loop_scope, cx.asm)?;
 
     if asm.converging() {
         cx.asm.jump(&continue_label, span)?;
@@ -2478,7 +2742,7 @@ fn expr_if<'a, 'hir>(
             block(cx, &branch.block, needs)?
         };
 
-        cx.scopes.pop(branch, scope)?;
+        cx.scopes.pop(branch, scope, cx.asm)?;
 
         if asm.converging() && it.peek().is_some() {
             cx.asm.jump(&end_label, branch)?;
@@ -2537,6 +2801,10 @@ fn expr_let<'a, 'hir>(
         pat_binding(cx, &hir.pat, false_label, &mut load)
     })?);
 
+    if may_share(&hir.expr) {
+        clone_immutable(cx, hir, hir.pat.names)?;
+    }
+
     // If a value is needed for a let expression, it is evaluated as a unit.
     if let Some(out) = needs.try_alloc_output()? {
         cx.asm.push(Inst::unit(out), hir)?;
@@ -2552,6 +2820,8 @@ fn expr_match<'a, 'hir>(
     span: &'hir dyn Spanned,
     needs: &mut dyn Needs<'a, 'hir>,
 ) -> compile::Result<Asm<'hir>> {
+    exhaustive::match_variants(cx, hir, span)?;
+
     let mut value = cx.scopes.defer(span);
     converge!(expr(cx, hir.expr, &mut value)?, free(value));
     let value = value.into_addr()?;
@@ -2562,14 +2832,14 @@ fn expr_match<'a, 'hir>(
     let count = hir
         .branches
         .iter()
-        .map(|b| b.pat.names.len())
+        .map(|b| b.pat.names.len() + b.condition.and_then(|c| c.count()).unwrap_or_default())
         .max()
         .unwrap_or_default();
 
     let mut linear = cx.scopes.linear(span, count)?;
     let mut is_irrefutable = false;
 
-    for branch in hir.branches {
+    for (index, branch) in hir.branches.iter().enumerate() {
         let span = branch;
 
         let branch_label = cx.asm.new_label("match_branch");
@@ -2594,8 +2864,9 @@ fn expr_match<'a, 'hir>(
 
         if let Some(pat) = asm.into_converging() {
             let mut converges = true;
+            let mut condition_scope = None;
 
-            if let Some(condition) = branch.condition {
+            if let Some(&hir::Condition::Expr(condition)) = branch.condition {
                 let scope = cx.scopes.child(condition)?;
                 let mut cond = cx.scopes.alloc(condition)?.with_name("match condition");
 
@@ -2607,7 +2878,20 @@ fn expr_match<'a, 'hir>(
                 }
 
                 cond.free()?;
-                cx.scopes.pop(span, scope)?;
+                cx.scopes.pop(span, scope, cx.asm)?;
+            } else if let Some(condition) = branch.condition {
+                // Variables defined by the condition are visible in the body,
+                // so its scope is kept until the body has been assembled.
+                let linear = &mut linear[branch.pat.names.len()..];
+
+                if let Some((scope, _)) =
+                    self::condition(cx, condition, &branch_label, &match_false, linear)?
+                        .into_converging()
+                {
+                    condition_scope = Some(cx.scopes.dangle(condition, scope)?);
+                } else {
+                    converges = false;
+                }
             } else {
                 // If there is no branch condition, and the branch is
                 // irrefutable, there is no point in assembling the additional
@@ -2618,16 +2902,21 @@ fn expr_match<'a, 'hir>(
             if converges {
                 cx.asm.jump(&branch_label, span)?;
                 let pattern_scope = cx.scopes.dangle(span, pattern_scope)?;
-                branches.try_push((branch_label, pattern_scope))?;
+                branches.try_push((branch_label, pattern_scope, condition_scope))?;
             } else {
                 // If the branch condition diverges, there is no reason to
                 // assemble the other branches if this one is irrefutable.
                 is_irrefutable = matches!(pat, Pattern::Irrefutable);
-                cx.scopes.pop(span, pattern_scope)?;
+                cx.scopes.pop(span, pattern_scope, cx.asm)?;
             }
         }
 
         if is_irrefutable {
+            for unreachable in hir.branches.get(index + 1..).unwrap_or_default() {
+                cx.q.diagnostics
+                    .unreachable_code(cx.source_id, unreachable, &branch.pat)?;
+            }
+
             break;
         }
 
@@ -2642,25 +2931,42 @@ fn expr_match<'a, 'hir>(
         cx.asm.jump(&end_label, span)?;
     }
 
+    // A match only converges if it can fall through or if any of its
+    // branches converge.
+    let mut converges = !is_irrefutable;
     let mut it = hir.branches.iter().zip(branches).peekable();
 
-    while let Some((branch, (label, scope))) = it.next() {
+    while let Some((branch, (label, scope, condition_scope))) = it.next() {
         let span = branch;
 
         cx.asm.label(&label)?;
         let scope = cx.scopes.restore(scope);
+        let condition_scope = condition_scope.map(|scope| cx.scopes.restore(scope));
 
-        if expr(cx, &branch.body, needs)?.converging() && it.peek().is_some() {
-            cx.asm.jump(&end_label, span)?;
+        if expr(cx, &branch.body, needs)?.converging() {
+            converges = true;
+
+            if it.peek().is_some() {
+                cx.asm.jump(&end_label, span)?;
+            }
+        }
+
+        if let Some(condition_scope) = condition_scope {
+            cx.scopes.pop(span, condition_scope, cx.asm)?;
         }
 
-        cx.scopes.pop(span, scope)?;
+        cx.scopes.pop(span, scope, cx.asm)?;
     }
 
     cx.asm.label(&end_label)?;
 
     value.free()?;
     linear.free()?;
+
+    if !converges {
+        return Ok(Asm::diverge(span));
+    }
+
     Ok(Asm::new(span, ()))
 }
 
@@ -2675,6 +2981,9 @@ fn expr_object<'a, 'hir>(
     if let Some(linear) =
         exprs_with(cx, span, hir.assignments, |hir| &hir.assign)?.into_converging()
     {
+        let slots = hir.assignments.iter().map(|a| &a.assign);
+        clone_immutable_slots(cx, span, slots.zip(linear.iter().map(Address::addr)))?;
+
         match hir.kind {
             hir::ExprObjectKind::EmptyStruct { hash } => {
                 cx.asm.push(
@@ -2880,7 +3189,7 @@ fn expr_return<'hir>(
     span: &'hir dyn Spanned,
 ) -> compile::Result<Asm<'hir>> {
     if let Some(e) = hir {
-        converge!(return_(cx, span, e, expr)?);
+        converge!(return_(cx, span, e, may_share(e), expr)?);
     } else {
         cx.asm.push(Inst::ReturnUnit, span)?;
     }
@@ -2921,6 +3230,7 @@ fn expr_select_inner<'a, 'hir>(
             addr: linear.addr(),
             len: hir.exprs.len(),
             value: value_addr.output(),
+            default: default_branch.is_some(),
         },
         span,
     )?;
@@ -2960,7 +3270,7 @@ fn expr_select_inner<'a, 'hir>(
             cx.asm.jump(&end_label, span)?;
         }
 
-        cx.scopes.pop(&branch.body, scope)?;
+        cx.scopes.pop(&branch.body, scope, cx.asm)?;
     }
 
     cx.select_branches = branches;
@@ -3023,6 +3333,7 @@ fn expr_try<'a, 'hir>(
         Inst::Try {
             addr: e.addr()?.addr(),
             out: needs.alloc_output()?,
+            kind: cx.try_kind,
         },
         span,
     )?;
@@ -3059,16 +3370,22 @@ fn expr_tuple<'a, 'hir>(
         }};
     }
 
+    // Values of immutable types which might be shared are cloned in their own
+    // slots, which the fixed size tuple instructions don't guarantee.
+    let fixed = !cx.q.context.has_immutable() || !hir.items.iter().any(may_share);
+
     match hir.items {
         [] => {
             cx.asm.push(Inst::unit(needs.alloc_output()?), span)?;
         }
-        [e1] => tuple!(Tuple1, v1, e1),
-        [e1, e2] => tuple!(Tuple2, v1, e1, v2, e2),
-        [e1, e2, e3] => tuple!(Tuple3, v1, e1, v2, e2, v3, e3),
-        [e1, e2, e3, e4] => tuple!(Tuple4, v1, e1, v2, e2, v3, e3, v4, e4),
+        [e1] if fixed => tuple!(Tuple1, v1, e1),
+        [e1, e2] if fixed => tuple!(Tuple2, v1, e1, v2, e2),
+        [e1, e2, e3] if fixed => tuple!(Tuple3, v1, e1, v2, e2, v3, e3),
+        [e1, e2, e3, e4] if fixed => tuple!(Tuple4, v1, e1, v2, e2, v3, e3, v4, e4),
         _ => {
             let linear = converge!(exprs(cx, span, hir.items)?);
+            let slots = hir.items.iter().zip(linear.iter().map(Address::addr));
+            clone_immutable_slots(cx, span, slots)?;
 
             if let Some(out) = needs.try_alloc_output()? {
                 cx.asm.push(
@@ -3098,6 +3415,13 @@ fn expr_unary<'a, 'hir>(
     span: &'hir dyn Spanned,
     needs: &mut dyn Needs<'a, 'hir>,
 ) -> compile::Result<Asm<'hir>> {
+    if cx.options.constant_folding {
+        if let Some(value) = fold::expr_unary(cx, hir, span)? {
+            const_(cx, &value, span, needs)?;
+            return Ok(Asm::new(span, ()));
+        }
+    }
+
     let mut addr = cx.scopes.defer(span);
     converge!(expr(cx, &hir.expr, &mut addr)?, free(addr));
     let addr = addr.into_addr()?;
@@ -3148,6 +3472,9 @@ fn expr_vec<'a, 'hir>(
         converge!(expr(cx, e, needs)?, free(linear));
     }
 
+    let slots = hir.items.iter().zip(linear.iter().map(Address::addr));
+    clone_immutable_slots(cx, span, slots)?;
+
     if let Some(out) = needs.try_alloc_addr()? {
         cx.asm.push(
             Inst::Vec {
@@ -3177,13 +3504,21 @@ fn expr_loop<'a, 'hir>(
     let continue_label = cx.asm.new_label("while_continue");
     let then_label = cx.asm.new_label("while_then");
     let end_label = cx.asm.new_label("while_end");
-    let break_label = cx.asm.new_label("while_break");
+
+    // Only a `loop` can be broken out of with a value, breaking out of a
+    // `while` loop produces unit the same way as the loop ending does.
+    let (break_label, output) = if hir.condition.is_some() {
+        (end_label.try_clone()?, None)
+    } else {
+        (cx.asm.new_label("while_break"), Some(needs.alloc_output()?))
+    };
 
     cx.breaks.push(Break {
         label: hir.label,
+        span: span.span(),
         continue_label: Some(continue_label.try_clone()?),
         break_label: break_label.try_clone()?,
-        output: Some(needs.alloc_output()?),
+        output,
         drop: None,
     })?;
 
@@ -3210,7 +3545,7 @@ fn expr_loop<'a, 'hir>(
     block(cx, &hir.body, &mut Any::ignore(span))?.ignore();
 
     if let Some(scope) = condition_scope {
-        cx.scopes.pop(span, scope)?;
+        cx.scopes.pop(span, scope, cx.asm)?;
     }
 
     cx.asm.jump(&continue_label, span)?;
@@ -3220,7 +3555,9 @@ fn expr_loop<'a, 'hir>(
         cx.asm.push(Inst::unit(out), span)?;
     }
 
-    cx.asm.label(&break_label)?;
+    if hir.condition.is_none() {
+        cx.asm.label(&break_label)?;
+    }
 
     linear.free()?;
     cx.breaks.pop();
@@ -3317,6 +3654,10 @@ fn local<'a, 'hir>(
         pat_binding(cx, &hir.pat, false_label, &mut load)
     })?);
 
+    if may_share(&hir.expr) {
+        clone_immutable(cx, hir, hir.pat.names)?;
+    }
+
     // If a value is needed for a let expression, it is evaluated as a unit.
     if let Some(out) = needs.try_alloc_output()? {
         cx.asm.push(Inst::unit(out), hir)?;
@@ -1,7 +1,7 @@
 use crate as rune;
 use crate::alloc::prelude::*;
 use crate::alloc::{self, Vec};
-use crate::ast::Spanned;
+use crate::ast::{Span, Spanned};
 use crate::compile::{self, ErrorKind, WithSpan};
 use crate::runtime::{InstAddress, Label, Output};
 
@@ -10,6 +10,8 @@ use crate::runtime::{InstAddress, Label, Output};
 pub(crate) struct Break<'hir> {
     /// The optional label of the start of the break.
     pub(crate) label: Option<&'hir str>,
+    /// The span of the loop or block being broken out of.
+    pub(crate) span: Span,
     /// If the break supports breaking with a value, this would be where to
     /// store it.
     pub(crate) output: Option<Output>,
@@ -90,6 +92,7 @@ impl<'hir> Breaks<'hir> {
             span,
             ErrorKind::MissingLabel {
                 label: expected.try_into()?,
+                label_span: span.span(),
             },
         ))
     }
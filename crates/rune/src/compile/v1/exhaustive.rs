@@ -0,0 +1,172 @@
+//! Exhaustiveness checking of match expressions over enums.
+//!
+//! Since values are dynamically typed, the enum being matched over is inferred
+//! from the variant patterns used in the branches of the match. If every
+//! pattern doesn't refer to a variant of the same enum, no checking is
+//! performed.
+
+use crate::alloc::prelude::*;
+use crate::alloc::{Box, Vec};
+use crate::ast::Spanned;
+use crate::compile::{self, meta, ErrorKind};
+use crate::hir;
+use crate::item::ComponentRef;
+use crate::query::Used;
+use crate::{Hash, Item};
+
+use super::Ctxt;
+
+/// Check that the given match covers every variant of the enum that it
+/// matches over.
+pub(super) fn match_variants(
+    cx: &mut Ctxt<'_, '_, '_>,
+    hir: &hir::ExprMatch<'_>,
+    span: &dyn Spanned,
+) -> compile::Result<()> {
+    let mut matched = None;
+    let mut covered = Vec::new();
+
+    for branch in hir.branches {
+        let (kind, is_complete) = match branch.pat.pat.kind {
+            hir::PatKind::Ignore | hir::PatKind::Path(hir::PatPathKind::Ident(..)) => {
+                if branch.condition.is_none() {
+                    return Ok(());
+                }
+
+                continue;
+            }
+            hir::PatKind::Path(hir::PatPathKind::Kind(kind)) => (*kind, true),
+            hir::PatKind::Sequence(sequence) => {
+                (&sequence.kind, sequence.items.iter().all(is_irrefutable))
+            }
+            hir::PatKind::Object(object) => (
+                &object.kind,
+                object.bindings.iter().all(|binding| match binding {
+                    hir::Binding::Binding(_, _, pat) => is_irrefutable(pat),
+                    hir::Binding::Ident(..) => true,
+                }),
+            ),
+            hir::PatKind::Lit(..) => return Ok(()),
+        };
+
+        let &hir::PatSequenceKind::Variant {
+            enum_hash,
+            index,
+            item,
+            ..
+        } = kind
+        else {
+            return Ok(());
+        };
+
+        let (expected, _) = *matched.get_or_insert((enum_hash, item));
+
+        if expected != enum_hash {
+            return Ok(());
+        }
+
+        if is_complete && branch.condition.is_none() {
+            covered.try_push(index)?;
+        }
+    }
+
+    let Some((enum_hash, item)) = matched else {
+        return Ok(());
+    };
+
+    let Some(enum_item) = cx.q.pool.item(item).parent() else {
+        return Ok(());
+    };
+
+    let enum_item = enum_item.try_to_owned()?;
+
+    let mut missing = Vec::new();
+
+    for (index, name) in variants(cx, span, enum_hash, &enum_item)? {
+        if !covered.contains(&index) {
+            missing.try_push(name)?;
+        }
+    }
+
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    Err(compile::Error::new(
+        span,
+        ErrorKind::MatchMissingVariants {
+            item: enum_item,
+            variants: missing.try_into()?,
+        },
+    ))
+}
+
+/// Test if the pattern matches any value.
+fn is_irrefutable(pat: &hir::Pat<'_>) -> bool {
+    matches!(
+        pat.kind,
+        hir::PatKind::Ignore | hir::PatKind::Path(hir::PatPathKind::Ident(..))
+    )
+}
+
+/// Collect the index and name of every variant of the enum with the given
+/// hash and item, ordered by index.
+fn variants(
+    cx: &mut Ctxt<'_, '_, '_>,
+    span: &dyn Spanned,
+    enum_hash: Hash,
+    enum_item: &Item,
+) -> compile::Result<Vec<(usize, Box<str>)>> {
+    let mut names = Vec::<Box<str>>::new();
+
+    for component in
+        cx.q.iter_components(enum_item)?
+            .chain(cx.q.context.iter_components(enum_item)?)
+    {
+        if let ComponentRef::Str(name) = component {
+            if !names.iter().any(|n| n.as_ref() == name) {
+                names.try_push(name.try_into()?)?;
+            }
+        }
+    }
+
+    let mut variants = Vec::new();
+
+    for name in names {
+        let item = enum_item.extended(name.as_ref())?;
+
+        let index = match cx.q.context.lookup_meta(&item) {
+            Some(mut metas) => metas.find_map(|meta| match meta.kind {
+                meta::Kind::Variant {
+                    enum_hash: hash,
+                    index,
+                    ..
+                } if hash == enum_hash => Some(index),
+                _ => None,
+            }),
+            None => {
+                let id = cx.q.pool.alloc_item(&item)?;
+
+                match cx.q.query_meta(span, id, Used::Unused)? {
+                    Some(meta::Meta {
+                        kind:
+                            meta::Kind::Variant {
+                                enum_hash: hash,
+                                index,
+                                ..
+                            },
+                        ..
+                    }) if hash == enum_hash => Some(index),
+                    _ => None,
+                }
+            }
+        };
+
+        if let Some(index) = index {
+            variants.try_push((index, name))?;
+        }
+    }
+
+    variants.sort_by_key(|&(index, _)| index);
+    Ok(variants)
+}
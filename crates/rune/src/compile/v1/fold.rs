@@ -0,0 +1,331 @@
+//! Constant folding of expressions which only operate on literals.
+//!
+//! Folding is conservative. Anything which isn't trivially known to produce
+//! the same result as the virtual machine would, such as operations on mixed
+//! types, is left to be evaluated at runtime.
+
+use core::cmp::Ordering;
+
+use crate::alloc::fmt::TryWrite;
+use crate::alloc::prelude::*;
+use crate::alloc::String;
+use crate::ast::{self, Spanned};
+use crate::compile;
+use crate::hir;
+use crate::runtime::{
+    ArithmeticOps, BitwiseOps, ConstValue, ConstValueKind, Inline, InstArithmeticOp, InstBitwiseOp,
+    InstShiftOp, ShiftOps, VmError, VmErrorKind,
+};
+
+use super::Ctxt;
+
+/// Try to fold the given expression into a constant value.
+///
+/// Returns `None` if the expression can't be folded, in which case it should
+/// be assembled as usual.
+pub(super) fn expr(
+    cx: &mut Ctxt<'_, '_, '_>,
+    hir: &hir::Expr<'_>,
+) -> compile::Result<Option<ConstValue>> {
+    let value = match hir.kind {
+        hir::ExprKind::Lit(lit) => match lit {
+            hir::Lit::Bool(v) => ConstValue::from(Inline::Bool(v)),
+            hir::Lit::Unsigned(v) => ConstValue::from(Inline::Unsigned(v)),
+            hir::Lit::Signed(v) => ConstValue::from(Inline::Signed(v)),
+            hir::Lit::Float(v) => ConstValue::from(Inline::Float(v)),
+            hir::Lit::Char(v) => ConstValue::from(Inline::Char(v)),
+            hir::Lit::Str(v) => ConstValue::from(String::try_from(v)?),
+            hir::Lit::ByteStr(..) => return Ok(None),
+        },
+        hir::ExprKind::Const(hash) => {
+            let Some(value) = cx.q.get_const_value(hash) else {
+                return Ok(None);
+            };
+
+            match value.as_kind() {
+                ConstValueKind::Inline(..) | ConstValueKind::String(..) => value.try_clone()?,
+                _ => return Ok(None),
+            }
+        }
+        hir::ExprKind::Group(hir) => return expr(cx, hir),
+        hir::ExprKind::Unary(unary) => return expr_unary(cx, unary, hir),
+        hir::ExprKind::Binary(binary) => return expr_binary(cx, binary, hir),
+        hir::ExprKind::Template(template) => return builtin_template(cx, template),
+        _ => return Ok(None),
+    };
+
+    Ok(Some(value))
+}
+
+/// Fold a unary expression.
+pub(super) fn expr_unary(
+    cx: &mut Ctxt<'_, '_, '_>,
+    hir: &hir::ExprUnary<'_>,
+    span: &dyn Spanned,
+) -> compile::Result<Option<ConstValue>> {
+    let Some(value) = expr(cx, &hir.expr)? else {
+        return Ok(None);
+    };
+
+    let &ConstValueKind::Inline(value) = value.as_kind() else {
+        return Ok(None);
+    };
+
+    let value = match (hir.op, value) {
+        (ast::UnOp::Not(..), Inline::Bool(v)) => Inline::Bool(!v),
+        (ast::UnOp::Not(..), Inline::Unsigned(v)) => Inline::Unsigned(!v),
+        (ast::UnOp::Not(..), Inline::Signed(v)) => Inline::Signed(!v),
+        (ast::UnOp::Neg(..), Inline::Float(v)) => Inline::Float(-v),
+        (ast::UnOp::Neg(..), Inline::Signed(v)) => {
+            let Some(v) = v.checked_neg() else {
+                return Err(overflow(span, VmErrorKind::Overflow));
+            };
+
+            Inline::Signed(v)
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(Some(ConstValue::from(value)))
+}
+
+/// Fold a binary expression.
+pub(super) fn expr_binary(
+    cx: &mut Ctxt<'_, '_, '_>,
+    hir: &hir::ExprBinary<'_>,
+    span: &dyn Spanned,
+) -> compile::Result<Option<ConstValue>> {
+    if hir.op.is_assign() {
+        return Ok(None);
+    }
+
+    let Some(lhs) = expr(cx, &hir.lhs)? else {
+        return Ok(None);
+    };
+
+    if let ast::BinOp::And(..) | ast::BinOp::Or(..) = hir.op {
+        return expr_logical(cx, hir, &lhs);
+    }
+
+    let Some(rhs) = expr(cx, &hir.rhs)? else {
+        return Ok(None);
+    };
+
+    let value = match (lhs.as_kind(), rhs.as_kind()) {
+        (&ConstValueKind::Inline(lhs), &ConstValueKind::Inline(rhs)) => {
+            match inline_binary(hir.op, lhs, rhs) {
+                Ok(Some(value)) => ConstValue::from(value),
+                Ok(None) => return Ok(None),
+                Err(error) => return Err(overflow(span, error)),
+            }
+        }
+        (ConstValueKind::String(lhs), ConstValueKind::String(rhs)) => match hir.op {
+            ast::BinOp::Add(..) => {
+                let mut string = String::try_with_capacity(lhs.len() + rhs.len())?;
+                string.try_push_str(lhs)?;
+                string.try_push_str(rhs)?;
+                ConstValue::from(string)
+            }
+            ast::BinOp::Eq(..) => ConstValue::from(Inline::Bool(lhs == rhs)),
+            ast::BinOp::Neq(..) => ConstValue::from(Inline::Bool(lhs != rhs)),
+            _ => return Ok(None),
+        },
+        _ => return Ok(None),
+    };
+
+    Ok(Some(value))
+}
+
+/// Fold a short-circuiting logical expression.
+///
+/// The right-hand side is only folded if the left-hand side doesn't already
+/// determine the result, since it is otherwise never evaluated.
+fn expr_logical(
+    cx: &mut Ctxt<'_, '_, '_>,
+    hir: &hir::ExprBinary<'_>,
+    lhs: &ConstValue,
+) -> compile::Result<Option<ConstValue>> {
+    let &ConstValueKind::Inline(Inline::Bool(lhs)) = lhs.as_kind() else {
+        return Ok(None);
+    };
+
+    let short_circuits = match hir.op {
+        ast::BinOp::And(..) => !lhs,
+        _ => lhs,
+    };
+
+    if short_circuits {
+        return Ok(Some(ConstValue::from(Inline::Bool(lhs))));
+    }
+
+    let Some(rhs) = expr(cx, &hir.rhs)? else {
+        return Ok(None);
+    };
+
+    let &ConstValueKind::Inline(Inline::Bool(rhs)) = rhs.as_kind() else {
+        return Ok(None);
+    };
+
+    Ok(Some(ConstValue::from(Inline::Bool(rhs))))
+}
+
+/// Fold a template where every expansion is constant.
+pub(super) fn builtin_template(
+    cx: &mut Ctxt<'_, '_, '_>,
+    hir: &hir::BuiltInTemplate<'_>,
+) -> compile::Result<Option<ConstValue>> {
+    let mut string = String::new();
+
+    for hir in hir.exprs {
+        let Some(value) = expr(cx, hir)? else {
+            return Ok(None);
+        };
+
+        match value.as_kind() {
+            ConstValueKind::String(s) => string.try_push_str(s)?,
+            ConstValueKind::Inline(Inline::Signed(v)) => write!(string, "{v}")?,
+            ConstValueKind::Inline(Inline::Unsigned(v)) => write!(string, "{v}")?,
+            ConstValueKind::Inline(Inline::Bool(v)) => write!(string, "{v}")?,
+            ConstValueKind::Inline(Inline::Char(v)) => string.try_push(*v)?,
+            _ => return Ok(None),
+        }
+    }
+
+    Ok(Some(ConstValue::from(string)))
+}
+
+/// Perform a binary operation over two inline values, with the same
+/// semantics as the virtual machine.
+fn inline_binary(op: ast::BinOp, lhs: Inline, rhs: Inline) -> Result<Option<Inline>, VmErrorKind> {
+    let arithmetic = match op {
+        ast::BinOp::Add(..) => Some(InstArithmeticOp::Add),
+        ast::BinOp::Sub(..) => Some(InstArithmeticOp::Sub),
+        ast::BinOp::Mul(..) => Some(InstArithmeticOp::Mul),
+        ast::BinOp::Div(..) => Some(InstArithmeticOp::Div),
+        ast::BinOp::Rem(..) => Some(InstArithmeticOp::Rem),
+        _ => None,
+    };
+
+    if let Some(op) = arithmetic {
+        let ops = ArithmeticOps::from_op(op);
+
+        let value = match (lhs, rhs) {
+            (Inline::Unsigned(a), Inline::Unsigned(b)) => {
+                Inline::Unsigned((ops.u64)(a, b).ok_or_else(ops.error)?)
+            }
+            (Inline::Signed(a), Inline::Signed(b)) => {
+                Inline::Signed((ops.i64)(a, b).ok_or_else(ops.error)?)
+            }
+            (Inline::Float(a), Inline::Float(b)) => Inline::Float((ops.f64)(a, b)),
+            _ => return Ok(None),
+        };
+
+        return Ok(Some(value));
+    }
+
+    let bitwise = match op {
+        ast::BinOp::BitAnd(..) => Some(InstBitwiseOp::BitAnd),
+        ast::BinOp::BitXor(..) => Some(InstBitwiseOp::BitXor),
+        ast::BinOp::BitOr(..) => Some(InstBitwiseOp::BitOr),
+        _ => None,
+    };
+
+    if let Some(op) = bitwise {
+        let ops = BitwiseOps::from_op(op);
+
+        let value = match (lhs, rhs) {
+            (Inline::Unsigned(a), Inline::Unsigned(b)) => Inline::Unsigned((ops.u64)(a, b)),
+            (Inline::Signed(a), Inline::Signed(b)) => Inline::Signed((ops.i64)(a, b)),
+            (Inline::Bool(a), Inline::Bool(b)) => Inline::Bool((ops.bool)(a, b)),
+            _ => return Ok(None),
+        };
+
+        return Ok(Some(value));
+    }
+
+    let shift = match op {
+        ast::BinOp::Shl(..) => Some(InstShiftOp::Shl),
+        ast::BinOp::Shr(..) => Some(InstShiftOp::Shr),
+        _ => None,
+    };
+
+    if let Some(op) = shift {
+        let ops = ShiftOps::from_op(op);
+
+        let value = match (lhs, rhs) {
+            (Inline::Unsigned(a), Inline::Unsigned(b)) => {
+                let Ok(b) = u32::try_from(b) else {
+                    return Ok(None);
+                };
+
+                Inline::Unsigned((ops.u64)(a, b).ok_or_else(ops.error)?)
+            }
+            (Inline::Signed(a), Inline::Signed(b)) => {
+                let Ok(b) = u32::try_from(b) else {
+                    return Ok(None);
+                };
+
+                Inline::Signed((ops.i64)(a, b).ok_or_else(ops.error)?)
+            }
+            _ => return Ok(None),
+        };
+
+        return Ok(Some(value));
+    }
+
+    let value = match (op, lhs, rhs) {
+        (ast::BinOp::Eq(..), a, b) if same_kind(a, b) => {
+            let Ok(value) = a.partial_eq(&b) else {
+                return Ok(None);
+            };
+
+            value
+        }
+        (ast::BinOp::Neq(..), a, b) if same_kind(a, b) => {
+            let Ok(value) = a.partial_eq(&b) else {
+                return Ok(None);
+            };
+
+            !value
+        }
+        (ast::BinOp::Lt(..), a, b) if same_kind(a, b) => {
+            matches!(a.partial_cmp(&b), Ok(Some(Ordering::Less)))
+        }
+        (ast::BinOp::Lte(..), a, b) if same_kind(a, b) => {
+            matches!(
+                a.partial_cmp(&b),
+                Ok(Some(Ordering::Less | Ordering::Equal))
+            )
+        }
+        (ast::BinOp::Gt(..), a, b) if same_kind(a, b) => {
+            matches!(a.partial_cmp(&b), Ok(Some(Ordering::Greater)))
+        }
+        (ast::BinOp::Gte(..), a, b) if same_kind(a, b) => {
+            matches!(
+                a.partial_cmp(&b),
+                Ok(Some(Ordering::Greater | Ordering::Equal))
+            )
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(Some(Inline::Bool(value)))
+}
+
+/// Test if two inline values are of the same kind, which is the only case we
+/// fold comparisons for.
+fn same_kind(lhs: Inline, rhs: Inline) -> bool {
+    matches!(
+        (lhs, rhs),
+        (Inline::Bool(..), Inline::Bool(..))
+            | (Inline::Char(..), Inline::Char(..))
+            | (Inline::Unsigned(..), Inline::Unsigned(..))
+            | (Inline::Signed(..), Inline::Signed(..))
+            | (Inline::Float(..), Inline::Float(..))
+    )
+}
+
+/// Construct the error the virtual machine would raise at runtime.
+fn overflow(span: &dyn Spanned, kind: VmErrorKind) -> compile::Error {
+    compile::Error::new(span, VmError::from(kind))
+}
@@ -22,3 +22,9 @@ use self::linear::Linear;
 
 mod display_named;
 use self::display_named::DisplayNamed;
+
+mod fold;
+
+mod exhaustive;
+
+mod try_kind;
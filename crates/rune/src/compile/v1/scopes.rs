@@ -4,7 +4,7 @@ use core::fmt;
 use crate::alloc::prelude::*;
 use crate::alloc::{self, HashMap};
 use crate::ast::Spanned;
-use crate::compile::{self, Assembly, ErrorKind, WithSpan};
+use crate::compile::{self, Assembly, AssemblyVariable, ErrorKind, WithSpan};
 use crate::hir;
 use crate::query::Query;
 use crate::runtime::{Inst, InstAddress, Output};
@@ -296,6 +296,7 @@ impl<'hir> Scopes<'hir> {
         span: &'hir dyn Spanned,
         name: hir::Variable,
         addr: &Address<'_, 'hir>,
+        asm: &mut Assembly,
     ) -> compile::Result<()> {
         let mut scopes = self.scopes.borrow_mut();
 
@@ -308,9 +309,13 @@ impl<'hir> Scopes<'hir> {
             name,
             addr: addr.addr(),
             moved_at: Cell::new(None),
+            start: asm.instructions.len(),
         };
 
-        scope.names.try_insert(name, var).with_span(span)?;
+        if let Some(old) = scope.names.try_insert(name, var).with_span(span)? {
+            old.close(asm).with_span(span)?;
+        }
+
         tracing::trace!(?scope, ?name);
         Ok(())
     }
@@ -469,7 +474,12 @@ impl<'hir> Scopes<'hir> {
     }
 
     #[tracing::instrument(skip(self, span, handle), fields(id = ?handle.id))]
-    pub(super) fn pop(&self, span: &dyn Spanned, handle: ScopeHandle) -> compile::Result<()> {
+    pub(super) fn pop(
+        &self,
+        span: &dyn Spanned,
+        handle: ScopeHandle,
+        asm: &mut Assembly,
+    ) -> compile::Result<()> {
         let ScopeHandle { id } = handle;
 
         let Some(mut scope) = self.scopes.borrow_mut().try_remove(id.index) else {
@@ -489,6 +499,10 @@ impl<'hir> Scopes<'hir> {
             ));
         }
 
+        for (_, var) in scope.names.drain() {
+            var.close(asm).with_span(span)?;
+        }
+
         tracing::trace!(?scope, "freeing locals");
 
         let mut slots = self.slots.borrow_mut();
@@ -516,8 +530,8 @@ impl<'hir> Scopes<'hir> {
 
     /// Pop the last of the scope.
     #[tracing::instrument(skip(self, span))]
-    pub(super) fn pop_last(&self, span: &dyn Spanned) -> compile::Result<()> {
-        self.pop(span, ScopeHandle { id: ROOT })?;
+    pub(super) fn pop_last(&self, span: &dyn Spanned, asm: &mut Assembly) -> compile::Result<()> {
+        self.pop(span, ScopeHandle { id: ROOT }, asm)?;
         Ok(())
     }
 
@@ -626,8 +640,8 @@ impl Var<'_> {
         )
     }
 
-    /// Move the declared variable.
-    pub(super) fn move_(
+    /// Clone the declared variable, or move it if it can't be cloned.
+    pub(super) fn clone_(
         &self,
         asm: &mut Assembly,
         span: &dyn Spanned,
@@ -635,7 +649,7 @@ impl Var<'_> {
         out: Output,
     ) -> compile::Result<()> {
         asm.push_with_comment(
-            Inst::Move {
+            Inst::Clone {
                 addr: self.addr,
                 out,
             },
@@ -674,6 +688,20 @@ struct VarInner<'hir> {
     addr: InstAddress,
     /// Variable has been taken at the given position.
     moved_at: Cell<Option<&'hir dyn Spanned>>,
+    /// The index of the instruction at which the variable came into scope.
+    start: usize,
+}
+
+impl VarInner<'_> {
+    /// Record the range of instructions in which the variable was in scope.
+    fn close(self, asm: &mut Assembly) -> alloc::Result<()> {
+        asm.variables.try_push(AssemblyVariable {
+            name: self.name,
+            addr: self.addr,
+            start: self.start,
+            end: asm.instructions.len(),
+        })
+    }
 }
 
 impl fmt::Debug for VarInner<'_> {
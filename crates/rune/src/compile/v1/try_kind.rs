@@ -0,0 +1,112 @@
+//! Inference of the kind of value a function returns, which is used to report
+//! try operations which would return a different kind of value early.
+//!
+//! Since values are dynamically typed, the kind is inferred from the variant
+//! constructors used in the values that the function returns, like `Ok(..)`
+//! or `None`. If no such values are returned, or if they disagree, no kind is
+//! inferred and no checking is performed.
+
+use crate as rune;
+use crate::hash;
+use crate::hir;
+use crate::runtime::TryKind;
+
+/// Infer the kind of value returned by a function with the given body.
+pub(super) fn block(hir: &hir::Block<'_>) -> Option<TryKind> {
+    let mut infer = Infer::default();
+    infer.block(hir, true);
+    infer.finish()
+}
+
+/// Infer the kind of value returned by a closure with the given body.
+pub(super) fn expr(hir: &hir::Expr<'_>) -> Option<TryKind> {
+    let mut infer = Infer::default();
+    infer.expr(hir, true);
+    infer.finish()
+}
+
+#[derive(Default)]
+struct Infer {
+    kind: Option<TryKind>,
+    conflict: bool,
+}
+
+impl Infer {
+    fn finish(self) -> Option<TryKind> {
+        if self.conflict {
+            return None;
+        }
+
+        self.kind
+    }
+
+    fn block(&mut self, hir: &hir::Block<'_>, tail: bool) {
+        for stmt in hir.statements {
+            match stmt {
+                hir::Stmt::Local(hir) => self.expr(&hir.expr, false),
+                hir::Stmt::Expr(hir) => self.expr(hir, false),
+            }
+        }
+
+        if let Some(hir) = hir.value {
+            self.expr(hir, tail);
+        }
+    }
+
+    fn expr(&mut self, hir: &hir::Expr<'_>, tail: bool) {
+        match hir.kind {
+            hir::ExprKind::Return(Some(hir)) => {
+                self.expr(hir, true);
+            }
+            hir::ExprKind::Group(hir) => {
+                self.expr(hir, tail);
+            }
+            hir::ExprKind::Block(hir) => {
+                self.block(hir, tail);
+            }
+            hir::ExprKind::If(hir) => {
+                for branch in hir.branches {
+                    self.block(&branch.block, tail);
+                }
+
+                if let Some(hir) = hir.fallback {
+                    self.block(hir, tail);
+                }
+            }
+            hir::ExprKind::Match(hir) => {
+                for branch in hir.branches {
+                    self.expr(&branch.body, tail);
+                }
+            }
+            hir::ExprKind::Loop(hir) => {
+                self.block(&hir.body, false);
+            }
+            hir::ExprKind::For(hir) => {
+                self.block(&hir.body, false);
+            }
+            hir::ExprKind::Call(call) if tail => {
+                let hir::Call::Meta { hash } = call.call else {
+                    return;
+                };
+
+                let kind = match hash {
+                    hash!(::std::option::Option::Some) | hash!(::std::option::Option::None) => {
+                        TryKind::Option
+                    }
+                    hash!(::std::result::Result::Ok) | hash!(::std::result::Result::Err) => {
+                        TryKind::Result
+                    }
+                    hash!(::std::ops::ControlFlow::Continue)
+                    | hash!(::std::ops::ControlFlow::Break) => TryKind::ControlFlow,
+                    _ => return,
+                };
+
+                match self.kind {
+                    Some(existing) if existing != kind => self.conflict = true,
+                    _ => self.kind = Some(kind),
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -13,21 +13,20 @@ use crate::alloc::fmt::TryWrite;
 use crate::alloc::prelude::*;
 use crate::alloc::{self, String};
 use crate::ast::{Span, Spanned};
-use crate::compile::{ErrorKind, LinkerError, Location};
+use crate::compile::{self, meta, ErrorKind, LinkerError, Location};
 use crate::diagnostics::{
     Diagnostic, FatalDiagnostic, FatalDiagnosticKind, RuntimeWarningDiagnostic,
     RuntimeWarningDiagnosticKind, WarningDiagnostic, WarningDiagnosticKind,
 };
-use crate::hash::Hash;
+use crate::hash::{Hash, ToTypeHash};
+use crate::item::ComponentRef;
 use crate::runtime::DebugInfo;
-use crate::runtime::{DebugInst, Protocol, Unit, VmError, VmErrorAt, VmErrorKind};
+use crate::runtime::{
+    DebugInst, GuardedArgs, InstCall, Protocol, Unit, Value, Vm, VmError, VmErrorAt, VmErrorKind,
+    VmExecution, VmResult,
+};
 use crate::Context;
-use crate::{Diagnostics, Source, SourceId, Sources};
-
-struct StackFrame {
-    source_id: SourceId,
-    span: Span,
-}
+use crate::{Diagnostics, Item, Source, SourceId, Sources};
 
 /// Errors that can be raised when formatting diagnostics.
 #[derive(Debug)]
@@ -101,10 +100,12 @@ impl Diagnostics {
                     fatal_diagnostics_emit(e, out, sources, &config)?;
                 }
                 Diagnostic::Warning(w) => {
-                    warning_diagnostics_emit(w, out, sources, &config)?;
+                    let diagnostic = warning_diagnostic(w, sources)?;
+                    term::emit(out, &config, sources, &diagnostic)?;
                 }
                 Diagnostic::RuntimeWarning(w) => {
-                    runtime_warning_diagnostics_emit(w, out, sources, &config, None, None)?;
+                    let diagnostic = runtime_warning_diagnostic(w, None, None)?;
+                    term::emit(out, &config, sources, &diagnostic)?;
                 }
             }
         }
@@ -140,17 +141,12 @@ impl Diagnostics {
                     fatal_diagnostics_emit(e, out, sources, &config)?;
                 }
                 Diagnostic::Warning(w) => {
-                    warning_diagnostics_emit(w, out, sources, &config)?;
+                    let diagnostic = warning_diagnostic(w, sources)?;
+                    term::emit(out, &config, sources, &diagnostic)?;
                 }
                 Diagnostic::RuntimeWarning(w) => {
-                    runtime_warning_diagnostics_emit(
-                        w,
-                        out,
-                        sources,
-                        &config,
-                        debug_info,
-                        Some(context),
-                    )?;
+                    let diagnostic = runtime_warning_diagnostic(w, debug_info, Some(context))?;
+                    term::emit(out, &config, sources, &diagnostic)?;
                 }
             }
         }
@@ -168,34 +164,155 @@ impl VmError {
     where
         O: WriteColor,
     {
-        let mut red = termcolor::ColorSpec::new();
-        red.set_fg(Some(termcolor::Color::Red));
+        let config = term::Config::default();
+
+        for diagnostic in self.diagnostics()? {
+            term::emit(out, &config, sources, &diagnostic)?;
+        }
 
-        let mut backtrace = vec![];
+        Ok(())
+    }
+
+    /// Construct the diagnostics describing this error, which is the error
+    /// itself followed by a note for each frame it was called from.
+    pub(super) fn diagnostics(
+        &self,
+    ) -> Result<::rust_alloc::vec::Vec<d::Diagnostic<SourceId>>, EmitError> {
+        let mut diagnostics = ::rust_alloc::vec::Vec::new();
+        diagnostics.push(self.error_diagnostic(None)?);
+
+        // The first frame is where the error was raised, which is already
+        // covered by the diagnostic above.
+        for frame in self.backtrace().skip(1) {
+            let (Some(source_id), Some(span)) = (frame.source_id, frame.span) else {
+                continue;
+            };
+
+            let message = match frame.function {
+                Some(function) => format!("Called from `{function}`"),
+                None => "Called from here".into(),
+            };
+
+            let diagnostic = d::Diagnostic::note()
+                .with_message(message)
+                .with_labels(vec![d::Label::secondary(source_id, span.range())]);
+
+            diagnostics.push(diagnostic);
+        }
+
+        Ok(diagnostics)
+    }
+
+    /// Emit this error followed by at most `limit` frames of its backtrace,
+    /// most recent first.
+    ///
+    /// Unlike [`VmError::emit`], the frame in which the error was raised is
+    /// included, and if the error was raised by a native function that
+    /// function is listed first by resolving its name through `context`.
+    pub(crate) fn emit_backtrace(
+        &self,
+        out: &mut dyn WriteColor,
+        sources: &Sources,
+        context: &Context,
+        limit: usize,
+    ) -> Result<(), EmitError> {
         let config = term::Config::default();
+        term::emit(
+            out,
+            &config,
+            sources,
+            &self.error_diagnostic(Some(context))?,
+        )?;
+
+        if limit == 0 {
+            return Ok(());
+        }
 
-        for l in &self.inner.stacktrace {
-            let debug_info = match l.unit.debug_info() {
-                Some(debug_info) => debug_info,
-                None => continue,
+        let native = self.native_function(context);
+        let total = usize::from(native.is_some()) + self.backtrace().count();
+
+        let mut n = 0;
+
+        if let Some(function) = native {
+            let diagnostic =
+                d::Diagnostic::note().with_message(format!("#{n} [native] {function}"));
+            term::emit(out, &config, sources, &diagnostic)?;
+            n += 1;
+        }
+
+        for frame in self.backtrace().take(limit - n) {
+            let message = match frame.function {
+                Some(function) => format!("#{n} `{function}`"),
+                None => format!("#{n}"),
             };
 
-            for ip in [l.ip]
-                .into_iter()
-                .chain(l.frames.iter().rev().map(|v| v.ip))
-            {
-                let debug_inst = match debug_info.instruction_at(ip) {
-                    Some(debug_inst) => debug_inst,
-                    None => continue,
-                };
+            let diagnostic = match (frame.source_id, frame.span) {
+                (Some(source_id), Some(span)) => d::Diagnostic::note()
+                    .with_message(message)
+                    .with_labels(vec![d::Label::secondary(source_id, span.range())]),
+                _ => d::Diagnostic::note()
+                    .with_message(format!("{message} at instruction {}", frame.ip)),
+            };
 
-                let source_id = debug_inst.source_id;
-                let span = debug_inst.span;
+            term::emit(out, &config, sources, &diagnostic)?;
+            n += 1;
+        }
+
+        if total > n {
+            let diagnostic =
+                d::Diagnostic::note().with_message(format!("{} more frames omitted", total - n));
+            term::emit(out, &config, sources, &diagnostic)?;
+        }
 
-                backtrace.push(StackFrame { source_id, span });
+        Ok(())
+    }
+
+    /// Resolve the native function which raised this error, if any.
+    ///
+    /// The error is raised while the calling frame is executing the call, so
+    /// this is the function called by that instruction unless it's defined in
+    /// the unit. Errors raised by the virtual machine while dispatching the
+    /// call, like a missing function, are not attributed to any function.
+    fn native_function<'a>(&'a self, context: &'a Context) -> Option<NativeFunction<'a>> {
+        let l = self.inner.stacktrace.first()?;
+
+        if is_dispatch_error(self.inner.error.kind()) {
+            return None;
+        }
+
+        let Ok(Some((inst, _))) = l.unit.instruction_at(l.ip) else {
+            return None;
+        };
+
+        match inst.call()? {
+            InstCall::Function(hash) => {
+                if l.unit.function(&hash).is_some() {
+                    return None;
+                }
+
+                let item = context
+                    .lookup_meta_by_hash(hash)
+                    .find_map(|meta| meta.item.as_deref())?;
+
+                Some(NativeFunction::Item(item))
             }
+            InstCall::Associated(hash) => {
+                let name = l.unit.debug_info()?.ident_for_hash(hash)?;
+                Some(NativeFunction::Associated(name))
+            }
+            InstCall::Protocol(protocol) => Some(NativeFunction::Protocol(protocol)),
+            InstCall::Offset | InstCall::Value => None,
         }
+    }
 
+    /// Construct the diagnostic describing the error itself.
+    ///
+    /// If `context` is provided, it is used to suggest instance functions
+    /// with names similar to one which is missing.
+    fn error_diagnostic(
+        &self,
+        context: Option<&Context>,
+    ) -> Result<d::Diagnostic<SourceId>, EmitError> {
         let mut labels = ::rust_alloc::vec::Vec::new();
         let mut notes = ::rust_alloc::vec::Vec::new();
 
@@ -271,6 +388,23 @@ impl VmError {
                                 "This corresponds to the `{instance}::{ident}` instance function"
                             ),
                         ));
+
+                        if let Some(context) = context {
+                            let type_hash = instance.type_hash();
+
+                            if let Some(similar) =
+                                self.similar_instance_function(at, context, type_hash, ident)?
+                            {
+                                let message = format!(
+                                    "help: an instance function with a similar name exists: `{similar}`"
+                                );
+
+                                labels.push(
+                                    d::Label::secondary(source_id, span.range())
+                                        .with_message(message),
+                                );
+                            }
+                        }
                     }
 
                     if let Some(protocol) = Protocol::from_hash(instance_hash) {
@@ -281,41 +415,218 @@ impl VmError {
                     }
                 }
             };
+
+            if let VmErrorKind::MissingField {
+                did_you_mean: Some(did_you_mean),
+                ..
+            } = at.kind()
+            {
+                if let Some(&DebugInst {
+                    source_id, span, ..
+                }) = get(at)
+                {
+                    labels.push(d::Label::secondary(source_id, span.range()).with_message(
+                        format!("help: a field with a similar name exists: `{did_you_mean}`"),
+                    ));
+                }
+            }
+
+            #[cfg(feature = "debug-access")]
+            if let VmErrorKind::AccessError { error } = at.kind() {
+                if let Some((source_id, span)) = error.escaped_at() {
+                    labels.push(
+                        d::Label::secondary(source_id, span.range())
+                            .with_message("Reference taken here"),
+                    );
+                }
+            }
+
+            if let VmErrorKind::TryKindMismatch { expected, actual } = at.kind() {
+                notes.push(format!(
+                    "Hint: Convert the `{actual}` into a `{expected}` before using `?` on it"
+                ));
+            }
         }
 
-        let diagnostic = d::Diagnostic::error()
+        Ok(d::Diagnostic::error()
             .with_message(self.inner.error.try_to_string()?)
             .with_labels(labels)
-            .with_notes(notes);
+            .with_notes(notes))
+    }
 
-        term::emit(out, &config, sources, &diagnostic)?;
+    /// Find an instance function of the type identified by `type_hash` with a
+    /// name similar to `name`, either in the context or in the unit the error
+    /// was raised in.
+    fn similar_instance_function<'a>(
+        &'a self,
+        at: &VmErrorAt,
+        context: &'a Context,
+        type_hash: Hash,
+        name: &str,
+    ) -> alloc::Result<Option<&'a str>> {
+        let context_names = context.iter_meta().filter_map(|meta| match &meta.kind {
+            meta::Kind::Function {
+                associated: Some(meta::AssociatedKind::Instance(name)),
+                ..
+            } if meta.hash == Hash::associated_function(type_hash, &**name) => Some(&**name),
+            _ => None,
+        });
+
+        let unit_names = self
+            .inner
+            .stacktrace
+            .get(at.index())
+            .and_then(|l| l.unit.debug_info())
+            .into_iter()
+            .flat_map(|debug_info| &debug_info.functions)
+            .filter_map(|(hash, signature)| {
+                let Some(ComponentRef::Str(name)) = signature.path.last() else {
+                    return None;
+                };
 
-        if !backtrace.is_empty() {
-            writeln!(out, "Backtrace:")?;
+                (*hash == Hash::associated_function(type_hash, name)).then_some(name)
+            });
 
-            for frame in &backtrace {
-                let Some(source) = sources.get(frame.source_id) else {
-                    continue;
-                };
+        compile::similar(name, context_names.chain(unit_names))
+    }
+}
 
-                let (line, line_count, [prefix, mid, suffix]) = match source.line(frame.span) {
-                    Some((line, line_count, text)) => {
-                        (line.saturating_add(1), line_count.saturating_add(1), text)
-                    }
-                    None => continue,
-                };
+/// A native function which raised an error, as resolved by
+/// [`VmError::native_function`].
+enum NativeFunction<'a> {
+    /// A function with the given item.
+    Item(&'a Item),
+    /// An instance function with the given name.
+    Associated(&'a str),
+    /// A protocol function.
+    Protocol(&'static Protocol),
+}
+
+impl fmt::Display for NativeFunction<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Item(item) => write!(f, "{item}"),
+            Self::Associated(name) => write!(f, "instance function `{name}`"),
+            Self::Protocol(protocol) => write!(f, "protocol `{}`", protocol.name),
+        }
+    }
+}
 
-                writeln!(out, "{}:{line}:{line_count}:", source.name())?;
-                write!(out, "{prefix}")?;
-                out.set_color(&red)?;
-                write!(out, "{mid}")?;
-                out.reset()?;
-                writeln!(out, "{}", suffix.trim_end_matches(['\n', '\r']))?;
+/// Test if the error was raised by the virtual machine while dispatching a
+/// call or an operation, rather than by the function being called.
+fn is_dispatch_error(kind: &VmErrorKind) -> bool {
+    matches!(
+        kind,
+        VmErrorKind::MissingFunction { .. }
+            | VmErrorKind::MissingProtocolFunction { .. }
+            | VmErrorKind::MissingInstanceFunction { .. }
+            | VmErrorKind::BadArgumentCount { .. }
+            | VmErrorKind::UnsupportedBinaryOperation { .. }
+            | VmErrorKind::UnsupportedUnaryOperation { .. }
+            | VmErrorKind::UnsupportedIndexGet { .. }
+            | VmErrorKind::UnsupportedIndexSet { .. }
+            | VmErrorKind::UnsupportedTryOperand { .. }
+            | VmErrorKind::UnsupportedCallFn { .. }
+            | VmErrorKind::Overflow
+            | VmErrorKind::Underflow
+            | VmErrorKind::DivideByZero
+    )
+}
+
+impl Vm {
+    /// Call the given function immediately like [`Vm::call`], and if it
+    /// errors emit a diagnostic for the error to `out` before returning it.
+    ///
+    /// The diagnostic references `sources` through the debug info of the unit.
+    /// If debug info is absent or the error can't be mapped to `sources`, only
+    /// the textual error is written. Failing to write to `out` is ignored
+    /// since the error being reported takes precedence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::termcolor::Buffer;
+    /// use rune::{Context, Vm};
+    /// use std::sync::Arc;
+    ///
+    /// let context = Context::with_default_modules()?;
+    ///
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         pub fn main() {
+    ///             panic!("oh no")
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    /// let mut vm = Vm::new(Arc::new(context.runtime()?), Arc::new(unit));
+    ///
+    /// let mut out = Buffer::no_color();
+    /// assert!(vm.call_emit(&mut out, &sources, ["main"], ()).is_err());
+    ///
+    /// let out = String::from_utf8(out.into_inner())?;
+    /// assert!(out.contains("Panicked: oh no"));
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    pub fn call_emit<O>(
+        &mut self,
+        out: &mut O,
+        sources: &Sources,
+        name: impl ToTypeHash,
+        args: impl GuardedArgs,
+    ) -> Result<Value, VmError>
+    where
+        O: WriteColor,
+    {
+        match self.call(name, args) {
+            Ok(value) => Ok(value),
+            Err(error) => {
+                emit_vm_error(&error, out, sources);
+                Err(error)
             }
         }
+    }
+}
 
-        Ok(())
+impl<T> VmExecution<T>
+where
+    T: AsRef<Vm> + AsMut<Vm>,
+{
+    /// Complete the current execution like [`VmExecution::async_complete`],
+    /// and if it errors emit a diagnostic for the error to `out` before
+    /// returning it.
+    ///
+    /// See [`Vm::call_emit`] for how the diagnostic is emitted.
+    pub async fn async_complete_emit<O>(
+        &mut self,
+        out: &mut O,
+        sources: &Sources,
+    ) -> VmResult<Value>
+    where
+        O: WriteColor,
+    {
+        match self.async_complete().await {
+            VmResult::Ok(value) => VmResult::Ok(value),
+            VmResult::Err(error) => {
+                emit_vm_error(&error, out, sources);
+                VmResult::Err(error)
+            }
+        }
+    }
+}
+
+/// Emit a runtime error, falling back to its textual representation if it
+/// can't be mapped to sources.
+fn emit_vm_error<O>(error: &VmError, out: &mut O, sources: &Sources)
+where
+    O: WriteColor,
+{
+    if error.backtrace().any(|frame| frame.span.is_some()) && error.emit(out, sources).is_ok() {
+        return;
     }
+
+    _ = writeln!(out, "error: {error}");
 }
 
 impl FatalDiagnostic {
@@ -342,7 +653,9 @@ impl WarningDiagnostic {
         O: WriteColor,
     {
         let config = term::Config::default();
-        warning_diagnostics_emit(self, out, sources, &config)
+        let diagnostic = warning_diagnostic(self, sources)?;
+        term::emit(out, &config, sources, &diagnostic)?;
+        Ok(())
     }
 }
 
@@ -362,7 +675,9 @@ impl RuntimeWarningDiagnostic {
         O: WriteColor,
     {
         let config = term::Config::default();
-        runtime_warning_diagnostics_emit(self, out, sources, &config, debug_info, context)
+        let diagnostic = runtime_warning_diagnostic(self, debug_info, context)?;
+        term::emit(out, &config, sources, &diagnostic)?;
+        Ok(())
     }
 }
 
@@ -521,16 +836,11 @@ pub fn line_for(source: &Source, span: Span) -> Option<(usize, usize, &str, Span
     ))
 }
 
-/// Helper to emit diagnostics for a warning.
-fn warning_diagnostics_emit<O>(
+/// Helper to construct the diagnostic for a warning.
+pub(crate) fn warning_diagnostic(
     this: &WarningDiagnostic,
-    out: &mut O,
     sources: &Sources,
-    config: &term::Config,
-) -> Result<(), EmitError>
-where
-    O: WriteColor,
-{
+) -> Result<d::Diagnostic<SourceId>, EmitError> {
     let mut notes = ::rust_alloc::vec::Vec::new();
     let mut labels = ::rust_alloc::vec::Vec::new();
 
@@ -557,12 +867,46 @@ where
                 notes.push(note.into_std());
             }
         }
-        WarningDiagnosticKind::Unreachable { cause, .. } => {
+        WarningDiagnosticKind::UnusedVariable { name, .. } => {
+            let mut note = String::new();
+            writeln!(
+                note,
+                "Hint: If this is intentional, prefix it with an underscore: `_{name}`"
+            )?;
+            notes.push(note.into_std());
+        }
+        WarningDiagnosticKind::UnreachableCode { cause, .. } => {
             labels.push(
                 d::Label::secondary(this.source_id(), cause.range())
                     .with_message("This code diverges"),
             );
         }
+        WarningDiagnosticKind::ClosureCapture {
+            do_move, captures, ..
+        } => {
+            for (name, span) in captures {
+                labels.push(
+                    d::Label::secondary(this.source_id(), span.range())
+                        .with_message(format!("`{name}` is captured")),
+                );
+            }
+
+            let mut note = String::new();
+
+            if *do_move {
+                writeln!(
+                    note,
+                    "Hint: Captured variables are moved into the closure when it is created, and cloned if they support it"
+                )?;
+            } else {
+                writeln!(
+                    note,
+                    "Hint: Captured variables are shared with the closure, use `move` to move them into it when it is created"
+                )?;
+            }
+
+            notes.push(note.into_std());
+        }
         _ => {}
     };
 
@@ -572,27 +916,18 @@ where
         );
     }
 
-    let diagnostic = d::Diagnostic::warning()
+    Ok(d::Diagnostic::warning()
         .with_message("Warning")
         .with_labels(labels)
-        .with_notes(notes);
-
-    term::emit(out, config, sources, &diagnostic)?;
-    Ok(())
+        .with_notes(notes))
 }
 
-/// Helper to emit diagnostics for a runtime warning.
-fn runtime_warning_diagnostics_emit<O>(
+/// Helper to construct the diagnostic for a runtime warning.
+pub(super) fn runtime_warning_diagnostic(
     this: &RuntimeWarningDiagnostic,
-    out: &mut O,
-    sources: &Sources,
-    config: &term::Config,
     debug_info: Option<&DebugInfo>,
     context: Option<&Context>,
-) -> Result<(), EmitError>
-where
-    O: WriteColor,
-{
+) -> Result<d::Diagnostic<SourceId>, EmitError> {
     let mut notes = ::rust_alloc::vec::Vec::new();
     let mut labels = ::rust_alloc::vec::Vec::new();
     let mut message = String::new();
@@ -629,13 +964,10 @@ where
         }
     };
 
-    let diagnostic = d::Diagnostic::warning()
+    Ok(d::Diagnostic::warning()
         .with_message(message)
         .with_labels(labels)
-        .with_notes(notes);
-
-    term::emit(out, config, sources, &diagnostic)?;
-    Ok(())
+        .with_notes(notes))
 }
 
 /// Custom shared helper for emitting diagnostics for a single error.
@@ -648,6 +980,21 @@ fn fatal_diagnostics_emit<O>(
 where
     O: WriteColor,
 {
+    if let FatalDiagnosticKind::Internal(message) = this.kind() {
+        writeln!(out, "internal error: {}", message)?;
+        return Ok(());
+    }
+
+    let diagnostic = fatal_diagnostic(this, sources)?;
+    term::emit(out, config, sources, &diagnostic)?;
+    Ok(())
+}
+
+/// Helper to construct the diagnostic for a single error.
+pub(crate) fn fatal_diagnostic(
+    this: &FatalDiagnostic,
+    sources: &Sources,
+) -> Result<d::Diagnostic<SourceId>, EmitError> {
     let mut labels = ::rust_alloc::vec::Vec::new();
     let mut notes = ::rust_alloc::vec::Vec::new();
 
@@ -660,33 +1007,41 @@ where
 
     match this.kind() {
         FatalDiagnosticKind::Internal(message) => {
-            writeln!(out, "internal error: {}", message)?;
-            return Ok(());
+            return Ok(d::Diagnostic::error().with_message(format!("internal error: {message}")));
         }
-        FatalDiagnosticKind::LinkError(error) => {
-            match error {
-                LinkerError::MissingFunction { hash, spans } => {
-                    let mut labels = ::rust_alloc::vec::Vec::new();
+        FatalDiagnosticKind::LinkError(error) => match error {
+            LinkerError::MissingFunction { hash, spans, known } => {
+                let mut labels = ::rust_alloc::vec::Vec::new();
 
-                    for (span, source_id) in spans {
-                        labels.push(
-                            d::Label::primary(*source_id, span.range())
-                                .with_message("called here."),
-                        );
-                    }
+                for (span, source_id) in spans {
+                    labels.push(
+                        d::Label::primary(*source_id, span.range()).with_message("called here."),
+                    );
+                }
 
-                    let diagnostic = d::Diagnostic::error()
-                        .with_message(format!(
-                            "linker error: missing function with hash `{}`",
-                            hash
-                        ))
-                        .with_labels(labels);
+                let message = match known {
+                    Some((item, _)) => format!(
+                        "linker error: missing function `{}`",
+                        item.try_to_string()?.trim_start_matches("::")
+                    ),
+                    None => format!("linker error: missing function with hash `{}`", hash),
+                };
 
-                    term::emit(out, config, sources, &diagnostic)?;
+                if let Some((item, module)) = known {
+                    notes.push(known_module_note(item, module)?.into_std());
                 }
-            }
 
-            return Ok(());
+                return Ok(d::Diagnostic::error()
+                    .with_message(message)
+                    .with_labels(labels)
+                    .with_notes(notes));
+            }
+        },
+        FatalDiagnosticKind::DeniedWarning(warning) => {
+            let mut diagnostic = warning_diagnostic(warning, sources)?;
+            diagnostic.severity = d::Severity::Error;
+            diagnostic.message = format!("Denied warning `{}`", warning.name());
+            return Ok(diagnostic);
         }
         FatalDiagnosticKind::CompileError(error) => {
             format_compile_error(
@@ -700,13 +1055,10 @@ where
         }
     };
 
-    let diagnostic = d::Diagnostic::error()
+    return Ok(d::Diagnostic::error()
         .with_message(this.kind().try_to_string()?)
         .with_labels(labels)
-        .with_notes(notes);
-
-    term::emit(out, config, sources, &diagnostic)?;
-    return Ok(());
+        .with_notes(notes));
 
     fn format_compile_error(
         this: &FatalDiagnostic,
@@ -784,6 +1136,32 @@ where
                     );
                 }
             }
+            ErrorKind::MissingItem {
+                did_you_mean: Some(did_you_mean),
+                ..
+            } => {
+                let what = if did_you_mean.is_function {
+                    "a function"
+                } else {
+                    "an item"
+                };
+
+                labels.push(
+                    d::Label::secondary(this.source_id(), span.range()).with_message(format!(
+                        "help: {what} with a similar name exists: `{did_you_mean}`"
+                    )),
+                );
+            }
+            ErrorKind::LitObjectNotField {
+                did_you_mean: Some(did_you_mean),
+                ..
+            } => {
+                labels.push(
+                    d::Label::secondary(this.source_id(), span.range()).with_message(format!(
+                        "help: a field with a similar name exists: `{did_you_mean}`"
+                    )),
+                );
+            }
             ErrorKind::DuplicateObjectKey { existing, object } => {
                 labels.push(
                     d::Label::secondary(this.source_id(), existing.range())
@@ -835,6 +1213,9 @@ where
                         .with_message("Nested in here"),
                 );
             }
+            ErrorKind::MissingKnownItem { item, module } => {
+                notes.push(known_module_note(item, module)?.into_std());
+            }
             ErrorKind::PatternMissingFields { fields, .. } => {
                 let pl = if fields.len() == 1 { "field" } else { "fields" };
 
@@ -851,21 +1232,85 @@ where
                         .into_std(),
                 );
             }
+            ErrorKind::MatchMissingVariants { .. } => {
+                notes.push(
+                    "Hint: Add a `_` branch to match the remaining variants"
+                        .try_to_string()?
+                        .into_std(),
+                );
+            }
             ErrorKind::ConflictingLabels { existing, .. } => {
                 labels.push(
                     d::Label::secondary(this.source_id(), existing.range())
                         .with_message("Existing label here"),
                 );
             }
+            ErrorKind::BreakUnsupportedValue { loop_span } => {
+                labels.push(
+                    d::Label::secondary(this.source_id(), loop_span.range())
+                        .with_message("This loop doesn't produce a value"),
+                );
+            }
+            ErrorKind::MissingLabel { label_span, .. } => {
+                labels.push(
+                    d::Label::secondary(this.source_id(), label_span.range())
+                        .with_message("No enclosing loop or block has this label"),
+                );
+            }
             ErrorKind::DuplicateSelectDefault { existing, .. } => {
                 labels.push(
                     d::Label::secondary(this.source_id(), existing.range())
                         .with_message("Existing branch here"),
                 );
             }
+            ErrorKind::AwaitOutsideAsync { function, closure } => {
+                let what = if *closure { "closure" } else { "function" };
+
+                labels.push(
+                    d::Label::secondary(this.source_id(), function.range())
+                        .with_message(format!("this {what} is not async")),
+                );
+            }
+            ErrorKind::AwaitInConst { function } | ErrorKind::YieldInConst { function } => {
+                labels.push(
+                    d::Label::secondary(this.source_id(), function.range())
+                        .with_message("this function is const"),
+                );
+            }
             _ => (),
         }
 
+        if let Some(suggestion) = kind.suggestion() {
+            let mut note = String::new();
+
+            write!(
+                note,
+                "Hint: {} by inserting `{}`",
+                suggestion.message,
+                suggestion.replacement.trim()
+            )?;
+
+            notes.push(note.into_std());
+        }
+
         Ok(())
     }
 }
+
+/// Construct a note for a function which is provided by a module that is known
+/// but not installed.
+fn known_module_note(item: &Item, module: &Item) -> Result<String, EmitError> {
+    let item = item.try_to_string()?;
+    let module = module.try_to_string()?;
+
+    let mut note = String::new();
+
+    write!(
+        note,
+        "function `{}` is provided by the `{}` module which is not installed in this context",
+        item.trim_start_matches("::"),
+        module.trim_start_matches("::")
+    )?;
+
+    Ok(note)
+}
@@ -5,6 +5,7 @@ use ::rust_alloc::boxed::Box;
 #[cfg(feature = "emit")]
 use crate::ast::{Span, Spanned};
 use crate::compile::{self, LinkerError};
+use crate::diagnostics::WarningDiagnostic;
 use crate::SourceId;
 
 /// Fatal diagnostic emitted during compilation. Fatal diagnostics indicates an
@@ -39,6 +40,7 @@ impl FatalDiagnostic {
         match &*self.kind {
             FatalDiagnosticKind::CompileError(error) => Some(error.span()),
             FatalDiagnosticKind::LinkError(..) => None,
+            FatalDiagnosticKind::DeniedWarning(warning) => Some(warning.span()),
             FatalDiagnosticKind::Internal(..) => None,
         }
     }
@@ -56,6 +58,7 @@ impl core::error::Error for FatalDiagnostic {
         match &*self.kind {
             FatalDiagnosticKind::CompileError(error) => Some(error),
             FatalDiagnosticKind::LinkError(error) => Some(error),
+            FatalDiagnosticKind::DeniedWarning(warning) => Some(warning),
             _ => None,
         }
     }
@@ -68,6 +71,12 @@ impl core::error::Error for FatalDiagnostic {
 pub enum FatalDiagnosticKind {
     CompileError(compile::Error),
     LinkError(LinkerError),
+    /// A warning which has been denied through [`Options::deny`] or
+    /// [`Options::warnings_as_errors`].
+    ///
+    /// [`Options::deny`]: crate::Options::deny
+    /// [`Options::warnings_as_errors`]: crate::Options::warnings_as_errors
+    DeniedWarning(WarningDiagnostic),
     /// An internal error.
     Internal(&'static str),
 }
@@ -78,6 +87,7 @@ impl fmt::Display for FatalDiagnosticKind {
         match self {
             FatalDiagnosticKind::CompileError(error) => error.fmt(f),
             FatalDiagnosticKind::LinkError(error) => error.fmt(f),
+            FatalDiagnosticKind::DeniedWarning(warning) => warning.fmt(f),
             FatalDiagnosticKind::Internal(message) => message.fmt(f),
         }
     }
@@ -0,0 +1,203 @@
+//! Machine-readable JSON output for diagnostics.
+
+use std::io;
+
+use ::rust_alloc::string::{String, ToString};
+use ::rust_alloc::vec::Vec;
+
+use codespan_reporting::diagnostic as d;
+use codespan_reporting::files::{self, Files};
+use serde::Serialize;
+
+use crate::diagnostics::emit::{fatal_diagnostic, runtime_warning_diagnostic, warning_diagnostic};
+use crate::diagnostics::{Diagnostic, EmitError};
+use crate::runtime::VmError;
+use crate::{Diagnostics, SourceId, Sources};
+
+impl Diagnostics {
+    /// Write all diagnostics as JSON to `out`, one object per line.
+    ///
+    /// Each diagnostic is written with the following schema:
+    ///
+    /// ```text
+    /// {
+    ///     "severity": "bug" | "error" | "warning" | "note" | "help",
+    ///     "message": string,
+    ///     "span": Span | null,
+    ///     "labels": [Span],
+    ///     "notes": [string]
+    /// }
+    /// ```
+    ///
+    /// Where `span` is the primary span of the diagnostic if there is one, and
+    /// `labels` holds every other span which the diagnostic refers to. A `Span`
+    /// has the following schema:
+    ///
+    /// ```text
+    /// {
+    ///     "name": string,
+    ///     "path": string | null,
+    ///     "start": number,
+    ///     "end": number,
+    ///     "line_start": number,
+    ///     "column_start": number,
+    ///     "line_end": number,
+    ///     "column_end": number,
+    ///     "message": string | null
+    /// }
+    /// ```
+    ///
+    /// The `start` and `end` fields are byte offsets into the source, while lines
+    /// and columns are counted from 1. Columns count characters and not bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::{Diagnostics, Sources};
+    ///
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         pub fn main() {
+    ///             missing()
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let mut diagnostics = Diagnostics::new();
+    ///
+    /// let _ = rune::prepare(&mut sources)
+    ///     .with_diagnostics(&mut diagnostics)
+    ///     .build();
+    ///
+    /// let mut out = Vec::new();
+    /// diagnostics.emit_json(&mut out, &sources)?;
+    ///
+    /// let out = String::from_utf8(out)?;
+    /// assert!(out.starts_with("{\"severity\":\"error\""));
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    pub fn emit_json<O>(&self, out: &mut O, sources: &Sources) -> Result<(), EmitError>
+    where
+        O: ?Sized + io::Write,
+    {
+        for diagnostic in self.diagnostics() {
+            let diagnostic = match diagnostic {
+                Diagnostic::Fatal(e) => fatal_diagnostic(e, sources)?,
+                Diagnostic::Warning(w) => warning_diagnostic(w, sources)?,
+                Diagnostic::RuntimeWarning(w) => runtime_warning_diagnostic(w, None, None)?,
+            };
+
+            write_json(out, sources, &diagnostic)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl VmError {
+    /// Write this error as JSON diagnostics to `out`, one object per line.
+    ///
+    /// The first diagnostic describes the error itself, and is followed by a
+    /// `note` for every frame in the backtrace which it was called from. See
+    /// [`Diagnostics::emit_json`] for the schema used.
+    pub fn emit_json<O>(&self, out: &mut O, sources: &Sources) -> Result<(), EmitError>
+    where
+        O: ?Sized + io::Write,
+    {
+        for diagnostic in self.diagnostics()? {
+            write_json(out, sources, &diagnostic)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct JsonDiagnostic<'a> {
+    severity: &'static str,
+    message: &'a str,
+    span: Option<JsonSpan<'a>>,
+    labels: Vec<JsonSpan<'a>>,
+    notes: &'a [String],
+}
+
+#[derive(Serialize)]
+struct JsonSpan<'a> {
+    name: &'a str,
+    path: Option<String>,
+    start: usize,
+    end: usize,
+    line_start: usize,
+    column_start: usize,
+    line_end: usize,
+    column_end: usize,
+    message: Option<&'a str>,
+}
+
+/// Write a single diagnostic as a line of JSON.
+pub(crate) fn write_json<O>(
+    out: &mut O,
+    sources: &Sources,
+    diagnostic: &'_ d::Diagnostic<SourceId>,
+) -> Result<(), EmitError>
+where
+    O: ?Sized + io::Write,
+{
+    let mut span = None;
+    let mut labels = Vec::new();
+
+    for label in &diagnostic.labels {
+        let json = json_span(sources, label)?;
+
+        if span.is_none() && label.style == d::LabelStyle::Primary {
+            span = Some(json);
+        } else {
+            labels.push(json);
+        }
+    }
+
+    let severity = match diagnostic.severity {
+        d::Severity::Bug => "bug",
+        d::Severity::Error => "error",
+        d::Severity::Warning => "warning",
+        d::Severity::Note => "note",
+        d::Severity::Help => "help",
+    };
+
+    let json = JsonDiagnostic {
+        severity,
+        message: &diagnostic.message,
+        span,
+        labels,
+        notes: &diagnostic.notes,
+    };
+
+    serde_json::to_writer(&mut *out, &json).map_err(io::Error::from)?;
+    writeln!(out)?;
+    Ok(())
+}
+
+/// Convert a label into a span with computed line and column information.
+fn json_span<'a>(
+    sources: &'a Sources,
+    label: &'a d::Label<SourceId>,
+) -> Result<JsonSpan<'a>, EmitError> {
+    let start = sources.location(label.file_id, label.range.start)?;
+    let end = sources.location(label.file_id, label.range.end)?;
+
+    Ok(JsonSpan {
+        name: sources
+            .name(label.file_id)
+            .ok_or(files::Error::FileMissing)?,
+        path: sources
+            .path(label.file_id)
+            .map(|path| path.display().to_string()),
+        start: label.range.start,
+        end: label.range.end,
+        line_start: start.line_number,
+        column_start: start.column_number,
+        line_end: end.line_number,
+        column_end: end.column_number,
+        message: (!label.message.is_empty()).then_some(label.message.as_str()),
+    })
+}
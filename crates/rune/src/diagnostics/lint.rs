@@ -0,0 +1,66 @@
+use crate::ast::Span;
+use crate::SourceId;
+
+/// The level a warning is reported at, as set by the `#[allow(..)]`,
+/// `#[warn(..)]` and `#[deny(..)]` attributes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LintLevel {
+    /// The warning is not reported.
+    Allow,
+    /// The warning is reported as a warning.
+    Warn,
+    /// The warning is reported as an error.
+    Deny,
+}
+
+/// A span of source code in which a warning is reported at a given level.
+#[derive(Debug)]
+pub(super) struct LintScope {
+    /// The source the scope is in.
+    pub(super) source_id: SourceId,
+    /// The span covered by the scope.
+    pub(super) span: Span,
+    /// The name of the warning.
+    pub(super) name: &'static str,
+    /// The level the warning is reported at.
+    pub(super) level: LintLevel,
+}
+
+impl LintScope {
+    /// Test if the scope covers the given warning.
+    fn covers(&self, source_id: SourceId, span: Span, name: &str) -> bool {
+        self.source_id == source_id
+            && self.name == name
+            && self.span.start <= span.start
+            && span.end <= self.span.end
+    }
+}
+
+/// Find the level of the innermost scope covering the given warning.
+///
+/// Scopes nest, so the innermost scope is the smallest one. If two scopes
+/// cover the same span, the one registered last wins.
+pub(super) fn level(
+    scopes: &[LintScope],
+    source_id: SourceId,
+    span: Span,
+    name: &str,
+) -> Option<LintLevel> {
+    let mut found = None::<&LintScope>;
+
+    for scope in scopes {
+        if !scope.covers(source_id, span, name) {
+            continue;
+        }
+
+        if let Some(found) = found {
+            if found.span.range().len() < scope.span.range().len() {
+                continue;
+            }
+        }
+
+        found = Some(scope);
+    }
+
+    Some(found?.level)
+}
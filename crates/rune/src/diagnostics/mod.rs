@@ -37,6 +37,10 @@ pub use self::warning::WarningDiagnostic;
 pub(crate) use self::warning::WarningDiagnosticKind;
 mod warning;
 
+pub(crate) use self::lint::LintLevel;
+use self::lint::LintScope;
+mod lint;
+
 pub use self::runtime_warning::RuntimeWarningDiagnostic;
 pub(crate) use self::runtime_warning::RuntimeWarningDiagnosticKind;
 mod runtime_warning;
@@ -44,9 +48,10 @@ mod runtime_warning;
 use ::rust_alloc::boxed::Box;
 use rune_alloc::String;
 
+use crate::alloc::prelude::*;
 use crate::alloc::{self, Vec};
-use crate::ast::Spanned;
-use crate::{Hash, SourceId};
+use crate::ast::{Span, Spanned};
+use crate::{Hash, ItemBuf, Options, SourceId};
 
 #[cfg(feature = "emit")]
 #[cfg_attr(rune_docsrs, doc(cfg(feature = "emit")))]
@@ -55,6 +60,14 @@ mod emit;
 #[cfg_attr(rune_docsrs, doc(cfg(feature = "emit")))]
 #[doc(inline)]
 pub use self::emit::EmitError;
+#[cfg(feature = "languageserver")]
+pub(crate) use self::emit::{fatal_diagnostic, warning_diagnostic};
+
+#[cfg(feature = "emit-json")]
+#[cfg_attr(rune_docsrs, doc(cfg(feature = "emit-json")))]
+mod json;
+#[cfg(all(feature = "cli", feature = "emit-json"))]
+pub(crate) use self::json::write_json;
 
 /// A single diagnostic.
 #[derive(Debug)]
@@ -115,6 +128,12 @@ pub struct Diagnostics {
     has_error: bool,
     /// Indicates if diagnostics contains warnings.
     has_warning: bool,
+    /// Treat all warnings as errors.
+    warnings_as_errors: bool,
+    /// Names of warnings which are treated as errors.
+    denied: Vec<&'static str>,
+    /// Levels of warnings set by attributes in the source.
+    lints: Vec<LintScope>,
 }
 
 impl Diagnostics {
@@ -124,6 +143,9 @@ impl Diagnostics {
             mode,
             has_error: false,
             has_warning: false,
+            warnings_as_errors: false,
+            denied: Vec::new(),
+            lints: Vec::new(),
         }
     }
 
@@ -180,6 +202,11 @@ impl Diagnostics {
         self.has_warning
     }
 
+    /// Check if warnings are collected.
+    pub(crate) fn has_warnings_enabled(&self) -> bool {
+        self.mode.warnings()
+    }
+
     /// Access underlying diagnostics.
     pub fn diagnostics(&self) -> &[Diagnostic] {
         &self.diagnostics
@@ -190,6 +217,33 @@ impl Diagnostics {
         self.diagnostics
     }
 
+    /// Configure which warnings should be reported as errors from the given
+    /// options.
+    pub(crate) fn deny_from(&mut self, options: &Options) -> alloc::Result<()> {
+        self.warnings_as_errors = options.warnings_as_errors;
+        self.denied.clear();
+        self.denied.try_extend(options.deny.iter().copied())?;
+        self.lints.clear();
+        Ok(())
+    }
+
+    /// Set the level of the named warning within the given span, like through
+    /// an `#[allow(..)]` attribute.
+    pub(crate) fn lint_level(
+        &mut self,
+        source_id: SourceId,
+        span: &dyn Spanned,
+        name: &'static str,
+        level: LintLevel,
+    ) -> alloc::Result<()> {
+        self.lints.try_push(LintScope {
+            source_id,
+            span: span.span(),
+            name,
+            level,
+        })
+    }
+
     /// Report an internal error.
     ///
     /// This should be used for programming invariants of the compiler which are
@@ -218,8 +272,9 @@ impl Diagnostics {
         )
     }
 
-    /// Indicate that a value is produced but never used.
-    pub(crate) fn unreachable(
+    /// Indicate that code is unreachable since it follows the diverging
+    /// `cause`.
+    pub(crate) fn unreachable_code(
         &mut self,
         source_id: SourceId,
         span: &dyn Spanned,
@@ -227,7 +282,7 @@ impl Diagnostics {
     ) -> alloc::Result<()> {
         self.warning(
             source_id,
-            WarningDiagnosticKind::Unreachable {
+            WarningDiagnosticKind::UnreachableCode {
                 span: span.span(),
                 cause: cause.span(),
             },
@@ -322,6 +377,91 @@ impl Diagnostics {
         )
     }
 
+    /// Add a warning about a variable which is never read.
+    pub(crate) fn unused_variable(
+        &mut self,
+        source_id: SourceId,
+        span: &dyn Spanned,
+        name: String,
+    ) -> alloc::Result<()> {
+        self.warning(
+            source_id,
+            WarningDiagnosticKind::UnusedVariable {
+                span: span.span(),
+                name,
+            },
+        )
+    }
+
+    /// Add a warning about a lint attribute which refers to a warning which
+    /// doesn't exist.
+    pub(crate) fn unknown_lint(
+        &mut self,
+        source_id: SourceId,
+        span: &dyn Spanned,
+        name: String,
+    ) -> alloc::Result<()> {
+        self.warning(
+            source_id,
+            WarningDiagnosticKind::UnknownLint {
+                span: span.span(),
+                name,
+            },
+        )
+    }
+
+    /// Add a warning listing the variables captured by a closure or async
+    /// block.
+    pub(crate) fn closure_capture(
+        &mut self,
+        source_id: SourceId,
+        span: &dyn Spanned,
+        do_move: bool,
+        captures: Vec<(String, Span)>,
+    ) -> alloc::Result<()> {
+        self.warning(
+            source_id,
+            WarningDiagnosticKind::ClosureCapture {
+                span: span.span(),
+                do_move,
+                captures,
+            },
+        )
+    }
+
+    /// Add a warning about a function which was removed by dead code
+    /// elimination.
+    pub(crate) fn removed_function(
+        &mut self,
+        source_id: SourceId,
+        span: &dyn Spanned,
+        item: ItemBuf,
+    ) -> alloc::Result<()> {
+        self.warning(
+            source_id,
+            WarningDiagnosticKind::RemovedFunction {
+                span: span.span(),
+                item,
+            },
+        )
+    }
+
+    /// Add a warning reported by a macro.
+    pub(crate) fn macro_warning(
+        &mut self,
+        source_id: SourceId,
+        span: &dyn Spanned,
+        message: String,
+    ) -> alloc::Result<()> {
+        self.warning(
+            source_id,
+            WarningDiagnosticKind::MacroWarning {
+                span: span.span(),
+                message,
+            },
+        )
+    }
+
     /// Add a warning about using a deprecated function
     pub(crate) fn runtime_used_deprecated(&mut self, ip: usize, hash: Hash) -> alloc::Result<()> {
         self.runtime_warning(ip, RuntimeWarningDiagnosticKind::UsedDeprecated { hash })
@@ -332,15 +472,32 @@ impl Diagnostics {
     where
         WarningDiagnosticKind: From<T>,
     {
+        let warning = WarningDiagnostic {
+            source_id,
+            kind: kind.into(),
+        };
+
+        let name = warning.name();
+
+        let level = match lint::level(&self.lints, source_id, warning.span(), name) {
+            Some(level) => level,
+            None if self.warnings_as_errors || self.denied.contains(&name) => LintLevel::Deny,
+            None => LintLevel::Warn,
+        };
+
+        match level {
+            LintLevel::Allow => return Ok(()),
+            LintLevel::Deny => {
+                return self.error(source_id, FatalDiagnosticKind::DeniedWarning(warning));
+            }
+            LintLevel::Warn => {}
+        }
+
         if !self.mode.warnings() {
             return Ok(());
         }
 
-        self.diagnostics
-            .try_push(Diagnostic::Warning(WarningDiagnostic {
-                source_id,
-                kind: kind.into(),
-            }))?;
+        self.diagnostics.try_push(Diagnostic::Warning(warning))?;
 
         self.has_warning = true;
         Ok(())
@@ -1,9 +1,9 @@
 use core::fmt;
 
-use crate::alloc::String;
+use crate::alloc::{String, Vec};
 use crate::ast::Span;
 use crate::ast::Spanned;
-use crate::SourceId;
+use crate::{ItemBuf, SourceId};
 
 /// Warning diagnostic emitted during compilation. Warning diagnostics indicates
 /// an recoverable issues.
@@ -21,8 +21,17 @@ impl WarningDiagnostic {
         self.source_id
     }
 
+    /// The stable name of the warning, like `unused-variable`.
+    ///
+    /// This is the name used to deny the warning with [`Options::deny`].
+    ///
+    /// [`Options::deny`]: crate::Options::deny
+    pub fn name(&self) -> &'static str {
+        self.kind.name()
+    }
+
     /// The kind of the warning.
-    #[cfg(feature = "emit")]
+    #[cfg(any(feature = "emit", feature = "languageserver", test))]
     pub(crate) fn kind(&self) -> &WarningDiagnosticKind {
         &self.kind
     }
@@ -51,12 +60,17 @@ impl Spanned for WarningDiagnostic {
     fn span(&self) -> Span {
         match &self.kind {
             WarningDiagnosticKind::NotUsed { span, .. } => *span,
-            WarningDiagnosticKind::Unreachable { span, .. } => *span,
+            WarningDiagnosticKind::UnreachableCode { span, .. } => *span,
             WarningDiagnosticKind::LetPatternMightPanic { span, .. } => *span,
             WarningDiagnosticKind::TemplateWithoutExpansions { span, .. } => *span,
             WarningDiagnosticKind::RemoveTupleCallParams { span, .. } => *span,
             WarningDiagnosticKind::UnnecessarySemiColon { span, .. } => *span,
             WarningDiagnosticKind::UsedDeprecated { span, .. } => *span,
+            WarningDiagnosticKind::RemovedFunction { span, .. } => *span,
+            WarningDiagnosticKind::UnusedVariable { span, .. } => *span,
+            WarningDiagnosticKind::UnknownLint { span, .. } => *span,
+            WarningDiagnosticKind::ClosureCapture { span, .. } => *span,
+            WarningDiagnosticKind::MacroWarning { span, .. } => *span,
         }
     }
 }
@@ -89,8 +103,8 @@ pub(crate) enum WarningDiagnosticKind {
         context: Option<Span>,
     },
     /// Unreachable code.
-    Unreachable {
-        /// The span that is not used.
+    UnreachableCode {
+        /// The span which can't be reached.
         span: Span,
         /// The span which caused the code to be unreachable.
         #[cfg_attr(not(feature = "emit"), allow(dead_code))]
@@ -138,13 +152,111 @@ pub(crate) enum WarningDiagnosticKind {
         /// Deprecated message.
         message: String,
     },
+    /// A function was removed from the unit since it can't be reached from
+    /// any entry point.
+    RemovedFunction {
+        /// The span of the removed function.
+        span: Span,
+        /// The item of the removed function.
+        item: ItemBuf,
+    },
+    /// A variable is defined but never read.
+    UnusedVariable {
+        /// The span where the variable is defined.
+        span: Span,
+        /// The name of the variable.
+        name: String,
+    },
+    /// A lint attribute refers to a warning which doesn't exist.
+    UnknownLint {
+        /// The span of the attribute.
+        span: Span,
+        /// The unknown name.
+        name: String,
+    },
+    /// A closure or async block captures variables from its environment.
+    ClosureCapture {
+        /// The span of the closure or async block.
+        span: Span,
+        /// If the captures are moved into the closure when it's created.
+        #[cfg_attr(not(feature = "emit"), allow(dead_code))]
+        do_move: bool,
+        /// The names of the captured variables, and where they are declared.
+        captures: Vec<(String, Span)>,
+    },
+    /// A warning reported by a macro.
+    MacroWarning {
+        /// The span the warning refers to.
+        span: Span,
+        /// The message of the warning.
+        message: String,
+    },
+}
+
+macro_rules! names {
+    ($first:literal $(, $name:literal)* $(,)?) => {
+        /// The stable names of all warnings.
+        pub(crate) const NAMES: &'static [&'static str] = &[$first $(, $name)*];
+
+        /// The stable names of all warnings, separated by commas.
+        pub(crate) const NAMES_LIST: &'static str = concat!($first $(, ", ", $name)*);
+    };
+}
+
+impl WarningDiagnosticKind {
+    names! {
+        "not-used",
+        "unreachable-code",
+        "let-pattern-might-panic",
+        "template-without-expansions",
+        "remove-tuple-call-params",
+        "unnecessary-semicolon",
+        "used-deprecated",
+        "removed-function",
+        "unused-variable",
+        "unknown-lint",
+        "closure-capture",
+        "macro-warning",
+    }
+
+    /// Find the stable name of the warning referred to by a lint, which can
+    /// be spelled both like `unused_variable` and `unused-variable`.
+    pub(crate) fn find_name(lint: &str) -> Option<&'static str> {
+        Self::NAMES.iter().copied().find(|name| {
+            name.len() == lint.len()
+                && name
+                    .bytes()
+                    .zip(lint.bytes())
+                    .all(|(a, b)| a == b || a == b'-' && b == b'_')
+        })
+    }
+
+    /// The stable name of the warning.
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            WarningDiagnosticKind::NotUsed { .. } => "not-used",
+            WarningDiagnosticKind::UnreachableCode { .. } => "unreachable-code",
+            WarningDiagnosticKind::LetPatternMightPanic { .. } => "let-pattern-might-panic",
+            WarningDiagnosticKind::TemplateWithoutExpansions { .. } => {
+                "template-without-expansions"
+            }
+            WarningDiagnosticKind::RemoveTupleCallParams { .. } => "remove-tuple-call-params",
+            WarningDiagnosticKind::UnnecessarySemiColon { .. } => "unnecessary-semicolon",
+            WarningDiagnosticKind::UsedDeprecated { .. } => "used-deprecated",
+            WarningDiagnosticKind::RemovedFunction { .. } => "removed-function",
+            WarningDiagnosticKind::UnusedVariable { .. } => "unused-variable",
+            WarningDiagnosticKind::UnknownLint { .. } => "unknown-lint",
+            WarningDiagnosticKind::ClosureCapture { .. } => "closure-capture",
+            WarningDiagnosticKind::MacroWarning { .. } => "macro-warning",
+        }
+    }
 }
 
 impl fmt::Display for WarningDiagnosticKind {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             WarningDiagnosticKind::NotUsed { .. } => write!(f, "Not used"),
-            WarningDiagnosticKind::Unreachable { .. } => write!(f, "Unreachable code"),
+            WarningDiagnosticKind::UnreachableCode { .. } => write!(f, "Unreachable code"),
             WarningDiagnosticKind::LetPatternMightPanic { .. } => {
                 write!(f, "Pattern might panic")
             }
@@ -161,6 +273,33 @@ impl fmt::Display for WarningDiagnosticKind {
             WarningDiagnosticKind::UsedDeprecated { message, .. } => {
                 write!(f, "Used deprecated function: {message}")
             }
+            WarningDiagnosticKind::RemovedFunction { item, .. } => {
+                write!(f, "Function `{item}` is unreachable and was removed")
+            }
+            WarningDiagnosticKind::UnusedVariable { name, .. } => {
+                write!(f, "Unused variable `{name}`")
+            }
+            WarningDiagnosticKind::UnknownLint { name, .. } => {
+                write!(f, "Unknown lint `{name}`")
+            }
+            WarningDiagnosticKind::ClosureCapture { captures, .. } => {
+                write!(f, "Captures ")?;
+
+                let mut it = captures.iter().peekable();
+
+                while let Some((name, _)) = it.next() {
+                    write!(f, "`{name}`")?;
+
+                    if it.peek().is_some() {
+                        write!(f, ", ")?;
+                    }
+                }
+
+                Ok(())
+            }
+            WarningDiagnosticKind::MacroWarning { message, .. } => {
+                write!(f, "{message}")
+            }
         }
     }
 }
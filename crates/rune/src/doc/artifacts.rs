@@ -8,6 +8,7 @@ use std::path::Path;
 
 use crate::alloc::borrow::Cow;
 use crate::alloc::{String, Vec};
+use crate::compile::Location;
 use crate::runtime::Protocol;
 use crate::ItemBuf;
 
@@ -48,6 +49,9 @@ pub(crate) struct Test {
     pub(crate) content: String,
     /// Test parameters.
     pub(crate) params: TestParams,
+    /// The location of the doc comment the test was extracted from, if it was
+    /// declared in a source.
+    pub(crate) location: Option<Location>,
 }
 
 /// A collection of artifacts produced by a documentation build.
@@ -144,6 +148,18 @@ pub(crate) struct Asset {
 }
 
 impl Asset {
+    /// The path of the asset.
+    #[cfg(test)]
+    pub(crate) fn path(&self) -> &RelativePath {
+        &self.path
+    }
+
+    /// The content of the asset.
+    #[cfg(test)]
+    pub(crate) fn content(&self) -> &[u8] {
+        self.content.as_ref()
+    }
+
     /// Build the given asset.
     pub(crate) fn build(&self, root: &Path) -> Result<()> {
         let p = self.path.to_path(root);
@@ -1,11 +1,8 @@
-mod js;
 mod type_;
 
 use core::fmt;
 use core::str;
 
-use rust_alloc::string::ToString;
-
 use anyhow::{anyhow, bail, Context as _, Result};
 use relative_path::{RelativePath, RelativePathBuf};
 use serde::{Serialize, Serializer};
@@ -18,15 +15,17 @@ use crate::alloc::borrow::Cow;
 use crate::alloc::fmt::TryWrite;
 use crate::alloc::prelude::*;
 use crate::alloc::{self, HashSet, VecDeque};
-use crate::compile::meta;
+use crate::compile::{meta, Prelude};
 use crate::doc::artifacts::{Test, TestKind};
-use crate::doc::context::{Function, Kind, Meta, Signature};
+use crate::doc::context::{Function, Kind, Meta, MetaSource, Signature};
+use crate::doc::path::{build_item_path, ItemKind};
+use crate::doc::search::{self, SearchIndex};
 use crate::doc::templating;
-use crate::doc::{Artifacts, Context, Visitor};
+use crate::doc::{Artifacts, Context, Externs, Visitor};
 use crate::item::ComponentRef;
 use crate::runtime::OwnedTuple;
 use crate::std::borrow::ToOwned;
-use crate::{Hash, Item, TypeHash};
+use crate::{Hash, Item, ItemBuf, TypeHash};
 
 use super::markdown;
 
@@ -71,12 +70,25 @@ mod embed {
 }
 
 /// Build documentation based on the given context and visitors.
+///
+/// Items in crates which are part of `externs` are not documented, and links
+/// to them point to the external documentation instead.
+///
+/// If `playground` is specified, rune examples link to it so that they can be
+/// run.
 pub(crate) fn build(
     name: &str,
     artifacts: &mut Artifacts,
     context: Option<&crate::Context>,
     visitors: &[Visitor],
+    externs: &Externs,
+    playground: Option<&str>,
 ) -> Result<()> {
+    let prelude = match context {
+        Some(context) if context.has_default_modules() => Prelude::with_default_prelude()?,
+        _ => Prelude::default(),
+    };
+
     let context = Context::new(context, visitors);
 
     let paths = templating::Paths::default();
@@ -139,6 +151,10 @@ pub(crate) fn build(
     for item in context.iter_modules() {
         let item = item?;
 
+        if externs.contains(&item) {
+            continue;
+        }
+
         let meta = context
             .meta(&item)?
             .into_iter()
@@ -159,9 +175,11 @@ pub(crate) fn build(
 
     let mut cx = Ctxt {
         state: State::default(),
-        index: Vec::new(),
         name,
         context: &context,
+        externs,
+        playground,
+        prelude,
         search_index: Some(search_index),
         root_index,
         fonts: &fonts,
@@ -194,24 +212,16 @@ pub(crate) fn build(
 
         match build {
             Build::Type => {
-                let (builder, items) = self::type_::build(&mut cx, "Type", "type", meta)?;
-                builders.try_push(builder)?;
-                cx.index.try_extend(items)?;
+                builders.try_push(self::type_::build(&mut cx, "Type", "type", meta)?)?;
             }
             Build::Trait => {
-                let (builder, items) = self::type_::build(&mut cx, "Trait", "trait", meta)?;
-                builders.try_push(builder)?;
-                cx.index.try_extend(items)?;
+                builders.try_push(self::type_::build(&mut cx, "Trait", "trait", meta)?)?;
             }
             Build::Struct => {
-                let (builder, index) = self::type_::build(&mut cx, "Struct", "struct", meta)?;
-                builders.try_push(builder)?;
-                cx.index.try_extend(index)?;
+                builders.try_push(self::type_::build(&mut cx, "Struct", "struct", meta)?)?;
             }
             Build::Enum => {
-                let (builder, index) = self::type_::build(&mut cx, "Enum", "enum", meta)?;
-                builders.try_push(builder)?;
-                cx.index.try_extend(index)?;
+                builders.try_push(self::type_::build(&mut cx, "Enum", "enum", meta)?)?;
             }
             Build::Macro => {
                 builders.try_push(build_macro(&mut cx, meta)?)?;
@@ -226,8 +236,15 @@ pub(crate) fn build(
         }
     }
 
+    let index = search::build(name, &context, externs)?;
+
+    artifacts.asset(false, "search-index.json", || {
+        let content = String::try_from(serde_json::to_string(&index)?)?;
+        Ok(content.into_bytes().into())
+    })?;
+
     let search_index_path = artifacts.asset(true, "index.js", || {
-        let content = build_search_index(&cx)?;
+        let content = build_search_index(&index)?;
         Ok(content.into_bytes().into())
     })?;
 
@@ -247,33 +264,10 @@ pub(crate) fn build(
     Ok(())
 }
 
-fn build_search_index(cx: &Ctxt) -> Result<String> {
+/// Build the script which makes the search index available to the search UI.
+fn build_search_index(index: &SearchIndex) -> Result<String> {
     let mut s = String::new();
-    write!(s, "window.INDEX = [")?;
-    let mut it = cx.index.iter();
-
-    while let Some(IndexEntry {
-        path,
-        item,
-        kind,
-        doc,
-    }) = it.next()
-    {
-        write!(s, "[\"{path}\",\"{item}\",\"{kind}\",\"")?;
-
-        if let Some(doc) = doc {
-            js::encode_quoted(&mut s, doc)?;
-        }
-
-        write!(s, "\"]")?;
-
-        if it.clone().next().is_some() {
-            write!(s, ",")?;
-        }
-    }
-
-    write!(s, "];")?;
-    writeln!(s)?;
+    writeln!(s, "window.INDEX = {};", serde_json::to_string(index)?)?;
     Ok(s)
 }
 
@@ -287,54 +281,6 @@ struct Shared<'a> {
     js: Vec<RelativePathBuf>,
 }
 
-#[derive(Debug, Clone, Copy)]
-pub(crate) enum ItemKind {
-    Type,
-    Struct,
-    Enum,
-    Module,
-    Macro,
-    Function,
-    Trait,
-}
-
-impl fmt::Display for ItemKind {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            ItemKind::Type => "type".fmt(f),
-            ItemKind::Struct => "struct".fmt(f),
-            ItemKind::Enum => "enum".fmt(f),
-            ItemKind::Module => "module".fmt(f),
-            ItemKind::Macro => "macro".fmt(f),
-            ItemKind::Function => "function".fmt(f),
-            ItemKind::Trait => "trait".fmt(f),
-        }
-    }
-}
-
-pub(crate) enum IndexKind {
-    Item(ItemKind),
-    Method,
-    Variant,
-}
-
-impl fmt::Display for IndexKind {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            IndexKind::Item(item) => item.fmt(f),
-            IndexKind::Method => "method".fmt(f),
-            IndexKind::Variant => "variant".fmt(f),
-        }
-    }
-}
-
-pub(crate) struct IndexEntry<'m> {
-    pub(crate) path: RelativePathBuf,
-    pub(crate) item: Cow<'m, Item>,
-    pub(crate) kind: IndexKind,
-    pub(crate) doc: Option<String>,
-}
-
 #[derive(Default, TryClone)]
 pub(crate) struct State<'m> {
     #[try_clone(with = RelativePathBuf::clone)]
@@ -347,10 +293,14 @@ pub(crate) struct State<'m> {
 
 pub(crate) struct Ctxt<'a, 'm> {
     state: State<'m>,
-    /// A collection of all items visited.
-    index: Vec<IndexEntry<'m>>,
     name: &'a str,
     context: &'a Context<'m>,
+    externs: &'a Externs,
+    /// The URL of the playground which examples link to.
+    playground: Option<&'a str>,
+    /// The prelude which links in the documentation of items declared in
+    /// sources are resolved against.
+    prelude: Prelude,
     search_index: Option<&'a RelativePath>,
     root_index: &'a RelativePath,
     fonts: &'a [RelativePathBuf],
@@ -383,16 +333,6 @@ impl<'m> Ctxt<'_, 'm> {
         self.state.item = meta.item;
 
         build_item_path(self.name, meta.item, item_kind, &mut self.state.path)?;
-
-        let doc = self.render_line_docs(meta, meta.docs.get(..1).unwrap_or_default())?;
-
-        self.index.try_push(IndexEntry {
-            path: self.state.path.clone(),
-            item: Cow::Borrowed(meta.item),
-            kind: IndexKind::Item(item_kind),
-            doc,
-        })?;
-
         Ok(())
     }
 
@@ -502,7 +442,7 @@ impl<'m> Ctxt<'_, 'm> {
                 }
             };
 
-            Some((path.to_string().into(), title.into_std().into()))
+            Some((path.into_std().into(), title.into_std().into()))
         };
 
         let iter = Parser::new_with_broken_link_callback(&input, options, Some(&mut callback));
@@ -511,6 +451,7 @@ impl<'m> Ctxt<'_, 'm> {
 
         markdown::push_html(
             self.syntax_set.as_ref(),
+            self.playground,
             &mut o,
             iter,
             capture_tests.then_some(&mut tests),
@@ -526,6 +467,7 @@ impl<'m> Ctxt<'_, 'm> {
                 kind: self.state.kind,
                 content,
                 params,
+                location: self.context.doc_location(self.state.item),
             })?;
         }
 
@@ -540,6 +482,16 @@ impl<'m> Ctxt<'_, 'm> {
         Ok(self.dir().relative(path))
     }
 
+    /// Get the path used to link to the given item, which is an absolute URL
+    /// if the item belongs to an external crate.
+    fn link_path(&self, item: &Item, kind: ItemKind) -> Result<String> {
+        if let Some(url) = self.externs.url(self.name, item, kind)? {
+            return Ok(url);
+        }
+
+        Ok(self.item_path(item, kind)?.as_str().try_to_owned()?)
+    }
+
     /// Build backlinks for the current item.
     fn module_path_html(&self, meta: Meta<'_>, is_module: bool) -> Result<String> {
         fn unqualified_component<'a>(c: &'a ComponentRef<'_>) -> &'a dyn fmt::Display {
@@ -589,6 +541,26 @@ impl<'m> Ctxt<'_, 'm> {
         Ok(string)
     }
 
+    /// Find the path to the documentation of the target of a re-export.
+    fn alias_path(&self, to: &Item) -> Result<Option<String>> {
+        for meta in self.context.meta(to)? {
+            let kind = match meta.kind {
+                Kind::Type => ItemKind::Type,
+                Kind::Struct => ItemKind::Struct,
+                Kind::Enum => ItemKind::Enum,
+                Kind::Macro => ItemKind::Macro,
+                Kind::Function(..) => ItemKind::Function,
+                Kind::Module => ItemKind::Module,
+                Kind::Trait => ItemKind::Trait,
+                _ => continue,
+            };
+
+            return Ok(Some(self.link_path(to, kind)?));
+        }
+
+        Ok(None)
+    }
+
     /// Convert a hash into a link.
     fn link(&self, hash: Hash, text: Option<&str>, generics: &[meta::DocType]) -> Result<String> {
         let mut s = String::new();
@@ -654,7 +626,7 @@ impl<'m> Ctxt<'_, 'm> {
                 None => meta.item.last().and_then(|c| c.as_str()),
             };
 
-            (Some(self.item_path(meta.item, kind)?), Some(kind), text)
+            (Some(self.link_path(meta.item, kind)?), Some(kind), text)
         };
 
         let (path, kind, text) = outcome;
@@ -739,11 +711,7 @@ impl<'m> Ctxt<'_, 'm> {
         Ok(string)
     }
 
-    fn link_callback(
-        &self,
-        meta: Meta<'_>,
-        link: &str,
-    ) -> Result<Option<(RelativePathBuf, String)>> {
+    fn link_callback(&self, meta: Meta<'_>, link: &str) -> Result<Option<(String, String)>> {
         enum Flavor {
             Any,
             Macro,
@@ -751,6 +719,10 @@ impl<'m> Ctxt<'_, 'm> {
         }
 
         impl Flavor {
+            fn is_type(&self) -> bool {
+                matches!(self, Flavor::Any)
+            }
+
             fn is_struct(&self) -> bool {
                 matches!(self, Flavor::Any)
             }
@@ -780,26 +752,56 @@ impl<'m> Ctxt<'_, 'm> {
             (link, Flavor::Any)
         }
 
+        /// Construct an item from a path where the first component is the
+        /// name of a crate.
+        fn absolute(path: &str) -> alloc::Result<Option<ItemBuf>> {
+            let mut it = path.split("::");
+
+            let Some(name) = it.next().filter(|name| !name.is_empty()) else {
+                return Ok(None);
+            };
+
+            Ok(Some(ItemBuf::with_crate_item(name, it)?))
+        }
+
         let link = link.trim_matches(|c| matches!(c, '`'));
         let (link, flavor) = flavor(link);
 
-        let item = if matches!(meta.kind, Kind::Module) {
-            meta.item.join([link])?
+        let mut candidates = Vec::new();
+
+        if let Some(path) = link.strip_prefix("::") {
+            candidates.try_extend(absolute(path)?)?;
         } else {
-            let Some(parent) = meta.item.parent() else {
-                return Ok(None);
+            let base = if matches!(meta.kind, Kind::Module) {
+                Some(meta.item)
+            } else {
+                meta.item.parent()
             };
 
-            parent.join([link])?
-        };
+            if let Some(base) = base {
+                candidates.try_push(base.join(link.split("::"))?)?;
+            }
+
+            if link.contains("::") {
+                candidates.try_extend(absolute(link)?)?;
+            } else if matches!(meta.source, MetaSource::Source(..)) {
+                // Items declared in sources can refer to native items through
+                // the prelude.
+                if let Some(item) = self.prelude.get(link) {
+                    candidates.try_push(item.try_to_owned()?)?;
+                }
+            }
+        }
 
-        let item_path = 'out: {
+        for item in candidates {
             let mut alts = Vec::new();
 
             for meta in self.context.meta(&item)? {
                 alts.try_push(match meta.kind {
+                    Kind::Type if flavor.is_type() => ItemKind::Type,
                     Kind::Struct if flavor.is_struct() => ItemKind::Struct,
                     Kind::Enum if flavor.is_enum() => ItemKind::Enum,
+                    Kind::Trait if flavor.is_type() => ItemKind::Trait,
                     Kind::Macro if flavor.is_macro() => ItemKind::Macro,
                     Kind::Function(_) if flavor.is_function() => ItemKind::Function,
                     _ => {
@@ -809,21 +811,21 @@ impl<'m> Ctxt<'_, 'm> {
             }
 
             match &alts[..] {
-                [] => {
-                    tracing::warn!(?link, "Bad link, no items found");
+                [] => continue,
+                [item_path] => {
+                    let path = self.link_path(&item, *item_path)?;
+                    let title = try_format!("{item_path} {link}");
+                    return Ok(Some((path, title)));
                 }
-                [out] => break 'out *out,
                 _items => {
                     tracing::warn!(?link, items = ?_items, "Bad link, got multiple items");
+                    return Ok(None);
                 }
             }
+        }
 
-            return Ok(None);
-        };
-
-        let path = self.item_path(&item, item_path)?;
-        let title = try_format!("{item_path} {link}");
-        Ok(Some((path, title)))
+        tracing::warn!(?link, "Bad link, no items found");
+        Ok(None)
     }
 }
 
@@ -877,6 +879,9 @@ fn build_index<'m>(
         #[serde(serialize_with = "serialize_item")]
         item: &'a Item,
         path: RelativePathBuf,
+        version: Option<&'a str>,
+        description: Option<&'a str>,
+        authors: Option<String>,
     }
 
     let mut modules = Vec::new();
@@ -894,7 +899,22 @@ fn build_index<'m>(
             continue;
         }
 
-        modules.try_push(Module { item, path })?;
+        let package = cx.context.package(item);
+
+        let authors = match package {
+            Some(package) if !package.authors.is_empty() => {
+                Some(package.authors.iter().map(String::as_str).try_join(", ")?)
+            }
+            _ => None,
+        };
+
+        modules.try_push(Module {
+            item,
+            path,
+            version: package.and_then(|p| p.version.as_deref()),
+            description: package.and_then(|p| p.description.as_deref()),
+            authors,
+        })?;
     }
 
     // sort the modules by name
@@ -930,6 +950,7 @@ fn module<'m>(
         functions: Vec<Function<'a>>,
         modules: Vec<Module<'a>>,
         traits: Vec<Trait<'a>>,
+        reexports: Vec<Reexport<'a>>,
     }
 
     #[derive(Serialize)]
@@ -1005,6 +1026,17 @@ fn module<'m>(
         doc: Option<String>,
     }
 
+    #[derive(Serialize)]
+    struct Reexport<'a> {
+        #[serde(serialize_with = "serialize_item")]
+        item: &'a Item,
+        #[serde(serialize_with = "serialize_component_ref")]
+        name: ComponentRef<'a>,
+        #[serde(serialize_with = "serialize_item")]
+        to: &'a Item,
+        path: Option<String>,
+    }
+
     let mut types = Vec::new();
     let mut structs = Vec::new();
     let mut enums = Vec::new();
@@ -1012,6 +1044,7 @@ fn module<'m>(
     let mut functions = Vec::new();
     let mut modules = Vec::new();
     let mut traits = Vec::new();
+    let mut reexports = Vec::new();
 
     for (_, name) in cx.context.iter_components(meta.item)? {
         let item = meta.item.join([name])?;
@@ -1111,6 +1144,14 @@ fn module<'m>(
                         doc: cx.render_line_docs(m, m.docs.get(..1).unwrap_or_default())?,
                     })?;
                 }
+                Kind::Alias(to) => {
+                    reexports.try_push(Reexport {
+                        item: m.item,
+                        name,
+                        to,
+                        path: cx.alias_path(to)?,
+                    })?;
+                }
                 _ => {
                     continue;
                 }
@@ -1133,6 +1174,7 @@ fn module<'m>(
             functions,
             modules,
             traits,
+            reexports,
         })
     })?)
 }
@@ -1235,37 +1277,3 @@ where
 {
     serializer.collect_str(&c)
 }
-
-/// Helper for building an item path.
-fn build_item_path(
-    name: &str,
-    item: &Item,
-    kind: ItemKind,
-    path: &mut RelativePathBuf,
-) -> Result<()> {
-    if item.is_empty() {
-        path.push(name);
-    } else {
-        for c in item.iter() {
-            let string = match c {
-                ComponentRef::Crate(string) => string,
-                ComponentRef::Str(string) => string,
-                _ => continue,
-            };
-
-            path.push(string);
-        }
-    }
-
-    path.set_extension(match kind {
-        ItemKind::Type => "type.html",
-        ItemKind::Struct => "struct.html",
-        ItemKind::Enum => "enum.html",
-        ItemKind::Module => "module.html",
-        ItemKind::Macro => "macro.html",
-        ItemKind::Function => "fn.html",
-        ItemKind::Trait => "trait.html",
-    });
-
-    Ok(())
-}
@@ -1,18 +1,17 @@
 use core::mem::replace;
 
 use anyhow::{Context, Result};
-use relative_path::RelativePathBuf;
 use serde::Serialize;
 
-use crate::alloc::borrow::Cow;
 use crate::alloc::fmt::TryWrite;
 use crate::alloc::prelude::*;
 use crate::doc::artifacts::TestKind;
 use crate::doc::context::{Assoc, AssocFnKind, Kind, Meta};
 use crate::item::ComponentRef;
+use crate::runtime::{ConstValue, ConstValueKind, Inline};
 use crate::{Hash, Item};
 
-use super::{Builder, Ctxt, IndexEntry, IndexKind, ItemKind};
+use super::{Builder, Ctxt, ItemKind};
 
 #[derive(Serialize)]
 pub(super) struct Protocol<'a> {
@@ -36,6 +35,15 @@ pub(super) struct Method<'a> {
     doc: Option<String>,
 }
 
+#[derive(Serialize)]
+pub(super) struct Constant<'a> {
+    name: &'a str,
+    value: Option<String>,
+    deprecated: Option<&'a str>,
+    line_doc: Option<String>,
+    doc: Option<String>,
+}
+
 #[derive(Serialize)]
 pub(super) struct Variant<'a> {
     name: &'a str,
@@ -43,13 +51,19 @@ pub(super) struct Variant<'a> {
     doc: Option<String>,
 }
 
+#[derive(Serialize)]
+struct Field<'a> {
+    name: &'a str,
+    doc: Option<String>,
+}
+
 #[derive(Default, Serialize)]
 pub(super) struct Trait<'a> {
     #[serde(serialize_with = "super::serialize_item")]
     pub(super) item: &'a Item,
     pub(super) hash: Hash,
     pub(super) name: &'a str,
-    pub(super) url: RelativePathBuf,
+    pub(super) url: String,
     pub(super) methods: Vec<Method<'a>>,
     pub(super) protocols: Vec<Protocol<'a>>,
 }
@@ -60,41 +74,11 @@ pub(super) fn build_assoc_fns<'m>(
 ) -> Result<(
     Vec<Protocol<'m>>,
     Vec<Method<'m>>,
+    Vec<Constant<'m>>,
     Vec<Variant<'m>>,
-    Vec<IndexEntry<'m>>,
     Vec<Trait<'m>>,
 )> {
-    let (variants, protocols, methods) = associated_for_hash(cx, meta.hash, meta, true)?;
-
-    let mut index = Vec::new();
-
-    if let Some(name) = cx.state.path.file_name() {
-        index.try_reserve(methods.len())?;
-
-        for m in &methods {
-            index.try_push(IndexEntry {
-                path: cx
-                    .state
-                    .path
-                    .with_file_name(format!("{name}#method.{}", m.name)),
-                item: Cow::Owned(meta.item.join([m.name])?),
-                kind: IndexKind::Method,
-                doc: m.line_doc.try_clone()?,
-            })?;
-        }
-
-        for m in &variants {
-            index.try_push(IndexEntry {
-                path: cx
-                    .state
-                    .path
-                    .with_file_name(format!("{name}#variant.{}", m.name)),
-                item: Cow::Owned(meta.item.join([m.name])?),
-                kind: IndexKind::Variant,
-                doc: m.line_doc.try_clone()?,
-            })?;
-        }
-    }
+    let (variants, protocols, methods, constants) = associated_for_hash(cx, meta.hash, meta, true)?;
 
     let mut traits = Vec::new();
 
@@ -110,14 +94,14 @@ pub(super) fn build_assoc_fns<'m>(
             continue 'outer;
         };
 
-        let (_, protocols, methods) = associated_for_hash(cx, hash, meta, false)?;
+        let (_, protocols, methods, _) = associated_for_hash(cx, hash, meta, false)?;
 
         let name = item
             .last()
             .and_then(|c| c.as_str())
             .context("Missing trait name")?;
 
-        let url = cx.item_path(item, ItemKind::Trait)?;
+        let url = cx.link_path(item, ItemKind::Trait)?;
 
         traits.try_push(Trait {
             item,
@@ -129,7 +113,7 @@ pub(super) fn build_assoc_fns<'m>(
         })?;
     }
 
-    Ok((protocols, methods, variants, index, traits))
+    Ok((protocols, methods, constants, variants, traits))
 }
 
 fn associated_for_hash<'m>(
@@ -137,10 +121,16 @@ fn associated_for_hash<'m>(
     hash: Hash,
     meta: Meta<'m>,
     capture_tests: bool,
-) -> Result<(Vec<Variant<'m>>, Vec<Protocol<'m>>, Vec<Method<'m>>)> {
+) -> Result<(
+    Vec<Variant<'m>>,
+    Vec<Protocol<'m>>,
+    Vec<Method<'m>>,
+    Vec<Constant<'m>>,
+)> {
     let mut variants = Vec::new();
     let mut protocols = Vec::new();
     let mut methods = Vec::new();
+    let mut constants = Vec::new();
 
     for hash in cx.context.associated(hash) {
         for assoc in cx.context.associated_meta(hash) {
@@ -157,6 +147,25 @@ fn associated_for_hash<'m>(
                         doc,
                     })?;
                 }
+                Assoc::Const(constant) => {
+                    let line_doc =
+                        cx.render_line_docs(meta, constant.docs.get(..1).unwrap_or_default())?;
+
+                    let doc = cx.render_docs(meta, constant.docs, capture_tests)?;
+
+                    let value = match const_repr(constant.value)? {
+                        Some(repr) => Some(cx.render_code([repr])?),
+                        None => None,
+                    };
+
+                    constants.try_push(Constant {
+                        name: constant.name,
+                        value,
+                        deprecated: constant.deprecated,
+                        line_doc,
+                        doc,
+                    })?;
+                }
                 Assoc::Fn(assoc) => {
                     let value;
 
@@ -246,7 +255,58 @@ fn associated_for_hash<'m>(
             }
         }
     }
-    Ok((variants, protocols, methods))
+    Ok((variants, protocols, methods, constants))
+}
+
+/// Render a simple constant value as rune source, returns `None` if the
+/// value can't be represented.
+fn const_repr(value: &ConstValue) -> Result<Option<String>> {
+    let mut out = String::new();
+
+    if !write_const_repr(&mut out, value)? {
+        return Ok(None);
+    }
+
+    Ok(Some(out))
+}
+
+fn write_const_repr(out: &mut String, value: &ConstValue) -> Result<bool> {
+    match value.as_kind() {
+        ConstValueKind::Inline(value) => match value {
+            Inline::Unit => write!(out, "()")?,
+            Inline::Bool(value) => write!(out, "{value}")?,
+            Inline::Char(value) => write!(out, "{value:?}")?,
+            Inline::Signed(value) => write!(out, "{value}")?,
+            Inline::Unsigned(value) => write!(out, "{value}u64")?,
+            Inline::Float(value) => write!(out, "{value:?}")?,
+            _ => return Ok(false),
+        },
+        ConstValueKind::String(value) => write!(out, "{value:?}")?,
+        ConstValueKind::Tuple(values) => {
+            write!(out, "(")?;
+
+            let mut it = values.iter().peekable();
+
+            while let Some(value) = it.next() {
+                if !write_const_repr(out, value)? {
+                    return Ok(false);
+                }
+
+                if it.peek().is_some() || values.len() == 1 {
+                    write!(out, ",")?;
+                }
+
+                if it.peek().is_some() {
+                    write!(out, " ")?;
+                }
+            }
+
+            write!(out, ")")?;
+        }
+        _ => return Ok(false),
+    }
+
+    Ok(true)
 }
 
 #[derive(Serialize)]
@@ -260,7 +320,9 @@ struct Params<'a> {
     name: ComponentRef<'a>,
     #[serde(serialize_with = "super::serialize_item")]
     item: &'a Item,
+    fields: Vec<Field<'a>>,
     methods: Vec<Method<'a>>,
+    constants: Vec<Constant<'a>>,
     protocols: Vec<Protocol<'a>>,
     traits: Vec<Trait<'a>>,
     doc: Option<String>,
@@ -273,14 +335,23 @@ pub(crate) fn build<'m>(
     what: &'static str,
     what_class: &'static str,
     meta: Meta<'m>,
-) -> Result<(Builder<'m>, Vec<IndexEntry<'m>>)> {
+) -> Result<Builder<'m>> {
     let module = cx.module_path_html(meta, false)?;
 
-    let (protocols, methods, _, index, traits) = build_assoc_fns(cx, meta)?;
+    let (protocols, methods, constants, _, traits) = build_assoc_fns(cx, meta)?;
     let name = meta.item.last().context("Missing module name")?;
 
     let doc = cx.render_docs(meta, meta.docs, true)?;
 
+    let mut fields = Vec::new();
+
+    for field in cx.context.fields(meta.hash)? {
+        fields.try_push(Field {
+            name: field.name,
+            doc: cx.render_docs(meta, field.docs, true)?,
+        })?;
+    }
+
     let builder = Builder::new(cx, move |cx| {
         cx.type_template.render(&Params {
             shared: cx.shared()?,
@@ -289,12 +360,14 @@ pub(crate) fn build<'m>(
             module,
             name,
             item: meta.item,
+            fields,
             methods,
+            constants,
             protocols,
             traits,
             doc,
         })
     })?;
 
-    Ok((builder, index))
+    Ok(builder)
 }
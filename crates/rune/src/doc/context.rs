@@ -2,6 +2,10 @@ use crate::alloc::prelude::*;
 use crate::alloc::{self, String, Vec};
 use crate::compile::context::ContextMeta;
 use crate::compile::meta;
+#[cfg(feature = "cli")]
+use crate::compile::Location;
+#[cfg(feature = "cli")]
+use crate::doc::PackageMeta;
 use crate::doc::{Visitor, VisitorData};
 use crate::item::{ComponentRef, IntoComponent};
 use crate::runtime::ConstValue;
@@ -36,7 +40,9 @@ pub(crate) struct Meta<'a> {
 #[derive(Debug, Clone, Copy)]
 pub(crate) struct Function<'a> {
     pub(crate) is_async: bool,
+    #[cfg_attr(not(feature = "cli"), allow(dead_code))]
     pub(crate) is_test: bool,
+    #[cfg_attr(not(feature = "cli"), allow(dead_code))]
     pub(crate) is_bench: bool,
     pub(crate) signature: Signature,
     pub(crate) arguments: Option<&'a [meta::DocArgument]>,
@@ -53,7 +59,11 @@ pub(crate) enum AssocFnKind<'a> {
     /// An index function with the given protocol.
     IndexFn(&'static Protocol, usize),
     /// The instance function refers to the given named instance fn.
-    Method(&'a Item, &'a str, Signature),
+    Method(
+        #[cfg_attr(not(feature = "cli"), allow(dead_code))] &'a Item,
+        &'a str,
+        Signature,
+    ),
 }
 
 /// Information on an associated function.
@@ -65,20 +75,44 @@ pub(crate) struct AssocVariant<'a> {
     pub(crate) docs: &'a [String],
 }
 
+/// Information on an associated constant.
+#[derive(Debug)]
+pub(crate) struct AssocConst<'a> {
+    /// Name of the constant.
+    pub(crate) name: &'a str,
+    /// The value of the constant.
+    #[cfg_attr(not(feature = "cli"), allow(dead_code))]
+    pub(crate) value: &'a ConstValue,
+    pub(crate) deprecated: Option<&'a str>,
+    /// Documentation for the constant.
+    pub(crate) docs: &'a [String],
+}
+
 /// Information on an associated function.
 #[derive(Debug)]
 pub(crate) struct AssocFn<'a> {
     pub(crate) kind: AssocFnKind<'a>,
+    #[cfg_attr(not(feature = "cli"), allow(dead_code))]
     pub(crate) trait_hash: Option<Hash>,
     pub(crate) is_async: bool,
     pub(crate) arguments: Option<&'a [meta::DocArgument]>,
     pub(crate) return_type: &'a meta::DocType,
     /// Generic instance parameters for function.
+    #[cfg_attr(not(feature = "cli"), allow(dead_code))]
     pub(crate) parameter_types: &'a [Hash],
     pub(crate) deprecated: Option<&'a str>,
     pub(crate) docs: &'a [String],
 }
 
+/// Information on a named field of a struct.
+#[derive(Debug)]
+pub(crate) struct Field<'a> {
+    /// Name of the field.
+    pub(crate) name: &'a str,
+    /// Documentation for the field.
+    pub(crate) docs: &'a [String],
+}
+
 /// Information on an associated item.
 #[derive(Debug)]
 pub(crate) enum Assoc<'a> {
@@ -86,6 +120,8 @@ pub(crate) enum Assoc<'a> {
     Variant(AssocVariant<'a>),
     /// An associated function.
     Fn(AssocFn<'a>),
+    /// An associated constant.
+    Const(AssocConst<'a>),
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -100,6 +136,8 @@ pub(crate) enum Kind<'a> {
     Const(#[allow(unused)] &'a ConstValue),
     Module,
     Trait,
+    /// A re-export of the given item.
+    Alias(&'a Item),
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -121,6 +159,18 @@ impl<'a> Context<'a> {
         Self { context, visitors }
     }
 
+    /// Get the metadata of the package documented under the crate of the
+    /// given item, if any.
+    #[cfg(feature = "cli")]
+    pub(crate) fn package(&self, item: &Item) -> Option<&'a PackageMeta> {
+        let name = item.as_crate()?;
+
+        self.visitors
+            .iter()
+            .filter(|v| v.base.as_crate() == Some(name))
+            .find_map(|v| v.package.as_ref())
+    }
+
     /// Iterate over all types associated with the given hash.
     pub(crate) fn associated(&self, hash: Hash) -> impl Iterator<Item = Hash> + '_ {
         let visitors = self
@@ -156,7 +206,48 @@ impl<'a> Context<'a> {
         visitors.chain(context)
     }
 
+    /// Get the named fields of the struct with the given hash, in the order
+    /// in which they are declared.
+    ///
+    /// Fields are only known for types defined in sources.
+    pub(crate) fn fields(&self, hash: Hash) -> alloc::Result<Vec<Field<'a>>> {
+        let mut out = Vec::new();
+
+        for visitor in self.visitors {
+            let Some(data) = visitor.get_by_hash(hash) else {
+                continue;
+            };
+
+            let Some(meta::Kind::Struct {
+                fields: meta::Fields::Named(named),
+                ..
+            }) = &data.kind
+            else {
+                continue;
+            };
+
+            let mut fields = named.fields.iter().try_collect::<Vec<_>>()?;
+            fields.sort_by_key(|f| f.position);
+
+            for f in fields {
+                let docs = data
+                    .field_docs
+                    .get(&f.name)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+
+                out.try_push(Field {
+                    name: &f.name,
+                    docs,
+                })?;
+            }
+        }
+
+        Ok(out)
+    }
+
     /// Iterate over all traits associated with the given hash.
+    #[cfg(feature = "cli")]
     pub(crate) fn traits(&self, hash: Hash) -> impl Iterator<Item = Hash> + 'a {
         self.context.into_iter().flat_map(move |c| c.traits(hash))
     }
@@ -193,6 +284,10 @@ impl<'a> Context<'a> {
 
         for visitor in self.visitors {
             if let Some(data) = visitor.get_by_hash(hash) {
+                if !visitor.is_documented(data) {
+                    continue;
+                }
+
                 out.try_push(visitor_meta_to_meta(&visitor.base, data))?;
             }
         }
@@ -212,6 +307,10 @@ impl<'a> Context<'a> {
 
         for visitor in self.visitors {
             if let Some(data) = visitor.get(item) {
+                if !visitor.is_documented(data) {
+                    continue;
+                }
+
                 out.try_push(visitor_meta_to_meta(&visitor.base, data))?;
             }
         }
@@ -268,6 +367,7 @@ impl<'a> Context<'a> {
             meta::Kind::Macro => Kind::Macro,
             meta::Kind::Module { .. } => Kind::Module,
             meta::Kind::Trait { .. } => Kind::Trait,
+            meta::Kind::Alias(alias) => Kind::Alias(&alias.to),
             _ => Kind::Unsupported,
         };
 
@@ -281,6 +381,13 @@ impl<'a> Context<'a> {
         })
     }
 
+    /// Get the location of the doc comment of the given item, if it was
+    /// declared in a source.
+    #[cfg(feature = "cli")]
+    pub(crate) fn doc_location(&self, item: &Item) -> Option<Location> {
+        self.visitors.iter().find_map(|v| v.get(item)?.doc_location)
+    }
+
     /// Iterate over known modules.
     pub(crate) fn iter_modules(&self) -> impl IntoIterator<Item = alloc::Result<ItemBuf>> + '_ {
         let visitors = self
@@ -304,6 +411,10 @@ fn visitor_to_associated(visitor: &Visitor, hash: Hash) -> impl Iterator<Item =
         a.iter().flat_map(move |hash| {
             let data = visitor.data.get(hash)?;
 
+            if !visitor.is_documented(data) {
+                return None;
+            }
+
             let (associated, trait_hash, signature) = match &data.kind {
                 Some(meta::Kind::Function {
                     associated,
@@ -357,6 +468,17 @@ fn context_to_associated(context: &crate::Context, hash: Hash) -> Option<Assoc<'
                 docs: meta.docs.lines(),
             }))
         }
+        meta::Kind::Const => {
+            let name = meta.item.as_deref()?.last()?.as_str()?;
+            let value = context.get_const_value(meta.hash)?;
+
+            Some(Assoc::Const(AssocConst {
+                name,
+                value,
+                deprecated: meta.deprecated.as_deref(),
+                docs: meta.docs.lines(),
+            }))
+        }
         meta::Kind::Function {
             associated: Some(associated),
             trait_hash,
@@ -0,0 +1,74 @@
+#[cfg(feature = "cli")]
+use anyhow::bail;
+use anyhow::Result;
+use relative_path::RelativePathBuf;
+
+use crate::alloc::prelude::*;
+use crate::alloc::{BTreeMap, String};
+use crate::doc::path::{build_item_path, ItemKind};
+use crate::Item;
+
+/// A mapping from crate names to the URL where documentation for that crate
+/// has been published.
+///
+/// Items in an external crate are not documented locally, and links to them
+/// resolve to the external URL instead.
+#[derive(Default)]
+pub(crate) struct Externs {
+    urls: BTreeMap<String, String>,
+}
+
+impl Externs {
+    /// Construct a new empty extern map.
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse and insert an extern in the form of `name=url`.
+    #[cfg(feature = "cli")]
+    pub(crate) fn parse(&mut self, value: &str) -> Result<()> {
+        let Some((name, url)) = value.split_once('=') else {
+            bail!("Expected extern in the form `name=url`, but got `{value}`");
+        };
+
+        let name = name.trim();
+        let url = url.trim();
+
+        if name.is_empty() || url.is_empty() {
+            bail!("Expected extern in the form `name=url`, but got `{value}`");
+        }
+
+        self.insert(name, url)
+    }
+
+    /// Insert a crate with the given name which is documented at `url`.
+    #[cfg(feature = "cli")]
+    pub(crate) fn insert(&mut self, name: &str, url: &str) -> Result<()> {
+        let url = url.trim_end_matches('/');
+        self.urls
+            .try_insert(name.try_to_owned()?, url.try_to_owned()?)?;
+        Ok(())
+    }
+
+    /// Test if the given item belongs to an external crate.
+    pub(crate) fn contains(&self, item: &Item) -> bool {
+        self.base_url(item).is_some()
+    }
+
+    /// Get the external URL for the given item, if it belongs to an external
+    /// crate.
+    pub(crate) fn url(&self, name: &str, item: &Item, kind: ItemKind) -> Result<Option<String>> {
+        let Some(base) = self.base_url(item) else {
+            return Ok(None);
+        };
+
+        let mut path = RelativePathBuf::new();
+        build_item_path(name, item, kind, &mut path)?;
+        Ok(Some(try_format!("{base}/{path}")))
+    }
+
+    fn base_url(&self, item: &Item) -> Option<&str> {
+        let name = item.as_crate()?;
+        Some(self.urls.get(name)?.as_str())
+    }
+}
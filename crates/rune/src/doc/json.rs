@@ -0,0 +1,465 @@
+//! Output of documentation as JSON.
+//!
+//! The schema is versioned through [`FORMAT_VERSION`], which is incremented
+//! whenever a change is made which is not backwards compatible.
+
+use rust_alloc::format;
+use rust_alloc::string::String;
+use rust_alloc::vec::Vec;
+
+use anyhow::{anyhow, Context as _, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::alloc::prelude::*;
+use crate::alloc::{HashSet, VecDeque};
+use crate::compile::meta;
+use crate::doc::context::{Assoc, AssocFnKind, Kind, Meta, Signature};
+use crate::doc::path::ItemKind;
+use crate::doc::{Context, Externs, Visitor};
+use crate::runtime::OwnedTuple;
+use crate::{Hash, TypeHash};
+
+/// The current version of the JSON documentation format.
+pub(crate) const FORMAT_VERSION: u32 = 1;
+
+/// The root of a JSON documentation document.
+///
+/// This is the same document which is written by `rune doc --output-format
+/// json`, and can be serialized using any serde format.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Document {
+    /// The version of the format.
+    pub(crate) format_version: u32,
+    /// Documented modules.
+    pub(crate) modules: Vec<Module>,
+}
+
+/// A documented module.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Module {
+    /// The full path of the module.
+    pub(crate) path: String,
+    /// Documentation lines.
+    pub(crate) docs: Vec<String>,
+    /// Items declared in the module.
+    pub(crate) items: Vec<Item>,
+}
+
+/// The kind of a documented item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum ItemType {
+    Module,
+    Type,
+    Struct,
+    Enum,
+    Macro,
+    Function,
+    Trait,
+    Const,
+    Reexport,
+}
+
+/// A documented item.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Item {
+    /// The full path of the item.
+    pub(crate) path: String,
+    /// The kind of the item.
+    pub(crate) kind: ItemType,
+    /// Documentation lines.
+    pub(crate) docs: Vec<String>,
+    /// Deprecation message, if the item is deprecated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) deprecated: Option<String>,
+    /// The signature of the item, if it's a function.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) function: Option<Function>,
+    /// The path of the item being re-exported, if it's a re-export.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) target: Option<String>,
+    /// Named fields of the item, if it's a struct defined in a source.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) fields: Vec<Field>,
+    /// Items associated with the item, if it's a type.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) associated: Vec<Associated>,
+}
+
+/// A named field of a struct.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Field {
+    pub(crate) name: String,
+    /// Documentation lines.
+    pub(crate) docs: Vec<String>,
+}
+
+/// The signature of a function.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Function {
+    pub(crate) is_async: bool,
+    /// Arguments of the function, or `None` if they are not known.
+    #[serde(default)]
+    pub(crate) arguments: Option<Vec<Argument>>,
+    /// The return type of the function, or `None` if it returns `()`.
+    #[serde(default)]
+    pub(crate) return_type: Option<Type>,
+}
+
+/// A function argument.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Argument {
+    pub(crate) name: String,
+    #[serde(rename = "type")]
+    pub(crate) ty: Type,
+}
+
+/// A reference to a type.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Type {
+    /// The full path of the type, or `None` if it can be any type.
+    #[serde(default)]
+    pub(crate) path: Option<String>,
+    /// The external URL documenting the type, if it belongs to an extern.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) url: Option<String>,
+    /// Generic parameters of the type.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) generics: Vec<Type>,
+}
+
+/// An item associated with a type.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub(crate) enum Associated {
+    /// An enum variant.
+    Variant { name: String, docs: Vec<String> },
+    /// An associated constant.
+    Const {
+        name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        deprecated: Option<String>,
+        docs: Vec<String>,
+    },
+    /// An associated function or method.
+    Method {
+        name: String,
+        /// If the function takes `self`.
+        instance: bool,
+        function: Function,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        deprecated: Option<String>,
+        docs: Vec<String>,
+    },
+    /// A protocol implementation.
+    Protocol {
+        protocol: String,
+        /// The field the protocol applies to, if it's a field function.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        field: Option<String>,
+        /// The index the protocol applies to, if it's an index function.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        index: Option<usize>,
+        function: Function,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        deprecated: Option<String>,
+        docs: Vec<String>,
+    },
+}
+
+impl Document {
+    /// Build documentation for everything installed in the given context.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::doc::Document;
+    ///
+    /// let context = rune::Context::with_default_modules()?;
+    /// let document = Document::from_context(&context)?;
+    ///
+    /// let json = serde_json::to_string(&document)?;
+    /// assert!(json.contains("\"::std::option\""));
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    pub fn from_context(context: &crate::Context) -> Result<Self> {
+        build("root", Some(context), &[], &Externs::new())
+    }
+}
+
+/// Build a JSON documentation document based on the given context and
+/// visitors.
+///
+/// Items in crates which are part of `externs` are not documented, and
+/// references to them include their external URL instead.
+pub(crate) fn build(
+    name: &str,
+    context: Option<&crate::Context>,
+    visitors: &[Visitor],
+    externs: &Externs,
+) -> Result<Document> {
+    let context = Context::new(context, visitors);
+
+    let cx = Ctxt {
+        name,
+        context: &context,
+        externs,
+    };
+
+    let mut initial = Vec::new();
+
+    for item in context.iter_modules() {
+        let item = item?;
+
+        if externs.contains(&item) {
+            continue;
+        }
+
+        let meta = context
+            .meta(&item)?
+            .into_iter()
+            .find(|m| matches!(&m.kind, Kind::Module))
+            .with_context(|| anyhow!("Missing meta for {item}"))?;
+
+        initial.push(meta);
+    }
+
+    initial.sort_by_key(|meta| meta.item);
+
+    let mut queue = initial.into_iter().try_collect::<VecDeque<_>>()?;
+    let mut visited = HashSet::new();
+    let mut modules = Vec::new();
+
+    while let Some(meta) = queue.pop_front() {
+        if !visited.try_insert(meta.hash)? {
+            continue;
+        }
+
+        let mut items = Vec::new();
+
+        for (_, name) in context.iter_components(meta.item)? {
+            let item = meta.item.join([name])?;
+
+            for m in context.meta(&item)? {
+                let item = match m.kind {
+                    Kind::Module => {
+                        // Skip over crate items, since they are added separately.
+                        if meta.item.is_empty() && m.item.as_crate().is_some() {
+                            continue;
+                        }
+
+                        queue.try_push_back(m)?;
+                        cx.item(m, ItemType::Module)?
+                    }
+                    Kind::Type => cx.type_item(m, ItemType::Type)?,
+                    Kind::Struct => cx.type_item(m, ItemType::Struct)?,
+                    Kind::Enum => cx.type_item(m, ItemType::Enum)?,
+                    Kind::Trait => cx.type_item(m, ItemType::Trait)?,
+                    Kind::Macro => cx.item(m, ItemType::Macro)?,
+                    Kind::Const(..) => cx.item(m, ItemType::Const)?,
+                    Kind::Function(f) => {
+                        if matches!(f.signature, Signature::Instance) {
+                            continue;
+                        }
+
+                        let mut item = cx.item(m, ItemType::Function)?;
+                        item.function =
+                            Some(cx.function(f.is_async, f.arguments, f.return_type)?);
+                        item
+                    }
+                    Kind::Alias(to) => {
+                        let mut item = cx.item(m, ItemType::Reexport)?;
+                        item.target = Some(format!("{to}"));
+                        item
+                    }
+                    _ => continue,
+                };
+
+                items.push(item);
+            }
+        }
+
+        modules.push(Module {
+            path: format!("{}", meta.item),
+            docs: docs(meta.docs),
+            items,
+        });
+    }
+
+    Ok(Document {
+        format_version: FORMAT_VERSION,
+        modules,
+    })
+}
+
+struct Ctxt<'a, 'm> {
+    name: &'a str,
+    context: &'a Context<'m>,
+    externs: &'a Externs,
+}
+
+impl<'m> Ctxt<'_, 'm> {
+    fn item(&self, meta: Meta<'m>, kind: ItemType) -> Result<Item> {
+        Ok(Item {
+            path: format!("{}", meta.item),
+            kind,
+            docs: docs(meta.docs),
+            deprecated: meta.deprecated.map(String::from),
+            function: None,
+            target: None,
+            fields: Vec::new(),
+            associated: Vec::new(),
+        })
+    }
+
+    fn type_item(&self, meta: Meta<'m>, kind: ItemType) -> Result<Item> {
+        let mut item = self.item(meta, kind)?;
+
+        for field in self.context.fields(meta.hash)? {
+            item.fields.push(Field {
+                name: String::from(field.name),
+                docs: docs(field.docs),
+            });
+        }
+
+        for hash in self.context.associated(meta.hash) {
+            for assoc in self.context.associated_meta(hash) {
+                item.associated.push(self.associated(assoc)?);
+            }
+        }
+
+        Ok(item)
+    }
+
+    fn associated(&self, assoc: Assoc<'_>) -> Result<Associated> {
+        let assoc = match assoc {
+            Assoc::Variant(variant) => Associated::Variant {
+                name: String::from(variant.name),
+                docs: docs(variant.docs),
+            },
+            Assoc::Const(constant) => Associated::Const {
+                name: String::from(constant.name),
+                deprecated: constant.deprecated.map(String::from),
+                docs: docs(constant.docs),
+            },
+            Assoc::Fn(assoc) => {
+                let function = self.function(assoc.is_async, assoc.arguments, assoc.return_type)?;
+                let deprecated = assoc.deprecated.map(String::from);
+                let docs = docs(assoc.docs);
+
+                let (protocol, field, index) = match assoc.kind {
+                    AssocFnKind::Method(_, name, sig) => {
+                        return Ok(Associated::Method {
+                            name: String::from(name),
+                            instance: matches!(sig, Signature::Instance),
+                            function,
+                            deprecated,
+                            docs,
+                        });
+                    }
+                    AssocFnKind::Protocol(protocol) => (protocol, None, None),
+                    AssocFnKind::FieldFn(protocol, field) => {
+                        (protocol, Some(String::from(field)), None)
+                    }
+                    AssocFnKind::IndexFn(protocol, index) => (protocol, None, Some(index)),
+                };
+
+                Associated::Protocol {
+                    protocol: String::from(protocol.name),
+                    field,
+                    index,
+                    function,
+                    deprecated,
+                    docs,
+                }
+            }
+        };
+
+        Ok(assoc)
+    }
+
+    fn function(
+        &self,
+        is_async: bool,
+        arguments: Option<&[meta::DocArgument]>,
+        return_type: &meta::DocType,
+    ) -> Result<Function> {
+        let arguments = match arguments {
+            Some(arguments) => {
+                let mut out = Vec::with_capacity(arguments.len());
+
+                for arg in arguments {
+                    out.push(Argument {
+                        name: format!("{}", arg.name),
+                        ty: self.ty(arg.base, &arg.generics)?,
+                    });
+                }
+
+                Some(out)
+            }
+            None => None,
+        };
+
+        let return_type = if OwnedTuple::HASH == return_type.base && return_type.generics.is_empty()
+        {
+            None
+        } else {
+            Some(self.ty(return_type.base, &return_type.generics)?)
+        };
+
+        Ok(Function {
+            is_async,
+            arguments,
+            return_type,
+        })
+    }
+
+    fn ty(&self, hash: Hash, generics: &[meta::DocType]) -> Result<Type> {
+        let mut ty = Type {
+            path: None,
+            url: None,
+            generics: Vec::with_capacity(generics.len()),
+        };
+
+        for generic in generics {
+            ty.generics.push(self.ty(generic.base, &generic.generics)?);
+        }
+
+        let Some(hash) = hash.as_non_empty() else {
+            return Ok(ty);
+        };
+
+        for meta in self.context.meta_by_hash(hash)? {
+            let kind = match meta.kind {
+                Kind::Type => ItemKind::Type,
+                Kind::Struct => ItemKind::Struct,
+                Kind::Enum => ItemKind::Enum,
+                Kind::Trait => ItemKind::Trait,
+                Kind::Function(..) => ItemKind::Function,
+                _ => continue,
+            };
+
+            ty.path = Some(format!("{}", meta.item));
+
+            if let Some(url) = self.externs.url(self.name, meta.item, kind)? {
+                ty.url = Some(url.into_std());
+            }
+
+            break;
+        }
+
+        Ok(ty)
+    }
+}
+
+fn docs<S>(docs: &[S]) -> Vec<String>
+where
+    S: AsRef<str>,
+{
+    docs.iter()
+        .map(|line| {
+            let line = line.as_ref();
+            String::from(line.strip_prefix(' ').unwrap_or(line))
+        })
+        .collect()
+}
@@ -38,6 +38,7 @@ impl StrWrite for StringWriter<'_> {
 
 struct Writer<'a, 'o, I> {
     syntax_set: Option<&'a SyntaxSet>,
+    playground: Option<&'a str>,
     iter: I,
     out: StringWriter<'o>,
     tests: Option<&'o mut Vec<(String, TestParams)>>,
@@ -45,6 +46,9 @@ struct Writer<'a, 'o, I> {
         Option<(&'a SyntaxSet, &'a SyntaxReference)>,
         Option<TestParams>,
     )>,
+    /// The full code of the current rune code block, which is sent to the
+    /// playground.
+    playground_code: Option<String>,
     table_state: TableState,
     table_alignments: Vec<Alignment>,
     table_cell_index: usize,
@@ -74,7 +78,8 @@ where
                     if let Some((syntax, params)) = self.codeblock {
                         let mut string = String::new();
 
-                        let s = (self.tests.is_some() && params.is_some()).then_some(&mut string);
+                        let capture = self.tests.is_some() || self.playground_code.is_some();
+                        let s = (capture && params.is_some()).then_some(&mut string);
 
                         let html = match syntax {
                             Some((syntax_set, syntax)) => {
@@ -83,6 +88,10 @@ where
                             None => render_code_without_syntax(text.lines(), s)?,
                         };
 
+                        if let Some(code) = self.playground_code.as_mut() {
+                            code.try_push_str(&string)?;
+                        }
+
                         if let Some(params) = params {
                             if let Some(tests) = self.tests.as_mut() {
                                 tests.try_push((string, params))?;
@@ -221,6 +230,11 @@ where
             Tag::CodeBlock(kind) => {
                 self.write("<pre><code class=\"language-")?;
                 let (lang, syntax, params) = self.find_syntax(&kind);
+
+                if self.playground.is_some() && params.is_some() {
+                    self.playground_code = Some(String::new());
+                }
+
                 self.codeblock = Some((syntax, params));
                 escape_href(&mut self.out, lang)?;
                 self.write("\">")?;
@@ -414,6 +428,14 @@ where
             TagEnd::CodeBlock => {
                 self.write("</code></pre>")?;
                 self.codeblock = None;
+
+                if let (Some(url), Some(code)) = (self.playground, self.playground_code.take()) {
+                    self.write("<a class=\"playground-run\" href=\"")?;
+                    escape_href(&mut self.out, url)?;
+                    self.write("#code=")?;
+                    encode_fragment(self.out.string, &code)?;
+                    self.write("\" target=\"_blank\">Run</a>")?;
+                }
             }
             TagEnd::List(true) => {
                 self.write("</ol>")?;
@@ -490,8 +512,12 @@ where
 }
 
 /// Process markdown html and captures tests.
+///
+/// If a `playground` URL is specified, rune code blocks are followed by a link
+/// which opens the code in the playground.
 pub(crate) fn push_html<'a, I>(
     syntax_set: Option<&'a SyntaxSet>,
+    playground: Option<&'a str>,
     string: &'a mut String,
     iter: I,
     tests: Option<&'a mut Vec<(String, TestParams)>>,
@@ -501,10 +527,12 @@ where
 {
     let writer = Writer {
         syntax_set,
+        playground,
         iter,
         out: StringWriter { string },
         tests,
         codeblock: None,
+        playground_code: None,
         table_state: TableState::Head,
         table_alignments: try_vec![],
         table_cell_index: 0,
@@ -534,9 +562,9 @@ where
         let line = line.as_ref();
         let line = line.strip_prefix(' ').unwrap_or(line);
 
-        if line.starts_with('#') {
+        if let Some(hidden) = hidden_line(line) {
             if let Some(o) = out.as_mut() {
-                o.try_push_str(line.trim_start_matches('#'))?;
+                o.try_push_str(hidden)?;
                 o.try_push('\n')?;
             }
 
@@ -571,9 +599,9 @@ where
         let line = line.as_ref();
         let line = line.strip_prefix(' ').unwrap_or(line);
 
-        if line.starts_with('#') {
+        if let Some(hidden) = hidden_line(line) {
             if let Some(o) = out.as_mut() {
-                o.try_push_str(line.trim_start_matches('#'))?;
+                o.try_push_str(hidden)?;
                 o.try_push('\n')?;
             }
 
@@ -591,3 +619,31 @@ where
 
     Ok(buf)
 }
+
+/// Percent-encode code so that it can be used in the fragment of a URL.
+///
+/// Everything except unreserved characters is encoded, so that the fragment
+/// survives being copied around.
+pub(super) fn encode_fragment(out: &mut String, code: &str) -> alloc::Result<()> {
+    for b in code.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b'~') {
+            out.try_push(char::from(b))?;
+        } else {
+            write!(out, "%{b:02X}")?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Test if the given line of code is hidden, returning its content if it is.
+///
+/// Like in rustdoc, lines which consist of a single `#` or which start with
+/// `# ` are hidden. This leaves attributes and object literals alone.
+fn hidden_line(line: &str) -> Option<&str> {
+    if line == "#" {
+        return Some("");
+    }
+
+    line.strip_prefix("# ")
+}
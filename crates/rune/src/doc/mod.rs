@@ -1,14 +1,25 @@
 //! Helper to generate documentation from a context.
 
-#[cfg(feature = "cli")]
 mod context;
+pub(crate) use self::context::Context;
 #[cfg(feature = "cli")]
-pub(crate) use self::context::{Context, Function, Kind, Meta, Signature};
+pub(crate) use self::context::{Function, Kind, Meta, Signature};
 
 #[cfg(feature = "cli")]
 mod artifacts;
 #[cfg(feature = "cli")]
-pub(crate) use self::artifacts::{Artifacts, TestKind, TestParams};
+pub(crate) use self::artifacts::{Artifacts, Test, TestKind, TestParams};
+
+mod externs;
+pub(crate) use self::externs::Externs;
+
+mod path;
+
+pub(crate) mod json;
+pub use self::json::Document;
+
+mod search;
+pub use self::search::{build_index, SearchIndex};
 
 #[cfg(feature = "cli")]
 mod templating;
@@ -18,10 +29,12 @@ mod build;
 #[cfg(feature = "cli")]
 pub(crate) use self::build::build;
 
-#[cfg(feature = "languageserver")]
 mod visitor;
-#[cfg(feature = "languageserver")]
-pub(crate) use self::visitor::{Visitor, VisitorData};
+pub use self::visitor::Visitor;
+pub(crate) use self::visitor::{PackageMeta, VisitorData};
 
 #[cfg(feature = "cli")]
 pub(crate) mod markdown;
+
+#[cfg(all(test, feature = "cli"))]
+mod tests;
@@ -0,0 +1,68 @@
+//! Paths of documented items.
+
+use core::fmt;
+
+use anyhow::Result;
+use relative_path::RelativePathBuf;
+
+use crate::item::ComponentRef;
+use crate::Item;
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum ItemKind {
+    Type,
+    Struct,
+    Enum,
+    Module,
+    Macro,
+    Function,
+    Trait,
+}
+
+impl fmt::Display for ItemKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ItemKind::Type => "type".fmt(f),
+            ItemKind::Struct => "struct".fmt(f),
+            ItemKind::Enum => "enum".fmt(f),
+            ItemKind::Module => "module".fmt(f),
+            ItemKind::Macro => "macro".fmt(f),
+            ItemKind::Function => "function".fmt(f),
+            ItemKind::Trait => "trait".fmt(f),
+        }
+    }
+}
+
+/// Helper for building an item path.
+pub(crate) fn build_item_path(
+    name: &str,
+    item: &Item,
+    kind: ItemKind,
+    path: &mut RelativePathBuf,
+) -> Result<()> {
+    if item.is_empty() {
+        path.push(name);
+    } else {
+        for c in item.iter() {
+            let string = match c {
+                ComponentRef::Crate(string) => string,
+                ComponentRef::Str(string) => string,
+                _ => continue,
+            };
+
+            path.push(string);
+        }
+    }
+
+    path.set_extension(match kind {
+        ItemKind::Type => "type.html",
+        ItemKind::Struct => "struct.html",
+        ItemKind::Enum => "enum.html",
+        ItemKind::Module => "module.html",
+        ItemKind::Macro => "macro.html",
+        ItemKind::Function => "fn.html",
+        ItemKind::Trait => "trait.html",
+    });
+
+    Ok(())
+}
@@ -0,0 +1,268 @@
+//! A search index over documented items.
+//!
+//! This is the index which is embedded into generated HTML documentation, and
+//! can be serialized using any serde format.
+
+use rust_alloc::format;
+use rust_alloc::string::String;
+use rust_alloc::vec::Vec;
+
+use anyhow::{anyhow, Context as _, Result};
+use relative_path::RelativePathBuf;
+use serde::{Deserialize, Serialize};
+
+use crate::alloc::prelude::*;
+use crate::alloc::{HashSet, VecDeque};
+use crate::doc::context::{Assoc, AssocFnKind, Kind, Meta, Signature};
+use crate::doc::path::{build_item_path, ItemKind};
+use crate::doc::{Context, Externs, Visitor};
+use crate::item::ComponentRef;
+use crate::Item;
+
+/// A search index over documented items.
+///
+/// This is the same index which is embedded into HTML documentation generated
+/// by `rune doc`.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct SearchIndex {
+    /// Entries in the index.
+    pub(crate) entries: Vec<SearchEntry>,
+}
+
+/// An entry in the search index.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SearchEntry {
+    /// The full path of the item.
+    pub(crate) path: String,
+    /// The kind of the item.
+    pub(crate) kind: SearchKind,
+    /// The location of the documentation for the item, relative to the root
+    /// of the generated documentation.
+    pub(crate) url: String,
+    /// The first line of documentation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) summary: Option<String>,
+    /// Normalized lowercase tokens which queries are matched against.
+    pub(crate) tokens: Vec<String>,
+}
+
+/// The kind of an entry in the search index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum SearchKind {
+    Module,
+    Type,
+    Struct,
+    Enum,
+    Macro,
+    Function,
+    Trait,
+    Method,
+    Constant,
+    Variant,
+}
+
+/// Build a search index for everything installed in the given context and
+/// collected by the given visitors.
+///
+/// # Examples
+///
+/// ```
+/// let context = rune::Context::with_default_modules()?;
+/// let index = rune::doc::build_index(&context, &[])?;
+///
+/// let json = serde_json::to_string(&index)?;
+/// assert!(json.contains("\"::std::option\""));
+/// # Ok::<_, rune::support::Error>(())
+/// ```
+pub fn build_index(context: &crate::Context, visitors: &[Visitor]) -> Result<SearchIndex> {
+    let context = Context::new(Some(context), visitors);
+    build("root", &context, &Externs::new())
+}
+
+/// Build a search index based on the given documentation context.
+///
+/// Items in crates which are part of `externs` are not indexed.
+pub(crate) fn build(name: &str, context: &Context<'_>, externs: &Externs) -> Result<SearchIndex> {
+    let mut cx = Ctxt {
+        name,
+        context,
+        entries: Vec::new(),
+    };
+
+    let mut initial = Vec::new();
+
+    for item in context.iter_modules() {
+        let item = item?;
+
+        if externs.contains(&item) {
+            continue;
+        }
+
+        let meta = context
+            .meta(&item)?
+            .into_iter()
+            .find(|m| matches!(&m.kind, Kind::Module))
+            .with_context(|| anyhow!("Missing meta for {item}"))?;
+
+        initial.push(meta);
+    }
+
+    initial.sort_by_key(|meta| meta.item);
+
+    let mut queue = initial.into_iter().try_collect::<VecDeque<_>>()?;
+    let mut visited = HashSet::new();
+
+    while let Some(meta) = queue.pop_front() {
+        if !visited.try_insert(meta.hash)? {
+            continue;
+        }
+
+        cx.item(meta, ItemKind::Module, SearchKind::Module)?;
+
+        for (_, name) in context.iter_components(meta.item)? {
+            let item = meta.item.join([name])?;
+
+            for m in context.meta(&item)? {
+                match m.kind {
+                    Kind::Module => {
+                        // Skip over crate items, since they are added separately.
+                        if meta.item.is_empty() && m.item.as_crate().is_some() {
+                            continue;
+                        }
+
+                        queue.try_push_back(m)?;
+                    }
+                    Kind::Type => cx.type_item(m, ItemKind::Type, SearchKind::Type)?,
+                    Kind::Struct => cx.type_item(m, ItemKind::Struct, SearchKind::Struct)?,
+                    Kind::Enum => cx.type_item(m, ItemKind::Enum, SearchKind::Enum)?,
+                    Kind::Trait => cx.type_item(m, ItemKind::Trait, SearchKind::Trait)?,
+                    Kind::Macro => cx.item(m, ItemKind::Macro, SearchKind::Macro)?,
+                    Kind::Function(f) if !matches!(f.signature, Signature::Instance) => {
+                        cx.item(m, ItemKind::Function, SearchKind::Function)?;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    Ok(SearchIndex {
+        entries: cx.entries,
+    })
+}
+
+struct Ctxt<'a, 'm> {
+    name: &'a str,
+    context: &'a Context<'m>,
+    entries: Vec<SearchEntry>,
+}
+
+impl Ctxt<'_, '_> {
+    /// Add an item which has its own documentation page, returning the
+    /// location of that page.
+    fn item(&mut self, meta: Meta<'_>, item_kind: ItemKind, kind: SearchKind) -> Result<String> {
+        let mut url = RelativePathBuf::new();
+        build_item_path(self.name, meta.item, item_kind, &mut url)?;
+        let url = String::from(url.as_str());
+        self.push(meta.item, kind, url.clone(), meta.docs)?;
+        Ok(url)
+    }
+
+    /// Add a type together with everything associated with it, which is
+    /// indexed under the path of the type.
+    fn type_item(&mut self, meta: Meta<'_>, item_kind: ItemKind, kind: SearchKind) -> Result<()> {
+        let url = self.item(meta, item_kind, kind)?;
+        let context = self.context;
+
+        for hash in context.associated(meta.hash) {
+            for assoc in context.associated_meta(hash) {
+                let (kind, anchor, name, docs) = match assoc {
+                    Assoc::Variant(variant) => {
+                        (SearchKind::Variant, "variant", variant.name, variant.docs)
+                    }
+                    Assoc::Const(constant) => (
+                        SearchKind::Constant,
+                        "constant",
+                        constant.name,
+                        constant.docs,
+                    ),
+                    Assoc::Fn(assoc) => match assoc.kind {
+                        AssocFnKind::Method(_, name, _) => {
+                            (SearchKind::Method, "method", name, assoc.docs)
+                        }
+                        _ => continue,
+                    },
+                };
+
+                let item = meta.item.join([name])?;
+                self.push(&item, kind, format!("{url}#{anchor}.{name}"), docs)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn push<S>(&mut self, item: &Item, kind: SearchKind, url: String, docs: &[S]) -> Result<()>
+    where
+        S: AsRef<str>,
+    {
+        let summary = docs
+            .first()
+            .map(|line| line.as_ref().trim())
+            .filter(|line| !line.is_empty());
+
+        self.entries.push(SearchEntry {
+            path: format!("{item}"),
+            kind,
+            url,
+            summary: summary.map(String::from),
+            tokens: tokens(item, summary),
+        });
+
+        Ok(())
+    }
+}
+
+/// Normalize the components of an item and the words of its summary into
+/// unique lowercase tokens.
+fn tokens(item: &Item, summary: Option<&str>) -> Vec<String> {
+    fn push(tokens: &mut Vec<String>, token: &str) {
+        if token.is_empty() {
+            return;
+        }
+
+        let token = token.to_lowercase();
+
+        if !tokens.contains(&token) {
+            tokens.push(token);
+        }
+    }
+
+    let mut tokens = Vec::new();
+
+    for c in item.iter() {
+        let (ComponentRef::Crate(name) | ComponentRef::Str(name)) = c else {
+            continue;
+        };
+
+        push(&mut tokens, name);
+
+        if name.contains('_') {
+            for part in name.split('_') {
+                push(&mut tokens, part);
+            }
+        }
+    }
+
+    if let Some(summary) = summary {
+        for word in summary.split(|c: char| !c.is_alphanumeric()) {
+            // Short words are rarely useful to search for.
+            if word.chars().count() >= 3 {
+                push(&mut tokens, word);
+            }
+        }
+    }
+
+    tokens
+}
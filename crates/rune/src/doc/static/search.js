@@ -55,12 +55,31 @@
     let kindToClass = (kind) => {
         switch (kind) {
         case "function":
+        case "method":
             return "fn";
         default:
             return kind;
         }
     };
 
+    // Score a query against the normalized tokens of an entry, which is used
+    // when the query doesn't match the path of the entry.
+    let scoreTokens = (q, tokens) => {
+        let words = q.toLowerCase().split(/[^\p{L}\p{N}_]+/u).filter((w) => w !== "");
+
+        if (words.length === 0) {
+            return null;
+        }
+
+        for (let word of words) {
+            if (!tokens.some((t) => t.startsWith(word))) {
+                return null;
+            }
+        }
+
+        return 0.5;
+    };
+
     let score = (q, item) => {
         let s = 1.0;
         let any = true;
@@ -104,7 +123,7 @@
         return null;
     }
 
-    let makeResult = (child, [path, item, kind, doc]) => {
+    let makeResult = (child, {path, kind, url, summary}) => {
         let linkNode = null;
 
         if (child.firstChild) {
@@ -119,7 +138,7 @@
         linkNode.appendChild($doc.createTextNode(kind));
         linkNode.appendChild($doc.createTextNode(" "));
 
-        let parts = item.split("::");
+        let parts = path.split("::");
 
         if (parts.length !== 0) {
             let last = parts[parts.length - 1];
@@ -137,16 +156,16 @@
             linkNode.appendChild(span);
         }
 
-        if (!!doc) {
+        if (!!summary) {
             linkNode.appendChild($doc.createTextNode(" - "));
 
             let span = $doc.createElement("span");
             span.className = "inline-docs";
-            span.innerHTML = doc;
+            span.appendChild($doc.createTextNode(summary));
             linkNode.appendChild(span);
         }
 
-        linkNode.href = makePath(path);
+        linkNode.href = makePath(url);
     }
 
     let removeClass = (els, className) => {
@@ -214,8 +233,12 @@
 
             let results = [];
 
-            for (let row of w.INDEX) {
-                let s = score(q, row[1]);
+            for (let row of w.INDEX.entries) {
+                let s = score(q, row.path);
+
+                if (s === null) {
+                    s = scoreTokens(q, row.tokens);
+                }
 
                 if (s !== null) {
                     results.push([s, row]);
@@ -270,6 +293,76 @@
             processQuery(q);
         });
 
+        let resultLinks = () => Array.from(searchResults.querySelectorAll(".search-result a"));
+
+        // Move focus between results, wrapping around to the search input.
+        let moveFocus = (offset) => {
+            let links = resultLinks();
+
+            if (links.length === 0) {
+                return;
+            }
+
+            let current = links.indexOf($doc.activeElement);
+            let next = current + offset;
+
+            if (next < 0 || next >= links.length) {
+                input.focus();
+            } else {
+                links[next].focus();
+            }
+        };
+
+        $doc.addEventListener("keydown", (e) => {
+            if (e.ctrlKey || e.altKey || e.metaKey) {
+                return;
+            }
+
+            let inSearch = $doc.activeElement === input || resultLinks().includes($doc.activeElement);
+
+            switch (e.key) {
+            case "s":
+            case "S":
+            case "/":
+                if (!inSearch) {
+                    e.preventDefault();
+                    input.focus();
+                    input.select();
+                }
+                break;
+            case "ArrowDown":
+                if (inSearch) {
+                    e.preventDefault();
+                    moveFocus(1);
+                }
+                break;
+            case "ArrowUp":
+                if (inSearch) {
+                    e.preventDefault();
+                    moveFocus(-1);
+                }
+                break;
+            case "Enter":
+                if ($doc.activeElement === input) {
+                    let [first] = resultLinks();
+
+                    if (!!first) {
+                        e.preventDefault();
+                        first.click();
+                    }
+                }
+                break;
+            case "Escape":
+                if (inSearch) {
+                    e.preventDefault();
+                    input.value = "";
+                    processQuery("");
+                    input.blur();
+                }
+                break;
+            }
+        });
+
         let oldAttribute = null;
         
         input.addEventListener("blur", (e) => {
@@ -0,0 +1,462 @@
+use rust_alloc::string::String;
+use rust_alloc::vec::Vec;
+
+use crate as rune;
+use crate::alloc::prelude::*;
+use crate::doc::json::{self, Document, ItemType};
+use crate::doc::markdown;
+use crate::doc::search::{SearchEntry, SearchIndex, SearchKind};
+use crate::doc::{Artifacts, Externs, PackageMeta, Visitor};
+use crate::support::Result;
+use crate::{Any, Context, ContextError, ItemBuf, Module, Source, Sources};
+
+/// A type which lives in a crate that is documented elsewhere.
+#[derive(Any)]
+#[rune(item = ::ext)]
+struct Foo;
+
+/// Construct a new `Foo`.
+fn make() -> Foo {
+    Foo
+}
+
+fn context() -> Result<Context, ContextError> {
+    let mut ext = Module::with_crate("ext")?;
+    ext.ty::<Foo>()?;
+
+    let mut local = Module::with_crate("local")?;
+    local
+        .function("make", make)
+        .build()?
+        .docs(["Construct a new `Foo`."])?;
+
+    let mut context = Context::new();
+    context.install(ext)?;
+    context.install(local)?;
+    Ok(context)
+}
+
+/// A type with an instance function and an associated constant.
+#[derive(Any)]
+#[rune(item = ::local)]
+struct Counter;
+
+/// Construct a new `Counter`.
+fn counter() -> Counter {
+    Counter
+}
+
+fn search_context() -> Result<Context, ContextError> {
+    let mut local = Module::with_crate("local")?;
+    local.ty::<Counter>()?;
+
+    local
+        .function("counter", counter)
+        .build()?
+        .docs(["Construct a new `Counter`."])?;
+
+    local
+        .associated_function("current_count", |_: &Counter| 0i64)?
+        .docs(["Get the current count."])?;
+
+    local.constant("MAX", 10i64).build_associated::<Counter>()?;
+
+    let mut context = Context::new();
+    context.install(local)?;
+    Ok(context)
+}
+
+fn externs() -> Result<Externs> {
+    let mut externs = Externs::new();
+    externs.parse("ext=https://example.com/docs/")?;
+    Ok(externs)
+}
+
+#[test]
+fn json_roundtrip() -> Result<()> {
+    let context = context()?;
+    let document = json::build("root", Some(&context), &[], &Externs::new())?;
+
+    assert_eq!(document.format_version, json::FORMAT_VERSION);
+
+    let paths = document
+        .modules
+        .iter()
+        .map(|m| m.path.as_str())
+        .collect::<rust_alloc::vec::Vec<_>>();
+
+    assert_eq!(paths, ["::ext", "::local"]);
+
+    let string = serde_json::to_string(&document)?;
+    let decoded: Document = serde_json::from_str(&string)?;
+    assert_eq!(decoded, document);
+    Ok(())
+}
+
+#[test]
+fn json_extern_link() -> Result<()> {
+    let context = context()?;
+    let document = json::build("root", Some(&context), &[], &externs()?)?;
+
+    let [module] = &document.modules[..] else {
+        panic!("expected only the local module, got {:?}", document.modules);
+    };
+
+    assert_eq!(module.path, "::local");
+
+    let item = module
+        .items
+        .iter()
+        .find(|item| item.path == "::local::make")
+        .expect("missing function");
+
+    assert_eq!(item.kind, ItemType::Function);
+    assert_eq!(item.docs, ["Construct a new `Foo`."]);
+
+    let function = item.function.as_ref().expect("missing signature");
+    let return_type = function.return_type.as_ref().expect("missing return type");
+
+    assert_eq!(return_type.path.as_deref(), Some("::ext::Foo"));
+
+    let url = return_type.url.as_deref().expect("missing extern url");
+    assert!(
+        url.starts_with("https://example.com/docs/ext/Foo."),
+        "unexpected url {url}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn html_extern_link() -> Result<()> {
+    let context = context()?;
+
+    let mut artifacts = Artifacts::new();
+    crate::doc::build(
+        "root",
+        &mut artifacts,
+        Some(&context),
+        &[],
+        &externs()?,
+        None,
+    )?;
+
+    let mut function = None;
+
+    for asset in artifacts.assets() {
+        assert!(
+            !asset.path().starts_with("ext"),
+            "extern crate should not be documented, but found {}",
+            asset.path()
+        );
+
+        if asset.path().as_str() == "local/make.fn.html" {
+            function = Some(String::from_utf8(asset.content().to_vec())?);
+        }
+    }
+
+    let function = function.expect("missing function page");
+
+    assert!(
+        function.contains("href=\"https://example.com/docs/ext/Foo."),
+        "missing extern link in:\n{function}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn html_package_metadata() -> Result<()> {
+    let mut visitor = Visitor::new(&ItemBuf::with_crate_item("app", ["main"])?)?;
+
+    visitor.package = Some(PackageMeta {
+        version: Some("1.2.3".try_to_owned()?),
+        description: Some("An <example> package".try_to_owned()?),
+        authors: ["Jane Doe".try_to_owned()?, "John Doe".try_to_owned()?]
+            .into_iter()
+            .try_collect()?,
+    });
+
+    let mut artifacts = Artifacts::new();
+    crate::doc::build(
+        "root",
+        &mut artifacts,
+        None,
+        &[visitor],
+        &Externs::new(),
+        None,
+    )?;
+
+    let index = artifacts
+        .assets()
+        .find(|asset| asset.path().as_str() == "index.html")
+        .expect("missing index");
+
+    let index = String::from_utf8(index.content().to_vec())?;
+
+    assert!(index.contains("1.2.3"), "missing version in:\n{index}");
+    assert!(
+        index.contains("An &lt;example&gt; package"),
+        "missing escaped description in:\n{index}"
+    );
+    assert!(
+        index.contains("Jane Doe, John Doe"),
+        "missing authors in:\n{index}"
+    );
+
+    Ok(())
+}
+
+const WORKSPACE: &str = r#"
+/// Add two numbers or two [`std::string::String`] values.
+pub fn add(a, b) {
+    a + b
+}
+
+/// Only documented when private items are.
+fn private_helper() {}
+
+/// A point in space, which can be collected into a [`Vec`].
+pub struct Point {
+    /// The horizontal coordinate.
+    x,
+    /// The vertical coordinate.
+    y,
+}
+"#;
+
+/// Generate documentation for the fixture workspace, returning the path and
+/// content of every generated page.
+fn workspace_pages(document_private: bool) -> Result<Vec<(String, String)>> {
+    let context = Context::with_default_modules()?;
+
+    let mut sources = Sources::new();
+    sources.insert(Source::new("app", WORKSPACE)?)?;
+
+    let mut visitor = Visitor::new(&ItemBuf::with_crate("app")?)?;
+    visitor.document_private = document_private;
+
+    crate::prepare(&mut sources)
+        .with_context(&context)
+        .with_visitor(&mut visitor)?
+        .build()?;
+
+    let mut artifacts = Artifacts::new();
+    crate::doc::build(
+        "root",
+        &mut artifacts,
+        Some(&context),
+        &[visitor],
+        &Externs::new(),
+        None,
+    )?;
+
+    let mut pages = Vec::new();
+
+    for asset in artifacts.assets() {
+        let path = String::from(asset.path().as_str());
+
+        if path.ends_with(".html") {
+            pages.push((path, String::from_utf8(asset.content().to_vec())?));
+        }
+    }
+
+    Ok(pages)
+}
+
+fn page<'a>(pages: &'a [(String, String)], path: &str) -> &'a str {
+    pages
+        .iter()
+        .find(|(p, _)| p == path)
+        .map(|(_, content)| content.as_str())
+        .unwrap_or_else(|| panic!("missing {path}"))
+}
+
+#[test]
+fn html_workspace_items() -> Result<()> {
+    let pages = workspace_pages(false)?;
+
+    let function = page(&pages, "app/add.fn.html");
+    assert!(
+        function.contains("Add two numbers"),
+        "missing docs in:\n{function}"
+    );
+
+    let point = page(&pages, "app/Point.struct.html");
+
+    for expected in [
+        "id=\"field.x\"",
+        "The horizontal coordinate.",
+        "id=\"field.y\"",
+        "The vertical coordinate.",
+    ] {
+        assert!(point.contains(expected), "missing {expected} in:\n{point}");
+    }
+
+    // Native modules are still documented alongside the workspace.
+    page(&pages, "app.module.html");
+    page(&pages, "std.module.html");
+
+    for (path, content) in &pages {
+        assert!(
+            !path.contains("private_helper") && !content.contains("private_helper"),
+            "private item documented in {path}"
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn html_workspace_native_links() -> Result<()> {
+    let pages = workspace_pages(false)?;
+
+    // Full paths are resolved from the root of a crate.
+    let function = page(&pages, "app/add.fn.html");
+    assert!(
+        function.contains("href=\"../std/string/String."),
+        "missing link to String in:\n{function}"
+    );
+
+    // Names are resolved through the prelude.
+    let point = page(&pages, "app/Point.struct.html");
+    assert!(
+        point.contains("href=\"../std/vec/Vec."),
+        "missing link to Vec in:\n{point}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn html_workspace_private_items() -> Result<()> {
+    let pages = workspace_pages(true)?;
+
+    let helper = page(&pages, "app/private_helper.fn.html");
+    assert!(
+        helper.contains("Only documented when private items are."),
+        "missing docs in:\n{helper}"
+    );
+
+    Ok(())
+}
+
+fn search_entry<'a>(index: &'a SearchIndex, path: &str) -> &'a SearchEntry {
+    index
+        .entries
+        .iter()
+        .find(|entry| entry.path == path)
+        .unwrap_or_else(|| panic!("missing {path} in {:?}", index.entries))
+}
+
+#[test]
+fn search_index() -> Result<()> {
+    let index = crate::doc::build_index(&search_context()?, &[])?;
+
+    let function = search_entry(&index, "::local::counter");
+    assert_eq!(function.kind, SearchKind::Function);
+    assert_eq!(function.url, "local/counter.fn.html");
+    assert_eq!(
+        function.summary.as_deref(),
+        Some("Construct a new `Counter`.")
+    );
+
+    let method = search_entry(&index, "::local::Counter::current_count");
+    assert_eq!(method.kind, SearchKind::Method);
+    assert_eq!(method.url, "local/Counter.type.html#method.current_count");
+    assert_eq!(method.summary.as_deref(), Some("Get the current count."));
+
+    for token in ["counter", "current_count", "current", "count"] {
+        assert!(
+            method.tokens.iter().any(|t| t == token),
+            "missing token {token} in {:?}",
+            method.tokens
+        );
+    }
+
+    let constant = search_entry(&index, "::local::Counter::MAX");
+    assert_eq!(constant.kind, SearchKind::Constant);
+    assert_eq!(constant.url, "local/Counter.type.html#constant.MAX");
+
+    // Instance functions are only indexed under their receiving type.
+    assert!(index
+        .entries
+        .iter()
+        .all(|entry| entry.path != "::local::current_count"));
+
+    let string = serde_json::to_string(&index)?;
+    let decoded: SearchIndex = serde_json::from_str(&string)?;
+    assert_eq!(decoded, index);
+    Ok(())
+}
+
+#[test]
+fn html_search_index() -> Result<()> {
+    let mut artifacts = Artifacts::new();
+    crate::doc::build(
+        "root",
+        &mut artifacts,
+        Some(&search_context()?),
+        &[],
+        &Externs::new(),
+        None,
+    )?;
+
+    let index = artifacts
+        .assets()
+        .find(|asset| asset.path().as_str() == "search-index.json")
+        .expect("missing search index");
+
+    let index: SearchIndex = serde_json::from_slice(index.content())?;
+    assert_eq!(index, crate::doc::build_index(&search_context()?, &[])?);
+    Ok(())
+}
+
+fn render_markdown(input: &str, playground: Option<&str>) -> Result<crate::alloc::String> {
+    let mut o = crate::alloc::String::new();
+    let iter = pulldown_cmark::Parser::new(input);
+    markdown::push_html(None, playground, &mut o, iter, None)?;
+    Ok(o)
+}
+
+#[test]
+fn markdown_hidden_lines() -> Result<()> {
+    let input = "```rune\n# let a = 1;\nlet b = a + 1;\n```\n";
+
+    assert_eq!(
+        render_markdown(input, None)?,
+        "<pre><code class=\"language-rune\">let b = a + 1;\n</code></pre>"
+    );
+
+    // Only a lone `#` or a `# ` prefix hides a line.
+    assert_eq!(
+        render_markdown("```text\n#\n#{a: 1}\n#[test]\n```\n", None)?,
+        "<pre><code class=\"language-text\">#{a: 1}\n#[test]\n</code></pre>"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn markdown_playground_link() -> Result<()> {
+    let input = "```rune\n# let a = 1;\nlet b = a + 1;\n```\n";
+
+    // Hidden lines are still sent to the playground.
+    assert_eq!(
+        render_markdown(input, Some("https://example.com/play"))?,
+        concat!(
+            "<pre><code class=\"language-rune\">let b = a + 1;\n</code></pre>",
+            "<a class=\"playground-run\" href=\"https://example.com/play",
+            "#code=let%20a%20%3D%201%3B%0Alet%20b%20%3D%20a%20%2B%201%3B%0A\" ",
+            "target=\"_blank\">Run</a>"
+        )
+    );
+
+    // Only rune examples can be run.
+    assert_eq!(
+        render_markdown("```text\nhello\n```\n", Some("https://example.com/play"))?,
+        "<pre><code class=\"language-text\">hello\n</code></pre>"
+    );
+
+    Ok(())
+}
@@ -3,35 +3,51 @@ use crate::alloc::hash_map::{self, HashMap};
 use crate::alloc::prelude::*;
 use crate::alloc::{Box, String, Vec};
 use crate::compile::meta;
-use crate::compile::{CompileVisitor, Located, MetaError, MetaRef, Names};
+use crate::compile::{CompileVisitor, Located, Location, MetaError, MetaRef, Names};
 use crate::item::IntoComponent;
 use crate::{Hash, Item, ItemBuf};
 
 pub(crate) struct VisitorData {
-    #[cfg_attr(not(feature = "cli"), allow(dead_code))]
     pub(crate) item: ItemBuf,
-    #[cfg_attr(not(feature = "cli"), allow(dead_code))]
     pub(crate) hash: Hash,
     pub(crate) kind: Option<meta::Kind>,
-    #[cfg_attr(not(feature = "cli"), allow(dead_code))]
+    /// If the item is publicly visible.
+    pub(crate) public: bool,
     pub(crate) deprecated: Option<String>,
     pub(crate) docs: Vec<String>,
+    /// The location of the doc comment of the item, spanning all of its lines.
+    #[cfg_attr(not(feature = "cli"), allow(dead_code))]
+    pub(crate) doc_location: Option<Location>,
     pub(crate) field_docs: HashMap<Box<str>, Vec<String>>,
 }
 
 impl VisitorData {
-    fn new(item: ItemBuf, hash: Hash, kind: Option<meta::Kind>) -> Self {
+    fn new(item: ItemBuf, hash: Hash, kind: Option<meta::Kind>, public: bool) -> Self {
         Self {
             item,
             hash,
             kind,
+            public,
             deprecated: None,
             docs: Vec::new(),
+            doc_location: None,
             field_docs: HashMap::new(),
         }
     }
 }
 
+/// Metadata of the package a [`Visitor`] collects documentation for.
+#[derive(Default)]
+#[cfg_attr(not(feature = "cli"), allow(dead_code))]
+pub(crate) struct PackageMeta {
+    /// The version of the package.
+    pub(crate) version: Option<String>,
+    /// A short description of the package.
+    pub(crate) description: Option<String>,
+    /// The authors of the package.
+    pub(crate) authors: Vec<String>,
+}
+
 /// Visitor used to collect documentation from rune sources.
 pub struct Visitor {
     pub(crate) base: ItemBuf,
@@ -40,6 +56,12 @@ pub struct Visitor {
     pub(crate) item_to_hash: HashMap<ItemBuf, Hash>,
     /// Associated items.
     pub(crate) associated: HashMap<Hash, Vec<Hash>>,
+    /// Metadata of the package being documented, if any.
+    #[cfg_attr(not(feature = "cli"), allow(dead_code))]
+    pub(crate) package: Option<PackageMeta>,
+    /// Include items which are not publicly visible in generated
+    /// documentation.
+    pub(crate) document_private: bool,
 }
 
 impl Visitor {
@@ -51,6 +73,8 @@ impl Visitor {
             data: HashMap::default(),
             item_to_hash: HashMap::new(),
             associated: HashMap::new(),
+            package: None,
+            document_private: false,
         };
 
         this.names.insert(&this.base)?;
@@ -62,7 +86,12 @@ impl Visitor {
 
             this.data.try_insert(
                 hash,
-                VisitorData::new(it.as_item().try_to_owned()?, hash, Some(meta::Kind::Module)),
+                VisitorData::new(
+                    it.as_item().try_to_owned()?,
+                    hash,
+                    Some(meta::Kind::Module),
+                    true,
+                ),
             )?;
 
             this.item_to_hash
@@ -74,12 +103,16 @@ impl Visitor {
     }
 
     /// Get meta by item.
-    #[cfg(feature = "cli")]
     pub(crate) fn get(&self, item: &Item) -> Option<&VisitorData> {
         let hash = self.item_to_hash.get(item)?;
         self.data.get(hash)
     }
 
+    /// Test if the given item should be included in generated documentation.
+    pub(crate) fn is_documented(&self, data: &VisitorData) -> bool {
+        self.document_private || data.public
+    }
+
     /// Get meta by hash.
     pub(crate) fn get_by_hash(&self, hash: Hash) -> Option<&VisitorData> {
         self.data.get(&hash)
@@ -94,7 +127,7 @@ impl Visitor {
         let data = match self.data.entry(hash) {
             hash_map::Entry::Occupied(e) => e.into_mut(),
             hash_map::Entry::Vacant(e) => {
-                e.try_insert(VisitorData::new(item.try_to_owned()?, hash, None))?
+                e.try_insert(VisitorData::new(item.try_to_owned()?, hash, None, false))?
             }
         };
 
@@ -119,10 +152,17 @@ impl CompileVisitor for Visitor {
 
         match self.data.entry(hash) {
             hash_map::Entry::Occupied(e) => {
-                e.into_mut().kind = Some(meta.kind.try_clone()?);
+                let data = e.into_mut();
+                data.kind = Some(meta.kind.try_clone()?);
+                data.public = meta.public;
             }
             hash_map::Entry::Vacant(e) => {
-                e.try_insert(VisitorData::new(item, hash, Some(meta.kind.try_clone()?)))?;
+                e.try_insert(VisitorData::new(
+                    item,
+                    hash,
+                    Some(meta.kind.try_clone()?),
+                    meta.public,
+                ))?;
             }
         }
 
@@ -138,7 +178,7 @@ impl CompileVisitor for Visitor {
 
     fn visit_doc_comment(
         &mut self,
-        _location: &dyn Located,
+        location: &dyn Located,
         item: &Item,
         _: Hash,
         string: &str,
@@ -148,6 +188,15 @@ impl CompileVisitor for Visitor {
         data.docs
             .try_push(string.trim_end_matches(newlines).try_to_owned()?)?;
 
+        let location = location.location();
+
+        data.doc_location = Some(match data.doc_location {
+            Some(existing) if existing.source_id == location.source_id => {
+                Location::new(location.source_id, existing.span.join(location.span))
+            }
+            _ => location,
+        });
+
         Ok(())
     }
 
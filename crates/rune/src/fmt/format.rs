@@ -1,7 +1,7 @@
 use core::mem::take;
 
-use crate::ast::{Delimiter, Kind};
-use crate::compile::Result;
+use crate::ast::{Delimiter, Kind, Span};
+use crate::compile::{Result, TrailingComma};
 use crate::grammar::{classify, object_key, MaybeNode, NodeClass};
 
 use super::{Comments, Formatter, Node, Remaining, Stream, Tree};
@@ -195,6 +195,10 @@ fn item<'a>(fmt: &mut Formatter<'a>, p: &mut Stream<'a>) -> Result<()> {
                 modifiers(fmt, p)?;
                 item_const(fmt, p)?;
             }
+            ItemMacro => {
+                modifiers(fmt, p)?;
+                item_macro(fmt, p)?;
+            }
             _ => return Err(p.expected(Item)),
         }
 
@@ -292,6 +296,39 @@ fn path<'a>(fmt: &mut Formatter<'a>, p: &mut Stream<'a>) -> Result<()> {
     Ok(())
 }
 
+/// Write the comma trailing the last element of a list which is laid out on a
+/// single line.
+///
+/// Returns `true` if the list has any elements.
+fn trailing_comma<'a>(fmt: &mut Formatter<'a>, comma: Remaining<'a>) -> Result<bool> {
+    match fmt.options.trailing_comma {
+        TrailingComma::Always => comma.fmt(fmt),
+        TrailingComma::Never | TrailingComma::Vertical => comma.ignore(fmt),
+    }
+}
+
+/// Write the comma following an element in a list which is laid out
+/// vertically, where `last` indicates if it is the last element.
+fn vertical_comma<'a>(fmt: &mut Formatter<'a>, comma: Remaining<'a>, last: bool) -> Result<()> {
+    let needed = !last || fmt.options.trailing_comma != TrailingComma::Never;
+    comma.write_only_if(fmt, needed)
+}
+
+/// Write comments which are dangling inside of an empty group, like
+/// `{ /* comment */ }`. Line comments force the group to be expanded.
+fn dangling_comments(fmt: &mut Formatter<'_>) -> Result<()> {
+    if !fmt.has_line_comments() {
+        return fmt.comments(Infix);
+    }
+
+    fmt.indent(1)?;
+    fmt.nl(1)?;
+    fmt.comments(Line)?;
+    fmt.nl(1)?;
+    fmt.indent(-1)?;
+    Ok(())
+}
+
 fn path_generics<'a>(fmt: &mut Formatter<'a>, p: &mut Stream<'a>) -> Result<()> {
     p.expect(K![<])?.fmt(fmt)?;
 
@@ -338,7 +375,7 @@ fn pat_array<'a>(fmt: &mut Formatter<'a>, p: &mut Stream<'a>) -> Result<()> {
         fmt.comments(Suffix)?;
     }
 
-    if !comma.ignore(fmt)? {
+    if !trailing_comma(fmt, comma)? {
         fmt.comments(Infix)?;
     }
 
@@ -372,10 +409,10 @@ fn pat_tuple<'a>(fmt: &mut Formatter<'a>, p: &mut Stream<'a>, trailing: bool) ->
     if count == 1 && trailing {
         comma.fmt(fmt)?;
     } else {
-        comma.ignore(fmt)?;
+        trailing_comma(fmt, comma)?;
 
         if count == 0 {
-            fmt.comments(Infix)?;
+            dangling_comments(fmt)?;
         }
     }
 
@@ -405,10 +442,10 @@ fn expr_tuple<'a>(fmt: &mut Formatter<'a>, p: &mut Stream<'a>) -> Result<()> {
     if count == 1 {
         comma.fmt(fmt)?;
     } else {
-        comma.ignore(fmt)?;
+        trailing_comma(fmt, comma)?;
 
         if count == 0 {
-            fmt.comments(Infix)?;
+            dangling_comments(fmt)?;
         }
     }
 
@@ -428,7 +465,7 @@ fn expr_object<'a>(fmt: &mut Formatter<'a>, p: &mut Stream<'a>) -> Result<()> {
     }
 
     let mut count = 0;
-    let mut expanded = fmt.source.is_at_least(p.span(), 80)?;
+    let mut expanded = fmt.source.is_at_least(p.span(), fmt.options.max_width)?;
 
     for node in p.children() {
         if expanded {
@@ -462,11 +499,14 @@ fn expr_object_loose<'a>(fmt: &mut Formatter<'a>, p: &mut Stream<'a>) -> Result<
             p.pump()?.parse(|p| expr(fmt, p))
         })?;
 
-        p.remaining(fmt, K![,])?.fmt(fmt)?;
+        let comma = p.remaining(fmt, K![,])?;
+        let last = !matches!(p.peek(), object_key!());
+        vertical_comma(fmt, comma, last)?;
         fmt.nl(1)?;
     }
 
     fmt.nl(1)?;
+    fmt.comments(Line)?;
     fmt.indent(-1)?;
 
     p.remaining(fmt, K!['}'])?.fmt(fmt)?;
@@ -493,7 +533,7 @@ fn expr_object_compact<'a>(fmt: &mut Formatter<'a>, p: &mut Stream<'a>) -> Resul
         comma = p.remaining(fmt, K![,])?;
     }
 
-    if comma.ignore(fmt)? {
+    if trailing_comma(fmt, comma)? {
         fmt.ws()?;
     } else {
         fmt.comments(Infix)?;
@@ -540,7 +580,7 @@ fn pat_object<'a>(fmt: &mut Formatter<'a>, p: &mut Stream<'a>) -> Result<()> {
         comma = p.remaining(fmt, K![,])?;
     }
 
-    if comma.ignore(fmt)? {
+    if trailing_comma(fmt, comma)? {
         fmt.ws()?;
     } else {
         fmt.comments(Infix)?;
@@ -785,7 +825,7 @@ fn expr_assign<'a>(fmt: &mut Formatter<'a>, p: &mut Stream<'a>) -> Result<()> {
 
 fn exprs<'a>(fmt: &mut Formatter<'a>, p: &mut Stream<'a>, open: Kind, close: Kind) -> Result<()> {
     let mut count = 0;
-    let mut expanded = fmt.source.is_at_least(p.span(), 80)?;
+    let mut expanded = fmt.source.is_at_least(p.span(), fmt.options.max_width)?;
 
     for node in p.children() {
         if expanded {
@@ -815,7 +855,9 @@ fn exprs_loose<'a>(fmt: &mut Formatter<'a>, p: &mut Stream<'a>) -> Result<()> {
     while let MaybeNode::Some(node) = p.eat(Expr) {
         fmt.comments(Line)?;
         node.parse(|p| expr(fmt, p))?;
-        p.remaining(fmt, K![,])?.fmt(fmt)?;
+        let comma = p.remaining(fmt, K![,])?;
+        let last = !matches!(p.peek(), Expr);
+        vertical_comma(fmt, comma, last)?;
         fmt.nl(1)?;
     }
 
@@ -840,7 +882,7 @@ fn exprs_compact<'a>(fmt: &mut Formatter<'a>, p: &mut Stream<'a>) -> Result<()>
         fmt.comments(Suffix)?;
     }
 
-    if !comma.ignore(fmt)? {
+    if !trailing_comma(fmt, comma)? {
         fmt.comments(Infix)?;
     }
 
@@ -1130,7 +1172,7 @@ fn expr_match<'a>(fmt: &mut Formatter<'a>, p: &mut Stream<'a>) -> Result<()> {
         fmt.nl(1)?;
         fmt.indent(-1)?;
     } else {
-        fmt.comments(Infix)?;
+        dangling_comments(fmt)?;
     }
 
     p.one(K!['}']).fmt(fmt)?;
@@ -1182,7 +1224,7 @@ fn expr_closure<'a>(fmt: &mut Formatter<'a>, p: &mut Stream<'a>) -> Result<()> {
 }
 
 fn expr_chain<'a>(fmt: &mut Formatter<'a>, p: &mut Stream<'a>) -> Result<()> {
-    let expanded = fmt.source.is_at_least(p.span(), 80)?;
+    let expanded = fmt.source.is_at_least(p.span(), fmt.options.max_width)?;
 
     // If the first expression *is* small, and there are no other expressions
     // that need indentation in the chain, we can keep it all on one line.
@@ -1203,9 +1245,10 @@ fn expr_chain<'a>(fmt: &mut Formatter<'a>, p: &mut Stream<'a>) -> Result<()> {
     };
 
     let first_is_small = if let Some((_, tail)) = tail {
-        !fmt.source.is_at_least(head.join(tail.head()), 80)?
+        !fmt.source
+            .is_at_least(head.join(tail.head()), fmt.options.max_width)?
     } else {
-        !fmt.source.is_at_least(head, 80)?
+        !fmt.source.is_at_least(head, fmt.options.max_width)?
     };
 
     let from;
@@ -1357,7 +1400,9 @@ fn item_enum<'a>(fmt: &mut Formatter<'a>, p: &mut Stream<'a>) -> Result<()> {
         fmt.comments(Line)?;
         node.parse(|p| variant(fmt, p))?;
         empty = false;
-        p.remaining(fmt, K![,])?.fmt(fmt)?;
+        let comma = p.remaining(fmt, K![,])?;
+        let last = !matches!(p.peek(), Variant);
+        vertical_comma(fmt, comma, last)?;
     }
 
     fmt.comments(Line)?;
@@ -1404,7 +1449,9 @@ fn struct_body<'a>(fmt: &mut Formatter<'a>, p: &mut Stream<'a>) -> Result<()> {
         fmt.nl(1)?;
         fmt.comments(Line)?;
         field.parse(|p| p.pump()?.fmt(fmt))?;
-        p.remaining(fmt, K![,])?.fmt(fmt)?;
+        let comma = p.remaining(fmt, K![,])?;
+        let last = !matches!(p.peek(), Field);
+        vertical_comma(fmt, comma, last)?;
         empty = false;
     }
 
@@ -1432,7 +1479,7 @@ fn tuple_body<'a>(fmt: &mut Formatter<'a>, p: &mut Stream<'a>) -> Result<()> {
         fmt.comments(Suffix)?;
     }
 
-    if !comma.ignore(fmt)? {
+    if !trailing_comma(fmt, comma)? {
         fmt.comments(Infix)?;
     }
 
@@ -1575,6 +1622,30 @@ fn item_const<'a>(fmt: &mut Formatter<'a>, p: &mut Stream<'a>) -> Result<()> {
     Ok(())
 }
 
+fn item_macro<'a>(fmt: &mut Formatter<'a>, p: &mut Stream<'a>) -> Result<()> {
+    p.expect(K![macro])?.fmt(fmt)?;
+    fmt.ws()?;
+    p.pump()?.fmt(fmt)?;
+
+    // NB: The pattern and the body of a macro are arbitrary token streams, so
+    // they are written out verbatim.
+    for (open, close) in [(K!['('], K![')']), (K!['{'], K!['}'])] {
+        if open == K!['{'] {
+            fmt.ws()?;
+        }
+
+        let open = p.expect(open)?;
+        let open_span = open.span();
+        open.fmt(fmt)?;
+        p.expect(TokenStream)?.ignore(fmt)?;
+        let close = p.expect(close)?;
+        fmt.write_verbatim(Span::new(open_span.end, close.span().start))?;
+        close.fmt(fmt)?;
+    }
+
+    Ok(())
+}
+
 fn fn_args<'a>(fmt: &mut Formatter<'a>, p: &mut Stream<'a>) -> Result<()> {
     p.expect(K!['('])?.fmt(fmt)?;
     p.remaining(fmt, K![,])?.ignore(fmt)?;
@@ -1593,7 +1664,7 @@ fn fn_args<'a>(fmt: &mut Formatter<'a>, p: &mut Stream<'a>) -> Result<()> {
         fmt.comments(Suffix)?;
     }
 
-    if !comma.ignore(fmt)? {
+    if !trailing_comma(fmt, comma)? {
         fmt.comments(Infix)?;
     }
 
@@ -1608,6 +1679,9 @@ fn block<'a>(fmt: &mut Formatter<'a>, p: &mut Stream<'a>) -> Result<()> {
 fn block_with<'a>(fmt: &mut Formatter<'a>, p: &mut Stream<'a>, compact: bool) -> Result<()> {
     p.one(K!['{']).fmt(fmt)?;
 
+    // Line comments can't be laid out in a compact block.
+    let compact = compact && !fmt.has_line_comments();
+
     p.expect(BlockBody)?.parse(|p| {
         let expanded = !p.is_eof() || !compact;
 
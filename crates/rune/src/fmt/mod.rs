@@ -11,7 +11,7 @@ use core::fmt;
 use crate::alloc;
 use crate::alloc::prelude::*;
 use crate::ast::Span;
-use crate::compile::{ParseOptionError, Result, WithSpan};
+use crate::compile::{NewlineStyle, ParseOptionError, Result, WithSpan};
 use crate::grammar::{Node, Remaining, Stream, Tree};
 use crate::{Diagnostics, Options, SourceId, Sources};
 
@@ -20,8 +20,8 @@ pub(crate) use self::output::Formatter;
 
 const WS: &str = " ";
 const NL: &str = "\n";
+const CRLF: &str = "\r\n";
 const NL_CHAR: char = '\n';
-const INDENT: &str = "    ";
 
 #[derive(Debug)]
 enum FormatErrorKind {
@@ -173,6 +173,7 @@ pub(crate) fn layout_source_with(
         )?;
     }
 
+    let nl = newline(options.fmt.newline_style, source);
     let mut o = String::new();
 
     {
@@ -182,6 +183,7 @@ pub(crate) fn layout_source_with(
             source_id,
             &mut o,
             &options.fmt,
+            nl,
             diagnostics,
         );
 
@@ -190,10 +192,25 @@ pub(crate) fn layout_source_with(
         o.comments(Comments::Line)?;
     }
 
-    if options.fmt.force_newline && !o.ends_with(NL) {
-        o.try_push_str(NL)
+    if options.fmt.force_newline && !o.ends_with(nl) {
+        o.try_push_str(nl)
             .with_span(Span::new(source.len(), source.len()))?;
     }
 
     Ok(o)
 }
+
+/// Resolve the newline to write for the given style.
+///
+/// The automatic style uses the line ending of the first line in the source,
+/// falling back to `\n` if there is none.
+fn newline(style: NewlineStyle, source: &str) -> &'static str {
+    match style {
+        NewlineStyle::Unix => NL,
+        NewlineStyle::Windows => CRLF,
+        NewlineStyle::Auto => match source.find(NL_CHAR) {
+            Some(n) if source[..n].ends_with('\r') => CRLF,
+            _ => NL,
+        },
+    }
+}
@@ -7,7 +7,7 @@ use crate::compile::{Error, ErrorKind, FmtOptions, Result, WithSpan};
 use crate::grammar::{Ignore, Node, Tree};
 use crate::{Diagnostics, SourceId};
 
-use super::{INDENT, NL, NL_CHAR, WS};
+use super::{NL_CHAR, WS};
 
 /// Hint for how comments may be laid out.
 pub(super) enum Comments {
@@ -89,17 +89,29 @@ impl Buffer {
         self.0.try_push_str(s)
     }
 
-    fn lines(&mut self, indent: usize, lines: usize) -> alloc::Result<()> {
+    fn lines(
+        &mut self,
+        options: &FmtOptions,
+        nl: &str,
+        indent: usize,
+        lines: usize,
+    ) -> alloc::Result<()> {
         if lines == 0 {
             return Ok(());
         }
 
         for _ in 0..lines {
-            self.0.try_push_str(NL)?;
+            self.0.try_push_str(nl)?;
         }
 
-        for _ in 0..indent {
-            self.0.try_push_str(INDENT)?;
+        if options.hard_tabs {
+            for _ in 0..indent {
+                self.0.try_push('\t')?;
+            }
+        } else {
+            for _ in 0..indent * options.indent_width {
+                self.0.try_push(' ')?;
+            }
         }
 
         Ok(())
@@ -113,11 +125,15 @@ pub(crate) struct Formatter<'a> {
     source_id: SourceId,
     o: &'a mut Buffer,
     pub(super) options: &'a FmtOptions,
+    nl: &'static str,
     diagnostics: &'a mut Diagnostics,
     comments: VecDeque<Comment>,
     lines: usize,
     use_lines: bool,
     ws: bool,
+    /// Set if the last thing written was a line comment, which means that the
+    /// next write must be put on a new line.
+    line_comment: bool,
     indent: usize,
 }
 
@@ -129,6 +145,7 @@ impl<'a> Formatter<'a> {
         source_id: SourceId,
         o: &'a mut String,
         options: &'a FmtOptions,
+        nl: &'static str,
         diagnostics: &'a mut Diagnostics,
     ) -> Self {
         Self {
@@ -137,11 +154,13 @@ impl<'a> Formatter<'a> {
             source_id,
             o: Buffer::new(o),
             options,
+            nl,
             diagnostics,
             comments: VecDeque::new(),
             lines: 0,
             use_lines: false,
             ws: false,
+            line_comment: false,
             indent: 0,
         }
     }
@@ -167,6 +186,22 @@ impl<'a> Formatter<'a> {
         Ok(())
     }
 
+    /// Write the source of the given span verbatim, discarding any comments
+    /// or whitespace which have been buffered from inside of it.
+    pub(crate) fn write_verbatim(&mut self, span: Span) -> Result<()> {
+        self.comments
+            .retain(|c| !(span.start <= c.span.start && c.span.end <= span.end));
+        self.lines = 0;
+        self.use_lines = false;
+        self.ws = false;
+        self.break_line_comment()?;
+
+        let source = self.source.get(span)?;
+        self.span = span;
+        self.o.str(source).with_span(span)?;
+        Ok(())
+    }
+
     /// Buffer literal to output.
     pub(crate) fn lit(&mut self, s: &str) -> Result<()> {
         // We want whitespace to be preserved *unless* it was written out, since
@@ -249,6 +284,14 @@ impl<'a> Formatter<'a> {
         Ok(())
     }
 
+    /// Test if there are any buffered line comments.
+    ///
+    /// These can't be laid out on a single line, so the caller might want to
+    /// expand what it's formatting.
+    pub(super) fn has_line_comments(&self) -> bool {
+        self.comments.iter().any(|c| c.line)
+    }
+
     /// Write leading comments.
     pub(super) fn flush_prefix_comments(&mut self, tree: &'a Tree) -> Result<()> {
         self.process_comments(tree.walk())?;
@@ -265,11 +308,11 @@ impl<'a> Formatter<'a> {
             }
 
             if !self.o.is_empty() {
-                if c.before == 0 {
+                if c.before == 0 && !self.line_comment {
                     self.o.str(WS).with_span(c.span)?;
                 } else {
                     self.o
-                        .lines(self.indent, c.before.min(2))
+                        .lines(self.options, self.nl, self.indent, c.before.clamp(1, 2))
                         .with_span(c.span)?;
                 }
             }
@@ -277,6 +320,7 @@ impl<'a> Formatter<'a> {
             let source = self.source.get(c.span)?;
             let source = if c.line { source.trim_end() } else { source };
             self.o.str(source).with_span(c.span)?;
+            self.line_comment = c.line;
 
             _ = self.comments.pop_front();
         }
@@ -297,7 +341,7 @@ impl<'a> Formatter<'a> {
                 break;
             }
 
-            if (prefix || any) && !self.o.is_empty() {
+            if !self.break_line_comment()? && (prefix || any) && !self.o.is_empty() {
                 self.o.str(WS).with_span(c.span)?;
             }
 
@@ -333,12 +377,12 @@ impl<'a> Formatter<'a> {
         let span = node.span();
 
         match node.kind() {
-            Kind::Comment | Kind::MultilineComment(..) => {
+            Kind::Comment | Kind::MultilineComment(..) | Kind::Shebang(..) => {
                 self.comments
                     .try_push_back(Comment {
                         span,
                         before: take(&mut self.lines),
-                        line: matches!(node.kind(), Kind::Comment),
+                        line: matches!(node.kind(), Kind::Comment | Kind::Shebang(..)),
                     })
                     .with_span(span)?;
 
@@ -381,10 +425,14 @@ impl<'a> Formatter<'a> {
 
     pub(crate) fn flush_whitespace(&mut self, preserve: bool) -> Result<()> {
         if self.use_lines && self.lines > 0 {
-            self.o.lines(self.indent, self.lines.min(2))?;
+            self.o
+                .lines(self.options, self.nl, self.indent, self.lines.min(2))?;
             self.ws = false;
             self.use_lines = false;
             self.lines = 0;
+            self.line_comment = false;
+        } else if self.break_line_comment()? {
+            self.ws = false;
         }
 
         if self.ws {
@@ -399,6 +447,19 @@ impl<'a> Formatter<'a> {
 
         Ok(())
     }
+
+    /// Break the current line if the last thing written was a line comment,
+    /// since anything written after it would otherwise be commented out.
+    fn break_line_comment(&mut self) -> Result<bool> {
+        if !take(&mut self.line_comment) {
+            return Ok(false);
+        }
+
+        self.o
+            .lines(self.options, self.nl, self.indent, 1)
+            .with_span(self.span)?;
+        Ok(true)
+    }
 }
 
 impl<'a> Ignore<'a> for Formatter<'a> {
@@ -13,6 +13,14 @@ fn format_literals() {
         -100.0;
         100.0e10;
         -100.0e10;
+        1_000_000;
+        -1_000_000;
+        0b1010_1010;
+        0o7_7;
+        0x_ff_ff;
+        1_000.000_1;
+        1e1_0;
+        1_000_u32;
         true;
         false;
         "hello world";
@@ -343,6 +351,16 @@ fn patterns() {
     assert_format!("let _ = |    |    42    ;", "let _ = || 42;");
     assert_format!("let _ = ||    42    ;", "let _ = || 42;");
     assert_format!("let #[ignore] (a,) = (42,);");
+    assert_format!(
+        r#"
+        let value = match value {
+            1_000_000 => 1,
+            -0x_ff => 2,
+            0b1010_1010 => 3,
+            _ => 4,
+        };
+        "#
+    );
     assert_format!(
         r#"
         let #{ a, b, c: d } = value;
@@ -1357,3 +1375,333 @@ fn test_expanded_chain() {
         "#
     );
 }
+
+#[test]
+fn macro_definitions() {
+    assert_format!(
+        r#"
+        macro swap($a:expr, $b:expr) {
+            let tmp = $a;
+            $a = $b;
+            $b = tmp;
+        }
+
+        pub macro sum($($e:expr),*) { 0 $(+ $e)* }
+
+        fn main() {
+            macro local( $x:tt ) {
+                $x   // keep as written
+            }
+
+            local!(1)
+        }
+        "#
+    );
+
+    assert_format!(
+        r#"
+        pub  macro   empty() {}
+        macro  ident  ($i:ident){ $i }
+        "#,
+        r#"
+        pub macro empty() {}
+        macro ident($i:ident) { $i }
+        "#
+    );
+}
+
+/// Format the given source with a single option, returning the exact output.
+fn layout_with(option: &str, source: &str) -> crate::alloc::String {
+    let mut options = crate::Options::default();
+    options.parse_option(option).unwrap();
+
+    let mut diagnostics = crate::Diagnostics::new();
+    super::layout_source_with(source, crate::SourceId::EMPTY, &options, &mut diagnostics).unwrap()
+}
+
+#[test]
+fn option_indent_width() {
+    assert_format_with!(
+        { "fmt.indent-width=2" },
+        r#"
+        fn main() {
+            if true {
+                let a = 1;
+            }
+        }
+        "#,
+        r#"
+        fn main() {
+          if true {
+            let a = 1;
+          }
+        }
+        "#
+    );
+}
+
+#[test]
+fn option_hard_tabs() {
+    let output = layout_with(
+        "fmt.hard-tabs",
+        "fn main() {\n    if true {\n        let a = 1;\n    }\n}\n",
+    );
+
+    assert_eq!(output, "fn main() {\n\tif true {\n\t\tlet a = 1;\n\t}\n}\n");
+}
+
+#[test]
+fn option_max_width() {
+    assert_format_with!(
+        { "fmt.max-width=5" },
+        "let a = [1, 2, 3];",
+        r#"
+        let a = [
+            1,
+            2,
+            3,
+        ];
+        "#
+    );
+
+    assert_format_with!(
+        { "fmt.max-width=120" },
+        r#"
+        let a = [
+            a_very_long_name_1,
+            a_very_long_name_2,
+            a_very_long_name_3,
+            a_very_long_name_4,
+            a_very_long_name_5,
+        ];
+        "#,
+        r#"
+        let a = [a_very_long_name_1, a_very_long_name_2, a_very_long_name_3, a_very_long_name_4, a_very_long_name_5];
+        "#
+    );
+}
+
+#[test]
+fn option_trailing_comma() {
+    assert_format_with!(
+        { "fmt.trailing-comma=always" },
+        r#"
+        let a = [1, 2, 3];
+        let b = #{ a: 1, b: 2 };
+        let c = (1, 2);
+        let d = (1,);
+        let e = [];
+        "#,
+        r#"
+        let a = [1, 2, 3,];
+        let b = #{ a: 1, b: 2, };
+        let c = (1, 2,);
+        let d = (1,);
+        let e = [];
+        "#
+    );
+
+    assert_format_with!(
+        { "fmt.trailing-comma=never" },
+        r#"
+        let a = [1, 2, 3,];
+        let b = [1, 2, 3, 4, 5, 6];
+        let c = (1,);
+
+        struct Foo { a, b, }
+
+        enum Bar { A, B }
+        "#,
+        r#"
+        let a = [1, 2, 3];
+        let b = [
+            1,
+            2,
+            3,
+            4,
+            5,
+            6
+        ];
+        let c = (1,);
+
+        struct Foo {
+            a,
+            b
+        }
+
+        enum Bar {
+            A,
+            B
+        }
+        "#
+    );
+
+    assert_format_with!(
+        { "fmt.trailing-comma=vertical" },
+        r#"
+        let a = [1, 2, 3,];
+
+        struct Foo { a, b }
+        "#,
+        r#"
+        let a = [1, 2, 3];
+
+        struct Foo {
+            a,
+            b,
+        }
+        "#
+    );
+}
+
+#[test]
+fn option_newline_style() {
+    const UNIX: &str = "fn main() {\n    let a = 1;\n}\n";
+    const WINDOWS: &str = "fn main() {\r\n    let a = 1;\r\n}\r\n";
+
+    assert_eq!(layout_with("fmt.newline-style=unix", UNIX), UNIX);
+    assert_eq!(layout_with("fmt.newline-style=unix", WINDOWS), UNIX);
+    assert_eq!(layout_with("fmt.newline-style=windows", UNIX), WINDOWS);
+    assert_eq!(layout_with("fmt.newline-style=windows", WINDOWS), WINDOWS);
+    assert_eq!(layout_with("fmt.newline-style=auto", UNIX), UNIX);
+    assert_eq!(layout_with("fmt.newline-style=auto", WINDOWS), WINDOWS);
+}
+
+#[test]
+fn option_defaults() {
+    let mut options = crate::Options::default();
+
+    for option in [
+        "fmt.indent-width=4",
+        "fmt.hard-tabs=false",
+        "fmt.max-width=80",
+        "fmt.trailing-comma=vertical",
+        "fmt.newline-style=unix",
+    ] {
+        options.parse_option(option).unwrap();
+    }
+
+    assert!(options
+        .parse_option("fmt.trailing-comma=sometimes")
+        .is_err());
+    assert!(options.parse_option("fmt.indent-width=wide").is_err());
+
+    const SOURCE: &str = "struct Foo {\n    a,\n}\n\nlet a = [1, 2, 3];\n";
+
+    let mut diagnostics = crate::Diagnostics::new();
+    let output =
+        super::layout_source_with(SOURCE, crate::SourceId::EMPTY, &options, &mut diagnostics)
+            .unwrap();
+    assert_eq!(output, SOURCE);
+    assert_eq!(output, layout_with("fmt.force-newline", SOURCE));
+}
+
+/// Sources which exercise comments in every position they can be attached
+/// to.
+const COMMENT_CORPUS: &[&str] = &[
+    "#!/usr/bin/env rune\n//! Module docs.\n\nfn main() {}\n",
+    "struct Foo {\n    a, // a\n    b, // b\n}\n",
+    "struct Foo {\n    a, // a\n    b // b\n}\n",
+    "struct Foo {\n    // empty\n}\n",
+    "struct Foo { // empty\n}\n",
+    "enum Foo {\n    // empty\n}\n",
+    "enum Foo {\n    A, // a\n    // b\n    B,\n}\n",
+    "fn main() {\n    // empty\n}\n",
+    "let a = { // empty\n};\n",
+    "let a = {\n    // empty\n};\n",
+    "let a = match b {\n    // empty\n};\n",
+    "let a = match b {\n    1 => 2, // one\n    // between\n    2 => 3,\n    // last\n};\n",
+    "let a = #{\n    a: 1, // a\n    // last\n};\n",
+    "let a = [\n    1, // one\n    // last\n];\n",
+    "let a = foo(\n    1, // one\n    2,\n);\n",
+];
+
+/// Collect the text of every comment in the given source, sorted so that
+/// they can be compared as a multiset.
+fn comment_texts(source: &str) -> rust_alloc::vec::Vec<&str> {
+    use crate::ast::Kind;
+    use crate::parse::Lexer;
+
+    let mut lexer = Lexer::new(source, crate::SourceId::EMPTY, true).without_processing();
+    let mut texts = rust_alloc::vec::Vec::new();
+
+    while let Some(token) = lexer.next().unwrap() {
+        if matches!(
+            token.kind,
+            Kind::Comment | Kind::MultilineComment(..) | Kind::Shebang(..)
+        ) {
+            texts.push(source[token.span.range()].trim_end());
+        }
+    }
+
+    texts.sort();
+    texts
+}
+
+fn layout(source: &str) -> crate::alloc::String {
+    let options = crate::Options::default();
+    let mut diagnostics = crate::Diagnostics::new();
+    super::layout_source_with(source, crate::SourceId::EMPTY, &options, &mut diagnostics).unwrap()
+}
+
+#[test]
+fn comments_round_trip() {
+    for source in COMMENT_CORPUS {
+        let output = layout(source);
+
+        assert_eq!(
+            comment_texts(source),
+            comment_texts(&output),
+            "comments differ in:\n{output}"
+        );
+
+        assert_eq!(layout(&output), output, "not idempotent:\n{output}");
+    }
+}
+
+#[test]
+fn shebang_and_module_docs() {
+    assert_format!(
+        r#"
+        #!/usr/bin/env rune
+        //! Module docs.
+
+        fn main() {}
+        "#
+    );
+}
+
+#[test]
+fn dangling_comments() {
+    assert_format!(
+        r#"
+        struct Foo { // empty
+        }
+
+        let a = { // empty
+        };
+
+        let a = match b {
+            // empty
+        };
+        "#
+    );
+}
+
+#[test]
+fn trailing_field_comments() {
+    assert_format!(
+        r#"
+        struct Foo {
+            a, // a
+            b // b
+        }
+        "#,
+        r#"
+        struct Foo {
+            a, // a
+            b, // b
+        }
+        "#
+    );
+}
@@ -515,6 +515,8 @@ pub struct FunctionMetaStatics {
     #[doc(hidden)]
     pub deprecated: Option<&'static str>,
     #[doc(hidden)]
+    pub is_const: bool,
+    #[doc(hidden)]
     pub docs: &'static [&'static str],
     #[doc(hidden)]
     pub arguments: &'static [&'static str],
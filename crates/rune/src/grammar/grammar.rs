@@ -48,6 +48,7 @@ macro_rules! __ws {
         $crate::ast::Kind::Whitespace
             | $crate::ast::Kind::Comment
             | $crate::ast::Kind::MultilineComment(..)
+            | $crate::ast::Kind::Shebang(..)
     };
 }
 
@@ -132,6 +133,7 @@ fn stmt(p: &mut Parser<'_>) -> Result<()> {
             K![pub] => false,
             K![const] => false,
             K![async] => false,
+            K![macro] => false,
             K![#] => !matches!(p.nth(1)?, K!['['] | K![!]),
             _ => true,
         };
@@ -203,6 +205,11 @@ fn stmt(p: &mut Parser<'_>) -> Result<()> {
             p.close_at(&inner_c, ItemConst)?;
             Item
         }
+        K![macro] => {
+            item_macro(p)?;
+            p.close_at(&inner_c, ItemMacro)?;
+            Item
+        }
         _ => {
             labels(p)?;
 
@@ -230,6 +237,24 @@ fn item_const(p: &mut Parser<'_>) -> Result<()> {
     Ok(())
 }
 
+#[tracing::instrument(skip_all)]
+fn item_macro(p: &mut Parser<'_>) -> Result<()> {
+    p.bump()?;
+    p.bump()?;
+
+    if p.bump_if(K!['('])? {
+        token_stream(p, parens)?;
+        p.bump()?;
+    }
+
+    if p.bump_if(K!['{'])? {
+        token_stream(p, braces)?;
+        p.bump()?;
+    }
+
+    Ok(())
+}
+
 #[tracing::instrument(skip_all)]
 fn inner_attributes(p: &mut Parser<'_>) -> Result<()> {
     while matches!((p.peek()?, p.glued(1)?), (K![#], K![!])) {
@@ -2,12 +2,13 @@ use tracing::instrument_ast;
 
 use crate::alloc;
 use crate::alloc::prelude::*;
-use crate::ast::{self, Spanned};
-use crate::compile::{meta, DynLocation, Error, ItemId, Result};
+use crate::alloc::HashMap;
+use crate::ast::{self, Span, Spanned};
+use crate::compile::{meta, DynLocation, Error, ItemId, Result, WithSpan};
 use crate::grammar::{Ignore, Node};
 use crate::hir;
 use crate::query::{GenericsParameters, Query, SecondaryBuildEntry};
-use crate::SourceId;
+use crate::{Hash, SourceId};
 
 #[derive(Default, Clone, Copy)]
 pub(super) enum Needs {
@@ -31,6 +32,11 @@ pub(crate) struct Ctxt<'hir, 'a, 'arena> {
     pub(super) statements: Vec<hir::Stmt<'hir>>,
     pub(super) pattern_bindings: Vec<hir::Variable>,
     pub(super) label: Option<ast::Label>,
+    /// The type of `self` if an instance function is being lowered.
+    pub(crate) self_type: Option<Hash>,
+    /// Variables whose type is known, like those assigned a struct literal,
+    /// which is used to tell the fields of different types apart.
+    pub(super) variable_types: HashMap<hir::Variable, Hash>,
 }
 
 impl<'hir, 'a, 'arena> Ctxt<'hir, 'a, 'arena> {
@@ -78,6 +84,8 @@ impl<'hir, 'a, 'arena> Ctxt<'hir, 'a, 'arena> {
             statements: Vec::new(),
             pattern_bindings: Vec::new(),
             label: None,
+            self_type: None,
+            variable_types: HashMap::new(),
         })
     }
 
@@ -102,6 +110,95 @@ impl<'hir, 'a, 'arena> Ctxt<'hir, 'a, 'arena> {
         self.q
             .lookup_meta(&DynLocation::new(self.source_id, span), item, parameters)
     }
+
+    /// Export the names of all variables defined while lowering to the unit
+    /// being built, so that they can be included in debug info.
+    pub(crate) fn export_variable_names(&mut self) -> alloc::Result<()> {
+        for &(id, name, _) in self.scopes.names() {
+            self.q.unit.insert_variable_name(id, name)?;
+        }
+
+        Ok(())
+    }
+
+    /// Report all variables defined while lowering a function covering the
+    /// given span to the compile visitor.
+    ///
+    /// Variables are in scope from where they are defined until the end of
+    /// the scope they were defined in.
+    pub(crate) fn visit_locals(&mut self, span: &dyn Spanned) -> Result<()> {
+        let end = span.span().end;
+
+        for &(id, name, span) in self.scopes.names() {
+            let scope = Span::new(span.end, self.scopes.end_of(id).unwrap_or(end));
+
+            let name = match name {
+                hir::Name::SelfValue => "self",
+                hir::Name::Str(name) => name,
+            };
+
+            let parameter = self.scopes.is_parameter(id);
+
+            self.q
+                .visitor
+                .visit_local(self.source_id, name, &span, &scope, parameter)
+                .with_span(span)?;
+        }
+
+        Ok(())
+    }
+
+    /// Emit warnings for variables which were defined while lowering but
+    /// never read.
+    ///
+    /// Like in Rust, variables whose names are prefixed with an underscore
+    /// are exempt.
+    pub(crate) fn report_unused_variables(&mut self) -> alloc::Result<()> {
+        if !self.q.options.unused_variables {
+            return Ok(());
+        }
+
+        for &(id, name, span) in self.scopes.names() {
+            let hir::Name::Str(name) = name else {
+                continue;
+            };
+
+            if name.starts_with('_') || self.scopes.is_used(id) {
+                continue;
+            }
+
+            self.q
+                .diagnostics
+                .unused_variable(self.source_id, &span, name.try_into()?)?;
+        }
+
+        Ok(())
+    }
+
+    /// Emit a warning listing the variables captured by a closure or async
+    /// block.
+    pub(super) fn report_captures(
+        &mut self,
+        span: &dyn Spanned,
+        do_move: bool,
+        captures: &[hir::Variable],
+    ) -> alloc::Result<()> {
+        if !self.q.options.closure_captures || captures.is_empty() {
+            return Ok(());
+        }
+
+        let mut names = Vec::new();
+
+        for &(id, name, span) in self.scopes.names() {
+            if captures.contains(&id) {
+                names.try_push((name.try_to_string()?, span))?;
+            }
+        }
+
+        self.q
+            .diagnostics
+            .closure_capture(self.source_id, span, do_move, names)
+    }
 }
 
 impl<'a> Ignore<'a> for Ctxt<'_, '_, '_> {
@@ -116,6 +116,7 @@ pub(crate) enum PatSequenceKind {
         variant_hash: Hash,
         enum_hash: Hash,
         index: usize,
+        item: ItemId,
     },
     Anonymous {
         type_check: TypeCheck,
@@ -133,6 +134,19 @@ pub(crate) struct PatSequence<'hir> {
     pub(crate) kind: PatSequenceKind,
     /// The items in the tuple.
     pub(crate) items: &'hir [Pat<'hir>],
+    /// The rest pattern, if it's not trailing or binds the remaining items.
+    pub(crate) rest: Option<PatRest>,
+}
+
+/// A rest pattern `..` or `..rest` inside of a sequence pattern.
+#[derive(Debug, TryClone, Clone, Copy)]
+#[try_clone(copy)]
+#[non_exhaustive]
+pub(crate) struct PatRest {
+    /// The number of items which come before the rest pattern.
+    pub(crate) index: usize,
+    /// The variable which the remaining items are bound to.
+    pub(crate) binding: Option<Variable>,
 }
 
 /// Object pattern matching.
@@ -144,6 +158,8 @@ pub(crate) struct PatObject<'hir> {
     pub(crate) kind: PatSequenceKind,
     /// Bindings associated with the pattern.
     pub(crate) bindings: &'hir [Binding<'hir>],
+    /// The variable which the remaining keys are bound to, as in `..rest`.
+    pub(crate) rest: Option<Variable>,
 }
 
 #[derive(Debug, TryClone, Clone, Copy)]
@@ -263,7 +279,7 @@ pub(crate) struct BuiltInFormatSpec {
     /// Width to fill.
     pub(crate) width: Option<NonZeroUsize>,
     /// Precision to fill.
-    pub(crate) precision: Option<NonZeroUsize>,
+    pub(crate) precision: Option<usize>,
     /// A specification of flags.
     pub(crate) flags: Option<format::Flags>,
     /// The format specification type.
@@ -395,7 +411,7 @@ pub(crate) struct ExprMatchBranch<'hir> {
     /// The pattern to match.
     pub(crate) pat: PatBinding<'hir>,
     /// The branch condition.
-    pub(crate) condition: Option<&'hir Expr<'hir>>,
+    pub(crate) condition: Option<&'hir Condition<'hir>>,
     /// The body of the match.
     pub(crate) body: Expr<'hir>,
     /// Variables that have been defined by this match branch, which needs to be
@@ -609,6 +625,20 @@ pub(crate) enum ExprObjectKind {
     Anonymous,
 }
 
+impl ExprObjectKind {
+    /// The hash of the type being constructed, unless the object is
+    /// anonymous.
+    pub(crate) fn type_hash(&self) -> Option<Hash> {
+        match *self {
+            ExprObjectKind::EmptyStruct { hash }
+            | ExprObjectKind::Struct { hash }
+            | ExprObjectKind::StructVariant { hash }
+            | ExprObjectKind::ExternalType { hash, .. } => Some(hash),
+            ExprObjectKind::Anonymous => None,
+        }
+    }
+}
+
 /// An object expression.
 #[derive(Debug, TryClone, Clone, Copy)]
 #[try_clone(copy)]
@@ -670,6 +700,11 @@ pub(crate) enum Condition<'hir> {
     Expr(&'hir Expr<'hir>),
     /// A pattern match.
     ExprLet(&'hir ExprLet<'hir>),
+    /// A chain of conditions joined by `&&` which contains pattern matches,
+    /// like `let Some(a) = b && a > 0`.
+    ///
+    /// Variables defined by a condition are visible to the ones following it.
+    Chain(#[rune(span)] Span, &'hir [Condition<'hir>]),
 }
 
 impl Condition<'_> {
@@ -678,6 +713,9 @@ impl Condition<'_> {
         match self {
             Condition::Expr(_) => None,
             Condition::ExprLet(hir) => Some(hir.pat.names.len()),
+            Condition::Chain(_, conditions) => {
+                Some(conditions.iter().flat_map(|c| c.count()).sum())
+            }
         }
     }
 }
@@ -9,7 +9,7 @@ use crate::alloc::try_format;
 use crate::alloc::{self, Box, HashMap, HashSet};
 use crate::ast::{self, NumberSize, Spanned};
 use crate::compile::meta;
-use crate::compile::{self, ErrorKind, WithSpan};
+use crate::compile::{self, similar, ErrorKind, WithSpan};
 use crate::hash::ParametersBuilder;
 use crate::hir;
 use crate::parse::Resolve;
@@ -68,6 +68,7 @@ fn expr_call_closure<'hir>(
             ast,
             ErrorKind::MissingItem {
                 item: cx.q.pool.item(item.item).try_to_owned()?,
+                did_you_mean: None,
             },
         ));
     };
@@ -87,11 +88,12 @@ fn expr_call_closure<'hir>(
     let args = iter!(ast.args.as_slice(), |(arg, _)| fn_arg(cx, arg)?);
     let body = alloc!(expr(cx, &ast.body)?);
 
-    let layer = cx.scopes.pop().with_span(&ast.body)?;
+    let layer = cx.scopes.pop(&ast.body)?;
 
     cx.q.set_used(&meta.item_meta)?;
 
     let captures = &*iter!(layer.captures().map(|(_, id)| id));
+    cx.report_captures(ast, do_move, captures)?;
 
     let Some(queue) = cx.secondary_builds.as_mut() else {
         return Err(compile::Error::new(ast, ErrorKind::ClosureInConst));
@@ -193,7 +195,7 @@ fn statements<'hir>(
 
     let statements = iter!(cx.statements.drain(at..));
 
-    let layer = cx.scopes.pop().with_span(span)?;
+    let layer = cx.scopes.pop(span)?;
 
     Ok(hir::Block {
         span: span.span(),
@@ -308,6 +310,9 @@ fn expr_object<'hir>(
                         ErrorKind::LitObjectNotField {
                             field: assign.key.1.try_into()?,
                             item: item.try_to_owned()?,
+                            did_you_mean: similar(assign.key.1, named.keys().copied())?
+                                .map(Box::try_from)
+                                .transpose()?,
                         },
                     ));
                 }
@@ -359,10 +364,18 @@ fn expr_object<'hir>(
                 }
                 meta::Kind::Variant {
                     fields: meta::Fields::Named(st),
+                    constructor,
                     ..
                 } => {
                     check_object_fields(&st.fields, item)?;
-                    hir::ExprObjectKind::StructVariant { hash: meta.hash }
+
+                    match constructor {
+                        Some(_) => hir::ExprObjectKind::ExternalType {
+                            hash: meta.hash,
+                            args: st.fields.len(),
+                        },
+                        None => hir::ExprObjectKind::StructVariant { hash: meta.hash },
+                    }
                 }
                 _ => {
                     return Err(compile::Error::new(
@@ -377,6 +390,17 @@ fn expr_object<'hir>(
         ast::ObjectIdent::Anonymous(..) => hir::ExprObjectKind::Anonymous,
     };
 
+    // Fields of anonymous objects don't belong to any type.
+    if let Some(hash) = kind.type_hash() {
+        for ((ast, _), assign) in ast.assignments.iter().zip(assignments.iter()) {
+            if let ast::ObjectKey::Path(..) = ast.key {
+                cx.q.visitor
+                    .visit_field_use(cx.source_id, Some(hash), assign.key.1, &assign.key.0)
+                    .with_span(assign.key.0)?;
+            }
+        }
+    }
+
     Ok(hir::ExprKind::Object(alloc!(hir::ExprObject {
         kind,
         assignments,
@@ -395,10 +419,18 @@ pub(crate) fn expr<'hir>(
 
     let kind = match ast {
         ast::Expr::Path(ast) => expr_path(cx, ast, in_path)?,
-        ast::Expr::Assign(ast) => hir::ExprKind::Assign(alloc!(hir::ExprAssign {
-            lhs: expr(cx, &ast.lhs)?,
-            rhs: expr(cx, &ast.rhs)?,
-        })),
+        ast::Expr::Assign(ast) => {
+            let lhs = expr(cx, &ast.lhs)?;
+            let rhs = expr(cx, &ast.rhs)?;
+
+            // The variable no longer necessarily holds a value of the type it
+            // was declared with.
+            if let hir::ExprKind::Variable(variable) = lhs.kind {
+                cx.variable_types.remove(&variable);
+            }
+
+            hir::ExprKind::Assign(alloc!(hir::ExprAssign { lhs, rhs }))
+        }
         // TODO: lower all of these loop constructs to the same loop-like
         // representation. We only do different ones here right now since it's
         // easier when refactoring.
@@ -411,7 +443,7 @@ pub(crate) fn expr<'hir>(
             cx.scopes.push_loop(label)?;
             let condition = condition(cx, &ast.condition)?;
             let body = block(cx, None, &ast.body)?;
-            let layer = cx.scopes.pop().with_span(ast)?;
+            let layer = cx.scopes.pop(ast)?;
 
             hir::ExprKind::Loop(alloc!(hir::ExprLoop {
                 label,
@@ -428,7 +460,7 @@ pub(crate) fn expr<'hir>(
 
             cx.scopes.push_loop(label)?;
             let body = block(cx, None, &ast.body)?;
-            let layer = cx.scopes.pop().with_span(ast)?;
+            let layer = cx.scopes.pop(ast)?;
 
             let kind = hir::ExprKind::Loop(alloc!(hir::ExprLoop {
                 label,
@@ -451,7 +483,7 @@ pub(crate) fn expr<'hir>(
             let binding = pat_binding(cx, &ast.binding)?;
             let body = block(cx, None, &ast.body)?;
 
-            let layer = cx.scopes.pop().with_span(ast)?;
+            let layer = cx.scopes.pop(ast)?;
 
             hir::ExprKind::For(alloc!(hir::ExprFor {
                 label,
@@ -472,10 +504,10 @@ pub(crate) fn expr<'hir>(
                 cx.scopes.push(None)?;
 
                 let pat = pat_binding(cx, &ast.pat)?;
-                let condition = option!(&ast.condition, |(_, ast)| expr(cx, ast)?);
+                let condition = option!(&ast.condition, |(_, ast)| condition_expr(cx, ast)?);
                 let body = expr(cx, &ast.body)?;
 
-                let layer = cx.scopes.pop().with_span(ast)?;
+                let layer = cx.scopes.pop(ast)?;
 
                 hir::ExprMatchBranch {
                     span: ast.span(),
@@ -488,7 +520,30 @@ pub(crate) fn expr<'hir>(
         })),
         ast::Expr::Call(ast) => hir::ExprKind::Call(alloc!(expr_call(cx, ast)?)),
         ast::Expr::FieldAccess(ast) => {
-            hir::ExprKind::FieldAccess(alloc!(expr_field_access(cx, ast)?))
+            let access = expr_field_access(cx, ast)?;
+
+            // A field access being called is an instance function call rather
+            // than a use of the field.
+            if !in_path {
+                if let ast::ExprField::Path(path) = &ast.expr_field {
+                    if let Some(ident) = path.try_as_ident() {
+                        let name = ident.resolve(resolve_context!(cx.q))?;
+
+                        let container = match access.expr.kind {
+                            hir::ExprKind::Variable(variable) => {
+                                cx.variable_types.get(&variable).copied()
+                            }
+                            _ => None,
+                        };
+
+                        cx.q.visitor
+                            .visit_field_use(cx.source_id, container, name, ident)
+                            .with_span(ident)?;
+                    }
+                }
+            }
+
+            hir::ExprKind::FieldAccess(alloc!(access))
         }
         ast::Expr::Empty(ast) => {
             // NB: restore in_path setting.
@@ -538,7 +593,7 @@ pub(crate) fn expr<'hir>(
                         let pat = pat_binding(cx, &ast.pat)?;
                         let body = expr(cx, &ast.body)?;
 
-                        let layer = cx.scopes.pop().with_span(&ast)?;
+                        let layer = cx.scopes.pop(&ast)?;
 
                         exprs.try_push(expr(cx, &ast.expr)?).with_span(&ast.expr)?;
 
@@ -643,6 +698,7 @@ fn pat_const_value<'hir>(
                             is_open: false,
                         },
                         items: &[],
+                        rest: None,
                     }));
                 }
                 Inline::Bool(b) => hir::Lit::Bool(b),
@@ -670,6 +726,7 @@ fn pat_const_value<'hir>(
                         is_open: false,
                     },
                     items,
+                    rest: None,
                 }));
             }
             ConstValueKind::Tuple(ref items) => {
@@ -684,6 +741,7 @@ fn pat_const_value<'hir>(
                         is_open: false,
                     },
                     items,
+                    rest: None,
                 }));
             }
             ConstValueKind::Object(ref fields) => {
@@ -700,6 +758,7 @@ fn pat_const_value<'hir>(
                         is_open: false,
                     },
                     bindings,
+                    rest: None,
                 }));
             }
             _ => {
@@ -749,7 +808,7 @@ fn expr_if<'hir>(
         let condition = condition(cx, c)?;
         let block = block(cx, None, b)?;
 
-        let layer = cx.scopes.pop().with_span(ast)?;
+        let layer = cx.scopes.pop(ast)?;
 
         let condition = &*alloc!(condition);
         let drop = &*iter!(layer.into_drop_order());
@@ -1002,11 +1061,12 @@ fn expr_block<'hir>(
 
             cx.scopes.push_captures()?;
             let block = alloc!(block(cx, None, &ast.block)?);
-            let layer = cx.scopes.pop().with_span(&ast.block)?;
+            let layer = cx.scopes.pop(&ast.block)?;
 
             cx.q.set_used(&meta.item_meta)?;
 
             let captures = &*iter!(layer.captures().map(|(_, id)| id));
+            cx.report_captures(ast, do_move, captures)?;
 
             let Some(queue) = cx.secondary_builds.as_mut() else {
                 return Err(compile::Error::new(ast, ErrorKind::AsyncBlockInConst));
@@ -1044,16 +1104,17 @@ fn expr_break<'hir>(
     alloc_with!(cx, ast);
 
     let label = match &ast.label {
-        Some(label) => Some(label.resolve(resolve_context!(cx.q))?),
+        Some(label) => Some((label.resolve(resolve_context!(cx.q))?, label.span())),
         None => None,
     };
 
-    let Some(drop) = cx.scopes.loop_drop(label)? else {
-        if let Some(label) = label {
+    let Some(drop) = cx.scopes.loop_drop(label.map(|(label, _)| label))? else {
+        if let Some((label, label_span)) = label {
             return Err(compile::Error::new(
                 ast,
                 ErrorKind::MissingLabel {
                     label: label.try_into()?,
+                    label_span,
                 },
             ));
         } else {
@@ -1063,7 +1124,7 @@ fn expr_break<'hir>(
 
     Ok(hir::ExprBreak {
         label: match label {
-            Some(label) => Some(alloc_str!(label)),
+            Some((label, _)) => Some(alloc_str!(label)),
             None => None,
         },
         expr: match &ast.expr {
@@ -1083,16 +1144,17 @@ fn expr_continue<'hir>(
     alloc_with!(cx, ast);
 
     let label = match &ast.label {
-        Some(label) => Some(label.resolve(resolve_context!(cx.q))?),
+        Some(label) => Some((label.resolve(resolve_context!(cx.q))?, label.span())),
         None => None,
     };
 
-    let Some(drop) = cx.scopes.loop_drop(label)? else {
-        if let Some(label) = label {
+    let Some(drop) = cx.scopes.loop_drop(label.map(|(label, _)| label))? else {
+        if let Some((label, label_span)) = label {
             return Err(compile::Error::new(
                 ast,
                 ErrorKind::MissingLabel {
                     label: label.try_into()?,
+                    label_span,
                 },
             ));
         } else {
@@ -1102,7 +1164,7 @@ fn expr_continue<'hir>(
 
     Ok(hir::ExprContinue {
         label: match label {
-            Some(label) => Some(alloc_str!(label)),
+            Some((label, _)) => Some(alloc_str!(label)),
             None => None,
         },
         drop: iter!(drop),
@@ -1116,13 +1178,23 @@ fn fn_arg<'hir>(
 ) -> compile::Result<hir::FnArg<'hir>> {
     alloc_with!(cx, ast);
 
-    Ok(match ast {
+    let names = cx.scopes.names().len();
+
+    let arg = match ast {
         ast::FnArg::SelfValue(ast) => {
             let id = cx.scopes.define(hir::Name::SelfValue, ast)?;
+
+            if let Some(hash) = cx.self_type {
+                cx.variable_types.try_insert(id, hash)?;
+            }
+
             hir::FnArg::SelfValue(ast.span(), id)
         }
         ast::FnArg::Pat(ast) => hir::FnArg::Pat(alloc!(pat_binding(cx, ast)?)),
-    })
+    };
+
+    cx.scopes.mark_parameters(names)?;
+    Ok(arg)
 }
 
 /// Lower an assignment.
@@ -1131,6 +1203,7 @@ fn local<'hir>(cx: &mut Ctxt<'hir, '_, '_>, ast: &ast::Local) -> compile::Result
     // expression will see declarations in the pattern.
     let expr = expr(cx, &ast.expr)?;
     let pat = pat_binding(cx, &ast.pat)?;
+    record_variable_type(cx, &pat, &expr)?;
 
     Ok(hir::Local {
         span: ast.span(),
@@ -1152,6 +1225,7 @@ fn unpack_locals(cx: &mut Ctxt<'_, '_, '_>, p: &ast::Pat, e: &ast::Expr) -> comp
 
             let e = expr(cx, e)?;
             let p = pat_binding(cx, p)?;
+            record_variable_type(cx, &p, &e)?;
 
             cx.statement_buffer
                 .try_push(hir::Stmt::Local(alloc!(hir::Local {
@@ -1198,6 +1272,28 @@ fn unpack_locals(cx: &mut Ctxt<'_, '_, '_>, p: &ast::Pat, e: &ast::Expr) -> comp
     Ok(false)
 }
 
+/// Record the type of a variable which is bound to a struct literal, like
+/// `point` in `let point = Point { x: 1 }`.
+fn record_variable_type(
+    cx: &mut Ctxt<'_, '_, '_>,
+    pat: &hir::PatBinding<'_>,
+    expr: &hir::Expr<'_>,
+) -> compile::Result<()> {
+    let hir::PatKind::Path(&hir::PatPathKind::Ident(variable)) = pat.pat.kind else {
+        return Ok(());
+    };
+
+    let hir::ExprKind::Object(object) = expr.kind else {
+        return Ok(());
+    };
+
+    if let Some(hash) = object.kind.type_hash() {
+        cx.variable_types.try_insert(variable, hash)?;
+    }
+
+    Ok(())
+}
+
 fn pat_binding<'hir>(
     cx: &mut Ctxt<'hir, '_, '_>,
     ast: &ast::Pat,
@@ -1264,31 +1360,48 @@ fn pat<'hir>(cx: &mut Ctxt<'hir, '_, '_>, ast: &ast::Pat) -> compile::Result<hir
             }
             ast::Pat::Lit(ast) => hir::PatKind::Lit(alloc!(expr(cx, &ast.expr)?)),
             ast::Pat::Vec(ast) => {
-                let (is_open, count) = pat_items_count(ast.items.as_slice())?;
+                let rest = pat_rest(ast.items.as_slice())?;
                 let items = iter!(
                     ast.items.iter().filter_map(filter),
                     ast.items.len(),
                     |ast| pat(cx, ast)?
                 );
 
+                let is_open = rest.is_some();
+                let rest = pat_sequence_rest(cx, rest, items.len())?;
+
                 hir::PatKind::Sequence(alloc!(hir::PatSequence {
                     kind: hir::PatSequenceKind::Anonymous {
                         type_check: TypeCheck::Vec,
-                        count,
+                        count: items.len(),
                         is_open
                     },
                     items,
+                    rest,
                 }))
             }
             ast::Pat::Tuple(ast) => {
-                let (is_open, count) = pat_items_count(ast.items.as_slice())?;
+                let rest_ast = pat_rest(ast.items.as_slice())?;
                 let items = iter!(
                     ast.items.iter().filter_map(filter),
                     ast.items.len(),
                     |ast| pat(cx, ast)?
                 );
 
+                let is_open = rest_ast.is_some();
+                let count = items.len();
+                let rest = pat_sequence_rest(cx, rest_ast, count)?;
+
                 let kind = if let Some(path) = &ast.path {
+                    // Typed tuples only support a trailing rest pattern which
+                    // doesn't bind anything.
+                    if let (Some(..), Some((_, rest_ast))) = (rest, rest_ast) {
+                        return Err(compile::Error::new(
+                            rest_ast,
+                            ErrorKind::UnsupportedPatternRest,
+                        ));
+                    }
+
                     let named = cx.q.convert_path(path)?;
                     let parameters = generics_parameters(cx, &named)?;
                     let meta = cx.lookup_meta(path, named.item, parameters)?;
@@ -1322,7 +1435,7 @@ fn pat<'hir>(cx: &mut Ctxt<'hir, '_, '_>, ast: &ast::Pat) -> compile::Result<hir
                     }
                 };
 
-                hir::PatKind::Sequence(alloc!(hir::PatSequence { kind, items }))
+                hir::PatKind::Sequence(alloc!(hir::PatSequence { kind, items, rest }))
             }
             ast::Pat::Object(ast) => {
                 let (is_open, count) = pat_items_count(ast.items.as_slice())?;
@@ -1378,8 +1491,20 @@ fn pat<'hir>(cx: &mut Ctxt<'hir, '_, '_>, ast: &ast::Pat) -> compile::Result<hir
                     binding
                 });
 
+                let rest = match ast.items.last() {
+                    Some((ast::Pat::Rest(rest), _)) => Some((rest, pat_rest_binding(cx, rest)?)),
+                    _ => None,
+                };
+
                 let kind = match &ast.ident {
                     ast::ObjectIdent::Named(path) => {
+                        if let Some((rest, Some(..))) = rest {
+                            return Err(compile::Error::new(
+                                rest,
+                                ErrorKind::UnsupportedPatternRest,
+                            ));
+                        }
+
                         let named = cx.q.convert_path(path)?;
                         let parameters = generics_parameters(cx, &named)?;
                         let meta = cx.lookup_meta(path, named.item, parameters)?;
@@ -1401,6 +1526,12 @@ fn pat<'hir>(cx: &mut Ctxt<'hir, '_, '_>, ast: &ast::Pat) -> compile::Result<hir
                                     ErrorKind::LitObjectNotField {
                                         field: binding.key().try_into()?,
                                         item: cx.q.pool.item(meta.item_meta.item).try_to_owned()?,
+                                        did_you_mean: similar(
+                                            binding.key(),
+                                            fields.iter().map(AsRef::as_ref),
+                                        )?
+                                        .map(Box::try_from)
+                                        .transpose()?,
                                     },
                                 ));
                             }
@@ -1421,6 +1552,26 @@ fn pat<'hir>(cx: &mut Ctxt<'hir, '_, '_>, ast: &ast::Pat) -> compile::Result<hir
                             ));
                         }
 
+                        for ((pat, _), binding) in ast.items.iter().zip(bindings.iter()) {
+                            let is_path = match pat {
+                                ast::Pat::Binding(binding) => {
+                                    matches!(binding.key, ast::ObjectKey::Path(..))
+                                }
+                                _ => true,
+                            };
+
+                            if is_path {
+                                cx.q.visitor
+                                    .visit_field_use(
+                                        cx.source_id,
+                                        Some(meta.hash),
+                                        binding.key(),
+                                        binding,
+                                    )
+                                    .with_span(binding)?;
+                            }
+                        }
+
                         kind
                     }
                     ast::ObjectIdent::Anonymous(..) => hir::PatSequenceKind::Anonymous {
@@ -1430,7 +1581,11 @@ fn pat<'hir>(cx: &mut Ctxt<'hir, '_, '_>, ast: &ast::Pat) -> compile::Result<hir
                     },
                 };
 
-                hir::PatKind::Object(alloc!(hir::PatObject { kind, bindings }))
+                hir::PatKind::Object(alloc!(hir::PatObject {
+                    kind,
+                    bindings,
+                    rest: rest.and_then(|(_, binding)| binding),
+                }))
             }
             _ => {
                 return Err(compile::Error::new(ast, ErrorKind::UnsupportedPatternExpr));
@@ -1521,17 +1676,7 @@ fn expr_path<'hir>(
         }
     }
 
-    let kind = if !parameters.parameters.is_empty() {
-        ErrorKind::MissingItemParameters {
-            item: cx.q.pool.item(named.item).try_to_owned()?,
-            parameters: parameters.parameters,
-        }
-    } else {
-        ErrorKind::MissingItem {
-            item: cx.q.pool.item(named.item).try_to_owned()?,
-        }
-    };
-
+    let kind = cx.q.missing_item(named.item, &parameters)?;
     Err(compile::Error::new(ast, kind))
 }
 
@@ -1607,14 +1752,145 @@ fn condition<'hir>(
     alloc_with!(cx, ast);
 
     Ok(match ast {
-        ast::Condition::Expr(ast) => hir::Condition::Expr(alloc!(expr(cx, ast)?)),
-        ast::Condition::ExprLet(ast) => hir::Condition::ExprLet(alloc!(hir::ExprLet {
-            pat: pat_binding(cx, &ast.pat)?,
-            expr: expr(cx, &ast.expr)?,
-        })),
+        ast::Condition::Expr(ast) => condition_expr(cx, ast)?,
+        ast::Condition::ExprLet(ast) => hir::Condition::ExprLet(alloc!(condition_let(cx, ast)?)),
     })
 }
 
+/// Lower an expression used as a condition, which might be a `let` expression
+/// or a chain of them joined by `&&`.
+fn condition_expr<'hir>(
+    cx: &mut Ctxt<'hir, '_, '_>,
+    ast: &ast::Expr,
+) -> compile::Result<hir::Condition<'hir>> {
+    alloc_with!(cx, ast);
+
+    if let ast::Expr::Let(ast) = ast {
+        return Ok(hir::Condition::ExprLet(alloc!(condition_let(cx, ast)?)));
+    }
+
+    if !is_let_chain(ast) {
+        return Ok(hir::Condition::Expr(alloc!(expr(cx, ast)?)));
+    }
+
+    let mut operands = Vec::new();
+    let_chain(ast, &mut operands)?;
+
+    let conditions = iter!(operands, |ast| match ast {
+        ast::Expr::Let(ast) => hir::Condition::ExprLet(alloc!(condition_let(cx, ast)?)),
+        ast => hir::Condition::Expr(alloc!(expr(cx, ast)?)),
+    });
+
+    Ok(hir::Condition::Chain(ast.span(), conditions))
+}
+
+/// Lower a `let` condition.
+///
+/// The expression is lowered before the pattern, so that it doesn't see the
+/// variables defined by the pattern.
+fn condition_let<'hir>(
+    cx: &mut Ctxt<'hir, '_, '_>,
+    ast: &ast::ExprLet,
+) -> compile::Result<hir::ExprLet<'hir>> {
+    let expr = expr(cx, &ast.expr)?;
+    let pat = pat_binding(cx, &ast.pat)?;
+    Ok(hir::ExprLet { pat, expr })
+}
+
+/// Test if the given expression is a chain of conditions joined by `&&` which
+/// contains a `let` expression.
+fn is_let_chain(ast: &ast::Expr) -> bool {
+    match ast {
+        ast::Expr::Let(..) => true,
+        ast::Expr::Binary(ast) if matches!(ast.op, ast::BinOp::And(..)) => {
+            is_let_chain(&ast.lhs) || is_let_chain(&ast.rhs)
+        }
+        _ => false,
+    }
+}
+
+/// Collect the operands of a chain of conditions joined by `&&`.
+fn let_chain<'a>(ast: &'a ast::Expr, out: &mut Vec<&'a ast::Expr>) -> alloc::Result<()> {
+    match ast {
+        ast::Expr::Binary(ast) if matches!(ast.op, ast::BinOp::And(..)) => {
+            let_chain(&ast.lhs, out)?;
+            let_chain(&ast.rhs, out)?;
+        }
+        ast => {
+            out.try_push(ast)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Find the rest pattern among the items of a sequence pattern.
+///
+/// Returns the number of items which come before it and the rest pattern.
+fn pat_rest(
+    items: &[(ast::Pat, Option<ast::Comma>)],
+) -> compile::Result<Option<(usize, &ast::PatRest)>> {
+    let mut found = None;
+    let mut index = 0;
+
+    for (pat, _) in items {
+        match pat {
+            ast::Pat::Rest(rest) => {
+                if found.is_some() {
+                    return Err(compile::Error::new(pat, ErrorKind::UnsupportedPatternRest));
+                }
+
+                found = Some((index, rest));
+            }
+            ast::Pat::Binding(..) => {}
+            _ => {
+                index += 1;
+            }
+        }
+    }
+
+    Ok(found)
+}
+
+/// Lower the rest pattern of a sequence pattern with `len` items.
+///
+/// A trailing rest pattern which doesn't bind anything is fully described by
+/// the sequence being open, so no rest pattern is produced for it.
+fn pat_sequence_rest(
+    cx: &mut Ctxt<'_, '_, '_>,
+    rest: Option<(usize, &ast::PatRest)>,
+    len: usize,
+) -> compile::Result<Option<hir::PatRest>> {
+    let Some((index, rest)) = rest else {
+        return Ok(None);
+    };
+
+    let binding = pat_rest_binding(cx, rest)?;
+
+    if index == len && binding.is_none() {
+        return Ok(None);
+    }
+
+    Ok(Some(hir::PatRest { index, binding }))
+}
+
+/// Define the variable bound by a rest pattern like `..rest`, if any.
+fn pat_rest_binding(
+    cx: &mut Ctxt<'_, '_, '_>,
+    ast: &ast::PatRest,
+) -> compile::Result<Option<hir::Variable>> {
+    alloc_with!(cx, ast);
+
+    let Some(ident) = &ast.binding else {
+        return Ok(None);
+    };
+
+    let name = alloc_str!(ident.resolve(resolve_context!(cx.q))?);
+    let name = cx.scopes.define(hir::Name::Str(name), ident)?;
+    cx.pattern_bindings.try_push(name)?;
+    Ok(Some(name))
+}
+
 /// Test if the given pattern is open or not.
 fn pat_items_count(items: &[(ast::Pat, Option<ast::Comma>)]) -> compile::Result<(bool, usize)> {
     let mut it = items.iter();
@@ -1664,6 +1940,7 @@ fn struct_match_for(
                     variant_hash: meta.hash,
                     enum_hash: *enum_hash,
                     index: *index,
+                    item: meta.item_meta.item,
                 }
             };
 
@@ -1720,6 +1997,7 @@ fn tuple_match_for(
                     variant_hash: meta.hash,
                     enum_hash: *enum_hash,
                     index: *index,
+                    item: meta.item_meta.item,
                 }
             };
 
@@ -1851,7 +2129,12 @@ fn expr_call<'hir>(
                         }
                     }
                     meta::Kind::Function { .. } => {
-                        if let Some(message) = cx.q.lookup_deprecation(meta.hash) {
+                        let deprecation = match cx.q.options.deprecations {
+                            true => cx.q.lookup_deprecation(meta.hash),
+                            false => None,
+                        };
+
+                        if let Some(message) = deprecation {
                             cx.q.diagnostics.used_deprecated(
                                 cx.source_id,
                                 &expr.span,
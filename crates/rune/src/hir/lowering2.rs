@@ -10,7 +10,7 @@ use tracing::instrument_ast;
 use crate::alloc::prelude::*;
 use crate::alloc::{self, HashMap, HashSet};
 use crate::ast::{self, Delimiter, Kind, NumberSize, Span, Spanned};
-use crate::compile::{meta, Error, ErrorKind, ItemId, Result, WithSpan};
+use crate::compile::{meta, similar, Error, ErrorKind, ItemId, Result, WithSpan};
 use crate::grammar::{
     classify, object_key, Ignore, MaybeNode, NodeClass, Remaining, Stream, StreamBuf, Tree,
 };
@@ -202,7 +202,7 @@ fn statements<'hir>(
 
     let statements = iter!(cx.statements.drain(at..));
 
-    let layer = cx.scopes.pop().with_span(&*p)?;
+    let layer = cx.scopes.pop(&*p)?;
 
     Ok(hir::Block {
         span: p.span(),
@@ -354,7 +354,12 @@ impl<'hir> ExprInner<'hir, '_> {
                         }
                     }
                     meta::Kind::Function { .. } => {
-                        if let Some(message) = cx.q.lookup_deprecation(meta.hash) {
+                        let deprecation = match cx.q.options.deprecations {
+                            true => cx.q.lookup_deprecation(meta.hash),
+                            false => None,
+                        };
+
+                        if let Some(message) = deprecation {
                             cx.q.diagnostics.used_deprecated(
                                 cx.source_id,
                                 &self.span,
@@ -448,17 +453,7 @@ impl<'hir> ExprInner<'hir, '_> {
                     }
                 }
 
-                let kind = if !parameters.parameters.is_empty() {
-                    ErrorKind::MissingItemParameters {
-                        item: cx.q.pool.item(named.item).try_to_owned()?,
-                        parameters: parameters.parameters,
-                    }
-                } else {
-                    ErrorKind::MissingItem {
-                        item: cx.q.pool.item(named.item).try_to_owned()?,
-                    }
-                };
-
+                let kind = cx.q.missing_item(named.item, &parameters)?;
                 Err(Error::new(self.span, kind))
             }
         }
@@ -581,11 +576,12 @@ fn expr_async_block<'hir>(
 
     cx.scopes.push_captures()?;
     let block = alloc!(block(cx, None, p)?);
-    let layer = cx.scopes.pop().with_span(&*p)?;
+    let layer = cx.scopes.pop(&*p)?;
 
     cx.q.set_used(&meta.item_meta)?;
 
     let captures = &*iter!(layer.captures().map(|(_, id)| id));
+    cx.report_captures(&*p, do_move, captures)?;
 
     let Some(queue) = cx.secondary_builds.as_mut() else {
         return Err(Error::new(&*p, ErrorKind::AsyncBlockInConst));
@@ -824,7 +820,7 @@ fn expr_format_macro<'hir>(
                         return Err(Error::unsupported(arg, "argument out-of-bounds"));
                     };
 
-                    spec.precision = NonZero::new(f);
+                    spec.precision = Some(f);
                 }
                 "type" => {
                     if spec.format_type.is_some() {
@@ -942,16 +938,17 @@ fn expr_break<'hir>(
     let expr = p.eat(Expr).parse(|p| expr(cx, p))?;
 
     let label = match label {
-        Some(label) => Some(label.resolve(resolve_context!(cx.q))?),
+        Some(label) => Some((label.resolve(resolve_context!(cx.q))?, label.span())),
         None => None,
     };
 
-    let Some(drop) = cx.scopes.loop_drop(label)? else {
-        if let Some(label) = label {
+    let Some(drop) = cx.scopes.loop_drop(label.map(|(label, _)| label))? else {
+        if let Some((label, label_span)) = label {
             return Err(Error::new(
                 &*p,
                 ErrorKind::MissingLabel {
                     label: label.try_into()?,
+                    label_span,
                 },
             ));
         } else {
@@ -961,7 +958,7 @@ fn expr_break<'hir>(
 
     Ok(hir::ExprKind::Break(alloc!(hir::ExprBreak {
         label: match label {
-            Some(label) => Some(alloc_str!(label)),
+            Some((label, _)) => Some(alloc_str!(label)),
             None => None,
         },
         expr: option!(expr),
@@ -983,16 +980,17 @@ fn expr_continue<'hir>(
         .ast::<ast::Label>()?;
 
     let label = match label {
-        Some(label) => Some(label.resolve(resolve_context!(cx.q))?),
+        Some(label) => Some((label.resolve(resolve_context!(cx.q))?, label.span())),
         None => None,
     };
 
-    let Some(drop) = cx.scopes.loop_drop(label)? else {
-        if let Some(label) = label {
+    let Some(drop) = cx.scopes.loop_drop(label.map(|(label, _)| label))? else {
+        if let Some((label, label_span)) = label {
             return Err(Error::new(
                 &*p,
                 ErrorKind::MissingLabel {
                     label: label.try_into()?,
+                    label_span,
                 },
             ));
         } else {
@@ -1002,7 +1000,7 @@ fn expr_continue<'hir>(
 
     let kind = hir::ExprContinue {
         label: match label {
-            Some(label) => Some(alloc_str!(label)),
+            Some((label, _)) => Some(alloc_str!(label)),
             None => None,
         },
         drop: iter!(drop),
@@ -1199,6 +1197,9 @@ fn expr_object<'hir>(
                     ErrorKind::LitObjectNotField {
                         field: assign.key.1.try_into()?,
                         item: item.try_to_owned()?,
+                        did_you_mean: similar(assign.key.1, named.keys().copied())?
+                            .map(Box::try_from)
+                            .transpose()?,
                     },
                 ));
             };
@@ -1252,10 +1253,18 @@ fn expr_object<'hir>(
                 }
                 meta::Kind::Variant {
                     fields: meta::Fields::Named(st),
+                    constructor,
                     ..
                 } => {
                     check_object_fields(&st.fields, item)?;
-                    hir::ExprObjectKind::StructVariant { hash: meta.hash }
+
+                    match constructor {
+                        Some(_) => hir::ExprObjectKind::ExternalType {
+                            hash: meta.hash,
+                            args: st.fields.len(),
+                        },
+                        None => hir::ExprObjectKind::StructVariant { hash: meta.hash },
+                    }
                 }
                 _ => {
                     return Err(Error::new(
@@ -1421,7 +1430,7 @@ fn expr_if<'hir>(cx: &mut Ctxt<'hir, '_, '_>, p: &mut Stream<'_>) -> Result<hir:
     cx.scopes.push_loop(None)?;
     let condition = p.pump()?.parse(|p| self::condition(cx, p))?;
     let block = p.expect(Block)?.parse(|p| self::block(cx, None, p))?;
-    let layer = cx.scopes.pop().with_span(&*p)?;
+    let layer = cx.scopes.pop(&*p)?;
 
     branches.try_push(hir::ConditionalBranch {
         span: start.span().join(block.span),
@@ -1450,7 +1459,7 @@ fn expr_if<'hir>(cx: &mut Ctxt<'hir, '_, '_>, p: &mut Stream<'_>) -> Result<hir:
                     cx.scopes.push_loop(None)?;
                     let condition = p.pump()?.parse(|p| self::condition(cx, p))?;
                     let block = p.expect(Block)?.parse(|p| self::block(cx, None, p))?;
-                    let layer = cx.scopes.pop().with_span(&*p)?;
+                    let layer = cx.scopes.pop(&*p)?;
 
                     branches.try_push(hir::ConditionalBranch {
                         span: start.span().join(block.span),
@@ -1506,7 +1515,7 @@ fn expr_match<'hir>(
 
             let condition = if p.eat(K![if]).is_some() {
                 let expr = p.expect(Expr)?.parse(|p| self::expr(cx, p))?;
-                Some(&*alloc!(expr))
+                Some(&*alloc!(hir::Condition::Expr(alloc!(expr))))
             } else {
                 None
             };
@@ -1519,7 +1528,7 @@ fn expr_match<'hir>(
                 Ok((expr, is_block))
             })?;
 
-            let layer = cx.scopes.pop().with_span(&*p)?;
+            let layer = cx.scopes.pop(&*p)?;
 
             branches.try_push(hir::ExprMatchBranch {
                 span: p.span(),
@@ -1609,7 +1618,7 @@ fn expr_select<'hir>(
                         Ok((expr, is_block))
                     })?;
 
-                    let layer = cx.scopes.pop().with_span(&*p)?;
+                    let layer = cx.scopes.pop(&*p)?;
 
                     branches.try_push(hir::ExprSelectBranch {
                         pat,
@@ -1654,7 +1663,7 @@ fn expr_while<'hir>(
 
     let condition = p.pump()?.parse(|p| condition(cx, p))?;
     let body = p.expect(Block)?.parse(|p| block(cx, None, p))?;
-    let layer = cx.scopes.pop().with_span(&*p)?;
+    let layer = cx.scopes.pop(&*p)?;
 
     Ok(hir::ExprKind::Loop(alloc!(hir::ExprLoop {
         label,
@@ -1677,7 +1686,7 @@ fn expr_loop<'hir>(cx: &mut Ctxt<'hir, '_, '_>, p: &mut Stream<'_>) -> Result<hi
 
     p.expect(K![loop])?;
     let body = p.expect(Block)?.parse(|p| block(cx, None, p))?;
-    let layer = cx.scopes.pop().with_span(&*p)?;
+    let layer = cx.scopes.pop(&*p)?;
 
     Ok(hir::ExprKind::Loop(alloc!(hir::ExprLoop {
         label,
@@ -1709,7 +1718,7 @@ fn expr_for<'hir>(cx: &mut Ctxt<'hir, '_, '_>, p: &mut Stream<'_>) -> Result<hir
     let binding = pat.parse(|p| self::pat_binding(cx, p))?;
     let body = block.parse(|p| self::block(cx, None, p))?;
 
-    let layer = cx.scopes.pop().with_span(&*p)?;
+    let layer = cx.scopes.pop(&*p)?;
 
     Ok(hir::ExprKind::For(alloc!(hir::ExprFor {
         label,
@@ -1851,6 +1860,7 @@ fn expr_closure<'hir>(
             &*p,
             ErrorKind::MissingItem {
                 item: cx.q.pool.item(item).try_to_owned()?,
+                did_you_mean: None,
             },
         ));
     };
@@ -1894,11 +1904,12 @@ fn expr_closure<'hir>(
     let body = p.expect(Expr)?.parse(|p| expr(cx, p))?;
     let body = alloc!(body);
 
-    let layer = cx.scopes.pop().with_span(&*p)?;
+    let layer = cx.scopes.pop(&*p)?;
 
     cx.q.set_used(&meta.item_meta)?;
 
     let captures = &*iter!(layer.captures().map(|(_, id)| id));
+    cx.report_captures(&*p, do_move, captures)?;
 
     let Some(queue) = cx.secondary_builds.as_mut() else {
         return Err(Error::new(&*p, ErrorKind::ClosureInConst));
@@ -2326,7 +2337,11 @@ fn pat_tuple<'hir>(cx: &mut Ctxt<'hir, '_, '_>, p: &mut Stream<'_>) -> Result<hi
 
     Ok(hir::Pat {
         span: p.span(),
-        kind: hir::PatKind::Sequence(alloc!(hir::PatSequence { kind, items })),
+        kind: hir::PatKind::Sequence(alloc!(hir::PatSequence {
+            kind,
+            items,
+            rest: None,
+        })),
     })
 }
 
@@ -2426,6 +2441,9 @@ fn pat_object<'hir>(cx: &mut Ctxt<'hir, '_, '_>, p: &mut Stream<'_>) -> Result<h
                         ErrorKind::LitObjectNotField {
                             field: binding.key().try_into()?,
                             item: cx.q.pool.item(meta.item_meta.item).try_to_owned()?,
+                            did_you_mean: similar(binding.key(), fields.iter().map(AsRef::as_ref))?
+                                .map(Box::try_from)
+                                .transpose()?,
                         },
                     ));
                 }
@@ -2458,7 +2476,11 @@ fn pat_object<'hir>(cx: &mut Ctxt<'hir, '_, '_>, p: &mut Stream<'_>) -> Result<h
 
     Ok(hir::Pat {
         span: p.span(),
-        kind: hir::PatKind::Object(alloc!(hir::PatObject { kind, bindings })),
+        kind: hir::PatKind::Object(alloc!(hir::PatObject {
+            kind,
+            bindings,
+            rest: None,
+        })),
     })
 }
 
@@ -2497,7 +2519,11 @@ fn pat_array<'hir>(cx: &mut Ctxt<'hir, '_, '_>, p: &mut Stream<'_>) -> Result<hi
 
     Ok(hir::Pat {
         span: p.span(),
-        kind: hir::PatKind::Sequence(alloc!(hir::PatSequence { kind, items })),
+        kind: hir::PatKind::Sequence(alloc!(hir::PatSequence {
+            kind,
+            items,
+            rest: None,
+        })),
     })
 }
 
@@ -2570,6 +2596,7 @@ fn pat_const_value<'hir>(
                             is_open: false,
                         },
                         items: &[],
+                        rest: None,
                     }));
                 }
                 Inline::Bool(b) => hir::Lit::Bool(b),
@@ -2592,6 +2619,7 @@ fn pat_const_value<'hir>(
                         is_open: false,
                     },
                     items,
+                    rest: None,
                 }));
             }
             ConstValueKind::Tuple(ref items) => {
@@ -2606,6 +2634,7 @@ fn pat_const_value<'hir>(
                         is_open: false,
                     },
                     items,
+                    rest: None,
                 }));
             }
             ConstValueKind::Object(ref fields) => {
@@ -2622,6 +2651,7 @@ fn pat_const_value<'hir>(
                         is_open: false,
                     },
                     bindings,
+                    rest: None,
                 }));
             }
             _ => {
@@ -2668,6 +2698,7 @@ fn struct_match_for(
                     variant_hash: meta.hash,
                     enum_hash: *enum_hash,
                     index: *index,
+                    item: meta.item_meta.item,
                 }
             };
 
@@ -2724,6 +2755,7 @@ fn tuple_match_for(
                     variant_hash: meta.hash,
                     enum_hash: *enum_hash,
                     index: *index,
+                    item: meta.item_meta.item,
                 }
             };
 
@@ -5,8 +5,8 @@ use core::fmt;
 use core::num::NonZeroUsize;
 
 use crate::alloc::prelude::*;
-use crate::alloc::{self, BTreeSet, HashMap, Vec};
-use crate::ast::Spanned;
+use crate::alloc::{self, BTreeSet, HashMap, HashSet, Vec};
+use crate::ast::{ByteIndex, Span, Spanned};
 use crate::compile::error::{MissingScope, PopError};
 use crate::compile::{self, HasSpan};
 use crate::hir;
@@ -66,6 +66,15 @@ impl<'hir> Layer<'hir> {
 pub(crate) struct Scopes<'hir, 'a> {
     scope: Scope,
     scopes: Vec<Layer<'hir>>,
+    /// Names of all variables which have been defined, and where.
+    names: Vec<(hir::Variable, hir::Name<'hir>, Span)>,
+    /// Where variables go out of scope, for variables whose scope has been
+    /// popped.
+    ends: HashMap<hir::Variable, ByteIndex>,
+    /// Variables which have been read.
+    used: HashSet<hir::Variable>,
+    /// Variables which are arguments of a function or closure.
+    parameters: HashSet<hir::Variable>,
     gen: &'a Gen,
 }
 
@@ -81,6 +90,10 @@ impl<'hir, 'a> Scopes<'hir, 'a> {
         Ok(Self {
             scope: Scopes::ROOT,
             scopes,
+            names: Vec::new(),
+            ends: HashMap::new(),
+            used: HashSet::new(),
+            parameters: HashSet::new(),
             gen,
         })
     }
@@ -118,21 +131,27 @@ impl<'hir, 'a> Scopes<'hir, 'a> {
         Ok(())
     }
 
-    /// Pop the given scope.
+    /// Pop the given scope, which covers the given span.
     #[tracing::instrument(skip_all, fields(?self.scope))]
-    pub(crate) fn pop(&mut self) -> Result<Layer<'hir>, PopError> {
+    pub(crate) fn pop(&mut self, span: &dyn Spanned) -> compile::Result<Layer<'hir>> {
         let Some(layer) = self.scopes.pop() else {
-            return Err(PopError::MissingScope(self.scope.0));
+            return Err(HasSpan::new(span, PopError::MissingScope(self.scope.0)).into());
         };
 
         if layer.scope.0 != self.scope.0 {
-            return Err(PopError::MissingScope(self.scope.0));
+            return Err(HasSpan::new(span, PopError::MissingScope(self.scope.0)).into());
         }
 
         let Some(parent) = layer.parent() else {
-            return Err(PopError::MissingParentScope(self.scope.0));
+            return Err(HasSpan::new(span, PopError::MissingParentScope(self.scope.0)).into());
         };
 
+        let end = span.span().end;
+
+        for &id in &layer.order {
+            self.ends.try_insert(id, end)?;
+        }
+
         let to = Scope(parent);
         tracing::trace!(from = ?self.scope, ?to);
         self.scope = to;
@@ -174,9 +193,40 @@ impl<'hir, 'a> Scopes<'hir, 'a> {
         let id = hir::Variable(self.gen.next());
         layer.variables.try_insert(name, id)?;
         layer.order.try_push(id)?;
+        self.names.try_push((id, name, span.span()))?;
         Ok(id)
     }
 
+    /// Names of all variables which have been defined, and where.
+    pub(crate) fn names(&self) -> &[(hir::Variable, hir::Name<'hir>, Span)] {
+        &self.names
+    }
+
+    /// Mark all variables defined since the given number of names as
+    /// arguments of a function or closure.
+    pub(crate) fn mark_parameters(&mut self, from: usize) -> alloc::Result<()> {
+        for &(id, _, _) in self.names.get(from..).unwrap_or_default() {
+            self.parameters.try_insert(id)?;
+        }
+
+        Ok(())
+    }
+
+    /// Test if the given variable is an argument of a function or closure.
+    pub(crate) fn is_parameter(&self, id: hir::Variable) -> bool {
+        self.parameters.contains(&id)
+    }
+
+    /// Get where the given variable goes out of scope, if it is known.
+    pub(crate) fn end_of(&self, id: hir::Variable) -> Option<ByteIndex> {
+        self.ends.get(&id).copied()
+    }
+
+    /// Test if the given variable has been read.
+    pub(crate) fn is_used(&self, id: hir::Variable) -> bool {
+        self.used.contains(&id)
+    }
+
     /// Try to lookup the given variable.
     #[tracing::instrument(skip_all, fields(?self.scope, ?name))]
     pub(crate) fn get(
@@ -220,6 +270,7 @@ impl<'hir, 'a> Scopes<'hir, 'a> {
             layer.captures.try_insert((name, id))?;
         }
 
+        self.used.try_insert(id)?;
         Ok(Some((id, scope)))
     }
 
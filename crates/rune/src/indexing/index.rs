@@ -4,11 +4,14 @@ use tracing::instrument_ast;
 
 use crate::alloc::prelude::*;
 use crate::alloc::VecDeque;
-use crate::ast::{self, OptionSpanned, Spanned};
+use crate::ast::{self, OptionSpanned, Span, Spanned};
 use crate::compile::{
-    self, attrs, meta, Doc, DynLocation, ErrorKind, ItemMeta, Location, Visibility, WithSpan,
+    self, attrs, meta, DeclarationKind, Doc, DynLocation, ErrorKind, ItemMeta, Location,
+    Visibility, WithSpan,
 };
+use crate::diagnostics::WarningDiagnosticKind;
 use crate::indexing::{self, Indexed};
+use crate::macros::DeclarativeMacro;
 use crate::parse::{Resolve, ResolveContext};
 use crate::query::{DeferEntry, ImplItem, ImplItemKind};
 use crate::runtime::Call;
@@ -16,9 +19,6 @@ use crate::worker::{Import, ImportKind, ImportState};
 
 use super::{ast_to_visibility, validate_call, Indexer};
 
-/// Macros are only allowed to expand recursively into other macros 64 times.
-const MAX_MACRO_RECURSION: usize = 64;
-
 /// Index the contents of a module known by its AST as a "file".
 pub(crate) fn file(idx: &mut Indexer<'_, '_>, ast: &mut ast::File) -> compile::Result<()> {
     let mut p = attrs::Parser::new(&ast.attributes)?;
@@ -47,6 +47,21 @@ pub(crate) fn file(idx: &mut Indexer<'_, '_>, ast: &mut ast::File) -> compile::R
         ));
     }
 
+    let items = take(&mut ast.items);
+    self::items(idx, items, &mut ast.items)?;
+    Ok(())
+}
+
+/// Index a collection of items, expanding macro calls and attribute macros
+/// among them.
+///
+/// Items produced by macros which expand into built-in macros are added to
+/// `builtin`, since their instructions need to be assembled.
+fn items(
+    idx: &mut Indexer<'_, '_>,
+    items: Vec<(ast::Item, Option<T![;]>)>,
+    builtin: &mut Vec<(ast::Item, Option<T![;]>)>,
+) -> compile::Result<()> {
     // Items take priority.
     let mut head = VecDeque::new();
 
@@ -54,8 +69,13 @@ pub(crate) fn file(idx: &mut Indexer<'_, '_>, ast: &mut ast::File) -> compile::R
     // been processed.
     let mut queue = VecDeque::new();
 
-    for (item, semi) in ast.items.drain(..) {
+    for (item, semi) in items {
         match item {
+            // Declarative macros are registered up front, so that they can be
+            // used before they are defined.
+            ast::Item::Macro(item) => {
+                item_macro(idx, item)?;
+            }
             i @ ast::Item::MacroCall(_) => {
                 queue.try_push_back((0, i, Vec::new(), semi))?;
             }
@@ -82,13 +102,12 @@ pub(crate) fn file(idx: &mut Indexer<'_, '_>, ast: &mut ast::File) -> compile::R
         }
 
         while let Some((depth, mut item, mut skipped_attributes, semi)) = queue.pop_front() {
-            if depth >= MAX_MACRO_RECURSION {
+            let max = idx.q.options.max_macro_depth;
+
+            if depth >= max {
                 return Err(compile::Error::new(
                     &item,
-                    ErrorKind::MaxMacroRecursion {
-                        depth,
-                        max: MAX_MACRO_RECURSION,
-                    },
+                    ErrorKind::MaxMacroRecursion { depth, max },
                 ));
             }
 
@@ -151,8 +170,7 @@ pub(crate) fn file(idx: &mut Indexer<'_, '_>, ast: &mut ast::File) -> compile::R
                 }
 
                 // Macro call must be added to output to make sure its instructions are assembled.
-                ast.items
-                    .try_push((ast::Item::MacroCall(macro_call), semi))?;
+                builtin.try_push((ast::Item::MacroCall(macro_call), semi))?;
             } else {
                 if let Some(attr) = p.remaining(&macro_call.attributes).next() {
                     return Err(compile::Error::msg(
@@ -220,6 +238,7 @@ pub(crate) fn empty_block_fn(
             is_test: false,
             is_bench: false,
             impl_item: None,
+            protocol: None,
             args: Vec::new(),
         }),
     })?;
@@ -227,6 +246,53 @@ pub(crate) fn empty_block_fn(
     Ok(())
 }
 
+/// Register the levels of warnings set by `#[allow(..)]`, `#[warn(..)]` and
+/// `#[deny(..)]` attributes, which apply to everything within `span`.
+fn lints(
+    idx: &mut Indexer<'_, '_>,
+    p: &mut attrs::Parser,
+    attributes: &[ast::Attribute],
+    span: &dyn Spanned,
+) -> compile::Result<()> {
+    lint::<attrs::Allow>(idx, p, attributes, span)?;
+    lint::<attrs::Warn>(idx, p, attributes, span)?;
+    lint::<attrs::Deny>(idx, p, attributes, span)?;
+    Ok(())
+}
+
+fn lint<T>(
+    idx: &mut Indexer<'_, '_>,
+    p: &mut attrs::Parser,
+    attributes: &[ast::Attribute],
+    span: &dyn Spanned,
+) -> compile::Result<()>
+where
+    T: attrs::Lint,
+{
+    for result in p.parse_all::<T>(resolve_context!(idx.q), attributes)? {
+        let (attr, lint) = result?;
+
+        for (ident, _) in lint.lints() {
+            let name = ident.resolve(resolve_context!(idx.q))?;
+
+            match WarningDiagnosticKind::find_name(name) {
+                Some(known) => {
+                    idx.q
+                        .diagnostics
+                        .lint_level(idx.source_id, span, known, T::LEVEL)?;
+                }
+                None => {
+                    idx.q
+                        .diagnostics
+                        .unknown_lint(idx.source_id, attr, name.try_to_owned()?)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
 #[instrument_ast(span = ast)]
 pub(crate) fn item_fn(idx: &mut Indexer<'_, '_>, mut ast: ast::ItemFn) -> compile::Result<()> {
     let name = ast.name.resolve(resolve_context!(idx.q))?;
@@ -238,6 +304,8 @@ pub(crate) fn item_fn(idx: &mut Indexer<'_, '_>, mut ast: ast::ItemFn) -> compil
     let docs = Doc::collect_from(resolve_context!(idx.q), &mut p, &ast.attributes)?;
 
     let guard = idx.items.push_name(name.as_ref())?;
+    idx.visit_declaration(&ast, &ast.name, DeclarationKind::Function)?;
+    lints(idx, &mut p, &ast.attributes, &ast)?;
     let item_meta = idx.insert_new_item(&ast, visibility, &docs)?;
     let idx_item = idx.item.replace(item_meta.item);
 
@@ -266,7 +334,13 @@ pub(crate) fn item_fn(idx: &mut Indexer<'_, '_>, mut ast: ast::ItemFn) -> compil
         ));
     };
 
-    let call = validate_call(ast.const_token.is_some(), ast.async_token.is_some(), &layer)?;
+    let call = validate_call(
+        ast.const_token.is_some(),
+        ast.async_token.is_some(),
+        ast.fn_token.span(),
+        false,
+        &layer,
+    )?;
 
     let Some(call) = call else {
         idx.q
@@ -310,6 +384,11 @@ pub(crate) fn item_fn(idx: &mut Indexer<'_, '_>, mut ast: ast::ItemFn) -> compil
         _ => false,
     };
 
+    let protocol = match p.try_parse::<attrs::Protocol>(resolve_context!(idx.q), &ast.attributes)? {
+        Some((attr, protocol)) => Some(protocol.resolve(resolve_context!(idx.q), attr)?),
+        None => None,
+    };
+
     if let Some(attrs) = p.remaining(&ast.attributes).next() {
         return Err(compile::Error::msg(
             attrs,
@@ -349,6 +428,13 @@ pub(crate) fn item_fn(idx: &mut Indexer<'_, '_>, mut ast: ast::ItemFn) -> compil
         };
     }
 
+    if protocol.is_some() && (!is_instance || idx.item.impl_item.is_none()) {
+        return Err(compile::Error::msg(
+            &ast,
+            "The #[protocol] attribute is only supported on functions receiving `self` inside of an impl",
+        ));
+    }
+
     let name = ast.name;
     let args = ast.args.iter().map(|(a, _)| a.span()).try_collect()?;
 
@@ -361,6 +447,7 @@ pub(crate) fn item_fn(idx: &mut Indexer<'_, '_>, mut ast: ast::ItemFn) -> compil
             is_test,
             is_bench,
             impl_item: idx.item.impl_item,
+            protocol,
             args,
         }),
     };
@@ -384,9 +471,12 @@ pub(crate) fn item_fn(idx: &mut Indexer<'_, '_>, mut ast: ast::ItemFn) -> compil
 
 #[instrument_ast(span = ast)]
 fn expr_block(idx: &mut Indexer<'_, '_>, ast: &mut ast::ExprBlock) -> compile::Result<()> {
-    if let Some(span) = ast.attributes.option_span() {
+    let mut p = attrs::Parser::new(&ast.attributes)?;
+    lints(idx, &mut p, &ast.attributes, &ast.block)?;
+
+    if let Some(first) = p.remaining(&ast.attributes).next() {
         return Err(compile::Error::msg(
-            span,
+            first,
             "Attributes on blocks are not supported",
         ));
     }
@@ -422,7 +512,13 @@ fn expr_block(idx: &mut Indexer<'_, '_>, ast: &mut ast::ExprBlock) -> compile::R
         let item_meta = block(idx, &mut ast.block)?;
         let layer = idx.scopes.pop().with_span(&ast)?;
 
-        let call = validate_call(ast.const_token.is_some(), ast.async_token.is_some(), &layer)?;
+        let call = validate_call(
+            ast.const_token.is_some(),
+            ast.async_token.is_some(),
+            ast.span(),
+            false,
+            &layer,
+        )?;
 
         let Some(call) = call else {
             return Err(compile::Error::new(ast, ErrorKind::ClosureKind));
@@ -444,19 +540,12 @@ fn expr_block(idx: &mut Indexer<'_, '_>, ast: &mut ast::ExprBlock) -> compile::R
 
 fn statements(idx: &mut Indexer<'_, '_>, ast: &mut Vec<ast::Stmt>) -> compile::Result<()> {
     let mut statements = Vec::new();
+    let mut block_items = Vec::new();
 
     for stmt in ast.drain(..) {
         match stmt {
             ast::Stmt::Item(i, semi) => {
-                if let Some(semi) = semi {
-                    if !i.needs_semi_colon() {
-                        idx.q
-                            .diagnostics
-                            .unnecessary_semi_colon(idx.source_id, &semi)?;
-                    }
-                }
-
-                item(idx, i)?;
+                block_items.try_push((i, semi))?;
             }
             stmt => {
                 statements.try_push(stmt)?;
@@ -464,6 +553,16 @@ fn statements(idx: &mut Indexer<'_, '_>, ast: &mut Vec<ast::Stmt>) -> compile::R
         }
     }
 
+    let mut builtin = Vec::new();
+    items(idx, block_items, &mut builtin)?;
+
+    if let Some((i, _)) = builtin.first() {
+        return Err(compile::Error::msg(
+            i,
+            "Built-in macros are not supported as items in blocks",
+        ));
+    }
+
     let mut must_be_last = None;
 
     for stmt in &mut statements {
@@ -630,8 +729,11 @@ pub(crate) fn expr(idx: &mut Indexer<'_, '_>, ast: &mut ast::Expr) -> compile::R
         ast::Expr::Let(ast) => {
             expr_let(idx, ast)?;
         }
+        // NB: blocks check their own attributes since they support lint
+        // attributes.
         ast::Expr::Block(ast) => {
             expr_block(idx, ast)?;
+            return Ok(());
         }
         ast::Expr::Group(ast) => {
             expr(idx, &mut ast.expr)?;
@@ -693,7 +795,8 @@ pub(crate) fn expr(idx: &mut Indexer<'_, '_>, ast: &mut ast::Expr) -> compile::R
         }
         ast::Expr::Await(ast) => {
             let l = idx.scopes.mark().with_span(&*ast)?;
-            l.awaits.try_push(ast.span())?;
+            l.awaits
+                .try_push(ast.dot.span().join(ast.await_token.span()))?;
             expr(idx, &mut ast.expr)?;
         }
         ast::Expr::Try(ast) => {
@@ -840,6 +943,7 @@ fn item_enum(idx: &mut Indexer<'_, '_>, mut ast: ast::ItemEnum) -> compile::Resu
     let mut p = attrs::Parser::new(&ast.attributes)?;
 
     let docs = Doc::collect_from(resolve_context!(idx.q), &mut p, &ast.attributes)?;
+    lints(idx, &mut p, &ast.attributes, &ast)?;
 
     if let Some(first) = p.remaining(&ast.attributes).next() {
         return Err(compile::Error::msg(
@@ -850,6 +954,7 @@ fn item_enum(idx: &mut Indexer<'_, '_>, mut ast: ast::ItemEnum) -> compile::Resu
 
     let name = ast.name.resolve(resolve_context!(idx.q))?;
     let guard = idx.items.push_name(name.as_ref())?;
+    idx.visit_declaration(&ast, &ast.name, DeclarationKind::Enum)?;
 
     let visibility = ast_to_visibility(&ast.visibility)?;
 
@@ -872,6 +977,11 @@ fn item_enum(idx: &mut Indexer<'_, '_>, mut ast: ast::ItemEnum) -> compile::Resu
 
         let name = variant.name.resolve(resolve_context!(idx.q))?;
         let guard = idx.items.push_name(name.as_ref())?;
+        idx.visit_declaration(
+            &with_body(variant.span(), &variant.body),
+            &variant.name,
+            DeclarationKind::Variant,
+        )?;
 
         let item_meta = idx.insert_new_item(&variant.name, Visibility::Public, &docs)?;
         let idx_item = idx.item.replace(item_meta.item);
@@ -893,6 +1003,18 @@ fn item_enum(idx: &mut Indexer<'_, '_>, mut ast: ast::ItemEnum) -> compile::Resu
 
             let name = field.name.resolve(cx)?;
 
+            if let ast::Fields::Named(..) = &variant.body {
+                idx.q
+                    .visitor
+                    .visit_field(
+                        &DynLocation::new(idx.source_id, &field.name),
+                        idx.q.pool.item(item_meta.item),
+                        idx.q.pool.item_type_hash(item_meta.item),
+                        name,
+                    )
+                    .with_span(&field.name)?;
+            }
+
             for doc in docs {
                 idx.q
                     .visitor
@@ -930,6 +1052,7 @@ fn item_struct(idx: &mut Indexer<'_, '_>, mut ast: ast::ItemStruct) -> compile::
     let mut p = attrs::Parser::new(&ast.attributes)?;
 
     let docs = Doc::collect_from(resolve_context!(idx.q), &mut p, &ast.attributes)?;
+    lints(idx, &mut p, &ast.attributes, &ast)?;
 
     if let Some(first) = p.remaining(&ast.attributes).next() {
         return Err(compile::Error::msg(
@@ -940,6 +1063,11 @@ fn item_struct(idx: &mut Indexer<'_, '_>, mut ast: ast::ItemStruct) -> compile::
 
     let ident = ast.ident.resolve(resolve_context!(idx.q))?;
     let guard = idx.items.push_name(ident)?;
+    idx.visit_declaration(
+        &with_body(ast.span(), &ast.body),
+        &ast.ident,
+        DeclarationKind::Struct,
+    )?;
 
     let visibility = ast_to_visibility(&ast.visibility)?;
     let item_meta = idx.insert_new_item(&ast, visibility, &docs)?;
@@ -968,6 +1096,18 @@ fn item_struct(idx: &mut Indexer<'_, '_>, mut ast: ast::ItemStruct) -> compile::
 
         let name = field.name.resolve(cx)?;
 
+        if let ast::Fields::Named(..) = &ast.body {
+            idx.q
+                .visitor
+                .visit_field(
+                    &DynLocation::new(idx.source_id, &field.name),
+                    idx.q.pool.item(item_meta.item),
+                    idx.q.pool.item_type_hash(item_meta.item),
+                    name,
+                )
+                .with_span(&field.name)?;
+        }
+
         for doc in docs {
             idx.q
                 .visitor
@@ -999,7 +1139,10 @@ fn item_struct(idx: &mut Indexer<'_, '_>, mut ast: ast::ItemStruct) -> compile::
 
 #[instrument_ast(span = ast)]
 fn item_impl(idx: &mut Indexer<'_, '_>, mut ast: ast::ItemImpl) -> compile::Result<()> {
-    if let Some(first) = ast.attributes.first() {
+    let mut p = attrs::Parser::new(&ast.attributes)?;
+    lints(idx, &mut p, &ast.attributes, &ast)?;
+
+    if let Some(first) = p.remaining(&ast.attributes).next() {
         return Err(compile::Error::msg(
             first,
             "Attributes on impl blocks are not supported",
@@ -1032,6 +1175,7 @@ fn item_mod(idx: &mut Indexer<'_, '_>, mut ast: ast::ItemMod) -> compile::Result
     let mut p = attrs::Parser::new(&ast.attributes)?;
 
     let docs = Doc::collect_from(resolve_context!(idx.q), &mut p, &ast.attributes)?;
+    lints(idx, &mut p, &ast.attributes, &ast)?;
 
     if let Some(first) = p.remaining(&ast.attributes).next() {
         return Err(compile::Error::msg(
@@ -1040,6 +1184,7 @@ fn item_mod(idx: &mut Indexer<'_, '_>, mut ast: ast::ItemMod) -> compile::Result
         ));
     }
 
+    let span = ast.span();
     let name_span = ast.name_span();
 
     match &mut ast.body {
@@ -1049,6 +1194,7 @@ fn item_mod(idx: &mut Indexer<'_, '_>, mut ast: ast::ItemMod) -> compile::Result
         ast::ItemModBody::InlineBody(body) => {
             let name = ast.name.resolve(resolve_context!(idx.q))?;
             let guard = idx.items.push_name(name.as_ref())?;
+            idx.visit_declaration(&span, &name_span, DeclarationKind::Module)?;
 
             let visibility = ast_to_visibility(&ast.visibility)?;
 
@@ -1078,6 +1224,7 @@ fn item_const(idx: &mut Indexer<'_, '_>, mut ast: ast::ItemConst) -> compile::Re
     let mut p = attrs::Parser::new(&ast.attributes)?;
 
     let docs = Doc::collect_from(resolve_context!(idx.q), &mut p, &ast.attributes)?;
+    lints(idx, &mut p, &ast.attributes, &ast)?;
 
     if let Some(first) = p.remaining(&ast.attributes).next() {
         return Err(compile::Error::msg(
@@ -1088,6 +1235,7 @@ fn item_const(idx: &mut Indexer<'_, '_>, mut ast: ast::ItemConst) -> compile::Re
 
     let name = ast.name.resolve(resolve_context!(idx.q))?;
     let guard = idx.items.push_name(name.as_ref())?;
+    idx.visit_declaration(&ast, &ast.name, DeclarationKind::Const)?;
 
     let item_meta = idx.insert_new_item(&ast, ast_to_visibility(&ast.visibility)?, &docs)?;
     let idx_item = idx.item.replace(item_meta.item);
@@ -1108,6 +1256,38 @@ fn item_const(idx: &mut Indexer<'_, '_>, mut ast: ast::ItemConst) -> compile::Re
     Ok(())
 }
 
+#[instrument_ast(span = ast)]
+fn item_macro(idx: &mut Indexer<'_, '_>, mut ast: ast::ItemMacro) -> compile::Result<()> {
+    let mut p = attrs::Parser::new(&ast.attributes)?;
+
+    let docs = Doc::collect_from(resolve_context!(idx.q), &mut p, &ast.attributes)?;
+    lints(idx, &mut p, &ast.attributes, &ast)?;
+
+    if let Some(first) = p.remaining(&ast.attributes).next() {
+        return Err(compile::Error::msg(
+            first,
+            "Attributes on macros are not supported",
+        ));
+    }
+
+    let declarative = DeclarativeMacro::compile(resolve_context!(idx.q), &ast)?;
+
+    let name = ast.name.resolve(resolve_context!(idx.q))?;
+    let guard = idx.items.push_name(name.as_ref())?;
+
+    let item_meta = idx.insert_new_item(
+        &ast.descriptive_span(),
+        ast_to_visibility(&ast.visibility)?,
+        &docs,
+    )?;
+
+    ast.id = item_meta.item;
+    idx.q.index_macro(item_meta, declarative)?;
+
+    idx.items.pop(guard).with_span(&ast)?;
+    Ok(())
+}
+
 #[instrument_ast(span = ast)]
 fn item(idx: &mut Indexer<'_, '_>, ast: ast::Item) -> compile::Result<()> {
     match ast {
@@ -1129,6 +1309,9 @@ fn item(idx: &mut Indexer<'_, '_>, ast: ast::Item) -> compile::Result<()> {
         ast::Item::Const(item) => {
             item_const(idx, item)?;
         }
+        ast::Item::Macro(item) => {
+            item_macro(idx, item)?;
+        }
         ast::Item::MacroCall(macro_call) => {
             // Note: There is a preprocessing step involved with items for
             // which the macro must have been expanded to a built-in macro
@@ -1261,7 +1444,12 @@ fn expr_closure(idx: &mut Indexer<'_, '_>, ast: &mut ast::ExprClosure) -> compil
 
     let layer = idx.scopes.pop().with_span(&*ast)?;
 
-    let call = validate_call(false, ast.async_token.is_some(), &layer)?;
+    let function = match &ast.move_token {
+        Some(move_token) => move_token.span().join(ast.args.span()),
+        None => ast.args.span(),
+    };
+
+    let call = validate_call(false, ast.async_token.is_some(), function, true, &layer)?;
 
     let Some(call) = call else {
         return Err(compile::Error::new(&*ast, ErrorKind::ClosureKind));
@@ -1366,3 +1554,12 @@ fn convert_fields(cx: ResolveContext<'_>, body: ast::Fields) -> compile::Result<
         }
     })
 }
+
+/// The span of a struct or variant only extends to its last field, so join it
+/// with the span of its body to cover any closing delimiter.
+fn with_body(span: Span, body: &ast::Fields) -> Span {
+    match body.option_span() {
+        Some(body) => span.join(body),
+        None => span,
+    }
+}
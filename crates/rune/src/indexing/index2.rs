@@ -6,6 +6,7 @@ use crate::ast::{self, Kind, Span, Spanned};
 use crate::compile::{
     meta, Doc, DynLocation, Error, ErrorKind, Location, Result, Visibility, WithSpan,
 };
+use crate::diagnostics::{LintLevel, WarningDiagnosticKind};
 use crate::grammar::{Ignore, MaybeNode, Node, NodeId, Remaining, Stream, StreamBuf};
 use crate::indexing;
 use crate::parse::Resolve;
@@ -36,6 +37,7 @@ enum ExprSupport {
 
 struct Function<'a> {
     node: Node<'a>,
+    fn_span: Span,
     name: Option<ast::Ident>,
     attrs: Attrs,
     mods: Mods,
@@ -81,7 +83,13 @@ enum State<'a> {
     Bare(IndexItem, NodeId),
     Function(Guard, IndexItem, usize, Option<Span>, ExprSupport),
     Module(Guard, IndexItem),
-    Closure(Guard, IndexItem, Option<ast::Async>, Option<ast::Move>),
+    Closure(
+        Guard,
+        IndexItem,
+        Span,
+        Option<ast::Async>,
+        Option<ast::Move>,
+    ),
     Const(Guard, IndexItem, Option<Span>, Node<'a>, ExprSupport),
 }
 
@@ -141,7 +149,7 @@ impl<'a> Processor<'a> {
                         .item_for("async block", idx.item.id)
                         .with_span(self.span)?;
                     let layer = idx.scopes.pop().with_span(self.span)?;
-                    let call = validate_call(false, true, &layer)?;
+                    let call = validate_call(false, true, span, false, &layer)?;
 
                     if let Some(call) = call {
                         idx.q.index_meta(
@@ -173,9 +181,15 @@ impl<'a> Processor<'a> {
                     idx.item = item;
                     idx.items.pop(guard).with_span(self.span)?;
                 }
-                State::Closure(guard, item, async_token, move_token) => {
+                State::Closure(guard, item, args, async_token, move_token) => {
                     let layer = idx.scopes.pop().with_span(self.span)?;
-                    let call = validate_call(false, async_token.is_some(), &layer)?;
+
+                    let function = match &move_token {
+                        Some(move_token) => move_token.span.join(args),
+                        None => args,
+                    };
+
+                    let call = validate_call(false, async_token.is_some(), function, true, &layer)?;
 
                     let Some(call) = call else {
                         return Err(Error::new(self.span, ErrorKind::ClosureKind));
@@ -320,12 +334,17 @@ impl<'a> Processor<'a> {
                     move_token = None;
                 }
 
+                let args = node
+                    .children()
+                    .find(|node| node.kind() == ClosureArguments)
+                    .map_or(self.span, |node| node.span());
+
                 let guard = idx.push_id()?;
                 idx.scopes.push()?;
                 let item_meta = idx.insert_new_item(&self.span, Visibility::Inherited, &[])?;
                 let item = idx.item.replace(item_meta.item);
                 self.stack
-                    .try_push(State::Closure(guard, item, async_token, move_token))?;
+                    .try_push(State::Closure(guard, item, args, async_token, move_token))?;
                 node.replace(Closure(idx.item.id));
             }
             Path => {
@@ -441,6 +460,7 @@ impl<'a> Processor<'a> {
                 is_test: false,
                 is_bench: false,
                 impl_item: None,
+                protocol: None,
                 args: Vec::new(),
             }),
         })?;
@@ -469,6 +489,7 @@ impl<'a> Processor<'a> {
 
         let Function {
             node,
+            fn_span,
             name,
             attrs,
             mods,
@@ -489,6 +510,8 @@ impl<'a> Processor<'a> {
         let call = validate_call(
             mods.const_token.is_some(),
             mods.async_token.is_some(),
+            fn_span,
+            false,
             &layer,
         )?;
 
@@ -555,6 +578,7 @@ impl<'a> Processor<'a> {
                 is_test,
                 is_bench,
                 impl_item: idx.item.impl_item,
+                protocol: None,
                 args,
             }),
         };
@@ -622,7 +646,7 @@ impl<'a> Processor<'a> {
     ) -> Result<(), Error> {
         let expr = replace(&mut self.expr, ExprSupport::Yes);
 
-        p.expect(K![fn])?;
+        let fn_span = p.expect(K![fn])?.span();
 
         let (guard, name) = push_name(idx, p, "function")?;
         let item_meta = idx.insert_new_item(&*p, mods.visibility, &attrs.docs)?;
@@ -634,6 +658,7 @@ impl<'a> Processor<'a> {
 
         self.fns.try_push(Function {
             node: p.node(),
+            fn_span,
             name,
             attrs,
             mods,
@@ -1035,6 +1060,8 @@ fn push_name(
 
 fn attributes(idx: &mut Indexer<'_, '_>, p: &mut Stream<'_>) -> Result<Attrs> {
     let mut attrs = Attrs::default();
+    // Lint levels apply to everything the attributes are attached to.
+    let scope = p.span();
 
     while let MaybeNode::Some(node) = p.eat(Attribute) {
         node.parse(|p| {
@@ -1099,6 +1126,9 @@ fn attributes(idx: &mut Indexer<'_, '_>, p: &mut Stream<'_>) -> Result<Attrs> {
                             attrs.builtin = Some((ident.span, literal));
                         }
                     }
+                    "allow" => lint(idx, p, span, scope, LintLevel::Allow)?,
+                    "warn" => lint(idx, p, span, scope, LintLevel::Warn)?,
+                    "deny" => lint(idx, p, span, scope, LintLevel::Deny)?,
                     name => {
                         idx.error(Error::msg(
                             ident,
@@ -1118,6 +1148,42 @@ fn attributes(idx: &mut Indexer<'_, '_>, p: &mut Stream<'_>) -> Result<Attrs> {
     Ok(attrs)
 }
 
+/// Register the level of the warnings listed in an `#[allow(..)]`,
+/// `#[warn(..)]` or `#[deny(..)]` attribute, which applies to everything
+/// within `scope`.
+fn lint(
+    idx: &mut Indexer<'_, '_>,
+    p: &mut Stream<'_>,
+    attr: Span,
+    scope: Span,
+    level: LintLevel,
+) -> Result<()> {
+    p.expect(K!['('])?;
+
+    while matches!(p.peek(), K![ident]) {
+        let ident = p.ast::<ast::Ident>()?;
+        let name = ident.resolve(resolve_context!(idx.q))?;
+
+        match WarningDiagnosticKind::find_name(name) {
+            Some(known) => {
+                idx.q
+                    .diagnostics
+                    .lint_level(idx.source_id, &scope, known, level)?;
+            }
+            None => {
+                idx.q
+                    .diagnostics
+                    .unknown_lint(idx.source_id, &attr, name.try_to_owned()?)?;
+            }
+        }
+
+        p.remaining(idx, K![,])?.ignore(idx)?;
+    }
+
+    p.expect(K![')'])?;
+    Ok(())
+}
+
 fn inner_attributes(idx: &mut Indexer<'_, '_>, p: &mut Stream<'_>) -> Result<()> {
     while let MaybeNode::Some(node) = p.eat(InnerAttribute) {
         node.parse(|p| {
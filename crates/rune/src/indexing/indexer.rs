@@ -10,7 +10,8 @@ use crate::ast::spanned;
 use crate::ast::{self, Span, Spanned};
 use crate::compile::attrs;
 use crate::compile::{
-    self, Doc, DynLocation, Error, ErrorKind, ItemId, ItemMeta, ModId, Visibility, WithSpan,
+    self, DeclarationKind, Doc, DynLocation, Error, ErrorKind, ItemId, ItemMeta, ModId, Visibility,
+    WithSpan,
 };
 use crate::grammar::{Ignore, Node, Tree};
 use crate::macros::MacroCompiler;
@@ -22,9 +23,6 @@ use crate::SourceId;
 
 use super::{Guard, Items, Layer, Scopes};
 
-/// Macros are only allowed to expand recursively into other macros 64 times.
-const MAX_MACRO_RECURSION: usize = 64;
-
 pub(crate) struct Indexer<'a, 'arena> {
     /// Query engine.
     pub(crate) q: Query<'a, 'arena>,
@@ -96,8 +94,28 @@ impl Indexer<'_, '_> {
         )
     }
 
+    /// Report the declaration of the current item to the compile visitor.
+    pub(super) fn visit_declaration(
+        &mut self,
+        span: &dyn Spanned,
+        name: &dyn Spanned,
+        kind: DeclarationKind,
+    ) -> compile::Result<()> {
+        self.q
+            .visitor
+            .visit_declaration(
+                &DynLocation::new(self.source_id, span),
+                name.span(),
+                self.items.item(),
+                kind,
+            )
+            .with_span(span)?;
+
+        Ok(())
+    }
+
     /// Indicate that we've entered an expanded macro context, and ensure that
-    /// we don't blow past [`MAX_MACRO_RECURSION`].
+    /// we don't blow past the configured maximum macro depth.
     ///
     /// This is used when entering expressions which have been expanded from a
     /// macro - cause those expression might in turn be macros themselves.
@@ -107,12 +125,14 @@ impl Indexer<'_, '_> {
     {
         self.macro_depth = self.macro_depth.wrapping_add(1);
 
-        if self.macro_depth >= MAX_MACRO_RECURSION {
+        let max = self.q.options.max_macro_depth;
+
+        if self.macro_depth >= max {
             return Err(compile::Error::new(
                 span,
                 ErrorKind::MaxMacroRecursion {
                     depth: self.macro_depth,
-                    max: MAX_MACRO_RECURSION,
+                    max,
                 },
             ));
         }
@@ -315,7 +335,7 @@ impl Indexer<'_, '_> {
                         return Err(compile::Error::msg(arg, "Argument out-of-bounds"));
                     };
 
-                    precision = NonZeroUsize::new(f);
+                    precision = Some(f);
                 }
                 "type" => {
                     if format_type.is_some() {
@@ -450,6 +470,12 @@ impl Indexer<'_, '_> {
         let visibility = ast_to_visibility(&ast.visibility)?;
         let guard = self.items.push_name(name.as_ref())?;
 
+        self.visit_declaration(
+            &*ast,
+            &spanned::from_fn(|| ast.name_span()),
+            DeclarationKind::Module,
+        )?;
+
         let (mod_item, mod_item_id) = self.q.insert_mod(
             &self.items,
             &DynLocation::new(self.source_id, spanned::from_fn(|| ast.name_span())),
@@ -572,25 +598,40 @@ pub(super) fn ast_to_visibility(vis: &ast::Visibility) -> compile::Result<Visibi
     Err(compile::Error::new(span, ErrorKind::UnsupportedVisibility))
 }
 
-/// Construct the calling convention based on the parameters.
+/// Validate the calling convention of a function or closure.
+///
+/// The `function` span identifies the function being validated, which is the
+/// `fn` keyword for functions and the argument list for closures. It is used to
+/// label errors and as the insertion point for suggestions.
 pub(super) fn validate_call(
     is_const: bool,
     is_async: bool,
+    function: Span,
+    closure: bool,
     layer: &Layer,
 ) -> compile::Result<Option<Call>> {
     for span in &layer.awaits {
         if is_const {
-            return Err(compile::Error::new(span, ErrorKind::AwaitInConst));
+            return Err(compile::Error::new(
+                span,
+                ErrorKind::AwaitInConst { function },
+            ));
         }
 
         if !is_async {
-            return Err(compile::Error::new(span, ErrorKind::AwaitOutsideAsync));
+            return Err(compile::Error::new(
+                span,
+                ErrorKind::AwaitOutsideAsync { function, closure },
+            ));
         }
     }
 
     for span in &layer.yields {
         if is_const {
-            return Err(compile::Error::new(span, ErrorKind::YieldInConst));
+            return Err(compile::Error::new(
+                span,
+                ErrorKind::YieldInConst { function },
+            ));
         }
     }
 
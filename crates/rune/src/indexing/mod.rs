@@ -10,7 +10,7 @@ use crate::ast::{self, Span, Spanned};
 use crate::compile::meta;
 use crate::compile::{ItemId, ItemMeta};
 use crate::grammar::NodeAt;
-use crate::runtime::Call;
+use crate::runtime::{Call, Protocol};
 
 use self::indexer::{ast_to_visibility, validate_call};
 pub(crate) use self::indexer::{IndexItem, Indexer};
@@ -58,6 +58,8 @@ pub(crate) enum Indexed {
     Import(Import),
     /// An indexed module.
     Module,
+    /// A declarative macro.
+    Macro,
 }
 
 /// The ast of a function.
@@ -87,6 +89,8 @@ pub(crate) struct Function {
     pub(crate) is_bench: bool,
     /// The impl item this function is registered in.
     pub(crate) impl_item: Option<ItemId>,
+    /// The protocol this function implements for the type of its impl item.
+    pub(crate) protocol: Option<&'static Protocol>,
     /// Spans of the arguments to the function for diagnostics.
     pub(crate) args: Vec<Span>,
 }
@@ -1,4 +1,5 @@
 use std::borrow::ToOwned;
+use std::collections::HashSet;
 
 use anyhow::Result;
 use lsp::CompletionItem;
@@ -12,19 +13,228 @@ use lsp::TextEdit;
 
 use crate::alloc::fmt::TryWrite;
 use crate::alloc::prelude::*;
-use crate::alloc::{String, Vec};
+use crate::alloc::{self, String, Vec};
+use crate::compile::context::ContextMeta;
 use crate::compile::meta;
+use crate::item::ComponentRef;
 use crate::runtime::debug::DebugArgs;
-use crate::Context;
-use crate::Unit;
+use crate::runtime::TypeHash;
+use crate::{Context, Hash, ItemBuf, Unit};
 
 use super::state::ServerSource;
 
+/// What is being completed at a given position.
+pub(super) enum Completion<'a> {
+    /// Items inside of a module or a type, like `std::string::Str`.
+    Path {
+        path: Vec<&'a str>,
+        partial: &'a str,
+    },
+    /// Instance functions, like `"hello".le`.
+    ///
+    /// The type of the receiver is only known if it is a literal.
+    Instance {
+        receiver: Option<Hash>,
+        partial: &'a str,
+    },
+    /// A plain identifier, like the name of a local variable.
+    Ident { partial: &'a str },
+}
+
+impl<'a> Completion<'a> {
+    /// Determine what to complete from the text of the line leading up to the
+    /// cursor.
+    pub(super) fn parse(line: &'a str) -> alloc::Result<Self> {
+        let (before, partial) = split_ident(line);
+
+        if let Some(mut rest) = before.strip_suffix("::") {
+            let mut path = Vec::new();
+
+            loop {
+                let (before, segment) = split_ident(rest);
+
+                if segment.is_empty() {
+                    break;
+                }
+
+                path.try_push(segment)?;
+
+                let Some(before) = before.strip_suffix("::") else {
+                    break;
+                };
+
+                rest = before;
+            }
+
+            path.reverse();
+
+            if !path.is_empty() {
+                return Ok(Self::Path { path, partial });
+            }
+        }
+
+        if let Some(receiver) = before.strip_suffix('.') {
+            return Ok(Self::Instance {
+                receiver: literal_type(receiver.trim_end()),
+                partial,
+            });
+        }
+
+        Ok(Self::Ident { partial })
+    }
+
+    /// The partially typed name being completed.
+    pub(super) fn partial(&self) -> &'a str {
+        match self {
+            Self::Path { partial, .. } => partial,
+            Self::Instance { partial, .. } => partial,
+            Self::Ident { partial } => partial,
+        }
+    }
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Split a trailing identifier off the given text.
+fn split_ident(text: &str) -> (&str, &str) {
+    text.split_at(text.trim_end_matches(is_ident_char).len())
+}
+
+/// Get the type of a receiver if it is a literal.
+fn literal_type(receiver: &str) -> Option<Hash> {
+    if receiver.ends_with(['"', '`']) {
+        return Some(String::HASH);
+    }
+
+    if receiver.ends_with('\'') {
+        return Some(char::HASH);
+    }
+
+    let (before, last) = split_ident(receiver);
+
+    if matches!(last, "true" | "false") && !before.ends_with('.') {
+        return Some(bool::HASH);
+    }
+
+    if !last.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let Some(before) = before.strip_suffix('.') else {
+        return Some(i64::HASH);
+    };
+
+    // Something like `1.5`, as opposed to a tuple index like `a.0`.
+    let (before, whole) = split_ident(before);
+
+    if whole.starts_with(|c: char| c.is_ascii_digit()) && !before.ends_with('.') {
+        return Some(f64::HASH);
+    }
+
+    None
+}
+
+/// Construct a text edit replacing the given range.
+fn edit(range: lsp::Range, text: std::string::String) -> Option<CompletionTextEdit> {
+    Some(CompletionTextEdit::Edit(TextEdit {
+        range,
+        new_text: text,
+    }))
+}
+
+/// Convert documentation lines into markdown.
+fn markdown<S>(lines: &[S]) -> Option<Documentation>
+where
+    S: AsRef<str>,
+{
+    if lines.is_empty() {
+        return None;
+    }
+
+    Some(Documentation::MarkupContent(MarkupContent {
+        kind: MarkupKind::Markdown,
+        value: lines
+            .iter()
+            .map(|line| line.as_ref())
+            .collect::<std::vec::Vec<_>>()
+            .join("\n"),
+    }))
+}
+
+/// Get the detail of a native function, like `(a, b) -> String`.
+fn native_detail(context: &Context, meta: &ContextMeta) -> Option<std::string::String> {
+    let signature = meta.kind.as_signature()?;
+
+    let return_type = signature
+        .return_type
+        .base
+        .as_non_empty()
+        .and_then(|hash| context.lookup_meta_by_hash(hash).next())
+        .and_then(|r| r.item.as_deref())?;
+
+    let args = meta.docs.args().join(", ");
+    Some(format!("({args}) -> {return_type}"))
+}
+
+/// Get the completion kind of a native item.
+fn native_kind(meta: &ContextMeta) -> Option<CompletionItemKind> {
+    let kind = match &meta.kind {
+        meta::Kind::Type { .. } | meta::Kind::Struct { .. } => CompletionItemKind::STRUCT,
+        meta::Kind::Enum { .. } => CompletionItemKind::ENUM,
+        meta::Kind::Variant { .. } => CompletionItemKind::ENUM_MEMBER,
+        meta::Kind::Function {
+            associated: Some(meta::AssociatedKind::Instance(..)),
+            ..
+        } => CompletionItemKind::METHOD,
+        meta::Kind::Function { .. } | meta::Kind::ConstFn => CompletionItemKind::FUNCTION,
+        meta::Kind::Const => CompletionItemKind::CONSTANT,
+        meta::Kind::Module => CompletionItemKind::MODULE,
+        meta::Kind::Trait => CompletionItemKind::INTERFACE,
+        _ => return None,
+    };
+
+    Some(kind)
+}
+
+/// Complete local variables which are in scope at the given byte offset.
+pub(super) fn complete_locals(
+    workspace_source: &ServerSource,
+    offset: usize,
+    partial: &str,
+    range: lsp::Range,
+    results: &mut Vec<CompletionItem>,
+) -> Result<()> {
+    let mut seen = HashSet::new();
+
+    // Later definitions shadow earlier ones.
+    for local in workspace_source.locals_at(offset).rev() {
+        if !local.name.starts_with(partial) || !seen.insert(local.name.as_str()) {
+            continue;
+        }
+
+        results.try_push(CompletionItem {
+            label: local.name.as_str().to_owned(),
+            kind: Some(CompletionItemKind::VARIABLE),
+            text_edit: edit(range, local.name.as_str().to_owned()),
+            ..Default::default()
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Complete functions defined in the unit.
+///
+/// With an empty path, functions are matched by their full path. Otherwise
+/// only functions directly inside of the given path are matched by name.
 pub(super) fn complete_for_unit(
     workspace_source: &ServerSource,
     unit: &Unit,
-    symbol: &str,
-    position: lsp::Position,
+    path: &[&str],
+    partial: &str,
+    range: lsp::Range,
     results: &mut Vec<CompletionItem>,
 ) -> Result<()> {
     let Some(debug_info) = unit.debug_info() else {
@@ -32,16 +242,36 @@ pub(super) fn complete_for_unit(
     };
 
     for (hash, function) in debug_info.functions.iter() {
-        let func_name = function.try_to_string()?;
-
-        if !func_name.starts_with(symbol) {
-            continue;
-        }
-
         let Some(last) = function.path.base_name() else {
             continue;
         };
 
+        let new_text = if path.is_empty() {
+            let func_name = function.path.try_to_string()?;
+            let func_name = func_name.trim_start_matches("::");
+
+            if !func_name.starts_with(partial) {
+                continue;
+            }
+
+            func_name.to_owned()
+        } else {
+            let Some(parent) = function.path.parent() else {
+                continue;
+            };
+
+            let components = parent.iter().map(|c| match c {
+                ComponentRef::Crate(name) | ComponentRef::Str(name) => Some(name),
+                ComponentRef::Id(..) => None,
+            });
+
+            if !components.eq(path.iter().map(|name| Some(*name))) || !last.starts_with(partial) {
+                continue;
+            }
+
+            last.to_owned()
+        };
+
         let args = match &function.args {
             DebugArgs::EmptyArgs => None,
             DebugArgs::TupleArgs(n) => Some({
@@ -65,7 +295,7 @@ pub(super) fn complete_for_unit(
 
         let docs = workspace_source
             .get_docs_by_hash(*hash)
-            .map(|docs| docs.docs.join("\n"));
+            .and_then(|docs| markdown(&docs.docs));
 
         let detail = args.map(|a| format!("({a:}) -> ?"));
 
@@ -73,22 +303,8 @@ pub(super) fn complete_for_unit(
             label: last.to_owned(),
             kind: Some(CompletionItemKind::FUNCTION),
             detail: detail.clone(),
-            documentation: docs.map(|d| {
-                Documentation::MarkupContent(MarkupContent {
-                    kind: MarkupKind::Markdown,
-                    value: d,
-                })
-            }),
-            text_edit: Some(CompletionTextEdit::Edit(TextEdit {
-                range: lsp::Range {
-                    start: lsp::Position {
-                        line: position.line,
-                        character: position.character - symbol.len() as u32,
-                    },
-                    end: position,
-                },
-                new_text: format!("{}", function.path),
-            })),
+            documentation: docs,
+            text_edit: edit(range, new_text),
             label_details: Some(CompletionItemLabelDetails {
                 detail,
                 description: None,
@@ -101,79 +317,136 @@ pub(super) fn complete_for_unit(
     Ok(())
 }
 
+/// Complete native items which are directly inside of the given path.
+pub(super) fn complete_native_path(
+    context: &Context,
+    path: &[&str],
+    partial: &str,
+    range: lsp::Range,
+    results: &mut Vec<CompletionItem>,
+) -> Result<()> {
+    let Some((first, rest)) = path.split_first() else {
+        return Ok(());
+    };
+
+    let item = ItemBuf::with_crate_item(first, rest.iter().copied())?;
+
+    for c in context.iter_components(&item)? {
+        let ComponentRef::Str(name) = c else {
+            continue;
+        };
+
+        if !name.starts_with(partial) {
+            continue;
+        }
+
+        let mut child = item.try_clone()?;
+        child.push(name)?;
+
+        let meta = context.lookup_meta(&child).and_then(|mut meta| meta.next());
+
+        // Components without meta are modules which have items in them.
+        let (kind, detail, documentation) = match meta {
+            Some(meta) => {
+                let Some(kind) = native_kind(meta) else {
+                    continue;
+                };
+
+                (
+                    kind,
+                    native_detail(context, meta),
+                    markdown(meta.docs.lines()),
+                )
+            }
+            None => (CompletionItemKind::MODULE, None, None),
+        };
+
+        results.try_push(CompletionItem {
+            label: name.to_owned(),
+            kind: Some(kind),
+            detail,
+            documentation,
+            text_edit: edit(range, name.to_owned()),
+            label_details: Some(CompletionItemLabelDetails {
+                detail: None,
+                description: Some(child.try_to_string()?.into_std()),
+            }),
+            ..Default::default()
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Complete native instance functions, only including the ones associated
+/// with the receiver type if it is known.
 pub(super) fn complete_native_instance_data(
     context: &Context,
-    symbol: &str,
-    position: lsp::Position,
+    receiver: Option<Hash>,
+    partial: &str,
+    range: lsp::Range,
     results: &mut Vec<CompletionItem>,
 ) -> Result<()> {
-    for (meta, signature) in context.iter_functions() {
-        let (prefix, kind, n) = match (&meta.item, &meta.kind) {
+    for meta in context.iter_meta() {
+        let (prefix, n) = match (&meta.item, &meta.kind) {
             (
                 Some(item),
                 meta::Kind::Function {
                     associated: Some(meta::AssociatedKind::Instance(name)),
+                    container,
                     ..
                 },
-            ) => (item, CompletionItemKind::FUNCTION, name),
+            ) if receiver.is_none() || *container == receiver => (item, name),
             _ => continue,
         };
 
-        if n.starts_with(symbol) {
-            let return_type = signature
-                .return_type
-                .base
-                .as_non_empty()
-                .and_then(|hash| context.lookup_meta_by_hash(hash).next())
-                .and_then(|r| r.item.as_deref());
-
-            let docs = meta.docs.lines().join("\n");
-            let args = meta.docs.args().join(", ");
-
-            let detail = return_type.map(|r| format!("({args}) -> {r}"));
+        if !n.starts_with(partial) {
+            continue;
+        }
 
-            let Ok(data) = serde_json::to_value(meta.hash.into_inner()) else {
-                continue;
-            };
+        let Ok(data) = serde_json::to_value(meta.hash.into_inner()) else {
+            continue;
+        };
 
-            results.try_push(CompletionItem {
-                label: n.try_to_string()?.into_std(),
-                kind: Some(kind),
-                detail,
-                documentation: Some(lsp::Documentation::MarkupContent(MarkupContent {
-                    kind: MarkupKind::Markdown,
-                    value: docs,
-                })),
-                text_edit: Some(CompletionTextEdit::Edit(TextEdit {
-                    range: lsp::Range {
-                        start: lsp::Position {
-                            line: position.line,
-                            character: position.character - symbol.len() as u32,
-                        },
-                        end: position,
-                    },
-                    new_text: n.try_to_string()?.into_std(),
-                })),
-                label_details: Some(CompletionItemLabelDetails {
-                    detail: None,
-                    description: Some(prefix.try_to_string()?.into_std()),
-                }),
-                data: Some(data),
-                ..Default::default()
-            })?;
-        }
+        results.try_push(CompletionItem {
+            label: n.try_to_string()?.into_std(),
+            kind: Some(CompletionItemKind::METHOD),
+            detail: native_detail(context, meta),
+            documentation: markdown(meta.docs.lines()),
+            text_edit: edit(range, n.try_to_string()?.into_std()),
+            label_details: Some(CompletionItemLabelDetails {
+                detail: None,
+                description: Some(prefix.try_to_string()?.into_std()),
+            }),
+            data: Some(data),
+            ..Default::default()
+        })?;
     }
 
     Ok(())
 }
 
+/// Complete crates and native functions by their full path.
 pub(super) fn complete_native_loose_data(
     context: &Context,
-    symbol: &str,
-    position: lsp::Position,
+    partial: &str,
+    range: lsp::Range,
     results: &mut Vec<CompletionItem>,
 ) -> Result<()> {
-    for (meta, signature) in context.iter_functions() {
+    for name in context.iter_crates() {
+        if !name.starts_with(partial) {
+            continue;
+        }
+
+        results.try_push(CompletionItem {
+            label: name.to_owned(),
+            kind: Some(CompletionItemKind::MODULE),
+            text_edit: edit(range, name.to_owned()),
+            ..Default::default()
+        })?;
+    }
+
+    for (meta, _) in context.iter_functions() {
         let (item, kind) = match (&meta.item, &meta.kind) {
             (Some(item), meta::Kind::Function { .. }) => (item, CompletionItemKind::FUNCTION),
             _ => continue,
@@ -184,45 +457,23 @@ pub(super) fn complete_native_loose_data(
             .trim_start_matches("::")
             .try_to_owned()?;
 
-        if func_name.starts_with(symbol) {
-            let return_type = signature
-                .return_type
-                .base
-                .as_non_empty()
-                .and_then(|hash| context.lookup_meta_by_hash(hash).next())
-                .and_then(|r| r.item.as_deref());
-
-            let docs = meta.docs.lines().join("\n");
-            let args = meta.docs.args().join(", ");
-
-            let detail = return_type.map(|r| format!("({args}) -> {r}"));
+        if !func_name.starts_with(partial) {
+            continue;
+        }
 
-            let Ok(data) = serde_json::to_value(meta.hash.into_inner()) else {
-                continue;
-            };
+        let Ok(data) = serde_json::to_value(meta.hash.into_inner()) else {
+            continue;
+        };
 
-            results.try_push(CompletionItem {
-                label: func_name.try_clone()?.into_std(),
-                kind: Some(kind),
-                detail,
-                documentation: Some(lsp::Documentation::MarkupContent(MarkupContent {
-                    kind: MarkupKind::Markdown,
-                    value: docs,
-                })),
-                text_edit: Some(lsp::CompletionTextEdit::Edit(TextEdit {
-                    range: lsp::Range {
-                        start: lsp::Position {
-                            line: position.line,
-                            character: position.character - symbol.len() as u32,
-                        },
-                        end: position,
-                    },
-                    new_text: func_name.into_std(),
-                })),
-                data: Some(data),
-                ..Default::default()
-            })?;
-        }
+        results.try_push(CompletionItem {
+            label: func_name.try_clone()?.into_std(),
+            kind: Some(kind),
+            detail: native_detail(context, meta),
+            documentation: markdown(meta.docs.lines()),
+            text_edit: edit(range, func_name.into_std()),
+            data: Some(data),
+            ..Default::default()
+        })?;
     }
 
     Ok(())
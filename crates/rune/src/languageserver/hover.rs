@@ -0,0 +1,317 @@
+use crate::alloc::fmt::TryWrite;
+use crate::alloc::prelude::*;
+use crate::alloc::{self, String};
+use crate::ast;
+use crate::compile::context::ContextMeta;
+use crate::compile::meta;
+use crate::doc::VisitorData;
+use crate::parse::Parser;
+use crate::runtime::Protocol;
+use crate::{Context, Hash, Item, SourceId};
+
+/// A field access under the cursor, like `player.position`.
+pub(super) struct FieldAccess<'a> {
+    /// The identifier of the receiver, if it is a plain identifier.
+    pub(super) receiver: Option<&'a str>,
+    /// The name of the field being accessed.
+    pub(super) field: &'a str,
+}
+
+impl<'a> FieldAccess<'a> {
+    /// Find a field access in the given line, where `at` is the byte offset
+    /// of the cursor in the line.
+    pub(super) fn parse(line: &'a str, at: usize) -> Option<Self> {
+        let before = line.get(..at)?;
+        let after = line.get(at..)?;
+
+        let start = before.trim_end_matches(is_ident_char).len();
+        let end = at + (after.len() - after.trim_start_matches(is_ident_char).len());
+
+        let field = &line[start..end];
+
+        if field.is_empty() || field.starts_with(|c: char| c.is_ascii_digit()) {
+            return None;
+        }
+
+        // A call to an instance function rather than a field access.
+        if line[end..].trim_start().starts_with('(') {
+            return None;
+        }
+
+        let before = line[..start].strip_suffix('.')?;
+        let receiver = &before[before.trim_end_matches(is_ident_char).len()..];
+
+        Some(Self {
+            receiver: (!receiver.is_empty()).then_some(receiver),
+            field,
+        })
+    }
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Describe a local variable, including its kind if it is known.
+pub(super) fn local(name: &str, kind: Option<&str>) -> alloc::Result<String> {
+    let mut header = String::new();
+
+    if name != "self" {
+        header.try_push_str("let ")?;
+    }
+
+    header.try_push_str(name)?;
+
+    if let Some(kind) = kind {
+        write!(header, ": {kind}")?;
+    }
+
+    render::<&str>(&header, &[])
+}
+
+/// Describe a native item.
+pub(super) fn native(context: &Context, meta: &ContextMeta) -> alloc::Result<Option<String>> {
+    let Some(item) = &meta.item else {
+        return Ok(None);
+    };
+
+    let header = header(context, item, &meta.kind)?;
+    Ok(Some(render(&header, meta.docs.lines())?))
+}
+
+/// Describe an item defined in a script.
+pub(super) fn script(context: &Context, data: &VisitorData) -> alloc::Result<Option<String>> {
+    let Some(kind) = &data.kind else {
+        return Ok(None);
+    };
+
+    let header = header(context, &data.item, kind)?;
+    Ok(Some(render(&header, &data.docs)?))
+}
+
+/// Describe a field through the `GET` field functions registered in the
+/// context.
+///
+/// If the kind of the receiver is known, only fields on types with a matching
+/// name are considered.
+pub(super) fn field(
+    context: &Context,
+    receiver: Option<&str>,
+    field: &str,
+) -> alloc::Result<Option<String>> {
+    let receiver = receiver.map(|kind| kind.rsplit("::").next().unwrap_or(kind));
+    let mut out = String::new();
+
+    for meta in context.iter_meta() {
+        let meta::Kind::Function {
+            associated: Some(meta::AssociatedKind::FieldFn(protocol, name)),
+            signature,
+            container,
+            ..
+        } = &meta.kind
+        else {
+            continue;
+        };
+
+        if protocol.hash != Protocol::GET.hash || name.as_ref() != field {
+            continue;
+        }
+
+        if let Some(receiver) = receiver {
+            if type_name(context, *container).and_then(Item::base_name) != Some(receiver) {
+                continue;
+            }
+        }
+
+        if !out.is_empty() {
+            out.try_push_str("\n\n---\n\n")?;
+        }
+
+        // Field functions are not named items, so their header is built from
+        // the type they belong to.
+        let header = field_header(context, *container, name, signature)?;
+        out.try_push_str(&render(&header, meta.docs.lines())?)?;
+    }
+
+    Ok((!out.is_empty()).then_some(out))
+}
+
+/// Infer the kind of a local variable from the text following its
+/// declaration, like ` = Player { .. };`.
+pub(super) fn infer_kind(text: &str) -> alloc::Result<Option<String>> {
+    let Some(text) = text.trim_start().strip_prefix('=') else {
+        return Ok(None);
+    };
+
+    let Ok(expr) = Parser::new(text, SourceId::EMPTY, false).parse::<ast::Expr>() else {
+        return Ok(None);
+    };
+
+    let kind = match &expr {
+        ast::Expr::Lit(expr) => match &expr.lit {
+            ast::Lit::Bool(..) => "bool",
+            ast::Lit::Byte(..) => "u8",
+            ast::Lit::Str(..) => "String",
+            ast::Lit::ByteStr(..) => "Bytes",
+            ast::Lit::Char(..) => "char",
+            ast::Lit::Number(number) => match number.source {
+                ast::NumberSource::Text(number) => match text.get(number.suffix.range()) {
+                    Some(suffix) if !suffix.is_empty() => suffix,
+                    _ if number.is_fractional => "f64",
+                    _ => "i64",
+                },
+                _ => return Ok(None),
+            },
+        },
+        ast::Expr::Tuple(..) => "tuple",
+        ast::Expr::Vec(..) => "Vec",
+        ast::Expr::Object(expr) => match &expr.ident {
+            ast::ObjectIdent::Anonymous(..) => "Object",
+            ast::ObjectIdent::Named(path) => return path_text(text, path),
+        },
+        ast::Expr::Call(expr) => match &*expr.expr {
+            ast::Expr::Path(path) => return path_text(text, path),
+            _ => return Ok(None),
+        },
+        _ => return Ok(None),
+    };
+
+    Ok(Some(kind.try_to_owned()?))
+}
+
+/// The text of a path which names a type or a variant, like `Point` in
+/// `Point(1, 2)`.
+fn path_text(text: &str, path: &ast::Path) -> alloc::Result<Option<String>> {
+    let Some(path) = text.get(ast::Spanned::span(path).range()) else {
+        return Ok(None);
+    };
+
+    let name = path.rsplit("::").next().unwrap_or(path);
+
+    if !name.starts_with(|c: char| c.is_uppercase()) {
+        return Ok(None);
+    }
+
+    Ok(Some(path.try_to_owned()?))
+}
+
+/// Render the header of an item as it would be declared.
+fn header(context: &Context, item: &Item, kind: &meta::Kind) -> alloc::Result<String> {
+    let mut out = String::new();
+
+    match kind {
+        meta::Kind::Function {
+            associated: Some(meta::AssociatedKind::FieldFn(_, field)),
+            signature,
+            container,
+            ..
+        } => {
+            return field_header(context, *container, field, signature);
+        }
+        meta::Kind::Function { signature, .. } => {
+            if signature.is_async {
+                out.try_push_str("async ")?;
+            }
+
+            write!(out, "fn {item}(")?;
+
+            match &signature.arguments {
+                Some(arguments) => {
+                    let mut it = arguments.iter().peekable();
+
+                    while let Some(argument) = it.next() {
+                        write!(out, "{}", argument.name)?;
+
+                        if let Some(ty) = type_name(context, argument.base.as_non_empty()) {
+                            write!(out, ": {}", Name(ty))?;
+                        }
+
+                        if it.peek().is_some() {
+                            out.try_push_str(", ")?;
+                        }
+                    }
+                }
+                None => {
+                    out.try_push_str("..")?;
+                }
+            }
+
+            out.try_push(')')?;
+
+            if let Some(ty) = type_name(context, signature.return_type.base.as_non_empty()) {
+                write!(out, " -> {}", Name(ty))?;
+            }
+        }
+        meta::Kind::Type { .. } | meta::Kind::Struct { .. } => write!(out, "struct {item}")?,
+        meta::Kind::Enum { .. } => write!(out, "enum {item}")?,
+        meta::Kind::Const => write!(out, "const {item}")?,
+        meta::Kind::Module => write!(out, "mod {item}")?,
+        meta::Kind::Trait => write!(out, "trait {item}")?,
+        _ => write!(out, "{item}")?,
+    }
+
+    Ok(out)
+}
+
+/// Render the header of a field, like `Player.position: i64`.
+fn field_header(
+    context: &Context,
+    container: Option<Hash>,
+    field: &str,
+    signature: &meta::Signature,
+) -> alloc::Result<String> {
+    let mut out = String::new();
+
+    match type_name(context, container).and_then(Item::base_name) {
+        Some(container) => write!(out, "{container}.{field}")?,
+        None => out.try_push_str(field)?,
+    }
+
+    if let Some(ty) = type_name(context, signature.return_type.base.as_non_empty()) {
+        write!(out, ": {}", Name(ty))?;
+    }
+
+    Ok(out)
+}
+
+/// Look up the item of a type in the context.
+fn type_name(context: &Context, hash: Option<Hash>) -> Option<&Item> {
+    context.lookup_meta_by_hash(hash?).next()?.item.as_deref()
+}
+
+/// Display the name of a type without its module, like `String` for
+/// `::std::string::String`.
+struct Name<'a>(&'a Item);
+
+impl core::fmt::Display for Name<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.0.last() {
+            Some(last) => last.fmt(f),
+            None => self.0.fmt(f),
+        }
+    }
+}
+
+/// Render a header as a fenced rune code block followed by documentation.
+fn render<S>(header: &str, docs: &[S]) -> alloc::Result<String>
+where
+    S: AsRef<str>,
+{
+    let mut out = String::new();
+    write!(out, "```rune\n{header}\n```")?;
+
+    if !docs.is_empty() {
+        out.try_push_str("\n\n")?;
+
+        for (n, line) in docs.iter().enumerate() {
+            if n > 0 {
+                out.try_push('\n')?;
+            }
+
+            let line = line.as_ref();
+            out.try_push_str(line.strip_prefix(' ').unwrap_or(line))?;
+        }
+    }
+
+    Ok(out)
+}
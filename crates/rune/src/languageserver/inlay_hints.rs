@@ -0,0 +1,293 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use lsp::Url;
+use serde::Deserialize;
+
+use crate::alloc::prelude::*;
+use crate::alloc::{self, HashMap, String, Vec};
+use crate::ast::{self, Span, Spanned};
+use crate::compile::meta;
+use crate::languageserver::hover;
+use crate::languageserver::semantic_tokens::path_name;
+use crate::languageserver::state::{DefinitionKind, DefinitionSource, Index, StateEncoding};
+use crate::parse::Parser;
+use crate::{Context, Hash, SourceId, Sources};
+
+/// Which inlay hints to show, as configured through the initialization
+/// options of the client.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(super) struct InlayHintOptions {
+    /// Show the names of parameters at call sites.
+    pub(super) parameter_hints: bool,
+    /// Show what kind of value a `let` binding is initialized with.
+    pub(super) binding_hints: bool,
+}
+
+impl Default for InlayHintOptions {
+    fn default() -> Self {
+        Self {
+            parameter_hints: true,
+            binding_hints: true,
+        }
+    }
+}
+
+/// A single hint.
+struct Hint {
+    /// The byte offset the hint is shown at.
+    at: usize,
+    /// The text of the hint.
+    label: String,
+    /// If the hint names a parameter, or otherwise a binding.
+    parameter: bool,
+}
+
+/// The hints of a single document.
+struct Document {
+    /// The sources the document was built with.
+    sources: Arc<Sources>,
+    /// The id of the document in the sources.
+    source_id: SourceId,
+    /// Hints in the order they appear in the document.
+    hints: Vec<Hint>,
+}
+
+/// Inlay hints across all documents which were part of a build.
+#[derive(Default)]
+pub(super) struct InlayHints {
+    documents: HashMap<Url, Document>,
+}
+
+impl InlayHints {
+    /// Insert the hints for what was indexed while building the given
+    /// sources.
+    ///
+    /// Documents which are part of several builds are only inserted once.
+    pub(super) fn insert(
+        &mut self,
+        context: &Context,
+        docs: &crate::doc::Visitor,
+        id_to_url: &HashMap<SourceId, Url>,
+        sources: &Arc<Sources>,
+        indexes: &HashMap<SourceId, Index>,
+    ) -> alloc::Result<()> {
+        for (source_id, index) in indexes {
+            let Some(url) = id_to_url.get(source_id) else {
+                continue;
+            };
+
+            if self.documents.contains_key(url) {
+                continue;
+            }
+
+            let Some(text) = sources.get(*source_id).map(|s| s.as_str()) else {
+                continue;
+            };
+
+            let mut hints = Vec::new();
+
+            for (span, definition) in &index.definitions {
+                // Calls of function values stored in variables are not known
+                // to call any particular function.
+                if !matches!(
+                    definition.kind,
+                    DefinitionKind::Function | DefinitionKind::AssociatedFunction
+                ) {
+                    continue;
+                }
+
+                let Some(hash) = definition.hash else {
+                    continue;
+                };
+
+                let Some(arguments) = arguments(context, docs, &definition.source, hash) else {
+                    continue;
+                };
+
+                parameter_hints(text, *span, arguments, &mut hints)?;
+            }
+
+            for local in &index.locals {
+                if local.parameter || text.get(local.span.range()) != Some(&*local.name) {
+                    continue;
+                }
+
+                let Some(rest) =
+                    text.get(local.span.end.into_usize()..local.scope.end.into_usize())
+                else {
+                    continue;
+                };
+
+                let Some(kind) = hover::infer_kind(rest)? else {
+                    continue;
+                };
+
+                hints.try_push(Hint {
+                    at: local.span.end.into_usize(),
+                    label: try_format!(": {kind}"),
+                    parameter: false,
+                })?;
+            }
+
+            hints.sort_by_key(|hint| hint.at);
+
+            self.documents.try_insert(
+                url.clone(),
+                Document {
+                    sources: sources.clone(),
+                    source_id: *source_id,
+                    hints,
+                },
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Get the enabled hints of the given document which are positioned in
+    /// the given range.
+    pub(super) fn hints(
+        &self,
+        encoding: &StateEncoding,
+        options: &InlayHintOptions,
+        url: &Url,
+        range: lsp::Range,
+    ) -> Result<Option<::rust_alloc::vec::Vec<lsp::InlayHint>>> {
+        let Some(document) = self.documents.get(url) else {
+            return Ok(None);
+        };
+
+        let Some(source) = document.sources.get(document.source_id) else {
+            return Ok(None);
+        };
+
+        let mut out = ::rust_alloc::vec::Vec::new();
+
+        for hint in &document.hints {
+            let enabled = if hint.parameter {
+                options.parameter_hints
+            } else {
+                options.binding_hints
+            };
+
+            if !enabled {
+                continue;
+            }
+
+            let position = encoding.source_position(source, hint.at)?;
+
+            if position < range.start || range.end < position {
+                continue;
+            }
+
+            let kind = if hint.parameter {
+                lsp::InlayHintKind::PARAMETER
+            } else {
+                lsp::InlayHintKind::TYPE
+            };
+
+            out.push(lsp::InlayHint {
+                position,
+                label: lsp::InlayHintLabel::String(hint.label.as_str().into()),
+                kind: Some(kind),
+                text_edits: None,
+                tooltip: None,
+                padding_left: None,
+                padding_right: Some(hint.parameter),
+                data: None,
+            });
+        }
+
+        Ok(Some(out))
+    }
+}
+
+/// Look up the arguments of the function with the given hash.
+fn arguments<'a>(
+    context: &'a Context,
+    docs: &'a crate::doc::Visitor,
+    source: &DefinitionSource,
+    hash: Hash,
+) -> Option<&'a [meta::DocArgument]> {
+    let kind = match source {
+        DefinitionSource::Context => &context.lookup_meta_by_hash(hash).next()?.kind,
+        _ => docs.get_by_hash(hash)?.kind.as_ref()?,
+    };
+
+    let meta::Kind::Function { signature, .. } = kind else {
+        return None;
+    };
+
+    signature.arguments.as_deref()
+}
+
+/// Add hints with the names of parameters in front of the arguments of a
+/// call, where `span` covers the path of the function being called.
+///
+/// Arguments which are named like the parameter they are passed to don't get
+/// a hint, like `point` in `scale(point, 2)`.
+fn parameter_hints(
+    text: &str,
+    span: Span,
+    arguments: &[meta::DocArgument],
+    hints: &mut Vec<Hint>,
+) -> alloc::Result<()> {
+    let Some(name) = path_name(text, span) else {
+        return Ok(());
+    };
+
+    let start = span.start.into_usize();
+
+    let Some(call) = text.get(start..) else {
+        return Ok(());
+    };
+
+    let mut parser = Parser::new(call, SourceId::EMPTY, false);
+
+    if parser.parse::<ast::Path>().is_err() {
+        return Ok(());
+    }
+
+    let Ok(args) = parser.parse::<ast::Parenthesized<ast::Expr, T![,]>>() else {
+        return Ok(());
+    };
+
+    let mut arguments = arguments.iter().peekable();
+
+    // The receiver of an instance function called with a `.` is its first
+    // argument.
+    if text[..name.start.into_usize()].trim_end().ends_with('.') {
+        arguments.next_if(|argument| is_self(&argument.name));
+    }
+
+    for ((arg, _), argument) in args.iter().zip(arguments) {
+        let meta::DocName::Name(name) = &argument.name else {
+            continue;
+        };
+
+        if is_self(&argument.name) || name.starts_with('_') {
+            continue;
+        }
+
+        let arg = arg.span();
+        let at = start + arg.start.into_usize();
+
+        if text.get(at..start + arg.end.into_usize()) == Some(&**name) {
+            continue;
+        }
+
+        hints.try_push(Hint {
+            at,
+            label: try_format!("{name}:"),
+            parameter: true,
+        })?;
+    }
+
+    Ok(())
+}
+
+fn is_self(name: &meta::DocName) -> bool {
+    matches!(name, meta::DocName::Name(name) if name.as_ref() == "self")
+}
@@ -9,18 +9,27 @@ mod completion;
 mod connection;
 pub mod envelope;
 mod fs;
+mod hover;
+mod inlay_hints;
+mod rename;
+mod semantic_tokens;
 mod state;
+mod symbols;
 mod url;
 
+use std::sync::Arc;
+
 use lsp::notification::Notification;
 use lsp::request::Request;
 use serde::Deserialize;
-use tokio::sync::Notify;
-
-use crate::alloc::String;
-use crate::languageserver::connection::stdio;
-use crate::languageserver::envelope::Code;
-use crate::languageserver::state::State;
+use tokio::sync::{mpsc, oneshot, Mutex, Notify};
+use tokio::task::JoinSet;
+
+use crate::alloc::prelude::*;
+use crate::alloc::{HashMap, String};
+use crate::languageserver::connection::{stdio, Input, Output};
+use crate::languageserver::envelope::{Code, IncomingMessage, RequestId};
+use crate::languageserver::state::{CancellationToken, State};
 use crate::support::Result;
 use crate::workspace::MANIFEST_FILE;
 use crate::{Context, Options};
@@ -34,89 +43,363 @@ enum Language {
 
 /// Run a language server with the given options.
 pub async fn run(context: Context, options: Options) -> Result<()> {
-    let (mut input, output) = stdio()?;
+    let (input, output) = stdio()?;
+    serve(context, options, input, output).await
+}
+
+/// Serve the language server over the given connection.
+async fn serve(context: Context, options: Options, input: Input, output: Output) -> Result<()> {
+    let rebuild_cancel = CancellationToken::default();
+
+    // Messages are read on a separate task, so that cancellations and document
+    // changes are seen while requests are queued up or while the project is
+    // being rebuilt.
+    let (tx, rx) = mpsc::unbounded_channel();
+    let reader = tokio::spawn(read_messages(input, tx, rebuild_cancel.clone()));
+
+    let result = process(context, options, output, rx, rebuild_cancel).await;
+    reader.abort();
+    result
+}
+
+/// Read incoming messages and forward them to the server.
+async fn read_messages(
+    mut input: Input,
+    tx: mpsc::UnboundedSender<Result<IncomingMessage>>,
+    rebuild_cancel: CancellationToken,
+) {
+    loop {
+        let incoming = match read_message(&mut input).await {
+            Ok(Some(incoming)) => incoming,
+            Ok(None) => return,
+            Err(error) => {
+                _ = tx.send(Err(error));
+                return;
+            }
+        };
+
+        // A change to a document makes any ongoing rebuild outdated.
+        if is_document_change(&incoming.method) {
+            rebuild_cancel.cancel();
+        }
 
-    let rebuild_notify = Notify::new();
+        if tx.send(Ok(incoming)).is_err() {
+            return;
+        }
+    }
+}
+
+/// Read a single incoming message.
+async fn read_message(input: &mut Input) -> Result<Option<IncomingMessage>> {
+    let Some(frame) = input.next().await? else {
+        return Ok(None);
+    };
+
+    Ok(Some(serde_json::from_slice(frame.content)?))
+}
+
+/// Test if the given method modifies the documents being built.
+fn is_document_change(method: &str) -> bool {
+    matches!(
+        method,
+        lsp::notification::DidOpenTextDocument::METHOD
+            | lsp::notification::DidChangeTextDocument::METHOD
+            | lsp::notification::DidCloseTextDocument::METHOD
+    )
+}
+
+/// Process incoming messages until the server is stopped.
+async fn process(
+    context: Context,
+    options: Options,
+    output: Output,
+    mut rx: mpsc::UnboundedReceiver<Result<IncomingMessage>>,
+    rebuild_cancel: CancellationToken,
+) -> Result<()> {
+    let rebuild_notify = Arc::new(Notify::new());
 
     let rebuild = rebuild_notify.notified();
     tokio::pin!(rebuild);
 
-    let mut state = State::new(output, &rebuild_notify, context, options);
+    let state = State::new(
+        output,
+        rebuild_notify.clone(),
+        rebuild_cancel,
+        context,
+        options,
+    );
+
     tracing::info!("Starting server");
-    state.rebuild().await?;
 
-    while !state.is_stopped() {
+    let mut tasks = Tasks::new(state);
+    tasks.spawn(Task::Rebuild);
+
+    // Requests which have been received but not yet responded to, mapped to
+    // the token used to cancel them.
+    let mut requests = HashMap::new();
+
+    loop {
         tokio::select! {
+            biased;
+
+            incoming = rx.recv() => {
+                let incoming = match incoming {
+                    Some(incoming) => incoming?,
+                    None => break,
+                };
+
+                tracing::trace!(?incoming);
+                receive(&mut tasks, &mut requests, incoming)?;
+            },
+            finished = tasks.join.join_next(), if !tasks.join.is_empty() => {
+                let Some(finished) = finished else {
+                    continue;
+                };
+
+                let finished = finished??;
+
+                if let Some(id) = &finished.id {
+                    requests.remove(id);
+                }
+
+                if finished.stopped {
+                    break;
+                }
+            },
             _ = rebuild.as_mut() => {
                 tracing::info!("rebuilding project");
-                state.rebuild().await?;
+                tasks.spawn(Task::Rebuild);
                 rebuild.set(rebuild_notify.notified());
             },
-            frame = input.next() => {
-                let frame = match frame? {
-                    Some(frame) => frame,
-                    None => break,
-                };
+        }
+    }
 
-                let incoming: envelope::IncomingMessage = serde_json::from_slice(frame.content)?;
-                tracing::trace!(?incoming);
+    Ok(())
+}
 
-                // If server is not initialized, reject incoming requests.
-                if !state.is_initialized() && incoming.method != lsp::request::Initialize::METHOD {
-                    state.output
-                        .error(
-                            incoming.id,
-                            Code::InvalidRequest,
-                            "Server not initialized",
-                            None::<()>,
-                        )
-                        .await?;
+/// A unit of work which acts on the server state.
+enum Task {
+    /// Rebuild the project.
+    Rebuild,
+    /// Handle an incoming message, with the token used to cancel it if it is
+    /// a request.
+    Message(IncomingMessage, Option<CancellationToken>),
+}
 
-                    continue;
+/// The outcome of a task.
+struct Finished {
+    /// The request which was responded to, if any.
+    id: Option<RequestId>,
+    /// Indicates that the server was stopped.
+    stopped: bool,
+}
+
+/// Spawned tasks acting on the server state.
+///
+/// Tasks run separately from the loop receiving messages, so that
+/// cancellations are seen while they are waiting or running. They still
+/// acquire the state in the order in which they were spawned.
+struct Tasks {
+    state: Arc<Mutex<State>>,
+    /// Signalled once the most recently spawned task has acquired the state.
+    previous: Option<oneshot::Receiver<()>>,
+    join: JoinSet<Result<Finished>>,
+}
+
+impl Tasks {
+    fn new(state: State) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(state)),
+            previous: None,
+            join: JoinSet::new(),
+        }
+    }
+
+    /// Spawn a task which runs once all previously spawned tasks have
+    /// acquired the state.
+    fn spawn(&mut self, task: Task) {
+        let state = self.state.clone();
+        let previous = self.previous.take();
+        let (started, rx) = oneshot::channel();
+        self.previous = Some(rx);
+
+        self.join.spawn(async move {
+            if let Some(previous) = previous {
+                // An error means that the previous task has already finished.
+                _ = previous.await;
+            }
+
+            // The mutex is fair, so waiting for it before signalling the next
+            // task preserves the order in which tasks were spawned.
+            let mut state = state.lock().await;
+            _ = started.send(());
+
+            match task {
+                Task::Rebuild => {
+                    state.rebuild().await?;
+
+                    Ok(Finished {
+                        id: None,
+                        stopped: false,
+                    })
                 }
+                Task::Message(incoming, cancel) => {
+                    let id = incoming.id.try_clone()?;
+                    dispatch(&mut state, incoming, cancel.as_ref()).await?;
+
+                    Ok(Finished {
+                        id,
+                        stopped: state.is_stopped(),
+                    })
+                }
+            }
+        });
+    }
+}
+
+/// Receive an incoming message.
+///
+/// Cancellations are handled immediately, while everything else is spawned
+/// as a task. Requests are associated with a token so that they can be
+/// cancelled before they are processed.
+fn receive(
+    tasks: &mut Tasks,
+    requests: &mut HashMap<RequestId, CancellationToken>,
+    incoming: IncomingMessage,
+) -> Result<()> {
+    if incoming.method == lsp::notification::Cancel::METHOD {
+        let params = lsp::CancelParams::deserialize(incoming.params)?;
+
+        if let Some(cancel) = request_id(params.id)?.and_then(|id| requests.get(&id)) {
+            cancel.cancel();
+        }
+
+        return Ok(());
+    }
 
-                macro_rules! handle {
-                    ($(req($req_ty:ty, $req_handle:ident)),* $(, notif($notif_ty:ty, $notif_handle:ident))* $(,)?) => {
-                        match incoming.method.as_str() {
-                            $(<$req_ty>::METHOD => {
-                                let params = <$req_ty as Request>::Params::deserialize(incoming.params)?;
-                                let result = $req_handle(&mut state, params).await?;
-                                state.output.response(incoming.id, result).await?;
-                            })*
-                            $(<$notif_ty>::METHOD => {
-                                let params = <$notif_ty as Notification>::Params::deserialize(incoming.params)?;
-                                let () = $notif_handle(&mut state, params).await?;
-                            })*
-                            _ => {
-                                state.output
-                                .log(
-                                    lsp::MessageType::INFO,
-                                    format!("Unhandled method `{}`", incoming.method),
-                                )
-                                .await?;
-                                state.output.method_not_found(incoming.id).await?;
-                            }
+    let cancel = match &incoming.id {
+        Some(id) => {
+            let cancel = CancellationToken::default();
+            requests.try_insert(id.try_clone()?, cancel.clone())?;
+            Some(cancel)
+        }
+        None => None,
+    };
+
+    tasks.spawn(Task::Message(incoming, cancel));
+    Ok(())
+}
+
+/// Convert the identifier of a cancelled request.
+///
+/// Identifiers which cannot have been assigned to a request are ignored.
+fn request_id(id: lsp::NumberOrString) -> Result<Option<RequestId>> {
+    Ok(match id {
+        lsp::NumberOrString::Number(n) => match u64::try_from(n) {
+            Ok(n) => Some(RequestId::Number(n)),
+            Err(..) => None,
+        },
+        lsp::NumberOrString::String(s) => Some(RequestId::String(s.try_into()?)),
+    })
+}
+
+/// Dispatch an incoming message to its handler.
+async fn dispatch(
+    state: &mut State,
+    incoming: IncomingMessage,
+    cancel: Option<&CancellationToken>,
+) -> Result<()> {
+    // If server is not initialized, reject incoming requests.
+    if !state.is_initialized() && incoming.method != lsp::request::Initialize::METHOD {
+        state
+            .output
+            .error(
+                incoming.id,
+                Code::InvalidRequest,
+                "Server not initialized",
+                None::<()>,
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    if cancel.is_some_and(CancellationToken::is_cancelled) {
+        state
+            .output
+            .error(
+                incoming.id,
+                Code::RequestCancelled,
+                "Request cancelled",
+                None::<()>,
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    macro_rules! handle {
+        ($(req($req_ty:ty, $req_handle:ident)),* $(, try_req($try_req_ty:ty, $try_req_handle:ident))* $(, notif($notif_ty:ty, $notif_handle:ident))* $(,)?) => {
+            match incoming.method.as_str() {
+                $(<$req_ty>::METHOD => {
+                    let params = <$req_ty as Request>::Params::deserialize(incoming.params)?;
+                    let result = $req_handle(state, params).await?;
+                    state.output.response(incoming.id, result).await?;
+                })*
+                $(<$try_req_ty>::METHOD => {
+                    let params = <$try_req_ty as Request>::Params::deserialize(incoming.params)?;
+
+                    match $try_req_handle(state, params).await? {
+                        Ok(result) => {
+                            state.output.response(incoming.id, result).await?;
+                        }
+                        Err(message) => {
+                            state.output.error(incoming.id, Code::RequestFailed, &message, None::<()>).await?;
                         }
                     }
+                })*
+                $(<$notif_ty>::METHOD => {
+                    let params = <$notif_ty as Notification>::Params::deserialize(incoming.params)?;
+                    let () = $notif_handle(state, params).await?;
+                })*
+                _ => {
+                    state.output
+                    .log(
+                        lsp::MessageType::INFO,
+                        format!("Unhandled method `{}`", incoming.method),
+                    )
+                    .await?;
+                    state.output.method_not_found(incoming.id).await?;
                 }
-
-                handle! {
-                    req(lsp::request::Initialize, initialize),
-                    req(lsp::request::Shutdown, shutdown),
-                    req(lsp::request::GotoDefinition, goto_definition),
-                    req(lsp::request::Completion, completion),
-                    req(lsp::request::Formatting, formatting),
-                    req(lsp::request::RangeFormatting, range_formatting),
-                    notif(lsp::notification::DidOpenTextDocument, did_open_text_document),
-                    notif(lsp::notification::DidChangeTextDocument, did_change_text_document),
-                    notif(lsp::notification::DidCloseTextDocument, did_close_text_document),
-                    notif(lsp::notification::DidSaveTextDocument, did_save_text_document),
-                    notif(lsp::notification::Initialized, initialized),
-                }
-            },
+            }
         }
     }
 
+    handle! {
+        req(lsp::request::Initialize, initialize),
+        req(lsp::request::Shutdown, shutdown),
+        req(lsp::request::GotoDefinition, goto_definition),
+        req(lsp::request::Completion, completion),
+        req(lsp::request::HoverRequest, hover),
+        req(lsp::request::Formatting, formatting),
+        req(lsp::request::RangeFormatting, range_formatting),
+        req(lsp::request::CodeActionRequest, code_action),
+        req(lsp::request::PrepareRenameRequest, prepare_rename),
+        req(lsp::request::DocumentSymbolRequest, document_symbol),
+        req(lsp::request::WorkspaceSymbolRequest, workspace_symbol),
+        req(lsp::request::SemanticTokensFullRequest, semantic_tokens_full),
+        req(lsp::request::SemanticTokensRangeRequest, semantic_tokens_range),
+        req(lsp::request::InlayHintRequest, inlay_hint),
+        req(lsp::request::References, references),
+        req(lsp::request::GotoImplementation, goto_implementation),
+        try_req(lsp::request::Rename, rename),
+        notif(lsp::notification::DidOpenTextDocument, did_open_text_document),
+        notif(lsp::notification::DidChangeTextDocument, did_change_text_document),
+        notif(lsp::notification::DidCloseTextDocument, did_close_text_document),
+        notif(lsp::notification::DidSaveTextDocument, did_save_text_document),
+        notif(lsp::notification::Initialized, initialized),
+    }
+
     Ok(())
 }
 
@@ -139,10 +422,7 @@ fn is_utf8(params: &lsp::InitializeParams) -> bool {
 }
 
 /// Initialize the language state.
-async fn initialize(
-    s: &mut State<'_>,
-    params: lsp::InitializeParams,
-) -> Result<lsp::InitializeResult> {
+async fn initialize(s: &mut State, params: lsp::InitializeParams) -> Result<lsp::InitializeResult> {
     s.initialize();
 
     s.output
@@ -165,12 +445,31 @@ async fn initialize(
         )
         .await?;
 
+    if let Some(options) = params.initialization_options {
+        match serde_json::from_value(options) {
+            Ok(options) => {
+                s.inlay_hint_options = options;
+            }
+            Err(error) => {
+                s.output
+                    .log(
+                        lsp::MessageType::WARNING,
+                        format_args!("Ignoring invalid initialization options: {error}"),
+                    )
+                    .await?;
+            }
+        }
+    }
+
     let capabilities = lsp::ServerCapabilities {
         position_encoding,
         text_document_sync: Some(lsp::TextDocumentSyncCapability::Kind(
             lsp::TextDocumentSyncKind::INCREMENTAL,
         )),
         definition_provider: Some(lsp::OneOf::Left(true)),
+        references_provider: Some(lsp::OneOf::Left(true)),
+        implementation_provider: Some(lsp::ImplementationProviderCapability::Simple(true)),
+        hover_provider: Some(lsp::HoverProviderCapability::Simple(true)),
         completion_provider: Some(lsp::CompletionOptions {
             all_commit_characters: None,
             resolve_provider: Some(false),
@@ -184,6 +483,34 @@ async fn initialize(
         }),
         document_formatting_provider: Some(lsp::OneOf::Left(true)),
         document_range_formatting_provider: Some(lsp::OneOf::Left(true)),
+        document_symbol_provider: Some(lsp::OneOf::Left(true)),
+        workspace_symbol_provider: Some(lsp::OneOf::Left(true)),
+        inlay_hint_provider: Some(lsp::OneOf::Left(true)),
+        semantic_tokens_provider: Some(
+            lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(
+                lsp::SemanticTokensOptions {
+                    work_done_progress_options: lsp::WorkDoneProgressOptions::default(),
+                    legend: semantic_tokens::legend(),
+                    range: Some(true),
+                    full: Some(lsp::SemanticTokensFullOptions::Bool(true)),
+                },
+            ),
+        ),
+        rename_provider: Some(lsp::OneOf::Right(lsp::RenameOptions {
+            prepare_provider: Some(true),
+            work_done_progress_options: lsp::WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+        })),
+        code_action_provider: Some(lsp::CodeActionProviderCapability::Options(
+            lsp::CodeActionOptions {
+                code_action_kinds: Some(vec![lsp::CodeActionKind::QUICKFIX]),
+                work_done_progress_options: lsp::WorkDoneProgressOptions {
+                    work_done_progress: None,
+                },
+                resolve_provider: Some(false),
+            },
+        )),
         ..Default::default()
     };
 
@@ -221,20 +548,20 @@ async fn initialize(
     })
 }
 
-async fn shutdown(s: &mut State<'_>, _: ()) -> Result<()> {
+async fn shutdown(s: &mut State, _: ()) -> Result<()> {
     s.stop();
     Ok(())
 }
 
 /// Handle initialized notification.
-async fn initialized(_: &mut State<'_>, _: lsp::InitializedParams) -> Result<()> {
+async fn initialized(_: &mut State, _: lsp::InitializedParams) -> Result<()> {
     tracing::info!("Initialized");
     Ok(())
 }
 
 /// Handle initialized notification.
 async fn goto_definition(
-    s: &mut State<'_>,
+    s: &mut State,
     params: lsp::GotoDefinitionParams,
 ) -> Result<Option<lsp::GotoDefinitionResponse>> {
     let position = s
@@ -249,7 +576,7 @@ async fn goto_definition(
 
 /// Handle initialized notification.
 async fn completion(
-    state: &mut State<'_>,
+    state: &mut State,
     params: lsp::CompletionParams,
 ) -> Result<Option<lsp::CompletionResponse>> {
     let Some(results) = state.complete(
@@ -263,29 +590,180 @@ async fn completion(
     Ok(Some(lsp::CompletionResponse::Array(results.into_std())))
 }
 
+/// Handle hover request.
+async fn hover(state: &mut State, params: lsp::HoverParams) -> Result<Option<lsp::Hover>> {
+    state
+        .hover(
+            &params.text_document_position_params.text_document.uri,
+            params.text_document_position_params.position,
+        )
+        .await
+}
+
+/// Handle document symbol request.
+async fn document_symbol(
+    state: &mut State,
+    params: lsp::DocumentSymbolParams,
+) -> Result<Option<lsp::DocumentSymbolResponse>> {
+    let symbols = state.document_symbols(&params.text_document.uri).await?;
+    Ok(symbols.map(lsp::DocumentSymbolResponse::Nested))
+}
+
+/// Handle workspace symbol request.
+async fn workspace_symbol(
+    state: &mut State,
+    params: lsp::WorkspaceSymbolParams,
+) -> Result<Option<lsp::WorkspaceSymbolResponse>> {
+    let symbols = state.workspace_symbols(&params.query).await?;
+    Ok(Some(lsp::WorkspaceSymbolResponse::Nested(symbols)))
+}
+
+/// Handle semantic tokens request for a whole document.
+async fn semantic_tokens_full(
+    state: &mut State,
+    params: lsp::SemanticTokensParams,
+) -> Result<Option<lsp::SemanticTokensResult>> {
+    let Some(data) = state
+        .semantic_tokens(&params.text_document.uri, None)
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    Ok(Some(lsp::SemanticTokensResult::Tokens(
+        lsp::SemanticTokens {
+            result_id: None,
+            data,
+        },
+    )))
+}
+
+/// Handle semantic tokens request for a range of a document.
+async fn semantic_tokens_range(
+    state: &mut State,
+    params: lsp::SemanticTokensRangeParams,
+) -> Result<Option<lsp::SemanticTokensRangeResult>> {
+    let Some(data) = state
+        .semantic_tokens(&params.text_document.uri, Some(params.range))
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    Ok(Some(lsp::SemanticTokensRangeResult::Tokens(
+        lsp::SemanticTokens {
+            result_id: None,
+            data,
+        },
+    )))
+}
+
+/// Handle references request.
+async fn references(
+    state: &mut State,
+    params: lsp::ReferenceParams,
+) -> Result<Option<::rust_alloc::vec::Vec<lsp::Location>>> {
+    state
+        .references(
+            &params.text_document_position.text_document.uri,
+            params.text_document_position.position,
+            params.context.include_declaration,
+        )
+        .await
+}
+
+/// Handle goto implementation request.
+async fn goto_implementation(
+    state: &mut State,
+    params: lsp::request::GotoImplementationParams,
+) -> Result<Option<lsp::request::GotoImplementationResponse>> {
+    let locations = state
+        .implementations(
+            &params.text_document_position_params.text_document.uri,
+            params.text_document_position_params.position,
+        )
+        .await?;
+
+    Ok(locations.map(lsp::GotoDefinitionResponse::Array))
+}
+
+/// Handle inlay hint request.
+async fn inlay_hint(
+    state: &mut State,
+    params: lsp::InlayHintParams,
+) -> Result<Option<::rust_alloc::vec::Vec<lsp::InlayHint>>> {
+    state
+        .inlay_hints(&params.text_document.uri, params.range)
+        .await
+}
+
+/// Handle prepare rename request.
+async fn prepare_rename(
+    state: &mut State,
+    params: lsp::TextDocumentPositionParams,
+) -> Result<Option<lsp::PrepareRenameResponse>> {
+    state
+        .prepare_rename(&params.text_document.uri, params.position)
+        .await
+}
+
+/// Handle rename request.
+///
+/// Renames which are rejected are responded to with an error describing why.
+async fn rename(
+    state: &mut State,
+    params: lsp::RenameParams,
+) -> Result<Result<Option<lsp::WorkspaceEdit>, String>> {
+    state
+        .rename(
+            &params.text_document_position.text_document.uri,
+            params.text_document_position.position,
+            &params.new_name,
+        )
+        .await
+}
+
 /// Handle formatting request.
 async fn formatting(
-    state: &mut State<'_>,
+    state: &mut State,
     params: lsp::DocumentFormattingParams,
 ) -> Result<Option<::rust_alloc::vec::Vec<lsp::TextEdit>>> {
-    state
-        .format(&params.text_document.uri)
-        .map(|option| option.map(|formatted| vec![formatted]))
+    let Some(edits) = state.format(&params.text_document.uri).await? else {
+        return Ok(None);
+    };
+
+    Ok(Some(edits.into_std()))
 }
 
-/// Handle formatting request.
+/// Handle range formatting request.
 async fn range_formatting(
-    state: &mut State<'_>,
+    state: &mut State,
     params: lsp::DocumentRangeFormattingParams,
 ) -> Result<Option<::rust_alloc::vec::Vec<lsp::TextEdit>>> {
-    state
+    let edit = state
         .range_format(&params.text_document.uri, &params.range)
-        .map(|option| option.map(|formatted| vec![formatted]))
+        .await?;
+
+    Ok(edit.map(|formatted| vec![formatted]))
+}
+
+/// Handle code action request.
+async fn code_action(
+    state: &mut State,
+    params: lsp::CodeActionParams,
+) -> Result<Option<lsp::CodeActionResponse>> {
+    let actions = state.code_actions(&params.text_document.uri, &params.context.diagnostics)?;
+
+    if actions.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(actions))
 }
 
 /// Handle open text document.
 async fn did_open_text_document(
-    s: &mut State<'_>,
+    s: &mut State,
     params: lsp::DidOpenTextDocumentParams,
 ) -> Result<()> {
     let lagnuage = match params.text_document.language_id.as_str() {
@@ -313,7 +791,7 @@ async fn did_open_text_document(
 
 /// Handle open text document.
 async fn did_change_text_document(
-    s: &mut State<'_>,
+    s: &mut State,
     params: lsp::DidChangeTextDocumentParams,
 ) -> Result<()> {
     let mut interest = false;
@@ -341,7 +819,7 @@ async fn did_change_text_document(
 
 /// Handle open text document.
 async fn did_close_text_document(
-    s: &mut State<'_>,
+    s: &mut State,
     params: lsp::DidCloseTextDocumentParams,
 ) -> Result<()> {
     s.workspace.remove(&params.text_document.uri)?;
@@ -350,10 +828,7 @@ async fn did_close_text_document(
 }
 
 /// Handle saving of text documents.
-async fn did_save_text_document(
-    s: &mut State<'_>,
-    _: lsp::DidSaveTextDocumentParams,
-) -> Result<()> {
+async fn did_save_text_document(s: &mut State, _: lsp::DidSaveTextDocumentParams) -> Result<()> {
     s.rebuild_interest();
     Ok(())
 }
@@ -0,0 +1,574 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use lsp::Url;
+use unicode_ident::{is_xid_continue, is_xid_start};
+
+use crate::alloc::prelude::*;
+use crate::alloc::{self, HashMap, String, Vec};
+use crate::ast::{self, Span};
+use crate::compile::meta;
+use crate::languageserver::state::{Definition, DefinitionKind, DefinitionSource, Index, Local};
+use crate::parse::Lexer;
+use crate::{Context, Hash, Item, ItemBuf, SourceId, Sources};
+
+/// A symbol which can be renamed.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum Symbol {
+    /// A local variable, identified by where it is declared.
+    Local(Url, Span),
+    /// An item, identified by where its name is declared.
+    Item(Url, Span),
+    /// A field, identified by the type hash of the struct or variant declaring
+    /// it and its name.
+    ///
+    /// Uses of fields on values whose type isn't known, like parameters or
+    /// anonymous objects, don't reference any field.
+    Field(Hash, String),
+}
+
+/// A reference to a symbol.
+pub(super) struct Reference {
+    /// The span of the name of the symbol.
+    pub(super) span: Span,
+    /// The symbol being referenced.
+    pub(super) symbol: Symbol,
+    /// If the reference is where the symbol is declared.
+    pub(super) declaration: bool,
+}
+
+/// The references in a single document.
+pub(super) struct Document {
+    /// The sources the document was built with.
+    pub(super) sources: Arc<Sources>,
+    /// The id of the document in the sources.
+    pub(super) source_id: SourceId,
+    /// The references in the document, including declarations.
+    references: Vec<Reference>,
+    /// The local variables declared in the document.
+    locals: Vec<Local>,
+}
+
+impl Document {
+    /// The text of the given span in the document.
+    pub(super) fn text(&self, span: Span) -> Option<&str> {
+        self.sources.get(self.source_id)?.get(span.range())
+    }
+
+    /// Find the reference covering the given byte offset.
+    pub(super) fn reference_at(&self, offset: usize) -> Option<&Reference> {
+        self.references.iter().find(|reference| {
+            reference.span.start.into_usize() <= offset && offset <= reference.span.end.into_usize()
+        })
+    }
+
+    /// Iterate over the references to the given symbol.
+    pub(super) fn references_to<'a>(
+        &'a self,
+        symbol: &'a Symbol,
+    ) -> impl Iterator<Item = &'a Reference> + 'a {
+        self.references
+            .iter()
+            .filter(move |reference| reference.symbol == *symbol)
+    }
+
+    /// Test if the given span refers to both a field and a local variable,
+    /// like `x` in `Point { x }`.
+    pub(super) fn is_shorthand(&self, span: Span) -> bool {
+        let mut field = false;
+        let mut local = false;
+
+        for reference in &self.references {
+            if reference.span != span {
+                continue;
+            }
+
+            match reference.symbol {
+                Symbol::Local(..) => local = true,
+                Symbol::Field(..) => field = true,
+                Symbol::Item(..) => {}
+            }
+        }
+
+        field && local
+    }
+}
+
+/// The declaration of an item.
+struct ItemDeclaration {
+    item: ItemBuf,
+    url: Url,
+    span: Span,
+    /// The name of the instance function, if the item is one.
+    instance: Option<String>,
+}
+
+/// The declaration of a field.
+struct FieldDeclaration {
+    container: ItemBuf,
+    hash: Hash,
+    name: String,
+}
+
+/// References to renamable symbols across all documents which were part of
+/// a build.
+#[derive(Default)]
+pub(super) struct References {
+    /// Documents by url.
+    documents: HashMap<Url, Document>,
+    /// Items declared in scripts.
+    items: Vec<ItemDeclaration>,
+    /// Fields declared in scripts.
+    fields: Vec<FieldDeclaration>,
+}
+
+impl References {
+    /// Get the references in the given document.
+    pub(super) fn get(&self, url: &Url) -> Option<&Document> {
+        self.documents.get(url)
+    }
+
+    /// Iterate over all documents with references.
+    pub(super) fn iter(&self) -> impl Iterator<Item = (&Url, &Document)> {
+        self.documents.iter()
+    }
+
+    /// Insert the references indexed while building the given sources.
+    ///
+    /// Documents which are part of several builds are only inserted once.
+    pub(super) fn insert(
+        &mut self,
+        id_to_url: &HashMap<SourceId, Url>,
+        sources: &Arc<Sources>,
+        indexes: &HashMap<SourceId, Index>,
+    ) -> alloc::Result<()> {
+        for (source_id, index) in indexes {
+            let Some(url) = id_to_url.get(source_id) else {
+                continue;
+            };
+
+            if self.documents.contains_key(url) {
+                continue;
+            }
+
+            let Some(text) = sources.get(*source_id).map(|s| s.as_str()) else {
+                continue;
+            };
+
+            let mut references = Vec::new();
+            let mut locals = Vec::new();
+
+            for (declared, item) in &index.items {
+                let Some(span) = item_name(text, *declared, item) else {
+                    continue;
+                };
+
+                references.try_push(Reference {
+                    span,
+                    symbol: Symbol::Item(url.clone(), span),
+                    declaration: true,
+                })?;
+
+                let instance = index
+                    .instance_functions
+                    .iter()
+                    .find(|(span, _)| span == declared)
+                    .map(|(_, name)| name.try_clone())
+                    .transpose()?;
+
+                self.items.try_push(ItemDeclaration {
+                    item: item.try_clone()?,
+                    url: url.clone(),
+                    span,
+                    instance,
+                })?;
+            }
+
+            for field in &index.fields {
+                references.try_push(Reference {
+                    span: field.span,
+                    symbol: Symbol::Field(field.hash, field.name.try_clone()?),
+                    declaration: true,
+                })?;
+
+                self.fields.try_push(FieldDeclaration {
+                    container: field.container.try_clone()?,
+                    hash: field.hash,
+                    name: field.name.try_clone()?,
+                })?;
+            }
+
+            for field_use in &index.field_uses {
+                let Some(hash) = field_use.container else {
+                    continue;
+                };
+
+                references.try_push(Reference {
+                    span: field_use.span,
+                    symbol: Symbol::Field(hash, field_use.name.try_clone()?),
+                    declaration: false,
+                })?;
+            }
+
+            for local in &index.locals {
+                if local.name == "self" {
+                    continue;
+                }
+
+                references.try_push(Reference {
+                    span: local.span,
+                    symbol: Symbol::Local(url.clone(), local.span),
+                    declaration: true,
+                })?;
+
+                locals.try_push(local.try_clone()?)?;
+            }
+
+            for (span, definition) in &index.definitions {
+                if let Some(reference) =
+                    definition_reference(id_to_url, sources, indexes, text, *span, definition)?
+                {
+                    references.try_push(reference)?;
+                }
+            }
+
+            self.documents.try_insert(
+                url.clone(),
+                Document {
+                    sources: sources.clone(),
+                    source_id: *source_id,
+                    references,
+                    locals,
+                },
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Get the name of the instance function the given symbol is the
+    /// declaration of, if it is one.
+    pub(super) fn instance_function(&self, symbol: &Symbol) -> Option<&str> {
+        let Symbol::Item(url, span) = symbol else {
+            return None;
+        };
+
+        self.items
+            .iter()
+            .find(|declaration| declaration.url == *url && declaration.span == *span)?
+            .instance
+            .as_deref()
+    }
+
+    /// Iterate over the declarations of instance functions with the given
+    /// name, as the document and the span of their name.
+    ///
+    /// Instance functions of native types are found through
+    /// [`native_implementations`].
+    pub(super) fn implementations<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> impl Iterator<Item = (&'a Url, Span)> + 'a {
+        self.items
+            .iter()
+            .filter(move |declaration| declaration.instance.as_deref() == Some(name))
+            .map(|declaration| (&declaration.url, declaration.span))
+    }
+
+    /// Check that the given symbol can be renamed from `old` to `new`,
+    /// returning a message describing why if it can't.
+    pub(super) fn check(
+        &self,
+        context: &Context,
+        symbol: &Symbol,
+        old: &str,
+        new: &str,
+    ) -> alloc::Result<Option<String>> {
+        if !is_identifier(new) {
+            return Ok(Some(try_format!("`{new}` is not a valid identifier")));
+        }
+
+        match symbol {
+            Symbol::Local(url, span) => {
+                let Some(document) = self.documents.get(url) else {
+                    return Ok(None);
+                };
+
+                let Some(local) = document.locals.iter().find(|local| local.span == *span) else {
+                    return Ok(None);
+                };
+
+                for other in &document.locals {
+                    if *other.name != *new {
+                        continue;
+                    }
+
+                    // The renamed variable would either shadow the other one,
+                    // or be shadowed by it.
+                    if contains(other.scope, local.span.start.into_usize())
+                        || contains(local.scope, other.span.start.into_usize())
+                    {
+                        return Ok(Some(try_format!(
+                            "Renaming `{old}` to `{new}` would clash with a local variable named `{new}` which is already in scope"
+                        )));
+                    }
+                }
+            }
+            Symbol::Item(url, span) => {
+                let Some(declaration) = self
+                    .items
+                    .iter()
+                    .find(|declaration| declaration.url == *url && declaration.span == *span)
+                else {
+                    return Ok(None);
+                };
+
+                let sibling = declaration
+                    .item
+                    .parent()
+                    .unwrap_or(Item::new())
+                    .extended(new)?;
+
+                if self.items.iter().any(|d| d.item == sibling)
+                    || context.lookup_meta(&sibling).is_some()
+                {
+                    return Ok(Some(try_format!(
+                        "Renaming `{old}` to `{new}` would collide with the existing item `{sibling}`"
+                    )));
+                }
+            }
+            Symbol::Field(hash, name) => {
+                let Some(declaration) = self
+                    .fields
+                    .iter()
+                    .find(|f| f.hash == *hash && f.name == *name)
+                else {
+                    return Ok(Some(try_format!(
+                        "Field `{name}` is not declared by any struct in the workspace"
+                    )));
+                };
+
+                if self
+                    .fields
+                    .iter()
+                    .any(|f| f.hash == *hash && f.name == *new)
+                {
+                    let container = &declaration.container;
+
+                    return Ok(Some(try_format!(
+                        "Renaming `{old}` to `{new}` would collide with the existing field `{container}.{new}`"
+                    )));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// Convert a use of a definition into a reference to the symbol it defines.
+fn definition_reference(
+    id_to_url: &HashMap<SourceId, Url>,
+    sources: &Sources,
+    indexes: &HashMap<SourceId, Index>,
+    text: &str,
+    span: Span,
+    definition: &Definition,
+) -> alloc::Result<Option<Reference>> {
+    match (&definition.source, definition.kind) {
+        (DefinitionSource::Location(location), DefinitionKind::Local) => {
+            // Uses which don't name the variable, like closures capturing it,
+            // can't be renamed.
+            if !text.get(span.range()).is_some_and(is_identifier_text) {
+                return Ok(None);
+            }
+
+            let mut location = *location;
+
+            // Variables used inside of closures are declared by the closure
+            // as far as the compiler is concerned, so they are looked up by
+            // name in the scope of the use instead.
+            if let Some(index) = indexes.get(&location.source_id) {
+                if !index.locals.iter().any(|local| local.span == location.span) {
+                    let Some(local) = index.locals.iter().rev().find(|local| {
+                        text.get(span.range()) == Some(&*local.name)
+                            && local.span.end <= span.start
+                            && contains(local.scope, span.start.into_usize())
+                    }) else {
+                        return Ok(None);
+                    };
+
+                    location.span = local.span;
+                }
+            }
+
+            let Some(url) = id_to_url.get(&location.source_id) else {
+                return Ok(None);
+            };
+
+            Ok(Some(Reference {
+                span,
+                symbol: Symbol::Local(url.clone(), location.span),
+                declaration: false,
+            }))
+        }
+        (DefinitionSource::SourceMeta(meta), _) => {
+            let location = meta.location;
+
+            let Some(url) = id_to_url.get(&location.source_id) else {
+                return Ok(None);
+            };
+
+            let Some(item) = indexes.get(&location.source_id).and_then(|index| {
+                index
+                    .items
+                    .iter()
+                    .find(|(span, _)| *span == location.span)
+                    .map(|(_, item)| item)
+            }) else {
+                return Ok(None);
+            };
+
+            let Some(declared) = sources
+                .get(location.source_id)
+                .and_then(|source| item_name(source.as_str(), location.span, item))
+            else {
+                return Ok(None);
+            };
+
+            // Only the last component of a path names the item, like `bar` in
+            // `foo::bar`.
+            let Some(name) = item.base_name() else {
+                return Ok(None);
+            };
+
+            let Some(path) = text.get(span.range()) else {
+                return Ok(None);
+            };
+
+            let Some(prefix) = path.strip_suffix(name) else {
+                return Ok(None);
+            };
+
+            if prefix.ends_with(is_xid_continue) {
+                return Ok(None);
+            }
+
+            let start = span.start.into_usize() + prefix.len();
+
+            Ok(Some(Reference {
+                span: Span::new(start, span.end),
+                symbol: Symbol::Item(url.clone(), declared),
+                declaration: false,
+            }))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Find the span of the name of an item in its declaration.
+///
+/// The name is the first identifier matching the item which either follows
+/// the keyword it is declared with, or which starts a variant.
+fn item_name(text: &str, span: Span, item: &Item) -> Option<Span> {
+    let name = item.base_name()?;
+    let declaration = text.get(span.range())?;
+
+    let mut lexer = Lexer::new(declaration, SourceId::EMPTY, false).without_processing();
+    let mut previous = None;
+
+    while let Ok(Some(token)) = lexer.next() {
+        match token.kind {
+            ast::Kind::Whitespace | ast::Kind::Comment | ast::Kind::MultilineComment(..) => {
+                continue;
+            }
+            ast::Kind::Ident(..) if declaration.get(token.span.range()) == Some(name) => {
+                if matches!(
+                    previous,
+                    None | Some(K![fn] | K![struct] | K![enum] | K![const] | K![mod] | K![']'])
+                ) {
+                    let start = span.start.into_usize() + token.span.start.into_usize();
+                    return Some(Span::new(start, start + name.len()));
+                }
+            }
+            _ => {}
+        }
+
+        previous = Some(token.kind);
+    }
+
+    None
+}
+
+/// Find the instance functions with the given name which are provided by the
+/// context, as their items.
+pub(super) fn native_implementations<'a>(
+    context: &'a Context,
+    name: &str,
+) -> alloc::Result<Vec<&'a Item>> {
+    // Types with an alias register their functions under both names, so
+    // functions are deduplicated by hash with the last one being the
+    // canonical item.
+    let mut found = BTreeMap::new();
+
+    for meta in context.iter_meta() {
+        let meta::Kind::Function {
+            associated: Some(meta::AssociatedKind::Instance(instance)),
+            ..
+        } = &meta.kind
+        else {
+            continue;
+        };
+
+        if instance.as_ref() != name {
+            continue;
+        }
+
+        if let Some(item) = meta.item.as_deref() {
+            found.insert(meta.hash, item);
+        }
+    }
+
+    found.into_values().try_collect()
+}
+
+/// Find the name of an instance function being called in the given line,
+/// like `process` in `value.process()`, where `at` is the byte offset of the
+/// cursor in the line.
+pub(super) fn instance_call(line: &str, at: usize) -> Option<&str> {
+    let before = line.get(..at)?;
+    let after = line.get(at..)?;
+
+    let start = before.trim_end_matches(is_xid_continue).len();
+    let end = at + (after.len() - after.trim_start_matches(is_xid_continue).len());
+
+    let name = &line[start..end];
+
+    if !is_identifier_text(name)
+        || !line[..start].trim_end().ends_with('.')
+        || !line[end..].trim_start().starts_with('(')
+    {
+        return None;
+    }
+
+    Some(name)
+}
+
+/// Test if the given name is a valid identifier which isn't a keyword.
+fn is_identifier(name: &str) -> bool {
+    is_identifier_text(name) && name != "_" && ast::Kind::from_keyword(name).is_none()
+}
+
+/// Test if the given text is made up of a single identifier.
+fn is_identifier_text(text: &str) -> bool {
+    let mut chars = text.chars();
+
+    match chars.next() {
+        Some(c) => (c == '_' || is_xid_start(c)) && chars.all(is_xid_continue),
+        None => false,
+    }
+}
+
+fn contains(span: Span, offset: usize) -> bool {
+    span.start.into_usize() <= offset && offset <= span.end.into_usize()
+}
@@ -0,0 +1,547 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use anyhow::Result;
+use lsp::Url;
+use unicode_ident::is_xid_continue;
+
+use crate::alloc::{self, HashMap, Vec};
+use crate::ast::{self, Span};
+use crate::compile::DeclarationKind;
+use crate::languageserver::state::{DefinitionKind, DefinitionSource, Index, Local, StateEncoding};
+use crate::parse::Lexer;
+use crate::{Item, Source, SourceId, Sources};
+
+/// The type of a semantic token, where the discriminant is its index in the
+/// legend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenType {
+    Namespace,
+    Type,
+    EnumMember,
+    Function,
+    Method,
+    Parameter,
+    Variable,
+    Property,
+    Macro,
+    Keyword,
+    Comment,
+    String,
+    Number,
+}
+
+/// The modifier of a token which declares what it names.
+const DECLARATION: u32 = 1 << 0;
+/// The modifier of a token which names something that can't be assigned to.
+const READONLY: u32 = 1 << 1;
+
+/// The legend of semantic tokens, which is advertised in the capabilities of
+/// the server.
+pub(super) fn legend() -> lsp::SemanticTokensLegend {
+    lsp::SemanticTokensLegend {
+        token_types: vec![
+            lsp::SemanticTokenType::NAMESPACE,
+            lsp::SemanticTokenType::TYPE,
+            lsp::SemanticTokenType::ENUM_MEMBER,
+            lsp::SemanticTokenType::FUNCTION,
+            lsp::SemanticTokenType::METHOD,
+            lsp::SemanticTokenType::PARAMETER,
+            lsp::SemanticTokenType::VARIABLE,
+            lsp::SemanticTokenType::PROPERTY,
+            lsp::SemanticTokenType::MACRO,
+            lsp::SemanticTokenType::KEYWORD,
+            lsp::SemanticTokenType::COMMENT,
+            lsp::SemanticTokenType::STRING,
+            lsp::SemanticTokenType::NUMBER,
+        ],
+        token_modifiers: vec![
+            lsp::SemanticTokenModifier::DECLARATION,
+            lsp::SemanticTokenModifier::READONLY,
+        ],
+    }
+}
+
+/// How an identifier is classified.
+#[derive(Debug, Clone, Copy)]
+struct Class {
+    token_type: TokenType,
+    modifiers: u32,
+}
+
+impl Class {
+    const fn new(token_type: TokenType, modifiers: u32) -> Self {
+        Self {
+            token_type,
+            modifiers,
+        }
+    }
+}
+
+/// The classified identifiers of a single document.
+struct Document {
+    /// The sources the document was built with.
+    sources: Arc<Sources>,
+    /// The id of the document in the sources.
+    source_id: SourceId,
+    /// Identifiers which are known to name something, by their span.
+    classes: BTreeMap<Span, Class>,
+}
+
+/// Semantic information about identifiers across all documents which were
+/// part of a build.
+#[derive(Default)]
+pub(super) struct SemanticTokens {
+    documents: HashMap<Url, Document>,
+}
+
+impl SemanticTokens {
+    /// Insert the identifiers indexed while building the given sources.
+    ///
+    /// Documents which are part of several builds are only inserted once.
+    pub(super) fn insert(
+        &mut self,
+        id_to_url: &HashMap<SourceId, Url>,
+        sources: &Arc<Sources>,
+        indexes: &HashMap<SourceId, Index>,
+    ) -> alloc::Result<()> {
+        // Types declared anywhere in the build, so that functions declared in
+        // their `impl` blocks can be told apart as methods.
+        let mut types = Vec::new();
+
+        for index in indexes.values() {
+            for declaration in &index.declarations {
+                if matches!(
+                    declaration.kind,
+                    DeclarationKind::Struct | DeclarationKind::Enum
+                ) {
+                    types.try_push(&*declaration.item)?;
+                }
+            }
+        }
+
+        for (source_id, index) in indexes {
+            let Some(url) = id_to_url.get(source_id) else {
+                continue;
+            };
+
+            if self.documents.contains_key(url) {
+                continue;
+            }
+
+            let Some(text) = sources.get(*source_id).map(|s| s.as_str()) else {
+                continue;
+            };
+
+            let classes = classify(text, index, &types)?;
+
+            self.documents.try_insert(
+                url.clone(),
+                Document {
+                    sources: sources.clone(),
+                    source_id: *source_id,
+                    classes,
+                },
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Compute the semantic tokens of the given document, optionally limited
+    /// to the ones overlapping with the given range.
+    pub(super) fn tokens(
+        &self,
+        encoding: &StateEncoding,
+        url: &Url,
+        range: Option<lsp::Range>,
+    ) -> Result<Option<::rust_alloc::vec::Vec<lsp::SemanticToken>>> {
+        let Some(document) = self.documents.get(url) else {
+            return Ok(None);
+        };
+
+        let Some(source) = document.sources.get(document.source_id) else {
+            return Ok(None);
+        };
+
+        let text = source.as_str();
+
+        let mut tokens = Vec::new();
+        lex(text, 0, false, &mut tokens)?;
+
+        let mut classified = Vec::new();
+
+        for (n, token) in tokens.iter().enumerate() {
+            let Some(class) = token_class(&document.classes, &tokens, n) else {
+                continue;
+            };
+
+            split_lines(encoding, source, token.span, class, &mut classified)?;
+        }
+
+        if let Some(range) = range {
+            classified.retain(|(position, length, _)| {
+                let end = lsp::Position::new(position.line, position.character + length);
+                *position < range.end && range.start < end
+            });
+        }
+
+        let mut out = ::rust_alloc::vec::Vec::new();
+        let mut previous = lsp::Position::default();
+
+        for (position, length, class) in classified {
+            let delta_line = position.line - previous.line;
+
+            let delta_start = if delta_line == 0 {
+                position.character - previous.character
+            } else {
+                position.character
+            };
+
+            out.push(lsp::SemanticToken {
+                delta_line,
+                delta_start,
+                length,
+                token_type: class.token_type as u32,
+                token_modifiers_bitset: class.modifiers,
+            });
+
+            previous = position;
+        }
+
+        Ok(Some(out))
+    }
+}
+
+/// Classify the identifiers in a document using what was indexed while
+/// building it.
+///
+/// Later classifications take precedence, so that `x` in `Point { x }` is a
+/// variable rather than a field.
+fn classify(text: &str, index: &Index, types: &[&Item]) -> alloc::Result<BTreeMap<Span, Class>> {
+    let mut classes = BTreeMap::new();
+
+    for declaration in &index.declarations {
+        let class = match declaration.kind {
+            DeclarationKind::Module => Class::new(TokenType::Namespace, DECLARATION),
+            DeclarationKind::Struct | DeclarationKind::Enum => {
+                Class::new(TokenType::Type, DECLARATION)
+            }
+            DeclarationKind::Variant => Class::new(TokenType::EnumMember, DECLARATION),
+            DeclarationKind::Function => match declaration.item.parent() {
+                Some(parent) if types.contains(&parent) => {
+                    Class::new(TokenType::Method, DECLARATION)
+                }
+                _ => Class::new(TokenType::Function, DECLARATION),
+            },
+            DeclarationKind::Const => Class::new(TokenType::Variable, DECLARATION | READONLY),
+        };
+
+        classes.insert(declaration.name, class);
+    }
+
+    for field in &index.fields {
+        classes.insert(field.span, Class::new(TokenType::Property, DECLARATION));
+    }
+
+    for field_use in &index.field_uses {
+        classes.insert(field_use.span, Class::new(TokenType::Property, 0));
+    }
+
+    for local in &index.locals {
+        if text.get(local.span.range()) != Some(&*local.name) {
+            continue;
+        }
+
+        classes.insert(local.span, local_class(local, DECLARATION));
+    }
+
+    for (span, definition) in &index.definitions {
+        // Uses of items and variables are reported with the span of the
+        // whole path or call, so the name is the last component of the path
+        // it starts with, like `bar` in `foo::bar(1, 2)`.
+        let Some(name) = path_name(text, *span) else {
+            continue;
+        };
+
+        let class = match definition.kind {
+            DefinitionKind::EmptyStruct
+            | DefinitionKind::TupleStruct
+            | DefinitionKind::Struct
+            | DefinitionKind::Enum
+            | DefinitionKind::Type => Class::new(TokenType::Type, 0),
+            DefinitionKind::UnitVariant
+            | DefinitionKind::TupleVariant
+            | DefinitionKind::StructVariant => Class::new(TokenType::EnumMember, 0),
+            DefinitionKind::Function => Class::new(TokenType::Function, 0),
+            DefinitionKind::AssociatedFunction => Class::new(TokenType::Method, 0),
+            DefinitionKind::Module => Class::new(TokenType::Namespace, 0),
+            DefinitionKind::Local => {
+                let DefinitionSource::Location(location) = &definition.source else {
+                    continue;
+                };
+
+                let Some(local) = resolve_local(text, index, location.span, name) else {
+                    continue;
+                };
+
+                local_class(local, 0)
+            }
+        };
+
+        classes.insert(name, class);
+    }
+
+    Ok(classes)
+}
+
+/// Find the local variable a use refers to.
+///
+/// Variables used inside of closures are declared by the closure as far as
+/// the compiler is concerned, so they are looked up by name in the scope of
+/// the use instead.
+fn resolve_local<'a>(
+    text: &str,
+    index: &'a Index,
+    declared: Span,
+    name: Span,
+) -> Option<&'a Local> {
+    if let Some(local) = index.locals.iter().find(|local| local.span == declared) {
+        return Some(local);
+    }
+
+    let name_text = text.get(name.range())?;
+
+    index.locals.iter().rev().find(|local| {
+        &*local.name == name_text
+            && local.span.end <= name.start
+            && local.scope.start <= name.start
+            && name.start <= local.scope.end
+    })
+}
+
+fn local_class(local: &Local, modifiers: u32) -> Class {
+    if local.parameter {
+        Class::new(TokenType::Parameter, modifiers)
+    } else {
+        Class::new(TokenType::Variable, modifiers)
+    }
+}
+
+/// Find the span of the last identifier in the path which the given span
+/// starts with.
+pub(super) fn path_name(text: &str, span: Span) -> Option<Span> {
+    let path = text.get(span.range())?;
+    let len = path
+        .find(|c: char| !(is_xid_continue(c) || c == ':' || c.is_whitespace()))
+        .unwrap_or(path.len());
+    let path = path[..len].trim_end();
+
+    let name = path
+        .chars()
+        .rev()
+        .take_while(|c| is_xid_continue(*c))
+        .map(char::len_utf8)
+        .sum::<usize>();
+
+    if name == 0 {
+        return None;
+    }
+
+    let end = span.start.into_usize() + path.len();
+    Some(Span::new(end - name, end))
+}
+
+/// Lex the given text starting at the given offset, omitting whitespace.
+///
+/// Templates are expanded into their string segments and the tokens of
+/// their expressions. If `nested` is set the text is an expression in a
+/// template, and lexing stops at its closing brace whose offset is
+/// returned.
+///
+/// Lexing stops at the first error, since a document can be highlighted
+/// while it is broken.
+fn lex(
+    text: &str,
+    offset: usize,
+    nested: bool,
+    out: &mut Vec<ast::Token>,
+) -> alloc::Result<Option<usize>> {
+    let Some(input) = text.get(offset..) else {
+        return Ok(None);
+    };
+
+    let shebang = offset == 0 && !nested;
+    let mut lexer = Lexer::new(input, SourceId::EMPTY, shebang).without_processing();
+    let mut level = 0usize;
+
+    while let Ok(Some(token)) = lexer.next() {
+        let span = Span::new(
+            offset + token.span.start.into_usize(),
+            offset + token.span.end.into_usize(),
+        );
+
+        match token.kind {
+            ast::Kind::Whitespace => continue,
+            ast::Kind::Open(ast::Delimiter::Brace) if nested => {
+                level += 1;
+            }
+            ast::Kind::Close(ast::Delimiter::Brace) if nested => {
+                if level == 0 {
+                    return Ok(Some(span.start.into_usize()));
+                }
+
+                level -= 1;
+            }
+            ast::Kind::TemplateString => {
+                template(text, span, out)?;
+                continue;
+            }
+            _ => {}
+        }
+
+        out.try_push(ast::Token {
+            kind: token.kind,
+            span,
+        })?;
+    }
+
+    Ok(None)
+}
+
+/// Expand a template into string segments and the tokens of its
+/// expressions, like `a` and `b` in `` `${a} and ${b}` ``.
+fn template(text: &str, span: Span, out: &mut Vec<ast::Token>) -> alloc::Result<()> {
+    let end = span.end.into_usize();
+    let mut start = span.start.into_usize();
+    let mut at = start;
+
+    while let Some(c) = text.get(at..end).and_then(|rest| rest.chars().next()) {
+        match c {
+            '\\' => {
+                at += 1;
+                at += text[at..].chars().next().map_or(0, char::len_utf8);
+                continue;
+            }
+            '$' if text[at..end].starts_with("${") => {
+                push_segment(out, start, at)?;
+
+                let Some(close) = lex(text, at + 2, true, out)? else {
+                    return Ok(());
+                };
+
+                at = close + 1;
+                start = at;
+                continue;
+            }
+            _ => {
+                at += c.len_utf8();
+            }
+        }
+    }
+
+    push_segment(out, start, end)
+}
+
+fn push_segment(out: &mut Vec<ast::Token>, start: usize, end: usize) -> alloc::Result<()> {
+    if start < end {
+        out.try_push(ast::Token {
+            kind: ast::Kind::TemplateString,
+            span: Span::new(start, end),
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Classify the token at the given position.
+fn token_class(classes: &BTreeMap<Span, Class>, tokens: &[ast::Token], n: usize) -> Option<Class> {
+    let token = &tokens[n];
+
+    let token_type = match token.kind {
+        ast::Kind::Comment | ast::Kind::MultilineComment(..) | ast::Kind::Shebang(..) => {
+            TokenType::Comment
+        }
+        ast::Kind::Str(..)
+        | ast::Kind::ByteStr(..)
+        | ast::Kind::Char(..)
+        | ast::Kind::Byte(..)
+        | ast::Kind::TemplateString => TokenType::String,
+        ast::Kind::Number(..) => TokenType::Number,
+        ast::Kind::Ident(..) => return ident_class(classes, tokens, n),
+        kind => {
+            let keyword = kind.as_literal_str()?;
+
+            if !keyword.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                return None;
+            }
+
+            TokenType::Keyword
+        }
+    };
+
+    Some(Class::new(token_type, 0))
+}
+
+/// Classify an identifier, falling back to how it's used when nothing is
+/// known about it, like an instance function called on a value whose type
+/// isn't known.
+fn ident_class(classes: &BTreeMap<Span, Class>, tokens: &[ast::Token], n: usize) -> Option<Class> {
+    let is_code = |token: &&ast::Token| {
+        !matches!(
+            token.kind,
+            ast::Kind::Comment | ast::Kind::MultilineComment(..)
+        )
+    };
+
+    let previous = tokens[..n].iter().rev().find(is_code).map(|t| t.kind);
+    let next = tokens[n + 1..].iter().find(is_code).map(|t| t.kind);
+
+    if matches!(next, Some(K![!])) {
+        return Some(Class::new(TokenType::Macro, 0));
+    }
+
+    if let Some(class) = classes.get(&tokens[n].span) {
+        return Some(*class);
+    }
+
+    let token_type = match (previous, next) {
+        (Some(K![.]), Some(K!['('])) => TokenType::Method,
+        (Some(K![.]), _) => TokenType::Property,
+        (_, Some(K!['('])) => TokenType::Function,
+        _ => return None,
+    };
+
+    Some(Class::new(token_type, 0))
+}
+
+/// Split a token into one token per line it covers, as required by the
+/// protocol, and push them as positions and lengths in the given encoding.
+fn split_lines(
+    encoding: &StateEncoding,
+    source: &Source,
+    span: Span,
+    class: Class,
+    out: &mut Vec<(lsp::Position, u32, Class)>,
+) -> Result<()> {
+    let Some(text) = source.get(span.range()) else {
+        return Ok(());
+    };
+
+    let mut start = span.start.into_usize();
+
+    for line in text.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\n', '\r']);
+        let end = start + content.len();
+
+        if start < end {
+            let from = encoding.source_position(source, start)?;
+            let to = encoding.source_position(source, end)?;
+            out.try_push((from, to.character - from.character, class))?;
+        }
+
+        start += line.len();
+    }
+
+    Ok(())
+}
@@ -1,28 +1,39 @@
 use std::collections::{BTreeMap, HashSet};
 use std::fmt;
+use std::mem;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 use anyhow::{Context as _, Result};
+use codespan_reporting::diagnostic as d;
 use lsp::Url;
 use ropey::Rope;
+use similar::{DiffOp, TextDiff};
 use tokio::sync::Notify;
 
 use crate::alloc::prelude::*;
 use crate::alloc::{self, HashMap, String, Vec};
-use crate::ast::{Span, Spanned};
+use crate::ast::{self, Span, Spanned};
 use crate::compile::meta;
 use crate::compile::{
-    self, CompileVisitor, LinkerError, Located, Location, MetaError, MetaRef, SourceMeta, WithSpan,
+    self, CompileVisitor, DeclarationKind, LinkerError, Located, Location, MetaError, MetaRef,
+    SourceMeta, WithSpan,
 };
-use crate::diagnostics::{Diagnostic, FatalDiagnosticKind};
+use crate::diagnostics::{Diagnostic, FatalDiagnosticKind, WarningDiagnosticKind};
 use crate::doc::VisitorData;
 use crate::item::ComponentRef;
+use crate::languageserver::completion::{self, Completion};
 use crate::languageserver::connection::Output;
+use crate::languageserver::hover::{self, FieldAccess};
+use crate::languageserver::inlay_hints::{InlayHintOptions, InlayHints};
+use crate::languageserver::rename::{self, References};
+use crate::languageserver::semantic_tokens::SemanticTokens;
+use crate::languageserver::symbols::Symbols;
 use crate::languageserver::Language;
 use crate::workspace::{self, WorkspaceError};
 use crate::{self as rune, Diagnostics};
-use crate::{BuildError, Context, Item, Options, Source, SourceId, Sources, Unit};
+use crate::{BuildError, Context, Hash, Item, ItemBuf, Options, Source, SourceId, Sources, Unit};
 
 #[derive(Default)]
 struct Reporter {
@@ -156,6 +167,62 @@ impl StateEncoding {
             StateEncoding::Utf8 => rope_position_utf8(rope, pos),
         }
     }
+
+    /// Translate a byte offset in the given rope into an lsp::Position.
+    fn rope_offset_position(&self, rope: &Rope, offset: usize) -> Result<lsp::Position> {
+        let char = rope.try_byte_to_char(offset)?;
+        let line = rope.try_char_to_line(char)?;
+        let line_start = rope.try_line_to_char(line)?;
+
+        let character = match self {
+            StateEncoding::Utf16 => {
+                rope.try_char_to_utf16_cu(char)? - rope.try_char_to_utf16_cu(line_start)?
+            }
+            StateEncoding::Utf8 => offset - rope.try_char_to_byte(line_start)?,
+        };
+
+        Ok(lsp::Position {
+            line: u32::try_from(line)?,
+            character: u32::try_from(character)?,
+        })
+    }
+
+    /// Compute the line-based edits needed to turn `old`, which is the content
+    /// of the given rope, into `new`.
+    fn text_edits(&self, rope: &Rope, old: &str, new: &str) -> Result<Vec<lsp::TextEdit>> {
+        let diff = TextDiff::from_lines(old, new);
+
+        // Byte offsets of the start of each line in the old text, including the
+        // offset of the end of the text.
+        let mut offsets = Vec::new();
+        let mut offset = 0;
+        offsets.try_push(offset)?;
+
+        for line in diff.old_slices() {
+            offset += line.len();
+            offsets.try_push(offset)?;
+        }
+
+        let mut edits = Vec::new();
+
+        for op in diff.ops() {
+            if let DiffOp::Equal { .. } = op {
+                continue;
+            }
+
+            let old_range = op.old_range();
+
+            let range = lsp::Range {
+                start: self.rope_offset_position(rope, offsets[old_range.start])?,
+                end: self.rope_offset_position(rope, offsets[old_range.end])?,
+            };
+
+            let text = diff.new_slices()[op.new_range()].concat();
+            edits.try_push(lsp::TextEdit::new(range, text))?;
+        }
+
+        Ok(edits)
+    }
 }
 
 impl fmt::Display for StateEncoding {
@@ -167,31 +234,73 @@ impl fmt::Display for StateEncoding {
     }
 }
 
+/// A token used to signal that an ongoing operation should be aborted.
+#[derive(Default, Clone)]
+pub(super) struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Signal cancellation.
+    pub(super) fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+    }
+
+    /// Test if cancellation has been signalled.
+    pub(super) fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+
+    /// Reset the token so that it can be used for a new operation.
+    fn reset(&self) {
+        self.cancelled.store(false, Ordering::Release);
+    }
+}
+
 /// Shared server state.
-pub(super) struct State<'a> {
+pub(super) struct State {
     pub(super) encoding: StateEncoding,
     /// The output abstraction.
     pub(super) output: Output,
     /// Sender to indicate interest in rebuilding the project.
     /// Can be triggered on modification.
-    rebuild_notify: &'a Notify,
+    rebuild_notify: Arc<Notify>,
+    /// Token used to abort an in-progress rebuild.
+    rebuild_cancel: CancellationToken,
     /// The rune context to build for.
     context: crate::Context,
     /// Build options.
     options: Options,
+    /// Formatting options from the `[fmt]` section of the workspace manifest,
+    /// as of the last rebuild.
+    fmt_options: Vec<String>,
     /// Indicate if the server is initialized.
     initialized: bool,
     /// Indicate that the server is stopped.
     stopped: bool,
     /// Sources used in the project.
     pub(super) workspace: Workspace,
+    /// Documents which diagnostics were last published for, so that they can
+    /// be cleared once they no longer have any.
+    published: HashSet<Url>,
+    /// References to renamable symbols, as of the last rebuild.
+    references: References,
+    /// Symbols declared in documents, as of the last rebuild.
+    symbols: Symbols,
+    /// Classified identifiers in documents, as of the last rebuild.
+    semantic_tokens: SemanticTokens,
+    /// Which inlay hints are enabled.
+    pub(super) inlay_hint_options: InlayHintOptions,
+    /// Inlay hints in documents, as of the last rebuild.
+    inlay_hints: InlayHints,
 }
 
-impl<'a> State<'a> {
+impl State {
     /// Construct a new state.
     pub(super) fn new(
         output: Output,
-        rebuild_notify: &'a Notify,
+        rebuild_notify: Arc<Notify>,
+        rebuild_cancel: CancellationToken,
         context: Context,
         options: Options,
     ) -> Self {
@@ -199,11 +308,19 @@ impl<'a> State<'a> {
             encoding: StateEncoding::Utf16,
             output,
             rebuild_notify,
+            rebuild_cancel,
             context,
             options,
+            fmt_options: Vec::new(),
             initialized: bool::default(),
             stopped: bool::default(),
             workspace: Workspace::default(),
+            published: HashSet::new(),
+            references: References::default(),
+            symbols: Symbols::default(),
+            semantic_tokens: SemanticTokens::default(),
+            inlay_hint_options: InlayHintOptions::default(),
+            inlay_hints: InlayHints::default(),
         }
     }
 
@@ -255,11 +372,11 @@ impl<'a> State<'a> {
             None => uri.clone(),
         };
 
-        let Some(source) = source
-            .build_sources
-            .as_ref()
-            .and_then(|s| s.get(def.source.source_id()))
-        else {
+        let Some(source_id) = def.source.source_id() else {
+            return Ok(None);
+        };
+
+        let Some(source) = source.build_sources.as_ref().and_then(|s| s.get(source_id)) else {
             return Ok(None);
         };
 
@@ -271,7 +388,341 @@ impl<'a> State<'a> {
         Ok(Some(location))
     }
 
-    /// Find definition at the given uri and LSP position.
+    /// Describe the item or variable at the given uri and LSP position.
+    pub(super) async fn hover(
+        &self,
+        uri: &Url,
+        position: lsp::Position,
+    ) -> Result<Option<lsp::Hover>> {
+        let Some(source) = self.workspace.get(uri) else {
+            return Ok(None);
+        };
+
+        let content = &source.content;
+        let char = self.encoding.rope_position(content, position)?;
+        let offset = content.try_char_to_byte(char)?;
+
+        let definition = source.find_definition_at(Span::point(offset));
+
+        let local = match definition {
+            Some(Definition {
+                kind: DefinitionKind::Local,
+                source: DefinitionSource::Location(location),
+                ..
+            }) => source.local_defined_at(location.span.start.into_usize()),
+            Some(..) => None,
+            None => source.local_defined_at(offset),
+        };
+
+        let value = if let Some(local) = local {
+            let kind = source.infer_local_kind(local)?;
+            Some(hover::local(&local.name, kind.as_deref())?)
+        } else if let Some(definition) = definition {
+            match (&definition.source, definition.hash) {
+                (DefinitionSource::Context, Some(hash)) => {
+                    match self.context.lookup_meta_by_hash(hash).next() {
+                        Some(meta) => hover::native(&self.context, meta)?,
+                        None => None,
+                    }
+                }
+                (_, Some(hash)) => match source.get_docs_by_hash(hash) {
+                    Some(data) => hover::script(&self.context, data)?,
+                    None => None,
+                },
+                _ => None,
+            }
+        } else {
+            let line = content.try_char_to_line(char)?;
+            let line_start = content.try_line_to_char(line)?;
+            let line = content.line(line).try_to_string()?;
+            let at = content.try_char_to_byte(char)? - content.try_char_to_byte(line_start)?;
+
+            match FieldAccess::parse(&line, at) {
+                Some(access) => {
+                    let receiver = match access.receiver {
+                        Some(receiver) => match source
+                            .locals_at(offset)
+                            .rev()
+                            .find(|local| *local.name == *receiver)
+                        {
+                            Some(local) => source.infer_local_kind(local)?,
+                            None => None,
+                        },
+                        None => None,
+                    };
+
+                    hover::field(&self.context, receiver.as_deref(), access.field)?
+                }
+                None => None,
+            }
+        };
+
+        let Some(value) = value else {
+            return Ok(None);
+        };
+
+        Ok(Some(lsp::Hover {
+            contents: lsp::HoverContents::Markup(lsp::MarkupContent {
+                kind: lsp::MarkupKind::Markdown,
+                value: value.into_std(),
+            }),
+            range: None,
+        }))
+    }
+
+    /// Build the outline of symbols declared in the given document.
+    pub(super) async fn document_symbols(
+        &self,
+        uri: &Url,
+    ) -> Result<Option<::rust_alloc::vec::Vec<lsp::DocumentSymbol>>> {
+        self.symbols.document_symbols(&self.encoding, uri)
+    }
+
+    /// Search for symbols declared anywhere in the workspace.
+    pub(super) async fn workspace_symbols(
+        &self,
+        query: &str,
+    ) -> Result<::rust_alloc::vec::Vec<lsp::WorkspaceSymbol>> {
+        self.symbols.workspace_symbols(&self.encoding, query)
+    }
+
+    /// Compute the semantic tokens of the given document, optionally limited
+    /// to the given range.
+    pub(super) async fn semantic_tokens(
+        &self,
+        uri: &Url,
+        range: Option<lsp::Range>,
+    ) -> Result<Option<::rust_alloc::vec::Vec<lsp::SemanticToken>>> {
+        self.semantic_tokens.tokens(&self.encoding, uri, range)
+    }
+
+    /// Get the enabled inlay hints of the given document in the given range.
+    pub(super) async fn inlay_hints(
+        &self,
+        uri: &Url,
+        range: lsp::Range,
+    ) -> Result<Option<::rust_alloc::vec::Vec<lsp::InlayHint>>> {
+        self.inlay_hints
+            .hints(&self.encoding, &self.inlay_hint_options, uri, range)
+    }
+
+    /// Find the symbol which can be renamed at the given uri and LSP position.
+    pub(super) async fn prepare_rename(
+        &self,
+        uri: &Url,
+        position: lsp::Position,
+    ) -> Result<Option<lsp::PrepareRenameResponse>> {
+        let Some((document, reference)) = self.reference_at(uri, position)? else {
+            return Ok(None);
+        };
+
+        let (Some(source), Some(name)) = (
+            document.sources.get(document.source_id),
+            document.text(reference.span),
+        ) else {
+            return Ok(None);
+        };
+
+        Ok(Some(lsp::PrepareRenameResponse::RangeWithPlaceholder {
+            range: self.encoding.source_range(source, reference.span)?,
+            placeholder: name.into(),
+        }))
+    }
+
+    /// Rename the symbol at the given uri and LSP position, producing edits
+    /// for every document which references it.
+    ///
+    /// If the symbol can't be renamed to the new name, a message describing
+    /// why is returned instead.
+    pub(super) async fn rename(
+        &self,
+        uri: &Url,
+        position: lsp::Position,
+        new_name: &str,
+    ) -> Result<Result<Option<lsp::WorkspaceEdit>, String>> {
+        let Some((document, reference)) = self.reference_at(uri, position)? else {
+            return Ok(Ok(None));
+        };
+
+        let Some(old) = document.text(reference.span) else {
+            return Ok(Ok(None));
+        };
+
+        let symbol = &reference.symbol;
+
+        if let Some(message) = self
+            .references
+            .check(&self.context, symbol, old, new_name)?
+        {
+            return Ok(Err(message));
+        }
+
+        let mut changes = std::collections::HashMap::new();
+
+        for (url, document) in self.references.iter() {
+            let Some(source) = document.sources.get(document.source_id) else {
+                continue;
+            };
+
+            let mut edits = ::rust_alloc::vec::Vec::new();
+
+            for reference in document.references_to(symbol) {
+                // Shorthand fields like `x` in `Point { x }` name both a field
+                // and a local variable, so they have to be expanded.
+                let new_text = match symbol {
+                    rename::Symbol::Field(..) if document.is_shorthand(reference.span) => {
+                        try_format!("{new_name}: {old}")
+                    }
+                    rename::Symbol::Local(..) if document.is_shorthand(reference.span) => {
+                        try_format!("{old}: {new_name}")
+                    }
+                    _ => new_name.try_to_owned()?,
+                };
+
+                edits.push(lsp::TextEdit {
+                    range: self.encoding.source_range(source, reference.span)?,
+                    new_text: new_text.into_std(),
+                });
+            }
+
+            if !edits.is_empty() {
+                changes.insert(url.clone(), edits);
+            }
+        }
+
+        Ok(Ok(Some(lsp::WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        })))
+    }
+
+    /// Find all references to the symbol at the given uri and LSP position
+    /// across every document.
+    pub(super) async fn references(
+        &self,
+        uri: &Url,
+        position: lsp::Position,
+        include_declaration: bool,
+    ) -> Result<Option<::rust_alloc::vec::Vec<lsp::Location>>> {
+        let Some((_, reference)) = self.reference_at(uri, position)? else {
+            return Ok(None);
+        };
+
+        let symbol = &reference.symbol;
+        let mut locations = ::rust_alloc::vec::Vec::new();
+
+        for (url, document) in self.references.iter() {
+            let Some(source) = document.sources.get(document.source_id) else {
+                continue;
+            };
+
+            for reference in document.references_to(symbol) {
+                if reference.declaration && !include_declaration {
+                    continue;
+                }
+
+                locations.push(lsp::Location {
+                    uri: url.clone(),
+                    range: self.encoding.source_range(source, reference.span)?,
+                });
+            }
+        }
+
+        sort_locations(&mut locations);
+        locations.dedup();
+        Ok(Some(locations))
+    }
+
+    /// Find the implementations of the instance function called or declared
+    /// at the given uri and LSP position, like `process` in
+    /// `value.process()`.
+    ///
+    /// Since the type of the value isn't known, every instance function with
+    /// the same name is a candidate, including those of types provided by the
+    /// context. The latter have no source, so they are located through a
+    /// `rune:` URL naming the function.
+    pub(super) async fn implementations(
+        &self,
+        uri: &Url,
+        position: lsp::Position,
+    ) -> Result<Option<::rust_alloc::vec::Vec<lsp::Location>>> {
+        let Some(source) = self.workspace.get(uri) else {
+            return Ok(None);
+        };
+
+        let declared = match self.reference_at(uri, position)? {
+            Some((_, reference)) => self.references.instance_function(&reference.symbol),
+            None => None,
+        };
+
+        let line;
+
+        let name = match declared {
+            Some(name) => name,
+            None => {
+                let content = &source.content;
+                let char = self.encoding.rope_position(content, position)?;
+                let index = content.try_char_to_line(char)?;
+                let line_start = content.try_line_to_char(index)?;
+                let at = content.try_char_to_byte(char)? - content.try_char_to_byte(line_start)?;
+                line = content.line(index).try_to_string()?;
+
+                let Some(name) = rename::instance_call(&line, at) else {
+                    return Ok(None);
+                };
+
+                name
+            }
+        };
+
+        let mut locations = ::rust_alloc::vec::Vec::new();
+
+        for (url, span) in self.references.implementations(name) {
+            let Some(document) = self.references.get(url) else {
+                continue;
+            };
+
+            let Some(source) = document.sources.get(document.source_id) else {
+                continue;
+            };
+
+            locations.push(lsp::Location {
+                uri: url.clone(),
+                range: self.encoding.source_range(source, span)?,
+            });
+        }
+
+        for item in rename::native_implementations(&self.context, name)? {
+            locations.push(lsp::Location {
+                uri: crate::languageserver::url::from_item(item)?,
+                range: lsp::Range::default(),
+            });
+        }
+
+        sort_locations(&mut locations);
+        Ok(Some(locations))
+    }
+
+    /// Find the reference to a renamable symbol at the given uri and LSP
+    /// position.
+    fn reference_at(
+        &self,
+        uri: &Url,
+        position: lsp::Position,
+    ) -> Result<Option<(&rename::Document, &rename::Reference)>> {
+        let (Some(source), Some(document)) = (self.workspace.get(uri), self.references.get(uri))
+        else {
+            return Ok(None);
+        };
+
+        let char = self.encoding.rope_position(&source.content, position)?;
+        let offset = source.content.try_char_to_byte(char)?;
+        Ok(document
+            .reference_at(offset)
+            .map(|reference| (document, reference)))
+    }
+
+    /// Collect completions at the given uri and LSP position.
     #[tracing::instrument(skip_all)]
     pub(super) fn complete(
         &self,
@@ -285,102 +736,131 @@ impl<'a> State<'a> {
             return Ok(None);
         };
 
-        let offset = self
-            .encoding
-            .rope_position(&workspace_source.content, position)?;
+        let content = &workspace_source.content;
+
+        let char = self.encoding.rope_position(content, position)?;
+        let offset = content.try_char_to_byte(char)?;
+        let line_start = content.try_line_to_char(content.try_char_to_line(char)?)?;
 
-        let Some((mut symbol, _start)) = workspace_source.looking_back(offset)? else {
+        let Some(line) = content.get_slice(line_start..char) else {
             return Ok(None);
         };
 
-        tracing::trace!(?symbol, start = ?_start);
+        let line = line.try_to_string()?;
+        let completion = Completion::parse(&line)?;
+        tracing::trace!(partial = completion.partial());
 
-        if symbol.is_empty() {
-            return Ok(None);
-        }
+        let range = lsp::Range {
+            start: self
+                .encoding
+                .rope_offset_position(content, offset - completion.partial().len())?,
+            end: position,
+        };
 
         let mut results = Vec::new();
 
-        let can_use_instance_fn: &[_] = &['.'];
-        let first_char = symbol.remove(0);
-        let symbol = symbol.trim();
-
-        if let Some(unit) = workspace_source.unit.as_ref() {
-            super::completion::complete_for_unit(
-                workspace_source,
-                unit,
-                symbol,
-                position,
-                &mut results,
-            )?;
-        }
+        match &completion {
+            Completion::Path { path, partial } => {
+                if let Some(unit) = &workspace_source.unit {
+                    completion::complete_for_unit(
+                        workspace_source,
+                        unit,
+                        path,
+                        partial,
+                        range,
+                        &mut results,
+                    )?;
+                }
 
-        if first_char.is_ascii_alphabetic() || can_use_instance_fn.contains(&first_char) {
-            super::completion::complete_native_instance_data(
-                &self.context,
-                symbol,
-                position,
-                &mut results,
-            )?;
-        } else {
-            super::completion::complete_native_loose_data(
-                &self.context,
-                symbol,
-                position,
-                &mut results,
-            )?;
+                completion::complete_native_path(
+                    &self.context,
+                    path,
+                    partial,
+                    range,
+                    &mut results,
+                )?;
+            }
+            Completion::Instance { receiver, partial } => {
+                completion::complete_native_instance_data(
+                    &self.context,
+                    *receiver,
+                    partial,
+                    range,
+                    &mut results,
+                )?;
+            }
+            Completion::Ident { partial } => {
+                completion::complete_locals(
+                    workspace_source,
+                    offset,
+                    partial,
+                    range,
+                    &mut results,
+                )?;
+
+                if let Some(unit) = &workspace_source.unit {
+                    completion::complete_for_unit(
+                        workspace_source,
+                        unit,
+                        &[],
+                        partial,
+                        range,
+                        &mut results,
+                    )?;
+                }
+
+                completion::complete_native_loose_data(
+                    &self.context,
+                    partial,
+                    range,
+                    &mut results,
+                )?;
+            }
         }
 
         Ok(Some(results))
     }
 
-    pub(super) fn format(&mut self, uri: &Url) -> Result<Option<lsp::TextEdit>> {
-        let sources = &mut self.workspace.sources;
+    /// Format the in-memory source at the given uri, returning the edits
+    /// needed to bring it into its formatted form.
+    pub(super) async fn format(&self, uri: &Url) -> Result<Option<Vec<lsp::TextEdit>>> {
+        let sources = &self.workspace.sources;
         tracing::trace!(uri = ?uri.try_to_string()?, uri_exists = sources.get(uri).is_some());
 
-        let Some(s) = sources.get_mut(uri) else {
+        let Some(s) = sources.get(uri) else {
             return Ok(None);
         };
 
         let source = s.content.try_to_string()?;
 
-        let mut diagnostics = Diagnostics::new();
-
-        let Ok(formatted) = crate::fmt::layout_source_with(
-            &source,
-            SourceId::EMPTY,
-            &self.options,
-            &mut diagnostics,
-        ) else {
-            return Ok(None);
-        };
-
-        // Only modify if changed
-        if source == formatted {
+        // The formatter recovers from some syntax errors, so make sure that
+        // the source parses before rewriting it.
+        if let Err(error) = crate::parse::parse_all::<ast::File>(&source, SourceId::EMPTY, true) {
+            self.not_formatted(uri, error).await?;
             return Ok(None);
         }
 
-        let edit = lsp::TextEdit::new(
-            // Range over full document
-            lsp::Range::new(
-                lsp::Position::new(0, 0),
-                lsp::Position::new(u32::MAX, u32::MAX),
-            ),
-            formatted.into_std(),
-        );
+        let options = self.fmt_options()?;
 
-        Ok(Some(edit))
+        let Some(formatted) = self.layout(uri, &source, &options).await? else {
+            return Ok(None);
+        };
+
+        Ok(Some(
+            self.encoding.text_edits(&s.content, &source, &formatted)?,
+        ))
     }
 
-    pub(super) fn range_format(
-        &mut self,
+    /// Format the given range of the in-memory source at the given uri.
+    pub(super) async fn range_format(
+        &self,
         uri: &Url,
         range: &lsp::Range,
     ) -> Result<Option<lsp::TextEdit>> {
-        let sources = &mut self.workspace.sources;
+        let sources = &self.workspace.sources;
         tracing::trace!(uri = ?uri.try_to_string()?, uri_exists = sources.get(uri).is_some());
 
-        let Some(s) = sources.get_mut(uri) else {
+        let Some(s) = sources.get(uri) else {
             return Ok(None);
         };
 
@@ -393,14 +873,10 @@ impl<'a> State<'a> {
 
         let source = source.try_to_string()?;
 
-        let mut options = self.options.clone();
+        let mut options = self.fmt_options()?;
         options.fmt.force_newline = false;
 
-        let mut diagnostics = Diagnostics::new();
-
-        let Ok(formatted) =
-            crate::fmt::layout_source_with(&source, SourceId::EMPTY, &options, &mut diagnostics)
-        else {
+        let Some(formatted) = self.layout(uri, &source, &options).await? else {
             return Ok(None);
         };
 
@@ -413,16 +889,136 @@ impl<'a> State<'a> {
         Ok(Some(edit))
     }
 
+    /// Options used when formatting, including the formatting options of the
+    /// workspace manifest.
+    fn fmt_options(&self) -> Result<Options> {
+        let mut options = self.options.clone();
+
+        for option in &self.fmt_options {
+            options.parse_option(option)?;
+        }
+
+        Ok(options)
+    }
+
+    /// Lay out the given source, preserving its line endings.
+    ///
+    /// Sources which fail to format are logged instead of being reported as
+    /// errors, since editors tend to show those as intrusive dialogs.
+    async fn layout(&self, uri: &Url, source: &str, options: &Options) -> Result<Option<String>> {
+        let mut diagnostics = Diagnostics::new();
+
+        let result =
+            crate::fmt::layout_source_with(source, SourceId::EMPTY, options, &mut diagnostics);
+
+        let formatted = match result {
+            Ok(formatted) if !diagnostics.has_error() => formatted,
+            Ok(..) => {
+                self.not_formatted(uri, "source contains errors").await?;
+                return Ok(None);
+            }
+            Err(error) => {
+                self.not_formatted(uri, error).await?;
+                return Ok(None);
+            }
+        };
+
+        if !source.contains("\r\n") {
+            return Ok(Some(formatted));
+        }
+
+        let mut output = String::new();
+
+        for line in formatted.split_inclusive('\n') {
+            match line.strip_suffix('\n') {
+                Some(line) => {
+                    output.try_push_str(line)?;
+                    output.try_push_str("\r\n")?;
+                }
+                None => {
+                    output.try_push_str(line)?;
+                }
+            }
+        }
+
+        Ok(Some(output))
+    }
+
+    /// Log that the source at the given uri could not be formatted.
+    async fn not_formatted(&self, uri: &Url, reason: impl fmt::Display) -> Result<()> {
+        self.output
+            .log(
+                lsp::MessageType::INFO,
+                format_args!("Not formatting `{uri}`: {reason}"),
+            )
+            .await
+    }
+
+    /// Collect quick fixes for the given diagnostics.
+    ///
+    /// Suggestions are attached to diagnostics when they are emitted, so this
+    /// only needs to decode them from the diagnostics sent back by the client.
+    pub(super) fn code_actions(
+        &self,
+        uri: &Url,
+        diagnostics: &[lsp::Diagnostic],
+    ) -> Result<lsp::CodeActionResponse> {
+        let mut actions = ::rust_alloc::vec::Vec::new();
+
+        for diagnostic in diagnostics {
+            let Some(data) = &diagnostic.data else {
+                continue;
+            };
+
+            let Ok(data) = serde_json::from_value::<SuggestionData>(data.clone()) else {
+                continue;
+            };
+
+            let mut changes = std::collections::HashMap::new();
+            changes.insert(uri.clone(), vec![data.edit]);
+
+            actions.push(lsp::CodeActionOrCommand::CodeAction(lsp::CodeAction {
+                title: data.title,
+                kind: Some(lsp::CodeActionKind::QUICKFIX),
+                diagnostics: Some(vec![diagnostic.clone()]),
+                edit: Some(lsp::WorkspaceEdit {
+                    changes: Some(changes),
+                    ..Default::default()
+                }),
+                is_preferred: Some(true),
+                ..Default::default()
+            }));
+        }
+
+        Ok(actions)
+    }
+
     /// Rebuild the project.
+    ///
+    /// The rebuild is aborted between compilation phases if the rebuild
+    /// cancellation token is signalled, which happens when a newer change to
+    /// a document is received.
     pub(super) async fn rebuild(&mut self) -> Result<()> {
+        self.rebuild_cancel.reset();
+
         // Keep track of URLs visited as part of workspace builds.
         let mut visited = HashSet::new();
         // Workspace results.
         let mut workspace_results = Vec::new();
+        // Formatting options from the workspace manifest.
+        let mut fmt_options = Vec::new();
         // Build results.
         let mut script_results = Vec::new();
         // Emitted diagnostics, grouped by URL.
         let mut reporter = Reporter::default();
+        // References to renamable symbols.
+        let mut references = References::default();
+        // Declared symbols.
+        let mut symbols = Symbols::default();
+        // Classified identifiers.
+        let mut semantic_tokens = SemanticTokens::default();
+        // Inlay hints.
+        let mut inlay_hints = InlayHints::default();
 
         if let Some((workspace_url, workspace_path)) = &self.workspace.manifest_path {
             let mut diagnostics = workspace::Diagnostics::default();
@@ -434,6 +1030,7 @@ impl<'a> State<'a> {
                 &mut build,
                 &mut diagnostics,
                 &self.workspace,
+                &mut fmt_options,
             );
 
             match result {
@@ -446,6 +1043,10 @@ impl<'a> State<'a> {
                 }
                 Ok(script_builds) => {
                     for script_build in script_builds {
+                        if self.is_rebuild_cancelled().await {
+                            return Ok(());
+                        }
+
                         script_results
                             .try_push(self.build_scripts(script_build, Some(&mut visited))?)?;
                     }
@@ -465,6 +1066,10 @@ impl<'a> State<'a> {
                 continue;
             }
 
+            if self.is_rebuild_cancelled().await {
+                return Ok(());
+            }
+
             tracing::trace!(url = ?url.try_to_string()?, "build plain source");
 
             let mut build = Build::from_file();
@@ -478,12 +1083,20 @@ impl<'a> State<'a> {
             script_results.try_push(self.build_scripts(build, None)?)?;
         }
 
+        if self.is_rebuild_cancelled().await {
+            return Ok(());
+        }
+
         // We need to pupulate diagnostics for everything we know about, in
         // order to clear errors which might've previously been set.
         for url in self.workspace.removed.drain(..) {
             reporter.ensure(&url);
         }
 
+        for url in self.published.drain() {
+            reporter.ensure(&url);
+        }
+
         for (diagnostics, mut build) in workspace_results {
             build.populate(&mut reporter)?;
             self.emit_workspace(diagnostics, &build, &mut reporter)?;
@@ -496,7 +1109,18 @@ impl<'a> State<'a> {
             let sources = Arc::new(build.sources);
             let doc_visitor = Arc::new(doc_visitor);
 
-            for (source_id, value) in source_visitor.into_indexes() {
+            references.insert(&build.id_to_url, &sources, source_visitor.indexes())?;
+            symbols.insert(&build.id_to_url, &sources, source_visitor.indexes())?;
+            semantic_tokens.insert(&build.id_to_url, &sources, source_visitor.indexes())?;
+            inlay_hints.insert(
+                &self.context,
+                &doc_visitor,
+                &build.id_to_url,
+                &sources,
+                source_visitor.indexes(),
+            )?;
+
+            for (source_id, mut value) in source_visitor.into_indexes() {
                 let Some(url) = build.id_to_url.get(&source_id) else {
                     continue;
                 };
@@ -505,6 +1129,12 @@ impl<'a> State<'a> {
                     continue;
                 };
 
+                // Keep the local variables of the last successful build, so
+                // that they can still be completed while the source is broken.
+                if unit.is_err() {
+                    value.locals = mem::take(&mut source.index.locals);
+                }
+
                 source.index = value;
                 source.build_sources = Some(sources.clone());
 
@@ -516,7 +1146,17 @@ impl<'a> State<'a> {
             }
         }
 
+        self.fmt_options = fmt_options;
+        self.references = references;
+        self.symbols = symbols;
+        self.semantic_tokens = semantic_tokens;
+        self.inlay_hints = inlay_hints;
+
         for (url, diagnostics) in reporter.by_url {
+            if !diagnostics.is_empty() {
+                self.published.insert(url.clone());
+            }
+
             tracing::info!(
                 url = ?url.try_to_string()?,
                 diagnostics = diagnostics.len(),
@@ -537,6 +1177,24 @@ impl<'a> State<'a> {
         Ok(())
     }
 
+    /// Test if the ongoing rebuild has been cancelled.
+    ///
+    /// This yields first, so that incoming messages which might cancel the
+    /// rebuild get a chance to be processed.
+    async fn is_rebuild_cancelled(&self) -> bool {
+        tokio::task::yield_now().await;
+
+        if !self.rebuild_cancel.is_cancelled() {
+            return false;
+        }
+
+        tracing::info!("rebuild cancelled");
+        // Make sure that the project is eventually rebuilt with the changes
+        // which caused the cancellation.
+        self.rebuild_interest();
+        true
+    }
+
     /// Try to load workspace.
     fn load_workspace(
         &self,
@@ -545,6 +1203,7 @@ impl<'a> State<'a> {
         manifest_build: &mut Build,
         diagnostics: &mut workspace::Diagnostics,
         workspace: &Workspace,
+        fmt_options: &mut Vec<String>,
     ) -> Result<Vec<Build>, anyhow::Error> {
         tracing::info!(url = ?url.try_to_string(), "building workspace");
 
@@ -569,6 +1228,8 @@ impl<'a> State<'a> {
             .with_source_loader(&mut source_loader)
             .build()?;
 
+        *fmt_options = manifest.fmt.try_clone()?;
+
         let mut script_builds = Vec::new();
 
         for p in manifest.find_all(workspace::WorkspaceFilter::All)? {
@@ -628,6 +1289,7 @@ impl<'a> State<'a> {
             .with_visitor(&mut doc_visitor)?
             .with_visitor(&mut source_visitor)?
             .with_source_loader(&mut source_loader)
+            .with_cancellation(&self.rebuild_cancel.cancelled)
             .build();
 
         if let Some(built) = built {
@@ -657,8 +1319,14 @@ impl<'a> State<'a> {
         for diagnostic in diagnostics.diagnostics() {
             tracing::trace!(?diagnostic, "workspace diagnostic");
 
-            let workspace::Diagnostic::Fatal(f) = diagnostic;
-            self.report(build, reporter, f.source_id(), f.error(), to_error)?;
+            match diagnostic {
+                workspace::Diagnostic::Fatal(f) => {
+                    self.report(build, reporter, f.source_id(), f.error(), to_error)?;
+                }
+                workspace::Diagnostic::Warning(w) => {
+                    self.report(build, reporter, w.source_id(), w.error(), to_warning)?;
+                }
+            }
         }
 
         Ok(())
@@ -685,37 +1353,66 @@ impl<'a> State<'a> {
             tracing::trace!(?diagnostic, id_to_url = ?build.id_to_url, "script diagnostic");
 
             match diagnostic {
-                Diagnostic::Fatal(f) => match f.kind() {
-                    FatalDiagnosticKind::CompileError(e) => {
-                        self.report(build, reporter, f.source_id(), e, to_error)?;
-                    }
-                    FatalDiagnosticKind::LinkError(e) => match e {
-                        LinkerError::MissingFunction { hash, spans } => {
-                            for (span, source_id) in spans {
-                                let (Some(url), Some(source)) = (
-                                    build.id_to_url.get(source_id),
-                                    build.sources.get(*source_id),
-                                ) else {
-                                    continue;
-                                };
-
-                                let range = self.encoding.source_range(source, *span)?;
-
-                                let diagnostics = reporter.entry(url);
-
-                                diagnostics.try_push(to_error(
-                                    range,
-                                    format_args!("Missing function with hash `{}`", hash),
-                                )?)?;
+                Diagnostic::Fatal(f) => {
+                    let converted = crate::diagnostics::fatal_diagnostic(f, &build.sources)?;
+
+                    let (message, code) = match f.kind() {
+                        FatalDiagnosticKind::CompileError(e) => {
+                            (converted.message.as_str().try_into()?, e.name())
+                        }
+                        FatalDiagnosticKind::LinkError(e) => match e {
+                            LinkerError::MissingFunction { .. } => {
+                                (converted.message.as_str().try_into()?, "missing-function")
                             }
+                        },
+                        FatalDiagnosticKind::DeniedWarning(e) => (e.try_to_string()?, e.name()),
+                        FatalDiagnosticKind::Internal(..) => {
+                            (converted.message.as_str().try_into()?, "internal")
                         }
-                    },
-                    FatalDiagnosticKind::Internal(e) => {
-                        report_without_span(build, reporter, f.source_id(), e, to_error)?;
-                    }
-                },
+                    };
+
+                    let data = match f.kind() {
+                        FatalDiagnosticKind::CompileError(e) => {
+                            self.suggestion_data(build, f.source_id(), e)?
+                        }
+                        _ => None,
+                    };
+
+                    self.report_diagnostic(
+                        build,
+                        reporter,
+                        f.source_id(),
+                        &converted,
+                        &message,
+                        code,
+                        |range, message| {
+                            let mut diagnostic = to_error(range, message)?;
+                            diagnostic.data.clone_from(&data);
+                            Ok(diagnostic)
+                        },
+                    )?;
+                }
                 Diagnostic::Warning(e) => {
-                    self.report(build, reporter, e.source_id(), e, to_warning)?;
+                    let converted = crate::diagnostics::warning_diagnostic(e, &build.sources)?;
+                    let message = e.try_to_string()?;
+
+                    let report: fn(lsp::Range, &str) -> alloc::Result<lsp::Diagnostic> =
+                        match e.kind() {
+                            WarningDiagnosticKind::UnusedVariable { .. } => {
+                                |range, message| to_hint(range, message)
+                            }
+                            _ => |range, message| to_warning(range, message),
+                        };
+
+                    self.report_diagnostic(
+                        build,
+                        reporter,
+                        e.source_id(),
+                        &converted,
+                        &message,
+                        e.name(),
+                        report,
+                    )?;
                 }
                 Diagnostic::RuntimeWarning(_) => {}
             }
@@ -724,6 +1421,131 @@ impl<'a> State<'a> {
         Ok(())
     }
 
+    /// Report a diagnostic as constructed for emission.
+    ///
+    /// Every primary label is reported as a separate diagnostic in the
+    /// document it refers to, with the secondary labels included as related
+    /// information. Notes are appended to the message.
+    #[allow(clippy::too_many_arguments)]
+    fn report_diagnostic<R>(
+        &self,
+        build: &Build,
+        reporter: &mut Reporter,
+        source_id: SourceId,
+        diagnostic: &d::Diagnostic<SourceId>,
+        message: &str,
+        code: &str,
+        report: R,
+    ) -> Result<()>
+    where
+        R: Fn(lsp::Range, &str) -> alloc::Result<lsp::Diagnostic>,
+    {
+        let mut message = String::try_from(message)?;
+
+        for note in &diagnostic.notes {
+            message.try_push_str("\n")?;
+            message.try_push_str(note.trim_end())?;
+        }
+
+        let mut related = ::rust_alloc::vec::Vec::new();
+
+        for label in &diagnostic.labels {
+            if label.style != d::LabelStyle::Secondary {
+                continue;
+            }
+
+            let Some(location) = self.label_location(build, label)? else {
+                continue;
+            };
+
+            related.push(lsp::DiagnosticRelatedInformation {
+                location,
+                message: label.message.clone(),
+            });
+        }
+
+        let mut primary = diagnostic
+            .labels
+            .iter()
+            .filter(|label| label.style == d::LabelStyle::Primary)
+            .peekable();
+
+        if primary.peek().is_none() {
+            return report_without_span(build, reporter, source_id, &message, |range, message| {
+                let mut diagnostic = report(range, message)?;
+                diagnostic.code = Some(lsp::NumberOrString::String(code.into()));
+                Ok(diagnostic)
+            });
+        }
+
+        for label in primary {
+            let Some(location) = self.label_location(build, label)? else {
+                continue;
+            };
+
+            let mut diagnostic = report(location.range, &message)?;
+            diagnostic.code = Some(lsp::NumberOrString::String(code.into()));
+
+            if !related.is_empty() {
+                diagnostic.related_information = Some(related.clone());
+            }
+
+            reporter.entry(&location.uri).try_push(diagnostic)?;
+        }
+
+        Ok(())
+    }
+
+    /// Resolve the location of a diagnostic label.
+    fn label_location(
+        &self,
+        build: &Build,
+        label: &d::Label<SourceId>,
+    ) -> Result<Option<lsp::Location>> {
+        let (Some(source), Some(url)) = (
+            build.sources.get(label.file_id),
+            build.id_to_url.get(&label.file_id),
+        ) else {
+            return Ok(None);
+        };
+
+        let range = lsp::Range {
+            start: self.encoding.source_position(source, label.range.start)?,
+            end: self.encoding.source_position(source, label.range.end)?,
+        };
+
+        Ok(Some(lsp::Location {
+            uri: url.clone(),
+            range,
+        }))
+    }
+
+    /// Encode the suggestion associated with a compile error so that it can be
+    /// offered as a quick fix once the client asks for code actions.
+    fn suggestion_data(
+        &self,
+        build: &Build,
+        source_id: SourceId,
+        error: &compile::Error,
+    ) -> Result<Option<serde_json::Value>> {
+        let Some(suggestion) = error.suggestion() else {
+            return Ok(None);
+        };
+
+        let Some(source) = build.sources.get(source_id) else {
+            return Ok(None);
+        };
+
+        let range = self.encoding.source_range(source, suggestion.span)?;
+
+        let data = SuggestionData {
+            title: suggestion.message.into(),
+            edit: lsp::TextEdit::new(range, suggestion.replacement.into()),
+        };
+
+        Ok(Some(serde_json::to_value(data)?))
+    }
+
     /// Convert the given span and error into an error diagnostic.
     fn report<E, R>(
         &self,
@@ -862,25 +1684,34 @@ impl ServerSource {
         self.content.chunks()
     }
 
-    /// Returns the best match wordwise when looking back. Note that this will also include the *previous* terminal token.
-    pub(crate) fn looking_back(&self, offset: usize) -> alloc::Result<Option<(String, usize)>> {
-        let (chunk, start_byte, _, _) = self.content.chunk_at_byte(offset);
+    /// Iterate over the local variables which are in scope at the given byte
+    /// offset.
+    pub(super) fn locals_at(&self, offset: usize) -> impl DoubleEndedIterator<Item = &Local> {
+        self.index.locals.iter().filter(move |local| {
+            local.span.end.into_usize() <= offset
+                && local.scope.start.into_usize() <= offset
+                && offset <= local.scope.end.into_usize()
+        })
+    }
 
-        // The set of tokens that delimit symbols.
-        let x: &[_] = &[
-            ',', ';', '(', '.', '=', '+', '-', '*', '/', '}', '{', ']', '[', ')',
-        ];
+    /// Find the local variable whose definition covers the given byte offset.
+    pub(super) fn local_defined_at(&self, offset: usize) -> Option<&Local> {
+        self.index.locals.iter().find(|local| {
+            local.span.start.into_usize() <= offset && offset <= local.span.end.into_usize()
+        })
+    }
 
-        let end_search = (offset - start_byte + 1).min(chunk.len());
+    /// Infer the kind of a local variable from the expression it was
+    /// initialized with.
+    pub(super) fn infer_local_kind(&self, local: &Local) -> Result<Option<String>> {
+        let start = local.span.end.into_usize();
+        let end = local.scope.end.into_usize();
 
-        let Some(looking_back) = chunk[..end_search].rfind(x) else {
+        let Some(text) = self.content.get_byte_slice(start..end) else {
             return Ok(None);
         };
 
-        Ok(Some((
-            chunk[looking_back..end_search].trim().try_to_owned()?,
-            start_byte + looking_back,
-        )))
+        Ok(hover::infer_kind(&text.try_to_string()?)?)
     }
 
     pub(super) fn get_docs_by_hash(&self, hash: crate::Hash) -> Option<&VisitorData> {
@@ -894,6 +1725,11 @@ impl fmt::Display for ServerSource {
     }
 }
 
+/// Sort locations by the document they are in and where in it.
+fn sort_locations(locations: &mut [lsp::Location]) {
+    locations.sort_by(|a, b| (a.uri.as_str(), a.range.start).cmp(&(b.uri.as_str(), b.range.start)));
+}
+
 /// Convert the given span and error into an error diagnostic.
 fn report_without_span<E, R>(
     build: &Build,
@@ -924,6 +1760,13 @@ where
     display_to_diagnostic(range, error, lsp::DiagnosticSeverity::ERROR)
 }
 
+/// A machine-applicable suggestion attached to a diagnostic.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SuggestionData {
+    title: ::rust_alloc::string::String,
+    edit: lsp::TextEdit,
+}
+
 /// Convert the given span and error into a warning diagnostic.
 fn to_warning<E>(range: lsp::Range, error: E) -> alloc::Result<lsp::Diagnostic>
 where
@@ -932,6 +1775,17 @@ where
     display_to_diagnostic(range, error, lsp::DiagnosticSeverity::WARNING)
 }
 
+/// Convert the given span and error into a hint diagnostic for code which is
+/// unnecessary, which clients typically render as faded out.
+fn to_hint<E>(range: lsp::Range, error: E) -> alloc::Result<lsp::Diagnostic>
+where
+    E: fmt::Display,
+{
+    let mut diagnostic = display_to_diagnostic(range, error, lsp::DiagnosticSeverity::HINT)?;
+    diagnostic.tags = Some(vec![lsp::DiagnosticTag::UNNECESSARY]);
+    Ok(diagnostic)
+}
+
 /// Convert a span and something displayeable into diagnostics.
 fn display_to_diagnostic<E>(
     range: lsp::Range,
@@ -957,12 +1811,75 @@ where
 #[derive(Default)]
 pub(super) struct Index {
     /// Spans mapping to their corresponding definitions.
-    definitions: BTreeMap<Span, Definition>,
+    pub(super) definitions: BTreeMap<Span, Definition>,
+    /// Local variables, in the order that they were defined.
+    pub(super) locals: Vec<Local>,
+    /// Items declared in the source, with the span of their declaration.
+    pub(super) items: Vec<(Span, ItemBuf)>,
+    /// Fields declared in the source.
+    pub(super) fields: Vec<Field>,
+    /// Uses of fields by name, like `x` in `point.x`.
+    pub(super) field_uses: Vec<FieldUse>,
+    /// Items declared in the source, as seen while indexing.
+    pub(super) declarations: Vec<Declaration>,
+    /// Instance functions declared in the source by their name, with the same
+    /// span as their item.
+    pub(super) instance_functions: Vec<(Span, String)>,
+}
+
+/// A local variable.
+#[derive(TryClone)]
+pub(super) struct Local {
+    /// The name of the variable.
+    pub(super) name: String,
+    /// The span of the variable definition.
+    pub(super) span: Span,
+    /// The span in which the variable can be referenced.
+    pub(super) scope: Span,
+    /// If the variable is an argument of a function or closure.
+    pub(super) parameter: bool,
+}
+
+/// An item declared in a source.
+pub(super) struct Declaration {
+    /// The span of the whole declaration.
+    pub(super) span: Span,
+    /// The span of the name of the item.
+    pub(super) name: Span,
+    /// The item being declared.
+    pub(super) item: ItemBuf,
+    /// The kind of the declaration.
+    pub(super) kind: DeclarationKind,
+}
+
+/// A field declared in a struct or a struct variant.
+pub(super) struct Field {
+    /// The struct or variant declaring the field.
+    pub(super) container: ItemBuf,
+    /// The type hash of the struct or variant declaring the field.
+    pub(super) hash: Hash,
+    /// The name of the field.
+    pub(super) name: String,
+    /// The span of the name of the field.
+    pub(super) span: Span,
+}
+
+/// A use of a field.
+pub(super) struct FieldUse {
+    /// The span of the name of the field.
+    pub(super) span: Span,
+    /// The type hash of the struct or variant the field belongs to, if it is
+    /// known.
+    pub(super) container: Option<Hash>,
+    /// The name of the field.
+    pub(super) name: String,
 }
 
 /// A definition source.
 #[derive(Debug, TryClone)]
 pub(super) enum DefinitionSource {
+    /// An item provided by the context, which has no source.
+    Context,
     /// Only a file source.
     Source(SourceId),
     /// A location definition (source and span).
@@ -974,17 +1891,18 @@ pub(super) enum DefinitionSource {
 impl DefinitionSource {
     fn span(&self) -> Span {
         match self {
-            Self::Source(..) => Span::empty(),
+            Self::Context | Self::Source(..) => Span::empty(),
             Self::Location(location) => location.span,
             Self::SourceMeta(compile_source) => compile_source.location.span,
         }
     }
 
-    fn source_id(&self) -> SourceId {
+    fn source_id(&self) -> Option<SourceId> {
         match self {
-            Self::Source(source_id) => *source_id,
-            Self::Location(location) => location.source_id,
-            Self::SourceMeta(compile_source) => compile_source.location.source_id,
+            Self::Context => None,
+            Self::Source(source_id) => Some(*source_id),
+            Self::Location(location) => Some(location.source_id),
+            Self::SourceMeta(compile_source) => Some(compile_source.location.source_id),
         }
     }
 
@@ -1002,6 +1920,8 @@ pub(super) struct Definition {
     pub(super) kind: DefinitionKind,
     /// The id of the source id the definition corresponds to.
     pub(super) source: DefinitionSource,
+    /// The hash of the item being defined, if it is an item.
+    pub(super) hash: Option<Hash>,
 }
 
 #[derive(Debug, TryClone, Clone, Copy)]
@@ -1021,6 +1941,8 @@ pub(super) enum DefinitionKind {
     StructVariant,
     /// An enum.
     Enum,
+    /// A native type.
+    Type,
     /// A function.
     Function,
     /// An associated function.
@@ -1037,6 +1959,11 @@ struct Visitor {
 }
 
 impl Visitor {
+    /// Access the indexes being built.
+    pub(super) fn indexes(&self) -> &HashMap<SourceId, Index> {
+        &self.indexes
+    }
+
     /// Convert visitor back into an index.
     pub(super) fn into_indexes(self) -> HashMap<SourceId, Index> {
         self.indexes
@@ -1044,11 +1971,41 @@ impl Visitor {
 }
 
 impl CompileVisitor for Visitor {
-    fn visit_meta(&mut self, location: &dyn Located, meta: MetaRef<'_>) -> Result<(), MetaError> {
+    fn register_meta(&mut self, meta: MetaRef<'_>) -> Result<(), MetaError> {
+        if !matches!(
+            meta.kind,
+            meta::Kind::Struct { .. }
+                | meta::Kind::Variant { .. }
+                | meta::Kind::Enum { .. }
+                | meta::Kind::Function { .. }
+                | meta::Kind::Const
+        ) {
+            return Ok(());
+        }
+
         let Some(source) = meta.source else {
             return Ok(());
         };
 
+        let location = source.location;
+        let index = self.indexes.entry(location.source_id).or_try_default()?;
+        index
+            .items
+            .try_push((location.span, meta.item.try_to_owned()?))?;
+
+        if let meta::Kind::Function {
+            associated: Some(meta::AssociatedKind::Instance(name)),
+            ..
+        } = &meta.kind
+        {
+            index
+                .instance_functions
+                .try_push((location.span, name.as_ref().try_to_owned()?))?;
+        }
+        Ok(())
+    }
+
+    fn visit_meta(&mut self, location: &dyn Located, meta: MetaRef<'_>) -> Result<(), MetaError> {
         let kind = match &meta.kind {
             meta::Kind::Struct {
                 fields: meta::Fields::Empty,
@@ -1075,6 +2032,7 @@ impl CompileVisitor for Visitor {
                 ..
             } => DefinitionKind::StructVariant,
             meta::Kind::Enum { .. } => DefinitionKind::Enum,
+            meta::Kind::Type { .. } => DefinitionKind::Type,
             meta::Kind::Function {
                 associated: None, ..
             } => DefinitionKind::Function,
@@ -1085,9 +2043,15 @@ impl CompileVisitor for Visitor {
             _ => return Ok(()),
         };
 
+        let source = match meta.source {
+            Some(source) => DefinitionSource::SourceMeta(source.try_clone()?),
+            None => DefinitionSource::Context,
+        };
+
         let definition = Definition {
             kind,
-            source: DefinitionSource::SourceMeta(source.try_clone()?),
+            source,
+            hash: Some(meta.hash),
         };
 
         let location = location.location();
@@ -1110,6 +2074,7 @@ impl CompileVisitor for Visitor {
         let definition = Definition {
             kind: DefinitionKind::Local,
             source: DefinitionSource::Location(Location::new(source_id, var_span.span())),
+            hash: None,
         };
 
         let index = self.indexes.entry(source_id).or_try_default()?;
@@ -1121,12 +2086,93 @@ impl CompileVisitor for Visitor {
         Ok(())
     }
 
+    fn visit_local(
+        &mut self,
+        source_id: SourceId,
+        name: &str,
+        span: &dyn Spanned,
+        scope: &dyn Spanned,
+        parameter: bool,
+    ) -> Result<(), MetaError> {
+        let local = Local {
+            name: name.try_to_owned()?,
+            span: span.span(),
+            scope: scope.span(),
+            parameter,
+        };
+
+        let index = self.indexes.entry(source_id).or_try_default()?;
+        index.locals.try_push(local)?;
+        Ok(())
+    }
+
+    fn visit_field_use(
+        &mut self,
+        source_id: SourceId,
+        container: Option<Hash>,
+        name: &str,
+        span: &dyn Spanned,
+    ) -> Result<(), MetaError> {
+        let field_use = FieldUse {
+            span: span.span(),
+            container,
+            name: name.try_to_owned()?,
+        };
+
+        let index = self.indexes.entry(source_id).or_try_default()?;
+        index.field_uses.try_push(field_use)?;
+        Ok(())
+    }
+
+    fn visit_field(
+        &mut self,
+        location: &dyn Located,
+        item: &Item,
+        hash: Hash,
+        field: &str,
+    ) -> Result<(), MetaError> {
+        let location = location.location();
+
+        let field = Field {
+            container: item.try_to_owned()?,
+            hash,
+            name: field.try_to_owned()?,
+            span: location.span,
+        };
+
+        let index = self.indexes.entry(location.source_id).or_try_default()?;
+        index.fields.try_push(field)?;
+        Ok(())
+    }
+
+    fn visit_declaration(
+        &mut self,
+        location: &dyn Located,
+        name: Span,
+        item: &Item,
+        kind: DeclarationKind,
+    ) -> Result<(), MetaError> {
+        let location = location.location();
+
+        let declaration = Declaration {
+            span: location.span,
+            name,
+            item: item.try_to_owned()?,
+            kind,
+        };
+
+        let index = self.indexes.entry(location.source_id).or_try_default()?;
+        index.declarations.try_push(declaration)?;
+        Ok(())
+    }
+
     fn visit_mod(&mut self, location: &dyn Located) -> Result<(), MetaError> {
         let location = location.location();
 
         let definition = Definition {
             kind: DefinitionKind::Module,
             source: DefinitionSource::Source(location.source_id),
+            hash: None,
         };
 
         let index = self.indexes.entry(location.source_id).or_try_default()?;
@@ -1161,6 +2207,11 @@ impl<'a> ScriptSourceLoader<'a> {
     ) -> compile::Result<Option<[(Url, PathBuf); 2]>> {
         let mut base = root.try_to_owned()?;
 
+        // The root is the path of the source file which declares the module.
+        if !base.pop() {
+            return Ok(None);
+        }
+
         let mut it = item.iter().peekable();
         let mut last = None;
 
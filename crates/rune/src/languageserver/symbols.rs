@@ -0,0 +1,353 @@
+use std::cmp::Reverse;
+use std::sync::Arc;
+
+use anyhow::Result;
+use lsp::Url;
+
+use crate::alloc::prelude::*;
+use crate::alloc::{self, HashMap, String, Vec};
+use crate::ast::Span;
+use crate::compile::DeclarationKind;
+use crate::languageserver::state::{Index, StateEncoding};
+use crate::{ItemBuf, SourceId, Sources};
+
+/// A symbol declared in a document.
+struct Symbol {
+    /// The name of the symbol.
+    name: String,
+    /// The kind of the symbol.
+    kind: lsp::SymbolKind,
+    /// The item declared, which is missing for fields.
+    item: Option<ItemBuf>,
+    /// The item the symbol belongs to, like the struct of a field.
+    container: Option<ItemBuf>,
+    /// The span of the whole declaration.
+    span: Span,
+    /// The span of the name of the symbol.
+    name_span: Span,
+}
+
+/// The symbols declared in a single document.
+struct Document {
+    /// The sources the document was built with.
+    sources: Arc<Sources>,
+    /// The id of the document in the sources.
+    source_id: SourceId,
+    /// Symbols in the order they were declared.
+    symbols: Vec<Symbol>,
+}
+
+/// Symbols declared across all documents which were part of a build.
+#[derive(Default)]
+pub(super) struct Symbols {
+    documents: HashMap<Url, Document>,
+}
+
+impl Symbols {
+    /// Insert the symbols indexed while building the given sources.
+    ///
+    /// Documents which are part of several builds are only inserted once.
+    pub(super) fn insert(
+        &mut self,
+        id_to_url: &HashMap<SourceId, Url>,
+        sources: &Arc<Sources>,
+        indexes: &HashMap<SourceId, Index>,
+    ) -> alloc::Result<()> {
+        for (source_id, index) in indexes {
+            let Some(url) = id_to_url.get(source_id) else {
+                continue;
+            };
+
+            if self.documents.contains_key(url) {
+                continue;
+            }
+
+            let mut symbols = Vec::new();
+
+            for declaration in &index.declarations {
+                let Some(name) = declaration.item.base_name() else {
+                    continue;
+                };
+
+                let kind = match declaration.kind {
+                    DeclarationKind::Module => lsp::SymbolKind::MODULE,
+                    DeclarationKind::Struct => lsp::SymbolKind::STRUCT,
+                    DeclarationKind::Enum => lsp::SymbolKind::ENUM,
+                    DeclarationKind::Variant => lsp::SymbolKind::ENUM_MEMBER,
+                    DeclarationKind::Function => lsp::SymbolKind::FUNCTION,
+                    DeclarationKind::Const => lsp::SymbolKind::CONSTANT,
+                };
+
+                symbols.try_push(Symbol {
+                    name: name.try_to_owned()?,
+                    kind,
+                    item: Some(declaration.item.try_clone()?),
+                    container: declaration
+                        .item
+                        .parent()
+                        .map(|p| p.try_to_owned())
+                        .transpose()?,
+                    span: declaration.span,
+                    name_span: declaration.name,
+                })?;
+            }
+
+            for field in &index.fields {
+                symbols.try_push(Symbol {
+                    name: field.name.try_clone()?,
+                    kind: lsp::SymbolKind::FIELD,
+                    item: None,
+                    container: Some(field.container.try_clone()?),
+                    span: field.span,
+                    name_span: field.span,
+                })?;
+            }
+
+            self.documents.try_insert(
+                url.clone(),
+                Document {
+                    sources: sources.clone(),
+                    source_id: *source_id,
+                    symbols,
+                },
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Build the tree of symbols declared in the given document.
+    pub(super) fn document_symbols(
+        &self,
+        encoding: &StateEncoding,
+        url: &Url,
+    ) -> Result<Option<::rust_alloc::vec::Vec<lsp::DocumentSymbol>>> {
+        let Some(document) = self.documents.get(url) else {
+            return Ok(None);
+        };
+
+        let Some(source) = document.sources.get(document.source_id) else {
+            return Ok(None);
+        };
+
+        let symbols = &document.symbols;
+
+        let mut children = Vec::new();
+        let mut roots = Vec::new();
+
+        for _ in symbols.iter() {
+            children.try_push(Vec::new())?;
+        }
+
+        for n in 0..symbols.len() {
+            match parent(symbols, n) {
+                Some(parent) => children[parent].try_push(n)?,
+                None => roots.try_push(n)?,
+            }
+        }
+
+        fn build(
+            encoding: &StateEncoding,
+            source: &crate::Source,
+            symbols: &[Symbol],
+            children: &[Vec<usize>],
+            n: usize,
+            method: bool,
+        ) -> Result<lsp::DocumentSymbol> {
+            let symbol = &symbols[n];
+
+            // Functions nested in types come from `impl` blocks.
+            let methods = matches!(symbol.kind, lsp::SymbolKind::STRUCT | lsp::SymbolKind::ENUM);
+            let mut nested = ::rust_alloc::vec::Vec::new();
+
+            for &child in sorted(symbols, &children[n])?.iter() {
+                nested.push(build(encoding, source, symbols, children, child, methods)?);
+            }
+
+            let kind = match symbol.kind {
+                lsp::SymbolKind::FUNCTION if method => lsp::SymbolKind::METHOD,
+                kind => kind,
+            };
+
+            #[allow(deprecated)]
+            Ok(lsp::DocumentSymbol {
+                name: symbol.name.as_str().into(),
+                detail: None,
+                kind,
+                tags: None,
+                deprecated: None,
+                range: encoding.source_range(source, symbol.span)?,
+                selection_range: encoding.source_range(source, symbol.name_span)?,
+                children: (!nested.is_empty()).then_some(nested),
+            })
+        }
+
+        let mut out = ::rust_alloc::vec::Vec::new();
+
+        for &n in sorted(symbols, &roots)?.iter() {
+            out.push(build(encoding, source, symbols, &children, n, false)?);
+        }
+
+        Ok(Some(out))
+    }
+
+    /// Find symbols across all documents whose names fuzzy match the given
+    /// query, with the best matches first.
+    pub(super) fn workspace_symbols(
+        &self,
+        encoding: &StateEncoding,
+        query: &str,
+    ) -> Result<::rust_alloc::vec::Vec<lsp::WorkspaceSymbol>> {
+        let mut matches = Vec::new();
+
+        for (url, document) in &self.documents {
+            for symbol in &document.symbols {
+                if let Some(score) = fuzzy_score(&symbol.name, query) {
+                    matches.try_push((score, url, document, symbol))?;
+                }
+            }
+        }
+
+        matches.sort_by(|a, b| {
+            let (a_score, _, _, a) = a;
+            let (b_score, _, _, b) = b;
+
+            (Reverse(a_score), a.name.len(), &a.name).cmp(&(
+                Reverse(b_score),
+                b.name.len(),
+                &b.name,
+            ))
+        });
+
+        let mut out = ::rust_alloc::vec::Vec::new();
+
+        for (_, url, document, symbol) in matches {
+            let Some(source) = document.sources.get(document.source_id) else {
+                continue;
+            };
+
+            let container_name = match &symbol.container {
+                Some(container) if !container.is_empty() => {
+                    Some(container.try_to_string()?.into_std())
+                }
+                _ => None,
+            };
+
+            out.push(lsp::WorkspaceSymbol {
+                name: symbol.name.as_str().into(),
+                kind: symbol.kind,
+                tags: None,
+                container_name,
+                location: lsp::OneOf::Left(lsp::Location {
+                    uri: url.clone(),
+                    range: encoding.source_range(source, symbol.span)?,
+                }),
+                data: None,
+            });
+        }
+
+        Ok(out)
+    }
+}
+
+/// Find the parent of the given symbol.
+///
+/// Fields belong to the struct or variant declaring them, and functions in
+/// `impl` blocks to their type if it is declared in the same document.
+/// Everything else belongs to the innermost declaration containing it.
+fn parent(symbols: &[Symbol], n: usize) -> Option<usize> {
+    let symbol = &symbols[n];
+
+    let owner = match symbol.kind {
+        lsp::SymbolKind::FIELD => symbol.container.as_deref(),
+        lsp::SymbolKind::FUNCTION => symbol.container.as_deref().filter(|container| {
+            symbols.iter().any(|s| {
+                matches!(s.kind, lsp::SymbolKind::STRUCT | lsp::SymbolKind::ENUM)
+                    && s.item.as_deref() == Some(*container)
+            })
+        }),
+        _ => None,
+    };
+
+    if let Some(owner) = owner {
+        if let Some(parent) = symbols
+            .iter()
+            .position(|s| s.item.as_deref() == Some(owner))
+        {
+            return Some(parent);
+        }
+    }
+
+    let mut parent = None::<usize>;
+
+    for (i, other) in symbols.iter().enumerate() {
+        if i == n || other.span == symbol.span || !contains(other.span, symbol.span) {
+            continue;
+        }
+
+        if parent.map_or(true, |p| contains(symbols[p].span, other.span)) {
+            parent = Some(i);
+        }
+    }
+
+    parent
+}
+
+/// Sort the given symbols in the order they appear in the document.
+fn sorted(symbols: &[Symbol], indexes: &[usize]) -> alloc::Result<Vec<usize>> {
+    let mut indexes = indexes.try_to_owned()?;
+    indexes.sort_by_key(|&n| (symbols[n].span.start, Reverse(symbols[n].span.end)));
+    Ok(indexes)
+}
+
+fn contains(outer: Span, inner: Span) -> bool {
+    outer.start <= inner.start && inner.end <= outer.end
+}
+
+/// Score how well the given name fuzzy matches a query, where every
+/// character of the query has to appear in the name in order.
+///
+/// Matches at the start of words and runs of consecutive matches score
+/// higher.
+fn fuzzy_score(name: &str, query: &str) -> Option<usize> {
+    let mut query = query.chars().filter(|c| !c.is_whitespace()).peekable();
+    let mut score = 0;
+    let mut previous = None::<char>;
+    let mut consecutive = false;
+
+    for c in name.chars() {
+        let Some(&q) = query.peek() else {
+            break;
+        };
+
+        if c.to_lowercase().eq(q.to_lowercase()) {
+            query.next();
+            score += 1;
+
+            let word_start = match previous {
+                None => true,
+                Some(p) => p == '_' || (p.is_lowercase() && c.is_uppercase()),
+            };
+
+            if word_start {
+                score += 3;
+            }
+
+            if consecutive {
+                score += 2;
+            }
+
+            consecutive = true;
+        } else {
+            consecutive = false;
+        }
+
+        previous = Some(c);
+    }
+
+    if query.peek().is_some() {
+        return None;
+    }
+
+    Some(score)
+}
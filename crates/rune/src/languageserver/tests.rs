@@ -1,3 +1,17 @@
+use std::sync::Arc;
+
+use ::rust_alloc::vec::Vec;
+
+use serde_json::{json, Value};
+use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _, BufReader, DuplexStream};
+use tokio::sync::Notify;
+
+use crate as rune;
+use crate::support::Result;
+use crate::{Any, Context, Module, Options};
+
+use super::connection::{self, Headers};
+use super::state::{CancellationToken, State};
 use super::Code;
 
 #[test]
@@ -10,3 +24,1576 @@ fn test_code() {
     assert_eq!(code, Code::MethodNotFound);
     assert_eq!(serde_json::to_string(&code).unwrap(), "-32601");
 }
+
+/// Encode a message into a frame.
+fn frame(message: Value) -> Vec<u8> {
+    let content = serde_json::to_vec(&message).unwrap();
+    let mut frame = format!("Content-Length: {}\r\n\r\n", content.len()).into_bytes();
+    frame.extend(content);
+    frame
+}
+
+/// Read a single message sent by the server.
+async fn read(reader: &mut BufReader<DuplexStream>) -> Result<Option<Value>> {
+    let mut buf = Vec::new();
+
+    let Some(headers) = Headers::read(&mut buf, reader).await? else {
+        return Ok(None);
+    };
+
+    let length = headers.content_length.expect("missing content-length") as usize;
+    buf.resize(length, 0);
+    reader.read_exact(&mut buf).await?;
+    Ok(Some(serde_json::from_slice(&buf)?))
+}
+
+/// Run a session against the server, sending the given messages after it has
+/// been initialized and returning every message sent back by the server after
+/// that.
+///
+/// Everything is sent at once, so that all messages are received before the
+/// first request is processed.
+async fn session(messages: Vec<Value>) -> Result<Vec<Value>> {
+    let (mut client, input) = tokio::io::duplex(1 << 16);
+    let (output, server) = tokio::io::duplex(1 << 16);
+    let mut server = BufReader::new(server);
+
+    let (input, output) = connection::pipe(input, output);
+    let serve = super::serve(
+        Context::with_default_modules()?,
+        Options::default(),
+        input,
+        output,
+    );
+
+    let session = async {
+        client
+            .write_all(&frame(json!({
+                "jsonrpc": "2.0",
+                "id": 0,
+                "method": "initialize",
+                "params": { "processId": null, "capabilities": {} },
+            })))
+            .await?;
+
+        while let Some(message) = read(&mut server).await? {
+            if message["id"] == 0 {
+                break;
+            }
+        }
+
+        let mut batch = Vec::new();
+
+        for message in messages {
+            batch.extend(frame(message));
+        }
+
+        batch.extend(frame(json!({
+            "jsonrpc": "2.0",
+            "id": "shutdown",
+            "method": "shutdown",
+        })));
+
+        client.write_all(&batch).await?;
+
+        let mut received = Vec::new();
+
+        while let Some(message) = read(&mut server).await? {
+            received.push(message);
+        }
+
+        Ok::<_, crate::support::Error>(received)
+    };
+
+    let (served, received) = tokio::join!(serve, session);
+    served?;
+    received
+}
+
+/// Find the response to the request with the given id.
+fn response(messages: &[Value], id: i64) -> &Value {
+    messages
+        .iter()
+        .find(|m| m["id"] == id)
+        .expect("missing response")
+}
+
+fn did_open(uri: &str, text: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": {
+            "textDocument": {
+                "uri": uri,
+                "languageId": "rune",
+                "version": 1,
+                "text": text,
+            },
+        },
+    })
+}
+
+fn formatting(id: i64, uri: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "method": "textDocument/formatting",
+        "params": {
+            "textDocument": { "uri": uri },
+            "options": { "tabSize": 4, "insertSpaces": true },
+        },
+    })
+}
+
+#[tokio::test]
+async fn cancel_request() -> Result<()> {
+    let uri = "file:///main.rn";
+
+    let messages = session(vec![
+        did_open(uri, "pub fn main(){1}"),
+        formatting(1, uri),
+        json!({
+            "jsonrpc": "2.0",
+            "method": "$/cancelRequest",
+            "params": { "id": 1 },
+        }),
+        json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didChange",
+            "params": {
+                "textDocument": { "uri": uri, "version": 2 },
+                "contentChanges": [{
+                    "range": {
+                        "start": { "line": 0, "character": 14 },
+                        "end": { "line": 0, "character": 15 },
+                    },
+                    "text": "2",
+                }],
+            },
+        }),
+        formatting(2, uri),
+    ])
+    .await?;
+
+    let ids = messages
+        .iter()
+        .filter_map(|m| m.get("id").cloned())
+        .collect::<Vec<_>>();
+
+    assert_eq!(ids, [json!(1), json!(2), json!("shutdown")]);
+
+    let cancelled = response(&messages, 1);
+    assert_eq!(cancelled["error"]["code"], json!(-32800));
+    assert!(cancelled.get("result").is_none());
+
+    let formatted = response(&messages, 2);
+    assert_eq!(
+        formatted["result"],
+        json!([{
+            "range": {
+                "start": { "line": 0, "character": 0 },
+                "end": { "line": 0, "character": 16 },
+            },
+            "newText": "pub fn main() {\n    2\n}\n",
+        }])
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn formatting_edits() -> Result<()> {
+    let uri = "file:///main.rn";
+
+    let messages = session(vec![
+        did_open(uri, "pub fn main() {\n    let a =   1;\n    a\n}\n"),
+        formatting(1, uri),
+    ])
+    .await?;
+
+    assert_eq!(
+        response(&messages, 1)["result"],
+        json!([{
+            "range": {
+                "start": { "line": 1, "character": 0 },
+                "end": { "line": 2, "character": 0 },
+            },
+            "newText": "    let a = 1;\n",
+        }])
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn formatting_crlf() -> Result<()> {
+    let formatted = "file:///formatted.rn";
+    let unformatted = "file:///unformatted.rn";
+
+    let messages = session(vec![
+        did_open(
+            formatted,
+            "pub fn main() {\r\n    let a = 1;\r\n\r\n    a\r\n}\r\n",
+        ),
+        did_open(
+            unformatted,
+            "pub fn main() {\r\n  let a = 1;\r\n    a\r\n}\r\n",
+        ),
+        formatting(1, formatted),
+        formatting(2, unformatted),
+    ])
+    .await?;
+
+    assert_eq!(response(&messages, 1)["result"], json!([]));
+
+    assert_eq!(
+        response(&messages, 2)["result"],
+        json!([{
+            "range": {
+                "start": { "line": 1, "character": 0 },
+                "end": { "line": 2, "character": 0 },
+            },
+            "newText": "    let a = 1;\r\n",
+        }])
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn formatting_already_formatted() -> Result<()> {
+    let uri = "file:///main.rn";
+
+    let messages = session(vec![
+        did_open(uri, "pub fn main() {\n    1\n}\n"),
+        formatting(1, uri),
+    ])
+    .await?;
+
+    assert_eq!(response(&messages, 1)["result"], json!([]));
+    Ok(())
+}
+
+#[tokio::test]
+async fn formatting_parse_error() -> Result<()> {
+    let uri = "file:///main.rn";
+
+    let messages = session(vec![did_open(uri, "pub fn main( {"), formatting(1, uri)]).await?;
+
+    let response = response(&messages, 1);
+    assert!(response.get("error").is_none());
+    assert_eq!(response["result"], Value::Null);
+
+    let logged = messages.iter().any(|m| {
+        m["method"] == "window/logMessage"
+            && m["params"]["message"]
+                .as_str()
+                .is_some_and(|m| m.starts_with("Not formatting `file:///main.rn`"))
+    });
+
+    assert!(logged, "expected a log message");
+    Ok(())
+}
+
+/// Get the position of the `$0` cursor marker in the given text, and the text
+/// without the marker.
+fn cursor(text: &str) -> (lsp::Position, std::string::String) {
+    let at = text.find("$0").expect("missing cursor");
+    let before = &text[..at];
+    let line = before.matches('\n').count();
+    let character = before.len() - before.rfind('\n').map_or(0, |n| n + 1);
+
+    let position = lsp::Position {
+        line: line as u32,
+        character: character as u32,
+    };
+
+    (position, text.replacen("$0", "", 1))
+}
+
+fn open_params(uri: &lsp::Url, text: std::string::String) -> lsp::DidOpenTextDocumentParams {
+    lsp::DidOpenTextDocumentParams {
+        text_document: lsp::TextDocumentItem {
+            uri: uri.clone(),
+            language_id: "rune".into(),
+            version: 1,
+            text,
+        },
+    }
+}
+
+fn completion_params(uri: &lsp::Url, position: lsp::Position) -> lsp::CompletionParams {
+    lsp::CompletionParams {
+        text_document_position: lsp::TextDocumentPositionParams {
+            text_document: lsp::TextDocumentIdentifier { uri: uri.clone() },
+            position,
+        },
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+        context: None,
+    }
+}
+
+/// Construct a state whose output is discarded.
+fn state(context: Context) -> State {
+    let (_, output) = connection::pipe(tokio::io::empty(), tokio::io::sink());
+    State::new(
+        output,
+        Arc::new(Notify::new()),
+        CancellationToken::default(),
+        context,
+        Options::default(),
+    )
+}
+
+/// Complete at the `$0` marker in the given text after opening and building
+/// it.
+///
+/// If `broken` is specified, the document is changed to it and rebuilt before
+/// completing at its marker instead.
+async fn complete(text: &str, broken: Option<&str>) -> Result<Vec<lsp::CompletionItem>> {
+    let mut state = state(Context::with_default_modules()?);
+
+    let uri = lsp::Url::parse("file:///main.rn")?;
+
+    let (mut position, text) = cursor(text);
+    super::did_open_text_document(&mut state, open_params(&uri, text.clone())).await?;
+    state.rebuild().await?;
+
+    if let Some(broken) = broken {
+        let (broken_position, broken) = cursor(broken);
+        position = broken_position;
+
+        let lines = text.lines().count() as u32;
+
+        let change = lsp::DidChangeTextDocumentParams {
+            text_document: lsp::VersionedTextDocumentIdentifier {
+                uri: uri.clone(),
+                version: 2,
+            },
+            content_changes: vec![lsp::TextDocumentContentChangeEvent {
+                range: Some(lsp::Range::new(
+                    lsp::Position::new(0, 0),
+                    lsp::Position::new(lines, 0),
+                )),
+                range_length: None,
+                text: broken,
+            }],
+        };
+
+        super::did_change_text_document(&mut state, change).await?;
+        state.rebuild().await?;
+    }
+
+    let response = super::completion(&mut state, completion_params(&uri, position)).await?;
+
+    match response {
+        Some(lsp::CompletionResponse::Array(items)) => Ok(items),
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// Labels of completions of the given kind, in sorted order.
+fn labels(items: &[lsp::CompletionItem], kind: lsp::CompletionItemKind) -> Vec<&str> {
+    let mut labels = items
+        .iter()
+        .filter(|item| item.kind == Some(kind))
+        .map(|item| item.label.as_str())
+        .collect::<Vec<_>>();
+
+    labels.sort();
+    labels
+}
+
+fn find<'a>(items: &'a [lsp::CompletionItem], label: &str) -> &'a lsp::CompletionItem {
+    items
+        .iter()
+        .find(|item| item.label == label)
+        .unwrap_or_else(|| panic!("missing completion `{label}`"))
+}
+
+fn docs(item: &lsp::CompletionItem) -> &str {
+    match &item.documentation {
+        Some(lsp::Documentation::MarkupContent(content)) => {
+            assert_eq!(content.kind, lsp::MarkupKind::Markdown);
+            &content.value
+        }
+        _ => panic!("missing documentation for `{}`", item.label),
+    }
+}
+
+const LOCALS: &str = r#"
+fn helper(arg) {
+    let alpha = 1;
+
+    {
+        let beta = 2;
+    }
+
+    let gamma = alpha + arg;
+    $0
+    let delta = gamma;
+    delta
+}
+
+helper(1)
+"#;
+
+#[tokio::test]
+async fn complete_locals() -> Result<()> {
+    let items = complete(LOCALS, None).await?;
+
+    assert_eq!(
+        labels(&items, lsp::CompletionItemKind::VARIABLE),
+        ["alpha", "arg", "gamma"]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn complete_locals_in_broken_source() -> Result<()> {
+    let broken = LOCALS.replace("let delta = gamma;", "let delta = ;");
+    let items = complete(LOCALS, Some(&broken)).await?;
+
+    assert_eq!(
+        labels(&items, lsp::CompletionItemKind::VARIABLE),
+        ["alpha", "arg", "gamma"]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn complete_path() -> Result<()> {
+    let items = complete("let s = std::string::Str$0", None).await?;
+    let item = find(&items, "String");
+    assert_eq!(item.kind, Some(lsp::CompletionItemKind::STRUCT));
+
+    let Some(lsp::CompletionTextEdit::Edit(edit)) = &item.text_edit else {
+        panic!("expected a text edit");
+    };
+
+    assert_eq!(edit.new_text, "String");
+    assert_eq!(edit.range.start, lsp::Position::new(0, 21));
+    assert_eq!(edit.range.end, lsp::Position::new(0, 24));
+
+    let items = complete("let s = std::string::String::$0", None).await?;
+    find(&items, "new");
+    assert_eq!(
+        find(&items, "len").kind,
+        Some(lsp::CompletionItemKind::METHOD)
+    );
+    assert!(!docs(find(&items, "len")).is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn complete_instance_of_literal() -> Result<()> {
+    let items = complete(r#"let n = "hello".le$0"#, None).await?;
+    let item = find(&items, "len");
+    assert!(docs(item).contains("length"), "{}", docs(item));
+
+    for item in &items {
+        let description = item
+            .label_details
+            .as_ref()
+            .and_then(|details| details.description.as_deref());
+
+        assert_eq!(
+            description.map(|d| d.starts_with("::std::string::String::")),
+            Some(true),
+            "unexpected completion `{}` for `{description:?}`",
+            item.label
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn complete_unit_function_docs() -> Result<()> {
+    let text = r#"
+/// Adds one to the given value.
+fn add_one(value) {
+    value + 1
+}
+
+add_$0one(1)
+"#;
+
+    let items = complete(text, None).await?;
+    let item = find(&items, "add_one");
+    assert_eq!(item.kind, Some(lsp::CompletionItemKind::FUNCTION));
+    assert!(docs(item).contains("Adds one to the given value."));
+    Ok(())
+}
+
+/// A player in the game.
+#[derive(Any)]
+#[rune(item = ::game)]
+struct Player {
+    /// The position of the player along the track.
+    #[rune(get)]
+    position: i64,
+}
+
+/// Spawn a player at the given position.
+#[rune::function]
+fn spawn(position: i64) -> Player {
+    Player { position }
+}
+
+/// Hover over the `$0` marker in the given text with the `game` module
+/// installed.
+async fn hover(text: &str) -> Result<Option<std::string::String>> {
+    let mut module = Module::with_crate("game")?;
+    module.ty::<Player>()?;
+    module.function_meta(spawn)?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(module)?;
+
+    let mut state = state(context);
+
+    let uri = lsp::Url::parse("file:///main.rn")?;
+    let (position, text) = cursor(text);
+    super::did_open_text_document(&mut state, open_params(&uri, text)).await?;
+    state.rebuild().await?;
+
+    let params = lsp::HoverParams {
+        text_document_position_params: lsp::TextDocumentPositionParams {
+            text_document: lsp::TextDocumentIdentifier { uri },
+            position,
+        },
+        work_done_progress_params: Default::default(),
+    };
+
+    let Some(hover) = super::hover(&mut state, params).await? else {
+        return Ok(None);
+    };
+
+    let lsp::HoverContents::Markup(content) = hover.contents else {
+        panic!("expected markup contents");
+    };
+
+    assert_eq!(content.kind, lsp::MarkupKind::Markdown);
+    Ok(Some(content.value))
+}
+
+#[tokio::test]
+async fn hover_native_function() -> Result<()> {
+    let value = hover("let player = game::spa$0wn(1);").await?;
+
+    assert_eq!(
+        value.as_deref(),
+        Some("```rune\nfn ::game::spawn(position: i64) -> Player\n```\n\nSpawn a player at the given position.")
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn hover_script_function() -> Result<()> {
+    let text = r#"
+/// Adds one to the given value.
+///
+/// Returns the sum.
+fn add_one(value) {
+    value + 1
+}
+
+add_$0one(1)
+"#;
+
+    let value = hover(text).await?;
+
+    assert_eq!(
+        value.as_deref(),
+        Some("```rune\nfn add_one(value)\n```\n\nAdds one to the given value.\n\nReturns the sum.")
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn hover_field() -> Result<()> {
+    let text = r#"
+let player = game::spawn(1);
+player.posi$0tion
+"#;
+
+    let value = hover(text).await?;
+
+    assert_eq!(
+        value.as_deref(),
+        Some("```rune\nPlayer.position: i64\n```\n\nThe position of the player along the track.")
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn hover_local() -> Result<()> {
+    let value = hover("let point = (1, 2);\npo$0int").await?;
+    assert_eq!(value.as_deref(), Some("```rune\nlet point: tuple\n```"));
+
+    let value = hover("let na$0me = \"rune\";").await?;
+    assert_eq!(value.as_deref(), Some("```rune\nlet name: String\n```"));
+
+    let value = hover("let count = 1;\ncou$0nt").await?;
+    assert_eq!(value.as_deref(), Some("```rune\nlet count: i64\n```"));
+
+    Ok(())
+}
+
+/// Open the given documents, rebuilding after each of the given changes and
+/// returning every diagnostics notification which was published.
+///
+/// Changes replace the whole document, so every document must end with a
+/// newline.
+async fn publish_diagnostics(
+    documents: &[(&str, &str)],
+    changes: &[(&str, &str)],
+) -> Result<Vec<lsp::PublishDiagnosticsParams>> {
+    let (writer, reader) = tokio::io::duplex(1 << 20);
+    let (_, output) = connection::pipe(tokio::io::empty(), writer);
+
+    let mut state = State::new(
+        output,
+        Arc::new(Notify::new()),
+        CancellationToken::default(),
+        Context::with_default_modules()?,
+        Options::default(),
+    );
+
+    for (uri, text) in documents {
+        let uri = lsp::Url::parse(uri)?;
+        super::did_open_text_document(&mut state, open_params(&uri, (*text).into())).await?;
+    }
+
+    state.rebuild().await?;
+
+    let mut current = documents.to_vec();
+
+    for &(uri, text) in changes {
+        let Some(document) = current.iter_mut().find(|(u, _)| *u == uri) else {
+            panic!("document `{uri}` is not open");
+        };
+
+        let lines = document.1.lines().count() as u32;
+        document.1 = text;
+
+        let change = lsp::DidChangeTextDocumentParams {
+            text_document: lsp::VersionedTextDocumentIdentifier {
+                uri: lsp::Url::parse(uri)?,
+                version: 2,
+            },
+            content_changes: vec![lsp::TextDocumentContentChangeEvent {
+                range: Some(lsp::Range::new(
+                    lsp::Position::new(0, 0),
+                    lsp::Position::new(lines, 0),
+                )),
+                range_length: None,
+                text: text.into(),
+            }],
+        };
+
+        super::did_change_text_document(&mut state, change).await?;
+        state.rebuild().await?;
+    }
+
+    drop(state);
+
+    let mut reader = BufReader::new(reader);
+    let mut published = Vec::new();
+
+    while let Some(message) = read(&mut reader).await? {
+        if message["method"] == "textDocument/publishDiagnostics" {
+            published.push(serde_json::from_value(message["params"].clone())?);
+        }
+    }
+
+    Ok(published)
+}
+
+#[tokio::test]
+async fn diagnostics_related_across_documents() -> Result<()> {
+    const MAIN: &str = "file:///project/main.rn";
+    const FOO: &str = "file:///project/foo.rn";
+
+    let documents = [
+        (MAIN, "mod foo;\n\nfoo::bar()\n"),
+        (FOO, "fn bar() {\n    1\n}\n"),
+    ];
+
+    let changes = [(MAIN, "mod foo;\n")];
+
+    let published = publish_diagnostics(&documents, &changes).await?;
+
+    let first = published
+        .iter()
+        .find(|p| p.uri.as_str() == MAIN)
+        .expect("missing diagnostics for main");
+
+    let [diagnostic] = &first.diagnostics[..] else {
+        panic!("expected one diagnostic: {:?}", first.diagnostics);
+    };
+
+    assert_eq!(diagnostic.severity, Some(lsp::DiagnosticSeverity::ERROR));
+    assert_eq!(
+        diagnostic.code,
+        Some(lsp::NumberOrString::String("not-visible".into()))
+    );
+    assert_eq!(
+        diagnostic.range,
+        lsp::Range::new(lsp::Position::new(2, 0), lsp::Position::new(2, 8))
+    );
+
+    let related = diagnostic
+        .related_information
+        .as_deref()
+        .expect("missing related information");
+
+    let [related] = related else {
+        panic!("expected one related location: {related:?}");
+    };
+
+    assert_eq!(related.location.uri.as_str(), FOO);
+    assert_eq!(related.location.range.start, lsp::Position::new(0, 0));
+    assert_eq!(related.message, "defined here");
+
+    // Fixing the error clears the diagnostics of the document.
+    let last = published
+        .iter()
+        .rev()
+        .find(|p| p.uri.as_str() == MAIN)
+        .expect("missing diagnostics for main");
+
+    assert!(last.diagnostics.is_empty(), "{:?}", last.diagnostics);
+    Ok(())
+}
+
+#[tokio::test]
+async fn diagnostics_warning_code() -> Result<()> {
+    let published = publish_diagnostics(&[("file:///main.rn", "let unused = 1;\n")], &[]).await?;
+
+    let [published] = &published[..] else {
+        panic!("expected one publish: {published:?}");
+    };
+
+    let [diagnostic] = &published.diagnostics[..] else {
+        panic!("expected one diagnostic: {:?}", published.diagnostics);
+    };
+
+    assert_eq!(diagnostic.severity, Some(lsp::DiagnosticSeverity::HINT));
+    assert_eq!(
+        diagnostic.code,
+        Some(lsp::NumberOrString::String("unused-variable".into()))
+    );
+    assert!(
+        diagnostic.message.contains("prefix it with an underscore"),
+        "{}",
+        diagnostic.message
+    );
+    Ok(())
+}
+
+/// Open the given documents and rename the symbol at the `$0` marker in one of
+/// them, returning the renamed text of every document which was edited.
+async fn rename(
+    documents: &[(&str, &str)],
+    new_name: &str,
+) -> Result<Result<Vec<(std::string::String, std::string::String)>, std::string::String>> {
+    let mut state = state(Context::with_default_modules()?);
+
+    let mut texts = Vec::new();
+    let mut at = None;
+
+    for (uri, text) in documents {
+        let uri = lsp::Url::parse(uri)?;
+
+        let text = if text.contains("$0") {
+            let (position, text) = cursor(text);
+            at = Some((uri.clone(), position));
+            text
+        } else {
+            (*text).into()
+        };
+
+        super::did_open_text_document(&mut state, open_params(&uri, text.clone())).await?;
+        texts.push((uri, text));
+    }
+
+    state.rebuild().await?;
+
+    let (uri, position) = at.expect("missing cursor");
+    let text_document_position = lsp::TextDocumentPositionParams {
+        text_document: lsp::TextDocumentIdentifier { uri },
+        position,
+    };
+
+    let prepared = super::prepare_rename(&mut state, text_document_position.clone()).await?;
+    assert!(prepared.is_some(), "nothing to rename");
+
+    let params = lsp::RenameParams {
+        text_document_position,
+        new_name: new_name.into(),
+        work_done_progress_params: Default::default(),
+    };
+
+    let edit = match super::rename(&mut state, params).await? {
+        Ok(edit) => edit.expect("missing edit"),
+        Err(message) => return Ok(Err(message.into_std())),
+    };
+
+    let mut renamed = Vec::new();
+
+    for (uri, text) in texts {
+        let Some(edits) = edit.changes.as_ref().and_then(|changes| changes.get(&uri)) else {
+            continue;
+        };
+
+        renamed.push((uri.as_str().into(), apply(&text, edits)));
+    }
+
+    renamed.sort();
+    Ok(Ok(renamed))
+}
+
+/// Apply the given edits to an ASCII text.
+fn apply(text: &str, edits: &[lsp::TextEdit]) -> std::string::String {
+    let offset = |position: lsp::Position| {
+        let line: usize = text
+            .split_inclusive('\n')
+            .take(position.line as usize)
+            .map(str::len)
+            .sum();
+
+        line + position.character as usize
+    };
+
+    let mut edits = edits.to_vec();
+    edits.sort_by_key(|edit| std::cmp::Reverse(offset(edit.range.start)));
+
+    let mut text = std::string::String::from(text);
+
+    for edit in edits {
+        text.replace_range(
+            offset(edit.range.start)..offset(edit.range.end),
+            &edit.new_text,
+        );
+    }
+
+    text
+}
+
+#[tokio::test]
+async fn rename_local_in_closure() -> Result<()> {
+    const MAIN: &str = "file:///main.rn";
+
+    let text = "let val$0ue = 1;\nlet add = |n| n + value;\nadd(value)\n";
+
+    let renamed = rename(&[(MAIN, text)], "base")
+        .await?
+        .expect("rename failed");
+
+    assert_eq!(
+        renamed,
+        [(
+            MAIN.into(),
+            "let base = 1;\nlet add = |n| n + base;\nadd(base)\n".into()
+        )]
+    );
+
+    let text = "let count = 1;\nlet val$0ue = 2;\ncount + value\n";
+    let error = rename(&[(MAIN, text)], "count").await?.unwrap_err();
+    assert!(error.contains("already in scope"), "{error}");
+
+    let error = rename(&[(MAIN, text)], "fn").await?.unwrap_err();
+    assert_eq!(error, "`fn` is not a valid identifier");
+    Ok(())
+}
+
+#[tokio::test]
+async fn rename_function_across_modules() -> Result<()> {
+    const MAIN: &str = "file:///project/main.rn";
+    const FOO: &str = "file:///project/foo.rn";
+
+    let documents = [
+        (MAIN, "mod foo;\n\nfoo::bar()\n"),
+        (
+            FOO,
+            "/// Returns one.\npub fn ba$0r() {\n    1\n}\n\npub fn baz() {\n    2\n}\n",
+        ),
+    ];
+
+    let renamed = rename(&documents, "one").await?.expect("rename failed");
+
+    assert_eq!(
+        renamed,
+        [
+            (
+                FOO.into(),
+                "/// Returns one.\npub fn one() {\n    1\n}\n\npub fn baz() {\n    2\n}\n".into()
+            ),
+            (MAIN.into(), "mod foo;\n\nfoo::one()\n".into()),
+        ]
+    );
+
+    let error = rename(&documents, "baz").await?.unwrap_err();
+    assert!(error.contains("`foo::baz`"), "{error}");
+    Ok(())
+}
+
+#[tokio::test]
+async fn rename_field() -> Result<()> {
+    const MAIN: &str = "file:///main.rn";
+
+    let text = "struct Point { x, y }\n\nlet y = 2;\nlet point = Point { x: 1, y };\npoint.x$0 + point.y\n";
+
+    let renamed = rename(&[(MAIN, text)], "left")
+        .await?
+        .expect("rename failed");
+
+    assert_eq!(
+        renamed,
+        [(
+            MAIN.into(),
+            "struct Point { left, y }\n\nlet y = 2;\nlet point = Point { left: 1, y };\npoint.left + point.y\n".into()
+        )]
+    );
+
+    let text = "struct Point { x, y }\n\nlet point = Point { x: 1, y: 2 };\npoint.x$0 + point.y\n";
+    let error = rename(&[(MAIN, text)], "y").await?.unwrap_err();
+    assert!(error.contains("`Point.y`"), "{error}");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rename_field_of_resolved_type() -> Result<()> {
+    const MAIN: &str = "file:///main.rn";
+
+    // Fields with the same name in other types are left alone.
+    let text = "struct Point { x }\nstruct Size { x }\n\nlet point = Point { x: 1 };\nlet size = Size { x: 2 };\npoint.x$0 + size.x\n";
+
+    let renamed = rename(&[(MAIN, text)], "left")
+        .await?
+        .expect("rename failed");
+
+    assert_eq!(
+        renamed,
+        [(
+            MAIN.into(),
+            "struct Point { left }\nstruct Size { x }\n\nlet point = Point { left: 1 };\nlet size = Size { x: 2 };\npoint.left + size.x\n".into()
+        )]
+    );
+
+    // Accesses through `self` and struct patterns are renamed, while fields of
+    // anonymous objects are not.
+    let text = "struct Point { x }\n\nimpl Point {\n    fn get(self) {\n        self.x\n    }\n}\n\nlet point = Point { x$0: 1 };\nlet Point { x } = point;\nlet object = #{ x: 2 };\npoint.get() + object.x + x\n";
+
+    let renamed = rename(&[(MAIN, text)], "left")
+        .await?
+        .expect("rename failed");
+
+    assert_eq!(
+        renamed,
+        [(
+            MAIN.into(),
+            "struct Point { left }\n\nimpl Point {\n    fn get(self) {\n        self.left\n    }\n}\n\nlet point = Point { left: 1 };\nlet Point { left: x } = point;\nlet object = #{ x: 2 };\npoint.get() + object.x + x\n".into()
+        )]
+    );
+
+    Ok(())
+}
+
+const SYMBOLS: &str = r#"mod shapes {
+    pub struct Point {
+        x,
+        y,
+    }
+
+    impl Point {
+        pub fn new(x, y) {
+            Point { x, y }
+        }
+    }
+
+    pub mod colors {
+        pub enum Color {
+            Red,
+            Custom(value),
+            Named { name },
+        }
+
+        pub const DEFAULT = 1;
+    }
+}
+
+fn main() {
+    missing
+}
+"#;
+
+/// Open the given documents and build them.
+async fn built(documents: &[(&str, &str)]) -> Result<State> {
+    let mut state = state(Context::with_default_modules()?);
+
+    for (uri, text) in documents {
+        let uri = lsp::Url::parse(uri)?;
+        super::did_open_text_document(&mut state, open_params(&uri, (*text).into())).await?;
+    }
+
+    state.rebuild().await?;
+    Ok(state)
+}
+
+/// Render a tree of document symbols as indented lines of their kinds and
+/// names.
+fn outline(symbols: &[lsp::DocumentSymbol], depth: usize, out: &mut Vec<std::string::String>) {
+    for symbol in symbols {
+        out.push(format!("{:depth$}{:?} {}", "", symbol.kind, symbol.name));
+
+        if let Some(children) = &symbol.children {
+            outline(children, depth + 2, out);
+        }
+    }
+}
+
+#[tokio::test]
+async fn document_symbols() -> Result<()> {
+    const MAIN: &str = "file:///main.rn";
+
+    let mut state = built(&[(MAIN, SYMBOLS)]).await?;
+
+    let params = lsp::DocumentSymbolParams {
+        text_document: lsp::TextDocumentIdentifier {
+            uri: lsp::Url::parse(MAIN)?,
+        },
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    };
+
+    let Some(lsp::DocumentSymbolResponse::Nested(symbols)) =
+        super::document_symbol(&mut state, params).await?
+    else {
+        panic!("expected nested symbols");
+    };
+
+    let mut lines = Vec::new();
+    outline(&symbols, 0, &mut lines);
+
+    // The body of `main` fails to compile, which doesn't prevent its symbol
+    // from being reported.
+    assert_eq!(
+        lines,
+        [
+            "Module shapes",
+            "  Struct Point",
+            "    Field x",
+            "    Field y",
+            "    Method new",
+            "  Module colors",
+            "    Enum Color",
+            "      EnumMember Red",
+            "      EnumMember Custom",
+            "      EnumMember Named",
+            "        Field name",
+            "    Constant DEFAULT",
+            "Function main",
+        ]
+    );
+
+    let point = &symbols[0].children.as_ref().unwrap()[0];
+
+    assert_eq!(
+        point.range,
+        lsp::Range::new(lsp::Position::new(1, 4), lsp::Position::new(4, 5))
+    );
+    assert_eq!(
+        point.selection_range,
+        lsp::Range::new(lsp::Position::new(1, 15), lsp::Position::new(1, 20))
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn workspace_symbols() -> Result<()> {
+    let documents = [
+        ("file:///main.rn", SYMBOLS),
+        ("file:///other.rn", "fn new_player() {\n}\n"),
+    ];
+
+    let mut state = built(&documents).await?;
+
+    let params = lsp::WorkspaceSymbolParams {
+        query: "new".into(),
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    };
+
+    let Some(lsp::WorkspaceSymbolResponse::Nested(symbols)) =
+        super::workspace_symbol(&mut state, params).await?
+    else {
+        panic!("expected nested symbols");
+    };
+
+    let found = symbols
+        .iter()
+        .map(|s| (s.name.as_str(), s.container_name.as_deref()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        found,
+        [("new", Some("shapes::Point")), ("new_player", None)]
+    );
+
+    let params = lsp::WorkspaceSymbolParams {
+        query: "clr".into(),
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    };
+
+    let Some(lsp::WorkspaceSymbolResponse::Nested(symbols)) =
+        super::workspace_symbol(&mut state, params).await?
+    else {
+        panic!("expected nested symbols");
+    };
+
+    let names = symbols.iter().map(|s| s.name.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, ["Color", "colors"]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn semantic_tokens() -> Result<()> {
+    const MAIN: &str = "file:///main.rn";
+
+    const SOURCE: &str = r#"// Sums.
+fn add(a, b) {
+    let c = a + b;
+    let f = |x| x * c;
+    `${f(c)} is
+${a.len()}`
+}
+"#;
+
+    let mut state = built(&[(MAIN, SOURCE)]).await?;
+
+    let params = lsp::SemanticTokensParams {
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+        text_document: lsp::TextDocumentIdentifier {
+            uri: lsp::Url::parse(MAIN)?,
+        },
+    };
+
+    let Some(lsp::SemanticTokensResult::Tokens(tokens)) =
+        super::semantic_tokens_full(&mut state, params).await?
+    else {
+        panic!("expected semantic tokens");
+    };
+
+    let data = tokens
+        .data
+        .iter()
+        .map(|t| {
+            [
+                t.delta_line,
+                t.delta_start,
+                t.length,
+                t.token_type,
+                t.token_modifiers_bitset,
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    // Each token is encoded as its line and start relative to the previous
+    // token, its length, its index in the legend and its modifiers.
+    assert_eq!(
+        data,
+        [
+            [0, 0, 8, 10, 0], // `// Sums.` (comment)
+            [1, 0, 2, 9, 0],  // `fn` (keyword)
+            [0, 3, 3, 3, 1],  // `add` (function, declaration)
+            [0, 4, 1, 5, 1],  // `a` (parameter, declaration)
+            [0, 3, 1, 5, 1],  // `b` (parameter, declaration)
+            [1, 4, 3, 9, 0],  // `let` (keyword)
+            [0, 4, 1, 6, 1],  // `c` (variable, declaration)
+            [0, 4, 1, 5, 0],  // `a` (parameter)
+            [0, 4, 1, 5, 0],  // `b` (parameter)
+            [1, 4, 3, 9, 0],  // `let` (keyword)
+            [0, 4, 1, 6, 1],  // `f` (variable, declaration)
+            [0, 5, 1, 5, 1],  // `x` (parameter, declaration)
+            [0, 3, 1, 5, 0],  // `x` (parameter)
+            [0, 4, 1, 6, 0],  // `c` (variable)
+            [1, 4, 1, 11, 0], // `` ` `` (string)
+            [0, 3, 1, 6, 0],  // `f` (variable)
+            [0, 2, 1, 6, 0],  // `c` (variable)
+            [0, 3, 3, 11, 0], // ` is` (string)
+            [1, 2, 1, 5, 0],  // `a` (parameter)
+            [0, 2, 3, 4, 0],  // `len` (method)
+            [0, 6, 1, 11, 0], // `` ` `` (string)
+        ]
+    );
+
+    let params = lsp::SemanticTokensRangeParams {
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+        text_document: lsp::TextDocumentIdentifier {
+            uri: lsp::Url::parse(MAIN)?,
+        },
+        range: lsp::Range::new(lsp::Position::new(5, 0), lsp::Position::new(6, 0)),
+    };
+
+    let Some(lsp::SemanticTokensRangeResult::Tokens(tokens)) =
+        super::semantic_tokens_range(&mut state, params).await?
+    else {
+        panic!("expected semantic tokens");
+    };
+
+    let data = tokens
+        .data
+        .iter()
+        .map(|t| [t.delta_line, t.delta_start, t.length, t.token_type])
+        .collect::<Vec<_>>();
+
+    // The first token in a range is relative to the start of the document.
+    assert_eq!(data, [[5, 2, 1, 5], [0, 2, 3, 4], [0, 6, 1, 11]]);
+    Ok(())
+}
+
+const HINTS: &str = r#"struct Point { x, y }
+
+fn scale(point, factor) {
+    Point { x: point.x * factor, y: point.y * factor }
+}
+
+pub fn main() {
+    let x = 1;
+    let y = 2;
+    let point = Point { x, y };
+    let scaled = scale(point, 2);
+    let f = scale;
+    f(point, 3);
+    let café = [scale(scaled, x)]; let n = "n";
+}
+"#;
+
+/// Collect the inlay hints of the given document as their positions and
+/// labels.
+async fn inlay_hints(state: &mut State, uri: &str) -> Result<Vec<(u32, u32, std::string::String)>> {
+    let params = lsp::InlayHintParams {
+        work_done_progress_params: Default::default(),
+        text_document: lsp::TextDocumentIdentifier {
+            uri: lsp::Url::parse(uri)?,
+        },
+        range: lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(100, 0)),
+    };
+
+    let hints = super::inlay_hint(state, params).await?.unwrap_or_default();
+
+    Ok(hints
+        .into_iter()
+        .map(|hint| {
+            let lsp::InlayHintLabel::String(label) = hint.label else {
+                panic!("expected string label");
+            };
+
+            (hint.position.line, hint.position.character, label)
+        })
+        .collect())
+}
+
+#[tokio::test]
+async fn inlay_hints_parameters_and_bindings() -> Result<()> {
+    const MAIN: &str = "file:///main.rn";
+
+    let mut state = built(&[(MAIN, HINTS)]).await?;
+
+    let hints = inlay_hints(&mut state, MAIN).await?;
+
+    // `point` is passed to a parameter with the same name, and `f` isn't
+    // known to be any particular function so neither get a hint. Positions
+    // are in UTF-16 code units, which `é` is a single one of.
+    assert_eq!(
+        hints,
+        [
+            (7, 9, ": i64".into()),
+            (8, 9, ": i64".into()),
+            (9, 13, ": Point".into()),
+            (10, 30, "factor:".into()),
+            (13, 12, ": Vec".into()),
+            (13, 22, "point:".into()),
+            (13, 30, "factor:".into()),
+            (13, 40, ": String".into()),
+        ]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn inlay_hints_disabled() -> Result<()> {
+    const MAIN: &str = "file:///main.rn";
+
+    let mut state = built(&[(MAIN, HINTS)]).await?;
+
+    let params = lsp::InitializeParams {
+        initialization_options: Some(serde_json::json!({ "parameterHints": false })),
+        ..Default::default()
+    };
+
+    super::initialize(&mut state, params).await?;
+
+    let hints = inlay_hints(&mut state, MAIN).await?;
+
+    assert_eq!(
+        hints,
+        [
+            (7, 9, ": i64".into()),
+            (8, 9, ": i64".into()),
+            (9, 13, ": Point".into()),
+            (13, 12, ": Vec".into()),
+            (13, 40, ": String".into()),
+        ]
+    );
+
+    Ok(())
+}
+
+/// Open and build the given documents, returning the position of the `$0`
+/// marker in one of them.
+async fn built_at(documents: &[(&str, &str)]) -> Result<(State, lsp::TextDocumentPositionParams)> {
+    built_at_with(Context::with_default_modules()?, documents).await
+}
+
+/// Open and build the given documents with the given context, returning the
+/// position of the `$0` marker in one of them.
+async fn built_at_with(
+    context: Context,
+    documents: &[(&str, &str)],
+) -> Result<(State, lsp::TextDocumentPositionParams)> {
+    let mut state = state(context);
+    let mut at = None;
+
+    for (uri, text) in documents {
+        let uri = lsp::Url::parse(uri)?;
+
+        let text = if text.contains("$0") {
+            let (position, text) = cursor(text);
+            at = Some((uri.clone(), position));
+            text
+        } else {
+            (*text).into()
+        };
+
+        super::did_open_text_document(&mut state, open_params(&uri, text)).await?;
+    }
+
+    state.rebuild().await?;
+
+    let (uri, position) = at.expect("missing cursor");
+
+    let params = lsp::TextDocumentPositionParams {
+        text_document: lsp::TextDocumentIdentifier { uri },
+        position,
+    };
+
+    Ok((state, params))
+}
+
+/// Render locations as the file name, line and character they start at.
+fn locations(locations: &[lsp::Location]) -> Vec<(&str, u32, u32)> {
+    locations
+        .iter()
+        .map(|location| {
+            let name = location.uri.path().rsplit('/').next().unwrap_or_default();
+            (
+                name,
+                location.range.start.line,
+                location.range.start.character,
+            )
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn references_across_files() -> Result<()> {
+    let documents = [
+        (
+            "file:///project/main.rn",
+            "mod math;\nmod report;\n\npub fn main() {\n    let twice = |n| math::add(n, n);\n    math::add(1, twice(2))\n}\n",
+        ),
+        (
+            "file:///project/math.rn",
+            "pub fn ad$0d(a, b) {\n    a + b\n}\n\npub fn sum(values) {\n    values.iter().fold(0, add)\n}\n",
+        ),
+        (
+            "file:///project/report.rn",
+            "pub fn report() {\n    println!(\"{}\", crate::math::add(1, 2));\n}\n",
+        ),
+    ];
+
+    let (mut state, text_document_position) = built_at(&documents).await?;
+
+    let params = lsp::ReferenceParams {
+        text_document_position,
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+        context: lsp::ReferenceContext {
+            include_declaration: false,
+        },
+    };
+
+    let found = super::references(&mut state, params.clone())
+        .await?
+        .expect("missing references");
+
+    // Uses inside of closures and macros are included.
+    assert_eq!(
+        locations(&found),
+        [
+            ("main.rn", 4, 26),
+            ("main.rn", 5, 10),
+            ("math.rn", 5, 26),
+            ("report.rn", 1, 32),
+        ]
+    );
+
+    let params = lsp::ReferenceParams {
+        context: lsp::ReferenceContext {
+            include_declaration: true,
+        },
+        ..params
+    };
+
+    let found = super::references(&mut state, params)
+        .await?
+        .expect("missing references");
+
+    assert_eq!(found.len(), 5);
+    assert_eq!(locations(&found)[2], ("math.rn", 0, 7));
+    Ok(())
+}
+
+#[tokio::test]
+async fn implementations_of_instance_function() -> Result<()> {
+    let documents = [
+        (
+            "file:///project/main.rn",
+            "mod circle;\nmod square;\n\npub fn main(shape) {\n    shape.ar$0ea()\n}\n",
+        ),
+        (
+            "file:///project/circle.rn",
+            "pub struct Circle { radius }\n\nimpl Circle {\n    pub fn new(radius) {\n        Circle { radius }\n    }\n\n    pub fn area(self) {\n        3 * self.radius * self.radius\n    }\n}\n",
+        ),
+        (
+            "file:///project/square.rn",
+            "pub struct Square { side }\n\nimpl Square {\n    pub fn area(self) {\n        self.side * self.side\n    }\n}\n\npub fn area(side) {\n    side * side\n}\n",
+        ),
+    ];
+
+    let (mut state, text_document_position_params) = built_at(&documents).await?;
+
+    let params = lsp::GotoDefinitionParams {
+        text_document_position_params,
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    };
+
+    let Some(lsp::GotoDefinitionResponse::Array(found)) =
+        super::goto_implementation(&mut state, params).await?
+    else {
+        panic!("expected implementations");
+    };
+
+    // The free function named `area` is not an implementation.
+    assert_eq!(
+        locations(&found),
+        [("circle.rn", 7, 11), ("square.rn", 3, 11)]
+    );
+
+    Ok(())
+}
+
+/// A triangle provided by the context.
+#[derive(Any)]
+#[rune(item = ::shapes)]
+struct Triangle;
+
+impl Triangle {
+    /// The area of the triangle.
+    #[rune::function]
+    fn area(&self) -> i64 {
+        1
+    }
+}
+
+/// A hexagon provided by the context.
+#[derive(Any)]
+#[rune(item = ::shapes)]
+struct Hexagon;
+
+impl Hexagon {
+    /// The area of the hexagon.
+    #[rune::function]
+    fn area(&self) -> i64 {
+        6
+    }
+}
+
+#[tokio::test]
+async fn implementations_of_native_instance_function() -> Result<()> {
+    let mut module = Module::with_crate("shapes")?;
+    module.ty::<Triangle>()?;
+    module.function_meta(Triangle::area)?;
+    module.ty::<Hexagon>()?;
+    module.function_meta(Hexagon::area)?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(module)?;
+
+    let documents = [
+        (
+            "file:///project/main.rn",
+            "mod square;\n\npub fn main(shape) {\n    shape.ar$0ea()\n}\n",
+        ),
+        (
+            "file:///project/square.rn",
+            "pub struct Square { side }\n\nimpl Square {\n    pub fn area(self) {\n        self.side * self.side\n    }\n}\n",
+        ),
+    ];
+
+    let (mut state, text_document_position_params) = built_at_with(context, &documents).await?;
+
+    let params = lsp::GotoDefinitionParams {
+        text_document_position_params,
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    };
+
+    let Some(lsp::GotoDefinitionResponse::Array(found)) =
+        super::goto_implementation(&mut state, params).await?
+    else {
+        panic!("expected implementations");
+    };
+
+    let uris = found
+        .iter()
+        .map(|location| location.uri.as_str())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        uris,
+        [
+            "file:///project/square.rn",
+            "rune:shapes::Hexagon::area",
+            "rune:shapes::Triangle::area",
+        ]
+    );
+
+    Ok(())
+}
@@ -8,6 +8,7 @@ use crate::alloc::fmt::TryWrite;
 use crate::alloc::prelude::*;
 use crate::alloc::String;
 use crate::support::Result;
+use crate::Item;
 
 use percent_encoding::{percent_encode, AsciiSet, CONTROLS};
 use url::Url;
@@ -18,6 +19,21 @@ const FRAGMENT: &AsciiSet = &CONTROLS.add(b' ').add(b'"').add(b'<').add(b'>').ad
 const PATH: &AsciiSet = &FRAGMENT.add(b'#').add(b'?').add(b'{').add(b'}');
 const PATH_SEGMENT: &AsciiSet = &PATH.add(b'/').add(b'%');
 
+/// Construct a URL naming an item provided by the context, like
+/// `rune:std::string::String::len`.
+///
+/// Such items have no source, so this is used to identify them in locations.
+pub(super) fn from_item(item: &Item) -> Result<Url> {
+    let mut buf = "rune:".try_to_owned()?;
+    let item = item.try_to_string()?;
+    write!(
+        buf,
+        "{}",
+        percent_encode(item.trim_start_matches("::").as_bytes(), PATH)
+    )?;
+    Ok(Url::parse(&buf)?)
+}
+
 /// Convert a file path into a URL.
 pub(super) fn from_file_path<P>(path: P) -> Result<Url>
 where
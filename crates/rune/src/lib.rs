@@ -255,8 +255,8 @@ pub mod query;
 pub mod runtime;
 #[doc(inline)]
 pub use self::runtime::{
-    from_const_value, from_value, to_const_value, to_value, FromValue, Mut, Ref, ToConstValue,
-    ToValue, TypeHash, Unit, Value, Vm,
+    from_const_value, from_value, from_value_to_deserialize, to_const_value, to_value,
+    to_value_from_serialize, FromValue, Mut, Ref, ToConstValue, ToValue, TypeHash, Unit, Value, Vm,
 };
 
 mod shared;
@@ -295,6 +295,8 @@ pub mod workspace;
 ///   instance function that can be defined externally.
 /// * Instance functions can be made a protocol function
 ///   `#[rune::function(protocol = DISPLAY_FMT)]`.
+/// * Pure functions can be made callable in constant contexts using
+///   `#[rune::function(const)]`, see [`ModuleFunctionBuilder::const_fn`].
 ///
 /// # Instance and associated functions
 ///
@@ -615,6 +617,7 @@ pub mod workspace;
 ///
 /// [`VmResult`]: crate::runtime::VmResult
 /// [`vm_try!`]: crate::vm_try!
+/// [`ModuleFunctionBuilder::const_fn`]: crate::module::ModuleFunctionBuilder::const_fn
 pub use rune_macros::function;
 
 /// Macro used to annotate native functions which can be loaded as macros in
@@ -656,7 +659,7 @@ pub mod languageserver;
 
 #[cfg(feature = "doc")]
 #[cfg_attr(rune_docsrs, doc(cfg(feature = "doc")))]
-pub(crate) mod doc;
+pub mod doc;
 
 /// Privately exported details.
 #[doc(hidden)]
@@ -0,0 +1,906 @@
+//! Declarative macros which are defined in Rune source.
+//!
+//! A declarative macro consists of a pattern which the input of a macro call
+//! is matched against, and a body which is transcribed with the captured
+//! fragments substituted in.
+
+use core::mem::discriminant;
+
+use crate::alloc::prelude::*;
+use crate::alloc::{HashMap, String, Vec};
+use crate::ast::{self, Kind, Span, Spanned};
+use crate::compile::{self, ErrorKind};
+use crate::macros::{MacroContext, TokenStream};
+use crate::parse::{IntoExpectation, Parse, Parser, Resolve, ResolveContext};
+
+/// A compiled declarative macro.
+#[derive(Debug)]
+pub(crate) struct DeclarativeMacro {
+    /// The pattern of the macro.
+    matchers: Vec<Matcher>,
+    /// The body of the macro.
+    transcribers: Vec<Transcriber>,
+    /// Names of bindings introduced by the body, which are renamed for each
+    /// expansion.
+    hygienic: Vec<String>,
+}
+
+/// The kind of a captured fragment.
+#[derive(Debug, Clone, Copy)]
+enum FragmentKind {
+    /// An expression, `$x:expr`.
+    Expr,
+    /// An identifier, `$x:ident`.
+    Ident,
+    /// A literal, `$x:lit` or `$x:literal`.
+    Lit,
+    /// A single token tree, `$x:tt`.
+    Tt,
+}
+
+impl FragmentKind {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "expr" => Some(Self::Expr),
+            "ident" => Some(Self::Ident),
+            "lit" | "literal" => Some(Self::Lit),
+            "tt" => Some(Self::Tt),
+            _ => None,
+        }
+    }
+}
+
+/// A repetition operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RepeatKind {
+    /// `*`, zero or more repetitions.
+    ZeroOrMore,
+    /// `+`, one or more repetitions.
+    OneOrMore,
+}
+
+/// An element of the pattern of a macro.
+#[derive(Debug)]
+enum Matcher {
+    /// A token which must be present verbatim.
+    Token(ast::Token),
+    /// A captured fragment.
+    Fragment {
+        span: Span,
+        name: String,
+        kind: FragmentKind,
+    },
+    /// A repeated sequence of matchers.
+    Repeat {
+        span: Span,
+        matchers: Vec<Matcher>,
+        separator: Option<ast::Token>,
+        kind: RepeatKind,
+    },
+}
+
+/// An element of the body of a macro.
+#[derive(Debug)]
+enum Transcriber {
+    /// A token which is emitted verbatim.
+    Token(ast::Token),
+    /// A variable to substitute, `$x`.
+    Var { span: Span, name: String },
+    /// A repeated sequence of transcribers.
+    Repeat {
+        span: Span,
+        transcribers: Vec<Transcriber>,
+        separator: Option<ast::Token>,
+    },
+}
+
+/// A captured binding.
+#[derive(Debug)]
+enum Binding {
+    /// A captured fragment, and whether it needs to be wrapped in an invisible
+    /// group to preserve its precedence once substituted.
+    Fragment(Vec<ast::Token>, bool),
+    /// A repeated capture.
+    Repeat(Vec<Binding>),
+}
+
+type Bindings = HashMap<String, Binding>;
+
+impl DeclarativeMacro {
+    /// Compile the given macro definition.
+    pub(crate) fn compile(cx: ResolveContext<'_>, ast: &ast::ItemMacro) -> compile::Result<Self> {
+        let matchers = matchers(cx, ast.pattern.as_slice())?;
+
+        {
+            let mut names = Vec::new();
+            matcher_names(&matchers, &mut names)?;
+
+            for (n, (span, name)) in names.iter().enumerate() {
+                if names[..n].iter().any(|(_, other)| other == name) {
+                    return Err(compile::Error::msg(
+                        *span,
+                        try_format!("duplicate macro variable `${name}`"),
+                    ));
+                }
+            }
+        }
+
+        let transcribers = transcribers(cx, ast.body.as_slice())?;
+        let hygienic = hygienic(cx, ast.body.as_slice())?;
+
+        Ok(Self {
+            matchers,
+            transcribers,
+            hygienic,
+        })
+    }
+
+    /// Expand the macro with the given input.
+    pub(crate) fn expand(
+        &self,
+        cx: &mut MacroContext<'_, '_, '_>,
+        input: &TokenStream,
+    ) -> compile::Result<TokenStream> {
+        let tokens = input.as_slice();
+        let mut pos = 0;
+        let mut bindings = Bindings::new();
+
+        match_seq(cx, &self.matchers, tokens, &mut pos, &mut bindings)?;
+
+        if let Some(token) = tokens.get(pos) {
+            return Err(compile::Error::msg(
+                token,
+                "no macro pattern expects this token",
+            ));
+        }
+
+        let mut renames = HashMap::new();
+
+        for name in &self.hygienic {
+            let ident = cx.unique_ident(name)?;
+            renames.try_insert(name.as_str(), ident.source)?;
+        }
+
+        let mut scope = HashMap::new();
+
+        for (name, binding) in &bindings {
+            scope.try_insert(name.as_str(), binding)?;
+        }
+
+        let mut out = Vec::new();
+
+        transcribe(
+            cx,
+            &self.transcribers,
+            &scope,
+            &renames,
+            &mut Vec::new(),
+            &mut out,
+        )?;
+
+        Ok(TokenStream::from(out))
+    }
+}
+
+/// Parse the output of a declarative macro.
+///
+/// The body of a declarative macro can consist of multiple statements, so if
+/// the output can't be parsed as-is it is parsed as a block instead.
+pub(crate) fn parse_output<T>(stream: &TokenStream, span: Span) -> compile::Result<T>
+where
+    T: Parse,
+{
+    let error = match Parser::from_token_stream(stream, span).parse_all::<T>() {
+        Ok(output) => return Ok(output),
+        Err(error) => error,
+    };
+
+    let mut block = Vec::new();
+    block.try_push(ast::Token {
+        span: span.head(),
+        kind: K!['{'],
+    })?;
+    block.try_extend(stream.as_slice().iter().copied())?;
+    block.try_push(ast::Token {
+        span: span.tail(),
+        kind: K!['}'],
+    })?;
+
+    let block = TokenStream::from(block);
+
+    match Parser::from_token_stream(&block, span).parse_all::<T>() {
+        Ok(output) => Ok(output),
+        Err(..) => Err(error),
+    }
+}
+
+/// Resolve the identifier in the given token.
+fn ident<'a>(cx: ResolveContext<'a>, token: &ast::Token) -> compile::Result<Option<&'a str>> {
+    let Kind::Ident(source) = token.kind else {
+        return Ok(None);
+    };
+
+    let ident = ast::Ident {
+        span: token.span,
+        source,
+    };
+
+    Ok(Some(ident.resolve(cx)?))
+}
+
+/// Find the index of the close delimiter matching the open delimiter at
+/// `start`.
+fn find_close(tokens: &[ast::Token], start: usize) -> compile::Result<usize> {
+    let mut level = 0usize;
+
+    for (n, token) in tokens.iter().enumerate().skip(start) {
+        match token.kind {
+            Kind::Open(..) => level += 1,
+            Kind::Close(..) => {
+                level -= 1;
+
+                if level == 0 {
+                    return Ok(n);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Err(compile::Error::new(tokens[start], ErrorKind::UnexpectedEof))
+}
+
+/// Parse the repetition operator of `$(...)` group, with an optional
+/// separator.
+fn repetition(
+    tokens: &[ast::Token],
+    pos: &mut usize,
+    span: Span,
+) -> compile::Result<(Option<ast::Token>, RepeatKind)> {
+    fn kind(token: Option<&ast::Token>) -> Option<RepeatKind> {
+        match token?.kind {
+            K![*] => Some(RepeatKind::ZeroOrMore),
+            K![+] => Some(RepeatKind::OneOrMore),
+            _ => None,
+        }
+    }
+
+    if let Some(kind) = kind(tokens.get(*pos)) {
+        *pos += 1;
+        return Ok((None, kind));
+    }
+
+    if let (Some(separator), Some(kind)) = (tokens.get(*pos), kind(tokens.get(*pos + 1))) {
+        *pos += 2;
+        return Ok((Some(*separator), kind));
+    }
+
+    Err(compile::Error::msg(
+        span,
+        "expected repetition operator `*` or `+`",
+    ))
+}
+
+/// Parse the pattern of a macro.
+fn matchers(cx: ResolveContext<'_>, tokens: &[ast::Token]) -> compile::Result<Vec<Matcher>> {
+    let mut output = Vec::new();
+    let mut pos = 0;
+
+    while let Some(token) = tokens.get(pos) {
+        pos += 1;
+
+        if token.kind != K![$] {
+            output.try_push(Matcher::Token(*token))?;
+            continue;
+        }
+
+        let Some(next) = tokens.get(pos) else {
+            return Err(compile::Error::msg(
+                token,
+                "expected macro variable or repetition after `$`",
+            ));
+        };
+
+        if let K!['('] = next.kind {
+            let close = find_close(tokens, pos)?;
+            let matchers = matchers(cx, &tokens[pos + 1..close])?;
+            pos = close + 1;
+
+            let span = token.span.join(tokens[close].span);
+            let (separator, kind) = repetition(tokens, &mut pos, span)?;
+
+            output.try_push(Matcher::Repeat {
+                span,
+                matchers,
+                separator,
+                kind,
+            })?;
+
+            continue;
+        }
+
+        let Some(name) = ident(cx, next)? else {
+            return Err(compile::Error::msg(
+                next,
+                "expected macro variable or repetition after `$`",
+            ));
+        };
+
+        let (Some(K![:]), Some(kind)) = (tokens.get(pos + 1).map(|t| t.kind), tokens.get(pos + 2))
+        else {
+            return Err(compile::Error::msg(
+                token.span.join(next.span),
+                "expected fragment specifier, like `$name:expr`",
+            ));
+        };
+
+        let span = token.span.join(kind.span);
+
+        let Some(kind) = ident(cx, kind)?.and_then(FragmentKind::from_name) else {
+            return Err(compile::Error::msg(
+                kind,
+                "expected fragment specifier `expr`, `ident`, `lit`, `literal`, or `tt`",
+            ));
+        };
+
+        pos += 3;
+
+        output.try_push(Matcher::Fragment {
+            span,
+            name: name.try_to_owned()?,
+            kind,
+        })?;
+    }
+
+    Ok(output)
+}
+
+/// Collect the names of all variables declared in a pattern.
+fn matcher_names<'a>(
+    matchers: &'a [Matcher],
+    output: &mut Vec<(Span, &'a str)>,
+) -> compile::Result<()> {
+    for matcher in matchers {
+        match matcher {
+            Matcher::Token(..) => {}
+            Matcher::Fragment { span, name, .. } => {
+                output.try_push((*span, name.as_str()))?;
+            }
+            Matcher::Repeat { matchers, .. } => {
+                matcher_names(matchers, output)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse the body of a macro.
+fn transcribers(
+    cx: ResolveContext<'_>,
+    tokens: &[ast::Token],
+) -> compile::Result<Vec<Transcriber>> {
+    let mut output = Vec::new();
+    let mut pos = 0;
+
+    while let Some(token) = tokens.get(pos) {
+        pos += 1;
+
+        if token.kind != K![$] {
+            output.try_push(Transcriber::Token(*token))?;
+            continue;
+        }
+
+        let Some(next) = tokens.get(pos) else {
+            return Err(compile::Error::msg(
+                token,
+                "expected macro variable or repetition after `$`",
+            ));
+        };
+
+        if let K!['('] = next.kind {
+            let close = find_close(tokens, pos)?;
+            let transcribers = transcribers(cx, &tokens[pos + 1..close])?;
+            pos = close + 1;
+
+            let span = token.span.join(tokens[close].span);
+            let (separator, _) = repetition(tokens, &mut pos, span)?;
+
+            output.try_push(Transcriber::Repeat {
+                span,
+                transcribers,
+                separator,
+            })?;
+
+            continue;
+        }
+
+        let Some(name) = ident(cx, next)? else {
+            return Err(compile::Error::msg(
+                next,
+                "expected macro variable or repetition after `$`",
+            ));
+        };
+
+        pos += 1;
+
+        output.try_push(Transcriber::Var {
+            span: token.span.join(next.span),
+            name: name.try_to_owned()?,
+        })?;
+    }
+
+    Ok(output)
+}
+
+/// Collect the names of bindings introduced by `let` and `for` in the body of
+/// a macro.
+///
+/// These are renamed in each expansion, so that they don't interfere with
+/// variables provided through the input of the macro.
+fn hygienic(cx: ResolveContext<'_>, tokens: &[ast::Token]) -> compile::Result<Vec<String>> {
+    let mut output = Vec::<String>::new();
+
+    for (n, token) in tokens.iter().enumerate() {
+        let end = match token.kind {
+            K![let] => K![=],
+            K![for] => K![in],
+            _ => continue,
+        };
+
+        let mut level = 0usize;
+
+        for (m, token) in tokens.iter().enumerate().skip(n + 1) {
+            match token.kind {
+                Kind::Open(..) => level += 1,
+                Kind::Close(..) if level == 0 => break,
+                Kind::Close(..) => level -= 1,
+                kind if kind == end && level == 0 => break,
+                K![;] => break,
+                _ => {}
+            }
+
+            if is_path_like(tokens.get(m.wrapping_sub(1)), tokens.get(m + 1)) {
+                continue;
+            }
+
+            let Some(name) = ident(cx, token)? else {
+                continue;
+            };
+
+            if !name.starts_with(|c: char| c.is_lowercase() || c == '_') {
+                continue;
+            }
+
+            if output.iter().all(|n| n != name) {
+                output.try_push(name.try_to_owned()?)?;
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// Test if an identifier surrounded by the given tokens is part of a path,
+/// field access, or a macro variable rather than a binding.
+fn is_path_like(prev: Option<&ast::Token>, next: Option<&ast::Token>) -> bool {
+    let prev = matches!(prev.map(|t| t.kind), Some(K![$] | K![.] | K![::]));
+    let next = matches!(
+        next.map(|t| t.kind),
+        Some(K![::] | K![:] | K!['('] | K!['{'])
+    );
+    prev || next
+}
+
+/// Test if two tokens are the same, ignoring where they come from.
+fn token_eq(
+    cx: &mut MacroContext<'_, '_, '_>,
+    a: &ast::Token,
+    b: &ast::Token,
+) -> compile::Result<bool> {
+    if discriminant(&a.kind) != discriminant(&b.kind) {
+        return Ok(false);
+    }
+
+    if a.kind == b.kind {
+        return Ok(true);
+    }
+
+    let a = cx.stringify(a)?.try_to_string()?;
+    let b = cx.stringify(b)?.try_to_string()?;
+    Ok(a == b)
+}
+
+/// Match a sequence of matchers against the input.
+fn match_seq(
+    cx: &mut MacroContext<'_, '_, '_>,
+    matchers: &[Matcher],
+    tokens: &[ast::Token],
+    pos: &mut usize,
+    bindings: &mut Bindings,
+) -> compile::Result<()> {
+    for matcher in matchers {
+        match matcher {
+            Matcher::Token(expected) => {
+                let Some(actual) = tokens.get(*pos) else {
+                    return Err(compile::Error::new(
+                        cx.input_span().tail(),
+                        ErrorKind::ExpectedSyntax {
+                            expected: expected.kind.into_expectation(),
+                            actual: Kind::Eof.into_expectation(),
+                        },
+                    ));
+                };
+
+                if !token_eq(cx, expected, actual)? {
+                    return Err(compile::Error::new(
+                        actual,
+                        ErrorKind::ExpectedSyntax {
+                            expected: expected.kind.into_expectation(),
+                            actual: actual.kind.into_expectation(),
+                        },
+                    ));
+                }
+
+                *pos += 1;
+            }
+            Matcher::Fragment { name, kind, .. } => {
+                let binding = match_fragment(cx, *kind, tokens, pos)?;
+                bindings.try_insert(name.try_clone()?, binding)?;
+            }
+            Matcher::Repeat {
+                span,
+                matchers,
+                separator,
+                kind,
+            } => {
+                let mut iterations = Vec::new();
+
+                loop {
+                    let start = *pos;
+
+                    if !iterations.is_empty() {
+                        if let Some(separator) = separator {
+                            match tokens.get(*pos) {
+                                Some(token) if token_eq(cx, separator, token)? => *pos += 1,
+                                _ => break,
+                            }
+                        }
+                    }
+
+                    if *pos == tokens.len() && !matchers.is_empty() {
+                        *pos = start;
+                        break;
+                    }
+
+                    let mut iteration = Bindings::new();
+
+                    if match_seq(cx, matchers, tokens, pos, &mut iteration).is_err() {
+                        *pos = start;
+                        break;
+                    }
+
+                    iterations.try_push(iteration)?;
+
+                    // Guard against patterns which don't consume anything.
+                    if *pos == start {
+                        break;
+                    }
+                }
+
+                if *kind == RepeatKind::OneOrMore && iterations.is_empty() {
+                    let span = tokens.get(*pos).map(Spanned::span).unwrap_or(*span);
+
+                    return Err(compile::Error::msg(
+                        span,
+                        "expected at least one repetition",
+                    ));
+                }
+
+                let mut names = Vec::new();
+                matcher_names(matchers, &mut names)?;
+
+                for (_, name) in names {
+                    let mut values = Vec::new();
+
+                    for iteration in &mut iterations {
+                        if let Some(value) = iteration.remove(name) {
+                            values.try_push(value)?;
+                        }
+                    }
+
+                    bindings.try_insert(name.try_to_owned()?, Binding::Repeat(values))?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Match a single fragment against the input.
+fn match_fragment(
+    cx: &mut MacroContext<'_, '_, '_>,
+    kind: FragmentKind,
+    tokens: &[ast::Token],
+    pos: &mut usize,
+) -> compile::Result<Binding> {
+    let Some(first) = tokens.get(*pos) else {
+        return Err(compile::Error::new(
+            cx.input_span().tail(),
+            ErrorKind::UnexpectedEof,
+        ));
+    };
+
+    let (len, wrap) = match kind {
+        FragmentKind::Ident => {
+            if !matches!(first.kind, Kind::Ident(..)) {
+                return Err(compile::Error::expected(*first, "identifier"));
+            }
+
+            (1, false)
+        }
+        FragmentKind::Lit => match (first.kind, tokens.get(*pos + 1).map(|t| t.kind)) {
+            (K![-], Some(Kind::Number(..))) => (2, false),
+            (
+                Kind::Number(..)
+                | Kind::Str(..)
+                | Kind::Char(..)
+                | Kind::Byte(..)
+                | Kind::ByteStr(..)
+                | K![true]
+                | K![false],
+                _,
+            ) => (1, false),
+            _ => return Err(compile::Error::expected(*first, "literal")),
+        },
+        FragmentKind::Tt => match first.kind {
+            Kind::Open(..) => (find_close(tokens, *pos)? - *pos + 1, false),
+            Kind::Close(..) => return Err(compile::Error::expected(*first, "token tree")),
+            _ => (1, false),
+        },
+        FragmentKind::Expr => {
+            let mut rest = Vec::new();
+            rest.try_extend(tokens[*pos..].iter().copied())?;
+            let rest = TokenStream::from(rest);
+
+            let mut p = Parser::from_token_stream(&rest, cx.input_span());
+            let expr = p.parse::<ast::Expr>()?;
+
+            let mut remaining = 0;
+
+            while !p.is_eof()? {
+                p.next()?;
+                remaining += 1;
+            }
+
+            let wrap = !matches!(
+                expr,
+                ast::Expr::Path(..)
+                    | ast::Expr::Lit(..)
+                    | ast::Expr::Call(..)
+                    | ast::Expr::FieldAccess(..)
+                    | ast::Expr::Index(..)
+                    | ast::Expr::Group(..)
+                    | ast::Expr::Empty(..)
+                    | ast::Expr::Tuple(..)
+                    | ast::Expr::Vec(..)
+                    | ast::Expr::Object(..)
+                    | ast::Expr::MacroCall(..)
+            );
+
+            (rest.as_slice().len() - remaining, wrap)
+        }
+    };
+
+    let mut captured = Vec::new();
+    captured.try_extend(tokens[*pos..*pos + len].iter().copied())?;
+    *pos += len;
+    Ok(Binding::Fragment(captured, wrap))
+}
+
+/// Collect the names of all variables used in a sequence of transcribers.
+fn transcriber_vars<'a>(
+    transcribers: &'a [Transcriber],
+    output: &mut Vec<&'a str>,
+) -> compile::Result<()> {
+    for transcriber in transcribers {
+        match transcriber {
+            Transcriber::Token(..) => {}
+            Transcriber::Var { name, .. } => {
+                output.try_push(name.as_str())?;
+            }
+            Transcriber::Repeat { transcribers, .. } => {
+                transcriber_vars(transcribers, output)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Test if an open brace following the given token starts a struct or object
+/// literal, or the corresponding pattern.
+fn is_fields_open(
+    cx: &mut MacroContext<'_, '_, '_>,
+    prev: Option<&ast::Token>,
+) -> compile::Result<bool> {
+    let Some(prev) = prev else {
+        return Ok(false);
+    };
+
+    if prev.kind == K![#] {
+        return Ok(true);
+    }
+
+    let Some(name) = ident(resolve_context!(cx.idx.q), prev)? else {
+        return Ok(false);
+    };
+
+    Ok(name.starts_with(char::is_uppercase))
+}
+
+/// Transcribe the body of a macro.
+///
+/// The `delimiters` stack records for each open delimiter in the output
+/// whether it contains the fields of a struct or object.
+fn transcribe(
+    cx: &mut MacroContext<'_, '_, '_>,
+    transcribers: &[Transcriber],
+    scope: &HashMap<&str, &Binding>,
+    renames: &HashMap<&str, ast::LitSource>,
+    delimiters: &mut Vec<bool>,
+    out: &mut Vec<ast::Token>,
+) -> compile::Result<()> {
+    let mut prev = None;
+
+    for (n, transcriber) in transcribers.iter().enumerate() {
+        match transcriber {
+            Transcriber::Token(token) => {
+                let mut token = *token;
+
+                let next = match transcribers.get(n + 1) {
+                    Some(Transcriber::Token(next)) => Some(next.kind),
+                    _ => None,
+                };
+
+                match token.kind {
+                    K!['{'] => {
+                        let fields = is_fields_open(cx, out.last())?;
+                        delimiters.try_push(fields)?;
+                    }
+                    Kind::Open(..) => {
+                        delimiters.try_push(false)?;
+                    }
+                    Kind::Close(..) => {
+                        delimiters.pop();
+                    }
+                    _ => {}
+                }
+
+                // NB: field names, like in `#{ name: value }`, are not
+                // bindings.
+                if !matches!(prev, Some(K![.] | K![::])) && next != Some(K![:]) {
+                    if let Some(name) = ident(resolve_context!(cx.idx.q), &token)? {
+                        if let Some(source) = renames.get(name) {
+                            // A shorthand field like `#{ name }` is expanded
+                            // into `#{ name: name$0 }` so that only the
+                            // binding is renamed.
+                            let shorthand = delimiters.last() == Some(&true)
+                                && matches!(out.last().map(|t| t.kind), Some(K!['{'] | K![,]))
+                                && matches!(next, Some(K![,] | K!['}']));
+
+                            if shorthand {
+                                out.try_push(token)?;
+                                out.try_push(ast::Token {
+                                    span: token.span,
+                                    kind: K![:],
+                                })?;
+                            }
+
+                            token.kind = Kind::Ident(*source);
+                        }
+                    }
+                }
+
+                prev = Some(token.kind);
+                out.try_push(token)?;
+            }
+            Transcriber::Var { span, name } => {
+                prev = None;
+
+                let Some(binding) = scope.get(name.as_str()) else {
+                    return Err(compile::Error::msg(
+                        *span,
+                        try_format!("unknown macro variable `${name}`"),
+                    ));
+                };
+
+                let Binding::Fragment(tokens, wrap) = binding else {
+                    return Err(compile::Error::msg(
+                        *span,
+                        try_format!("variable `${name}` is still repeating at this depth"),
+                    ));
+                };
+
+                let (Some(first), Some(last)) = (tokens.first(), tokens.last()) else {
+                    continue;
+                };
+
+                if *wrap {
+                    out.try_push(ast::Token {
+                        span: first.span.head(),
+                        kind: Kind::Open(ast::Delimiter::Empty),
+                    })?;
+                }
+
+                out.try_extend(tokens.iter().copied())?;
+
+                if *wrap {
+                    out.try_push(ast::Token {
+                        span: last.span.tail(),
+                        kind: Kind::Close(ast::Delimiter::Empty),
+                    })?;
+                }
+            }
+            Transcriber::Repeat {
+                span: repeat_span,
+                transcribers,
+                separator,
+            } => {
+                prev = None;
+
+                let mut vars = Vec::new();
+                transcriber_vars(transcribers, &mut vars)?;
+
+                let mut count = None::<usize>;
+
+                for var in vars {
+                    let Some(Binding::Repeat(values)) = scope.get(var) else {
+                        continue;
+                    };
+
+                    match count {
+                        Some(count) if count != values.len() => {
+                            return Err(compile::Error::msg(
+                                *repeat_span,
+                                "meta-variable repeats with mismatched counts",
+                            ));
+                        }
+                        _ => count = Some(values.len()),
+                    }
+                }
+
+                let Some(count) = count else {
+                    return Err(compile::Error::msg(
+                        *repeat_span,
+                        "repetition in macro body doesn't use any repeating variables",
+                    ));
+                };
+
+                for n in 0..count {
+                    if n > 0 {
+                        if let Some(separator) = separator {
+                            out.try_push(*separator)?;
+                        }
+                    }
+
+                    let mut inner = HashMap::new();
+
+                    for (&name, &binding) in scope {
+                        let binding = match binding {
+                            Binding::Repeat(values) => values.get(n).unwrap_or(binding),
+                            binding => binding,
+                        };
+
+                        inner.try_insert(name, binding)?;
+                    }
+
+                    transcribe(cx, transcribers, &inner, renames, delimiters, out)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
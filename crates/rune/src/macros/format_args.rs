@@ -432,15 +432,15 @@ fn expand_format_spec<'a>(
                 },
                 Mode::FillAllign => {
                     // NB: parse alignment, if present.
-                    if matches!(a, '<' | '^' | '>') {
-                        align = Some(parse_align(a));
-                        iter.next();
-                    } else if matches!(b, '<' | '^' | '>') {
+                    if matches!(b, '<' | '^' | '>') {
                         fill = Some(a);
                         align = Some(parse_align(b));
 
                         iter.next();
                         iter.next();
+                    } else if matches!(a, '<' | '^' | '>') {
+                        align = Some(parse_align(a));
+                        iter.next();
                     }
 
                     mode = Mode::Sign;
@@ -519,6 +519,18 @@ fn expand_format_spec<'a>(
                             format_type = Some(format::Type::LowerHex);
                             iter.next();
                         }
+                        'o' => {
+                            format_type = Some(format::Type::Octal);
+                            iter.next();
+                        }
+                        'e' => {
+                            format_type = Some(format::Type::LowerExp);
+                            iter.next();
+                        }
+                        'E' => {
+                            format_type = Some(format::Type::UpperExp);
+                            iter.next();
+                        }
                         'X' => {
                             format_type = Some(format::Type::UpperHex);
                             iter.next();
@@ -8,7 +8,7 @@ use crate::indexing::Indexer;
 use crate::macros::{MacroContext, ToTokens};
 use crate::parse::{Parse, Parser};
 
-use super::TokenStream;
+use super::{declarative, TokenStream};
 
 pub(crate) struct MacroCompiler<'a, 'b, 'arena> {
     pub(crate) item_meta: ItemMeta,
@@ -31,6 +31,22 @@ impl MacroCompiler<'_, '_, '_> {
         }
 
         let named = self.idx.q.convert_path(&macro_call.path)?;
+
+        if let Some(declarative) = self.idx.q.declarative_macro_for(named.item) {
+            let token_stream = {
+                let mut macro_context = MacroContext {
+                    macro_span: span,
+                    input_span: macro_call.input_span(),
+                    item_meta: self.item_meta,
+                    idx: self.idx,
+                };
+
+                declarative.expand(&mut macro_context, &macro_call.input)?
+            };
+
+            return declarative::parse_output(&token_stream, span);
+        }
+
         let hash = self.idx.q.pool.item_type_hash(named.item);
 
         let Some(handler) = self.idx.q.context.lookup_macro(hash) else {
@@ -3,8 +3,9 @@
 use core::fmt;
 
 use crate::alloc;
+use crate::alloc::prelude::*;
 use crate::ast;
-use crate::ast::Span;
+use crate::ast::{Span, Spanned};
 use crate::compile::{self, ErrorKind, ItemMeta};
 use crate::indexing::Indexer;
 use crate::macros::{IntoLit, ToTokens, TokenStream};
@@ -201,6 +202,33 @@ impl<'a, 'b, 'arena> MacroContext<'a, 'b, 'arena> {
         Ok(ast::Ident { span, source })
     }
 
+    /// Construct a new identifier which is guaranteed to be unique, using the
+    /// given string as a prefix.
+    ///
+    /// This is useful for macros which need to generate items, like helper
+    /// functions, in the enclosing module without colliding with items
+    /// declared by the user or generated by other macro calls. The returned
+    /// identifier can't be written in source code.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::macros;
+    ///
+    /// macros::test(|cx| {
+    ///     let a = cx.unique_ident("helper")?;
+    ///     let b = cx.unique_ident("helper")?;
+    ///     assert_ne!(cx.resolve(a)?, cx.resolve(b)?);
+    ///     Ok(())
+    /// })?;
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    pub fn unique_ident(&mut self, prefix: &str) -> alloc::Result<ast::Ident> {
+        let id = self.idx.q.gen.next();
+        let ident = alloc::try_format!("{prefix}${id}");
+        self.ident(&ident)
+    }
+
     /// Construct a new label from the given string. The string should be
     /// specified *without* the leading `'`, so `"foo"` instead of `"'foo"`.
     ///
@@ -245,6 +273,65 @@ impl<'a, 'b, 'arena> MacroContext<'a, 'b, 'arena> {
         item.resolve(resolve_context!(self.idx.q))
     }
 
+    /// Report an error for the given span, which can refer to any token in the
+    /// input stream of the macro.
+    ///
+    /// In contrast to returning an error, this allows a macro to report
+    /// multiple errors before producing its output. Compilation will fail once
+    /// the current source has been processed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::macros;
+    ///
+    /// macros::test(|cx| {
+    ///     let span = cx.input_span();
+    ///     cx.error(span, "first argument is not supported")?;
+    ///     cx.error(span, "second argument is not supported")?;
+    ///     Ok(())
+    /// })?;
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    pub fn error<S, M>(&mut self, span: S, message: M) -> alloc::Result<()>
+    where
+        S: Spanned,
+        M: fmt::Display + fmt::Debug + Send + Sync + 'static,
+    {
+        let error = compile::Error::msg(span, message);
+        self.idx.q.diagnostics.error(self.idx.source_id, error)
+    }
+
+    /// Report a warning for the given span, which can refer to any token in
+    /// the input stream of the macro.
+    ///
+    /// The warning is reported under the `macro-warning` lint.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::macros;
+    ///
+    /// macros::test(|cx| {
+    ///     let span = cx.input_span();
+    ///     cx.warning(span, "argument is deprecated")?;
+    ///     Ok(())
+    /// })?;
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    pub fn warning<S, M>(&mut self, span: S, message: M) -> alloc::Result<()>
+    where
+        S: Spanned,
+        M: fmt::Display,
+    {
+        let message = message.try_to_string()?;
+
+        self.idx
+            .q
+            .diagnostics
+            .macro_warning(self.idx.source_id, &span, message)
+    }
+
     /// Access a literal source as a string.
     pub(crate) fn literal_source(&self, source: ast::LitSource, span: Span) -> Option<&str> {
         match source {
@@ -104,6 +104,7 @@
 //! # Ok::<_, rune::support::Error>(())
 //! ```
 
+mod declarative;
 mod format_args;
 mod into_lit;
 mod macro_compiler;
@@ -112,6 +113,7 @@ mod quote_fn;
 mod storage;
 mod token_stream;
 
+pub(crate) use self::declarative::DeclarativeMacro;
 pub use self::format_args::FormatArgs;
 pub use self::into_lit::IntoLit;
 pub(crate) use self::macro_compiler::MacroCompiler;
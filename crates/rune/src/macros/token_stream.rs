@@ -41,6 +41,11 @@ impl TokenStream {
         Ok(())
     }
 
+    /// Access the tokens in the stream as a slice.
+    pub(crate) fn as_slice(&self) -> &[ast::Token] {
+        &self.stream
+    }
+
     /// Create an iterator over the token stream.
     pub(crate) fn iter(&self) -> TokenStreamIter<'_> {
         TokenStreamIter {
@@ -1,6 +1,5 @@
 use core::fmt;
 
-#[cfg(feature = "doc")]
 use crate::alloc::Box;
 #[cfg(feature = "doc")]
 use crate::compile::meta;
@@ -29,8 +28,8 @@ use crate::runtime::MaybeTypeOf;
 /// [`Module::function_meta`]: super::Module::function_meta
 pub struct ItemFnMut<'a> {
     pub(super) docs: &'a mut Docs,
-    #[cfg(feature = "doc")]
     pub(super) deprecated: &'a mut Option<Box<str>>,
+    pub(super) is_diverging: Option<&'a mut bool>,
     #[cfg(feature = "doc")]
     pub(super) is_async: &'a mut bool,
     #[cfg(feature = "doc")]
@@ -61,16 +60,29 @@ impl ItemFnMut<'_> {
     }
 
     /// Mark the given item as deprecated.
-    pub fn deprecated(
-        self,
-        #[cfg_attr(not(feature = "doc"), allow(unused))] deprecated: impl AsRef<str>,
-    ) -> Result<Self, ContextError> {
-        #[cfg(feature = "doc")]
-        {
-            *self.deprecated = Some(deprecated.as_ref().try_into()?);
+    ///
+    /// Calling a deprecated function causes the compiler to emit a warning
+    /// with the given message, unless disabled through
+    /// [`Options::deprecations`].
+    ///
+    /// [`Options::deprecations`]: crate::Options::deprecations
+    pub fn deprecated(self, deprecated: impl AsRef<str>) -> Result<Self, ContextError> {
+        *self.deprecated = Some(deprecated.as_ref().try_into()?);
+        Ok(self)
+    }
+
+    /// Mark the given item as diverging, meaning that calling it never returns
+    /// normally, like the `panic` function.
+    ///
+    /// The compiler uses this to emit a warning for code which follows a call
+    /// to the function since it can never be reached. This has no effect on
+    /// trait functions.
+    pub fn diverging(mut self) -> Self {
+        if let Some(is_diverging) = &mut self.is_diverging {
+            **is_diverging = true;
         }
 
-        Ok(self)
+        self
     }
 
     /// Indicate the number of arguments this function accepts.
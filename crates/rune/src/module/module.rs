@@ -193,6 +193,65 @@ impl Module {
     /// # Ok::<_, rune::support::Error>(())
     /// ```
     pub fn ty<T>(&mut self) -> Result<TypeMut<'_, T>, ContextError>
+    where
+        T: ?Sized + TypeOf + Named + InstallWith,
+    {
+        self.ty_inner::<T>(None)
+    }
+
+    /// Register a concrete instantiation of a generic type under its own name
+    /// in this module.
+    ///
+    /// The type is still registered under its generic item, so that for
+    /// example `Grid::<f64>` and `FloatGrid` refer to the same type. But each
+    /// instantiation can now be imported and referenced by its own name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::{Any, Context, Module};
+    /// use rune::compile::Named;
+    /// use rune::runtime::{MaybeTypeOf, TypeOf};
+    ///
+    /// #[derive(Any)]
+    /// #[rune(item = ::grid)]
+    /// struct Grid<T>
+    /// where
+    ///     T: 'static + Named + MaybeTypeOf + TypeOf,
+    /// {
+    ///     cells: Vec<T>,
+    /// }
+    ///
+    /// let mut m = Module::with_crate("grid")?;
+    /// m.ty_with_name::<Grid<f64>>("FloatGrid")?;
+    /// m.ty_with_name::<Grid<i64>>("IntGrid")?;
+    ///
+    /// let mut context = Context::new();
+    /// context.install(m)?;
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    pub fn ty_with_name<T>(&mut self, name: &str) -> Result<TypeMut<'_, T>, ContextError>
+    where
+        T: ?Sized + TypeOf + Named + InstallWith,
+    {
+        let item = self.item.join([name])?;
+        let hash = Hash::type_hash(&item);
+
+        if !self.names.try_insert(Name::Item(hash))? {
+            return Err(ContextError::ConflictingType {
+                existing: item.try_clone()?,
+                item,
+                type_info: T::type_info(),
+                hash,
+                module: self.item.try_clone()?,
+                existing_module: self.item.try_clone()?,
+            });
+        }
+
+        self.ty_inner::<T>(Some(item))
+    }
+
+    fn ty_inner<T>(&mut self, alias: Option<ItemBuf>) -> Result<TypeMut<'_, T>, ContextError>
     where
         T: ?Sized + TypeOf + Named + InstallWith,
     {
@@ -201,6 +260,9 @@ impl Module {
                 item: T::ITEM.try_to_owned()?,
                 type_info: T::type_info(),
                 hash: T::HASH,
+                module: self.item.try_clone()?,
+                existing: T::ITEM.try_to_owned()?,
+                existing_module: self.item.try_clone()?,
             });
         }
 
@@ -218,6 +280,9 @@ impl Module {
             type_info: T::type_info(),
             spec: None,
             constructor: None,
+            immutable: None,
+            serialize: None,
+            alias,
         })?;
 
         T::install_with(self)?;
@@ -230,6 +295,8 @@ impl Module {
             deprecated: &mut ty.common.deprecated,
             spec: &mut ty.spec,
             constructor: &mut ty.constructor,
+            immutable: &mut ty.immutable,
+            serialize: &mut ty.serialize,
             item: &ty.item,
             _marker: PhantomData,
         })
@@ -257,6 +324,8 @@ impl Module {
             deprecated: &mut ty.common.deprecated,
             spec: &mut ty.spec,
             constructor: &mut ty.constructor,
+            immutable: &mut ty.immutable,
+            serialize: &mut ty.serialize,
             item: &ty.item,
             _marker: PhantomData,
         })
@@ -798,11 +867,14 @@ impl Module {
         docs.set_docs(meta.statics.docs)?;
         docs.set_arguments(meta.statics.arguments)?;
         let deprecated = meta.statics.deprecated.map(TryInto::try_into).transpose()?;
+        let is_const = meta.statics.is_const;
 
         match meta.kind {
-            FunctionMetaKind::Function(data) => self.function_inner(data, docs, deprecated),
+            FunctionMetaKind::Function(data) => {
+                self.function_inner(data, docs, deprecated, is_const)
+            }
             FunctionMetaKind::AssociatedFunction(data) => {
-                self.insert_associated_function(data, docs, deprecated)
+                self.insert_associated_function(data, docs, deprecated, is_const)
             }
         }
     }
@@ -810,11 +882,14 @@ impl Module {
     pub(super) fn function_from_meta_kind(
         &mut self,
         kind: FunctionMetaKind,
+        is_const: bool,
     ) -> Result<ItemFnMut<'_>, ContextError> {
         match kind {
-            FunctionMetaKind::Function(data) => self.function_inner(data, Docs::EMPTY, None),
+            FunctionMetaKind::Function(data) => {
+                self.function_inner(data, Docs::EMPTY, None, is_const)
+            }
             FunctionMetaKind::AssociatedFunction(data) => {
-                self.insert_associated_function(data, Docs::EMPTY, None)
+                self.insert_associated_function(data, Docs::EMPTY, None, is_const)
             }
         }
     }
@@ -877,6 +952,8 @@ impl Module {
         ModuleFunctionBuilder {
             module: self,
             inner: FunctionBuilder::new(name, f),
+            deprecated: None,
+            is_const: false,
         }
     }
 
@@ -896,6 +973,8 @@ impl Module {
         Ok(ModuleFunctionBuilder {
             module: self,
             inner: FunctionBuilder::new(name, f),
+            deprecated: None,
+            is_const: false,
         })
     }
 
@@ -908,7 +987,7 @@ impl Module {
         N: IntoComponent,
         A: FunctionArgs,
     {
-        self.function_inner(FunctionData::new(name, f)?, Docs::EMPTY, None)
+        self.function_inner(FunctionData::new(name, f)?, Docs::EMPTY, None, false)
     }
 
     /// Register an instance function.
@@ -1104,6 +1183,7 @@ impl Module {
             AssociatedFunctionData::from_instance_function(name.to_instance()?, f)?,
             Docs::EMPTY,
             None,
+            false,
         )
     }
 
@@ -1153,6 +1233,7 @@ impl Module {
             AssociatedFunctionData::from_instance_function(name.to_field_function(protocol)?, f)?,
             Docs::EMPTY,
             None,
+            false,
         )
     }
 
@@ -1194,6 +1275,7 @@ impl Module {
             AssociatedFunctionData::from_instance_function(name, f)?,
             Docs::EMPTY,
             None,
+            false,
         )
     }
 
@@ -1274,6 +1356,7 @@ impl Module {
         data: FunctionData,
         docs: Docs,
         #[allow(unused)] deprecated: Option<Box<str>>,
+        is_const: bool,
     ) -> Result<ItemFnMut<'_>, ContextError> {
         let item = self.item.join(&data.item)?;
         let hash = Hash::type_hash(&item);
@@ -1289,6 +1372,8 @@ impl Module {
             kind: ModuleItemKind::Function(ModuleFunction {
                 handler: data.handler,
                 trait_hash: None,
+                is_const,
+                is_diverging: false,
                 doc: DocFunction {
                     #[cfg(feature = "doc")]
                     is_async: data.is_async,
@@ -1304,7 +1389,6 @@ impl Module {
 
         let last = self.items.last_mut().unwrap();
 
-        #[cfg(feature = "doc")]
         let last_fn = match &mut last.kind {
             ModuleItemKind::Function(f) => f,
             _ => unreachable!(),
@@ -1312,8 +1396,8 @@ impl Module {
 
         Ok(ItemFnMut {
             docs: &mut last.common.docs,
-            #[cfg(feature = "doc")]
             deprecated: &mut last.common.deprecated,
+            is_diverging: Some(&mut last_fn.is_diverging),
             #[cfg(feature = "doc")]
             is_async: &mut last_fn.doc.is_async,
             #[cfg(feature = "doc")]
@@ -1331,6 +1415,7 @@ impl Module {
         data: AssociatedFunctionData,
         docs: Docs,
         #[allow(unused)] deprecated: Option<Box<str>>,
+        is_const: bool,
     ) -> Result<ItemFnMut<'_>, ContextError> {
         self.insert_associated_name(&data.associated)?;
 
@@ -1342,6 +1427,8 @@ impl Module {
             kind: ModuleAssociatedKind::Function(ModuleFunction {
                 handler: data.handler,
                 trait_hash: None,
+                is_const,
+                is_diverging: false,
                 doc: DocFunction {
                     #[cfg(feature = "doc")]
                     is_async: data.is_async,
@@ -1357,7 +1444,6 @@ impl Module {
 
         let last = self.associated.last_mut().unwrap();
 
-        #[cfg(feature = "doc")]
         let last_fn = match &mut last.kind {
             ModuleAssociatedKind::Function(f) => f,
             _ => unreachable!(),
@@ -1365,8 +1451,8 @@ impl Module {
 
         Ok(ItemFnMut {
             docs: &mut last.common.docs,
-            #[cfg(feature = "doc")]
             deprecated: &mut last.common.deprecated,
+            is_diverging: Some(&mut last_fn.is_diverging),
             #[cfg(feature = "doc")]
             is_async: &mut last_fn.doc.is_async,
             #[cfg(feature = "doc")]
@@ -1423,7 +1509,13 @@ impl Module {
         let hash = Hash::type_hash(&item);
 
         if !self.names.try_insert(Name::Item(hash))? {
-            return Err(ContextError::ConflictingTrait { item, hash });
+            return Err(ContextError::ConflictingTrait {
+                existing: item.try_clone()?,
+                item,
+                hash,
+                module: self.item.try_clone()?,
+                existing_module: self.item.try_clone()?,
+            });
         }
 
         self.traits.try_push(ModuleTrait {
@@ -1475,6 +1567,26 @@ impl Module {
     }
 
     /// Define a re-export.
+    ///
+    /// This makes the item `to` available under the path `item` in the current
+    /// module, which is useful for building facade modules. The re-export is
+    /// an alias, so both paths resolve to the same functions and types.
+    ///
+    /// Installing a conflicting re-export for the same path into a
+    /// [`Context`][crate::Context] results in an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::{item, Module};
+    ///
+    /// let mut json = Module::with_crate("json")?;
+    /// json.function("parse", |value: i64| value).build()?;
+    ///
+    /// let mut app = Module::with_crate("app")?;
+    /// app.reexport(["json"], item!(::json))?;
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
     pub fn reexport(
         &mut self,
         item: impl IntoIterator<Item: IntoComponent>,
@@ -1,3 +1,4 @@
+use crate::alloc::Box;
 use crate::compile::ContextError;
 use crate::function::{Function, FunctionKind};
 use crate::function_meta::{FunctionArgs, FunctionBuilder, ToInstance};
@@ -17,6 +18,8 @@ use super::Module;
 pub struct ModuleFunctionBuilder<'a, F, A, N, K> {
     pub(super) module: &'a mut Module,
     pub(super) inner: FunctionBuilder<N, F, A, K>,
+    pub(super) deprecated: Option<Box<str>>,
+    pub(super) is_const: bool,
 }
 
 impl<'a, F, A, N, K> ModuleFunctionBuilder<'a, F, A, N, K>
@@ -26,6 +29,62 @@ where
     A: FunctionArgs,
     K: FunctionKind,
 {
+    /// Mark the function as deprecated with the given message.
+    ///
+    /// Calling a deprecated function causes the compiler to emit a warning
+    /// with the given message, unless disabled through
+    /// [`Options::deprecations`].
+    ///
+    /// [`Options::deprecations`]: crate::Options::deprecations
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::Module;
+    ///
+    /// let mut m = Module::with_item(["module"])?;
+    ///
+    /// m.function("old", || ())
+    ///     .deprecated("use module::new() instead")?
+    ///     .build()?;
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    #[inline]
+    pub fn deprecated(mut self, message: impl AsRef<str>) -> Result<Self, ContextError> {
+        self.deprecated = Some(message.as_ref().try_into()?);
+        Ok(self)
+    }
+
+    /// Mark the function as callable in constant contexts, like `const`
+    /// items and `const fn` functions.
+    ///
+    /// Constant evaluation happens during compilation, so the function must be
+    /// pure and all of its arguments and its return value must be
+    /// representable as constant values, meaning that they implement
+    /// [`FromConstValue`] and [`ToConstValue`] respectively. Calling it with
+    /// something else results in a compile error.
+    ///
+    /// [`FromConstValue`]: crate::runtime::FromConstValue
+    /// [`ToConstValue`]: crate::runtime::ToConstValue
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::Module;
+    ///
+    /// let mut m = Module::with_item(["module"])?;
+    ///
+    /// m.function("max", |a: i64, b: i64| a.max(b))
+    ///     .const_fn()
+    ///     .build()?;
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    #[inline]
+    pub fn const_fn(mut self) -> Self {
+        self.is_const = true;
+        self
+    }
+
     /// Construct a regular function.
     ///
     /// This register the function as a free function in the module it's
@@ -47,7 +106,9 @@ where
         N: IntoComponent,
     {
         let meta = self.inner.build()?;
-        self.module.function_from_meta_kind(meta)
+        let item = self.module.function_from_meta_kind(meta, self.is_const)?;
+        *item.deprecated = self.deprecated;
+        Ok(item)
     }
 
     /// Construct a function that is associated with `T`.
@@ -96,7 +157,9 @@ where
         T: TypeOf,
     {
         let meta = self.inner.build_associated::<T>()?;
-        self.module.function_from_meta_kind(meta)
+        let item = self.module.function_from_meta_kind(meta, self.is_const)?;
+        *item.deprecated = self.deprecated;
+        Ok(item)
     }
 
     /// Construct a function that is associated with a custom dynamically
@@ -128,6 +191,8 @@ where
         let meta = self
             .inner
             .build_associated_with(container, container_type_info)?;
-        self.module.function_from_meta_kind(meta)
+        let item = self.module.function_from_meta_kind(meta, self.is_const)?;
+        *item.deprecated = self.deprecated;
+        Ok(item)
     }
 }
@@ -8,7 +8,9 @@ use crate::alloc::prelude::*;
 use crate::compile::context::{AttributeMacroHandler, MacroHandler, TraitHandler};
 use crate::compile::{meta, Docs};
 use crate::function_meta::AssociatedName;
-use crate::runtime::{ConstValue, FieldMap, FunctionHandler, TypeInfo};
+use crate::runtime::{
+    CloneHandler, ConstValue, FieldMap, FunctionHandler, SerializeHandler, TypeInfo,
+};
 use crate::{Hash, ItemBuf};
 
 #[doc(hidden)]
@@ -49,6 +51,14 @@ pub(crate) struct ModuleType {
     pub(crate) spec: Option<TypeSpecification>,
     /// Handler to use if this type can be constructed through a regular function call.
     pub(crate) constructor: Option<Arc<FunctionHandler>>,
+    /// Handler used to clone the type if it has been marked as immutable.
+    pub(crate) immutable: Option<Arc<CloneHandler>>,
+    /// Handler used to serialize the type if it has been marked as
+    /// serializable.
+    pub(crate) serialize: Option<Arc<SerializeHandler>>,
+    /// An additional item under which a concrete instantiation of a generic
+    /// type is available.
+    pub(crate) alias: Option<ItemBuf>,
 }
 
 /// A trait defined in a module.
@@ -208,6 +218,12 @@ pub(crate) struct ModuleFunction {
     pub(crate) handler: Arc<FunctionHandler>,
     /// If the function is associated with a trait, this is the hash of that trait.
     pub(crate) trait_hash: Option<Hash>,
+    /// If the function can be called in constant contexts.
+    #[try_clone(copy)]
+    pub(crate) is_const: bool,
+    /// If calling the function never returns normally.
+    #[try_clone(copy)]
+    pub(crate) is_diverging: bool,
     /// Documentation related to the function.
     pub(crate) doc: DocFunction,
 }
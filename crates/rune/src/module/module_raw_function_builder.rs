@@ -46,11 +46,10 @@ impl<'a, N> ModuleRawFunctionBuilder<'a, N> {
         N: IntoComponent,
     {
         let item = ItemBuf::with_item([self.name])?;
-        self.module
-            .function_from_meta_kind(FunctionMetaKind::Function(FunctionData::from_raw(
-                item,
-                self.handler,
-            )))
+        self.module.function_from_meta_kind(
+            FunctionMetaKind::Function(FunctionData::from_raw(item, self.handler)),
+            false,
+        )
     }
 
     /// Construct a function that is associated with `T`.
@@ -101,10 +100,13 @@ impl<'a, N> ModuleRawFunctionBuilder<'a, N> {
     {
         let associated = Associated::from_type::<T>(self.name.to_instance()?)?;
 
-        self.module
-            .function_from_meta_kind(FunctionMetaKind::AssociatedFunction(
-                AssociatedFunctionData::from_raw(associated, self.handler),
-            ))
+        self.module.function_from_meta_kind(
+            FunctionMetaKind::AssociatedFunction(AssociatedFunctionData::from_raw(
+                associated,
+                self.handler,
+            )),
+            false,
+        )
     }
 
     /// Construct a function that is associated with a custom dynamically
@@ -134,9 +136,12 @@ impl<'a, N> ModuleRawFunctionBuilder<'a, N> {
         N: ToInstance,
     {
         let associated = Associated::new(self.name.to_instance()?, container, container_type_info);
-        self.module
-            .function_from_meta_kind(FunctionMetaKind::AssociatedFunction(
-                AssociatedFunctionData::from_raw(associated, self.handler),
-            ))
+        self.module.function_from_meta_kind(
+            FunctionMetaKind::AssociatedFunction(AssociatedFunctionData::from_raw(
+                associated,
+                self.handler,
+            )),
+            false,
+        )
     }
 }
@@ -84,8 +84,8 @@ impl TraitMut<'_> {
 
         Ok(ItemFnMut {
             docs: &mut f.common.docs,
-            #[cfg(feature = "doc")]
             deprecated: &mut f.common.deprecated,
+            is_diverging: None,
             #[cfg(feature = "doc")]
             is_async: &mut f.doc.is_async,
             #[cfg(feature = "doc")]
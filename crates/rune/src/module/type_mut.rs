@@ -3,11 +3,16 @@ use core::marker::PhantomData;
 
 use ::rust_alloc::sync::Arc;
 
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
 use crate::alloc::prelude::*;
 use crate::compile::{ContextError, Docs};
 use crate::function::{Function, Plain};
-use crate::runtime::{FunctionHandler, TypeOf};
-use crate::Item;
+use crate::runtime::{
+    CloneHandler, FunctionHandler, SerdeError, SerializeHandler, TypeOf, Value, VmResult,
+};
+use crate::{Any, Item};
 
 use super::{Enum, EnumMut, Fields, TypeSpecification, Variant};
 
@@ -30,6 +35,8 @@ where
     pub(super) deprecated: &'a mut Option<Box<str>>,
     pub(super) spec: &'a mut Option<TypeSpecification>,
     pub(super) constructor: &'a mut Option<Arc<FunctionHandler>>,
+    pub(super) immutable: &'a mut Option<Arc<CloneHandler>>,
+    pub(super) serialize: &'a mut Option<Arc<SerializeHandler>>,
     pub(super) item: &'a Item,
     pub(super) _marker: PhantomData<T>,
 }
@@ -134,6 +141,114 @@ where
         Ok(self)
     }
 
+    /// Mark the current type as immutable, giving it value semantics inside of
+    /// scripts.
+    ///
+    /// Values of an immutable type are cloned when they are assigned to a
+    /// variable or passed as an argument to a function defined in a script.
+    /// Mutating the value through one binding is therefore never visible
+    /// through another. Native functions still receive references to the
+    /// values they are called with, so instance functions which mutate their
+    /// receiver work as usual.
+    ///
+    /// This comes at the cost of performing a clone on every such assignment,
+    /// so it should only be used for small types which are conceptually
+    /// values, like colors or vectors.
+    ///
+    /// This is what the `#[rune(immutable)]` attribute of the [`Any`] derive
+    /// uses.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::{Any, Module};
+    /// use rune::alloc::prelude::*;
+    ///
+    /// #[derive(Any, TryClone)]
+    /// struct Color {
+    ///     #[rune(get, set, copy)]
+    ///     red: u8,
+    /// }
+    ///
+    /// let mut m = Module::new();
+    /// m.ty::<Color>()?.immutable()?;
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    pub fn immutable(self) -> Result<Self, ContextError>
+    where
+        T: Any + TryClone,
+    {
+        *self.immutable = Some(Arc::new(|value: &Value| -> VmResult<Value> {
+            let value = vm_try!(value.borrow_ref::<T>());
+            let value = vm_try!(value.try_clone());
+            VmResult::Ok(vm_try!(Value::new(value)))
+        }));
+
+        Ok(self)
+    }
+
+    /// Mark the current type as serializable, which allows values of it to be
+    /// serialized and deserialized through the [`RuntimeContext`] the type is
+    /// installed in.
+    ///
+    /// Values of external types can't be serialized on their own, since a
+    /// format can't know how to construct them again. Instead
+    /// [`RuntimeContext::to_serializable`] replaces values of serializable
+    /// types with a representation tagged by their type hash, which
+    /// [`RuntimeContext::from_serializable`] later uses to construct them.
+    ///
+    /// This is what the `#[rune(serialize)]` attribute of the [`Any`] derive
+    /// uses.
+    ///
+    /// [`RuntimeContext`]: crate::runtime::RuntimeContext
+    /// [`RuntimeContext::to_serializable`]: crate::runtime::RuntimeContext::to_serializable
+    /// [`RuntimeContext::from_serializable`]: crate::runtime::RuntimeContext::from_serializable
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::{Any, Module};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Any, Serialize, Deserialize)]
+    /// struct Point {
+    ///     x: i64,
+    ///     y: i64,
+    /// }
+    ///
+    /// let mut m = Module::new();
+    /// m.ty::<Point>()?.serializable()?;
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    pub fn serializable(self) -> Result<Self, ContextError>
+    where
+        T: Any + Serialize + DeserializeOwned,
+    {
+        fn to_value<T>(value: &Value) -> Result<Value, SerdeError>
+        where
+            T: Any + Serialize,
+        {
+            let value = value.borrow_ref::<T>()?;
+            crate::runtime::to_value_from_serialize(&*value)
+        }
+
+        fn from_value<T>(value: Value) -> Result<Value, SerdeError>
+        where
+            T: Any + DeserializeOwned,
+        {
+            let value = crate::runtime::from_value_to_deserialize::<T>(value)?;
+            Ok(Value::new(value)?)
+        }
+
+        *self.serialize = Some(Arc::new(SerializeHandler {
+            item: T::ITEM.try_to_owned()?,
+            to_value: to_value::<T>,
+            from_value: from_value::<T>,
+        }));
+
+        Ok(self)
+    }
+
     fn make_struct(self, fields: Fields) -> Result<Self, ContextError> {
         let old = self.spec.replace(TypeSpecification::Struct(fields));
 
@@ -0,0 +1,63 @@
+//! Inspecting the execution budget.
+
+use crate as rune;
+use crate::runtime::budget;
+use crate::{ContextError, Module};
+
+/// Inspecting the execution budget.
+///
+/// The budget is configured by the host which is running the script.
+///
+/// # Examples
+///
+/// ```rune
+/// use std::budget;
+///
+/// let before = budget::consumed();
+/// let after = budget::consumed();
+/// assert!(after >= before);
+/// ```
+#[rune::module(::std::budget)]
+pub fn module() -> Result<Module, ContextError> {
+    let mut m = Module::from_meta(self::module_meta)?;
+    m.function_meta(remaining)?;
+    m.function_meta(consumed)?;
+    Ok(m)
+}
+
+/// Get the remaining instruction budget, or `None` if the script is not being
+/// executed with a budget.
+///
+/// # Examples
+///
+/// ```rune
+/// use std::budget;
+///
+/// if let Some(remaining) = budget::remaining() {
+///     assert!(remaining > 0);
+/// }
+/// ```
+#[rune::function]
+fn remaining() -> Option<u64> {
+    budget::remaining().map(|n| n as u64)
+}
+
+/// Get the number of instructions which have been consumed from the budget.
+///
+/// This is always zero if the script is not being executed with a budget.
+///
+/// # Examples
+///
+/// ```rune
+/// use std::budget;
+///
+/// let consumed = budget::consumed();
+///
+/// if budget::remaining().is_none() {
+///     assert_eq!(consumed, 0);
+/// }
+/// ```
+#[rune::function]
+fn consumed() -> u64 {
+    budget::consumed() as u64
+}
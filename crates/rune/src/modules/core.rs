@@ -2,6 +2,7 @@
 
 use crate as rune;
 use crate::alloc::prelude::*;
+use crate::ast;
 use crate::compile;
 use crate::macros::{quote, FormatArgs, MacroContext, TokenStream};
 use crate::parse::Parser;
@@ -31,12 +32,13 @@ pub fn module() -> Result<Module, ContextError> {
         /// The primitive float type.
     })?;
 
-    module.function_meta(panic)?;
+    module.function_meta(panic)?.diverging();
     module.function_meta(is_readable)?;
     module.function_meta(is_writable)?;
 
     module.macro_meta(stringify_macro)?;
     module.macro_meta(panic_macro)?;
+    module.macro_meta(compile_error_macro)?;
     Ok(module)
 }
 
@@ -124,3 +126,25 @@ pub(crate) fn panic_macro(
     let expanded = args.expand(cx)?;
     Ok(quote!(::std::panic(#expanded)).into_token_stream(cx)?)
 }
+
+/// Cause compilation to fail with the given error message.
+///
+/// The error is reported at the location of the macro call. This can be used
+/// to prevent code which is known to be invalid from compiling.
+///
+/// ```rune,ignore
+/// compile_error!("this code should not compile");
+/// ```
+#[rune::macro_(path = compile_error)]
+pub(crate) fn compile_error_macro(
+    cx: &mut MacroContext<'_, '_, '_>,
+    stream: &TokenStream,
+) -> compile::Result<TokenStream> {
+    let mut p = Parser::from_token_stream(stream, cx.input_span());
+    let message = p.parse::<ast::LitStr>()?;
+    p.parse::<Option<T![,]>>()?;
+    p.eof()?;
+
+    let message = cx.resolve(message)?.try_into_owned()?;
+    Err(compile::Error::msg(cx.macro_span(), message))
+}
@@ -27,6 +27,7 @@ pub fn module() -> Result<Module, ContextError> {
     let mut m = Module::from_meta(self::module_meta)?.with_unique("std::fmt");
 
     m.ty::<Formatter>()?;
+    m.function_meta(formatter_write_str)?;
     m.ty::<fmt::Error>()?;
     m.function_meta(fmt_error_display_fmt)?;
     m.macro_meta(format)?;
@@ -40,6 +41,30 @@ pub fn module() -> Result<Module, ContextError> {
     Ok(m)
 }
 
+/// Write a string to the formatter.
+///
+/// This is used when implementing formatting protocols for script types.
+///
+/// # Examples
+///
+/// ```rune
+/// struct Point { x, y }
+///
+/// impl Point {
+///     #[protocol(display_fmt)]
+///     fn display_fmt(self, f) {
+///         f.write_str(`(${self.x}, ${self.y})`);
+///     }
+/// }
+///
+/// let p = Point { x: 1, y: 2 };
+/// assert_eq!(format!("{p}"), "(1, 2)");
+/// ```
+#[rune::function(instance, path = write_str)]
+fn formatter_write_str(f: &mut Formatter, string: &str) -> VmResult<()> {
+    vm_write!(f, "{string}")
+}
+
 #[rune::function(instance, protocol = DISPLAY_FMT)]
 fn fmt_error_display_fmt(error: &fmt::Error, f: &mut Formatter) -> VmResult<()> {
     vm_write!(f, "{error}")
@@ -47,12 +72,21 @@ fn fmt_error_display_fmt(error: &fmt::Error, f: &mut Formatter) -> VmResult<()>
 
 /// Format a string using a format specifier.
 ///
+/// Format specifications follow the same syntax and semantics as in Rust,
+/// supporting fill, alignment, sign, alternate, zero padding, width,
+/// precision and the `?`, `x`, `X`, `o`, `b`, `e` and `E` types.
+///
 /// # Examples
 ///
 /// ```rune
 /// let who = "World";
 /// let string = format!("Hello {}", who);
 /// assert_eq!(string, "Hello World");
+///
+/// assert_eq!(format!("{:>8.2}", 3.14159), "    3.14");
+/// assert_eq!(format!("{:*^9}", -12), "***-12***");
+/// assert_eq!(format!("{:#010x}", 255), "0x000000ff");
+/// assert_eq!(format!("{:.2e}", 1234.5), "1.23e3");
 /// ```
 #[rune::macro_(path = format)]
 pub(crate) fn format(
@@ -1,7 +1,7 @@
 //! Hashing types.
 
 use crate as rune;
-use crate::runtime::Hasher;
+use crate::runtime::{EnvProtocolCaller, Hasher, Value, VmResult};
 use crate::{ContextError, Module};
 
 /// Hashing types.
@@ -10,5 +10,39 @@ pub fn module() -> Result<Module, ContextError> {
     #[allow(unused_mut)]
     let mut module = Module::from_meta(self::module_meta)?;
     module.ty::<Hasher>()?;
+    module.function_meta(hasher_hash)?;
     Ok(module)
 }
+
+/// Feed a value into the hasher.
+///
+/// This is used when implementing the hash protocol for script types.
+///
+/// # Examples
+///
+/// ```rune
+/// use std::collections::HashSet;
+///
+/// struct Key { id, name }
+///
+/// impl Key {
+///     #[protocol(hash)]
+///     fn hash(self, hasher) {
+///         hasher.hash(self.id);
+///     }
+///
+///     #[protocol(eq)]
+///     fn eq(self, other) {
+///         self.id == other.id
+///     }
+/// }
+///
+/// let set = HashSet::new();
+/// set.insert(Key { id: 1, name: "a" });
+/// set.insert(Key { id: 1, name: "b" });
+/// assert_eq!(set.len(), 1);
+/// ```
+#[rune::function(instance, path = hash)]
+fn hasher_hash(hasher: &mut Hasher, value: Value) -> VmResult<()> {
+    value.hash_with(hasher, &mut EnvProtocolCaller)
+}
@@ -8,8 +8,8 @@ use crate::modules::collections::VecDeque;
 use crate::modules::collections::{HashMap, HashSet};
 use crate::runtime::range::RangeIter;
 use crate::runtime::{
-    FromValue, Function, Inline, InstAddress, Object, Output, OwnedTuple, Protocol, Repr, TypeHash,
-    Value, Vec, VmErrorKind, VmResult,
+    FromValue, Function, Inline, InstAddress, NativeIter, Object, Output, OwnedTuple, Protocol,
+    Repr, TypeHash, Value, Vec, VmErrorKind, VmResult,
 };
 use crate::shared::Caller;
 use crate::{Any, ContextError, Module, Params};
@@ -21,6 +21,11 @@ use crate::{Any, ContextError, Module, Params};
 pub fn module() -> Result<Module, ContextError> {
     let mut m = Module::from_meta(self::module_meta)?;
 
+    m.ty::<NativeIter>()?;
+    m.function_meta(NativeIter::next__meta)?;
+    m.function_meta(NativeIter::size_hint__meta)?;
+    m.implement_trait::<NativeIter>(rune::item!(::std::iter::Iterator))?;
+
     m.ty::<Rev>()?;
     m.function_meta(Rev::next__meta)?;
     m.function_meta(Rev::next_back__meta)?;
@@ -260,6 +265,38 @@ pub fn module() -> Result<Module, ContextError> {
                 })?;
             }
 
+            {
+                let next = next.clone();
+
+                cx.function(
+                    "try_fold",
+                    move |iter: Value, mut acc: Value, f: Function| loop {
+                        let Some(value) = vm_try!(next.call((iter.clone(),))) else {
+                            break VmResult::Ok(Ok::<_, Value>(acc));
+                        };
+
+                        match vm_try!(f.call::<Result<Value, Value>>((acc, value))) {
+                            Ok(value) => acc = value,
+                            Err(error) => break VmResult::Ok(Err(error)),
+                        }
+                    },
+                )?;
+            }
+
+            {
+                let next = next.clone();
+
+                cx.function("try_for_each", move |iter: Value, f: Function| loop {
+                    let Some(value) = vm_try!(next.call((iter.clone(),))) else {
+                        break VmResult::Ok(Ok::<_, Value>(()));
+                    };
+
+                    if let Err(error) = vm_try!(f.call::<Result<Value, Value>>((value,))) {
+                        break VmResult::Ok(Err(error));
+                    }
+                })?;
+            }
+
             {
                 let next = next.clone();
 
@@ -464,6 +501,28 @@ pub fn module() -> Result<Module, ContextError> {
                 )?;
             }
 
+            {
+                let next = next.with_return::<Option<Result<Value, Value>>>();
+                let size_hint = size_hint.clone();
+
+                cx.function(
+                    Params::new("collect", [Result::<Value, Value>::HASH]),
+                    move |iter: Value| {
+                        let (cap, _) = vm_try!(size_hint.call((&iter,)));
+                        let mut vec = vm_try!(Vec::with_capacity(cap));
+
+                        while let Some(result) = vm_try!(next.call((iter.clone(),))) {
+                            match result {
+                                Ok(value) => vm_try!(vec.push(value)),
+                                Err(error) => return VmResult::Ok(Err(error)),
+                            }
+                        }
+
+                        VmResult::Ok(Ok(vec))
+                    },
+                )?;
+            }
+
             {
                 let next = next.clone();
 
@@ -844,6 +903,79 @@ pub fn module() -> Result<Module, ContextError> {
                 /// [`reduce()`]: Iterator::reduce
             })?;
 
+        t.function("try_fold")?
+            .argument_types::<(Value, Value, Function)>()?
+            .return_type::<Result<Value, Value>>()?
+            .docs(docstring! {
+                /// An iterator method that applies a function as long as it returns
+                /// successfully, producing a single, final value.
+                ///
+                /// `try_fold()` takes two arguments: an initial value, and a closure with
+                /// two arguments: an 'accumulator', and an element. The closure either
+                /// returns `Ok` with the value that the accumulator should have for the
+                /// next iteration, or it returns `Err` which is propagated back to the
+                /// caller immediately (short-circuiting).
+                ///
+                /// The closure is not called again once it has returned an `Err`, and no
+                /// further elements are consumed from the iterator.
+                ///
+                /// If the iterator is exhausted, the final accumulator is returned wrapped
+                /// in `Ok`.
+                ///
+                /// # Examples
+                ///
+                /// Parsing a collection of strings, stopping at the first error:
+                ///
+                /// ```rune
+                /// let a = ["1", "2", "3"];
+                /// let sum = a.iter().try_fold(0, |acc, x| Ok(acc + x.parse::<i64>()?));
+                /// assert_eq!(sum, Ok(6));
+                ///
+                /// let a = ["1", "x", "3"];
+                /// let sum = a.iter().try_fold(0, |acc, x| Ok(acc + x.parse::<i64>()?));
+                /// assert!(sum.is_err());
+                /// ```
+                ///
+                /// Short-circuiting:
+                ///
+                /// ```rune
+                /// let it = [10, 20, 30, 100, 40, 50].iter();
+                ///
+                /// let sum = it.try_fold(0, |acc, x| if x < 100 { Ok(acc + x) } else { Err(x) });
+                /// assert_eq!(sum, Err(100));
+                ///
+                /// // Because it short-circuited, the remaining elements are still
+                /// // available through the iterator.
+                /// assert_eq!(it.collect::<Vec>(), [40, 50]);
+                /// ```
+            })?;
+
+        t.function("try_for_each")?
+            .argument_types::<(Value, Function)>()?
+            .return_type::<Result<(), Value>>()?
+            .docs(docstring! {
+                /// An iterator method that applies a fallible function to each item in
+                /// the iterator, stopping at the first error and returning that error.
+                ///
+                /// The closure should return `Ok` for each element it successfully
+                /// processed. Once it returns `Err`, the error is returned and no further
+                /// elements are consumed from the iterator.
+                ///
+                /// # Examples
+                ///
+                /// ```rune
+                /// let seen = [];
+                ///
+                /// let result = ["1", "2", "x", "4"].iter().try_for_each(|s| {
+                ///     seen.push(s.parse::<i64>()?);
+                ///     Ok(())
+                /// });
+                ///
+                /// assert!(result.is_err());
+                /// assert_eq!(seen, [1, 2]);
+                /// ```
+            })?;
+
         t.function("reduce")?
             .argument_types::<(Value, Function)>()?
             .return_type::<Option<Value>>()?
@@ -1531,6 +1663,28 @@ pub fn module() -> Result<Module, ContextError> {
                 /// ```
             })?;
 
+        t.function(Params::new("collect", [Result::<Value, Value>::HASH]))?
+            .return_type::<Result<Vec, Value>>()?
+            .docs(docstring! {
+                /// Collect an iterator of [`Result`] values into a [`Result`] containing a
+                /// [`Vec`].
+                ///
+                /// Iteration stops at the first `Err` encountered, which is then returned.
+                /// Otherwise all `Ok` values are collected and returned as `Ok(Vec)`.
+                ///
+                /// # Examples
+                ///
+                /// ```rune
+                /// let a = ["1", "2", "3"];
+                /// let numbers = a.iter().map(|s| s.parse::<i64>()).collect::<Result>();
+                /// assert_eq!(numbers, Ok([1, 2, 3]));
+                ///
+                /// let a = ["1", "x", "3"];
+                /// let numbers = a.iter().map(|s| s.parse::<i64>()).collect::<Result>();
+                /// assert!(numbers.is_err());
+                /// ```
+            })?;
+
         t.function(Params::new("collect", [String::HASH]))?
             .return_type::<String>()?
             .docs(docstring! {
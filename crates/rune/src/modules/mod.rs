@@ -15,6 +15,7 @@
 mod inner_macros;
 
 pub mod any;
+pub mod budget;
 pub mod bytes;
 #[cfg(feature = "capture-io")]
 pub mod capture_io;
@@ -190,6 +190,8 @@ pub fn module() -> Result<Module, ContextError> {
 
         m.function_meta(ControlFlow::debug_fmt__meta)?;
 
+        m.function_meta(ControlFlow::try___meta)?;
+
         m.function_meta(ControlFlow::clone__meta)?;
         m.implement_trait::<ControlFlow>(item!(::std::clone::Clone))?;
     }
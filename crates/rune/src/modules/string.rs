@@ -60,6 +60,7 @@ pub fn module() -> Result<Module, ContextError> {
     m.function_meta(trim)?;
     m.function_meta(trim_end)?;
     m.function_meta(replace)?;
+    m.function_meta(repeat)?;
     m.function_meta(is_empty)?;
     m.function_meta(chars)?;
     m.function_meta(get)?;
@@ -1093,6 +1094,36 @@ fn replace(a: &str, from: &str, to: &str) -> VmResult<String> {
     VmResult::Ok(vm_try!(String::try_from(a.replace(from, to))))
 }
 
+/// Creates a new [`String`] by repeating a string `n` times.
+///
+/// This function can be called in constant contexts.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```rune
+/// assert_eq!("abababab", "ab".repeat(4));
+/// assert_eq!("", "ab".repeat(0));
+/// ```
+///
+/// Used in a constant:
+///
+/// ```rune
+/// const LINE = String::repeat("-", 3);
+/// assert_eq!(LINE, "---");
+/// ```
+#[rune::function(instance, const)]
+fn repeat(s: &str, n: usize) -> VmResult<String> {
+    let mut out = vm_try!(String::try_with_capacity(s.len().saturating_mul(n)));
+
+    for _ in 0..n {
+        vm_try!(out.try_push_str(s));
+    }
+
+    VmResult::Ok(out)
+}
+
 /// Returns an iterator over the [`char`]s of a string slice.
 ///
 /// As a string slice consists of valid UTF-8, we can iterate through a string
@@ -3,6 +3,7 @@
 use core::cmp::Ordering;
 
 use crate as rune;
+use crate::alloc;
 use crate::runtime::slice::Iter;
 use crate::runtime::{
     EnvProtocolCaller, Formatter, Hasher, OwnedTuple, Ref, Tuple, Value, Vec, VmResult,
@@ -52,6 +53,8 @@ pub fn module() -> Result<Module, ContextError> {
     m.function_meta(get)?;
     m.function_meta(iter)?;
     m.function_meta(into_iter)?;
+    m.function_meta(to_vec)?;
+    m.function_meta(from_vec)?;
 
     m.function_meta(partial_eq__meta)?;
     m.implement_trait::<OwnedTuple>(rune::item!(::std::cmp::PartialEq))?;
@@ -156,6 +159,42 @@ fn into_iter(this: Ref<Tuple>) -> Iter {
     Iter::new(Ref::map(this, |tuple| &**tuple))
 }
 
+/// Copy the elements of the tuple into a new vector.
+///
+/// # Examples
+///
+/// ```rune
+/// let tuple = (1, "two", 3.0);
+/// let vec = tuple.to_vec();
+/// vec.push(4);
+///
+/// assert_eq!(vec, [1, "two", 3.0, 4]);
+/// assert_eq!(tuple, (1, "two", 3.0));
+/// assert_eq!(().to_vec(), []);
+/// ```
+#[rune::function(instance)]
+fn to_vec(this: &Tuple) -> VmResult<Vec> {
+    VmResult::Ok(Vec::from(vm_try!(alloc::Vec::try_from(&**this))))
+}
+
+/// Construct a tuple by copying the elements of a vector.
+///
+/// # Examples
+///
+/// ```rune
+/// let vec = [1, 2, 3];
+/// let tuple = Tuple::from_vec(vec);
+///
+/// assert_eq!(tuple, (1, 2, 3));
+/// assert_eq!(tuple.len(), 3);
+/// assert_eq!(Tuple::from_vec([]), ());
+/// ```
+#[rune::function(free, path = OwnedTuple::from_vec)]
+fn from_vec(vec: &Vec) -> VmResult<OwnedTuple> {
+    let values = vm_try!(alloc::Vec::try_from(vec.as_slice()));
+    VmResult::Ok(vm_try!(OwnedTuple::try_from(values)))
+}
+
 /// Perform a partial equality check with this tuple.
 ///
 /// This can take any argument which can be converted into an iterator using
@@ -1,3 +1,6 @@
+#[cfg(feature = "unit-file")]
+pub(crate) use self::serde::Serde;
+
 pub(crate) mod ordering {
     use core::cmp::Ordering;
 
@@ -26,3 +29,40 @@ pub(crate) mod ordering {
         }
     }
 }
+
+#[cfg(feature = "unit-file")]
+mod serde {
+    use musli::de::Decoder;
+    use musli::en::Encoder;
+    use musli::{Decode, Encode};
+    use serde::{Deserialize, Serialize};
+
+    /// Adapter to encode and decode serde types with musli.
+    pub(crate) struct Serde<T>(pub(crate) T);
+
+    impl<M, T> Encode<M> for Serde<T>
+    where
+        T: Serialize,
+    {
+        #[inline]
+        fn encode<E>(&self, cx: &E::Cx, encoder: E) -> Result<E::Ok, E::Error>
+        where
+            E: Encoder<Mode = M>,
+        {
+            musli::serde::encode(&self.0, cx, encoder)
+        }
+    }
+
+    impl<'de, M, T> Decode<'de, M> for Serde<T>
+    where
+        T: Deserialize<'de>,
+    {
+        #[inline]
+        fn decode<D>(cx: &D::Cx, decoder: D) -> Result<Self, D::Error>
+        where
+            D: Decoder<'de, Mode = M>,
+        {
+            Ok(Serde(musli::serde::decode(cx, decoder)?))
+        }
+    }
+}
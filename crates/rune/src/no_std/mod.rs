@@ -9,6 +9,12 @@ pub struct RawEnv {
     pub(crate) context: Option<NonNull<()>>,
     pub(crate) unit: Option<NonNull<()>>,
     pub(crate) diagnostics: Option<NonNull<()>>,
+    pub(crate) max_call_frames: usize,
+    pub(crate) max_stack: usize,
+    pub(crate) used_call_frames: usize,
+    pub(crate) used_stack: usize,
+    pub(crate) data: Option<NonNull<()>>,
+    pub(crate) tracer: Option<NonNull<()>>,
 }
 
 impl RawEnv {
@@ -18,6 +24,12 @@ impl RawEnv {
             context: None,
             unit: None,
             diagnostics: None,
+            max_call_frames: usize::MAX,
+            max_stack: usize::MAX,
+            used_call_frames: 0,
+            used_stack: 0,
+            data: None,
+            tracer: None,
         }
     }
 }
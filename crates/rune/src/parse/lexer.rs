@@ -112,6 +112,7 @@ impl<'a> Lexer<'a> {
                 source_id: self.source_id,
                 escaped: false,
                 wrapped: false,
+                raw: None,
             })),
             span: docstring_span,
         })?;
@@ -424,6 +425,49 @@ impl<'a> Lexer<'a> {
                 source_id: self.source_id,
                 escaped,
                 wrapped: true,
+                raw: None,
+            })),
+            span: self.iter.span_to_pos(start),
+        }))
+    }
+
+    /// Consume a raw string literal delimited by the given number of `#`,
+    /// where the opening delimiter has already been consumed.
+    fn next_raw_str(
+        &mut self,
+        start: usize,
+        hashes: u32,
+        error_kind: impl FnOnce() -> ErrorKind,
+        kind: impl FnOnce(ast::StrSource) -> ast::Kind,
+    ) -> compile::Result<Option<ast::Token>> {
+        let open = self.iter.span_to_pos(start);
+
+        'outer: loop {
+            let Some(c) = self.iter.next() else {
+                return Err(compile::Error::new(open, error_kind()));
+            };
+
+            if c != '"' {
+                continue;
+            }
+
+            for _ in 0..hashes {
+                if self.iter.peek() != Some('#') {
+                    continue 'outer;
+                }
+
+                self.iter.next();
+            }
+
+            break;
+        }
+
+        Ok(Some(ast::Token {
+            kind: kind(ast::StrSource::Text(ast::StrText {
+                source_id: self.source_id,
+                escaped: false,
+                wrapped: true,
+                raw: Some(hashes),
             })),
             span: self.iter.span_to_pos(start),
         }))
@@ -505,6 +549,7 @@ impl<'a> Lexer<'a> {
                                 source_id: self.source_id,
                                 escaped: take(&mut escaped),
                                 wrapped: false,
+                                raw: None,
                             })),
                             span,
                         })?;
@@ -555,6 +600,7 @@ impl<'a> Lexer<'a> {
                                 source_id: self.source_id,
                                 escaped: take(&mut escaped),
                                 wrapped: false,
+                                raw: None,
                             })),
                             span,
                         })?;
@@ -795,6 +841,30 @@ impl<'a> Lexer<'a> {
                                 ast::Kind::ByteStr,
                             );
                         }
+                        ('b', 'r') => {
+                            if let Some(hashes) = self.iter.peek_raw_str(1) {
+                                self.iter.consume(hashes as usize + 2);
+
+                                return self.next_raw_str(
+                                    start,
+                                    hashes,
+                                    || ErrorKind::UnterminatedByteStrLit,
+                                    ast::Kind::ByteStr,
+                                );
+                            }
+                        }
+                        ('r', '"' | '#') => {
+                            if let Some(hashes) = self.iter.peek_raw_str(0) {
+                                self.iter.consume(hashes as usize + 1);
+
+                                return self.next_raw_str(
+                                    start,
+                                    hashes,
+                                    || ErrorKind::UnterminatedStrLit,
+                                    ast::Kind::Str,
+                                );
+                            }
+                        }
                         _ => (),
                     }
                 }
@@ -977,6 +1047,30 @@ impl<'a> SourceIter<'a> {
         it.next()
     }
 
+    /// Test if the opening delimiter of a raw string follows after skipping
+    /// `skip` characters, which is any number of `#` followed by `"`.
+    ///
+    /// Returns the number of `#` in the delimiter.
+    fn peek_raw_str(&self, skip: usize) -> Option<u32> {
+        let mut it = self.source.get(self.cursor..)?.chars().skip(skip);
+        let mut hashes = 0u32;
+
+        loop {
+            match it.next()? {
+                '#' => hashes = hashes.checked_add(1)?,
+                '"' => return Some(hashes),
+                _ => return None,
+            }
+        }
+    }
+
+    /// Consume the given number of characters.
+    fn consume(&mut self, count: usize) {
+        for _ in 0..count {
+            self.next();
+        }
+    }
+
     /// Peek the next character with position.
     fn peek_with_pos(&self) -> Option<(usize, char)> {
         self.clone().next_with_pos()
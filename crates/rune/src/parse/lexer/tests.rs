@@ -1,4 +1,5 @@
 use super::Lexer;
+use crate::ast::Spanned;
 use crate::{ast, SourceId};
 
 macro_rules! test_lexer {
@@ -108,7 +109,7 @@ fn test_label() {
         },
         ast::Token {
             span: span!(10, 19),
-            kind: ast::Kind::Str(ast::StrSource::Text(ast::StrText { source_id: SourceId::EMPTY, escaped: false, wrapped: true })),
+            kind: ast::Kind::Str(ast::StrSource::Text(ast::StrText { source_id: SourceId::EMPTY, escaped: false, wrapped: true, raw: None })),
         }
     };
 }
@@ -246,6 +247,7 @@ fn test_doc_strings() {
                 source_id: SourceId::EMPTY,
                 escaped: false,
                 wrapped: false,
+                raw: None,
             })),
             span: span!(3, 9)
         },
@@ -278,6 +280,7 @@ fn test_doc_strings() {
                 source_id: SourceId::EMPTY,
                 escaped: false,
                 wrapped: false,
+                raw: None,
             })),
             span: span!(13, 22)
         },
@@ -323,6 +326,7 @@ fn test_multiline_docstring() {
                 source_id: SourceId::EMPTY,
                 escaped: false,
                 wrapped: false,
+                raw: None,
             })),
             span: span!(3, 21)
         },
@@ -355,6 +359,7 @@ fn test_multiline_docstring() {
                 source_id: SourceId::EMPTY,
                 escaped: false,
                 wrapped: false,
+                raw: None,
             })),
             span: span!(27, 39)
         },
@@ -449,6 +454,7 @@ fn test_template_literals() {
                 source_id: SourceId::EMPTY,
                 escaped: false,
                 wrapped: false,
+                raw: None,
             })),
             span: span!(1, 5),
         },
@@ -469,6 +475,7 @@ fn test_template_literals() {
                 source_id: SourceId::EMPTY,
                 escaped: true,
                 wrapped: false,
+                raw: None,
             })),
             span: span!(11, 18),
         },
@@ -536,6 +543,7 @@ fn test_template_literals_multi() {
                 source_id: SourceId::EMPTY,
                 escaped: false,
                 wrapped: false,
+                raw: None,
             })),
             span: span!(1, 5),
         },
@@ -556,6 +564,7 @@ fn test_template_literals_multi() {
                 source_id: SourceId::EMPTY,
                 escaped: false,
                 wrapped: false,
+                raw: None,
             })),
             span: span!(11, 12),
         },
@@ -588,6 +597,7 @@ fn test_literals() {
                 source_id: SourceId::EMPTY,
                 escaped: false,
                 wrapped: true,
+                raw: None,
             })),
         },
     };
@@ -600,6 +610,7 @@ fn test_literals() {
                 source_id: SourceId::EMPTY,
                 escaped: false,
                 wrapped: true,
+                raw: None,
             })),
         },
     };
@@ -652,3 +663,95 @@ fn test_literals() {
         },
     };
 }
+
+#[test]
+fn test_raw_strings() {
+    test_lexer! {
+        "r\"a\\b\" r#\"\"a\"\"# br##\"\"#\"##",
+        ast::Token {
+            span: span!(0, 6),
+            kind: ast::Kind::Str(ast::StrSource::Text(ast::StrText {
+                escaped: false,
+                wrapped: true,
+                raw: Some(0),
+                ..
+            })),
+        },
+        ast::Token {
+            span: span!(6, 7),
+            kind: ast::Kind::Whitespace,
+        },
+        ast::Token {
+            span: span!(7, 15),
+            kind: ast::Kind::Str(ast::StrSource::Text(ast::StrText {
+                escaped: false,
+                wrapped: true,
+                raw: Some(1),
+                ..
+            })),
+        },
+        ast::Token {
+            span: span!(15, 16),
+            kind: ast::Kind::Whitespace,
+        },
+        ast::Token {
+            span: span!(16, 26),
+            kind: ast::Kind::ByteStr(ast::StrSource::Text(ast::StrText {
+                escaped: false,
+                wrapped: true,
+                raw: Some(2),
+                ..
+            })),
+        },
+    };
+}
+
+#[test]
+fn test_raw_string_like_idents() {
+    test_lexer! {
+        "r #r br",
+        ast::Token {
+            span: span!(0, 1),
+            kind: ast::Kind::Ident(..),
+        },
+        ast::Token {
+            span: span!(1, 2),
+            kind: ast::Kind::Whitespace,
+        },
+        ast::Token {
+            span: span!(2, 3),
+            kind: ast::Kind::Pound,
+        },
+        ast::Token {
+            span: span!(3, 4),
+            kind: ast::Kind::Ident(..),
+        },
+        ast::Token {
+            span: span!(4, 5),
+            kind: ast::Kind::Whitespace,
+        },
+        ast::Token {
+            span: span!(5, 7),
+            kind: ast::Kind::Ident(..),
+        },
+    };
+}
+
+#[test]
+fn test_unterminated_raw_string() {
+    let mut it = Lexer::new("let a = r#\"hello\" world", SourceId::empty(), false);
+
+    let error = loop {
+        match it.next() {
+            Ok(Some(..)) => continue,
+            Ok(None) => panic!("expected error"),
+            Err(error) => break error,
+        }
+    };
+
+    assert_eq!(error.span(), span!(8, 11));
+    assert!(matches!(
+        error.kind(),
+        crate::compile::ErrorKind::UnterminatedStrLit
+    ));
+}
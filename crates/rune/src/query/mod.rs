@@ -42,6 +42,15 @@ impl Used {
     }
 }
 
+/// An effect on the query engine which is recorded while compiling an item, so
+/// that it can be replayed when a later build reuses the compiled item.
+pub(crate) enum Effect<T = ItemId> {
+    /// The given item was queried for.
+    Query(T, Used),
+    /// The given item was marked as used.
+    Used(T),
+}
+
 /// The result of calling [Query::convert_path].
 pub(crate) struct Named<'ast> {
     /// Module named item belongs to.
@@ -132,7 +141,7 @@ pub(crate) struct BuiltInFormat {
     /// Width to fill.
     pub(crate) width: Option<NonZeroUsize>,
     /// Precision to fill.
-    pub(crate) precision: Option<NonZeroUsize>,
+    pub(crate) precision: Option<usize>,
     /// A specification of flags.
     pub(crate) flags: Option<format::Flags>,
     /// The format specification type.
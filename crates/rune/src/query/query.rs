@@ -14,15 +14,15 @@ use crate::ast::{Span, Spanned};
 use crate::compile::context::ContextMeta;
 use crate::compile::{
     self, ir, meta, CompileVisitor, Doc, DynLocation, ErrorKind, ImportStep, ItemId, ItemMeta,
-    Located, Location, MetaError, ModId, ModMeta, Names, Pool, Prelude, SourceLoader, SourceMeta,
-    UnitBuilder, Visibility, WithSpan,
+    Located, Location, MetaError, ModId, ModMeta, Names, Pool, Prelude, Recording, SimilarItem,
+    SourceLoader, SourceMeta, UnitBuilder, Visibility, WithSpan,
 };
 use crate::grammar::{Ignore, Node, Stream};
 use crate::hir;
 use crate::indexing::{self, FunctionAst, Indexed, Items};
 use crate::item::ComponentRef;
 use crate::item::IntoComponent;
-use crate::macros::Storage;
+use crate::macros::{DeclarativeMacro, Storage};
 use crate::parse::{NonZeroId, Resolve};
 #[cfg(feature = "doc")]
 use crate::runtime::Call;
@@ -31,8 +31,8 @@ use crate::shared::{Consts, Gen};
 use crate::{Context, Diagnostics, Hash, Item, ItemBuf, Options, SourceId, Sources};
 
 use super::{
-    Build, BuildEntry, BuiltInMacro, ConstFn, DeferEntry, ExpandedMacro, GenericsParameters, Named,
-    Named2, Named2Kind, Used,
+    Build, BuildEntry, BuiltInMacro, ConstFn, DeferEntry, Effect, ExpandedMacro,
+    GenericsParameters, Named, Named2, Named2Kind, Used,
 };
 
 enum ContextMatch<'this, 'm> {
@@ -63,6 +63,8 @@ pub(crate) struct QueryInner<'arena> {
     internal_macros: HashMap<NonZeroId, Arc<BuiltInMacro>>,
     /// Expanded macros.
     expanded_macros: HashMap<NonZeroId, ExpandedMacro>,
+    /// Declarative macros defined in source.
+    declarative_macros: HashMap<ItemId, Rc<DeclarativeMacro>>,
     /// Associated between `id` and `Item`. Use to look up items through
     /// `item_for` with an opaque id.
     ///
@@ -73,6 +75,9 @@ pub(crate) struct QueryInner<'arena> {
     names: Names,
     /// Queue of impl items to process.
     pub(crate) defer_queue: VecDeque<DeferEntry>,
+    /// Effects recorded while compiling the current item, if it's being
+    /// recorded.
+    recording: Option<Vec<Effect>>,
 }
 
 impl QueryInner<'_> {
@@ -226,10 +231,56 @@ impl<'a, 'arena> Query<'a, 'arena> {
 
     /// Set the given meta item as used.
     pub(crate) fn set_used(&mut self, item_meta: &ItemMeta) -> alloc::Result<()> {
+        self.record(Effect::Used(item_meta.item))?;
         self.inner.used.try_insert(item_meta.item)?;
         Ok(())
     }
 
+    /// Start recording effects on the query engine and everything added to
+    /// the unit, so that the item being compiled can be reused by a later
+    /// build.
+    pub(crate) fn begin_recording(&mut self) {
+        self.inner.recording = Some(Vec::new());
+        self.unit.begin_recording();
+    }
+
+    /// Stop recording, returning everything which was recorded.
+    pub(crate) fn finish_recording(&mut self) -> Option<(Vec<Effect>, Recording)> {
+        let effects = self.inner.recording.take();
+        let recording = self.unit.finish_recording();
+        Some((effects?, recording?))
+    }
+
+    /// Record an effect if recording is enabled.
+    fn record(&mut self, effect: Effect) -> alloc::Result<()> {
+        if let Some(recording) = &mut self.inner.recording {
+            recording.try_push(effect)?;
+        }
+
+        Ok(())
+    }
+
+    /// Replay an effect which was recorded while compiling an item in a
+    /// previous build.
+    pub(crate) fn replay(
+        &mut self,
+        span: &dyn Spanned,
+        effect: &Effect<ItemBuf>,
+    ) -> compile::Result<()> {
+        match effect {
+            Effect::Query(item, used) => {
+                let item = self.pool.alloc_item(item)?;
+                self.query_meta(span, item, *used)?;
+            }
+            Effect::Used(item) => {
+                let item = self.pool.alloc_item(item)?;
+                self.inner.used.try_insert(item)?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Insert a new macro to build.
     pub(crate) fn insert_new_macro(
         &mut self,
@@ -435,18 +486,105 @@ impl<'a, 'arena> Query<'a, 'arena> {
             return Ok(meta);
         }
 
+        let kind = self.missing_item(item, parameters)?;
+        Err(compile::Error::new(location.as_spanned(), kind))
+    }
+
+    /// Construct the error kind for an item which is missing.
+    ///
+    /// If the item is a function provided by a module which is known but not
+    /// installed, the error will indicate which module provides it.
+    pub(crate) fn missing_item(
+        &self,
+        item: ItemId,
+        parameters: &GenericsParameters,
+    ) -> compile::Result<ErrorKind> {
+        let item = self.pool.item(item);
+
+        if let Some(known) = self.context.lookup_known_item(item)? {
+            return Ok(ErrorKind::MissingKnownItem {
+                item: known.item.try_clone()?,
+                module: known.module.try_clone()?,
+            });
+        }
+
         let kind = if !parameters.parameters.is_empty() {
             ErrorKind::MissingItemParameters {
-                item: self.pool.item(item).try_to_owned()?,
+                item: item.try_to_owned()?,
                 parameters: parameters.parameters,
             }
         } else {
             ErrorKind::MissingItem {
-                item: self.pool.item(item).try_to_owned()?,
+                item: item.try_to_owned()?,
+                did_you_mean: self.similar_item(item)?,
             }
         };
 
-        Err(compile::Error::new(location.as_spanned(), kind))
+        Ok(kind)
+    }
+
+    /// Find an item with a name similar to the given missing item, which is
+    /// either a sibling of it or an import from the prelude.
+    fn similar_item(&self, item: &Item) -> alloc::Result<Option<SimilarItem>> {
+        let (Some(parent), Some(ComponentRef::Str(name))) = (item.parent(), item.last()) else {
+            return Ok(None);
+        };
+
+        let siblings = self
+            .inner
+            .names
+            .iter_components(parent)?
+            .chain(self.context.iter_components(parent)?)
+            .filter_map(|c| match c {
+                ComponentRef::Str(name) => Some(name),
+                _ => None,
+            });
+
+        if let Some(similar) = compile::similar(name, siblings)? {
+            let item = parent.extended(similar)?;
+            let is_function = self.is_function(&item);
+            return Ok(Some(SimilarItem { item, is_function }));
+        }
+
+        if parent.is_empty() {
+            if let Some(similar) = compile::similar(name, self.prelude.names())? {
+                let is_function = match self.prelude.get(similar) {
+                    Some(item) => self.is_function(item),
+                    None => false,
+                };
+
+                let item = ItemBuf::with_item([similar])?;
+                return Ok(Some(SimilarItem { item, is_function }));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Test if the given item is a function, either in the context or in the
+    /// unit being compiled.
+    fn is_function(&self, item: &Item) -> bool {
+        if let Some(mut metas) = self.context.lookup_meta(item) {
+            if metas.any(|meta| matches!(meta.kind, meta::Kind::Function { .. })) {
+                return true;
+            }
+        }
+
+        let is_meta = self.inner.meta.values().any(|meta| {
+            matches!(meta.kind, meta::Kind::Function { .. })
+                && self.pool.item(meta.item_meta.item) == item
+        });
+
+        let is_indexed = || {
+            self.inner.indexed.iter().any(|(id, entries)| {
+                self.pool.item(*id) == item
+                    && entries
+                        .iter()
+                        .any(|entry| matches!(entry.indexed, Indexed::Function(..)))
+            })
+        };
+
+        is_meta || is_indexed()
     }
 
     pub(crate) fn lookup_deprecation(&self, hash: Hash) -> Option<&str> {
@@ -491,27 +629,42 @@ impl<'a, 'arena> Query<'a, 'arena> {
     ) -> compile::Result<(ItemId, ModId)> {
         let location = Location::new(source_id, span);
 
+        // Sources mounted under a crate have the crate as their root item.
+        let item = match self.sources.crate_name(source_id) {
+            Some(name) => self.pool.alloc_item(ItemBuf::with_crate(name)?)?,
+            None => ItemId::ROOT,
+        };
+
         let module = self.pool.alloc_module(ModMeta {
             #[cfg(feature = "emit")]
             location,
-            item: ItemId::ROOT,
+            item,
             visibility: Visibility::Public,
             parent: None,
         })?;
 
         self.inner.items.try_insert(
-            ItemId::ROOT,
+            item,
             ItemMeta {
                 location,
-                item: ItemId::ROOT,
+                item,
                 visibility: Visibility::Public,
                 module,
                 impl_item: None,
             },
         )?;
 
-        self.insert_name(ItemId::ROOT).with_span(span)?;
-        Ok((ItemId::ROOT, module))
+        self.insert_name(item).with_span(span)?;
+        Ok((item, module))
+    }
+
+    /// Get the root item of the crate the given module belongs to.
+    fn crate_root(&self, mut module: ModId) -> ItemId {
+        while let Some(parent) = self.pool.module(module).parent {
+            module = parent;
+        }
+
+        self.pool.module(module).item
     }
 
     /// Inserts an item that *has* to be unique, else cause an error.
@@ -646,6 +799,11 @@ impl<'a, 'arena> Query<'a, 'arena> {
         Ok(internal_macro.clone())
     }
 
+    /// Get the declarative macro defined at the given item, if any.
+    pub(crate) fn declarative_macro_for(&self, item: ItemId) -> Option<Rc<DeclarativeMacro>> {
+        self.inner.declarative_macros.get(&item).cloned()
+    }
+
     /// Get the constant function associated with the opaque.
     pub(crate) fn const_fn_for(&self, id: ItemId) -> anyhow::Result<Rc<ConstFn<'a>>> {
         let Some(const_fn) = self.inner.const_fns.get(&id) else {
@@ -741,6 +899,26 @@ impl<'a, 'arena> Query<'a, 'arena> {
         Ok(())
     }
 
+    /// Index a declarative macro.
+    #[tracing::instrument(skip_all)]
+    pub(crate) fn index_macro(
+        &mut self,
+        item_meta: ItemMeta,
+        declarative: DeclarativeMacro,
+    ) -> compile::Result<()> {
+        tracing::trace!(item = ?self.pool.item(item_meta.item));
+
+        self.index(indexing::Entry {
+            item_meta,
+            indexed: Indexed::Macro,
+        })?;
+
+        self.inner
+            .declarative_macros
+            .try_insert(item_meta.item, Rc::new(declarative))?;
+        Ok(())
+    }
+
     /// Add a new enum item.
     #[tracing::instrument(skip_all)]
     pub(crate) fn index_enum(&mut self, item_meta: ItemMeta) -> compile::Result<()> {
@@ -866,6 +1044,8 @@ impl<'a, 'arena> Query<'a, 'arena> {
         item: ItemId,
         used: Used,
     ) -> compile::Result<Option<meta::Meta>> {
+        self.record(Effect::Query(item, used))?;
+
         if let Some(meta) = self.inner.meta.get(&(item, Hash::EMPTY)) {
             tracing::trace!(item = ?item, meta = ?meta, "cached");
             // Ensure that the given item is not indexed, cause if it is
@@ -976,7 +1156,7 @@ impl<'a, 'arena> Query<'a, 'arena> {
                     impl_item.item
                 }
                 ast::PathSegment::SelfValue(..) => self.pool.module(module).item,
-                ast::PathSegment::Crate(..) => ItemId::ROOT,
+                ast::PathSegment::Crate(..) => self.crate_root(module),
                 ast::PathSegment::Generics(..) => {
                     return Err(compile::Error::new(
                         segment.span(),
@@ -1223,7 +1403,7 @@ impl<'a, 'arena> Query<'a, 'arena> {
                 let item = self.pool.module(module).item;
                 (item, false)
             }
-            (None, K![crate]) => (ItemId::ROOT, false),
+            (None, K![crate]) => (self.crate_root(module), false),
             (_, PathGenerics) => {
                 return Err(compile::Error::new(first, ErrorKind::UnsupportedGenerics));
             }
@@ -1512,6 +1692,8 @@ impl<'a, 'arena> Query<'a, 'arena> {
             return Ok(None);
         };
 
+        self.record(Effect::Query(item, used))?;
+
         self.check_access_to(
             span,
             module,
@@ -1825,6 +2007,7 @@ impl<'a, 'arena> Query<'a, 'arena> {
                 meta::Kind::Import(import.entry)
             }
             Indexed::Module => meta::Kind::Module,
+            Indexed::Macro => meta::Kind::Macro,
         };
 
         let source = SourceMeta {
@@ -1989,6 +2172,13 @@ impl<'a, 'arena> Query<'a, 'arena> {
             }
         }
 
+        // Crates mounted from sources take precedence over the prelude.
+        let krate = ItemBuf::with_crate(&local_str)?;
+
+        if self.inner.names.contains(&krate)? {
+            return Ok(self.pool.alloc_item(krate)?);
+        }
+
         if let Some(item) = self.prelude.get(&local_str) {
             return Ok(self.pool.alloc_item(item)?);
         }
@@ -3,6 +3,13 @@ use core::fmt;
 use core::mem::ManuallyDrop;
 use core::ptr::NonNull;
 
+#[cfg(feature = "debug-access")]
+use crate::ast::Span;
+#[cfg(feature = "debug-access")]
+use crate::SourceId;
+
+#[cfg(feature = "debug-access")]
+use super::debug_access::Escaped;
 use super::TypeInfo;
 
 /// Test if exclusively held.
@@ -24,32 +31,60 @@ const MASK: usize = EXCLUSIVE | MOVED;
 #[non_exhaustive]
 pub struct AccessError {
     kind: AccessErrorKind,
+    #[cfg(feature = "debug-access")]
+    escaped: Option<Escaped>,
 }
 
 impl AccessError {
     #[inline]
     pub(crate) const fn not_owned(type_info: TypeInfo) -> Self {
-        Self {
-            kind: AccessErrorKind::NotAccessibleOwned(type_info),
-        }
+        Self::new(AccessErrorKind::NotAccessibleOwned(type_info))
     }
 
     #[inline]
     pub(crate) const fn new(kind: AccessErrorKind) -> Self {
-        Self { kind }
+        Self {
+            kind,
+            #[cfg(feature = "debug-access")]
+            escaped: None,
+        }
+    }
+
+    /// Attach information on a reference which was accessed after it escaped
+    /// its scope.
+    #[cfg(feature = "debug-access")]
+    pub(crate) fn with_escaped(self, escaped: Escaped) -> Self {
+        Self {
+            escaped: Some(escaped),
+            ..self
+        }
+    }
+
+    /// Get the source location where a reference which escaped its scope was
+    /// taken, if known.
+    #[cfg(feature = "debug-access")]
+    pub(crate) fn escaped_at(&self) -> Option<(SourceId, Span)> {
+        self.escaped.as_ref()?.provenance()?.location()
     }
 }
 
 impl fmt::Display for AccessError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match &self.kind {
-            AccessErrorKind::NotAccessibleRef(s) => write!(f, "Cannot read, value is {s}"),
-            AccessErrorKind::NotAccessibleMut(s) => write!(f, "Cannot write, value is {s}"),
-            AccessErrorKind::NotAccessibleTake(s) => write!(f, "Cannot take, value is {s}"),
+            AccessErrorKind::NotAccessibleRef(s) => write!(f, "Cannot read, value is {s}")?,
+            AccessErrorKind::NotAccessibleMut(s) => write!(f, "Cannot write, value is {s}")?,
+            AccessErrorKind::NotAccessibleTake(s) => write!(f, "Cannot take, value is {s}")?,
             AccessErrorKind::NotAccessibleOwned(type_info) => {
-                write!(f, "Cannot use owned operations for {type_info}")
+                write!(f, "Cannot use owned operations for {type_info}")?
             }
         }
+
+        #[cfg(feature = "debug-access")]
+        if let Some(escaped) = &self.escaped {
+            write!(f, "; {escaped}")?;
+        }
+
+        Ok(())
     }
 }
 
@@ -8,6 +8,8 @@ use crate::alloc::alloc::Global;
 use crate::alloc::{self, Box};
 use crate::{Any, Hash};
 
+#[cfg(feature = "debug-access")]
+use super::debug_access::{self, Escaped, Provenance};
 use super::{
     Access, AccessError, AnyTypeInfo, BorrowMut, BorrowRef, Mut, RawAccessGuard, RawAnyGuard, Ref,
     RefVtable, Snapshot, TypeInfo, VmErrorKind,
@@ -134,6 +136,8 @@ impl AnyObj {
             access: Access::new(),
             count: Cell::new(1),
             vtable,
+            #[cfg(feature = "debug-access")]
+            provenance: Cell::new(None),
             data,
         };
 
@@ -166,6 +170,8 @@ impl AnyObj {
             access: Access::new(),
             count: Cell::new(1),
             vtable,
+            #[cfg(feature = "debug-access")]
+            provenance: Cell::new(None),
             data: NonNull::new_unchecked(data.cast_mut()),
         };
 
@@ -198,6 +204,8 @@ impl AnyObj {
             access: Access::new(),
             count: Cell::new(1),
             vtable,
+            #[cfg(feature = "debug-access")]
+            provenance: Cell::new(None),
             data: NonNull::new_unchecked(data),
         };
 
@@ -377,7 +385,12 @@ impl AnyObj {
 
         // SAFETY: We've checked for the appropriate type just above.
         unsafe {
-            let guard = self.shared.as_ref().access.shared()?;
+            let guard = self
+                .shared
+                .as_ref()
+                .access
+                .shared()
+                .map_err(|error| self.access_error(error))?;
             let data = vtable.as_ptr(self.shared);
             Ok(BorrowRef::new(data, guard.into_raw()))
         }
@@ -402,7 +415,12 @@ impl AnyObj {
 
         // SAFETY: We've checked for the appropriate type just above.
         unsafe {
-            let guard = self.shared.as_ref().access.shared()?;
+            let guard = self
+                .shared
+                .as_ref()
+                .access
+                .shared()
+                .map_err(|error| self.access_error(error))?;
             let data = vtable.as_ptr(self.shared);
             Ok(Some(BorrowRef::new(data, guard.into_raw())))
         }
@@ -427,7 +445,12 @@ impl AnyObj {
 
         // SAFETY: We've checked for the appropriate type just above.
         unsafe {
-            let guard = self.shared.as_ref().access.exclusive()?;
+            let guard = self
+                .shared
+                .as_ref()
+                .access
+                .exclusive()
+                .map_err(|error| self.access_error(error))?;
             let data = vtable.as_ptr(self.shared);
             Ok(Some(BorrowMut::new(data, guard.into_raw())))
         }
@@ -456,7 +479,12 @@ impl AnyObj {
 
         // SAFETY: We've checked for the appropriate type just above.
         unsafe {
-            let guard = self.shared.as_ref().access.exclusive()?;
+            let guard = self
+                .shared
+                .as_ref()
+                .access
+                .exclusive()
+                .map_err(|error| self.access_error(error))?;
             let data = vtable.as_ptr(self.shared);
             Ok(BorrowMut::new(data, guard.into_raw()))
         }
@@ -481,7 +509,13 @@ impl AnyObj {
 
         // SAFETY: We've checked for the appropriate type just above.
         unsafe {
-            let guard = self.shared.as_ref().access.shared()?.into_raw();
+            let guard = self
+                .shared
+                .as_ref()
+                .access
+                .shared()
+                .map_err(|error| self.access_error(error))?
+                .into_raw();
             let this = ManuallyDrop::new(self);
 
             let data = vtable.as_ptr(this.shared);
@@ -520,7 +554,13 @@ impl AnyObj {
 
         // SAFETY: We've checked for the appropriate type just above.
         unsafe {
-            let guard = self.shared.as_ref().access.exclusive()?.into_raw();
+            let guard = self
+                .shared
+                .as_ref()
+                .access
+                .exclusive()
+                .map_err(|error| self.access_error(error))?
+                .into_raw();
             let this = ManuallyDrop::new(self);
 
             let data = vtable.as_ptr(this.shared);
@@ -555,6 +595,30 @@ impl AnyObj {
         (self, guard)
     }
 
+    /// Enrich an access error raised for a reference which has escaped its
+    /// scope with where it was taken.
+    #[cfg(feature = "debug-access")]
+    fn access_error(&self, error: AccessError) -> AccessError {
+        // Safety: Since we have a reference to this shared, we know that the
+        // inner is available.
+        let shared = unsafe { self.shared.as_ref() };
+
+        if matches!(shared.vtable.kind, Kind::Own) || !shared.access.is_taken() {
+            return error;
+        }
+
+        error.with_escaped(Escaped::new(
+            shared.vtable.type_info(),
+            shared.provenance.get(),
+        ))
+    }
+
+    #[cfg(not(feature = "debug-access"))]
+    #[inline(always)]
+    fn access_error(&self, error: AccessError) -> AccessError {
+        error
+    }
+
     /// Test if the value is sharable.
     pub(crate) fn is_readable(&self) -> bool {
         // Safety: Since we have a reference to this shared, we know that the
@@ -589,6 +653,12 @@ impl AnyObj {
     pub(crate) fn type_info(&self) -> TypeInfo {
         TypeInfo::any_type_info(vtable(self).type_info)
     }
+
+    /// Test if two objects refer to the same underlying value.
+    #[inline]
+    pub(crate) fn ptr_eq(&self, other: &Self) -> bool {
+        ptr::eq(self.shared.as_ptr(), other.shared.as_ptr())
+    }
 }
 
 impl Clone for AnyObj {
@@ -698,6 +768,9 @@ struct Shared<T = ()> {
     count: Cell<usize>,
     /// Vtable of the shared value.
     vtable: &'static Vtable,
+    /// Where a reference was last taken by the virtual machine.
+    #[cfg(feature = "debug-access")]
+    provenance: Cell<Option<Provenance>>,
     /// Data of the shared reference.
     data: T,
 }
@@ -719,6 +792,35 @@ impl Shared {
         }
 
         count_ref.set(count + 1);
+
+        #[cfg(feature = "debug-access")]
+        Shared::record(this);
+    }
+
+    /// Record where a reference is being taken if the virtual machine is
+    /// executing.
+    ///
+    /// Copies made after the reference has escaped its scope are not recorded,
+    /// so that the recorded location is the last one where it was taken while
+    /// still being valid.
+    #[cfg(feature = "debug-access")]
+    unsafe fn record(this: NonNull<Self>) {
+        let vtable = *addr_of!((*this.as_ptr()).vtable);
+
+        if matches!(vtable.kind, Kind::Own) {
+            return;
+        }
+
+        let access = &*addr_of!((*this.as_ptr()).access);
+
+        if access.is_taken() {
+            return;
+        }
+
+        if let Some(provenance) = debug_access::current() {
+            let provenance_ref = &*addr_of!((*this.as_ptr()).provenance);
+            provenance_ref.set(Some(provenance));
+        }
     }
 
     /// Decrement the reference count in inner, and free the underlying data if
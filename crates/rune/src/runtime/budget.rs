@@ -5,6 +5,21 @@
 //!
 //! By default the budget is disabled, but can be enabled by wrapping your
 //! function call in [with].
+//!
+//! There are two separate budgets which can be applied:
+//! * An instruction budget, which limits the number of instructions executed
+//!   and is set up using [with].
+//! * An allocation budget, which limits the number of bytes which may be
+//!   allocated and is set up using [`limit::with`]. Errors raised by the
+//!   virtual machine when it is exceeded can be identified with
+//!   [`VmError::is_allocation_budget_exceeded`].
+//!
+//! How much of the instruction budget has been used can be queried with
+//! [remaining] and [consumed] while executing, and through
+//! [`Budget::with_usage`] once the budgeted value has completed.
+//!
+//! [`limit::with`]: crate::alloc::limit::with
+//! [`VmError::is_allocation_budget_exceeded`]: crate::runtime::VmError::is_allocation_budget_exceeded
 
 #[cfg_attr(feature = "std", path = "budget/std.rs")]
 mod no_std;
@@ -26,6 +41,8 @@ use pin_project::pin_project;
 pub struct Budget<T> {
     /// Instruction budget.
     budget: usize,
+    /// The budget which was initially assigned.
+    limit: usize,
     /// The thing being budgeted.
     #[pin]
     value: T,
@@ -85,7 +102,119 @@ pub struct Budget<T> {
 /// ```
 pub fn with<T>(budget: usize, value: T) -> Budget<T> {
     tracing::trace!(?budget);
-    Budget { budget, value }
+
+    Budget {
+        budget,
+        limit: budget,
+        value,
+    }
+}
+
+/// Get the remaining instruction budget, or `None` if no budget is in effect.
+///
+/// # Examples
+///
+/// ```
+/// use rune::runtime::budget;
+///
+/// assert_eq!(budget::remaining(), None);
+///
+/// let f = budget::with(10, || {
+///     let mut budget = budget::acquire();
+///     assert!(budget.take());
+///     drop(budget);
+///     budget::remaining()
+/// });
+///
+/// assert_eq!(f.call(), Some(9));
+/// ```
+pub fn remaining() -> Option<usize> {
+    match self::no_std::rune_budget_get() {
+        usize::MAX => None,
+        budget => Some(budget),
+    }
+}
+
+/// Get the number of instructions consumed from the budget currently in
+/// effect. This is always zero if no budget is in effect.
+///
+/// # Examples
+///
+/// ```
+/// use rune::runtime::budget;
+///
+/// let f = budget::with(10, || {
+///     let mut budget = budget::acquire();
+///     assert!(budget.take());
+///     assert!(budget.take());
+///     drop(budget);
+///     budget::consumed()
+/// });
+///
+/// assert_eq!(f.call(), 2);
+/// assert_eq!(budget::consumed(), 0);
+/// ```
+pub fn consumed() -> usize {
+    let limit = self::no_std::rune_budget_limit_get();
+
+    if limit == usize::MAX {
+        return 0;
+    }
+
+    limit.saturating_sub(self::no_std::rune_budget_get())
+}
+
+/// Split off half of the instruction budget currently in effect, returning
+/// the part which was split off or `None` if no budget is in effect.
+///
+/// This is intended for native functions which start new executions, like
+/// spawned tasks, so that they can be given a part of the budget of their
+/// caller instead of running unbudgeted.
+///
+/// # Examples
+///
+/// ```
+/// use rune::runtime::budget;
+///
+/// assert_eq!(budget::split(), None);
+///
+/// let f = budget::with(9, || {
+///     let split = budget::split();
+///     (split, budget::remaining())
+/// });
+///
+/// assert_eq!(f.call(), (Some(4), Some(5)));
+/// ```
+pub fn split() -> Option<usize> {
+    let budget = self::no_std::rune_budget_get();
+
+    if budget == usize::MAX {
+        return None;
+    }
+
+    let split = budget / 2;
+    self::no_std::rune_budget_replace(budget - split);
+    Some(split)
+}
+
+/// Take a single instruction from the budget currently in effect.
+///
+/// Unlike [`acquire`], this operates directly on the budget, which is suitable
+/// for infrequent checks outside of the virtual machine.
+#[inline]
+pub(crate) fn take() -> bool {
+    let budget = self::no_std::rune_budget_get();
+
+    if budget == usize::MAX {
+        return true;
+    }
+
+    if budget == 0 {
+        return false;
+    }
+
+    self::no_std::rune_budget_replace(budget - 1);
+    true
 }
 
 /// Acquire the current budget.
@@ -93,36 +222,249 @@ pub fn with<T>(budget: usize, value: T) -> Budget<T> {
 /// Use [`BudgetGuard::take`] to take permites from the returned budget.
 #[inline(never)]
 pub fn acquire() -> BudgetGuard {
-    BudgetGuard(self::no_std::rune_budget_replace(usize::MAX))
+    let budget = self::no_std::rune_budget_get();
+    BudgetGuard {
+        budget,
+        base: budget,
+    }
 }
 
 /// A locally acquired budget.
 ///
 /// This guard is acquired by calling [`take`] and can be used to take permits.
+/// Permits are taken from a local copy of the budget, which is written back
+/// when the guard is dropped. Anything else which takes from the budget in
+/// the meantime, like a nested virtual machine, is accounted for at the same
+/// time.
 ///
 /// [`take`]: BudgetGuard::take
-#[repr(transparent)]
-pub struct BudgetGuard(usize);
+pub struct BudgetGuard {
+    /// The local budget.
+    budget: usize,
+    /// The budget when it was last synchronized.
+    base: usize,
+}
 
 impl BudgetGuard {
     /// Take a ticker from the budget.
     pub fn take(&mut self) -> bool {
-        if self.0 == usize::MAX {
+        if self.budget == usize::MAX {
             return true;
         }
 
-        if self.0 == 0 {
+        if self.budget == 0 {
             return false;
         }
 
-        self.0 -= 1;
+        self.budget -= 1;
         true
     }
+
+    /// Synchronize the local budget with the budget currently in effect.
+    ///
+    /// This is called before handing control to code which might observe or
+    /// take from the budget, like native functions.
+    pub(crate) fn sync(&mut self) {
+        let consumed = self.base - self.budget;
+        let current = self::no_std::rune_budget_get();
+
+        if consumed == 0 || current == usize::MAX {
+            self.budget = current;
+        } else {
+            self.budget = current.saturating_sub(consumed);
+            self::no_std::rune_budget_replace(self.budget);
+        }
+
+        self.base = self.budget;
+    }
 }
 
 impl Drop for BudgetGuard {
     fn drop(&mut self) {
-        let _ = self::no_std::rune_budget_replace(self.0);
+        if self.base != self.budget {
+            self.sync();
+        }
+    }
+}
+
+/// Guard which restores the budget which was previously in effect.
+struct Restore {
+    budget: usize,
+    limit: usize,
+}
+
+impl Restore {
+    fn replace(budget: usize, limit: usize) -> Self {
+        Self {
+            budget: self::no_std::rune_budget_replace(budget),
+            limit: self::no_std::rune_budget_limit_replace(limit),
+        }
+    }
+}
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        let _ = self::no_std::rune_budget_replace(self.budget);
+        let _ = self::no_std::rune_budget_limit_replace(self.limit);
+    }
+}
+
+impl<T> Budget<T> {
+    /// Get the remaining budget.
+    ///
+    /// When budgeting a future, this is updated every time the future is
+    /// polled, so it can be used to inspect how much budget remains once it
+    /// has completed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::pin::pin;
+    /// use std::future::poll_fn;
+    /// use std::task::Poll;
+    ///
+    /// use rune::runtime::budget;
+    ///
+    /// # futures_executor::block_on(async {
+    /// let mut future = pin!(budget::with(10, poll_fn(|_| {
+    ///     let mut budget = budget::acquire();
+    ///     assert!(budget.take());
+    ///     Poll::Ready(())
+    /// })));
+    ///
+    /// future.as_mut().await;
+    /// assert_eq!(future.remaining(), 9);
+    /// assert_eq!(future.consumed(), 1);
+    /// # });
+    /// ```
+    pub fn remaining(&self) -> usize {
+        self.budget
+    }
+
+    /// Get the amount of budget which has been consumed.
+    ///
+    /// See [`Budget::remaining`].
+    pub fn consumed(&self) -> usize {
+        self.limit.saturating_sub(self.budget)
+    }
+
+    /// Produce the [`Usage`] of the budget alongside the output of the
+    /// budgeted value once it completes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::runtime::budget;
+    ///
+    /// let f = budget::with(10, || {
+    ///     let mut budget = budget::acquire();
+    ///     assert!(budget.take());
+    ///     assert!(budget.take());
+    ///     42
+    /// });
+    ///
+    /// let (output, usage) = f.with_usage().call();
+    /// assert_eq!(output, 42);
+    /// assert_eq!(usage.remaining(), 8);
+    /// assert_eq!(usage.consumed(), 2);
+    /// ```
+    pub fn with_usage(self) -> WithUsage<T> {
+        WithUsage { budget: self }
+    }
+}
+
+/// The usage of a budget after the budgeted value has completed.
+///
+/// See [`Budget::with_usage`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Usage {
+    limit: usize,
+    remaining: usize,
+}
+
+impl Usage {
+    /// Get the budget which was initially assigned.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Get the remaining budget.
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    /// Get the amount of budget which was consumed.
+    pub fn consumed(&self) -> usize {
+        self.limit.saturating_sub(self.remaining)
+    }
+}
+
+/// Wrapper for something being budgeted which produces the [`Usage`] of the
+/// budget alongside its output.
+///
+/// See [`Budget::with_usage`].
+#[pin_project]
+pub struct WithUsage<T> {
+    #[pin]
+    budget: Budget<T>,
+}
+
+impl<T> WithUsage<T>
+where
+    T: Callable,
+{
+    /// Call the budgeted function.
+    pub fn call(self) -> (T::Output, Usage) {
+        Callable::call(self)
+    }
+}
+
+impl<T> Callable for WithUsage<T>
+where
+    T: Callable,
+{
+    type Output = (T::Output, Usage);
+
+    #[inline]
+    fn call(self) -> Self::Output {
+        let Budget {
+            budget,
+            limit,
+            value,
+        } = self.budget;
+
+        let _guard = Restore::replace(budget, limit);
+        let output = value.call();
+
+        let usage = Usage {
+            limit,
+            remaining: self::no_std::rune_budget_get(),
+        };
+
+        (output, usage)
+    }
+}
+
+impl<T> Future for WithUsage<T>
+where
+    T: Future,
+{
+    type Output = (T::Output, Usage);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut budget = self.project().budget;
+
+        let Poll::Ready(output) = budget.as_mut().poll(cx) else {
+            return Poll::Pending;
+        };
+
+        let usage = Usage {
+            limit: budget.limit,
+            remaining: budget.budget,
+        };
+
+        Poll::Ready((output, usage))
     }
 }
 
@@ -144,7 +486,7 @@ where
 
     #[inline]
     fn call(self) -> Self::Output {
-        let _guard = BudgetGuard(self::no_std::rune_budget_replace(self.budget));
+        let _guard = Restore::replace(self.budget, self.limit);
         self.value.call()
     }
 }
@@ -158,7 +500,7 @@ where
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let this = self.project();
 
-        let _guard = BudgetGuard(self::no_std::rune_budget_replace(*this.budget));
+        let _guard = Restore::replace(*this.budget, *this.limit);
         let poll = this.value.poll(cx);
         *this.budget = self::no_std::rune_budget_get();
         poll
@@ -1,3 +1,5 @@
+use core::sync::atomic::{AtomicUsize, Ordering};
+
 // In no-std environments, the implementor must define these functions.
 //
 // Normally these make use of thread-local storage, but if you want them to be
@@ -23,3 +25,16 @@ pub(super) fn rune_budget_replace(value: usize) -> usize {
     // implemented this correctly.
     unsafe { __rune_budget_replace(value) }
 }
+
+// The limit is only used to report how much of the budget has been consumed,
+// so rather than requiring implementors to define additional functions it is
+// kept in static storage which assumes a singlethreaded environment.
+static LIMIT: AtomicUsize = AtomicUsize::new(usize::MAX);
+
+pub(super) fn rune_budget_limit_get() -> usize {
+    LIMIT.load(Ordering::Relaxed)
+}
+
+pub(super) fn rune_budget_limit_replace(value: usize) -> usize {
+    LIMIT.swap(value, Ordering::Relaxed)
+}
@@ -1,6 +1,7 @@
 use core::cell::Cell;
 
 std::thread_local!(static BUDGET: Cell<usize> = const { Cell::new(usize::MAX) });
+std::thread_local!(static LIMIT: Cell<usize> = const { Cell::new(usize::MAX) });
 
 pub(super) fn rune_budget_get() -> usize {
     BUDGET.with(|tls| tls.get())
@@ -9,3 +10,11 @@ pub(super) fn rune_budget_get() -> usize {
 pub(super) fn rune_budget_replace(value: usize) -> usize {
     BUDGET.with(|tls| tls.replace(value))
 }
+
+pub(super) fn rune_budget_limit_get() -> usize {
+    LIMIT.with(|tls| tls.get())
+}
+
+pub(super) fn rune_budget_limit_replace(value: usize) -> usize {
+    LIMIT.with(|tls| tls.replace(value))
+}
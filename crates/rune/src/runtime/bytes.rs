@@ -518,7 +518,7 @@ impl<'de> de::Deserialize<'de> for Bytes {
     {
         struct Visitor;
 
-        impl de::Visitor<'_> for Visitor {
+        impl<'de> de::Visitor<'de> for Visitor {
             type Value = Bytes;
 
             #[inline]
@@ -533,6 +533,23 @@ impl<'de> de::Deserialize<'de> for Bytes {
             {
                 Bytes::from_slice(v).map_err(E::custom)
             }
+
+            #[inline]
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::SeqAccess<'de>,
+            {
+                // Formats like JSON which lack a native representation for
+                // bytes store them as a sequence of numbers.
+                let capacity = seq.size_hint().unwrap_or_default().min(4096);
+                let mut bytes = Vec::try_with_capacity(capacity).map_err(de::Error::custom)?;
+
+                while let Some(byte) = seq.next_element::<u8>()? {
+                    bytes.try_push(byte).map_err(de::Error::custom)?;
+                }
+
+                Ok(Bytes::from_vec(bytes))
+            }
         }
 
         deserializer.deserialize_bytes(Visitor)
@@ -8,7 +8,7 @@ use crate::alloc::prelude::*;
 use crate::runtime::{Future, Generator, Stream, Value, Vm, VmResult};
 
 /// The calling convention of a function.
-#[derive(Debug, TryClone, Clone, Copy, Serialize, Deserialize, Encode, Decode)]
+#[derive(Debug, TryClone, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Encode, Decode)]
 #[try_clone(copy)]
 #[non_exhaustive]
 pub enum Call {
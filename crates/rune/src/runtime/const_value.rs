@@ -1,6 +1,9 @@
 #[macro_use]
 mod macros;
 
+#[cfg(test)]
+mod tests;
+
 use core::any;
 use core::cmp::Ordering;
 use core::fmt;
@@ -149,6 +152,53 @@ impl ConstValueKind {
 }
 
 /// A constant value.
+///
+/// # Serialization
+///
+/// Constant values implement [`Serialize`] and [`Deserialize`], which can be
+/// used to persist them, for example to cache precomputed constants between
+/// builds. The representation is stable and uses externally tagged variants,
+/// which in JSON looks like this:
+///
+/// | Value | Representation |
+/// |-------|----------------|
+/// | `()` | `{"Inline": "Unit"}` |
+/// | `true` | `{"Inline": {"Bool": true}}` |
+/// | `'a'` | `{"Inline": {"Char": "a"}}` |
+/// | `-1` | `{"Inline": {"Signed": -1}}` |
+/// | `1u64` | `{"Inline": {"Unsigned": 1}}` |
+/// | `1.5` | `{"Inline": {"Float": 1.5}}` |
+/// | `"hello"` | `{"String": "hello"}` |
+/// | `b"hi"` | `{"Bytes": [104, 105]}` |
+/// | `[1]` | `{"Vec": [{"Inline": {"Signed": 1}}]}` |
+/// | `(1,)` | `{"Tuple": [{"Inline": {"Signed": 1}}]}` |
+/// | `#{a: 1}` | `{"Object": {"a": {"Inline": {"Signed": 1}}}}` |
+/// | `None` | `{"Option": null}` |
+/// | `Some(1)` | `{"Option": {"Inline": {"Signed": 1}}}` |
+///
+/// Values of [`ToConstValue`] structs are stored as `{"Struct": [hash,
+/// [fields..]]}`, where `hash` is the type hash of the struct.
+///
+/// Binary formats use the same structure, and compiled units store their
+/// constants using this representation.
+///
+/// # Examples
+///
+/// ```
+/// use rune::runtime::ConstValue;
+///
+/// let value = rune::to_const_value((1u64, "hello"))?;
+/// let json = serde_json::to_string(&value)?;
+///
+/// assert_eq!(
+///     json,
+///     r#"{"Tuple":[{"Inline":{"Unsigned":1}},{"String":"hello"}]}"#
+/// );
+///
+/// let value2: ConstValue = serde_json::from_str(&json)?;
+/// assert_eq!(value, value2);
+/// # Ok::<_, rune::support::Error>(())
+/// ```
 #[derive(Deserialize, Serialize)]
 #[serde(transparent)]
 pub struct ConstValue {
@@ -301,9 +351,36 @@ impl ConstValue {
         Ok(Self { kind: inner })
     }
 
+    /// Construct a constant value from a value.
+    ///
+    /// This fails if the value or any value it contains can't be represented
+    /// as a constant, like functions or external types.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::runtime::ConstValue;
+    ///
+    /// let value = rune::to_value((1, "hello"))?;
+    /// let constant = ConstValue::from_value(&value)?;
+    /// let value = constant.to_value()?;
+    ///
+    /// let (a, b) = rune::from_value::<(i64, String)>(value)?;
+    /// assert_eq!(a, 1);
+    /// assert_eq!(b, "hello");
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    #[inline]
+    pub fn from_value(value: &Value) -> Result<ConstValue, RuntimeError> {
+        Self::from_value_ref(value)
+    }
+
+    /// Convert the constant value into a value.
+    ///
+    /// This fails for constant structs, since they require the context which
+    /// defines them to be constructed.
     #[inline]
-    #[cfg(test)]
-    pub(crate) fn to_value(&self) -> Result<Value, RuntimeError> {
+    pub fn to_value(&self) -> Result<Value, RuntimeError> {
         self.to_value_with(&EmptyConstContext)
     }
 
@@ -448,6 +525,41 @@ impl ToConstValue for &[u8] {
     }
 }
 
+impl PartialEq for ConstValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind == other.kind
+    }
+}
+
+impl PartialEq for ConstValueKind {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ConstValueKind::Inline(a), ConstValueKind::Inline(b)) => match (a, b) {
+                (Inline::Empty, Inline::Empty) => true,
+                (Inline::Unit, Inline::Unit) => true,
+                (Inline::Bool(a), Inline::Bool(b)) => a == b,
+                (Inline::Char(a), Inline::Char(b)) => a == b,
+                (Inline::Signed(a), Inline::Signed(b)) => a == b,
+                (Inline::Unsigned(a), Inline::Unsigned(b)) => a == b,
+                (Inline::Float(a), Inline::Float(b)) => a == b,
+                (Inline::Type(a), Inline::Type(b)) => a == b,
+                (Inline::Ordering(a), Inline::Ordering(b)) => a == b,
+                _ => false,
+            },
+            (ConstValueKind::String(a), ConstValueKind::String(b)) => a == b,
+            (ConstValueKind::Bytes(a), ConstValueKind::Bytes(b)) => a == b,
+            (ConstValueKind::Vec(a), ConstValueKind::Vec(b)) => a == b,
+            (ConstValueKind::Tuple(a), ConstValueKind::Tuple(b)) => a == b,
+            (ConstValueKind::Object(a), ConstValueKind::Object(b)) => a == b,
+            (ConstValueKind::Option(a), ConstValueKind::Option(b)) => a == b,
+            (ConstValueKind::Struct(a, a_fields), ConstValueKind::Struct(b, b_fields)) => {
+                a == b && a_fields == b_fields
+            }
+            _ => false,
+        }
+    }
+}
+
 impl fmt::Debug for ConstValue {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -0,0 +1,197 @@
+use crate::alloc::prelude::*;
+use crate::alloc::{self, Box, HashMap, String, Vec};
+use crate::runtime::{Bytes, Inline};
+use crate::Hash;
+
+use super::{ConstValue, ConstValueKind};
+
+/// The number of randomly generated values to test.
+const ITERATIONS: usize = 500;
+
+/// A small deterministic random number generator, so that failures are
+/// reproducible.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        // xorshift64*
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+
+    fn char(&mut self) -> char {
+        const CHARS: &[char] = &['a', 'z', '0', ' ', '"', '\\', '\n', 'é', '☃', '🦀'];
+        CHARS[self.below(CHARS.len() as u64) as usize]
+    }
+
+    fn string(&mut self) -> alloc::Result<String> {
+        let mut string = String::new();
+
+        for _ in 0..self.below(8) {
+            string.try_push(self.char())?;
+        }
+
+        Ok(string)
+    }
+
+    fn values(&mut self, depth: usize, structs: bool) -> alloc::Result<Vec<ConstValue>> {
+        let mut values = Vec::new();
+
+        for _ in 0..self.below(4) {
+            values.try_push(self.value(depth + 1, structs)?)?;
+        }
+
+        Ok(values)
+    }
+
+    fn value(&mut self, depth: usize, structs: bool) -> alloc::Result<ConstValue> {
+        // Only generate leaves once we're deep enough.
+        let kinds = if depth >= 3 { 7 } else { 12 };
+
+        let kind = match self.below(kinds + u64::from(structs && depth < 3)) {
+            0 => ConstValueKind::Inline(Inline::Unit),
+            1 => ConstValueKind::Inline(Inline::Bool(self.below(2) == 0)),
+            2 => ConstValueKind::Inline(Inline::Char(self.char())),
+            3 => ConstValueKind::Inline(Inline::Signed(self.next() as i64)),
+            4 => ConstValueKind::Inline(Inline::Unsigned(self.next())),
+            // Floats which are exactly representable in decimal, so that
+            // precision isn't a concern for textual formats.
+            5 => ConstValueKind::Inline(Inline::Float((self.next() as i32) as f64 / 8.0)),
+            6 => ConstValueKind::String(self.string()?),
+            7 => {
+                let mut bytes = Vec::new();
+
+                for _ in 0..self.below(8) {
+                    bytes.try_push(self.next() as u8)?;
+                }
+
+                ConstValueKind::Bytes(Bytes::from_vec(bytes))
+            }
+            8 => ConstValueKind::Vec(self.values(depth, structs)?),
+            9 => ConstValueKind::Tuple(self.values(depth, structs)?.try_into_boxed_slice()?),
+            10 => {
+                let mut object = HashMap::new();
+
+                for _ in 0..self.below(4) {
+                    object.try_insert(self.string()?, self.value(depth + 1, structs)?)?;
+                }
+
+                ConstValueKind::Object(object)
+            }
+            11 => ConstValueKind::Option(match self.below(2) {
+                0 => None,
+                _ => Some(Box::try_new(self.value(depth + 1, structs)?)?),
+            }),
+            _ => ConstValueKind::Struct(
+                Hash::new(self.next()),
+                self.values(depth, structs)?.try_into_boxed_slice()?,
+            ),
+        };
+
+        Ok(ConstValue::from(kind))
+    }
+}
+
+#[test]
+fn json_round_trip() {
+    let mut rng = Rng(0x5eed_0001);
+
+    for _ in 0..ITERATIONS {
+        let value = rng.value(0, true).unwrap();
+        let json = serde_json::to_string(&value).unwrap();
+        let output: ConstValue = serde_json::from_str(&json).unwrap();
+        assert_eq!(output, value, "{json}");
+    }
+}
+
+#[cfg(feature = "unit-file")]
+#[test]
+fn binary_round_trip() {
+    use crate::musli::Serde;
+
+    let mut rng = Rng(0x5eed_0002);
+
+    for _ in 0..ITERATIONS {
+        let value = rng.value(0, true).unwrap();
+
+        let mut bytes = rust_alloc::vec::Vec::new();
+        musli::descriptive::to_writer(&mut bytes, &Serde(&value)).unwrap();
+
+        let Serde(output) = musli::descriptive::from_slice::<Serde<ConstValue>>(&bytes).unwrap();
+        assert_eq!(output, value);
+    }
+}
+
+#[test]
+fn value_round_trip() {
+    let mut rng = Rng(0x5eed_0003);
+
+    for _ in 0..ITERATIONS {
+        // Structs need a constructor from the context to be turned into
+        // values.
+        let value = rng.value(0, false).unwrap();
+        let output = ConstValue::from_value(&value.to_value().unwrap()).unwrap();
+        assert_eq!(output, value);
+    }
+}
+
+#[test]
+fn representation() {
+    let value = crate::to_const_value((true, 'a', -1i64, 1u64, 1.5f64, "hello")).unwrap();
+
+    assert_eq!(
+        serde_json::to_value(&value).unwrap(),
+        serde_json::json!({
+            "Tuple": [
+                {"Inline": {"Bool": true}},
+                {"Inline": {"Char": "a"}},
+                {"Inline": {"Signed": -1}},
+                {"Inline": {"Unsigned": 1}},
+                {"Inline": {"Float": 1.5}},
+                {"String": "hello"},
+            ]
+        })
+    );
+
+    let some = Box::try_new(ConstValue::from(Inline::Unit)).unwrap();
+    let value = ConstValue::tuple(
+        [
+            ConstValue::from(ConstValueKind::Option(Some(some))),
+            ConstValue::from(ConstValueKind::Option(None)),
+        ]
+        .try_into()
+        .unwrap(),
+    );
+
+    assert_eq!(
+        serde_json::to_value(&value).unwrap(),
+        serde_json::json!({
+            "Tuple": [
+                {"Option": {"Inline": "Unit"}},
+                {"Option": null},
+            ]
+        })
+    );
+
+    let bytes = ConstValue::try_from(&b"hi"[..]).unwrap();
+    let json = serde_json::json!({"Bytes": [104, 105]});
+    assert_eq!(serde_json::to_value(&bytes).unwrap(), json);
+
+    let output: ConstValue = serde_json::from_value(json).unwrap();
+    assert_eq!(output, bytes);
+}
+
+#[test]
+fn equality() {
+    let a = ConstValue::try_from("a").unwrap();
+    let b = ConstValue::try_from("b").unwrap();
+    assert_eq!(a, a.try_clone().unwrap());
+    assert_ne!(a, b);
+    assert_ne!(a, ConstValue::from(Inline::Unit));
+}
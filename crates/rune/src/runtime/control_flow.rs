@@ -148,6 +148,34 @@ impl ControlFlow {
         VmResult::Ok(())
     }
 
+    /// Using [`ControlFlow`] with the try protocol.
+    ///
+    /// A `Continue` produces its value, while a `Break` is returned early from
+    /// the enclosing function.
+    ///
+    /// # Examples
+    ///
+    /// ```rune
+    /// use std::ops::ControlFlow;
+    ///
+    /// fn add_one(flow) {
+    ///     ControlFlow::Continue(flow? + 1)
+    /// }
+    ///
+    /// assert_eq!(add_one(ControlFlow::Continue(1)), ControlFlow::Continue(2));
+    /// assert_eq!(add_one(ControlFlow::Break("stop")), ControlFlow::Break("stop"));
+    /// ```
+    #[rune::function(keep, protocol = TRY)]
+    pub(crate) fn try_(&self) -> VmResult<Self> {
+        VmResult::Ok(match self {
+            ControlFlow::Continue(value) => ControlFlow::Continue(value.clone()),
+            ControlFlow::Break(value) => {
+                let flow = ControlFlow::Break(value.clone());
+                ControlFlow::Break(vm_try!(Value::try_from(flow)))
+            }
+        })
+    }
+
     /// Clone the control flow.
     ///
     /// # Examples
@@ -23,6 +23,11 @@ pub struct DebugInfo {
     pub functions_rev: HashMap<usize, Hash>,
     /// Hash to identifier.
     pub hash_to_ident: HashMap<Hash, Box<str>>,
+    /// Local variables and the instructions in which they are in scope.
+    pub variables: Vec<DebugVariable>,
+    /// Sources the unit was compiled from, which is only populated if the
+    /// `debug-access` feature is enabled.
+    pub sources: HashMap<SourceId, DebugSource>,
 }
 
 impl DebugInfo {
@@ -38,10 +43,66 @@ impl DebugInfo {
         Some((hash, signature))
     }
 
+    /// Get the function which contains the given instruction pointer.
+    pub fn function_containing(&self, ip: usize) -> Option<(Hash, &DebugSignature)> {
+        let (_, hash) = self
+            .functions_rev
+            .iter()
+            .filter(|(offset, _)| **offset <= ip)
+            .max_by_key(|(offset, _)| **offset)?;
+
+        let signature = self.functions.get(hash)?;
+        Some((*hash, signature))
+    }
+
+    /// Iterate over the local variables which are in scope at the given
+    /// instruction pointer.
+    ///
+    /// If multiple variables with the same name are in scope, the one which
+    /// shadows the others is produced last.
+    pub fn variables_at(&self, ip: usize) -> impl Iterator<Item = &DebugVariable> {
+        self.variables
+            .iter()
+            .filter(move |var| var.start <= ip && ip < var.end)
+    }
+
     /// Access an identifier for the given hash - if it exists.
     pub fn ident_for_hash(&self, hash: Hash) -> Option<&str> {
         Some(self.hash_to_ident.get(&hash)?)
     }
+
+    /// Get the source with the given id.
+    pub fn source(&self, source_id: SourceId) -> Option<&DebugSource> {
+        self.sources.get(&source_id)
+    }
+}
+
+/// Debug information about a source.
+#[derive(Debug, TryClone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct DebugSource {
+    /// The name of the source.
+    pub name: Box<str>,
+    /// The byte offset at which each line in the source starts.
+    pub line_starts: Vec<usize>,
+}
+
+impl DebugSource {
+    /// Construct debug information about a source.
+    pub fn new(name: Box<str>, line_starts: Vec<usize>) -> Self {
+        Self { name, line_starts }
+    }
+
+    /// Convert the given byte offset to a zero-based line and byte column.
+    pub fn line_column(&self, offset: usize) -> (usize, usize) {
+        let line = match self.line_starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(line) => line.saturating_sub(1),
+        };
+
+        let start = self.line_starts.get(line).copied().unwrap_or_default();
+        (line, offset.saturating_sub(start))
+    }
 }
 
 /// Debug information for every instruction.
@@ -75,6 +136,21 @@ impl DebugInst {
     }
 }
 
+/// Debug information about a local variable.
+#[derive(Debug, TryClone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct DebugVariable {
+    /// The name of the variable.
+    pub name: Box<str>,
+    /// The stack slot of the variable, relative to the start of the call
+    /// frame it belongs to.
+    pub addr: usize,
+    /// The instruction pointer at which the variable comes into scope.
+    pub start: usize,
+    /// The instruction pointer at which the variable goes out of scope.
+    pub end: usize,
+}
+
 /// Debug information on function arguments.
 #[derive(Debug, TryClone, Serialize, Deserialize)]
 pub enum DebugArgs {
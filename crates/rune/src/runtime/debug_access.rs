@@ -0,0 +1,173 @@
+//! Tracking of where references to external values are taken by the virtual
+//! machine, which is enabled through the `debug-access` feature.
+//!
+//! Every time a reference to an external value is copied while the virtual
+//! machine is executing, the instruction doing so is recorded in the
+//! reference. If the reference escapes the scope it was passed in through,
+//! accessing it produces an error pointing to the last instruction which
+//! copied it. For a reference which has been stashed away, this is where it
+//! was stored.
+
+use core::cell::Cell;
+use core::fmt;
+
+use crate::alloc::clone::TryClone;
+use crate::alloc::Box;
+use crate::ast::Span;
+use crate::runtime::{env, TypeInfo, VmResult};
+use crate::SourceId;
+
+std::thread_local!(static IP: Cell<Option<usize>> = const { Cell::new(None) });
+
+/// Where a reference to an external value was taken.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(test, derive(PartialEq))]
+pub(crate) struct Provenance {
+    /// The location of the instruction which took the reference, if the unit
+    /// has debug information.
+    location: Option<(SourceId, Span)>,
+}
+
+impl Provenance {
+    /// Get the source location where the reference was taken.
+    #[inline]
+    pub(crate) fn location(&self) -> Option<(SourceId, Span)> {
+        self.location
+    }
+}
+
+/// A reference which was accessed after it escaped its scope.
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub(crate) struct Escaped {
+    type_info: TypeInfo,
+    provenance: Option<Provenance>,
+    /// The resolved location where the reference was taken, such as
+    /// `main.rn:12:5`.
+    location: Option<Location>,
+}
+
+impl Escaped {
+    /// Construct information on an escaped reference, resolving where it was
+    /// taken using the debug information of the executing unit.
+    pub(crate) fn new(type_info: TypeInfo, provenance: Option<Provenance>) -> Self {
+        let location = provenance
+            .and_then(|provenance| provenance.location)
+            .and_then(|(source_id, span)| Location::resolve(source_id, span));
+
+        Self {
+            type_info,
+            provenance,
+            location,
+        }
+    }
+
+    /// Get the provenance of the reference.
+    #[inline]
+    pub(crate) fn provenance(&self) -> Option<Provenance> {
+        self.provenance
+    }
+}
+
+impl fmt::Display for Escaped {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.location {
+            Some(location) => write!(
+                f,
+                "reference to `{}` taken at {location} escaped its scope",
+                self.type_info
+            ),
+            None => write!(f, "reference to `{}` escaped its scope", self.type_info),
+        }
+    }
+}
+
+/// A location in a named source.
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+struct Location {
+    name: Box<str>,
+    /// Zero-based line.
+    line: usize,
+    /// Zero-based byte column.
+    column: usize,
+}
+
+impl Location {
+    fn resolve(source_id: SourceId, span: Span) -> Option<Self> {
+        let location = env::shared(|_, unit| {
+            let Some(source) = unit.debug_info().and_then(|debug| debug.source(source_id)) else {
+                return VmResult::Ok(None);
+            };
+
+            let (line, column) = source.line_column(span.start.into_usize());
+
+            VmResult::Ok(Some(Self {
+                name: vm_try!(source.name.try_clone()),
+                line,
+                column,
+            }))
+        });
+
+        match location {
+            VmResult::Ok(location) => location,
+            VmResult::Err(..) => None,
+        }
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.name, self.line + 1, self.column + 1)
+    }
+}
+
+/// Guard which restores the instruction which was previously executing.
+pub(crate) struct Guard {
+    ip: Option<usize>,
+}
+
+impl Guard {
+    /// Construct a new guard for an executing virtual machine.
+    #[inline]
+    pub(crate) fn new() -> Self {
+        Self {
+            ip: IP.with(|ip| ip.replace(None)),
+        }
+    }
+}
+
+impl Drop for Guard {
+    #[inline]
+    fn drop(&mut self) {
+        IP.with(|ip| ip.set(self.ip));
+    }
+}
+
+/// Set the instruction which is currently executing.
+#[inline]
+pub(crate) fn set_ip(ip: usize) {
+    IP.with(|cell| cell.set(Some(ip)));
+}
+
+/// Get the provenance of the instruction which is currently executing, or
+/// `None` if no virtual machine is executing.
+pub(crate) fn current() -> Option<Provenance> {
+    let ip = IP.with(Cell::get)?;
+
+    let location = env::shared(|_, unit| {
+        let location = unit
+            .debug_info()
+            .and_then(|debug| debug.instruction_at(ip))
+            .map(|inst| (inst.source_id, inst.span));
+
+        VmResult::Ok(location)
+    });
+
+    let location = match location {
+        VmResult::Ok(location) => location,
+        VmResult::Err(..) => None,
+    };
+
+    Some(Provenance { location })
+}
@@ -15,8 +15,9 @@ mod no_std;
 
 use ::rust_alloc::sync::Arc;
 
+use crate::runtime::vm::{VmLimits, VmTracer};
 use crate::runtime::vm_diagnostics::VmDiagnosticsObj;
-use crate::runtime::{RuntimeContext, Unit, VmErrorKind, VmResult};
+use crate::runtime::{RuntimeContext, Unit, VmData, VmErrorKind, VmResult};
 
 /// Access shared parts of the environment.
 ///
@@ -45,6 +46,71 @@ where
     c(&context, &unit)
 }
 
+/// Get the limits of the virtual machine which is currently executing.
+///
+/// If no virtual machine is executing, this returns limits which are
+/// unrestricted.
+pub(crate) fn limits() -> VmLimits {
+    let env = self::no_std::rune_env_get();
+
+    VmLimits {
+        call_frames: env.max_call_frames,
+        stack: env.max_stack,
+        used_call_frames: env.used_call_frames,
+        used_stack: env.used_stack,
+    }
+}
+
+/// Restrict the limits of virtual machines which are constructed while the
+/// returned guard is live.
+///
+/// This is used when calling native functions, so that nested virtual
+/// machines account for what the calling virtual machine has already used.
+pub(crate) fn restrict(limits: VmLimits) -> LimitsGuard {
+    let mut env = self::no_std::rune_env_get();
+    let old = (env.used_call_frames, env.used_stack);
+    env.used_call_frames = limits.used_call_frames;
+    env.used_stack = limits.used_stack;
+    self::no_std::rune_env_replace(env);
+    LimitsGuard { old }
+}
+
+/// Guard returned by [`restrict`].
+pub(crate) struct LimitsGuard {
+    old: (usize, usize),
+}
+
+impl Drop for LimitsGuard {
+    fn drop(&mut self) {
+        let mut env = self::no_std::rune_env_get();
+        (env.used_call_frames, env.used_stack) = self.old;
+        self::no_std::rune_env_replace(env);
+    }
+}
+
+/// Get the data of the virtual machine which is currently executing.
+///
+/// If no virtual machine is executing, this returns empty data.
+pub(crate) fn data() -> VmData {
+    let env = self::no_std::rune_env_get();
+    // Safety: data can only be registered publicly through [`Guard`], which
+    // makes sure that it is live for the duration of the registration.
+    unsafe { VmData::clone_from_raw(env.data) }
+}
+
+/// Get the tracer of the virtual machine which is currently executing.
+pub(crate) fn tracer_hook() -> Option<Arc<VmTracer>> {
+    let env = self::no_std::rune_env_get();
+    let tracer = env.tracer?.as_ptr().cast_const();
+
+    // Safety: the tracer can only be registered publicly through [`Guard`],
+    // which makes sure that it is live for the duration of the registration.
+    unsafe {
+        Arc::increment_strong_count(tracer);
+        Some(Arc::from_raw(tracer))
+    }
+}
+
 /// Call the given closure with access to the checked environment accessing it
 /// exclusively.
 ///
@@ -94,12 +160,22 @@ impl Guard {
         context: Arc<RuntimeContext>,
         unit: Arc<Unit>,
         diagnostics: Option<NonNull<VmDiagnosticsObj>>,
+        limits: VmLimits,
+        data: VmData,
+        tracer: Option<Arc<VmTracer>>,
     ) -> Guard {
         let env = unsafe {
             self::no_std::rune_env_replace(Env {
                 context: Some(NonNull::new_unchecked(Arc::into_raw(context).cast_mut())),
                 unit: Some(NonNull::new_unchecked(Arc::into_raw(unit).cast_mut())),
                 diagnostics,
+                max_call_frames: limits.call_frames,
+                max_stack: limits.stack,
+                used_call_frames: limits.used_call_frames,
+                used_stack: limits.used_stack,
+                data: data.into_raw(),
+                tracer: tracer
+                    .map(|tracer| NonNull::new_unchecked(Arc::into_raw(tracer).cast_mut())),
             })
         };
 
@@ -119,6 +195,14 @@ impl Drop for Guard {
             if let Some(unit) = old_env.unit {
                 drop(Arc::from_raw(unit.as_ptr().cast_const()));
             }
+
+            if let Some(data) = old_env.data {
+                VmData::drop_raw(data);
+            }
+
+            if let Some(tracer) = old_env.tracer {
+                drop(Arc::from_raw(tracer.as_ptr().cast_const()));
+            }
         }
     }
 }
@@ -128,6 +212,12 @@ struct Env {
     context: Option<NonNull<RuntimeContext>>,
     unit: Option<NonNull<Unit>>,
     diagnostics: Option<NonNull<VmDiagnosticsObj>>,
+    max_call_frames: usize,
+    max_stack: usize,
+    used_call_frames: usize,
+    used_stack: usize,
+    data: Option<NonNull<()>>,
+    tracer: Option<NonNull<VmTracer>>,
 }
 
 impl Env {
@@ -136,6 +226,12 @@ impl Env {
             context: None,
             unit: None,
             diagnostics: None,
+            max_call_frames: usize::MAX,
+            max_stack: usize::MAX,
+            used_call_frames: 0,
+            used_stack: 0,
+            data: None,
+            tracer: None,
         }
     }
 }
@@ -33,6 +33,12 @@ unsafe fn from_env(env: Env) -> RawEnv {
         context: env.context.map(|ptr| ptr.cast()),
         unit: env.unit.map(|ptr| ptr.cast()),
         diagnostics: env.diagnostics.map(|ptr| ptr.cast()),
+        max_call_frames: env.max_call_frames,
+        max_stack: env.max_stack,
+        used_call_frames: env.used_call_frames,
+        used_stack: env.used_stack,
+        data: env.data,
+        tracer: env.tracer.map(|ptr| ptr.cast()),
     }
 }
 
@@ -41,5 +47,11 @@ unsafe fn from_raw_env(env: RawEnv) -> Env {
         context: env.context.map(|ptr| ptr.cast()),
         unit: env.unit.map(|ptr| ptr.cast()),
         diagnostics: env.diagnostics.map(|ptr| ptr.cast()),
+        max_call_frames: env.max_call_frames,
+        max_stack: env.max_stack,
+        used_call_frames: env.used_call_frames,
+        used_stack: env.used_stack,
+        data: env.data,
+        tracer: env.tracer.map(|ptr| ptr.cast()),
     }
 }
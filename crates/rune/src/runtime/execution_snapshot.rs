@@ -0,0 +1,146 @@
+use ::rust_alloc::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::alloc::Vec;
+use crate::hash::Hash;
+use crate::runtime::{
+    AnyObj, CallFrame, ConstValue, ExecutionState, Repr, RuntimeContext, Stack, Unit, Value, Vm,
+    VmError, VmErrorKind,
+};
+
+/// A serializable snapshot of a suspended [`VmExecution`].
+///
+/// This is constructed through [`VmExecution::snapshot`] and can be turned
+/// back into an execution using [`VmExecution::restore`].
+///
+/// Values on the stack are stored as [`ConstValue`], so only values which can
+/// be converted into constant values can be part of a snapshot. Values which
+/// are shared across multiple stack slots remain shared once restored, but
+/// sharing between values nested inside of other values is not preserved.
+///
+/// [`VmExecution`]: crate::runtime::VmExecution
+/// [`VmExecution::snapshot`]: crate::runtime::VmExecution::snapshot
+/// [`VmExecution::restore`]: crate::runtime::VmExecution::restore
+#[derive(Debug, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct ExecutionSnapshot {
+    /// Fingerprint of the unit the snapshot was taken from.
+    unit: Hash,
+    /// The state of the execution.
+    state: ExecutionState,
+    /// The instruction pointer.
+    ip: usize,
+    /// The length of the last instruction.
+    last_ip_len: u8,
+    /// The top of the current stack frame.
+    top: usize,
+    /// Values on the stack.
+    stack: Vec<Slot>,
+    /// Call frames relative to the stack.
+    call_frames: Vec<CallFrame>,
+}
+
+impl ExecutionSnapshot {
+    /// Take a snapshot of the given virtual machine.
+    pub(crate) fn new(vm: &Vm, state: ExecutionState) -> Result<Self, VmError> {
+        let values = vm.stack().as_slice();
+
+        let mut stack = Vec::try_with_capacity(values.len())?;
+        let mut unsupported = Vec::new();
+        let mut objects = Vec::<(usize, &AnyObj)>::new();
+
+        for (slot, value) in values.iter().enumerate() {
+            if let Repr::Any(object) = value.as_ref() {
+                if let Some(&(shared, _)) = objects.iter().find(|(_, o)| o.ptr_eq(object)) {
+                    stack.try_push(Slot::Shared(shared))?;
+                    continue;
+                }
+
+                objects.try_push((slot, object))?;
+            }
+
+            match ConstValue::from_value_ref(value) {
+                Ok(value) => {
+                    stack.try_push(Slot::Value(value))?;
+                }
+                Err(..) => {
+                    unsupported.try_push((slot, value.type_info()))?;
+                }
+            }
+        }
+
+        if !unsupported.is_empty() {
+            return Err(VmError::new(VmErrorKind::SnapshotUnsupported {
+                slots: unsupported,
+            }));
+        }
+
+        let mut call_frames = Vec::try_with_capacity(vm.call_frames().len())?;
+
+        for frame in vm.call_frames() {
+            call_frames.try_push(*frame)?;
+        }
+
+        Ok(Self {
+            unit: vm.unit().fingerprint()?,
+            state,
+            ip: vm.ip(),
+            last_ip_len: vm.last_ip_len(),
+            top: vm.stack().top(),
+            stack,
+            call_frames,
+        })
+    }
+
+    /// Restore a virtual machine from the snapshot.
+    pub(crate) fn restore(
+        &self,
+        context: Arc<RuntimeContext>,
+        unit: Arc<Unit>,
+    ) -> Result<(Vm, ExecutionState), VmError> {
+        if unit.fingerprint()? != self.unit {
+            return Err(VmError::new(VmErrorKind::SnapshotUnitMismatch));
+        }
+
+        let len = self.stack.len();
+
+        if self.top > len || self.call_frames.iter().any(|frame| frame.top > len) {
+            return Err(VmError::new(VmErrorKind::SnapshotCorrupt));
+        }
+
+        let mut stack = Vec::try_with_capacity(len)?;
+
+        for slot in &self.stack {
+            let value = match slot {
+                Slot::Value(value) => value.to_value_with(&*context)?,
+                Slot::Shared(index) => match stack.get(*index) {
+                    Some(value) => Value::clone(value),
+                    None => return Err(VmError::new(VmErrorKind::SnapshotCorrupt)),
+                },
+            };
+
+            stack.try_push(value)?;
+        }
+
+        let mut call_frames = Vec::try_with_capacity(self.call_frames.len())?;
+
+        for frame in &self.call_frames {
+            call_frames.try_push(*frame)?;
+        }
+
+        let stack = Stack::from_parts(stack, self.top);
+        let mut vm = Vm::with_stack(context, unit, stack);
+        vm.restore(self.ip, self.last_ip_len, call_frames);
+        Ok((vm, self.state))
+    }
+}
+
+/// A single slot on the stack.
+#[derive(Debug, Serialize, Deserialize)]
+enum Slot {
+    /// A value stored in the slot.
+    Value(ConstValue),
+    /// A value which is shared with the given earlier slot.
+    Shared(usize),
+}
@@ -1,7 +1,6 @@
 //! Types for dealing with formatting specifications.
 
 use core::fmt;
-use core::iter;
 use core::mem::take;
 use core::num::NonZeroUsize;
 use core::str;
@@ -12,7 +11,7 @@ use serde::{Deserialize, Serialize};
 use crate as rune;
 use crate::alloc::clone::TryClone;
 use crate::alloc::fmt::TryWrite;
-use crate::alloc::String;
+use crate::alloc::{self, String};
 use crate::runtime::{Formatter, Inline, ProtocolCaller, Repr, Value, VmErrorKind, VmResult};
 use crate::{Any, TypeHash};
 
@@ -59,12 +58,13 @@ pub struct FormatSpec {
     pub(crate) flags: Flags,
     /// The fill character.
     pub(crate) fill: char,
-    /// The alignment specification.
-    pub(crate) align: Alignment,
+    /// The alignment specification, if none is specified numbers are aligned
+    /// to the right and everything else to the left.
+    pub(crate) align: Option<Alignment>,
     /// Formatting width.
     pub(crate) width: Option<NonZeroUsize>,
     /// Formatting precision.
-    pub(crate) precision: Option<NonZeroUsize>,
+    pub(crate) precision: Option<usize>,
     /// The type specification.
     pub(crate) format_type: Type,
 }
@@ -74,9 +74,9 @@ impl FormatSpec {
     pub fn new(
         flags: Flags,
         fill: char,
-        align: Alignment,
+        align: Option<Alignment>,
         width: Option<NonZeroUsize>,
-        precision: Option<NonZeroUsize>,
+        precision: Option<usize>,
         format_type: Type,
     ) -> Self {
         Self {
@@ -89,267 +89,322 @@ impl FormatSpec {
         }
     }
 
-    /// get traits out of a floating point number.
-    fn float_traits(&self, n: f64) -> (f64, Alignment, char, Option<char>) {
-        if self.flags.test(Flag::SignAwareZeroPad) {
-            if n.is_sign_negative() {
-                (-n, Alignment::Right, '0', Some('-'))
-            } else {
-                (n, Alignment::Right, '0', None)
-            }
-        } else if self.flags.test(Flag::SignPlus) && n.is_sign_positive() {
-            (n, self.align, self.fill, Some('+'))
-        } else {
-            (n, self.align, self.fill, None)
-        }
+    /// Write the string in `buf` to the output, truncated to the precision
+    /// if `truncate` is set and padded to the width.
+    fn pad(&self, f: &mut Formatter, truncate: bool) -> VmResult<()> {
+        let (out, buf) = f.parts_mut();
+
+        let s = match self.precision {
+            Some(precision) if truncate => match buf.char_indices().nth(precision) {
+                Some((n, _)) => &buf[..n],
+                None => buf,
+            },
+            _ => buf,
+        };
+
+        let align = self.align.unwrap_or(Alignment::Left);
+        let padding = self.padding(s.chars().count());
+        vm_try!(write_padded(out, self.fill, align, padding, |out| out.try_write_str(s)));
+        VmResult::Ok(())
     }
 
-    /// get traits out of an integer.
-    fn int_traits(&self, n: i64) -> (i64, Alignment, char, Option<char>) {
-        if self.flags.test(Flag::SignAwareZeroPad) {
-            if n < 0 {
-                (-n, Alignment::Right, '0', Some('-'))
-            } else {
-                (n, Alignment::Right, '0', None)
+    /// Write the digits in `buf` to the output, prefixed by a sign and
+    /// `prefix` if the alternate flag is set.
+    ///
+    /// The sign aware zero pad flag places zeros between the prefix and the
+    /// digits, in which case the fill and alignment are ignored.
+    fn pad_integral(&self, f: &mut Formatter, negative: bool, prefix: &str) -> VmResult<()> {
+        let (out, buf) = f.parts_mut();
+
+        let sign = if negative {
+            Some('-')
+        } else if self.flags.test(Flag::SignPlus) {
+            Some('+')
+        } else {
+            None
+        };
+
+        let prefix = if self.flags.test(Flag::Alternate) {
+            prefix
+        } else {
+            ""
+        };
+
+        let len = buf.chars().count() + prefix.len() + usize::from(sign.is_some());
+        let padding = self.padding(len);
+
+        let write = |out: &mut dyn TryWrite| {
+            if let Some(sign) = sign {
+                out.try_write_char(sign)?;
             }
-        } else if self.flags.test(Flag::SignPlus) && n >= 0 {
-            (n, self.align, self.fill, Some('+'))
+
+            out.try_write_str(prefix)
+        };
+
+        if self.flags.test(Flag::SignAwareZeroPad) {
+            vm_try!(write(out));
+            vm_try!(write_padded(out, '0', Alignment::Right, padding, |out| out
+                .try_write_str(buf)));
         } else {
-            (n, self.align, self.fill, None)
+            let align = self.align.unwrap_or(Alignment::Right);
+
+            vm_try!(write_padded(out, self.fill, align, padding, |out| {
+                write(out)?;
+                out.try_write_str(buf)
+            }));
         }
-    }
 
-    /// Format the given number.
-    fn format_number(&self, buf: &mut String, n: i64) -> VmResult<()> {
-        let mut buffer = itoa::Buffer::new();
-        vm_try!(buf.try_push_str(buffer.format(n)));
         VmResult::Ok(())
     }
 
-    /// Format the given float.
-    fn format_float(&self, buf: &mut String, n: f64) -> VmResult<()> {
-        if let Some(precision) = self.precision {
-            vm_try!(vm_write!(buf, "{:.*}", precision.get(), n));
-        } else {
-            let mut buffer = ryu::Buffer::new();
-            vm_try!(buf.try_push_str(buffer.format(n)));
+    /// The amount of padding needed for a string of the given length.
+    fn padding(&self, len: usize) -> usize {
+        match self.width {
+            Some(width) => width.get().saturating_sub(len),
+            None => 0,
         }
-
-        VmResult::Ok(())
     }
 
-    /// Format fill.
-    fn format_fill(
-        &self,
-        f: &mut Formatter,
-        align: Alignment,
-        fill: char,
-        sign: Option<char>,
-    ) -> VmResult<()> {
-        let (f, buf) = f.parts_mut();
+    /// Format an integer, where `n` is the magnitude of the number.
+    fn format_integer(&self, f: &mut Formatter, negative: bool, n: u64) -> VmResult<()> {
+        let buf = f.buf_mut();
 
-        if let Some(sign) = sign {
-            vm_try!(f.try_write_char(sign));
-        }
+        let prefix = match self.format_type {
+            Type::Display | Type::Debug => {
+                vm_try!(buf.try_push_str(itoa::Buffer::new().format(n)));
+                ""
+            }
+            Type::LowerHex => {
+                vm_try!(vm_write!(buf, "{n:x}"));
+                "0x"
+            }
+            Type::UpperHex => {
+                vm_try!(vm_write!(buf, "{n:X}"));
+                "0x"
+            }
+            Type::Octal => {
+                vm_try!(vm_write!(buf, "{n:o}"));
+                "0o"
+            }
+            Type::Binary => {
+                vm_try!(vm_write!(buf, "{n:b}"));
+                "0b"
+            }
+            Type::LowerExp => {
+                vm_try!(self.format_integer_exp(buf, n, 'e'));
+                ""
+            }
+            Type::UpperExp => {
+                vm_try!(self.format_integer_exp(buf, n, 'E'));
+                ""
+            }
+            Type::Pointer => {
+                return VmResult::err(VmErrorKind::IllegalFormat);
+            }
+        };
+
+        self.pad_integral(f, negative, prefix)
+    }
 
-        let mut w = self.width.map(|n| n.get()).unwrap_or_default();
+    /// Format an integer in scientific notation, rounding to the precision
+    /// with ties rounded to even.
+    fn format_integer_exp(&self, buf: &mut String, mut n: u64, e: char) -> VmResult<()> {
+        let mut exponent = 0usize;
 
-        if w == 0 {
-            vm_try!(f.try_write_str(buf));
-            return VmResult::Ok(());
+        while n % 10 == 0 && n >= 10 {
+            n /= 10;
+            exponent += 1;
         }
 
-        w = w
-            .saturating_sub(buf.chars().count())
-            .saturating_sub(sign.map(|_| 1).unwrap_or_default());
+        let digits = n.checked_ilog10().unwrap_or(0) as usize;
+
+        let (added, subtracted) = match self.precision {
+            Some(precision) => (
+                precision.saturating_sub(digits),
+                digits.saturating_sub(precision),
+            ),
+            None => (0, 0),
+        };
 
-        if w == 0 {
-            vm_try!(f.try_write_str(buf));
-            return VmResult::Ok(());
+        for _ in 1..subtracted {
+            n /= 10;
+            exponent += 1;
         }
 
-        let mut filler = iter::repeat(fill).take(w);
+        if subtracted != 0 {
+            let rem = n % 10;
+            n /= 10;
+            exponent += 1;
 
-        match align {
-            Alignment::Left => {
-                vm_try!(f.try_write_str(buf));
+            if rem > 5 || (rem == 5 && (n % 2 != 0 || subtracted > 1)) {
+                n += 1;
 
-                for c in filler {
-                    vm_try!(f.try_write_char(c));
+                if n.ilog10() > (n - 1).ilog10() {
+                    n /= 10;
+                    exponent += 1;
                 }
             }
-            Alignment::Center => {
-                for c in (&mut filler).take(w / 2) {
-                    vm_try!(f.try_write_char(c));
-                }
+        }
 
-                vm_try!(f.try_write_str(buf));
+        let mut b = itoa::Buffer::new();
+        let mantissa = b.format(n);
+        exponent += mantissa.len() - 1;
 
-                for c in filler {
-                    vm_try!(f.try_write_char(c));
-                }
-            }
-            Alignment::Right => {
-                for c in filler {
-                    vm_try!(f.try_write_char(c));
-                }
+        let (first, rest) = mantissa.split_at(1);
+        vm_try!(buf.try_push_str(first));
 
-                vm_try!(f.try_write_str(buf));
+        if !rest.is_empty() || added > 0 {
+            vm_try!(buf.try_push('.'));
+            vm_try!(buf.try_push_str(rest));
+
+            for _ in 0..added {
+                vm_try!(buf.try_push('0'));
             }
         }
 
+        vm_try!(vm_write!(buf, "{e}{exponent}"));
         VmResult::Ok(())
     }
 
+    /// Format a float.
+    fn format_float(&self, f: &mut Formatter, n: f64) -> VmResult<()> {
+        let negative = n.is_sign_negative() && !n.is_nan();
+        let n = n.abs();
+        let buf = f.buf_mut();
+
+        match (self.format_type, self.precision) {
+            (Type::Display | Type::Debug, Some(precision)) => {
+                vm_try!(vm_write!(buf, "{n:.precision$}"));
+            }
+            (Type::Display | Type::Debug, None) => {
+                vm_try!(buf.try_push_str(ryu::Buffer::new().format(n)));
+            }
+            (Type::LowerExp, Some(precision)) => {
+                vm_try!(vm_write!(buf, "{n:.precision$e}"));
+            }
+            (Type::LowerExp, None) => {
+                vm_try!(vm_write!(buf, "{n:e}"));
+            }
+            (Type::UpperExp, Some(precision)) => {
+                vm_try!(vm_write!(buf, "{n:.precision$E}"));
+            }
+            (Type::UpperExp, None) => {
+                vm_try!(vm_write!(buf, "{n:E}"));
+            }
+            _ => {
+                return VmResult::err(VmErrorKind::IllegalFormat);
+            }
+        }
+
+        self.pad_integral(f, negative, "")
+    }
+
     fn format_display(
         &self,
         value: &Value,
         f: &mut Formatter,
         caller: &mut dyn ProtocolCaller,
     ) -> VmResult<()> {
-        'fallback: {
-            match value.as_ref() {
-                Repr::Inline(value) => match value {
-                    Inline::Char(c) => {
-                        vm_try!(f.buf_mut().try_push(*c));
-                        vm_try!(self.format_fill(f, self.align, self.fill, None));
-                    }
-                    Inline::Signed(n) => {
-                        let (n, align, fill, sign) = self.int_traits(*n);
-                        vm_try!(self.format_number(f.buf_mut(), n));
-                        vm_try!(self.format_fill(f, align, fill, sign));
-                    }
-                    Inline::Float(n) => {
-                        let (n, align, fill, sign) = self.float_traits(*n);
-                        vm_try!(self.format_float(f.buf_mut(), n));
-                        vm_try!(self.format_fill(f, align, fill, sign));
-                    }
-                    _ => {
-                        break 'fallback;
-                    }
-                },
-                Repr::Dynamic(..) => {
-                    break 'fallback;
+        match value.as_ref() {
+            Repr::Inline(inline) => match inline {
+                Inline::Char(c) => {
+                    vm_try!(f.buf_mut().try_push(*c));
+                    self.pad(f, true)
                 }
-                Repr::Any(value) => match value.type_hash() {
-                    String::HASH => {
-                        let s = vm_try!(value.borrow_ref::<String>());
-                        vm_try!(f.buf_mut().try_push_str(&s));
-                        vm_try!(self.format_fill(f, self.align, self.fill, None));
-                    }
-                    _ => {
-                        break 'fallback;
-                    }
-                },
+                Inline::Bool(b) => {
+                    vm_try!(f.buf_mut().try_push_str(if *b { "true" } else { "false" }));
+                    self.pad(f, true)
+                }
+                Inline::Signed(n) => self.format_integer(f, *n < 0, n.unsigned_abs()),
+                Inline::Unsigned(n) => self.format_integer(f, false, *n),
+                Inline::Float(n) => self.format_float(f, *n),
+                _ => self.format_padded(value, f, caller),
+            },
+            Repr::Any(any) if any.type_hash() == String::HASH => {
+                let s = vm_try!(any.borrow_ref::<String>());
+                vm_try!(f.buf_mut().try_push_str(&s));
+                self.pad(f, true)
             }
-
-            return VmResult::Ok(());
+            _ => self.format_padded(value, f, caller),
         }
-
-        value.display_fmt_with(f, caller)
     }
 
-    fn format_debug(
+    /// Format a value through the `DISPLAY_FMT` protocol, padding it to the
+    /// width if one is specified.
+    fn format_padded(
         &self,
         value: &Value,
         f: &mut Formatter,
         caller: &mut dyn ProtocolCaller,
     ) -> VmResult<()> {
-        'fallback: {
-            match value.as_ref() {
-                Repr::Inline(value) => match value {
-                    Inline::Signed(n) => {
-                        let (n, align, fill, sign) = self.int_traits(*n);
-                        vm_try!(self.format_number(f.buf_mut(), n));
-                        vm_try!(self.format_fill(f, align, fill, sign));
-                    }
-                    Inline::Float(n) => {
-                        let (n, align, fill, sign) = self.float_traits(*n);
-                        vm_try!(self.format_float(f.buf_mut(), n));
-                        vm_try!(self.format_fill(f, align, fill, sign));
-                    }
-                    _ => {
-                        break 'fallback;
-                    }
-                },
-                Repr::Dynamic(..) => {
-                    break 'fallback;
-                }
-                Repr::Any(value) => match value.type_hash() {
-                    String::HASH => {
-                        let s = vm_try!(value.borrow_ref::<String>());
-                        vm_try!(vm_write!(f, "{s:?}"));
-                    }
-                    _ => {
-                        break 'fallback;
-                    }
-                },
-            }
+        if self.width.is_none() {
+            return value.display_fmt_with(f, caller);
+        }
 
-            return VmResult::Ok(());
-        };
+        let mut buf = String::new();
+        vm_try!(Formatter::format_with(&mut buf, |f| value.display_fmt_with(f, caller)));
+        *f.buf_mut() = buf;
 
-        value.debug_fmt_with(f, caller)
+        let (out, buf) = f.parts_mut();
+        let align = self.align.unwrap_or(Alignment::Left);
+        let padding = self.padding(buf.chars().count());
+        vm_try!(write_padded(out, self.fill, align, padding, |out| out.try_write_str(buf)));
+        VmResult::Ok(())
     }
 
-    fn format_upper_hex(&self, value: &Value, f: &mut Formatter) -> VmResult<()> {
-        match value.as_inline() {
-            Some(Inline::Signed(n)) => {
-                let (n, align, fill, sign) = self.int_traits(*n);
-                vm_try!(vm_write!(f.buf_mut(), "{:X}", n));
-                vm_try!(self.format_fill(f, align, fill, sign));
-            }
-            _ => {
-                return VmResult::err(VmErrorKind::IllegalFormat);
+    fn format_debug(
+        &self,
+        value: &Value,
+        f: &mut Formatter,
+        caller: &mut dyn ProtocolCaller,
+    ) -> VmResult<()> {
+        match value.as_ref() {
+            Repr::Inline(inline) => match inline {
+                Inline::Bool(b) => {
+                    vm_try!(f.buf_mut().try_push_str(if *b { "true" } else { "false" }));
+                    self.pad(f, true)
+                }
+                Inline::Signed(n) => self.format_integer(f, *n < 0, n.unsigned_abs()),
+                Inline::Unsigned(n) => self.format_integer(f, false, *n),
+                Inline::Float(n) => self.format_float(f, *n),
+                _ => value.debug_fmt_with(f, caller),
+            },
+            Repr::Any(any) if any.type_hash() == String::HASH => {
+                let s = vm_try!(any.borrow_ref::<String>());
+                vm_try!(vm_write!(f, "{s:?}"));
+                VmResult::Ok(())
             }
+            _ => value.debug_fmt_with(f, caller),
         }
-
-        VmResult::Ok(())
     }
 
-    fn format_lower_hex(&self, value: &Value, f: &mut Formatter) -> VmResult<()> {
+    fn format_radix(&self, value: &Value, f: &mut Formatter) -> VmResult<()> {
         match value.as_inline() {
-            Some(Inline::Signed(n)) => {
-                let (n, align, fill, sign) = self.int_traits(*n);
-                vm_try!(vm_write!(f.buf_mut(), "{:x}", n));
-                vm_try!(self.format_fill(f, align, fill, sign));
-            }
-            _ => {
-                return VmResult::err(VmErrorKind::IllegalFormat);
-            }
+            // NB: Like in Rust, signed numbers are formatted in their two's
+            // complement representation.
+            Some(Inline::Signed(n)) => self.format_integer(f, false, *n as u64),
+            Some(Inline::Unsigned(n)) => self.format_integer(f, false, *n),
+            _ => VmResult::err(VmErrorKind::IllegalFormat),
         }
-
-        VmResult::Ok(())
     }
 
-    fn format_binary(&self, value: &Value, f: &mut Formatter) -> VmResult<()> {
+    fn format_exp(&self, value: &Value, f: &mut Formatter) -> VmResult<()> {
         match value.as_inline() {
-            Some(Inline::Signed(n)) => {
-                let (n, align, fill, sign) = self.int_traits(*n);
-                vm_try!(vm_write!(f.buf_mut(), "{:b}", n));
-                vm_try!(self.format_fill(f, align, fill, sign));
-            }
-            _ => {
-                return VmResult::err(VmErrorKind::IllegalFormat);
-            }
+            Some(Inline::Signed(n)) => self.format_integer(f, *n < 0, n.unsigned_abs()),
+            Some(Inline::Unsigned(n)) => self.format_integer(f, false, *n),
+            Some(Inline::Float(n)) => self.format_float(f, *n),
+            _ => VmResult::err(VmErrorKind::IllegalFormat),
         }
-
-        VmResult::Ok(())
     }
 
     fn format_pointer(&self, value: &Value, f: &mut Formatter) -> VmResult<()> {
         match value.as_inline() {
             Some(Inline::Signed(n)) => {
-                let (n, align, fill, sign) = self.int_traits(*n);
-                vm_try!(vm_write!(f.buf_mut(), "{:p}", n as *const ()));
-                vm_try!(self.format_fill(f, align, fill, sign));
-            }
-            _ => {
-                return VmResult::err(VmErrorKind::IllegalFormat);
+                vm_try!(vm_write!(f.buf_mut(), "{:p}", *n as *const ()));
+                self.pad(f, false)
             }
+            _ => VmResult::err(VmErrorKind::IllegalFormat),
         }
-
-        VmResult::Ok(())
     }
 
     /// Format the given value to the out buffer `out`, using `buf` for
@@ -365,9 +420,10 @@ impl FormatSpec {
         match self.format_type {
             Type::Display => vm_try!(self.format_display(value, f, caller)),
             Type::Debug => vm_try!(self.format_debug(value, f, caller)),
-            Type::UpperHex => vm_try!(self.format_upper_hex(value, f)),
-            Type::LowerHex => vm_try!(self.format_lower_hex(value, f)),
-            Type::Binary => vm_try!(self.format_binary(value, f)),
+            Type::UpperHex | Type::LowerHex | Type::Octal | Type::Binary => {
+                vm_try!(self.format_radix(value, f))
+            }
+            Type::LowerExp | Type::UpperExp => vm_try!(self.format_exp(value, f)),
             Type::Pointer => vm_try!(self.format_pointer(value, f)),
         }
 
@@ -375,13 +431,40 @@ impl FormatSpec {
     }
 }
 
+/// Write padding around whatever is written by `write`.
+fn write_padded(
+    out: &mut dyn TryWrite,
+    fill: char,
+    align: Alignment,
+    padding: usize,
+    write: impl FnOnce(&mut dyn TryWrite) -> alloc::Result<()>,
+) -> alloc::Result<()> {
+    let (before, after) = match align {
+        Alignment::Left => (0, padding),
+        Alignment::Center => (padding / 2, padding - padding / 2),
+        Alignment::Right => (padding, 0),
+    };
+
+    for _ in 0..before {
+        out.try_write_char(fill)?;
+    }
+
+    write(out)?;
+
+    for _ in 0..after {
+        out.try_write_char(fill)?;
+    }
+
+    Ok(())
+}
+
 impl fmt::Display for FormatSpec {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
             "format(fill = {fill:?}, align = {align}, flags = {flags:?}, width = {width}, precision = {precision}, format_type = {format_type})",
             fill = self.fill,
-            align = self.align,
+            align = OptionDebug(self.align.as_ref()),
             flags = self.flags,
             width = OptionDebug(self.width.as_ref()),
             precision = OptionDebug(self.precision.as_ref()),
@@ -414,10 +497,16 @@ pub enum Type {
     Debug,
     /// Upper hex type.
     UpperHex,
-    /// Upper hex type.
+    /// Lower hex type.
     LowerHex,
+    /// Octal formatting type.
+    Octal,
     /// Binary formatting type.
     Binary,
+    /// Lower exponent type.
+    LowerExp,
+    /// Upper exponent type.
+    UpperExp,
     /// Pointer formatting type.
     Pointer,
 }
@@ -431,7 +520,10 @@ impl str::FromStr for Type {
             "debug" => Ok(Self::Debug),
             "upper_hex" => Ok(Self::UpperHex),
             "lower_hex" => Ok(Self::LowerHex),
+            "octal" => Ok(Self::Octal),
             "binary" => Ok(Self::Binary),
+            "lower_exp" => Ok(Self::LowerExp),
+            "upper_exp" => Ok(Self::UpperExp),
             "pointer" => Ok(Self::Pointer),
             _ => Err(TypeFromStrError),
         }
@@ -459,9 +551,18 @@ impl fmt::Display for Type {
             Self::LowerHex => {
                 write!(f, "lower_hex")?;
             }
+            Self::Octal => {
+                write!(f, "octal")?;
+            }
             Self::Binary => {
                 write!(f, "binary")?;
             }
+            Self::LowerExp => {
+                write!(f, "lower_exp")?;
+            }
+            Self::UpperExp => {
+                write!(f, "upper_exp")?;
+            }
             Self::Pointer => {
                 write!(f, "pointer")?;
             }
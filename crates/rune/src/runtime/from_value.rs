@@ -452,7 +452,7 @@ cfg_std! {
                     let mut output = <$ty>::with_capacity(object.len());
 
                     for (key, value) in object {
-                        let key = <$key>::try_from(key)?;
+                        let key = <$key>::try_from(key.try_into_string()?)?;
                         let value = <T>::from_value(value)?;
                         output.insert(key, value);
                     }
@@ -479,7 +479,7 @@ macro_rules! impl_try_map {
                 let mut output = <$ty>::try_with_capacity(object.len())?;
 
                 for (key, value) in object {
-                    let key = <$key>::try_from(key)?;
+                    let key = <$key>::try_from(key.try_into_string()?)?;
                     let value = <T>::from_value(value)?;
                     output.try_insert(key, value)?;
                 }
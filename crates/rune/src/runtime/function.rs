@@ -394,6 +394,17 @@ impl Function {
 
 /// A callable sync function. This currently only supports a subset of values
 /// that are supported by the Vm.
+///
+/// A [`SyncFunction`] is [`Send`] and [`Sync`], since it captures the
+/// [`RuntimeContext`] and [`Unit`] it belongs to when it is converted from a
+/// [`Function`], and any captured environment is converted into constant
+/// values. It can therefore be called concurrently from multiple threads
+/// without access to a [`Vm`], in which case a lightweight virtual machine is
+/// constructed for each call.
+///
+/// [`RuntimeContext`]: crate::runtime::RuntimeContext
+/// [`Unit`]: crate::Unit
+/// [`Vm`]: crate::Vm
 #[repr(transparent)]
 pub struct SyncFunction(FunctionImpl<ConstValue>);
 
@@ -473,6 +484,56 @@ impl SyncFunction {
         self.0.call(args)
     }
 
+    /// Perform a call over the function, blocking the current thread until
+    /// the future produced by an `async` function has completed.
+    ///
+    /// This behaves like [`SyncFunction::call`] for functions which are not
+    /// `async`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::Vm;
+    /// use rune::runtime::SyncFunction;
+    /// use std::sync::Arc;
+    ///
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         async fn add(a, b) {
+    ///             a + b
+    ///         }
+    ///
+    ///         pub fn main() { add }
+    ///     }
+    /// };
+    ///
+    /// let unit = rune::prepare(&mut sources).build()?;
+    /// let mut vm = Vm::without_runtime(Arc::new(unit));
+    /// let add: SyncFunction = rune::from_value(vm.call(["main"], ())?)?;
+    ///
+    /// let value = std::thread::spawn(move || add.call_blocking::<u32>((1, 2)))
+    ///     .join()
+    ///     .unwrap()
+    ///     .into_result()?;
+    ///
+    /// assert_eq!(value, 3);
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn call_blocking<T>(&self, args: impl GuardedArgs) -> VmResult<T>
+    where
+        T: FromValue,
+    {
+        let value: Value = vm_try!(self.0.call(args));
+
+        let value = match vm_try!(value.try_borrow_mut::<runtime::Future>()) {
+            Some(future) => vm_try!(block_on(future)),
+            None => value,
+        };
+
+        VmResult::Ok(vm_try!(T::from_value(value)))
+    }
+
     /// Type [Hash][struct@Hash] of the underlying function.
     ///
     /// # Examples
@@ -616,6 +677,7 @@ where
     ) -> VmResult<Option<VmHalt>> {
         let reason = match &self.inner {
             Inner::FnHandler(handler) => {
+                let _limits = crate::runtime::env::restrict(vm.nested_limits());
                 vm_try!((handler.handler)(vm.stack_mut(), addr, args, out));
                 None
             }
@@ -867,7 +929,10 @@ impl FnOffset {
             self.args
         ));
 
-        let mut vm = Vm::new(self.context.clone(), self.unit.clone());
+        let mut vm = Vm::new(self.context.clone(), self.unit.clone())
+            .with_limits(crate::runtime::env::limits())
+            .with_data(crate::runtime::env::data())
+            .with_tracer_hook(crate::runtime::env::tracer_hook());
 
         vm.set_ip(self.offset);
         let _guard = vm_try!(unsafe { args.guarded_into_stack(vm.stack_mut()) });
@@ -974,3 +1039,39 @@ fn check_args(actual: usize, expected: usize) -> VmResult<()> {
 
     VmResult::Ok(())
 }
+
+/// Drive the given future to completion, parking the current thread while it
+/// is pending.
+#[cfg(feature = "std")]
+fn block_on<F>(future: F) -> F::Output
+where
+    F: Future,
+{
+    use core::pin::pin;
+    use core::task::{Context, Poll};
+    use std::task::{Wake, Waker};
+    use std::thread::{self, Thread};
+
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+
+        fn wake_by_ref(self: &Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
@@ -7,9 +7,9 @@ use serde::{Deserialize, Serialize};
 
 use crate as rune;
 use crate::alloc::prelude::*;
-use crate::Hash;
+use crate::{Hash, TypeHash};
 
-use super::{Call, FormatSpec, Memory, RuntimeError, Type, Value};
+use super::{Call, ControlFlow, FormatSpec, Memory, RuntimeError, Type, Value};
 
 /// Pre-canned panic reasons.
 ///
@@ -176,11 +176,17 @@ pub enum Inst {
     /// The instance being called should be the the object at address `addr`.
     /// The number of arguments specified should include this object.
     ///
+    /// The function which is resolved is remembered in the inline cache slot
+    /// `cache`, so that subsequent calls on the same type of instance can skip
+    /// the lookup.
+    ///
     /// The return value of the function call will be written to `out`.
     #[musli(packed)]
     CallAssociated {
         /// The hash of the name of the function to call.
         hash: Hash,
+        /// The inline cache slot of the call site.
+        cache: usize,
         /// The address of arguments being passed.
         addr: InstAddress,
         /// The number of arguments passed in at `addr`.
@@ -274,6 +280,50 @@ pub enum Inst {
         /// Whether the produced value should be kept or not.
         out: Output,
     },
+    /// Get the given index out of a sequence from the given variable slot,
+    /// counting from the back of the sequence where `0` is the last item.
+    /// Errors if the item doesn't exist or the sequence doesn't match the
+    /// given type.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// => <value>
+    /// ```
+    #[musli(packed)]
+    SequenceIndexGetBack {
+        /// Type constraints that the sequence must match.
+        type_check: TypeCheck,
+        /// The address where the sequence we are getting from is stored.
+        addr: InstAddress,
+        /// The index to fetch, counting from the back.
+        index: usize,
+        /// Whether the produced value should be kept or not.
+        out: Output,
+    },
+    /// Construct a new sequence of the same type out of the items of the
+    /// sequence in the given variable slot, skipping `front` items at the
+    /// front and `back` items at the back. Errors if the sequence is too short
+    /// or doesn't match the given type.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// => <sequence>
+    /// ```
+    #[musli(packed)]
+    SequenceRest {
+        /// Type constraints that the sequence must match.
+        type_check: TypeCheck,
+        /// The address where the sequence is stored.
+        addr: InstAddress,
+        /// The number of items to skip at the front.
+        front: usize,
+        /// The number of items to skip at the back.
+        back: usize,
+        /// Where to store the new sequence.
+        out: Output,
+    },
     /// Set the given index out of an object on the top of the stack.
     /// Errors if the item doesn't exist or the item is not an object.
     ///
@@ -316,6 +366,24 @@ pub enum Inst {
         /// Where to store the fetched value.
         out: Output,
     },
+    /// Construct a new object out of the entries of the object in the given
+    /// variable slot, skipping the keys in the given slot of object keys.
+    /// Errors if the value is not an object.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// => <object>
+    /// ```
+    #[musli(packed)]
+    ObjectRest {
+        /// The address where the object is stored.
+        addr: InstAddress,
+        /// The slot of object keys to skip.
+        slot: usize,
+        /// Where to store the new object.
+        out: Output,
+    },
     /// Perform an index set operation.
     ///
     /// # Operation
@@ -357,6 +425,9 @@ pub enum Inst {
     ///
     /// Will also store the output if the future into `value`. If no branch
     /// matched, the empty value will be stored.
+    ///
+    /// If `default` is set, the futures are only polled once and if none of
+    /// them are ready no branch is matched.
     #[musli(packed)]
     Select {
         /// The base address of futures being waited on.
@@ -365,6 +436,9 @@ pub enum Inst {
         len: usize,
         /// Where to store the value produced by the future that completed.
         value: Output,
+        /// Whether the select has a default branch, in which case it doesn't
+        /// wait for any futures to complete.
+        default: bool,
     },
     /// Load the given function by hash and push onto the stack.
     ///
@@ -414,6 +488,28 @@ pub enum Inst {
         /// Where the value is being moved to.
         out: Output,
     },
+    /// Clone a variable from a location `offset` relative to the current call
+    /// frame using the [`CLONE`] protocol. Values which don't support the
+    /// protocol are moved instead.
+    ///
+    /// [`CLONE`]: crate::runtime::Protocol::CLONE
+    #[musli(packed)]
+    Clone {
+        /// Address of the value being cloned.
+        addr: InstAddress,
+        /// Where the value is being cloned to.
+        out: Output,
+    },
+    /// Replace the value at the given address with a clone of itself if its
+    /// type has been marked as immutable.
+    ///
+    /// This is emitted when a value is stored in a variable to give immutable
+    /// types value semantics.
+    #[musli(packed)]
+    CloneImmutable {
+        /// Address of the value to clone.
+        addr: InstAddress,
+    },
     /// Drop the given value set.
     #[musli(packed)]
     Drop {
@@ -747,6 +843,10 @@ pub enum Inst {
         addr: InstAddress,
         /// Where to store the value in case there is a continuation.
         out: Output,
+        /// The kind of value the current function is expected to return, if it
+        /// is known. Returning early with any other kind of value is an error.
+        #[inst_display(display_with = DisplayDebug::new)]
+        kind: Option<TryKind>,
     },
     /// Test if the top of the stack is a specific character.
     ///
@@ -1153,6 +1253,111 @@ impl Inst {
             out,
         }
     }
+
+    /// Get the function which is called or loaded by this instruction, if
+    /// any.
+    ///
+    /// Operations which are implemented through protocols for types which
+    /// aren't handled by the virtual machine itself are included.
+    #[cfg(any(all(feature = "unit-file", feature = "std"), feature = "emit"))]
+    pub(crate) fn call(&self) -> Option<InstCall> {
+        use super::{
+            ArithmeticOps, AssignArithmeticOps, AssignBitwiseOps, AssignShiftOps, BitwiseOps,
+            Protocol, ShiftOps,
+        };
+
+        let call = match *self {
+            Self::CallOffset { .. } => InstCall::Offset,
+            Self::Call { hash, .. } | Self::LoadFn { hash, .. } => InstCall::Function(hash),
+            Self::CallAssociated { hash, .. } => InstCall::Associated(hash),
+            Self::CallFn { .. } => InstCall::Value,
+            Self::Not { .. } => InstCall::Protocol(&Protocol::NOT),
+            Self::Neg { .. } => InstCall::Protocol(&Protocol::NEG),
+            Self::IndexGet { .. } => InstCall::Protocol(&Protocol::INDEX_GET),
+            Self::IndexSet { .. } => InstCall::Protocol(&Protocol::INDEX_SET),
+            Self::Await { .. } | Self::Select { .. } => InstCall::Protocol(&Protocol::INTO_FUTURE),
+            Self::Clone { .. } => InstCall::Protocol(&Protocol::CLONE),
+            Self::Try { .. } => InstCall::Protocol(&Protocol::TRY),
+            Self::Op { op, .. } => match op {
+                InstOp::Lt | InstOp::Le | InstOp::Gt | InstOp::Ge => {
+                    InstCall::Protocol(&Protocol::PARTIAL_CMP)
+                }
+                InstOp::Eq | InstOp::Neq => InstCall::Protocol(&Protocol::PARTIAL_EQ),
+                _ => return None,
+            },
+            Self::Arithmetic { op, .. } => InstCall::Protocol(&ArithmeticOps::from_op(op).protocol),
+            Self::Bitwise { op, .. } => InstCall::Protocol(&BitwiseOps::from_op(op).protocol),
+            Self::Shift { op, .. } => InstCall::Protocol(&ShiftOps::from_op(op).protocol),
+            Self::AssignArithmetic { op, .. } => {
+                InstCall::Protocol(&AssignArithmeticOps::from_op(op).protocol)
+            }
+            Self::AssignBitwise { op, .. } => {
+                InstCall::Protocol(&AssignBitwiseOps::from_ops(op).protocol)
+            }
+            Self::AssignShift { op, .. } => {
+                InstCall::Protocol(&AssignShiftOps::from_op(op).protocol)
+            }
+            _ => return None,
+        };
+
+        Some(call)
+    }
+}
+
+/// A function called or loaded by an instruction, as returned by
+/// [`Inst::call`].
+#[cfg(any(all(feature = "unit-file", feature = "std"), feature = "emit"))]
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum InstCall {
+    /// A function in the same unit, called by its offset.
+    Offset,
+    /// A function with the given hash, which is either defined in the unit or
+    /// provided by the context.
+    Function(Hash),
+    /// An associated function with the given name hash, which is resolved
+    /// through the type of the instance it is called on.
+    Associated(Hash),
+    /// A function value stored on the stack.
+    Value,
+    /// A protocol function, which is resolved through the type of the operand.
+    Protocol(&'static super::Protocol),
+}
+
+/// The kind of value a function returns, which a try operation returning early
+/// from it has to match.
+#[derive(Debug, TryClone, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Decode, Encode)]
+#[try_clone(copy)]
+#[non_exhaustive]
+pub enum TryKind {
+    /// The function returns an [`Option`].
+    Option,
+    /// The function returns a [`Result`].
+    Result,
+    /// The function returns a [`ControlFlow`].
+    ///
+    /// [`ControlFlow`]: crate::runtime::ControlFlow
+    ControlFlow,
+}
+
+impl TryKind {
+    /// Get the type hash of the kind.
+    pub(crate) fn type_hash(self) -> Hash {
+        match self {
+            Self::Option => Option::<Value>::HASH,
+            Self::Result => Result::<Value, Value>::HASH,
+            Self::ControlFlow => ControlFlow::HASH,
+        }
+    }
+}
+
+impl fmt::Display for TryKind {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Option => write!(fmt, "::std::option::Option"),
+            Self::Result => write!(fmt, "::std::result::Result"),
+            Self::ControlFlow => write!(fmt, "::std::ops::ControlFlow"),
+        }
+    }
 }
 
 /// What to do with the output of an instruction.
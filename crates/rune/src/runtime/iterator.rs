@@ -1,7 +1,12 @@
+use core::fmt;
+
+use crate as rune;
 use crate::alloc;
+use crate::alloc::Box;
 use crate::compile::meta;
+use crate::Any;
 
-use super::{FromValue, MaybeTypeOf, RuntimeError, Value, VmResult};
+use super::{FromValue, MaybeTypeOf, RuntimeError, ToValue, Value, VmResult};
 
 /// An owning iterator.
 #[derive(Debug)]
@@ -14,6 +19,77 @@ impl Iterator {
         Self { iter }
     }
 
+    /// Construct an iterator which is driven by the given native iterator.
+    ///
+    /// The native iterator is stored as-is, and each call to the [`NEXT`]
+    /// protocol advances it by one element. Nothing is buffered, so this is
+    /// suitable for iterating over large or lazily computed collections.
+    ///
+    /// To iterate over the contents of an external type, capture a [`Ref`] to
+    /// it in the native iterator. The collection can then not be mutated for as
+    /// long as the iterator is alive.
+    ///
+    /// [`NEXT`]: crate::runtime::Protocol::NEXT
+    /// [`Ref`]: crate::runtime::Ref
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use rune::{Any, Context, Module, Vm};
+    /// use rune::runtime::{Iterator, Ref, VmResult};
+    ///
+    /// #[derive(Any)]
+    /// struct Numbers {
+    ///     values: Vec<i64>,
+    /// }
+    ///
+    /// #[rune::function(instance, protocol = INTO_ITER)]
+    /// fn into_iter(this: Ref<Numbers>) -> VmResult<Iterator> {
+    ///     let len = this.values.len();
+    ///     let iter = (0..len).map(move |n| this.values[n]);
+    ///     VmResult::Ok(rune::vm_try!(Iterator::from_native(iter)))
+    /// }
+    ///
+    /// let mut m = Module::new();
+    /// m.ty::<Numbers>()?;
+    /// m.function_meta(into_iter)?;
+    ///
+    /// let mut context = Context::with_default_modules()?;
+    /// context.install(m)?;
+    ///
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         pub fn main(numbers) {
+    ///             let sum = 0;
+    ///
+    ///             for n in numbers {
+    ///                 sum += n;
+    ///             }
+    ///
+    ///             sum
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    /// let mut vm = Vm::new(Arc::new(context.runtime()?), Arc::new(unit));
+    ///
+    /// let numbers = Numbers { values: vec![1, 2, 3] };
+    /// let sum: i64 = rune::from_value(vm.call(["main"], (numbers,))?)?;
+    /// assert_eq!(sum, 6);
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    pub fn from_native<I>(iter: I) -> alloc::Result<Self>
+    where
+        I: 'static + core::iter::Iterator,
+        I::Item: ToValue,
+    {
+        let iter = NativeIter::new(iter)?;
+        Ok(Self::new(Value::new(iter)?))
+    }
+
     #[inline]
     pub(crate) fn size_hint(&self) -> VmResult<(usize, Option<usize>)> {
         self.iter.protocol_size_hint()
@@ -32,9 +108,83 @@ impl FromValue for Iterator {
     }
 }
 
+impl ToValue for Iterator {
+    #[inline]
+    fn to_value(self) -> Result<Value, RuntimeError> {
+        Ok(self.iter)
+    }
+}
+
 impl MaybeTypeOf for Iterator {
     #[inline]
     fn maybe_type_of() -> alloc::Result<meta::DocType> {
         Ok(meta::DocType::empty())
     }
 }
+
+/// A type-erased native iterator.
+trait Iter {
+    fn next(&mut self) -> Result<Option<Value>, RuntimeError>;
+
+    fn size_hint(&self) -> (usize, Option<usize>);
+}
+
+impl<I> Iter for I
+where
+    I: core::iter::Iterator,
+    I::Item: ToValue,
+{
+    #[inline]
+    fn next(&mut self) -> Result<Option<Value>, RuntimeError> {
+        match core::iter::Iterator::next(self) {
+            Some(value) => Ok(Some(value.to_value()?)),
+            None => Ok(None),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        core::iter::Iterator::size_hint(self)
+    }
+}
+
+/// An iterator driven by a native Rust iterator.
+///
+/// This is constructed through [`Iterator::from_native`].
+#[derive(Any)]
+#[rune(item = ::std::iter)]
+pub(crate) struct NativeIter {
+    iter: Box<dyn Iter>,
+}
+
+impl NativeIter {
+    fn new<I>(iter: I) -> alloc::Result<Self>
+    where
+        I: 'static + core::iter::Iterator,
+        I::Item: ToValue,
+    {
+        let iter: rust_alloc::boxed::Box<dyn Iter> = rust_alloc::boxed::Box::new(iter);
+
+        Ok(Self {
+            iter: Box::from_std(iter)?,
+        })
+    }
+
+    #[rune::function(keep, protocol = NEXT)]
+    #[inline]
+    fn next(&mut self) -> VmResult<Option<Value>> {
+        VmResult::Ok(vm_try!(self.iter.next()))
+    }
+
+    #[rune::function(keep, protocol = SIZE_HINT)]
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl fmt::Debug for NativeIter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NativeIter").finish_non_exhaustive()
+    }
+}
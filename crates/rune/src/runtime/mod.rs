@@ -49,10 +49,16 @@ pub use self::const_value::{
 pub(crate) use self::const_value::{ConstContext, ConstValueKind, EmptyConstContext};
 
 pub mod debug;
-pub use self::debug::{DebugInfo, DebugInst};
+pub use self::debug::{DebugInfo, DebugInst, DebugSource, DebugVariable};
+
+#[cfg(feature = "debug-access")]
+pub(crate) mod debug_access;
 
 mod env;
 
+mod execution_snapshot;
+pub use self::execution_snapshot::ExecutionSnapshot;
+
 pub mod format;
 pub use self::format::{Format, FormatSpec};
 
@@ -78,13 +84,16 @@ mod guarded_args;
 pub use self::guarded_args::GuardedArgs;
 
 mod inst;
+#[cfg(any(all(feature = "unit-file", feature = "std"), feature = "emit"))]
+pub(crate) use self::inst::InstCall;
 pub use self::inst::{
     Inst, InstAddress, InstArithmeticOp, InstBitwiseOp, InstOp, InstRange, InstShiftOp, InstTarget,
-    InstValue, InstVariant, IntoOutput, Output, PanicReason, TypeCheck,
+    InstValue, InstVariant, IntoOutput, Output, PanicReason, TryKind, TypeCheck,
 };
 
 mod iterator;
 pub use self::iterator::Iterator;
+pub(crate) use self::iterator::NativeIter;
 
 mod type_;
 pub use self::type_::Type;
@@ -96,6 +105,10 @@ pub(crate) use self::label::Label;
 pub(crate) mod object;
 pub use self::object::Object;
 
+mod object_key;
+pub use self::object_key::ObjectKey;
+pub(crate) use self::object_key::ObjectKeys;
+
 mod panic;
 pub(crate) use self::panic::{BoxedPanic, Panic};
 
@@ -124,8 +137,8 @@ pub(crate) mod range;
 pub use self::range::Range;
 
 mod runtime_context;
-pub(crate) use self::runtime_context::FunctionHandler;
 pub use self::runtime_context::RuntimeContext;
+pub(crate) use self::runtime_context::{CloneHandler, FunctionHandler, SerializeHandler};
 
 mod select;
 pub(crate) use self::select::Select;
@@ -156,14 +169,19 @@ pub use self::type_info::{AnyTypeInfo, TypeInfo};
 mod type_of;
 pub use self::type_of::{MaybeTypeOf, TypeHash, TypeOf};
 
+mod typed_function;
+pub use self::typed_function::{TypedArgs, TypedFunction};
+
 pub mod unit;
 pub(crate) use self::unit::UnitFn;
-pub use self::unit::{Unit, UnitStorage};
+pub use self::unit::{FunctionInfo, Unit, UnitDiff, UnitStorage};
 
 mod value;
+pub(crate) use self::value::{from_serializable, to_serializable};
 pub use self::value::{
-    Accessor, EmptyStruct, Inline, RawValueGuard, Rtti, Struct, TupleStruct, TypeValue, Value,
-    ValueMutGuard, ValueRefGuard,
+    from_value_to_deserialize, to_value_from_serialize, Accessor, EmptyStruct, Inline,
+    RawValueGuard, Rtti, SerdeError, Struct, TupleStruct, TypeValue, Value, ValueMutGuard,
+    ValueRefGuard,
 };
 pub(crate) use self::value::{Dynamic, DynamicTakeError, Repr, RttiKind};
 
@@ -176,19 +194,33 @@ mod vec_tuple;
 pub use self::vec_tuple::VecTuple;
 
 mod vm;
+pub(crate) use self::vm::ops::{
+    ArithmeticOps, AssignArithmeticOps, AssignBitwiseOps, AssignShiftOps, BitwiseOps, ShiftOps,
+};
 use self::vm::CallResultOnly;
 pub use self::vm::{CallFrame, Isolated, Vm};
 
 mod vm_call;
 pub(crate) use self::vm_call::VmCall;
 
+mod vm_data;
+pub use self::vm_data::VmData;
+
+mod vm_debugger;
+pub use self::vm_debugger::{Debugger, VmFrame};
+
+mod vm_tracer;
+pub use self::vm_tracer::Tracer;
+#[cfg(feature = "std")]
+pub use self::vm_tracer::{CollectingTracer, TraceEntry};
+
 pub(crate) mod vm_diagnostics;
 pub(crate) use self::vm_diagnostics::{VmDiagnostics, VmDiagnosticsObj};
 
 mod vm_error;
 #[cfg(feature = "emit")]
 pub(crate) use self::vm_error::VmErrorAt;
-pub use self::vm_error::{try_result, RuntimeError, TryFromResult, VmError, VmResult};
+pub use self::vm_error::{try_result, FrameInfo, RuntimeError, TryFromResult, VmError, VmResult};
 pub(crate) use self::vm_error::{VmErrorKind, VmIntegerRepr};
 
 mod vm_execution;
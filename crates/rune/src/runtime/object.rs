@@ -10,7 +10,8 @@ use crate::alloc::hashbrown::raw::RawIter;
 use crate::alloc::prelude::*;
 use crate::alloc::{self, String};
 use crate::runtime::{
-    FieldMap, FromValue, ProtocolCaller, RawAnyGuard, Ref, ToValue, Value, VmError, VmResult,
+    FieldMap, FromValue, ObjectKey, ProtocolCaller, RawAnyGuard, Ref, ToValue, Value, VmError,
+    VmResult,
 };
 use crate::Any;
 
@@ -19,9 +20,13 @@ use crate::Any;
 /// This `struct` is created by the [`into_iter`] method on [`Object`]
 /// (provided by the `IntoIterator` trait). See its documentation for more.
 ///
+/// Keys are yielded as the [`ObjectKey`] they are stored as, since turning a
+/// shared key into a [`String`] would have to allocate. Use
+/// [`ObjectKey::try_into_string`] where an owned string is needed.
+///
 /// [`into_iter`]: struct.Object.html#method.into_iter
 /// [`Object`]: struct.Object.html
-pub type IntoIter = hash_map::IntoIter<String, Value>;
+pub type IntoIter = hash_map::IntoIter<ObjectKey, Value>;
 
 /// A mutable iterator over the entries of a `Object`.
 ///
@@ -30,7 +35,34 @@ pub type IntoIter = hash_map::IntoIter<String, Value>;
 ///
 /// [`iter_mut`]: struct.Object.html#method.iter_mut
 /// [`Object`]: struct.Object.html
-pub type IterMut<'a> = hash_map::IterMut<'a, String, Value>;
+#[derive(Debug)]
+pub struct IterMut<'a> {
+    iter: hash_map::IterMut<'a, ObjectKey, Value>,
+}
+
+impl<'a> Iterator for IterMut<'a> {
+    type Item = (&'a String, &'a mut Value);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, value) = self.iter.next()?;
+        Some((key.as_string(), value))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl ExactSizeIterator for IterMut<'_> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl iter::FusedIterator for IterMut<'_> {}
 
 /// An iterator over the entries of a `Object`.
 ///
@@ -39,7 +71,34 @@ pub type IterMut<'a> = hash_map::IterMut<'a, String, Value>;
 ///
 /// [`iter`]: struct.Object.html#method.iter
 /// [`Object`]: struct.Object.html
-pub type Iter<'a> = hash_map::Iter<'a, String, Value>;
+#[derive(Debug, Clone)]
+pub struct Iter<'a> {
+    iter: hash_map::Iter<'a, ObjectKey, Value>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (&'a String, &'a Value);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, value) = self.iter.next()?;
+        Some((key.as_string(), value))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl iter::FusedIterator for Iter<'_> {}
 
 /// An iterator over the keys of a `HashMap`.
 ///
@@ -48,7 +107,33 @@ pub type Iter<'a> = hash_map::Iter<'a, String, Value>;
 ///
 /// [`keys`]: struct.Object.html#method.keys
 /// [`Object`]: struct.Object.html
-pub type Keys<'a> = hash_map::Keys<'a, String, Value>;
+#[derive(Debug, Clone)]
+pub struct Keys<'a> {
+    iter: hash_map::Keys<'a, ObjectKey, Value>,
+}
+
+impl<'a> Iterator for Keys<'a> {
+    type Item = &'a String;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.iter.next()?.as_string())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Keys<'_> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl iter::FusedIterator for Keys<'_> {}
 
 /// An iterator over the values of a `HashMap`.
 ///
@@ -57,7 +142,7 @@ pub type Keys<'a> = hash_map::Keys<'a, String, Value>;
 ///
 /// [`values`]: struct.Object.html#method.values
 /// [`Object`]: struct.Object.html
-pub type Values<'a> = hash_map::Values<'a, String, Value>;
+pub type Values<'a> = hash_map::Values<'a, ObjectKey, Value>;
 
 /// Struct representing a dynamic anonymous object.
 ///
@@ -82,7 +167,7 @@ pub type Values<'a> = hash_map::Values<'a, String, Value>;
 #[repr(transparent)]
 #[rune(item = ::std::object)]
 pub struct Object {
-    inner: FieldMap<String, Value>,
+    inner: FieldMap<ObjectKey, Value>,
 }
 
 impl Object {
@@ -160,7 +245,7 @@ impl Object {
     #[inline]
     pub fn get<Q>(&self, k: &Q) -> Option<&Value>
     where
-        String: borrow::Borrow<Q>,
+        ObjectKey: borrow::Borrow<Q>,
         Q: ?Sized + hash::Hash + cmp::Eq + cmp::Ord,
     {
         self.inner.get(k)
@@ -169,7 +254,7 @@ impl Object {
     /// Get the given value at the given index.
     pub fn get_value<Q, T>(&self, k: &Q) -> VmResult<Option<T>>
     where
-        String: borrow::Borrow<Q>,
+        ObjectKey: borrow::Borrow<Q>,
         Q: ?Sized + hash::Hash + cmp::Eq + cmp::Ord,
         T: FromValue,
     {
@@ -185,7 +270,7 @@ impl Object {
     #[inline]
     pub fn get_mut<Q>(&mut self, k: &Q) -> Option<&mut Value>
     where
-        String: borrow::Borrow<Q>,
+        ObjectKey: borrow::Borrow<Q>,
         Q: ?Sized + hash::Hash + cmp::Eq + cmp::Ord,
     {
         self.inner.get_mut(k)
@@ -195,7 +280,7 @@ impl Object {
     #[inline]
     pub fn contains_key<Q>(&self, k: &Q) -> bool
     where
-        String: borrow::Borrow<Q>,
+        ObjectKey: borrow::Borrow<Q>,
         Q: ?Sized + hash::Hash + cmp::Eq + cmp::Ord,
     {
         self.inner.contains_key(k)
@@ -206,7 +291,7 @@ impl Object {
     #[inline]
     pub fn remove<Q>(&mut self, k: &Q) -> Option<Value>
     where
-        String: borrow::Borrow<Q>,
+        ObjectKey: borrow::Borrow<Q>,
         Q: ?Sized + hash::Hash + cmp::Eq + cmp::Ord,
     {
         self.inner.remove(k)
@@ -215,11 +300,12 @@ impl Object {
     /// Inserts a key-value pair into the dynamic object, converting it as
     /// necessary through the [`ToValue`] trait.
     #[inline]
-    pub fn insert_value<T>(&mut self, k: String, v: T) -> VmResult<()>
+    pub fn insert_value<K, T>(&mut self, k: K, v: T) -> VmResult<()>
     where
+        K: Into<ObjectKey>,
         T: ToValue,
     {
-        vm_try!(self.inner.try_insert(k, vm_try!(v.to_value())));
+        vm_try!(self.inner.try_insert(k.into(), vm_try!(v.to_value())));
         VmResult::Ok(())
     }
 
@@ -241,15 +327,21 @@ impl Object {
     #[inline]
     #[rune::function(path = Self::insert)]
     pub(crate) fn rune_insert(&mut self, k: String, v: Value) -> VmResult<Option<Value>> {
-        VmResult::Ok(vm_try!(self.inner.try_insert(k, v)))
+        VmResult::Ok(vm_try!(self.inner.try_insert(ObjectKey::from(k), v)))
     }
 
     /// Inserts a key-value pair into the map.
     ///
     /// If the map did not have this key present, `None` is returned.
+    ///
+    /// The key can either be a [`String`] or an [`ObjectKey`], where the
+    /// latter avoids allocating if the key is shared.
     #[inline]
-    pub fn insert(&mut self, k: String, v: Value) -> alloc::Result<Option<Value>> {
-        self.inner.try_insert(k, v)
+    pub fn insert<K>(&mut self, k: K, v: Value) -> alloc::Result<Option<Value>>
+    where
+        K: Into<ObjectKey>,
+    {
+        self.inner.try_insert(k.into(), v)
     }
 
     /// Clears the object, removing all key-value pairs. Keeps the allocated
@@ -263,13 +355,25 @@ impl Object {
     /// An iterator visiting all key-value pairs in arbitrary order.
     /// The iterator element type is `(&'a String, &'a Value)`.
     pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            iter: self.inner.iter(),
+        }
+    }
+
+    /// An iterator visiting all key-value pairs in arbitrary order, where the
+    /// keys are the [`ObjectKey`] they are stored as.
+    ///
+    /// Cloning these keys doesn't allocate if they are shared.
+    pub(crate) fn entries(&self) -> hash_map::Iter<'_, ObjectKey, Value> {
         self.inner.iter()
     }
 
     /// An iterator visiting all keys in arbitrary order.
     /// The iterator element type is `&'a String`.
     pub fn keys(&self) -> Keys<'_> {
-        self.inner.keys()
+        Keys {
+            iter: self.inner.keys(),
+        }
     }
 
     /// An iterator visiting all values in arbitrary order.
@@ -283,7 +387,9 @@ impl Object {
     ///
     /// The iterator element type is `(&'a String, &'a mut Value)`.
     pub fn iter_mut(&mut self) -> IterMut<'_> {
-        self.inner.iter_mut()
+        IterMut {
+            iter: self.inner.iter_mut(),
+        }
     }
 
     /// An iterator visiting all keys and values in arbitrary order.
@@ -367,8 +473,8 @@ impl Object {
             return VmResult::Ok(false);
         }
 
-        for (k1, v1) in a.iter() {
-            let Some(v2) = b.get(k1) else {
+        for (k1, v1) in a.inner.iter() {
+            let Some(v2) = b.inner.get(k1) else {
                 return VmResult::Ok(false);
             };
 
@@ -431,7 +537,7 @@ impl<'a> IntoIterator for &'a mut Object {
 }
 
 impl IntoIterator for Object {
-    type Item = (String, Value);
+    type Item = (ObjectKey, Value);
     type IntoIter = IntoIter;
 
     /// Creates a consuming iterator, that is, one that moves each key-value
@@ -452,7 +558,7 @@ impl fmt::Debug for Object {
 #[derive(Any)]
 #[rune(item = ::std::object, name = Iter)]
 pub struct RuneIter {
-    iter: RawIter<(String, Value)>,
+    iter: RawIter<(ObjectKey, Value)>,
     #[allow(unused)]
     guard: RawAnyGuard,
 }
@@ -466,7 +572,7 @@ impl RuneIter {
             };
 
             let (key, value) = bucket.as_ref();
-            let key = vm_try!(key.try_clone());
+            let key = vm_try!(key.as_str().try_to_owned());
             VmResult::Ok(Some((key, value.clone())))
         }
     }
@@ -498,7 +604,7 @@ impl iter::Iterator for RuneIter {
 #[derive(Any)]
 #[rune(item = ::std::object, name = Keys)]
 pub struct RuneIterKeys {
-    iter: RawIter<(String, Value)>,
+    iter: RawIter<(ObjectKey, Value)>,
     #[allow(unused)]
     guard: RawAnyGuard,
 }
@@ -512,7 +618,7 @@ impl RuneIterKeys {
             };
 
             let (key, _) = bucket.as_ref();
-            let key = vm_try!(key.try_clone());
+            let key = vm_try!(key.as_str().try_to_owned());
             VmResult::Ok(Some(key))
         }
     }
@@ -544,7 +650,7 @@ impl iter::Iterator for RuneIterKeys {
 #[derive(Any)]
 #[rune(item = ::std::object, name = Values)]
 pub struct RuneValues {
-    iter: RawIter<(String, Value)>,
+    iter: RawIter<(ObjectKey, Value)>,
     #[allow(unused)]
     guard: RawAnyGuard,
 }
@@ -0,0 +1,365 @@
+use core::borrow::Borrow;
+use core::cmp;
+use core::fmt;
+use core::hash;
+use core::ops;
+use core::ptr::NonNull;
+use core::sync::atomic::{self, AtomicUsize, Ordering};
+
+use serde::de;
+use serde::{Deserialize, Serialize};
+
+use crate::alloc::alloc::Global;
+use crate::alloc::prelude::*;
+use crate::alloc::{self, Box, HashSet, String, Vec};
+
+/// The key of an [`Object`].
+///
+/// A key is either an owned string, or a handle to a string which is shared
+/// with other keys. Shared keys are reference counted, so cloning them does
+/// not allocate. This is what the keys of object literals use, since they are
+/// interned in the unit they are defined in and copied into every object
+/// constructed from them.
+///
+/// Keys compare, order and hash exactly like the strings they contain,
+/// regardless of whether they are shared or not.
+///
+/// [`Object`]: crate::runtime::Object
+///
+/// # Examples
+///
+/// ```
+/// use rune::alloc::String;
+/// use rune::alloc::prelude::*;
+/// use rune::runtime::ObjectKey;
+///
+/// let a = ObjectKey::shared("hello")?;
+/// let b = a.try_clone()?;
+/// let c = ObjectKey::from(String::try_from("hello")?);
+///
+/// assert!(a.is_shared());
+/// assert!(ObjectKey::ptr_eq(&a, &b));
+/// assert!(!c.is_shared());
+/// assert_eq!(a, c);
+/// # Ok::<_, rune::alloc::Error>(())
+/// ```
+pub struct ObjectKey {
+    repr: Repr,
+}
+
+enum Repr {
+    Owned(String),
+    Shared(SharedStr),
+}
+
+/// A reference counted handle to a shared string.
+struct SharedStr {
+    ptr: NonNull<Shared>,
+}
+
+/// The reference counted allocation backing a shared key.
+struct Shared {
+    count: AtomicUsize,
+    string: String,
+}
+
+// SAFETY: Shared strings are immutable and reference counted atomically.
+unsafe impl Send for SharedStr {}
+unsafe impl Sync for SharedStr {}
+
+impl SharedStr {
+    #[inline]
+    fn as_string(&self) -> &String {
+        // SAFETY: The allocation is kept alive by the reference we hold.
+        unsafe { &self.ptr.as_ref().string }
+    }
+}
+
+impl Drop for SharedStr {
+    fn drop(&mut self) {
+        // SAFETY: The allocation is kept alive by the reference we hold.
+        unsafe {
+            if self.ptr.as_ref().count.fetch_sub(1, Ordering::Release) != 1 {
+                return;
+            }
+
+            atomic::fence(Ordering::Acquire);
+            drop(Box::from_raw_in(self.ptr.as_ptr(), Global));
+        }
+    }
+}
+
+impl TryClone for SharedStr {
+    fn try_clone(&self) -> alloc::Result<Self> {
+        // SAFETY: The allocation is kept alive by the reference we hold.
+        let old = unsafe { self.ptr.as_ref().count.fetch_add(1, Ordering::Relaxed) };
+
+        if old > isize::MAX as usize {
+            crate::alloc::abort();
+        }
+
+        Ok(Self { ptr: self.ptr })
+    }
+}
+
+impl ObjectKey {
+    /// Construct a new shared key.
+    ///
+    /// Both the string and the reference count are allocated through the
+    /// rune allocator, so they are subject to the limits set through
+    /// [`rune::alloc::limit`].
+    ///
+    /// [`rune::alloc::limit`]: crate::alloc::limit
+    pub fn shared(string: &str) -> alloc::Result<Self> {
+        let shared = Box::try_new(Shared {
+            count: AtomicUsize::new(1),
+            string: String::try_from(string)?,
+        })?;
+
+        let (ptr, Global) = Box::into_raw_with_allocator(shared);
+
+        // SAFETY: Pointers produced by a box are non-null.
+        let ptr = unsafe { NonNull::new_unchecked(ptr) };
+
+        Ok(Self {
+            repr: Repr::Shared(SharedStr { ptr }),
+        })
+    }
+
+    /// Test if the key is shared.
+    #[inline]
+    pub fn is_shared(&self) -> bool {
+        matches!(self.repr, Repr::Shared(..))
+    }
+
+    /// Test if two keys are the same shared key.
+    #[inline]
+    pub fn ptr_eq(a: &Self, b: &Self) -> bool {
+        match (&a.repr, &b.repr) {
+            (Repr::Shared(a), Repr::Shared(b)) => a.ptr == b.ptr,
+            _ => false,
+        }
+    }
+
+    /// Access the key as a string.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        self.as_string().as_str()
+    }
+
+    /// Access the key as a reference to a string.
+    #[inline]
+    pub(crate) fn as_string(&self) -> &String {
+        match &self.repr {
+            Repr::Owned(string) => string,
+            Repr::Shared(shared) => shared.as_string(),
+        }
+    }
+
+    /// Convert the key into an owned string.
+    ///
+    /// This only allocates if the key is shared.
+    pub fn try_into_string(self) -> alloc::Result<String> {
+        match self.repr {
+            Repr::Owned(string) => Ok(string),
+            Repr::Shared(shared) => shared.as_string().try_clone(),
+        }
+    }
+}
+
+impl TryClone for ObjectKey {
+    fn try_clone(&self) -> alloc::Result<Self> {
+        let repr = match &self.repr {
+            Repr::Owned(string) => Repr::Owned(string.try_clone()?),
+            Repr::Shared(shared) => Repr::Shared(shared.try_clone()?),
+        };
+
+        Ok(Self { repr })
+    }
+}
+
+impl From<String> for ObjectKey {
+    #[inline]
+    fn from(string: String) -> Self {
+        Self {
+            repr: Repr::Owned(string),
+        }
+    }
+}
+
+impl TryFrom<&str> for ObjectKey {
+    type Error = alloc::Error;
+
+    #[inline]
+    fn try_from(string: &str) -> alloc::Result<Self> {
+        Ok(Self::from(String::try_from(string)?))
+    }
+}
+
+impl ops::Deref for ObjectKey {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for ObjectKey {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Borrow<str> for ObjectKey {
+    #[inline]
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl cmp::PartialEq for ObjectKey {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        Self::ptr_eq(self, other) || self.as_str() == other.as_str()
+    }
+}
+
+impl cmp::Eq for ObjectKey {}
+
+impl cmp::PartialEq<str> for ObjectKey {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl cmp::PartialEq<&str> for ObjectKey {
+    #[inline]
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl cmp::PartialOrd for ObjectKey {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl cmp::Ord for ObjectKey {
+    #[inline]
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl hash::Hash for ObjectKey {
+    #[inline]
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl fmt::Display for ObjectKey {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+impl fmt::Debug for ObjectKey {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+impl Serialize for ObjectKey {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ObjectKey {
+    /// Deserialized keys are shared, since they are stored in units.
+    ///
+    /// Each key is allocated separately. Units intern their static object keys
+    /// when deserialized, so that equal keys share one allocation.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct Visitor;
+
+        impl de::Visitor<'_> for Visitor {
+            type Value = ObjectKey;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("an object key")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                ObjectKey::shared(v).map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(Visitor)
+    }
+}
+
+/// A table of interned object keys, so that equal keys share the same
+/// allocation.
+#[derive(Debug, Default)]
+pub(crate) struct ObjectKeys {
+    keys: HashSet<ObjectKey>,
+}
+
+impl ObjectKeys {
+    /// Intern the given key.
+    pub(crate) fn intern(&mut self, key: &str) -> alloc::Result<ObjectKey> {
+        if let Some(existing) = self.keys.get(key) {
+            return existing.try_clone();
+        }
+
+        let key = ObjectKey::shared(key)?;
+        self.keys.try_insert(key.try_clone()?)?;
+        Ok(key)
+    }
+}
+
+/// Deserialize collections of static object keys, interning every key so that
+/// they are shared like when the unit was built.
+pub(crate) fn deserialize_interned<'de, D>(
+    deserializer: D,
+) -> Result<Vec<Box<[ObjectKey]>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let collections = Vec::<Vec<String>>::deserialize(deserializer)?;
+
+    let mut keys = ObjectKeys::default();
+    let mut output = Vec::try_with_capacity(collections.len()).map_err(de::Error::custom)?;
+
+    for collection in collections {
+        let collection = collection
+            .iter()
+            .map(|key| keys.intern(key))
+            .try_collect::<alloc::Result<Box<[_]>>>()
+            .map_err(de::Error::custom)?
+            .map_err(de::Error::custom)?;
+
+        output.try_push(collection).map_err(de::Error::custom)?;
+    }
+
+    Ok(output)
+}
@@ -73,7 +73,10 @@ impl ProtocolCaller for EnvProtocolCaller {
                 let mut stack = vm_try!(Stack::with_capacity(count));
                 vm_try!(stack.push(target));
                 vm_try!(args.push_to_stack(&mut stack));
-                let mut vm = Vm::with_stack(context.clone(), unit.clone(), stack);
+                let mut vm = Vm::with_stack(context.clone(), unit.clone(), stack)
+                    .with_limits(crate::runtime::env::limits())
+                    .with_data(crate::runtime::env::data())
+                    .with_tracer_hook(crate::runtime::env::tracer_hook());
                 vm.set_ip(*offset);
                 return VmResult::Ok(CallResultOnly::Ok(vm_try!(call.call_with_vm(vm))));
             }
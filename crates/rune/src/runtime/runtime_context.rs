@@ -5,13 +5,30 @@ use ::rust_alloc::sync::Arc;
 use crate as rune;
 use crate::alloc::prelude::*;
 use crate::hash;
-use crate::runtime::{ConstConstruct, ConstValue, InstAddress, Memory, Output, VmResult};
-use crate::Hash;
+use crate::runtime::{
+    ConstConstruct, ConstContext, ConstValue, InstAddress, Memory, Output, SerdeError, Value,
+    VmResult,
+};
+use crate::{Hash, ItemBuf};
 
 /// A type-reduced function handler.
 pub(crate) type FunctionHandler =
     dyn Fn(&mut dyn Memory, InstAddress, usize, Output) -> VmResult<()> + Send + Sync;
 
+/// A type-reduced handler used to clone values of immutable types.
+pub(crate) type CloneHandler = dyn Fn(&Value) -> VmResult<Value> + Send + Sync;
+
+/// Type-reduced handlers used to convert values of external types to and from
+/// a representation which can be serialized.
+pub(crate) struct SerializeHandler {
+    /// The item of the type.
+    pub(crate) item: ItemBuf,
+    /// Convert a value of the type into a serializable value.
+    pub(crate) to_value: fn(&Value) -> Result<Value, SerdeError>,
+    /// Construct a value of the type from a deserialized value.
+    pub(crate) from_value: fn(Value) -> Result<Value, SerdeError>,
+}
+
 /// Static run context visible to the virtual machine.
 ///
 /// This contains:
@@ -26,6 +43,10 @@ pub struct RuntimeContext {
     constants: hash::Map<ConstValue>,
     /// Constant constructors.
     construct: hash::Map<Arc<dyn ConstConstruct>>,
+    /// Clone handlers for types with value semantics.
+    immutable: hash::Map<Arc<CloneHandler>>,
+    /// Serialization handlers for external types.
+    serialize: hash::Map<Arc<SerializeHandler>>,
 }
 
 assert_impl!(RuntimeContext: Send + Sync);
@@ -35,11 +56,15 @@ impl RuntimeContext {
         functions: hash::Map<Arc<FunctionHandler>>,
         constants: hash::Map<ConstValue>,
         construct: hash::Map<Arc<dyn ConstConstruct>>,
+        immutable: hash::Map<Arc<CloneHandler>>,
+        serialize: hash::Map<Arc<SerializeHandler>>,
     ) -> Self {
         Self {
             functions,
             constants,
             construct,
+            immutable,
+            serialize,
         }
     }
 
@@ -60,6 +85,82 @@ impl RuntimeContext {
     pub(crate) fn construct(&self, hash: &Hash) -> Option<&dyn ConstConstruct> {
         Some(&**self.construct.get(hash)?)
     }
+
+    /// Lookup the clone handler for a type which has been marked as
+    /// immutable.
+    #[inline]
+    pub(crate) fn immutable(&self, hash: &Hash) -> Option<&CloneHandler> {
+        Some(&**self.immutable.get(hash)?)
+    }
+
+    /// Lookup the serialization handler for a type which has been marked as
+    /// serializable.
+    #[inline]
+    pub(crate) fn serialize_handler(&self, hash: &Hash) -> Option<&SerializeHandler> {
+        Some(&**self.serialize.get(hash)?)
+    }
+
+    /// Convert a value into a value which can be serialized.
+    ///
+    /// Values of external types which have been marked as [serializable] are
+    /// replaced with an object tagged with the hash and item of their type,
+    /// while values of other external types cause an error.
+    ///
+    /// Use [`RuntimeContext::from_serializable`] to convert a deserialized
+    /// value back.
+    ///
+    /// [serializable]: crate::module::TypeMut::serializable
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::{Any, Context, Module, Value};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Any, Debug, PartialEq, Serialize, Deserialize)]
+    /// #[rune(serialize)]
+    /// struct Point {
+    ///     x: i64,
+    ///     y: i64,
+    /// }
+    ///
+    /// let mut m = Module::new();
+    /// m.ty::<Point>()?;
+    ///
+    /// let mut context = Context::new();
+    /// context.install(m)?;
+    /// let runtime = context.runtime()?;
+    ///
+    /// let value = rune::to_value((Point { x: 1, y: 2 }, 42))?;
+    ///
+    /// let json = serde_json::to_string(&runtime.to_serializable(&value)?)?;
+    /// let value = runtime.from_serializable(serde_json::from_str::<Value>(&json)?)?;
+    ///
+    /// let (point, n) = rune::from_value::<(Point, i64)>(value)?;
+    /// assert_eq!(point, Point { x: 1, y: 2 });
+    /// assert_eq!(n, 42);
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    pub fn to_serializable(&self, value: &Value) -> Result<Value, SerdeError> {
+        crate::runtime::to_serializable(self, value)
+    }
+
+    /// Convert a deserialized value back, constructing values of external
+    /// types from their tagged representation as produced by
+    /// [`RuntimeContext::to_serializable`].
+    ///
+    /// Values tagged with a type which isn't registered in this context cause
+    /// an error, see [`SerdeError::unregistered_type`].
+    pub fn from_serializable(&self, value: Value) -> Result<Value, SerdeError> {
+        crate::runtime::from_serializable(self, value)
+    }
+}
+
+impl ConstContext for RuntimeContext {
+    #[inline]
+    fn get(&self, hash: Hash) -> Option<&dyn ConstConstruct> {
+        self.construct(&hash)
+    }
 }
 
 impl fmt::Debug for RuntimeContext {
@@ -2,22 +2,28 @@ use core::future;
 use core::pin::Pin;
 use core::task::{Context, Poll};
 
-use futures_core::Stream;
-use futures_util::stream::FuturesUnordered;
-
-use crate::runtime::future::SelectFuture;
+use crate::alloc::Vec;
 use crate::runtime::{Future, Mut, Value, VmResult};
 
 /// A stored select.
+///
+/// The futures being selected over are polled in the order of their branches,
+/// starting at a branch which rotates between selects so that none of them is
+/// starved. Futures which are not ready are left as-is. Since the futures are
+/// shared with the values they were loaded from, a future which loses a select
+/// keeps its progress and can be selected over again.
 #[derive(Debug)]
 pub struct Select {
-    futures: FuturesUnordered<SelectFuture<usize, Mut<Future>>>,
+    futures: Vec<(usize, Mut<Future>)>,
+    start: usize,
 }
 
 impl Select {
-    /// Construct a new stored select.
-    pub(crate) fn new(futures: FuturesUnordered<SelectFuture<usize, Mut<Future>>>) -> Self {
-        Self { futures }
+    /// Construct a new stored select over the given futures, each associated
+    /// with the branch to jump to if it completes, which polls the future at
+    /// `start` first.
+    pub(crate) fn new(futures: Vec<(usize, Mut<Future>)>, start: usize) -> Self {
+        Self { futures, start }
     }
 }
 
@@ -25,13 +31,15 @@ impl future::Future for Select {
     type Output = VmResult<(usize, Value)>;
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        let poll = Pin::new(&mut self.futures).poll_next(cx);
+        let this = &mut *self;
+        let (head, tail) = this.futures.split_at_mut(this.start);
 
-        let poll = match poll {
-            Poll::Ready(poll) => poll.expect("inner stream should never end"),
-            Poll::Pending => return Poll::Pending,
-        };
+        for (branch, future) in tail.iter_mut().chain(head) {
+            if let Poll::Ready(result) = Pin::new(future).poll(cx) {
+                return Poll::Ready(VmResult::Ok((*branch, vm_try!(result))));
+            }
+        }
 
-        Poll::Ready(poll)
+        Poll::Pending
     }
 }
@@ -231,6 +231,11 @@ impl Stack {
         }
     }
 
+    /// Construct a stack from its raw parts.
+    pub(crate) fn from_parts(stack: Vec<Value>, top: usize) -> Self {
+        Self { stack, top }
+    }
+
     /// Access the value at the given frame offset.
     ///
     /// # Examples
@@ -379,6 +384,11 @@ impl Stack {
         self.stack.get(index)
     }
 
+    /// Access all values on the stack, regardless of the current stack frame.
+    pub(crate) fn as_slice(&self) -> &[Value] {
+        &self.stack
+    }
+
     /// Push a value onto the stack.
     pub(crate) fn push<T>(&mut self, value: T) -> alloc::Result<()>
     where
@@ -411,7 +421,6 @@ impl Stack {
     /// The stack is partitioned into call frames, and once we enter a call
     /// frame the bottom of the stack corresponds to the bottom of the current
     /// call frame.
-    #[cfg_attr(not(feature = "tracing"), allow(unused))]
     pub(crate) const fn top(&self) -> usize {
         self.top
     }
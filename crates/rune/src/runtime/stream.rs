@@ -1,17 +1,24 @@
 use core::fmt;
+use core::future::poll_fn;
+use core::pin::Pin;
+use core::task::{Context, Poll};
 
 use crate as rune;
+use crate::alloc;
 use crate::alloc::clone::TryClone;
 use crate::alloc::fmt::TryWrite;
+use crate::alloc::Box;
 use crate::runtime::{
-    Formatter, GeneratorState, Mut, Value, Vm, VmErrorKind, VmExecution, VmResult,
+    budget, Formatter, GeneratorState, Mut, ToValue, Value, Vm, VmError, VmErrorKind, VmExecution,
+    VmHaltInfo, VmResult,
 };
 use crate::Any;
 
-/// A stream produced by an async generator function.
+/// A stream produced by an async generator function, or by a native stream.
 ///
 /// Generator are async functions or closures which contain the `yield`
-/// expressions.
+/// expressions. Native streams are constructed using
+/// [`Stream::from_native`].
 ///
 /// # Examples
 ///
@@ -33,7 +40,21 @@ pub struct Stream<T = Vm>
 where
     T: AsRef<Vm> + AsMut<Vm>,
 {
-    execution: Option<VmExecution<T>>,
+    inner: Option<Inner<T>>,
+}
+
+/// The source of values produced by a stream.
+enum Inner<T>
+where
+    T: AsRef<Vm> + AsMut<Vm>,
+{
+    /// The execution of an async generator.
+    Execution(VmExecution<T>),
+    /// A value holding a [`NativeStream`].
+    ///
+    /// This is stored as a value so that clones of the stream share it, and
+    /// so that access to it is guarded if it's polled from multiple clones.
+    Native(Value),
 }
 
 impl<T> Stream<T>
@@ -43,53 +64,73 @@ where
     /// Construct a stream from a virtual machine.
     pub(crate) fn new(vm: T) -> Self {
         Self {
-            execution: Some(VmExecution::new(vm)),
+            inner: Some(Inner::Execution(VmExecution::new(vm))),
         }
     }
 
     /// Construct a generator from a complete execution.
     pub(crate) fn from_execution(execution: VmExecution<T>) -> Self {
         Self {
-            execution: Some(execution),
+            inner: Some(Inner::Execution(execution)),
         }
     }
 
     /// Get the next value produced by this stream.
     pub async fn next(&mut self) -> VmResult<Option<Value>> {
-        let Some(execution) = self.execution.as_mut() else {
-            return VmResult::Ok(None);
-        };
+        let value = match self.inner.as_mut() {
+            None => return VmResult::Ok(None),
+            Some(Inner::Execution(execution)) => {
+                let state = if execution.is_resumed() {
+                    vm_try!(execution.async_resume_with(Value::empty()).await)
+                } else {
+                    vm_try!(execution.async_resume().await)
+                };
 
-        let state = if execution.is_resumed() {
-            vm_try!(execution.async_resume_with(Value::empty()).await)
-        } else {
-            vm_try!(execution.async_resume().await)
+                match state {
+                    GeneratorState::Yielded(value) => Some(value),
+                    GeneratorState::Complete(_) => None,
+                }
+            }
+            Some(Inner::Native(stream)) => {
+                let mut stream = vm_try!(stream.borrow_mut::<NativeStream>());
+                vm_try!(stream.next().await)
+            }
         };
 
-        VmResult::Ok(match state {
-            GeneratorState::Yielded(value) => Some(value),
-            GeneratorState::Complete(_) => {
-                self.execution = None;
-                None
-            }
-        })
+        if value.is_none() {
+            self.inner = None;
+        }
+
+        VmResult::Ok(value)
     }
 
     /// Resume the generator and return the next generator state.
+    ///
+    /// Native streams can't receive values, so the value resumed with is
+    /// ignored for them.
     pub async fn resume(&mut self, value: Value) -> VmResult<GeneratorState> {
-        let execution = vm_try!(self
-            .execution
-            .as_mut()
-            .ok_or(VmErrorKind::GeneratorComplete));
-
-        let state = if execution.is_resumed() {
-            vm_try!(execution.async_resume_with(value).await)
-        } else {
-            vm_try!(execution.async_resume().await)
+        let inner = vm_try!(self.inner.as_mut().ok_or(VmErrorKind::GeneratorComplete));
+
+        let state = match inner {
+            Inner::Execution(execution) => {
+                if execution.is_resumed() {
+                    vm_try!(execution.async_resume_with(value).await)
+                } else {
+                    vm_try!(execution.async_resume().await)
+                }
+            }
+            Inner::Native(stream) => {
+                let mut stream = vm_try!(stream.borrow_mut::<NativeStream>());
+
+                match vm_try!(stream.next().await) {
+                    Some(value) => GeneratorState::Yielded(value),
+                    None => GeneratorState::Complete(Value::unit()),
+                }
+            }
         };
 
         if state.is_complete() {
-            self.execution = None;
+            self.inner = None;
         }
 
         VmResult::Ok(state)
@@ -97,6 +138,69 @@ where
 }
 
 impl Stream {
+    /// Construct a stream which is driven by the given native stream.
+    ///
+    /// The native stream is only polled when the script asks for the next
+    /// value, so no values are buffered and back-pressure is preserved. Each
+    /// request for a value takes one unit from the instruction [`budget`] in
+    /// effect, and fails without polling the native stream once the budget is
+    /// exhausted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use rune::{Context, Module, Vm};
+    /// use rune::runtime::{Stream, VmError, VmResult};
+    ///
+    /// #[rune::function]
+    /// async fn numbers(n: i64) -> VmResult<Stream> {
+    ///     let stream = futures_util::stream::iter((0..n).map(Ok::<_, VmError>));
+    ///     VmResult::Ok(rune::vm_try!(Stream::from_native(stream)))
+    /// }
+    ///
+    /// let mut m = Module::new();
+    /// m.function_meta(numbers)?;
+    ///
+    /// let mut context = Context::with_default_modules()?;
+    /// context.install(m)?;
+    ///
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         pub async fn main() {
+    ///             let stream = numbers(4).await;
+    ///             let sum = 0;
+    ///
+    ///             while let Some(n) = stream.next().await {
+    ///                 sum += n;
+    ///             }
+    ///
+    ///             sum
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    /// let mut vm = Vm::new(Arc::new(context.runtime()?), Arc::new(unit));
+    ///
+    /// let sum = futures_executor::block_on(vm.async_call(["main"], ()))?;
+    /// let sum: i64 = rune::from_value(sum)?;
+    /// assert_eq!(sum, 6);
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    pub fn from_native<S, V>(stream: S) -> alloc::Result<Self>
+    where
+        S: 'static + futures_core::Stream<Item = Result<V, VmError>>,
+        V: ToValue,
+    {
+        let stream = Value::new(NativeStream::new(stream)?)?;
+
+        Ok(Self {
+            inner: Some(Inner::Native(stream)),
+        })
+    }
+
     /// Get the next value produced by this stream through an asynchronous
     /// iterator-like protocol.
     ///
@@ -231,9 +335,12 @@ impl Stream {
 impl Stream<&mut Vm> {
     /// Convert the current stream into one which owns its virtual machine.
     pub fn into_owned(self) -> Stream<Vm> {
-        Stream {
-            execution: self.execution.map(|e| e.into_owned()),
-        }
+        let inner = self.inner.map(|inner| match inner {
+            Inner::Execution(execution) => Inner::Execution(execution.into_owned()),
+            Inner::Native(stream) => Inner::Native(stream),
+        });
+
+        Stream { inner }
     }
 }
 
@@ -243,7 +350,7 @@ where
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Stream")
-            .field("completed", &self.execution.is_none())
+            .field("completed", &self.inner.is_none())
             .finish()
     }
 }
@@ -253,8 +360,81 @@ where
     T: TryClone + AsRef<Vm> + AsMut<Vm>,
 {
     fn try_clone(&self) -> crate::alloc::Result<Self> {
+        let inner = match &self.inner {
+            Some(Inner::Execution(execution)) => Some(Inner::Execution(execution.try_clone()?)),
+            // Clones of a native stream share the underlying stream.
+            Some(Inner::Native(stream)) => Some(Inner::Native(stream.clone())),
+            None => None,
+        };
+
+        Ok(Self { inner })
+    }
+}
+
+/// A type-erased native stream.
+trait PollNext {
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Value, VmError>>>;
+}
+
+impl<S, V> PollNext for S
+where
+    S: futures_core::Stream<Item = Result<V, VmError>>,
+    V: ToValue,
+{
+    #[inline]
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Value, VmError>>> {
+        match futures_core::Stream::poll_next(self, cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(Some(Ok(value))) => {
+                Poll::Ready(Some(value.to_value().map_err(VmError::from)))
+            }
+            Poll::Ready(Some(Err(error))) => Poll::Ready(Some(Err(error))),
+            Poll::Ready(None) => Poll::Ready(None),
+        }
+    }
+}
+
+/// A native stream.
+#[derive(Any)]
+#[rune(item = ::std::stream)]
+struct NativeStream {
+    stream: Pin<Box<dyn PollNext>>,
+}
+
+impl NativeStream {
+    fn new<S, V>(stream: S) -> alloc::Result<Self>
+    where
+        S: 'static + futures_core::Stream<Item = Result<V, VmError>>,
+        V: ToValue,
+    {
+        let stream: rust_alloc::boxed::Box<dyn PollNext> = rust_alloc::boxed::Box::new(stream);
+
         Ok(Self {
-            execution: self.execution.try_clone()?,
+            stream: Box::into_pin(Box::from_std(stream)?),
         })
     }
+
+    /// Poll the native stream for its next value.
+    async fn next(&mut self) -> VmResult<Option<Value>> {
+        if !budget::take() {
+            return VmResult::err(VmErrorKind::Halted {
+                halt: VmHaltInfo::Limited,
+            });
+        }
+
+        let value = poll_fn(|cx| self.stream.as_mut().poll_next(cx)).await;
+        VmResult::Ok(vm_try!(value.transpose()))
+    }
+}
+
+impl fmt::Debug for NativeStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NativeStream").finish_non_exhaustive()
+    }
 }
@@ -0,0 +1,116 @@
+use core::fmt;
+use core::marker::PhantomData;
+
+use ::rust_alloc::sync::Arc;
+
+use crate::hash::Hash;
+use crate::runtime::{FromValue, GuardedArgs, Unit, UnsafeToValue, Vm, VmError};
+
+/// Trait for arguments where the number of arguments is known at compile
+/// time, which is implemented for tuples.
+///
+/// This is used by [`TypedFunction`] to check the number of arguments once
+/// when the handle is constructed.
+pub trait TypedArgs: GuardedArgs {
+    /// The number of arguments.
+    const COUNT: usize;
+}
+
+macro_rules! impl_typed_args {
+    ($count:expr $(, $ty:ident $value:ident $_:expr)*) => {
+        impl<$($ty,)*> TypedArgs for ($($ty,)*)
+        where
+            $($ty: UnsafeToValue,)*
+        {
+            const COUNT: usize = $count;
+        }
+    };
+}
+
+repeat_macro!(impl_typed_args);
+
+/// A handle to a function in a unit, with typed arguments and return value.
+///
+/// This is constructed through [`Vm::typed_function`], which looks up the
+/// function and checks the number of arguments it takes. Calling it is
+/// therefore cheaper than calling [`Vm::call`] repeatedly with the same name.
+pub struct TypedFunction<A, R> {
+    unit: Arc<Unit>,
+    hash: Hash,
+    offset: usize,
+    _marker: PhantomData<fn(A) -> R>,
+}
+
+impl<A, R> TypedFunction<A, R>
+where
+    A: TypedArgs,
+    R: FromValue,
+{
+    pub(crate) fn new(unit: Arc<Unit>, hash: Hash, offset: usize) -> Self {
+        Self {
+            unit,
+            hash,
+            offset,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Get the hash of the function.
+    pub fn hash(&self) -> Hash {
+        self.hash
+    }
+
+    /// Call the function in the given virtual machine, converting the
+    /// produced value into `R`.
+    ///
+    /// If any async instructions are encountered, this will error.
+    ///
+    /// If the virtual machine doesn't use the unit the handle was constructed
+    /// from, the function is looked up again by hash.
+    pub fn call(&self, vm: &mut Vm, args: A) -> Result<R, VmError> {
+        self.enter(vm)?;
+        let value = vm.call_entered(args)?;
+        Ok(R::from_value(value)?)
+    }
+
+    /// Call the function in the given virtual machine with support for async
+    /// functions, converting the produced value into `R`.
+    ///
+    /// See [`TypedFunction::call`].
+    pub async fn async_call(&self, vm: &mut Vm, args: A) -> Result<R, VmError> {
+        self.enter(vm)?;
+        let value = vm.async_call_entered(args).await?;
+        Ok(R::from_value(value)?)
+    }
+
+    fn enter(&self, vm: &mut Vm) -> Result<(), VmError> {
+        if Arc::ptr_eq(vm.unit(), &self.unit) {
+            vm.set_entrypoint_offset(self.offset);
+        } else {
+            vm.set_entrypoint(self.hash, A::COUNT)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<A, R> Clone for TypedFunction<A, R> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            unit: self.unit.clone(),
+            hash: self.hash,
+            offset: self.offset,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<A, R> fmt::Debug for TypedFunction<A, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypedFunction")
+            .field("hash", &self.hash)
+            .field("offset", &self.offset)
+            .finish_non_exhaustive()
+    }
+}
@@ -5,6 +5,9 @@
 
 #[cfg(feature = "byte-code")]
 mod byte_code;
+mod diff;
+#[cfg(all(feature = "unit-file", feature = "std"))]
+mod file;
 mod storage;
 
 use core::fmt;
@@ -16,17 +19,23 @@ use serde::{Deserialize, Serialize};
 
 use crate as rune;
 use crate::alloc::prelude::*;
-use crate::alloc::{self, Box, String, Vec};
+use crate::alloc::{self, Box, Vec};
 use crate::hash;
-use crate::runtime::{Call, ConstValue, DebugInfo, Inst, InstAddress, Rtti, StaticString};
+use crate::runtime::{
+    Call, ConstValue, DebugInfo, Inst, InstAddress, ObjectKey, Rtti, StaticString,
+};
 use crate::Hash;
 
+pub use self::diff::UnitDiff;
 pub use self::storage::{ArrayUnit, EncodeError, UnitEncoder, UnitStorage};
 pub(crate) use self::storage::{BadInstruction, BadJump};
 
 #[cfg(feature = "byte-code")]
 pub use self::byte_code::ByteCodeUnit;
 
+#[cfg(all(feature = "unit-file", feature = "std"))]
+pub use self::file::UnitFileError;
+
 /// Default storage implementation to use.
 #[cfg(not(rune_byte_code))]
 pub type DefaultStorage = ArrayUnit;
@@ -69,13 +78,17 @@ pub struct Logic<S = DefaultStorage> {
     /// to send the collection of keys to the virtual machine.
     ///
     /// All keys are sorted with the default string sort.
-    static_object_keys: Vec<Box<[String]>>,
+    #[serde(deserialize_with = "crate::runtime::object_key::deserialize_interned")]
+    static_object_keys: Vec<Box<[ObjectKey]>>,
     /// Drop sets.
     drop_sets: Vec<Arc<[InstAddress]>>,
     /// Runtime information for types.
     rtti: hash::Map<Arc<Rtti>>,
     /// Named constants
     constants: hash::Map<ConstValue>,
+    /// The number of call sites which use an inline cache.
+    #[serde(default)]
+    call_sites: usize,
 }
 
 impl<S> Unit<S> {
@@ -96,11 +109,12 @@ impl<S> Unit<S> {
         functions: hash::Map<UnitFn>,
         static_strings: Vec<Arc<StaticString>>,
         static_bytes: Vec<Vec<u8>>,
-        static_object_keys: Vec<Box<[String]>>,
+        static_object_keys: Vec<Box<[ObjectKey]>>,
         drop_sets: Vec<Arc<[InstAddress]>>,
         rtti: hash::Map<Arc<Rtti>>,
         debug: Option<Box<DebugInfo>>,
         constants: hash::Map<ConstValue>,
+        call_sites: usize,
     ) -> Self {
         Self {
             logic: Logic {
@@ -112,6 +126,7 @@ impl<S> Unit<S> {
                 drop_sets,
                 rtti,
                 constants,
+                call_sites,
             },
             debug,
         }
@@ -166,7 +181,9 @@ impl<S> Unit<S> {
     /// Iterate over all static object keys in the unit.
     #[cfg(feature = "cli")]
     #[inline]
-    pub(crate) fn iter_static_object_keys(&self) -> impl Iterator<Item = (usize, &[String])> + '_ {
+    pub(crate) fn iter_static_object_keys(
+        &self,
+    ) -> impl Iterator<Item = (usize, &[ObjectKey])> + '_ {
         use core::iter;
 
         let mut it = self.logic.static_object_keys.iter().enumerate();
@@ -178,7 +195,6 @@ impl<S> Unit<S> {
     }
 
     /// Iterate over dynamic functions.
-    #[cfg(feature = "cli")]
     #[inline]
     pub(crate) fn iter_functions(&self) -> impl Iterator<Item = (Hash, &UnitFn)> + '_ {
         self.logic.functions.iter().map(|(h, f)| (*h, f))
@@ -198,10 +214,16 @@ impl<S> Unit<S> {
 
     /// Lookup the static object keys by slot, if it exists.
     #[inline]
-    pub(crate) fn lookup_object_keys(&self, slot: usize) -> Option<&[String]> {
+    pub(crate) fn lookup_object_keys(&self, slot: usize) -> Option<&[ObjectKey]> {
         Some(self.logic.static_object_keys.get(slot)?)
     }
 
+    /// The number of call sites which use an inline cache.
+    #[inline]
+    pub(crate) fn call_sites(&self) -> usize {
+        self.logic.call_sites
+    }
+
     #[inline]
     pub(crate) fn lookup_drop_set(&self, set: usize) -> Option<&[InstAddress]> {
         Some(self.logic.drop_sets.get(set)?)
@@ -219,6 +241,40 @@ impl<S> Unit<S> {
         self.logic.functions.get(hash)
     }
 
+    /// Get information about the function with the given hash, like the
+    /// number of arguments it takes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::Hash;
+    ///
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         pub fn add(a, b) {
+    ///             a + b
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let unit = rune::prepare(&mut sources).build()?;
+    ///
+    /// let info = unit.function_info(Hash::type_hash(["add"])).expect("function should exist");
+    /// assert_eq!(info.args(), 2);
+    ///
+    /// assert!(unit.function_info(Hash::type_hash(["missing"])).is_none());
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    pub fn function_info(&self, hash: Hash) -> Option<FunctionInfo> {
+        let args = match self.logic.functions.get(&hash)? {
+            UnitFn::Offset { args, .. } => *args,
+            UnitFn::EmptyStruct { .. } => 0,
+            UnitFn::TupleStruct { args, .. } => *args,
+        };
+
+        Some(FunctionInfo { args })
+    }
+
     /// Lookup a constant from the unit.
     #[inline]
     pub(crate) fn constant(&self, hash: &Hash) -> Option<&ConstValue> {
@@ -245,11 +301,83 @@ where
     }
 
     /// Iterate over all instructions in order.
-    #[cfg(feature = "emit")]
     #[inline]
     pub(crate) fn iter_instructions(&self) -> impl Iterator<Item = (usize, Inst)> + '_ {
         self.logic.storage.iter()
     }
+
+    /// Calculate a fingerprint over everything needed to execute the unit.
+    ///
+    /// Tables which are stored in hash maps are visited in sorted order, so
+    /// that the fingerprint doesn't depend on how the unit was constructed.
+    /// Debug information is not included.
+    pub(crate) fn fingerprint(&self) -> alloc::Result<Hash> {
+        use crate::alloc::fmt::TryWrite;
+
+        let logic = &self.logic;
+        let mut string = String::new();
+
+        for (offset, inst) in logic.storage.iter() {
+            writeln!(string, "inst {offset}: {inst:?}")?;
+        }
+
+        let mut functions = logic.functions.iter().try_collect::<Vec<_>>()?;
+        functions.sort_by_key(|&(hash, _)| *hash);
+
+        for (hash, f) in functions {
+            writeln!(string, "fn {hash}: {f:?}")?;
+        }
+
+        for (n, s) in logic.static_strings.iter().enumerate() {
+            writeln!(string, "string {n}: {s:?}")?;
+        }
+
+        for (n, b) in logic.static_bytes.iter().enumerate() {
+            writeln!(string, "bytes {n}: {b:?}")?;
+        }
+
+        for (n, keys) in logic.static_object_keys.iter().enumerate() {
+            writeln!(string, "keys {n}: {keys:?}")?;
+        }
+
+        for (n, set) in logic.drop_sets.iter().enumerate() {
+            writeln!(string, "drop {n}: {set:?}")?;
+        }
+
+        let mut rtti = logic.rtti.iter().try_collect::<Vec<_>>()?;
+        rtti.sort_by_key(|&(hash, _)| *hash);
+
+        for (hash, rtti) in rtti {
+            writeln!(string, "rtti {hash}: {rtti:?}")?;
+        }
+
+        let mut constants = logic.constants.iter().try_collect::<Vec<_>>()?;
+        constants.sort_by_key(|&(hash, _)| *hash);
+
+        for (hash, value) in constants {
+            writeln!(string, "const {hash}: {value:?}")?;
+        }
+
+        writeln!(string, "call sites: {}", logic.call_sites)?;
+        Ok(Hash::static_bytes(string.as_bytes()))
+    }
+}
+
+/// Information about a function in a unit.
+///
+/// This is returned by [`Unit::function_info`].
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct FunctionInfo {
+    args: usize,
+}
+
+impl FunctionInfo {
+    /// The number of arguments the function takes.
+    #[inline]
+    pub fn args(&self) -> usize {
+        self.args
+    }
 }
 
 /// The kind and necessary information on registered functions.
@@ -0,0 +1,137 @@
+use crate::alloc::{self, Vec};
+use crate::runtime::unit::UnitFn;
+use crate::runtime::Unit;
+use crate::Hash;
+
+/// The difference in functions between two units.
+///
+/// This is intended to help decide what to do with long-running executions,
+/// like generators and futures, when a unit is swapped out using
+/// [`Vm::try_swap_unit`]. Executions which are already in progress keep
+/// running against the unit they were started with, so if functions they
+/// depend on have been removed or changed signature they probably need to be
+/// restarted.
+///
+/// [`Vm::try_swap_unit`]: crate::Vm::try_swap_unit
+///
+/// # Examples
+///
+/// ```
+/// use rune::runtime::UnitDiff;
+/// use rune::Context;
+///
+/// let context = Context::with_default_modules()?;
+///
+/// let mut old = rune::sources! {
+///     entry => {
+///         pub fn a() { 1 }
+///         pub fn b(x) { x }
+///     }
+/// };
+///
+/// let mut new = rune::sources! {
+///     entry => {
+///         pub fn b(x, y) { x + y }
+///         pub fn c() { 3 }
+///     }
+/// };
+///
+/// let old = rune::prepare(&mut old).with_context(&context).build()?;
+/// let new = rune::prepare(&mut new).with_context(&context).build()?;
+///
+/// let diff = UnitDiff::new(&old, &new)?;
+///
+/// assert_eq!(diff.added(), [rune::Hash::type_hash(["c"])]);
+/// assert_eq!(diff.removed(), [rune::Hash::type_hash(["a"])]);
+/// assert_eq!(diff.changed(), [rune::Hash::type_hash(["b"])]);
+/// assert!(!diff.is_compatible());
+/// # Ok::<_, rune::support::Error>(())
+/// ```
+#[derive(Debug, Default)]
+pub struct UnitDiff {
+    added: Vec<Hash>,
+    removed: Vec<Hash>,
+    changed: Vec<Hash>,
+}
+
+impl UnitDiff {
+    /// Compare the functions in the `old` unit with the ones in the `new`
+    /// unit.
+    pub fn new<S>(old: &Unit<S>, new: &Unit<S>) -> alloc::Result<Self> {
+        let mut diff = Self::default();
+
+        for (hash, f) in old.iter_functions() {
+            match new.function(&hash) {
+                Some(other) if same_signature(f, other) => {}
+                Some(..) => diff.changed.try_push(hash)?,
+                None => diff.removed.try_push(hash)?,
+            }
+        }
+
+        for (hash, _) in new.iter_functions() {
+            if old.function(&hash).is_none() {
+                diff.added.try_push(hash)?;
+            }
+        }
+
+        diff.added.sort();
+        diff.removed.sort();
+        diff.changed.sort();
+        Ok(diff)
+    }
+
+    /// Functions which are present in the new unit but not in the old one.
+    pub fn added(&self) -> &[Hash] {
+        &self.added
+    }
+
+    /// Functions which are present in the old unit but not in the new one.
+    pub fn removed(&self) -> &[Hash] {
+        &self.removed
+    }
+
+    /// Functions which are present in both units, but which are called in a
+    /// different way, like taking a different number of arguments.
+    pub fn changed(&self) -> &[Hash] {
+        &self.changed
+    }
+
+    /// Test if every function in the old unit can be called in the same way
+    /// in the new unit.
+    pub fn is_compatible(&self) -> bool {
+        self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Test if two functions are called in the same way, ignoring where they are
+/// located.
+fn same_signature(a: &UnitFn, b: &UnitFn) -> bool {
+    match (a, b) {
+        (
+            UnitFn::Offset {
+                call: a_call,
+                args: a_args,
+                captures: a_captures,
+                ..
+            },
+            UnitFn::Offset {
+                call: b_call,
+                args: b_args,
+                captures: b_captures,
+                ..
+            },
+        ) => a_call == b_call && a_args == b_args && a_captures == b_captures,
+        (UnitFn::EmptyStruct { hash: a }, UnitFn::EmptyStruct { hash: b }) => a == b,
+        (
+            UnitFn::TupleStruct {
+                hash: a,
+                args: a_args,
+            },
+            UnitFn::TupleStruct {
+                hash: b,
+                args: b_args,
+            },
+        ) => a == b && a_args == b_args,
+        _ => false,
+    }
+}
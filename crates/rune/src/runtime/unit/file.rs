@@ -0,0 +1,265 @@
+//! A versioned binary format for storing compiled units.
+//!
+//! The format consists of:
+//! * The magic bytes `RUNC`.
+//! * The format version as a little-endian `u32`.
+//! * The hashes of all native functions the unit depends on, followed by the
+//!   unit itself, encoded using [`musli::descriptive`].
+
+use core::fmt;
+
+use std::io;
+
+use ::rust_alloc::vec::Vec;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::alloc;
+use crate::musli::Serde;
+use crate::runtime::unit::Logic;
+use crate::runtime::{DebugInfo, InstCall, RuntimeContext, Unit, UnitStorage};
+use crate::Hash;
+
+/// Magic bytes at the start of a serialized unit.
+const MAGIC: [u8; 4] = *b"RUNC";
+
+/// The current version of the format.
+///
+/// This must be bumped whenever the encoding of a unit changes, such as when
+/// instructions are added or changed.
+const VERSION: u32 = 4;
+
+/// Error raised when a unit could not be serialized or deserialized.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum UnitFileError {
+    /// An I/O error.
+    Io {
+        /// The underlying error.
+        error: io::Error,
+    },
+    /// Allocation failed.
+    Alloc {
+        /// The underlying error.
+        error: alloc::Error,
+    },
+    /// The data was not in the expected format.
+    Format {
+        /// The underlying error.
+        error: musli::descriptive::Error,
+    },
+    /// The data does not start with the expected magic bytes.
+    BadMagic,
+    /// The data uses a format version which is not supported.
+    UnsupportedVersion {
+        /// The version of the data.
+        version: u32,
+    },
+    /// The context the unit is loaded into is missing native functions which
+    /// the unit depends on.
+    ContextMismatch {
+        /// Hashes of the native functions which are missing.
+        missing: Vec<Hash>,
+    },
+}
+
+impl fmt::Display for UnitFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnitFileError::Io { error } => error.fmt(f),
+            UnitFileError::Alloc { error } => error.fmt(f),
+            UnitFileError::Format { error } => write!(f, "Bad unit format: {error}"),
+            UnitFileError::BadMagic => write!(f, "Data is not a serialized unit"),
+            UnitFileError::UnsupportedVersion { version } => write!(
+                f,
+                "Unsupported unit format version {version}, expected {VERSION}"
+            ),
+            UnitFileError::ContextMismatch { missing } => {
+                write!(f, "Context is missing native functions used by the unit:")?;
+
+                for hash in missing {
+                    write!(f, " {hash}")?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl core::error::Error for UnitFileError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            UnitFileError::Io { error } => Some(error),
+            UnitFileError::Alloc { error } => Some(error),
+            UnitFileError::Format { error } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for UnitFileError {
+    #[inline]
+    fn from(error: io::Error) -> Self {
+        UnitFileError::Io { error }
+    }
+}
+
+impl From<alloc::Error> for UnitFileError {
+    #[inline]
+    fn from(error: alloc::Error) -> Self {
+        UnitFileError::Alloc { error }
+    }
+}
+
+impl From<musli::descriptive::Error> for UnitFileError {
+    #[inline]
+    fn from(error: musli::descriptive::Error) -> Self {
+        UnitFileError::Format { error }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(bound = "S: Serialize + DeserializeOwned")]
+struct BodyRef<'a, S> {
+    functions: &'a [Hash],
+    logic: &'a Logic<S>,
+    debug: Option<&'a DebugInfo>,
+}
+
+#[derive(Deserialize)]
+#[serde(bound = "S: Serialize + DeserializeOwned")]
+struct Body<S> {
+    functions: Vec<Hash>,
+    logic: Logic<S>,
+    debug: Option<DebugInfo>,
+}
+
+impl<S> Unit<S>
+where
+    S: UnitStorage + Serialize + DeserializeOwned,
+{
+    /// Serialize the unit into the given writer.
+    ///
+    /// The unit can be loaded again using [`Unit::deserialize_from`], without
+    /// access to the sources it was compiled from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use rune::{Context, Unit, Vm};
+    ///
+    /// let context = Context::with_default_modules()?;
+    ///
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         pub fn main(n) {
+    ///             n.max(10)
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    ///
+    /// let mut bytes = Vec::new();
+    /// unit.serialize_to(&mut bytes)?;
+    ///
+    /// let runtime = Arc::new(context.runtime()?);
+    /// let unit = Unit::deserialize_from(&bytes[..], &runtime)?;
+    ///
+    /// let mut vm = Vm::new(runtime, Arc::new(unit));
+    /// let output: i64 = rune::from_value(vm.call(["main"], (42i64,))?)?;
+    /// assert_eq!(output, 42);
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    pub fn serialize_to<W>(&self, mut writer: W) -> Result<(), UnitFileError>
+    where
+        W: io::Write,
+    {
+        let functions = self.native_functions();
+
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+
+        let body = BodyRef {
+            functions: &functions,
+            logic: self.logic(),
+            debug: self.debug_info(),
+        };
+
+        musli::descriptive::to_writer(writer, &Serde(body))?;
+        Ok(())
+    }
+
+    /// Deserialize a unit from the given reader, which was previously
+    /// serialized with [`Unit::serialize_to`].
+    ///
+    /// This errors with [`UnitFileError::ContextMismatch`] if `context` does
+    /// not provide all native functions which the unit depends on.
+    pub fn deserialize_from<R>(
+        mut reader: R,
+        context: &RuntimeContext,
+    ) -> Result<Self, UnitFileError>
+    where
+        R: io::Read,
+    {
+        let mut header = [0; 8];
+        reader.read_exact(&mut header)?;
+
+        let (magic, version) = header.split_at(4);
+
+        if magic != MAGIC {
+            return Err(UnitFileError::BadMagic);
+        }
+
+        let version = u32::from_le_bytes([version[0], version[1], version[2], version[3]]);
+
+        if version != VERSION {
+            return Err(UnitFileError::UnsupportedVersion { version });
+        }
+
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+
+        let Serde(Body::<S> {
+            functions,
+            logic,
+            debug,
+        }) = musli::descriptive::from_slice(&bytes)?;
+
+        let missing = functions
+            .into_iter()
+            .filter(|hash| context.function(hash).is_none())
+            .collect::<Vec<_>>();
+
+        if !missing.is_empty() {
+            return Err(UnitFileError::ContextMismatch { missing });
+        }
+
+        Ok(Unit::from_parts(logic, debug)?)
+    }
+
+    /// Collect the sorted hashes of all functions which are called by the
+    /// unit but not defined in it, and must therefore be provided by the
+    /// context.
+    fn native_functions(&self) -> Vec<Hash> {
+        let mut functions = Vec::new();
+
+        for (_, inst) in self.iter_instructions() {
+            let Some(InstCall::Function(hash)) = inst.call() else {
+                continue;
+            };
+
+            if self.function(&hash).is_none() {
+                functions.push(hash);
+            }
+        }
+
+        functions.sort();
+        functions.dedup();
+        functions
+    }
+}
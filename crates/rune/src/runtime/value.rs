@@ -8,6 +8,8 @@ mod inline;
 pub use self::inline::Inline;
 
 mod serde;
+pub(crate) use self::serde::{from_serializable, to_serializable};
+pub use self::serde::{from_value_to_deserialize, to_value_from_serialize, SerdeError};
 
 mod rtti;
 pub(crate) use self::rtti::RttiKind;
@@ -32,7 +34,7 @@ use crate::alloc::fmt::TryWrite;
 use crate::alloc::prelude::*;
 use crate::alloc::{self, String};
 use crate::compile::meta;
-use crate::{Any, Hash, TypeHash};
+use crate::{Any, Hash, Item, TypeHash};
 
 use super::{
     AccessError, AnyObj, AnyObjDrop, BorrowMut, BorrowRef, CallResultOnly, ConstValue,
@@ -43,6 +45,10 @@ use super::{
 #[cfg(feature = "alloc")]
 use super::{Hasher, Tuple};
 
+/// The maximum depth to which fields of values are cloned by
+/// [`Value::clone_or_move_with`].
+const MAX_CLONE_DEPTH: usize = 256;
+
 /// Defined guard for a reference value.
 ///
 /// See [Value::from_ref].
@@ -390,6 +396,61 @@ impl Value {
         )))
     }
 
+    /// Clone a value which is captured by a `move` closure or async block.
+    ///
+    /// Unlike [`Value::clone_with`], the fields of values of types defined in
+    /// scripts are cloned recursively, and values which don't support the
+    /// [`CLONE`] protocol are moved instead of raising an error.
+    ///
+    /// [`CLONE`]: Protocol::CLONE
+    pub(crate) fn clone_or_move_with(&self, caller: &mut dyn ProtocolCaller) -> VmResult<Value> {
+        self.clone_or_move_with_depth(caller, 0)
+    }
+
+    fn clone_or_move_with_depth(
+        &self,
+        caller: &mut dyn ProtocolCaller,
+        depth: usize,
+    ) -> VmResult<Value> {
+        match self.as_ref() {
+            Repr::Inline(value) => {
+                return VmResult::Ok(Self {
+                    repr: Repr::Inline(*value),
+                });
+            }
+            Repr::Dynamic(value) => {
+                // Fields are cloned recursively, so values which are nested
+                // too deeply or which contain themselves would otherwise
+                // overflow the stack.
+                if depth == MAX_CLONE_DEPTH {
+                    return VmResult::err(VmErrorKind::CloneDepthExceeded {
+                        limit: MAX_CLONE_DEPTH,
+                    });
+                }
+
+                let fields = vm_try!(value.borrow_ref());
+                let mut cloned = vm_try!(alloc::Vec::try_with_capacity(fields.len()));
+
+                for field in fields.iter() {
+                    let field = vm_try!(field.clone_or_move_with_depth(caller, depth + 1));
+                    vm_try!(cloned.try_push(field));
+                }
+
+                let value = vm_try!(Dynamic::new(value.rtti().clone(), cloned));
+
+                return VmResult::Ok(Self {
+                    repr: Repr::Dynamic(value),
+                });
+            }
+            Repr::Any(..) => {}
+        }
+
+        match vm_try!(caller.try_call_protocol_fn(&Protocol::CLONE, self.clone(), &mut ())) {
+            CallResultOnly::Ok(value) => VmResult::Ok(value),
+            CallResultOnly::Unsupported(value) => value.move_(),
+        }
+    }
+
     /// Debug format the value using the [`DEBUG_FMT`] protocol.
     ///
     /// You must use [`Vm::with`] to specify which virtual machine this function
@@ -417,7 +478,22 @@ impl Value {
                 vm_try!(vm_write!(f, "{value:?}"));
             }
             Repr::Dynamic(ref value) => {
-                vm_try!(value.debug_fmt_with(f, caller));
+                // Script types might implement the protocol in the unit, in
+                // which case it takes precedence over the structural output.
+                let mut args = DynGuardedArgs::new((&mut *f,));
+
+                match vm_try!(caller.try_call_protocol_fn(
+                    &Protocol::DEBUG_FMT,
+                    self.clone(),
+                    &mut args
+                )) {
+                    CallResultOnly::Ok(value) => {
+                        vm_try!(<()>::from_value(value));
+                    }
+                    CallResultOnly::Unsupported(..) => {
+                        vm_try!(value.debug_fmt_with(f, caller));
+                    }
+                }
             }
             Repr::Any(..) => {
                 // reborrow f to avoid moving it
@@ -1258,6 +1334,14 @@ impl Value {
                 });
             }
             (Repr::Dynamic(lhs), Repr::Dynamic(rhs)) => {
+                if let CallResultOnly::Ok(value) = vm_try!(caller.try_call_protocol_fn(
+                    protocol,
+                    self.clone(),
+                    &mut Some((b.clone(),))
+                )) {
+                    return VmResult::Ok(vm_try!(T::from_value(value)));
+                }
+
                 let lhs_rtti = lhs.rtti();
                 let rhs_rtti = rhs.rtti();
 
@@ -1450,6 +1534,75 @@ impl Value {
     }
 }
 
+#[allow(non_snake_case)]
+impl Value {
+    #[doc(hidden)]
+    pub fn __rune_macros__empty_variant(
+        enum_item: &'static Item,
+        variant: &'static str,
+    ) -> Result<Self, RuntimeError> {
+        let rtti = Self::__rune_macros__variant_rtti(
+            enum_item,
+            variant,
+            RttiKind::Empty,
+            super::new_field_map(),
+        )?;
+
+        Ok(Value::empty_struct(rtti)?)
+    }
+
+    #[doc(hidden)]
+    pub fn __rune_macros__tuple_variant(
+        enum_item: &'static Item,
+        variant: &'static str,
+        values: alloc::Vec<Value>,
+    ) -> Result<Self, RuntimeError> {
+        let rtti = Self::__rune_macros__variant_rtti(
+            enum_item,
+            variant,
+            RttiKind::Tuple,
+            super::new_field_map(),
+        )?;
+
+        Ok(Value::tuple_struct(rtti, values)?)
+    }
+
+    #[doc(hidden)]
+    pub fn __rune_macros__struct_variant(
+        enum_item: &'static Item,
+        variant: &'static str,
+        names: &[&'static str],
+        values: alloc::Vec<Value>,
+    ) -> Result<Self, RuntimeError> {
+        let mut fields = super::new_field_hash_map_with_capacity(names.len())?;
+
+        for (index, name) in names.iter().enumerate() {
+            fields.try_insert((*name).try_into()?, index)?;
+        }
+
+        let rtti = Self::__rune_macros__variant_rtti(enum_item, variant, RttiKind::Struct, fields)?;
+        Ok(Value::from(Dynamic::new(rtti, values)?))
+    }
+
+    fn __rune_macros__variant_rtti(
+        enum_item: &'static Item,
+        variant: &'static str,
+        kind: RttiKind,
+        fields: super::FieldMap<Box<str>, usize>,
+    ) -> alloc::Result<Arc<Rtti>> {
+        let mut item = enum_item.try_to_owned()?;
+        item.push(variant)?;
+
+        Ok(Arc::new(Rtti {
+            kind,
+            hash: Hash::type_hash(enum_item),
+            variant_hash: Hash::type_hash(&item),
+            item,
+            fields,
+        }))
+    }
+}
+
 impl fmt::Debug for Value {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match &self.repr {
@@ -77,6 +77,7 @@ impl Inline {
     }
 
     /// Perform a partial equality check over two inline values.
+    #[inline]
     pub(crate) fn partial_eq(&self, other: &Self) -> Result<bool, RuntimeError> {
         match (self, other) {
             (Inline::Unit, Inline::Unit) => Ok(true),
@@ -128,6 +129,7 @@ impl Inline {
     }
 
     /// Partial comparison implementation for inline.
+    #[inline]
     pub(crate) fn partial_cmp(&self, other: &Self) -> Result<Option<Ordering>, RuntimeError> {
         match (self, other) {
             (Inline::Unit, Inline::Unit) => Ok(Some(Ordering::Equal)),
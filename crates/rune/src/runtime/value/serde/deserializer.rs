@@ -0,0 +1,337 @@
+use crate::alloc;
+use crate::alloc::prelude::*;
+use crate::runtime::{Bytes, Inline, Object, ObjectKey, OwnedTuple, Repr, Vec};
+use crate::TypeHash;
+
+use serde::de::{self, IntoDeserializer as _};
+
+use super::{SerdeError, Value};
+
+/// A deserializer which feeds a [`Value`] directly into any type implementing
+/// [`Deserialize`].
+///
+/// [`Deserialize`]: serde::Deserialize
+pub(super) struct ValueDeserializer(pub(super) Value);
+
+impl ValueDeserializer {
+    /// Test if the value is an option, and if so what it contains.
+    fn as_option(&self) -> Result<Option<Option<Value>>, SerdeError> {
+        let Repr::Any(value) = self.0.as_ref() else {
+            return Ok(None);
+        };
+
+        if value.type_hash() != Option::<Value>::HASH {
+            return Ok(None);
+        }
+
+        let option = value.borrow_ref::<Option<Value>>()?;
+        Ok(Some(Option::clone(&option)))
+    }
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer {
+    type Error = SerdeError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, SerdeError>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.0.as_ref() {
+            Repr::Inline(value) => match *value {
+                Inline::Unit => visitor.visit_unit(),
+                Inline::Bool(value) => visitor.visit_bool(value),
+                Inline::Char(value) => visitor.visit_char(value),
+                Inline::Unsigned(value) => visitor.visit_u64(value),
+                Inline::Signed(value) => visitor.visit_i64(value),
+                Inline::Float(value) => visitor.visit_f64(value),
+                Inline::Empty => Err(de::Error::custom("cannot deserialize empty values")),
+                Inline::Type(..) => Err(de::Error::custom("cannot deserialize types")),
+                Inline::Ordering(..) => Err(de::Error::custom("cannot deserialize orderings")),
+            },
+            Repr::Dynamic(value) => Err(de::Error::custom(format_args!(
+                "cannot deserialize struct {}",
+                value.rtti().item
+            ))),
+            Repr::Any(value) => match value.type_hash() {
+                Option::<Value>::HASH => {
+                    match Option::clone(&*value.borrow_ref::<Option<Value>>()?) {
+                        Some(value) => visitor.visit_some(ValueDeserializer(value)),
+                        None => visitor.visit_none(),
+                    }
+                }
+                alloc::String::HASH => {
+                    let string = value.borrow_ref::<alloc::String>()?;
+                    visitor.visit_str(string.as_str())
+                }
+                Bytes::HASH => {
+                    let bytes = value.borrow_ref::<Bytes>()?;
+                    visitor.visit_bytes(bytes.as_slice())
+                }
+                Vec::HASH => {
+                    let vec = value.borrow_ref::<Vec>()?;
+                    visitor.visit_seq(SeqDeserializer::new(vec.iter())?)
+                }
+                OwnedTuple::HASH => {
+                    let tuple = value.borrow_ref::<OwnedTuple>()?;
+                    visitor.visit_seq(SeqDeserializer::new(tuple.iter())?)
+                }
+                Object::HASH => {
+                    let object = value.borrow_ref::<Object>()?;
+                    visitor.visit_map(MapDeserializer::new(&object)?)
+                }
+                _ => Err(de::Error::custom(format_args!(
+                    "cannot deserialize external value of type {}",
+                    value.type_info()
+                ))),
+            },
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, SerdeError>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.as_option()? {
+            Some(Some(value)) => visitor.visit_some(ValueDeserializer(value)),
+            Some(None) => visitor.visit_none(),
+            None if matches!(self.0.as_ref(), Repr::Inline(Inline::Unit)) => visitor.visit_none(),
+            // Plain values are treated as present, which matches how formats
+            // such as JSON represent optional values.
+            None => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeError>
+    where
+        V: de::Visitor<'de>,
+    {
+        let Repr::Any(value) = self.0.as_ref() else {
+            return Err(de::Error::custom(format_args!(
+                "expected an enum variant, but found {}",
+                self.0.type_info()
+            )));
+        };
+
+        match value.type_hash() {
+            alloc::String::HASH => {
+                let variant = value.borrow_ref::<alloc::String>()?.try_clone()?;
+                visitor.visit_enum(EnumDeserializer {
+                    variant,
+                    value: None,
+                })
+            }
+            Object::HASH => {
+                let object = value.borrow_ref::<Object>()?;
+                let mut it = object.iter();
+
+                let (Some((variant, value)), None) = (it.next(), it.next()) else {
+                    return Err(de::Error::custom(
+                        "expected an object with a single entry for an enum variant",
+                    ));
+                };
+
+                visitor.visit_enum(EnumDeserializer {
+                    variant: variant.as_str().try_to_owned()?,
+                    value: Some(value.clone()),
+                })
+            }
+            _ => Err(de::Error::custom(format_args!(
+                "expected an enum variant, but found {}",
+                value.type_info()
+            ))),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+impl<'de> de::IntoDeserializer<'de, SerdeError> for ValueDeserializer {
+    type Deserializer = Self;
+
+    #[inline]
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+/// Access to the elements of a vector or a tuple.
+struct SeqDeserializer {
+    iter: alloc::vec::IntoIter<Value>,
+}
+
+impl SeqDeserializer {
+    fn new<'a>(values: impl IntoIterator<Item = &'a Value>) -> alloc::Result<Self> {
+        let mut vec = alloc::Vec::new();
+
+        for value in values {
+            vec.try_push(value.clone())?;
+        }
+
+        Ok(Self {
+            iter: vec.into_iter(),
+        })
+    }
+}
+
+impl<'de> de::SeqAccess<'de> for SeqDeserializer {
+    type Error = SerdeError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, SerdeError>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some(value) => seed.deserialize(ValueDeserializer(value)).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+/// Access to the entries of an object.
+struct MapDeserializer {
+    iter: alloc::vec::IntoIter<(ObjectKey, Value)>,
+    value: Option<Value>,
+}
+
+impl MapDeserializer {
+    fn new(object: &Object) -> alloc::Result<Self> {
+        let mut entries = alloc::Vec::try_with_capacity(object.len())?;
+
+        for (key, value) in object.entries() {
+            entries.try_push((key.try_clone()?, value.clone()))?;
+        }
+
+        Ok(Self {
+            iter: entries.into_iter(),
+            value: None,
+        })
+    }
+}
+
+impl<'de> de::MapAccess<'de> for MapDeserializer {
+    type Error = SerdeError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, SerdeError>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        let Some((key, value)) = self.iter.next() else {
+            return Ok(None);
+        };
+
+        self.value = Some(value);
+        seed.deserialize(key.as_str().into_deserializer()).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, SerdeError>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let Some(value) = self.value.take() else {
+            return Err(de::Error::custom("value deserialized before its key"));
+        };
+
+        seed.deserialize(ValueDeserializer(value))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+/// Access to an externally tagged enum variant.
+struct EnumDeserializer {
+    variant: alloc::String,
+    value: Option<Value>,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumDeserializer {
+    type Error = SerdeError;
+    type Variant = VariantDeserializer;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, VariantDeserializer), SerdeError>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(self.variant.as_str().into_deserializer())?;
+        Ok((variant, VariantDeserializer { value: self.value }))
+    }
+}
+
+struct VariantDeserializer {
+    value: Option<Value>,
+}
+
+impl VariantDeserializer {
+    fn content(self) -> Result<ValueDeserializer, SerdeError> {
+        match self.value {
+            Some(value) => Ok(ValueDeserializer(value)),
+            None => Err(de::Error::invalid_type(
+                de::Unexpected::UnitVariant,
+                &"a variant with content",
+            )),
+        }
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for VariantDeserializer {
+    type Error = SerdeError;
+
+    fn unit_variant(self) -> Result<(), SerdeError> {
+        match self.value {
+            None => Ok(()),
+            Some(value) => de::Deserialize::deserialize(ValueDeserializer(value)),
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, SerdeError>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        seed.deserialize(self.content()?)
+    }
+
+    fn tuple_variant<V>(self, _: usize, visitor: V) -> Result<V::Value, SerdeError>
+    where
+        V: de::Visitor<'de>,
+    {
+        de::Deserializer::deserialize_seq(self.content()?, visitor)
+    }
+
+    fn struct_variant<V>(
+        self,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeError>
+    where
+        V: de::Visitor<'de>,
+    {
+        de::Deserializer::deserialize_map(self.content()?, visitor)
+    }
+}
@@ -0,0 +1,192 @@
+//! Conversion of values containing external types to and from a tagged
+//! representation which can be serialized.
+
+use crate::alloc;
+use crate::alloc::prelude::*;
+use crate::runtime::{
+    Bytes, Inline, Object, OwnedTuple, Repr, RuntimeContext, SerializeHandler, Vec,
+};
+use crate::{Hash, TypeHash};
+
+use super::{SerdeError, SerdeErrorKind, Value};
+
+/// The key storing the type hash of a tagged value.
+const TYPE: &str = "$type";
+/// The key storing the item of a tagged value.
+const ITEM: &str = "$item";
+/// The key storing the serialized content of a tagged value.
+const VALUE: &str = "$value";
+
+/// Test if the given type hash is a type which is serializable without a
+/// handler.
+fn is_builtin(hash: Hash) -> bool {
+    matches!(
+        hash,
+        Option::<Value>::HASH
+            | alloc::String::HASH
+            | Bytes::HASH
+            | Vec::HASH
+            | OwnedTuple::HASH
+            | Object::HASH
+    )
+}
+
+pub(crate) fn to_serializable(cx: &RuntimeContext, value: &Value) -> Result<Value, SerdeError> {
+    let Repr::Any(any) = value.as_ref() else {
+        return Ok(value.clone());
+    };
+
+    match any.type_hash() {
+        Option::<Value>::HASH => {
+            let option = any.borrow_ref::<Option<Value>>()?;
+
+            let option = match &*option {
+                Some(value) => Some(to_serializable(cx, value)?),
+                None => None,
+            };
+
+            Ok(Value::try_from(option)?)
+        }
+        Vec::HASH => {
+            let vec = any.borrow_ref::<Vec>()?;
+            Ok(Value::vec(map(vec.iter(), |value| {
+                to_serializable(cx, value)
+            })?)?)
+        }
+        OwnedTuple::HASH => {
+            let tuple = any.borrow_ref::<OwnedTuple>()?;
+            Ok(Value::tuple(map(tuple.iter(), |value| {
+                to_serializable(cx, value)
+            })?)?)
+        }
+        Object::HASH => {
+            let object = any.borrow_ref::<Object>()?;
+            let mut output = Object::with_capacity(object.len())?;
+
+            for (key, value) in object.entries() {
+                output.insert(key.try_clone()?, to_serializable(cx, value)?)?;
+            }
+
+            Ok(Value::try_from(output)?)
+        }
+        hash if is_builtin(hash) => Ok(value.clone()),
+        hash => {
+            let Some(handler) = cx.serialize_handler(&hash) else {
+                return Err(SerdeError::new(SerdeErrorKind::UnregisteredType {
+                    hash,
+                    item: Some(any.type_info().try_to_string()?),
+                }));
+            };
+
+            tag(hash, handler, (handler.to_value)(value)?)
+        }
+    }
+}
+
+pub(crate) fn from_serializable(cx: &RuntimeContext, value: Value) -> Result<Value, SerdeError> {
+    let Repr::Any(any) = value.as_ref() else {
+        return Ok(value);
+    };
+
+    match any.type_hash() {
+        Option::<Value>::HASH => {
+            let option = match Option::clone(&*any.borrow_ref::<Option<Value>>()?) {
+                Some(value) => Some(from_serializable(cx, value)?),
+                None => None,
+            };
+
+            Ok(Value::try_from(option)?)
+        }
+        Vec::HASH => {
+            let vec = any.borrow_ref::<Vec>()?;
+            Ok(Value::vec(map(vec.iter(), |value| {
+                from_serializable(cx, value.clone())
+            })?)?)
+        }
+        OwnedTuple::HASH => {
+            let tuple = any.borrow_ref::<OwnedTuple>()?;
+            Ok(Value::tuple(map(tuple.iter(), |value| {
+                from_serializable(cx, value.clone())
+            })?)?)
+        }
+        Object::HASH => {
+            let object = any.borrow_ref::<Object>()?;
+
+            if let Some((hash, item, content)) = untag(&object)? {
+                let Some(handler) = cx.serialize_handler(&hash) else {
+                    return Err(SerdeError::new(SerdeErrorKind::UnregisteredType {
+                        hash,
+                        item,
+                    }));
+                };
+
+                return (handler.from_value)(content);
+            }
+
+            let mut output = Object::with_capacity(object.len())?;
+
+            for (key, value) in object.entries() {
+                output.insert(key.try_clone()?, from_serializable(cx, value.clone())?)?;
+            }
+
+            Ok(Value::try_from(output)?)
+        }
+        _ => Ok(value),
+    }
+}
+
+fn map<'a, I>(
+    values: I,
+    mut f: impl FnMut(&'a Value) -> Result<Value, SerdeError>,
+) -> Result<alloc::Vec<Value>, SerdeError>
+where
+    I: ExactSizeIterator<Item = &'a Value>,
+{
+    let mut output = alloc::Vec::try_with_capacity(values.len())?;
+
+    for value in values {
+        output.try_push(f(value)?)?;
+    }
+
+    Ok(output)
+}
+
+/// Construct the tagged representation of a value.
+fn tag(hash: Hash, handler: &SerializeHandler, content: Value) -> Result<Value, SerdeError> {
+    let mut object = Object::with_capacity(3)?;
+    object.insert(TYPE.try_to_owned()?, Value::from(hash.into_inner()))?;
+    object.insert(
+        ITEM.try_to_owned()?,
+        Value::try_from(handler.item.try_to_string()?)?,
+    )?;
+    object.insert(VALUE.try_to_owned()?, content)?;
+    Ok(Value::try_from(object)?)
+}
+
+/// Test if an object is the tagged representation of a value, and if so
+/// return its type hash, item and content.
+fn untag(object: &Object) -> Result<Option<(Hash, Option<alloc::String>, Value)>, SerdeError> {
+    if object.len() != 3 {
+        return Ok(None);
+    }
+
+    let (Some(hash), Some(item), Some(content)) =
+        (object.get(TYPE), object.get(ITEM), object.get(VALUE))
+    else {
+        return Ok(None);
+    };
+
+    // Formats might not preserve the signedness of the hash.
+    let hash = match hash.as_inline() {
+        Some(Inline::Unsigned(hash)) => Hash::new(*hash),
+        Some(Inline::Signed(hash)) => Hash::new(*hash as u64),
+        _ => return Ok(None),
+    };
+
+    let item = match item.borrow_string_ref() {
+        Ok(item) => Some((*item).try_to_owned()?),
+        Err(..) => None,
+    };
+
+    Ok(Some((hash, item, content.clone())))
+}
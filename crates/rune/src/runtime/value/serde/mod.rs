@@ -1,15 +1,218 @@
+#[cfg(test)]
+mod tests;
+
+mod deserializer;
+mod external;
+mod serializer;
+
+pub(crate) use self::external::{from_serializable, to_serializable};
+
 use core::fmt;
 
 use crate::alloc;
 use crate::alloc::prelude::*;
-use crate::runtime::{self, Bytes, Inline, Object, OwnedTuple, Repr, RttiKind, Vec};
-use crate::TypeHash;
+use crate::runtime::{self, Bytes, Inline, Object, OwnedTuple, Repr, RttiKind, RuntimeError, Vec};
+use crate::{Hash, TypeHash};
 
-use serde::de::{self, Deserialize as _, Error as _};
-use serde::ser::{self, Error as _, SerializeMap as _, SerializeSeq as _};
+use serde::de::{self, Deserialize as _, DeserializeOwned, Error as _};
+use serde::ser::{self, Error as _, Serialize, SerializeMap as _, SerializeSeq as _};
 
 use super::Value;
 
+/// Convert any type implementing [`Serialize`] directly into a [`Value`],
+/// without going through an intermediate format like JSON.
+///
+/// Values are mapped in the same way as when a serialization format is
+/// deserialized into a [`Value`]:
+/// * Sequences become vectors, while tuples and tuple structs become tuples.
+/// * Maps and structs become objects. Since object keys are strings, maps with
+///   keys other than strings or characters produce an error.
+/// * Integers become signed integers if they fit in an `i64`, otherwise they
+///   become unsigned integers. `i128` and `u128` values which don't fit in
+///   either produce an error rather than being truncated.
+/// * Bytes become [`Bytes`].
+/// * Enums are externally tagged, so unit variants become strings and other
+///   variants become an object with a single entry keyed by the variant name.
+///
+/// # Examples
+///
+/// ```
+/// use rune::runtime::Object;
+///
+/// let value = serde_json::json!({"name": "rune", "tags": ["fast", "small"]});
+/// let value = rune::to_value_from_serialize(&value)?;
+///
+/// let object = rune::from_value::<Object>(value)?;
+/// assert_eq!(object.len(), 2);
+/// assert!(object.get("tags").is_some());
+/// # Ok::<_, rune::support::Error>(())
+/// ```
+pub fn to_value_from_serialize<T>(value: &T) -> Result<Value, SerdeError>
+where
+    T: ?Sized + Serialize,
+{
+    value.serialize(serializer::ValueSerializer)
+}
+
+/// Convert a [`Value`] directly into any type implementing [`Deserialize`],
+/// without going through an intermediate format like JSON.
+///
+/// This is the inverse of [`to_value_from_serialize`], and uses the same
+/// mapping.
+///
+/// [`Deserialize`]: serde::Deserialize
+///
+/// # Examples
+///
+/// ```
+/// let value = rune::to_value_from_serialize(&serde_json::json!({"a": [1, 2]}))?;
+/// let output: serde_json::Value = rune::from_value_to_deserialize(value)?;
+/// assert_eq!(output, serde_json::json!({"a": [1, 2]}));
+/// # Ok::<_, rune::support::Error>(())
+/// ```
+pub fn from_value_to_deserialize<T>(value: Value) -> Result<T, SerdeError>
+where
+    T: DeserializeOwned,
+{
+    T::deserialize(deserializer::ValueDeserializer(value))
+}
+
+/// An error raised when converting between a [`Value`] and a type implementing
+/// [`Serialize`] or [`Deserialize`].
+///
+/// [`Deserialize`]: serde::Deserialize
+#[derive(Debug)]
+pub struct SerdeError {
+    kind: SerdeErrorKind,
+}
+
+impl SerdeError {
+    #[inline]
+    fn new(kind: SerdeErrorKind) -> Self {
+        Self { kind }
+    }
+
+    /// If the error was caused by a value of an external type which hasn't
+    /// been registered for serialization, get the hash of the type and its
+    /// item if it is known.
+    ///
+    /// See [`TypeMut::serializable`].
+    ///
+    /// [`TypeMut::serializable`]: crate::module::TypeMut::serializable
+    pub fn unregistered_type(&self) -> Option<(Hash, Option<&str>)> {
+        match &self.kind {
+            SerdeErrorKind::UnregisteredType { hash, item } => Some((*hash, item.as_deref())),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+enum SerdeErrorKind {
+    Custom(alloc::String),
+    Alloc(alloc::Error),
+    Runtime(RuntimeError),
+    NonStringKey {
+        found: &'static str,
+    },
+    I128OutOfRange(i128),
+    U128OutOfRange(u128),
+    UnregisteredType {
+        hash: Hash,
+        item: Option<alloc::String>,
+    },
+}
+
+impl fmt::Display for SerdeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            SerdeErrorKind::Custom(message) => message.fmt(f),
+            SerdeErrorKind::Alloc(error) => error.fmt(f),
+            SerdeErrorKind::Runtime(error) => error.fmt(f),
+            SerdeErrorKind::NonStringKey { found } => {
+                write!(f, "map keys must be strings, but found {found}")
+            }
+            SerdeErrorKind::I128OutOfRange(value) => {
+                write!(f, "integer {value} is out of range for a value")
+            }
+            SerdeErrorKind::U128OutOfRange(value) => {
+                write!(f, "integer {value} is out of range for a value")
+            }
+            SerdeErrorKind::UnregisteredType {
+                hash,
+                item: Some(item),
+            } => {
+                write!(
+                    f,
+                    "type `{item}` with hash {hash} is not registered for serialization"
+                )
+            }
+            SerdeErrorKind::UnregisteredType { hash, item: None } => {
+                write!(
+                    f,
+                    "type with hash {hash} is not registered for serialization"
+                )
+            }
+        }
+    }
+}
+
+impl core::error::Error for SerdeError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match &self.kind {
+            SerdeErrorKind::Alloc(error) => Some(error),
+            SerdeErrorKind::Runtime(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<alloc::Error> for SerdeError {
+    #[inline]
+    fn from(error: alloc::Error) -> Self {
+        Self::new(SerdeErrorKind::Alloc(error))
+    }
+}
+
+impl From<RuntimeError> for SerdeError {
+    #[inline]
+    fn from(error: RuntimeError) -> Self {
+        Self::new(SerdeErrorKind::Runtime(error))
+    }
+}
+
+impl SerdeError {
+    fn custom<T>(message: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        match alloc::fmt::try_format(format_args!("{message}")) {
+            Ok(message) => Self::new(SerdeErrorKind::Custom(message)),
+            Err(error) => Self::new(SerdeErrorKind::Alloc(error)),
+        }
+    }
+}
+
+impl ser::Error for SerdeError {
+    #[inline]
+    fn custom<T>(message: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        SerdeError::custom(message)
+    }
+}
+
+impl de::Error for SerdeError {
+    #[inline]
+    fn custom<T>(message: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        SerdeError::custom(message)
+    }
+}
+
 /// Deserialize implementation for value pointers.
 impl<'de> de::Deserialize<'de> for Value {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
@@ -306,7 +509,7 @@ impl<'de> de::Visitor<'de> for VmVisitor {
     {
         let mut object = Object::new();
 
-        while let Some((key, value)) = visitor.next_entry()? {
+        while let Some((key, value)) = visitor.next_entry::<alloc::String, Value>()? {
             object.insert(key, value).map_err(V::Error::custom)?;
         }
 
@@ -0,0 +1,583 @@
+use crate::alloc;
+use crate::alloc::prelude::*;
+use crate::runtime::{Bytes, Object};
+
+use serde::ser::{self, Serialize};
+
+use super::{SerdeError, SerdeErrorKind, Value};
+
+/// A serializer which constructs a [`Value`] directly from any type
+/// implementing [`Serialize`].
+pub(super) struct ValueSerializer;
+
+impl ValueSerializer {
+    #[inline]
+    fn unsigned(v: u64) -> Value {
+        match i64::try_from(v) {
+            Ok(v) => Value::from(v),
+            Err(..) => Value::from(v),
+        }
+    }
+}
+
+/// Construct a single entry object used to represent an enum variant with
+/// content.
+fn variant(variant: &'static str, value: Value) -> Result<Value, SerdeError> {
+    let mut object = Object::new();
+    object.insert(variant.try_to_owned()?, value)?;
+    Ok(Value::try_from(object)?)
+}
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = Value;
+    type Error = SerdeError;
+
+    type SerializeSeq = SerializeVec;
+    type SerializeTuple = SerializeTuple;
+    type SerializeTupleStruct = SerializeTuple;
+    type SerializeTupleVariant = SerializeVariant<SerializeTuple>;
+    type SerializeMap = SerializeObject;
+    type SerializeStruct = SerializeObject;
+    type SerializeStructVariant = SerializeVariant<SerializeObject>;
+
+    #[inline]
+    fn serialize_bool(self, v: bool) -> Result<Value, SerdeError> {
+        Ok(Value::from(v))
+    }
+
+    #[inline]
+    fn serialize_i8(self, v: i8) -> Result<Value, SerdeError> {
+        Ok(Value::from(i64::from(v)))
+    }
+
+    #[inline]
+    fn serialize_i16(self, v: i16) -> Result<Value, SerdeError> {
+        Ok(Value::from(i64::from(v)))
+    }
+
+    #[inline]
+    fn serialize_i32(self, v: i32) -> Result<Value, SerdeError> {
+        Ok(Value::from(i64::from(v)))
+    }
+
+    #[inline]
+    fn serialize_i64(self, v: i64) -> Result<Value, SerdeError> {
+        Ok(Value::from(v))
+    }
+
+    #[inline]
+    fn serialize_i128(self, v: i128) -> Result<Value, SerdeError> {
+        if let Ok(v) = i64::try_from(v) {
+            return Ok(Value::from(v));
+        }
+
+        if let Ok(v) = u64::try_from(v) {
+            return Ok(Value::from(v));
+        }
+
+        Err(SerdeError::new(SerdeErrorKind::I128OutOfRange(v)))
+    }
+
+    #[inline]
+    fn serialize_u8(self, v: u8) -> Result<Value, SerdeError> {
+        Ok(Value::from(i64::from(v)))
+    }
+
+    #[inline]
+    fn serialize_u16(self, v: u16) -> Result<Value, SerdeError> {
+        Ok(Value::from(i64::from(v)))
+    }
+
+    #[inline]
+    fn serialize_u32(self, v: u32) -> Result<Value, SerdeError> {
+        Ok(Value::from(i64::from(v)))
+    }
+
+    #[inline]
+    fn serialize_u64(self, v: u64) -> Result<Value, SerdeError> {
+        Ok(Self::unsigned(v))
+    }
+
+    #[inline]
+    fn serialize_u128(self, v: u128) -> Result<Value, SerdeError> {
+        match u64::try_from(v) {
+            Ok(v) => Ok(Self::unsigned(v)),
+            Err(..) => Err(SerdeError::new(SerdeErrorKind::U128OutOfRange(v))),
+        }
+    }
+
+    #[inline]
+    fn serialize_f32(self, v: f32) -> Result<Value, SerdeError> {
+        Ok(Value::from(f64::from(v)))
+    }
+
+    #[inline]
+    fn serialize_f64(self, v: f64) -> Result<Value, SerdeError> {
+        Ok(Value::from(v))
+    }
+
+    #[inline]
+    fn serialize_char(self, v: char) -> Result<Value, SerdeError> {
+        Ok(Value::from(v))
+    }
+
+    #[inline]
+    fn serialize_str(self, v: &str) -> Result<Value, SerdeError> {
+        Ok(Value::try_from(v.try_to_owned()?)?)
+    }
+
+    #[inline]
+    fn serialize_bytes(self, v: &[u8]) -> Result<Value, SerdeError> {
+        let bytes = Bytes::from_vec(alloc::Vec::try_from(v)?);
+        Ok(Value::try_from(bytes)?)
+    }
+
+    #[inline]
+    fn serialize_none(self) -> Result<Value, SerdeError> {
+        Ok(Value::try_from(None)?)
+    }
+
+    #[inline]
+    fn serialize_some<T>(self, value: &T) -> Result<Value, SerdeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        let value = value.serialize(ValueSerializer)?;
+        Ok(Value::try_from(Some(value))?)
+    }
+
+    #[inline]
+    fn serialize_unit(self) -> Result<Value, SerdeError> {
+        Ok(Value::unit())
+    }
+
+    #[inline]
+    fn serialize_unit_struct(self, _: &'static str) -> Result<Value, SerdeError> {
+        Ok(Value::unit())
+    }
+
+    #[inline]
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> Result<Value, SerdeError> {
+        self.serialize_str(variant)
+    }
+
+    #[inline]
+    fn serialize_newtype_struct<T>(self, _: &'static str, value: &T) -> Result<Value, SerdeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(ValueSerializer)
+    }
+
+    #[inline]
+    fn serialize_newtype_variant<T>(
+        self,
+        _: &'static str,
+        _: u32,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Value, SerdeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        variant(name, value.serialize(ValueSerializer)?)
+    }
+
+    #[inline]
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeVec, SerdeError> {
+        Ok(SerializeVec {
+            vec: alloc::Vec::try_with_capacity(len.unwrap_or_default())?,
+        })
+    }
+
+    #[inline]
+    fn serialize_tuple(self, len: usize) -> Result<SerializeTuple, SerdeError> {
+        Ok(SerializeTuple {
+            vec: alloc::Vec::try_with_capacity(len)?,
+        })
+    }
+
+    #[inline]
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        len: usize,
+    ) -> Result<SerializeTuple, SerdeError> {
+        self.serialize_tuple(len)
+    }
+
+    #[inline]
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, SerdeError> {
+        Ok(SerializeVariant {
+            variant,
+            inner: self.serialize_tuple(len)?,
+        })
+    }
+
+    #[inline]
+    fn serialize_map(self, _: Option<usize>) -> Result<SerializeObject, SerdeError> {
+        Ok(SerializeObject {
+            object: Object::new(),
+            key: None,
+        })
+    }
+
+    #[inline]
+    fn serialize_struct(self, _: &'static str, len: usize) -> Result<SerializeObject, SerdeError> {
+        self.serialize_map(Some(len))
+    }
+
+    #[inline]
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, SerdeError> {
+        Ok(SerializeVariant {
+            variant,
+            inner: self.serialize_map(Some(len))?,
+        })
+    }
+}
+
+pub(super) struct SerializeVec {
+    vec: alloc::Vec<Value>,
+}
+
+impl ser::SerializeSeq for SerializeVec {
+    type Ok = Value;
+    type Error = SerdeError;
+
+    #[inline]
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), SerdeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.vec.try_push(value.serialize(ValueSerializer)?)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn end(self) -> Result<Value, SerdeError> {
+        Ok(Value::vec(self.vec)?)
+    }
+}
+
+pub(super) struct SerializeTuple {
+    vec: alloc::Vec<Value>,
+}
+
+impl ser::SerializeTuple for SerializeTuple {
+    type Ok = Value;
+    type Error = SerdeError;
+
+    #[inline]
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), SerdeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.vec.try_push(value.serialize(ValueSerializer)?)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn end(self) -> Result<Value, SerdeError> {
+        Ok(Value::tuple(self.vec)?)
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeTuple {
+    type Ok = Value;
+    type Error = SerdeError;
+
+    #[inline]
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), SerdeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeTuple::serialize_element(self, value)
+    }
+
+    #[inline]
+    fn end(self) -> Result<Value, SerdeError> {
+        ser::SerializeTuple::end(self)
+    }
+}
+
+pub(super) struct SerializeObject {
+    object: Object,
+    key: Option<alloc::String>,
+}
+
+impl ser::SerializeMap for SerializeObject {
+    type Ok = Value;
+    type Error = SerdeError;
+
+    #[inline]
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), SerdeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.key = Some(key.serialize(KeySerializer)?);
+        Ok(())
+    }
+
+    #[inline]
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), SerdeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        let Some(key) = self.key.take() else {
+            return Err(ser::Error::custom("value serialized before its key"));
+        };
+
+        self.object.insert(key, value.serialize(ValueSerializer)?)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn end(self) -> Result<Value, SerdeError> {
+        Ok(Value::try_from(self.object)?)
+    }
+}
+
+impl ser::SerializeStruct for SerializeObject {
+    type Ok = Value;
+    type Error = SerdeError;
+
+    #[inline]
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), SerdeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        let value = value.serialize(ValueSerializer)?;
+        self.object.insert(key.try_to_owned()?, value)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn end(self) -> Result<Value, SerdeError> {
+        ser::SerializeMap::end(self)
+    }
+}
+
+/// Wraps the serialized content of a tuple or struct variant in a single entry
+/// object keyed by the name of the variant.
+pub(super) struct SerializeVariant<S> {
+    variant: &'static str,
+    inner: S,
+}
+
+impl ser::SerializeTupleVariant for SerializeVariant<SerializeTuple> {
+    type Ok = Value;
+    type Error = SerdeError;
+
+    #[inline]
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), SerdeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeTuple::serialize_element(&mut self.inner, value)
+    }
+
+    #[inline]
+    fn end(self) -> Result<Value, SerdeError> {
+        variant(self.variant, ser::SerializeTuple::end(self.inner)?)
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeVariant<SerializeObject> {
+    type Ok = Value;
+    type Error = SerdeError;
+
+    #[inline]
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), SerdeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeStruct::serialize_field(&mut self.inner, key, value)
+    }
+
+    #[inline]
+    fn end(self) -> Result<Value, SerdeError> {
+        variant(self.variant, ser::SerializeMap::end(self.inner)?)
+    }
+}
+
+/// Serializer for object keys, which only permits strings.
+struct KeySerializer;
+
+impl KeySerializer {
+    #[inline]
+    fn error(found: &'static str) -> SerdeError {
+        SerdeError::new(SerdeErrorKind::NonStringKey { found })
+    }
+}
+
+macro_rules! reject_key {
+    ($($method:ident($($ty:ty),*) => $found:literal),* $(,)?) => {
+        $(
+            #[inline]
+            fn $method(self, $(_: $ty),*) -> Result<alloc::String, SerdeError> {
+                Err(Self::error($found))
+            }
+        )*
+    };
+}
+
+impl ser::Serializer for KeySerializer {
+    type Ok = alloc::String;
+    type Error = SerdeError;
+
+    type SerializeSeq = ser::Impossible<alloc::String, SerdeError>;
+    type SerializeTuple = ser::Impossible<alloc::String, SerdeError>;
+    type SerializeTupleStruct = ser::Impossible<alloc::String, SerdeError>;
+    type SerializeTupleVariant = ser::Impossible<alloc::String, SerdeError>;
+    type SerializeMap = ser::Impossible<alloc::String, SerdeError>;
+    type SerializeStruct = ser::Impossible<alloc::String, SerdeError>;
+    type SerializeStructVariant = ser::Impossible<alloc::String, SerdeError>;
+
+    reject_key! {
+        serialize_bool(bool) => "a boolean",
+        serialize_i8(i8) => "an integer",
+        serialize_i16(i16) => "an integer",
+        serialize_i32(i32) => "an integer",
+        serialize_i64(i64) => "an integer",
+        serialize_i128(i128) => "an integer",
+        serialize_u8(u8) => "an integer",
+        serialize_u16(u16) => "an integer",
+        serialize_u32(u32) => "an integer",
+        serialize_u64(u64) => "an integer",
+        serialize_u128(u128) => "an integer",
+        serialize_f32(f32) => "a float",
+        serialize_f64(f64) => "a float",
+        serialize_bytes(&[u8]) => "bytes",
+        serialize_none() => "an option",
+        serialize_unit() => "a unit",
+        serialize_unit_struct(&'static str) => "a unit struct",
+    }
+
+    #[inline]
+    fn serialize_char(self, v: char) -> Result<alloc::String, SerdeError> {
+        let mut string = alloc::String::new();
+        string.try_push(v)?;
+        Ok(string)
+    }
+
+    #[inline]
+    fn serialize_str(self, v: &str) -> Result<alloc::String, SerdeError> {
+        Ok(v.try_to_owned()?)
+    }
+
+    #[inline]
+    fn serialize_some<T>(self, _: &T) -> Result<alloc::String, SerdeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(Self::error("an option"))
+    }
+
+    #[inline]
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> Result<alloc::String, SerdeError> {
+        self.serialize_str(variant)
+    }
+
+    #[inline]
+    fn serialize_newtype_struct<T>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<alloc::String, SerdeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    #[inline]
+    fn serialize_newtype_variant<T>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<alloc::String, SerdeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(Self::error("an enum variant"))
+    }
+
+    #[inline]
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, SerdeError> {
+        Err(Self::error("a sequence"))
+    }
+
+    #[inline]
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, SerdeError> {
+        Err(Self::error("a tuple"))
+    }
+
+    #[inline]
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleStruct, SerdeError> {
+        Err(Self::error("a tuple struct"))
+    }
+
+    #[inline]
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, SerdeError> {
+        Err(Self::error("an enum variant"))
+    }
+
+    #[inline]
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, SerdeError> {
+        Err(Self::error("a map"))
+    }
+
+    #[inline]
+    fn serialize_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStruct, SerdeError> {
+        Err(Self::error("a struct"))
+    }
+
+    #[inline]
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, SerdeError> {
+        Err(Self::error("an enum variant"))
+    }
+}
@@ -0,0 +1,212 @@
+use std::boxed::Box;
+use std::collections::BTreeMap;
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::runtime::{Bytes, Object, OwnedTuple, Value};
+
+use super::{from_value_to_deserialize, to_value_from_serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Config {
+    name: String,
+    retries: u32,
+    ratio: f64,
+    tags: Vec<String>,
+    limits: BTreeMap<String, i64>,
+    shape: Shape,
+    fallback: Option<Box<Config>>,
+    pair: (bool, char),
+    id: Id,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Id(u64);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Shape {
+    Empty,
+    Circle(f64),
+    Line(i64, i64),
+    Rect { width: i64, height: i64 },
+}
+
+fn config(shape: Shape) -> Config {
+    Config {
+        name: String::from("primary"),
+        retries: 3,
+        ratio: 0.5,
+        tags: vec![String::from("a"), String::from("b")],
+        limits: BTreeMap::from([(String::from("cpu"), 4), (String::from("memory"), -1)]),
+        shape,
+        fallback: Some(Box::new(Config {
+            name: String::from("fallback"),
+            retries: 0,
+            ratio: 1.0,
+            tags: Vec::new(),
+            limits: BTreeMap::new(),
+            shape: Shape::Empty,
+            fallback: None,
+            pair: (false, 'b'),
+            id: Id(u64::MAX),
+        })),
+        pair: (true, 'a'),
+        id: Id(7),
+    }
+}
+
+#[test]
+fn json_round_trip() {
+    let input = json!({
+        "null": null,
+        "bool": true,
+        "int": -42,
+        "big": u64::MAX,
+        "float": 1.5,
+        "string": "hello",
+        "array": [1, [2, 3], {"nested": {"deep": [null, false]}}],
+        "object": {},
+    });
+
+    let value = to_value_from_serialize(&input).unwrap();
+    let output: serde_json::Value = from_value_to_deserialize(value).unwrap();
+    assert_eq!(output, input);
+}
+
+#[test]
+fn json_to_runtime_types() {
+    let value = to_value_from_serialize(&json!({"list": [1, "two"], "n": 10})).unwrap();
+    let object = crate::from_value::<Object>(value).unwrap();
+
+    let n = object.get("n").unwrap();
+    assert_eq!(n.as_integer::<i64>().unwrap(), 10);
+
+    let list = object.get("list").unwrap();
+    let list = list.borrow_ref::<crate::runtime::Vec>().unwrap();
+    assert_eq!(list.len(), 2);
+    assert_eq!(&*list[1].borrow_string_ref().unwrap(), "two");
+}
+
+#[test]
+fn derived_round_trip() {
+    for shape in [
+        Shape::Empty,
+        Shape::Circle(2.5),
+        Shape::Line(-1, 1),
+        Shape::Rect {
+            width: 4,
+            height: 2,
+        },
+    ] {
+        let input = config(shape);
+        let value = to_value_from_serialize(&input).unwrap();
+        let output: Config = from_value_to_deserialize(value).unwrap();
+        assert_eq!(output, input);
+    }
+}
+
+#[test]
+fn derived_matches_json() {
+    // The mapping used for values should be the same as for JSON, so that
+    // going through either representation produces the same result.
+    let input = config(Shape::Rect {
+        width: 1,
+        height: 2,
+    });
+
+    let value = to_value_from_serialize(&input).unwrap();
+    let output: serde_json::Value = from_value_to_deserialize(value).unwrap();
+    assert_eq!(output, serde_json::to_value(&input).unwrap());
+}
+
+#[test]
+fn tuples() {
+    let value = to_value_from_serialize(&(1u8, "two", 3.0f32)).unwrap();
+    let tuple = value.borrow_ref::<OwnedTuple>().unwrap();
+    assert_eq!(tuple.len(), 3);
+    drop(tuple);
+
+    let output: (u8, String, f32) = from_value_to_deserialize(value).unwrap();
+    assert_eq!(output, (1, String::from("two"), 3.0));
+}
+
+#[test]
+fn bytes() {
+    struct Raw<'a>(&'a [u8]);
+
+    impl Serialize for Raw<'_> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            serializer.serialize_bytes(self.0)
+        }
+    }
+
+    let value = to_value_from_serialize(&Raw(b"\x00\x01rune")).unwrap();
+    let bytes = value.borrow_ref::<Bytes>().unwrap();
+    assert_eq!(bytes.as_slice(), b"\x00\x01rune");
+    drop(bytes);
+
+    let output: Bytes = from_value_to_deserialize(value).unwrap();
+    assert_eq!(output.as_slice(), b"\x00\x01rune");
+}
+
+#[test]
+fn integers() {
+    let value = to_value_from_serialize(&u64::MAX).unwrap();
+    assert_eq!(value.as_integer::<u64>().unwrap(), u64::MAX);
+
+    let value = to_value_from_serialize(&(i64::MIN as i128)).unwrap();
+    assert_eq!(value.as_integer::<i64>().unwrap(), i64::MIN);
+
+    let value = to_value_from_serialize(&(u64::MAX as u128)).unwrap();
+    assert_eq!(
+        from_value_to_deserialize::<u128>(value).unwrap(),
+        u64::MAX as u128
+    );
+}
+
+#[test]
+fn integer_out_of_range() {
+    let error = to_value_from_serialize(&u128::MAX).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        std::format!("integer {} is out of range for a value", u128::MAX)
+    );
+
+    let error = to_value_from_serialize(&i128::MIN).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        std::format!("integer {} is out of range for a value", i128::MIN)
+    );
+}
+
+#[test]
+fn non_string_keys() {
+    let input = BTreeMap::from([(1, "one"), (2, "two")]);
+    let error = to_value_from_serialize(&input).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "map keys must be strings, but found an integer"
+    );
+
+    let input = BTreeMap::from([('a', 1), ('b', 2)]);
+    let value = to_value_from_serialize(&input).unwrap();
+    let output: BTreeMap<char, i32> = from_value_to_deserialize(value).unwrap();
+    assert_eq!(output, input);
+}
+
+#[test]
+fn deserialize_mismatch() {
+    let value = to_value_from_serialize(&json!({"name": 1})).unwrap();
+    let error = from_value_to_deserialize::<Config>(value).unwrap_err();
+    assert!(error.to_string().contains("invalid type"), "{error}");
+
+    let value = Value::unit();
+    let output: Option<i64> = from_value_to_deserialize(value).unwrap();
+    assert_eq!(output, None);
+}
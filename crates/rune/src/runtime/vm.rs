@@ -1,10 +1,16 @@
 use core::cmp::Ordering;
 use core::fmt;
+use core::future::Future as _;
 use core::mem::replace;
+use core::pin::Pin;
 use core::ptr::NonNull;
+use core::task::{Context, Poll};
 
+use ::rust_alloc::boxed::Box;
 use ::rust_alloc::sync::Arc;
 
+use serde::{Deserialize, Serialize};
+
 use crate as rune;
 use crate::alloc::prelude::*;
 use crate::alloc::{self, String};
@@ -12,20 +18,23 @@ use crate::hash;
 use crate::hash::{Hash, IntoHash, ToTypeHash};
 use crate::modules::{option, result};
 use crate::runtime;
+use crate::Any;
 
-mod ops;
+mod inline_cache;
+pub(crate) mod ops;
+use self::inline_cache::{InlineCache, InlineEntry, InlineTarget};
 use self::ops::*;
 
 use super::{
-    budget, Args, Awaited, BorrowMut, Bytes, Call, ControlFlow, DynArgs, DynGuardedArgs, Dynamic,
-    Format, FormatSpec, Formatter, FromValue, Function, Future, Generator, GeneratorState,
+    budget, Args, Awaited, BorrowMut, Bytes, Call, ControlFlow, Debugger, DynArgs, DynGuardedArgs,
+    Dynamic, Format, FormatSpec, Formatter, FromValue, Function, Future, Generator, GeneratorState,
     GuardedArgs, Inline, Inst, InstAddress, InstArithmeticOp, InstBitwiseOp, InstOp, InstRange,
-    InstShiftOp, InstTarget, InstValue, InstVariant, Object, Output, OwnedTuple, Pair, Panic,
-    Protocol, ProtocolCaller, Range, RangeFrom, RangeFull, RangeInclusive, RangeTo,
-    RangeToInclusive, Repr, RttiKind, RuntimeContext, Select, SelectFuture, Stack, Stream, Type,
-    TypeCheck, TypeHash, TypeInfo, TypeOf, Unit, UnitFn, UnitStorage, Value, Vec, VmDiagnostics,
-    VmDiagnosticsObj, VmError, VmErrorKind, VmExecution, VmHalt, VmIntegerRepr, VmResult,
-    VmSendExecution,
+    InstShiftOp, InstTarget, InstValue, InstVariant, Object, ObjectKey, Output, OwnedTuple, Pair,
+    Panic, Protocol, ProtocolCaller, Range, RangeFrom, RangeFull, RangeInclusive, RangeTo,
+    RangeToInclusive, Repr, RttiKind, RuntimeContext, Select, Stack, Stream, ToValue, Tracer,
+    TryKind, Type, TypeCheck, TypeHash, TypeInfo, TypeOf, TypedArgs, TypedFunction, Unit, UnitFn,
+    UnitStorage, Value, Vec, VmData, VmDiagnostics, VmDiagnosticsObj, VmError, VmErrorKind,
+    VmExecution, VmFrame, VmHalt, VmIntegerRepr, VmResult, VmSendExecution,
 };
 
 /// Helper to take a value, replacing the old one with empty.
@@ -40,7 +49,7 @@ fn consume(value: &mut Value) {
 }
 
 /// Indicating the kind of isolation that is present for a frame.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum Isolated {
     /// The frame is isolated, once pop it will cause the execution to complete.
     Isolated,
@@ -110,6 +119,90 @@ pub(crate) enum CallResult<T> {
     Unsupported(Value),
 }
 
+/// Limits which apply to a virtual machine.
+///
+/// These are inherited by virtual machines which are constructed to call
+/// function values or to resume async functions, generators and streams.
+/// Virtual machines which are nested inside of a native function call also
+/// inherit how much of the limits the calling virtual machine has used.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct VmLimits {
+    /// The maximum number of call frames.
+    pub(crate) call_frames: usize,
+    /// The maximum number of stack slots.
+    pub(crate) stack: usize,
+    /// Call frames used by enclosing executions.
+    pub(crate) used_call_frames: usize,
+    /// Stack slots used by enclosing executions.
+    pub(crate) used_stack: usize,
+}
+
+impl VmLimits {
+    /// Limits which do not restrict the virtual machine.
+    pub(crate) const UNLIMITED: Self = Self {
+        call_frames: usize::MAX,
+        stack: usize::MAX,
+        used_call_frames: 0,
+        used_stack: 0,
+    };
+}
+
+/// The hook called by [`Vm::set_interrupt_hook`].
+pub(crate) struct InterruptHook {
+    /// Number of instructions between each call to the hook.
+    every: usize,
+    /// The hook to call.
+    hook: Box<dyn Fn(&Vm) -> core::ops::ControlFlow<()> + Send + Sync>,
+}
+
+/// An interrupt hook installed in a virtual machine.
+#[derive(Clone)]
+struct Interrupt {
+    /// Number of instructions remaining until the hook is called.
+    remaining: usize,
+    /// The hook to call.
+    hook: Arc<InterruptHook>,
+}
+
+impl fmt::Debug for Interrupt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Interrupt")
+            .field("every", &self.hook.every)
+            .field("remaining", &self.remaining)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A debugger installed in a virtual machine.
+struct VmDebugger {
+    /// The debugger.
+    debugger: Box<dyn Debugger>,
+    /// Set when execution was paused by the debugger, so that the instruction
+    /// it was paused at is not reported again when resumed.
+    paused: bool,
+}
+
+impl fmt::Debug for VmDebugger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VmDebugger")
+            .field("paused", &self.paused)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A tracer installed in a virtual machine.
+///
+/// The tracer is shared with virtual machines which are nested inside of
+/// native function calls and which execute async functions, generators and
+/// streams.
+pub(crate) struct VmTracer(Box<dyn Tracer>);
+
+impl fmt::Debug for VmTracer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VmTracer").finish_non_exhaustive()
+    }
+}
+
 /// A stack which references variables indirectly from a slab.
 #[derive(Debug)]
 pub struct Vm {
@@ -125,6 +218,23 @@ pub struct Vm {
     stack: Stack,
     /// Frames relative to the stack.
     call_frames: alloc::Vec<CallFrame>,
+    /// Limits which apply to the virtual machine.
+    limits: VmLimits,
+    /// Interrupt hook.
+    interrupt: Option<Interrupt>,
+    /// Host data associated with the virtual machine.
+    data: VmData,
+    /// Debugger hook.
+    debugger: Option<VmDebugger>,
+    /// Instruction tracer.
+    tracer: Option<Arc<VmTracer>>,
+    /// Whether an execution has been started which has not yet completed.
+    executing: bool,
+    /// Inline caches for instance function calls.
+    inline_cache: InlineCache,
+    /// Rotates which branch a select polls first, so that a branch which is
+    /// always ready doesn't starve the branches after it.
+    select_start: usize,
 }
 
 impl Vm {
@@ -150,9 +260,446 @@ impl Vm {
             last_ip_len: 0,
             stack,
             call_frames: alloc::Vec::new(),
+            limits: VmLimits::UNLIMITED,
+            interrupt: None,
+            data: VmData::new(),
+            debugger: None,
+            tracer: None,
+            executing: false,
+            inline_cache: InlineCache::new(),
+            select_start: 0,
         }
     }
 
+    /// Limit the number of call frames which may be active at once.
+    ///
+    /// Exceeding this limit, for example through deep recursion, causes the
+    /// virtual machine to error with a stack overflow. This is unlimited by
+    /// default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::{Context, Vm};
+    /// use std::sync::Arc;
+    ///
+    /// let context = Context::with_default_modules()?;
+    /// let runtime = Arc::new(context.runtime()?);
+    ///
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         fn recurse(n) {
+    ///             recurse(n + 1)
+    ///         }
+    ///
+    ///         pub fn main() {
+    ///             recurse(0)
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    /// let mut vm = Vm::new(runtime, Arc::new(unit)).with_max_call_frames(64);
+    ///
+    /// let error = vm.call(["main"], ()).unwrap_err();
+    /// assert!(error.is_stack_overflow());
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    pub fn with_max_call_frames(mut self, max: usize) -> Self {
+        self.limits.call_frames = max;
+        self
+    }
+
+    /// Limit the number of slots the stack may grow to.
+    ///
+    /// Exceeding this limit causes the virtual machine to error with a stack
+    /// overflow. This is unlimited by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::{Context, Vm};
+    /// use std::sync::Arc;
+    ///
+    /// let context = Context::with_default_modules()?;
+    /// let runtime = Arc::new(context.runtime()?);
+    ///
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         fn recurse(n) {
+    ///             recurse(n + 1)
+    ///         }
+    ///
+    ///         pub fn main() {
+    ///             recurse(0)
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    /// let mut vm = Vm::new(runtime, Arc::new(unit)).with_max_stack(1024);
+    ///
+    /// let error = vm.call(["main"], ()).unwrap_err();
+    /// assert!(error.is_stack_overflow());
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    pub fn with_max_stack(mut self, max: usize) -> Self {
+        self.limits.stack = max;
+        self
+    }
+
+    /// Install a hook which is called every `every` executed instructions.
+    ///
+    /// If the hook returns [`ControlFlow::Break`], execution is suspended
+    /// before the next instruction is executed and the current
+    /// [`VmExecution`] returns an error for which
+    /// [`VmError::is_interrupted`] is `true`. The execution can later be
+    /// continued from where it was suspended by calling
+    /// [`VmExecution::resume`].
+    ///
+    /// The hook is only called between instructions executed by this virtual
+    /// machine. In particular it is never called while a native function is
+    /// executing, which includes function values which are called from native
+    /// functions, and async functions, generators and streams which are
+    /// driven by native code. Such nested executions can't be suspended, so
+    /// interrupting them would only cause the native call to fail.
+    ///
+    /// [`ControlFlow::Break`]: core::ops::ControlFlow::Break
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::ops::ControlFlow;
+    /// use std::sync::Arc;
+    ///
+    /// use rune::runtime::GeneratorState;
+    /// use rune::{Context, Vm};
+    ///
+    /// let context = Context::with_default_modules()?;
+    /// let runtime = Arc::new(context.runtime()?);
+    ///
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         pub fn main() {
+    ///             let n = 0;
+    ///
+    ///             while n < 1000 {
+    ///                 n += 1;
+    ///             }
+    ///
+    ///             n
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    /// let mut vm = Vm::new(runtime, Arc::new(unit));
+    /// vm.set_interrupt_hook(100, |_| ControlFlow::Break(()));
+    ///
+    /// let mut execution = vm.execute(["main"], ())?;
+    /// let mut interrupts = 0;
+    ///
+    /// let value = loop {
+    ///     match execution.resume().into_result() {
+    ///         Ok(GeneratorState::Complete(value)) => break value,
+    ///         Ok(GeneratorState::Yielded(..)) => panic!("unexpected yield"),
+    ///         Err(error) if error.is_interrupted() => interrupts += 1,
+    ///         Err(error) => return Err(error.into()),
+    ///     }
+    /// };
+    ///
+    /// assert!(interrupts > 0);
+    /// assert_eq!(rune::from_value::<i64>(value)?, 1000);
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    pub fn set_interrupt_hook<F>(&mut self, every: usize, hook: F)
+    where
+        F: 'static + Fn(&Vm) -> core::ops::ControlFlow<()> + Send + Sync,
+    {
+        let every = every.max(1);
+
+        self.interrupt = Some(Interrupt {
+            remaining: every,
+            hook: Arc::new(InterruptHook {
+                every,
+                hook: Box::new(hook),
+            }),
+        });
+    }
+
+    /// Get the interrupt hook installed in this virtual machine.
+    #[inline]
+    pub(crate) fn interrupt_hook(&self) -> Option<Arc<InterruptHook>> {
+        Some(self.interrupt.as_ref()?.hook.clone())
+    }
+
+    /// Set the interrupt hook of this virtual machine.
+    #[inline]
+    pub(crate) fn with_interrupt_hook(mut self, hook: Option<Arc<InterruptHook>>) -> Self {
+        self.interrupt = hook.map(|hook| Interrupt {
+            remaining: hook.every,
+            hook,
+        });
+
+        self
+    }
+
+    /// Remove an interrupt hook installed with [`Vm::set_interrupt_hook`].
+    pub fn clear_interrupt_hook(&mut self) {
+        self.interrupt = None;
+    }
+
+    /// Tick the interrupt hook, returning `true` if execution should be
+    /// interrupted.
+    #[inline]
+    fn interrupt(&mut self) -> bool {
+        let Some(interrupt) = &mut self.interrupt else {
+            return false;
+        };
+
+        interrupt.remaining -= 1;
+
+        if interrupt.remaining > 0 {
+            return false;
+        }
+
+        interrupt.remaining = interrupt.hook.every;
+        let hook = interrupt.hook.clone();
+        (hook.hook)(self).is_break()
+    }
+
+    /// Install a tracer which is called before each instruction is executed.
+    ///
+    /// The tracer also observes instructions executed by virtual machines
+    /// which are nested inside of native function calls, like function values
+    /// called from native functions, and by async functions, generators and
+    /// streams. Like a debugger, it is not copied when the virtual machine is
+    /// cloned.
+    ///
+    /// See [`CollectingTracer`] for a tracer which records the last executed
+    /// instructions.
+    ///
+    /// [`CollectingTracer`]: crate::runtime::CollectingTracer
+    pub fn with_tracer<T>(mut self, tracer: T) -> Self
+    where
+        T: 'static + Tracer,
+    {
+        self.tracer = Some(Arc::new(VmTracer(Box::new(tracer))));
+        self
+    }
+
+    /// Get the tracer installed in this virtual machine.
+    #[inline]
+    pub(crate) fn tracer_hook(&self) -> Option<Arc<VmTracer>> {
+        self.tracer.clone()
+    }
+
+    /// Set the tracer of this virtual machine.
+    #[inline]
+    pub(crate) fn with_tracer_hook(mut self, tracer: Option<Arc<VmTracer>>) -> Self {
+        self.tracer = tracer;
+        self
+    }
+
+    /// Install a debugger in the virtual machine.
+    ///
+    /// The debugger is notified before each instruction which has debug
+    /// information associated with it is executed and can pause execution
+    /// there, which is how breakpoints are implemented. While paused, the
+    /// state of the virtual machine can be inspected through [`Vm::frames`].
+    /// See [`Debugger`] for details.
+    ///
+    /// The debugger only observes instructions executed by this virtual
+    /// machine. It is not inherited by virtual machines constructed to call
+    /// function values or to drive async functions, generators and streams,
+    /// and it is not copied when the virtual machine is cloned.
+    pub fn set_debugger<D>(&mut self, debugger: D)
+    where
+        D: 'static + Debugger,
+    {
+        self.debugger = Some(VmDebugger {
+            debugger: Box::new(debugger),
+            paused: false,
+        });
+    }
+
+    /// Remove a debugger installed with [`Vm::set_debugger`].
+    pub fn clear_debugger(&mut self) {
+        self.debugger = None;
+    }
+
+    /// Notify the debugger that the instruction at the current instruction
+    /// pointer is about to be executed, returning `true` if execution should
+    /// be paused.
+    #[inline]
+    fn debug_instruction(&mut self) -> bool {
+        let Some(debugger) = &mut self.debugger else {
+            return false;
+        };
+
+        if replace(&mut debugger.paused, false) {
+            return false;
+        }
+
+        let Some(inst) = self
+            .unit
+            .debug_info()
+            .and_then(|debug| debug.instruction_at(self.ip))
+        else {
+            return false;
+        };
+
+        if debugger.debugger.on_instruction(self.ip, inst).is_break() {
+            debugger.paused = true;
+            return true;
+        }
+
+        false
+    }
+
+    /// Iterate over the call frames of the virtual machine, starting with the
+    /// innermost one.
+    ///
+    /// Each frame provides access to the local variables which are in scope
+    /// in it, as long as the unit was compiled with debug information.
+    pub fn frames(&self) -> impl Iterator<Item = VmFrame<'_>> + '_ {
+        let current = VmFrame::new(self, self.ip, self.stack.top());
+
+        let callers = self
+            .call_frames
+            .iter()
+            .rev()
+            .map(move |frame| VmFrame::new(self, frame.last_ip(), frame.top));
+
+        core::iter::once(current).chain(callers)
+    }
+
+    /// Insert host data of type `T` into the virtual machine, returning the
+    /// previous value if one was present.
+    ///
+    /// This can be accessed by native functions through [`VmData`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    ///
+    /// use rune::runtime::VmData;
+    /// use rune::{Context, Module, Vm};
+    ///
+    /// #[derive(Default)]
+    /// struct Counter(AtomicUsize);
+    ///
+    /// #[rune::function]
+    /// fn increment(data: &VmData) -> usize {
+    ///     match data.get::<Counter>() {
+    ///         Some(counter) => counter.0.fetch_add(1, Ordering::SeqCst) + 1,
+    ///         None => 0,
+    ///     }
+    /// }
+    ///
+    /// let mut m = Module::new();
+    /// m.function_meta(increment)?;
+    ///
+    /// let mut context = Context::new();
+    /// context.install(m)?;
+    ///
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         pub fn main() {
+    ///             increment();
+    ///             increment()
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    /// let mut vm = Vm::new(Arc::new(context.runtime()?), Arc::new(unit));
+    /// vm.insert_data(Counter::default())?;
+    ///
+    /// let output = vm.call(["main"], ())?;
+    /// assert_eq!(rune::from_value::<usize>(output)?, 2);
+    ///
+    /// let counter = vm.data::<Counter>().expect("missing counter");
+    /// assert_eq!(counter.0.load(Ordering::SeqCst), 2);
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    pub fn insert_data<T>(&mut self, value: T) -> alloc::Result<Option<Arc<T>>>
+    where
+        T: core::any::Any + Send + Sync,
+    {
+        self.data.insert(value)
+    }
+
+    /// Get host data of type `T` inserted with [`Vm::insert_data`].
+    pub fn data<T>(&self) -> Option<&T>
+    where
+        T: core::any::Any + Send + Sync,
+    {
+        self.data.get()
+    }
+
+    /// Remove host data of type `T` inserted with [`Vm::insert_data`].
+    pub fn remove_data<T>(&mut self) -> alloc::Result<Option<Arc<T>>>
+    where
+        T: core::any::Any + Send + Sync,
+    {
+        self.data.remove()
+    }
+
+    /// Get all host data associated with the virtual machine.
+    #[inline]
+    pub(crate) fn vm_data(&self) -> &VmData {
+        &self.data
+    }
+
+    /// Set the host data associated with the virtual machine.
+    #[inline]
+    pub(crate) fn with_data(mut self, data: VmData) -> Self {
+        self.data = data;
+        self
+    }
+
+    /// Get the limits which apply to this virtual machine.
+    #[inline]
+    pub(crate) fn limits(&self) -> VmLimits {
+        self.limits
+    }
+
+    /// Set the limits which apply to this virtual machine.
+    #[inline]
+    pub(crate) fn with_limits(mut self, limits: VmLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Get the limits which apply to virtual machines nested inside of a
+    /// native function called from this virtual machine.
+    #[inline]
+    pub(crate) fn nested_limits(&self) -> VmLimits {
+        VmLimits {
+            used_call_frames: self
+                .limits
+                .used_call_frames
+                .saturating_add(self.call_frames.len()),
+            used_stack: self.limits.used_stack.saturating_add(self.stack.len()),
+            ..self.limits
+        }
+    }
+
+    /// Check that the stack is within the configured limit.
+    #[inline]
+    fn check_stack(&self) -> Result<(), VmErrorKind> {
+        if self.stack.len().saturating_add(self.limits.used_stack) > self.limits.stack {
+            return Err(VmErrorKind::StackOverflow {
+                limit: self.limits.stack,
+            });
+        }
+
+        Ok(())
+    }
+
     /// Construct a vm with a default empty [RuntimeContext]. This is useful
     /// when the [Unit] was constructed with an empty
     /// [Context][crate::compile::Context].
@@ -215,6 +762,7 @@ impl Vm {
     /// [`new`]: Vm::new
     #[inline]
     pub fn context_mut(&mut self) -> &mut Arc<RuntimeContext> {
+        self.inline_cache.clear();
         &mut self.context
     }
 
@@ -237,6 +785,7 @@ impl Vm {
     /// [`new`]: Vm::new
     #[inline]
     pub fn unit_mut(&mut self) -> &mut Arc<Unit> {
+        self.inline_cache.clear();
         &mut self.unit
     }
 
@@ -258,11 +807,90 @@ impl Vm {
         self.ip.wrapping_sub(self.last_ip_len as usize)
     }
 
+    /// Access the length of the last instruction that was executed.
+    #[inline]
+    pub(crate) fn last_ip_len(&self) -> u8 {
+        self.last_ip_len
+    }
+
+    /// Restore the instruction pointer and call frames of the virtual machine.
+    pub(crate) fn restore(
+        &mut self,
+        ip: usize,
+        last_ip_len: u8,
+        call_frames: alloc::Vec<CallFrame>,
+    ) {
+        self.ip = ip;
+        self.last_ip_len = last_ip_len;
+        self.call_frames = call_frames;
+        self.executing = true;
+    }
+
     /// Reset this virtual machine, freeing all memory used.
     pub fn clear(&mut self) {
         self.ip = 0;
         self.stack.clear();
         self.call_frames.clear();
+        self.executing = false;
+        self.inline_cache.clear();
+    }
+
+    /// Swap out the [`Unit`] of the virtual machine, returning the old one.
+    ///
+    /// This only succeeds if no execution is in flight, in which case the
+    /// virtual machine is also cleared. If an execution is in flight, the new
+    /// unit is handed back as an error.
+    ///
+    /// Calls made through [`Vm::call`] are never in flight once they return.
+    /// An execution started with [`Vm::execute`] is no longer in flight once
+    /// it completes or fails with an error it can't be resumed from. If it was
+    /// interrupted or abandoned before that, it is considered to be in flight
+    /// until [`Vm::clear`] is called.
+    ///
+    /// Executions which run on their own virtual machines, like generators,
+    /// streams, futures and executions started with [`Vm::send_execute`],
+    /// keep running against the unit they were started with. [`UnitDiff`] can
+    /// be used to decide whether they need to be restarted.
+    ///
+    /// [`UnitDiff`]: crate::runtime::UnitDiff
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use rune::{Context, Vm};
+    ///
+    /// let context = Context::with_default_modules()?;
+    /// let runtime = Arc::new(context.runtime()?);
+    ///
+    /// let mut old = rune::sources!(entry => { pub fn main() { 1 } });
+    /// let mut new = rune::sources!(entry => { pub fn main() { 2 } });
+    ///
+    /// let old = rune::prepare(&mut old).with_context(&context).build()?;
+    /// let new = rune::prepare(&mut new).with_context(&context).build()?;
+    ///
+    /// let mut vm = Vm::new(runtime, Arc::new(old));
+    /// assert_eq!(rune::from_value::<i64>(vm.call(["main"], ())?)?, 1);
+    ///
+    /// assert!(vm.try_swap_unit(Arc::new(new)).is_ok());
+    /// assert_eq!(rune::from_value::<i64>(vm.call(["main"], ())?)?, 2);
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    pub fn try_swap_unit(&mut self, unit: Arc<Unit>) -> Result<Arc<Unit>, Arc<Unit>> {
+        if self.executing {
+            return Err(unit);
+        }
+
+        self.clear();
+        Ok(replace(&mut self.unit, unit))
+    }
+
+    /// Mark the execution which was started on this virtual machine as
+    /// completed.
+    #[inline]
+    pub(crate) fn complete_execution(&mut self) {
+        self.executing = false;
     }
 
     /// Look up a function in the virtual machine by its name.
@@ -315,6 +943,55 @@ impl Vm {
         Ok(self.lookup_function_by_hash(name.to_type_hash())?)
     }
 
+    /// Construct a typed handle to the function identified by the given name.
+    ///
+    /// The function is looked up once and the number of arguments in `A` is
+    /// checked against the number of arguments the function takes, so an
+    /// error is raised here rather than on each call. The handle performs all
+    /// argument and return value conversions, and can be called any number of
+    /// times through [`TypedFunction::call`] or [`TypedFunction::async_call`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use rune::{Context, Vm};
+    ///
+    /// let context = Context::with_default_modules()?;
+    ///
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         pub fn add(a, b) {
+    ///             a + b
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    /// let mut vm = Vm::new(Arc::new(context.runtime()?), Arc::new(unit));
+    ///
+    /// let add = vm.typed_function::<(i64, i64), i64>(["add"])?;
+    /// assert_eq!(add.call(&mut vm, (1, 2))?, 3);
+    /// assert_eq!(add.call(&mut vm, (3, 4))?, 7);
+    ///
+    /// // The number of arguments is checked when the handle is constructed.
+    /// assert!(vm.typed_function::<(i64,), i64>(["add"]).is_err());
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    pub fn typed_function<A, R>(
+        &self,
+        name: impl ToTypeHash,
+    ) -> Result<TypedFunction<A, R>, VmError>
+    where
+        A: TypedArgs,
+        R: FromValue,
+    {
+        let hash = name.to_type_hash();
+        let offset = self.lookup_entrypoint(name, A::COUNT)?;
+        Ok(TypedFunction::new(self.unit.clone(), hash, offset))
+    }
+
     /// Convert into an execution.
     pub(crate) fn into_execution(self) -> VmExecution<Self> {
         VmExecution::new(self)
@@ -387,7 +1064,12 @@ impl Vm {
         args: impl Args,
     ) -> Result<VmExecution<&mut Self>, VmError> {
         self.set_entrypoint(name, args.count())?;
-        args.into_stack(&mut self.stack).into_result()?;
+
+        if let Err(error) = args.into_stack(&mut self.stack).into_result() {
+            self.abort_entry();
+            return Err(error);
+        }
+
         Ok(VmExecution::new(self))
     }
 
@@ -407,7 +1089,12 @@ impl Vm {
         self.stack.clear();
 
         self.set_entrypoint(name, args.count())?;
-        args.into_stack(&mut self.stack).into_result()?;
+
+        if let Err(error) = args.into_stack(&mut self.stack).into_result() {
+            self.abort_entry();
+            return Err(error);
+        }
+
         Ok(VmSendExecution(VmExecution::new(self)))
     }
 
@@ -431,11 +1118,22 @@ impl Vm {
         args: impl GuardedArgs,
     ) -> Result<Value, VmError> {
         self.set_entrypoint(name, args.count())?;
+        self.call_entered(args)
+    }
 
+    /// Call the function which has been entered through [`Vm::set_entrypoint`]
+    /// or [`Vm::set_entrypoint_offset`] with the given arguments.
+    pub(crate) fn call_entered(&mut self, args: impl GuardedArgs) -> Result<Value, VmError> {
         // Safety: We hold onto the guard until the vm has completed and
         // `VmExecution` will clear the stack before this function returns.
         // Erronously or not.
-        let guard = unsafe { args.guarded_into_stack(&mut self.stack).into_result()? };
+        let guard = match unsafe { args.guarded_into_stack(&mut self.stack).into_result() } {
+            Ok(guard) => guard,
+            Err(error) => {
+                self.abort_entry();
+                return Err(error);
+            }
+        };
 
         let value = {
             // Clearing the stack here on panics has safety implications - see
@@ -476,7 +1174,13 @@ impl Vm {
         // Safety: We hold onto the guard until the vm has completed and
         // `VmExecution` will clear the stack before this function returns.
         // Erronously or not.
-        let guard = unsafe { args.guarded_into_stack(&mut self.stack).into_result()? };
+        let guard = match unsafe { args.guarded_into_stack(&mut self.stack).into_result() } {
+            Ok(guard) => guard,
+            Err(error) => {
+                self.abort_entry();
+                return Err(error);
+            }
+        };
 
         let value = {
             // Clearing the stack here on panics has safety implications - see
@@ -515,11 +1219,160 @@ impl Vm {
         A: GuardedArgs,
     {
         self.set_entrypoint(name, args.count())?;
+        self.async_call_entered(args).await
+    }
+
+    /// Hand a reference to a native value to the given closure as a [`Value`],
+    /// which can be passed as an argument to any number of calls on the
+    /// virtual machine without cloning the native value or transferring
+    /// ownership of it.
+    ///
+    /// # Safety model
+    ///
+    /// The value handed to the closure doesn't own the data it refers to, so
+    /// scripts can only read from it for the duration of a native call. Any
+    /// attempt to acquire an owned [`Ref<T>`] or [`Mut<T>`] from it results in
+    /// an access error. Once the closure returns the value is invalidated, so
+    /// if the script stored it somewhere, like in an object, using it later
+    /// errors instead of accessing the reference after it has expired.
+    ///
+    /// See [`Vm::with_mut`] for a variant which permits the script to modify
+    /// the value.
+    ///
+    /// [`Ref<T>`]: runtime::Ref
+    /// [`Mut<T>`]: runtime::Mut
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use rune::{Any, Context, Module, Vm};
+    /// use rune::runtime::VmError;
+    ///
+    /// #[derive(Any)]
+    /// struct Config {
+    ///     #[rune(get)]
+    ///     limit: i64,
+    /// }
+    ///
+    /// let mut m = Module::new();
+    /// m.ty::<Config>()?;
+    ///
+    /// let mut context = Context::with_default_modules()?;
+    /// context.install(m)?;
+    ///
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         pub fn within(config, n) {
+    ///             n <= config.limit
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    /// let mut vm = Vm::new(Arc::new(context.runtime()?), Arc::new(unit));
+    ///
+    /// let config = Config { limit: 10 };
+    ///
+    /// let (a, b) = vm.with_ref(&config, |vm, config| {
+    ///     let a = vm.call(["within"], (config.clone(), 5))?;
+    ///     let b = vm.call(["within"], (config, 15))?;
+    ///     Ok::<_, VmError>((a, b))
+    /// })??;
+    ///
+    /// assert!(rune::from_value::<bool>(a)?);
+    /// assert!(!rune::from_value::<bool>(b)?);
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    pub fn with_ref<T, O>(
+        &mut self,
+        value: &T,
+        f: impl FnOnce(&mut Self, Value) -> O,
+    ) -> Result<O, VmError>
+    where
+        T: Any,
+    {
+        // Safety: The guard is dropped before the reference expires, even if
+        // the closure panics, which invalidates any copies of the value.
+        let (value, _guard) = unsafe { Value::from_ref(value)? };
+        Ok(f(self, value))
+    }
+
+    /// Hand a mutable reference to a native value to the given closure as a
+    /// [`Value`], which can be passed as an argument to any number of calls on
+    /// the virtual machine. Any modifications made by scripts are made in
+    /// place.
+    ///
+    /// This follows the same safety model as [`Vm::with_ref`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use rune::{Any, Context, Module, Vm};
+    ///
+    /// #[derive(Any)]
+    /// struct Counter {
+    ///     #[rune(get, set)]
+    ///     value: i64,
+    /// }
+    ///
+    /// let mut m = Module::new();
+    /// m.ty::<Counter>()?;
+    ///
+    /// let mut context = Context::with_default_modules()?;
+    /// context.install(m)?;
+    ///
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         pub fn update(counter) {
+    ///             counter.value += 1;
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    /// let mut vm = Vm::new(Arc::new(context.runtime()?), Arc::new(unit));
+    ///
+    /// let mut counter = Counter { value: 0 };
+    ///
+    /// vm.with_mut(&mut counter, |vm, counter| vm.call(["update"], (counter,)))??;
+    /// assert_eq!(counter.value, 1);
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    pub fn with_mut<T, O>(
+        &mut self,
+        value: &mut T,
+        f: impl FnOnce(&mut Self, Value) -> O,
+    ) -> Result<O, VmError>
+    where
+        T: Any,
+    {
+        // Safety: The guard is dropped before the reference expires, even if
+        // the closure panics, which invalidates any copies of the value.
+        let (value, _guard) = unsafe { Value::from_mut(value)? };
+        Ok(f(self, value))
+    }
 
+    /// Asynchronously call the function which has been entered through
+    /// [`Vm::set_entrypoint`] or [`Vm::set_entrypoint_offset`] with the given
+    /// arguments.
+    pub(crate) async fn async_call_entered<A>(&mut self, args: A) -> Result<Value, VmError>
+    where
+        A: GuardedArgs,
+    {
         // Safety: We hold onto the guard until the vm has completed and
         // `VmExecution` will clear the stack before this function returns.
         // Erronously or not.
-        let guard = unsafe { args.guarded_into_stack(&mut self.stack).into_result()? };
+        let guard = match unsafe { args.guarded_into_stack(&mut self.stack).into_result() } {
+            Ok(guard) => guard,
+            Err(error) => {
+                self.abort_entry();
+                return Err(error);
+            }
+        };
 
         let value = {
             // Clearing the stack here on panics has safety implications - see
@@ -540,7 +1393,35 @@ impl Vm {
 
     /// Update the instruction pointer to match the function matching the given
     /// name and check that the number of argument matches.
-    fn set_entrypoint<N>(&mut self, name: N, count: usize) -> Result<(), VmErrorKind>
+    pub(crate) fn set_entrypoint<N>(&mut self, name: N, count: usize) -> Result<(), VmErrorKind>
+    where
+        N: ToTypeHash,
+    {
+        let offset = self.lookup_entrypoint(name, count)?;
+        self.set_entrypoint_offset(offset);
+        Ok(())
+    }
+
+    /// Update the instruction pointer to the given offset, which must have
+    /// been looked up using [`Vm::lookup_entrypoint`] in the unit of this
+    /// virtual machine.
+    pub(crate) fn set_entrypoint_offset(&mut self, offset: usize) {
+        self.ip = offset;
+        self.stack.clear();
+        self.call_frames.clear();
+        self.executing = true;
+    }
+
+    /// Leave the function which was entered, since its arguments couldn't be
+    /// pushed onto the stack.
+    fn abort_entry(&mut self) {
+        self.stack.clear();
+        self.executing = false;
+    }
+
+    /// Look up the offset of the function matching the given name and check
+    /// that the number of arguments matches.
+    fn lookup_entrypoint<N>(&self, name: N, count: usize) -> Result<usize, VmErrorKind>
     where
         N: ToTypeHash,
     {
@@ -570,10 +1451,7 @@ impl Vm {
             }
         };
 
-        self.ip = offset;
-        self.stack.clear();
-        self.call_frames.clear();
-        Ok(())
+        Ok(offset)
     }
 
     /// Helper function to call an instance function.
@@ -649,7 +1527,10 @@ impl Vm {
             vm_try!(self.stack.push(target));
             vm_try!(args.push_to_stack(&mut self.stack));
 
-            let result = handler(&mut self.stack, addr, count, out);
+            let result = {
+                let _limits = runtime::env::restrict(self.nested_limits());
+                handler(&mut self.stack, addr, count, out)
+            };
             self.stack.truncate(addr);
             vm_try!(result);
             return VmResult::Ok(CallResult::Ok(()));
@@ -695,7 +1576,10 @@ impl Vm {
         let lhs = self.stack.at(lhs);
 
         let ordering = match (lhs.as_inline_unchecked(), rhs.as_inline_unchecked()) {
-            (Some(lhs), Some(rhs)) => vm_try!(lhs.partial_cmp(rhs)),
+            (Some(lhs), Some(rhs)) => match fast_partial_cmp(lhs, rhs) {
+                Some(ordering) => ordering,
+                None => vm_try!(lhs.partial_cmp(rhs)),
+            },
             _ => {
                 let lhs = lhs.clone();
                 let rhs = rhs.clone();
@@ -726,17 +1610,35 @@ impl Vm {
     ) -> Result<(), VmErrorKind> {
         tracing::trace!("pushing call frame");
 
+        let call_frames = self
+            .call_frames
+            .len()
+            .saturating_add(self.limits.used_call_frames);
+
+        if call_frames >= self.limits.call_frames {
+            return Err(VmErrorKind::StackOverflow {
+                limit: self.limits.call_frames,
+            });
+        }
+
         let top = self.stack.swap_top(addr, args)?;
         let ip = replace(&mut self.ip, ip);
 
         let frame = CallFrame {
             ip,
+            last_ip_len: self.last_ip_len,
             top,
             isolated,
             out,
         };
 
         self.call_frames.try_push(frame)?;
+        self.check_stack()?;
+
+        if let Some(debugger) = &mut self.debugger {
+            debugger.debugger.on_enter_frame(self.ip);
+        }
+
         Ok(())
     }
 
@@ -749,6 +1651,11 @@ impl Vm {
         let frame = self.call_frames.pop()?;
         tracing::trace!(?frame);
         self.stack.pop_stack_top(frame.top);
+
+        if let Some(debugger) = &mut self.debugger {
+            debugger.debugger.on_exit_frame(frame.ip);
+        }
+
         Some(replace(&mut self.ip, frame.ip))
     }
 
@@ -765,6 +1672,11 @@ impl Vm {
         tracing::trace!(?frame);
         self.stack.pop_stack_top(frame.top);
         self.ip = frame.ip;
+
+        if let Some(debugger) = &mut self.debugger {
+            debugger.debugger.on_exit_frame(frame.ip);
+        }
+
         (frame.isolated, Some(frame.out))
     }
 
@@ -773,10 +1685,8 @@ impl Vm {
         match target.as_ref() {
             Repr::Dynamic(data) if matches!(data.rtti().kind, RttiKind::Struct) => {
                 let Some(value) = vm_try!(data.get_field_ref(field)) else {
-                    return err(VmErrorKind::MissingField {
-                        target: data.type_info(),
-                        field: vm_try!(field.try_to_owned()),
-                    });
+                    let fields = data.rtti().fields.keys().map(AsRef::as_ref);
+                    return err(vm_try!(missing_field(data.type_info(), field, fields)));
                 };
 
                 VmResult::Ok(Some(value.clone()))
@@ -786,10 +1696,12 @@ impl Vm {
                     let target = vm_try!(value.borrow_ref::<Object>());
 
                     let Some(value) = target.get(field) else {
-                        return err(VmErrorKind::MissingField {
-                            target: TypeInfo::any::<Object>(),
-                            field: vm_try!(field.try_to_owned()),
-                        });
+                        let fields = target.keys().map(AsRef::as_ref);
+                        return err(vm_try!(missing_field(
+                            TypeInfo::any::<Object>(),
+                            field,
+                            fields
+                        )));
                     };
 
                     VmResult::Ok(Some(value.clone()))
@@ -944,10 +1856,8 @@ impl Vm {
                     return Ok(true);
                 }
 
-                Err(VmErrorKind::MissingField {
-                    target: data.type_info(),
-                    field: field.try_to_owned()?,
-                })
+                let fields = data.rtti().fields.keys().map(AsRef::as_ref);
+                Err(missing_field(data.type_info(), field, fields)?)
             }
             Repr::Any(target) => match target.type_hash() {
                 Object::HASH => {
@@ -964,10 +1874,7 @@ impl Vm {
                 }
                 _ => Ok(false),
             },
-            target => Err(VmErrorKind::MissingField {
-                target: target.type_info(),
-                field: field.try_to_owned()?,
-            }),
+            target => Err(missing_field(target.type_info(), field, [])?),
         }
     }
 
@@ -1266,24 +2173,55 @@ impl Vm {
         addr: InstAddress,
         len: usize,
         value: Output,
+        default: bool,
     ) -> VmResult<Option<Select>> {
-        let futures = futures_util::stream::FuturesUnordered::new();
+        let mut futures = vm_try!(alloc::Vec::try_with_capacity(len));
+
+        for (branch, slot) in vm_try!(self.stack.slice_at_mut(addr, len))
+            .iter_mut()
+            .enumerate()
+        {
+            // Values which are not futures are converted using the
+            // `INTO_FUTURE` protocol. The converted future is stored in the
+            // temporary slot being selected over and not in the variable it
+            // was loaded from, so it's recreated each time the select is
+            // evaluated.
+            if slot.type_hash() != Future::HASH {
+                let future = vm_try!(slot.clone().into_future());
+                *slot = vm_try!(Value::new(future));
+            }
 
-        for (branch, value) in vm_try!(self.stack.slice_at(addr, len)).iter().enumerate() {
-            let future = vm_try!(value.clone().into_mut::<Future>());
+            let future = vm_try!(slot.clone().into_mut::<Future>());
 
             if !future.is_completed() {
-                futures.push(SelectFuture::new(self.ip + branch, future));
+                vm_try!(futures.try_push((self.ip + branch, future)));
             }
         }
 
-        if futures.is_empty() {
-            vm_try!(value.store(&mut self.stack, ()));
-            self.ip = self.ip.wrapping_add(len);
-            return VmResult::Ok(None);
+        if !futures.is_empty() {
+            let start = self.select_start % futures.len();
+            self.select_start = self.select_start.wrapping_add(1);
+            let mut select = Select::new(futures, start);
+
+            if !default {
+                return VmResult::Ok(Some(select));
+            }
+
+            // Poll every future once without waiting, and fall through to the
+            // default branch if none of them are ready.
+            let mut cx = Context::from_waker(futures_util::task::noop_waker_ref());
+
+            if let Poll::Ready(result) = Pin::new(&mut select).poll(&mut cx) {
+                let (ip, output) = vm_try!(result);
+                self.ip = ip;
+                vm_try!(value.store(&mut self.stack, output));
+                return VmResult::Ok(None);
+            }
         }
 
-        VmResult::Ok(Some(Select::new(futures)))
+        vm_try!(value.store(&mut self.stack, ()));
+        self.ip = self.ip.wrapping_add(len);
+        VmResult::Ok(None)
     }
 
     #[cfg_attr(feature = "bench", inline(never))]
@@ -1310,6 +2248,31 @@ impl Vm {
         VmResult::Ok(())
     }
 
+    /// Clone a value from a position relative to the top of the stack using
+    /// the `CLONE` protocol, or move it if it doesn't support the protocol.
+    #[cfg_attr(feature = "bench", inline(never))]
+    fn op_clone(&mut self, addr: InstAddress, out: Output) -> VmResult<()> {
+        let value = self.stack.at(addr).clone();
+        let value = vm_try!(value.clone_or_move_with(self));
+        vm_try!(out.store(&mut self.stack, value));
+        VmResult::Ok(())
+    }
+
+    /// Clone the value at the given address in place if it is of a type which
+    /// has been marked as immutable.
+    #[cfg_attr(feature = "bench", inline(never))]
+    fn op_clone_immutable(&mut self, addr: InstAddress) -> VmResult<()> {
+        let value = self.stack.at(addr);
+
+        let Some(handler) = self.context.immutable(&value.type_hash()) else {
+            return VmResult::Ok(());
+        };
+
+        let value = vm_try!(handler(value));
+        *vm_try!(self.stack.at_mut(addr)) = value;
+        VmResult::Ok(())
+    }
+
     #[cfg_attr(feature = "bench", inline(never))]
     fn op_drop(&mut self, set: usize) -> VmResult<()> {
         let Some(addresses) = self.unit.lookup_drop_set(set) else {
@@ -1428,6 +2391,7 @@ impl Vm {
     #[cfg_attr(feature = "bench", inline(never))]
     fn op_allocate(&mut self, size: usize) -> VmResult<()> {
         vm_try!(self.stack.resize(size));
+        vm_try!(self.check_stack());
         VmResult::Ok(())
     }
 
@@ -1439,6 +2403,10 @@ impl Vm {
             Repr::Inline(Inline::Bool(value)) => Value::from(!value),
             Repr::Inline(Inline::Unsigned(value)) => Value::from(!value),
             Repr::Inline(Inline::Signed(value)) => Value::from(!value),
+            Repr::Any(..) => {
+                let value = value.clone();
+                return self.unary_fallback(value, &Protocol::NOT, "!", out);
+            }
             value => {
                 let operand = value.type_info();
                 return err(VmErrorKind::UnsupportedUnaryOperation { op: "!", operand });
@@ -1449,6 +2417,26 @@ impl Vm {
         VmResult::Ok(())
     }
 
+    /// Call the protocol implementing a unary operation for an external type.
+    fn unary_fallback(
+        &mut self,
+        value: Value,
+        protocol: &Protocol,
+        op: &'static str,
+        out: Output,
+    ) -> VmResult<()> {
+        if let CallResult::Unsupported(value) =
+            vm_try!(self.call_instance_fn(Isolated::None, value, protocol, &mut (), out))
+        {
+            return err(VmErrorKind::UnsupportedUnaryOperation {
+                op,
+                operand: value.type_info(),
+            });
+        }
+
+        VmResult::Ok(())
+    }
+
     #[cfg_attr(feature = "bench", inline(never))]
     fn op_neg(&mut self, addr: InstAddress, out: Output) -> VmResult<()> {
         let value = self.stack.at(addr);
@@ -1456,6 +2444,10 @@ impl Vm {
         let value = match value.as_ref() {
             Repr::Inline(Inline::Float(value)) => Value::from(-value),
             Repr::Inline(Inline::Signed(value)) => Value::from(-value),
+            Repr::Any(..) => {
+                let value = value.clone();
+                return self.unary_fallback(value, &Protocol::NEG, "-", out);
+            }
             actual => {
                 let operand = actual.type_info();
                 return err(VmErrorKind::UnsupportedUnaryOperation { op: "-", operand });
@@ -1502,7 +2494,10 @@ impl Vm {
                 let lhs = self.stack.at(lhs);
 
                 let test = if let (Some(lhs), Some(rhs)) = (lhs.as_inline(), rhs.as_inline()) {
-                    vm_try!(lhs.partial_eq(rhs))
+                    match fast_partial_eq(lhs, rhs) {
+                        Some(test) => test,
+                        None => vm_try!(lhs.partial_eq(rhs)),
+                    }
                 } else {
                     let lhs = lhs.clone();
                     let rhs = rhs.clone();
@@ -1516,7 +2511,10 @@ impl Vm {
                 let lhs = self.stack.at(lhs);
 
                 let test = if let (Some(lhs), Some(rhs)) = (lhs.as_inline(), rhs.as_inline()) {
-                    vm_try!(lhs.partial_eq(rhs))
+                    match fast_partial_eq(lhs, rhs) {
+                        Some(test) => test,
+                        None => vm_try!(lhs.partial_eq(rhs)),
+                    }
                 } else {
                     let lhs = lhs.clone();
                     let rhs = rhs.clone();
@@ -1556,11 +2554,24 @@ impl Vm {
         rhs: InstAddress,
         out: Output,
     ) -> VmResult<()> {
-        let ops = ArithmeticOps::from_op(op);
-
         let lhs = self.stack.at(lhs);
         let rhs = self.stack.at(rhs);
 
+        // Arithmetic over signed integers is by far the most common case, so
+        // it is handled before looking up the operation.
+        if let (Some(&Inline::Signed(lhs)), Some(&Inline::Signed(rhs))) =
+            (lhs.as_inline(), rhs.as_inline())
+        {
+            let Some(value) = signed_arithmetic(op, lhs, rhs) else {
+                return err((ArithmeticOps::from_op(op).error)());
+            };
+
+            vm_try!(out.store(&mut self.stack, Inline::Signed(value)));
+            return VmResult::Ok(());
+        }
+
+        let ops = ArithmeticOps::from_op(op);
+
         'fallback: {
             let inline = match (lhs.as_ref(), rhs.as_ref()) {
                 (Repr::Inline(lhs), Repr::Inline(rhs)) => match (lhs, rhs) {
@@ -2154,9 +3165,41 @@ impl Vm {
             return VmResult::Ok(());
         }
 
-        err(VmErrorKind::UnsupportedTupleIndexSet {
-            target: target.type_info(),
-        })
+        let target = target.clone();
+        let value = value.clone();
+
+        let mut args = DynGuardedArgs::new((&value,));
+
+        let result = vm_try!(self.call_index_fn(
+            &Protocol::SET,
+            target,
+            index,
+            &mut args,
+            Output::discard()
+        ));
+
+        let CallResult::Unsupported(target) = result else {
+            return VmResult::Ok(());
+        };
+
+        // Fall back to a dynamic tuple index set for types which opt into it,
+        // like tuple structs deriving `#[rune(index)]`.
+        let index = vm_try!(index.to_value());
+        let mut args = DynGuardedArgs::new((&index, &value));
+
+        if let CallResult::Unsupported(target) = vm_try!(self.call_instance_fn(
+            Isolated::None,
+            target,
+            &Protocol::TUPLE_INDEX_SET,
+            &mut args,
+            Output::discard()
+        )) {
+            return err(VmErrorKind::UnsupportedTupleIndexSet {
+                target: target.type_info(),
+            });
+        }
+
+        VmResult::Ok(())
     }
 
     /// Perform an index get operation specialized for tuples.
@@ -2176,9 +3219,24 @@ impl Vm {
 
         let value = value.clone();
 
-        if let CallResult::Unsupported(value) =
+        let CallResult::Unsupported(value) =
             vm_try!(self.call_index_fn(&Protocol::GET, value, index, &mut (), out))
-        {
+        else {
+            return VmResult::Ok(());
+        };
+
+        // Fall back to a dynamic tuple index get for types which opt into it,
+        // like tuple structs deriving `#[rune(index)]`.
+        let index_value = vm_try!(index.to_value());
+        let mut args = DynGuardedArgs::new((&index_value,));
+
+        if let CallResult::Unsupported(value) = vm_try!(self.call_instance_fn(
+            Isolated::None,
+            value,
+            &Protocol::TUPLE_INDEX_GET,
+            &mut args,
+            out
+        )) {
             return err(VmErrorKind::UnsupportedTupleIndexGet {
                 target: value.type_info(),
                 index,
@@ -2188,6 +3246,71 @@ impl Vm {
         VmResult::Ok(())
     }
 
+    /// Get an item out of a sequence, counting from the back.
+    #[cfg_attr(feature = "bench", inline(never))]
+    fn op_sequence_index_get_back(
+        &mut self,
+        ty: TypeCheck,
+        addr: InstAddress,
+        index: usize,
+        out: Output,
+    ) -> VmResult<()> {
+        let value = self.stack.at(addr);
+
+        let result = vm_try!(self.on_tuple(ty, value, |tuple| {
+            let index = tuple.len().checked_sub(index)?.checked_sub(1)?;
+            tuple.get(index).cloned()
+        }));
+
+        let Some(Some(result)) = result else {
+            return err(VmErrorKind::UnsupportedTupleIndexGet {
+                target: value.type_info(),
+                index,
+            });
+        };
+
+        vm_try!(out.store(&mut self.stack, result));
+        VmResult::Ok(())
+    }
+
+    /// Construct a new sequence out of the remaining items of a sequence.
+    #[cfg_attr(feature = "bench", inline(never))]
+    fn op_sequence_rest(
+        &mut self,
+        ty: TypeCheck,
+        addr: InstAddress,
+        front: usize,
+        back: usize,
+        out: Output,
+    ) -> VmResult<()> {
+        let value = self.stack.at(addr);
+
+        let result = vm_try!(self.on_tuple(ty, value, |tuple| {
+            let end = tuple.len().checked_sub(back)?;
+            tuple
+                .get(front..end)?
+                .iter()
+                .cloned()
+                .try_collect::<alloc::Vec<Value>>()
+                .ok()
+        }));
+
+        let Some(Some(items)) = result else {
+            return err(VmErrorKind::UnsupportedTupleIndexGet {
+                target: value.type_info(),
+                index: front,
+            });
+        };
+
+        let value = match ty {
+            TypeCheck::Vec => vm_try!(Value::try_from(Vec::from(items))),
+            _ => vm_try!(Value::try_from(vm_try!(OwnedTuple::try_from(items)))),
+        };
+
+        vm_try!(out.store(&mut self.stack, value));
+        VmResult::Ok(())
+    }
+
     /// Perform a specialized index set operation on an object.
     #[cfg_attr(feature = "bench", inline(never))]
     fn op_object_index_set(
@@ -2304,8 +3427,7 @@ impl Vm {
         let values = vm_try!(self.stack.slice_at_mut(addr, keys.len()));
 
         for (key, value) in keys.iter().zip(values) {
-            let key = vm_try!(String::try_from(key.as_str()));
-            vm_try!(object.insert(key, take(value)));
+            vm_try!(object.insert(vm_try!(key.try_clone()), take(value)));
         }
 
         vm_try!(out.store(&mut self.stack, object));
@@ -2457,7 +3579,12 @@ impl Vm {
 
     /// Perform the try operation on the given stack location.
     #[cfg_attr(feature = "bench", inline(never))]
-    fn op_try(&mut self, addr: InstAddress, out: Output) -> VmResult<Option<Output>> {
+    fn op_try(
+        &mut self,
+        addr: InstAddress,
+        out: Output,
+        kind: Option<TryKind>,
+    ) -> VmResult<Option<Output>> {
         let result = 'out: {
             let value = {
                 let value = self.stack.at(addr);
@@ -2472,6 +3599,10 @@ impl Vm {
                             let option = vm_try!(value.borrow_ref::<Option<Value>>());
                             break 'out vm_try!(option::option_try(&option));
                         }
+                        ControlFlow::HASH => {
+                            let flow = vm_try!(value.borrow_ref::<ControlFlow>());
+                            break 'out vm_try!(flow.try_());
+                        }
                         _ => {}
                     }
                 }
@@ -2494,7 +3625,18 @@ impl Vm {
                 vm_try!(out.store(&mut self.stack, value));
                 VmResult::Ok(None)
             }
-            ControlFlow::Break(error) => VmResult::Ok(vm_try!(self.op_return_internal(error))),
+            ControlFlow::Break(value) => {
+                if let Some(kind) = kind {
+                    if value.type_hash() != kind.type_hash() {
+                        return err(VmErrorKind::TryKindMismatch {
+                            expected: kind,
+                            actual: value.type_info(),
+                        });
+                    }
+                }
+
+                VmResult::Ok(vm_try!(self.op_return_internal(value)))
+            }
         }
     }
 
@@ -2615,6 +3757,33 @@ impl Vm {
         VmResult::Ok(())
     }
 
+    #[cfg_attr(feature = "bench", inline(never))]
+    fn op_object_rest(&mut self, addr: InstAddress, slot: usize, out: Output) -> VmResult<()> {
+        let value = self.stack.at(addr);
+
+        let Some(object) = vm_try!(value.try_borrow_ref::<Object>()) else {
+            return err(VmErrorKind::expected::<Object>(value.type_info()));
+        };
+
+        let Some(keys) = self.unit.lookup_object_keys(slot) else {
+            return err(VmErrorKind::MissingStaticObjectKeys { slot });
+        };
+
+        let mut rest = Object::new();
+
+        for (key, value) in object.entries() {
+            if keys.iter().any(|k| k.as_str() == key.as_str()) {
+                continue;
+            }
+
+            vm_try!(rest.insert(vm_try!(key.try_clone()), value.clone()));
+        }
+
+        drop(object);
+        vm_try!(out.store(&mut self.stack, rest));
+        VmResult::Ok(())
+    }
+
     #[cfg_attr(feature = "bench", inline(never))]
     fn op_match_type(&mut self, hash: Hash, addr: InstAddress, out: Output) -> VmResult<()> {
         let value = self.stack.at(addr);
@@ -2719,7 +3888,7 @@ impl Vm {
         addr: InstAddress,
         out: Output,
     ) -> VmResult<()> {
-        fn test(object: &Object, keys: &[alloc::String], exact: bool) -> bool {
+        fn test(object: &Object, keys: &[ObjectKey], exact: bool) -> bool {
             if exact {
                 if object.len() != keys.len() {
                     return false;
@@ -2841,6 +4010,7 @@ impl Vm {
                 return err(VmErrorKind::MissingFunction { hash });
             };
 
+            let _limits = runtime::env::restrict(self.nested_limits());
             vm_try!(handler(&mut self.stack, addr, args, out));
             return VmResult::Ok(());
         };
@@ -2902,16 +4072,49 @@ impl Vm {
     fn op_call_associated(
         &mut self,
         hash: Hash,
+        cache: usize,
         addr: InstAddress,
         args: usize,
         out: Output,
     ) -> VmResult<()> {
         let instance = self.stack.at(addr);
         let type_hash = instance.type_hash();
+
+        if let Some(entry) = self.inline_cache.get(cache, type_hash) {
+            vm_try!(self.called_function_hook(entry.hash));
+
+            match entry.target {
+                InlineTarget::Handler(ref handler) => {
+                    let _limits = runtime::env::restrict(self.nested_limits());
+                    vm_try!(handler(&mut self.stack, addr, args, out));
+                }
+                InlineTarget::Offset {
+                    offset,
+                    call,
+                    args: expected,
+                } => {
+                    vm_try!(check_args(args, expected));
+                    vm_try!(self.call_offset_fn(offset, call, addr, args, Isolated::None, out));
+                }
+            }
+
+            return VmResult::Ok(());
+        }
+
         let hash = Hash::associated_function(type_hash, hash);
 
         if let Some(handler) = self.context.function(&hash) {
+            let entry = InlineEntry {
+                type_hash,
+                hash,
+                target: InlineTarget::Handler(handler.clone()),
+            };
+
+            vm_try!(self
+                .inline_cache
+                .insert(self.unit.call_sites(), cache, entry));
             vm_try!(self.called_function_hook(hash));
+            let _limits = runtime::env::restrict(self.nested_limits());
             vm_try!(handler(&mut self.stack, addr, args, out));
             return VmResult::Ok(());
         }
@@ -2923,9 +4126,24 @@ impl Vm {
             ..
         }) = self.unit.function(&hash)
         {
+            let (offset, call, expected) = (*offset, *call, *expected);
+
+            let entry = InlineEntry {
+                type_hash,
+                hash,
+                target: InlineTarget::Offset {
+                    offset,
+                    call,
+                    args: expected,
+                },
+            };
+
+            vm_try!(self
+                .inline_cache
+                .insert(self.unit.call_sites(), cache, entry));
             vm_try!(self.called_function_hook(hash));
-            vm_try!(check_args(args, *expected));
-            vm_try!(self.call_offset_fn(*offset, *call, addr, args, Isolated::None, out));
+            vm_try!(check_args(args, expected));
+            vm_try!(self.call_offset_fn(offset, call, addr, args, Isolated::None, out));
             return VmResult::Ok(());
         }
 
@@ -3013,7 +4231,15 @@ impl Vm {
     where
         F: FnOnce() -> T,
     {
-        let _guard = runtime::env::Guard::new(self.context.clone(), self.unit.clone(), None);
+        let _guard = runtime::env::Guard::new(
+            self.context.clone(),
+            self.unit.clone(),
+            None,
+            self.limits,
+            self.data.clone(),
+            self.tracer_hook(),
+        );
+
         f()
     }
 
@@ -3031,15 +4257,35 @@ impl Vm {
 
         // NB: set up environment so that native function can access context and
         // unit.
-        let _guard = runtime::env::Guard::new(self.context.clone(), self.unit.clone(), diagnostics);
+        let _guard = runtime::env::Guard::new(
+            self.context.clone(),
+            self.unit.clone(),
+            diagnostics,
+            self.limits,
+            self.data.clone(),
+            self.tracer_hook(),
+        );
 
+        #[cfg(feature = "debug-access")]
+        let _debug_access = runtime::debug_access::Guard::new();
+
+        // NB: the budget is cached for the duration of the loop, and is
+        // synchronized before calling functions which might observe it.
         let mut budget = budget::acquire();
 
         loop {
+            if self.interrupt() {
+                return VmResult::Ok(VmHalt::Interrupted);
+            }
+
             if !budget.take() {
                 return VmResult::Ok(VmHalt::Limited);
             }
 
+            if self.debug_instruction() {
+                return VmResult::Ok(VmHalt::Breakpoint);
+            }
+
             let Some((inst, inst_len)) = vm_try!(self.unit.instruction_at(self.ip)) else {
                 return VmResult::err(VmErrorKind::IpOutOfBounds {
                     ip: self.ip,
@@ -3049,6 +4295,13 @@ impl Vm {
 
             tracing::trace!(ip = ?self.ip, ?inst);
 
+            if let Some(VmTracer(tracer)) = self.tracer.as_deref() {
+                tracer.trace(self.ip, &inst, &self.stack);
+            }
+
+            #[cfg(feature = "debug-access")]
+            runtime::debug_access::set_ip(self.ip);
+
             self.ip = self.ip.wrapping_add(inst_len);
             self.last_ip_len = inst_len as u8;
 
@@ -3076,6 +4329,7 @@ impl Vm {
                     args,
                     out,
                 } => {
+                    budget.sync();
                     vm_try!(self.op_call(hash, addr, args, out));
                 }
                 Inst::CallOffset {
@@ -3089,11 +4343,13 @@ impl Vm {
                 }
                 Inst::CallAssociated {
                     hash,
+                    cache,
                     addr,
                     args,
                     out,
                 } => {
-                    vm_try!(self.op_call_associated(hash, addr, args, out));
+                    budget.sync();
+                    vm_try!(self.op_call_associated(hash, cache, addr, args, out));
                 }
                 Inst::CallFn {
                     function,
@@ -3101,6 +4357,8 @@ impl Vm {
                     args,
                     out,
                 } => {
+                    budget.sync();
+
                     if let Some(reason) = vm_try!(self.op_call_fn(function, addr, args, out)) {
                         return VmResult::Ok(reason);
                     }
@@ -3121,6 +4379,23 @@ impl Vm {
                 Inst::TupleIndexGetAt { addr, index, out } => {
                     vm_try!(self.op_tuple_index_get_at(addr, index, out));
                 }
+                Inst::SequenceIndexGetBack {
+                    type_check,
+                    addr,
+                    index,
+                    out,
+                } => {
+                    vm_try!(self.op_sequence_index_get_back(type_check, addr, index, out));
+                }
+                Inst::SequenceRest {
+                    type_check,
+                    addr,
+                    front,
+                    back,
+                    out,
+                } => {
+                    vm_try!(self.op_sequence_rest(type_check, addr, front, back, out));
+                }
                 Inst::ObjectIndexSet {
                     target,
                     slot,
@@ -3131,6 +4406,9 @@ impl Vm {
                 Inst::ObjectIndexGetAt { addr, slot, out } => {
                     vm_try!(self.op_object_index_get_at(addr, slot, out));
                 }
+                Inst::ObjectRest { addr, slot, out } => {
+                    vm_try!(self.op_object_rest(addr, slot, out));
+                }
                 Inst::IndexSet {
                     target,
                     index,
@@ -3152,8 +4430,13 @@ impl Vm {
                     let future = vm_try!(self.op_await(addr));
                     return VmResult::Ok(VmHalt::Awaited(Awaited::Future(future, out)));
                 }
-                Inst::Select { addr, len, value } => {
-                    if let Some(select) = vm_try!(self.op_select(addr, len, value)) {
+                Inst::Select {
+                    addr,
+                    len,
+                    value,
+                    default,
+                } => {
+                    if let Some(select) = vm_try!(self.op_select(addr, len, value, default)) {
                         return VmResult::Ok(VmHalt::Awaited(Awaited::Select(select, value)));
                     }
                 }
@@ -3169,6 +4452,12 @@ impl Vm {
                 Inst::Move { addr, out } => {
                     vm_try!(self.op_move(addr, out));
                 }
+                Inst::Clone { addr, out } => {
+                    vm_try!(self.op_clone(addr, out));
+                }
+                Inst::CloneImmutable { addr } => {
+                    vm_try!(self.op_clone_immutable(addr));
+                }
                 Inst::Drop { set } => {
                     vm_try!(self.op_drop(set));
                 }
@@ -3245,8 +4534,8 @@ impl Vm {
                 Inst::IsUnit { addr, out } => {
                     vm_try!(self.op_is_unit(addr, out));
                 }
-                Inst::Try { addr, out } => {
-                    if let Some(out) = vm_try!(self.op_try(addr, out)) {
+                Inst::Try { addr, out, kind } => {
+                    if let Some(out) = vm_try!(self.op_try(addr, out, kind)) {
                         return VmResult::Ok(VmHalt::Exited(out.as_addr()));
                     }
                 }
@@ -3360,6 +4649,14 @@ impl TryClone for Vm {
             last_ip_len: self.last_ip_len,
             stack: self.stack.try_clone()?,
             call_frames: self.call_frames.try_clone()?,
+            limits: self.limits,
+            interrupt: self.interrupt.clone(),
+            data: self.data.clone(),
+            debugger: None,
+            tracer: None,
+            executing: self.executing,
+            inline_cache: InlineCache::new(),
+            select_start: self.select_start,
         })
     }
 }
@@ -3381,11 +4678,13 @@ impl AsRef<Vm> for Vm {
 /// A call frame.
 ///
 /// This is used to store the return point after an instruction has been run.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[non_exhaustive]
 pub struct CallFrame {
     /// The stored instruction pointer.
     pub ip: usize,
+    /// The length of the instruction which pushed the call frame.
+    last_ip_len: u8,
     /// The top of the stack at the time of the call to ensure stack isolation
     /// across function calls.
     ///
@@ -3399,6 +4698,14 @@ pub struct CallFrame {
     pub out: Output,
 }
 
+impl CallFrame {
+    /// The instruction pointer of the instruction which pushed the call frame.
+    #[inline]
+    pub fn last_ip(&self) -> usize {
+        self.ip.wrapping_sub(self.last_ip_len as usize)
+    }
+}
+
 impl TryClone for CallFrame {
     #[inline]
     fn try_clone(&self) -> alloc::Result<Self> {
@@ -3412,6 +4719,7 @@ struct ClearStack<'a>(&'a mut Vm);
 impl Drop for ClearStack<'_> {
     fn drop(&mut self) {
         self.0.stack.clear();
+        self.0.executing = false;
     }
 }
 
@@ -3600,32 +4908,45 @@ fn try_tuple_like_index_get_mut(
     }
 }
 
+/// Construct an error for a field which is missing on `target`, suggesting
+/// one of `fields` if it has a similar name.
+fn missing_field<'a, I>(target: TypeInfo, field: &str, fields: I) -> alloc::Result<VmErrorKind>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let did_you_mean = match crate::compile::similar(field, fields)? {
+        Some(similar) => Some(similar.try_to_owned()?),
+        None => None,
+    };
+
+    Ok(VmErrorKind::MissingField {
+        target,
+        field: field.try_to_owned()?,
+        did_you_mean,
+    })
+}
+
 /// Implementation of getting a mutable string index on an object-like type.
 fn try_object_like_index_get_mut<'a>(
     target: &'a Value,
     field: &str,
 ) -> Result<Option<BorrowMut<'a, Value>>, VmErrorKind> {
     match target.as_ref() {
-        Repr::Inline(value) => Err(VmErrorKind::MissingField {
-            target: value.type_info(),
-            field: field.try_to_owned()?,
-        }),
+        Repr::Inline(value) => Err(missing_field(value.type_info(), field, [])?),
         Repr::Dynamic(data) if matches!(data.rtti().kind, RttiKind::Struct) => {
             Ok(data.get_field_mut(field)?)
         }
-        Repr::Dynamic(data) => Err(VmErrorKind::MissingField {
-            target: data.type_info(),
-            field: field.try_to_owned()?,
-        }),
+        Repr::Dynamic(data) => Err(missing_field(data.type_info(), field, [])?),
         Repr::Any(value) => match value.type_hash() {
             Object::HASH => {
                 let object = value.borrow_mut::<Object>()?;
 
-                let Ok(value) = BorrowMut::try_map(object, |object| object.get_mut(field)) else {
-                    return Err(VmErrorKind::MissingField {
-                        target: value.type_info(),
-                        field: field.try_to_owned()?,
-                    });
+                let value = match BorrowMut::try_map(object, |object| object.get_mut(field)) {
+                    Ok(value) => value,
+                    Err(object) => {
+                        let fields = object.keys().map(AsRef::as_ref);
+                        return Err(missing_field(value.type_info(), field, fields)?);
+                    }
                 };
 
                 Ok(Some(value))
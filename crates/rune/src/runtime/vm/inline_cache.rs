@@ -0,0 +1,92 @@
+use core::fmt;
+
+use ::rust_alloc::sync::Arc;
+
+use crate::alloc;
+use crate::runtime::{Call, FunctionHandler};
+use crate::Hash;
+
+/// Per call-site caches of resolved instance functions.
+///
+/// Every `CallAssociated` instruction has a slot in the cache, which remembers
+/// the function the call resolved to for the last receiver type seen at that
+/// call site. Entries refer to functions in the unit and context of the
+/// virtual machine, so the cache must be cleared whenever either changes.
+pub(crate) struct InlineCache {
+    entries: alloc::Vec<Option<InlineEntry>>,
+}
+
+impl InlineCache {
+    /// Construct a new empty cache.
+    pub(crate) const fn new() -> Self {
+        Self {
+            entries: alloc::Vec::new(),
+        }
+    }
+
+    /// Get the cached function for the given call site and receiver type.
+    #[inline]
+    pub(crate) fn get(&self, slot: usize, type_hash: Hash) -> Option<&InlineEntry> {
+        let entry = self.entries.get(slot)?.as_ref()?;
+
+        if entry.type_hash != type_hash {
+            return None;
+        }
+
+        Some(entry)
+    }
+
+    /// Store a resolved function for the given call site, where `call_sites`
+    /// is the total number of call sites in the unit.
+    pub(crate) fn insert(
+        &mut self,
+        call_sites: usize,
+        slot: usize,
+        entry: InlineEntry,
+    ) -> alloc::Result<()> {
+        if self.entries.len() < call_sites {
+            self.entries.try_resize_with(call_sites, || None)?;
+        }
+
+        if let Some(existing) = self.entries.get_mut(slot) {
+            *existing = Some(entry);
+        }
+
+        Ok(())
+    }
+
+    /// Clear all cached functions.
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+impl fmt::Debug for InlineCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InlineCache")
+            .field("len", &self.entries.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// A function resolved for a single call site.
+pub(crate) struct InlineEntry {
+    /// The type of the receiver the function was resolved for.
+    pub(crate) type_hash: Hash,
+    /// The hash of the resolved function.
+    pub(crate) hash: Hash,
+    /// The resolved function.
+    pub(crate) target: InlineTarget,
+}
+
+/// The target of a cached call.
+pub(crate) enum InlineTarget {
+    /// A native function in the runtime context.
+    Handler(Arc<FunctionHandler>),
+    /// A function in the unit.
+    Offset {
+        offset: usize,
+        call: Call,
+        args: usize,
+    },
+}
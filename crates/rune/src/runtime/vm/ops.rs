@@ -1,19 +1,20 @@
+use core::cmp::Ordering;
 use core::ops::{
     Add, BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Div, Mul, Rem, Sub,
 };
 
-use crate::runtime::{InstArithmeticOp, InstBitwiseOp, InstShiftOp, Protocol, VmErrorKind};
+use crate::runtime::{Inline, InstArithmeticOp, InstBitwiseOp, InstShiftOp, Protocol, VmErrorKind};
 
-pub(super) struct ArithmeticOps {
-    pub(super) protocol: Protocol,
-    pub(super) error: fn() -> VmErrorKind,
-    pub(super) i64: fn(i64, i64) -> Option<i64>,
-    pub(super) u64: fn(u64, u64) -> Option<u64>,
-    pub(super) f64: fn(f64, f64) -> f64,
+pub(crate) struct ArithmeticOps {
+    pub(crate) protocol: Protocol,
+    pub(crate) error: fn() -> VmErrorKind,
+    pub(crate) i64: fn(i64, i64) -> Option<i64>,
+    pub(crate) u64: fn(u64, u64) -> Option<u64>,
+    pub(crate) f64: fn(f64, f64) -> f64,
 }
 
 impl ArithmeticOps {
-    pub(super) fn from_op(op: InstArithmeticOp) -> &'static Self {
+    pub(crate) fn from_op(op: InstArithmeticOp) -> &'static Self {
         match op {
             InstArithmeticOp::Add => &Self {
                 protocol: Protocol::ADD,
@@ -54,16 +55,39 @@ impl ArithmeticOps {
     }
 }
 
-pub(super) struct AssignArithmeticOps {
-    pub(super) protocol: Protocol,
-    pub(super) error: fn() -> VmErrorKind,
-    pub(super) i64: fn(i64, i64) -> Option<i64>,
-    pub(super) u64: fn(u64, u64) -> Option<u64>,
-    pub(super) f64: fn(f64, f64) -> f64,
+/// Perform an arithmetic operation over two signed integers.
+///
+/// This behaves the same as [`ArithmeticOps::i64`], but dispatches on the
+/// operation directly so that it can be inlined into the virtual machine.
+#[inline(always)]
+pub(crate) fn signed_arithmetic(op: InstArithmeticOp, lhs: i64, rhs: i64) -> Option<i64> {
+    let value = match op {
+        InstArithmeticOp::Add => lhs.checked_add(rhs),
+        InstArithmeticOp::Sub => lhs.checked_sub(rhs),
+        InstArithmeticOp::Mul => lhs.checked_mul(rhs),
+        InstArithmeticOp::Div => lhs.checked_div(rhs),
+        InstArithmeticOp::Rem => lhs.checked_rem(rhs),
+    };
+
+    debug_assert_eq!(
+        value,
+        (ArithmeticOps::from_op(op).i64)(lhs, rhs),
+        "fast path disagrees for {lhs} {op:?} {rhs}"
+    );
+
+    value
+}
+
+pub(crate) struct AssignArithmeticOps {
+    pub(crate) protocol: Protocol,
+    pub(crate) error: fn() -> VmErrorKind,
+    pub(crate) i64: fn(i64, i64) -> Option<i64>,
+    pub(crate) u64: fn(u64, u64) -> Option<u64>,
+    pub(crate) f64: fn(f64, f64) -> f64,
 }
 
 impl AssignArithmeticOps {
-    pub(super) fn from_op(op: InstArithmeticOp) -> &'static AssignArithmeticOps {
+    pub(crate) fn from_op(op: InstArithmeticOp) -> &'static AssignArithmeticOps {
         match op {
             InstArithmeticOp::Add => &Self {
                 protocol: Protocol::ADD_ASSIGN,
@@ -104,15 +128,15 @@ impl AssignArithmeticOps {
     }
 }
 
-pub(super) struct AssignBitwiseOps {
-    pub(super) protocol: Protocol,
-    pub(super) i64: fn(&mut i64, i64),
-    pub(super) u64: fn(&mut u64, u64),
-    pub(super) bool: fn(&mut bool, bool),
+pub(crate) struct AssignBitwiseOps {
+    pub(crate) protocol: Protocol,
+    pub(crate) i64: fn(&mut i64, i64),
+    pub(crate) u64: fn(&mut u64, u64),
+    pub(crate) bool: fn(&mut bool, bool),
 }
 
 impl AssignBitwiseOps {
-    pub(super) fn from_ops(op: InstBitwiseOp) -> &'static Self {
+    pub(crate) fn from_ops(op: InstBitwiseOp) -> &'static Self {
         match op {
             InstBitwiseOp::BitAnd => &Self {
                 protocol: Protocol::BIT_AND_ASSIGN,
@@ -136,15 +160,15 @@ impl AssignBitwiseOps {
     }
 }
 
-pub(super) struct BitwiseOps {
-    pub(super) protocol: Protocol,
-    pub(super) i64: fn(i64, i64) -> i64,
-    pub(super) u64: fn(u64, u64) -> u64,
-    pub(super) bool: fn(bool, bool) -> bool,
+pub(crate) struct BitwiseOps {
+    pub(crate) protocol: Protocol,
+    pub(crate) i64: fn(i64, i64) -> i64,
+    pub(crate) u64: fn(u64, u64) -> u64,
+    pub(crate) bool: fn(bool, bool) -> bool,
 }
 
 impl BitwiseOps {
-    pub(super) fn from_op(op: InstBitwiseOp) -> &'static BitwiseOps {
+    pub(crate) fn from_op(op: InstBitwiseOp) -> &'static BitwiseOps {
         match op {
             InstBitwiseOp::BitAnd => &BitwiseOps {
                 protocol: Protocol::BIT_AND,
@@ -168,15 +192,15 @@ impl BitwiseOps {
     }
 }
 
-pub(super) struct AssignShiftOps {
-    pub(super) protocol: Protocol,
-    pub(super) error: fn() -> VmErrorKind,
-    pub(super) i64: fn(i64, u32) -> Option<i64>,
-    pub(super) u64: fn(u64, u32) -> Option<u64>,
+pub(crate) struct AssignShiftOps {
+    pub(crate) protocol: Protocol,
+    pub(crate) error: fn() -> VmErrorKind,
+    pub(crate) i64: fn(i64, u32) -> Option<i64>,
+    pub(crate) u64: fn(u64, u32) -> Option<u64>,
 }
 
 impl AssignShiftOps {
-    pub(super) fn from_op(op: InstShiftOp) -> &'static AssignShiftOps {
+    pub(crate) fn from_op(op: InstShiftOp) -> &'static AssignShiftOps {
         match op {
             InstShiftOp::Shl => &Self {
                 protocol: Protocol::SHL_ASSIGN,
@@ -194,15 +218,15 @@ impl AssignShiftOps {
     }
 }
 
-pub(super) struct ShiftOps {
-    pub(super) protocol: Protocol,
-    pub(super) error: fn() -> VmErrorKind,
-    pub(super) i64: fn(i64, u32) -> Option<i64>,
-    pub(super) u64: fn(u64, u32) -> Option<u64>,
+pub(crate) struct ShiftOps {
+    pub(crate) protocol: Protocol,
+    pub(crate) error: fn() -> VmErrorKind,
+    pub(crate) i64: fn(i64, u32) -> Option<i64>,
+    pub(crate) u64: fn(u64, u32) -> Option<u64>,
 }
 
 impl ShiftOps {
-    pub(super) fn from_op(op: InstShiftOp) -> &'static Self {
+    pub(crate) fn from_op(op: InstShiftOp) -> &'static Self {
         match op {
             InstShiftOp::Shl => &Self {
                 protocol: Protocol::SHL,
@@ -219,3 +243,50 @@ impl ShiftOps {
         }
     }
 }
+
+/// Compare two inline values of the same primitive type.
+///
+/// Returns `None` if the comparison has to go through [`Inline::partial_cmp`],
+/// which also handles coercions and errors.
+#[inline(always)]
+pub(crate) fn fast_partial_cmp(lhs: &Inline, rhs: &Inline) -> Option<Option<Ordering>> {
+    let ordering = match (lhs, rhs) {
+        (Inline::Signed(lhs), Inline::Signed(rhs)) => Some(lhs.cmp(rhs)),
+        (Inline::Unsigned(lhs), Inline::Unsigned(rhs)) => Some(lhs.cmp(rhs)),
+        (Inline::Float(lhs), Inline::Float(rhs)) => lhs.partial_cmp(rhs),
+        (Inline::Bool(lhs), Inline::Bool(rhs)) => Some(lhs.cmp(rhs)),
+        (Inline::Char(lhs), Inline::Char(rhs)) => Some(lhs.cmp(rhs)),
+        _ => return None,
+    };
+
+    debug_assert!(
+        matches!(lhs.partial_cmp(rhs), Ok(o) if o == ordering),
+        "fast path disagrees when comparing {lhs:?} and {rhs:?}"
+    );
+
+    Some(ordering)
+}
+
+/// Test two inline values of the same primitive type for equality.
+///
+/// Returns `None` if the test has to go through [`Inline::partial_eq`], which
+/// also handles coercions and errors.
+#[inline(always)]
+pub(crate) fn fast_partial_eq(lhs: &Inline, rhs: &Inline) -> Option<bool> {
+    let eq = match (lhs, rhs) {
+        (Inline::Signed(lhs), Inline::Signed(rhs)) => lhs == rhs,
+        (Inline::Unsigned(lhs), Inline::Unsigned(rhs)) => lhs == rhs,
+        (Inline::Float(lhs), Inline::Float(rhs)) => lhs == rhs,
+        (Inline::Bool(lhs), Inline::Bool(rhs)) => lhs == rhs,
+        (Inline::Char(lhs), Inline::Char(rhs)) => lhs == rhs,
+        (Inline::Unit, Inline::Unit) => true,
+        _ => return None,
+    };
+
+    debug_assert!(
+        matches!(lhs.partial_eq(rhs), Ok(o) if o == eq),
+        "fast path disagrees when testing {lhs:?} and {rhs:?} for equality"
+    );
+
+    Some(eq)
+}
@@ -80,6 +80,9 @@ impl VmCall {
         T: AsRef<Vm> + AsMut<Vm>,
     {
         let vm = execution.vm_mut();
+        let limits = vm.limits();
+        let data = vm.vm_data().clone();
+        let tracer = vm.tracer_hook();
 
         let new_stack = vm_try!(vm.stack_mut().drain().try_collect::<Stack>());
 
@@ -90,7 +93,10 @@ impl VmCall {
         let context = self.context.unwrap_or_else(|| vm.context().clone());
         let unit = self.unit.unwrap_or_else(|| vm.unit().clone());
 
-        let mut vm = Vm::with_stack(context, unit, new_stack);
+        let mut vm = Vm::with_stack(context, unit, new_stack)
+            .with_limits(limits)
+            .with_data(data)
+            .with_tracer_hook(tracer);
         vm.set_ip(ip);
         VmResult::Ok(vm)
     }
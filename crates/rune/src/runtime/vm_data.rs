@@ -0,0 +1,197 @@
+use core::any::{Any, TypeId};
+use core::fmt;
+use core::ptr::NonNull;
+
+use ::rust_alloc::sync::Arc;
+
+use crate::alloc::{self, Vec};
+
+type Entries = Vec<(TypeId, Arc<dyn Any + Send + Sync>)>;
+
+/// Host data associated with a virtual machine.
+///
+/// This is a map from types to values, which can be used to associate state
+/// with a [`Vm`] which should be accessible from native functions. Data is
+/// inserted with [`Vm::insert_data`], and native functions can access it by
+/// taking a [`VmData`] or `&VmData` argument when declared with
+/// [`#[rune::function]`], or by calling [`VmData::current`].
+///
+/// Any virtual machine which is spawned to call back into Rune from a native
+/// function shares the data of the virtual machine which called it.
+///
+/// Values stored must be [`Send`] and [`Sync`] since they might be accessed
+/// from multiple executions at once, so state which needs to be modified
+/// should use some form of interior mutability.
+///
+/// Cloning a [`VmData`] is cheap, and modifying a clone does not affect the
+/// original.
+///
+/// [`Vm`]: crate::Vm
+/// [`Vm::insert_data`]: crate::Vm::insert_data
+/// [`#[rune::function]`]: crate::function
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// use rune::runtime::VmData;
+///
+/// struct Counter(AtomicUsize);
+///
+/// let mut data = VmData::new();
+/// assert!(data.get::<Counter>().is_none());
+///
+/// data.insert(Counter(AtomicUsize::new(1)))?;
+///
+/// let counter = data.get::<Counter>().expect("missing counter");
+/// assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+/// # Ok::<_, rune::support::Error>(())
+/// ```
+#[derive(Default, Clone)]
+pub struct VmData {
+    entries: Option<Arc<Entries>>,
+}
+
+assert_impl!(VmData: Send + Sync);
+
+impl VmData {
+    /// Construct a new empty collection of data.
+    #[inline]
+    pub const fn new() -> Self {
+        Self { entries: None }
+    }
+
+    /// Get the data of the virtual machine which is currently executing.
+    ///
+    /// This is intended to be called from native functions. If no virtual
+    /// machine is executing, empty data is returned.
+    pub fn current() -> Self {
+        crate::runtime::env::data()
+    }
+
+    /// Test if there is no data.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries
+            .as_ref()
+            .map_or(true, |entries| entries.is_empty())
+    }
+
+    /// Get the value of type `T`, if it is present.
+    pub fn get<T>(&self) -> Option<&T>
+    where
+        T: Any + Send + Sync,
+    {
+        let entries = self.entries.as_ref()?;
+        let (_, value) = entries.iter().find(|(id, _)| *id == TypeId::of::<T>())?;
+        value.downcast_ref()
+    }
+
+    /// Insert a value of type `T`, returning the previous value if one was
+    /// present.
+    pub fn insert<T>(&mut self, value: T) -> alloc::Result<Option<Arc<T>>>
+    where
+        T: Any + Send + Sync,
+    {
+        let entries = self.entries_mut()?;
+        let value = Arc::new(value);
+
+        for (id, existing) in entries.iter_mut() {
+            if *id == TypeId::of::<T>() {
+                let old = core::mem::replace(existing, value);
+                return Ok(old.downcast().ok());
+            }
+        }
+
+        entries.try_push((TypeId::of::<T>(), value))?;
+        Ok(None)
+    }
+
+    /// Remove the value of type `T`, returning it if it was present.
+    pub fn remove<T>(&mut self) -> alloc::Result<Option<Arc<T>>>
+    where
+        T: Any + Send + Sync,
+    {
+        let entries = self.entries_mut()?;
+
+        let Some(index) = entries.iter().position(|(id, _)| *id == TypeId::of::<T>()) else {
+            return Ok(None);
+        };
+
+        let (_, value) = entries.remove(index);
+        Ok(value.downcast().ok())
+    }
+
+    /// Convert into a raw pointer which can be stored in the environment.
+    pub(crate) fn into_raw(self) -> Option<NonNull<()>> {
+        let raw = Arc::into_raw(self.entries?);
+        // SAFETY: Pointers produced by `Arc::into_raw` are never null.
+        Some(unsafe { NonNull::new_unchecked(raw.cast_mut().cast()) })
+    }
+
+    /// Clone from a raw pointer produced by [`VmData::into_raw`] without
+    /// taking ownership of it.
+    ///
+    /// # Safety
+    ///
+    /// The pointer must have been produced by [`VmData::into_raw`] and must
+    /// still be live.
+    pub(crate) unsafe fn clone_from_raw(raw: Option<NonNull<()>>) -> Self {
+        let Some(raw) = raw else {
+            return Self::new();
+        };
+
+        let raw = raw.as_ptr().cast_const().cast::<Entries>();
+        Arc::increment_strong_count(raw);
+
+        Self {
+            entries: Some(Arc::from_raw(raw)),
+        }
+    }
+
+    /// Drop a raw pointer produced by [`VmData::into_raw`].
+    ///
+    /// # Safety
+    ///
+    /// The pointer must have been produced by [`VmData::into_raw`], and must
+    /// not be used again.
+    pub(crate) unsafe fn drop_raw(raw: NonNull<()>) {
+        drop(Arc::from_raw(raw.as_ptr().cast_const().cast::<Entries>()));
+    }
+
+    /// Access entries mutably, cloning them if they are shared.
+    fn entries_mut(&mut self) -> alloc::Result<&mut Entries> {
+        let entries = self.entries.get_or_insert_with(|| Arc::new(Vec::new()));
+
+        if Arc::get_mut(entries).is_none() {
+            let mut cloned = Vec::try_with_capacity(entries.len())?;
+
+            for (id, value) in entries.iter() {
+                cloned.try_push((*id, value.clone()))?;
+            }
+
+            *entries = Arc::new(cloned);
+        }
+
+        let Some(entries) = Arc::get_mut(entries) else {
+            unreachable!("entries should be uniquely owned");
+        };
+
+        Ok(entries)
+    }
+}
+
+impl fmt::Debug for VmData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut list = f.debug_list();
+
+        if let Some(entries) = &self.entries {
+            for (id, _) in entries.iter() {
+                list.entry(id);
+            }
+        }
+
+        list.finish()
+    }
+}
@@ -0,0 +1,115 @@
+use core::fmt;
+use core::ops::ControlFlow;
+
+use crate::runtime::{DebugInst, Value, Vm};
+use crate::Hash;
+
+/// A debugger which can be installed in a virtual machine using
+/// [`Vm::set_debugger`].
+///
+/// All methods have default implementations which do nothing, so a debugger
+/// only needs to implement the callbacks it cares about.
+pub trait Debugger: Send {
+    /// Called before the instruction at `ip` is executed, for every
+    /// instruction which has debug information associated with it.
+    ///
+    /// Returning [`ControlFlow::Break`] pauses execution before the
+    /// instruction is executed. The current [`VmExecution`] then returns an
+    /// error for which [`VmError::is_breakpoint`] is `true`, and can be
+    /// continued by calling [`VmExecution::resume`]. When resumed, this
+    /// callback is not called again for the instruction execution was paused
+    /// at.
+    ///
+    /// [`VmExecution`]: crate::runtime::VmExecution
+    /// [`VmExecution::resume`]: crate::runtime::VmExecution::resume
+    /// [`VmError::is_breakpoint`]: crate::runtime::VmError::is_breakpoint
+    fn on_instruction(&mut self, ip: usize, inst: &DebugInst) -> ControlFlow<()> {
+        _ = ip;
+        _ = inst;
+        ControlFlow::Continue(())
+    }
+
+    /// Called when a call frame is entered, where `ip` is the first
+    /// instruction of the called function.
+    fn on_enter_frame(&mut self, ip: usize) {
+        _ = ip;
+    }
+
+    /// Called when a call frame is exited, where `ip` is the instruction
+    /// execution continues at in the caller.
+    fn on_exit_frame(&mut self, ip: usize) {
+        _ = ip;
+    }
+}
+
+/// A call frame of a virtual machine, as returned by [`Vm::frames`].
+pub struct VmFrame<'a> {
+    vm: &'a Vm,
+    ip: usize,
+    base: usize,
+}
+
+impl<'a> VmFrame<'a> {
+    #[inline]
+    pub(crate) fn new(vm: &'a Vm, ip: usize, base: usize) -> Self {
+        Self { vm, ip, base }
+    }
+
+    /// The instruction pointer the frame is currently at.
+    ///
+    /// For frames which are not the innermost frame, this is the instruction
+    /// which performed the call.
+    #[inline]
+    pub fn ip(&self) -> usize {
+        self.ip
+    }
+
+    /// The hash of the function the frame belongs to, if debug information is
+    /// available.
+    pub fn function(&self) -> Option<Hash> {
+        let debug = self.vm.unit().debug_info()?;
+        let (hash, _) = debug.function_containing(self.ip)?;
+        Some(hash)
+    }
+
+    /// Iterate over the names and values of the local variables which are in
+    /// scope in the frame.
+    ///
+    /// This requires the unit to have been compiled with debug information.
+    /// If multiple variables with the same name are in scope, the one which
+    /// shadows the others is produced last.
+    pub fn variables(&self) -> impl Iterator<Item = (&'a str, &'a Value)> + 'a {
+        let vm = self.vm;
+        let ip = self.ip;
+        let base = self.base;
+
+        let variables = vm
+            .unit()
+            .debug_info()
+            .into_iter()
+            .flat_map(move |debug| debug.variables_at(ip));
+
+        variables.filter_map(move |var| {
+            let value = vm.stack().as_slice().get(base.checked_add(var.addr)?)?;
+            Some((&*var.name, value))
+        })
+    }
+
+    /// Get the value of the local variable with the given name, if it's in
+    /// scope in the frame.
+    pub fn variable(&self, name: &str) -> Option<&'a Value> {
+        self.variables()
+            .filter(|(n, _)| *n == name)
+            .map(|(_, value)| value)
+            .last()
+    }
+}
+
+impl fmt::Debug for VmFrame<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VmFrame")
+            .field("ip", &self.ip)
+            .field("base", &self.base)
+            .finish_non_exhaustive()
+    }
+}
@@ -4,17 +4,20 @@ use core::fmt;
 use ::rust_alloc::boxed::Box;
 use ::rust_alloc::sync::Arc;
 
+use serde::Serialize;
+
 use crate::alloc::error::CustomError;
 use crate::alloc::prelude::*;
 use crate::alloc::{self, String};
+use crate::ast::Span;
 use crate::compile::meta;
 use crate::runtime::unit::{BadInstruction, BadJump};
-use crate::{Any, Hash, ItemBuf};
+use crate::{Any, Hash, Item, ItemBuf, SourceId};
 
 use super::{
     AccessError, AccessErrorKind, AnyObjError, AnyObjErrorKind, AnyTypeInfo, BoxedPanic, CallFrame,
     DynArgsUsed, DynamicTakeError, ExecutionState, MaybeTypeOf, Panic, Protocol, SliceError,
-    StackError, StaticString, TypeInfo, TypeOf, Unit, Vm, VmHaltInfo,
+    StackError, StaticString, TryKind, TypeInfo, TypeOf, Unit, Vm, VmHaltInfo,
 };
 
 /// A virtual machine error which includes tracing information.
@@ -77,6 +80,113 @@ impl VmError {
         self.inner.stacktrace.first()
     }
 
+    /// Get the backtrace of the error.
+    ///
+    /// This starts with the frame in which the error was raised, followed by
+    /// each frame which called into it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use rune::{Context, Vm};
+    ///
+    /// let context = Context::with_default_modules()?;
+    /// let runtime = Arc::new(context.runtime()?);
+    ///
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         fn inner() {
+    ///             panic!("oh no");
+    ///         }
+    ///
+    ///         pub fn main() {
+    ///             inner();
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    /// let mut vm = Vm::new(runtime, Arc::new(unit));
+    ///
+    /// let error = vm.call(["main"], ()).unwrap_err();
+    ///
+    /// let functions = error
+    ///     .backtrace()
+    ///     .map(|frame| frame.function.map(|item| item.to_string()))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(functions, [Some("inner".to_owned()), Some("main".to_owned())]);
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    pub fn backtrace(&self) -> impl Iterator<Item = FrameInfo<'_>> + '_ {
+        self.inner.stacktrace.iter().flat_map(|l| {
+            [l.ip]
+                .into_iter()
+                .chain(l.frames.iter().rev().map(CallFrame::last_ip))
+                .map(|ip| FrameInfo::new(&l.unit, ip))
+        })
+    }
+
+    /// Test if the error was caused by the allocation budget being exceeded.
+    ///
+    /// See [`budget`] for more information.
+    ///
+    /// [`budget`]: crate::runtime::budget
+    pub fn is_allocation_budget_exceeded(&self) -> bool {
+        matches!(
+            self.inner.error.kind,
+            VmErrorKind::AllocationBudgetExceeded { .. }
+        )
+    }
+
+    /// Test if the error was caused by a configured call frame or stack limit
+    /// being exceeded.
+    ///
+    /// See [`Vm::with_max_call_frames`] and [`Vm::with_max_stack`].
+    pub fn is_stack_overflow(&self) -> bool {
+        matches!(self.inner.error.kind, VmErrorKind::StackOverflow { .. })
+    }
+
+    /// Test if the error was caused by execution being interrupted by the hook
+    /// installed with [`Vm::set_interrupt_hook`].
+    ///
+    /// The execution which produced the error can be continued by resuming
+    /// it.
+    pub fn is_interrupted(&self) -> bool {
+        matches!(
+            self.inner.error.kind,
+            VmErrorKind::Halted {
+                halt: VmHaltInfo::Interrupted
+            }
+        )
+    }
+
+    /// Test if the error was caused by execution being paused by the
+    /// debugger installed with [`Vm::set_debugger`].
+    ///
+    /// The execution which produced the error can be continued by resuming
+    /// it.
+    pub fn is_breakpoint(&self) -> bool {
+        matches!(
+            self.inner.error.kind,
+            VmErrorKind::Halted {
+                halt: VmHaltInfo::Breakpoint
+            }
+        )
+    }
+
+    /// Test if the execution which produced the error can be resumed.
+    pub(crate) fn is_resumable(&self) -> bool {
+        matches!(
+            self.inner.error.kind,
+            VmErrorKind::Halted {
+                halt: VmHaltInfo::Interrupted | VmHaltInfo::Breakpoint | VmHaltInfo::Limited
+            }
+        )
+    }
+
     pub(crate) fn into_kind(self) -> VmErrorKind {
         self.inner.error.kind
     }
@@ -168,6 +278,70 @@ pub struct VmErrorLocation {
     pub frames: ::rust_alloc::vec::Vec<CallFrame>,
 }
 
+/// Information on a single frame in the backtrace of a [`VmError`].
+///
+/// See [`VmError::backtrace`].
+#[derive(Clone, Copy, Serialize)]
+#[non_exhaustive]
+pub struct FrameInfo<'a> {
+    /// The unit the frame is executing in.
+    #[serde(skip)]
+    pub unit: &'a Arc<Unit>,
+    /// The instruction pointer of the frame.
+    pub ip: usize,
+    /// The function the frame is executing, if it can be resolved through
+    /// debug info.
+    #[serde(serialize_with = "serialize_function")]
+    pub function: Option<&'a Item>,
+    /// The source the frame is executing in, if debug info is available.
+    pub source_id: Option<SourceId>,
+    /// The span of the instruction being executed, if debug info is
+    /// available.
+    pub span: Option<Span>,
+}
+
+/// Serialize the function of a frame as its item path.
+fn serialize_function<S>(function: &Option<&Item>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    match function {
+        Some(item) => serializer.serialize_some(&format_args!("{item}")),
+        None => serializer.serialize_none(),
+    }
+}
+
+impl<'a> FrameInfo<'a> {
+    fn new(unit: &'a Arc<Unit>, ip: usize) -> Self {
+        let debug_info = unit.debug_info();
+
+        let function = debug_info
+            .and_then(|d| d.function_containing(ip))
+            .map(|(_, signature)| &*signature.path);
+
+        let inst = debug_info.and_then(|d| d.instruction_at(ip));
+
+        Self {
+            unit,
+            ip,
+            function,
+            source_id: inst.map(|inst| inst.source_id),
+            span: inst.map(|inst| inst.span),
+        }
+    }
+}
+
+impl fmt::Debug for FrameInfo<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FrameInfo")
+            .field("ip", &self.ip)
+            .field("function", &self.function)
+            .field("source_id", &self.source_id)
+            .field("span", &self.span)
+            .finish_non_exhaustive()
+    }
+}
+
 #[derive(Debug)]
 #[non_exhaustive]
 pub struct VmErrorAt {
@@ -528,6 +702,22 @@ impl RuntimeError {
             index,
         })
     }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn __rune_macros__unsupported_object_field_set(target: AnyTypeInfo) -> Self {
+        Self::new(VmErrorKind::UnsupportedObjectFieldSet {
+            target: TypeInfo::from(target),
+        })
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn __rune_macros__unsupported_tuple_index_set(target: AnyTypeInfo) -> Self {
+        Self::new(VmErrorKind::UnsupportedTupleIndexSet {
+            target: TypeInfo::from(target),
+        })
+    }
 }
 
 impl fmt::Debug for RuntimeError {
@@ -638,6 +828,10 @@ pub(crate) enum VmErrorKind {
     AllocError {
         error: alloc::Error,
     },
+    AllocationBudgetExceeded {
+        requested: usize,
+        remaining: usize,
+    },
     AccessError {
         error: AccessError,
     },
@@ -782,6 +976,10 @@ pub(crate) enum VmErrorKind {
     UnsupportedTryOperand {
         actual: TypeInfo,
     },
+    TryKindMismatch {
+        expected: TryKind,
+        actual: TypeInfo,
+    },
     UnsupportedIterRangeInclusive {
         start: TypeInfo,
         end: TypeInfo,
@@ -849,6 +1047,7 @@ pub(crate) enum VmErrorKind {
     MissingField {
         target: TypeInfo,
         field: String,
+        did_you_mean: Option<String>,
     },
     MissingVariantName,
     MissingStructField {
@@ -865,6 +1064,9 @@ pub(crate) enum VmErrorKind {
     UnsupportedObjectFieldGet {
         target: TypeInfo,
     },
+    UnsupportedObjectFieldSet {
+        target: TypeInfo,
+    },
     IllegalFloatComparison {
         lhs: f64,
         rhs: f64,
@@ -874,13 +1076,34 @@ pub(crate) enum VmErrorKind {
         value: f64,
     },
     MissingCallFrame,
+    StackOverflow {
+        limit: usize,
+    },
+    CloneDepthExceeded {
+        limit: usize,
+    },
     IllegalFormat,
+    SnapshotUnsupported {
+        slots: alloc::Vec<(usize, TypeInfo)>,
+    },
+    SnapshotNested,
+    SnapshotUnitMismatch,
+    SnapshotCorrupt,
 }
 
 impl fmt::Display for VmErrorKind {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             VmErrorKind::AllocError { error } => error.fmt(f),
+            VmErrorKind::AllocationBudgetExceeded {
+                requested,
+                remaining,
+            } => {
+                write!(
+                    f,
+                    "Allocation budget exceeded when allocating {requested} bytes with {remaining} bytes remaining"
+                )
+            }
             VmErrorKind::AccessError { error } => error.fmt(f),
             VmErrorKind::StackError { error } => error.fmt(f),
             VmErrorKind::SliceError { error } => error.fmt(f),
@@ -1003,6 +1226,12 @@ impl fmt::Display for VmErrorKind {
             VmErrorKind::UnsupportedTryOperand { actual } => {
                 write!(f, "Type `{actual}` is not supported as try operand")
             }
+            VmErrorKind::TryKindMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "Try operator cannot return `{actual}` from a function returning `{expected}`"
+                )
+            }
             VmErrorKind::UnsupportedIterRangeInclusive { start, end } => {
                 write!(f, "Cannot build an iterator out of {start}..={end}")
             }
@@ -1063,7 +1292,7 @@ impl fmt::Display for VmErrorKind {
             }
             VmErrorKind::FutureCompleted {} => write!(f, "Future already completed"),
             VmErrorKind::MissingVariant { name } => write!(f, "No variant matching `{name}`"),
-            VmErrorKind::MissingField { target, field } => {
+            VmErrorKind::MissingField { target, field, .. } => {
                 write!(f, "Missing field `{field}` on `{target}`")
             }
             VmErrorKind::MissingVariantName {} => {
@@ -1084,6 +1313,10 @@ impl fmt::Display for VmErrorKind {
                 f,
                 "The object field get operation is not supported on `{target}`",
             ),
+            VmErrorKind::UnsupportedObjectFieldSet { target } => write!(
+                f,
+                "The object field set operation is not supported on `{target}`",
+            ),
             VmErrorKind::IllegalFloatComparison { lhs, rhs } => {
                 write!(
                     f,
@@ -1097,9 +1330,48 @@ impl fmt::Display for VmErrorKind {
             VmErrorKind::MissingCallFrame => {
                 write!(f, "Missing call frame for internal vm call")
             }
+            VmErrorKind::StackOverflow { limit } => {
+                write!(f, "Stack overflow, exceeded the limit of {limit}")
+            }
+            VmErrorKind::CloneDepthExceeded { limit } => {
+                write!(
+                    f,
+                    "Value is nested too deeply to be cloned, exceeded the limit of {limit}"
+                )
+            }
             VmErrorKind::IllegalFormat => {
                 write!(f, "Value cannot be formatted")
             }
+            VmErrorKind::SnapshotUnsupported { slots } => {
+                write!(
+                    f,
+                    "Execution can't be snapshotted since values in the following stack slots can't be converted to constant values:"
+                )?;
+
+                let mut it = slots.iter();
+
+                if let Some((slot, type_info)) = it.next() {
+                    write!(f, " {slot} (`{type_info}`)")?;
+                }
+
+                for (slot, type_info) in it {
+                    write!(f, ", {slot} (`{type_info}`)")?;
+                }
+
+                Ok(())
+            }
+            VmErrorKind::SnapshotNested => {
+                write!(
+                    f,
+                    "Execution can't be snapshotted while it is calling into another unit or context"
+                )
+            }
+            VmErrorKind::SnapshotUnitMismatch => {
+                write!(f, "Snapshot was taken from a different unit")
+            }
+            VmErrorKind::SnapshotCorrupt => {
+                write!(f, "Snapshot is corrupt")
+            }
         }
     }
 }
@@ -1180,6 +1452,15 @@ impl From<DynArgsUsed> for VmErrorKind {
 impl From<alloc::Error> for VmErrorKind {
     #[inline]
     fn from(error: alloc::Error) -> Self {
+        if let alloc::Error::AllocError { error } = &error {
+            if error.is_limited() {
+                return VmErrorKind::AllocationBudgetExceeded {
+                    requested: error.size(),
+                    remaining: alloc::limit::get(),
+                };
+            }
+        }
+
         VmErrorKind::AllocError { error }
     }
 }
@@ -1187,9 +1468,7 @@ impl From<alloc::Error> for VmErrorKind {
 impl From<alloc::alloc::AllocError> for VmErrorKind {
     #[inline]
     fn from(error: alloc::alloc::AllocError) -> Self {
-        VmErrorKind::AllocError {
-            error: error.into(),
-        }
+        VmErrorKind::from(alloc::Error::from(error))
     }
 }
 
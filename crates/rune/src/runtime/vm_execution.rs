@@ -4,11 +4,13 @@ use core::mem::{replace, take};
 
 use ::rust_alloc::sync::Arc;
 
+use serde::{Deserialize, Serialize};
+
 use crate::alloc::prelude::*;
 use crate::runtime::budget;
 use crate::runtime::{
-    Generator, GeneratorState, InstAddress, Output, RuntimeContext, Stream, Unit, Value, Vm,
-    VmErrorKind, VmHalt, VmHaltInfo, VmResult,
+    ExecutionSnapshot, Generator, GeneratorState, InstAddress, Output, RuntimeContext, Stream,
+    Unit, Value, Vm, VmError, VmErrorKind, VmHalt, VmHaltInfo, VmResult,
 };
 use crate::shared::AssertSend;
 
@@ -18,7 +20,7 @@ use super::VmDiagnostics;
 /// correctly interact with functions that yield (like generators and streams)
 /// by initially just calling the function, then by providing a value pushed
 /// onto the stack.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 #[non_exhaustive]
 pub(crate) enum ExecutionState {
     /// The initial state of an execution.
@@ -162,6 +164,73 @@ where
         self.head.as_mut()
     }
 
+    /// Take a snapshot of the current execution, which can later be restored
+    /// with [`VmExecution::restore`].
+    ///
+    /// This is intended to be used on an execution which is suspended, such
+    /// as when a generator has yielded or when it has been interrupted.
+    ///
+    /// Every value on the stack must be convertible into a [`ConstValue`],
+    /// otherwise an error listing the stack slots which couldn't be converted
+    /// is returned.
+    ///
+    /// [`ConstValue`]: crate::runtime::ConstValue
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::Vm;
+    /// use rune::runtime::{ExecutionSnapshot, GeneratorState, VmExecution};
+    /// use std::sync::Arc;
+    ///
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         pub fn main() {
+    ///             let n = 1;
+    ///             yield n;
+    ///             n + 1
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let unit = Arc::new(rune::prepare(&mut sources).build()?);
+    ///
+    /// let mut vm = Vm::without_runtime(unit.clone());
+    /// let mut execution = vm.execute(["main"], ())?;
+    ///
+    /// let GeneratorState::Yielded(value) = execution.resume().into_result()? else {
+    ///     panic!("expected yield");
+    /// };
+    ///
+    /// assert_eq!(rune::from_value::<i64>(value)?, 1);
+    ///
+    /// let snapshot = serde_json::to_string(&execution.snapshot()?)?;
+    /// let snapshot: ExecutionSnapshot = serde_json::from_str(&snapshot)?;
+    ///
+    /// let context = vm.context().clone();
+    /// let mut execution = VmExecution::restore(context, unit, &snapshot)?;
+    ///
+    /// let GeneratorState::Complete(value) = execution.resume().into_result()? else {
+    ///     panic!("expected completion");
+    /// };
+    ///
+    /// assert_eq!(rune::from_value::<i64>(value)?, 2);
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    pub fn snapshot(&self) -> Result<ExecutionSnapshot, VmError> {
+        if !self.states.is_empty() {
+            return Err(VmError::new(VmErrorKind::SnapshotNested));
+        }
+
+        if let ExecutionState::Exited(..) = self.state {
+            return Err(VmError::new(VmErrorKind::ExpectedExecutionState {
+                actual: self.state,
+            }));
+        }
+
+        ExecutionSnapshot::new(self.head.as_ref(), self.state)
+    }
+
     /// Complete the current execution without support for async instructions.
     ///
     /// This will error if the execution is suspended through yielding.
@@ -174,6 +243,104 @@ where
         }
     }
 
+    /// Complete the current execution with support for async instructions,
+    /// halting once the instruction [budget] in effect is exhausted.
+    ///
+    /// This returns `None` if the budget was exhausted, in which case calling
+    /// it again continues execution where it left off. This allows for running
+    /// an execution in slices, doing other work in between them.
+    ///
+    /// This will error if the execution is suspended through yielding.
+    ///
+    /// [budget]: crate::runtime::budget
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::{Context, Unit, Vm};
+    /// use rune::runtime::budget;
+    ///
+    /// use std::sync::Arc;
+    ///
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         pub fn main() {
+    ///             let n = 0;
+    ///
+    ///             for i in 0..100 {
+    ///                 n += i;
+    ///             }
+    ///
+    ///             n
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let context = Context::with_default_modules()?;
+    /// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    /// let mut vm = Vm::new(Arc::new(context.runtime()?), Arc::new(unit));
+    ///
+    /// let mut execution = vm.execute(["main"], ())?;
+    /// let mut slices = 0;
+    ///
+    /// let value = loop {
+    ///     slices += 1;
+    ///     let future = budget::with(100, execution.async_complete_limited());
+    ///
+    ///     if let Some(value) = futures_executor::block_on(future).into_result()? {
+    ///         break value;
+    ///     }
+    /// };
+    ///
+    /// let value: i64 = rune::from_value(value)?;
+    /// assert_eq!(value, 4950);
+    /// assert!(slices > 1);
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    pub async fn async_complete_limited(&mut self) -> VmResult<Option<Value>> {
+        let result = self.run_async_complete_limited().await;
+        self.check(result)
+    }
+
+    async fn run_async_complete_limited(&mut self) -> VmResult<Option<Value>> {
+        if let ExecutionState::Resumed(out) = self.state {
+            vm_try!(out.store(self.head.as_mut().stack_mut(), Value::unit));
+        }
+
+        loop {
+            let vm = self.head.as_mut();
+
+            match vm_try!(vm.run(None).with_vm(vm)) {
+                VmHalt::Exited(addr) => {
+                    self.state = ExecutionState::Exited(addr);
+                }
+                VmHalt::Awaited(awaited) => {
+                    vm_try!(awaited.into_vm(vm).await);
+                    continue;
+                }
+                VmHalt::VmCall(vm_call) => {
+                    vm_try!(vm_call.into_execution(self));
+                    continue;
+                }
+                VmHalt::Limited => {
+                    return VmResult::Ok(None);
+                }
+                halt => {
+                    return VmResult::err(VmErrorKind::Halted {
+                        halt: halt.into_info(),
+                    })
+                }
+            }
+
+            if self.states.is_empty() {
+                let value = vm_try!(self.end());
+                return VmResult::Ok(Some(value));
+            }
+
+            vm_try!(self.pop_state());
+        }
+    }
+
     /// Complete the current execution without support for async instructions.
     ///
     /// If any async instructions are encountered, this will error. This will
@@ -235,6 +402,14 @@ where
     }
 
     async fn inner_async_resume(
+        &mut self,
+        diagnostics: Option<&mut dyn VmDiagnostics>,
+    ) -> VmResult<GeneratorState> {
+        let result = self.run_async_resume(diagnostics).await;
+        self.check(result)
+    }
+
+    async fn run_async_resume(
         &mut self,
         mut diagnostics: Option<&mut dyn VmDiagnostics>,
     ) -> VmResult<GeneratorState> {
@@ -327,6 +502,14 @@ where
     }
 
     fn inner_resume(
+        &mut self,
+        diagnostics: Option<&mut dyn VmDiagnostics>,
+    ) -> VmResult<GeneratorState> {
+        let result = self.run_resume(diagnostics);
+        self.check(result)
+    }
+
+    fn run_resume(
         &mut self,
         mut diagnostics: Option<&mut dyn VmDiagnostics>,
     ) -> VmResult<GeneratorState> {
@@ -378,6 +561,11 @@ where
     ///
     /// If any async instructions are encountered, this will error.
     pub fn step(&mut self) -> VmResult<Option<Value>> {
+        let result = self.run_step();
+        self.check(result)
+    }
+
+    fn run_step(&mut self) -> VmResult<Option<Value>> {
         let len = self.states.len();
         let vm = self.head.as_mut();
 
@@ -389,7 +577,9 @@ where
                 vm_try!(vm_call.into_execution(self));
                 return VmResult::Ok(None);
             }
-            VmHalt::Limited => return VmResult::Ok(None),
+            VmHalt::Limited | VmHalt::Interrupted | VmHalt::Breakpoint => {
+                return VmResult::Ok(None)
+            }
             halt => {
                 return VmResult::err(VmErrorKind::Halted {
                     halt: halt.into_info(),
@@ -409,6 +599,11 @@ where
     /// Step the single execution for one step with support for async
     /// instructions.
     pub async fn async_step(&mut self) -> VmResult<Option<Value>> {
+        let result = self.run_async_step().await;
+        self.check(result)
+    }
+
+    async fn run_async_step(&mut self) -> VmResult<Option<Value>> {
         let vm = self.head.as_mut();
 
         match vm_try!(budget::with(1, || vm.run(None).with_vm(vm)).call()) {
@@ -423,7 +618,9 @@ where
                 vm_try!(vm_call.into_execution(self));
                 return VmResult::Ok(None);
             }
-            VmHalt::Limited => return VmResult::Ok(None),
+            VmHalt::Limited | VmHalt::Interrupted | VmHalt::Breakpoint => {
+                return VmResult::Ok(None)
+            }
             halt => {
                 return VmResult::err(VmErrorKind::Halted {
                     halt: halt.into_info(),
@@ -440,6 +637,19 @@ where
         VmResult::Ok(None)
     }
 
+    /// Mark the execution as completed if it failed with an error which it
+    /// can't be resumed from, so that the virtual machine is no longer
+    /// considered to be executing.
+    fn check<V>(&mut self, result: VmResult<V>) -> VmResult<V> {
+        if let VmResult::Err(error) = &result {
+            if !error.is_resumable() {
+                self.head.as_mut().complete_execution();
+            }
+        }
+
+        result
+    }
+
     /// End execution and perform debug checks.
     pub(crate) fn end(&mut self) -> VmResult<Value> {
         let ExecutionState::Exited(addr) = self.state else {
@@ -452,6 +662,7 @@ where
         };
 
         debug_assert!(self.states.is_empty(), "Execution states should be empty");
+        self.head.as_mut().complete_execution();
         VmResult::Ok(value)
     }
 
@@ -491,7 +702,11 @@ impl VmExecution<&mut Vm> {
     /// Convert the current execution into one which owns its virtual machine.
     pub fn into_owned(self) -> VmExecution<Vm> {
         let stack = take(self.head.stack_mut());
-        let head = Vm::with_stack(self.head.context().clone(), self.head.unit().clone(), stack);
+        let head = Vm::with_stack(self.head.context().clone(), self.head.unit().clone(), stack)
+            .with_limits(self.head.limits())
+            .with_data(self.head.vm_data().clone())
+            .with_interrupt_hook(self.head.interrupt_hook())
+            .with_tracer_hook(self.head.tracer_hook());
 
         VmExecution {
             head,
@@ -501,6 +716,28 @@ impl VmExecution<&mut Vm> {
     }
 }
 
+impl VmExecution<Vm> {
+    /// Restore an execution from a snapshot taken with
+    /// [`VmExecution::snapshot`].
+    ///
+    /// The `unit` must be the same as the one which the snapshot was taken
+    /// from, and the `context` is used to reconstruct any values which require
+    /// it.
+    pub fn restore(
+        context: Arc<RuntimeContext>,
+        unit: Arc<Unit>,
+        snapshot: &ExecutionSnapshot,
+    ) -> Result<Self, VmError> {
+        let (head, state) = snapshot.restore(context, unit)?;
+
+        Ok(Self {
+            head,
+            state,
+            states: Vec::new(),
+        })
+    }
+}
+
 /// A wrapper that makes [`VmExecution`] [`Send`].
 ///
 /// This is accomplished by preventing any [`Value`] from escaping the [`Vm`].
@@ -515,6 +752,8 @@ pub struct VmSendExecution(pub(crate) VmExecution<Vm>);
 // escaping from contained virtual machine.
 unsafe impl Send for VmSendExecution {}
 
+assert_impl!(VmSendExecution: Send);
+
 impl VmSendExecution {
     /// Complete the current execution with support for async instructions.
     ///
@@ -9,6 +9,10 @@ pub(crate) enum VmHalt {
     Exited(Option<InstAddress>),
     /// The virtual machine exited because it ran out of execution quota.
     Limited,
+    /// The virtual machine was interrupted by its interrupt hook.
+    Interrupted,
+    /// The virtual machine was paused by its debugger.
+    Breakpoint,
     /// The virtual machine yielded.
     Yielded(Option<InstAddress>, Output),
     /// The virtual machine awaited on the given future.
@@ -23,6 +27,8 @@ impl VmHalt {
         match self {
             Self::Exited(..) => VmHaltInfo::Exited,
             Self::Limited => VmHaltInfo::Limited,
+            Self::Interrupted => VmHaltInfo::Interrupted,
+            Self::Breakpoint => VmHaltInfo::Breakpoint,
             Self::Yielded(..) => VmHaltInfo::Yielded,
             Self::Awaited(..) => VmHaltInfo::Awaited,
             Self::VmCall(..) => VmHaltInfo::VmCall,
@@ -37,6 +43,10 @@ pub(crate) enum VmHaltInfo {
     Exited,
     /// The virtual machine exited because it ran out of execution quota.
     Limited,
+    /// The virtual machine was interrupted by its interrupt hook.
+    Interrupted,
+    /// The virtual machine was paused by its debugger.
+    Breakpoint,
     /// The virtual machine yielded.
     Yielded,
     /// The virtual machine awaited on the given future.
@@ -50,6 +60,8 @@ impl fmt::Display for VmHaltInfo {
         match self {
             Self::Exited => write!(f, "exited"),
             Self::Limited => write!(f, "limited"),
+            Self::Interrupted => write!(f, "interrupted"),
+            Self::Breakpoint => write!(f, "breakpoint"),
             Self::Yielded => write!(f, "yielded"),
             Self::Awaited => write!(f, "awaited"),
             Self::VmCall => write!(f, "calling into other vm"),
@@ -0,0 +1,156 @@
+use crate::runtime::{Inst, Stack};
+
+/// A sink for instruction-level traces, which can be installed in a virtual
+/// machine using [`Vm::with_tracer`].
+///
+/// A tracer is shared with the virtual machines which are nested inside of
+/// the one it is installed in, so it is called through a shared reference.
+///
+/// [`Vm::with_tracer`]: crate::Vm::with_tracer
+pub trait Tracer: Send + Sync {
+    /// Called before the instruction `inst` at `ip` is executed, with the
+    /// current state of the stack.
+    ///
+    /// Since this is called for every executed instruction, it should be
+    /// cheap.
+    fn trace(&self, ip: usize, inst: &Inst, stack: &Stack);
+}
+
+#[cfg(feature = "std")]
+pub use self::collecting::{CollectingTracer, TraceEntry};
+
+#[cfg(feature = "std")]
+mod collecting {
+    use std::collections::VecDeque;
+    use std::io;
+    use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+    use std::vec::Vec;
+
+    use super::Tracer;
+    use crate::runtime::{Inst, Stack};
+
+    /// An instruction recorded by a [`CollectingTracer`].
+    #[derive(Debug, Clone, Copy)]
+    #[non_exhaustive]
+    pub struct TraceEntry {
+        /// The instruction pointer of the instruction.
+        pub ip: usize,
+        /// The instruction which was executed.
+        pub inst: Inst,
+    }
+
+    #[derive(Debug)]
+    struct Inner {
+        capacity: usize,
+        entries: VecDeque<TraceEntry>,
+    }
+
+    /// A [`Tracer`] which records the last executed instructions in a ring
+    /// buffer.
+    ///
+    /// Cloning a [`CollectingTracer`] produces a handle to the same buffer,
+    /// so a clone can be installed in a virtual machine while the original is
+    /// kept around to inspect what was executed, for example when an error
+    /// occurs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use rune::runtime::CollectingTracer;
+    /// use rune::{Context, Vm};
+    ///
+    /// let context = Context::with_default_modules()?;
+    /// let runtime = Arc::new(context.runtime()?);
+    ///
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         pub fn main() {
+    ///             let a = 1;
+    ///             a / 0
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    ///
+    /// let tracer = CollectingTracer::new(32);
+    /// let mut vm = Vm::new(runtime, Arc::new(unit)).with_tracer(tracer.clone());
+    ///
+    /// assert!(vm.call(["main"], ()).is_err());
+    ///
+    /// let entries = tracer.entries();
+    /// let last = entries.last().expect("missing instruction");
+    /// assert!(last.inst.to_string().contains('/'));
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    #[derive(Debug, Clone)]
+    pub struct CollectingTracer {
+        inner: Arc<Mutex<Inner>>,
+    }
+
+    impl CollectingTracer {
+        /// Construct a new tracer which records the last `capacity`
+        /// instructions.
+        pub fn new(capacity: usize) -> Self {
+            Self {
+                inner: Arc::new(Mutex::new(Inner {
+                    capacity,
+                    entries: VecDeque::new(),
+                })),
+            }
+        }
+
+        /// Get the recorded instructions, from the oldest to the most recently
+        /// executed one.
+        pub fn entries(&self) -> Vec<TraceEntry> {
+            self.lock().entries.iter().copied().collect()
+        }
+
+        /// Clear all recorded instructions.
+        pub fn clear(&self) {
+            self.lock().entries.clear();
+        }
+
+        /// Render the recorded instructions to the given output.
+        pub fn render<O>(&self, mut out: O) -> io::Result<()>
+        where
+            O: io::Write,
+        {
+            let inner = self.lock();
+
+            writeln!(
+                out,
+                "# last {} instructions before the error",
+                inner.entries.len()
+            )?;
+
+            for entry in &inner.entries {
+                writeln!(out, "  {:04} = {}", entry.ip, entry.inst)?;
+            }
+
+            Ok(())
+        }
+
+        fn lock(&self) -> MutexGuard<'_, Inner> {
+            self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+        }
+    }
+
+    impl Tracer for CollectingTracer {
+        fn trace(&self, ip: usize, inst: &Inst, _: &Stack) {
+            let mut inner = self.lock();
+
+            if inner.capacity == 0 {
+                return;
+            }
+
+            if inner.entries.len() == inner.capacity {
+                inner.entries.pop_front();
+            }
+
+            inner.entries.push_back(TraceEntry { ip, inst: *inst });
+        }
+    }
+}
@@ -21,9 +21,7 @@ use crate::alloc::borrow::Cow;
 use crate::alloc::path::Path;
 use crate::alloc::prelude::*;
 use crate::alloc::{self, Box};
-
-#[cfg(feature = "emit")]
-use crate::ast::Span;
+use crate::Hash;
 
 /// Error raised when constructing a source.
 #[derive(Debug)]
@@ -146,6 +144,11 @@ impl Source {
 
     /// Construct a new source with the given content and path.
     ///
+    /// This is useful for sources which are loaded from memory but which still
+    /// correspond to a file, since diagnostics refer to a source by its path
+    /// when it has one while [`Source::name`] keeps returning the given name.
+    /// The path is also used to resolve relative `mod` declarations.
+    ///
     /// # Examples
     ///
     /// ```
@@ -176,7 +179,7 @@ impl Source {
     }
 
     /// Access all line starts in the source.
-    #[cfg(feature = "emit")]
+    #[cfg(any(feature = "emit", feature = "debug-access"))]
     pub(crate) fn line_starts(&self) -> &[usize] {
         &self.line_starts
     }
@@ -219,6 +222,28 @@ impl Source {
         self.path.as_deref()
     }
 
+    /// Get a hash of the content of the source.
+    ///
+    /// Sources with the same content have the same hash regardless of their
+    /// name or path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::Source;
+    ///
+    /// let a = Source::new("a", "pub fn main() { 42 }")?;
+    /// let b = Source::memory("pub fn main() { 42 }")?;
+    /// let c = Source::new("a", "pub fn main() { 43 }")?;
+    ///
+    /// assert_eq!(a.content_hash(), b.content_hash());
+    /// assert_ne!(a.content_hash(), c.content_hash());
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    pub fn content_hash(&self) -> Hash {
+        Hash::static_bytes(self.source.as_bytes())
+    }
+
     /// Convert the given offset to a utf-16 line and character.
     #[cfg(feature = "languageserver")]
     pub(crate) fn pos_to_utf16cu_linecol(&self, offset: usize) -> (usize, usize) {
@@ -261,24 +286,6 @@ impl Source {
         self.line_starts.len()
     }
 
-    /// Access the line number of content that starts with the given span.
-    #[cfg(feature = "emit")]
-    pub(crate) fn line(&self, span: Span) -> Option<(usize, usize, [&str; 3])> {
-        let from = span.range();
-        let (lin, col) = self.pos_to_utf8_linecol(from.start);
-        let line = self.line_range(lin)?;
-
-        let start = from.start.checked_sub(line.start)?;
-        let end = from.end.checked_sub(line.start)?;
-
-        let text = self.source.get(line)?;
-        let prefix = text.get(..start)?;
-        let mid = text.get(start..end)?;
-        let suffix = text.get(end..)?;
-
-        Some((lin, col, [prefix, mid, suffix]))
-    }
-
     fn position(&self, offset: usize) -> (usize, usize, &str) {
         if offset == 0 {
             return Default::default();
@@ -5,9 +5,12 @@ use crate as rune;
 use crate::alloc;
 use crate::alloc::path::Path;
 use crate::alloc::prelude::*;
+use crate::alloc::{Box, HashMap};
 use crate::ast::Span;
 use crate::source::Source;
 #[cfg(feature = "codespan-reporting")]
+use ::rust_alloc::borrow::Cow;
+#[cfg(feature = "codespan-reporting")]
 use codespan_reporting::files;
 
 /// Helper macro to define a collection of sources populatedc with the given
@@ -41,6 +44,8 @@ macro_rules! sources {
 pub struct Sources {
     /// Sources associated.
     sources: Vec<Source>,
+    /// Sources which are mounted under a crate, by the name of the crate.
+    crates: HashMap<SourceId, Box<str>>,
 }
 
 impl Sources {
@@ -48,6 +53,7 @@ impl Sources {
     pub fn new() -> Self {
         Self {
             sources: Vec::new(),
+            crates: HashMap::new(),
         }
     }
 
@@ -71,6 +77,40 @@ impl Sources {
         Ok(id)
     }
 
+    /// Insert a source which is mounted under the crate `name` and return its
+    /// [`SourceId`].
+    ///
+    /// Public items in the source can be imported by other sources through
+    /// `use name::item`, as if they were provided by a crate in the context.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use rune::{Context, Source, Sources, Vm};
+    ///
+    /// let context = Context::with_default_modules()?;
+    /// let runtime = Arc::new(context.runtime()?);
+    ///
+    /// let mut sources = Sources::new();
+    /// sources.insert_crate("greeting", Source::memory("pub fn hello() { \"Hello\" }")?)?;
+    /// sources.insert(Source::memory("use greeting::hello; pub fn main() { hello() }")?)?;
+    ///
+    /// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    /// let mut vm = Vm::new(runtime, Arc::new(unit));
+    ///
+    /// let output: String = rune::from_value(vm.call(["main"], ())?)?;
+    /// assert_eq!(output, "Hello");
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    pub fn insert_crate(&mut self, name: &str, source: Source) -> alloc::Result<SourceId> {
+        let name = Box::try_from(name)?;
+        let id = self.insert(source)?;
+        self.crates.try_insert(id, name)?;
+        Ok(id)
+    }
+
     /// Get the source matching the given source id.
     ///
     /// # Examples
@@ -91,6 +131,69 @@ impl Sources {
         self.sources.get(id.into_index())
     }
 
+    /// Replace the source with the given id, returning the previous source.
+    ///
+    /// Returns `None` and leaves the collection unchanged if there is no
+    /// source with the given id.
+    ///
+    /// Together with a [`BuildCache`], this allows for rebuilding after a
+    /// single source has changed, where the unchanged sources reuse the work
+    /// done by the previous build.
+    ///
+    /// [`BuildCache`]: crate::compile::BuildCache
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::{Sources, Source};
+    ///
+    /// let mut sources = Sources::new();
+    /// let id = sources.insert(Source::new("a", "pub fn main() { 10 }")?)?;
+    ///
+    /// let old = sources.replace(id, Source::new("b", "pub fn main() { 20 }")?);
+    /// assert_eq!(old.map(|s| s.name().to_owned()).as_deref(), Some("a"));
+    /// assert_eq!(sources.get(id).map(|s| s.name()), Some("b"));
+    /// # Ok::<_, rune::support::Error>(())
+    /// ```
+    pub fn replace(&mut self, id: SourceId, source: Source) -> Option<Source> {
+        let existing = self.sources.get_mut(id.into_index())?;
+        Some(core::mem::replace(existing, source))
+    }
+
+    cfg_std! {
+        /// Get the source which was loaded from the given path, if any.
+        ///
+        /// This is useful when rebuilding incrementally, where a change to a
+        /// file needs to be mapped back to the source it corresponds to.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use std::path::Path;
+        /// use rune::{Sources, Source};
+        ///
+        /// let mut sources = Sources::new();
+        /// let id = sources.insert(Source::with_path("a", "pub fn main() { 10 }", "a.rn")?)?;
+        ///
+        /// let (found, source) = sources.get_by_path(Path::new("a.rn")).expect("expected source");
+        /// assert_eq!(found, id);
+        /// assert_eq!(source.name(), "a");
+        /// assert!(sources.get_by_path(Path::new("b.rn")).is_none());
+        /// # Ok::<_, rune::support::Error>(())
+        /// ```
+        pub fn get_by_path(&self, path: &Path) -> Option<(SourceId, &Source)> {
+            self.source_ids()
+                .zip(&self.sources)
+                .find(|(_, source)| source.path() == Some(path))
+        }
+    }
+
+    /// Get the name of the crate the given source is mounted under, if it was
+    /// inserted with [`Sources::insert_crate`].
+    pub(crate) fn crate_name(&self, id: SourceId) -> Option<&str> {
+        Some(self.crates.get(&id)?.as_ref())
+    }
+
     /// Fetch name for the given source id.
     pub(crate) fn name(&self, id: SourceId) -> Option<&str> {
         let source = self.sources.get(id.into_index())?;
@@ -124,12 +227,18 @@ impl Sources {
 #[cfg(feature = "codespan-reporting")]
 impl<'a> files::Files<'a> for Sources {
     type FileId = SourceId;
-    type Name = &'a str;
+    type Name = Cow<'a, str>;
     type Source = &'a str;
 
+    /// Diagnostics prefer to refer to a source by its path when it has one,
+    /// since that is what lets users find the file in question.
     fn name(&'a self, file_id: SourceId) -> Result<Self::Name, files::Error> {
         let source = self.get(file_id).ok_or(files::Error::FileMissing)?;
-        Ok(source.name())
+
+        Ok(match source.path() {
+            Some(path) => path.to_string_lossy(),
+            None => Cow::Borrowed(source.name()),
+        })
     }
 
     fn source(&'a self, file_id: SourceId) -> Result<Self::Source, files::Error> {
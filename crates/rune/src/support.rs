@@ -95,6 +95,14 @@ pub(crate) mod no_std {
         }
     }
 
+    impl From<runtime::SerdeError> for Error {
+        fn from(error: runtime::SerdeError) -> Self {
+            Self {
+                kind: ErrorKind::Serde(error),
+            }
+        }
+    }
+
     impl From<anyhow::Error> for Error {
         fn from(error: anyhow::Error) -> Self {
             Self {
@@ -121,6 +129,7 @@ pub(crate) mod no_std {
                 ErrorKind::Build(error) => error.fmt(f),
                 ErrorKind::Runtime(error) => error.fmt(f),
                 ErrorKind::Vm(error) => error.fmt(f),
+                ErrorKind::Serde(error) => error.fmt(f),
                 ErrorKind::Custom(error) => error.fmt(f),
                 #[cfg(test)]
                 ErrorKind::Test(error) => error.fmt(f),
@@ -136,6 +145,7 @@ pub(crate) mod no_std {
         Build(build::BuildError),
         Vm(runtime::VmError),
         Runtime(runtime::RuntimeError),
+        Serde(runtime::SerdeError),
         Custom(anyhow::Error),
         #[cfg(test)]
         Test(tests::TestError),
@@ -150,6 +160,7 @@ pub(crate) mod no_std {
                 ErrorKind::Build(error) => Some(error),
                 ErrorKind::Vm(error) => Some(error),
                 ErrorKind::Runtime(error) => Some(error),
+                ErrorKind::Serde(error) => Some(error),
                 ErrorKind::Custom(error) => Some(error.as_ref()),
                 #[cfg(test)]
                 ErrorKind::Test(error) => Some(error),
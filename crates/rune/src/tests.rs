@@ -92,7 +92,16 @@ impl core::error::Error for TestError {
 #[doc(hidden)]
 pub fn compile_helper(source: &str, diagnostics: &mut Diagnostics) -> Result<Unit, BuildError> {
     let context = crate::Context::with_default_modules().expect("setting up default modules");
+    compile_helper_with_context(&context, source, diagnostics)
+}
 
+/// Compile the given source as a script using the given context.
+#[doc(hidden)]
+pub fn compile_helper_with_context(
+    context: &Context,
+    source: &str,
+    diagnostics: &mut Diagnostics,
+) -> Result<Unit, BuildError> {
     let mut sources = Sources::new();
     sources.insert(Source::new("main", source)?)?;
 
@@ -100,7 +109,7 @@ pub fn compile_helper(source: &str, diagnostics: &mut Diagnostics) -> Result<Uni
     options.script(true);
 
     let unit = crate::prepare(&mut sources)
-        .with_context(&context)
+        .with_context(context)
         .with_diagnostics(diagnostics)
         .with_options(&options)
         .build()?;
@@ -359,9 +368,8 @@ macro_rules! assert_errors {
 /// Assert that the given rune program parses, but raises the specified set of
 /// warnings.
 macro_rules! assert_warnings {
-    ($source:expr, $span:pat $(, $pat:pat $(=> $cond:expr)?)* $(,)?) => {{
-        let mut diagnostics = Default::default();
-        let _ = $crate::tests::compile_helper($source, &mut diagnostics).expect("source should compile");
+    (@check $diagnostics:ident, $span:pat $(, $pat:pat $(=> $cond:expr)?)*) => {{
+        let diagnostics: rune::Diagnostics = $diagnostics;
         assert!(diagnostics.has_warning(), "no warnings produced");
 
         let mut it = diagnostics.into_diagnostics().into_iter();
@@ -393,6 +401,18 @@ macro_rules! assert_warnings {
 
         assert!(it.next().is_none(), "there should be no more warnings");
     }};
+
+    (context = $context:expr, $source:expr, $span:pat $(, $pat:pat $(=> $cond:expr)?)* $(,)?) => {{
+        let mut diagnostics = Default::default();
+        let _ = $crate::tests::compile_helper_with_context($context, $source, &mut diagnostics).expect("source should compile");
+        assert_warnings!(@check diagnostics, $span $(, $pat $(=> $cond)?)*);
+    }};
+
+    ($source:expr, $span:pat $(, $pat:pat $(=> $cond:expr)?)* $(,)?) => {{
+        let mut diagnostics = Default::default();
+        let _ = $crate::tests::compile_helper($source, &mut diagnostics).expect("source should compile");
+        assert_warnings!(@check diagnostics, $span $(, $pat $(=> $cond)?)*);
+    }};
 }
 
 /// Assert that the given value matches the provided pattern.
@@ -412,11 +432,19 @@ macro_rules! prelude {
     };
 }
 
+#[cfg(not(miri))]
+mod associated_constants;
 #[cfg(not(miri))]
 mod attribute;
 #[cfg(not(miri))]
+mod attribute_macros;
+#[cfg(not(miri))]
+mod await_outside_async;
+#[cfg(not(miri))]
 mod binary;
 #[cfg(not(miri))]
+mod budget;
+#[cfg(not(miri))]
 mod bug_326;
 #[cfg(not(miri))]
 mod bug_344;
@@ -433,6 +461,10 @@ mod bug_700;
 #[cfg(not(miri))]
 mod bugfixes;
 #[cfg(not(miri))]
+mod build_cache;
+#[cfg(not(miri))]
+mod build_cancellation;
+#[cfg(not(miri))]
 mod builtin_macros;
 #[cfg(not(miri))]
 mod capture;
@@ -459,38 +491,84 @@ mod compiler_visibility;
 #[cfg(not(miri))]
 mod compiler_warnings;
 #[cfg(not(miri))]
+mod conflicting_modules;
+#[cfg(not(miri))]
+mod const_native_fn;
+#[cfg(not(miri))]
+mod constant_folding;
+#[cfg(not(miri))]
 mod continue_;
 #[cfg(not(miri))]
 mod core_macros;
 #[cfg(not(miri))]
 mod custom_macros;
 #[cfg(not(miri))]
+mod dead_code_elimination;
+#[cfg(all(not(miri), feature = "debug-access", feature = "emit"))]
+mod debug_access;
+#[cfg(not(miri))]
 mod debug_fmt;
 #[cfg(not(miri))]
+mod declarative_macros;
+#[cfg(not(miri))]
+mod deny_warnings;
+#[cfg(not(miri))]
 mod deprecation;
 #[cfg(not(miri))]
 mod destructuring;
+#[cfg(all(not(miri), feature = "emit-json"))]
+mod emit_json;
 #[cfg(not(miri))]
 mod esoteric_impls;
 #[cfg(not(miri))]
+mod exhaustive_match;
+#[cfg(not(miri))]
 mod external_constructor;
 #[cfg(not(miri))]
 mod external_generic;
 #[cfg(not(miri))]
+mod external_generic_named;
+#[cfg(not(miri))]
+mod external_iter;
+#[cfg(not(miri))]
 mod external_match;
 #[cfg(not(miri))]
 mod external_ops;
+#[cfg(not(miri))]
+mod external_stream;
+#[cfg(not(miri))]
+mod format_spec;
 mod function_guardedargs;
 #[cfg(not(miri))]
 mod getter_setter;
 #[cfg(not(miri))]
+mod immutable;
+#[cfg(not(miri))]
+mod inline_cache;
+#[cfg(not(miri))]
+mod inline_ops;
+#[cfg(not(miri))]
 mod iterator;
 #[cfg(not(miri))]
+mod known_modules;
+#[cfg(not(miri))]
+mod labeled_loops;
+#[cfg(not(miri))]
+mod let_chains;
+#[cfg(not(miri))]
+mod lint_attributes;
+#[cfg(not(miri))]
+mod macro_diagnostics;
+#[cfg(not(miri))]
 mod macros;
 #[cfg(not(miri))]
 mod moved;
 #[cfg(not(miri))]
+mod object_keys;
+#[cfg(not(miri))]
 mod option;
+#[cfg(all(not(miri), feature = "parallel"))]
+mod parallel;
 #[cfg(not(miri))]
 mod patterns;
 #[cfg(not(miri))]
@@ -498,34 +576,62 @@ mod quote;
 #[cfg(not(miri))]
 mod range;
 #[cfg(not(miri))]
+mod reexport;
+#[cfg(not(miri))]
 mod reference_error;
 #[cfg(not(miri))]
 mod rename_type;
 #[cfg(not(miri))]
+mod replace_module;
+#[cfg(not(miri))]
+mod rest_patterns;
+#[cfg(not(miri))]
 mod result;
 #[cfg(not(miri))]
+mod script_protocols;
+#[cfg(not(miri))]
+mod select;
+#[cfg(not(miri))]
+mod similar_names;
+#[cfg(not(miri))]
+mod source_path;
+#[cfg(not(miri))]
 mod static_typing;
 #[cfg(not(miri))]
 mod tuple;
 #[cfg(not(miri))]
 mod type_name_native;
 #[cfg(not(miri))]
+mod type_serialization;
+#[cfg(not(miri))]
 mod unit_constants;
+#[cfg(all(not(miri), feature = "unit-file"))]
+mod unit_file;
 #[cfg(not(miri))]
 mod unreachable;
 #[cfg(not(miri))]
+mod unused_variables;
+#[cfg(not(miri))]
 mod vm_arithmetic;
 #[cfg(not(miri))]
 mod vm_assign_exprs;
 #[cfg(not(miri))]
 mod vm_async_block;
 #[cfg(not(miri))]
+mod vm_backtrace;
+#[cfg(not(miri))]
 mod vm_blocks;
 #[cfg(not(miri))]
+mod vm_call_emit;
+#[cfg(not(miri))]
 mod vm_closures;
 #[cfg(not(miri))]
 mod vm_const_exprs;
 #[cfg(not(miri))]
+mod vm_data;
+#[cfg(not(miri))]
+mod vm_debugger;
+#[cfg(not(miri))]
 mod vm_early_termination;
 #[cfg(not(miri))]
 mod vm_function;
@@ -534,12 +640,22 @@ mod vm_function_pointers;
 #[cfg(not(miri))]
 mod vm_general;
 #[cfg(not(miri))]
+mod vm_interrupt;
+#[cfg(not(miri))]
+mod vm_limits;
+#[cfg(not(miri))]
 mod vm_literals;
 #[cfg(not(miri))]
 mod vm_not_used;
 #[cfg(not(miri))]
 mod vm_result;
 #[cfg(not(miri))]
+mod vm_snapshot;
+#[cfg(not(miri))]
+mod vm_swap_unit;
+#[cfg(not(miri))]
+mod vm_sync_function;
+#[cfg(not(miri))]
 mod vm_test_from_value_derive;
 #[cfg(not(miri))]
 mod vm_test_imports;
@@ -550,6 +666,14 @@ mod vm_test_linked_list;
 #[cfg(not(miri))]
 mod vm_test_mod;
 #[cfg(not(miri))]
+mod vm_tracer;
+#[cfg(not(miri))]
 mod vm_try;
 #[cfg(not(miri))]
+mod vm_typed_function;
+#[cfg(not(miri))]
+mod vm_with_ref;
+#[cfg(not(miri))]
 mod wildcard_imports;
+#[cfg(all(not(miri), feature = "workspace"))]
+mod workspace;
@@ -0,0 +1,98 @@
+prelude!();
+
+use crate::runtime::Inst;
+use crate::ToConstValue;
+
+#[derive(Any)]
+struct Thing;
+
+#[derive(Debug, Any, ToConstValue)]
+struct Limits {
+    #[rune(get)]
+    min: i64,
+    #[rune(get)]
+    max: i64,
+}
+
+fn context() -> Result<Context> {
+    let mut m = Module::new();
+    m.ty::<Thing>()?;
+    m.ty::<Limits>()?;
+    m.constant("MAX", 10i64).build_associated::<Thing>()?;
+    m.constant("PAIR", (1i64, 2i64))
+        .build_associated::<Thing>()?;
+    m.constant("NAME", "thing").build_associated::<Thing>()?;
+    m.constant("LIMITS", Limits { min: -5, max: 5 })
+        .build_associated::<Thing>()?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(m)?;
+    Ok(context)
+}
+
+#[test]
+fn test_associated_constant() -> Result<()> {
+    let context = context()?;
+
+    let value: i64 = run(&context, "pub fn main() { Thing::MAX }", (), false)?;
+    assert_eq!(value, 10);
+
+    let value: i64 = run(&context, "pub fn main() { Thing::PAIR.1 }", (), false)?;
+    assert_eq!(value, 2);
+
+    let value: String = run(&context, "pub fn main() { Thing::NAME }", (), false)?;
+    assert_eq!(value, "thing");
+
+    let value: i64 = run(
+        &context,
+        "pub fn main() { Thing::LIMITS.max - Thing::LIMITS.min }",
+        (),
+        false,
+    )?;
+    assert_eq!(value, 10);
+    Ok(())
+}
+
+#[test]
+fn test_associated_constant_in_const() -> Result<()> {
+    let context = context()?;
+
+    let value: i64 = run(
+        &context,
+        "const X = Thing::MAX * 2; pub fn main() { X }",
+        (),
+        false,
+    )?;
+    assert_eq!(value, 20);
+
+    let value: (i64, String) = run(
+        &context,
+        "const X = (Thing::PAIR.0 + Thing::PAIR.1, Thing::NAME); pub fn main() { X }",
+        (),
+        false,
+    )?;
+    assert_eq!(value, (3, String::from("thing")));
+    Ok(())
+}
+
+#[test]
+fn test_associated_constant_folding() -> Result<()> {
+    let context = context()?;
+
+    let mut sources = crate::tests::sources("const X = Thing::MAX * 2; pub fn main() { X }");
+    let unit = prepare(&mut sources).with_context(&context).build()?;
+
+    let folded = unit
+        .constant(&Hash::type_hash(["X"]))
+        .expect("constant should be folded into the unit");
+    assert_eq!(folded.as_integer::<i64>()?, 20);
+
+    for (_, inst) in unit.iter_instructions() {
+        assert!(
+            !matches!(inst, Inst::Op { .. } | Inst::Arithmetic { .. }),
+            "constant expression should not be evaluated at runtime: {inst:?}"
+        );
+    }
+
+    Ok(())
+}
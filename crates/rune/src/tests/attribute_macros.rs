@@ -0,0 +1,199 @@
+prelude!();
+
+use macros::{quote, MacroContext, ToTokens, TokenStream};
+use parse::Parser;
+
+/// Derive a `to_object` method for a struct with named fields, which goes
+/// through a uniquely named helper function in the enclosing module.
+#[rune::attribute_macro]
+fn to_object(
+    cx: &mut MacroContext<'_, '_, '_>,
+    _: &TokenStream,
+    item: &TokenStream,
+) -> compile::Result<TokenStream> {
+    let mut p = Parser::from_token_stream(item, cx.macro_span());
+    let item = p.parse_all::<ast::ItemStruct>()?;
+
+    let ast::Fields::Named(fields) = &item.body else {
+        return Err(compile::Error::msg(
+            &item,
+            "expected a struct with named fields",
+        ));
+    };
+
+    let name = item.ident;
+    let helper = cx.unique_ident("to_object")?;
+
+    let mut entries = TokenStream::new();
+
+    for (field, _) in fields {
+        let field = &field.name;
+        quote!(#field: this. #field,).to_tokens(cx, &mut entries)?;
+    }
+
+    Ok(quote! {
+        #item
+
+        fn #helper(this) {
+            #{ #entries }
+        }
+
+        impl #name {
+            fn to_object(self) {
+                #helper(self)
+            }
+        }
+    }
+    .into_token_stream(cx)?)
+}
+
+/// Add a `name_of_type` function to the enclosing module which returns the
+/// name of the struct or enum it's attached to.
+#[rune::attribute_macro]
+fn type_name(
+    cx: &mut MacroContext<'_, '_, '_>,
+    _: &TokenStream,
+    item: &TokenStream,
+) -> compile::Result<TokenStream> {
+    let mut p = Parser::from_token_stream(item, cx.macro_span());
+    let item = p.parse_all::<ast::Item>()?;
+
+    let name = match &item {
+        ast::Item::Struct(item) => item.ident,
+        ast::Item::Enum(item) => item.name,
+        _ => return Err(compile::Error::msg(&item, "expected a struct or an enum")),
+    };
+
+    let name = cx.lit(cx.resolve(name)?.try_to_owned()?)?;
+
+    Ok(quote! {
+        #item
+
+        fn name_of_type() {
+            #name
+        }
+    }
+    .into_token_stream(cx)?)
+}
+
+fn context() -> Result<Context> {
+    let mut m = Module::with_crate("test")?;
+    m.macro_meta(to_object)?;
+    m.macro_meta(type_name)?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(m)?;
+    Ok(context)
+}
+
+fn run<T>(mut sources: Sources) -> Result<T>
+where
+    T: FromValue,
+{
+    let context = context()?;
+    let runtime = Arc::new(context.runtime()?);
+    let unit = prepare(&mut sources).with_context(&context).build()?;
+    let mut vm = Vm::new(runtime, Arc::new(unit));
+    Ok(from_value(vm.call(["main"], ())?)?)
+}
+
+#[test]
+fn derive_on_struct() -> Result<()> {
+    let out: (i64, String) = run(sources! {
+        entry => {
+            use ::test::to_object;
+
+            #[to_object]
+            struct Point { x, y }
+
+            pub fn main() {
+                let o = Point { x: 1, y: "two" }.to_object();
+                (o.x, o.y)
+            }
+        }
+    })?;
+
+    assert_eq!(out, (1, String::from("two")));
+    Ok(())
+}
+
+#[test]
+fn derive_on_enum() -> Result<()> {
+    let out: String = run(sources! {
+        entry => {
+            use ::test::type_name;
+
+            #[type_name]
+            enum Shape {
+                Circle(radius),
+                Square(side),
+            }
+
+            pub fn main() {
+                name_of_type()
+            }
+        }
+    })?;
+
+    assert_eq!(out, "Shape");
+    Ok(())
+}
+
+#[test]
+fn derive_in_nested_module() -> Result<()> {
+    let out: i64 = run(sources! {
+        entry => {
+            mod shapes {
+                #[::test::to_object]
+                pub struct Circle { radius }
+            }
+
+            pub fn main() {
+                shapes::Circle { radius: 4 }.to_object().radius
+            }
+        }
+    })?;
+
+    assert_eq!(out, 4);
+    Ok(())
+}
+
+#[test]
+fn derive_in_block() -> Result<()> {
+    let out: (i64, i64) = run(sources! {
+        entry => {
+            pub fn main() {
+                #[::test::to_object]
+                struct Size { w, h }
+
+                let o = Size { w: 2, h: 3 }.to_object();
+                (o.w, o.h)
+            }
+        }
+    })?;
+
+    assert_eq!(out, (2, 3));
+    Ok(())
+}
+
+#[test]
+fn generated_items_are_unique() -> Result<()> {
+    let out: (i64, i64) = run(sources! {
+        entry => {
+            use ::test::to_object;
+
+            #[to_object]
+            struct A { a }
+
+            #[to_object]
+            struct B { b }
+
+            pub fn main() {
+                (A { a: 1 }.to_object().a, B { b: 2 }.to_object().b)
+            }
+        }
+    })?;
+
+    assert_eq!(out, (1, 2));
+    Ok(())
+}
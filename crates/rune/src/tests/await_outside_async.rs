@@ -0,0 +1,122 @@
+//! Tests for diagnostics when `.await` is used outside of an async context.
+
+prelude!();
+
+use rune::ast::Spanned;
+use rune::compile::Suggestion;
+use rune::diagnostics::{Diagnostic, FatalDiagnosticKind};
+use rune::termcolor;
+use rune::tests::sources;
+
+fn compile(source: &str) -> Result<(compile::Error, String)> {
+    let context = Context::with_default_modules()?;
+    let mut sources = sources(source);
+    let mut diagnostics = Diagnostics::new();
+
+    let result = rune::prepare(&mut sources)
+        .with_context(&context)
+        .with_diagnostics(&mut diagnostics)
+        .build();
+
+    assert!(result.is_err());
+
+    let mut out = termcolor::Buffer::no_color();
+    diagnostics.emit(&mut out, &sources)?;
+    let out = String::from_utf8(out.into_inner())?;
+
+    let Some(Diagnostic::Fatal(fatal)) = diagnostics.into_diagnostics().into_iter().next() else {
+        panic!("expected fatal diagnostic");
+    };
+
+    let FatalDiagnosticKind::CompileError(error) = fatal.into_kind() else {
+        panic!("expected compile error");
+    };
+
+    Ok((error, out))
+}
+
+#[test]
+fn await_in_function() -> Result<()> {
+    let (error, out) = compile("pub fn main(x) { x.await }")?;
+
+    assert_eq!(error.span(), span!(18, 24));
+
+    assert_eq!(
+        error.suggestion(),
+        Some(Suggestion {
+            span: span!(4, 4),
+            replacement: "async ",
+            message: "Make the function async",
+        })
+    );
+
+    let ErrorKind::AwaitOutsideAsync { function, closure } = error.into_kind() else {
+        panic!("expected await outside async");
+    };
+
+    assert_eq!(function, span!(4, 6));
+    assert!(!closure);
+
+    assert!(out.contains("this function is not async"), "{out}");
+    assert!(
+        out.contains("Hint: Make the function async by inserting `async`"),
+        "{out}"
+    );
+    Ok(())
+}
+
+#[test]
+fn await_in_nested_closure() -> Result<()> {
+    // The enclosing function is async, so it's the closure which must be made
+    // async.
+    let (error, out) = compile("pub async fn main() { let f = move |x| x.await; f }")?;
+
+    assert_eq!(error.span(), span!(40, 46));
+
+    assert_eq!(
+        error.suggestion(),
+        Some(Suggestion {
+            span: span!(30, 30),
+            replacement: "async ",
+            message: "Make the closure async",
+        })
+    );
+
+    let ErrorKind::AwaitOutsideAsync { function, closure } = error.into_kind() else {
+        panic!("expected await outside async");
+    };
+
+    assert_eq!(function, span!(30, 38));
+    assert!(closure);
+
+    assert!(out.contains("this closure is not async"), "{out}");
+    assert!(!out.contains("this function is not async"), "{out}");
+
+    let (error, _) = compile("pub fn main() { let f = |x| x.await; f }")?;
+    assert_eq!(error.span(), span!(29, 35));
+    assert_eq!(error.suggestion().map(|s| s.span), Some(span!(24, 24)));
+    Ok(())
+}
+
+#[test]
+fn await_and_yield_in_const() -> Result<()> {
+    let (error, out) = compile("pub const fn main(x) { x.await }")?;
+
+    let ErrorKind::AwaitInConst { function } = error.into_kind() else {
+        panic!("expected await in const");
+    };
+
+    assert_eq!(function, span!(10, 12));
+    assert!(out.contains("this function is const"), "{out}");
+
+    let (error, out) = compile("pub const fn main() { yield 1 }")?;
+    assert_eq!(error.suggestion(), None);
+
+    let ErrorKind::YieldInConst { function } = error.into_kind() else {
+        panic!("expected yield in const");
+    };
+
+    assert_eq!(function, span!(10, 12));
+    assert!(out.contains("this function is const"), "{out}");
+    Ok(())
+}
@@ -0,0 +1,227 @@
+prelude!();
+
+use core::pin::pin;
+
+use rune::alloc::limit;
+use rune::runtime::budget;
+
+#[test]
+fn budget_introspection() -> Result<()> {
+    let context = Context::with_default_modules()?;
+
+    let mut sources = sources! {
+        entry => {
+            pub fn main() {
+                let a = 1 + 2;
+                (std::budget::remaining(), std::budget::consumed())
+            }
+        }
+    };
+
+    let mut vm = crate::tests::vm(&context, &mut sources, &mut Diagnostics::new(), false)?;
+
+    let output = budget::with(1000, || vm.call(["main"], ())).call()?;
+    let (remaining, consumed): (Option<u64>, u64) = rune::from_value(output)?;
+    let remaining = remaining.expect("budget should be in effect");
+    assert!(consumed > 0);
+    // Instructions are consumed between the two calls.
+    assert!((1000..1010).contains(&(remaining + consumed)));
+
+    let output = vm.call(["main"], ())?;
+    let (remaining, consumed): (Option<u64>, u64) = rune::from_value(output)?;
+    assert_eq!(remaining, None);
+    assert_eq!(consumed, 0);
+    Ok(())
+}
+
+#[test]
+fn budget_exhausted_is_not_allocation() -> Result<()> {
+    let context = Context::with_default_modules()?;
+
+    let mut sources = sources! {
+        entry => {
+            pub fn main() {
+                loop {}
+            }
+        }
+    };
+
+    let mut vm = crate::tests::vm(&context, &mut sources, &mut Diagnostics::new(), false)?;
+
+    let error = budget::with(100, || vm.call(["main"], ()))
+        .call()
+        .unwrap_err();
+
+    assert!(!error.is_allocation_budget_exceeded());
+    Ok(())
+}
+
+#[test]
+fn allocation_budget_exceeded() -> Result<()> {
+    let context = Context::with_default_modules()?;
+
+    let mut sources = sources! {
+        entry => {
+            pub fn main() {
+                let s = String::with_capacity(1 << 20);
+                s
+            }
+        }
+    };
+
+    let mut vm = crate::tests::vm(&context, &mut sources, &mut Diagnostics::new(), false)?;
+
+    let error = limit::with(1024, || vm.call(["main"], ()))
+        .call()
+        .unwrap_err();
+
+    assert!(error.is_allocation_budget_exceeded());
+
+    assert!(matches!(
+        error.into_kind(),
+        VmErrorKind::AllocationBudgetExceeded {
+            requested: 1048576,
+            ..
+        }
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn budget_future_consumed() -> Result<()> {
+    let context = Context::with_default_modules()?;
+
+    let mut sources = sources! {
+        entry => {
+            pub async fn main() {
+                let a = 1;
+                a + 2
+            }
+        }
+    };
+
+    let mut vm = crate::tests::vm(&context, &mut sources, &mut Diagnostics::new(), false)?;
+
+    let mut future = pin!(budget::with(1000, vm.async_call(["main"], ())));
+    let output: i64 = rune::from_value(block_on(future.as_mut())?)?;
+    assert_eq!(output, 3);
+    assert!(future.consumed() > 0);
+    assert_eq!(future.remaining() + future.consumed(), 1000);
+    Ok(())
+}
+
+#[test]
+fn budget_sliced_execution() -> Result<()> {
+    let context = Context::with_default_modules()?;
+
+    let mut sources = sources! {
+        entry => {
+            pub fn main() {
+                let n = 0;
+
+                for i in 0..1000 {
+                    n += i;
+                }
+
+                n
+            }
+        }
+    };
+
+    let mut vm = crate::tests::vm(&context, &mut sources, &mut Diagnostics::new(), false)?;
+
+    let mut execution = vm.execute(["main"], ())?;
+    let mut slices = 0;
+
+    let output = loop {
+        slices += 1;
+
+        let mut future = pin!(budget::with(100, execution.async_complete_limited()));
+
+        if let Some(output) = block_on(future.as_mut()).into_result()? {
+            break output;
+        }
+
+        // An exhausted slice uses up all of its budget.
+        assert_eq!(future.remaining(), 0);
+    };
+
+    let output: i64 = rune::from_value(output)?;
+    assert_eq!(output, 499500);
+    assert!(slices > 10, "{slices}");
+    Ok(())
+}
+
+#[test]
+fn budget_with_usage() -> Result<()> {
+    let context = Context::with_default_modules()?;
+
+    let mut sources = sources! {
+        entry => {
+            pub fn main(n) {
+                let out = 0;
+
+                for i in 0..n {
+                    out += i;
+                }
+
+                out
+            }
+        }
+    };
+
+    let mut vm = crate::tests::vm(&context, &mut sources, &mut Diagnostics::new(), false)?;
+
+    let (output, small) = budget::with(10_000, || vm.call(["main"], (10i64,)))
+        .with_usage()
+        .call();
+    assert_eq!(from_value::<i64>(output?)?, 45);
+
+    let (output, large) = budget::with(10_000, || vm.call(["main"], (100i64,)))
+        .with_usage()
+        .call();
+    assert_eq!(from_value::<i64>(output?)?, 4950);
+
+    assert!(small.consumed() > 0);
+    assert!(large.consumed() > small.consumed());
+    assert_eq!(large.remaining() + large.consumed(), 10_000);
+    Ok(())
+}
+
+#[test]
+fn budget_shared_with_nested_vm() -> Result<()> {
+    let mut m = Module::new();
+
+    m.function("nested", |f: Function| f.call::<i64>(()))
+        .build()?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(m)?;
+
+    let mut sources = sources! {
+        entry => {
+            pub fn main() {
+                nested(|| {
+                    let n = 0;
+
+                    loop {
+                        n += 1;
+                    }
+                })
+            }
+        }
+    };
+
+    let mut vm = crate::tests::vm(&context, &mut sources, &mut Diagnostics::new(), false)?;
+
+    // The nested execution takes from the same budget, so the infinite loop
+    // is eventually interrupted.
+    let (output, usage) = budget::with(1000, || vm.call(["main"], ()))
+        .with_usage()
+        .call();
+
+    assert!(output.is_err());
+    assert_eq!(usage.remaining(), 0);
+    Ok(())
+}
@@ -0,0 +1,238 @@
+//! Tests for reusing work across builds with a build cache.
+
+prelude!();
+
+use rune::compile::BuildCache;
+use rune::SourceId;
+
+const MAIN: &str = "pub fn main() { 1 }";
+
+fn build(
+    cache: &mut BuildCache,
+    context: &Context,
+    options: &Options,
+    sources: &[(&str, &str)],
+) -> Result<Vm> {
+    let mut s = Sources::new();
+
+    for (name, source) in sources {
+        s.insert(Source::new(name, source)?)?;
+    }
+
+    let mut diagnostics = Diagnostics::new();
+
+    let unit = rune::prepare(&mut s)
+        .with_context(context)
+        .with_diagnostics(&mut diagnostics)
+        .with_options(options)
+        .with_cache(cache)
+        .build();
+
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics.diagnostics());
+
+    let runtime = Arc::new(context.runtime()?);
+    Ok(Vm::new(runtime, Arc::new(unit?)))
+}
+
+fn call(vm: &mut Vm, name: &str) -> Result<i64> {
+    Ok(from_value(vm.call([name], ())?)?)
+}
+
+#[test]
+fn edit_one_source() -> Result<()> {
+    let context = Context::with_default_modules()?;
+    let options = Options::default();
+    let mut cache = BuildCache::new();
+
+    let mut vm = build(
+        &mut cache,
+        &context,
+        &options,
+        &[("main", MAIN), ("other", "pub fn other() { 2 }")],
+    )?;
+
+    assert_eq!(call(&mut vm, "main")?, 1);
+    assert_eq!(call(&mut vm, "other")?, 2);
+    assert_eq!((cache.hits(), cache.misses()), (0, 2));
+    assert_eq!((cache.item_hits(), cache.item_misses()), (0, 2));
+
+    // Only the edited source is processed again.
+    let mut vm = build(
+        &mut cache,
+        &context,
+        &options,
+        &[("main", MAIN), ("other", "pub fn other() { 3 }")],
+    )?;
+
+    assert_eq!(call(&mut vm, "main")?, 1);
+    assert_eq!(call(&mut vm, "other")?, 3);
+    assert_eq!((cache.hits(), cache.misses()), (1, 3));
+
+    // The items in the unchanged source aren't lowered again.
+    assert_eq!((cache.item_hits(), cache.item_misses()), (1, 3));
+
+    let main = SourceId::new(0);
+    let other = SourceId::new(1);
+
+    assert!(cache.contains(main, &Source::new("main", MAIN)?));
+    assert!(cache.contains(other, &Source::new("other", "pub fn other() { 3 }")?));
+    assert!(!cache.contains(other, &Source::new("other", "pub fn other() { 2 }")?));
+
+    // Sources which are no longer part of the build are evicted.
+    build(&mut cache, &context, &options, &[("main", MAIN)])?;
+    assert_eq!((cache.hits(), cache.misses()), (2, 3));
+    assert_eq!((cache.item_hits(), cache.item_misses()), (1, 4));
+    assert!(!cache.contains(other, &Source::new("other", "pub fn other() { 3 }")?));
+    assert_eq!(cache.reused(), 0);
+    Ok(())
+}
+
+#[test]
+fn reuse_items() -> Result<()> {
+    const MAIN: &str = r#"
+        pub fn main() {
+            let add = |n| n + value();
+            let object = #{ name: "value", value: add(1) };
+
+            match object {
+                #{ name: "value", value: v } => v,
+                _ => 0,
+            }
+        }
+    "#;
+
+    let context = Context::with_default_modules()?;
+    let options = Options::default();
+    let mut cache = BuildCache::new();
+
+    let mut vm = build(
+        &mut cache,
+        &context,
+        &options,
+        &[("main", MAIN), ("lib", "pub fn value() { 1 }")],
+    )?;
+
+    assert_eq!(call(&mut vm, "main")?, 2);
+    assert_eq!((cache.item_hits(), cache.item_misses()), (0, 2));
+
+    // Editing the body of a function only compiles the items in its own
+    // source again. The closure and static data in `main` is reused.
+    let mut vm = build(
+        &mut cache,
+        &context,
+        &options,
+        &[("main", MAIN), ("lib", "pub fn value() { 10 }")],
+    )?;
+
+    assert_eq!(call(&mut vm, "main")?, 11);
+    assert_eq!((cache.item_hits(), cache.item_misses()), (1, 3));
+
+    // Changing the declarations of a source compiles every item again, since
+    // it might affect how they're resolved.
+    let mut vm = build(
+        &mut cache,
+        &context,
+        &options,
+        &[
+            ("main", MAIN),
+            ("lib", "pub fn value() { 10 } pub fn other() { 20 }"),
+        ],
+    )?;
+
+    assert_eq!(call(&mut vm, "main")?, 11);
+    assert_eq!(call(&mut vm, "other")?, 20);
+    assert_eq!((cache.item_hits(), cache.item_misses()), (1, 6));
+    Ok(())
+}
+
+#[test]
+fn reuse_unit() -> Result<()> {
+    let context = Context::with_default_modules()?;
+    let options = Options::default();
+    let mut cache = BuildCache::new();
+
+    let sources = [("main", MAIN), ("other", "pub fn other() { 2 }")];
+
+    build(&mut cache, &context, &options, &sources)?;
+    assert_eq!(cache.reused(), 0);
+
+    // Nothing changed, so the previous unit is used as-is.
+    let mut vm = build(&mut cache, &context, &options, &sources)?;
+    assert_eq!(call(&mut vm, "main")?, 1);
+    assert_eq!(call(&mut vm, "other")?, 2);
+    assert_eq!(cache.reused(), 1);
+    assert_eq!((cache.hits(), cache.misses()), (2, 2));
+
+    // Renaming a source changes how it's built.
+    build(
+        &mut cache,
+        &context,
+        &options,
+        &[("main", MAIN), ("renamed", "pub fn other() { 2 }")],
+    )?;
+
+    assert_eq!(cache.reused(), 1);
+    Ok(())
+}
+
+#[test]
+fn reorder_sources() -> Result<()> {
+    let context = Context::with_default_modules()?;
+    let options = Options::default();
+    let mut cache = BuildCache::new();
+
+    let first = ("first", "pub fn first() { \"first value\" }");
+    let second = ("second", "pub fn second() { \"second\" }");
+
+    build(&mut cache, &context, &options, &[first, second])?;
+
+    // The parsed sources refer back to the source id they were parsed from,
+    // so they can't be reused once the order of the sources changes.
+    let mut vm = build(&mut cache, &context, &options, &[second, first])?;
+
+    let value: String = from_value(vm.call(["first"], ())?)?;
+    assert_eq!(value, "first value");
+
+    let value: String = from_value(vm.call(["second"], ())?)?;
+    assert_eq!(value, "second");
+
+    assert_eq!((cache.hits(), cache.misses()), (0, 4));
+    assert_eq!(cache.reused(), 0);
+    Ok(())
+}
+
+#[test]
+fn invalidate_on_options() -> Result<()> {
+    let context = Context::with_default_modules()?;
+    let mut cache = BuildCache::new();
+
+    build(&mut cache, &context, &Options::default(), &[("main", MAIN)])?;
+    build(&mut cache, &context, &Options::default(), &[("main", MAIN)])?;
+    assert_eq!((cache.hits(), cache.misses()), (1, 1));
+
+    let mut options = Options::default();
+    options.parse_option("debug-info=false")?;
+
+    build(&mut cache, &context, &options, &[("main", MAIN)])?;
+    assert_eq!((cache.hits(), cache.misses()), (1, 2));
+    Ok(())
+}
+
+#[test]
+fn invalidate_on_context() -> Result<()> {
+    let mut cache = BuildCache::new();
+    let options = Options::default();
+
+    let context = Context::with_default_modules()?;
+    build(&mut cache, &context, &options, &[("main", MAIN)])?;
+
+    let mut module = Module::with_crate("extra")?;
+    module.function("extra", || 42i64).build()?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(module)?;
+
+    build(&mut cache, &context, &options, &[("main", MAIN)])?;
+    assert_eq!((cache.hits(), cache.misses()), (0, 2));
+    Ok(())
+}
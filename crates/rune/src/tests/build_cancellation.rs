@@ -0,0 +1,33 @@
+//! Tests for aborting a build through a cancellation flag.
+
+prelude!();
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use rune::{BuildError, Unit};
+
+fn build(cancel: &AtomicBool) -> Result<Unit, BuildError> {
+    let mut sources = sources! {
+        entry => {
+            pub fn main() {
+                1
+            }
+        }
+    };
+
+    rune::prepare(&mut sources)
+        .with_cancellation(cancel)
+        .build()
+}
+
+#[test]
+fn build_cancelled() -> Result<()> {
+    let cancel = AtomicBool::new(false);
+    assert!(build(&cancel).is_ok());
+
+    cancel.store(true, Ordering::Release);
+    let error = build(&cancel).unwrap_err();
+    assert!(error.is_cancelled());
+    assert_eq!(error.to_string(), "Build was cancelled");
+    Ok(())
+}
@@ -11,6 +11,8 @@ fn test_fn_const_async() {
 
     assert_errors! {
         r#"pub const fn main() { yield true }"#,
-        span!(22, 32), YieldInConst
+        span!(22, 32), YieldInConst { function } => {
+            assert_eq!(function, span!(10, 12));
+        }
     };
 }
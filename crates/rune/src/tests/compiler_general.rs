@@ -24,7 +24,7 @@ fn break_outside_of_loop() {
 fn for_break_with_value() {
     assert_errors! {
         "for _ in 0..10 { break 42; }",
-        span!(17, 25), BreakUnsupportedValue
+        span!(17, 25), BreakUnsupportedValue { .. }
     };
 }
 
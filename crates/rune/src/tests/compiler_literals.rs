@@ -29,3 +29,38 @@ fn number_literals_oob() {
         span!(0, 66), BadSignedOutOfBounds { .. }
     };
 }
+
+#[test]
+fn number_literals_in_patterns_oob() {
+    assert_errors! {
+        "match 0 { 0x_ffff_ffff_ffff_ffff => 1, _ => 2 }",
+        span!(10, 32), BadSignedOutOfBounds { .. }
+    };
+
+    assert_errors! {
+        "match 0 { -9_223_372_036_854_775_809 => 1, _ => 2 }",
+        span!(10, 36), BadSignedOutOfBounds { .. }
+    };
+
+    assert_errors! {
+        "match 0u8 { 1_000u8 => 1, _ => 2 }",
+        span!(12, 19), BadUnsignedOutOfBounds { .. }
+    };
+}
+
+#[test]
+fn raw_string_literals() {
+    assert_parse!(r####"r"hello""####);
+    assert_parse!(r####"r#"say "hello""#"####);
+    assert_parse!(r####"br##"say "# hello"##"####);
+
+    assert_errors! {
+        r####"let _ = r##"hello"#;"####,
+        span!(8, 12), UnterminatedStrLit { .. }
+    };
+
+    assert_errors! {
+        r####"let _ = br#"hello";"####,
+        span!(8, 12), UnterminatedByteStrLit
+    };
+}
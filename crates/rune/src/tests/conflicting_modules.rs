@@ -0,0 +1,110 @@
+//! Tests for errors raised when installing modules which conflict with each
+//! other.
+
+prelude!();
+
+#[derive(Any)]
+#[rune(item = ::json)]
+struct Value;
+
+fn json() -> Result<Module, ContextError> {
+    let mut m = Module::with_crate("json")?;
+    m.ty::<Value>()?;
+    m.function("from_string", |s: &str| s.len() as i64)
+        .build()?;
+    m.associated_function("len", |_: &Value| 1i64)?;
+    Ok(m)
+}
+
+#[test]
+fn conflicting_function() -> Result<()> {
+    let mut context = Context::with_default_modules()?;
+    context.install(json()?)?;
+
+    let mut other = Module::with_crate("json")?;
+    other
+        .function("from_string", |s: &str| s.len() as i64)
+        .build()?;
+
+    let error = context.install(other).unwrap_err();
+
+    let ContextError::ConflictingFunction {
+        part,
+        item,
+        module,
+        existing,
+        existing_module,
+        ..
+    } = &error
+    else {
+        panic!("Expected conflicting function but got: {error:?}");
+    };
+
+    assert_eq!(&**part, "::json::from_string");
+    assert_eq!(*item, rune::item!(::json::from_string));
+    assert_eq!(*module, rune::item!(::json));
+    assert_eq!(*existing, rune::item!(::json::from_string));
+    assert_eq!(*existing_module, rune::item!(::json));
+
+    let mut serde = Module::with_crate("serde")?;
+    serde.associated_function("len", |_: &Value| 0i64)?;
+
+    let error = context.install(serde).unwrap_err();
+
+    let ContextError::ConflictingFunction {
+        part,
+        item,
+        module,
+        existing,
+        existing_module,
+        ..
+    } = &error
+    else {
+        panic!("Expected conflicting function but got: {error:?}");
+    };
+
+    assert_eq!(&**part, "::json::Value");
+    assert_eq!(*item, rune::item!(::json::Value::len));
+    assert_eq!(*module, rune::item!(::serde));
+    assert_eq!(*existing, rune::item!(::json::Value::len));
+    assert_eq!(*existing_module, rune::item!(::json));
+
+    assert_eq!(
+        error.to_string(),
+        format!("Function `::json::Value::len` with hash `{}` part of `::json::Value` in module `::serde` conflicts with existing function `::json::Value::len` in module `::json`", Hash::type_hash(rune::item!(::json::Value::len))),
+    );
+
+    Ok(())
+}
+
+#[test]
+fn conflicting_type() -> Result<()> {
+    let mut serde = Module::with_crate("serde")?;
+    serde.ty::<Value>()?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(json()?)?;
+
+    let error = context.install(serde).unwrap_err();
+
+    let ContextError::ConflictingType {
+        item,
+        module,
+        existing,
+        existing_module,
+        ..
+    } = &error
+    else {
+        panic!("Expected conflicting type but got: {error:?}");
+    };
+
+    assert_eq!(*item, rune::item!(::json::Value));
+    assert_eq!(*module, rune::item!(::serde));
+    assert_eq!(*existing, rune::item!(::json::Value));
+    assert_eq!(*existing_module, rune::item!(::json));
+
+    let message = error.to_string();
+    assert!(message.contains("in module `::serde`"), "{message}");
+    assert!(message.contains("in module `::json`"), "{message}");
+    Ok(())
+}
@@ -0,0 +1,117 @@
+prelude!();
+
+use ErrorKind::*;
+
+use crate::compile::IrErrorKind;
+use crate::runtime::Inst;
+
+/// Get the size of something, which can be called in constant contexts.
+#[rune::function(const)]
+fn default_size() -> i64 {
+    8
+}
+
+fn module() -> Result<Module> {
+    let mut m = Module::with_item(["config"])?;
+    m.function("max", |a: i64, b: i64| a.max(b))
+        .const_fn()
+        .build()?;
+    m.function("runtime_size", || 4i64).build()?;
+    m.function_meta(default_size)?;
+    Ok(m)
+}
+
+fn build(mut sources: Sources) -> Result<(Context, rune::Unit)> {
+    let mut context = Context::with_default_modules()?;
+    context.install(module()?)?;
+    let unit = prepare(&mut sources).with_context(&context).build()?;
+    Ok((context, unit))
+}
+
+#[test]
+fn string_repeat_at_compile_time() -> Result<()> {
+    let (context, unit) = build(sources! {
+        entry => {
+            const X = String::repeat("ab", 3);
+
+            pub fn main() {
+                X
+            }
+        }
+    })?;
+
+    let has_calls = unit
+        .iter_instructions()
+        .any(|(_, inst)| matches!(inst, Inst::Call { .. } | Inst::CallAssociated { .. }));
+    assert!(
+        !has_calls,
+        "expected no runtime calls to remain in the unit"
+    );
+
+    let mut vm = Vm::new(Arc::new(context.runtime()?), Arc::new(unit));
+    let value: String = from_value(vm.call(["main"], ())?)?;
+    assert_eq!(value, "ababab");
+    Ok(())
+}
+
+#[test]
+fn native_const_fns() -> Result<()> {
+    let (context, unit) = build(sources! {
+        entry => {
+            use config::{default_size, max};
+
+            const SIZE = max(16, default_size());
+
+            const fn double(n) {
+                max(n, 0) * 2
+            }
+
+            pub fn main() {
+                (SIZE, double(SIZE))
+            }
+        }
+    })?;
+
+    let mut vm = Vm::new(Arc::new(context.runtime()?), Arc::new(unit));
+    let value: (i64, i64) = from_value(vm.call(["main"], ())?)?;
+    assert_eq!(value, (16, 32));
+    Ok(())
+}
+
+#[test]
+fn non_const_fn() -> Result<()> {
+    let source = r#"const SIZE = config::runtime_size();"#;
+
+    let mut sources = Sources::new();
+    sources.insert(Source::memory(source)?)?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(module()?)?;
+
+    let mut diagnostics = Diagnostics::new();
+
+    let result = prepare(&mut sources)
+        .with_context(&context)
+        .with_diagnostics(&mut diagnostics)
+        .build();
+
+    assert!(result.is_err());
+
+    let Some(rune::diagnostics::Diagnostic::Fatal(error)) = diagnostics.diagnostics().first()
+    else {
+        panic!("expected a fatal diagnostic");
+    };
+
+    assert!(error
+        .to_string()
+        .contains("Only functions marked as `const` can be called in constant contexts"));
+    Ok(())
+}
+
+#[test]
+fn non_const_fn_span() {
+    assert_errors! {
+        r#"const VALUE = String::new();"#,
+        span!(14, 27), IrError(IrErrorKind::NotConstFn)
+    };
+}
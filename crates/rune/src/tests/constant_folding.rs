@@ -0,0 +1,130 @@
+prelude!();
+
+use ErrorKind::*;
+
+use crate::termcolor::NoColor;
+
+/// Build the given source and dump its instructions like `rune run --dump`
+/// does, returning the dumped instructions.
+fn dump(source: &str, constant_folding: bool) -> Result<(String, Vec<String>)> {
+    let context = Context::with_default_modules()?;
+
+    let mut sources = Sources::new();
+    sources.insert(Source::memory(source)?)?;
+
+    let mut options = Options::default();
+    options.constant_folding(constant_folding);
+
+    let unit = prepare(&mut sources)
+        .with_context(&context)
+        .with_options(&options)
+        .build()?;
+
+    let mut out = NoColor::new(Vec::new());
+    unit.emit_instructions(&mut out, &sources, true)?;
+    let out = String::from_utf8(out.into_inner())?;
+
+    let instructions = out
+        .lines()
+        .filter(|line| line.starts_with("  "))
+        .map(|line| line.trim().to_string())
+        .collect::<Vec<_>>();
+
+    let mut vm = Vm::new(Arc::new(context.runtime()?), Arc::new(unit));
+    let value = vm.call(["main"], ())?;
+    Ok((format!("{value:?}"), instructions))
+}
+
+#[test]
+fn folding_reduces_instructions() -> Result<()> {
+    let source = r#"
+    pub fn main() {
+        let x = 2 * 60 * 60;
+        let y = (x > 1000) && !false;
+        let z = `prefix-${"literal"}-${2 * 3}` + "!";
+        (x, y, z)
+    }
+    "#;
+
+    let (folded, folded_insts) = dump(source, true)?;
+    let (unfolded, unfolded_insts) = dump(source, false)?;
+
+    assert_eq!(folded, unfolded);
+    assert!(
+        folded_insts.len() < unfolded_insts.len(),
+        "{folded_insts:#?} should be shorter than {unfolded_insts:#?}"
+    );
+
+    assert!(!folded_insts.iter().any(|inst| inst.contains("mul")));
+    assert!(!folded_insts
+        .iter()
+        .any(|inst| inst.contains("string-concat")));
+    Ok(())
+}
+
+#[test]
+fn folded_values() {
+    let out: i64 = rune!(2 * 60 * 60);
+    assert_eq!(out, 7200);
+
+    let out: i64 = rune!(-(1 << 4) | 3);
+    assert_eq!(out, -13);
+
+    let out: f64 = rune!(1.5 * 2.0 - 0.5);
+    assert_eq!(out, 2.5);
+
+    let out: bool = rune!(1 < 2 && 'a' != 'b');
+    assert!(out);
+
+    let out: String = eval(r#"`${"a"}-${1}-${true}-${'c'}`"#);
+    assert_eq!(out, "a-1-true-c");
+
+    let out: String = eval(r#"const A = "a"; const B = 2; `${A}${B}` + "c""#);
+    assert_eq!(out, "a2c");
+}
+
+#[test]
+fn short_circuit_skips_right_hand_side() {
+    let out: bool = rune!(false && 1 / 0 == 0);
+    assert!(!out);
+
+    let out: bool = rune!(true || 9223372036854775807 + 1 > 0);
+    assert!(out);
+
+    let out: bool = rune!(true && 2 > 1);
+    assert!(out);
+
+    assert_errors! {
+        "true && 1 / 0 == 0",
+        span!(8, 13), VmError(error) => {
+            assert!(matches!(error.into_kind(), VmErrorKind::DivideByZero));
+        }
+    };
+}
+
+#[test]
+fn mixed_types_are_not_folded() -> Result<()> {
+    let context = Context::with_default_modules()?;
+
+    // This is left for the virtual machine to report at runtime.
+    let result = run::<Value>(&context, "1 + 1.0", (), true);
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn overflow_is_a_compile_error() {
+    assert_errors! {
+        "9223372036854775807 + 1",
+        span!(0, 23), VmError(error) => {
+            assert!(matches!(error.into_kind(), VmErrorKind::Overflow));
+        }
+    };
+
+    assert_errors! {
+        "1 / (2 - 2)",
+        span!(0, 11), VmError(error) => {
+            assert!(matches!(error.into_kind(), VmErrorKind::DivideByZero));
+        }
+    };
+}
@@ -14,7 +14,7 @@ fn test_continue_not_in_loop() {
 fn test_continue_missing_label() {
     assert_errors! {
         r#"pub fn main() { 'existing: loop { loop { continue 'missing; } } }"#,
-        span!(41, 58), MissingLabel { label } => {
+        span!(41, 58), MissingLabel { label, .. } => {
             assert_eq!(&*label, "missing");
         }
     };
@@ -0,0 +1,162 @@
+prelude!();
+
+use rune::diagnostics::Diagnostic;
+use rune::runtime::{Unit, UnitStorage};
+use rune::Diagnostics;
+
+const SOURCE: &str = r#"
+struct Counter { value }
+
+impl Counter {
+    fn bump(self) {
+        self.value += 1;
+        self.value
+    }
+}
+
+fn double(n) {
+    n * 2
+}
+
+fn pointer(n) {
+    n + 100
+}
+
+fn recurse(n) {
+    if n <= 0 {
+        return 0;
+    }
+
+    n + recurse(n - 1)
+}
+
+pub fn main() {
+    let f = pointer;
+    let counter = Counter { value: 1 };
+    let total = 0;
+
+    for n in 0..10 {
+        if n % 2 == 0 {
+            total += double(n);
+        } else {
+            total += recurse(n);
+        }
+    }
+
+    let add = |n| n + total;
+    let name = match "retained" {
+        "retained" => 1,
+        _ => 0,
+    };
+
+    [f(1), counter.bump(), add(1), name, total]
+}
+
+pub fn other() {
+    let text = "only used by other";
+    other_helper(text)
+}
+
+fn other_helper(text) {
+    double(text.len())
+}
+"#;
+
+fn build(dce: bool, retain: Option<&[&str]>) -> Result<(Unit, Diagnostics)> {
+    let context = Context::with_default_modules()?;
+
+    let mut options = Options::default();
+    options.dce(dce);
+
+    if let Some(retain) = retain {
+        options.retain(retain.iter().map(|name| [*name]));
+    }
+
+    let mut sources = Sources::new();
+    sources.insert(Source::memory(SOURCE)?)?;
+
+    let mut diagnostics = Diagnostics::new();
+
+    let unit = prepare(&mut sources)
+        .with_context(&context)
+        .with_options(&options)
+        .with_diagnostics(&mut diagnostics)
+        .build()?;
+
+    Ok((unit, diagnostics))
+}
+
+fn call(unit: Unit, name: &str) -> Result<Value> {
+    let context = Context::with_default_modules()?;
+    let mut vm = Vm::new(Arc::new(context.runtime()?), Arc::new(unit));
+    Ok(vm.call([name], ())?)
+}
+
+fn removed(diagnostics: &Diagnostics) -> Vec<String> {
+    let mut removed = Vec::new();
+
+    for diagnostic in diagnostics.diagnostics() {
+        if let Diagnostic::Warning(warning) = diagnostic {
+            if let WarningDiagnosticKind::RemovedFunction { item, .. } = warning.kind() {
+                removed.push(item.to_string());
+            }
+        }
+    }
+
+    removed.sort();
+    removed
+}
+
+#[test]
+fn retain_entry_points() -> Result<()> {
+    let (full, _) = build(false, None)?;
+    let (reduced, diagnostics) = build(true, Some(&["main"]))?;
+
+    assert!(reduced.iter_functions().count() < full.iter_functions().count());
+    assert!(reduced.instructions().end() < full.instructions().end());
+
+    assert!(reduced.function(&Hash::type_hash(["other"])).is_none());
+    assert!(reduced
+        .function(&Hash::type_hash(["other_helper"]))
+        .is_none());
+    assert!(reduced.function(&Hash::type_hash(["double"])).is_some());
+    assert!(reduced.function(&Hash::type_hash(["pointer"])).is_some());
+    assert!(reduced.function(&Hash::type_hash(["recurse"])).is_some());
+
+    assert_eq!(removed(&diagnostics), ["other", "other_helper"]);
+
+    let expected = from_value::<Vec<i64>>(call(full, "main")?)?;
+    let actual = from_value::<Vec<i64>>(call(reduced, "main")?)?;
+    assert_eq!(actual, expected);
+    assert_eq!(actual, [101, 2, 136, 1, 135]);
+    Ok(())
+}
+
+#[test]
+fn public_functions_are_retained() -> Result<()> {
+    let (full, _) = build(false, None)?;
+    let (unit, diagnostics) = build(true, None)?;
+
+    assert_eq!(unit.iter_functions().count(), full.iter_functions().count());
+    assert!(removed(&diagnostics).is_empty());
+
+    let value = call(unit, "other")?;
+    assert_eq!(from_value::<i64>(value)?, 36);
+    Ok(())
+}
+
+#[test]
+fn retain_other_entry_point() -> Result<()> {
+    let (unit, diagnostics) = build(true, Some(&["other"]))?;
+
+    assert!(unit.function(&Hash::type_hash(["main"])).is_none());
+    assert!(unit.function(&Hash::type_hash(["pointer"])).is_none());
+    assert!(unit.function(&Hash::type_hash(["recurse"])).is_none());
+
+    let removed = removed(&diagnostics);
+    assert!(removed.iter().any(|item| item == "main"));
+
+    let value = call(unit, "other")?;
+    assert_eq!(from_value::<i64>(value)?, 36);
+    Ok(())
+}
@@ -0,0 +1,67 @@
+//! Tests for reporting references which escape their scope, which is enabled
+//! through the `debug-access` feature.
+
+prelude!();
+
+use rune::termcolor;
+
+#[derive(Any, Debug, Default)]
+struct Player {
+    #[rune(get, set, copy)]
+    health: i64,
+}
+
+const SOURCE: &str = r#"
+pub fn object() {
+    #{}
+}
+
+pub fn store(object, player) {
+    object.player = player;
+}
+
+pub fn load(object) {
+    object.player.health
+}
+"#;
+
+#[test]
+fn escaped_reference() -> Result<()> {
+    let mut module = Module::new();
+    module.ty::<Player>()?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(module)?;
+
+    let mut sources = Sources::new();
+
+    sources.insert(Source::new("main.rn", SOURCE)?)?;
+
+    let unit = prepare(&mut sources).with_context(&context).build()?;
+    let mut vm = Vm::new(Arc::new(context.runtime()?), Arc::new(unit));
+
+    let object = vm.call(["object"], ())?;
+
+    let mut player = Player { health: 100 };
+    vm.call(["store"], (object.clone(), &mut player))?;
+
+    let error = vm
+        .call(["load"], (object,))
+        .expect_err("reference should have escaped");
+
+    // The original access error is kept, followed by where the reference was
+    // stored.
+    let message = error.to_string();
+    assert!(message.contains("Cannot read"), "{message}");
+    assert!(message.contains("reference to `"), "{message}");
+    assert!(message.contains("Player` taken at main.rn:7:"), "{message}");
+    assert!(message.contains("escaped its scope"), "{message}");
+
+    let mut out = termcolor::Buffer::no_color();
+    error.emit(&mut out, &sources)?;
+    let out = String::from_utf8(out.into_inner())?;
+
+    assert!(out.contains("Reference taken here"), "{out}");
+    assert!(out.contains("object.player = player"), "{out}");
+    Ok(())
+}
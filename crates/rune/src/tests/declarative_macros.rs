@@ -0,0 +1,285 @@
+prelude!();
+
+use diagnostics::{Diagnostic, FatalDiagnosticKind};
+use ErrorKind::*;
+
+/// Run the `main` function of the given source, as opposed to running it as
+/// a script.
+fn run_main<T>(source: &str) -> Result<T>
+where
+    T: FromValue,
+{
+    let context = Context::with_default_modules()?;
+    run(&context, source, (), false)
+}
+
+#[test]
+fn swap() {
+    let out: (i64, i64) = eval(
+        r#"
+        macro swap($a:expr, $b:expr) {
+            let tmp = $a;
+            $a = $b;
+            $b = tmp;
+        }
+
+        let a = 1;
+        let b = 2;
+        swap!(a, b);
+        (a, b)
+        "#,
+    );
+
+    assert_eq!(out, (2, 1));
+}
+
+#[test]
+fn used_before_definition() -> Result<()> {
+    let out: i64 = run_main(
+        r#"
+        mod util {
+            pub macro double($e:expr) {
+                $e * 2
+            }
+        }
+
+        pub fn main() {
+            util::double!(triple!(3))
+        }
+
+        macro triple($e:expr) {
+            $e * 3
+        }
+        "#,
+    )?;
+
+    assert_eq!(out, 18);
+    Ok(())
+}
+
+#[test]
+fn expr_precedence() {
+    let out: i64 = eval(
+        r#"
+        macro square($e:expr) {
+            $e * $e
+        }
+
+        square!(1 + 2)
+        "#,
+    );
+
+    assert_eq!(out, 9);
+}
+
+#[test]
+fn repetition() {
+    let out: (i64, i64, i64) = eval(
+        r#"
+        macro sum($($e:expr),*) {
+            0 $(+ $e)*
+        }
+
+        macro count($($t:tt)+) {
+            0 $(+ { let _ = stringify!($t); 1 })+
+        }
+
+        (sum!(), sum!(1, 2 * 3, 4), count!(a (b c) [d] "e"))
+        "#,
+    );
+
+    assert_eq!(out, (0, 11, 4));
+}
+
+#[test]
+fn ident_lit_and_tt() -> Result<()> {
+    let out: (i64, String, i64) = run_main(
+        r#"
+        macro make_fn($name:ident, $value:lit) {
+            fn $name() {
+                $value
+            }
+        }
+
+        macro first($a:tt $($rest:tt)*) {
+            $a
+        }
+
+        macro object($($key:ident = $value:expr),*) {
+            #{ $($key: $value),* }
+        }
+
+        make_fn!(number, -42);
+        make_fn!(string, "hello");
+
+        pub fn main() {
+            let o = object!(a = 1, b = first!((2 + 3) 4 5));
+            (number(), string(), o.a + o.b)
+        }
+        "#,
+    )?;
+
+    assert_eq!(out, (-42, String::from("hello"), 6));
+    Ok(())
+}
+
+#[test]
+fn hygiene() {
+    let out: (i64, i64, i64) = eval(
+        r#"
+        macro swap($a:expr, $b:expr) {
+            let tmp = $a;
+            $a = $b;
+            $b = tmp;
+        }
+
+        macro add_hundred($e:expr) {
+            let n = 100;
+            $e + n
+        }
+
+        let tmp = 1;
+        let other = 2;
+        swap!(tmp, other);
+
+        let n = 1;
+        (tmp, other, add_hundred!(n))
+        "#,
+    );
+
+    assert_eq!(out, (2, 1, 101));
+}
+
+#[test]
+fn hygiene_object_fields() {
+    let out: (i64, i64) = eval(
+        r#"
+        macro make($e:expr) {
+            let value = $e;
+            let double = value * 2;
+            #{ value, double: double }
+        }
+
+        let o = make!(1);
+        (o.value, o.double)
+        "#,
+    );
+
+    assert_eq!(out, (1, 2));
+}
+
+#[test]
+fn hygiene_struct_fields() {
+    let out: (i64, i64, i64) = eval(
+        r#"
+        struct Point { x, y }
+
+        macro double($e:expr) {
+            let Point { x, y: other } = $e;
+            let y = other * 2;
+            Point { x, y }
+        }
+
+        let x = 10;
+        let p = double!(Point { x: 1, y: 2 });
+        (p.x, p.y, x)
+        "#,
+    );
+
+    assert_eq!(out, (1, 4, 10));
+}
+
+#[test]
+fn pattern_mismatch() {
+    assert_errors! {
+        r#"
+        macro pair($a:expr => $b:expr) { ($a, $b) }
+        pair!(1, 2)
+        "#,
+        _,
+        ExpectedSyntax { .. }
+    };
+
+    assert_errors! {
+        r#"
+        macro one($a:ident) { $a }
+        one!(a b)
+        "#,
+        _,
+        Custom { error } => {
+            assert_eq!(error.to_string(), "no macro pattern expects this token");
+        }
+    };
+}
+
+#[test]
+fn bad_definition() {
+    assert_errors! {
+        r#"macro bad($a:block) { $a }"#,
+        _,
+        Custom { error } => {
+            assert_eq!(
+                error.to_string(),
+                "expected fragment specifier `expr`, `ident`, `lit`, `literal`, or `tt`"
+            );
+        }
+    };
+
+    assert_errors! {
+        r#"
+        macro bad($a:expr) { $b }
+        bad!(1)
+        "#,
+        _,
+        Custom { error } => {
+            assert_eq!(error.to_string(), "unknown macro variable `$b`");
+        }
+    };
+}
+
+#[test]
+fn recursion_limit() -> Result<()> {
+    let mut sources = sources! {
+        entry => {
+            macro forever($e:expr) {
+                forever!($e)
+            }
+
+            pub fn main() {
+                forever!(1)
+            }
+        }
+    };
+
+    let mut options = Options::default();
+    options.parse_option("max-macro-depth=8")?;
+
+    let mut diagnostics = Diagnostics::new();
+
+    let result = prepare(&mut sources)
+        .with_diagnostics(&mut diagnostics)
+        .with_options(&options)
+        .build();
+
+    assert!(result.is_err());
+
+    let error = diagnostics
+        .diagnostics()
+        .iter()
+        .find_map(|d| match d {
+            Diagnostic::Fatal(error) => Some(error),
+            _ => None,
+        })
+        .expect("expected an error");
+
+    let FatalDiagnosticKind::CompileError(error) = error.kind() else {
+        panic!("expected compile error, got {:?}", error.kind());
+    };
+
+    assert!(
+        matches!(error.kind(), MaxMacroRecursion { depth: 8, max: 8 }),
+        "{error:?}"
+    );
+
+    Ok(())
+}
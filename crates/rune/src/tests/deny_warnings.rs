@@ -0,0 +1,106 @@
+prelude!();
+
+use crate::diagnostics::{Diagnostic, FatalDiagnosticKind};
+
+static SOURCE: &str = r#"
+pub fn main() {
+    let unused = 1;
+    `hello`
+}
+"#;
+
+fn build(options: &Options, diagnostics: &mut Diagnostics) -> bool {
+    let mut sources = Sources::new();
+    sources
+        .insert(Source::new("main", SOURCE).unwrap())
+        .unwrap();
+
+    prepare(&mut sources)
+        .with_options(options)
+        .with_diagnostics(diagnostics)
+        .build()
+        .is_ok()
+}
+
+#[test]
+fn denied_warning_fails_build() {
+    let mut options = Options::default();
+    options.deny("unused-variable").unwrap();
+
+    let mut diagnostics = Diagnostics::new();
+    assert!(!build(&options, &mut diagnostics));
+    assert!(diagnostics.has_error());
+
+    let denied = diagnostics
+        .diagnostics()
+        .iter()
+        .filter_map(|d| match d {
+            Diagnostic::Fatal(f) => match f.kind() {
+                FatalDiagnosticKind::DeniedWarning(w) => Some(w.name()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(denied, ["unused-variable"]);
+
+    // The warning which wasn't denied is still reported as a warning.
+    assert!(diagnostics.has_warning());
+}
+
+#[test]
+fn undenied_warning_passes_build() {
+    let mut options = Options::default();
+    options.deny("unreachable-code").unwrap();
+
+    let mut diagnostics = Diagnostics::new();
+    assert!(build(&options, &mut diagnostics));
+    assert!(!diagnostics.has_error());
+    assert!(diagnostics.has_warning());
+}
+
+#[test]
+fn denied_without_warnings() {
+    let mut options = Options::default();
+    options
+        .parse_option("deny=template-without-expansions")
+        .unwrap();
+
+    let mut diagnostics = Diagnostics::without_warnings();
+    assert!(!build(&options, &mut diagnostics));
+    assert!(diagnostics.has_error());
+    assert!(!diagnostics.has_warning());
+}
+
+#[test]
+fn warnings_as_errors() {
+    let mut options = Options::default();
+    options.parse_option("warnings-as-errors").unwrap();
+
+    let mut diagnostics = Diagnostics::new();
+    assert!(!build(&options, &mut diagnostics));
+    assert!(!diagnostics.has_warning());
+    assert_eq!(diagnostics.diagnostics().len(), 2);
+}
+
+#[test]
+fn unknown_warning_name() {
+    let mut options = Options::default();
+    assert!(options.deny("unused-variables").is_err());
+    assert!(options.parse_option("deny=nope").is_err());
+    assert!(options.parse_option("deny").is_err());
+}
+
+#[test]
+fn deny_option_lists_every_warning() {
+    use crate::diagnostics::WarningDiagnosticKind;
+
+    let option = Options::available()
+        .iter()
+        .find(|option| option.key == "deny")
+        .unwrap();
+
+    let names = option.options.split(", ").collect::<Vec<_>>();
+    assert_eq!(names, WarningDiagnosticKind::NAMES);
+}
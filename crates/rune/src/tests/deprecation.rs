@@ -1,5 +1,3 @@
-#![cfg(feature = "doc")]
-
 prelude!();
 
 use std::sync::Arc;
@@ -10,6 +8,12 @@ use crate::diagnostics::Diagnostic;
 
 use self::diagnostics::{RuntimeWarningDiagnosticKind, WarningDiagnosticKind};
 
+/// Replaced by `abc::new_function`.
+#[rune::function(deprecated = "use new_function() instead")]
+fn old_function() -> i64 {
+    1
+}
+
 fn create_context() -> Result<Context> {
     #[derive(Debug, rune::Any)]
     #[rune(item = ::abc)]
@@ -38,6 +42,13 @@ fn create_context() -> Result<Context> {
         .associated_function("test", |_this: &TestStruct| 1)?
         .deprecated("Deprecated associated fn")?;
 
+    module.function_meta(old_function)?;
+
+    module
+        .function("builder", || 1)
+        .deprecated("Deprecated through builder")?
+        .build()?;
+
     let mut context = Context::with_default_modules()?;
     context.install(module)?;
     Ok(context)
@@ -139,3 +150,58 @@ async fn test_deprecation_warnings_async() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_deprecated_function_meta() -> Result<()> {
+    let context = create_context()?;
+
+    assert_warnings! {
+        context = &context,
+        "abc::old_function()",
+        span!(0, 17),
+        WarningDiagnosticKind::UsedDeprecated { message, .. } => {
+            assert_eq!(message, "use new_function() instead");
+        }
+    };
+
+    Ok(())
+}
+
+#[test]
+fn test_deprecated_builder() -> Result<()> {
+    let context = create_context()?;
+
+    assert_warnings! {
+        context = &context,
+        "abc::builder() + abc::abc()",
+        _,
+        WarningDiagnosticKind::UsedDeprecated { message, .. } => {
+            assert_eq!(message, "Deprecated through builder");
+        },
+        WarningDiagnosticKind::UsedDeprecated { message, .. } => {
+            assert_eq!(message, "Deprecated function");
+        }
+    };
+
+    Ok(())
+}
+
+#[test]
+fn test_deprecations_disabled() -> Result<()> {
+    let context = create_context()?;
+
+    let mut sources = crate::tests::sources("pub fn main() { abc::old_function() + abc::abc() }");
+    let mut diagnostics = Diagnostics::new();
+
+    let mut options = Options::default();
+    options.deprecations(false);
+
+    let _ = rune::prepare(&mut sources)
+        .with_context(&context)
+        .with_diagnostics(&mut diagnostics)
+        .with_options(&options)
+        .build()?;
+
+    assert!(!diagnostics.has_warning());
+    Ok(())
+}
@@ -0,0 +1,108 @@
+prelude!();
+
+use serde_json::Value as Json;
+
+/// Build the given source and emit its diagnostics as JSON.
+fn emit_json(source: &str) -> Result<Vec<Json>> {
+    let context = Context::with_default_modules()?;
+
+    let mut sources = Sources::new();
+    sources.insert(Source::new("entry", source)?)?;
+
+    let mut diagnostics = Diagnostics::new();
+
+    let _ = prepare(&mut sources)
+        .with_context(&context)
+        .with_diagnostics(&mut diagnostics)
+        .build();
+
+    let mut out = Vec::new();
+    diagnostics.emit_json(&mut out, &sources)?;
+    parse_lines(out)
+}
+
+fn parse_lines(out: Vec<u8>) -> Result<Vec<Json>> {
+    let out = String::from_utf8(out)?;
+    let mut values = Vec::new();
+
+    for line in out.lines() {
+        values.push(serde_json::from_str(line)?);
+    }
+
+    Ok(values)
+}
+
+/// Assert that the given value is a span following the documented schema.
+fn assert_span(span: &Json, start: usize, end: usize, line: u64, column: u64) {
+    assert_eq!(span["name"], "entry");
+    assert!(span["path"].is_null());
+    assert_eq!(span["start"], start);
+    assert_eq!(span["end"], end);
+    assert_eq!(span["line_start"], line);
+    assert_eq!(span["column_start"], column);
+    assert_eq!(span["line_end"], line);
+    assert_eq!(span["column_end"], column + (end - start) as u64);
+    assert!(span["message"].is_string());
+}
+
+#[test]
+fn error() -> Result<()> {
+    let source = "pub fn main() {\n    missing()\n}\n";
+    let start = source.find("missing").unwrap();
+
+    let values = emit_json(source)?;
+    let [value] = &values[..] else {
+        panic!("expected one diagnostic, got {values:?}");
+    };
+
+    assert_eq!(value["severity"], "error");
+    assert!(value["message"].as_str().unwrap().contains("missing"));
+    assert_span(&value["span"], start, start + 7, 2, 5);
+    assert!(value["labels"].is_array());
+    assert!(value["notes"].is_array());
+    Ok(())
+}
+
+#[test]
+fn warning() -> Result<()> {
+    let source = "pub fn main() {\n    let unused = 1;\n}\n";
+    let start = source.find("unused").unwrap();
+
+    let values = emit_json(source)?;
+    let [value] = &values[..] else {
+        panic!("expected one diagnostic, got {values:?}");
+    };
+
+    assert_eq!(value["severity"], "warning");
+    assert_eq!(value["message"], "Warning");
+    assert_span(&value["span"], start, start + 6, 2, 9);
+    assert_eq!(value["labels"], serde_json::json!([]));
+    assert_eq!(value["notes"].as_array().map(Vec::len), Some(1));
+    Ok(())
+}
+
+#[test]
+fn vm_error() -> Result<()> {
+    let context = Context::with_default_modules()?;
+    let runtime = Arc::new(context.runtime()?);
+
+    let source = "pub fn main() {\n    panic!(\"oh no\")\n}\n";
+
+    let mut sources = Sources::new();
+    sources.insert(Source::new("entry", source)?)?;
+
+    let unit = prepare(&mut sources).with_context(&context).build()?;
+    let mut vm = Vm::new(runtime, Arc::new(unit));
+
+    let error = vm.call(["main"], ()).unwrap_err();
+
+    let mut out = Vec::new();
+    error.emit_json(&mut out, &sources)?;
+    let values = parse_lines(out)?;
+
+    let value = &values[0];
+    assert_eq!(value["severity"], "error");
+    assert_eq!(value["message"], "Panicked: oh no");
+    assert_eq!(value["span"]["line_start"], 2);
+    Ok(())
+}
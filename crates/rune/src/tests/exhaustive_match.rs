@@ -0,0 +1,197 @@
+prelude!();
+
+use rune::diagnostics::{Diagnostic, FatalDiagnosticKind};
+use ErrorKind::*;
+
+#[test]
+fn missing_variants() {
+    assert_errors! {
+        r#"
+        enum Color { Red, Green, Blue(value), Custom { r, g, b } }
+
+        match Color::Red {
+            Color::Red => 1,
+            Color::Blue(..) => 2,
+        }
+        "#,
+        span!(77, 168),
+        MatchMissingVariants { item, variants } => {
+            assert_eq!(item.to_string(), "Color");
+            assert_eq!(variants.len(), 2);
+            assert_eq!(variants[0].as_ref(), "Green");
+            assert_eq!(variants[1].as_ref(), "Custom");
+        }
+    };
+}
+
+#[test]
+fn partial_patterns_are_missing() {
+    assert_errors! {
+        r#"
+        enum Shape { Circle(radius), Rect { w, h } }
+
+        match Shape::Circle(1) {
+            Shape::Circle(0) => 1,
+            Shape::Circle(r) if r > 10 => 2,
+            Shape::Rect { w, h } => w * h,
+        }
+        "#,
+        span!(63, 220),
+        MatchMissingVariants { variants, .. } => {
+            assert_eq!(variants.len(), 1);
+            assert_eq!(variants[0].as_ref(), "Circle");
+        }
+    };
+}
+
+#[test]
+fn exhaustive() -> Result<()> {
+    let value: i64 = rune! {
+        enum Shape { Circle(radius), Rect { w, h }, Empty }
+
+        fn area(shape) {
+            match shape {
+                Shape::Circle(r) => r * r * 3,
+                Shape::Rect { w, h } => w * h,
+                Shape::Empty => 0,
+            }
+        }
+
+        area(Shape::Circle(2)) + area(Shape::Rect { w: 2, h: 3 }) + area(Shape::Empty)
+    };
+
+    assert_eq!(value, 18);
+    Ok(())
+}
+
+#[test]
+fn wildcard_suppresses() -> Result<()> {
+    let mut diagnostics = Default::default();
+
+    let source = r#"
+    enum Color { Red, Green, Blue }
+
+    let a = match Color::Red {
+        Color::Red => 1,
+        _ => 2,
+    };
+
+    let b = match Color::Green {
+        Color::Green => 1,
+        _other => 2,
+    };
+
+    a + b
+    "#;
+
+    crate::tests::compile_helper(source, &mut diagnostics)?;
+    assert!(!diagnostics.has_error(), "{diagnostics:?}");
+    Ok(())
+}
+
+#[test]
+fn unknown_type_is_not_checked() -> Result<()> {
+    let mut diagnostics = Default::default();
+
+    let source = r#"
+    enum Color { Red, Green, Blue }
+    struct Point { x, y }
+
+    let a = match 1 {
+        1 => Color::Red,
+        2 => Color::Green,
+    };
+
+    let point = Point { x: 1, y: 2 };
+
+    let b = match point {
+        Point { x, y } => x + y,
+    };
+
+    match a {
+        Color::Red => b,
+        2 => 0,
+    }
+    "#;
+
+    crate::tests::compile_helper(source, &mut diagnostics)?;
+    assert!(!diagnostics.has_error(), "{diagnostics:?}");
+    Ok(())
+}
+
+#[test]
+fn unreachable_branches() {
+    assert_warnings! {
+        r#"
+        enum Color { Red, Green, Blue }
+
+        match Color::Red {
+            Color::Red => 1,
+            _ => 2,
+            Color::Green => 3,
+        }
+        "#,
+        span!(130, 147),
+        WarningDiagnosticKind::UnreachableCode { cause: span!(110, 111), .. },
+    };
+}
+
+#[test]
+fn external_enum() -> Result<()> {
+    #[derive(Debug, Any, Clone, Copy)]
+    enum External {
+        First,
+        Second,
+        Third,
+    }
+
+    let mut module = Module::new();
+    module.ty::<External>()?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(module)?;
+
+    let source = r#"
+    pub fn main(value) {
+        match value {
+            External::First => 1,
+            External::Third => 3,
+        }
+    }
+    "#;
+
+    let mut diagnostics = Diagnostics::new();
+    let result = crate::tests::compile_helper_with_context(&context, source, &mut diagnostics);
+    assert!(result.is_err());
+
+    let Some(Diagnostic::Fatal(error)) = diagnostics.diagnostics().first() else {
+        panic!("expected a fatal diagnostic: {diagnostics:?}");
+    };
+
+    let FatalDiagnosticKind::CompileError(error) = error.kind() else {
+        panic!("expected a compile error: {error:?}");
+    };
+
+    let MatchMissingVariants { item, variants } = error.kind() else {
+        panic!("expected missing variants: {error:?}");
+    };
+
+    assert_eq!(item.to_string(), "External");
+    assert_eq!(variants.len(), 1);
+    assert_eq!(variants[0].as_ref(), "Second");
+
+    let source = r#"
+    pub fn main(value) {
+        match value {
+            External::First => 1,
+            External::Second => 2,
+            External::Third => 3,
+        }
+    }
+    "#;
+
+    let mut diagnostics = Diagnostics::new();
+    crate::tests::compile_helper_with_context(&context, source, &mut diagnostics)?;
+    assert!(!diagnostics.has_error());
+    Ok(())
+}
@@ -0,0 +1,143 @@
+//! Tests for registering concrete instantiations of generic types under their
+//! own names.
+
+prelude!();
+
+use rune::compile::Named;
+use rune::runtime::{MaybeTypeOf, ToValue, TypeOf};
+
+#[derive(Any)]
+#[rune(item = ::grid)]
+struct Grid<T>
+where
+    T: 'static + TryClone + Named + FromValue + ToValue + MaybeTypeOf + TypeOf,
+{
+    #[rune(get, set)]
+    value: T,
+}
+
+impl<T> Grid<T>
+where
+    T: 'static + TryClone + Copy + Named + FromValue + ToValue + MaybeTypeOf + TypeOf,
+{
+    fn new(value: T) -> Self {
+        Self { value }
+    }
+
+    fn get(&self) -> T {
+        self.value
+    }
+}
+
+fn make_module() -> Result<Module, ContextError> {
+    let mut module = Module::with_crate("grid")?;
+
+    module.ty_with_name::<Grid<f64>>("FloatGrid")?;
+    module
+        .function("new", Grid::<f64>::new)
+        .build_associated::<Grid<f64>>()?;
+    module.associated_function("get", Grid::<f64>::get)?;
+
+    module.ty_with_name::<Grid<i64>>("IntGrid")?;
+    module
+        .function("new", Grid::<i64>::new)
+        .build_associated::<Grid<i64>>()?;
+    module.associated_function("get", Grid::<i64>::get)?;
+    Ok(module)
+}
+
+#[test]
+fn distinct_hashes() {
+    assert_ne!(Grid::<f64>::HASH, Grid::<i64>::HASH);
+}
+
+#[test]
+fn use_named_instantiation() {
+    let m = make_module().expect("failed to make module");
+
+    let value: f64 = rune_n! {
+        mod m,
+        (),
+        use grid::FloatGrid;
+
+        pub fn main() {
+            let grid = FloatGrid::new(1.5);
+            grid.value = grid.value + 1.0;
+            grid.get()
+        }
+    };
+
+    assert_eq!(value, 2.5);
+
+    let value: i64 = rune_n! {
+        mod m,
+        (),
+        pub fn main() { grid::IntGrid::new(2).get() }
+    };
+
+    assert_eq!(value, 2);
+}
+
+#[test]
+fn is_distinguishes_instantiations() {
+    let m = make_module().expect("failed to make module");
+
+    let float_grid = Grid::<f64>::new(1.0);
+
+    let out: (bool, bool, bool) = rune_n! {
+        mod m,
+        (float_grid,),
+        use grid::{FloatGrid, IntGrid};
+
+        pub fn main(g) {
+            (g is FloatGrid, g is IntGrid, g is grid::Grid::<f64>)
+        }
+    };
+
+    assert_eq!(out, (true, false, true));
+
+    let int_grid = Grid::<i64>::new(1);
+
+    let out: (bool, bool) = rune_n! {
+        mod m,
+        (int_grid,),
+        pub fn main(g) { (g is grid::FloatGrid, g is grid::IntGrid) }
+    };
+
+    assert_eq!(out, (false, true));
+}
+
+#[test]
+fn named_instantiation_is_installed_once() -> Result<()> {
+    let mut context = Context::new();
+    context.install(make_module()?)?;
+
+    for hash in [Grid::<f64>::HASH, Grid::<i64>::HASH] {
+        assert_eq!(context.lookup_meta_by_hash(hash).len(), 1);
+    }
+
+    let new = Hash::associated_function(Grid::<f64>::HASH, "new");
+    assert_eq!(context.lookup_meta_by_hash(new).len(), 1);
+
+    #[cfg(feature = "doc")]
+    assert_eq!(
+        context
+            .associated(Grid::<f64>::HASH)
+            .filter(|hash| *hash == new)
+            .count(),
+        1
+    );
+
+    // Each name refers to a single instantiation.
+    let float_grid = context
+        .lookup_meta(&rune::item!(::grid::FloatGrid))
+        .unwrap();
+    assert_eq!(
+        float_grid.map(|meta| meta.hash).collect::<Vec<_>>(),
+        [Grid::<f64>::HASH]
+    );
+
+    let grid = context.lookup_meta(&rune::item!(::grid::Grid)).unwrap();
+    assert_eq!(grid.count(), 2);
+    Ok(())
+}
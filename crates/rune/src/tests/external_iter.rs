@@ -0,0 +1,152 @@
+prelude!();
+
+use core::cell::Cell;
+
+use rust_alloc::rc::Rc;
+
+use rune::alloc::limit;
+use rune::runtime::Iterator;
+
+/// The memory limit scripts are run under.
+const LIMIT: usize = 1 << 20;
+
+/// A native range of numbers, which records the lowest amount of remaining
+/// memory observed while it's being iterated over.
+#[derive(Any)]
+struct Numbers {
+    len: i64,
+    lowest: Rc<Cell<usize>>,
+}
+
+#[rune::function(instance, protocol = INTO_ITER)]
+fn numbers_iter(this: Ref<Numbers>) -> VmResult<Iterator> {
+    let lowest = this.lowest.clone();
+
+    let iter = (0..this.len).inspect(move |_| {
+        lowest.set(lowest.get().min(limit::get()));
+    });
+
+    VmResult::Ok(vm_try!(Iterator::from_native(iter)))
+}
+
+/// A native collection which can be mutated from scripts.
+#[derive(Any)]
+struct Collection {
+    values: Vec<i64>,
+}
+
+#[rune::function(keep, instance)]
+fn iter(this: Ref<Collection>) -> VmResult<Iterator> {
+    let len = this.values.len();
+    let iter = (0..len).map(move |n| this.values[n]);
+    VmResult::Ok(vm_try!(Iterator::from_native(iter)))
+}
+
+#[rune::function(instance, protocol = INTO_ITER)]
+fn collection_iter(this: Ref<Collection>) -> VmResult<Iterator> {
+    iter(this)
+}
+
+#[rune::function(instance)]
+fn push(this: &mut Collection, value: i64) {
+    this.values.push(value);
+}
+
+fn vm() -> Result<Vm> {
+    let mut m = Module::new();
+    m.ty::<Numbers>()?;
+    m.function_meta(numbers_iter)?;
+    m.ty::<Collection>()?;
+    m.function_meta(iter__meta)?;
+    m.function_meta(collection_iter)?;
+    m.function_meta(push)?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(m)?;
+
+    let mut sources = sources! {
+        entry => {
+            pub fn sum(values) {
+                let sum = 0;
+
+                for n in values {
+                    sum += n;
+                }
+
+                sum
+            }
+
+            pub fn odd(values) {
+                values.iter().filter(|n| n % 2 == 1).collect::<Vec>()
+            }
+
+            pub fn extend(values) {
+                for n in values {
+                    values.push(n);
+                }
+            }
+        }
+    };
+
+    let unit = prepare(&mut sources).with_context(&context).build()?;
+    Ok(Vm::new(Arc::new(context.runtime()?), Arc::new(unit)))
+}
+
+/// Sum `0..len` using a native iterator, returning the sum and the peak amount
+/// of memory used while iterating.
+fn sum_with_peak(vm: &mut Vm, len: i64) -> Result<(i64, usize)> {
+    let lowest = Rc::new(Cell::new(LIMIT));
+
+    let numbers = Numbers {
+        len,
+        lowest: lowest.clone(),
+    };
+
+    let sum = limit::with(LIMIT, || vm.call(["sum"], (numbers,))).call()?;
+    Ok((from_value(sum)?, LIMIT - lowest.get()))
+}
+
+#[test]
+fn native_iter_memory_is_flat() -> Result<()> {
+    let mut vm = vm()?;
+
+    let (sum, small) = sum_with_peak(&mut vm, 1_000)?;
+    assert_eq!(sum, 999 * 1_000 / 2);
+
+    // Buffering a million values would far exceed the memory limit.
+    let (sum, large) = sum_with_peak(&mut vm, 1_000_000)?;
+    assert_eq!(sum, 999_999 * 1_000_000 / 2);
+
+    assert!(
+        large <= small,
+        "peak memory grew from {small} to {large} bytes"
+    );
+    Ok(())
+}
+
+#[test]
+fn native_iter_adapters() -> Result<()> {
+    let mut vm = vm()?;
+
+    let collection = Collection {
+        values: vec![1, 2, 3, 4, 5],
+    };
+
+    let odd: Vec<i64> = from_value(vm.call(["odd"], (collection,))?)?;
+    assert_eq!(odd, [1, 3, 5]);
+    Ok(())
+}
+
+#[test]
+fn native_iter_guards_collection() -> Result<()> {
+    let mut vm = vm()?;
+
+    let collection = Collection {
+        values: vec![1, 2, 3],
+    };
+
+    // The collection is borrowed for as long as it's being iterated over.
+    let error = vm.call(["extend"], (collection,)).unwrap_err();
+    assert!(error.to_string().contains("Cannot write"), "{error}");
+    Ok(())
+}
@@ -111,3 +111,143 @@ fn enum_match() {
     test!(Aborted, Errored);
     test!(Errored, Success);
 }
+
+#[derive(Debug, Any, Clone, Copy, PartialEq)]
+enum Shape {
+    #[rune(constructor)]
+    Point {
+        #[rune(get, set)]
+        x: i64,
+        #[rune(get, set)]
+        y: i64,
+    },
+    #[rune(constructor)]
+    Circle(#[rune(get, set)] i64),
+    Empty,
+}
+
+impl Shape {
+    #[rune::function(protocol = DEBUG_FMT)]
+    fn debug_fmt(&self, f: &mut Formatter) -> VmResult<()> {
+        match self {
+            Shape::Point { x, y } => vm_write!(f, "Shape::Point {{ x: {x}, y: {y} }}"),
+            Shape::Circle(r) => vm_write!(f, "Shape::Circle({r})"),
+            Shape::Empty => vm_write!(f, "Shape::Empty"),
+        }
+    }
+}
+
+fn make_shape_module() -> Result<Module, ContextError> {
+    let mut module = Module::new();
+    module.ty::<Shape>()?;
+    module.function_meta(Shape::debug_fmt)?;
+    Ok(module)
+}
+
+#[test]
+fn enum_struct_variant_match() {
+    let m = make_shape_module().expect("failed make module");
+
+    let e = Shape::Point { x: 3, y: 4 };
+
+    let n: i64 = rune_n! {
+        mod m,
+        (e,),
+        pub fn main(v) {
+            match v {
+                Shape::Point { x, y } => x * 10 + y,
+                Shape::Circle(r) => r,
+                _ => 0,
+            }
+        }
+    };
+
+    assert_eq!(n, 34);
+
+    let e = Shape::Circle(7);
+
+    let n: i64 = rune_n! {
+        mod m,
+        (e,),
+        pub fn main(v) {
+            match v {
+                Shape::Point { x, y } => x * 10 + y,
+                Shape::Circle(r) => r,
+                _ => 0,
+            }
+        }
+    };
+
+    assert_eq!(n, 7);
+}
+
+#[test]
+fn enum_variant_field_set() {
+    let m = make_shape_module().expect("failed make module");
+
+    let e = Shape::Point { x: 3, y: 4 };
+
+    let out: Shape = rune_n! {
+        mod m,
+        (e,),
+        pub fn main(v) {
+            v.x = 30;
+            v.y = v.y + 10;
+            v
+        }
+    };
+
+    assert_eq!(out, Shape::Point { x: 30, y: 14 });
+
+    let e = Shape::Circle(7);
+
+    let out: Shape = rune_n! {
+        mod m,
+        (e,),
+        pub fn main(v) {
+            v.0 = 8;
+            v
+        }
+    };
+
+    assert_eq!(out, Shape::Circle(8));
+
+    let e = Shape::Empty;
+
+    let mut context = Context::with_default_modules().unwrap();
+    context.install(&m).unwrap();
+
+    let result = run::<Value>(&context, "pub fn main(v) { v.x = 1; }", (e,), false);
+    assert!(result.is_err());
+}
+
+#[test]
+fn enum_variant_constructor_and_debug() {
+    let m = make_shape_module().expect("failed make module");
+
+    let out: Shape = rune_n! {
+        mod m,
+        (),
+        pub fn main() { Shape::Point { y: 2, x: 1 } }
+    };
+
+    assert_eq!(out, Shape::Point { x: 1, y: 2 });
+
+    let e = Shape::Point { x: 1, y: 2 };
+
+    let s: String = rune_n! {
+        mod m,
+        (e,),
+        pub fn main(v) { v.x = 5; format!("{v:?}") }
+    };
+
+    assert_eq!(s, "Shape::Point { x: 5, y: 2 }");
+
+    let s: String = rune_n! {
+        mod m,
+        (Shape::Empty,),
+        pub fn main(e) { format!("{:?} {:?}", Shape::Circle(3), e) }
+    };
+
+    assert_eq!(s, "Shape::Circle(3) Shape::Empty");
+}
@@ -388,3 +388,117 @@ fn eq_struct() -> Result<()> {
     test_case!([==], PARTIAL_EQ, 2, 1, false);
     Ok(())
 }
+
+#[test]
+fn derived_ops() -> Result<()> {
+    use core::ops::{Add, AddAssign, Mul, Neg, Sub};
+
+    #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Any)]
+    #[rune(ops(add, sub, mul = f64, neg, add_assign, partial_eq, partial_cmp, cmp))]
+    struct Vec3 {
+        x: f64,
+        y: f64,
+        z: f64,
+    }
+
+    impl Vec3 {
+        fn new(x: f64, y: f64, z: f64) -> Self {
+            Self { x, y, z }
+        }
+    }
+
+    impl Add for Vec3 {
+        type Output = Self;
+
+        fn add(self, rhs: Self) -> Self {
+            Self::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+        }
+    }
+
+    impl Sub for Vec3 {
+        type Output = Self;
+
+        fn sub(self, rhs: Self) -> Self {
+            Self::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+        }
+    }
+
+    impl Mul<f64> for Vec3 {
+        type Output = Self;
+
+        fn mul(self, rhs: f64) -> Self {
+            Self::new(self.x * rhs, self.y * rhs, self.z * rhs)
+        }
+    }
+
+    impl Neg for Vec3 {
+        type Output = Self;
+
+        fn neg(self) -> Self {
+            Self::new(-self.x, -self.y, -self.z)
+        }
+    }
+
+    impl AddAssign for Vec3 {
+        fn add_assign(&mut self, rhs: Self) {
+            *self = *self + rhs;
+        }
+    }
+
+    impl Eq for Vec3 {}
+
+    impl Ord for Vec3 {
+        fn cmp(&self, other: &Self) -> Ordering {
+            self.x
+                .total_cmp(&other.x)
+                .then(self.y.total_cmp(&other.y))
+                .then(self.z.total_cmp(&other.z))
+        }
+    }
+
+    let mut m = Module::new();
+    m.ty::<Vec3>()?;
+    m.function("new", Vec3::new).build_associated::<Vec3>()?;
+
+    let ((sum, scaled, negated, assigned), (eq, lt)): ((Vec3, Vec3, Vec3, Vec3), (bool, bool)) = rune_n! {
+        mod m,
+        (),
+        pub fn main() {
+            let a = Vec3::new(1.0, 2.0, 3.0);
+            let b = Vec3::new(4.0, 5.0, 6.0);
+
+            let c = Vec3::new(1.0, 2.0, 3.0);
+            c += b;
+
+            ((a + b, a * 2.0, -(b - a), c), (a == Vec3::new(1.0, 2.0, 3.0), a < b))
+        }
+    };
+
+    assert_eq!(sum, Vec3::new(5.0, 7.0, 9.0));
+    assert_eq!(scaled, Vec3::new(2.0, 4.0, 6.0));
+    assert_eq!(negated, Vec3::new(-3.0, -3.0, -3.0));
+    assert_eq!(assigned, Vec3::new(5.0, 7.0, 9.0));
+    assert!(eq);
+    assert!(lt);
+
+    let sorted: Vec<Vec3> = rune_n! {
+        mod m,
+        (),
+        pub fn main() {
+            let values = [Vec3::new(3.0, 0.0, 0.0), Vec3::new(1.0, 2.0, 0.0), Vec3::new(1.0, 1.0, 0.0)];
+            values.sort();
+            values
+        }
+    };
+
+    assert_eq!(
+        sorted,
+        [
+            Vec3::new(1.0, 1.0, 0.0),
+            Vec3::new(1.0, 2.0, 0.0),
+            Vec3::new(3.0, 0.0, 0.0)
+        ]
+    );
+
+    Ok(())
+}
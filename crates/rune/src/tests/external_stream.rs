@@ -0,0 +1,136 @@
+prelude!();
+
+use core::cell::Cell;
+
+use futures_util::{stream, StreamExt};
+use rust_alloc::rc::Rc;
+
+use rune::runtime::{budget, Stream, VmError};
+
+use crate::runtime::VmHaltInfo;
+
+/// A mock source of ten chunks, which counts how many chunks it has produced.
+#[derive(Any)]
+struct Source {
+    produced: Rc<Cell<usize>>,
+}
+
+/// A native stream of ten chunks, which counts how many chunks it has
+/// produced.
+fn counted(produced: Rc<Cell<usize>>) -> Result<Stream, rune::alloc::Error> {
+    let chunks = stream::iter(0..10i64).map(move |n| {
+        produced.set(produced.get() + 1);
+        Ok::<_, VmError>(n)
+    });
+
+    Stream::from_native(chunks)
+}
+
+#[rune::function(instance)]
+async fn chunks(this: Ref<Source>) -> VmResult<Stream> {
+    VmResult::Ok(vm_try!(counted(this.produced.clone())))
+}
+
+fn vm() -> Result<Vm> {
+    let mut m = Module::new();
+    m.ty::<Source>()?;
+    m.function_meta(chunks)?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(m)?;
+
+    let mut sources = sources! {
+        entry => {
+            pub async fn collect(source) {
+                let stream = source.chunks().await;
+                let out = [];
+
+                while let Some(chunk) = stream.next().await {
+                    out.push(chunk);
+                }
+
+                (out, stream.next().await)
+            }
+
+            pub async fn take(source, n) {
+                let stream = source.chunks().await;
+                let out = [];
+
+                while let Some(chunk) = stream.next().await {
+                    out.push(chunk);
+
+                    if out.len() == n {
+                        break;
+                    }
+                }
+
+                out
+            }
+        }
+    };
+
+    let unit = prepare(&mut sources).with_context(&context).build()?;
+    Ok(Vm::new(Arc::new(context.runtime()?), Arc::new(unit)))
+}
+
+#[test]
+fn native_stream_order_and_completion() -> Result<()> {
+    let mut vm = vm()?;
+    let produced = Rc::new(Cell::new(0));
+
+    let source = Source {
+        produced: produced.clone(),
+    };
+
+    let output = block_on(vm.async_call(["collect"], (source,)))?;
+    let (chunks, after) = from_value::<(Vec<i64>, Option<i64>)>(output)?;
+
+    assert_eq!(chunks, (0..10).collect::<Vec<i64>>());
+    assert_eq!(after, None);
+    assert_eq!(produced.get(), 10);
+    Ok(())
+}
+
+#[test]
+fn native_stream_back_pressure() -> Result<()> {
+    let mut vm = vm()?;
+    let produced = Rc::new(Cell::new(0));
+
+    let source = Source {
+        produced: produced.clone(),
+    };
+
+    let output = block_on(vm.async_call(["take"], (source, 3i64)))?;
+    assert_eq!(from_value::<Vec<i64>>(output)?, [0, 1, 2]);
+
+    // Chunks are only produced as they are asked for.
+    assert_eq!(produced.get(), 3);
+    Ok(())
+}
+
+#[test]
+fn native_stream_budget() -> Result<()> {
+    let produced = Rc::new(Cell::new(0));
+    let mut stream = counted(produced.clone())?;
+
+    let (first, second, third) = block_on(budget::with(2, async {
+        let first = stream.next().await.into_result();
+        let second = stream.next().await.into_result();
+        let third = stream.next().await.into_result();
+        (first, second, third)
+    }));
+
+    assert_eq!(first?.map(from_value::<i64>).transpose()?, Some(0));
+    assert_eq!(second?.map(from_value::<i64>).transpose()?, Some(1));
+
+    assert!(matches!(
+        third.unwrap_err().into_kind(),
+        VmErrorKind::Halted {
+            halt: VmHaltInfo::Limited
+        }
+    ));
+
+    // The native stream isn't polled once the budget is exhausted.
+    assert_eq!(produced.get(), 2);
+    Ok(())
+}
@@ -0,0 +1,136 @@
+prelude!();
+
+use std::fmt::Write;
+
+/// Build a script which formats every value with every spec, alongside what
+/// `std::format!` produces for the same spec and value.
+macro_rules! matrix {
+    ($($fmt:literal => [$($value:expr),* $(,)?]),* $(,)?) => {{
+        let mut source = std::string::String::from("pub fn main() { [");
+        let mut expected = std::vec::Vec::new();
+
+        $($(
+            write!(source, "format!({:?}, {}),", $fmt, stringify!($value)).unwrap();
+            expected.push((concat!($fmt, " ", stringify!($value)), format!($fmt, $value)));
+        )*)*
+
+        source.push_str("] }");
+        (source, expected)
+    }};
+}
+
+#[test]
+fn format_spec_matches_std() -> Result<()> {
+    let (source, expected) = matrix! {
+        "{:8}" => [42i64, -42i64, 42u64, 1.5f64, 'x', true, "ab"],
+        "{:<8}" => [42i64, -42i64, 1.5f64, 'x', true, "ab"],
+        "{:>8}" => [42i64, -42i64, 1.5f64, 'x', true, "ab"],
+        "{:^8}" => [42i64, -42i64, 1.5f64, 'x', false, "ab"],
+        "{:*^9}" => [-12i64, "ab", 'x'],
+        "{:-<6}" => [7i64, "ab"],
+        "{:<<6}" => [7i64, "ab"],
+        "{:>>6}" => [7i64, "ab"],
+        "{:+}" => [0i64, 5i64, -5i64, 5u64, 1.5f64, -1.5f64],
+        "{:+8}" => [5i64, -5i64, 2.5f64],
+        "{:08}" => [42i64, -42i64, 42u64, 1.5f64, -1.5f64],
+        "{:+08}" => [42i64, -42i64, 2.5f64],
+        "{:<08}" => [7i64, -7i64],
+        "{:^08}" => [7i64],
+        "{:.2}" => [3.14159f64, -3.14159f64, 2.0f64, "abcdef", 'x', true],
+        "{:.0}" => [2.5f64, 3.5f64, 0.4f64, "abc"],
+        "{:>8.2}" => [3.14159f64, -3.14159f64, "abcdef"],
+        "{:08.2}" => [3.14159f64, -3.14159f64],
+        "{:+.1}" => [1.25f64, -0.0f64],
+        "{:10.3}" => [1.0f64, "abcdef"],
+        "{:x}" => [255i64, -1i64, 255u64, 0i64],
+        "{:X}" => [255i64, -1i64, 3054u64],
+        "{:#x}" => [255i64, 0u64],
+        "{:#X}" => [255i64],
+        "{:#010x}" => [255i64, 255u64],
+        "{:>#10x}" => [255i64],
+        "{:+x}" => [-1i64, 10i64],
+        "{:o}" => [8i64, 511u64, -1i64],
+        "{:#o}" => [8i64],
+        "{:b}" => [5i64, 5u64, -1i64],
+        "{:#b}" => [5i64],
+        "{:#010b}" => [5i64],
+        "{:e}" => [1234i64, -1200i64, 0i64, 7u64, 1234.5f64, -0.5f64, 1.0f64],
+        "{:E}" => [1234i64, 0.00012f64],
+        "{:.2e}" => [1234i64, 1235i64, 1245i64, 1225i64, 1999i64, 1234.5678f64],
+        "{:.5e}" => [1234i64, 1.5f64],
+        "{:.0e}" => [15i64, 25i64, 2.5f64],
+        "{:10.3e}" => [1234i64, 1234.5f64],
+        "{:+e}" => [12i64, 1.5f64],
+        "{:012e}" => [-1234i64, -1234.5f64],
+        "{:?}" => [42i64, -42i64, 1.5f64, true, "a\"b", 'x'],
+        "{:8?}" => [42i64, -42i64, 1.5f64, true],
+        "{:>8?}" => [42i64, false],
+        "{:08?}" => [-42i64, 1.5f64],
+        "{:.3?}" => [1.5f64],
+        "{:+?}" => [1i64],
+    };
+
+    let mut sources = Sources::new();
+    sources.insert(Source::new("main", source)?)?;
+
+    let context = Context::with_default_modules()?;
+    let runtime = Arc::new(context.runtime()?);
+    let unit = prepare(&mut sources).with_context(&context).build()?;
+    let mut vm = Vm::new(runtime, Arc::new(unit));
+
+    let actual: Vec<String> = from_value(vm.call(["main"], ())?)?;
+    assert_eq!(actual.len(), expected.len());
+
+    for (actual, (case, expected)) in actual.iter().zip(&expected) {
+        assert_eq!(actual, expected, "{case}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn format_spec_display_protocol() {
+    let out: String = rune! {
+        struct Point { x, y }
+
+        impl Point {
+            #[protocol(display_fmt)]
+            fn fmt(self, f) {
+                f.write_str(format!("({}, {})", self.x, self.y))
+            }
+        }
+
+        let p = Point { x: 1, y: 2 };
+        format!("[{:>10}|{:<10}|{:^10}|{:*^11}|{}]", p, p, p, p, p)
+    };
+
+    assert_eq!(out, "[    (1, 2)|(1, 2)    |  (1, 2)  |**(1, 2)***|(1, 2)]");
+}
+
+#[test]
+fn format_spec_template() {
+    let out: String = rune! {
+        let x = 3.14159;
+        let name = "rune";
+        format!("{x:>8.2}|{name:^8}|{x:+.1}")
+    };
+
+    assert_eq!(out, "    3.14|  rune  |+3.1");
+}
+
+/// Like `std`, debug formatting of strings and characters ignores the width,
+/// fill and alignment of the spec.
+#[test]
+fn format_spec_debug_padding() {
+    let out: String = rune! {
+        format!("[{:8?}|{:>5?}|{:*^9?}]", "ab", 'x', "ab")
+    };
+
+    assert_eq!(out, r#"["ab"|'x'|"ab"]"#);
+
+    let out: String = rune! {
+        format!("[{:>8?}|{:^5?}]", "a\"b", '\n')
+    };
+
+    assert_eq!(out, r#"["a\"b"|'\n']"#);
+}
@@ -44,3 +44,135 @@ fn test_getter_setter() -> Result<()> {
     output.into_unit().unwrap();
     Ok(())
 }
+
+#[derive(Any, Debug, Default)]
+#[rune(index)]
+struct Vec2(#[rune(get, set, copy)] f64, #[rune(get, set, copy)] f64);
+
+fn vec2_vm(source: &str) -> Result<Vm> {
+    let mut module = Module::new();
+    module.ty::<Vec2>()?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(module)?;
+
+    let mut sources = Sources::new();
+    sources.insert(Source::memory(source)?)?;
+
+    let unit = prepare(&mut sources).with_context(&context).build()?;
+    Ok(Vm::new(Arc::new(context.runtime()?), Arc::new(unit)))
+}
+
+#[test]
+fn test_tuple_getter_setter() -> Result<()> {
+    let mut vm = vec2_vm(
+        r#"
+        pub fn main(v) {
+            v.0 = v.0 + 1.0;
+            v.1 = v.0 * 2.0;
+            v.0 + v.1
+        }
+        "#,
+    )?;
+
+    let mut v = Vec2(1.0, 2.0);
+    let output = vm.call(["main"], (&mut v,))?;
+
+    assert_eq!(v.0, 2.0);
+    assert_eq!(v.1, 4.0);
+    assert_eq!(f64::from_value(output)?, 6.0);
+    Ok(())
+}
+
+#[test]
+fn test_tuple_index() -> Result<()> {
+    let mut vm = vec2_vm(
+        r#"
+        pub fn main(v) {
+            v[0] = v[1] + 1.0;
+            v[1] = 10.0;
+        }
+        "#,
+    )?;
+
+    let mut v = Vec2(1.0, 2.0);
+    vm.call(["main"], (&mut v,))?;
+
+    assert_eq!(v.0, 3.0);
+    assert_eq!(v.1, 10.0);
+    Ok(())
+}
+
+#[test]
+fn test_tuple_index_missing() -> Result<()> {
+    for source in [
+        "pub fn main(v) { v[2] }",
+        "pub fn main(v) { v[2] = 1.0 }",
+        "pub fn main(v) { v.2 }",
+        "pub fn main(v) { v.2 = 1.0 }",
+    ] {
+        let mut vm = vec2_vm(source)?;
+        let error = vm.call(["main"], (Vec2(1.0, 2.0),)).unwrap_err();
+
+        match error.into_kind() {
+            VmErrorKind::MissingTupleIndex { target, index } => {
+                assert!(target.ends_with("Vec2"), "{target}");
+                assert_eq!(index, 2);
+            }
+            actual => panic!("{source}: expected missing tuple index, got {actual:?}"),
+        }
+    }
+
+    Ok(())
+}
+
+/// A type which supports index operations, but hasn't opted into tuple index
+/// operations.
+#[derive(Any, Debug, Default)]
+struct Indexed(i64);
+
+#[test]
+fn test_index_is_not_tuple_index() -> Result<()> {
+    let mut module = Module::new();
+    module.ty::<Indexed>()?;
+    module.associated_function(&Protocol::INDEX_GET, |this: &Indexed, _: usize| this.0)?;
+    module.associated_function(
+        &Protocol::INDEX_SET,
+        |this: &mut Indexed, _: usize, value: i64| {
+            this.0 = value;
+        },
+    )?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(module)?;
+
+    let mut sources = sources! {
+        entry => {
+            pub fn index(v) { v[0] }
+            pub fn get(v) { v.0 }
+            pub fn set(v) { v.0 = 1; }
+        }
+    };
+
+    let unit = prepare(&mut sources).with_context(&context).build()?;
+    let mut vm = Vm::new(Arc::new(context.runtime()?), Arc::new(unit));
+
+    let output = vm.call(["index"], (Indexed(42),))?;
+    assert_eq!(i64::from_value(output)?, 42);
+
+    let error = vm.call(["get"], (Indexed(42),)).unwrap_err();
+
+    assert!(matches!(
+        error.into_kind(),
+        VmErrorKind::UnsupportedTupleIndexGet { index: 0, .. }
+    ));
+
+    let error = vm.call(["set"], (Indexed(42),)).unwrap_err();
+
+    assert!(matches!(
+        error.into_kind(),
+        VmErrorKind::UnsupportedTupleIndexSet { .. }
+    ));
+
+    Ok(())
+}
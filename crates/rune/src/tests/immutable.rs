@@ -0,0 +1,141 @@
+prelude!();
+
+#[derive(Any, TryClone)]
+#[rune(constructor, immutable)]
+struct Color {
+    #[rune(get, set, copy)]
+    value: i64,
+}
+
+impl Color {
+    #[rune::function]
+    fn bump(&mut self) {
+        self.value += 1;
+    }
+}
+
+#[derive(Any, TryClone)]
+#[rune(constructor)]
+struct Point {
+    #[rune(get, set, copy)]
+    value: i64,
+}
+
+impl Point {
+    #[rune::function]
+    fn bump(&mut self) {
+        self.value += 1;
+    }
+}
+
+fn context() -> Result<Context> {
+    let mut m = Module::new();
+    m.ty::<Color>()?;
+    m.function_meta(Color::bump)?;
+    m.ty::<Point>()?;
+    m.function_meta(Point::bump)?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(m)?;
+    Ok(context)
+}
+
+/// The same script is run against a marked and an unmarked type.
+fn script(ty: &str) -> String {
+    format!(
+        r#"
+        fn mutate(v) {{
+            v.value = 10;
+        }}
+
+        pub fn main() {{
+            let a = {ty} {{ value: 1 }};
+            let b = a;
+            b.value = 2;
+            mutate(b);
+            b.bump();
+
+            let c = {ty} {{ value: 0 }};
+            c = b;
+            c.value = 20;
+
+            (a.value, b.value, c.value)
+        }}
+        "#
+    )
+}
+
+#[test]
+fn immutable_value_semantics() -> Result<()> {
+    let context = context()?;
+
+    let output: (i64, i64, i64) = run(&context, &script("Color"), (), false)?;
+    assert_eq!(output, (1, 3, 20));
+
+    let output: (i64, i64, i64) = run(&context, &script("Point"), (), false)?;
+    assert_eq!(output, (20, 20, 20));
+    Ok(())
+}
+
+#[test]
+fn immutable_native_arguments() -> Result<()> {
+    let context = context()?;
+
+    let output: i64 = run(
+        &context,
+        r#"
+        pub fn main(color) {
+            let other = color;
+            other.value = 5;
+            color.value
+        }
+        "#,
+        (Color { value: 1 },),
+        false,
+    )?;
+
+    assert_eq!(output, 1);
+    Ok(())
+}
+
+#[test]
+fn immutable_escape_points() -> Result<()> {
+    let context = context()?;
+
+    let output: Vec<i64> = run(
+        &context,
+        r#"
+        struct Holder { color }
+
+        fn get(holder) {
+            holder.color
+        }
+
+        pub fn main() {
+            let a = Color { value: 1 };
+
+            let t = (a, a);
+            t.0.bump();
+
+            let v = [a, a];
+            v[0].bump();
+
+            let o = #{ color: a };
+            o.color.bump();
+
+            let h = Holder { color: a };
+            get(h).bump();
+
+            let f = || a.value;
+            a.value = 10;
+
+            [t.0.value, t.1.value, v[0].value, v[1].value, o.color.value, h.color.value, f(), a.value]
+        }
+        "#,
+        (),
+        false,
+    )?;
+
+    assert_eq!(output, [2, 1, 2, 1, 2, 1, 1, 10]);
+    Ok(())
+}
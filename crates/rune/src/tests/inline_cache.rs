@@ -0,0 +1,105 @@
+prelude!();
+
+use rune::Unit;
+
+fn build(context: &Context, sources: &mut Sources) -> Result<Arc<Unit>> {
+    Ok(Arc::new(prepare(sources).with_context(context).build()?))
+}
+
+#[test]
+fn alternating_receivers() {
+    let out: Vec<i64> = rune! {
+        struct A;
+        struct B;
+
+        impl A {
+            fn value(self) { 1 }
+        }
+
+        impl B {
+            fn value(self) { 2 }
+        }
+
+        let values = [A, B, A, A, B, B];
+        let out = [];
+
+        for v in values {
+            out.push(v.value());
+        }
+
+        out
+    };
+
+    assert_eq!(out, [1, 2, 1, 1, 2, 2]);
+}
+
+#[test]
+fn alternating_native_and_script_receivers() {
+    let out: Vec<i64> = rune! {
+        struct A;
+
+        impl A {
+            fn len(self) { 10 }
+        }
+
+        let values = [A, [1, 2], A, "abc", [1]];
+        let out = [];
+
+        for v in values {
+            out.push(v.len());
+        }
+
+        out
+    };
+
+    assert_eq!(out, [10, 2, 10, 3, 1]);
+}
+
+#[test]
+fn swap_unit_invalidates_cache() -> Result<()> {
+    let context = Context::with_default_modules()?;
+    let runtime = Arc::new(context.runtime()?);
+
+    let old = build(
+        &context,
+        &mut sources! {
+            entry => {
+                struct A;
+
+                impl A {
+                    fn value(self) { 1 }
+                }
+
+                pub fn main() { A.value() }
+            }
+        },
+    )?;
+
+    let new = build(
+        &context,
+        &mut sources! {
+            entry => {
+                struct A;
+
+                fn padding() { 0 }
+
+                impl A {
+                    fn value(self) { padding() + 2 }
+                }
+
+                pub fn main() { A.value() }
+            }
+        },
+    )?;
+
+    let mut vm = Vm::new(runtime, old);
+    assert_eq!(from_value::<i64>(vm.call(["main"], ())?)?, 1);
+    assert_eq!(from_value::<i64>(vm.call(["main"], ())?)?, 1);
+
+    assert!(vm.try_swap_unit(new.clone()).is_ok());
+    assert_eq!(from_value::<i64>(vm.call(["main"], ())?)?, 2);
+
+    *vm.unit_mut() = new;
+    assert_eq!(from_value::<i64>(vm.call(["main"], ())?)?, 2);
+    Ok(())
+}
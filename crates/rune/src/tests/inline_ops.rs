@@ -0,0 +1,298 @@
+//! Compare the specialized operations the virtual machine uses for inline
+//! values against the generic implementations, both for a table of edge cases
+//! and for randomly generated combinations of values.
+
+prelude!();
+
+use core::cmp::Ordering;
+
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+use crate::runtime::Inline;
+
+/// The number of randomly generated pairs of values to test.
+const ITERATIONS: usize = 10_000;
+
+/// Primitive values which are represented inline.
+#[derive(Debug, Clone, Copy)]
+enum Prim {
+    Unit,
+    Bool(bool),
+    Char(char),
+    Signed(i64),
+    Unsigned(u64),
+    Float(f64),
+}
+
+impl Prim {
+    fn to_value(self) -> Result<Value> {
+        Ok(match self {
+            Prim::Unit => rune::to_value(())?,
+            Prim::Bool(value) => rune::to_value(value)?,
+            Prim::Char(value) => rune::to_value(value)?,
+            Prim::Signed(value) => rune::to_value(value)?,
+            Prim::Unsigned(value) => rune::to_value(value)?,
+            Prim::Float(value) => rune::to_value(value)?,
+        })
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        Some(match *value.as_inline()? {
+            Inline::Unit => Prim::Unit,
+            Inline::Bool(value) => Prim::Bool(value),
+            Inline::Char(value) => Prim::Char(value),
+            Inline::Signed(value) => Prim::Signed(value),
+            Inline::Unsigned(value) => Prim::Unsigned(value),
+            Inline::Float(value) => Prim::Float(value),
+            _ => return None,
+        })
+    }
+
+    /// Test if two values are identical, treating all NaNs as equal.
+    fn same(self, other: Self) -> bool {
+        match (self, other) {
+            (Prim::Unit, Prim::Unit) => true,
+            (Prim::Bool(a), Prim::Bool(b)) => a == b,
+            (Prim::Char(a), Prim::Char(b)) => a == b,
+            (Prim::Signed(a), Prim::Signed(b)) => a == b,
+            (Prim::Unsigned(a), Prim::Unsigned(b)) => a == b,
+            (Prim::Float(a), Prim::Float(b)) => {
+                a.to_bits() == b.to_bits() || (a.is_nan() && b.is_nan())
+            }
+            _ => false,
+        }
+    }
+}
+
+fn values() -> Vec<Prim> {
+    vec![
+        Prim::Unit,
+        Prim::Bool(true),
+        Prim::Bool(false),
+        Prim::Char('a'),
+        Prim::Char('b'),
+        Prim::Signed(0),
+        Prim::Signed(-1),
+        Prim::Signed(1),
+        Prim::Signed(i64::MIN),
+        Prim::Signed(i64::MAX),
+        Prim::Unsigned(0),
+        Prim::Unsigned(1),
+        Prim::Unsigned(u64::MAX),
+        Prim::Float(0.0),
+        Prim::Float(-0.0),
+        Prim::Float(1.5),
+        Prim::Float(f64::NAN),
+        Prim::Float(f64::INFINITY),
+    ]
+}
+
+/// Generate a random value of the given kind, biased towards small numbers
+/// and edge cases.
+fn random_of(rng: &mut SmallRng, kind: usize) -> Prim {
+    match kind {
+        0 => Prim::Unit,
+        1 => Prim::Bool(rng.gen()),
+        2 => Prim::Char(rng.gen()),
+        3 => Prim::Signed(match rng.gen_range(0..4) {
+            0 => rng.gen_range(-8..8),
+            1 => [i64::MIN, i64::MAX, i64::MIN + 1, i64::MAX - 1][rng.gen_range(0..4)],
+            _ => rng.gen(),
+        }),
+        4 => Prim::Unsigned(match rng.gen_range(0..4) {
+            0 => rng.gen_range(0..8),
+            1 => [u64::MAX, u64::MAX - 1, i64::MAX as u64][rng.gen_range(0..3)],
+            _ => rng.gen(),
+        }),
+        _ => Prim::Float(match rng.gen_range(0..4) {
+            0 => rng.gen_range(-8.0..8.0),
+            1 => [f64::NAN, f64::INFINITY, f64::NEG_INFINITY, 0.0, -0.0][rng.gen_range(0..5)],
+            _ => f64::from_bits(rng.gen()),
+        }),
+    }
+}
+
+/// Generate a random pair of values, which are of the same kind half of the
+/// time so that the specialized paths are exercised.
+fn random_pair(rng: &mut SmallRng) -> (Prim, Prim) {
+    let kind = rng.gen_range(0..6);
+    let a = random_of(rng, kind);
+
+    let kind = if rng.gen() { kind } else { rng.gen_range(0..6) };
+    let b = random_of(rng, kind);
+    (a, b)
+}
+
+/// The expected result of an arithmetic operation, where `None` indicates
+/// that the operation errors.
+fn arithmetic(name: &str, a: Prim, b: Prim) -> Option<Prim> {
+    fn integer<T>(value: Prim) -> Option<T>
+    where
+        T: TryFrom<i64> + TryFrom<u64>,
+    {
+        match value {
+            Prim::Signed(value) => value.try_into().ok(),
+            Prim::Unsigned(value) => value.try_into().ok(),
+            _ => None,
+        }
+    }
+
+    Some(match a {
+        Prim::Signed(a) => {
+            let b = integer::<i64>(b)?;
+
+            Prim::Signed(match name {
+                "add" => a.checked_add(b)?,
+                "sub" => a.checked_sub(b)?,
+                "mul" => a.checked_mul(b)?,
+                "div" => a.checked_div(b)?,
+                _ => a.checked_rem(b)?,
+            })
+        }
+        Prim::Unsigned(a) => {
+            let b = integer::<u64>(b)?;
+
+            Prim::Unsigned(match name {
+                "add" => a.checked_add(b)?,
+                "sub" => a.checked_sub(b)?,
+                "mul" => a.checked_mul(b)?,
+                "div" => a.checked_div(b)?,
+                _ => a.checked_rem(b)?,
+            })
+        }
+        Prim::Float(a) => {
+            let Prim::Float(b) = b else {
+                return None;
+            };
+
+            Prim::Float(match name {
+                "add" => a + b,
+                "sub" => a - b,
+                "mul" => a * b,
+                "div" => a / b,
+                _ => a % b,
+            })
+        }
+        _ => return None,
+    })
+}
+
+fn vm() -> Result<Vm> {
+    let context = Context::with_default_modules()?;
+
+    let mut sources = sources! {
+        entry => {
+            pub fn lt(a, b) { a < b }
+            pub fn le(a, b) { a <= b }
+            pub fn gt(a, b) { a > b }
+            pub fn ge(a, b) { a >= b }
+            pub fn eq(a, b) { a == b }
+            pub fn ne(a, b) { a != b }
+            pub fn add(a, b) { a + b }
+            pub fn sub(a, b) { a - b }
+            pub fn mul(a, b) { a * b }
+            pub fn div(a, b) { a / b }
+            pub fn rem(a, b) { a % b }
+        }
+    };
+
+    Ok(crate::tests::vm(
+        &context,
+        &mut sources,
+        &mut Diagnostics::new(),
+        false,
+    )?)
+}
+
+fn check_comparisons(vm: &mut Vm, a: Prim, b: Prim) -> Result<()> {
+    let cmp_ops: [(&str, fn(Ordering) -> bool); 4] = [
+        ("lt", |o| o.is_lt()),
+        ("le", |o| o.is_le()),
+        ("gt", |o| o.is_gt()),
+        ("ge", |o| o.is_ge()),
+    ];
+
+    let (a, b) = (a.to_value()?, b.to_value()?);
+
+    let expected = Value::partial_cmp(&a, &b).into_result().ok();
+
+    for (name, test) in cmp_ops {
+        let actual = vm.call([name], (a.clone(), b.clone())).ok();
+        let actual = actual.map(from_value::<bool>).transpose()?;
+        let expected = expected.map(|o| o.is_some_and(test));
+        assert_eq!(actual, expected, "{a:?} {name} {b:?}");
+    }
+
+    let expected = Value::partial_eq(&a, &b).into_result().ok();
+
+    for (name, negate) in [("eq", false), ("ne", true)] {
+        let actual = vm.call([name], (a.clone(), b.clone())).ok();
+        let actual = actual.map(from_value::<bool>).transpose()?;
+        let expected = expected.map(|eq| eq != negate);
+        assert_eq!(actual, expected, "{a:?} {name} {b:?}");
+    }
+
+    Ok(())
+}
+
+fn check_arithmetic(vm: &mut Vm, a: Prim, b: Prim) -> Result<()> {
+    for name in ["add", "sub", "mul", "div", "rem"] {
+        let expected = arithmetic(name, a, b);
+
+        let actual = match vm.call([name], (a.to_value()?, b.to_value()?)) {
+            Ok(value) => Some(Prim::from_value(&value).context("Expected inline value")?),
+            Err(..) => None,
+        };
+
+        let same = match (actual, expected) {
+            (Some(actual), Some(expected)) => actual.same(expected),
+            (actual, expected) => actual.is_none() && expected.is_none(),
+        };
+
+        assert!(same, "{a:?} {name} {b:?}: {actual:?} != {expected:?}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn specialized_ops_agree_with_generic() -> Result<()> {
+    let mut vm = vm()?;
+    let values = values();
+
+    for &a in &values {
+        for &b in &values {
+            check_comparisons(&mut vm, a, b)?;
+            check_arithmetic(&mut vm, a, b)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn random_comparisons_agree_with_generic() -> Result<()> {
+    let mut vm = vm()?;
+    let mut rng = SmallRng::seed_from_u64(0x5eed);
+
+    for _ in 0..ITERATIONS {
+        let (a, b) = random_pair(&mut rng);
+        check_comparisons(&mut vm, a, b)?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn random_arithmetic_agrees_with_reference() -> Result<()> {
+    let mut vm = vm()?;
+    let mut rng = SmallRng::seed_from_u64(0x5eed);
+
+    for _ in 0..ITERATIONS {
+        let (a, b) = random_pair(&mut rng);
+        check_arithmetic(&mut vm, a, b)?;
+    }
+
+    Ok(())
+}
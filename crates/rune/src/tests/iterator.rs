@@ -147,3 +147,70 @@ fn test_flat_map() {
 
     assert_eq!(actual, expected);
 }
+
+#[test]
+fn test_try_fold_stops_at_first_error() {
+    let (result, calls): (Result<i64, i64>, Vec<i64>) = rune! {
+        let calls = [];
+
+        let result = [1, 2, 3, 4, 5].iter().map(|n| n * 10).try_fold(0, |acc, n| {
+            calls.push(n);
+
+            if n == 30 {
+                return Err(n);
+            }
+
+            Ok(acc + n)
+        });
+
+        (result, calls)
+    };
+
+    assert_eq!(result, Err(30));
+    assert_eq!(calls, [10, 20, 30]);
+}
+
+#[test]
+fn test_try_for_each_stops_at_first_error() {
+    let (result, seen): (Result<(), i64>, Vec<i64>) = rune! {
+        let seen = [];
+
+        let result = (0..10).iter().filter(|n| n % 2 == 0).try_for_each(|n| {
+            seen.push(n);
+
+            if n >= 4 {
+                return Err(n);
+            }
+
+            Ok(())
+        });
+
+        (result, seen)
+    };
+
+    assert_eq!(result, Err(4));
+    assert_eq!(seen, [0, 2, 4]);
+}
+
+#[test]
+fn test_collect_result_stops_at_first_error() {
+    let (result, calls): (Result<Vec<i64>, i64>, Vec<i64>) = rune! {
+        let calls = [];
+
+        let result = [1, 2, -1, 3].iter().map(|n| {
+            calls.push(n);
+            if n < 0 { Err(n) } else { Ok(n) }
+        }).collect::<Result>();
+
+        (result, calls)
+    };
+
+    assert_eq!(result, Err(-1));
+    assert_eq!(calls, [1, 2, -1]);
+
+    let result: Result<Vec<i64>, i64> = rune! {
+        [1, 2, 3].iter().map(Ok).collect::<Result>()
+    };
+
+    assert_eq!(result, Ok(vec![1, 2, 3]));
+}
@@ -0,0 +1,125 @@
+//! Tests for diagnostics referencing modules which are known but not installed.
+
+prelude!();
+
+use rune::diagnostics::{Diagnostic, FatalDiagnosticKind};
+use rune::termcolor;
+use rune::tests::sources;
+
+#[derive(Any)]
+#[rune(item = ::http)]
+struct Client;
+
+impl Client {
+    #[rune::function(path = Self::new)]
+    fn new() -> Self {
+        Self
+    }
+}
+
+fn http_module() -> Result<Module, ContextError> {
+    let mut m = Module::with_crate("http")?;
+    m.ty::<Client>()?;
+    m.function_meta(Client::new)?;
+    m.function("get", |url: &str| url.len() as i64).build()?;
+    Ok(m)
+}
+
+fn compile(context: &Context, source: &str) -> Result<(ErrorKind, String)> {
+    let mut sources = sources(source);
+    let mut diagnostics = Diagnostics::new();
+
+    let result = rune::prepare(&mut sources)
+        .with_context(context)
+        .with_diagnostics(&mut diagnostics)
+        .build();
+
+    assert!(result.is_err());
+
+    let mut out = termcolor::Buffer::no_color();
+    diagnostics.emit(&mut out, &sources)?;
+    let out = String::from_utf8(out.into_inner())?;
+
+    let Some(Diagnostic::Fatal(fatal)) = diagnostics.into_diagnostics().into_iter().next() else {
+        panic!("expected fatal diagnostic");
+    };
+
+    let FatalDiagnosticKind::CompileError(error) = fatal.into_kind() else {
+        panic!("expected compile error");
+    };
+
+    Ok((error.into_kind(), out))
+}
+
+#[test]
+fn known_module_note() -> Result<()> {
+    let mut context = Context::with_default_modules()?;
+    context.known_module(http_module()?)?;
+
+    let (kind, out) = compile(
+        &context,
+        r#"pub fn main() { http::get("https://example.com") }"#,
+    )?;
+
+    let ErrorKind::MissingKnownItem { item, module } = kind else {
+        panic!("expected missing known item, got {kind:?}");
+    };
+
+    assert_eq!(item, ItemBuf::with_crate_item("http", ["get"])?);
+    assert_eq!(module, ItemBuf::with_crate("http")?);
+
+    assert!(
+        out.contains(
+            "function `http::get` is provided by the `http` module which is not installed in this context"
+        ),
+        "{out}"
+    );
+
+    let (kind, out) = compile(&context, r#"pub fn main() { http::Client::new() }"#)?;
+    assert!(
+        matches!(kind, ErrorKind::MissingKnownItem { .. }),
+        "{kind:?}"
+    );
+
+    assert!(
+        out.contains("function `http::Client::new` is provided by the `http` module"),
+        "{out}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn unknown_module_no_note() -> Result<()> {
+    let mut context = Context::with_default_modules()?;
+    context.known_module(http_module()?)?;
+
+    let (kind, out) = compile(&context, r#"pub fn main() { http::post("x") }"#)?;
+    assert!(
+        matches!(
+            kind,
+            ErrorKind::MissingItem { .. } | ErrorKind::MissingItemParameters { .. }
+        ),
+        "{kind:?}"
+    );
+    assert!(!out.contains("not installed"), "{out}");
+
+    let context = Context::with_default_modules()?;
+    let (kind, _) = compile(&context, r#"pub fn main() { http::get("x") }"#)?;
+    assert!(
+        !matches!(kind, ErrorKind::MissingKnownItem { .. }),
+        "{kind:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn installed_known_module() -> Result<()> {
+    let mut context = Context::with_default_modules()?;
+    context.known_module(http_module()?)?;
+    context.install(http_module()?)?;
+
+    let n: i64 = run(&context, r#"pub fn main() { http::get("abc") }"#, (), false)?;
+    assert_eq!(n, 3);
+    Ok(())
+}
@@ -0,0 +1,150 @@
+prelude!();
+
+use ErrorKind::*;
+
+#[test]
+fn break_out_of_nested_for() {
+    let out: (i64, i64) = rune! {
+        let found = None;
+
+        'outer: for x in 1..10 {
+            for y in 1..10 {
+                if x * y == 12 {
+                    found = Some((x, y));
+                    break 'outer;
+                }
+            }
+        }
+
+        found.unwrap()
+    };
+    assert_eq!(out, (2, 6));
+}
+
+#[test]
+fn break_value_out_of_labeled_loop() {
+    let out: i64 = rune! {
+        let xs = [1, 2, 3];
+        let ys = [4, 5, 6];
+
+        'outer: loop {
+            for x in xs {
+                let n = 0;
+
+                while n < 10 {
+                    for y in ys {
+                        if x * y == 10 {
+                            break 'outer x * 100 + y;
+                        }
+                    }
+
+                    n += 1;
+                }
+            }
+
+            break 0;
+        }
+    };
+    assert_eq!(out, 205);
+}
+
+#[test]
+fn continue_labeled() {
+    let out: i64 = rune! {
+        let count = 0;
+
+        'outer: for x in 0..5 {
+            for y in 0..5 {
+                if y > x {
+                    continue 'outer;
+                }
+
+                count += 1;
+            }
+        }
+
+        count
+    };
+    assert_eq!(out, 15);
+
+    let out: i64 = rune! {
+        let n = 0;
+        let count = 0;
+
+        'outer: while n < 5 {
+            n += 1;
+
+            loop {
+                count += n;
+                continue 'outer;
+            }
+        }
+
+        count
+    };
+    assert_eq!(out, 15);
+}
+
+#[test]
+fn break_labeled_while_produces_unit() {
+    let out: () = rune! {
+        let n = 0;
+
+        'outer: while true {
+            loop {
+                n += 1;
+
+                if n == 3 {
+                    break 'outer;
+                }
+            }
+        }
+    };
+    assert_eq!(out, ());
+}
+
+#[test]
+fn missing_label() {
+    assert_errors! {
+        "'a: loop { break 'b; }",
+        span!(11, 19), MissingLabel { label, label_span } => {
+            assert_eq!(&*label, "b");
+            assert_eq!(label_span, span!(17, 19));
+        }
+    };
+
+    assert_errors! {
+        "'a: for _ in 0..10 { continue 'b; }",
+        span!(21, 32), MissingLabel { label, label_span } => {
+            assert_eq!(&*label, "b");
+            assert_eq!(label_span, span!(30, 32));
+        }
+    };
+}
+
+#[test]
+fn break_value_out_of_for() {
+    assert_errors! {
+        "'outer: for _ in 0..10 { loop { break 'outer 42; } }",
+        span!(32, 47), BreakUnsupportedValue { loop_span } => {
+            assert_eq!(loop_span, span!(0, 52));
+        }
+    };
+}
+
+#[test]
+fn break_value_out_of_while() {
+    assert_errors! {
+        "while true { break 42; }",
+        span!(13, 21), BreakUnsupportedValue { loop_span } => {
+            assert_eq!(loop_span, span!(0, 24));
+        }
+    };
+
+    assert_errors! {
+        "'outer: while true { for _ in 0..10 { break 'outer 42; } }",
+        span!(38, 53), BreakUnsupportedValue { loop_span } => {
+            assert_eq!(loop_span, span!(0, 58));
+        }
+    };
+}
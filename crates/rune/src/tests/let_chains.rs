@@ -0,0 +1,129 @@
+prelude!();
+
+#[test]
+fn if_let_chain() {
+    let out: i64 = rune! {
+        let a = Some(1);
+        let b = Ok(2);
+
+        if true && let Some(x) = a && x > 0 && let Ok(y) = b && y > x {
+            x + y
+        } else {
+            0
+        }
+    };
+    assert_eq!(out, 3);
+
+    let out: i64 = rune! {
+        let a = Some(1);
+        let b = Err(2);
+
+        if let Some(x) = a && let Ok(y) = b {
+            x + y
+        } else {
+            -1
+        }
+    };
+    assert_eq!(out, -1);
+
+    let out: i64 = rune! {
+        let a = Some(1);
+
+        if let Some(x) = a && x > 1 {
+            x
+        } else if let Some(x) = a && x == 1 {
+            x + 10
+        } else {
+            0
+        }
+    };
+    assert_eq!(out, 11);
+}
+
+#[test]
+fn if_let_scrutinee_operators() {
+    let out: bool = rune! {
+        let a = false;
+        let b = true;
+
+        if let true = a || b && b {
+            true
+        } else {
+            false
+        }
+    };
+    assert!(out);
+
+    let out: i64 = rune! {
+        let sum = 0;
+
+        if let r = 0..4 && true {
+            for n in r {
+                sum += n;
+            }
+        }
+
+        sum
+    };
+    assert_eq!(out, 6);
+}
+
+#[test]
+fn later_elements_see_earlier_bindings() {
+    let out: i64 = rune! {
+        let value = Some([1, 2]);
+
+        if let Some(v) = value && let [a, b] = v && let Some(c) = Some(a + b) {
+            c
+        } else {
+            0
+        }
+    };
+    assert_eq!(out, 3);
+}
+
+#[test]
+fn while_let_chain() {
+    let out: i64 = rune! {
+        let values = [1, 2, 3, -1, 4];
+        let it = values.iter();
+        let sum = 0;
+
+        while let Some(v) = it.next() && v > 0 {
+            sum += v;
+        }
+
+        sum
+    };
+    assert_eq!(out, 6);
+}
+
+#[test]
+fn match_guard_let_chain() {
+    let out: Vec<i64> = rune! {
+        fn check(value, other) {
+            match value {
+                Some(x) if let Ok(y) = other && y > 0 => x + y,
+                Some(x) if x > 0 => x * 100,
+                _ => -1,
+            }
+        }
+
+        [check(Some(1), Ok(2)), check(Some(1), Ok(0)), check(Some(1), Err(2)), check(None, Ok(2))]
+    };
+    assert_eq!(out, vec![3, 100, 100, -1]);
+}
+
+#[test]
+fn match_guard_bindings_do_not_leak() {
+    let out: i64 = rune! {
+        let y = 10;
+
+        match Some(1) {
+            Some(x) if let Some(y) = None && y > x => y,
+            Some(x) => x + y,
+            None => 0,
+        }
+    };
+    assert_eq!(out, 11);
+}
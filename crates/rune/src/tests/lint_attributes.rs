@@ -0,0 +1,185 @@
+prelude!();
+
+use crate::diagnostics::{Diagnostic, FatalDiagnosticKind, WarningDiagnosticKind};
+
+fn build(source: &str, diagnostics: &mut Diagnostics) -> bool {
+    build_with(source, diagnostics, &Options::default())
+}
+
+fn build_with(source: &str, diagnostics: &mut Diagnostics, options: &Options) -> bool {
+    let mut sources = Sources::new();
+    sources
+        .insert(Source::new("main", source).unwrap())
+        .unwrap();
+
+    prepare(&mut sources)
+        .with_diagnostics(diagnostics)
+        .with_options(options)
+        .build()
+        .is_ok()
+}
+
+/// Collect the names of all unused variables which were denied.
+fn denied_unused(diagnostics: &Diagnostics) -> Vec<&str> {
+    diagnostics
+        .diagnostics()
+        .iter()
+        .filter_map(|d| match d {
+            Diagnostic::Fatal(f) => match f.kind() {
+                FatalDiagnosticKind::DeniedWarning(w) => match w.kind() {
+                    WarningDiagnosticKind::UnusedVariable { name, .. } => Some(name.as_str()),
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn allow_nested_in_deny() {
+    let mut diagnostics = Diagnostics::new();
+
+    let ok = build(
+        r#"
+        #[deny(unused_variable)]
+        pub fn main() {
+            let outer = 1;
+
+            #[allow(unused_variable)]
+            {
+                let inner = 2;
+            }
+        }
+        "#,
+        &mut diagnostics,
+    );
+
+    assert!(!ok);
+    assert_eq!(denied_unused(&diagnostics), ["outer"]);
+    assert!(!diagnostics.has_warning());
+}
+
+#[test]
+fn allow_function() {
+    let mut diagnostics = Diagnostics::new();
+
+    let ok = build(
+        r#"
+        #[allow(unused_variable)]
+        pub fn main() {
+            let unused = 1;
+        }
+        "#,
+        &mut diagnostics,
+    );
+
+    assert!(ok);
+    assert!(diagnostics.is_empty());
+}
+
+#[test]
+fn warn_overrides_options() -> Result<()> {
+    let mut options = Options::default();
+    options.deny("unused-variable")?;
+
+    let mut sources = sources! {
+        entry => {
+            #[warn(unused_variable)]
+            pub fn main() {
+                let unused = 1;
+            }
+        }
+    };
+
+    let mut diagnostics = Diagnostics::new();
+
+    let ok = prepare(&mut sources)
+        .with_options(&options)
+        .with_diagnostics(&mut diagnostics)
+        .build()
+        .is_ok();
+
+    assert!(ok);
+    assert!(!diagnostics.has_error());
+    assert!(diagnostics.has_warning());
+    Ok(())
+}
+
+#[test]
+fn unknown_lint() {
+    let source = r#"
+    #[allow(not_a_lint)]
+    pub fn main() {
+    }
+    "#;
+
+    let mut diagnostics = Diagnostics::new();
+    assert!(build(source, &mut diagnostics));
+
+    let [Diagnostic::Warning(warning)] = diagnostics.diagnostics() else {
+        panic!("expected one warning, got {:?}", diagnostics.diagnostics());
+    };
+
+    let WarningDiagnosticKind::UnknownLint { span, name } = warning.kind() else {
+        panic!("expected unknown lint, got {:?}", warning.kind());
+    };
+
+    assert_eq!(name, "not_a_lint");
+
+    let start = source.find("#[allow").unwrap();
+    let end = start + "#[allow(not_a_lint)]".len();
+    assert_eq!(span.range(), start..end);
+}
+
+#[test]
+fn v2_lint_attributes() {
+    let mut options = Options::default();
+    options.v2 = true;
+
+    let mut diagnostics = Diagnostics::new();
+
+    let ok = build_with(
+        r#"
+        #[deny(unused_variable)]
+        pub fn main() {
+            let outer = 1;
+
+            #[allow(unused_variable)]
+            {
+                let inner = 2;
+            }
+        }
+        "#,
+        &mut diagnostics,
+        &options,
+    );
+
+    assert!(!ok);
+    assert_eq!(denied_unused(&diagnostics), ["outer"]);
+    assert!(!diagnostics.has_warning());
+
+    let source = r#"
+    #[allow(not_a_lint)]
+    pub fn main() {
+    }
+    "#;
+
+    let mut diagnostics = Diagnostics::new();
+    assert!(build_with(source, &mut diagnostics, &options));
+
+    let [Diagnostic::Warning(warning)] = diagnostics.diagnostics() else {
+        panic!("expected one warning, got {:?}", diagnostics.diagnostics());
+    };
+
+    let WarningDiagnosticKind::UnknownLint { span, name } = warning.kind() else {
+        panic!("expected unknown lint, got {:?}", warning.kind());
+    };
+
+    assert_eq!(name, "not_a_lint");
+
+    let start = source.find("#[allow").unwrap();
+    let end = start + "#[allow(not_a_lint)]".len();
+    assert_eq!(span.range(), start..end);
+}
@@ -0,0 +1,143 @@
+prelude!();
+
+use ast::Spanned;
+use diagnostics::{Diagnostic, FatalDiagnosticKind, WarningDiagnosticKind};
+use macros::quote;
+use parse::Parser;
+use ErrorKind::*;
+
+/// Construct a context with a `check!(value, deprecated, count)` macro, which
+/// warns about its second argument and reports an error if its third argument
+/// is not a number.
+fn context() -> Result<Context> {
+    let mut m = Module::default();
+
+    m.macro_(["check"], |cx, stream| {
+        let mut p = Parser::from_token_stream(stream, cx.input_span());
+        let value = p.parse::<ast::Expr>()?;
+        p.parse::<T![,]>()?;
+        let deprecated = p.parse::<ast::Expr>()?;
+        p.parse::<T![,]>()?;
+        let count = p.parse::<ast::Expr>()?;
+        p.eof()?;
+
+        cx.warning(&deprecated, "second argument is deprecated")?;
+
+        if !matches!(
+            count,
+            ast::Expr::Lit(ast::ExprLit {
+                lit: ast::Lit::Number(..),
+                ..
+            })
+        ) {
+            cx.error(&count, "expected a number")?;
+        }
+
+        Ok(quote!(#value).into_token_stream(cx)?)
+    })?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(m)?;
+    Ok(context)
+}
+
+fn span_of(source: &str, needle: &str) -> std::ops::Range<usize> {
+    let start = source.find(needle).expect("needle in source");
+    start..start + needle.len()
+}
+
+#[test]
+fn macro_warning_and_error() -> Result<()> {
+    let source = r#"
+    pub fn main() {
+        check!(a_value, old_thing, "three")
+    }
+    "#;
+
+    let context = context()?;
+    let mut diagnostics = Diagnostics::new();
+    let result = crate::tests::compile_helper_with_context(&context, source, &mut diagnostics);
+    assert!(result.is_err());
+
+    let [Diagnostic::Warning(warning), Diagnostic::Fatal(error), ..] = diagnostics.diagnostics()
+    else {
+        panic!("expected a warning and an error: {diagnostics:?}");
+    };
+
+    let WarningDiagnosticKind::MacroWarning { span, message } = warning.kind() else {
+        panic!("expected macro warning, got {:?}", warning.kind());
+    };
+
+    assert_eq!(message, "second argument is deprecated");
+    assert_eq!(span.range(), span_of(source, "old_thing"));
+
+    let FatalDiagnosticKind::CompileError(error) = error.kind() else {
+        panic!("expected compile error, got {:?}", error.kind());
+    };
+
+    assert_eq!(error.to_string(), "expected a number");
+    assert_eq!(error.span().range(), span_of(source, "\"three\""));
+    Ok(())
+}
+
+#[test]
+fn macro_warning_only() -> Result<()> {
+    let source = r#"
+    pub fn main() {
+        check!(1, old_thing, 3)
+    }
+    "#;
+
+    let context = context()?;
+    let mut diagnostics = Diagnostics::new();
+    crate::tests::compile_helper_with_context(&context, source, &mut diagnostics)?;
+
+    assert!(!diagnostics.has_error());
+
+    let [Diagnostic::Warning(warning)] = diagnostics.diagnostics() else {
+        panic!("expected one warning: {diagnostics:?}");
+    };
+
+    assert_eq!(warning.span().range(), span_of(source, "old_thing"));
+    Ok(())
+}
+
+#[test]
+fn quoted_tokens_keep_spans() -> Result<()> {
+    let source = r#"
+    pub fn main() {
+        check!(missing, 2, 3)
+    }
+    "#;
+
+    let context = context()?;
+    let mut diagnostics = Diagnostics::new();
+    let result = crate::tests::compile_helper_with_context(&context, source, &mut diagnostics);
+    assert!(result.is_err());
+
+    let error = diagnostics
+        .diagnostics()
+        .iter()
+        .find_map(|d| match d {
+            Diagnostic::Fatal(error) => Some(error),
+            _ => None,
+        })
+        .expect("expected an error");
+
+    assert_eq!(
+        error.span().map(|s| s.range()),
+        Some(span_of(source, "missing"))
+    );
+    Ok(())
+}
+
+#[test]
+fn compile_error() {
+    assert_errors! {
+        r#"pub fn main() { compile_error!("not supported") }"#,
+        span!(16, 47),
+        Custom { error } => {
+            assert_eq!(error.to_string(), "not supported");
+        }
+    };
+}
@@ -45,3 +45,151 @@ fn test_async_moved() {
         }
     )
 }
+
+#[test]
+fn test_closure_moved_clones() {
+    let out: (i64, i64) = rune! {
+        let o = [];
+        let p = o;
+        let a = || o.len();
+        let b = move || o.len();
+        p.push(42);
+        (a(), b())
+    };
+
+    assert_eq!(out, (1, 0));
+
+    let out: (i64, i64) = rune! {
+        struct Counter { value }
+
+        let o = Counter { value: 1 };
+        let p = o;
+        let a = || o.value;
+        let b = move || o.value;
+        p.value = 2;
+        (a(), b())
+    };
+
+    assert_eq!(out, (2, 1));
+}
+
+#[test]
+fn test_async_moved_clones() {
+    let out: (i64, i64) = rune! {
+        let o = [];
+        let p = o;
+        let a = async { o.len() };
+        let b = async move { o.len() };
+        p.push(42);
+        (a.await, b.await)
+    };
+
+    assert_eq!(out, (1, 0));
+}
+
+#[test]
+fn test_closure_moved_uncloneable() {
+    let out: (Option<i64>, Option<i64>) = rune! {
+        let it = [1, 2, 3].iter();
+        let next = move || it.next();
+        (next(), next())
+    };
+
+    assert_eq!(out, (Some(1), Some(2)));
+}
+
+#[test]
+fn test_closure_moved_too_deep() {
+    assert_vm_error!(
+        r#"
+        struct Node { next }
+
+        let node = Node { next: () };
+
+        for _ in 0..1000 {
+            node = Node { next: node };
+        }
+
+        let f = move || node;
+        f()
+        "#,
+        VmErrorKind::CloneDepthExceeded { limit } => {
+            assert_eq!(limit, 256);
+        }
+    );
+}
+
+/// Compile the given source with warnings about closure captures enabled.
+fn compile_with_captures(source: &str, captures: bool) -> Result<Diagnostics> {
+    let mut sources = Sources::new();
+    sources.insert(Source::new("main", source)?)?;
+
+    let mut options = Options::default();
+    options.script(true);
+    options.closure_captures(captures);
+
+    let mut diagnostics = Diagnostics::new();
+
+    crate::prepare(&mut sources)
+        .with_context(&Context::with_default_modules()?)
+        .with_diagnostics(&mut diagnostics)
+        .with_options(&options)
+        .build()?;
+
+    Ok(diagnostics)
+}
+
+/// Get the variables reported as captured by each warning.
+fn captures(diagnostics: Diagnostics) -> Vec<(ast::Span, bool, Vec<String>)> {
+    let mut output = Vec::new();
+
+    for diagnostic in diagnostics.into_diagnostics() {
+        let diagnostics::Diagnostic::Warning(warning) = diagnostic else {
+            panic!("expected warning, but was {diagnostic:?}");
+        };
+
+        let span = ast::Spanned::span(&warning);
+
+        let WarningDiagnosticKind::ClosureCapture {
+            do_move, captures, ..
+        } = warning.into_kind()
+        else {
+            panic!("expected closure capture warning");
+        };
+
+        let names = captures
+            .iter()
+            .map(|(name, _)| name.as_str().to_owned())
+            .collect();
+
+        output.push((span, do_move, names));
+    }
+
+    output
+}
+
+#[test]
+fn test_closure_capture_warning() -> Result<()> {
+    let source = r#"
+    let a = 1;
+    let b = 2;
+    let c = || a + b;
+    let d = move || a;
+    c() + d()
+    "#;
+
+    let diagnostics = compile_with_captures(source, false)?;
+    assert!(!diagnostics.has_warning(), "{diagnostics:?}");
+
+    let diagnostics = compile_with_captures(source, true)?;
+
+    assert_eq!(
+        captures(diagnostics),
+        [
+            (span!(43, 51), false, vec!["a".to_owned(), "b".to_owned()]),
+            (span!(65, 74), true, vec!["a".to_owned()]),
+        ]
+    );
+
+    Ok(())
+}
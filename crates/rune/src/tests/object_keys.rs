@@ -0,0 +1,66 @@
+prelude!();
+
+use rune::alloc::limit;
+use rune::runtime::{Object, ObjectKey};
+
+fn key<'a>(object: &'a Object, name: &str) -> &'a ObjectKey {
+    object
+        .entries()
+        .map(|(key, _)| key)
+        .find(|key| *key == name)
+        .unwrap()
+}
+
+#[test]
+fn literal_keys_are_shared() -> Result<()> {
+    let (a, b): (Object, Object) = rune! {
+        fn make(n) {
+            #{ first: n, second: n + 1 }
+        }
+
+        (make(1), make(2))
+    };
+
+    assert!(key(&a, "first").is_shared());
+    assert!(ObjectKey::ptr_eq(key(&a, "first"), key(&b, "first")));
+    assert!(ObjectKey::ptr_eq(key(&a, "second"), key(&b, "second")));
+    assert!(!ObjectKey::ptr_eq(key(&a, "first"), key(&a, "second")));
+    Ok(())
+}
+
+#[test]
+fn keys_are_interned_across_literals() -> Result<()> {
+    let (a, b): (Object, Object) = rune! {
+        (#{ shared: 1, a: 2 }, #{ shared: 3, b: 4 })
+    };
+
+    assert!(ObjectKey::ptr_eq(key(&a, "shared"), key(&b, "shared")));
+    Ok(())
+}
+
+#[test]
+fn dynamic_keys_are_owned() -> Result<()> {
+    let object: Object = rune! {
+        let object = #{};
+        object.insert("dynamic", 1);
+        object
+    };
+
+    assert!(!key(&object, "dynamic").is_shared());
+    Ok(())
+}
+
+#[test]
+fn shared_keys_are_limited() {
+    assert!(limit::with(0, || ObjectKey::shared("key")).call().is_err());
+
+    let remaining = limit::with(1024, || {
+        let key = ObjectKey::shared("key").unwrap();
+        assert!(limit::get() < 1024);
+        drop(key);
+        limit::get()
+    })
+    .call();
+
+    assert_eq!(remaining, 1024);
+}
@@ -0,0 +1,168 @@
+//! Tests that parsing sources in parallel produces the same output as a
+//! serial build.
+
+prelude!();
+
+use rune::alloc::limit;
+use rune::termcolor::Buffer;
+
+const MODULES: usize = 200;
+
+/// The memory limit to build under when testing that it's respected.
+const LIMIT: usize = 1 << 28;
+
+fn generated() -> Result<Vec<Source>> {
+    let mut sources = Vec::new();
+    let mut main = String::from("pub fn main() {\n    let n = 0;\n");
+
+    for i in 0..MODULES {
+        let source = std::format!(
+            r#"
+            pub fn f{i}(x) {{
+                let values = [x, {i}, x * {i}];
+                let sum = 0;
+
+                for v in values {{
+                    sum += v;
+                }}
+
+                if sum % 2 == 0 {{ sum / 2 }} else {{ sum }}
+            }}
+            "#
+        );
+
+        sources.push(Source::new(std::format!("f{i}"), source)?);
+        main.push_str(&std::format!("    n += f{i}({i});\n"));
+    }
+
+    main.push_str("    n\n}\n");
+    sources.push(Source::new("main", main)?);
+    Ok(sources)
+}
+
+/// Build the given sources, returning a textual representation of the
+/// instructions in the unit and any emitted diagnostics.
+fn build(sources: &[Source], parallel: bool) -> Result<(Vec<String>, String)> {
+    let context = Context::with_default_modules()?;
+    let options = options(parallel)?;
+    let mut s = collect(sources)?;
+
+    let mut diagnostics = Diagnostics::new();
+
+    let unit = rune::prepare(&mut s)
+        .with_context(&context)
+        .with_diagnostics(&mut diagnostics)
+        .with_options(&options)
+        .build();
+
+    let mut out = Buffer::no_color();
+    diagnostics.emit(&mut out, &s)?;
+    let diagnostics = String::from_utf8(out.into_inner())?;
+
+    let instructions = match unit {
+        Ok(unit) => unit
+            .iter_instructions()
+            .map(|(ip, inst)| std::format!("{ip:04}: {inst:?}"))
+            .collect(),
+        Err(..) => Vec::new(),
+    };
+
+    Ok((instructions, diagnostics))
+}
+
+/// Build the given sources under a memory limit, returning how much of it
+/// remains once everything allocated during the build has been dropped.
+fn remaining(sources: &[Source], parallel: bool) -> Result<usize> {
+    let context = Context::with_default_modules()?;
+    let options = options(parallel)?;
+    let mut s = collect(sources)?;
+
+    limit::with(LIMIT, || {
+        let unit = rune::prepare(&mut s)
+            .with_context(&context)
+            .with_options(&options)
+            .build()?;
+
+        drop(unit);
+        Ok(limit::get())
+    })
+    .call()
+}
+
+fn options(parallel: bool) -> Result<Options> {
+    let mut options = Options::default();
+
+    options.parse_option(if parallel {
+        "parallel=true"
+    } else {
+        "parallel=false"
+    })?;
+
+    Ok(options)
+}
+
+fn collect(sources: &[Source]) -> Result<Sources> {
+    let mut s = Sources::new();
+
+    for source in sources {
+        s.insert(source.try_clone()?)?;
+    }
+
+    Ok(s)
+}
+
+#[test]
+fn identical_units() -> Result<()> {
+    let sources = generated()?;
+
+    let (serial, serial_diagnostics) = build(&sources, false)?;
+    assert!(!serial.is_empty());
+    assert!(serial_diagnostics.is_empty(), "{serial_diagnostics}");
+
+    for _ in 0..4 {
+        let (parallel, parallel_diagnostics) = build(&sources, true)?;
+        assert_eq!(serial, parallel);
+        assert_eq!(serial_diagnostics, parallel_diagnostics);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn identical_diagnostics() -> Result<()> {
+    let mut sources = generated()?;
+
+    // Introduce syntax errors in a handful of modules.
+    for i in [3, 17, 42, 199] {
+        sources[i] = Source::new(
+            std::format!("f{i}"),
+            std::format!("pub fn f{i}(x) {{ x + }}"),
+        )?;
+    }
+
+    let (_, serial) = build(&sources, false)?;
+    assert!(serial.contains("f17"), "{serial}");
+
+    for _ in 0..4 {
+        let (_, parallel) = build(&sources, true)?;
+        assert_eq!(serial, parallel);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn memory_limit() -> Result<()> {
+    let sources = generated()?;
+
+    // Syntax trees parsed on other threads are copied by the building thread,
+    // which is where they are dropped, so the limit is the same after a
+    // parallel build as after a serial one.
+    let serial = remaining(&sources, false)?;
+
+    for _ in 0..4 {
+        assert_eq!(remaining(&sources, true)?, serial);
+    }
+
+    Ok(())
+}
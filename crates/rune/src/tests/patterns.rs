@@ -78,3 +78,23 @@ fn test_const_in_pattern() {
         }
     }
 }
+
+#[test]
+fn test_number_literal_patterns() {
+    macro_rules! test_case {
+        ($value:expr, $pat:expr) => {
+            let string = format!("match {} {{ {} => true, _ => false }}", $value, $pat);
+            let out: bool = eval(&string);
+            assert!(out, "{string}");
+        };
+    }
+
+    test_case!("1000000", "1_000_000");
+    test_case!("170", "0b1010_1010");
+    test_case!("255", "0x_ff");
+    test_case!("-255", "-0xf_f");
+    test_case!("63", "0o7_7");
+    test_case!("-5", "-5");
+    test_case!("200u8", "2_0_0u8");
+    test_case!("[1, 2]", "[0x1, 0b10]");
+}
@@ -129,6 +129,16 @@ fn test_synthetic() -> Result<()> {
         assert_quote!(cx, [Ident(LitSource::Synthetic(..))], quote!(hello));
         assert_quote!(cx, [ByteStr(StrSource::Synthetic(..))], quote!(b"hello"));
         assert_quote!(cx, [Str(StrSource::Synthetic(..))], quote!("hello"));
+        assert_quote!(
+            cx,
+            [ByteStr(StrSource::Synthetic(..))],
+            quote!(br#"say "hello""#)
+        );
+        assert_quote!(
+            cx,
+            [Str(StrSource::Synthetic(..))],
+            quote!(r#"say "hello""#)
+        );
         assert_quote!(cx, [Number(NumberSource::Synthetic(..))], quote!(0));
         assert_quote!(cx, [Number(NumberSource::Synthetic(..))], quote!(42.0));
         assert_quote!(cx, [Char(CopySource::Inline('a'))], quote!('a'));
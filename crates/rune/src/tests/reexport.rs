@@ -0,0 +1,83 @@
+prelude!();
+
+#[derive(Any, Debug)]
+#[rune(item = ::json)]
+struct Value {
+    #[rune(get)]
+    number: i64,
+}
+
+fn json() -> Result<Module, ContextError> {
+    let mut m = Module::with_crate("json")?;
+    m.ty::<Value>()?;
+    m.function("parse", |number: i64| Value { number })
+        .build()?;
+    Ok(m)
+}
+
+fn app() -> Result<Module, ContextError> {
+    let mut m = Module::with_crate("app")?;
+    m.reexport(["json"], rune::item!(::json))?;
+    m.reexport(["parse"], rune::item!(::json::parse))?;
+    Ok(m)
+}
+
+#[test]
+fn reexport_module() -> Result<()> {
+    let mut context = Context::with_default_modules()?;
+    context.install(json()?)?;
+    context.install(app()?)?;
+
+    let out: (i64, i64, i64, i64) = run(
+        &context,
+        r#"
+        use app::json;
+
+        pub fn main() {
+            let a = json::parse(1);
+            let b = app::json::parse(2);
+            let c = ::json::parse(3);
+            let d = app::parse(4);
+            assert!(a is ::json::Value);
+            assert!(b is app::json::Value);
+            (a.number, b.number, c.number, d.number)
+        }
+        "#,
+        (),
+        false,
+    )?;
+
+    assert_eq!(out, (1, 2, 3, 4));
+    Ok(())
+}
+
+#[test]
+fn reexport_conflict() -> Result<()> {
+    let mut other = Module::with_crate("app")?;
+    other.reexport(["json"], rune::item!(::other::json))?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(json()?)?;
+    context.install(app()?)?;
+
+    // Installing the same re-export again is fine.
+    context.install(app()?)?;
+
+    let error = context.install(other).unwrap_err();
+
+    match &error {
+        ContextError::ConflictingReexportTarget { item, to, existing } => {
+            assert_eq!(*item, rune::item!(::app::json));
+            assert_eq!(*to, rune::item!(::other::json));
+            assert_eq!(*existing, rune::item!(::json));
+        }
+        error => panic!("Unexpected error: {error}"),
+    }
+
+    assert_eq!(
+        error.to_string(),
+        "Reexport at `::app::json` to `::other::json` conflicts with existing reexport to `::json`"
+    );
+
+    Ok(())
+}
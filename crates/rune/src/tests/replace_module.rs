@@ -0,0 +1,158 @@
+prelude!();
+
+use std::sync::Mutex;
+
+use rune::diagnostics::{Diagnostic, FatalDiagnosticKind};
+
+fn sandboxed_io(output: Arc<Mutex<Vec<String>>>) -> Result<Module, ContextError> {
+    let mut m = Module::with_crate_item("std", ["io"])?;
+
+    m.function("println", move |message: &str| {
+        output.lock().unwrap().push(format!("sandboxed: {message}"));
+    })
+    .build()?;
+
+    Ok(m)
+}
+
+#[test]
+fn replace_println() -> Result<()> {
+    let output = Arc::new(Mutex::new(Vec::new()));
+
+    let mut context = Context::with_default_modules()?;
+    context.replace(sandboxed_io(output.clone())?)?;
+
+    let () = run(&context, r#"println("Hello World")"#, (), true)?;
+
+    assert_eq!(
+        &*output.lock().unwrap(),
+        &[String::from("sandboxed: Hello World")]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn replace_conflict_is_atomic() -> Result<()> {
+    #[derive(Any)]
+    struct Unregistered;
+
+    let mut m = Module::with_crate_item("std", ["io"])?;
+    m.function("println", |_: &str| {}).build()?;
+    // Associated functions for types which aren't installed fail to install.
+    m.associated_function("len", |_: &Unregistered| 0i64)?;
+
+    let mut context = Context::with_default_modules()?;
+    assert!(context.replace(m).is_err());
+
+    // The original module is still installed.
+    let output: String = run(
+        &context,
+        r#"let s = String::new(); s.push_str("ok"); dbg(s); s"#,
+        (),
+        true,
+    )?;
+
+    assert_eq!(output, "ok");
+
+    // Functions in the module which failed to replace the original one still
+    // resolve.
+    let () = run(&context, r#"println("still here")"#, (), true)?;
+    Ok(())
+}
+
+#[test]
+fn remove_module() -> Result<()> {
+    let mut context = Context::with_default_modules()?;
+
+    let mut sources = sources! {
+        entry => {
+            pub fn main() {
+                println("Hello World");
+            }
+        }
+    };
+
+    let unit = Arc::new(prepare(&mut sources).with_context(&context).build()?);
+
+    context.remove_module(rune::item!(::std::io))?;
+
+    let error = context.remove_module(rune::item!(::std::io)).unwrap_err();
+
+    assert!(matches!(error, ContextError::MissingModule { .. }));
+
+    // Compiling against the context no longer works.
+    let mut sources = sources! {
+        entry => {
+            pub fn main() {
+                println("Hello World");
+            }
+        }
+    };
+
+    assert!(prepare(&mut sources)
+        .with_context(&context)
+        .with_diagnostics(&mut Diagnostics::new())
+        .build()
+        .is_err());
+
+    // The previously compiled unit fails to call the removed function.
+    let mut vm = Vm::new(Arc::new(context.runtime()?), unit);
+    let error = vm.call(["main"], ()).unwrap_err();
+
+    assert!(matches!(
+        error.into_kind(),
+        VmErrorKind::MissingFunction { .. }
+    ));
+
+    // The module can be installed again after it has been removed.
+    context.install(crate::modules::io::module(true)?)?;
+    let () = run(&context, r#"println("Hello World")"#, (), true)?;
+    Ok(())
+}
+
+#[test]
+fn remove_known_module() -> Result<()> {
+    let mut m = Module::with_crate("http")?;
+    m.function("get", |url: &str| url.len() as i64).build()?;
+
+    let mut context = Context::with_default_modules()?;
+    context.known_module(&m)?;
+    context.install(m)?;
+    context.remove_module(rune::item!(::http))?;
+
+    let mut sources = sources! {
+        entry => {
+            pub fn main() {
+                http::get("https://example.com")
+            }
+        }
+    };
+
+    let mut diagnostics = Diagnostics::new();
+
+    let result = prepare(&mut sources)
+        .with_context(&context)
+        .with_diagnostics(&mut diagnostics)
+        .build();
+
+    assert!(result.is_err());
+
+    let Some(Diagnostic::Fatal(fatal)) = diagnostics.into_diagnostics().into_iter().next() else {
+        panic!("expected fatal diagnostic");
+    };
+
+    let FatalDiagnosticKind::CompileError(error) = fatal.into_kind() else {
+        panic!("expected compile error");
+    };
+
+    // The removed module is no longer suggested.
+    let kind = error.into_kind();
+
+    assert!(
+        !matches!(kind, ErrorKind::MissingKnownItem { .. }),
+        "{kind:?}"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,143 @@
+prelude!();
+
+use ErrorKind::*;
+
+#[test]
+fn vec_rest() {
+    let out: (i64, Vec<i64>) = rune! {
+        match [1, 2, 3] {
+            [head, ..tail] => (head, tail),
+        }
+    };
+    assert_eq!(out, (1, vec![2, 3]));
+
+    let out: (Vec<i64>, i64) = rune! {
+        match [1, 2, 3] {
+            [..init, last] => (init, last),
+        }
+    };
+    assert_eq!(out, (vec![1, 2], 3));
+
+    let out: (i64, Vec<i64>, i64) = rune! {
+        match [1, 2, 3, 4] {
+            [first, ..middle, last] => (first, middle, last),
+        }
+    };
+    assert_eq!(out, (1, vec![2, 3], 4));
+
+    let out: (i64, i64) = rune! {
+        match [1, 2, 3] {
+            [.., a, b] => (a, b),
+        }
+    };
+    assert_eq!(out, (2, 3));
+}
+
+#[test]
+fn vec_rest_empty() {
+    let out: i64 = rune! {
+        match [] {
+            [_, ..] => 1,
+            [..rest] => rest.len() + 10,
+        }
+    };
+    assert_eq!(out, 10);
+
+    let out: Vec<i64> = rune! {
+        let [..rest] = [];
+        rest
+    };
+    assert_eq!(out, Vec::<i64>::new());
+}
+
+#[test]
+fn vec_rest_exact() {
+    let out: (i64, Vec<i64>) = rune! {
+        match [1] {
+            [a, ..rest] => (a, rest),
+        }
+    };
+    assert_eq!(out, (1, vec![]));
+
+    let out: i64 = rune! {
+        match [1, 2] {
+            [_, _, _, ..] => 1,
+            [a, ..rest, b] => a + b + rest.len(),
+        }
+    };
+    assert_eq!(out, 3);
+}
+
+#[test]
+fn vec_rest_nested() {
+    let out: (i64, Vec<i64>, i64, Vec<i64>) = rune! {
+        match [[1, 2, 3], [4, 5]] {
+            [[a, ..x], ..rest] => {
+                let [[b, ..y]] = rest;
+                (a, x, b, y)
+            }
+        }
+    };
+    assert_eq!(out, (1, vec![2, 3], 4, vec![5]));
+}
+
+#[test]
+fn vec_rest_copies() {
+    let out: (Vec<i64>, Vec<i64>) = rune! {
+        let v = [1, 2, 3];
+        let [_, ..tail] = v;
+        tail.push(4);
+        (v, tail)
+    };
+    assert_eq!(out, (vec![1, 2, 3], vec![2, 3, 4]));
+}
+
+#[test]
+fn tuple_rest() {
+    let out: (i64, (i64, i64)) = rune! {
+        let (a, ..rest) = (1, 2, 3);
+        (a, rest)
+    };
+    assert_eq!(out, (1, (2, 3)));
+
+    let out: (i64, i64) = rune! {
+        match (1, 2, 3, 4) {
+            (a, .., b) => (a, b),
+        }
+    };
+    assert_eq!(out, (1, 4));
+}
+
+#[test]
+fn object_rest() {
+    let out: (i64, i64, i64) = rune! {
+        match #{a: 1, b: 2, c: 3} {
+            #{a, ..rest} => (a, rest.len(), rest.b + rest.c),
+        }
+    };
+    assert_eq!(out, (1, 2, 5));
+
+    let out: i64 = rune! {
+        let #{a, b, ..rest} = #{a: 1, b: 2};
+        a + b + rest.len()
+    };
+    assert_eq!(out, 3);
+}
+
+#[test]
+fn unsupported_rest() {
+    assert_errors! {
+        "let [a, .., ..] = [1];",
+        span!(12, 14), UnsupportedPatternRest
+    };
+
+    assert_errors! {
+        "struct Foo(a, b); let Foo(.., b) = Foo(1, 2);",
+        span!(26, 28), UnsupportedPatternRest
+    };
+
+    assert_errors! {
+        "struct Foo { a } let Foo { a, ..rest } = Foo { a: 1 };",
+        span!(30, 36), UnsupportedPatternRest
+    };
+}
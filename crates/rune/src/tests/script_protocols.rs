@@ -0,0 +1,163 @@
+prelude!();
+
+use ErrorKind::*;
+
+#[test]
+fn display_and_debug_fmt() {
+    let out: String = rune! {
+        struct Point { x, y }
+
+        impl Point {
+            #[protocol(display_fmt)]
+            fn display_fmt(self, f) {
+                f.write_str(format!("({}, {})", self.x, self.y));
+            }
+
+            #[protocol(debug_fmt)]
+            fn debug_fmt(self, f) {
+                f.write_str(format!("Point({}, {})", self.x, self.y));
+            }
+        }
+
+        let p = Point { x: 1, y: 2 };
+        format!("{} {:?} {:?}", p, p, [p])
+    };
+
+    assert_eq!(out, "(1, 2) Point(1, 2) [Point(1, 2)]");
+}
+
+#[test]
+fn partial_eq_and_cmp() {
+    let out: (bool, bool, bool, bool) = rune! {
+        struct Version { major, minor, label }
+
+        impl Version {
+            #[protocol(partial_eq)]
+            fn eq(self, other) {
+                self.major == other.major && self.minor == other.minor
+            }
+
+            #[protocol(partial_cmp)]
+            fn partial_cmp(self, other) {
+                Some(self.cmp(other))
+            }
+
+            #[protocol(cmp)]
+            fn cmp(self, other) {
+                (self.major, self.minor).cmp((other.major, other.minor))
+            }
+        }
+
+        let a = Version { major: 1, minor: 2, label: "a" };
+        let b = Version { major: 1, minor: 2, label: "b" };
+        let c = Version { major: 1, minor: 10, label: "c" };
+
+        (a == b, a != c, a < c, std::cmp::max(a, c).label == "c")
+    };
+
+    assert_eq!(out, (true, true, true, true));
+}
+
+#[test]
+fn hash() {
+    let out: i64 = rune! {
+        use std::collections::HashSet;
+
+        struct Key { id, name }
+
+        impl Key {
+            #[protocol(hash)]
+            fn hash(self, hasher) {
+                hasher.hash(self.id);
+            }
+
+            #[protocol(eq)]
+            fn eq(self, other) {
+                self.id == other.id
+            }
+        }
+
+        let set = HashSet::new();
+        set.insert(Key { id: 1, name: "a" });
+        set.insert(Key { id: 1, name: "b" });
+        set.insert(Key { id: 2, name: "c" });
+        set.len()
+    };
+
+    assert_eq!(out, 2);
+}
+
+#[test]
+fn into_iter() {
+    let out: i64 = rune! {
+        struct Bag { items }
+
+        impl Bag {
+            #[protocol(into_iter)]
+            fn iter(self) {
+                self.items.iter()
+            }
+        }
+
+        let bag = Bag { items: [1, 2, 3] };
+        let sum = 0;
+
+        for item in bag {
+            sum += item;
+        }
+
+        sum
+    };
+
+    assert_eq!(out, 6);
+}
+
+#[test]
+fn index_get_and_set() {
+    let out: (i64, i64) = rune! {
+        struct Grid { width, cells }
+
+        impl Grid {
+            #[protocol(index_get)]
+            fn get(self, (x, y)) {
+                self.cells[y * self.width + x]
+            }
+
+            #[protocol(index_set)]
+            fn set(self, (x, y), value) {
+                self.cells[y * self.width + x] = value;
+            }
+        }
+
+        let grid = Grid { width: 2, cells: [0, 0, 0, 0] };
+        grid[(1, 1)] = 42;
+        (grid[(1, 1)], grid.cells[3])
+    };
+
+    assert_eq!(out, (42, 42));
+}
+
+#[test]
+fn unsupported_protocol() {
+    assert_errors! {
+        r#"
+        struct Foo;
+
+        impl Foo {
+            #[protocol(add)]
+            fn add(self, other) {}
+        }
+        "#,
+        span!(64, 67), UnsupportedProtocol { name } => {
+            assert_eq!(&*name, "add");
+        }
+    };
+
+    assert_errors! {
+        r#"
+        #[protocol(display_fmt)]
+        fn display_fmt(f) {}
+        "#,
+        span!(9, 62), Custom { .. }
+    };
+}
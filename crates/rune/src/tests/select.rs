@@ -0,0 +1,202 @@
+prelude!();
+
+use std::time::{Duration, Instant};
+
+use tokio::sync::mpsc;
+
+use rune::runtime::Mut;
+
+/// The receiving end of a channel of integers.
+#[derive(Any)]
+struct Receiver {
+    inner: mpsc::UnboundedReceiver<i64>,
+}
+
+#[rune::function(keep, instance)]
+async fn recv(mut this: Mut<Receiver>) -> Option<i64> {
+    this.inner.recv().await
+}
+
+#[rune::function(instance, protocol = INTO_FUTURE)]
+async fn into_future(this: Mut<Receiver>) -> Option<i64> {
+    recv(this).await
+}
+
+/// Sleep for the given number of milliseconds.
+#[rune::function]
+async fn sleep(ms: u64) {
+    tokio::time::sleep(Duration::from_millis(ms)).await;
+}
+
+fn vm() -> Result<Vm> {
+    let mut m = Module::with_crate("time")?;
+    m.function_meta(sleep)?;
+    m.ty::<Receiver>()?;
+    m.function_meta(recv__meta)?;
+    m.function_meta(into_future)?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(m)?;
+
+    let mut sources = sources! {
+        entry => {
+            pub async fn pending() {
+                select {
+                    _ = time::sleep(10000) => 1,
+                    default => 2,
+                }
+            }
+
+            pub async fn wait(rx) {
+                let idle = 0;
+                let value = None;
+
+                while value.is_none() {
+                    select {
+                        v = rx => {
+                            value = v;
+                        }
+                        default => {
+                            idle += 1;
+                            time::sleep(5).await;
+                        }
+                    }
+                }
+
+                (value, idle)
+            }
+
+            pub async fn collect(rx) {
+                let timeout = time::sleep(200);
+                let values = [];
+                let done = false;
+
+                while !done {
+                    select {
+                        _ = timeout => {
+                            done = true;
+                        }
+                        value = rx => {
+                            if let Some(value) = value {
+                                values.push(value);
+                            } else {
+                                done = true;
+                            }
+                        }
+                    }
+                }
+
+                values
+            }
+
+            pub async fn fair(a, b, n) {
+                let from_a = 0;
+                let from_b = 0;
+
+                for _ in 0..n {
+                    select {
+                        _ = a => {
+                            from_a += 1;
+                        }
+                        _ = b => {
+                            from_b += 1;
+                        }
+                    }
+                }
+
+                (from_a, from_b)
+            }
+        }
+    };
+
+    let unit = prepare(&mut sources).with_context(&context).build()?;
+    Ok(Vm::new(Arc::new(context.runtime()?), Arc::new(unit)))
+}
+
+#[tokio::test]
+async fn select_default_when_pending() -> Result<()> {
+    let mut vm = vm()?;
+    let start = Instant::now();
+
+    let output = vm.async_call(["pending"], ()).await?;
+    assert_eq!(from_value::<i64>(output)?, 2);
+    assert!(start.elapsed() < Duration::from_secs(5));
+    Ok(())
+}
+
+#[tokio::test]
+async fn select_default_until_ready() -> Result<()> {
+    let mut vm = vm()?;
+    let (tx, rx) = mpsc::unbounded_channel();
+
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        _ = tx.send(42);
+    });
+
+    let rx = Receiver { inner: rx };
+    let output = vm.async_call(["wait"], (rx,)).await?;
+    let (value, idle) = from_value::<(Option<i64>, i64)>(output)?;
+
+    assert_eq!(value, Some(42));
+    assert!(
+        idle > 0,
+        "default branch should fire while nothing is ready"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn select_retains_pending_futures() -> Result<()> {
+    let mut vm = vm()?;
+    let (tx, rx) = mpsc::unbounded_channel();
+
+    // Keep sending for much longer than the timeout, so that the select only
+    // completes if the timeout keeps its progress when it loses. The receiver
+    // is converted into a new future through `INTO_FUTURE` every time the
+    // select is evaluated.
+    tokio::spawn(async move {
+        for n in 0..1000 {
+            if tx.send(n).is_err() {
+                break;
+            }
+
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    });
+
+    let start = Instant::now();
+    let rx = Receiver { inner: rx };
+    let output = vm.async_call(["collect"], (rx,)).await?;
+    let values = from_value::<Vec<i64>>(output)?;
+
+    assert!(start.elapsed() < Duration::from_secs(2));
+    assert!(!values.is_empty());
+    assert_eq!(values, (0..values.len() as i64).collect::<Vec<_>>());
+    Ok(())
+}
+
+#[tokio::test]
+async fn select_is_fair() -> Result<()> {
+    let mut vm = vm()?;
+
+    let (a_tx, a_rx) = mpsc::unbounded_channel();
+    let (b_tx, b_rx) = mpsc::unbounded_channel();
+
+    for n in 0..10 {
+        a_tx.send(n)?;
+        b_tx.send(n)?;
+    }
+
+    // Both branches are always ready, so a select which always polls the
+    // first branch first would never pick the second one.
+    let a = Receiver { inner: a_rx };
+    let b = Receiver { inner: b_rx };
+    let output = vm.async_call(["fair"], (a, b, 10i64)).await?;
+    let (from_a, from_b) = from_value::<(i64, i64)>(output)?;
+
+    assert_eq!(from_a + from_b, 10);
+    assert!(from_a > 0, "{from_a}");
+    assert!(from_b > 0, "{from_b}");
+    Ok(())
+}
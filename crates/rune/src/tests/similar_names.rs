@@ -0,0 +1,130 @@
+prelude!();
+
+use ErrorKind::*;
+
+#[test]
+fn prelude_function() {
+    assert_errors! {
+        r#"prinltn("hello")"#,
+        span!(0, 7),
+        MissingItem { item, did_you_mean } => {
+            let did_you_mean = did_you_mean.unwrap();
+            assert_eq!(item.to_string(), "prinltn");
+            assert_eq!(did_you_mean.to_string(), "println");
+            assert!(did_you_mean.is_function);
+        }
+    };
+}
+
+#[test]
+fn unit_function() {
+    assert_errors! {
+        r#"
+        fn helper() { 42 }
+        helpr()
+        "#,
+        span!(36, 41),
+        MissingItem { item, did_you_mean } => {
+            let did_you_mean = did_you_mean.unwrap();
+            assert_eq!(item.to_string(), "helpr");
+            assert_eq!(did_you_mean.to_string(), "helper");
+            assert!(did_you_mean.is_function);
+        }
+    };
+}
+
+#[test]
+fn module_function() {
+    assert_errors! {
+        r#"std::iter::rang(0, 10)"#,
+        span!(0, 15),
+        MissingItem { item, did_you_mean } => {
+            let did_you_mean = did_you_mean.unwrap();
+            assert_eq!(item.to_string(), "::std::iter::rang");
+            assert_eq!(did_you_mean.to_string(), "::std::iter::range");
+            assert!(did_you_mean.is_function);
+        }
+    };
+}
+
+#[test]
+fn unit_struct() {
+    assert_errors! {
+        r#"
+        struct Point(x, y);
+        Poitn(1, 2)
+        "#,
+        span!(37, 42),
+        MissingItem { item, did_you_mean } => {
+            let did_you_mean = did_you_mean.unwrap();
+            assert_eq!(item.to_string(), "Poitn");
+            assert_eq!(did_you_mean.to_string(), "Point");
+            assert!(!did_you_mean.is_function);
+        }
+    };
+}
+
+#[test]
+fn struct_field() {
+    assert_errors! {
+        r#"
+        struct Foo { bar, baz }
+        Foo { bar: 1, bax: 2 }
+        "#,
+        span!(55, 58),
+        LitObjectNotField { field, did_you_mean, .. } => {
+            assert_eq!(field.as_ref(), "bax");
+            assert_eq!(did_you_mean.as_deref(), Some("baz"));
+        }
+    };
+}
+
+#[test]
+fn object_key() {
+    assert_vm_error!(
+        r#"
+        let object = #{ name: "John", age: 42 };
+        object.nmae
+        "#,
+        VmErrorKind::MissingField { field, did_you_mean, .. } => {
+            assert_eq!(field.as_str(), "nmae");
+            assert_eq!(did_you_mean.as_deref(), Some("name"));
+        }
+    );
+}
+
+#[test]
+fn dynamic_struct_field() {
+    assert_vm_error!(
+        r#"
+        struct Person { name, age }
+        let person = Person { name: "John", age: 42 };
+        person.aeg
+        "#,
+        VmErrorKind::MissingField { field, did_you_mean, .. } => {
+            assert_eq!(field.as_str(), "aeg");
+            assert_eq!(did_you_mean.as_deref(), Some("age"));
+        }
+    );
+}
+
+#[test]
+fn no_similar_name() {
+    assert_errors! {
+        r#"
+        fn helper() { 42 }
+        completely_different()
+        "#,
+        span!(36, 56),
+        MissingItem { did_you_mean: None, .. }
+    };
+
+    assert_errors! {
+        r#"
+        struct Foo { bar, baz }
+        Foo { bar: 1, quux: 2 }
+        "#,
+        span!(55, 59),
+        LitObjectNotField { did_you_mean: None, .. }
+    };
+}
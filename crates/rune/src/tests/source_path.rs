@@ -0,0 +1,90 @@
+prelude!();
+
+use std::path::Path;
+
+use rune::termcolor::Buffer;
+
+#[test]
+fn diagnostics_render_path() -> Result<()> {
+    let mut sources = Sources::new();
+
+    let id = sources.insert(Source::with_path(
+        "entry",
+        "pub fn main() { missing() }",
+        "scripts/entry.rn",
+    )?)?;
+
+    let mut diagnostics = Diagnostics::new();
+
+    let _ = prepare(&mut sources)
+        .with_diagnostics(&mut diagnostics)
+        .build();
+
+    let mut out = Buffer::no_color();
+    diagnostics.emit(&mut out, &sources)?;
+    let out = String::from_utf8(out.into_inner())?;
+
+    assert!(out.contains("scripts/entry.rn:1:17"), "{out}");
+    assert_eq!(sources.get(id).map(Source::name), Some("entry"));
+    Ok(())
+}
+
+#[test]
+fn vm_error_renders_path() -> Result<()> {
+    let context = Context::with_default_modules()?;
+    let runtime = Arc::new(context.runtime()?);
+
+    let mut sources = Sources::new();
+
+    sources.insert(Source::with_path(
+        "entry",
+        "pub fn main() { panic!(\"oh no\") }",
+        "scripts/entry.rn",
+    )?)?;
+
+    let unit = prepare(&mut sources).with_context(&context).build()?;
+    let mut vm = Vm::new(runtime, Arc::new(unit));
+
+    let mut out = Buffer::no_color();
+    assert!(vm.call_emit(&mut out, &sources, ["main"], ()).is_err());
+    let out = String::from_utf8(out.into_inner())?;
+
+    assert!(out.contains("scripts/entry.rn:1:17"), "{out}");
+    assert!(!out.contains("─ entry:"), "{out}");
+    Ok(())
+}
+
+#[test]
+fn memory_source_renders_name() -> Result<()> {
+    let mut sources = sources! {
+        entry => {
+            pub fn main() { missing() }
+        }
+    };
+
+    let mut diagnostics = Diagnostics::new();
+
+    let _ = prepare(&mut sources)
+        .with_diagnostics(&mut diagnostics)
+        .build();
+
+    let mut out = Buffer::no_color();
+    diagnostics.emit(&mut out, &sources)?;
+    let out = String::from_utf8(out.into_inner())?;
+
+    assert!(out.contains("entry:1:"), "{out}");
+    Ok(())
+}
+
+#[test]
+fn get_by_path() -> Result<()> {
+    let mut sources = Sources::new();
+    sources.insert(Source::new("a", "pub fn main() {}")?)?;
+    let b = sources.insert(Source::with_path("b", "pub fn main() {}", "b.rn")?)?;
+
+    let (id, source) = sources.get_by_path(Path::new("b.rn")).unwrap();
+    assert_eq!(id, b);
+    assert_eq!(source.name(), "b");
+    assert!(sources.get_by_path(Path::new("a")).is_none());
+    Ok(())
+}
@@ -0,0 +1,134 @@
+prelude!();
+
+use serde::{Deserialize, Serialize};
+
+use crate::runtime::RuntimeContext;
+
+#[derive(Any, Debug, PartialEq, Serialize, Deserialize)]
+#[rune(serialize)]
+struct Point {
+    #[rune(get)]
+    x: i64,
+    #[rune(get)]
+    y: i64,
+}
+
+#[derive(Any, Debug, PartialEq, Serialize, Deserialize)]
+struct Circle {
+    radius: f64,
+}
+
+#[derive(Any)]
+struct Opaque;
+
+fn runtime(m: Module) -> Result<RuntimeContext> {
+    let mut context = Context::with_default_modules()?;
+    context.install(m)?;
+    Ok(context.runtime()?)
+}
+
+fn module() -> Result<Module> {
+    let mut m = Module::new();
+    m.ty::<Point>()?;
+    m.ty::<Circle>()?.serializable()?;
+    m.ty::<Opaque>()?;
+    Ok(m)
+}
+
+/// Construct a value tree containing external values from a script.
+fn value() -> Result<Value> {
+    let mut sources = sources! {
+        entry => {
+            pub fn main(point, circle) {
+                (point, #{ circle: Some(circle), sum: point.x + point.y }, [1, 2])
+            }
+        }
+    };
+
+    let mut context = Context::with_default_modules()?;
+    context.install(module()?)?;
+
+    let unit = prepare(&mut sources).with_context(&context).build()?;
+    let mut vm = Vm::new(Arc::new(context.runtime()?), Arc::new(unit));
+
+    let point = Point { x: 1, y: 2 };
+    let circle = Circle { radius: 0.5 };
+    Ok(vm.call(["main"], (point, circle))?)
+}
+
+fn check(value: Value) -> Result<()> {
+    let (point, object, vec) = from_value::<(Point, Object, Vec<i64>)>(value)?;
+    assert_eq!(point, Point { x: 1, y: 2 });
+    assert_eq!(vec, [1, 2]);
+
+    let circle = from_value::<Option<Circle>>(object.get("circle").unwrap().clone())?;
+    assert_eq!(circle, Some(Circle { radius: 0.5 }));
+
+    let sum = from_value::<i64>(object.get("sum").unwrap().clone())?;
+    assert_eq!(sum, 3);
+    Ok(())
+}
+
+#[test]
+fn serde_json_round_trip() -> Result<()> {
+    let runtime = runtime(module()?)?;
+
+    let json = serde_json::to_string(&runtime.to_serializable(&value()?)?)?;
+    assert!(json.contains("\"$item\""), "{json}");
+
+    let value = runtime.from_serializable(serde_json::from_str::<Value>(&json)?)?;
+    check(value)
+}
+
+#[cfg(feature = "unit-file")]
+#[test]
+fn musli_round_trip() -> Result<()> {
+    use crate::musli::Serde;
+
+    let runtime = runtime(module()?)?;
+
+    let mut bytes = Vec::new();
+    musli::descriptive::to_writer(&mut bytes, &Serde(runtime.to_serializable(&value()?)?))?;
+
+    let Serde(value) = musli::descriptive::from_slice::<Serde<Value>>(&bytes)?;
+    check(runtime.from_serializable(value)?)
+}
+
+#[test]
+fn unregistered_serialize() -> Result<()> {
+    let runtime = runtime(module()?)?;
+
+    let value = rune::to_value(vec![rune::to_value(Opaque)?])?;
+    let error = runtime.to_serializable(&value).unwrap_err();
+
+    let (hash, item) = error.unregistered_type().expect("unregistered type");
+    assert_eq!(hash, Opaque::HASH);
+    assert!(
+        item.is_some_and(|item| item.ends_with("Opaque")),
+        "{item:?}"
+    );
+    assert!(error.to_string().contains("Opaque"), "{error}");
+    Ok(())
+}
+
+#[test]
+fn unregistered_deserialize() -> Result<()> {
+    let json = serde_json::to_string(&runtime(module()?)?.to_serializable(&value()?)?)?;
+
+    // A context where the type is installed but not marked as serializable.
+    let mut m = Module::new();
+    m.ty::<Point>()?;
+    m.ty::<Circle>()?;
+    let other = runtime(m)?;
+
+    let value = serde_json::from_str::<Value>(&json)?;
+    let error = other.from_serializable(value).unwrap_err();
+
+    let (hash, item) = error.unregistered_type().expect("unregistered type");
+    assert_eq!(hash, Circle::HASH);
+    assert!(
+        item.is_some_and(|item| item.ends_with("Circle")),
+        "{item:?}"
+    );
+    Ok(())
+}
@@ -0,0 +1,87 @@
+prelude!();
+
+use rune::runtime::unit::UnitFileError;
+use rune::runtime::Unit;
+
+fn build(context: &Context) -> Result<Unit> {
+    let mut sources = sources! {
+        entry => {
+            pub fn main(n) {
+                let values = [1, 2, 3].iter().map(|v| v * n).collect::<Vec>();
+                (values, i64::max(n, 2))
+            }
+        }
+    };
+
+    Ok(prepare(&mut sources).with_context(context).build()?)
+}
+
+#[test]
+fn round_trip() -> Result<()> {
+    let context = Context::with_default_modules()?;
+    let unit = build(&context)?;
+
+    let mut bytes = Vec::new();
+    unit.serialize_to(&mut bytes)?;
+
+    // Load into a fresh context.
+    let context = Context::with_default_modules()?;
+    let runtime = Arc::new(context.runtime()?);
+    let unit = <Unit>::deserialize_from(&bytes[..], &runtime)?;
+
+    let mut vm = Vm::new(runtime, Arc::new(unit));
+    let output: (Vec<i64>, i64) = from_value(vm.call(["main"], (3i64,))?)?;
+    assert_eq!(output, (vec![3, 6, 9], 3));
+    Ok(())
+}
+
+#[test]
+fn context_mismatch() -> Result<()> {
+    let context = Context::with_default_modules()?;
+    let unit = build(&context)?;
+
+    let mut bytes = Vec::new();
+    unit.serialize_to(&mut bytes)?;
+
+    let runtime = Context::new().runtime()?;
+    let error = <Unit>::deserialize_from(&bytes[..], &runtime).unwrap_err();
+
+    let UnitFileError::ContextMismatch { missing } = error else {
+        panic!("expected context mismatch, got {error}");
+    };
+
+    assert!(!missing.is_empty());
+    Ok(())
+}
+
+#[test]
+fn bad_header() -> Result<()> {
+    let runtime = Context::new().runtime()?;
+
+    let error = <Unit>::deserialize_from(&b"RUNE\x01\0\0\0"[..], &runtime).unwrap_err();
+    assert!(matches!(error, UnitFileError::BadMagic));
+
+    // Units written with an older version of the format are rejected.
+    let error = <Unit>::deserialize_from(&b"RUNC\x03\0\0\0"[..], &runtime).unwrap_err();
+    assert!(matches!(
+        error,
+        UnitFileError::UnsupportedVersion { version: 3 }
+    ));
+    Ok(())
+}
+
+#[test]
+fn item_round_trip() -> Result<()> {
+    use crate::musli::Serde;
+
+    let item = ItemBuf::with_crate_item("std", ["iter", "Iterator"])?;
+
+    let mut bytes = Vec::new();
+    musli::descriptive::to_writer(&mut bytes, &Serde(&item))?;
+    let Serde(output) = musli::descriptive::from_slice::<Serde<ItemBuf>>(&bytes)?;
+    assert_eq!(output, item);
+
+    let json = serde_json::to_string(&item)?;
+    assert_eq!(serde_json::from_str::<ItemBuf>(&json)?, item);
+    Ok(())
+}
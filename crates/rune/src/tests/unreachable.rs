@@ -12,11 +12,142 @@ fn unreachable_iter() {
         }
         "#,
         span,
-        Unreachable { cause: span!(50, 63), .. } => {
+        UnreachableCode { cause: span!(50, 63), .. } => {
             assert_eq!(span, span!(64, 69));
         },
-        Unreachable { cause: span!(41, 69), .. } => {
+        UnreachableCode { cause: span!(41, 69), .. } => {
             assert_eq!(span, span!(82, 83));
         },
     }
 }
+
+#[test]
+fn unreachable_after_return() {
+    assert_warnings! {
+        r#"
+        pub fn function() {
+            return 1;
+            let a = 2;
+            a
+        }
+        "#,
+        span,
+        UnreachableCode { cause: span!(41, 49), .. } => {
+            assert_eq!(span, span!(63, 73));
+        },
+        UnreachableCode { cause: span!(41, 49), .. } => {
+            assert_eq!(span, span!(86, 87));
+        },
+    }
+}
+
+#[test]
+fn unreachable_in_loops() {
+    assert_warnings! {
+        r#"
+        pub fn function() {
+            loop {
+                break;
+                1;
+            }
+
+            for n in 0..10 {
+                continue;
+                n;
+            }
+        }
+        "#,
+        span,
+        UnreachableCode { cause: span!(64, 69), .. } => {
+            assert_eq!(span, span!(87, 88));
+        },
+        UnreachableCode { cause: span!(150, 158), .. } => {
+            assert_eq!(span, span!(176, 177));
+        },
+    }
+}
+
+#[test]
+fn unreachable_after_panic() {
+    assert_warnings! {
+        r#"
+        pub fn function() {
+            panic!("nope");
+            1
+        }
+        "#,
+        span!(69, 70),
+        UnreachableCode { cause: span!(41, 55), .. },
+    }
+}
+
+#[test]
+fn unreachable_after_diverging_match() {
+    assert_warnings! {
+        r#"
+        pub fn function(value) {
+            match value {
+                Some(n) => return n,
+                _ => panic("missing"),
+            }
+
+            0
+        }
+        "#,
+        span!(163, 164),
+        UnreachableCode { cause: span!(46, 149), .. },
+    }
+}
+
+#[test]
+fn reachable_after_converging_match() -> Result<()> {
+    let mut diagnostics = Default::default();
+
+    let source = r#"
+    pub fn function(value) {
+        match value {
+            Some(n) => return n,
+            None => panic("missing"),
+        }
+
+        match value {
+            Some(n) => return n,
+            _ => {}
+        }
+
+        0
+    }
+    "#;
+
+    crate::tests::compile_helper(source, &mut diagnostics)?;
+    assert!(!diagnostics.has_warning(), "{diagnostics:?}");
+    Ok(())
+}
+
+#[test]
+fn unreachable_in_closure_and_async() {
+    assert_warnings! {
+        r#"
+        pub fn function() {
+            let f = |n| {
+                return n;
+                n + 1
+            };
+
+            let a = async {
+                return 1;
+                2
+            };
+
+            (f, a)
+        }
+        "#,
+        span,
+        UnreachableCode { cause: span!(71, 79), .. } => {
+            assert_eq!(span, span!(97, 102));
+        },
+        UnreachableCode { cause: span!(163, 171), .. } => {
+            assert_eq!(span, span!(189, 190));
+        },
+    }
+}
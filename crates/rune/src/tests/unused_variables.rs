@@ -0,0 +1,131 @@
+prelude!();
+
+use WarningDiagnosticKind::*;
+
+#[test]
+fn unused_let() {
+    assert_warnings! {
+        r#"let unused = 1; let used = 2; used"#,
+        span!(4, 10),
+        UnusedVariable { name, .. } => {
+            assert_eq!(name, "unused");
+        }
+    };
+}
+
+#[test]
+fn unused_arguments() {
+    assert_warnings! {
+        r#"
+        fn foo(a, b) { a }
+        let f = |x, y| y;
+        foo(1, 2) + f(3, 4)
+        "#,
+        span,
+        UnusedVariable { name, .. } => {
+            assert_eq!(span, span!(45, 46));
+            assert_eq!(name, "x");
+        },
+        UnusedVariable { name, .. } => {
+            assert_eq!(span, span!(19, 20));
+            assert_eq!(name, "b");
+        }
+    };
+}
+
+#[test]
+fn unused_pattern_bindings() {
+    assert_warnings! {
+        r#"
+        match (1, 2, #{c: 3, d: 4}) {
+            (a, b, #{c, d: e}) => match Some(a + c) {
+                Some(f) => 0,
+                None => 1,
+            },
+            _ => 2,
+        }
+        "#,
+        span,
+        UnusedVariable { name, .. } => {
+            assert_eq!(span, span!(55, 56));
+            assert_eq!(name, "b");
+        },
+        UnusedVariable { name, .. } => {
+            assert_eq!(span, span!(66, 67));
+            assert_eq!(name, "e");
+        },
+        UnusedVariable { name, .. } => {
+            assert_eq!(span, span!(114, 115));
+            assert_eq!(name, "f");
+        }
+    };
+}
+
+#[test]
+fn shadowed_is_unused() {
+    assert_warnings! {
+        r#"let value = 1; let value = 2; value"#,
+        span!(4, 9),
+        UnusedVariable { name, .. } => {
+            assert_eq!(name, "value");
+        }
+    };
+}
+
+#[test]
+fn underscore_prefix_is_exempt() -> Result<()> {
+    let mut diagnostics = Default::default();
+
+    let source = r#"
+    fn foo(_a, b) { b }
+    let _unused = 1;
+    let _x = 1;
+    let y = 2;
+    let f = |_z| 1;
+    match Some(1) { Some(_w) => foo(f(y), 2), None => 0 }
+    "#;
+
+    crate::tests::compile_helper(source, &mut diagnostics)?;
+    assert!(!diagnostics.has_warning(), "{diagnostics:?}");
+    Ok(())
+}
+
+#[test]
+fn used_by_closure_or_template() -> Result<()> {
+    let mut diagnostics = Default::default();
+
+    let source = r#"
+    let captured = 1;
+    let name = "world";
+    let f = || captured;
+    `hello ${name} ${f()}`
+    "#;
+
+    crate::tests::compile_helper(source, &mut diagnostics)?;
+    assert!(!diagnostics.has_warning(), "{diagnostics:?}");
+    Ok(())
+}
+
+#[test]
+fn disabled() -> Result<()> {
+    let mut options = Options::default();
+    options.unused_variables(false);
+
+    let mut sources = sources! {
+        entry => {
+            pub fn main(a) {
+                let unused = 1;
+            }
+        }
+    };
+
+    let mut diagnostics = Diagnostics::new();
+
+    prepare(&mut sources)
+        .with_options(&options)
+        .with_diagnostics(&mut diagnostics)
+        .build()?;
+
+    assert!(!diagnostics.has_warning());
+    Ok(())
+}
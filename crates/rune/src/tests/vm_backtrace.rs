@@ -0,0 +1,104 @@
+prelude!();
+
+use rune::runtime::VmError;
+use rune::termcolor::Buffer;
+
+fn error() -> Result<(Sources, VmError)> {
+    let context = Context::with_default_modules()?;
+    let runtime = Arc::new(context.runtime()?);
+
+    let mut sources = sources! {
+        entry => {
+            fn c() {
+                panic!("oh no")
+            }
+
+            fn b() {
+                c()
+            }
+
+            pub fn a() {
+                b()
+            }
+        }
+    };
+
+    let unit = prepare(&mut sources).with_context(&context).build()?;
+    let mut vm = Vm::new(runtime, Arc::new(unit));
+    let error = vm.call(["a"], ()).unwrap_err();
+    Ok((sources, error))
+}
+
+#[test]
+fn backtrace_frames() -> Result<()> {
+    let (sources, error) = error()?;
+
+    let frames = error.backtrace().collect::<Vec<_>>();
+    assert_eq!(frames.len(), 3);
+
+    let functions = frames
+        .iter()
+        .map(|frame| frame.function.map(|item| item.to_string()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        functions,
+        [
+            Some(String::from("c")),
+            Some(String::from("b")),
+            Some(String::from("a"))
+        ]
+    );
+
+    let spans = frames
+        .iter()
+        .map(|frame| {
+            let source = sources.get(frame.source_id?)?;
+            source.get(frame.span?.range())
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(spans, [Some("panic!(\"oh no\")"), Some("c()"), Some("b()")]);
+
+    Ok(())
+}
+
+#[test]
+fn backtrace_serialize() -> Result<()> {
+    let (_, error) = error()?;
+
+    let frames = error.backtrace().collect::<Vec<_>>();
+    let value = serde_json::to_value(&frames)?;
+
+    let functions = value
+        .as_array()
+        .expect("expected array")
+        .iter()
+        .map(|frame| frame["function"].clone())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        functions,
+        [
+            serde_json::json!("c"),
+            serde_json::json!("b"),
+            serde_json::json!("a")
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn backtrace_emit() -> Result<()> {
+    let (sources, error) = error()?;
+
+    let mut out = Buffer::no_color();
+    error.emit(&mut out, &sources)?;
+    let out = core::str::from_utf8(out.as_slice())?;
+
+    let b = out.find("Called from `b`").expect("missing frame for `b`");
+    let a = out.find("Called from `a`").expect("missing frame for `a`");
+    assert!(b < a, "{out}");
+    Ok(())
+}
@@ -0,0 +1,62 @@
+prelude!();
+
+use rune::runtime::VmError;
+use rune::termcolor::Buffer;
+
+fn call_emit(with_sources: bool, name: &str) -> Result<(Result<Value, VmError>, String)> {
+    let context = Context::with_default_modules()?;
+    let runtime = Arc::new(context.runtime()?);
+
+    let mut sources = sources! {
+        entry => {
+            pub fn ok() {
+                42
+            }
+
+            pub fn fail() {
+                panic!("oh no")
+            }
+        }
+    };
+
+    let unit = prepare(&mut sources).with_context(&context).build()?;
+
+    let mut vm = Vm::new(runtime, Arc::new(unit));
+
+    let mut out = Buffer::no_color();
+
+    let result = if with_sources {
+        vm.call_emit(&mut out, &sources, [name], ())
+    } else {
+        vm.call_emit(&mut out, &Sources::new(), [name], ())
+    };
+
+    let out = String::from_utf8(out.into_inner())?;
+    Ok((result, out))
+}
+
+#[test]
+fn call_emit_ok() -> Result<()> {
+    let (result, out) = call_emit(true, "ok")?;
+    let value: i64 = from_value(result?)?;
+    assert_eq!(value, 42);
+    assert!(out.is_empty());
+    Ok(())
+}
+
+#[test]
+fn call_emit_with_sources() -> Result<()> {
+    let (result, out) = call_emit(true, "fail")?;
+    assert!(result.is_err());
+    assert!(out.starts_with("error: Panicked: oh no"));
+    assert!(out.contains("entry:"), "{out}");
+    Ok(())
+}
+
+#[test]
+fn call_emit_without_sources() -> Result<()> {
+    let (result, out) = call_emit(false, "fail")?;
+    let error = result.unwrap_err();
+    assert_eq!(out.trim_end(), format!("error: {error}"));
+    Ok(())
+}
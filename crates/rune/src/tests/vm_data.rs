@@ -0,0 +1,136 @@
+prelude!();
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rune::runtime::VmData;
+
+#[derive(Default)]
+struct Counter(AtomicUsize);
+
+#[rune::function]
+fn increment(data: &VmData) {
+    if let Some(counter) = data.get::<Counter>() {
+        counter.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[rune::function]
+fn count(data: VmData) -> usize {
+    data.get::<Counter>()
+        .map_or(0, |counter| counter.0.load(Ordering::SeqCst))
+}
+
+#[rune::function]
+fn add(a: usize, data: &VmData, b: usize) -> usize {
+    let base = data
+        .get::<Counter>()
+        .map_or(0, |c| c.0.load(Ordering::SeqCst));
+    base + a + b
+}
+
+fn vm(sources: &mut Sources) -> Result<Vm> {
+    let mut m = Module::new();
+    m.function_meta(increment)?;
+    m.function_meta(count)?;
+    m.function_meta(add)?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(m)?;
+
+    Ok(crate::tests::vm(
+        &context,
+        sources,
+        &mut Diagnostics::new(),
+        false,
+    )?)
+}
+
+#[test]
+fn shared_between_functions() -> Result<()> {
+    let mut sources = sources! {
+        entry => {
+            pub fn main() {
+                increment();
+                increment();
+                count()
+            }
+        }
+    };
+
+    let mut a = vm(&mut sources)?;
+    let mut b = a.try_clone()?;
+
+    a.insert_data(Counter::default())?;
+    b.insert_data(Counter::default())?;
+
+    let output: usize = from_value(a.call(["main"], ())?)?;
+    assert_eq!(output, 2);
+    let output: usize = from_value(a.call(["main"], ())?)?;
+    assert_eq!(output, 4);
+
+    let output: usize = from_value(b.call(["main"], ())?)?;
+    assert_eq!(output, 2);
+
+    let counter = a.data::<Counter>().expect("missing counter");
+    assert_eq!(counter.0.load(Ordering::SeqCst), 4);
+    Ok(())
+}
+
+#[test]
+fn mixed_arguments() -> Result<()> {
+    let mut sources = sources! {
+        entry => {
+            pub fn main() {
+                increment();
+                add(10, 20)
+            }
+        }
+    };
+
+    let mut vm = vm(&mut sources)?;
+    vm.insert_data(Counter::default())?;
+
+    let output: usize = from_value(vm.call(["main"], ())?)?;
+    assert_eq!(output, 31);
+    Ok(())
+}
+
+#[test]
+fn inherited_by_nested_calls() -> Result<()> {
+    let mut sources = sources! {
+        entry => {
+            pub fn main() {
+                let f = || increment();
+                [1, 2, 3].iter().map(|_| f()).collect::<Vec>();
+                let g = count;
+                g()
+            }
+        }
+    };
+
+    let mut vm = vm(&mut sources)?;
+    vm.insert_data(Counter::default())?;
+
+    let output: usize = from_value(vm.call(["main"], ())?)?;
+    assert_eq!(output, 3);
+    Ok(())
+}
+
+#[test]
+fn missing_data() -> Result<()> {
+    let mut sources = sources! {
+        entry => {
+            pub fn main() {
+                increment();
+                count()
+            }
+        }
+    };
+
+    let mut vm = vm(&mut sources)?;
+    let output: usize = from_value(vm.call(["main"], ())?)?;
+    assert_eq!(output, 0);
+    assert!(vm.remove_data::<Counter>()?.is_none());
+    assert!(VmData::current().is_empty());
+    Ok(())
+}
@@ -0,0 +1,137 @@
+prelude!();
+
+use core::ops::ControlFlow;
+use std::sync::Mutex;
+
+use rune::ast::Span;
+use rune::runtime::{DebugInst, Debugger, GeneratorState};
+
+const SOURCE: &str = r#"
+pub fn add(a, b) {
+    let c = a + b;
+    c * 2
+}
+
+pub fn main() {
+    let x = 10;
+    add(x, 5)
+}
+"#;
+
+#[derive(Default)]
+struct Events {
+    hits: usize,
+    enter: usize,
+    exit: usize,
+}
+
+/// A debugger which pauses once at the first instruction inside of the given
+/// span.
+struct Breakpoint {
+    span: Span,
+    armed: bool,
+    events: Arc<Mutex<Events>>,
+}
+
+impl Debugger for Breakpoint {
+    fn on_instruction(&mut self, _: usize, inst: &DebugInst) -> ControlFlow<()> {
+        let inside = self.span.start <= inst.span.start && inst.span.end <= self.span.end;
+
+        if !self.armed || !inside {
+            return ControlFlow::Continue(());
+        }
+
+        self.armed = false;
+        self.events.lock().unwrap().hits += 1;
+        ControlFlow::Break(())
+    }
+
+    fn on_enter_frame(&mut self, _: usize) {
+        self.events.lock().unwrap().enter += 1;
+    }
+
+    fn on_exit_frame(&mut self, _: usize) {
+        self.events.lock().unwrap().exit += 1;
+    }
+}
+
+fn vm() -> Result<Vm> {
+    let context = Context::with_default_modules()?;
+
+    let mut sources = Sources::new();
+    sources.insert(Source::memory(SOURCE)?)?;
+
+    Ok(crate::tests::vm(
+        &context,
+        &mut sources,
+        &mut Diagnostics::new(),
+        false,
+    )?)
+}
+
+fn span_of(needle: &str) -> Span {
+    let start = SOURCE.find(needle).expect("missing needle");
+    Span::new(start, start + needle.len())
+}
+
+#[test]
+fn breakpoint_inspect_and_resume() -> Result<()> {
+    let events = Arc::new(Mutex::new(Events::default()));
+
+    let mut vm = vm()?;
+
+    vm.set_debugger(Breakpoint {
+        span: span_of("c * 2"),
+        armed: true,
+        events: events.clone(),
+    });
+
+    let mut execution = vm.execute(["main"], ())?;
+
+    let Err(error) = execution.resume().into_result() else {
+        panic!("expected execution to pause at the breakpoint");
+    };
+
+    assert!(error.is_breakpoint(), "{error}");
+
+    let frames = execution.vm().frames().collect::<Vec<_>>();
+    assert_eq!(frames.len(), 2);
+
+    let c = frames[0].variable("c").expect("missing `c`");
+    assert_eq!(from_value::<i64>(c.clone())?, 15);
+    let a = frames[0].variable("a").expect("missing `a`");
+    assert_eq!(from_value::<i64>(a.clone())?, 10);
+    assert!(frames[0].variable("x").is_none());
+
+    let x = frames[1].variable("x").expect("missing `x`");
+    assert_eq!(from_value::<i64>(x.clone())?, 10);
+
+    let GeneratorState::Complete(value) = execution.resume().into_result()? else {
+        panic!("expected execution to complete");
+    };
+
+    assert_eq!(from_value::<i64>(value)?, 30);
+
+    let events = events.lock().unwrap();
+    assert_eq!(events.hits, 1);
+    assert_eq!(events.enter, 1);
+    assert_eq!(events.exit, 1);
+    Ok(())
+}
+
+#[test]
+fn variables_without_debugger() -> Result<()> {
+    let mut vm = vm()?;
+    let value = vm.call(["main"], ())?;
+    assert_eq!(from_value::<i64>(value)?, 30);
+
+    let debug = vm.unit().debug_info().expect("missing debug info");
+    let mut names = debug
+        .variables
+        .iter()
+        .map(|var| &*var.name)
+        .collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, ["a", "b", "c", "x"]);
+    Ok(())
+}
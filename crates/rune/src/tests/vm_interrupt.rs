@@ -0,0 +1,158 @@
+prelude!();
+
+use core::ops::ControlFlow;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use rune::runtime::GeneratorState;
+
+fn vm() -> Result<Vm> {
+    let context = Context::with_default_modules()?;
+
+    let mut sources = sources! {
+        entry => {
+            pub fn main(n) {
+                let count = 0;
+
+                while count < n {
+                    count += 1;
+                }
+
+                count
+            }
+
+            pub fn nested(n) {
+                [n].iter().map(main).collect::<Vec>()
+            }
+        }
+    };
+
+    Ok(crate::tests::vm(
+        &context,
+        &mut sources,
+        &mut Diagnostics::new(),
+        false,
+    )?)
+}
+
+#[test]
+fn interrupt_and_resume() -> Result<()> {
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    let mut vm = vm()?;
+
+    vm.set_interrupt_hook(1000, {
+        let calls = calls.clone();
+
+        move |_| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            ControlFlow::Break(())
+        }
+    });
+
+    let mut execution = vm.execute(["main"], (10000i64,))?;
+
+    let error = execution.resume().into_result().unwrap_err();
+    assert!(error.is_interrupted());
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    let mut interrupts = 1;
+
+    let value = loop {
+        match execution.resume().into_result() {
+            Ok(GeneratorState::Complete(value)) => break value,
+            Ok(GeneratorState::Yielded(..)) => panic!("unexpected yield"),
+            Err(error) if error.is_interrupted() => interrupts += 1,
+            Err(error) => return Err(error.into()),
+        }
+    };
+
+    assert_eq!(from_value::<i64>(value)?, 10000);
+    assert!(interrupts > 10);
+    assert_eq!(calls.load(Ordering::SeqCst), interrupts);
+    Ok(())
+}
+
+#[test]
+fn interrupt_continue() -> Result<()> {
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    let mut vm = vm()?;
+
+    vm.set_interrupt_hook(100, {
+        let calls = calls.clone();
+
+        move |_| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            ControlFlow::Continue(())
+        }
+    });
+
+    let value: i64 = from_value(vm.call(["main"], (1000i64,))?)?;
+    assert_eq!(value, 1000);
+    assert!(calls.load(Ordering::SeqCst) > 10);
+
+    vm.clear_interrupt_hook();
+    calls.store(0, Ordering::SeqCst);
+
+    let value: i64 = from_value(vm.call(["main"], (1000i64,))?)?;
+    assert_eq!(value, 1000);
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+    Ok(())
+}
+
+#[test]
+fn interrupt_async() -> Result<()> {
+    let mut vm = vm()?;
+    vm.set_interrupt_hook(1000, |_| ControlFlow::Break(()));
+
+    let mut execution = vm.execute(["main"], (10000i64,))?;
+    let mut interrupts = 0;
+
+    let value = loop {
+        match block_on(execution.async_resume()).into_result() {
+            Ok(GeneratorState::Complete(value)) => break value,
+            Ok(GeneratorState::Yielded(..)) => panic!("unexpected yield"),
+            Err(error) if error.is_interrupted() => interrupts += 1,
+            Err(error) => return Err(error.into()),
+        }
+    };
+
+    assert_eq!(from_value::<i64>(value)?, 10000);
+    assert!(interrupts > 10);
+    Ok(())
+}
+
+#[test]
+fn interrupt_nested() -> Result<()> {
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    let mut vm = vm()?;
+
+    vm.set_interrupt_hook(1, {
+        let calls = calls.clone();
+
+        move |_| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            ControlFlow::Break(())
+        }
+    });
+
+    // The loop executes in a virtual machine nested inside of `collect`, which
+    // doesn't call the hook since it can't be suspended.
+    let mut execution = vm.execute(["nested"], (1000i64,))?;
+
+    let value = loop {
+        match execution.resume().into_result() {
+            Ok(GeneratorState::Complete(value)) => break value,
+            Ok(GeneratorState::Yielded(..)) => panic!("unexpected yield"),
+            Err(error) if error.is_interrupted() => {}
+            Err(error) => return Err(error.into()),
+        }
+    };
+
+    assert_eq!(from_value::<Vec<i64>>(value)?, [1000]);
+
+    let calls = calls.load(Ordering::SeqCst);
+    assert!(calls > 0 && calls < 100, "{calls}");
+    Ok(())
+}
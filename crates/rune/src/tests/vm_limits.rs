@@ -0,0 +1,152 @@
+prelude!();
+
+use rune::runtime::VmError;
+
+fn recurse() -> Result<Vm> {
+    let context = Context::with_default_modules()?;
+
+    let mut sources = sources! {
+        entry => {
+            fn recurse(n) {
+                if n == 0 {
+                    0
+                } else {
+                    recurse(n - 1) + 1
+                }
+            }
+
+            pub fn main(n) {
+                recurse(n)
+            }
+
+            pub async fn main_async(n) {
+                let n = async { n }.await;
+                recurse(n)
+            }
+
+            pub fn main_map(n) {
+                [n].iter().map(recurse).collect::<Vec>()
+            }
+
+            fn nested(n) {
+                if n == 0 {
+                    [10].iter().map(recurse).collect::<Vec>()
+                } else {
+                    nested(n - 1)
+                }
+            }
+
+            pub fn main_nested(n) {
+                nested(n)
+            }
+        }
+    };
+
+    Ok(crate::tests::vm(
+        &context,
+        &mut sources,
+        &mut Diagnostics::new(),
+        false,
+    )?)
+}
+
+fn assert_overflow(error: VmError, expected: usize) {
+    assert!(error.is_stack_overflow(), "{error}");
+
+    match error.into_kind() {
+        VmErrorKind::StackOverflow { limit } => assert_eq!(limit, expected),
+        kind => panic!("Unexpected error: {kind}"),
+    }
+}
+
+#[test]
+fn max_call_frames() -> Result<()> {
+    // Calling `recurse(n)` requires `n + 1` call frames.
+    let mut vm = recurse()?.with_max_call_frames(11);
+
+    let value: i64 = from_value(vm.call(["main"], (10i64,))?)?;
+    assert_eq!(value, 10);
+
+    let error = vm.call(["main"], (11i64,)).unwrap_err();
+    assert_overflow(error, 11);
+    Ok(())
+}
+
+#[test]
+fn max_stack() -> Result<()> {
+    let mut vm = recurse()?;
+
+    let value: i64 = from_value(vm.call(["main"], (10i64,))?)?;
+    assert_eq!(value, 10);
+
+    // Find the smallest stack which can recurse to the given depth.
+    let limit = (0..)
+        .find(|&limit| {
+            let mut vm = recurse().unwrap().with_max_stack(limit);
+            vm.call(["main"], (10i64,)).is_ok()
+        })
+        .unwrap();
+
+    let mut vm = recurse()?.with_max_stack(limit);
+    let value: i64 = from_value(vm.call(["main"], (10i64,))?)?;
+    assert_eq!(value, 10);
+
+    let error = vm.call(["main"], (11i64,)).unwrap_err();
+    assert_overflow(error, limit);
+
+    let mut vm = recurse()?.with_max_stack(limit - 1);
+    let error = vm.call(["main"], (10i64,)).unwrap_err();
+    assert_overflow(error, limit - 1);
+    Ok(())
+}
+
+#[test]
+fn unlimited_by_default() -> Result<()> {
+    let mut vm = recurse()?;
+    let value: i64 = from_value(vm.call(["main"], (1000i64,))?)?;
+    assert_eq!(value, 1000);
+    Ok(())
+}
+
+#[test]
+fn max_call_frames_async() -> Result<()> {
+    let mut vm = recurse()?.with_max_call_frames(11);
+
+    let value: i64 = from_value(block_on(vm.async_call(["main_async"], (10i64,)))?)?;
+    assert_eq!(value, 10);
+
+    let error = block_on(vm.async_call(["main_async"], (11i64,))).unwrap_err();
+    assert_overflow(error, 11);
+    Ok(())
+}
+
+#[test]
+fn max_call_frames_function_value() -> Result<()> {
+    // Function values called from native functions execute in a new virtual
+    // machine which inherits the limits of the caller. Since `recurse` is the
+    // entrypoint of that virtual machine, `recurse(n)` only requires `n` call
+    // frames.
+    let mut vm = recurse()?.with_max_call_frames(11);
+
+    let value: Vec<i64> = from_value(vm.call(["main_map"], (11i64,))?)?;
+    assert_eq!(value, [11]);
+
+    let error = vm.call(["main_map"], (12i64,)).unwrap_err();
+    assert_overflow(error, 11);
+    Ok(())
+}
+
+#[test]
+fn max_call_frames_nested() -> Result<()> {
+    // Call frames used by the caller count towards the limit of virtual
+    // machines nested inside of native function calls. `nested(n)` uses
+    // `n + 1` call frames before `recurse(10)` uses another 10.
+    let mut vm = recurse()?.with_max_call_frames(11);
+
+    let value: Vec<i64> = from_value(vm.call(["main_nested"], (0i64,))?)?;
+    assert_eq!(value, [10]);
+
+    let error = vm.call(["main_nested"], (1i64,)).unwrap_err();
+    assert_overflow(error, 11);
+    Ok(())
+}
@@ -66,6 +66,30 @@ fn test_byte_string_literals() {
     assert_eq!(out, b"a b"[..]);
 }
 
+#[test]
+fn test_raw_string_literals() {
+    let out: String = rune!(r"a\nb");
+    assert_eq!(out, r"a\nb");
+
+    let out: String = rune!(r#"say "hi""#);
+    assert_eq!(out, r#"say "hi""#);
+
+    let out: String = rune!(r##"a "# b"##);
+    assert_eq!(out, r##"a "# b"##);
+
+    let out: String = rune!(
+        r"a
+b\"
+    );
+    assert_eq!(out, "a\nb\\");
+
+    let out: Bytes = rune!(br"a\nb");
+    assert_eq!(out, br"a\nb"[..]);
+
+    let out: Bytes = rune!(br#"say "hi""#);
+    assert_eq!(out, br#"say "hi""#[..]);
+}
+
 #[test]
 fn test_number_literals() {
     macro_rules! test_case {
@@ -85,6 +109,11 @@ fn test_number_literals() {
     test_case!(0xf_f);
     test_case!(-0xf_f);
 
+    test_case!(0x_ff);
+    test_case!(-0x_ff);
+
+    test_case!(1_000_000);
+
     test_case!(42);
     test_case!(-42);
 
@@ -106,18 +135,16 @@ fn test_number_literals() {
     test_case!(42.42, f32);
     test_case!(-42.42, f32);
 
-    // TODO: we need a different float parsing routine to support _ in floats.
-    // test_case!(42_.42, f32);
-    // test_case!(4_2.42, f32);
-    // test_case!(42.4_2, f32);
-    // test_case!(4_2.4_2, f32);
+    test_case!(42_.42, f32);
+    test_case!(4_2.42, f32);
+    test_case!(42.4_2, f32);
+    test_case!(4_2.4_2, f32);
 
     test_case!(1.9e10, f64);
     test_case!(-1.9e10, f64);
 
-    // TODO: we need a different float parsing routine to support _ in floats.
-    // test_case!(1_.9e10, f64);
-    // test_case!(1.9e1_0, f64);
+    test_case!(1_.9e10, f64);
+    test_case!(1.9e1_0, f64);
 
     test_case!(1e10, f64);
 }
@@ -0,0 +1,173 @@
+prelude!();
+
+use core::ops::ControlFlow;
+
+use rune::runtime::{ExecutionSnapshot, GeneratorState, RuntimeContext, Unit, VmExecution};
+
+fn build(context: &Context, mut sources: Sources) -> Result<Arc<Unit>> {
+    Ok(Arc::new(
+        prepare(&mut sources).with_context(context).build()?,
+    ))
+}
+
+fn generator() -> Result<(Arc<RuntimeContext>, Arc<Unit>)> {
+    let context = Context::with_default_modules()?;
+    let runtime = Arc::new(context.runtime()?);
+
+    let sources = sources! {
+        entry => {
+            pub fn main(n) {
+                let values = [];
+                let name = "numbers";
+
+                let i = 0;
+
+                while i < n {
+                    values.push(i);
+                    yield i;
+                    i += 1;
+                }
+
+                (name, values)
+            }
+        }
+    };
+
+    Ok((runtime, build(&context, sources)?))
+}
+
+fn round_trip(snapshot: &ExecutionSnapshot) -> Result<ExecutionSnapshot> {
+    let json = serde_json::to_string(snapshot)?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+fn expect_yield<T>(execution: &mut VmExecution<T>) -> Result<i64>
+where
+    T: AsRef<Vm> + AsMut<Vm>,
+{
+    match execution.resume().into_result()? {
+        GeneratorState::Yielded(value) => Ok(from_value(value)?),
+        GeneratorState::Complete(..) => panic!("unexpected completion"),
+    }
+}
+
+#[test]
+fn snapshot_generator() -> Result<()> {
+    let (runtime, unit) = generator()?;
+
+    let mut vm = Vm::new(runtime.clone(), unit.clone());
+    let mut execution = vm.execute(["main"], (4i64,))?;
+
+    assert_eq!(expect_yield(&mut execution)?, 0);
+    assert_eq!(expect_yield(&mut execution)?, 1);
+
+    let snapshot = round_trip(&execution.snapshot()?)?;
+    drop(execution);
+
+    let mut execution = VmExecution::restore(runtime, unit, &snapshot)?;
+
+    assert_eq!(expect_yield(&mut execution)?, 2);
+    assert_eq!(expect_yield(&mut execution)?, 3);
+
+    let GeneratorState::Complete(value) = execution.resume().into_result()? else {
+        panic!("expected completion");
+    };
+
+    let (name, values): (String, Vec<i64>) = from_value(value)?;
+    assert_eq!(name, "numbers");
+    assert_eq!(values, [0, 1, 2, 3]);
+    Ok(())
+}
+
+#[test]
+fn snapshot_interrupted() -> Result<()> {
+    let (runtime, unit) = generator()?;
+
+    let mut vm = Vm::new(runtime.clone(), unit.clone());
+    vm.set_interrupt_hook(8, |_| ControlFlow::Break(()));
+
+    let mut execution = vm.execute(["main"], (4i64,))?;
+
+    let error = execution.resume().into_result().unwrap_err();
+    assert!(error.is_interrupted());
+
+    let snapshot = round_trip(&execution.snapshot()?)?;
+
+    let mut execution = VmExecution::restore(runtime, unit, &snapshot)?;
+    let mut yielded = Vec::new();
+
+    let value = loop {
+        match execution.resume().into_result()? {
+            GeneratorState::Yielded(value) => yielded.push(from_value::<i64>(value)?),
+            GeneratorState::Complete(value) => break value,
+        }
+    };
+
+    let (_, values): (String, Vec<i64>) = from_value(value)?;
+    assert_eq!(values, [0, 1, 2, 3]);
+    assert_eq!(yielded, [0, 1, 2, 3]);
+    Ok(())
+}
+
+#[test]
+fn snapshot_unsupported() -> Result<()> {
+    let context = Context::with_default_modules()?;
+    let runtime = Arc::new(context.runtime()?);
+
+    let sources = sources! {
+        entry => {
+            struct Point { x, y }
+
+            pub fn main() {
+                let point = Point { x: 1, y: 2 };
+                let f = || 42;
+                yield 1;
+                point.x + f()
+            }
+        }
+    };
+
+    let unit = build(&context, sources)?;
+    let mut vm = Vm::new(runtime, unit);
+    let mut execution = vm.execute(["main"], ())?;
+    assert_eq!(expect_yield(&mut execution)?, 1);
+
+    let error = execution.snapshot().unwrap_err();
+    let message = error.to_string();
+    assert!(message.contains("`Point`"), "{message}");
+    assert!(message.contains("Function`"), "{message}");
+    Ok(())
+}
+
+#[test]
+fn snapshot_unit_mismatch() -> Result<()> {
+    let (runtime, unit) = generator()?;
+
+    let mut vm = Vm::new(runtime.clone(), unit);
+    let mut execution = vm.execute(["main"], (4i64,))?;
+    assert_eq!(expect_yield(&mut execution)?, 0);
+    let snapshot = execution.snapshot()?;
+
+    let context = Context::with_default_modules()?;
+
+    let other = build(
+        &context,
+        sources! {
+            entry => {
+                pub fn main(n) {
+                    yield n;
+                }
+            }
+        },
+    )?;
+
+    let Err(error) = VmExecution::restore(runtime, other, &snapshot) else {
+        panic!("expected restore to fail");
+    };
+
+    assert_eq!(
+        error.to_string(),
+        "Snapshot was taken from a different unit"
+    );
+    Ok(())
+}
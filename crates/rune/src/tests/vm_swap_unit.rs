@@ -0,0 +1,153 @@
+prelude!();
+
+use rune::compile::BuildCache;
+use rune::runtime::{Generator, GeneratorState, UnitDiff};
+use rune::Unit;
+
+fn build(context: &Context, sources: &mut Sources) -> Result<Arc<Unit>> {
+    Ok(Arc::new(prepare(sources).with_context(context).build()?))
+}
+
+#[test]
+fn swap_between_calls() -> Result<()> {
+    let context = Context::with_default_modules()?;
+    let runtime = Arc::new(context.runtime()?);
+
+    let old = build(
+        &context,
+        &mut sources! {
+            entry => {
+                pub fn main() { 1 }
+                fn generate() { yield 1; yield 2; }
+                pub fn numbers() { generate() }
+            }
+        },
+    )?;
+
+    let new = build(
+        &context,
+        &mut sources! {
+            entry => {
+                pub fn main() { 2 }
+                fn generate() { yield 10; yield 20; }
+                pub fn numbers() { generate() }
+            }
+        },
+    )?;
+
+    let mut vm = Vm::new(runtime, old.clone());
+    assert_eq!(from_value::<i64>(vm.call(["main"], ())?)?, 1);
+
+    // Executions which are pending against the old unit.
+    let mut generator: Generator = from_value(vm.call(["numbers"], ())?)?;
+    let mut pending = vm.try_clone()?;
+    let mut execution = pending.execute(["main"], ())?;
+
+    let diff = UnitDiff::new(&old, &new)?;
+    assert!(diff.is_compatible());
+    assert!(diff.added().is_empty());
+
+    let previous = vm.try_swap_unit(new.clone()).ok().expect("swap failed");
+    assert!(Arc::ptr_eq(&previous, &old));
+    assert_eq!(from_value::<i64>(vm.call(["main"], ())?)?, 2);
+
+    let GeneratorState::Yielded(value) = generator.resume(Value::empty()).into_result()? else {
+        panic!("expected yield");
+    };
+
+    assert_eq!(from_value::<i64>(value)?, 1);
+
+    let value = execution.complete().into_result()?;
+    assert_eq!(from_value::<i64>(value)?, 1);
+    Ok(())
+}
+
+#[test]
+fn swap_rejected_while_in_flight() -> Result<()> {
+    let context = Context::with_default_modules()?;
+    let runtime = Arc::new(context.runtime()?);
+
+    let old = build(&context, &mut sources!(entry => { pub fn main() { 1 } }))?;
+    let new = build(&context, &mut sources!(entry => { pub fn main() { 2 } }))?;
+
+    let mut vm = Vm::new(runtime, old);
+
+    // Start an execution and abandon it before it completes.
+    let mut execution = vm.execute(["main"], ())?;
+    assert!(execution.step().into_result()?.is_none());
+    drop(execution);
+
+    let new = vm.try_swap_unit(new).err().expect("swap should fail");
+
+    vm.clear();
+    assert!(vm.try_swap_unit(new).is_ok());
+    assert_eq!(from_value::<i64>(vm.call(["main"], ())?)?, 2);
+
+    // A completed execution is no longer in flight.
+    let value = vm.execute(["main"], ())?.complete().into_result()?;
+    assert_eq!(from_value::<i64>(value)?, 2);
+    assert!(vm.try_swap_unit(vm.unit().clone()).is_ok());
+    Ok(())
+}
+
+#[test]
+fn swap_after_failed_call() -> Result<()> {
+    let context = Context::with_default_modules()?;
+    let runtime = Arc::new(context.runtime()?);
+
+    let old = build(
+        &context,
+        &mut sources!(entry => { pub fn main() { panic("failed") } }),
+    )?;
+
+    let new = build(&context, &mut sources!(entry => { pub fn main() { 2 } }))?;
+
+    let mut vm = Vm::new(runtime, old.clone());
+    assert!(vm.call(["main"], ()).is_err());
+    assert!(vm.try_swap_unit(old).is_ok());
+
+    // An execution which failed can't be resumed, so it's no longer in
+    // flight.
+    assert!(vm.execute(["main"], ())?.complete().is_err());
+    assert!(vm.try_swap_unit(new).is_ok());
+    assert_eq!(from_value::<i64>(vm.call(["main"], ())?)?, 2);
+    Ok(())
+}
+
+#[test]
+fn replace_source_and_rebuild() -> Result<()> {
+    let context = Context::with_default_modules()?;
+    let runtime = Arc::new(context.runtime()?);
+    let mut cache = BuildCache::new();
+
+    let mut sources = Sources::new();
+    sources.insert(Source::new("main", "pub fn main() { value() }")?)?;
+    let lib = sources.insert(Source::new("lib", "pub fn value() { 1 }")?)?;
+
+    let old = Arc::new(
+        prepare(&mut sources)
+            .with_context(&context)
+            .with_cache(&mut cache)
+            .build()?,
+    );
+
+    let mut vm = Vm::new(runtime, old);
+    assert_eq!(from_value::<i64>(vm.call(["main"], ())?)?, 1);
+
+    let previous = sources.replace(lib, Source::new("lib", "pub fn value() { 2 }")?);
+    assert!(previous.is_some());
+
+    // Only the replaced source is processed again.
+    let new = Arc::new(
+        prepare(&mut sources)
+            .with_context(&context)
+            .with_cache(&mut cache)
+            .build()?,
+    );
+
+    assert_eq!((cache.hits(), cache.misses()), (1, 3));
+
+    assert!(vm.try_swap_unit(new).is_ok());
+    assert_eq!(from_value::<i64>(vm.call(["main"], ())?)?, 2);
+    Ok(())
+}
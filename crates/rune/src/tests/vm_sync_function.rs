@@ -0,0 +1,73 @@
+prelude!();
+
+use rune::runtime::SyncFunction;
+
+fn function(sources: &mut Sources) -> Result<SyncFunction> {
+    let context = Context::with_default_modules()?;
+    let unit = prepare(sources).with_context(&context).build()?;
+
+    let mut vm = Vm::new(Arc::new(context.runtime()?), Arc::new(unit));
+    let function = vm.call(["main"], ())?;
+    Ok(from_value(function)?)
+}
+
+#[test]
+fn concurrent_calls() -> Result<()> {
+    let function = function(&mut sources! {
+        entry => {
+            pub fn main() {
+                let offset = 100;
+                move |n| offset + n * 2
+            }
+        }
+    })?;
+
+    let sum = std::thread::scope(|s| {
+        let handles = (0..4i64)
+            .map(|n| {
+                let function = &function;
+                s.spawn(move || function.call::<i64>((n,)).into_result())
+            })
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("thread panicked"))
+            .sum::<Result<i64, _>>()
+    })?;
+
+    assert_eq!(sum, 4 * 100 + (0 + 1 + 2 + 3) * 2);
+    Ok(())
+}
+
+#[test]
+fn concurrent_blocking_async_calls() -> Result<()> {
+    let function = function(&mut sources! {
+        entry => {
+            async fn square(n) {
+                n * n
+            }
+
+            pub fn main() {
+                square
+            }
+        }
+    })?;
+
+    let sum = std::thread::scope(|s| {
+        let handles = (1..=4i64)
+            .map(|n| {
+                let function = &function;
+                s.spawn(move || function.call_blocking::<i64>((n,)).into_result())
+            })
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("thread panicked"))
+            .sum::<Result<i64, _>>()
+    })?;
+
+    assert_eq!(sum, 1 + 4 + 9 + 16);
+    Ok(())
+}
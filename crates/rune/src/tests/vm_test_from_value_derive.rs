@@ -1,5 +1,6 @@
 prelude!();
 
+use crate::ToValue;
 use VmErrorKind::*;
 
 #[test]
@@ -113,3 +114,180 @@ fn test_enum_proxy() {
         }
     );
 }
+
+#[test]
+fn test_enum_round_trip() {
+    #[derive(Debug, PartialEq, Eq, FromValue, ToValue)]
+    enum Proxy {
+        Empty,
+        Tuple(String, u32),
+        Struct { field: String, number: u32 },
+    }
+
+    for proxy in [
+        Proxy::Empty,
+        Proxy::Tuple(String::from("Hello World"), 42),
+        Proxy::Struct {
+            field: String::from("Hello World"),
+            number: 42,
+        },
+    ] {
+        let expected = format!("{proxy:?}");
+        let value = rune::to_value(proxy).unwrap();
+        let proxy: Proxy = rune::from_value(value).unwrap();
+        assert_eq!(format!("{proxy:?}"), expected);
+    }
+}
+
+#[test]
+fn test_enum_to_script() {
+    #[derive(Debug, PartialEq, Eq, FromValue, ToValue)]
+    enum Proxy {
+        Empty,
+        Tuple(u32),
+        Struct { field: u32 },
+    }
+
+    let context = Context::with_default_modules().unwrap();
+
+    let source = r#"
+        enum Proxy { Empty, Tuple(a), Struct { field } }
+
+        pub fn main(proxy) {
+            match proxy {
+                Proxy::Empty => Proxy::Tuple(1),
+                Proxy::Tuple(a) => Proxy::Struct { field: a + 1 },
+                Proxy::Struct { field } => Proxy::Struct { field: field + 1 },
+            }
+        }
+    "#;
+
+    let proxy: Proxy = run(&context, source, (Proxy::Empty,), false).unwrap();
+    assert_eq!(proxy, Proxy::Tuple(1));
+
+    let proxy: Proxy = run(&context, source, (Proxy::Tuple(1),), false).unwrap();
+    assert_eq!(proxy, Proxy::Struct { field: 2 });
+
+    let proxy: Proxy = run(&context, source, (Proxy::Struct { field: 2 },), false).unwrap();
+    assert_eq!(proxy, Proxy::Struct { field: 3 });
+}
+
+#[test]
+fn test_rename() {
+    #[derive(Debug, PartialEq, Eq, FromValue, ToValue)]
+    struct Proxy {
+        #[rune(rename = "fieldName")]
+        field_name: u32,
+    }
+
+    #[derive(Debug, PartialEq, Eq, FromValue, ToValue)]
+    enum ProxyEnum {
+        #[rune(rename = "Renamed")]
+        Variant {
+            #[rune(rename = "fieldName")]
+            field_name: u32,
+        },
+    }
+
+    let value: Proxy = rune! {
+        struct Value { fieldName }
+        Value { fieldName: 42 }
+    };
+
+    assert_eq!(value, Proxy { field_name: 42 });
+
+    let value: u32 = rune_n! {
+        (Proxy { field_name: 42 },),
+        pub fn main(proxy) { proxy.fieldName }
+    };
+
+    assert_eq!(value, 42);
+
+    let value: ProxyEnum = rune! {
+        enum ProxyEnum { Renamed { fieldName } }
+        ProxyEnum::Renamed { fieldName: 42 }
+    };
+
+    assert_eq!(value, ProxyEnum::Variant { field_name: 42 });
+
+    let value = rune::to_value(ProxyEnum::Variant { field_name: 42 }).unwrap();
+    let value: ProxyEnum = rune::from_value(value).unwrap();
+    assert_eq!(value, ProxyEnum::Variant { field_name: 42 });
+}
+
+#[test]
+fn test_missing_variant() {
+    #[derive(Debug, FromValue)]
+    enum Proxy {
+        Empty,
+    }
+
+    #[derive(Debug, ToValue)]
+    enum Other {
+        Unknown,
+    }
+
+    let value = rune::to_value(Other::Unknown).unwrap();
+    let error = rune::from_value::<Proxy>(value).unwrap_err();
+    assert_eq!(error.to_string(), "No variant matching `Unknown`");
+}
+
+#[test]
+fn test_enum_type_hash() {
+    #[derive(Debug, ToValue)]
+    enum Proxy {
+        Empty,
+    }
+
+    #[derive(Debug, ToValue)]
+    #[rune(item = ::module)]
+    enum Nested {
+        Empty,
+    }
+
+    let expected: Value = rune! {
+        enum Proxy { Empty }
+        Proxy::Empty
+    };
+
+    let value = rune::to_value(Proxy::Empty).unwrap();
+    assert_eq!(value.type_hash(), expected.type_hash());
+
+    let value = rune::to_value(Nested::Empty).unwrap();
+    let item = ItemBuf::with_crate_item("module", ["Nested"]).unwrap();
+    assert_eq!(value.type_hash(), Hash::type_hash(&item));
+}
+
+#[test]
+fn test_generic_enum() {
+    #[derive(Debug, PartialEq, Eq, FromValue, ToValue)]
+    enum Proxy<T> {
+        Tuple(T),
+        Struct { field: T },
+    }
+
+    #[derive(Debug, PartialEq, Eq, FromValue, ToValue)]
+    struct Wrapper<T>(T);
+
+    let value = rune::to_value(Proxy::Tuple(42u32)).unwrap();
+    let value: Proxy<u32> = rune::from_value(value).unwrap();
+    assert_eq!(value, Proxy::Tuple(42));
+
+    let value = rune::to_value(Proxy::Struct {
+        field: String::from("Hello World"),
+    })
+    .unwrap();
+
+    let value: Proxy<String> = rune::from_value(value).unwrap();
+
+    assert_eq!(
+        value,
+        Proxy::Struct {
+            field: String::from("Hello World")
+        }
+    );
+
+    let value = rune::to_value(Wrapper(42u32)).unwrap();
+    let value: (u32,) = rune::from_value(value).unwrap();
+    assert_eq!(value, (42,));
+}
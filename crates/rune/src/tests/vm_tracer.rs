@@ -0,0 +1,109 @@
+prelude!();
+
+use rune::runtime::{CollectingTracer, Inst, InstArithmeticOp};
+
+fn vm(tracer: &CollectingTracer) -> Result<Vm> {
+    let context = Context::with_default_modules()?;
+
+    let mut sources = sources! {
+        entry => {
+            pub fn main(d) {
+                let n = 0;
+
+                while n < 100 {
+                    n += 1;
+                }
+
+                n / d
+            }
+        }
+    };
+
+    let vm = crate::tests::vm(&context, &mut sources, &mut Diagnostics::new(), false)?;
+    Ok(vm.with_tracer(tracer.clone()))
+}
+
+#[test]
+fn last_instructions_before_error() -> Result<()> {
+    let tracer = CollectingTracer::new(4);
+    let mut vm = vm(&tracer)?;
+
+    assert!(vm.call(["main"], (0i64,)).is_err());
+
+    let entries = tracer.entries();
+    assert_eq!(entries.len(), 4);
+
+    let last = entries.last().expect("missing entry");
+    assert_eq!(last.ip, vm.last_ip());
+
+    assert!(matches!(
+        last.inst,
+        Inst::Arithmetic {
+            op: InstArithmeticOp::Div,
+            ..
+        }
+    ));
+
+    let mut out = Vec::new();
+    tracer.render(&mut out)?;
+    let out = core::str::from_utf8(&out)?;
+    assert!(out.starts_with("# last 4 instructions before the error\n"));
+    assert_eq!(out.lines().count(), 5);
+    Ok(())
+}
+
+#[test]
+fn records_everything_within_capacity() -> Result<()> {
+    let tracer = CollectingTracer::new(usize::MAX);
+    let mut vm = vm(&tracer)?;
+
+    let value = vm.call(["main"], (2i64,))?;
+    assert_eq!(from_value::<i64>(value)?, 50);
+
+    let entries = tracer.entries();
+    assert!(entries.len() > 100);
+    assert_eq!(entries[0].ip, 0);
+
+    tracer.clear();
+    assert!(tracer.entries().is_empty());
+    Ok(())
+}
+
+#[test]
+fn traces_nested_executions() -> Result<()> {
+    let context = Context::with_default_modules()?;
+
+    let mut sources = sources! {
+        entry => {
+            pub fn main() {
+                [1, 2, 3].iter().map(|n| n * 10).collect::<Vec>()
+            }
+        }
+    };
+
+    let tracer = CollectingTracer::new(usize::MAX);
+    let vm = crate::tests::vm(&context, &mut sources, &mut Diagnostics::new(), false)?;
+    let mut vm = vm.with_tracer(tracer.clone());
+
+    let value: Vec<i64> = from_value(vm.call(["main"], ())?)?;
+    assert_eq!(value, [10, 20, 30]);
+
+    // The closure is called from a native function, so it executes in a
+    // nested virtual machine.
+    let multiplications = tracer
+        .entries()
+        .iter()
+        .filter(|entry| {
+            matches!(
+                entry.inst,
+                Inst::Arithmetic {
+                    op: InstArithmeticOp::Mul,
+                    ..
+                }
+            )
+        })
+        .count();
+
+    assert_eq!(multiplications, 3);
+    Ok(())
+}
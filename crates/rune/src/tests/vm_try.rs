@@ -2,6 +2,9 @@ prelude!();
 
 use core::ops::ControlFlow;
 
+use crate::runtime::TryKind;
+use VmErrorKind::*;
+
 #[test]
 fn custom_try() -> Result<()> {
     #[derive(Any)]
@@ -36,3 +39,121 @@ fn custom_try() -> Result<()> {
     assert_eq!(result, Err(0));
     Ok(())
 }
+
+#[test]
+fn option_in_option() {
+    let out: Option<i64> = rune! {
+        fn add(a, b) {
+            Some(a? + b?)
+        }
+
+        add(Some(1), Some(2))
+    };
+    assert_eq!(out, Some(3));
+
+    let out: Option<i64> = rune! {
+        fn add(a, b) {
+            let a = a?;
+            let b = b?;
+            Some(a + b)
+        }
+
+        add(Some(1), None)
+    };
+    assert_eq!(out, None);
+}
+
+#[test]
+fn result_in_result() {
+    let out: Result<i64, i64> = rune! {
+        fn add(a, b) {
+            Ok(a? + b?)
+        }
+
+        add(Ok(1), Ok(2))
+    };
+    assert_eq!(out, Ok(3));
+
+    let out: Result<i64, i64> = rune! {
+        fn add(a, b) {
+            if a? < 0 {
+                return Err(-1);
+            }
+
+            Ok(a? + b?)
+        }
+
+        add(Ok(1), Err(2))
+    };
+    assert_eq!(out, Err(2));
+}
+
+#[test]
+fn control_flow() {
+    let out: bool = rune! {
+        use std::ops::ControlFlow;
+
+        fn add(a, b) {
+            ControlFlow::Continue(a? + b?)
+        }
+
+        add(ControlFlow::Continue(1), ControlFlow::Continue(2)) == ControlFlow::Continue(3)
+            && add(ControlFlow::Continue(1), ControlFlow::Break(2)) == ControlFlow::Break(2)
+    };
+    assert!(out);
+}
+
+#[test]
+fn closure_in_option() {
+    let out: Option<i64> = rune! {
+        let add = |a, b| Some(a? + b?);
+        add(None, Some(2))
+    };
+    assert_eq!(out, None);
+}
+
+#[test]
+fn kind_mismatch() {
+    assert_vm_error!(
+        r#"
+        fn add(a, b) {
+            Ok(a? + b?)
+        }
+
+        add(Ok(1), None)
+        "#,
+        TryKindMismatch { expected, actual } => {
+            assert_eq!(expected, TryKind::Result);
+            assert_eq!(actual.to_string(), "::std::option::Option");
+        }
+    );
+
+    assert_vm_error!(
+        r#"
+        fn add(a, b) {
+            Some(a? + b?)
+        }
+
+        add(Some(1), Err(2))
+        "#,
+        TryKindMismatch { expected, actual } => {
+            assert_eq!(expected, TryKind::Option);
+            assert_eq!(actual.to_string(), "::std::result::Result");
+        }
+    );
+}
+
+#[test]
+fn kind_unknown() {
+    // The kind of the function can't be inferred, so no checking is
+    // performed.
+    let out: Option<i64> = rune! {
+        fn add(a, b) {
+            let out = Ok(a? + b?);
+            out
+        }
+
+        add(Ok(1), None)
+    };
+    assert_eq!(out, None);
+}
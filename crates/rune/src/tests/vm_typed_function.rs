@@ -0,0 +1,99 @@
+prelude!();
+
+use rune::runtime::{RuntimeContext, Unit};
+
+fn unit() -> Result<(Arc<RuntimeContext>, Arc<Unit>)> {
+    let context = Context::with_default_modules()?;
+
+    let mut sources = sources! {
+        entry => {
+            pub fn add(a, b) {
+                a + b
+            }
+
+            pub async fn async_add(a, b) {
+                a + b
+            }
+
+            pub fn greet(name) {
+                format!("Hello, {}!", name)
+            }
+        }
+    };
+
+    let unit = prepare(&mut sources).with_context(&context).build()?;
+    Ok((Arc::new(context.runtime()?), Arc::new(unit)))
+}
+
+#[test]
+fn typed_function_calls() -> Result<()> {
+    let (context, unit) = unit()?;
+    let mut vm = Vm::new(context, unit);
+
+    let add = vm.typed_function::<(i64, i64), i64>(["add"])?;
+    assert_eq!(add.call(&mut vm, (1, 2))?, 3);
+    assert_eq!(add.call(&mut vm, (3, 4))?, 7);
+
+    let greet = vm.typed_function::<(&str,), String>(["greet"])?;
+    assert_eq!(greet.call(&mut vm, ("John",))?, "Hello, John!");
+    Ok(())
+}
+
+#[test]
+fn typed_function_async_calls() -> Result<()> {
+    let (context, unit) = unit()?;
+    let mut vm = Vm::new(context, unit);
+
+    let add = vm.typed_function::<(i64, i64), i64>(["async_add"])?;
+    let value = block_on(async { add.async_call(&mut vm, (1, 2)).await })?;
+    assert_eq!(value, 3);
+    Ok(())
+}
+
+#[test]
+fn typed_function_arity_mismatch() -> Result<()> {
+    let (context, unit) = unit()?;
+    let vm = Vm::new(context, unit.clone());
+
+    assert_eq!(
+        unit.function_info(Hash::type_hash(["add"]))
+            .map(|info| info.args()),
+        Some(2)
+    );
+
+    let error = vm
+        .typed_function::<(i64,), i64>(["add"])
+        .expect_err("arity should not match");
+
+    assert_eq!(
+        error.into_kind(),
+        VmErrorKind::BadArgumentCount {
+            actual: 1,
+            expected: 2
+        }
+    );
+
+    let error = vm
+        .typed_function::<(i64, i64), i64>(["missing"])
+        .expect_err("function should be missing");
+
+    assert!(matches!(
+        error.into_kind(),
+        VmErrorKind::MissingEntry { .. }
+    ));
+    Ok(())
+}
+
+#[test]
+fn typed_function_other_unit() -> Result<()> {
+    let (context, unit) = unit()?;
+    let vm = Vm::new(context.clone(), unit);
+    let add = vm.typed_function::<(i64, i64), i64>(["add"])?;
+
+    // A handle used with a virtual machine with a different unit looks the
+    // function up again.
+    let (_, other) = self::unit()?;
+    let mut other = Vm::new(context, other);
+    assert_eq!(add.call(&mut other, (1, 2))?, 3);
+    Ok(())
+}
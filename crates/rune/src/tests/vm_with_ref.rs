@@ -0,0 +1,95 @@
+prelude!();
+
+#[derive(Any, Debug, Default)]
+struct Counter {
+    #[rune(get, set, copy)]
+    value: i64,
+}
+
+fn vm() -> Result<Vm> {
+    let mut module = Module::new();
+    module.ty::<Counter>()?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(module)?;
+
+    let mut sources = sources! {
+        entry => {
+            pub fn update(counter) {
+                counter.value += 1;
+            }
+
+            pub fn read(counter) {
+                counter.value
+            }
+
+            pub fn object() {
+                #{}
+            }
+
+            pub fn store(object, counter) {
+                object.counter = counter;
+            }
+
+            pub fn load(object) {
+                object.counter.value
+            }
+        }
+    };
+
+    let unit = prepare(&mut sources).with_context(&context).build()?;
+    Ok(Vm::new(Arc::new(context.runtime()?), Arc::new(unit)))
+}
+
+#[test]
+fn with_mut_in_place() -> Result<()> {
+    let mut vm = vm()?;
+    let mut counter = Counter::default();
+
+    vm.with_mut(&mut counter, |vm, handle| {
+        vm.call(["update"], (handle.clone(),))?;
+        vm.call(["update"], (handle,))
+    })??;
+
+    assert_eq!(counter.value, 2);
+    Ok(())
+}
+
+#[test]
+fn with_ref_read() -> Result<()> {
+    let mut vm = vm()?;
+    let counter = Counter { value: 42 };
+
+    let output = vm.with_ref(&counter, |vm, handle| vm.call(["read"], (handle,)))??;
+    assert_eq!(from_value::<i64>(output)?, 42);
+
+    // Shared references can't be written to.
+    let result = vm.with_ref(&counter, |vm, handle| vm.call(["update"], (handle,)))?;
+    assert!(result.is_err());
+    assert_eq!(counter.value, 42);
+    Ok(())
+}
+
+#[test]
+fn with_mut_escaped() -> Result<()> {
+    let mut vm = vm()?;
+    let object = vm.call(["object"], ())?;
+
+    let mut counter = Counter { value: 42 };
+
+    let output = vm.with_mut(&mut counter, |vm, handle| {
+        vm.call(["store"], (object.clone(), handle))?;
+        vm.call(["load"], (object.clone(),))
+    })??;
+
+    assert_eq!(from_value::<i64>(output)?, 42);
+
+    // The stored value has been invalidated once the scope ended.
+    let error = vm
+        .call(["load"], (object,))
+        .expect_err("value should have been invalidated");
+
+    assert!(error.to_string().contains("Cannot read"), "{error}");
+    assert_eq!(counter.value, 42);
+    Ok(())
+}
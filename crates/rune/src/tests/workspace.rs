@@ -0,0 +1,439 @@
+//! Tests for loading workspace manifests.
+
+prelude!();
+
+use std::path::{Path, PathBuf};
+
+use rune::ast::Spanned;
+use rune::workspace::{self, Diagnostic, FoundKind, WorkspaceFilter};
+
+fn fixture() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/workspace")
+}
+
+fn fixture_named(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join(name)
+}
+
+#[test]
+fn workspace_manifest() -> Result<()> {
+    let root = fixture();
+
+    let mut sources = Sources::new();
+    sources.insert(Source::from_path(root.join(workspace::MANIFEST_FILE))?)?;
+
+    let mut diagnostics = workspace::Diagnostics::new();
+
+    let manifest = workspace::prepare(&mut sources)
+        .with_diagnostics(&mut diagnostics)
+        .build()?;
+
+    assert!(!diagnostics.has_errors());
+    assert!(diagnostics.has_warnings());
+
+    let [Diagnostic::Warning(warning)] = diagnostics.diagnostics() else {
+        panic!("expected a single warning: {:?}", diagnostics.diagnostics());
+    };
+
+    assert_eq!(warning.error().to_string(), "Key `unknown` not supported");
+
+    let source = sources.get(warning.source_id()).context("missing source")?;
+    let span = warning.error().span();
+    assert_eq!(&source.as_str()[span.range()], "unknown");
+
+    let names = manifest
+        .packages()
+        .iter()
+        .map(|p| p.name.as_str())
+        .collect::<Vec<_>>();
+
+    assert_eq!(names, ["a", "b"]);
+
+    let a = manifest.package("a").context("missing package a")?;
+    assert_eq!(a.version.to_string(), "0.1.0");
+    assert_eq!(a.authors.as_slice(), ["Jane Doe <jane@example.com>"]);
+    assert_eq!(a.description.as_deref(), Some("The first package"));
+    assert_eq!(a.license.as_deref(), Some("MIT"));
+    assert_eq!(a.root.as_deref(), Some(root.join("a").as_path()));
+
+    let targets = manifest.targets(a)?;
+    let targets = targets
+        .iter()
+        .map(|t| (t.kind, t.name.as_str(), t.path.clone()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        targets,
+        [
+            (FoundKind::Binary, "main", root.join("a/bin/main.rn")),
+            (FoundKind::Test, "smoke", root.join("a/tests/smoke.rn")),
+        ]
+    );
+
+    let b = manifest.package("b").context("missing package b")?;
+    assert_eq!(b.version.to_string(), "0.2.0");
+    assert!(b.authors.is_empty());
+    assert_eq!(b.description, None);
+
+    let targets = manifest.targets(b)?;
+    let targets = targets
+        .iter()
+        .map(|t| (t.kind, t.name.as_str(), t.path.clone()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        targets,
+        [(FoundKind::Example, "demo", root.join("b/examples/demo.rn"))]
+    );
+
+    let bins = manifest.find_bins(WorkspaceFilter::Name("main"))?;
+    assert_eq!(bins.len(), 1);
+    assert_eq!(bins[0].package.name, "a");
+
+    let value = serde_json::to_value(&manifest)?;
+
+    assert_eq!(
+        value,
+        serde_json::json!({
+            "packages": [
+                {
+                    "name": "a",
+                    "version": "0.1.0",
+                    "authors": ["Jane Doe <jane@example.com>"],
+                    "description": "The first package",
+                    "license": "MIT",
+                    "root": root.join("a"),
+                    "auto_bins": true,
+                    "auto_tests": true,
+                    "auto_examples": true,
+                    "auto_benches": true,
+                },
+                {
+                    "name": "b",
+                    "version": "0.2.0",
+                    "authors": [],
+                    "description": null,
+                    "license": null,
+                    "root": root.join("b"),
+                    "auto_bins": true,
+                    "auto_tests": true,
+                    "auto_examples": true,
+                    "auto_benches": true,
+                },
+            ]
+        })
+    );
+
+    let value = serde_json::to_value(&manifest.targets(b)?)?;
+
+    assert_eq!(
+        value,
+        serde_json::json!([
+            {
+                "kind": "example",
+                "path": root.join("b/examples/demo.rn"),
+                "name": "demo",
+            }
+        ])
+    );
+
+    Ok(())
+}
+
+#[test]
+fn workspace_dependencies() -> Result<()> {
+    let root = fixture_named("workspace-deps");
+
+    let mut sources = Sources::new();
+    sources.insert(Source::from_path(root.join(workspace::MANIFEST_FILE))?)?;
+
+    let mut diagnostics = workspace::Diagnostics::new();
+
+    let manifest = workspace::prepare(&mut sources)
+        .with_diagnostics(&mut diagnostics)
+        .build()?;
+
+    assert!(!diagnostics.has_errors());
+
+    let app = manifest.package("app").context("missing package app")?;
+
+    let [dependency] = &app.dependencies[..] else {
+        panic!("expected a single dependency: {:?}", app.dependencies);
+    };
+
+    assert_eq!(dependency.name, "greeting");
+    assert!(dependency.path.is_some());
+
+    let greeting = manifest
+        .package("greeting")
+        .context("missing package greeting")?;
+    assert_eq!(greeting.lib(), Some(root.join("greeting/lib.rn")));
+
+    let [bin] = &manifest.find_bins(WorkspaceFilter::All)?[..] else {
+        panic!("expected a single binary");
+    };
+
+    assert_eq!(bin.package.name, "app");
+
+    let mut sources = Sources::new();
+    bin.package.prepare_dependencies(&mut sources)?;
+    sources.insert(Source::from_path(&bin.found.path)?)?;
+
+    let context = Context::with_default_modules()?;
+    let mut diagnostics = Diagnostics::new();
+
+    let unit = prepare(&mut sources)
+        .with_context(&context)
+        .with_diagnostics(&mut diagnostics)
+        .build();
+
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics.diagnostics());
+
+    let mut vm = Vm::new(Arc::new(context.runtime()?), Arc::new(unit?));
+    let output: String = from_value(vm.call(["main"], ())?)?;
+    assert_eq!(output, "Hello, world!");
+    Ok(())
+}
+
+#[test]
+fn workspace_dependency_cycle() -> Result<()> {
+    let root = fixture_named("workspace-cycle");
+
+    let mut sources = Sources::new();
+    sources.insert(Source::from_path(root.join(workspace::MANIFEST_FILE))?)?;
+
+    let mut diagnostics = workspace::Diagnostics::new();
+
+    let result = workspace::prepare(&mut sources)
+        .with_diagnostics(&mut diagnostics)
+        .build();
+
+    assert!(result.is_err());
+
+    let [Diagnostic::Fatal(fatal)] = diagnostics.diagnostics() else {
+        panic!("expected a single error: {:?}", diagnostics.diagnostics());
+    };
+
+    let message = fatal.error().to_string();
+    assert!(message.contains("a -> b -> a"), "{message}");
+    Ok(())
+}
+
+#[test]
+fn workspace_glob_members() -> Result<()> {
+    let root = fixture_named("workspace-glob");
+
+    let mut sources = Sources::new();
+    let id = sources.insert(Source::from_path(root.join(workspace::MANIFEST_FILE))?)?;
+
+    let mut diagnostics = workspace::Diagnostics::new();
+
+    let manifest = workspace::prepare(&mut sources)
+        .with_diagnostics(&mut diagnostics)
+        .build()?;
+
+    assert!(!diagnostics.has_errors());
+
+    // `packages/beta` is matched by both `packages/*` and itself.
+    let [Diagnostic::Warning(warning)] = diagnostics.diagnostics() else {
+        panic!("expected a single warning: {:?}", diagnostics.diagnostics());
+    };
+
+    assert_eq!(warning.source_id(), id);
+    assert_eq!(
+        warning.error().to_string(),
+        std::format!(
+            "Package at `{}` is already a member of the workspace",
+            root.join("packages/beta").display()
+        )
+    );
+
+    let source = sources.get(id).context("missing source")?;
+    let span = warning.error().span();
+    assert_eq!(
+        source.as_str()[span.range()].trim_matches('"'),
+        "packages/beta"
+    );
+
+    // Members are always loaded in sorted order, and directories without a
+    // manifest are ignored.
+    let names = manifest
+        .packages()
+        .iter()
+        .map(|p| p.name.as_str())
+        .collect::<Vec<_>>();
+
+    assert_eq!(names, ["alpha", "beta"]);
+
+    let alpha = manifest.package("alpha").context("missing package alpha")?;
+    assert_eq!(alpha.version.to_string(), "1.2.3");
+    assert_eq!(alpha.description.as_deref(), Some("The alpha package"));
+    assert_eq!(
+        alpha.authors.as_slice(),
+        ["Jane Doe <jane@example.com>", "John Doe <john@example.com>"]
+    );
+
+    let beta = manifest.package("beta").context("missing package beta")?;
+    assert_eq!(beta.description, None);
+    assert!(beta.authors.is_empty());
+    Ok(())
+}
+
+#[test]
+fn workspace_empty_glob() -> Result<()> {
+    let root = fixture_named("workspace-empty-glob");
+
+    let mut sources = Sources::new();
+    let id = sources.insert(Source::from_path(root.join(workspace::MANIFEST_FILE))?)?;
+
+    let mut diagnostics = workspace::Diagnostics::new();
+
+    let result = workspace::prepare(&mut sources)
+        .with_diagnostics(&mut diagnostics)
+        .build();
+
+    assert!(result.is_err());
+
+    let [Diagnostic::Fatal(fatal)] = diagnostics.diagnostics() else {
+        panic!("expected a single error: {:?}", diagnostics.diagnostics());
+    };
+
+    assert_eq!(
+        fatal.error().to_string(),
+        "Member `missing/*` did not match any packages"
+    );
+
+    let source = sources.get(id).context("missing source")?;
+    let span = fatal.error().span();
+    assert_eq!(source.as_str()[span.range()].trim_matches('"'), "missing/*");
+    Ok(())
+}
+
+#[test]
+fn workspace_fmt_options() -> Result<()> {
+    let mut sources = Sources::new();
+
+    sources.insert(Source::new(
+        workspace::MANIFEST_FILE,
+        r#"
+        [package]
+        name = "a"
+        version = "0.1.0"
+
+        [fmt]
+        indent_width = 2
+        hard-tabs = false
+        trailing_comma = "never"
+        newline_style = "windows"
+        unknown = true
+        "#,
+    )?)?;
+
+    let mut diagnostics = workspace::Diagnostics::new();
+
+    let manifest = workspace::prepare(&mut sources)
+        .with_diagnostics(&mut diagnostics)
+        .build()?;
+
+    let [Diagnostic::Warning(warning)] = diagnostics.diagnostics() else {
+        panic!("expected a single warning: {:?}", diagnostics.diagnostics());
+    };
+
+    assert_eq!(warning.error().to_string(), "Key `unknown` not supported");
+
+    assert_eq!(
+        manifest.fmt,
+        [
+            "fmt.indent-width=2",
+            "fmt.hard-tabs=false",
+            "fmt.trailing-comma=never",
+            "fmt.newline-style=windows",
+        ]
+    );
+
+    let mut options = Options::default();
+    manifest.apply_fmt_options(&mut options)?;
+    assert_eq!(options.fmt.indent_width, 2);
+
+    #[cfg(feature = "fmt")]
+    {
+        let mut sources = Sources::new();
+        sources.insert(Source::memory("struct Foo { a, b }")?)?;
+
+        let [(_, output)] = &crate::fmt::prepare(&sources)
+            .with_options(&options)
+            .format()?[..]
+        else {
+            panic!("expected a single formatted source");
+        };
+
+        assert_eq!(output, "struct Foo {\r\n  a,\r\n  b\r\n}\r\n");
+    }
+
+    // Invalid values are reported as errors.
+    let mut sources = Sources::new();
+
+    let id = sources.insert(Source::new(
+        workspace::MANIFEST_FILE,
+        "[fmt]\ntrailing_comma = \"sometimes\"\n",
+    )?)?;
+
+    let mut diagnostics = workspace::Diagnostics::new();
+
+    let result = workspace::prepare(&mut sources)
+        .with_diagnostics(&mut diagnostics)
+        .build();
+
+    assert!(result.is_err());
+
+    let [Diagnostic::Fatal(fatal)] = diagnostics.diagnostics() else {
+        panic!("expected a single error: {:?}", diagnostics.diagnostics());
+    };
+
+    assert_eq!(
+        fatal.error().to_string(),
+        "Invalid value for formatting option `trailing_comma`"
+    );
+
+    let source = sources.get(id).context("missing source")?;
+    let span = fatal.error().span();
+    assert_eq!(&source.as_str()[span.range()], "\"sometimes\"");
+    Ok(())
+}
+
+#[test]
+fn workspace_unsupported_key_outside_package() -> Result<()> {
+    let mut sources = Sources::new();
+
+    let id = sources.insert(Source::new(
+        workspace::MANIFEST_FILE,
+        r#"
+        unknown = true
+
+        [package]
+        name = "a"
+        version = "0.1.0"
+        "#,
+    )?)?;
+
+    let mut diagnostics = workspace::Diagnostics::new();
+
+    let result = workspace::prepare(&mut sources)
+        .with_diagnostics(&mut diagnostics)
+        .build();
+
+    assert!(result.is_err());
+
+    let [Diagnostic::Fatal(fatal)] = diagnostics.diagnostics() else {
+        panic!("expected a single error: {:?}", diagnostics.diagnostics());
+    };
+
+    assert_eq!(fatal.error().to_string(), "Key `unknown` not supported");
+
+    let source = sources.get(id).context("missing source")?;
+    let span = fatal.error().span();
+    assert_eq!(&source.as_str()[span.range()], "unknown");
+    Ok(())
+}
@@ -1,6 +1,8 @@
 //! Worker used by compiler.
 
 mod import;
+#[cfg(feature = "parallel")]
+mod parallel;
 mod task;
 mod wildcard_import;
 
@@ -9,7 +11,7 @@ use rust_alloc::rc::Rc;
 use crate::alloc::prelude::*;
 use crate::alloc::{self, HashMap, Vec, VecDeque};
 use crate::ast::{self, Kind, Span, Spanned};
-use crate::compile::{self, ItemId, ModId, WithSpan};
+use crate::compile::{self, FileCache, ItemId, ModId, WithSpan};
 use crate::grammar::{Node, Stream};
 use crate::indexing::{index, index2};
 use crate::macros::{MacroContext, TokenStream};
@@ -31,15 +33,23 @@ pub(crate) struct Worker<'a, 'arena> {
     pub(crate) loaded: HashMap<ModId, (SourceId, Span)>,
     /// Worker queue.
     pub(crate) queue: VecDeque<Task>,
+    /// Cache of parsed files from previous builds.
+    pub(crate) cache: Option<&'a mut FileCache>,
+    /// Files which have been parsed ahead of time on other threads.
+    #[cfg(feature = "parallel")]
+    parsed: HashMap<SourceId, ast::File>,
 }
 
 impl<'a, 'arena> Worker<'a, 'arena> {
     /// Construct a new worker.
-    pub(crate) fn new(q: Query<'a, 'arena>) -> Self {
+    pub(crate) fn new(q: Query<'a, 'arena>, cache: Option<&'a mut FileCache>) -> Self {
         Self {
             q,
             loaded: HashMap::new(),
             queue: VecDeque::new(),
+            cache,
+            #[cfg(feature = "parallel")]
+            parsed: HashMap::new(),
         }
     }
 
@@ -67,6 +77,9 @@ impl<'a, 'arena> Worker<'a, 'arena> {
                         mod_item,
                         mod_item_id,
                     } => {
+                        #[cfg(feature = "parallel")]
+                        self.parse_ahead(&kind, source_id)?;
+
                         let result = self.load_file(kind, source_id, mod_item, mod_item_id);
 
                         if let Err(error) = result {
@@ -223,8 +236,37 @@ impl<'a, 'arena> Worker<'a, 'arena> {
 
                 index::empty_block_fn(&mut idx, ast, &span)?;
             } else {
-                let mut ast =
-                    crate::parse::parse_all::<ast::File>(source.as_str(), source_id, true)?;
+                let cached = match &mut self.cache {
+                    Some(cache) => cache.get(source_id, source)?,
+                    None => None,
+                };
+
+                // Files which failed to parse ahead of time are parsed again
+                // here to report the error.
+                #[cfg(feature = "parallel")]
+                let parsed = self.parsed.remove(&source_id);
+                #[cfg(not(feature = "parallel"))]
+                let parsed = None;
+
+                let mut ast = match cached {
+                    Some(ast) => ast,
+                    None => {
+                        let ast = match parsed {
+                            Some(ast) => ast,
+                            None => crate::parse::parse_all::<ast::File>(
+                                source.as_str(),
+                                source_id,
+                                true,
+                            )?,
+                        };
+
+                        if let Some(cache) = &mut self.cache {
+                            cache.insert(source_id, source, &ast)?;
+                        }
+
+                        ast
+                    }
+                };
 
                 let empty = Rc::default();
                 let mut idx = indexer!(&empty);
@@ -0,0 +1,145 @@
+//! Parsing of queued files on multiple threads.
+//!
+//! Only parsing is performed in parallel. Indexing, lowering and assembly
+//! require exclusive access to the query engine and are performed on the
+//! calling thread, which is also the only thread diagnostics are reported
+//! from so they are emitted in the same order as in a serial build.
+
+use std::sync::mpsc;
+use std::thread;
+
+use crate::alloc::prelude::*;
+use crate::alloc::{self, limit, Vec};
+use crate::ast;
+use crate::worker::{LoadFileKind, Task, Worker};
+use crate::SourceId;
+
+impl Worker<'_, '_> {
+    /// Parse the file which is about to be loaded together with every other
+    /// file which is waiting to be loaded on multiple threads.
+    ///
+    /// Files which fail to parse are skipped and parsed again once they are
+    /// loaded, so that errors are reported in the same order as in a serial
+    /// build.
+    pub(super) fn parse_ahead(
+        &mut self,
+        kind: &LoadFileKind,
+        source_id: SourceId,
+    ) -> alloc::Result<()> {
+        let options = self.q.options;
+
+        if !options.parallel || options.v2 || self.parsed.contains_key(&source_id) {
+            return Ok(());
+        }
+
+        let queued = self.queue.iter().filter_map(|task| match task {
+            Task::LoadFile {
+                kind, source_id, ..
+            } => Some((kind, *source_id)),
+            _ => None,
+        });
+
+        let mut pending = Vec::new();
+
+        for (kind, source_id) in [(kind, source_id)].into_iter().chain(queued) {
+            // Root files are parsed as blocks when building function bodies.
+            if matches!(kind, LoadFileKind::Root) && options.function_body {
+                continue;
+            }
+
+            if self.parsed.contains_key(&source_id) {
+                continue;
+            }
+
+            let Some(source) = self.q.sources.get(source_id) else {
+                continue;
+            };
+
+            if self
+                .cache
+                .as_ref()
+                .is_some_and(|c| c.contains(source_id, source))
+            {
+                continue;
+            }
+
+            pending.try_push((source_id, source.as_str()))?;
+        }
+
+        // Not worth spawning threads for.
+        if pending.len() < 2 {
+            return Ok(());
+        }
+
+        let threads = thread::available_parallelism()
+            .map_or(1, |n| n.get())
+            .min(pending.len());
+
+        let chunk_size = pending.len().div_ceil(threads);
+
+        // The memory limit is thread-local, so the remaining memory is split
+        // between the threads.
+        let remaining = limit::get();
+        let share = if remaining == usize::MAX {
+            usize::MAX
+        } else {
+            remaining / threads
+        };
+
+        thread::scope(|s| -> alloc::Result<()> {
+            let mut workers = rust_alloc::vec::Vec::new();
+
+            for chunk in pending.chunks(chunk_size) {
+                let (parsed_tx, parsed_rx) = mpsc::channel();
+                let (release_tx, release_rx) = mpsc::channel::<Parsed>();
+
+                let handle = s.spawn(move || {
+                    limit::with(share, || {
+                        if parsed_tx.send(parse_chunk(chunk)).is_ok() {
+                            // Wait for the calling thread to copy the parsed
+                            // files, so that the originals are released under
+                            // the budget of the thread which allocated them.
+                            drop(release_rx.recv());
+                        }
+                    })
+                    .call()
+                });
+
+                workers.push((handle, parsed_rx, release_tx));
+            }
+
+            for (handle, parsed_rx, release_tx) in workers {
+                if let Ok(parsed) = parsed_rx.recv() {
+                    // Copying the files accounts for their memory in the budget
+                    // of the calling thread, which is where it's released.
+                    for (source_id, file) in &parsed {
+                        self.parsed.try_insert(*source_id, file.try_clone()?)?;
+                    }
+
+                    _ = release_tx.send(parsed);
+                }
+
+                if let Err(panic) = handle.join() {
+                    std::panic::resume_unwind(panic);
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
+
+type Parsed = rust_alloc::vec::Vec<(SourceId, ast::File)>;
+
+/// Parse a chunk of sources, skipping the ones which fail to parse.
+fn parse_chunk(chunk: &[(SourceId, &str)]) -> Parsed {
+    let mut output = rust_alloc::vec::Vec::with_capacity(chunk.len());
+
+    for &(source_id, source) in chunk {
+        if let Ok(file) = crate::parse::parse_all::<ast::File>(source, source_id, true) {
+            output.push((source_id, file));
+        }
+    }
+
+    output
+}
@@ -66,6 +66,7 @@ impl WildcardImport {
                 self.location,
                 ErrorKind::MissingItem {
                     item: self.name.try_clone()?,
+                    did_you_mean: None,
                 },
             ));
         }
@@ -2,7 +2,7 @@ use core::fmt;
 
 use crate::alloc;
 use crate::ast::Span;
-use crate::workspace::manifest::{Loader, Manifest};
+use crate::workspace::manifest::{self, Loader, Manifest};
 use crate::workspace::{Diagnostics, FileSourceLoader, SourceLoader, WorkspaceError};
 use crate::Sources;
 
@@ -114,6 +114,8 @@ impl<'a> Build<'a> {
             }
         }
 
+        manifest::resolve_dependencies(&mut manifest, diagnostics)?;
+
         if diagnostics.has_errors() {
             return Err(BuildError::DEFAULT);
         }
@@ -21,12 +21,36 @@ impl FatalDiagnostic {
     }
 }
 
+/// A warning diagnostic in a workspace.
+///
+/// Warnings don't prevent the workspace from being loaded, but indicate that
+/// something in the manifest was ignored.
+#[derive(Debug)]
+pub struct WarningDiagnostic {
+    source_id: SourceId,
+    error: WorkspaceError,
+}
+
+impl WarningDiagnostic {
+    /// Get source id of the diagnostic.
+    pub fn source_id(&self) -> SourceId {
+        self.source_id
+    }
+
+    /// Access the underlying workspace error.
+    pub fn error(&self) -> &WorkspaceError {
+        &self.error
+    }
+}
+
 /// A single workspace diagnostic.
 #[derive(Debug)]
 #[non_exhaustive]
 pub enum Diagnostic {
     /// An error in a workspace.
     Fatal(FatalDiagnostic),
+    /// A warning in a workspace.
+    Warning(WarningDiagnostic),
 }
 
 /// Diagnostics emitted about a workspace.
@@ -48,6 +72,13 @@ impl Diagnostics {
             .any(|e| matches!(e, Diagnostic::Fatal(..)))
     }
 
+    /// Test if diagnostics has warnings.
+    pub fn has_warnings(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|e| matches!(e, Diagnostic::Warning(..)))
+    }
+
     /// Test if diagnostics is empty.
     pub fn is_empty(&self) -> bool {
         self.diagnostics.is_empty()
@@ -62,6 +93,16 @@ impl Diagnostics {
         self.diagnostics
             .try_push(Diagnostic::Fatal(FatalDiagnostic { source_id, error }))
     }
+
+    /// Report a single workspace warning.
+    pub(crate) fn warning(
+        &mut self,
+        source_id: SourceId,
+        error: WorkspaceError,
+    ) -> alloc::Result<()> {
+        self.diagnostics
+            .try_push(Diagnostic::Warning(WarningDiagnostic { source_id, error }))
+    }
 }
 
 impl Diagnostics {
@@ -10,7 +10,8 @@ use codespan_reporting::term::termcolor::WriteColor;
 use crate::alloc;
 use crate::alloc::prelude::*;
 use crate::ast::Spanned;
-use crate::workspace::{Diagnostic, Diagnostics, FatalDiagnostic};
+use crate::workspace::{Diagnostic, Diagnostics, WorkspaceError};
+use crate::SourceId;
 use crate::Sources;
 
 /// Errors that can be raised when formatting diagnostics.
@@ -81,7 +82,12 @@ impl Diagnostics {
         for diagnostic in &self.diagnostics {
             match diagnostic {
                 Diagnostic::Fatal(e) => {
-                    error_diagnostics_emit(e, out, sources, &config)?;
+                    let diagnostic = d::Diagnostic::error();
+                    diagnostics_emit(diagnostic, e.source_id(), e.error(), out, sources, &config)?;
+                }
+                Diagnostic::Warning(e) => {
+                    let diagnostic = d::Diagnostic::warning();
+                    diagnostics_emit(diagnostic, e.source_id(), e.error(), out, sources, &config)?;
                 }
             }
         }
@@ -91,8 +97,10 @@ impl Diagnostics {
 }
 
 /// Custom shared helper for emitting diagnostics for a single error.
-fn error_diagnostics_emit<O>(
-    this: &FatalDiagnostic,
+fn diagnostics_emit<O>(
+    diagnostic: d::Diagnostic<SourceId>,
+    source_id: SourceId,
+    error: &WorkspaceError,
     out: &mut O,
     sources: &Sources,
     config: &codespan_reporting::term::Config,
@@ -102,15 +110,14 @@ where
 {
     let mut labels = rust_alloc::vec::Vec::new();
 
-    let span = this.error().span();
+    let span = error.span();
 
     labels.push(
-        d::Label::primary(this.source_id(), span.range())
-            .with_message(this.error().try_to_string()?.into_std()),
+        d::Label::primary(source_id, span.range()).with_message(error.try_to_string()?.into_std()),
     );
 
-    let diagnostic = d::Diagnostic::error()
-        .with_message(this.error().try_to_string()?.into_std())
+    let diagnostic = diagnostic
+        .with_message(error.try_to_string()?.into_std())
         .with_labels(labels);
 
     term::emit(out, config, sources, &diagnostic)?;
@@ -119,6 +119,28 @@ pub(crate) enum WorkspaceErrorKind {
     UnsupportedKey {
         key: String,
     },
+    InvalidFmtOption {
+        key: String,
+    },
+    EmptyGlob {
+        pattern: String,
+    },
+    DuplicateMember {
+        path: Box<Path>,
+    },
+    UnknownDependency {
+        name: String,
+    },
+    DependencyPathMismatch {
+        name: String,
+        path: Box<Path>,
+    },
+    MissingLibrary {
+        name: String,
+    },
+    DependencyCycle {
+        cycle: String,
+    },
     AllocError {
         error: alloc::Error,
     },
@@ -169,6 +191,32 @@ impl fmt::Display for WorkspaceErrorKind {
             ),
             WorkspaceErrorKind::ExpectedTable {} => write!(f, "Expected table"),
             WorkspaceErrorKind::UnsupportedKey { key } => write!(f, "Key `{key}` not supported",),
+            WorkspaceErrorKind::InvalidFmtOption { key } => {
+                write!(f, "Invalid value for formatting option `{key}`")
+            }
+            WorkspaceErrorKind::EmptyGlob { pattern } => {
+                write!(f, "Member `{pattern}` did not match any packages")
+            }
+            WorkspaceErrorKind::DuplicateMember { path } => write!(
+                f,
+                "Package at `{path}` is already a member of the workspace",
+                path = path.display()
+            ),
+            WorkspaceErrorKind::UnknownDependency { name } => {
+                write!(f, "Dependency `{name}` is not a package in the workspace")
+            }
+            WorkspaceErrorKind::DependencyPathMismatch { name, path } => write!(
+                f,
+                "Dependency `{name}` is not located at `{path}`",
+                path = path.display()
+            ),
+            WorkspaceErrorKind::MissingLibrary { name } => write!(
+                f,
+                "Dependency `{name}` has no library, expected a `lib.rn` in its root"
+            ),
+            WorkspaceErrorKind::DependencyCycle { cycle } => {
+                write!(f, "Cyclic dependency between packages: {cycle}")
+            }
             WorkspaceErrorKind::AllocError { error } => error.fmt(f),
         }
     }
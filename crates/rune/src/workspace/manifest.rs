@@ -9,19 +9,21 @@ use anyhow::Result;
 use relative_path::{RelativePath, RelativePathBuf};
 use semver::Version;
 use serde::de::IntoDeserializer;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_hashkey as key;
 
 use crate as rune;
 use crate::alloc::prelude::*;
-use crate::alloc::{self, String, Vec};
+use crate::alloc::{self, try_format, String, Vec};
 use crate::ast::{Span, Spanned};
+use crate::compile::{Options, ParseOptionError};
 use crate::workspace::spanned_value::{Array, SpannedValue, Table, Value};
 use crate::workspace::{
     glob, Diagnostics, SourceLoader, WorkspaceError, WorkspaceErrorKind, MANIFEST_FILE,
 };
-use crate::{SourceId, Sources};
+use crate::{Source, SourceId, Sources};
 
+const LIB: &str = "lib.rn";
 const BIN: &str = "bin";
 const TESTS: &str = "tests";
 const EXAMPLES: &str = "examples";
@@ -40,16 +42,20 @@ pub enum WorkspaceFilter<'a> {
 }
 
 /// The kind of a found entry.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 #[non_exhaustive]
 pub enum FoundKind {
     /// The found entry is a binary.
+    #[serde(rename = "bin")]
     Binary,
     /// The found entry is a test.
+    #[serde(rename = "test")]
     Test,
     /// The found entry is an example.
+    #[serde(rename = "example")]
     Example,
     /// The found entry is a benchmark.
+    #[serde(rename = "bench")]
     Bench,
 }
 
@@ -66,7 +72,9 @@ impl fmt::Display for FoundKind {
 }
 
 /// A found item in the workspace.
-#[derive(Debug, TryClone)]
+///
+/// This describes a single target of a package, like a binary or a test.
+#[derive(Debug, TryClone, Serialize)]
 #[non_exhaustive]
 pub struct Found {
     /// The kind found.
@@ -89,7 +97,8 @@ pub struct FoundPackage<'a> {
 }
 
 impl WorkspaceFilter<'_> {
-    fn matches(self, name: &str) -> bool {
+    /// Test if the filter matches the given name.
+    pub fn matches(self, name: &str) -> bool {
         match self {
             WorkspaceFilter::Name(expected) => name == expected,
             WorkspaceFilter::All => true,
@@ -106,14 +115,49 @@ impl<T> Spanned for toml::Spanned<T> {
 }
 
 /// The manifest of a workspace.
-#[derive(Default, Debug)]
+///
+/// The manifest can be serialized, which produces a stable representation of
+/// all the packages it contains.
+#[derive(Default, Debug, Serialize)]
 #[non_exhaustive]
 pub struct Manifest {
     /// List of packages found.
     pub packages: Vec<Package>,
+    /// Formatting options from the `[fmt]` section of the root manifest, in
+    /// the `fmt.<option>=<value>` form accepted by [`Options::parse_option`].
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fmt: Vec<String>,
 }
 
 impl Manifest {
+    /// Access all packages in the manifest.
+    pub fn packages(&self) -> &[Package] {
+        &self.packages
+    }
+
+    /// Find a package by name.
+    pub fn package(&self, name: &str) -> Option<&Package> {
+        self.packages.iter().find(|p| p.name == name)
+    }
+
+    /// Apply the formatting options from the `[fmt]` section of the manifest
+    /// to the given options.
+    pub fn apply_fmt_options(&self, options: &mut Options) -> Result<(), ParseOptionError> {
+        for option in &self.fmt {
+            options.parse_option(option)?;
+        }
+
+        Ok(())
+    }
+
+    /// Find all targets of the given package.
+    ///
+    /// This is the same as calling [`Package::find_all`] with
+    /// [`WorkspaceFilter::All`].
+    pub fn targets(&self, package: &Package) -> Result<Vec<Found>> {
+        package.find_all(WorkspaceFilter::All)
+    }
+
     fn find_paths<'m>(
         &'m self,
         m: WorkspaceFilter<'_>,
@@ -166,13 +210,19 @@ impl Manifest {
 }
 
 /// A single package.
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[non_exhaustive]
 pub struct Package {
     /// The name of the package.
     pub name: String,
     /// The version of the package..
     pub version: Version,
+    /// The authors of the package.
+    pub authors: Vec<String>,
+    /// The description of the package.
+    pub description: Option<String>,
+    /// The license of the package.
+    pub license: Option<String>,
     /// The root of the package.
     pub root: Option<PathBuf>,
     /// Automatically detect binaries.
@@ -183,9 +233,78 @@ pub struct Package {
     pub auto_examples: bool,
     /// Automatically detect benches.
     pub auto_benches: bool,
+    /// Other packages in the workspace which this package depends on.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<Dependency>,
+    /// The libraries of all dependencies, including transitive ones, in the
+    /// order in which they have to be built.
+    #[serde(skip)]
+    pub(crate) libraries: Vec<Library>,
+}
+
+/// A dependency of a package on another package in the same workspace.
+#[derive(Debug, Serialize)]
+#[non_exhaustive]
+pub struct Dependency {
+    /// The name of the package being depended on.
+    pub name: String,
+    /// The path to the package being depended on, if specified.
+    pub path: Option<PathBuf>,
+    /// The manifest the dependency was declared in.
+    #[serde(skip)]
+    source_id: SourceId,
+    /// The span of the dependency declaration.
+    #[serde(skip)]
+    span: Span,
+}
+
+/// The library of a dependency, as it is mounted when building a package.
+#[derive(Debug)]
+pub(crate) struct Library {
+    /// The name of the crate the library is mounted under.
+    pub(crate) name: String,
+    /// The path to the library.
+    pub(crate) path: PathBuf,
 }
 
 impl Package {
+    /// Get the path to the library of the package, which is the `lib.rn` file
+    /// in the root of the package.
+    ///
+    /// Other packages in the workspace can import public items from the
+    /// library by depending on the package.
+    pub fn lib(&self) -> Option<PathBuf> {
+        let path = self.root.as_ref()?.join(LIB);
+        path.is_file().then_some(path)
+    }
+
+    /// Get the name of the crate the library of the package is mounted under.
+    ///
+    /// Since `-` is not valid in identifiers it is replaced with `_`.
+    pub fn crate_name(&self) -> alloc::Result<String> {
+        let mut name = String::new();
+
+        for c in self.name.chars() {
+            name.try_push(if c == '-' { '_' } else { c })?;
+        }
+
+        Ok(name)
+    }
+
+    /// Insert the libraries of all dependencies of the package into `sources`,
+    /// so that sources in the package can import items from them through
+    /// `use dependency::item`.
+    ///
+    /// Libraries are inserted in the order in which they have to be built,
+    /// each being mounted under its own crate.
+    pub fn prepare_dependencies(&self, sources: &mut Sources) -> Result<()> {
+        for library in &self.libraries {
+            sources.insert_crate(&library.name, Source::from_path(&library.path)?)?;
+        }
+
+        Ok(())
+    }
+
     fn find_paths(
         &self,
         m: WorkspaceFilter<'_>,
@@ -206,6 +325,9 @@ impl Package {
                     output.try_push(Found { kind, path, name })?;
                 }
             }
+
+            // Directory listings have no stable order.
+            output.sort_by(|a, b| a.name.cmp(&b.name));
         }
 
         Ok(output)
@@ -246,6 +368,7 @@ impl Package {
 
 pub(crate) struct Loader<'a> {
     id: SourceId,
+    root_id: SourceId,
     sources: &'a mut Sources,
     diagnostics: &'a mut Diagnostics,
     source_loader: &'a mut dyn SourceLoader,
@@ -262,6 +385,7 @@ impl<'a> Loader<'a> {
     ) -> Self {
         Self {
             id,
+            root_id: id,
             sources,
             diagnostics,
             source_loader,
@@ -340,10 +464,75 @@ impl<'a> Loader<'a> {
             self.ensure_empty(table)?;
         }
 
+        // Formatting options only apply to the root of the workspace, so
+        // they're reported as unsupported in members.
+        if self.id == self.root_id {
+            if let Some((table, _)) = table
+                .remove("fmt")
+                .map(|value| self.ensure_table(value))
+                .transpose()?
+                .flatten()
+            {
+                self.load_fmt(table)?;
+            }
+        }
+
         self.ensure_empty(table)?;
         Ok(())
     }
 
+    /// Load formatting options from the `[fmt]` section.
+    ///
+    /// Keys may be written with either underscores or dashes, so that both
+    /// `indent_width` and `indent-width` refer to `fmt.indent-width`.
+    fn load_fmt(&mut self, table: Table) -> alloc::Result<()> {
+        for (key, value) in table {
+            let span = Spanned::span(&key);
+            let name = try_format!("fmt.{}", key.get_ref().replace('_', "-"));
+
+            if !Options::available().iter().any(|o| o.key == name.as_str()) {
+                self.warning(WorkspaceError::new(
+                    span,
+                    WorkspaceErrorKind::UnsupportedKey {
+                        key: key.get_ref().as_str().try_into()?,
+                    },
+                ))?;
+                continue;
+            }
+
+            let span = Spanned::span(&value);
+
+            let option = match value.into_inner() {
+                Value::String(value) => try_format!("{name}={value}"),
+                Value::Integer(value) => try_format!("{name}={value}"),
+                Value::Boolean(value) => try_format!("{name}={value}"),
+                _ => {
+                    self.fatal(WorkspaceError::new(
+                        span,
+                        WorkspaceErrorKind::InvalidFmtOption {
+                            key: key.get_ref().as_str().try_into()?,
+                        },
+                    ))?;
+                    continue;
+                }
+            };
+
+            if Options::default().parse_option(&option).is_err() {
+                self.fatal(WorkspaceError::new(
+                    span,
+                    WorkspaceErrorKind::InvalidFmtOption {
+                        key: key.get_ref().as_str().try_into()?,
+                    },
+                ))?;
+                continue;
+            }
+
+            self.manifest.fmt.try_push(option)?;
+        }
+
+        Ok(())
+    }
+
     /// Load members from the given workspace configuration.
     fn load_members(
         &mut self,
@@ -373,13 +562,32 @@ impl<'a> Loader<'a> {
             };
         }
 
-        Ok(Some(output))
+        let mut seen = Vec::<PathBuf>::new();
+        let mut members = Vec::new();
+
+        for (span, path) in output {
+            if seen.iter().any(|other| same_path(other, &path)) {
+                let kind = WorkspaceErrorKind::DuplicateMember {
+                    path: path.parent().unwrap_or(&path).try_into()?,
+                };
+
+                self.warning(WorkspaceError::new(span, kind))?;
+                continue;
+            }
+
+            seen.try_push(path.try_clone()?)?;
+            members.try_push((span, path))?;
+        }
+
+        Ok(Some(members))
     }
 
     /// Glob a relative path.
     ///
     /// Currently only supports expanding `*` and required interacting with the
-    /// filesystem.
+    /// filesystem. Matches are sorted by path so that members are always
+    /// loaded in the same order, and a pattern which doesn't match any
+    /// manifest is an error.
     fn glob_relative_path(
         &mut self,
         output: &mut Vec<(Span, PathBuf)>,
@@ -388,10 +596,11 @@ impl<'a> Loader<'a> {
         root: &Path,
     ) -> Result<()> {
         let glob = glob::Glob::new(root, member)?;
+        let mut paths = Vec::new();
 
         for m in glob.matcher()? {
             let Some(mut path) = self.glob_error(span, root, m)? else {
-                continue;
+                return Ok(());
             };
 
             path.push(MANIFEST_FILE);
@@ -400,6 +609,21 @@ impl<'a> Loader<'a> {
                 continue;
             }
 
+            paths.try_push(path)?;
+        }
+
+        if paths.is_empty() {
+            let kind = WorkspaceErrorKind::EmptyGlob {
+                pattern: member.as_str().try_to_owned()?,
+            };
+
+            self.fatal(WorkspaceError::new(span, kind))?;
+            return Ok(());
+        }
+
+        paths.sort();
+
+        for path in paths {
             output.try_push((span, path))?;
         }
 
@@ -455,7 +679,16 @@ impl<'a> Loader<'a> {
     ) -> alloc::Result<Option<Package>> {
         let name = self.field(&mut table, span, "name")?;
         let version = self.field(&mut table, span, "version")?;
-        self.ensure_empty(table)?;
+        let authors: Option<Vec<String>> = self.optional_field(&mut table, "authors")?;
+        let description = self.optional_field(&mut table, "description")?;
+        let license = self.optional_field(&mut table, "license")?;
+
+        let dependencies = match table.remove("dependencies") {
+            Some(value) => self.load_dependencies(value, root)?,
+            None => Vec::new(),
+        };
+
+        self.warn_unsupported(table)?;
 
         let (Some(name), Some(version)) = (name, version) else {
             return Ok(None);
@@ -464,15 +697,68 @@ impl<'a> Loader<'a> {
         Ok(Some(Package {
             name,
             version,
+            authors: authors.unwrap_or_default(),
+            description,
+            license,
             root: root.map(|p| p.into()),
             auto_bins: true,
             auto_tests: true,
             auto_examples: true,
             auto_benches: true,
+            dependencies,
+            libraries: Vec::new(),
         }))
     }
 
-    /// Ensure that a table is empty and mark any additional elements as erroneous.
+    /// Load the `[dependencies]` table of a package.
+    ///
+    /// Each key names a package in the workspace, and its value is a table
+    /// with an optional `path` to the package relative to `root`.
+    fn load_dependencies(
+        &mut self,
+        value: SpannedValue,
+        root: Option<&Path>,
+    ) -> alloc::Result<Vec<Dependency>> {
+        let mut output = Vec::new();
+
+        let Some((table, _)) = self.ensure_table(value)? else {
+            return Ok(output);
+        };
+
+        for (key, value) in table {
+            let span = Spanned::span(&key);
+
+            let Some((mut table, _)) = self.ensure_table(value)? else {
+                continue;
+            };
+
+            let path: Option<RelativePathBuf> = self.optional_field(&mut table, "path")?;
+            self.ensure_empty(table)?;
+
+            let path = match (path, root) {
+                (Some(path), Some(root)) => Some(path.to_path(root)),
+                (Some(..), None) => {
+                    self.fatal(WorkspaceError::new(
+                        span,
+                        WorkspaceErrorKind::MissingManifestPath,
+                    ))?;
+                    continue;
+                }
+                (None, _) => None,
+            };
+
+            output.try_push(Dependency {
+                name: key.get_ref().as_str().try_to_owned()?,
+                path,
+                source_id: self.id,
+                span,
+            })?;
+        }
+
+        Ok(output)
+    }
+
+    /// Ensure that a table is empty and error about any additional elements.
     fn ensure_empty(&mut self, table: Table) -> alloc::Result<()> {
         for (key, _) in table {
             let span = Spanned::span(&key);
@@ -487,6 +773,24 @@ impl<'a> Loader<'a> {
         Ok(())
     }
 
+    /// Warn about any elements left in a table.
+    ///
+    /// This is used for the `[package]` section, which may contain metadata
+    /// that we don't know about.
+    fn warn_unsupported(&mut self, table: Table) -> alloc::Result<()> {
+        for (key, _) in table {
+            let span = Spanned::span(&key);
+            self.warning(WorkspaceError::new(
+                span,
+                WorkspaceErrorKind::UnsupportedKey {
+                    key: key.get_ref().as_str().try_into()?,
+                },
+            ))?;
+        }
+
+        Ok(())
+    }
+
     /// Ensure that value is a table.
     fn ensure_table(&mut self, value: SpannedValue) -> alloc::Result<Option<(Table, Span)>> {
         let span = Spanned::span(&value);
@@ -541,10 +845,236 @@ impl<'a> Loader<'a> {
         })
     }
 
+    /// Helper to load a single optional field.
+    fn optional_field<T>(
+        &mut self,
+        table: &mut Table,
+        field: &'static str,
+    ) -> alloc::Result<Option<T>>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let Some(value) = table.remove(field) else {
+            return Ok(None);
+        };
+
+        Ok(match deserialize(value) {
+            Ok(value) => Some(value),
+            Err(error) => {
+                self.fatal(error)?;
+                None
+            }
+        })
+    }
+
     /// Report a fatal diagnostic.
     fn fatal(&mut self, error: WorkspaceError) -> alloc::Result<()> {
         self.diagnostics.fatal(self.id, error)
     }
+
+    /// Report a warning diagnostic.
+    fn warning(&mut self, error: WorkspaceError) -> alloc::Result<()> {
+        self.diagnostics.warning(self.id, error)
+    }
+}
+
+/// Resolve the dependencies of all packages in the manifest.
+///
+/// This reports dependencies which can't be resolved to a package with a
+/// library in the workspace and cyclic dependencies. If all dependencies
+/// could be resolved, the libraries each package depends on are populated.
+pub(crate) fn resolve_dependencies(
+    manifest: &mut Manifest,
+    diagnostics: &mut Diagnostics,
+) -> alloc::Result<()> {
+    let packages = &manifest.packages;
+
+    // Edges from each package to the packages it depends on, alongside the
+    // index of the dependency declaring the edge.
+    let mut edges = Vec::<Vec<(usize, usize)>>::new();
+
+    for package in packages {
+        let mut out = Vec::new();
+
+        for (d, dep) in package.dependencies.iter().enumerate() {
+            let Some(index) = packages.iter().position(|p| p.name == dep.name) else {
+                let kind = WorkspaceErrorKind::UnknownDependency {
+                    name: dep.name.try_clone()?,
+                };
+
+                diagnostics.fatal(dep.source_id, WorkspaceError::new(dep.span, kind))?;
+                continue;
+            };
+
+            let target = &packages[index];
+
+            if let Some(path) = &dep.path {
+                if !target
+                    .root
+                    .as_deref()
+                    .is_some_and(|root| same_path(path, root))
+                {
+                    let kind = WorkspaceErrorKind::DependencyPathMismatch {
+                        name: dep.name.try_clone()?,
+                        path: path.as_path().try_into()?,
+                    };
+
+                    diagnostics.fatal(dep.source_id, WorkspaceError::new(dep.span, kind))?;
+                    continue;
+                }
+            }
+
+            if target.lib().is_none() {
+                let kind = WorkspaceErrorKind::MissingLibrary {
+                    name: dep.name.try_clone()?,
+                };
+
+                diagnostics.fatal(dep.source_id, WorkspaceError::new(dep.span, kind))?;
+                continue;
+            }
+
+            out.try_push((index, d))?;
+        }
+
+        edges.try_push(out)?;
+    }
+
+    if !find_cycles(packages, &edges, diagnostics)? {
+        return Ok(());
+    }
+
+    let mut libraries = Vec::new();
+
+    for index in 0..packages.len() {
+        let mut visited = Vec::try_with_capacity(packages.len())?;
+        visited.try_resize(packages.len(), false)?;
+        visited[index] = true;
+
+        let mut order = Vec::new();
+        build_order(index, &edges, &mut visited, &mut order)?;
+
+        let mut output = Vec::new();
+
+        for index in order {
+            let package = &packages[index];
+
+            let Some(path) = package.lib() else {
+                continue;
+            };
+
+            output.try_push(Library {
+                name: package.crate_name()?,
+                path,
+            })?;
+        }
+
+        libraries.try_push(output)?;
+    }
+
+    for (package, libraries) in manifest.packages.iter_mut().zip(libraries) {
+        package.libraries = libraries;
+    }
+
+    Ok(())
+}
+
+/// Report every cycle among the dependency edges, returning `false` if any
+/// were found.
+fn find_cycles(
+    packages: &[Package],
+    edges: &[Vec<(usize, usize)>],
+    diagnostics: &mut Diagnostics,
+) -> alloc::Result<bool> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        Unvisited,
+        Visiting,
+        Done,
+    }
+
+    fn visit(
+        index: usize,
+        packages: &[Package],
+        edges: &[Vec<(usize, usize)>],
+        states: &mut [State],
+        stack: &mut Vec<usize>,
+        diagnostics: &mut Diagnostics,
+    ) -> alloc::Result<bool> {
+        states[index] = State::Visiting;
+        stack.try_push(index)?;
+
+        let mut ok = true;
+
+        for &(target, d) in &edges[index] {
+            match states[target] {
+                State::Unvisited => {
+                    ok &= visit(target, packages, edges, states, stack, diagnostics)?;
+                }
+                State::Visiting => {
+                    let start = stack.iter().position(|&i| i == target).unwrap_or_default();
+
+                    let mut cycle = String::new();
+
+                    for &i in stack[start..].iter().chain([&target]) {
+                        if !cycle.is_empty() {
+                            cycle.try_push_str(" -> ")?;
+                        }
+
+                        cycle.try_push_str(&packages[i].name)?;
+                    }
+
+                    let dep = &packages[index].dependencies[d];
+                    let kind = WorkspaceErrorKind::DependencyCycle { cycle };
+                    diagnostics.fatal(dep.source_id, WorkspaceError::new(dep.span, kind))?;
+                    ok = false;
+                }
+                State::Done => {}
+            }
+        }
+
+        stack.pop();
+        states[index] = State::Done;
+        Ok(ok)
+    }
+
+    let mut states = Vec::try_with_capacity(packages.len())?;
+    states.try_resize(packages.len(), State::Unvisited)?;
+
+    let mut stack = Vec::new();
+    let mut ok = true;
+
+    while let Some(index) = states.iter().position(|&s| s == State::Unvisited) {
+        ok &= visit(index, packages, edges, &mut states, &mut stack, diagnostics)?;
+    }
+
+    Ok(ok)
+}
+
+/// Collect the transitive dependencies of `index` in post-order, so that
+/// each dependency comes before the packages depending on it.
+fn build_order(
+    index: usize,
+    edges: &[Vec<(usize, usize)>],
+    visited: &mut [bool],
+    order: &mut Vec<usize>,
+) -> alloc::Result<()> {
+    for &(target, _) in &edges[index] {
+        if !visited[target] {
+            visited[target] = true;
+            build_order(target, edges, visited, order)?;
+            order.try_push(target)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Test if two paths refer to the same location.
+fn same_path(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
 }
 
 /// Helper to load a single field.
@@ -23,10 +23,12 @@ pub use self::error::WorkspaceError;
 pub(crate) use self::error::WorkspaceErrorKind;
 
 mod manifest;
-pub use self::manifest::{Found, FoundKind, FoundPackage, Manifest, Package, WorkspaceFilter};
+pub use self::manifest::{
+    Dependency, Found, FoundKind, FoundPackage, Manifest, Package, WorkspaceFilter,
+};
 
 mod diagnostics;
-pub use self::diagnostics::{Diagnostic, Diagnostics, FatalDiagnostic};
+pub use self::diagnostics::{Diagnostic, Diagnostics, FatalDiagnostic, WarningDiagnostic};
 
 mod source_loader;
 pub use self::source_loader::{FileSourceLoader, SourceLoader};
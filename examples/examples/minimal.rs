@@ -21,15 +21,16 @@ fn main() -> rune::support::Result<()> {
         .with_diagnostics(&mut diagnostics)
         .build();
 
+    let mut writer = StandardStream::stderr(ColorChoice::Always);
+
     if !diagnostics.is_empty() {
-        let mut writer = StandardStream::stderr(ColorChoice::Always);
         diagnostics.emit(&mut writer, &sources)?;
     }
 
     let unit = result?;
 
     let mut vm = Vm::new(Arc::new(context.runtime()?), Arc::new(unit));
-    let output = vm.execute(["main"], (33i64,))?.complete().into_result()?;
+    let output = vm.call_emit(&mut writer, &sources, ["main"], (33i64,))?;
     let output: i64 = rune::from_value(output)?;
 
     println!("output: {}", output);
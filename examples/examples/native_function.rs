@@ -26,15 +26,16 @@ fn main() -> rune::support::Result<()> {
         .with_diagnostics(&mut diagnostics)
         .build();
 
+    let mut writer = StandardStream::stderr(ColorChoice::Always);
+
     if !diagnostics.is_empty() {
-        let mut writer = StandardStream::stderr(ColorChoice::Always);
         diagnostics.emit(&mut writer, &sources)?;
     }
 
     let unit = result?;
 
     let mut vm = Vm::new(runtime, Arc::new(unit));
-    let output = vm.call(["main"], (1u32,))?;
+    let output = vm.call_emit(&mut writer, &sources, ["main"], (1u32,))?;
     let output: i64 = rune::from_value(output)?;
 
     println!("{}", output);
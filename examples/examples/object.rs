@@ -26,8 +26,9 @@ fn main() -> rune::support::Result<()> {
         .with_diagnostics(&mut diagnostics)
         .build();
 
+    let mut writer = StandardStream::stderr(ColorChoice::Always);
+
     if !diagnostics.is_empty() {
-        let mut writer = StandardStream::stderr(ColorChoice::Always);
         diagnostics.emit(&mut writer, &sources)?;
     }
 
@@ -38,7 +39,7 @@ fn main() -> rune::support::Result<()> {
     let mut object = Object::new();
     object.insert(alloc::String::try_from("key")?, rune::to_value(42i64)?)?;
 
-    let output = vm.call(["calc"], (object,))?;
+    let output = vm.call_emit(&mut writer, &sources, ["calc"], (object,))?;
     let output: Object = rune::from_value(output)?;
 
     println!("{:?}", output.get("key"));
@@ -27,15 +27,16 @@ fn main() -> rune::support::Result<()> {
         .with_diagnostics(&mut diagnostics)
         .build();
 
+    let mut writer = StandardStream::stderr(ColorChoice::Always);
+
     if !diagnostics.is_empty() {
-        let mut writer = StandardStream::stderr(ColorChoice::Always);
         diagnostics.emit(&mut writer, &sources)?;
     }
 
     let unit = result?;
 
     let mut vm = Vm::new(runtime, Arc::new(unit));
-    let output = vm.call(["main"], ())?;
+    let output = vm.call_emit(&mut writer, &sources, ["main"], ())?;
     let output: Function = rune::from_value(output)?;
 
     println!("{}", output.call::<i64>((1, 3)).into_result()?);
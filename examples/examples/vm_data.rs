@@ -0,0 +1,78 @@
+use rune::runtime::VmData;
+use rune::termcolor::{ColorChoice, StandardStream};
+use rune::{ContextError, Diagnostics, Module, Vm};
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// A counter which is unique to each request.
+#[derive(Default)]
+struct RequestCounter(AtomicUsize);
+
+#[rune::function]
+fn hit(data: &VmData) {
+    if let Some(counter) = data.get::<RequestCounter>() {
+        counter.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[rune::function]
+fn hits(data: &VmData) -> usize {
+    data.get::<RequestCounter>()
+        .map_or(0, |counter| counter.0.load(Ordering::SeqCst))
+}
+
+fn main() -> rune::support::Result<()> {
+    let m = module()?;
+
+    let mut context = rune_modules::default_context()?;
+    context.install(m)?;
+
+    let runtime = Arc::new(context.runtime()?);
+
+    let mut sources = rune::sources! {
+        entry => {
+            pub fn main(n) {
+                for _ in 0..n {
+                    hit();
+                }
+
+                hits()
+            }
+        }
+    };
+
+    let mut diagnostics = Diagnostics::new();
+
+    let result = rune::prepare(&mut sources)
+        .with_context(&context)
+        .with_diagnostics(&mut diagnostics)
+        .build();
+
+    let mut writer = StandardStream::stderr(ColorChoice::Always);
+
+    if !diagnostics.is_empty() {
+        diagnostics.emit(&mut writer, &sources)?;
+    }
+
+    let unit = Arc::new(result?);
+
+    for request in 1..=3u32 {
+        let mut vm = Vm::new(runtime.clone(), unit.clone());
+        vm.insert_data(RequestCounter::default())?;
+
+        let output = vm.call_emit(&mut writer, &sources, ["main"], (request,))?;
+        let output: usize = rune::from_value(output)?;
+
+        println!("request {request}: {output} hits");
+    }
+
+    Ok(())
+}
+
+fn module() -> Result<Module, ContextError> {
+    let mut m = Module::new();
+    m.function_meta(hit)?;
+    m.function_meta(hits)?;
+    Ok(m)
+}
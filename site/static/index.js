@@ -71,6 +71,15 @@ function filterPrelude(input) {
 }
 
 function getUrlContent() {
+    // Examples in generated documentation link to the playground with their
+    // code percent-encoded in the fragment.
+    let fragment = new URLSearchParams(window.location.hash.substring(1));
+    let code = fragment.get("code");
+
+    if (code !== null) {
+        return code;
+    }
+
     var query = new URLSearchParams(window.location.search);
     let content = query.get("c");
     
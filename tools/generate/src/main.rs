@@ -320,12 +320,18 @@ fn main() -> Result<()> {
                 $("/// A byte literal.")
                 Byte($copy_source<u8>),
                 $("/// A byte string literal, including escape sequences. Like `b\"hello\\nworld\"`.")
+                $("///")
+                $("/// Raw byte strings like `br\"hello\"` or `br#\"\"hello\"\"#` are also")
+                $("/// represented by this token.")
                 ByteStr($lit_str_source),
                 $("/// A characer literal.")
                 Char($copy_source<char>),
                 $("/// A number literal, like `42` or `3.14` or `0xff`.")
                 Number($number_source),
                 $("/// A string literal, including escape sequences. Like `\"hello\\nworld\"`.")
+                $("///")
+                $("/// Raw strings like `r\"hello\"` or `r#\"\"hello\"\"#` are also represented")
+                $("/// by this token.")
                 Str($lit_str_source),
                 $("/// A path with an associated item.")
                 IndexedPath($item_id),